@@ -1,15 +1,36 @@
+from .admin import router as admin_router
 from .api_keys import router as api_keys_router
 from .catalog import router as catalog_router
 from .colors import router as colors_router
+from .config_bundle import router as config_bundle_router
+from .crash_reports import router as crash_reports_router
 from .device import router as device_router
+from .diagnostics import router as diagnostics_router
 from .discovery import router as discovery_router
 from .firmware import router as firmware_router
+from .integrations import router as integrations_router
+from .jobs import router as jobs_router
+from .kiosk import router as kiosk_router
+from .moonraker import router as moonraker_router
+from .notifications import router as notifications_router
 from .printers import router as printers_router
+from .projects import router as projects_router
+from .purchases import router as purchases_router
+from .reconciliation import router as reconciliation_router
+from .reports import router as reports_router
+from .search import router as search_router
 from .serial import router as serial_router
+from .share import public_router as share_router
+from .share import router as share_links_router
+from .slicer import router as slicer_router
 from .spools import router as spools_router
+from .stocktakes import router as stocktakes_router
 from .support import router as support_router
 from .tags import router as tags_router
+from .templates import router as templates_router
+from .trash import router as trash_router
 from .updates import router as updates_router
+from .webhooks import router as webhooks_router
 
 __all__ = [
     "spools_router",
@@ -24,4 +45,25 @@ __all__ = [
     "colors_router",
     "support_router",
     "api_keys_router",
+    "reports_router",
+    "webhooks_router",
+    "notifications_router",
+    "trash_router",
+    "crash_reports_router",
+    "config_bundle_router",
+    "diagnostics_router",
+    "slicer_router",
+    "projects_router",
+    "moonraker_router",
+    "search_router",
+    "jobs_router",
+    "templates_router",
+    "stocktakes_router",
+    "purchases_router",
+    "share_links_router",
+    "share_router",
+    "integrations_router",
+    "kiosk_router",
+    "admin_router",
+    "reconciliation_router",
 ]
@@ -0,0 +1,129 @@
+"""Administration endpoints for self-hosters.
+
+- Database status for running SpoolBuddy with a replicated SQLite database
+  (e.g. Litestream streaming the write-ahead log to object storage): journal
+  mode, WAL size, the last checkpoint and, when configured, the metrics of
+  the replication sidecar.
+- Request latency per route and slow database queries.
+- Connected WebSocket clients, who they are and what they follow.
+"""
+
+import logging
+from typing import Literal
+
+import httpx
+from config import settings
+from db import get_db
+from fastapi import APIRouter, Query
+from pydantic import BaseModel
+from services.perf import PerfMetrics, get_perf_stats
+from services.ws_clients import WebSocketClientInfo, get_ws_clients
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/admin", tags=["admin"])
+
+REPLICATION_METRICS_TIMEOUT = 3.0
+
+
+class CheckpointResult(BaseModel):
+    """Outcome of a WAL checkpoint."""
+
+    at: int
+    mode: str
+    busy: bool  # A reader or writer kept the checkpoint from finishing
+    log_frames: int  # Frames in the WAL (-1 when not in WAL mode)
+    checkpointed_frames: int
+
+
+class ReplicationStatus(BaseModel):
+    """Metrics scraped from the replication sidecar."""
+
+    metrics_url: str
+    reachable: bool
+    error: str | None = None
+    metrics: dict[str, float] = {}  # litestream_* samples, keyed by name and labels
+
+
+class DatabaseStatus(BaseModel):
+    """SQLite journal and checkpoint state."""
+
+    path: str
+    journal_mode: str
+    size: int  # Database file in bytes
+    wal_size: int  # Write-ahead log file in bytes
+    wal_autocheckpoint_pages: int
+    checkpoint_interval_minutes: float
+    last_checkpoint: CheckpointResult | None = None  # Since startup
+    replication: ReplicationStatus | None = None  # None without replication_metrics_url
+
+
+def _parse_metrics(text: str) -> dict[str, float]:
+    """Litestream samples from a Prometheus text exposition."""
+    metrics = {}
+    for line in text.splitlines():
+        if not line.startswith("litestream_"):
+            continue
+        name, _, value = line.rpartition(" ")
+        try:
+            metrics[name] = float(value)
+        except ValueError:
+            continue
+    return metrics
+
+
+async def _replication_status(url: str) -> ReplicationStatus:
+    try:
+        async with httpx.AsyncClient(timeout=REPLICATION_METRICS_TIMEOUT) as client:
+            response = await client.get(url)
+        response.raise_for_status()
+    except httpx.HTTPError as e:
+        logger.debug(f"Replication metrics at {url} unavailable: {e}")
+        return ReplicationStatus(metrics_url=url, reachable=False, error=str(e))
+    return ReplicationStatus(metrics_url=url, reachable=True, metrics=_parse_metrics(response.text))
+
+
+@router.get("/db-status", response_model=DatabaseStatus)
+async def get_db_status():
+    """Get the journal mode, WAL size, last checkpoint and replication metrics."""
+    db = await get_db()
+    url = settings.replication_metrics_url
+    return DatabaseStatus(
+        path=str(db.db_path),
+        journal_mode=await db.get_journal_mode(),
+        size=await db.get_database_size(),
+        wal_size=db.get_wal_size(),
+        wal_autocheckpoint_pages=settings.wal_autocheckpoint_pages,
+        checkpoint_interval_minutes=settings.wal_checkpoint_interval_minutes,
+        last_checkpoint=db.last_checkpoint,
+        replication=await _replication_status(url) if url else None,
+    )
+
+
+@router.post("/db-checkpoint", response_model=CheckpointResult)
+async def checkpoint_db(
+    mode: Literal["PASSIVE", "FULL", "RESTART", "TRUNCATE"] = Query(
+        default="PASSIVE", description="TRUNCATE waits for readers and empties the WAL file"
+    ),
+):
+    """Checkpoint the write-ahead log now."""
+    db = await get_db()
+    return await db.checkpoint(mode)
+
+
+@router.get("/perf", response_model=PerfMetrics)
+async def get_perf_metrics():
+    """Get request latency and status codes per route, and the slow query log."""
+    return get_perf_stats().snapshot()
+
+
+@router.delete("/perf", status_code=204)
+async def reset_perf_metrics():
+    """Reset the request and query metrics."""
+    get_perf_stats().reset()
+
+
+@router.get("/ws-clients", response_model=list[WebSocketClientInfo])
+async def list_ws_clients():
+    """List the connected WebSocket clients with their identity, subscription and last activity."""
+    return get_ws_clients().snapshot()
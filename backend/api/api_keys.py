@@ -235,29 +235,17 @@ async def delete_api_key(key_id: int):
 # === API Key Validation Dependency ===
 
 
-async def validate_api_key(
-    x_api_key: str | None = Header(None, alias="X-API-Key"),
-    authorization: str | None = Header(None),
-) -> dict:
-    """Validate API key from request headers.
-
-    Accepts both:
-    - X-API-Key: <key>
-    - Authorization: Bearer <key>
-    """
-    api_key_value = None
-
+def api_key_from_headers(x_api_key: str | None, authorization: str | None) -> str | None:
+    """The key given as X-API-Key or as an Authorization bearer token."""
     if x_api_key:
-        api_key_value = x_api_key
-    elif authorization and authorization.startswith("Bearer "):
-        api_key_value = authorization.replace("Bearer ", "")
+        return x_api_key
+    if authorization and authorization.startswith("Bearer "):
+        return authorization.replace("Bearer ", "")
+    return None
 
-    if not api_key_value:
-        raise HTTPException(
-            status_code=401,
-            detail="API key required. Provide 'X-API-Key' header or 'Authorization: Bearer <key>'",
-        )
 
+async def authenticate_api_key(api_key_value: str) -> dict | None:
+    """Look up an enabled API key and record its use; None if there is none."""
     # Get all enabled API keys and check them
     db = await get_db()
     async with db.conn.execute(
@@ -286,8 +274,31 @@ async def validate_api_key(
                 "can_write": bool(can_write),
                 "can_control": bool(can_control),
             }
+    return None
+
+
+async def validate_api_key(
+    x_api_key: str | None = Header(None, alias="X-API-Key"),
+    authorization: str | None = Header(None),
+) -> dict:
+    """Validate API key from request headers.
+
+    Accepts both:
+    - X-API-Key: <key>
+    - Authorization: Bearer <key>
+    """
+    api_key_value = api_key_from_headers(x_api_key, authorization)
+
+    if not api_key_value:
+        raise HTTPException(
+            status_code=401,
+            detail="API key required. Provide 'X-API-Key' header or 'Authorization: Bearer <key>'",
+        )
 
-    raise HTTPException(status_code=401, detail="Invalid API key")
+    api_key = await authenticate_api_key(api_key_value)
+    if not api_key:
+        raise HTTPException(status_code=401, detail="Invalid API key")
+    return api_key
 
 
 def require_permission(permission: str):
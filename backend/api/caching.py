@@ -0,0 +1,36 @@
+"""ETag/If-None-Match support for polled list endpoints.
+
+The web UI and the device poll these lists every few seconds; answering an
+unchanged list with 304 Not Modified saves re-downloading it over slow Wi-Fi.
+The ETag is a hash of the response body, so it also covers live data (printer
+state) that has no updated_at column.
+"""
+
+import hashlib
+
+from fastapi import Request, Response
+from pydantic import TypeAdapter
+
+
+def make_etag(body: bytes) -> str:
+    """Strong ETag for a response body."""
+    return f'"{hashlib.sha256(body).hexdigest()[:32]}"'
+
+
+def etag_matches(if_none_match: str | None, etag: str) -> bool:
+    """Whether an If-None-Match header matches the ETag (weak comparison, per RFC 9110)."""
+    if not if_none_match:
+        return False
+    candidates = [tag.strip().removeprefix("W/") for tag in if_none_match.split(",")]
+    return "*" in candidates or etag in candidates
+
+
+def etag_response(request: Request, data, response_type) -> Response:
+    """Serialize data as JSON with an ETag, or 304 if the client already has it."""
+    body = TypeAdapter(response_type).dump_json(data)
+    etag = make_etag(body)
+    # no-cache: browsers keep the copy but revalidate it on every request
+    headers = {"ETag": etag, "Cache-Control": "no-cache"}
+    if etag_matches(request.headers.get("If-None-Match"), etag):
+        return Response(status_code=304, headers=headers)
+    return Response(content=body, media_type="application/json", headers=headers)
@@ -8,6 +8,8 @@ from db.database import get_db
 from fastapi import APIRouter, HTTPException
 from fastapi.responses import StreamingResponse
 from pydantic import BaseModel
+from services.colors import ResolvedColor, resolve_color
+from services.jobs import JobContext, job_kind
 
 logger = logging.getLogger(__name__)
 router = APIRouter(prefix="/colors", tags=["colors"])
@@ -112,6 +114,16 @@ async def lookup_color(manufacturer: str, color_name: str, material: str | None
     return ColorLookupResult(found=False)
 
 
+@router.get("/resolve", response_model=ResolvedColor)
+async def resolve_color_value(value: str, manufacturer: str | None = None, material: str | None = None):
+    """Resolve a hex color ("#f80", "FF8800FF") or a color name ("red", "Jade White") to a swatch."""
+    db = await get_db()
+    resolved = await resolve_color(db, value, manufacturer, material)
+    if not resolved:
+        raise HTTPException(status_code=404, detail=f"Unknown color '{value}'")
+    return resolved
+
+
 @router.get("/search")
 async def search_colors(manufacturer: str | None = None, material: str | None = None) -> list[ColorEntry]:
     """Search colors by manufacturer and/or material."""
@@ -161,128 +173,153 @@ class SyncResult(BaseModel):
     error: str | None = None
 
 
+async def _sync_filamentcolors(db):
+    """Import colors from the FilamentColors.xyz API, yielding a progress message per page.
+
+    The last message is of type "complete" or "error".
+    """
+    added = 0
+    skipped = 0
+    total_fetched = 0
+    total_available = 0
+
+    try:
+        async with httpx.AsyncClient(timeout=120.0) as client:
+            # Fetch all swatches (paginated using page parameter)
+            page = 1
+
+            while True:
+                response = await client.get(
+                    f"{FILAMENT_COLORS_API}/swatch/",
+                    params={"page": page},
+                )
+                response.raise_for_status()
+                data = response.json()
+
+                # Update total count
+                total_available = data.get("count", total_available)
+
+                results = data.get("results", [])
+                if not results:
+                    break
+
+                for swatch in results:
+                    total_fetched += 1
+
+                    # Extract manufacturer name from nested object
+                    manufacturer_data = swatch.get("manufacturer")
+                    if isinstance(manufacturer_data, dict):
+                        manufacturer_name = manufacturer_data.get("name", "")
+                    else:
+                        manufacturer_name = ""
+
+                    # Extract filament type name from nested object
+                    filament_type_data = swatch.get("filament_type")
+                    if isinstance(filament_type_data, dict):
+                        material = filament_type_data.get("name", "")
+                    else:
+                        material = None
+
+                    color_name = swatch.get("color_name", "")
+                    hex_color = swatch.get("hex_color", "")
+
+                    if not manufacturer_name or not color_name or not hex_color:
+                        skipped += 1
+                        continue
+
+                    # Ensure hex color has # prefix
+                    if not hex_color.startswith("#"):
+                        hex_color = f"#{hex_color}"
+
+                    # Try to add using INSERT OR IGNORE to handle duplicates
+                    try:
+                        cursor = await db.conn.execute(
+                            "INSERT OR IGNORE INTO color_catalog (manufacturer, color_name, hex_color, material, is_default) VALUES (?, ?, ?, ?, 0)",
+                            (manufacturer_name, color_name, hex_color.upper(), material),
+                        )
+                        if cursor.rowcount > 0:
+                            added += 1
+                        else:
+                            skipped += 1
+                    except Exception as e:
+                        logger.warning(f"Failed to insert color {manufacturer_name} - {color_name}: {e}")
+                        skipped += 1
+
+                # Commit after each page
+                await db.conn.commit()
+
+                # Send progress update after each page
+                progress = {
+                    "type": "progress",
+                    "added": added,
+                    "skipped": skipped,
+                    "total_fetched": total_fetched,
+                    "total_available": total_available,
+                }
+                yield progress
+
+                # Check if there are more pages
+                if not data.get("next") or total_fetched >= total_available:
+                    break
+                page += 1
+
+        # Send final result
+        result = {
+            "type": "complete",
+            "added": added,
+            "skipped": skipped,
+            "total_fetched": total_fetched,
+            "total_available": total_available,
+        }
+        yield result
+
+    except httpx.HTTPError as e:
+        logger.error(f"HTTP error syncing from FilamentColors.xyz: {e}")
+        error_result = {
+            "type": "error",
+            "added": added,
+            "skipped": skipped,
+            "total_fetched": total_fetched,
+            "total_available": total_available,
+            "error": f"HTTP error: {str(e)}",
+        }
+        yield error_result
+    except Exception as e:
+        logger.error(f"Error syncing from FilamentColors.xyz: {e}")
+        error_result = {
+            "type": "error",
+            "added": added,
+            "skipped": skipped,
+            "total_fetched": total_fetched,
+            "total_available": total_available,
+            "error": "An unexpected error occurred during sync",
+        }
+        yield error_result
+
+
 @router.post("/sync")
 async def sync_from_filamentcolors():
     """Sync colors from FilamentColors.xyz API with progress streaming."""
 
     async def generate():
         db = await get_db()
-        added = 0
-        skipped = 0
-        total_fetched = 0
-        total_available = 0
-
-        try:
-            async with httpx.AsyncClient(timeout=120.0) as client:
-                # Fetch all swatches (paginated using page parameter)
-                page = 1
-
-                while True:
-                    response = await client.get(
-                        f"{FILAMENT_COLORS_API}/swatch/",
-                        params={"page": page},
-                    )
-                    response.raise_for_status()
-                    data = response.json()
-
-                    # Update total count
-                    total_available = data.get("count", total_available)
-
-                    results = data.get("results", [])
-                    if not results:
-                        break
-
-                    for swatch in results:
-                        total_fetched += 1
-
-                        # Extract manufacturer name from nested object
-                        manufacturer_data = swatch.get("manufacturer")
-                        if isinstance(manufacturer_data, dict):
-                            manufacturer_name = manufacturer_data.get("name", "")
-                        else:
-                            manufacturer_name = ""
+        async for message in _sync_filamentcolors(db):
+            yield f"data: {json.dumps(message)}\n\n"
 
-                        # Extract filament type name from nested object
-                        filament_type_data = swatch.get("filament_type")
-                        if isinstance(filament_type_data, dict):
-                            material = filament_type_data.get("name", "")
-                        else:
-                            material = None
-
-                        color_name = swatch.get("color_name", "")
-                        hex_color = swatch.get("hex_color", "")
-
-                        if not manufacturer_name or not color_name or not hex_color:
-                            skipped += 1
-                            continue
-
-                        # Ensure hex color has # prefix
-                        if not hex_color.startswith("#"):
-                            hex_color = f"#{hex_color}"
-
-                        # Try to add using INSERT OR IGNORE to handle duplicates
-                        try:
-                            cursor = await db.conn.execute(
-                                "INSERT OR IGNORE INTO color_catalog (manufacturer, color_name, hex_color, material, is_default) VALUES (?, ?, ?, ?, 0)",
-                                (manufacturer_name, color_name, hex_color.upper(), material),
-                            )
-                            if cursor.rowcount > 0:
-                                added += 1
-                            else:
-                                skipped += 1
-                        except Exception as e:
-                            logger.warning(f"Failed to insert color {manufacturer_name} - {color_name}: {e}")
-                            skipped += 1
+    return StreamingResponse(generate(), media_type="text/event-stream")
 
-                    # Commit after each page
-                    await db.conn.commit()
-
-                    # Send progress update after each page
-                    progress = {
-                        "type": "progress",
-                        "added": added,
-                        "skipped": skipped,
-                        "total_fetched": total_fetched,
-                        "total_available": total_available,
-                    }
-                    yield f"data: {json.dumps(progress)}\n\n"
-
-                    # Check if there are more pages
-                    if not data.get("next") or total_fetched >= total_available:
-                        break
-                    page += 1
-
-            # Send final result
-            result = {
-                "type": "complete",
-                "added": added,
-                "skipped": skipped,
-                "total_fetched": total_fetched,
-                "total_available": total_available,
-            }
-            yield f"data: {json.dumps(result)}\n\n"
-
-        except httpx.HTTPError as e:
-            logger.error(f"HTTP error syncing from FilamentColors.xyz: {e}")
-            error_result = {
-                "type": "error",
-                "added": added,
-                "skipped": skipped,
-                "total_fetched": total_fetched,
-                "total_available": total_available,
-                "error": f"HTTP error: {str(e)}",
-            }
-            yield f"data: {json.dumps(error_result)}\n\n"
-        except Exception as e:
-            logger.error(f"Error syncing from FilamentColors.xyz: {e}")
-            error_result = {
-                "type": "error",
-                "added": added,
-                "skipped": skipped,
-                "total_fetched": total_fetched,
-                "total_available": total_available,
-                "error": "An unexpected error occurred during sync",
-            }
-            yield f"data: {json.dumps(error_result)}\n\n"
 
-    return StreamingResponse(generate(), media_type="text/event-stream")
+@job_kind("color_import")
+async def color_import_job(ctx: JobContext, params: dict) -> dict:
+    """Background job importing the FilamentColors.xyz catalog."""
+    db = await get_db()
+    async for message in _sync_filamentcolors(db):
+        if message["type"] == "error":
+            raise RuntimeError(message["error"])
+        result = {k: v for k, v in message.items() if k != "type"}
+        await ctx.update(
+            progress=message["total_fetched"],
+            total=message["total_available"],
+            message=f"{message['added']} colors added",
+        )
+    return result
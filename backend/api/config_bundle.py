@@ -0,0 +1,214 @@
+"""Server configuration export/import.
+
+Exports the setup of a server (settings and thresholds, printers, spool
+templates, custom spool catalog entries, Moonraker printers, webhooks,
+notification channels and the WLED location mappings) as one JSON bundle,
+and imports it on another install, for moving SpoolBuddy to a new machine.
+Spools and history are not part of the bundle.
+
+Credentials are left out: webhook secrets, Moonraker API keys, notification
+channel tokens and the Bambu Cloud login always, printer access codes unless
+asked for. Imported notification channels missing their credentials are
+disabled until they are filled in again.
+"""
+
+import logging
+import time
+
+from api.cloud import CLOUD_EMAIL_KEY, CLOUD_TOKEN_KEY
+from api.moonraker import MoonrakerPrinterCreate
+from api.notifications import ChannelCreate
+from api.projects import ACTIVE_PROJECT_SETTING
+from api.templates import SpoolTemplateCreate
+from api.webhooks import WebhookCreate
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from models import PrinterCreate, PrinterUpdate
+from pydantic import BaseModel, Field
+from services.moonraker import get_moonraker_manager
+from services.notifiers import NOTIFIER_TYPES
+from services.reconciliation import LAST_SCANNED_SETTING
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/config", tags=["config"])
+
+CONFIG_BUNDLE_VERSION = 1
+
+# Settings that are credentials or state of this install rather than configuration
+EXCLUDED_SETTINGS = {
+    CLOUD_TOKEN_KEY,
+    CLOUD_EMAIL_KEY,
+    ACTIVE_PROJECT_SETTING,
+    LAST_SCANNED_SETTING,
+    "debug_logging_enabled",
+    "debug_logging_timestamp",
+}
+
+
+class SpoolCatalogEntry(BaseModel):
+    """Custom empty spool weight."""
+
+    name: str
+    weight: int = Field(ge=0)
+
+
+class ConfigBundle(BaseModel):
+    """Server configuration, as exported and imported."""
+
+    version: int = CONFIG_BUNDLE_VERSION
+    exported_at: int | None = None
+    settings: dict[str, str] = {}  # Thresholds, device, kiosk and WLED settings (with location mappings)
+    printers: list[PrinterCreate] = []
+    spool_templates: list[SpoolTemplateCreate] = []
+    spool_catalog: list[SpoolCatalogEntry] = []  # Custom entries only, defaults are seeded on every install
+    moonraker_printers: list[MoonrakerPrinterCreate] = []
+    webhooks: list[WebhookCreate] = []
+    notification_channels: list[ChannelCreate] = []
+
+
+class ConfigImportResult(BaseModel):
+    """Items imported per section, and items skipped because they already exist."""
+
+    imported: dict[str, int]
+    skipped: dict[str, int]
+
+
+def _strip_channel_secrets(channel: dict) -> dict:
+    notifier_cls = NOTIFIER_TYPES.get(channel["type"])
+    secret_keys = notifier_cls.secret_config if notifier_cls else ()
+    return {k: v for k, v in channel["config"].items() if k not in secret_keys}
+
+
+@router.get("/export", response_model=ConfigBundle)
+async def export_config(include_access_codes: bool = Query(default=False)):
+    """Export the server configuration (without credentials) as a JSON bundle."""
+    db = await get_db()
+    settings = {k: v for k, v in (await db.get_settings()).items() if k not in EXCLUDED_SETTINGS}
+
+    printers = []
+    for printer in await db.get_printers():
+        data = PrinterCreate.model_validate(printer.model_dump())
+        if not include_access_codes:
+            data.access_code = None
+        printers.append(data)
+
+    return ConfigBundle(
+        exported_at=int(time.time()),
+        settings=settings,
+        printers=printers,
+        spool_templates=[SpoolTemplateCreate.model_validate(t) for t in await db.get_spool_templates()],
+        spool_catalog=[
+            SpoolCatalogEntry(name=e["name"], weight=e["weight"])
+            for e in await db.get_spool_catalog()
+            if not e["is_default"]
+        ],
+        moonraker_printers=[
+            MoonrakerPrinterCreate(name=p["name"], url=p["url"], enabled=p["enabled"])
+            for p in await db.get_moonraker_printers()
+        ],
+        webhooks=[
+            WebhookCreate(name=w["name"], url=w["url"], events=w["events"], enabled=w["enabled"])
+            for w in await db.get_webhooks()
+        ],
+        notification_channels=[
+            ChannelCreate(
+                name=c["name"],
+                type=c["type"],
+                config=_strip_channel_secrets(c),
+                events=c["events"],
+                enabled=c["enabled"],
+            )
+            for c in await db.get_notification_channels()
+        ],
+    )
+
+
+@router.post("/import", response_model=ConfigImportResult)
+async def import_config(bundle: ConfigBundle):
+    """Import a configuration bundle.
+
+    Settings are overwritten and printers are matched by serial (an existing
+    printer keeps its access code unless the bundle has one). Everything else
+    is matched by name and only added if it doesn't exist yet, so importing
+    the same bundle twice changes nothing.
+    """
+    if bundle.version > CONFIG_BUNDLE_VERSION:
+        raise HTTPException(
+            status_code=400, detail=f"Bundle version {bundle.version} is newer than this server supports"
+        )
+
+    db = await get_db()
+    imported = dict.fromkeys(ConfigBundle.model_fields.keys() - {"version", "exported_at"}, 0)
+    skipped = dict(imported)
+
+    for key, value in bundle.settings.items():
+        if key in EXCLUDED_SETTINGS:
+            skipped["settings"] += 1
+            continue
+        await db.set_setting(key, value)
+        imported["settings"] += 1
+
+    for printer in bundle.printers:
+        if await db.get_printer(printer.serial):
+            update = PrinterUpdate(**printer.model_dump(exclude={"serial"}, exclude_none=True))
+            await db.update_printer(printer.serial, update)
+        else:
+            await db.create_printer(printer)
+        imported["printers"] += 1
+
+    template_names = {t["name"] for t in await db.get_spool_templates()}
+    for template in bundle.spool_templates:
+        if template.name in template_names:
+            skipped["spool_templates"] += 1
+            continue
+        await db.create_spool_template(**template.model_dump())
+        imported["spool_templates"] += 1
+
+    catalog_names = {e["name"] for e in await db.get_spool_catalog()}
+    for entry in bundle.spool_catalog:
+        if entry.name in catalog_names:
+            skipped["spool_catalog"] += 1
+            continue
+        await db.add_spool_catalog_entry(entry.name, entry.weight)
+        imported["spool_catalog"] += 1
+
+    moonraker_names = {p["name"] for p in await db.get_moonraker_printers()}
+    for data in bundle.moonraker_printers:
+        if data.name in moonraker_names:
+            skipped["moonraker_printers"] += 1
+            continue
+        printer = await db.create_moonraker_printer(
+            name=data.name, url=data.url, api_key=data.api_key, enabled=data.enabled
+        )
+        get_moonraker_manager().start(printer)
+        imported["moonraker_printers"] += 1
+
+    webhook_names = {w["name"] for w in await db.get_webhooks()}
+    for data in bundle.webhooks:
+        if data.name in webhook_names:
+            skipped["webhooks"] += 1
+            continue
+        await db.create_webhook(
+            name=data.name, url=data.url, events=data.events, secret=data.secret, enabled=data.enabled
+        )
+        imported["webhooks"] += 1
+
+    channel_names = {c["name"] for c in await db.get_notification_channels()}
+    for data in bundle.notification_channels:
+        notifier_cls = NOTIFIER_TYPES.get(data.type)
+        if data.name in channel_names or not notifier_cls:
+            skipped["notification_channels"] += 1
+            continue
+        enabled = data.enabled
+        try:
+            notifier_cls.validate_config(data.config)
+        except ValueError:
+            enabled = False  # Credentials weren't exported
+        await db.create_notification_channel(
+            name=data.name, channel_type=data.type, config=data.config, events=data.events, enabled=enabled
+        )
+        imported["notification_channels"] += 1
+
+    logger.info(f"Imported configuration bundle: {imported}")
+    return ConfigImportResult(imported=imported, skipped=skipped)
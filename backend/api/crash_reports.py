@@ -0,0 +1,84 @@
+"""Device crash report endpoints.
+
+The firmware stores panic and watchdog information in flash and uploads it
+here on the next boot, so field failures can be diagnosed without a serial
+console attached.
+"""
+
+import logging
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel, Field
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/devices", tags=["devices"])
+
+
+class CrashReportCreate(BaseModel):
+    """Crash report uploaded by a device."""
+
+    firmware_version: str | None = None
+    reset_reason: str = Field(min_length=1, max_length=32)  # e.g. "panic", "task_wdt", "brownout"
+    panic_message: str | None = Field(default=None, max_length=1024)
+    task: str | None = Field(default=None, max_length=32)  # Task that crashed, from the core dump
+    backtrace: str | None = Field(default=None, max_length=4096)  # Space-separated PC addresses from the core dump
+    crashed_at: int | None = None  # Device clock at the time of the panic, if it was set
+
+
+class CrashReport(BaseModel):
+    """Stored crash report."""
+
+    id: int
+    device_id: str
+    firmware_version: str | None = None
+    reset_reason: str
+    panic_message: str | None = None
+    task: str | None = None
+    backtrace: str | None = None
+    crashed_at: int | None = None
+    created_at: int | None = None
+
+
+@router.get("/crash-reports", response_model=list[CrashReport])
+async def list_all_crash_reports(limit: int = Query(default=50, le=500)):
+    """List recent crash reports from all devices, newest first."""
+    db = await get_db()
+    return await db.get_crash_reports(limit=limit)
+
+
+@router.post("/{device_id}/crash-reports", response_model=CrashReport, status_code=201)
+async def upload_crash_report(device_id: str, data: CrashReportCreate):
+    """Store a crash report uploaded by a device."""
+    db = await get_db()
+    report = await db.create_crash_report(device_id=device_id, **data.model_dump())
+    logger.warning(f"Crash report from {device_id}: {data.reset_reason} (firmware {data.firmware_version})")
+    return report
+
+
+@router.get("/{device_id}/crash-reports", response_model=list[CrashReport])
+async def list_device_crash_reports(device_id: str, limit: int = Query(default=50, le=500)):
+    """List recent crash reports for a device, newest first."""
+    db = await get_db()
+    return await db.get_crash_reports(device_id=device_id, limit=limit)
+
+
+@router.get("/{device_id}/crash-reports/{report_id}", response_model=CrashReport)
+async def get_crash_report(device_id: str, report_id: int):
+    """Get a single crash report."""
+    db = await get_db()
+    report = await db.get_crash_report(report_id)
+    if not report or report["device_id"] != device_id:
+        raise HTTPException(status_code=404, detail="Crash report not found")
+    return report
+
+
+@router.delete("/{device_id}/crash-reports/{report_id}", status_code=204)
+async def delete_crash_report(device_id: str, report_id: int):
+    """Delete a crash report once it has been looked at."""
+    db = await get_db()
+    report = await db.get_crash_report(report_id)
+    if not report or report["device_id"] != device_id:
+        raise HTTPException(status_code=404, detail="Crash report not found")
+    await db.delete_crash_report(report_id)
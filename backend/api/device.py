@@ -9,11 +9,14 @@ import logging
 import socket
 from datetime import datetime
 
-from fastapi import APIRouter, HTTPException
+from fastapi import APIRouter, HTTPException, Query
 from pydantic import BaseModel
 
 logger = logging.getLogger(__name__)
 
+# Load cell channels supported by the device (NAU7802 CH1/CH2)
+SCALE_CHANNELS = 2
+
 
 def _is_private_ip(ip: str) -> bool:
     """Check if an IP address is private/local (not routable on public internet).
@@ -238,31 +241,40 @@ async def factory_reset_device():
 
 
 @router.post("/scale/tare")
-async def scale_tare():
-    """Send tare (zero) command to scale."""
+async def scale_tare(channel: int = Query(default=0, ge=0, lt=SCALE_CHANNELS)):
+    """Send tare (zero) command to scale.
+
+    Args:
+        channel: Load cell channel (0 = first scale, 1 = second scale)
+    """
     from main import is_display_connected, queue_display_command
 
     if not is_display_connected():
         raise HTTPException(status_code=400, detail="No device connected")
 
-    queue_display_command("scale_tare")
+    # Channel 0 keeps the plain command so older firmware still understands it
+    queue_display_command("scale_tare" if channel == 0 else f"scale_tare:{channel}")
     return {"success": True, "message": "Tare command queued"}
 
 
 @router.post("/scale/calibrate")
-async def scale_calibrate(known_weight: float):
+async def scale_calibrate(known_weight: float, channel: int = Query(default=0, ge=0, lt=SCALE_CHANNELS)):
     """Send calibration command to scale with known weight.
 
     Args:
         known_weight: The known weight in grams placed on the scale
+        channel: Load cell channel (0 = first scale, 1 = second scale)
     """
     from main import is_display_connected, queue_display_command
 
     if not is_display_connected():
         raise HTTPException(status_code=400, detail="No device connected")
 
-    # Queue calibrate command with weight parameter
-    queue_display_command(f"scale_calibrate:{known_weight:.1f}")
+    # Queue calibrate command with weight parameter (and channel, if not the first)
+    command = f"scale_calibrate:{known_weight:.1f}"
+    if channel != 0:
+        command += f":{channel}"
+    queue_display_command(command)
     return {"success": True, "message": f"Calibrate command queued (known weight: {known_weight}g)"}
 
 
@@ -0,0 +1,73 @@
+"""Device diagnostics endpoints.
+
+The firmware uploads a diagnostics snapshot (memory, Wi-Fi signal, I2C error
+counters, NFC bridge, scale and frame rate) about once a minute. Only the
+latest snapshot per device is kept, in memory: it is for looking at a device
+while supporting it, not for history.
+"""
+
+import time
+
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel, Field
+
+router = APIRouter(prefix="/devices", tags=["devices"])
+
+
+class MemoryUsage(BaseModel):
+    """Heap region usage in bytes."""
+
+    free: int = 0
+    total: int = 0
+    min_free: int = 0  # Low-water mark since boot
+
+
+class I2cCounters(BaseModel):
+    """Failed transactions of one shared I2C bus client."""
+
+    client: str
+    errors: int = 0
+    timeouts: int = 0  # Gave up waiting for the bus lock
+
+
+class DeviceDiagnostics(BaseModel):
+    """Diagnostics snapshot uploaded by a device."""
+
+    firmware_version: str | None = None
+    uptime_s: int = 0
+    heap: MemoryUsage = Field(default_factory=MemoryUsage)
+    psram: MemoryUsage | None = None  # None on boards without PSRAM
+    wifi_rssi: int | None = None  # dBm, None while disconnected
+    i2c: list[I2cCounters] = []
+    nfc_bridge_firmware: str | None = None  # None without a Pico bridge
+    nfc_bridge_status: str | None = None
+    scale_raw: list[int] = []  # Raw ADC reading per channel
+    fps: float = 0.0
+
+
+class StoredDeviceDiagnostics(DeviceDiagnostics):
+    """Latest diagnostics snapshot of a device."""
+
+    device_id: str
+    received_at: int
+
+
+# Latest snapshot per device ID
+_latest: dict[str, StoredDeviceDiagnostics] = {}
+
+
+@router.post("/{device_id}/diagnostics", response_model=StoredDeviceDiagnostics)
+async def upload_diagnostics(device_id: str, data: DeviceDiagnostics):
+    """Store the latest diagnostics snapshot of a device."""
+    stored = StoredDeviceDiagnostics(device_id=device_id, received_at=int(time.time()), **data.model_dump())
+    _latest[device_id] = stored
+    return stored
+
+
+@router.get("/{device_id}/diagnostics", response_model=StoredDeviceDiagnostics)
+async def get_diagnostics(device_id: str):
+    """Get the latest diagnostics snapshot of a device."""
+    stored = _latest.get(device_id)
+    if not stored:
+        raise HTTPException(status_code=404, detail="No diagnostics received from this device")
+    return stored
@@ -292,11 +292,8 @@ async def start_discovery():
     return DiscoveryStatus(running=True)
 
 
-@router.post("/stop", response_model=DiscoveryStatus)
-async def stop_discovery():
-    """Stop printer discovery."""
-    global _state
-
+async def stop_printer_discovery():
+    """Stop a running discovery scan and release its sockets."""
     _state.running = False
     if _state.task:
         _state.task.cancel()
@@ -306,6 +303,11 @@ async def stop_discovery():
             pass
         _state.task = None
 
+
+@router.post("/stop", response_model=DiscoveryStatus)
+async def stop_discovery():
+    """Stop printer discovery."""
+    await stop_printer_discovery()
     logger.info("Stopped printer discovery")
     return DiscoveryStatus(running=False)
 
@@ -2,6 +2,11 @@
 ESP32 Firmware OTA Update API Routes
 
 Handles firmware version checking and OTA binary serving for the SpoolBuddy device.
+
+Release images can come with a detached ed25519 signature (`<image>.bin.sig`,
+128 hex characters, written by firmware/tools/ota-sign). The OTA download
+passes it to the device in the X-Firmware-Signature header; firmware built
+with a public key refuses images without a valid one.
 """
 
 import hashlib
@@ -9,6 +14,7 @@ import logging
 import re
 import struct
 from datetime import datetime, timedelta
+from pathlib import Path
 
 import httpx
 from config import GITHUB_REPO, settings
@@ -27,6 +33,10 @@ _firmware_cache: dict | None = None
 _firmware_cache_time: datetime | None = None
 CACHE_DURATION = timedelta(minutes=5)
 
+# Detached image signatures (see firmware/core/src/ota.rs)
+SIGNATURE_HEADER = "X-Firmware-Signature"
+SIGNATURE_RE = re.compile(r"^[0-9a-fA-F]{128}$")
+
 
 class FirmwareVersion(BaseModel):
     version: str
@@ -34,6 +44,7 @@ class FirmwareVersion(BaseModel):
     size: int | None = None
     checksum: str | None = None
     url: str | None = None
+    signed: bool = False
 
 
 class FirmwareCheck(BaseModel):
@@ -45,6 +56,23 @@ class FirmwareCheck(BaseModel):
     error: str | None = None
 
 
+def _signature_path(filepath: Path) -> Path:
+    """Signature file of a firmware image (spoolbuddy-1.0.0.bin -> spoolbuddy-1.0.0.bin.sig)."""
+    return filepath.with_name(filepath.name + ".sig")
+
+
+def _read_signature(filepath: Path) -> str | None:
+    """Hex signature of a firmware image, None if unsigned or the signature file is malformed."""
+    sig_path = _signature_path(filepath)
+    if not sig_path.exists():
+        return None
+    signature = sig_path.read_text().strip()
+    if not SIGNATURE_RE.match(signature):
+        logger.warning(f"Ignoring malformed firmware signature {sig_path.name}")
+        return None
+    return signature.lower()
+
+
 def _get_local_firmware() -> list[FirmwareVersion]:
     """Get list of locally available firmware files."""
     if not FIRMWARE_DIR.exists():
@@ -61,6 +89,7 @@ def _get_local_firmware() -> list[FirmwareVersion]:
                 version=version,
                 filename=f.name,
                 size=f.stat().st_size,
+                signed=_read_signature(f) is not None,
             )
         )
 
@@ -288,14 +317,18 @@ async def get_ota_firmware(version: str | None = None):
         raise HTTPException(status_code=404, detail="Firmware file not found")
 
     # Return binary with ESP32 OTA-compatible headers
+    headers = {
+        "Content-Length": str(filepath.stat().st_size),
+        "X-Firmware-Version": firmware.version,
+    }
+    signature = _read_signature(filepath)
+    if signature:
+        headers[SIGNATURE_HEADER] = signature
     return FileResponse(
         filepath,
         media_type="application/octet-stream",
         filename=firmware.filename,
-        headers={
-            "Content-Length": str(filepath.stat().st_size),
-            "X-Firmware-Version": firmware.version,
-        },
+        headers=headers,
     )
 
 
@@ -417,12 +450,14 @@ class FirmwareUploadResponse(BaseModel):
     filename: str | None = None
     size: int | None = None
     checksum: str | None = None
+    signed: bool = False
 
 
 @router.post("/upload", response_model=FirmwareUploadResponse)
 async def upload_firmware(
     file: UploadFile = File(...),
     version: str | None = Form(None),
+    signature: UploadFile | None = File(None),
 ):
     """
     Upload a new firmware binary.
@@ -432,6 +467,7 @@ async def upload_firmware(
     Args:
         file: The firmware binary file (.bin)
         version: Optional version override (extracted from binary if not provided)
+        signature: Optional detached signature (.sig file written by ota-sign)
 
     Returns:
         Upload result with version and filename
@@ -446,6 +482,14 @@ async def upload_firmware(
     except Exception as e:
         raise HTTPException(status_code=400, detail=f"Failed to read file: {e}")
 
+    # Signatures are only checked for format here, the device verifies them
+    signature_hex = None
+    if signature is not None:
+        signature_hex = (await signature.read()).decode("ascii", errors="replace").strip()
+        if not SIGNATURE_RE.match(signature_hex):
+            raise HTTPException(status_code=400, detail="Invalid signature. Expected 128 hex characters")
+        signature_hex = signature_hex.lower()
+
     # Validate firmware
     try:
         metadata = _validate_esp32_firmware(content)
@@ -477,11 +521,14 @@ async def upload_firmware(
     checksum = hashlib.sha256(content).hexdigest()[:16]
     filename = f"spoolbuddy-{firmware_version}.bin"
     filepath = FIRMWARE_DIR / filename
+    sig_path = _signature_path(filepath)
 
     # Check for existing file with same version
     if filepath.exists():
         existing_checksum = hashlib.sha256(filepath.read_bytes()).hexdigest()[:16]
         if existing_checksum == checksum:
+            if signature_hex:
+                sig_path.write_text(signature_hex + "\n")
             return FirmwareUploadResponse(
                 success=True,
                 message=f"Firmware {firmware_version} already exists (identical)",
@@ -489,16 +536,20 @@ async def upload_firmware(
                 filename=filename,
                 size=len(content),
                 checksum=checksum,
+                signed=_read_signature(filepath) is not None,
             )
         else:
             # Different file with same version - rename old one
             backup_name = f"spoolbuddy-{firmware_version}.{existing_checksum}.bin.bak"
             filepath.rename(FIRMWARE_DIR / backup_name)
             logger.info(f"Backed up existing firmware to {backup_name}")
+            sig_path.unlink(missing_ok=True)
 
     # Save firmware
     try:
         filepath.write_bytes(content)
+        if signature_hex:
+            sig_path.write_text(signature_hex + "\n")
     except Exception as e:
         raise HTTPException(status_code=500, detail=f"Failed to save firmware: {e}")
 
@@ -511,6 +562,7 @@ async def upload_firmware(
         filename=filename,
         size=len(content),
         checksum=checksum,
+        signed=signature_hex is not None,
     )
 
 
@@ -531,6 +583,7 @@ async def delete_firmware(version: str):
 
     try:
         filepath.unlink()
+        _signature_path(filepath).unlink(missing_ok=True)
         logger.info(f"Deleted firmware {version}")
         return {"success": True, "message": f"Firmware {version} deleted"}
     except Exception as e:
@@ -0,0 +1,54 @@
+"""Integration settings endpoints.
+
+WLED: map storage locations and AMS slots to segments of a WLED LED
+controller, so locating a spool (POST /spools/{id}/locate, or a search
+with locate=true) lights up where it is.
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from services.wled import WLED_SETTING, WledConfig, WledError, get_wled_config, get_wled_controller
+
+router = APIRouter(prefix="/integrations", tags=["integrations"])
+
+
+def require_wled(config: WledConfig):
+    """Raise 409 unless the WLED integration is enabled."""
+    if not config.enabled:
+        raise HTTPException(status_code=409, detail="WLED integration is not enabled")
+
+
+@router.get("/wled", response_model=WledConfig)
+async def get_wled_settings():
+    """Get the WLED integration settings and segment mappings."""
+    db = await get_db()
+    return await get_wled_config(db)
+
+
+@router.put("/wled", response_model=WledConfig)
+async def set_wled_settings(config: WledConfig):
+    """Set the WLED integration settings and segment mappings."""
+    db = await get_db()
+    await db.set_setting(WLED_SETTING, config.model_dump_json())
+    if not config.enabled:
+        await get_wled_controller().restore()
+    return config
+
+
+@router.post("/wled/test", status_code=204)
+async def test_wled(segment: int | None = Query(default=None, ge=0)):
+    """Light one segment, or every mapped segment, to check the mappings against the shelf."""
+    db = await get_db()
+    config = await get_wled_config(db)
+    require_wled(config)
+    segments = [segment] if segment is not None else [m.segment for m in config.mappings]
+    try:
+        await get_wled_controller().highlight(config, segments)
+    except WledError as e:
+        raise HTTPException(status_code=502, detail=str(e)) from e
+
+
+@router.post("/wled/off", status_code=204)
+async def wled_off():
+    """End the current highlight, putting the controller back as it was."""
+    await get_wled_controller().restore()
@@ -0,0 +1,71 @@
+"""Background job endpoints.
+
+Long operations (imports, backups, report generation) are started here and
+run in the background; poll GET /jobs/{id} or follow the job_progress
+WebSocket messages for progress and the result.
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel, ValidationError
+from services.jobs import Job, JobStatus, UnknownJobKind, get_job_runner, job_kinds
+
+router = APIRouter(prefix="/jobs", tags=["jobs"])
+
+
+class JobStart(BaseModel):
+    """Request to start a background job."""
+
+    kind: str  # e.g. "color_import", "backup", "report"
+    params: dict = {}
+
+
+@router.get("/kinds", response_model=list[str])
+async def list_job_kinds():
+    """List the kinds of job that can be started."""
+    return job_kinds()
+
+
+@router.post("", response_model=Job, status_code=202)
+async def start_job(data: JobStart):
+    """Start a background job. Returns immediately with the queued job."""
+    db = await get_db()
+    try:
+        return await get_job_runner().start(db, data.kind, data.params)
+    except UnknownJobKind:
+        raise HTTPException(status_code=404, detail=f"Unknown job kind: {data.kind}") from None
+    except ValidationError as e:
+        raise HTTPException(status_code=422, detail=e.errors(include_url=False, include_context=False)) from None
+
+
+@router.get("", response_model=list[Job])
+async def list_jobs(
+    kind: str | None = None,
+    status: JobStatus | None = None,
+    limit: int = Query(default=50, ge=1, le=500),
+):
+    """List recent jobs, newest first."""
+    db = await get_db()
+    return await db.get_jobs(kind=kind, status=status, limit=limit)
+
+
+@router.get("/{job_id}", response_model=Job)
+async def get_job(job_id: str):
+    """Get a job's status, progress and result."""
+    db = await get_db()
+    job = await db.get_job(job_id)
+    if not job:
+        raise HTTPException(status_code=404, detail="Job not found")
+    return job
+
+
+@router.post("/{job_id}/cancel", response_model=Job)
+async def cancel_job(job_id: str):
+    """Cancel a queued or running job."""
+    db = await get_db()
+    job = await db.get_job(job_id)
+    if not job:
+        raise HTTPException(status_code=404, detail="Job not found")
+    if not await get_job_runner().cancel(job_id):
+        raise HTTPException(status_code=409, detail=f"Job is {job['status']}, not running")
+    return await db.get_job(job_id)
@@ -0,0 +1,144 @@
+"""Check-in/check-out kiosk endpoints.
+
+Members and their badges are managed here; the flow itself runs on the
+device (see services/kiosk.py). Checkouts can also be closed from the web
+UI, e.g. for a spool that came back without going over the scale.
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from models import KioskStatus
+from pydantic import BaseModel, Field
+from services.kiosk import KIOSK_SETTING, Checkout, KioskConfig, Member, check_in, get_kiosk, get_kiosk_config
+
+router = APIRouter(prefix="/kiosk", tags=["kiosk"])
+
+
+class MemberCreate(BaseModel):
+    """Request to add a member."""
+
+    name: str = Field(min_length=1)
+    badge_id: str | None = None
+    note: str | None = None
+
+
+class MemberUpdate(BaseModel):
+    """Member fields to change."""
+
+    name: str | None = Field(default=None, min_length=1)
+    badge_id: str | None = None
+    note: str | None = None
+    active: bool | None = None
+
+
+class CheckInRequest(BaseModel):
+    """Manual check-in of a checked-out spool."""
+
+    weight: float | None = Field(default=None, ge=0)  # Gross scale reading, if weighed
+    member_id: int | None = None  # Member bringing it back
+
+
+async def _get_member_or_404(db, member_id: int) -> dict:
+    member = await db.get_member(member_id)
+    if not member:
+        raise HTTPException(status_code=404, detail="Member not found")
+    return member
+
+
+async def _check_badge_free(db, badge_id: str | None, member_id: int | None = None):
+    """Raise 409 if the badge belongs to another member or is a spool tag."""
+    if not badge_id:
+        return
+    owner = await db.get_member_by_badge(badge_id)
+    if owner and owner["id"] != member_id:
+        raise HTTPException(status_code=409, detail=f"Badge is already assigned to {owner['name']}")
+    if await db.get_spool_by_tag(badge_id):
+        raise HTTPException(status_code=409, detail="Tag belongs to a spool")
+
+
+@router.get("/config", response_model=KioskConfig)
+async def get_kiosk_settings():
+    """Get the kiosk mode settings."""
+    db = await get_db()
+    return await get_kiosk_config(db)
+
+
+@router.put("/config", response_model=KioskConfig)
+async def set_kiosk_settings(config: KioskConfig):
+    """Turn kiosk mode on or off."""
+    db = await get_db()
+    await db.set_setting(KIOSK_SETTING, config.model_dump_json())
+    return config
+
+
+@router.get("/status", response_model=KioskStatus)
+async def get_kiosk_status():
+    """What the kiosk screen on the device shows right now."""
+    return get_kiosk().status()
+
+
+@router.get("/members", response_model=list[Member])
+async def list_members(include_inactive: bool = True):
+    """List members by name."""
+    db = await get_db()
+    return await db.get_members(include_inactive=include_inactive)
+
+
+@router.post("/members", response_model=Member, status_code=201)
+async def create_member(member: MemberCreate):
+    """Add a member, optionally with their badge."""
+    db = await get_db()
+    await _check_badge_free(db, member.badge_id)
+    return await db.create_member(member.name, member.badge_id, member.note)
+
+
+@router.get("/members/{member_id}", response_model=Member)
+async def get_member(member_id: int):
+    """Get a member."""
+    db = await get_db()
+    return await _get_member_or_404(db, member_id)
+
+
+@router.patch("/members/{member_id}", response_model=Member)
+async def update_member(member_id: int, changes: MemberUpdate):
+    """Update a member. Deactivating keeps their history but refuses their badge."""
+    db = await get_db()
+    await _get_member_or_404(db, member_id)
+    fields = changes.model_dump(exclude_unset=True)
+    if "badge_id" in fields:
+        await _check_badge_free(db, fields["badge_id"], member_id)
+    return await db.update_member(member_id, **fields)
+
+
+@router.delete("/members/{member_id}", status_code=204)
+async def delete_member(member_id: int):
+    """Delete a member. Their checkouts and usage stay, without the name."""
+    db = await get_db()
+    if not await db.delete_member(member_id):
+        raise HTTPException(status_code=404, detail="Member not found")
+
+
+@router.get("/checkouts", response_model=list[Checkout])
+async def list_checkouts(
+    member_id: int | None = None,
+    spool_id: str | None = None,
+    open_only: bool = Query(default=False, alias="open"),
+    limit: int = Query(default=100, ge=1, le=1000),
+):
+    """List checkouts, most recent first. open=true gives only spools still out."""
+    db = await get_db()
+    return await db.get_checkouts(member_id=member_id, spool_id=spool_id, open_only=open_only, limit=limit)
+
+
+@router.post("/checkouts/{checkout_id}/check-in", response_model=Checkout)
+async def check_in_checkout(checkout_id: int, request: CheckInRequest):
+    """Check a spool back in without the device."""
+    db = await get_db()
+    checkout = await db.get_checkout(checkout_id)
+    if not checkout:
+        raise HTTPException(status_code=404, detail="Checkout not found")
+    if checkout["checked_in_at"] is not None:
+        raise HTTPException(status_code=409, detail="Spool is already checked in")
+    if request.member_id is not None:
+        await _get_member_or_404(db, request.member_id)
+    return await check_in(db, checkout["spool_id"], checkout, request.weight, request.member_id)
@@ -0,0 +1,122 @@
+"""Moonraker (Klipper) printer endpoints."""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel, field_validator
+from services.moonraker import ACTIVE_SPOOL_SLOT, get_moonraker_manager, printer_key
+
+router = APIRouter(prefix="/moonraker", tags=["moonraker"])
+
+
+def _check_url(v: str | None) -> str | None:
+    if v is not None and not v.strip():
+        raise ValueError("URL must not be empty")
+    return v.strip().rstrip("/") if v else v
+
+
+class MoonrakerPrinterCreate(BaseModel):
+    """Request to add a Klipper printer."""
+
+    name: str
+    url: str  # Moonraker base URL, e.g. http://voron.local:7125
+    api_key: str | None = None  # Only needed if Moonraker requires authorization
+    enabled: bool = True
+
+    @field_validator("url")
+    @classmethod
+    def check_url(cls, v):
+        return _check_url(v)
+
+
+class MoonrakerPrinterUpdate(BaseModel):
+    """Request to update a Klipper printer."""
+
+    name: str | None = None
+    url: str | None = None
+    api_key: str | None = None
+    enabled: bool | None = None
+
+    @field_validator("url")
+    @classmethod
+    def check_url(cls, v):
+        return _check_url(v)
+
+
+class MoonrakerPrinterResponse(BaseModel):
+    """Klipper printer with its agent state (API key is never returned)."""
+
+    id: int
+    serial: str  # Key used for slot assignments and usage history
+    name: str
+    url: str
+    enabled: bool
+    has_api_key: bool
+    connected: bool = False
+    active_spool_id: int | None = None  # Moonraker/Spoolman spool ID (SpoolBuddy spool number)
+    spool_id: str | None = None  # Assigned SpoolBuddy spool
+    last_error: str | None = None
+    created_at: int | None = None
+
+
+async def _to_response(db, printer: dict) -> MoonrakerPrinterResponse:
+    serial = printer_key(printer["id"])
+    return MoonrakerPrinterResponse(
+        id=printer["id"],
+        serial=serial,
+        name=printer["name"],
+        url=printer["url"],
+        enabled=printer["enabled"],
+        has_api_key=bool(printer.get("api_key")),
+        spool_id=await db.get_spool_for_slot(serial, *ACTIVE_SPOOL_SLOT),
+        created_at=printer.get("created_at"),
+        **get_moonraker_manager().status(printer["id"]),
+    )
+
+
+@router.get("/printers", response_model=list[MoonrakerPrinterResponse])
+async def list_moonraker_printers():
+    """List Klipper printers followed through Moonraker."""
+    db = await get_db()
+    return [await _to_response(db, p) for p in await db.get_moonraker_printers()]
+
+
+@router.post("/printers", response_model=MoonrakerPrinterResponse, status_code=201)
+async def create_moonraker_printer(data: MoonrakerPrinterCreate):
+    """Add a Klipper printer and start following its active spool and print history."""
+    db = await get_db()
+    printer = await db.create_moonraker_printer(
+        name=data.name, url=data.url, api_key=data.api_key, enabled=data.enabled
+    )
+    get_moonraker_manager().start(printer)
+    return await _to_response(db, printer)
+
+
+@router.get("/printers/{printer_id}", response_model=MoonrakerPrinterResponse)
+async def get_moonraker_printer(printer_id: int):
+    """Get a Klipper printer by ID."""
+    db = await get_db()
+    printer = await db.get_moonraker_printer(printer_id)
+    if not printer:
+        raise HTTPException(status_code=404, detail="Printer not found")
+    return await _to_response(db, printer)
+
+
+@router.patch("/printers/{printer_id}", response_model=MoonrakerPrinterResponse)
+async def update_moonraker_printer(printer_id: int, data: MoonrakerPrinterUpdate):
+    """Update a Klipper printer; the agent reconnects with the new settings."""
+    db = await get_db()
+    if not await db.get_moonraker_printer(printer_id):
+        raise HTTPException(status_code=404, detail="Printer not found")
+
+    printer = await db.update_moonraker_printer(printer_id, **data.model_dump(exclude_unset=True))
+    get_moonraker_manager().start(printer)
+    return await _to_response(db, printer)
+
+
+@router.delete("/printers/{printer_id}", status_code=204)
+async def delete_moonraker_printer(printer_id: int):
+    """Stop following a Klipper printer and remove its spool assignment."""
+    db = await get_db()
+    get_moonraker_manager().stop(printer_id)
+    if not await db.delete_moonraker_printer(printer_id, printer_key(printer_id)):
+        raise HTTPException(status_code=404, detail="Printer not found")
@@ -0,0 +1,131 @@
+"""Notification channel endpoints (ntfy, Telegram, Discord)."""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException
+from pydantic import BaseModel, field_validator
+from services.notifiers import NOTIFIER_TYPES, get_notification_dispatcher
+from services.webhooks import validate_event_types
+
+router = APIRouter(prefix="/notifications", tags=["notifications"])
+
+
+class ChannelCreate(BaseModel):
+    """Request to create a notification channel."""
+
+    name: str
+    type: str  # ntfy, telegram, discord
+    config: dict  # Type-specific settings (topic, bot_token/chat_id, webhook_url)
+    events: list[str]
+    enabled: bool = True
+
+    @field_validator("events")
+    @classmethod
+    def check_events(cls, v):
+        return validate_event_types(v)
+
+
+class ChannelUpdate(BaseModel):
+    """Request to update a notification channel."""
+
+    name: str | None = None
+    config: dict | None = None
+    events: list[str] | None = None
+    enabled: bool | None = None
+
+    @field_validator("events")
+    @classmethod
+    def check_events(cls, v):
+        return validate_event_types(v)
+
+
+class ChannelResponse(BaseModel):
+    """Notification channel."""
+
+    id: int
+    name: str
+    type: str
+    config: dict
+    events: list[str]
+    enabled: bool
+    created_at: int | None = None
+
+
+def _check_config(channel_type: str, config: dict):
+    notifier_cls = NOTIFIER_TYPES.get(channel_type)
+    if not notifier_cls:
+        raise HTTPException(status_code=400, detail=f"Unknown channel type: {channel_type}")
+    try:
+        notifier_cls.validate_config(config)
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=str(e))
+
+
+@router.get("/types")
+async def list_channel_types() -> dict[str, list[str]]:
+    """List supported channel types and their required config keys."""
+    return {name: list(cls.required_config) for name, cls in NOTIFIER_TYPES.items()}
+
+
+@router.get("/channels", response_model=list[ChannelResponse])
+async def list_channels():
+    """List all notification channels."""
+    db = await get_db()
+    return await db.get_notification_channels()
+
+
+@router.post("/channels", response_model=ChannelResponse, status_code=201)
+async def create_channel(data: ChannelCreate):
+    """Create a notification channel."""
+    _check_config(data.type, data.config)
+    db = await get_db()
+    return await db.create_notification_channel(
+        name=data.name,
+        channel_type=data.type,
+        config=data.config,
+        events=data.events,
+        enabled=data.enabled,
+    )
+
+
+@router.get("/channels/{channel_id}", response_model=ChannelResponse)
+async def get_channel(channel_id: int):
+    """Get a notification channel by ID."""
+    db = await get_db()
+    channel = await db.get_notification_channel(channel_id)
+    if not channel:
+        raise HTTPException(status_code=404, detail="Notification channel not found")
+    return channel
+
+
+@router.patch("/channels/{channel_id}", response_model=ChannelResponse)
+async def update_channel(channel_id: int, data: ChannelUpdate):
+    """Update a notification channel."""
+    db = await get_db()
+    channel = await db.get_notification_channel(channel_id)
+    if not channel:
+        raise HTTPException(status_code=404, detail="Notification channel not found")
+
+    updates = data.model_dump(exclude_unset=True)
+    if "config" in updates:
+        _check_config(channel["type"], updates["config"])
+    return await db.update_notification_channel(channel_id, **updates)
+
+
+@router.delete("/channels/{channel_id}", status_code=204)
+async def delete_channel(channel_id: int):
+    """Delete a notification channel."""
+    db = await get_db()
+    if not await db.delete_notification_channel(channel_id):
+        raise HTTPException(status_code=404, detail="Notification channel not found")
+
+
+@router.post("/channels/{channel_id}/test")
+async def test_channel(channel_id: int):
+    """Send a test notification through a channel."""
+    db = await get_db()
+    channel = await db.get_notification_channel(channel_id)
+    if not channel:
+        raise HTTPException(status_code=404, detail="Notification channel not found")
+
+    error = await get_notification_dispatcher().send(channel, "SpoolBuddy test", "Notifications are working")
+    return {"success": error is None, "error": error}
@@ -1,24 +1,63 @@
+import asyncio
 import io
+import json
 import logging
-import re
 import zipfile
 
+from api.caching import etag_response
+from config import settings
 from db import get_db
-from fastapi import APIRouter, HTTPException
-from fastapi.responses import Response
+from fastapi import APIRouter, HTTPException, Query, Request
+from fastapi.responses import Response, StreamingResponse
 from models import (
+    ActiveSpool,
+    AmsEvent,
+    AmsUnitRecord,
     AmsFilamentSettingRequest,
     AssignSpoolRequest,
+    CommandStatus,
+    ExternalSpool,
+    ExternalSpoolRequest,
+    NozzleChange,
+    NozzleInfo,
     Printer,
     PrinterCreate,
+    PrinterNozzles,
+    PrinterState,
+    PrinterType,
     PrinterUpdate,
     PrinterWithStatus,
+    PrintAction,
+    RunoutEvent,
+    RunoutEventRequest,
+    RunoutKind,
     SetCalibrationRequest,
+    SkipObjectsRequest,
+    SpeedLevelRequest,
 )
 from PIL import Image
-from pydantic import BaseModel
-from services.bambu_cloud import get_cloud_service
+from pydantic import BaseModel, field_validator
+from services.bambu_camera import CameraError, CameraUnavailable, camera_frames, first_frame, snapshot
 from services.bambu_ftp import download_file_try_paths_async
+from services.forecast import remaining_grams
+from services.print_job import plate_number, print_file_paths
+from services.printer_capabilities import (
+    SEVERITY_ERROR,
+    CompatibilityIssue,
+    PrinterCapabilities,
+    check_compatibility,
+    effective_capabilities,
+)
+from services.slicer import (
+    filament_preset_name,
+    normalize_tray_color,
+    resolve_filament_id,
+    spool_temp_range,
+    temp_range,
+)
+from services.spool_status import active_slots
+from services.tracing import get_printer_log_handler, log_event_matches
+from services.webhooks import EVENT_FILAMENT_RUNOUT, emit_event
 
 logger = logging.getLogger(__name__)
 router = APIRouter(prefix="/printers", tags=["printers"])
@@ -32,12 +71,21 @@ _cover_cache: dict[str, dict[tuple[str, int], bytes]] = {}
 # Cover image size for ESP32 display (must match EEZ design: 70x70)
 COVER_SIZE = (70, 70)
 
+# Camera thumbnail size for the ESP32 "now printing" screen (16:9 like the camera)
+CAMERA_THUMBNAIL_SIZE = (160, 90)
+
+LOG_STREAM_KEEPALIVE_SECONDS = 15
+
+# Interval for long-polling a command's outcome
+COMMAND_POLL_INTERVAL = 0.2
+
 
-def resize_cover_image(image_data: bytes) -> bytes:
-    """Resize a PNG image to COVER_SIZE and convert to raw RGB565 for ESP32 display.
+def resize_cover_image(image_data: bytes, size: tuple[int, int] = COVER_SIZE) -> bytes:
+    """Resize an image to size and convert to raw RGB565 for ESP32 display.
 
     Args:
-        image_data: Original PNG bytes
+        image_data: Original PNG or JPEG bytes
+        size: Target (width, height)
 
     Returns:
         Raw RGB565 pixel data (no header, just pixels)
@@ -46,7 +94,7 @@ def resize_cover_image(image_data: bytes) -> bytes:
     # Convert to RGB (no alpha needed for RGB565)
     if img.mode != "RGB":
         img = img.convert("RGB")
-    img = img.resize(COVER_SIZE, Image.LANCZOS)
+    img = img.resize(size, Image.LANCZOS)
 
     # Convert to RGB565 (16-bit: 5 bits red, 6 bits green, 5 bits blue)
     pixels = img.load()
@@ -76,9 +124,62 @@ def set_printer_manager(manager):
     _printer_manager = manager
 
 
-@router.get("", response_model=list[PrinterWithStatus])
-async def list_printers():
-    """Get all printers with connection status and live state."""
+def get_printer_manager():
+    """Get the printer manager reference (None until main.py sets it)."""
+    return _printer_manager
+
+
+MANUAL_PRINTER_DETAIL = "Manual printers don't connect"
+
+
+def _needs_access_code(printer) -> bool:
+    """Whether the printer refused its stored access code (cleared once the code is changed)."""
+    return bool(_printer_manager and _printer_manager.access_code_refused(printer.serial, printer.access_code))
+
+
+async def _active_spools(db, serial: str, state: PrinterState) -> list[ActiveSpool]:
+    """Slots feeding the printer's extruders, with their assigned spools and filament left.
+
+    The remaining weight follows the slot's remain reading while the printer
+    reports one, so it drops during the print; otherwise it comes from the
+    inventory.
+    """
+    slots = active_slots(state)
+    if not slots:
+        return []
+    trays = {(tray.ams_id, tray.tray_id): tray for unit in state.ams_units for tray in unit.trays}
+    if state.vt_tray:
+        trays[(255, 0)] = state.vt_tray
+    assigned = {(a["ams_id"], a["tray_id"]): a["spool_id"] for a in await db.get_slot_assignments(serial)}
+
+    result = []
+    for extruder, (ams_id, tray_id) in slots.items():
+        tray = trays.get((ams_id, tray_id))
+        spool_id = assigned.get((ams_id, tray_id))
+        spool = await db.get_spool(spool_id) if spool_id else None
+        active = ActiveSpool(ams_id=ams_id, tray_id=tray_id, extruder=extruder)
+        if spool:
+            remaining = remaining_grams(spool)
+            if remaining is not None and tray and tray.remain is not None and tray.remain >= 0:
+                remaining = spool.label_weight * tray.remain / 100
+            active.spool_id = spool.id
+            active.material = spool.material
+            active.color_name = spool.color_name
+            active.rgba = spool.rgba
+            active.remaining_grams = round(remaining, 1) if remaining is not None else None
+        elif tray:
+            active.material = tray.tray_type
+            active.rgba = tray.tray_color
+        result.append(active)
+    return result
+
+
+@router.get("", response_model=list[PrinterWithStatus], responses={304: {"description": "Not modified"}})
+async def list_printers(request: Request):
+    """Get all printers with connection status and live state.
+
+    Supports If-None-Match, answering 304 while nothing has changed.
+    """
     db = await get_db()
     printers = await db.get_printers()
 
@@ -92,10 +193,18 @@ async def list_printers():
     result = []
     for printer in printers:
         connected = statuses.get(printer.serial, False)
+        needs_access_code = _needs_access_code(printer)
         gcode_state = None
         print_progress = None
         subtask_name = None
         mc_remaining_time = None
+        print_eta = None
+        speed_level = None
+        print_error_code = None
+        skipped_objects = []
+        layer_num = None
+        total_layer_num = None
+        active_spools = []
         cover_url = None
         ams_units = []
         tray_now = None
@@ -105,6 +214,7 @@ async def list_printers():
         stg_cur = -1
         stg_cur_name = None
         tray_reading_bits = None
+        vt_tray = None
 
         # Get live state if connected
         if connected and _printer_manager:
@@ -114,6 +224,12 @@ async def list_printers():
                 print_progress = state.print_progress
                 subtask_name = state.subtask_name
                 mc_remaining_time = state.mc_remaining_time
+                print_eta = state.print_eta
+                speed_level = state.speed_level
+                print_error_code = state.print_error_code
+                skipped_objects = state.skipped_objects
+                layer_num = state.layer_num
+                total_layer_num = state.total_layer_num
                 ams_units = state.ams_units
                 tray_now = state.tray_now
                 tray_now_left = state.tray_now_left
@@ -122,6 +238,9 @@ async def list_printers():
                 stg_cur = state.stg_cur
                 stg_cur_name = state.stg_cur_name
                 tray_reading_bits = state.tray_reading_bits
+                vt_tray = state.vt_tray
+                if gcode_state in ("RUNNING", "PAUSE", "PAUSED"):
+                    active_spools = await _active_spools(db, printer.serial, state)
                 # Add cover URL if printing
                 if gcode_state in ("RUNNING", "PAUSE", "PAUSED") and subtask_name:
                     cover_url = f"/api/printers/{printer.serial}/cover"
@@ -130,10 +249,18 @@ async def list_printers():
             PrinterWithStatus(
                 **printer.model_dump(),
                 connected=connected,
+                needs_access_code=needs_access_code,
                 gcode_state=gcode_state,
                 print_progress=print_progress,
                 subtask_name=subtask_name,
                 mc_remaining_time=mc_remaining_time,
+                print_eta=print_eta,
+                speed_level=speed_level,
+                print_error_code=print_error_code,
+                skipped_objects=skipped_objects,
+                layer_num=layer_num,
+                total_layer_num=total_layer_num,
+                active_spools=active_spools,
                 cover_url=cover_url,
                 ams_units=ams_units,
                 tray_now=tray_now,
@@ -143,10 +270,11 @@ async def list_printers():
                 stg_cur=stg_cur,
                 stg_cur_name=stg_cur_name,
                 tray_reading_bits=tray_reading_bits,
+                vt_tray=vt_tray,
             )
         )
 
-    return result
+    return etag_response(request, result, list[PrinterWithStatus])
 
 
 # NOTE: This route must be BEFORE /{serial} routes to avoid matching "assignment-completions" as a serial
@@ -190,29 +318,53 @@ async def get_printer(serial: str):
         raise HTTPException(status_code=404, detail="Printer not found")
 
     connected = _printer_manager.is_connected(serial) if _printer_manager else False
-    return PrinterWithStatus(**printer.model_dump(), connected=connected)
+    return PrinterWithStatus(**printer.model_dump(), connected=connected, needs_access_code=_needs_access_code(printer))
 
 
 @router.post("", response_model=Printer, status_code=201)
 async def create_printer(printer: PrinterCreate):
-    """Create or update a printer."""
+    """Create or update a printer.
+
+    Printers of type manual never connect. Log usage against them by hand and
+    put spools on their external holder to get per-printer statistics for
+    printers that aren't networked.
+    """
     db = await get_db()
     return await db.create_printer(printer)
 
 
 @router.put("/{serial}", response_model=Printer)
 async def update_printer(serial: str, printer: PrinterUpdate):
-    """Update an existing printer."""
+    """Update an existing printer.
+
+    A printer that refused its access code is reconnected once a new code is set.
+    """
     db = await get_db()
+    existing = await db.get_printer(serial)
+    if existing and existing.printer_type == PrinterType.MANUAL and printer.auto_connect:
+        raise HTTPException(status_code=400, detail=MANUAL_PRINTER_DETAIL)
     updated = await db.update_printer(serial, printer)
     if not updated:
         raise HTTPException(status_code=404, detail="Printer not found")
+
+    if _printer_manager and existing and _needs_access_code(existing) and not _needs_access_code(updated):
+        logger.info(f"Access code of {serial} changed, reconnecting")
+        try:
+            await _printer_manager.connect(
+                serial=updated.serial,
+                ip_address=updated.ip_address,
+                access_code=updated.access_code,
+                name=updated.name,
+                cert_fingerprint=updated.cert_fingerprint,
+            )
+        except Exception as e:
+            logger.error(f"Failed to reconnect to {serial}: {e}")
     return updated
 
 
 @router.delete("/{serial}", status_code=204)
 async def delete_printer(serial: str):
-    """Delete a printer."""
+    """Move a printer to the trash (restorable via the trash API)."""
     # Disconnect first
     if _printer_manager:
         await _printer_manager.disconnect(serial)
@@ -233,6 +385,9 @@ async def connect_printer(serial: str):
     if not printer:
         raise HTTPException(status_code=404, detail="Printer not found")
 
+    if printer.printer_type == PrinterType.MANUAL:
+        raise HTTPException(status_code=400, detail=MANUAL_PRINTER_DETAIL)
+
     if not printer.ip_address or not printer.access_code:
         raise HTTPException(status_code=400, detail="Printer missing IP address or access code")
 
@@ -243,6 +398,7 @@ async def connect_printer(serial: str):
             ip_address=printer.ip_address,
             access_code=printer.access_code,
             name=printer.name,
+            cert_fingerprint=printer.cert_fingerprint,
         )
         logger.info(f"Connection initiated for {serial}, waiting for MQTT callback")
     except Exception as e:
@@ -257,6 +413,137 @@ async def disconnect_printer(serial: str):
         await _printer_manager.disconnect(serial)
 
 
+class CertificateStatus(BaseModel):
+    """A printer's pinned TLS certificate."""
+
+    pinned: str | None = None  # SHA-256 fingerprint, None = the next certificate seen is pinned
+    mismatch: str | None = None  # Fingerprint of a refused certificate that didn't match the pin
+    insecure: bool = False  # Pinning is off, any certificate is accepted
+
+
+class RepinRequest(BaseModel):
+    fingerprint: str | None = None  # Certificate to trust, default: the one the printer presented
+
+    @field_validator("fingerprint")
+    @classmethod
+    def check_fingerprint(cls, v):
+        if v is None:
+            return None
+        digits = v.replace(":", "").replace(" ", "").upper()
+        if len(digits) != 64 or any(c not in "0123456789ABCDEF" for c in digits):
+            raise ValueError("must be a SHA-256 fingerprint (64 hex digits, colons optional)")
+        return ":".join(digits[i : i + 2] for i in range(0, 64, 2))
+
+
+async def _certificate_status(serial: str) -> CertificateStatus:
+    db = await get_db()
+    printer = await db.get_printer(serial)
+    if not printer:
+        raise HTTPException(status_code=404, detail="Printer not found")
+    return CertificateStatus(
+        pinned=printer.cert_fingerprint,
+        mismatch=_printer_manager.cert_mismatch(serial) if _printer_manager else None,
+        insecure=settings.printer_tls_insecure,
+    )
+
+
+@router.get("/{serial}/certificate", response_model=CertificateStatus)
+async def get_printer_certificate(serial: str):
+    """Get the printer's pinned TLS certificate and whether a different one was refused."""
+    return await _certificate_status(serial)
+
+
+@router.post("/{serial}/certificate/repin", response_model=CertificateStatus)
+async def repin_printer_certificate(serial: str, request: RepinRequest | None = None):
+    """Trust a printer's new TLS certificate, e.g. after a firmware update or factory reset.
+
+    Pins the given fingerprint, or the one the printer presented when its
+    connection was refused. If neither is known the pin is cleared and the
+    next certificate seen is pinned. The printer is reconnected.
+    """
+    status = await _certificate_status(serial)
+    fingerprint = (request.fingerprint if request else None) or status.mismatch
+    db = await get_db()
+    await db.set_printer_cert_fingerprint(serial, fingerprint)
+    logger.info(f"Re-pinned TLS certificate of {serial}: {fingerprint or 'next seen'}")
+
+    printer = await db.get_printer(serial)
+    if _printer_manager and printer.ip_address and printer.access_code:
+        await _printer_manager.disconnect(serial)
+        try:
+            await _printer_manager.connect(
+                serial=printer.serial,
+                ip_address=printer.ip_address,
+                access_code=printer.access_code,
+                name=printer.name,
+                cert_fingerprint=printer.cert_fingerprint,
+            )
+        except Exception as e:
+            logger.error(f"Failed to reconnect {serial} after re-pinning: {e}")
+    return await _certificate_status(serial)
+
+
+@router.get("/{serial}/logs/stream")
+async def stream_printer_logs(
+    serial: str,
+    request: Request,
+    level: str = Query(default="DEBUG", pattern="^(DEBUG|INFO|WARNING|ERROR)$"),
+    search: str | None = Query(default=None, min_length=1),
+    tail: int = Query(default=100, ge=0, le=500, description="Recent records to send first"),
+):
+    """Tail the printer's log records as server-sent events.
+
+    Streams the records logged while handling the printer's MQTT traffic,
+    commands and API requests, each with its span and request id. Records
+    below the server's log level (INFO unless debug logging is on) are not
+    captured.
+
+    Args:
+        level: Minimum level to send
+        search: Only send records whose logger name or message contains this text
+        tail: Number of buffered records to send before live ones
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+
+    handler = get_printer_log_handler()
+    min_level = logging.getLevelName(level)
+    # Subscribe before reading the buffer so nothing logged in between is lost
+    sub = handler.subscribe(serial)
+    recent = [e for e in handler.recent(serial) if log_event_matches(e, min_level, search)]
+    recent = recent[-tail:] if tail else []
+
+    async def generate():
+        try:
+            replayed = {id(e) for e in recent}
+            for event in recent:
+                yield f"data: {json.dumps(event.to_dict())}\n\n"
+            while not sub.dropped:
+                try:
+                    event = await asyncio.wait_for(sub.queue.get(), LOG_STREAM_KEEPALIVE_SECONDS)
+                except TimeoutError:
+                    if await request.is_disconnected():
+                        break
+                    yield ": keepalive\n\n"
+                    continue
+                if sub.dropped:
+                    break  # Server shutting down
+                if id(event) in replayed:
+                    continue  # Logged between subscribing and reading the buffer, already sent
+                if log_event_matches(event, min_level, search):
+                    yield f"data: {json.dumps(event.to_dict())}\n\n"
+        finally:
+            handler.unsubscribe(sub)
+
+    return StreamingResponse(
+        generate(),
+        media_type="text/event-stream",
+        # X-Accel-Buffering: stop nginx from buffering the stream
+        headers={"Cache-Control": "no-cache", "X-Accel-Buffering": "no"},
+    )
+
+
 class AutoConnectRequest(BaseModel):
     auto_connect: bool
 
@@ -268,14 +555,174 @@ async def set_auto_connect(serial: str, request: AutoConnectRequest):
     printer = await db.get_printer(serial)
     if not printer:
         raise HTTPException(status_code=404, detail="Printer not found")
+    if printer.printer_type == PrinterType.MANUAL and request.auto_connect:
+        raise HTTPException(status_code=400, detail=MANUAL_PRINTER_DETAIL)
 
     return await db.update_printer(serial, PrinterUpdate(auto_connect=request.auto_connect))
 
 
-@router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/filament", status_code=204)
+async def _spool_slot_settings(db, spool) -> dict:
+    """AMS slot filament settings (AmsFilamentSettingRequest fields) for a spool.
+
+    Nozzle temperatures are the spool's user-tuned values where set, else
+    the material's defaults.
+    """
+    # Bambu format: setting_id like "GFSL05", tray_info_idx (filament_id) like "GFL05".
+    # User-created custom presets (PFUS*) get their filament_id from the cloud.
+    slicer_filament = spool.slicer_filament or ""
+    tray_info_idx = await resolve_filament_id(db, spool) if spool.slicer_setting_id else slicer_filament
+    temp_min, temp_max = spool_temp_range(spool)
+    return {
+        "tray_info_idx": tray_info_idx,
+        "setting_id": spool.slicer_setting_id or slicer_filament,
+        "tray_type": spool.material or "",
+        "tray_sub_brands": spool.slicer_filament_name or filament_preset_name(tray_info_idx) or "",
+        "tray_color": normalize_tray_color(spool.rgba) or "FFFFFFFF",
+        "nozzle_temp_min": temp_min,
+        "nozzle_temp_max": temp_max,
+    }
+
+
+def _slot_extruder(serial: str, ams_id: int) -> int:
+    """Extruder fed by an AMS unit or external holder (0 on single-nozzle printers)."""
+    if ams_id == 254:
+        return 1  # Left external holder
+    state = _printer_manager.get_state(serial) if _printer_manager else None
+    if state:
+        for unit in state.ams_units:
+            if unit.id == ams_id and unit.extruder is not None:
+                return unit.extruder
+    return 0
+
+
+def _installed_nozzle_type(serial: str, extruder_id: int) -> str | None:
+    """Type of the nozzle on an extruder, if the printer reported it."""
+    state = _printer_manager.get_state(serial) if _printer_manager else None
+    for nozzle in state.nozzles if state else []:
+        if nozzle.extruder_id == extruder_id:
+            return nozzle.nozzle_type
+    return None
+
+
+async def _compatibility(db, serial: str, ams_id: int, spool) -> tuple[PrinterCapabilities, list[CompatibilityIssue]]:
+    """Capabilities of the printer (for the extruder a slot feeds) and the spool's issues with them."""
+    printer = await db.get_printer(serial)
+    if not printer:
+        return PrinterCapabilities(), []
+    extruder_id = _slot_extruder(serial, ams_id)
+    state = _printer_manager.get_state(serial) if _printer_manager else None
+    nozzles = [n for n in state.nozzles if n.extruder_id == extruder_id] if state else []
+    caps = effective_capabilities(printer, nozzles)
+    return caps, check_compatibility(caps, spool)
+
+
+async def _check_compatibility(db, serial: str, ams_id: int, spool, force: bool) -> list[str]:
+    """Reject a spool the printer can't handle unless forced; returns the warnings to report."""
+    _, issues = await _compatibility(db, serial, ams_id, spool)
+    errors = [issue.message for issue in issues if issue.severity == SEVERITY_ERROR]
+    if errors and not force:
+        raise HTTPException(
+            status_code=422,
+            detail=f"Spool is not compatible with this printer: {'; '.join(errors)}. Set force to assign anyway.",
+        )
+    if issues:
+        logger.warning(f"Spool {spool.id} on {serial}: {'; '.join(issue.message for issue in issues)}")
+    return [issue.message for issue in issues]
+
+
+async def _find_k_profile(db, spool_id: str, serial: str, extruder_id: int = 0) -> dict | None:
+    """The spool's K profile for the nozzle currently on an extruder, if it has one.
+
+    Profiles for the same nozzle type are preferred; profiles without an
+    extruder or type apply to any.
+    """
+    nozzle_diameter = _printer_manager.get_nozzle_diameter(serial, extruder_id)
+    nozzle_type = _installed_nozzle_type(serial, extruder_id)
+    candidates = [
+        kp
+        for kp in await db.get_spool_k_profiles(spool_id)
+        # Match by printer, nozzle diameter and extruder
+        if kp.get("printer_serial") == serial
+        and kp.get("nozzle_diameter") == nozzle_diameter
+        and kp.get("extruder") in (None, extruder_id)
+        and (not kp.get("nozzle_type") or not nozzle_type or kp["nozzle_type"] == nozzle_type)
+    ]
+    if candidates:
+        kp = max(candidates, key=lambda c: bool(nozzle_type) and c.get("nozzle_type") == nozzle_type)
+        logger.info(
+            f"Found matching K-profile for spool {spool_id}: cali_idx={kp.get('cali_idx')}, name={kp.get('name')}"
+        )
+        return kp
+
+    logger.info(f"No matching K-profile found for spool {spool_id} on printer {serial} with nozzle {nozzle_diameter}")
+    return None
+
+
+async def apply_nozzle_k_profiles(db, serial: str, extruder_ids: set[int]) -> int:
+    """Re-select K profiles for the slots fed by extruders whose nozzle changed.
+
+    Slots whose spool has no profile for the new nozzle are reset to the
+    default profile, since the old one was calibrated for another nozzle.
+    Returns the number of slots updated.
+    """
+    if not _printer_manager or not _printer_manager.is_connected(serial):
+        return 0
+
+    updated = 0
+    for assignment in await db.get_slot_assignments(serial):
+        ams_id, tray_id = assignment["ams_id"], assignment["tray_id"]
+        extruder_id = _slot_extruder(serial, ams_id)
+        if extruder_id not in extruder_ids:
+            continue
+        spool = await db.get_spool(assignment["spool_id"])
+        if not spool:
+            continue
+
+        settings = await _spool_slot_settings(db, spool)
+        k_profile = await _find_k_profile(db, spool.id, serial, extruder_id)
+        nozzle_diameter = _printer_manager.get_nozzle_diameter(serial, extruder_id)
+        sent = _printer_manager.set_calibration(
+            serial=serial,
+            ams_id=ams_id,
+            tray_id=tray_id,
+            cali_idx=k_profile.get("cali_idx", -1) if k_profile else -1,
+            filament_id=settings["tray_info_idx"],
+            nozzle_diameter=nozzle_diameter,
+            setting_id=(k_profile.get("setting_id") or "") if k_profile else "",
+        )
+        if sent:
+            updated += 1
+    logger.info(f"[{serial}] Re-applied K-profiles for {updated} slot(s) after nozzle change")
+    return updated
+
+
+def _require_printing(serial: str):
+    """Raise unless the printer is connected and has a print running or paused."""
+    if not _printer_manager:
+        raise HTTPException(status_code=500, detail="Printer manager not available")
+    if not _printer_manager.is_connected(serial):
+        raise HTTPException(status_code=400, detail="Printer not connected")
+    state = _printer_manager.get_state(serial)
+    if not state or state.gcode_state not in ("RUNNING", "PAUSE"):
+        raise HTTPException(status_code=409, detail="Printer is not printing")
+
+
+def _sent_command(serial: str, command: str) -> CommandStatus:
+    """Status of a command just sent, for 202 responses."""
+    return CommandStatus(**_printer_manager.get_last_command(serial, command))
+
+
+@router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/filament", status_code=202, response_model=CommandStatus)
 async def set_filament(serial: str, ams_id: int, tray_id: int, filament: AmsFilamentSettingRequest):
     """Set filament information for an AMS slot.
 
+    The printer applies the setting asynchronously. The response carries the
+    command's sequence_id; its outcome is broadcast as a "command_result"
+    WebSocket message and can be polled at /{serial}/commands/{sequence_id}.
+
+    With spool_id, the slot is configured from the spool (and its K profile
+    for this printer, if any) and the spool is recorded as loaded in it.
+
     Args:
         serial: Printer serial number
         ams_id: AMS unit ID (0-3 for regular AMS, 128-135 for AMS-HT, 254/255 for external)
@@ -288,6 +735,24 @@ async def set_filament(serial: str, ams_id: int, tray_id: int, filament: AmsFila
     if not _printer_manager.is_connected(serial):
         raise HTTPException(status_code=400, detail="Printer not connected")
 
+    db = await get_db()
+    k_profile = None
+    if filament.spool_id:
+        spool = await db.get_spool(filament.spool_id)
+        if not spool:
+            raise HTTPException(status_code=404, detail="Spool not found")
+        await _check_compatibility(db, serial, ams_id, spool, filament.force)
+        overrides = filament.model_dump(include=filament.model_fields_set - {"spool_id"})
+        filament = filament.model_copy(update={**await _spool_slot_settings(db, spool), **overrides})
+        k_profile = await _find_k_profile(db, spool.id, serial, _slot_extruder(serial, ams_id))
+
+    if filament.tray_type or filament.tray_info_idx:
+        default_min, default_max = temp_range(filament.tray_type)
+    else:
+        default_min, default_max = 0, 0  # Clearing the slot
+    temp_min = filament.nozzle_temp_min if filament.nozzle_temp_min is not None else default_min
+    temp_max = filament.nozzle_temp_max if filament.nozzle_temp_max is not None else default_max
+
     success = _printer_manager.set_filament(
         serial=serial,
         ams_id=ams_id,
@@ -297,12 +762,29 @@ async def set_filament(serial: str, ams_id: int, tray_id: int, filament: AmsFila
         tray_type=filament.tray_type,
         tray_sub_brands=filament.tray_sub_brands,
         tray_color=filament.tray_color,
-        nozzle_temp_min=filament.nozzle_temp_min,
-        nozzle_temp_max=filament.nozzle_temp_max,
+        nozzle_temp_min=temp_min,
+        nozzle_temp_max=temp_max,
     )
 
     if not success:
         raise HTTPException(status_code=500, detail="Failed to set filament")
+    command = _sent_command(serial, "ams_filament_setting")
+
+    if k_profile:
+        _printer_manager.set_calibration(
+            serial=serial,
+            ams_id=ams_id,
+            tray_id=tray_id,
+            cali_idx=k_profile.get("cali_idx", -1),
+            filament_id=filament.tray_info_idx,
+            nozzle_diameter=k_profile["nozzle_diameter"],
+            setting_id=k_profile.get("setting_id") or "",
+        )
+    if filament.spool_id:
+        await db.assign_spool_to_slot(filament.spool_id, serial, ams_id, tray_id)
+        await _publish_spool_status(db)
+
+    return command
 
 
 class AssignResponse(BaseModel):
@@ -311,6 +793,8 @@ class AssignResponse(BaseModel):
     status: str  # "configured" or "staged"
     message: str
     needs_replacement: bool = False  # True if slot has wrong spool that needs removal
+    sequence_id: str | None = None  # ams_filament_setting command sent to the printer, see set_filament
+    warnings: list[str] = []  # Material compatibility issues with the printer
 
 
 @router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/assign", response_model=AssignResponse)
@@ -332,115 +816,35 @@ async def assign_spool_to_tray(serial: str, ams_id: int, tray_id: int, request:
     if not _printer_manager:
         raise HTTPException(status_code=500, detail="Printer manager not available")
 
+    db = await get_db()
     if not _printer_manager.is_connected(serial):
+        printer = await db.get_printer(serial)
+        if printer and printer.printer_type == PrinterType.MANUAL:
+            raise HTTPException(status_code=400, detail="Manual printers have no AMS, use the external spool holder")
         raise HTTPException(status_code=400, detail="Printer not connected")
 
     # Look up spool from database
-    db = await get_db()
     spool = await db.get_spool(request.spool_id)
     if not spool:
         raise HTTPException(status_code=404, detail="Spool not found")
+    warnings = await _check_compatibility(db, serial, ams_id, spool, request.force)
 
-    # Convert spool color (RGBA format) - ensure it's 8 hex chars
-    tray_color = spool.rgba or "FFFFFFFF"
-    if len(tray_color) == 6:
-        tray_color = tray_color + "FF"  # Add alpha if missing
-
-    # Determine temperature range based on material
-    temp_ranges = {
-        "PLA": (190, 230),
-        "PETG": (220, 260),
-        "ABS": (240, 270),
-        "ASA": (240, 270),
-        "TPU": (200, 230),
-        "PA": (260, 290),
-        "PC": (260, 280),
-        "PVA": (190, 210),
-    }
-    material = (spool.material or "").upper()
-    temp_min, temp_max = temp_ranges.get(material, (190, 250))
-
-    # Build tray_info_idx and setting_id from slicer_filament
-    # Bambu format: setting_id like "GFSL05" or "GFSA01", tray_info_idx like "GFL05" or "GFA01"
-    # Some IDs have version suffix like "GFSL05_07" - strip it for compatibility
-    # User-created custom filaments have PFUS* prefix and need special handling
-    slicer_filament = spool.slicer_filament or ""
-
-    # Strip version suffix if present (e.g., GFSL05_07 -> GFSL05)
-    if "_" in slicer_filament:
-        setting_id = slicer_filament.split("_")[0]
-    else:
-        setting_id = slicer_filament
-
-    # Convert setting_id to filament_id for tray_info_idx
-    # Generic filament IDs for when we need a fallback
-    generic_filament_ids = {
-        "PLA": "GFL99",
-        "PETG": "GFG99",
-        "ABS": "GFB99",
-        "ASA": "GFB98",
-        "TPU": "GFU99",
-        "PA": "GFN99",
-        "PC": "GFC99",
-        "PVA": "GFS99",
-    }
-
-    if setting_id.startswith("GFS"):
-        # Bambu preset: GFSL05 -> GFL05
-        tray_info_idx = "GF" + setting_id[3:]
-    elif setting_id.startswith("PFUS") or setting_id.startswith("PFSP"):
-        # User-created custom filament - fetch filament_id from cloud
-        # The cloud returns a P-prefix filament_id (e.g., "P4d64437") that should be used as tray_info_idx
-        cloud = get_cloud_service()
-        if not cloud.is_authenticated:
-            token = await db.get_setting("cloud_access_token")
-            if token:
-                cloud.set_token(token)
-
-        preset_detail = await cloud.get_setting_detail(slicer_filament)
-        if preset_detail:
-            # filament_id is at root level or in setting.filament_id
-            cloud_filament_id = preset_detail.get("filament_id")
-            if not cloud_filament_id and preset_detail.get("setting"):
-                cloud_filament_id = preset_detail["setting"].get("filament_id")
-
-            if cloud_filament_id:
-                tray_info_idx = cloud_filament_id
-                logger.info(f"Custom preset {slicer_filament} -> filament_id={cloud_filament_id}")
-            else:
-                # Fallback to generic if no filament_id found
-                tray_info_idx = generic_filament_ids.get(material, "GFL99")
-                logger.warning(f"No filament_id in cloud response for {slicer_filament}, using: {tray_info_idx}")
-        else:
-            # Fallback if cloud lookup fails
-            tray_info_idx = generic_filament_ids.get(material, "GFL99")
-            logger.warning(f"Cloud lookup failed for {slicer_filament}, using: {tray_info_idx}")
-        setting_id = slicer_filament
-    else:
-        tray_info_idx = setting_id
+    settings = await _spool_slot_settings(db, spool)
+    tray_color = settings["tray_color"]
+    setting_id = settings["setting_id"]
+    tray_info_idx = settings["tray_info_idx"]
+    temp_min, temp_max = settings["nozzle_temp_min"], settings["nozzle_temp_max"]
 
     logger.info(
-        f"Setting filament: slicer={slicer_filament} -> tray_info_idx={tray_info_idx}, setting_id={setting_id}, type={spool.material}, color={tray_color}"
+        f"Setting filament: slicer={spool.slicer_filament} -> tray_info_idx={tray_info_idx}, "
+        f"setting_id={setting_id}, type={spool.material}, color={tray_color}"
     )
 
     # Look up K-profile for this spool, printer, and nozzle diameter
-    nozzle_diameter = _printer_manager.get_nozzle_diameter(serial)
-    k_profiles = await db.get_spool_k_profiles(request.spool_id)
-    matching_cali_idx = -1  # Default: no specific profile
-
-    for kp in k_profiles:
-        # Match by printer and nozzle diameter
-        if kp.get("printer_serial") == serial and kp.get("nozzle_diameter") == nozzle_diameter:
-            matching_cali_idx = kp.get("cali_idx", -1)
-            logger.info(
-                f"Found matching K-profile for spool {request.spool_id}: cali_idx={matching_cali_idx}, name={kp.get('name')}"
-            )
-            break
-
-    if matching_cali_idx == -1:
-        logger.info(
-            f"No matching K-profile found for spool {request.spool_id} on printer {serial} with nozzle {nozzle_diameter}"
-        )
+    extruder_id = _slot_extruder(serial, ams_id)
+    nozzle_diameter = _printer_manager.get_nozzle_diameter(serial, extruder_id)
+    k_profile = await _find_k_profile(db, request.spool_id, serial, extruder_id)
+    matching_cali_idx = k_profile.get("cali_idx", -1) if k_profile else -1  # Default: no specific profile
 
     # Check if tray has a spool and if it matches the one we're assigning
     state = _printer_manager.get_state(serial)
@@ -509,8 +913,14 @@ async def assign_spool_to_tray(serial: str, ams_id: int, tray_id: int, request:
         if success:
             # Persist assignment for usage tracking
             await db.assign_spool_to_slot(request.spool_id, serial, ams_id, tray_id)
+            await _publish_spool_status(db)
             logger.info(f"Assigned spool {spool.id} ({spool.material}) to {serial} AMS {ams_id} tray {tray_id}")
-            return AssignResponse(status="configured", message="Slot configured successfully")
+            return AssignResponse(
+                status="configured",
+                message="Slot configured successfully",
+                sequence_id=_sent_command(serial, "ams_filament_setting").sequence_id,
+                warnings=warnings,
+            )
         else:
             raise HTTPException(status_code=500, detail="Failed to configure slot")
     else:
@@ -560,7 +970,14 @@ async def assign_spool_to_tray(serial: str, ams_id: int, tray_id: int, request:
         else:
             message = "Insert spool to configure slot"
 
-        return AssignResponse(status="staged", message=message, needs_replacement=needs_replacement)
+        command = _printer_manager.get_last_command(serial, "ams_filament_setting")
+        return AssignResponse(
+            status="staged",
+            message=message,
+            needs_replacement=needs_replacement,
+            sequence_id=command["sequence_id"] if command else None,
+            warnings=warnings,
+        )
 
 
 @router.delete("/{serial}/ams/{ams_id}/tray/{tray_id}/assign", status_code=204)
@@ -576,6 +993,7 @@ async def unassign_spool_from_tray(serial: str, ams_id: int, tray_id: int):
 
     db = await get_db()
     await db.unassign_slot(serial, ams_id, tray_id)
+    await _publish_spool_status(db)
 
 
 @router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/cancel-staged", status_code=204)
@@ -602,6 +1020,65 @@ async def get_slot_assignments(serial: str):
     return await db.get_slot_assignments(serial)
 
 
+@router.get("/{serial}/nozzles", response_model=PrinterNozzles)
+async def get_printer_nozzles(serial: str, limit: int = Query(default=50, ge=1, le=500)):
+    """Nozzles installed on a printer and its nozzle change log (newest first)."""
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+    return PrinterNozzles(
+        nozzles=[
+            NozzleInfo(extruder_id=n["extruder_id"], diameter=n["diameter"], nozzle_type=n["nozzle_type"])
+            for n in await db.get_printer_nozzles(serial)
+        ],
+        changes=[NozzleChange(**c) for c in await db.get_nozzle_changes(serial, limit)],
+    )
+
+
+@router.get("/{serial}/ams-units", response_model=list[AmsUnitRecord])
+async def get_ams_units(serial: str, include_detached: bool = False):
+    """AMS units of a printer by serial number, as reported on connect.
+
+    The unit id stays the same when the printer renumbers its AMS units;
+    slot assignments follow the unit.
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+    return await db.get_ams_units(serial, include_detached=include_detached)
+
+
+class CompatibilityResponse(BaseModel):
+    """A spool's compatibility with a printer."""
+
+    capabilities: PrinterCapabilities  # Effective profile; None fields are unknown and not checked
+    issues: list[CompatibilityIssue]
+    compatible: bool  # No error-level issues
+
+
+@router.get("/{serial}/compatibility", response_model=CompatibilityResponse)
+async def get_compatibility(serial: str, spool_id: str, ams_id: int = 0):
+    """Check whether a spool's material suits a printer before assigning it.
+
+    Args:
+        serial: Printer serial number
+        spool_id: Spool to check
+        ams_id: Slot's AMS unit, to check against the nozzle on the extruder it feeds
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    caps, issues = await _compatibility(db, serial, ams_id, spool)
+    return CompatibilityResponse(
+        capabilities=caps,
+        issues=issues,
+        compatible=not any(issue.severity == SEVERITY_ERROR for issue in issues),
+    )
+
+
 @router.get("/{serial}/pending-assignments")
 async def get_pending_assignments(serial: str):
     """Get all pending (staged) assignments for a printer.
@@ -627,7 +1104,169 @@ async def get_pending_assignments(serial: str):
     return result
 
 
-@router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/reset", status_code=204)
+async def _external_spool(db, serial: str, ams_id: int) -> ExternalSpool:
+    """Spool assigned to an external holder, with the printer's live vt_tray."""
+    spool_id = await db.get_spool_for_slot(serial, ams_id, 0)
+    spool = await db.get_spool(spool_id) if spool_id else None
+    state = _printer_manager.get_state(serial) if _printer_manager else None
+    # The printer reports a single vt_tray, for the main (255) holder
+    tray = state.vt_tray if state and ams_id == 255 else None
+    return ExternalSpool(ams_id=ams_id, spool=spool, tray=tray)
+
+
+async def _broadcast_external_spool(db, serial: str, external: ExternalSpool):
+    """Tell UI clients which spool is on an external holder."""
+    from main import broadcast_message
+
+    fields = external.model_dump(mode="json", exclude={"sequence_id", "warnings"})
+    await broadcast_message({"type": "external_spool", "serial": serial, **fields})
+    await _publish_spool_status(db)
+
+
+async def _publish_spool_status(db):
+    """Tell UI clients about spools that moved in or out of a slot."""
+    from main import publish_spool_status
+
+    await publish_spool_status(db)
+
+
+@router.get("/{serial}/external-spool", response_model=ExternalSpool)
+async def get_external_spool(serial: str, ams_id: int = Query(default=255, ge=254, le=255)):
+    """Get the spool on a printer's external spool holder (virtual tray).
+
+    Args:
+        serial: Printer serial number
+        ams_id: 255 for the external holder, 254 for the left holder on dual-nozzle printers
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+    return await _external_spool(db, serial, ams_id)
+
+
+@router.put("/{serial}/external-spool", response_model=ExternalSpool)
+async def set_external_spool(serial: str, request: ExternalSpoolRequest):
+    """Put a spool on a printer's external spool holder.
+
+    The spool is charged for prints fed from the holder (tray_now 254). If the
+    printer is connected, its external slot is also configured from the spool.
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+    spool = await db.get_spool(request.spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    warnings = await _check_compatibility(db, serial, request.ams_id, spool, request.force)
+
+    await db.assign_spool_to_slot(spool.id, serial, request.ams_id, 0)
+    logger.info(f"Spool {spool.id} ({spool.material}) on external holder {request.ams_id} of {serial}")
+
+    sequence_id = None
+    if _printer_manager and _printer_manager.is_connected(serial):
+        settings = await _spool_slot_settings(db, spool)
+        if _printer_manager.set_filament(serial=serial, ams_id=request.ams_id, tray_id=0, **settings):
+            sequence_id = _sent_command(serial, "ams_filament_setting").sequence_id
+
+        k_profile = await _find_k_profile(db, spool.id, serial)
+        if k_profile:
+            _printer_manager.set_calibration(
+                serial=serial,
+                ams_id=request.ams_id,
+                tray_id=0,
+                cali_idx=k_profile.get("cali_idx", -1),
+                filament_id=settings["tray_info_idx"],
+                nozzle_diameter=k_profile["nozzle_diameter"],
+                setting_id=k_profile.get("setting_id") or "",
+            )
+
+    external = await _external_spool(db, serial, request.ams_id)
+    external.sequence_id = sequence_id
+    external.warnings = warnings
+    await _broadcast_external_spool(db, serial, external)
+    return external
+
+
+@router.delete("/{serial}/external-spool", status_code=204)
+async def clear_external_spool(serial: str, ams_id: int = Query(default=255, ge=254, le=255)):
+    """Remove the spool from a printer's external spool holder.
+
+    Only the tracking assignment is removed, not the filament setting on the printer.
+    """
+    db = await get_db()
+    await db.unassign_slot(serial, ams_id, 0)
+    await _broadcast_external_spool(db, serial, await _external_spool(db, serial, ams_id))
+
+
+@router.post("/{serial}/events/runout", response_model=RunoutEvent, status_code=201)
+async def report_runout(serial: str, request: RunoutEventRequest):
+    """Record a filament runout or jam from a printer or DIY sensor.
+
+    By default a runout marks the slot's spool as empty (a jam doesn't). The
+    event is kept so failed prints can be traced back to the spool, and
+    notification channels/webhooks subscribed to filament.runout are alerted.
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+
+    spool_id = request.spool_id or await db.get_spool_for_slot(serial, request.ams_id, request.tray_id)
+    spool = await db.get_spool(spool_id) if spool_id else None
+    if request.spool_id and not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+
+    print_name = request.print_name
+    if print_name is None and _printer_manager:
+        state = _printer_manager.get_state(serial)
+        if state and state.gcode_state in ("RUNNING", "PAUSE"):
+            print_name = state.subtask_name
+
+    mark_empty = request.mark_empty if request.mark_empty is not None else request.kind == RunoutKind.RUNOUT
+    if spool and mark_empty:
+        await db.mark_spool_empty(spool.id)
+
+    event = RunoutEvent(
+        **await db.log_runout_event(
+            printer_serial=serial,
+            ams_id=request.ams_id,
+            tray_id=request.tray_id,
+            kind=request.kind.value,
+            spool_id=spool.id if spool else None,
+            print_name=print_name,
+            source=request.source,
+            marked_empty=bool(spool and mark_empty),
+        )
+    )
+    logger.info(
+        f"Filament {request.kind.value} on {serial} AMS {request.ams_id} tray {request.tray_id}"
+        f" (spool {event.spool_id or 'unknown'}, source {request.source or 'unknown'})"
+    )
+
+    emit_event(
+        EVENT_FILAMENT_RUNOUT,
+        {
+            **event.model_dump(mode="json", exclude={"id", "printer_serial", "created_at"}),
+            "serial": serial,
+            "material": spool.material if spool else None,
+            "color_name": spool.color_name if spool else None,
+            "brand": spool.brand if spool else None,
+        },
+    )
+
+    from main import broadcast_message
+
+    await broadcast_message({"type": "filament_runout", **event.model_dump(mode="json")})
+    return event
+
+
+@router.get("/{serial}/events/runout", response_model=list[RunoutEvent])
+async def list_runout_events(serial: str, limit: int = Query(default=50, ge=1, le=500)):
+    """Get a printer's recent runout/jam events, newest first."""
+    db = await get_db()
+    return await db.get_runout_events(printer_serial=serial, limit=limit)
+
+
+@router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/reset", status_code=202, response_model=CommandStatus)
 async def reset_slot(serial: str, ams_id: int, tray_id: int):
     """Reset/clear an AMS slot to trigger RFID re-read.
 
@@ -650,8 +1289,10 @@ async def reset_slot(serial: str, ams_id: int, tray_id: int):
     if not success:
         raise HTTPException(status_code=500, detail="Failed to reset slot")
 
+    return _sent_command(serial, "ams_get_rfid")
+
 
-@router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/calibration", status_code=204)
+@router.post("/{serial}/ams/{ams_id}/tray/{tray_id}/calibration", status_code=202, response_model=CommandStatus)
 async def set_calibration(serial: str, ams_id: int, tray_id: int, request: SetCalibrationRequest):
     """Set calibration profile (k-value) for an AMS slot.
 
@@ -659,6 +1300,8 @@ async def set_calibration(serial: str, ams_id: int, tray_id: int, request: SetCa
     1. extrusion_cali_sel - selects the K profile
     2. extrusion_cali_set - directly sets the K value (if k_value > 0)
 
+    Returns the extrusion_cali_sel command, see set_filament.
+
     Args:
         serial: Printer serial number
         ams_id: AMS unit ID
@@ -703,6 +1346,72 @@ async def set_calibration(serial: str, ams_id: int, tray_id: int, request: SetCa
             nozzle_temp=request.nozzle_temp_max,
         )
 
+    return _sent_command(serial, "extrusion_cali_sel")
+
+
+@router.post("/{serial}/speed", status_code=202, response_model=CommandStatus)
+async def set_speed_level(serial: str, request: SpeedLevelRequest):
+    """Change the speed of the running print (1 silent, 2 standard, 3 sport, 4 ludicrous)."""
+    _require_printing(serial)
+    if not _printer_manager.set_speed_level(serial, request.level):
+        raise HTTPException(status_code=500, detail="Failed to set speed")
+    return _sent_command(serial, "print_speed")
+
+
+@router.post("/{serial}/skip-objects", status_code=202, response_model=CommandStatus)
+async def skip_objects(serial: str, request: SkipObjectsRequest):
+    """Skip objects of the running print; already skipped ones are listed in the state's skipped_objects."""
+    _require_printing(serial)
+    if not _printer_manager.skip_objects(serial, request.obj_ids):
+        raise HTTPException(status_code=500, detail="Failed to skip objects")
+    return _sent_command(serial, "skip_objects")
+
+
+@router.post("/{serial}/print/{action}", status_code=202, response_model=CommandStatus)
+async def control_print(serial: str, action: PrintAction):
+    """Pause, resume or stop the running print."""
+    _require_printing(serial)
+    gcode_state = _printer_manager.get_state(serial).gcode_state
+    if action == PrintAction.PAUSE and gcode_state != "RUNNING":
+        raise HTTPException(status_code=409, detail="Print is not running")
+    if action == PrintAction.RESUME and gcode_state != "PAUSE":
+        raise HTTPException(status_code=409, detail="Print is not paused")
+    if not _printer_manager.control_print(serial, action):
+        raise HTTPException(status_code=500, detail=f"Failed to {action} print")
+    return _sent_command(serial, action.value)
+
+
+@router.get("/{serial}/commands", response_model=list[CommandStatus])
+async def get_commands(serial: str):
+    """Get commands recently sent to a printer, newest first."""
+    if not _printer_manager:
+        raise HTTPException(status_code=500, detail="Printer manager not available")
+
+    return _printer_manager.get_commands(serial)
+
+
+@router.get("/{serial}/commands/{sequence_id}", response_model=CommandStatus)
+async def get_command(serial: str, sequence_id: str, wait: float = Query(default=0, ge=0, le=30)):
+    """Get the outcome of a command sent to a printer.
+
+    Args:
+        serial: Printer serial number
+        sequence_id: sequence_id returned when the command was sent
+        wait: Seconds to wait for a pending command to complete (long-poll)
+    """
+    if not _printer_manager:
+        raise HTTPException(status_code=500, detail="Printer manager not available")
+
+    loop = asyncio.get_running_loop()
+    deadline = loop.time() + wait
+    while True:
+        command = _printer_manager.get_command(serial, sequence_id)
+        if not command:
+            raise HTTPException(status_code=404, detail="Command not found")
+        if command["status"] != "pending" or loop.time() >= deadline:
+            return command
+        await asyncio.sleep(COMMAND_POLL_INTERVAL)
+
 
 @router.get("/{serial}/calibrations")
 async def get_calibrations(serial: str, nozzle_diameter: str = "0.4"):
@@ -760,12 +1469,7 @@ async def get_printer_cover(serial: str, format: str = "rgb565"):
         raise HTTPException(status_code=404, detail="No active print job")
 
     # Extract plate number from gcode_file (e.g., "plate_1.gcode" -> 1)
-    plate_num = 1
-    gcode_file = getattr(state, "gcode_file", None)
-    if gcode_file:
-        match = re.search(r"plate_(\d+)\.gcode", gcode_file)
-        if match:
-            plate_num = int(match.group(1))
+    plate_num = plate_number(getattr(state, "gcode_file", None))
 
     # Check cache (include format in key)
     cache_key = (subtask_name, plate_num, format)
@@ -773,18 +1477,9 @@ async def get_printer_cover(serial: str, format: str = "rgb565"):
         media_type = "image/png" if format == "png" else "application/octet-stream"
         return Response(content=_cover_cache[serial][cache_key], media_type=media_type)
 
-    # Build 3MF filename
-    filename = subtask_name
-    if not filename.endswith(".3mf"):
-        filename = filename + ".gcode.3mf"
-
-    # Possible paths on printer
-    remote_paths = [
-        f"/{filename}",
-        f"/cache/{filename}",
-        f"/model/{filename}",
-        f"/data/{filename}",
-    ]
+    # Possible paths of the 3MF on the printer
+    remote_paths = print_file_paths(subtask_name)
+    filename = remote_paths[0].lstrip("/")
 
     logger.info(f"Downloading cover for '{filename}' from {printer.ip_address}")
 
@@ -862,6 +1557,116 @@ async def get_printer_cover(serial: str, format: str = "rgb565"):
         zf.close()
 
 
+async def _camera_printer(serial: str) -> Printer:
+    db = await get_db()
+    printer = await db.get_printer(serial)
+    if not printer:
+        raise HTTPException(status_code=404, detail="Printer not found")
+    if not printer.ip_address or not printer.access_code:
+        raise HTTPException(status_code=400, detail="Printer has no IP address or access code")
+    return printer
+
+
+def _camera_http_error(e: CameraError) -> HTTPException:
+    status = 501 if isinstance(e, CameraUnavailable) else 502
+    return HTTPException(status_code=status, detail=str(e))
+
+
+@router.get("/{serial}/camera/snapshot")
+async def get_camera_snapshot(
+    serial: str,
+    format: str = Query(default="jpeg", pattern="^(jpeg|rgb565)$"),
+    width: int = Query(default=CAMERA_THUMBNAIL_SIZE[0], ge=16, le=480),
+    height: int = Query(default=CAMERA_THUMBNAIL_SIZE[1], ge=16, le=320),
+):
+    """Get a still image from the printer's chamber camera.
+
+    Args:
+        serial: Printer serial number
+        format: 'jpeg' for web display (default), 'rgb565' for the ESP32 display
+        width: Thumbnail width for rgb565
+        height: Thumbnail height for rgb565
+    """
+    printer = await _camera_printer(serial)
+    try:
+        frame = await snapshot(printer.ip_address, printer.access_code, printer.model)
+    except CameraError as e:
+        logger.warning(f"Camera snapshot from {serial} failed: {e}")
+        raise _camera_http_error(e)
+
+    if format == "jpeg":
+        return Response(content=frame, media_type="image/jpeg", headers={"Cache-Control": "no-store"})
+    rgb565_data = await asyncio.to_thread(resize_cover_image, frame, (width, height))
+    return Response(content=rgb565_data, media_type="application/octet-stream")
+
+
+@router.get("/{serial}/camera/stream")
+async def stream_camera(serial: str, request: Request):
+    """Proxy the printer's chamber camera as an MJPEG stream.
+
+    The response is multipart/x-mixed-replace, so it can be used directly as
+    an <img> source.
+    """
+    printer = await _camera_printer(serial)
+    frames = camera_frames(printer.ip_address, printer.access_code, printer.model)
+    try:
+        frame = await first_frame(frames)
+    except CameraError as e:
+        await frames.aclose()
+        logger.warning(f"Camera stream from {serial} failed: {e}")
+        raise _camera_http_error(e)
+
+    def part(jpeg: bytes) -> bytes:
+        return b"--frame\r\nContent-Type: image/jpeg\r\nContent-Length: %d\r\n\r\n%b\r\n" % (len(jpeg), jpeg)
+
+    async def generate():
+        try:
+            yield part(frame)
+            async for jpeg in frames:
+                if await request.is_disconnected():
+                    break
+                yield part(jpeg)
+        except CameraError as e:
+            logger.info(f"Camera stream from {serial} ended: {e}")
+        finally:
+            await frames.aclose()
+
+    return StreamingResponse(
+        generate(),
+        media_type="multipart/x-mixed-replace; boundary=frame",
+        headers={"Cache-Control": "no-store"},
+    )
+
+
+@router.get("/{serial}/ams-events", response_model=list[AmsEvent])
+async def get_ams_events(
+    serial: str,
+    ams_id: int | None = None,
+    tray_id: int | None = None,
+    spool_id: str | None = None,
+    since: int | None = None,
+    limit: int = Query(default=100, ge=1, le=1000),
+):
+    """AMS slot change ledger for a printer, newest first.
+
+    Lists when spools were inserted into or removed from slots, when a slot's
+    filament changed, and when filament was loaded into or unloaded from an
+    extruder, along with the spool assigned to the slot at the time.
+
+    Args:
+        serial: Printer serial number
+        ams_id: Only events for this AMS unit (255/254 for external holders)
+        tray_id: Only events for this tray within the AMS unit
+        spool_id: Only events for slots holding this spool
+        since: Only events at or after this Unix timestamp
+        limit: Maximum number of events
+    """
+    db = await get_db()
+    if not await db.get_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found")
+    return await db.get_ams_events(serial, ams_id, tray_id, spool_id, since, limit)
+
+
 class AMSHistoryResponse(BaseModel):
     """Response for AMS sensor history."""
 
@@ -0,0 +1,150 @@
+"""Project endpoints.
+
+Projects group print jobs and manual usage entries ("Voron build", "Etsy
+order #123"). Prints finished while a project is active are added to it
+automatically; per-project rollups are in the reports API.
+"""
+
+import time
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel
+
+router = APIRouter(prefix="/projects", tags=["projects"])
+
+# Setting holding the project that new print jobs are logged against
+ACTIVE_PROJECT_SETTING = "active_project_id"
+
+
+class ProjectCreate(BaseModel):
+    """Request to create a project."""
+
+    name: str
+    description: str | None = None
+
+
+class ProjectUpdate(BaseModel):
+    """Request to update a project."""
+
+    name: str | None = None
+    description: str | None = None
+    archived: bool | None = None
+
+
+class Project(BaseModel):
+    """A project grouping usage entries."""
+
+    id: int
+    name: str
+    description: str | None = None
+    archived_at: int | None = None
+    created_at: int | None = None
+
+
+class ActiveProject(BaseModel):
+    """Project that automatically logged print jobs are grouped under."""
+
+    project_id: int | None = None
+
+
+class AssignUsageRequest(BaseModel):
+    """Usage history entries to move into a project."""
+
+    usage_ids: list[int]
+
+
+async def get_active_project_id(db) -> int | None:
+    """Active project ID, or None if unset or the project no longer exists."""
+    value = await db.get_setting(ACTIVE_PROJECT_SETTING)
+    if not value:
+        return None
+    project = await db.get_project(int(value))
+    return project["id"] if project and not project["archived_at"] else None
+
+
+async def _get_project_or_404(db, project_id: int) -> dict:
+    project = await db.get_project(project_id)
+    if not project:
+        raise HTTPException(status_code=404, detail="Project not found")
+    return project
+
+
+@router.get("", response_model=list[Project])
+async def list_projects(include_archived: bool = Query(default=False)):
+    """List projects, newest first."""
+    db = await get_db()
+    return await db.get_projects(include_archived=include_archived)
+
+
+@router.post("", response_model=Project, status_code=201)
+async def create_project(request: ProjectCreate):
+    """Create a project."""
+    db = await get_db()
+    return await db.create_project(request.name, request.description)
+
+
+@router.get("/active", response_model=ActiveProject)
+async def get_active_project():
+    """Get the project new print jobs are added to."""
+    db = await get_db()
+    return ActiveProject(project_id=await get_active_project_id(db))
+
+
+@router.put("/active", response_model=ActiveProject)
+async def set_active_project(request: ActiveProject):
+    """Set (or clear, with null) the project new print jobs are added to."""
+    db = await get_db()
+    if request.project_id is not None:
+        project = await _get_project_or_404(db, request.project_id)
+        if project["archived_at"]:
+            raise HTTPException(status_code=400, detail="Project is archived")
+    await db.set_setting(ACTIVE_PROJECT_SETTING, str(request.project_id) if request.project_id else "")
+    return request
+
+
+@router.get("/{project_id}", response_model=Project)
+async def get_project(project_id: int):
+    """Get a project."""
+    db = await get_db()
+    return await _get_project_or_404(db, project_id)
+
+
+@router.put("/{project_id}", response_model=Project)
+async def update_project(project_id: int, request: ProjectUpdate):
+    """Update a project. Archiving hides it from lists and reports by default."""
+    db = await get_db()
+    project = await _get_project_or_404(db, project_id)
+
+    fields = request.model_dump(exclude_unset=True, exclude={"archived"})
+    if request.archived is not None:
+        if not request.archived:
+            fields["archived_at"] = None
+        elif not project["archived_at"]:
+            fields["archived_at"] = int(time.time())
+    return await db.update_project(project_id, **fields)
+
+
+@router.delete("/{project_id}", status_code=204)
+async def delete_project(project_id: int):
+    """Delete a project. Its usage entries are kept but no longer grouped."""
+    db = await get_db()
+    if not await db.delete_project(project_id):
+        raise HTTPException(status_code=404, detail="Project not found")
+
+
+@router.get("/{project_id}/usage")
+async def get_project_usage(project_id: int, limit: int = Query(default=100, le=1000)):
+    """Get the usage entries grouped under a project."""
+    db = await get_db()
+    await _get_project_or_404(db, project_id)
+    return await db.get_project_usage(project_id, limit=limit)
+
+
+@router.post("/{project_id}/usage")
+async def assign_project_usage(project_id: int, request: AssignUsageRequest):
+    """Move existing usage history entries (print jobs or manual entries) into a project."""
+    db = await get_db()
+    await _get_project_or_404(db, project_id)
+    count = await db.assign_usage_to_project(project_id, request.usage_ids)
+    return {"status": "ok", "count": count}
@@ -0,0 +1,177 @@
+"""Purchase and restock endpoints.
+
+Purchases record where filament was bought and what it cost, linked to the
+spools that came from them. The restock endpoints compare each material's
+stock with a configured level and suggest what to reorder, and from where.
+"""
+
+import time
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel, Field, computed_field, field_validator
+from services.restock import (
+    RESTOCK_LEVELS_SETTING,
+    RestockLevels,
+    RestockSuggestion,
+    get_restock_levels,
+    restock_suggestions,
+)
+
+router = APIRouter(prefix="/purchases", tags=["purchases"])
+
+
+class PurchaseFields(BaseModel):
+    """Fields of a purchase."""
+
+    supplier: str | None = None
+    url: str | None = None  # Product or order page
+    order_ref: str | None = None
+    purchased_at: int | None = None  # Epoch seconds
+    price: float | None = Field(default=None, ge=0)  # Total paid
+    quantity: int | None = Field(default=None, ge=1)  # Spools bought
+    note: str | None = None
+
+    @field_validator("url")
+    @classmethod
+    def check_url(cls, v):
+        # Shown as a link in the UI, so only web links
+        if v and not v.lower().startswith(("http://", "https://")):
+            raise ValueError("url must be an http(s) link")
+        return v
+
+
+class PurchaseCreate(PurchaseFields):
+    """Request to record a purchase, optionally with the spools that came from it."""
+
+    quantity: int = Field(default=1, ge=1)
+    spool_ids: list[str] = []
+
+
+class Purchase(PurchaseFields):
+    """A filament purchase."""
+
+    id: int
+    quantity: int = 1
+    spool_ids: list[str] = []
+    created_at: int | None = None
+
+    @computed_field
+    @property
+    def unit_price(self) -> float | None:
+        """Price per spool."""
+        return round(self.price / self.quantity, 2) if self.price is not None else None
+
+
+class LinkSpoolsRequest(BaseModel):
+    """Spools that came from a purchase."""
+
+    spool_ids: list[str]
+
+
+async def _get_purchase_or_404(db, purchase_id: int) -> dict:
+    purchase = await db.get_purchase(purchase_id)
+    if not purchase:
+        raise HTTPException(status_code=404, detail="Purchase not found")
+    return purchase
+
+
+async def _check_spools(db, spool_ids: list[str]):
+    for spool_id in spool_ids:
+        if not await db.get_spool(spool_id):
+            raise HTTPException(status_code=404, detail=f"Spool not found: {spool_id}")
+
+
+async def _link_spools(db, purchase: dict, spool_ids: list[str]):
+    unit_price = purchase["price"] / purchase["quantity"] if purchase["price"] is not None else None
+    await db.link_purchase_spools(purchase["id"], spool_ids, unit_price)
+
+
+@router.get("", response_model=list[Purchase])
+async def list_purchases(supplier: str | None = None, limit: int = Query(default=100, ge=1, le=1000)):
+    """List purchases, most recent first."""
+    db = await get_db()
+    return await db.get_purchases(supplier=supplier, limit=limit)
+
+
+@router.post("", response_model=Purchase, status_code=201)
+async def create_purchase(request: PurchaseCreate):
+    """Record a purchase. Linked spools without a price get the purchase's price per spool."""
+    db = await get_db()
+    await _check_spools(db, request.spool_ids)
+    fields = request.model_dump(exclude={"spool_ids"})
+    fields["purchased_at"] = fields["purchased_at"] or int(time.time())
+    purchase = await db.create_purchase(**fields)
+    if request.spool_ids:
+        await _link_spools(db, purchase, request.spool_ids)
+    return await db.get_purchase(purchase["id"])
+
+
+@router.get("/restock", response_model=list[RestockSuggestion])
+async def get_restock_suggestions(include_ok: bool = Query(default=False)):
+    """Materials below their stock level, most urgent first.
+
+    Each comes with its recent consumption rate and last purchase. With
+    include_ok, materials with enough stock (or no level) are listed too.
+    """
+    db = await get_db()
+    return await restock_suggestions(db, include_ok=include_ok)
+
+
+@router.get("/restock/levels", response_model=RestockLevels)
+async def get_restock_stock_levels():
+    """Get the stock level per material that restocking aims for."""
+    db = await get_db()
+    return await get_restock_levels(db)
+
+
+@router.put("/restock/levels", response_model=RestockLevels)
+async def set_restock_stock_levels(levels: RestockLevels):
+    """Set the stock level per material (grams of filament), and the level for other materials."""
+    db = await get_db()
+    await db.set_setting(RESTOCK_LEVELS_SETTING, levels.model_dump_json())
+    return levels
+
+
+@router.get("/{purchase_id}", response_model=Purchase)
+async def get_purchase(purchase_id: int):
+    """Get a purchase."""
+    db = await get_db()
+    return await _get_purchase_or_404(db, purchase_id)
+
+
+@router.put("/{purchase_id}", response_model=Purchase)
+async def update_purchase(purchase_id: int, request: PurchaseFields):
+    """Update a purchase. Prices already given to its spools are not changed."""
+    db = await get_db()
+    await _get_purchase_or_404(db, purchase_id)
+    fields = request.model_dump(exclude_unset=True)
+    if "quantity" in fields and fields["quantity"] is None:
+        raise HTTPException(status_code=422, detail="quantity must not be empty")
+    return await db.update_purchase(purchase_id, **fields)
+
+
+@router.delete("/{purchase_id}", status_code=204)
+async def delete_purchase(purchase_id: int):
+    """Delete a purchase. Its spools are kept."""
+    db = await get_db()
+    if not await db.delete_purchase(purchase_id):
+        raise HTTPException(status_code=404, detail="Purchase not found")
+
+
+@router.post("/{purchase_id}/spools", response_model=Purchase)
+async def link_purchase_spools(purchase_id: int, request: LinkSpoolsRequest):
+    """Link spools to a purchase, moving them from any other purchase."""
+    db = await get_db()
+    purchase = await _get_purchase_or_404(db, purchase_id)
+    await _check_spools(db, request.spool_ids)
+    await _link_spools(db, purchase, request.spool_ids)
+    return await db.get_purchase(purchase_id)
+
+
+@router.delete("/{purchase_id}/spools/{spool_id}", status_code=204)
+async def unlink_purchase_spool(purchase_id: int, spool_id: str):
+    """Remove a spool from a purchase."""
+    db = await get_db()
+    if not await db.unlink_purchase_spool(purchase_id, spool_id):
+        raise HTTPException(status_code=404, detail="Spool not linked to this purchase")
@@ -0,0 +1,80 @@
+"""Spool weight reconciliation endpoints.
+
+Scale readings that disagree with the usage tracked since the previous
+reading are listed as suggestions. Accepting one logs the untracked grams
+(or the amount given instead) as usage, dismissing it leaves the usage as
+it is, e.g. when the spool was weighed on its holder.
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel
+from services.reconciliation import (
+    RECONCILIATION_REASON,
+    ReconciliationStatus,
+    WeightReconciliation,
+    scan_measurements,
+)
+
+router = APIRouter(prefix="/reconciliation", tags=["reconciliation"])
+
+
+class AcceptRequest(BaseModel):
+    """Accept a suggestion, optionally with an adjusted amount."""
+
+    grams: float | None = None  # Usage to log, defaults to the untracked difference
+
+
+async def _get_open_or_409(db, reconciliation_id: int) -> dict:
+    row = await db.get_weight_reconciliation(reconciliation_id)
+    if not row:
+        raise HTTPException(status_code=404, detail="Reconciliation not found")
+    if row["status"] != ReconciliationStatus.OPEN:
+        raise HTTPException(status_code=409, detail=f"Reconciliation is {row['status']}")
+    return row
+
+
+@router.get("", response_model=list[WeightReconciliation])
+async def list_reconciliations(
+    status: ReconciliationStatus | None = Query(default=ReconciliationStatus.OPEN),
+    spool_id: str | None = None,
+    limit: int = Query(default=100, ge=1, le=500),
+):
+    """List reconciliation suggestions, newest reading first (open ones by default)."""
+    db = await get_db()
+    return await db.get_weight_reconciliations(status=status, spool_id=spool_id, limit=limit)
+
+
+@router.post("/scan", response_model=list[WeightReconciliation])
+async def scan_reconciliations():
+    """Check the scale readings recorded since the last scan now; returns the new suggestions."""
+    db = await get_db()
+    return await scan_measurements(db)
+
+
+@router.post("/{reconciliation_id}/accept", response_model=WeightReconciliation)
+async def accept_reconciliation(reconciliation_id: int, request: AcceptRequest | None = None):
+    """Log the untracked difference (or the given grams) as usage of the spool.
+
+    The spool's weight isn't changed, it was synced to the scale reading already.
+    """
+    db = await get_db()
+    row = await _get_open_or_409(db, reconciliation_id)
+    grams = request.grams if request and request.grams is not None else -row["discrepancy"]
+
+    await db.log_usage(
+        spool_id=row["spool_id"],
+        printer_serial="manual",
+        print_name="Weight reconciliation",
+        weight_used=grams,
+        reason=RECONCILIATION_REASON,
+    )
+    return await db.resolve_weight_reconciliation(reconciliation_id, ReconciliationStatus.ACCEPTED, grams)
+
+
+@router.post("/{reconciliation_id}/dismiss", response_model=WeightReconciliation)
+async def dismiss_reconciliation(reconciliation_id: int):
+    """Leave the tracked usage as it is."""
+    db = await get_db()
+    await _get_open_or_409(db, reconciliation_id)
+    return await db.resolve_weight_reconciliation(reconciliation_id, ReconciliationStatus.DISMISSED)
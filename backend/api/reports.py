@@ -0,0 +1,298 @@
+"""Usage reporting API endpoints.
+
+Aggregates are computed in SQL so the web UI can render charts without
+pulling raw usage history rows. Month and week groups follow the local
+calendar of the report timezone (see services/report_periods.py).
+"""
+
+import time
+from enum import StrEnum
+from typing import Literal
+
+from api.settings import ReportCalendar, get_electricity_price, get_report_calendar, valid_timezone
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel, Field, model_validator
+from services.jobs import JobContext, job_kind
+from services.report_periods import period_bounds
+
+router = APIRouter(prefix="/reports", tags=["reports"])
+
+TOP_N = 10
+
+PERIOD_GROUPINGS = ("month", "week")
+
+
+class UsageGroupBy(StrEnum):
+    """Supported usage report groupings."""
+
+    MATERIAL = "material"
+    PRINTER = "printer"
+    MONTH = "month"
+    WEEK = "week"
+
+
+class UsageReportGroup(BaseModel):
+    """Aggregated usage for a single group."""
+
+    key: str  # Material name, printer name/serial, YYYY-MM, or the first day of the week as YYYY-MM-DD
+    weight_used: float  # Grams consumed
+    prints: int  # Number of usage entries
+    spools: int  # Distinct spools involved
+    share: float  # Percentage of total weight
+    rank: int  # 1 = highest consumption
+    cumulative_weight: float  # Running total in report order
+    change: float | None = None  # Weight delta vs previous group (trend)
+    last_used: int | None = None
+    start: int | None = None  # Month/week groups: local midnight starting the period (epoch seconds)
+    end: int | None = None  # Month/week groups: start of the next period
+
+
+class UsageReport(BaseModel):
+    """Usage report response."""
+
+    group_by: UsageGroupBy
+    since: int | None = None
+    timezone: str | None = None  # Calendar of month/week groups
+    total_weight: float = 0
+    total_prints: int = 0
+    groups: list[UsageReportGroup] = []
+    top: list[UsageReportGroup] = []  # Top 10 groups by weight
+
+
+async def _report_calendar(db, timezone: str | None) -> ReportCalendar:
+    """The configured report calendar, with the timezone overridden if given."""
+    calendar = await get_report_calendar(db)
+    if timezone is not None:
+        if not valid_timezone(timezone):
+            raise HTTPException(status_code=400, detail=f"Unknown timezone: {timezone}")
+        calendar.timezone = timezone
+    return calendar
+
+
+def _period_bounds(group_by: str, key: str, calendar: ReportCalendar) -> dict:
+    if group_by not in PERIOD_GROUPINGS:
+        return {}
+    start, end = period_bounds(key, calendar.timezone, group_by)
+    return {"start": start, "end": end}
+
+
+@router.get("/usage", response_model=UsageReport)
+async def get_usage_report(
+    group_by: UsageGroupBy = Query(UsageGroupBy.MATERIAL, description="Grouping for the report"),
+    days: int | None = Query(None, ge=1, le=3650, description="Only include the last N days"),
+    timezone: str | None = Query(None, description="IANA timezone for month/week groups, default the configured one"),
+):
+    """Get filament usage aggregated by material, printer, month or week.
+
+    Month and week reports follow the local calendar, are ordered
+    chronologically and include the change versus the previous period; other
+    groupings are ordered by consumption.
+    """
+    since = int(time.time()) - days * 86400 if days else None
+
+    db = await get_db()
+    calendar = await _report_calendar(db, timezone)
+    rows = await db.get_usage_report(
+        group_by.value, since=since, timezone=calendar.timezone, week_start=calendar.week_start
+    )
+
+    groups = []
+    for row in rows:
+        previous = row.pop("previous_weight")
+        groups.append(
+            UsageReportGroup(
+                **row,
+                **_period_bounds(group_by, row["key"], calendar),
+                change=row["weight_used"] - previous if previous is not None else None,
+            )
+        )
+
+    return UsageReport(
+        group_by=group_by,
+        since=since,
+        timezone=calendar.timezone if group_by in PERIOD_GROUPINGS else None,
+        total_weight=sum(g.weight_used for g in groups),
+        total_prints=sum(g.prints for g in groups),
+        groups=groups,
+        top=sorted(groups, key=lambda g: g.rank)[:TOP_N],
+    )
+
+
+class ProjectMaterialUsage(BaseModel):
+    """Usage of one material within a project."""
+
+    material: str
+    weight_used: float
+    cost: float | None = None
+
+
+class ProjectReportEntry(BaseModel):
+    """Filament and cost rollup for a project."""
+
+    project_id: int
+    name: str
+    archived_at: int | None = None
+    weight_used: float  # Grams consumed
+    prints: int  # Number of usage entries
+    spools: int  # Distinct spools involved
+    cost: float | None = None  # From spool prices, None if no spool had a price
+    priced_weight: float  # Grams that went into the cost
+    first_used: int | None = None
+    last_used: int | None = None
+    materials: list[ProjectMaterialUsage] = []
+    energy_kwh: float = 0  # Estimated print energy
+    energy_cost: float | None = None  # None if no electricity price is set
+
+
+class ProjectReport(BaseModel):
+    """Per-project report response."""
+
+    since: int | None = None
+    total_weight: float = 0
+    total_energy_kwh: float = 0
+    total_energy_cost: float = 0
+    total_cost: float = 0  # Filament and energy
+    electricity_price: float | None = None
+    projects: list[ProjectReportEntry] = []
+
+
+@router.get("/projects", response_model=ProjectReport)
+async def get_project_report(
+    days: int | None = Query(None, ge=1, le=3650, description="Only include the last N days"),
+    include_archived: bool = Query(False, description="Include archived projects"),
+):
+    """Get filament usage and cost per project, highest consumption first.
+
+    Cost is prorated from each spool's price over its label weight. Print
+    energy is costed at the configured electricity price.
+    """
+    since = int(time.time()) - days * 86400 if days else None
+
+    db = await get_db()
+    price = await get_electricity_price(db)
+    projects = [
+        ProjectReportEntry(
+            **{**row, "energy_kwh": round(row["energy_kwh"], 3)}, energy_cost=_energy_cost(row["energy_kwh"], price)
+        )
+        for row in await db.get_project_report(since=since, include_archived=include_archived)
+    ]
+
+    total_energy_cost = sum(p.energy_cost or 0 for p in projects)
+    return ProjectReport(
+        since=since,
+        total_weight=sum(p.weight_used for p in projects),
+        total_energy_kwh=round(sum(p.energy_kwh for p in projects), 3),
+        total_energy_cost=round(total_energy_cost, 2),
+        total_cost=round(sum(p.cost or 0 for p in projects) + total_energy_cost, 2),
+        electricity_price=price,
+        projects=projects,
+    )
+
+
+def _energy_cost(energy_kwh: float, price: float | None) -> float | None:
+    return round(energy_kwh * price, 2) if price is not None else None
+
+
+class EnergyGroupBy(StrEnum):
+    """Supported energy report groupings."""
+
+    PRINTER = "printer"
+    MONTH = "month"
+    WEEK = "week"
+
+
+class EnergyReportGroup(BaseModel):
+    """Estimated print energy for a single group."""
+
+    key: str  # Printer name/serial, YYYY-MM, or the first day of the week as YYYY-MM-DD
+    energy_kwh: float
+    duration_sec: int  # Total print time
+    prints: int
+    cost: float | None = None  # None if no electricity price is set
+    start: int | None = None  # Month/week groups: local midnight starting the period (epoch seconds)
+    end: int | None = None  # Month/week groups: start of the next period
+
+
+class EnergyReport(BaseModel):
+    """Energy report response."""
+
+    group_by: EnergyGroupBy
+    since: int | None = None
+    timezone: str | None = None  # Calendar of month/week groups
+    electricity_price: float | None = None
+    total_energy_kwh: float = 0
+    total_cost: float | None = None
+    groups: list[EnergyReportGroup] = []
+
+
+@router.get("/energy", response_model=EnergyReport)
+async def get_energy_report(
+    group_by: EnergyGroupBy = Query(EnergyGroupBy.PRINTER, description="Grouping for the report"),
+    days: int | None = Query(None, ge=1, le=3650, description="Only include the last N days"),
+    timezone: str | None = Query(None, description="IANA timezone for month/week groups, default the configured one"),
+):
+    """Get estimated print energy and its cost by printer, month or week.
+
+    Energy is estimated from each printer's configured average power draw and
+    the print duration; printers without a power draw are not tracked.
+    """
+    since = int(time.time()) - days * 86400 if days else None
+
+    db = await get_db()
+    price = await get_electricity_price(db)
+    calendar = await _report_calendar(db, timezone)
+    rows = await db.get_energy_report(
+        group_by.value, since=since, timezone=calendar.timezone, week_start=calendar.week_start
+    )
+    groups = [
+        EnergyReportGroup(
+            **{**row, "energy_kwh": round(row["energy_kwh"], 3)},
+            **_period_bounds(group_by, row["key"], calendar),
+            cost=_energy_cost(row["energy_kwh"], price),
+        )
+        for row in rows
+    ]
+    total_energy = sum(g.energy_kwh for g in groups)
+
+    return EnergyReport(
+        group_by=group_by,
+        since=since,
+        timezone=calendar.timezone if group_by in PERIOD_GROUPINGS else None,
+        electricity_price=price,
+        total_energy_kwh=round(total_energy, 3),
+        total_cost=_energy_cost(total_energy, price),
+        groups=groups,
+    )
+
+
+class ReportJobParams(BaseModel):
+    """Parameters of a report generation job."""
+
+    report: Literal["usage", "projects", "energy"]
+    group_by: str | None = None  # Usage or energy report grouping, default as for the endpoint
+    days: int | None = Field(default=None, ge=1, le=3650)
+    timezone: str | None = None  # Usage and energy month/week groups, default the report calendar's
+    include_archived: bool = False  # Project report only
+
+    @model_validator(mode="after")
+    def check_group_by(self):
+        groupings = {"usage": UsageGroupBy, "energy": EnergyGroupBy}.get(self.report)
+        if self.group_by is not None and (groupings is None or self.group_by not in list(groupings)):
+            raise ValueError(f"Invalid group_by {self.group_by!r} for the {self.report} report")
+        return self
+
+
+@job_kind("report", ReportJobParams)
+async def report_job(ctx: JobContext, params: ReportJobParams) -> dict:
+    """Background job generating a report; the result is the report as the endpoint returns it."""
+    await ctx.update(message=f"Generating {params.report} report", force=True)
+    if params.report == "usage":
+        group_by = UsageGroupBy(params.group_by or UsageGroupBy.MATERIAL)
+        report = await get_usage_report(group_by=group_by, days=params.days, timezone=params.timezone)
+    elif params.report == "energy":
+        group_by = EnergyGroupBy(params.group_by or EnergyGroupBy.PRINTER)
+        report = await get_energy_report(group_by=group_by, days=params.days, timezone=params.timezone)
+    else:
+        report = await get_project_report(days=params.days, include_archived=params.include_archived)
+    return report.model_dump(mode="json")
@@ -0,0 +1,76 @@
+"""Global text search.
+
+One search box for the UI (and voice assistants) across spools, printers
+and projects, backed by the SQLite FTS5 search_index table.
+"""
+
+import logging
+from typing import Literal
+
+from db import get_db
+from fastapi import APIRouter, Query
+from pydantic import BaseModel
+from services.wled import WledError, get_wled_config, locate_spool
+
+logger = logging.getLogger(__name__)
+
+router = APIRouter(prefix="/search", tags=["search"])
+
+SearchKind = Literal["spool", "printer", "project"]
+
+
+class SearchResult(BaseModel):
+    """A record matching a search."""
+
+    kind: SearchKind
+    id: str  # Spool ID, printer serial or project ID
+    title: str  # e.g. "#12 Bambu PLA Basic Jade White", a printer's name
+    snippet: str | None = None  # Matching notes/description with the hits in [brackets]
+
+
+@router.get("", response_model=list[SearchResult])
+async def search(
+    q: str = Query(min_length=1, max_length=200),
+    kind: list[SearchKind] | None = Query(default=None),
+    limit: int = Query(default=20, ge=1, le=100),
+    locate: bool = Query(default=False, description="Light up the best spool match on the WLED shelf"),
+):
+    """Search spools (brand, material, color, notes, location), printers and projects.
+
+    Every word must match the start of a word in the record, so partial
+    input ("jade wh") already finds results. Best matches come first.
+
+    Args:
+        q: Search text
+        kind: Only return these kinds of record (repeatable)
+        limit: Maximum number of results
+        locate: Light up where the best spool match is, if the WLED integration is enabled
+    """
+    db = await get_db()
+    results = [
+        SearchResult(
+            kind=row["kind"],
+            id=str(row["ref_id"]),
+            title=" ".join(row["title"].split()),
+            snippet=" ".join((row["snippet"] or "").split()) or None,
+        )
+        for row in await db.search(q, kinds=kind, limit=limit)
+    ]
+    if locate:
+        await _locate_best_spool(db, results)
+    return results
+
+
+async def _locate_best_spool(db, results: list[SearchResult]):
+    """Light the best spool match; a failing WLED controller doesn't fail the search."""
+    spool_id = next((r.id for r in results if r.kind == "spool"), None)
+    config = await get_wled_config(db)
+    if not spool_id or not config.enabled:
+        return
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        return
+    try:
+        await locate_spool(db, spool, config)
+    except WledError as e:
+        logger.warning(f"Failed to locate spool {spool_id}: {e}")
@@ -1,6 +1,14 @@
+import os
+import zoneinfo
+from datetime import datetime
+from importlib import resources
+from pathlib import Path
+from typing import Literal
+
 from db import get_db
 from fastapi import APIRouter, HTTPException
-from pydantic import BaseModel
+from pydantic import BaseModel, Field
+from services.report_periods import WeekStart
 
 router = APIRouter(prefix="/settings", tags=["settings"])
 
@@ -24,6 +32,134 @@ class AMSThresholds(BaseModel):
 # Default AMS thresholds
 DEFAULT_AMS_THRESHOLDS = AMSThresholds()
 
+DEFAULT_NTP_SERVER = "pool.ntp.org"
+
+ELECTRICITY_PRICE_SETTING = "electricity_price"
+REPORT_TIMEZONE_SETTING = "report_timezone"
+REPORT_WEEK_START_SETTING = "report_week_start"
+
+
+class DeviceTimeSettingsUpdate(BaseModel):
+    """Clock settings for the display device."""
+
+    timezone: str  # IANA name, e.g. "Europe/Berlin"
+    ntp_server: str = DEFAULT_NTP_SERVER
+
+
+class DeviceTimeSettings(DeviceTimeSettingsUpdate):
+    """Clock settings as fetched by the device."""
+
+    posix_tz: str  # Same zone as a POSIX TZ string (with DST rules) for the device's libc
+
+
+class DeviceSoundSettings(BaseModel):
+    """Buzzer settings for the display device."""
+
+    muted: bool = False
+    buzzer_gpio: int | None = Field(default=None, ge=0, le=48)  # Piezo pin, None if no buzzer is fitted
+
+
+class DeviceThemeSettings(BaseModel):
+    """Display theme for the device UI."""
+
+    mode: Literal["dark", "light", "high_contrast"] = "dark"
+    accent_color: str | None = Field(default=None, pattern=r"^#[0-9A-Fa-f]{6}$")  # "#RRGGBB", None = theme default
+
+
+class EnergyPricing(BaseModel):
+    """Electricity price used to cost print energy."""
+
+    price_per_kwh: float | None = Field(default=None, ge=0)  # Same currency as spool prices, None = not costed
+
+
+class ReportCalendar(BaseModel):
+    """Calendar that month and week reports follow."""
+
+    timezone: str | None = None  # IANA name, None = the device's timezone
+    week_start: WeekStart = WeekStart.MONDAY
+
+
+async def get_ams_history_retention_days(db) -> int:
+    """Days of AMS sensor history to keep."""
+    value = await db.get_setting("ams_history_retention_days")
+    return int(value) if value else DEFAULT_AMS_THRESHOLDS.history_retention_days
+
+
+async def get_electricity_price(db) -> float | None:
+    """Configured electricity price per kWh, or None if unset."""
+    value = await db.get_setting(ELECTRICITY_PRICE_SETTING)
+    return float(value) if value else None
+
+
+async def get_report_calendar(db) -> ReportCalendar:
+    """Report calendar, with the timezone resolved (report setting, else the device's, else the server's)."""
+    week_start = await db.get_setting(REPORT_WEEK_START_SETTING)
+    calendar = ReportCalendar(week_start=week_start if week_start in list(WeekStart) else WeekStart.MONDAY)
+    for key in (REPORT_TIMEZONE_SETTING, "device_timezone"):
+        timezone = await db.get_setting(key)
+        if timezone and valid_timezone(timezone):
+            calendar.timezone = timezone
+            return calendar
+    calendar.timezone = server_timezone()
+    return calendar
+
+
+def server_timezone() -> str:
+    """IANA name of the server's local timezone (default for the device)."""
+    candidates = [os.environ.get("TZ", "").lstrip(":")]
+    localtime = Path("/etc/localtime")
+    if localtime.is_symlink():
+        target = str(localtime.resolve())
+        if "zoneinfo/" in target:
+            candidates.append(target.split("zoneinfo/", 1)[1])
+
+    for name in candidates:
+        if name and valid_timezone(name):
+            return name
+    return "UTC"
+
+
+def valid_timezone(name: str) -> bool:
+    try:
+        zoneinfo.ZoneInfo(name)
+        return True
+    except (zoneinfo.ZoneInfoNotFoundError, ValueError):
+        return False
+
+
+def _tzif_data(name: str) -> bytes | None:
+    """Raw compiled TZif file for a zone, from the system or the tzdata package."""
+    for base in zoneinfo.TZPATH:
+        path = Path(base, name)
+        if path.is_file():
+            return path.read_bytes()
+    try:
+        return resources.files("tzdata.zoneinfo").joinpath(name).read_bytes()
+    except (ModuleNotFoundError, FileNotFoundError):
+        return None
+
+
+def posix_tz(name: str) -> str:
+    """Convert an IANA timezone to a POSIX TZ string.
+
+    TZif files (version 2+) end with a newline-enclosed POSIX TZ footer that
+    describes the zone after its last transition, DST rules included. If that
+    isn't available, fall back to the zone's current fixed offset.
+    """
+    data = _tzif_data(name)
+    if data and data[:4] == b"TZif" and data[4:5] >= b"2":
+        footer = data.rstrip(b"\n").rsplit(b"\n", 1)[-1].decode("ascii", errors="ignore")
+        if footer:
+            return footer
+
+    offset = int(zoneinfo.ZoneInfo(name).utcoffset(datetime.now()).total_seconds())
+    if offset == 0:
+        return "UTC0"
+    # POSIX offsets are west-positive: UTC+1 is "UTC-1"
+    sign = "-" if offset >= 0 else "+"
+    hours, minutes = divmod(abs(offset) // 60, 60)
+    return f"UTC{sign}{hours}:{minutes:02d}" if minutes else f"UTC{sign}{hours}"
+
 
 @router.get("/{key}")
 async def get_setting(key: str) -> dict:
@@ -53,6 +189,119 @@ async def delete_setting(key: str):
     return {"status": "deleted"}
 
 
+@router.get("/device/time", response_model=DeviceTimeSettings)
+async def get_device_time_settings() -> DeviceTimeSettings:
+    """Get the device clock settings (timezone and NTP server)."""
+    db = await get_db()
+    timezone = await db.get_setting("device_timezone")
+    if not timezone or not valid_timezone(timezone):
+        timezone = server_timezone()
+    ntp_server = await db.get_setting("device_ntp_server") or DEFAULT_NTP_SERVER
+    return DeviceTimeSettings(timezone=timezone, ntp_server=ntp_server, posix_tz=posix_tz(timezone))
+
+
+@router.put("/device/time", response_model=DeviceTimeSettings)
+async def set_device_time_settings(settings: DeviceTimeSettingsUpdate) -> DeviceTimeSettings:
+    """Set the device clock settings. The device picks them up on its next sync."""
+    if not valid_timezone(settings.timezone):
+        raise HTTPException(status_code=400, detail=f"Unknown timezone: {settings.timezone}")
+    if not settings.ntp_server.strip():
+        raise HTTPException(status_code=400, detail="NTP server is required")
+
+    db = await get_db()
+    await db.set_setting("device_timezone", settings.timezone)
+    await db.set_setting("device_ntp_server", settings.ntp_server.strip())
+    return DeviceTimeSettings(
+        timezone=settings.timezone, ntp_server=settings.ntp_server.strip(), posix_tz=posix_tz(settings.timezone)
+    )
+
+
+@router.get("/device/sound", response_model=DeviceSoundSettings)
+async def get_device_sound_settings() -> DeviceSoundSettings:
+    """Get the device buzzer settings."""
+    db = await get_db()
+    muted = await db.get_setting("device_sound_muted")
+    buzzer_gpio = await db.get_setting("device_buzzer_gpio")
+    return DeviceSoundSettings(
+        muted=muted == "true",
+        buzzer_gpio=int(buzzer_gpio) if buzzer_gpio else None,
+    )
+
+
+@router.put("/device/sound", response_model=DeviceSoundSettings)
+async def set_device_sound_settings(settings: DeviceSoundSettings) -> DeviceSoundSettings:
+    """Set the device buzzer settings. Also called by the device when muted from its UI."""
+    db = await get_db()
+    await db.set_setting("device_sound_muted", "true" if settings.muted else "false")
+    if settings.buzzer_gpio is None:
+        await db.delete_setting("device_buzzer_gpio")
+    else:
+        await db.set_setting("device_buzzer_gpio", str(settings.buzzer_gpio))
+    return settings
+
+
+@router.get("/device/theme", response_model=DeviceThemeSettings)
+async def get_device_theme_settings() -> DeviceThemeSettings:
+    """Get the device display theme."""
+    db = await get_db()
+    mode = await db.get_setting("device_theme_mode")
+    accent_color = await db.get_setting("device_theme_accent")
+    return DeviceThemeSettings(mode=mode or "dark", accent_color=accent_color or None)
+
+
+@router.put("/device/theme", response_model=DeviceThemeSettings)
+async def set_device_theme_settings(settings: DeviceThemeSettings) -> DeviceThemeSettings:
+    """Set the device display theme. Also called by the device when the theme is toggled on its UI."""
+    db = await get_db()
+    await db.set_setting("device_theme_mode", settings.mode)
+    if settings.accent_color is None:
+        await db.delete_setting("device_theme_accent")
+    else:
+        settings.accent_color = settings.accent_color.upper()
+        await db.set_setting("device_theme_accent", settings.accent_color)
+    return settings
+
+
+@router.get("/energy/pricing", response_model=EnergyPricing)
+async def get_energy_pricing() -> EnergyPricing:
+    """Get the electricity price used for print energy costs."""
+    db = await get_db()
+    return EnergyPricing(price_per_kwh=await get_electricity_price(db))
+
+
+@router.put("/energy/pricing", response_model=EnergyPricing)
+async def set_energy_pricing(pricing: EnergyPricing) -> EnergyPricing:
+    """Set the electricity price used for print energy costs (null to stop costing energy)."""
+    db = await get_db()
+    if pricing.price_per_kwh is None:
+        await db.delete_setting(ELECTRICITY_PRICE_SETTING)
+    else:
+        await db.set_setting(ELECTRICITY_PRICE_SETTING, str(pricing.price_per_kwh))
+    return pricing
+
+
+@router.get("/reports/calendar", response_model=ReportCalendar)
+async def get_report_calendar_settings() -> ReportCalendar:
+    """Get the timezone and first day of the week that month and week reports follow."""
+    db = await get_db()
+    return await get_report_calendar(db)
+
+
+@router.put("/reports/calendar", response_model=ReportCalendar)
+async def set_report_calendar_settings(calendar: ReportCalendar) -> ReportCalendar:
+    """Set the report calendar (timezone null to follow the device's timezone)."""
+    if calendar.timezone is not None and not valid_timezone(calendar.timezone):
+        raise HTTPException(status_code=400, detail=f"Unknown timezone: {calendar.timezone}")
+
+    db = await get_db()
+    if calendar.timezone is None:
+        await db.delete_setting(REPORT_TIMEZONE_SETTING)
+    else:
+        await db.set_setting(REPORT_TIMEZONE_SETTING, calendar.timezone)
+    await db.set_setting(REPORT_WEEK_START_SETTING, calendar.week_start.value)
+    return await get_report_calendar(db)
+
+
 @router.get("/ams/thresholds", response_model=AMSThresholds)
 async def get_ams_thresholds() -> AMSThresholds:
     """Get AMS humidity/temperature thresholds."""
@@ -63,16 +312,13 @@ async def get_ams_thresholds() -> AMSThresholds:
     humidity_fair = await db.get_setting("ams_humidity_fair")
     temp_good = await db.get_setting("ams_temp_good")
     temp_fair = await db.get_setting("ams_temp_fair")
-    history_retention = await db.get_setting("ams_history_retention_days")
 
     return AMSThresholds(
         humidity_good=int(humidity_good) if humidity_good else DEFAULT_AMS_THRESHOLDS.humidity_good,
         humidity_fair=int(humidity_fair) if humidity_fair else DEFAULT_AMS_THRESHOLDS.humidity_fair,
         temp_good=float(temp_good) if temp_good else DEFAULT_AMS_THRESHOLDS.temp_good,
         temp_fair=float(temp_fair) if temp_fair else DEFAULT_AMS_THRESHOLDS.temp_fair,
-        history_retention_days=int(history_retention)
-        if history_retention
-        else DEFAULT_AMS_THRESHOLDS.history_retention_days,
+        history_retention_days=await get_ams_history_retention_days(db),
     )
 
 
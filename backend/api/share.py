@@ -0,0 +1,142 @@
+"""Share link endpoints.
+
+Share links are managed under /api/share-links. The links themselves are
+served at /share/{token}, outside /api, so a reverse proxy can expose
+them to the internet while keeping the rest of the server private.
+"""
+
+from enum import StrEnum
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from fastapi.responses import HTMLResponse, JSONResponse
+from pydantic import BaseModel, computed_field, field_validator
+from services.share import (
+    DEFAULT_SHARE_FIELDS,
+    ShareField,
+    generate_share_token,
+    is_share_link_active,
+    render_html,
+    shared_spools,
+)
+
+router = APIRouter(prefix="/share-links", tags=["share-links"])
+public_router = APIRouter(prefix="/share", tags=["share"])
+
+DEFAULT_SHARE_TITLE = "Filament inventory"
+
+# Shared pages must not be cached by proxies, indexed, or leak their token in the Referer header
+_SHARE_HEADERS = {"Cache-Control": "no-store", "Referrer-Policy": "no-referrer", "X-Robots-Tag": "noindex"}
+
+
+class ShareFormat(StrEnum):
+    """Output formats for a shared inventory."""
+
+    HTML = "html"
+    JSON = "json"
+
+
+class ShareLinkCreate(BaseModel):
+    """Request to create a share link."""
+
+    name: str | None = None  # Page title, e.g. "Filament for the makerspace"
+    fields: list[ShareField] = DEFAULT_SHARE_FIELDS
+    expires_at: int | None = None  # Epoch seconds, None = until revoked
+
+    @field_validator("fields")
+    @classmethod
+    def check_fields(cls, v):
+        if not v:
+            raise ValueError("must share at least one field")
+        return list(dict.fromkeys(v))
+
+
+class ShareLink(BaseModel):
+    """A read-only inventory link."""
+
+    id: int
+    token: str
+    name: str | None = None
+    fields: list[ShareField]
+    expires_at: int | None = None
+    revoked_at: int | None = None
+    access_count: int = 0
+    last_accessed_at: int | None = None
+    created_at: int | None = None
+
+    @computed_field
+    @property
+    def path(self) -> str:
+        """Path of the shared page on this server."""
+        return f"/share/{self.token}"
+
+    @computed_field
+    @property
+    def active(self) -> bool:
+        """Whether the link can still be viewed."""
+        return is_share_link_active({"revoked_at": self.revoked_at, "expires_at": self.expires_at})
+
+
+async def _get_share_link_or_404(db, link_id: int) -> dict:
+    link = await db.get_share_link(link_id)
+    if not link:
+        raise HTTPException(status_code=404, detail="Share link not found")
+    return link
+
+
+@router.get("", response_model=list[ShareLink])
+async def list_share_links():
+    """List share links, newest first, including revoked and expired ones."""
+    db = await get_db()
+    return await db.get_share_links()
+
+
+@router.post("", response_model=ShareLink, status_code=201)
+async def create_share_link(request: ShareLinkCreate):
+    """Create a share link showing the given spool fields."""
+    db = await get_db()
+    return await db.create_share_link(
+        generate_share_token(), [str(f) for f in request.fields], request.name, request.expires_at
+    )
+
+
+@router.get("/{link_id}", response_model=ShareLink)
+async def get_share_link(link_id: int):
+    """Get a share link."""
+    db = await get_db()
+    return await _get_share_link_or_404(db, link_id)
+
+
+@router.post("/{link_id}/revoke", response_model=ShareLink)
+async def revoke_share_link(link_id: int):
+    """Revoke a share link. It stays listed, but its page is gone."""
+    db = await get_db()
+    await _get_share_link_or_404(db, link_id)
+    return await db.revoke_share_link(link_id)
+
+
+@router.delete("/{link_id}", status_code=204)
+async def delete_share_link(link_id: int):
+    """Delete a share link."""
+    db = await get_db()
+    if not await db.delete_share_link(link_id):
+        raise HTTPException(status_code=404, detail="Share link not found")
+
+
+@public_router.get("/{token}")
+async def view_shared_inventory(token: str, format: ShareFormat = Query(default=ShareFormat.HTML)):
+    """The inventory behind a share link, as a page or JSON.
+
+    Unknown, revoked and expired links all answer 404.
+    """
+    db = await get_db()
+    link = await db.get_share_link_by_token(token)
+    if not link or not is_share_link_active(link):
+        raise HTTPException(status_code=404, detail="Share link not found", headers=_SHARE_HEADERS)
+    await db.record_share_link_access(link["id"])
+
+    items = shared_spools(await db.get_spools(), link["fields"])
+    title = link["name"] or DEFAULT_SHARE_TITLE
+    if format == ShareFormat.JSON:
+        return JSONResponse({"name": title, "fields": link["fields"], "spools": items}, headers=_SHARE_HEADERS)
+    return HTMLResponse(render_html(items, link["fields"], title), headers=_SHARE_HEADERS)
@@ -0,0 +1,163 @@
+"""Slicer companion API.
+
+Lets a slicer post-processing script (e.g. in OrcaSlicer) check a sliced
+project's filament requirements against the spool inventory before printing.
+"""
+
+from api.printers import get_printer_manager
+from db import get_db
+from fastapi import APIRouter
+from models import Spool
+from pydantic import BaseModel, Field
+from services.forecast import remaining_grams
+
+router = APIRouter(prefix="/slicer", tags=["slicer"])
+
+# Candidates returned per tool
+MAX_CANDIDATES = 3
+
+
+class FilamentRequirement(BaseModel):
+    """Filament needed by one tool (extruder/filament index) of a sliced project."""
+
+    tool: int
+    material: str
+    color: str | None = None  # Hex, e.g. "#FF8800"
+    grams: float = Field(default=0, ge=0)
+    setting_id: str | None = None  # Slicer filament preset, preferred when it matches
+
+
+class SpoolMatchRequest(BaseModel):
+    """Filament requirements of a sliced project."""
+
+    printer_serial: str | None = None  # Suggest AMS slots on this printer
+    filaments: list[FilamentRequirement]
+
+
+class SlotSuggestion(BaseModel):
+    """AMS slot to print a tool from."""
+
+    ams_id: int
+    tray_id: int
+    loaded: bool  # Spool is already assigned to this slot
+
+
+class SpoolCandidate(BaseModel):
+    """In-stock spool that can satisfy a requirement."""
+
+    spool: Spool
+    remaining_grams: float | None
+    enough: bool | None  # None when the remaining weight is unknown
+    preset_match: bool
+    color_distance: float | None  # RGB distance 0-441, None if either color is unknown
+    slot: SlotSuggestion | None = None
+
+
+class ToolMatch(BaseModel):
+    """Best matching spools for one tool, best first."""
+
+    tool: int
+    material: str
+    color: str | None
+    grams: float
+    candidates: list[SpoolCandidate]
+
+
+class SpoolMatchResponse(BaseModel):
+    """Spool matches for every tool of a project."""
+
+    tools: list[ToolMatch]
+    ready: bool  # Every tool has a spool with enough filament
+
+
+def _rgb(color: str | None) -> tuple[int, int, int] | None:
+    hex_color = (color or "").lstrip("#")[:6]
+    try:
+        return tuple(int(hex_color[i : i + 2], 16) for i in (0, 2, 4)) if len(hex_color) == 6 else None
+    except ValueError:
+        return None
+
+
+def color_distance(a: str | None, b: str | None) -> float | None:
+    """Euclidean RGB distance between two hex colors."""
+    rgb_a, rgb_b = _rgb(a), _rgb(b)
+    if rgb_a is None or rgb_b is None:
+        return None
+    return round(sum((x - y) ** 2 for x, y in zip(rgb_a, rgb_b, strict=True)) ** 0.5, 1)
+
+
+def _rank(candidate: SpoolCandidate) -> tuple:
+    return (
+        candidate.enough is not True,
+        not candidate.preset_match,
+        candidate.color_distance if candidate.color_distance is not None else 1000,
+        candidate.slot is None or not candidate.slot.loaded,
+        # Use up partial spools first
+        candidate.remaining_grams if candidate.remaining_grams is not None else float("inf"),
+    )
+
+
+@router.post("/match", response_model=SpoolMatchResponse)
+async def match_spools(request: SpoolMatchRequest):
+    """Find in-stock spools for a sliced project's filaments.
+
+    Spools must match the material; they are ranked by having enough
+    filament left, matching the requested slicer preset, color closeness and
+    already being loaded in the printer. With a printer_serial, each tool
+    also gets an AMS slot: the slot the spool is assigned to, or a free one.
+    """
+    db = await get_db()
+    spools = [s for s in await db.get_spools() if s.archived_at is None]
+
+    loaded: dict[str, tuple[int, int]] = {}
+    free_slots: list[tuple[int, int]] = []
+    if request.printer_serial:
+        for a in await db.get_slot_assignments(request.printer_serial):
+            if a["spool_id"]:
+                loaded[a["spool_id"]] = (a["ams_id"], a["tray_id"])
+        manager = get_printer_manager()
+        state = manager.get_state(request.printer_serial) if manager else None
+        if state:
+            assigned = set(loaded.values())
+            free_slots = [
+                (tray.ams_id, tray.tray_id)
+                for unit in state.ams_units
+                for tray in unit.trays
+                if not tray.tray_type and (tray.ams_id, tray.tray_id) not in assigned
+            ]
+
+    tools = []
+    for req in sorted(request.filaments, key=lambda f: f.tool):
+        candidates = []
+        for spool in spools:
+            if (spool.material or "").upper() != req.material.upper():
+                continue
+            remaining = remaining_grams(spool)
+            slot = None
+            if spool.id in loaded:
+                slot = SlotSuggestion(ams_id=loaded[spool.id][0], tray_id=loaded[spool.id][1], loaded=True)
+            candidates.append(
+                SpoolCandidate(
+                    spool=spool,
+                    remaining_grams=remaining,
+                    enough=None if remaining is None else remaining >= req.grams,
+                    preset_match=bool(req.setting_id)
+                    and req.setting_id.split("_")[0] in (spool.slicer_setting_id, spool.slicer_filament),
+                    color_distance=color_distance(req.color, spool.rgba),
+                    slot=slot,
+                )
+            )
+        candidates.sort(key=_rank)
+        candidates = candidates[:MAX_CANDIDATES]
+
+        # Best spool isn't loaded yet - suggest the next free slot for it
+        if candidates and candidates[0].slot is None and free_slots:
+            ams_id, tray_id = free_slots.pop(0)
+            candidates[0].slot = SlotSuggestion(ams_id=ams_id, tray_id=tray_id, loaded=False)
+
+        tools.append(
+            ToolMatch(tool=req.tool, material=req.material, color=req.color, grams=req.grams, candidates=candidates)
+        )
+
+    ready = all(t.candidates and t.candidates[0].enough for t in tools)
+    return SpoolMatchResponse(tools=tools, ready=ready)
@@ -1,7 +1,20 @@
-from db import get_db
-from fastapi import APIRouter, HTTPException, Query
-from models import Spool, SpoolCreate, SpoolUpdate
-from pydantic import BaseModel
+from enum import StrEnum
+
+from api.caching import etag_response
+from api.templates import SpoolFromTemplateRequest, spool_from_template
+from db import SpoolVersionConflict, TagInUse, get_db
+from fastapi import APIRouter, Header, HTTPException, Query, Request, Response
+from fastapi.responses import HTMLResponse, JSONResponse, PlainTextResponse
+from models import RunoutEvent, Spool, SpoolCreate, SpoolUpdate
+from pydantic import BaseModel, Field, field_validator
+from services.aging import SpoolAging, shelf_life_overrides, spool_aging, spools_aging
+from services.colors import color_distance, resolve_color
+from services.forecast import SpoolForecast, forecast_spool, forecast_spools
+from services.slicer import build_filament_preset, resolve_filament_id
+from services.spool_status import get_spool_status_tracker
+from services.spool_undo import ACTION_DEVICE_UPDATE, ACTION_LINK_TAG, ACTION_WEIGHT_SYNC, get_undo_log
+from services.swap_list import SwapListItem, render_html, render_markdown, swap_list_items
+from services.wled import LocateResult, WledError, get_wled_config, locate_spool
 
 
 class SetWeightRequest(BaseModel):
@@ -15,7 +28,41 @@ class LogUsageRequest(BaseModel):
 
     weight_used: float  # Grams consumed
     print_name: str | None = None
-    printer_serial: str | None = None
+    printer_serial: str | None = None  # e.g. a manual printer, for per-printer statistics
+    project_id: int | None = None  # Group the entry under a project
+
+
+class AdjustmentReason(StrEnum):
+    """Why a spool's consumption was corrected by hand."""
+
+    PURGE = "purge"  # Filament purged/wasted outside a tracked print
+    FAILED_PRINT = "failed_print"  # Failed print scraped off the bed
+    MEASUREMENT_ERROR = "measurement_error"  # Bookkeeping disagreed with reality
+    OTHER = "other"
+
+
+ADJUSTMENT_LABELS = {
+    AdjustmentReason.PURGE: "Purge",
+    AdjustmentReason.FAILED_PRINT: "Failed print",
+    AdjustmentReason.MEASUREMENT_ERROR: "Measurement correction",
+    AdjustmentReason.OTHER: "Adjustment",
+}
+
+
+class AdjustmentRequest(BaseModel):
+    """Manual correction of a spool's consumption."""
+
+    grams: float  # Positive = filament lost, negative = credited back to the spool
+    reason: AdjustmentReason
+    note: str | None = None
+    project_id: int | None = None
+
+    @field_validator("grams")
+    @classmethod
+    def check_grams(cls, v):
+        if v == 0:
+            raise ValueError("grams must not be zero")
+        return v
 
 
 class KProfileInput(BaseModel):
@@ -45,14 +92,157 @@ class LinkTagRequest(BaseModel):
     data_origin: str | None = None  # e.g., "nfc_link"
 
 
+class TagMoveResult(BaseModel):
+    """Result of moving a tag to another spool."""
+
+    spool: Spool  # Spool the tag is now linked to
+    previous_spool: Spool | None = None  # Spool the tag was taken from, None if it was unlinked
+
+
+class TagMove(BaseModel):
+    """A tag moved from one spool to another."""
+
+    tag_id: str
+    from_spool_id: str | None = None
+    to_spool_id: str
+    moved_at: int
+
+
+class MergeSpoolsRequest(BaseModel):
+    """Request to merge duplicate spools into one surviving record."""
+
+    target_id: str  # Spool that survives the merge
+    source_ids: list[str]  # Spools merged into the target and deleted
+
+
+class BulkUpdateRequest(BaseModel):
+    """Same partial update applied to many spools."""
+
+    ids: list[str] = Field(min_length=1, max_length=1000)
+    changes: SpoolUpdate = Field(default_factory=SpoolUpdate)  # Only the fields that are set are changed
+    archived: bool | None = None  # True = archive, False = restore
+    atomic: bool = False  # Roll back every change if any spool fails
+
+
+class BulkItemResult(BaseModel):
+    """Outcome for one spool of a bulk update."""
+
+    id: str
+    ok: bool
+    error: str | None = None
+    spool: Spool | None = None  # Updated record
+
+
+class BulkUpdateResult(BaseModel):
+    """Per-spool results of a bulk update."""
+
+    updated: int
+    failed: int
+    results: list[BulkItemResult]
+
+
+class UndoInfo(BaseModel):
+    """Device-triggered update that can still be undone."""
+
+    action: str  # weight_sync, link_tag or device_update
+    previous: dict  # Field values the undo restores
+    expires_at: float  # Unix timestamp
+
+
+class ColorMatch(BaseModel):
+    """A spool and how far its color is from the one searched for."""
+
+    spool: Spool
+    delta_e: float  # CIEDE2000, 0 = identical
+
+
+class DuplicateGroup(BaseModel):
+    """A group of spools that are likely duplicates of each other."""
+
+    reason: str  # "same_tag" or "same_attributes"
+    spools: list[Spool]
+
+
+class WeightResolution(StrEnum):
+    """Bucket size for downsampled weight history."""
+
+    RAW = "raw"
+    HOUR = "1h"
+    SIX_HOURS = "6h"
+    DAY = "1d"
+    WEEK = "1w"
+
+
+RESOLUTION_SECONDS = {
+    WeightResolution.RAW: 1,
+    WeightResolution.HOUR: 3600,
+    WeightResolution.SIX_HOURS: 6 * 3600,
+    WeightResolution.DAY: 86400,
+    WeightResolution.WEEK: 7 * 86400,
+}
+
+# A drop of at least this many grams between two readings is a print (or
+# other real consumption); smaller changes are scale drift
+SUDDEN_DROP_GRAMS = 5
+
+
+class WeightHistoryPoint(BaseModel):
+    """Spool weight within one time bucket."""
+
+    timestamp: int  # Bucket start (epoch seconds)
+    weight: int  # Last weight recorded in the bucket (grams, including core)
+    min_weight: int
+    max_weight: int
+    samples: int
+
+
+class WeightChange(BaseModel):
+    """A significant change between two consecutive weight readings."""
+
+    timestamp: int
+    delta: int  # Grams (negative = consumed)
+    kind: str  # "print" (sudden drop) or "increase" (spool re-weighed higher)
+
+
+class WeightHistory(BaseModel):
+    """Weight time series for a spool."""
+
+    spool_id: str
+    resolution: WeightResolution
+    points: list[WeightHistoryPoint]
+    changes: list[WeightChange]  # Sudden drops and increases, oldest first
+    print_consumption: int  # Grams lost in sudden drops
+    drift: int  # Net grams from small changes between readings
+
+
+class SwapListFormat(StrEnum):
+    """Output formats for the swap list."""
+
+    JSON = "json"
+    MARKDOWN = "markdown"
+    HTML = "html"
+
+
 router = APIRouter(prefix="/spools", tags=["spools"])
 
 
-@router.get("", response_model=list[Spool])
-async def list_spools():
-    """Get all spools."""
+async def _with_live_status(db, spools: list[Spool]) -> list[Spool]:
+    """Fill in where each spool is right now (on the scale, loaded in a printer, in storage)."""
+    tracker = get_spool_status_tracker()
+    statuses = await tracker.current(db)
+    for spool in spools:
+        spool.live_status = tracker.status_of(spool.id, statuses)
+    return spools
+
+
+@router.get("", response_model=list[Spool], responses={304: {"description": "Not modified"}})
+async def list_spools(request: Request):
+    """Get all spools.
+
+    Supports If-None-Match, answering 304 while the inventory is unchanged.
+    """
     db = await get_db()
-    return await db.get_spools()
+    return etag_response(request, await _with_live_status(db, await db.get_spools()), list[Spool])
 
 
 @router.get("/untagged", response_model=list[Spool])
@@ -66,36 +256,307 @@ async def list_untagged_spools():
     return await db.get_untagged_spools()
 
 
+@router.get("/by-tag/{uid}", response_model=Spool)
+async def get_spool_by_tag(uid: str):
+    """Get the active spool linked to an NFC tag.
+
+    Readers report UIDs differently, so the UID may be colon-separated or
+    plain hex, base64, or in reversed byte order (e.g. "04:A1:B2:C3",
+    "04a1b2c3" and "C3B2A104" all find the same spool).
+    """
+    db = await get_db()
+    spool = await db.get_spool_by_tag_uid(uid)
+    if not spool:
+        raise HTTPException(status_code=404, detail="No spool with this tag")
+    return (await _with_live_status(db, [spool]))[0]
+
+
+@router.get("/duplicates", response_model=list[DuplicateGroup])
+async def find_duplicate_spools(
+    window_minutes: int = Query(default=60, ge=0, le=10080, description="Max creation time gap for attribute matches"),
+):
+    """Find likely duplicate spools.
+
+    Matches spools whose tag IDs refer to the same physical tag, and spools
+    with identical brand/material/subtype/color created close together.
+    """
+    db = await get_db()
+    groups = await db.find_duplicate_spools(window_seconds=window_minutes * 60)
+
+    result = []
+    for group in groups:
+        spools = [await db.get_spool(spool_id) for spool_id in group["spool_ids"]]
+        result.append(DuplicateGroup(reason=group["reason"], spools=[s for s in spools if s]))
+    return result
+
+
+@router.get("/similar-color", response_model=list[ColorMatch])
+async def find_similar_color_spools(
+    color: str,
+    max_delta_e: float = Query(default=10, ge=0, le=100),
+    material: str | None = None,
+    include_archived: bool = False,
+    limit: int = Query(default=10, ge=1, le=100),
+):
+    """Find spools with a color close to the given one, closest first.
+
+    Args:
+        color: Hex color ("#AABBCC") or color name ("red", "Jade White")
+        max_delta_e: CIEDE2000 distance cutoff; ~2 is barely visible, ~10 the same color family
+        material: Only spools of this material (case-insensitive)
+        include_archived: Also match archived (used up) spools
+        limit: Maximum number of results
+    """
+    db = await get_db()
+    resolved = await resolve_color(db, color)
+    if not resolved:
+        raise HTTPException(status_code=400, detail=f"Unknown color '{color}'")
+
+    matches = []
+    for spool in await db.get_spools():
+        if spool.archived_at is not None and not include_archived:
+            continue
+        if material and (spool.material or "").lower() != material.lower():
+            continue
+        distance = color_distance(resolved.swatch.rgba, spool.rgba)
+        if distance is not None and distance <= max_delta_e:
+            matches.append(ColorMatch(spool=spool, delta_e=round(distance, 2)))
+    matches.sort(key=lambda m: m.delta_e)
+    return matches[:limit]
+
+
+@router.get("/forecast", response_model=list[SpoolForecast])
+async def list_spool_forecasts():
+    """Estimated days remaining for active spools in recent use.
+
+    Based on consumption over the last 30 days, soonest to run out first.
+    Spools without recent usage are omitted.
+    """
+    db = await get_db()
+    spools = [s for s in await db.get_spools() if s.archived_at is None]
+    forecasts = [f for f in await forecast_spools(db, spools) if f.days_remaining is not None]
+    return sorted(forecasts, key=lambda f: f.days_remaining)
+
+
+@router.get("/aging", response_model=list[SpoolAging])
+async def list_spool_aging(
+    aging_only: bool = Query(default=False, description="Only spools open past their shelf life"),
+    limit: int = Query(default=50, ge=1, le=500),
+):
+    """Active opened spools against their material's shelf life, longest open first.
+
+    Sealed spools are omitted.
+    """
+    db = await get_db()
+    spools = [s for s in await db.get_spools() if s.archived_at is None and s.opened_at is not None]
+    aging = [a for a in await spools_aging(db, spools) if a.aging or not aging_only]
+    return sorted(aging, key=lambda a: a.opened_at)[:limit]
+
+
+@router.get("/swap-list", response_model=list[SwapListItem])
+async def get_swap_list(
+    format: SwapListFormat = Query(default=SwapListFormat.JSON, description="Output format"),
+    title: str = Query(default="Filament swap list", max_length=100),
+    download: bool = Query(default=False, description="Serve as a file attachment"),
+):
+    """Shareable list of spools marked as available for swap/sale.
+
+    Lists remaining weights and photos, for posting in community swap threads.
+    Archived spools are left out.
+    """
+    db = await get_db()
+    items = swap_list_items(await db.get_spools())
+
+    headers = {}
+    if download:
+        extension = {SwapListFormat.JSON: "json", SwapListFormat.MARKDOWN: "md", SwapListFormat.HTML: "html"}[format]
+        headers["Content-Disposition"] = f'attachment; filename="swap-list.{extension}"'
+
+    if format == SwapListFormat.MARKDOWN:
+        return PlainTextResponse(render_markdown(items, title), media_type="text/markdown", headers=headers)
+    if format == SwapListFormat.HTML:
+        return HTMLResponse(render_html(items, title), headers=headers)
+    return JSONResponse([item.model_dump() for item in items], headers=headers)
+
+
+@router.post("/merge", response_model=Spool)
+async def merge_spools(request: MergeSpoolsRequest):
+    """Merge duplicate spools into a surviving spool.
+
+    Usage history, K-profiles and AMS slot assignments are moved to the target
+    spool and the source spools are deleted, all within one transaction.
+    """
+    if request.target_id in request.source_ids:
+        raise HTTPException(status_code=400, detail="Target spool cannot also be a source")
+    if not request.source_ids:
+        raise HTTPException(status_code=400, detail="No source spools given")
+
+    db = await get_db()
+    for spool_id in request.source_ids:
+        if not await db.get_spool(spool_id):
+            raise HTTPException(status_code=404, detail=f"Spool not found: {spool_id}")
+
+    merged = await db.merge_spools(request.target_id, request.source_ids)
+    if not merged:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    return merged
+
+
+@router.patch("/bulk", response_model=BulkUpdateResult)
+async def bulk_update_spools(request: BulkUpdateRequest):
+    """Update many spools at once, e.g. move them to a location, archive them or change the brand.
+
+    All changes run in a single transaction. Spools that fail (not found,
+    duplicate tag) are reported per item; with `atomic` nothing is applied
+    if any spool fails.
+    """
+    if "expected_version" in request.changes.model_fields_set:
+        raise HTTPException(status_code=400, detail="expected_version is not supported for bulk updates")
+    if not request.changes.model_fields_set and request.archived is None:
+        raise HTTPException(status_code=400, detail="No changes given")
+
+    db = await get_db()
+    errors = await db.bulk_update_spools(
+        request.ids, request.changes, archived=request.archived, atomic=request.atomic
+    )
+    applied = not (request.atomic and any(errors.values()))
+
+    results = []
+    for spool_id, error in errors.items():
+        if error is None and not applied:
+            error = "Rolled back"
+        spool = await db.get_spool(spool_id) if error is None else None
+        results.append(BulkItemResult(id=spool_id, ok=error is None, error=error, spool=spool))
+
+    updated = sum(1 for r in results if r.ok)
+    return BulkUpdateResult(updated=updated, failed=len(results) - updated, results=results)
+
+
 @router.get("/{spool_id}", response_model=Spool)
-async def get_spool(spool_id: str):
-    """Get a single spool."""
+async def get_spool(spool_id: str, response: Response):
+    """Get a single spool.
+
+    The ETag header carries the spool version for use with If-Match on update.
+    """
     db = await get_db()
     spool = await db.get_spool(spool_id)
     if not spool:
         raise HTTPException(status_code=404, detail="Spool not found")
-    return spool
+    response.headers["ETag"] = f'"{spool.version}"'
+    return (await _with_live_status(db, [spool]))[0]
+
+
+def _tag_conflict(e: TagInUse) -> JSONResponse:
+    """409 naming the spool that has the tag, so the client can offer to move it."""
+    holder = e.holder
+    return JSONResponse(
+        status_code=409,
+        content={
+            "detail": f"Tag already assigned to spool: {holder.brand or 'Unknown'} {holder.material}",
+            "tag_id": e.tag_id,
+            "assigned_to": holder.model_dump(),
+        },
+    )
 
 
-@router.post("", response_model=Spool, status_code=201)
+@router.post("", response_model=Spool, status_code=201, responses={409: {"description": "Tag is on another spool"}})
 async def create_spool(spool: SpoolCreate):
-    """Create a new spool."""
+    """Create a new spool.
+
+    A tag on an archived or trashed spool is taken over. A tag on another
+    active spool is refused with a 409 naming that spool; move it with
+    POST /{spool_id}/move-tag.
+    """
+    db = await get_db()
+    try:
+        return await db.create_spool(spool)
+    except TagInUse as e:
+        return _tag_conflict(e)
+
+
+@router.post(
+    "/from-template/{template_id}",
+    response_model=Spool,
+    status_code=201,
+    responses={409: {"description": "Tag is on another spool"}},
+)
+async def create_spool_from_template(template_id: int, request: SpoolFromTemplateRequest | None = None):
+    """Add a spool from a spool template.
+
+    The body is optional and carries what differs per spool (tag, weight,
+    location, note, price).
+    """
     db = await get_db()
-    return await db.create_spool(spool)
+    template = await db.get_spool_template(template_id)
+    if not template:
+        raise HTTPException(status_code=404, detail="Spool template not found")
+    try:
+        spool = await db.create_spool(spool_from_template(template, request or SpoolFromTemplateRequest()))
+    except TagInUse as e:
+        return _tag_conflict(e)
+    await db.mark_spool_template_used(template_id)
+    return spool
+
 
+def _parse_if_match(value: str | None) -> int | None:
+    """Parse a spool version from an If-Match header (e.g. '"3"' or 'W/"3"')."""
+    if not value or value.strip() == "*":
+        return None
+    value = value.strip().removeprefix("W/").strip('"')
+    try:
+        return int(value)
+    except ValueError:
+        raise HTTPException(status_code=400, detail="Invalid If-Match header") from None
+
+
+@router.put("/{spool_id}", response_model=Spool, responses={409: {"description": "Spool was modified concurrently"}})
+async def update_spool(
+    spool_id: str,
+    spool: SpoolUpdate,
+    response: Response,
+    if_match: str | None = Header(None, alias="If-Match"),
+    source: str | None = Header(None, alias="X-SpoolBuddy-Source"),
+):
+    """Update an existing spool.
+
+    Pass the spool's current version as `expected_version` (or as an
+    `If-Match` header) to avoid overwriting a concurrent edit. On conflict
+    a 409 is returned with the current record.
+
+    Updates sent by the device (`X-SpoolBuddy-Source: device`) can be
+    reverted with POST /{spool_id}/undo-last for a few minutes.
+    """
+    expected_version = spool.expected_version
+    if expected_version is None:
+        expected_version = _parse_if_match(if_match)
 
-@router.put("/{spool_id}", response_model=Spool)
-async def update_spool(spool_id: str, spool: SpoolUpdate):
-    """Update an existing spool."""
     db = await get_db()
-    updated = await db.update_spool(spool_id, spool)
+    before = await db.get_spool(spool_id) if source == "device" else None
+    try:
+        updated = await db.update_spool(spool_id, spool, expected_version=expected_version)
+    except SpoolVersionConflict as e:
+        return JSONResponse(
+            status_code=409,
+            content={"detail": "Spool was modified by another client", "current": e.current.model_dump()},
+            headers={"ETag": f'"{e.current.version}"'},
+        )
+    except TagInUse as e:
+        return _tag_conflict(e)
     if not updated:
         raise HTTPException(status_code=404, detail="Spool not found")
+    if before:
+        get_undo_log().record(ACTION_DEVICE_UPDATE, before, updated)
+    response.headers["ETag"] = f'"{updated.version}"'
     return updated
 
 
 @router.delete("/{spool_id}", status_code=204)
 async def delete_spool(spool_id: str):
-    """Delete a spool."""
+    """Move a spool to the trash.
+
+    Trashed spools keep their usage history and can be restored via the
+    trash API until they are purged.
+    """
     db = await get_db()
     if not await db.delete_spool(spool_id):
         raise HTTPException(status_code=404, detail="Spool not found")
@@ -117,7 +578,7 @@ async def link_tag_to_spool(spool_id: str, request: LinkTagRequest):
 
     Raises:
         404: Spool not found
-        409: Tag is already assigned to another active spool
+        409: Tag is already assigned to another active spool (the response names it)
     """
     db = await get_db()
 
@@ -126,21 +587,42 @@ async def link_tag_to_spool(spool_id: str, request: LinkTagRequest):
     if not spool:
         raise HTTPException(status_code=404, detail="Spool not found")
 
-    # Check if tag is already assigned to another ACTIVE spool
-    existing_spool = await db.get_spool_by_tag(request.tag_id, include_archived=False)
-    if existing_spool and existing_spool.id != spool_id:
-        detail = f"Tag already assigned to spool: {existing_spool.brand or 'Unknown'} {existing_spool.material}"
-        raise HTTPException(status_code=409, detail=detail)
+    try:
+        updated = await db.link_tag_to_spool(
+            spool_id, request.tag_id, request.tag_type, request.data_origin or "nfc_link"
+        )
+    except TagInUse as e:
+        return _tag_conflict(e)
+    if updated:
+        get_undo_log().record(ACTION_LINK_TAG, spool, updated)
 
-    # Check if tag is on an archived spool - if so, clear it (tag recycling)
-    archived_spool = await db.get_spool_by_tag(request.tag_id, include_archived=True)
-    if archived_spool and archived_spool.id != spool_id and archived_spool.archived_at:
-        await db.clear_spool_tag(archived_spool.id)
+    return updated
 
-    # Link the tag
-    updated = await db.link_tag_to_spool(spool_id, request.tag_id, request.tag_type, request.data_origin or "nfc_link")
 
-    return updated
+@router.post("/{spool_id}/move-tag", response_model=TagMoveResult)
+async def move_tag_to_spool(spool_id: str, request: LinkTagRequest):
+    """Move an NFC tag to this spool, even if another active spool has it.
+
+    Use this after a link was refused with a 409, e.g. when a tag is reused
+    on a new spool. The spool the tag is taken from keeps its usage and
+    weight history; the move is listed under GET /{spool_id}/tag-moves of
+    both spools.
+    """
+    db = await get_db()
+    result = await db.move_tag(spool_id, request.tag_id, request.tag_type, request.data_origin or "nfc_link")
+    if result is None:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    previous, updated = result
+    return TagMoveResult(spool=updated, previous_spool=previous)
+
+
+@router.get("/{spool_id}/tag-moves", response_model=list[TagMove])
+async def get_spool_tag_moves(spool_id: str):
+    """Tags moved onto or off a spool, newest first."""
+    db = await get_db()
+    if not await db.get_spool(spool_id, include_deleted=True):
+        raise HTTPException(status_code=404, detail="Spool not found")
+    return await db.get_tag_moves(spool_id)
 
 
 @router.post("/{spool_id}/weight", response_model=Spool)
@@ -162,9 +644,48 @@ async def set_spool_weight(spool_id: str, request: SetWeightRequest):
 
     # Store gross weight directly (weight_current is the total scale reading)
     updated = await db.set_spool_weight(spool_id, request.weight)
+    if updated:
+        get_undo_log().record(ACTION_WEIGHT_SYNC, spool, updated)
     return updated
 
 
+@router.get("/{spool_id}/undo-last", response_model=UndoInfo)
+async def get_undo_last(spool_id: str):
+    """The spool's last device-triggered update, if it can still be undone."""
+    entry = get_undo_log().get(spool_id)
+    if not entry:
+        raise HTTPException(status_code=404, detail="Nothing to undo")
+    return UndoInfo(action=entry.action, previous=entry.previous, expires_at=entry.expires_at)
+
+
+@router.post("/{spool_id}/undo-last", response_model=Spool, responses={409: {"description": "Spool changed since"}})
+async def undo_last(spool_id: str):
+    """Revert the spool's last scale weight sync, tag link or device update.
+
+    Only possible within the undo window (SPOOLBUDDY_UNDO_WINDOW_MINUTES)
+    and while nothing else has changed the spool since; otherwise 404 or 409.
+    A tag taken off a recycled archived spool by the link is not put back.
+    """
+    undo_log = get_undo_log()
+    entry = undo_log.get(spool_id)
+    if not entry:
+        raise HTTPException(status_code=404, detail="Nothing to undo")
+
+    db = await get_db()
+    try:
+        restored = await db.restore_spool_fields(spool_id, entry.previous, entry.version)
+    except SpoolVersionConflict as e:
+        undo_log.discard(spool_id)
+        return JSONResponse(
+            status_code=409,
+            content={"detail": "Spool was changed since, undo not possible", "current": e.current.model_dump()},
+        )
+    undo_log.discard(spool_id)
+    if not restored:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    return restored
+
+
 @router.post("/{spool_id}/usage", response_model=Spool)
 async def log_manual_usage(spool_id: str, request: LogUsageRequest):
     """Manually log filament usage for a spool.
@@ -181,6 +702,8 @@ async def log_manual_usage(spool_id: str, request: LogUsageRequest):
     spool = await db.get_spool(spool_id)
     if not spool:
         raise HTTPException(status_code=404, detail="Spool not found")
+    if request.project_id is not None and not await db.get_project(request.project_id):
+        raise HTTPException(status_code=404, detail="Project not found")
 
     # Log to usage history
     await db.log_usage(
@@ -188,6 +711,7 @@ async def log_manual_usage(spool_id: str, request: LogUsageRequest):
         printer_serial=request.printer_serial or "manual",
         print_name=request.print_name or "Manual entry",
         weight_used=request.weight_used,
+        project_id=request.project_id,
     )
 
     # Update spool consumption
@@ -195,6 +719,35 @@ async def log_manual_usage(spool_id: str, request: LogUsageRequest):
     return updated
 
 
+@router.post("/{spool_id}/adjustments", response_model=Spool)
+async def add_spool_adjustment(spool_id: str, request: AdjustmentRequest):
+    """Correct a spool's consumption by hand.
+
+    Adjustments are logged to usage history with their reason, so reports,
+    forecasts and the weight history include them like any other usage.
+    Negative grams credit filament back (e.g. a measurement error).
+    """
+    db = await get_db()
+
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    if request.project_id is not None and not await db.get_project(request.project_id):
+        raise HTTPException(status_code=404, detail="Project not found")
+
+    label = ADJUSTMENT_LABELS[request.reason]
+    await db.log_usage(
+        spool_id=spool_id,
+        printer_serial="manual",
+        print_name=f"{label}: {request.note}" if request.note else label,
+        weight_used=request.grams,
+        project_id=request.project_id,
+        reason=request.reason.value,
+    )
+
+    return await db.update_spool_consumption(spool_id, request.grams, source="adjustment")
+
+
 @router.get("/{spool_id}/history")
 async def get_spool_usage_history(spool_id: str, limit: int = Query(default=50, le=500)):
     """Get usage history for a specific spool.
@@ -210,6 +763,82 @@ async def get_spool_usage_history(spool_id: str, limit: int = Query(default=50,
     return await db.get_usage_history(spool_id=spool_id, limit=limit)
 
 
+@router.get("/{spool_id}/weight-history", response_model=WeightHistory)
+async def get_spool_weight_history(
+    spool_id: str,
+    resolution: WeightResolution = WeightResolution.DAY,
+    since: int | None = Query(default=None, description="Epoch seconds; defaults to the spool's whole life"),
+):
+    """Get a spool's weight over time for charting.
+
+    Readings are downsampled to one point per bucket. Changes between
+    consecutive readings are split into sudden drops (prints) and small
+    changes (scale drift).
+    """
+    db = await get_db()
+
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+
+    points = await db.get_weight_history(spool_id, RESOLUTION_SECONDS[resolution], since)
+
+    changes = []
+    print_consumption = 0
+    drift = 0
+    for row in await db.get_weight_changes(spool_id, since):
+        delta = row["delta"]
+        if delta <= -SUDDEN_DROP_GRAMS or (delta < 0 and row["source"] == "usage"):
+            changes.append(WeightChange(timestamp=row["recorded_at"], delta=delta, kind="print"))
+            print_consumption -= delta
+        elif delta >= SUDDEN_DROP_GRAMS:
+            changes.append(WeightChange(timestamp=row["recorded_at"], delta=delta, kind="increase"))
+        else:
+            drift += delta
+
+    return WeightHistory(
+        spool_id=spool_id,
+        resolution=resolution,
+        points=[WeightHistoryPoint(**p) for p in points],
+        changes=changes,
+        print_consumption=print_consumption,
+        drift=drift,
+    )
+
+
+@router.get("/{spool_id}/runout-events", response_model=list[RunoutEvent])
+async def get_spool_runout_events(spool_id: str, limit: int = Query(default=50, ge=1, le=500)):
+    """Get runout/jam events recorded for a spool, newest first."""
+    db = await get_db()
+    if not await db.get_spool(spool_id, include_deleted=True):
+        raise HTTPException(status_code=404, detail="Spool not found")
+    return await db.get_runout_events(spool_id=spool_id, limit=limit)
+
+
+@router.get("/{spool_id}/forecast", response_model=SpoolForecast)
+async def get_spool_forecast(spool_id: str):
+    """Estimate when a spool will run out at its recent consumption rate."""
+    db = await get_db()
+
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+
+    return await forecast_spool(db, spool)
+
+
+@router.get("/{spool_id}/aging", response_model=SpoolAging)
+async def get_spool_aging(spool_id: str):
+    """How long a spool has been open against its material's shelf life."""
+    db = await get_db()
+
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+
+    return spool_aging(spool, await shelf_life_overrides(db))
+
+
 @router.get("/usage/history")
 async def get_all_usage_history(limit: int = Query(default=100, le=500)):
     """Get global usage history across all spools.
@@ -247,6 +876,59 @@ async def save_spool_k_profiles(spool_id: str, request: SaveKProfilesRequest):
     return {"status": "ok", "count": len(profiles)}
 
 
+@router.get("/{spool_id}/slicer-preset")
+async def export_slicer_preset(
+    spool_id: str,
+    printer_serial: str | None = Query(default=None, description="Use the K-profile calibrated on this printer"),
+    nozzle_diameter: str | None = Query(default=None, description="Use the K-profile for this nozzle (e.g. 0.4)"),
+):
+    """Export a spool as a Bambu Studio / OrcaSlicer filament preset.
+
+    The preset inherits the spool's linked system preset and carries its
+    color, temperatures and calibrated pressure advance (K) value. Import it
+    in the slicer via File > Import > Import Configs.
+    """
+    db = await get_db()
+
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+
+    k_value = None
+    for kp in await db.get_spool_k_profiles(spool_id):
+        if printer_serial and kp.get("printer_serial") != printer_serial:
+            continue
+        if nozzle_diameter and kp.get("nozzle_diameter") != nozzle_diameter:
+            continue
+        k_value = kp.get("k_value")
+        break
+
+    filament_id = await resolve_filament_id(db, spool)
+    preset = build_filament_preset(spool, filament_id, k_value)
+    filename = f"spoolbuddy-{spool.spool_number or spool.id}.json"
+    return JSONResponse(preset, headers={"Content-Disposition": f'attachment; filename="{filename}"'})
+
+
+@router.post("/{spool_id}/locate", response_model=LocateResult)
+async def locate(spool_id: str):
+    """Light up where a spool is on the WLED shelf indicator.
+
+    Lights its AMS slot while it is loaded, its storage location otherwise.
+    An unmapped position lights nothing and returns no segments.
+    """
+    db = await get_db()
+    spool = await db.get_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    config = await get_wled_config(db)
+    if not config.enabled:
+        raise HTTPException(status_code=409, detail="WLED integration is not enabled")
+    try:
+        return await locate_spool(db, spool, config)
+    except WledError as e:
+        raise HTTPException(status_code=502, detail=str(e)) from e
+
+
 @router.post("/{spool_id}/archive", response_model=Spool)
 async def archive_spool(spool_id: str):
     """Archive a spool.
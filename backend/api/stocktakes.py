@@ -0,0 +1,171 @@
+"""Stocktake endpoints.
+
+Start a stocktake, scan each spool on the shelf (the device records spools
+put on its scale by itself while a stocktake is open), then check the
+report for spools that weren't found and weights that don't match, and
+complete it to keep the report as the record of the count.
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel, Field, model_validator
+from services.stocktake import (
+    DISCREPANCY_TOLERANCE_GRAMS,
+    ScanSource,
+    Stocktake,
+    StocktakeReport,
+    StocktakeScan,
+    StocktakeStatus,
+    build_report,
+    record_scan,
+)
+
+router = APIRouter(prefix="/stocktakes", tags=["stocktakes"])
+
+
+class StocktakeStart(BaseModel):
+    """Request to start a stocktake."""
+
+    name: str | None = None  # e.g. "Year end 2026"
+
+
+class ScanRequest(BaseModel):
+    """A spool counted in a stocktake, by spool ID or tag."""
+
+    spool_id: str | None = None
+    tag_id: str | None = None
+    weight: float | None = Field(default=None, ge=0)  # Gross scale reading, if weighed
+    source: ScanSource = ScanSource.WEB
+
+    @model_validator(mode="after")
+    def check_spool(self):
+        if (self.spool_id is None) == (self.tag_id is None):
+            raise ValueError("Give either spool_id or tag_id")
+        return self
+
+
+class CompleteRequest(BaseModel):
+    """Request to complete a stocktake."""
+
+    tolerance: float = Field(default=DISCREPANCY_TOLERANCE_GRAMS, ge=0)
+    apply_weights: bool = False  # Sync every weighed spool to its scale reading
+
+
+class StocktakeDetail(Stocktake):
+    """A stocktake with its report (stored once completed)."""
+
+    report: StocktakeReport | None = None
+
+
+async def _get_stocktake_or_404(db, stocktake_id: int) -> dict:
+    stocktake = await db.get_stocktake(stocktake_id)
+    if not stocktake:
+        raise HTTPException(status_code=404, detail="Stocktake not found")
+    return stocktake
+
+
+async def _get_open_stocktake_or_409(db, stocktake_id: int) -> dict:
+    stocktake = await _get_stocktake_or_404(db, stocktake_id)
+    if stocktake["status"] != StocktakeStatus.OPEN:
+        raise HTTPException(status_code=409, detail=f"Stocktake is {stocktake['status']}")
+    return stocktake
+
+
+async def _report(db, stocktake_id: int, tolerance: float) -> StocktakeReport:
+    return build_report(stocktake_id, await db.get_spools(), await db.get_stocktake_scans(stocktake_id), tolerance)
+
+
+@router.get("", response_model=list[Stocktake])
+async def list_stocktakes(limit: int = Query(default=50, ge=1, le=500)):
+    """List stocktakes, newest first."""
+    db = await get_db()
+    return await db.get_stocktakes(limit=limit)
+
+
+@router.post("", response_model=Stocktake, status_code=201)
+async def start_stocktake(request: StocktakeStart):
+    """Start a stocktake. Only one can be open at a time."""
+    db = await get_db()
+    if open_stocktake := await db.get_open_stocktake():
+        raise HTTPException(status_code=409, detail=f"Stocktake {open_stocktake['id']} is still open")
+    return await db.create_stocktake(request.name)
+
+
+@router.get("/active", response_model=Stocktake | None)
+async def get_active_stocktake():
+    """Get the open stocktake, null if none."""
+    db = await get_db()
+    return await db.get_open_stocktake()
+
+
+@router.get("/{stocktake_id}", response_model=StocktakeDetail)
+async def get_stocktake(stocktake_id: int):
+    """Get a stocktake, with its stored report if completed."""
+    db = await get_db()
+    return await _get_stocktake_or_404(db, stocktake_id)
+
+
+@router.get("/{stocktake_id}/scans", response_model=list[StocktakeScan])
+async def list_stocktake_scans(stocktake_id: int):
+    """List the spools counted in a stocktake, most recent first."""
+    db = await get_db()
+    await _get_stocktake_or_404(db, stocktake_id)
+    return await db.get_stocktake_scans(stocktake_id)
+
+
+@router.post("/{stocktake_id}/scans", response_model=StocktakeScan, status_code=201)
+async def scan_spool(stocktake_id: int, request: ScanRequest):
+    """Count a spool. Scanning a spool again replaces its earlier scan."""
+    db = await get_db()
+    await _get_open_stocktake_or_409(db, stocktake_id)
+    if request.spool_id:
+        spool = await db.get_spool(request.spool_id)
+    else:
+        spool = await db.get_spool_by_tag(request.tag_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found")
+    return await record_scan(db, stocktake_id, spool, request.weight, request.source)
+
+
+@router.delete("/{stocktake_id}/scans/{spool_id}", status_code=204)
+async def delete_stocktake_scan(stocktake_id: int, spool_id: str):
+    """Uncount a spool scanned by mistake."""
+    db = await get_db()
+    await _get_open_stocktake_or_409(db, stocktake_id)
+    if not await db.delete_stocktake_scan(stocktake_id, spool_id):
+        raise HTTPException(status_code=404, detail="Spool not counted in this stocktake")
+
+
+@router.get("/{stocktake_id}/report", response_model=StocktakeReport)
+async def get_stocktake_report(stocktake_id: int, tolerance: float = Query(default=DISCREPANCY_TOLERANCE_GRAMS, ge=0)):
+    """Unseen spools and weight discrepancies so far. A completed stocktake returns its stored report."""
+    db = await get_db()
+    stocktake = await _get_stocktake_or_404(db, stocktake_id)
+    if stocktake["report"]:
+        return stocktake["report"]
+    return await _report(db, stocktake_id, tolerance)
+
+
+@router.post("/{stocktake_id}/complete", response_model=StocktakeDetail)
+async def complete_stocktake(stocktake_id: int, request: CompleteRequest):
+    """Complete a stocktake and store its report.
+
+    With apply_weights, the weight of every weighed spool is synced to its
+    scale reading, as if weighed on its own.
+    """
+    db = await get_db()
+    await _get_open_stocktake_or_409(db, stocktake_id)
+    report = await _report(db, stocktake_id, request.tolerance)
+    if request.apply_weights:
+        for scan in await db.get_stocktake_scans(stocktake_id):
+            if scan["weight"] is not None:
+                await db.set_spool_weight(scan["spool_id"], round(scan["weight"]))
+    return await db.finish_stocktake(stocktake_id, StocktakeStatus.COMPLETED, report.model_dump(mode="json"))
+
+
+@router.post("/{stocktake_id}/cancel", response_model=StocktakeDetail)
+async def cancel_stocktake(stocktake_id: int):
+    """Abandon an open stocktake. Its scans are kept, without a report."""
+    db = await get_db()
+    await _get_open_stocktake_or_409(db, stocktake_id)
+    return await db.finish_stocktake(stocktake_id, StocktakeStatus.CANCELLED)
@@ -6,6 +6,8 @@ Provides endpoints for:
 - Log retrieval and clearing
 - Support bundle generation
 - System information
+- Database maintenance (retention, VACUUM)
+- Database backups (as background jobs)
 """
 
 import io
@@ -18,11 +20,14 @@ from datetime import datetime
 from pathlib import Path
 
 import psutil
+from api.settings import get_ams_history_retention_days
 from config import APP_VERSION, settings
 from db import get_db
 from fastapi import APIRouter, HTTPException, Query
-from fastapi.responses import StreamingResponse
-from pydantic import BaseModel
+from fastapi.responses import FileResponse, StreamingResponse
+from pydantic import BaseModel, Field
+from services.jobs import JobContext, job_kind
+from services.maintenance import MaintenanceResult, RetentionPolicy, last_result, retention_policy, run_maintenance
 
 logger = logging.getLogger(__name__)
 router = APIRouter(prefix="/support", tags=["support"])
@@ -33,6 +38,9 @@ _start_time = time.time()
 # Log file path
 LOG_FILE = Path("spoolbuddy.log")
 
+# Backup files written by the backup job
+_BACKUP_NAME = re.compile(r"spoolbuddy-\d{8}-\d{6}\.db")
+
 
 # ============ Models ============
 
@@ -106,6 +114,15 @@ class SystemInfo(BaseModel):
     cpu_percent: float
 
 
+class MaintenanceStatus(BaseModel):
+    """Retention policy and the last maintenance run."""
+
+    policy: RetentionPolicy
+    interval_hours: float
+    vacuum: bool
+    last_run: MaintenanceResult | None = None
+
+
 # ============ Helper Functions ============
 
 
@@ -535,3 +552,54 @@ async def get_system_info():
         cpu_count=psutil.cpu_count() or 1,
         cpu_percent=psutil.cpu_percent(interval=0.1),
     )
+
+
+@router.get("/maintenance", response_model=MaintenanceStatus)
+async def get_maintenance_status():
+    """Get the data retention policy and the result of the last maintenance run."""
+    db = await get_db()
+    return MaintenanceStatus(
+        policy=retention_policy(await get_ams_history_retention_days(db)),
+        interval_hours=settings.maintenance_interval_hours,
+        vacuum=settings.maintenance_vacuum,
+        last_run=last_result(),
+    )
+
+
+@router.post("/maintenance", response_model=MaintenanceResult)
+async def run_maintenance_now(vacuum: bool | None = Query(default=None, description="Override the VACUUM setting")):
+    """Apply the retention policy and optimize the database now."""
+    db = await get_db()
+    return await run_maintenance(db, retention_policy(await get_ams_history_retention_days(db)), vacuum=vacuum)
+
+
+class BackupParams(BaseModel):
+    """Parameters of a database backup job."""
+
+    keep: int = Field(default=10, ge=1, le=100)  # Older backups are deleted
+
+
+@job_kind("backup", BackupParams)
+async def backup_job(ctx: JobContext, params: BackupParams) -> dict:
+    """Background job writing a copy of the database to the backup directory."""
+    backup_dir = settings.backup_dir
+    backup_dir.mkdir(parents=True, exist_ok=True)
+    path = backup_dir / f"spoolbuddy-{datetime.now():%Y%m%d-%H%M%S}.db"
+    await ctx.update(progress=0, total=1, message="Writing backup", force=True)
+
+    db = await get_db()
+    await db.backup_to(path)
+    backups = sorted(p for p in backup_dir.iterdir() if _BACKUP_NAME.fullmatch(p.name))
+    for old in backups[: -params.keep]:
+        old.unlink()
+    logger.info(f"Database backed up to {path}")
+    return {"file": path.name, "size": path.stat().st_size}
+
+
+@router.get("/backups/{name}")
+async def download_backup(name: str):
+    """Download a database backup written by a backup job."""
+    path = settings.backup_dir / name
+    if not _BACKUP_NAME.fullmatch(name) or not path.is_file():
+        raise HTTPException(status_code=404, detail="Backup not found")
+    return FileResponse(path, media_type="application/vnd.sqlite3", filename=name)
@@ -1,7 +1,8 @@
 """Tag encoding/decoding API endpoints.
 
 Provides endpoints for encoding spool data into various NFC tag formats
-and decoding tag data for testing without hardware.
+and decoding tag data for testing without hardware, and the tag_batch job
+that writes tags for a batch of spools on the device.
 """
 
 import base64
@@ -9,15 +10,23 @@ import logging
 import time
 from enum import StrEnum
 
-from db import get_db
+from db import TagInUse, get_db
+from db.database import normalize_tag_id
 from fastapi import APIRouter, HTTPException, Query
-from pydantic import BaseModel
+from models import EncodeResult, Spool, SpoolCreate
+from pydantic import BaseModel, Field
+from services.jobs import JobContext, job_kind
+from services.tag_batch import MAX_ATTEMPTS, BatchSpool, EncodingStation, TagRefused, get_encoding_station
 from tags import (
+    OpenPrintTagData,
+    OpenPrintTagDecoder,
     OpenSpoolTagData,
     SpoolEaseEncoder,
     TagDecoder,
     TagType,
 )
+from tags.dump import ndef_record, ndef_tlv, parse_block_map, parse_dump_bytes, parse_dump_text
+from tags.models import NfcTagType, TagReadResult
 from tags.openspool import OpenSpoolDecoder
 from tags.opentag3d import OpenTag3DDecoder, OpenTag3DTagData
 
@@ -31,6 +40,7 @@ class TagFormat(StrEnum):
     SPOOLEASE_V2 = "SpoolEaseV2"
     OPENSPOOL = "OpenSpool"
     OPENTAG3D = "OpenTag3D"
+    OPENPRINTTAG = "OpenPrintTag"
 
 
 class TagFormatInfo(BaseModel):
@@ -57,13 +67,13 @@ class EncodeResponse(BaseModel):
     """Response with encoded tag data."""
 
     format: str
-    spool_id: str
+    spool_id: str | None = None  # None for converted tags
     tag_uid: str | None = None
 
     # For URL-based formats (SpoolEase)
     url: str | None = None
 
-    # For binary formats (OpenTag3D)
+    # For binary formats (OpenTag3D, OpenPrintTag)
     payload_base64: str | None = None
     payload_hex: str | None = None
 
@@ -76,14 +86,18 @@ class EncodeResponse(BaseModel):
 
 
 class DecodeRequest(BaseModel):
-    """Request to decode tag data (for testing)."""
+    """Request to decode tag data."""
 
-    tag_uid: str  # Hex-encoded UID
+    tag_uid: str | None = None  # Hex-encoded UID (optional for dumps that contain it)
 
     # One of these should be provided
     url: str | None = None  # For SpoolEase (NDEF URL)
     json_payload: str | None = None  # For OpenSpool
     payload_base64: str | None = None  # For OpenTag3D binary
+    blocks: dict[int, str] | None = None  # Mifare Classic block number -> hex (as sent by the Pico bridge)
+    pages: dict[int, str] | None = None  # NTAG page number -> hex
+    dump: str | None = None  # Text dump (Flipper Zero .nfc or Proxmark3 .eml)
+    dump_base64: str | None = None  # Binary dump (Proxmark3 .bin)
 
 
 class DecodeResponse(BaseModel):
@@ -92,6 +106,9 @@ class DecodeResponse(BaseModel):
     tag_type: str
     tag_uid: str
     uid_base64: str
+    nfc_type: str | None = None  # Physical tag type (for raw dumps)
+    decoder: str | None = None  # Decoder that recognized the tag (see tags.registry)
+    confidence: int = 0  # How sure the decoder was, 0-100
 
     # Normalized spool data
     material: str | None = None
@@ -136,6 +153,14 @@ async def list_tag_formats():
             min_capacity=137,  # NTAG213 for core, 504 for extended
             writable=True,
         ),
+        TagFormatInfo(
+            id=TagFormat.OPENPRINTTAG.value,
+            name="OpenPrintTag",
+            description="Open standard with CBOR-encoded data in an NDEF MIME record.",
+            nfc_type="NTAG213/215/216",
+            min_capacity=137,  # NTAG213
+            writable=True,
+        ),
     ]
 
 
@@ -157,9 +182,6 @@ async def encode_tag(request: EncodeRequest):
     if not spool:
         raise HTTPException(status_code=404, detail="Spool not found")
 
-    # Convert spool to dict for encoding
-    spool_dict = spool.model_dump() if hasattr(spool, "model_dump") else dict(spool)
-
     # Generate or use provided tag UID
     if request.tag_uid:
         tag_uid_hex = request.tag_uid.replace(":", "").replace(" ", "").upper()
@@ -167,21 +189,33 @@ async def encode_tag(request: EncodeRequest):
         # Generate a placeholder UID (7 bytes for NTAG)
         tag_uid_hex = "00000000000000"
 
+    return _encode_spool(spool, request.format, tag_uid_hex, request.extended)
+
+
+def _encode_spool(spool: Spool, format: TagFormat, tag_uid_hex: str, extended: bool = False) -> EncodeResponse:
+    """Encode a spool in a tag format for the tag with this UID."""
+    return _encode_data(spool.model_dump(), format, tag_uid_hex, extended, spool_id=spool.id)
+
+
+def _encode_data(
+    spool_dict: dict, format: TagFormat, tag_uid_hex: str, extended: bool = False, spool_id: str | None = None
+) -> EncodeResponse:
+    """Encode spool fields (as in Spool) in a tag format for the tag with this UID."""
     # Convert UID to base64
     uid_bytes = bytes.fromhex(tag_uid_hex)
     uid_base64 = base64.urlsafe_b64encode(uid_bytes).decode("ascii").rstrip("=")
 
     response = EncodeResponse(
-        format=request.format.value,
-        spool_id=request.spool_id,
+        format=format.value,
+        spool_id=spool_id,
         tag_uid=tag_uid_hex,
     )
 
-    if request.format == TagFormat.SPOOLEASE_V2:
+    if format == TagFormat.SPOOLEASE_V2:
         # Encode as SpoolEase V2 URL
         url = SpoolEaseEncoder.encode(
             tag_id=uid_base64,
-            spool_id=request.spool_id,
+            spool_id=spool_id,
             material=spool_dict.get("material"),
             material_subtype=spool_dict.get("subtype"),
             color_code=spool_dict.get("rgba"),
@@ -198,7 +232,7 @@ async def encode_tag(request: EncodeRequest):
         response.ndef_type = "U"  # NDEF URL record
         response.payload_size = len(url)
 
-    elif request.format == TagFormat.OPENSPOOL:
+    elif format == TagFormat.OPENSPOOL:
         # Encode as OpenSpool JSON
         # Convert RGBA to RGB for OpenSpool
         color_hex = None
@@ -223,7 +257,7 @@ async def encode_tag(request: EncodeRequest):
         response.ndef_type = "application/json"
         response.payload_size = len(payload)
 
-    elif request.format == TagFormat.OPENTAG3D:
+    elif format == TagFormat.OPENTAG3D:
         # Encode as OpenTag3D binary
         # Parse RGBA color
         primary_color = spool_dict.get("rgba")
@@ -242,28 +276,122 @@ async def encode_tag(request: EncodeRequest):
             diameter_um=1750,  # Default 1.75mm
         )
 
-        payload = OpenTag3DDecoder.encode(opentag3d_data, extended=request.extended)
+        payload = OpenTag3DDecoder.encode(opentag3d_data, extended=extended)
         response.payload_base64 = base64.b64encode(payload).decode("ascii")
         response.payload_hex = payload.hex().upper()
         response.ndef_type = "application/opentag3d"
         response.payload_size = len(payload)
 
+    elif format == TagFormat.OPENPRINTTAG:
+        # Encode as OpenPrintTag CBOR; readers take the color from the material name
+        name_parts = (spool_dict.get("material"), spool_dict.get("color_name"))
+        openprinttag_data = OpenPrintTagData(
+            tag_id=uid_base64,
+            material_type=spool_dict.get("material"),
+            material_name=" ".join(p for p in name_parts if p) or None,
+            brand_name=spool_dict.get("brand"),
+            primary_color=spool_dict.get("rgba"),
+            nominal_weight=spool_dict.get("label_weight"),
+            actual_weight=spool_dict.get("weight_new"),
+            empty_weight=spool_dict.get("core_weight"),
+        )
+
+        payload = OpenPrintTagDecoder.encode(openprinttag_data)
+        response.payload_base64 = base64.b64encode(payload).decode("ascii")
+        response.payload_hex = payload.hex().upper()
+        response.ndef_type = OpenPrintTagDecoder.RECORD_TYPE
+        response.payload_size = len(payload)
+
+    return response
+
+
+def _ndef_tag_data(encoded: EncodeResponse) -> bytes:
+    """NTAG user memory (NDEF TLV from page 4 on) holding an encoded spool."""
+    if encoded.url:
+        message = ndef_record("U", encoded.url)
+    else:
+        message = ndef_record(encoded.ndef_type, base64.b64decode(encoded.payload_base64))
+    return ndef_tlv(message)
+
+
+def _raw_data(result: TagReadResult) -> dict | None:
+    """Get the format-specific decoded data from a tag read result."""
+    if result.spoolease_data:
+        return result.spoolease_data.model_dump()
+    if result.bambulab_data:
+        return result.bambulab_data.model_dump(exclude={"blocks"})
+    if result.creality_data:
+        return result.creality_data.model_dump()
+    if result.openprinttag_data:
+        return result.openprinttag_data.model_dump()
+    if result.openspool_data:
+        return result.openspool_data.model_dump()
+    return result.opentag3d_data
+
+
+def _decode_raw_dump(request: DecodeRequest) -> DecodeResponse:
+    """Decode a raw block/page dump using the shared tag decoders."""
+    try:
+        if request.blocks:
+            dump = parse_block_map(request.blocks, NfcTagType.MIFARE_CLASSIC_1K)
+        elif request.pages:
+            dump = parse_block_map(request.pages, NfcTagType.NTAG)
+        elif request.dump:
+            dump = parse_dump_text(request.dump)
+        else:
+            dump = parse_dump_bytes(base64.b64decode(request.dump_base64))
+    except ValueError as e:
+        raise HTTPException(status_code=400, detail=f"Invalid tag dump: {e}") from e
+
+    uid_hex = request.tag_uid.replace(":", "").replace(" ", "").upper() if request.tag_uid else None
+    result = TagDecoder.decode_dump(dump, uid_hex)
+    if not result:
+        raise HTTPException(status_code=400, detail="Tag UID not found in dump, provide tag_uid")
+
+    response = DecodeResponse(
+        tag_type=result.tag_type.value,
+        tag_uid=result.uid,
+        uid_base64=result.uid_base64,
+        nfc_type=result.nfc_type.value,
+        decoder=result.decoder,
+        confidence=result.confidence,
+        raw_data=_raw_data(result),
+    )
+    spool = TagDecoder.to_spool(result)
+    if spool:
+        response.material = spool.material
+        response.subtype = spool.subtype
+        response.color_name = spool.color_name
+        response.rgba = spool.rgba
+        response.brand = spool.brand
+        response.label_weight = spool.label_weight
+        response.core_weight = spool.core_weight
+        response.slicer_filament = spool.slicer_filament
+        response.note = spool.note
     return response
 
 
 @router.post("/decode", response_model=DecodeResponse)
 async def decode_tag(request: DecodeRequest):
-    """Decode tag data for testing without hardware.
+    """Decode tag data without hardware.
 
-    Accepts tag data in various formats and returns the decoded spool information.
-    Useful for testing tag encoding/decoding without physical NFC hardware.
+    Accepts either a decoded NDEF payload (url, json_payload, payload_base64)
+    or a raw memory dump (blocks, pages, dump, dump_base64). Raw dumps let the
+    device delegate decoding of formats it doesn't understand, and let users
+    decode dumps taken with a Proxmark3 or Flipper Zero.
 
     Args:
-        request: Tag data to decode (provide url, json_payload, or payload_base64)
+        request: Tag data to decode
 
     Returns:
         Decoded tag information
     """
+    if request.blocks or request.pages or request.dump or request.dump_base64:
+        return _decode_raw_dump(request)
+
+    if not request.tag_uid:
+        raise HTTPException(status_code=400, detail="tag_uid is required unless decoding a raw dump")
+
     tag_uid_hex = request.tag_uid.replace(":", "").replace(" ", "").upper()
     uid_bytes = bytes.fromhex(tag_uid_hex)
     uid_base64 = base64.urlsafe_b64encode(uid_bytes).decode("ascii").rstrip("=")
@@ -279,6 +407,8 @@ async def decode_tag(request: DecodeRequest):
         result = TagDecoder.decode_ndef_url(tag_uid_hex, request.url)
         if result and result.spoolease_data:
             response.tag_type = result.tag_type.value
+            response.decoder = result.decoder
+            response.confidence = result.confidence
             data = result.spoolease_data
             response.material = data.material
             response.subtype = data.material_subtype
@@ -298,6 +428,8 @@ async def decode_tag(request: DecodeRequest):
         result = TagDecoder.decode_ndef_records(tag_uid_hex, ndef_records)
         if result and result.openspool_data:
             response.tag_type = result.tag_type.value
+            response.decoder = result.decoder
+            response.confidence = result.confidence
             data = result.openspool_data
             response.material = data.material_type
             response.brand = data.brand
@@ -313,6 +445,8 @@ async def decode_tag(request: DecodeRequest):
         result = TagDecoder.decode_ndef_records(tag_uid_hex, ndef_records)
         if result and result.opentag3d_data:
             response.tag_type = result.tag_type.value
+            response.decoder = result.decoder
+            response.confidence = result.confidence
             data = result.opentag3d_data
             response.material = data.get("material_name")
             response.subtype = data.get("modifiers")
@@ -322,11 +456,86 @@ async def decode_tag(request: DecodeRequest):
             response.label_weight = data.get("weight_g")
             response.raw_data = data
     else:
-        raise HTTPException(status_code=400, detail="Must provide one of: url, json_payload, or payload_base64")
+        raise HTTPException(
+            status_code=400,
+            detail="Must provide one of: url, json_payload, payload_base64, blocks, pages, dump, or dump_base64",
+        )
 
     return response
 
 
+# Normalized fields each format can hold. Decoded notes only list missing fields, so they aren't converted.
+FORMAT_FIELDS = {
+    TagFormat.SPOOLEASE_V2: (
+        "material",
+        "subtype",
+        "color_name",
+        "rgba",
+        "brand",
+        "label_weight",
+        "core_weight",
+        "slicer_filament",
+    ),
+    TagFormat.OPENSPOOL: ("material", "rgba", "brand"),
+    TagFormat.OPENTAG3D: ("material", "subtype", "color_name", "rgba", "brand", "label_weight"),
+    TagFormat.OPENPRINTTAG: ("material", "color_name", "rgba", "brand", "label_weight", "core_weight"),
+}
+
+
+class ConvertRequest(DecodeRequest):
+    """Request to convert a tag to another format, with the source as for /decode."""
+
+    format: TagFormat = TagFormat.OPENPRINTTAG
+    target_uid: str | None = None  # UID of the tag to write (formats that embed it)
+    extended: bool = False  # For OpenTag3D: use extended format
+
+
+class ConvertResponse(BaseModel):
+    """Response with a decoded tag re-encoded in another format."""
+
+    source: DecodeResponse
+    encoded: EncodeResponse
+    tag_data_hex: str  # NTAG user memory from page 4 on
+    dropped: list[str] = []  # Source fields the target format can't hold
+
+
+@router.post("/convert", response_model=ConvertResponse)
+async def convert_tag(request: ConvertRequest):
+    """Re-encode a decoded tag in another format.
+
+    Lets spool data from a proprietary tag (e.g. a Bambu Lab dump) be written
+    to a cheap NTAG sticker. Any decodable tag can be the source; Bambu Lab
+    tags are signed, so they can't be a target.
+
+    Args:
+        request: Source tag data and target format
+
+    Returns:
+        Decoded source, encoded target and the NTAG memory to write
+    """
+    source = await decode_tag(request)
+    if source.tag_type == TagType.UNKNOWN.value:
+        raise HTTPException(status_code=422, detail="Couldn't decode the source tag")
+
+    fields = source.model_dump(include=set(FORMAT_FIELDS[TagFormat.SPOOLEASE_V2]))
+    target_uid = (request.target_uid or "00000000000000").replace(":", "").replace(" ", "").upper()
+    try:
+        bytes.fromhex(target_uid)
+    except ValueError:
+        raise HTTPException(status_code=400, detail="target_uid must be hex") from None
+
+    encoded = _encode_data(fields, request.format, target_uid, request.extended)
+    dropped = [
+        name for name, value in fields.items() if value is not None and name not in FORMAT_FIELDS[request.format]
+    ]
+    return ConvertResponse(
+        source=source,
+        encoded=encoded,
+        tag_data_hex=_ndef_tag_data(encoded).hex().upper(),
+        dropped=dropped,
+    )
+
+
 class TagLookupResponse(BaseModel):
     """Response with tag/spool data lookup."""
 
@@ -408,6 +617,98 @@ async def lookup_tag_by_uid(uid: str = Query(..., description="Tag UID in hex (e
     return response
 
 
+class ScanRecord(BaseModel):
+    """An NDEF record as read by a phone."""
+
+    type: str  # Record type, e.g. "U", "application/json"
+    payload: str  # Base64-encoded record payload
+
+
+class TagScanRequest(BaseModel):
+    """A tag read with a phone (iOS Shortcuts, Tasker, ...)."""
+
+    uid: str  # Tag UID in hex, with or without colons
+    ndef_url: str | None = None  # URL record, already decoded
+    ndef_records: list[ScanRecord] | None = None  # Raw NDEF records
+    create: bool = True  # Add a spool for an unknown tag that carries spool data
+
+
+class TagScanResponse(BaseModel):
+    """What a scanned tag resolved to."""
+
+    uid: str
+    uid_base64: str
+    tag_type: str
+    spool: Spool | None = None  # Matched or created spool, None if the tag is unknown
+    created: bool = False
+
+
+def _decode_scan(uid_hex: str, request: TagScanRequest) -> TagReadResult | None:
+    """Decode a phone scan the same way as a tag read by the device."""
+    if request.ndef_url:
+        return TagDecoder.decode_ndef_url(uid_hex, request.ndef_url)
+    if request.ndef_records:
+        try:
+            records = [{"type": r.type, "payload": base64.b64decode(r.payload)} for r in request.ndef_records]
+        except ValueError:
+            raise HTTPException(status_code=400, detail="NDEF record payloads must be base64") from None
+        return TagDecoder.decode_ndef_records(uid_hex, records)
+    return None
+
+
+@router.post("/scan", response_model=TagScanResponse)
+async def scan_tag(request: TagScanRequest):
+    """Resolve a tag read with a phone, adding a spool for a new tag.
+
+    Lets spools be registered with just a phone's NFC reader: the tag is
+    decoded and matched like a tag on the SpoolBuddy scale. An unknown tag
+    carrying spool data (SpoolEase, OpenSpool, OpenTag3D, OpenPrintTag)
+    gets a new spool unless create is false. Open UIs receive the same
+    tag_result message as for a device read.
+    """
+    uid_hex = request.uid.replace(":", "").replace(" ", "").upper()
+    try:
+        uid_bytes = bytes.fromhex(uid_hex)
+    except ValueError:
+        raise HTTPException(status_code=400, detail="uid must be hex") from None
+    if not uid_bytes:
+        raise HTTPException(status_code=400, detail="uid is required")
+
+    result = _decode_scan(uid_hex, request)
+    uid_base64 = base64.urlsafe_b64encode(uid_bytes).decode("ascii").rstrip("=")
+    response = TagScanResponse(
+        uid=uid_hex,
+        uid_base64=uid_base64,
+        tag_type=result.tag_type.value if result else TagType.UNKNOWN.value,
+    )
+
+    db = await get_db()
+    response.spool = await db.get_spool_by_tag(uid_base64)
+    if not response.spool and request.create and result:
+        spool_data = TagDecoder.to_spool(result)
+        if spool_data and spool_data.material:
+            fields = spool_data.model_dump(exclude={"tag_id"}, exclude_none=True)
+            response.spool = await db.create_spool(SpoolCreate(**fields, tag_id=uid_base64))
+            response.created = True
+            logger.info(f"Phone scan added spool {response.spool.id} for tag {uid_hex}")
+
+    from main import broadcast_message
+
+    message = {
+        "type": "tag_result",
+        "uid": uid_hex,
+        "uid_base64": uid_base64,
+        "tag_type": response.tag_type,
+        "matched_spool_id": response.spool.id if response.spool else None,
+    }
+    if result and result.spoolease_data:
+        message["spoolease_data"] = result.spoolease_data.model_dump()
+    if result and result.openprinttag_data:
+        message["openprinttag_data"] = result.openprinttag_data.model_dump()
+    await broadcast_message(message)
+    return response
+
+
 @router.post("/encode-from-spool/{spool_id}")
 async def encode_from_spool(
     spool_id: str,
@@ -426,3 +727,206 @@ async def encode_from_spool(
         extended=extended,
     )
     return await encode_tag(request)
+
+
+MAX_BATCH_TAGS = 100
+
+# What the device shows after a failed write
+OUTCOME_MESSAGES = {
+    "write_failed": "Write failed",
+    "read_failed": "Couldn't read the tag back",
+    "mismatch": "Tag didn't read back correctly",
+    "too_large": "Data doesn't fit on this tag",
+    "tag_in_use": "Tag belongs to another spool",
+}
+
+
+class TagBatchParams(BaseModel):
+    """Parameters of a tag_batch job."""
+
+    spool_ids: list[str] = Field(min_length=1, max_length=MAX_BATCH_TAGS)
+    format: TagFormat = TagFormat.SPOOLEASE_V2
+    extended: bool = False  # For OpenTag3D: use extended format
+
+
+def _spool_label(spool: Spool) -> str:
+    """Short name the device prompts with, e.g. "Red PETG"."""
+    return " ".join(p for p in (spool.color_name, spool.material, spool.subtype) if p) or f"spool #{spool.spool_number}"
+
+
+async def _encode_batch_spool(
+    ctx: JobContext, station: EncodingStation, db, index: int, spool: Spool, format: TagFormat
+) -> dict:
+    """Wait for the device to write a spool's tag, retrying failed writes up to MAX_ATTEMPTS."""
+    result = {
+        "index": index,
+        "spool_id": spool.id,
+        "label": _spool_label(spool),
+        "tag_id": None,
+        "outcome": None,
+        "attempts": 0,
+        "retries": 0,
+    }
+    while True:
+        report, handled = await station.next_result()
+        try:
+            result["attempts"] += 1
+            result["retries"] += report.retries
+            result["outcome"] = report.outcome
+            await db.log_tag_write(
+                normalize_tag_id(report.tag_uid),
+                report.outcome,
+                spool_id=spool.id,
+                job_id=ctx.job_id,
+                pages_written=report.pages_written,
+                retries=report.retries,
+                failed_page=report.failed_page,
+                rf_signal=report.rf_signal,
+            )
+            if report.outcome == "verified":
+                try:
+                    await db.link_tag_to_spool(spool.id, report.tag_uid, tag_type=format.value, data_origin="tag_batch")
+                except TagInUse:
+                    result["outcome"] = "tag_in_use"
+                else:
+                    station.mark_written(report.tag_uid)
+                    result["tag_id"] = report.tag_uid
+                    return result
+            if result["attempts"] >= MAX_ATTEMPTS:
+                return result
+            station.retry(f"{OUTCOME_MESSAGES.get(result['outcome'], 'Write failed')}, place the tag again")
+            await ctx.update(message=station.message, force=True)
+        finally:
+            handled.set_result(None)
+
+
+@job_kind("tag_batch", TagBatchParams)
+async def tag_batch_job(ctx: JobContext, params: TagBatchParams) -> dict:
+    """Background job putting the device into batch encoding mode.
+
+    The device prompts for a tag per spool, writes it, verifies it by
+    reading it back and reports the outcome (see services/tag_batch.py).
+    Verified tags are linked to their spool. The result lists the outcome
+    per tag.
+    """
+    db = await get_db()
+    spools = []
+    for spool_id in params.spool_ids:
+        spool = await db.get_spool(spool_id)
+        if not spool:
+            raise ValueError(f"Spool {spool_id} not found")
+        spools.append(spool)
+    by_id = {spool.id: spool for spool in spools}
+
+    async def prepare(spool_id: str, tag_id: str) -> bytes:
+        holder = await db.get_spool_by_tag(tag_id)
+        if holder and holder.id != spool_id:
+            raise TagRefused(f"Tag belongs to spool #{holder.spool_number}")
+        encoded = _encode_spool(by_id[spool_id], params.format, normalize_tag_id(tag_id), params.extended)
+        return _ndef_tag_data(encoded)
+
+    station = get_encoding_station()
+    station.start(ctx.job_id, [BatchSpool(spool.id, _spool_label(spool)) for spool in spools], prepare)
+    results = []
+    try:
+        for index, spool in enumerate(spools):
+            previous = results[-1] if results else None
+            done = None
+            if previous:
+                done = f"Tag {index} {'done' if previous['tag_id'] else 'skipped'}"
+            station.select(index, done)
+            await ctx.update(progress=index, total=len(spools), message=station.message, force=True)
+            results.append(await _encode_batch_spool(ctx, station, db, index + 1, spool, params.format))
+    finally:
+        station.stop(ctx.job_id)
+
+    encoded = sum(1 for r in results if r["tag_id"])
+    await ctx.update(progress=len(spools), message=f"{encoded} of {len(spools)} tags encoded", force=True)
+    return {"encoded": encoded, "failed": len(spools) - encoded, "tags": results}
+
+
+@router.post("/batch/result")
+async def report_batch_result(result: EncodeResult):
+    """Outcome of writing a tag in batch encoding mode, posted by the device.
+
+    Returns once the job has handled it, with what the device should show next.
+    """
+    station = get_encoding_station()
+    if not await station.report(result):
+        raise HTTPException(status_code=409, detail="No tag batch is waiting for this tag")
+    return {"ok": True, "encode": await station.task_for(result.tag_uid)}
+
+
+class TagWrite(BaseModel):
+    """A tag write reported by the device."""
+
+    id: int
+    tag_id: str
+    spool_id: str | None = None
+    job_id: str | None = None
+    outcome: str  # verified, write_failed, read_failed, mismatch, too_large
+    pages_written: int = 0
+    retries: int = 0
+    failed_page: int | None = None
+    rf_signal: int | None = None
+    created_at: int
+
+
+class TagWriteSummary(BaseModel):
+    """Failure statistics of a set of tag writes."""
+
+    writes: int = 0
+    verified: int = 0
+    failed: int = 0
+    failure_rate: float = 0.0  # Failed writes / writes
+    tags: int = 0  # Distinct tags written
+    retries: int = 0
+    avg_retries: float = 0.0
+    avg_rf_signal: float | None = None
+    min_rf_signal: int | None = None
+    first_at: int | None = None
+    last_at: int | None = None
+
+
+class TagBatchWriteStats(TagWriteSummary):
+    """Failure statistics of one tag batch."""
+
+    job_id: str | None = None  # None for writes outside a batch
+
+
+class TagWriteStats(BaseModel):
+    """Tag write failure statistics."""
+
+    since: int | None = None
+    total: TagWriteSummary
+    outcomes: dict[str, int] = {}  # Writes per outcome
+    batches: list[TagBatchWriteStats] = []  # Newest first
+
+
+@router.get("/writes", response_model=list[TagWrite])
+async def list_tag_writes(
+    tag_id: str | None = Query(None, description="Only writes of this tag"),
+    spool_id: str | None = Query(None, description="Only writes for this spool"),
+    job_id: str | None = Query(None, description="Only writes of this tag batch"),
+    failed: bool = Query(False, description="Only failed writes"),
+    limit: int = Query(default=50, ge=1, le=500),
+):
+    """Get recent tag writes with their read-back outcome and quality metrics, newest first."""
+    db = await get_db()
+    return await db.get_tag_writes(
+        tag_id=normalize_tag_id(tag_id), spool_id=spool_id, job_id=job_id, failed_only=failed, limit=limit
+    )
+
+
+@router.get("/writes/stats", response_model=TagWriteStats)
+async def get_tag_write_stats(
+    days: int | None = Query(None, ge=1, le=3650, description="Only include the last N days"),
+):
+    """Tag write failure rates, retries and RF signal, overall and per tag batch.
+
+    A batch whose failure rate or retries stand out from the others points
+    at bad tags (or a reader problem, if the RF signal is low as well).
+    """
+    since = int(time.time()) - days * 86400 if days else None
+    db = await get_db()
+    return TagWriteStats(since=since, **await db.get_tag_write_stats(since=since))
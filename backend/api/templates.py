@@ -0,0 +1,138 @@
+"""Spool template endpoints.
+
+A template holds what identical spools have in common (brand, material,
+color, weights, slicer preset), so adding another roll of a filament that
+is bought again and again is a single POST /spools/from-template/{id}.
+Templates are listed most used first, for quick-add lists.
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException
+from models import SpoolCreate
+from pydantic import BaseModel, Field, model_validator
+
+router = APIRouter(prefix="/spool-templates", tags=["spool-templates"])
+
+
+class SpoolTemplateFields(BaseModel):
+    """Spool fields a template fills in."""
+
+    subtype: str | None = None
+    brand: str | None = None
+    color_name: str | None = None
+    rgba: str | None = None
+    label_weight: int | None = Field(default=None, ge=0)
+    core_weight: int | None = Field(default=None, ge=0)
+    price: float | None = Field(default=None, ge=0)
+    slicer_filament: str | None = None
+    slicer_filament_name: str | None = None
+    location: str | None = None
+    nozzle_temp_min: int | None = Field(default=None, ge=0, le=350)
+    nozzle_temp_max: int | None = Field(default=None, ge=0, le=350)
+
+    @model_validator(mode="after")
+    def check_nozzle_temps(self):
+        if self.nozzle_temp_min is not None and self.nozzle_temp_max is not None:
+            if self.nozzle_temp_min > self.nozzle_temp_max:
+                raise ValueError("nozzle_temp_min must not exceed nozzle_temp_max")
+        return self
+
+
+class SpoolTemplateCreate(SpoolTemplateFields):
+    """Request to create a spool template."""
+
+    name: str | None = None  # Defaults to brand, material, subtype and color, e.g. "Bambu PLA Matte Black"
+    material: str
+    label_weight: int | None = Field(default=1000, ge=0)
+    core_weight: int | None = Field(default=250, ge=0)
+
+    @model_validator(mode="after")
+    def default_name(self):
+        if not self.name:
+            parts = (self.brand, self.material, self.subtype, self.color_name)
+            self.name = " ".join(p for p in parts if p)
+        return self
+
+
+class SpoolTemplateUpdate(SpoolTemplateFields):
+    """Request to update a spool template."""
+
+    name: str | None = None
+    material: str | None = None
+
+
+class SpoolTemplate(SpoolTemplateFields):
+    """A quick-add preset for new spools."""
+
+    id: int
+    name: str
+    material: str
+    use_count: int = 0  # Spools added from the template
+    last_used_at: int | None = None
+    created_at: int | None = None
+
+
+class SpoolFromTemplateRequest(BaseModel):
+    """Per-spool details for a spool added from a template."""
+
+    tag_id: str | None = None
+    weight_current: int | None = None  # Gross weight from the scale, if weighed
+    location: str | None = None  # Overrides the template's location
+    note: str | None = None
+    price: float | None = None  # Overrides the template's price
+
+
+def spool_from_template(template: dict, request: SpoolFromTemplateRequest) -> SpoolCreate:
+    """New spool from a template and the details of this particular spool."""
+    fields = {k: template[k] for k in SpoolTemplateFields.model_fields}
+    fields["material"] = template["material"]
+    overrides = request.model_dump(exclude_none=True)
+    return SpoolCreate(**{**fields, **overrides, "data_origin": "template"})
+
+
+async def _get_template_or_404(db, template_id: int) -> dict:
+    template = await db.get_spool_template(template_id)
+    if not template:
+        raise HTTPException(status_code=404, detail="Spool template not found")
+    return template
+
+
+@router.get("", response_model=list[SpoolTemplate])
+async def list_spool_templates():
+    """List spool templates, most used first."""
+    db = await get_db()
+    return await db.get_spool_templates()
+
+
+@router.post("", response_model=SpoolTemplate, status_code=201)
+async def create_spool_template(request: SpoolTemplateCreate):
+    """Create a spool template."""
+    db = await get_db()
+    return await db.create_spool_template(**request.model_dump())
+
+
+@router.get("/{template_id}", response_model=SpoolTemplate)
+async def get_spool_template(template_id: int):
+    """Get a spool template."""
+    db = await get_db()
+    return await _get_template_or_404(db, template_id)
+
+
+@router.put("/{template_id}", response_model=SpoolTemplate)
+async def update_spool_template(template_id: int, request: SpoolTemplateUpdate):
+    """Update a spool template. Spools already added from it are not changed."""
+    db = await get_db()
+    await _get_template_or_404(db, template_id)
+    fields = request.model_dump(exclude_unset=True)
+    for required in ("name", "material"):
+        if required in fields and not fields[required]:
+            raise HTTPException(status_code=422, detail=f"{required} must not be empty")
+    return await db.update_spool_template(template_id, **fields)
+
+
+@router.delete("/{template_id}", status_code=204)
+async def delete_spool_template(template_id: int):
+    """Delete a spool template. Spools added from it are kept."""
+    db = await get_db()
+    if not await db.delete_spool_template(template_id):
+        raise HTTPException(status_code=404, detail="Spool template not found")
@@ -0,0 +1,91 @@
+"""Trash API endpoints.
+
+Deleted spools and printers are soft-deleted into the trash, where they can
+be restored until they are purged (manually or after the retention window).
+"""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException
+from models import Printer, Spool
+from pydantic import BaseModel
+
+router = APIRouter(prefix="/trash", tags=["trash"])
+
+DEFAULT_TRASH_RETENTION_DAYS = 30
+TRASH_RETENTION_SETTING = "trash_retention_days"
+
+
+class TrashContents(BaseModel):
+    """Items currently in the trash."""
+
+    spools: list[Spool]
+    printers: list[Printer]
+    retention_days: int  # Items older than this are purged automatically
+
+
+class PurgeResult(BaseModel):
+    """Number of items permanently deleted."""
+
+    spools: int
+    printers: int
+
+
+async def get_trash_retention_days() -> int:
+    """Get the configured trash retention window in days."""
+    db = await get_db()
+    value = await db.get_setting(TRASH_RETENTION_SETTING)
+    return int(value) if value else DEFAULT_TRASH_RETENTION_DAYS
+
+
+@router.get("", response_model=TrashContents)
+async def list_trash():
+    """List trashed spools and printers."""
+    db = await get_db()
+    return TrashContents(
+        spools=await db.get_deleted_spools(),
+        printers=await db.get_deleted_printers(),
+        retention_days=await get_trash_retention_days(),
+    )
+
+
+@router.delete("", response_model=PurgeResult)
+async def empty_trash():
+    """Permanently delete everything in the trash."""
+    db = await get_db()
+    return await db.purge_trash()
+
+
+@router.post("/spools/{spool_id}/restore", response_model=Spool)
+async def restore_spool(spool_id: str):
+    """Restore a spool from the trash."""
+    db = await get_db()
+    spool = await db.restore_deleted_spool(spool_id)
+    if not spool:
+        raise HTTPException(status_code=404, detail="Spool not found in trash")
+    return spool
+
+
+@router.delete("/spools/{spool_id}", status_code=204)
+async def purge_spool(spool_id: str):
+    """Permanently delete a trashed spool, including its usage history."""
+    db = await get_db()
+    if not await db.purge_spool(spool_id):
+        raise HTTPException(status_code=404, detail="Spool not found in trash")
+
+
+@router.post("/printers/{serial}/restore", response_model=Printer)
+async def restore_printer(serial: str):
+    """Restore a printer from the trash."""
+    db = await get_db()
+    printer = await db.restore_deleted_printer(serial)
+    if not printer:
+        raise HTTPException(status_code=404, detail="Printer not found in trash")
+    return printer
+
+
+@router.delete("/printers/{serial}", status_code=204)
+async def purge_printer(serial: str):
+    """Permanently delete a trashed printer."""
+    db = await get_db()
+    if not await db.purge_printer(serial):
+        raise HTTPException(status_code=404, detail="Printer not found in trash")
@@ -0,0 +1,153 @@
+"""Webhook management endpoints."""
+
+from db import get_db
+from fastapi import APIRouter, HTTPException, Query
+from pydantic import BaseModel, field_validator
+from services.webhooks import WEBHOOK_EVENTS, get_webhook_dispatcher, validate_event_types
+
+router = APIRouter(prefix="/webhooks", tags=["webhooks"])
+
+
+class WebhookCreate(BaseModel):
+    """Request to register a webhook."""
+
+    name: str
+    url: str
+    events: list[str]  # Subset of WEBHOOK_EVENTS
+    secret: str | None = None  # Used to sign payloads (HMAC-SHA256)
+    enabled: bool = True
+
+    @field_validator("events")
+    @classmethod
+    def check_events(cls, v):
+        return validate_event_types(v)
+
+
+class WebhookUpdate(BaseModel):
+    """Request to update a webhook."""
+
+    name: str | None = None
+    url: str | None = None
+    events: list[str] | None = None
+    secret: str | None = None
+    enabled: bool | None = None
+
+    @field_validator("events")
+    @classmethod
+    def check_events(cls, v):
+        return validate_event_types(v)
+
+
+class WebhookResponse(BaseModel):
+    """Registered webhook (secret is never returned)."""
+
+    id: int
+    name: str
+    url: str
+    events: list[str]
+    enabled: bool
+    has_secret: bool
+    created_at: int | None = None
+
+
+class WebhookDelivery(BaseModel):
+    """Logged webhook delivery attempt."""
+
+    id: int
+    webhook_id: int
+    event: str
+    payload: str | None = None
+    status_code: int | None = None
+    success: bool
+    attempts: int
+    error: str | None = None
+    created_at: int | None = None
+
+
+def _to_response(webhook: dict) -> WebhookResponse:
+    return WebhookResponse(
+        id=webhook["id"],
+        name=webhook["name"],
+        url=webhook["url"],
+        events=webhook["events"],
+        enabled=webhook["enabled"],
+        has_secret=bool(webhook.get("secret")),
+        created_at=webhook.get("created_at"),
+    )
+
+
+@router.get("/events")
+async def list_webhook_events() -> list[str]:
+    """List the event types webhooks can subscribe to."""
+    return list(WEBHOOK_EVENTS)
+
+
+@router.get("", response_model=list[WebhookResponse])
+async def list_webhooks():
+    """List all registered webhooks."""
+    db = await get_db()
+    return [_to_response(w) for w in await db.get_webhooks()]
+
+
+@router.post("", response_model=WebhookResponse, status_code=201)
+async def create_webhook(data: WebhookCreate):
+    """Register a new webhook."""
+    db = await get_db()
+    webhook = await db.create_webhook(
+        name=data.name,
+        url=data.url,
+        events=data.events,
+        secret=data.secret,
+        enabled=data.enabled,
+    )
+    return _to_response(webhook)
+
+
+@router.get("/{webhook_id}", response_model=WebhookResponse)
+async def get_webhook(webhook_id: int):
+    """Get a webhook by ID."""
+    db = await get_db()
+    webhook = await db.get_webhook(webhook_id)
+    if not webhook:
+        raise HTTPException(status_code=404, detail="Webhook not found")
+    return _to_response(webhook)
+
+
+@router.patch("/{webhook_id}", response_model=WebhookResponse)
+async def update_webhook(webhook_id: int, data: WebhookUpdate):
+    """Update a webhook."""
+    db = await get_db()
+    if not await db.get_webhook(webhook_id):
+        raise HTTPException(status_code=404, detail="Webhook not found")
+
+    webhook = await db.update_webhook(webhook_id, **data.model_dump(exclude_unset=True))
+    return _to_response(webhook)
+
+
+@router.delete("/{webhook_id}", status_code=204)
+async def delete_webhook(webhook_id: int):
+    """Delete a webhook and its delivery log."""
+    db = await get_db()
+    if not await db.delete_webhook(webhook_id):
+        raise HTTPException(status_code=404, detail="Webhook not found")
+
+
+@router.get("/{webhook_id}/deliveries", response_model=list[WebhookDelivery])
+async def get_webhook_deliveries(webhook_id: int, limit: int = Query(default=50, le=500)):
+    """Get recent delivery attempts for a webhook (for debugging)."""
+    db = await get_db()
+    if not await db.get_webhook(webhook_id):
+        raise HTTPException(status_code=404, detail="Webhook not found")
+    return await db.get_webhook_deliveries(webhook_id, limit=limit)
+
+
+@router.post("/{webhook_id}/test")
+async def test_webhook(webhook_id: int):
+    """Send a test event to a webhook and return the delivery result."""
+    db = await get_db()
+    webhook = await db.get_webhook(webhook_id)
+    if not webhook:
+        raise HTTPException(status_code=404, detail="Webhook not found")
+
+    event = webhook["events"][0] if webhook["events"] else WEBHOOK_EVENTS[0]
+    return await get_webhook_dispatcher().deliver(webhook, event, {"test": True})
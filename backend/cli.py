@@ -0,0 +1,450 @@
+"""
+SpoolBuddy command line administration tool.
+
+Talks to a running SpoolBuddy server over its REST API, for headless
+servers and scripting. Request bodies are built and validated with the
+server's own models, so the CLI accepts exactly what the API does.
+
+    spoolbuddy-cli spools list
+    spoolbuddy-cli spools add --material PLA --brand Bambu --color-name Black --weight 1240
+    spoolbuddy-cli spools import inventory.csv
+    spoolbuddy-cli backup --output spoolbuddy.db
+    spoolbuddy-cli printers pair 01S00A000000000 --access-code 12345678
+    spoolbuddy-cli printers add-manual mk3s --name "Prusa MK3S"
+    spoolbuddy-cli tags convert bambu-dump.nfc --format OpenPrintTag --output sticker.bin
+    spoolbuddy-cli events --type printer_state
+
+The server is taken from --url or SPOOLBUDDY_URL (default http://localhost:3000).
+"""
+
+import argparse
+import base64
+import csv
+import json
+import os
+import sys
+import time
+from collections.abc import Iterable, Iterator
+
+import httpx
+from models import PrinterCreate, PrinterType, PrinterWithStatus, Spool, SpoolCreate
+from pydantic import ValidationError
+
+DEFAULT_URL = "http://localhost:3000"
+REQUEST_TIMEOUT = 30.0
+JOB_POLL_SECONDS = 1.0
+DISCOVERY_SECONDS = 5.0
+EVENTS_RETRY_SECONDS = 3.0
+
+# CSV columns that hold lists, written as JSON or "|"-separated
+_LIST_COLUMNS = {"color_stops"}
+
+
+class CliError(Exception):
+    """A command failed; the message is shown to the user."""
+
+
+class Api:
+    """Thin client for the SpoolBuddy REST API."""
+
+    def __init__(self, url: str, client: httpx.Client | None = None):
+        self.url = url.rstrip("/")
+        self._client = client or httpx.Client(base_url=f"{self.url}/api", timeout=REQUEST_TIMEOUT)
+
+    def request(self, method: str, path: str, **kwargs):
+        try:
+            response = self._client.request(method, path, **kwargs)
+        except httpx.HTTPError as e:
+            raise CliError(f"Can't reach SpoolBuddy at {self.url}: {e}") from e
+        if response.status_code >= 400:
+            raise CliError(f"{method} {path} failed: HTTP {response.status_code} {_error_detail(response)}")
+        if response.status_code == 204 or not response.content:
+            return None
+        return response.json()
+
+    def get(self, path: str, **kwargs):
+        return self.request("GET", path, **kwargs)
+
+    def post(self, path: str, **kwargs):
+        return self.request("POST", path, **kwargs)
+
+    def download(self, path: str, output: str):
+        with self._client.stream("GET", path) as response:
+            if response.status_code >= 400:
+                raise CliError(f"GET {path} failed: HTTP {response.status_code}")
+            with open(output, "wb") as f:
+                for chunk in response.iter_bytes():
+                    f.write(chunk)
+
+    def stream_lines(self, path: str, headers: dict) -> Iterator[str]:
+        with self._client.stream("GET", path, headers=headers, timeout=None) as response:
+            if response.status_code >= 400:
+                raise CliError(f"GET {path} failed: HTTP {response.status_code}")
+            yield from response.iter_lines()
+
+
+def _error_detail(response: httpx.Response) -> str:
+    try:
+        detail = response.json().get("detail")
+    except (ValueError, AttributeError):
+        return response.text
+    return detail if isinstance(detail, str) else json.dumps(detail)
+
+
+def _validation_message(e: ValidationError) -> str:
+    return "; ".join(f"{'.'.join(str(p) for p in err['loc']) or 'spool'}: {err['msg']}" for err in e.errors())
+
+
+# ============ Spools ============
+
+
+def spools_from_csv(rows: Iterable[dict]) -> Iterator[tuple[int, SpoolCreate | None, str | None]]:
+    """Spools from CSV rows keyed by SpoolCreate field names.
+
+    Yields (line, spool, error) with the line number in the file; empty cells
+    are left unset and unknown columns are ignored.
+    """
+    fields = SpoolCreate.model_fields
+    for line, row in enumerate(rows, start=2):
+        data = {}
+        for key, value in row.items():
+            key = (key or "").strip()
+            value = (value or "").strip()
+            if key not in fields or not value:
+                continue
+            if key in _LIST_COLUMNS and not value.startswith("["):
+                value = [v.strip() for v in value.split("|") if v.strip()]
+            data[key] = value
+        try:
+            yield line, SpoolCreate.model_validate(data), None
+        except ValidationError as e:
+            yield line, None, _validation_message(e)
+
+
+def format_spool(spool: Spool) -> str:
+    """One line per spool for listings."""
+    number = f"#{spool.spool_number}" if spool.spool_number is not None else "-"
+    name = " ".join(p for p in (spool.brand, spool.material, spool.subtype, spool.color_name) if p)
+    weight = f"{spool.weight_current}g" if spool.weight_current is not None else "?"
+    flags = " (archived)" if spool.archived_at else ""
+    return f"{number:>6}  {spool.id:<36}  {name:<40}  {weight:>7}  {spool.location or ''}{flags}"
+
+
+def cmd_spools_list(api: Api, args):
+    spools = [Spool.model_validate(s) for s in api.get("/spools")]
+    if not args.all:
+        spools = [s for s in spools if s.archived_at is None]
+    if args.json:
+        print(json.dumps([s.model_dump(mode="json") for s in spools], indent=2))
+        return
+    for spool in spools:
+        print(format_spool(spool))
+
+
+def cmd_spools_add(api: Api, args):
+    data = {
+        "material": args.material,
+        "brand": args.brand,
+        "subtype": args.subtype,
+        "color_name": args.color_name,
+        "rgba": args.rgba,
+        "label_weight": args.label_weight,
+        "weight_current": args.weight,
+        "location": args.location,
+        "tag_id": args.tag_id,
+        "note": args.note,
+    }
+    try:
+        spool = SpoolCreate.model_validate({k: v for k, v in data.items() if v is not None})
+    except ValidationError as e:
+        raise CliError(_validation_message(e)) from e
+    created = Spool.model_validate(api.post("/spools", json=spool.model_dump(mode="json", exclude_unset=True)))
+    print(format_spool(created))
+
+
+def cmd_spools_import(api: Api, args):
+    with open(args.file, newline="", encoding="utf-8-sig") as f:
+        parsed = list(spools_from_csv(csv.DictReader(f)))
+    errors = [(line, error) for line, _, error in parsed if error]
+    for line, error in errors:
+        print(f"line {line}: {error}", file=sys.stderr)
+    if errors and not args.skip_invalid:
+        raise CliError(f"{len(errors)} invalid row(s), nothing imported (use --skip-invalid to import the rest)")
+    spools = [(line, spool) for line, spool, _ in parsed if spool]
+    if args.dry_run:
+        print(f"{len(spools)} spool(s) would be imported")
+        return
+    imported = 0
+    for line, spool in spools:
+        try:
+            api.post("/spools", json=spool.model_dump(mode="json", exclude_unset=True))
+            imported += 1
+        except CliError as e:
+            print(f"line {line}: {e}", file=sys.stderr)
+    print(f"Imported {imported} of {len(spools)} spool(s)")
+
+
+# ============ Backups ============
+
+
+def wait_for_job(api: Api, job: dict) -> dict:
+    """Poll a background job until it finishes."""
+    while job["status"] in ("queued", "running"):
+        time.sleep(JOB_POLL_SECONDS)
+        job = api.get(f"/jobs/{job['id']}")
+    if job["status"] != "completed":
+        raise CliError(f"Job {job['kind']} {job['status']}: {job.get('error') or 'no details'}")
+    return job
+
+
+def cmd_backup(api: Api, args):
+    job = api.post("/jobs", json={"kind": "backup", "params": {"keep": args.keep}})
+    if args.no_wait:
+        print(job["id"])
+        return
+    result = wait_for_job(api, job)["result"]
+    print(f"Backup written: {result['file']} ({result['size']} bytes)")
+    if args.output:
+        api.download(f"/support/backups/{result['file']}", args.output)
+        print(f"Downloaded to {args.output}")
+
+
+# ============ Printers ============
+
+
+def cmd_printers_list(api: Api, args):
+    printers = [PrinterWithStatus.model_validate(p) for p in api.get("/printers")]
+    if args.json:
+        print(json.dumps([p.model_dump(mode="json") for p in printers], indent=2))
+        return
+    for printer in printers:
+        if printer.printer_type == PrinterType.MANUAL:
+            state = "manual"
+        else:
+            state = "connected" if printer.connected else "disconnected"
+        print(f"{printer.serial:<18}  {printer.name or '':<24}  {printer.ip_address or '':<16}  {state}")
+
+
+def discover_printers(api: Api, seconds: float) -> list[dict]:
+    """Run a discovery scan and return the printers found."""
+    api.post("/discovery/start")
+    try:
+        time.sleep(seconds)
+        return api.get("/discovery/printers")
+    finally:
+        api.post("/discovery/stop")
+
+
+def cmd_printers_discover(api: Api, args):
+    for printer in discover_printers(api, args.timeout):
+        name, model = printer.get("name") or "", printer.get("model") or ""
+        print(f"{printer['serial']:<18}  {name:<24}  {printer['ip_address']:<16}  {model}")
+
+
+def cmd_printers_pair(api: Api, args):
+    ip_address = args.ip
+    name, model = args.name, args.model
+    if not ip_address:
+        found = {p["serial"]: p for p in discover_printers(api, args.timeout)}
+        if args.serial not in found:
+            raise CliError(f"Printer {args.serial} not found on the network, give its address with --ip")
+        ip_address = found[args.serial]["ip_address"]
+        name = name or found[args.serial].get("name")
+        model = model or found[args.serial].get("model")
+    printer = PrinterCreate(
+        serial=args.serial,
+        name=name,
+        model=model,
+        ip_address=ip_address,
+        access_code=args.access_code,
+        auto_connect=not args.no_auto_connect,
+    )
+    api.post("/printers", json=printer.model_dump(mode="json"))
+    api.post(f"/printers/{args.serial}/connect")
+    print(f"Paired {args.serial} at {ip_address}")
+
+
+def cmd_printers_add_manual(api: Api, args):
+    printer = PrinterCreate(serial=args.serial, name=args.name, model=args.model, printer_type=PrinterType.MANUAL)
+    api.post("/printers", json=printer.model_dump(mode="json"))
+    print(f"Added manual printer {args.serial}")
+
+
+# ============ Events ============
+
+
+def parse_sse(lines: Iterable[str]) -> Iterator[tuple[str | None, str]]:
+    """(id, data) of each server-sent event in a stream of lines."""
+    event_id, data = None, []
+    for line in lines:
+        if not line:
+            if data:
+                yield event_id, "\n".join(data)
+            event_id, data = None, []
+        elif line.startswith("id:"):
+            event_id = line[3:].strip()
+        elif line.startswith("data:"):
+            data.append(line[5:].removeprefix(" "))
+        # Comments (keepalives) and retry: are ignored
+
+
+def cmd_events(api: Api, args):
+    types = set(args.type or [])
+    last_id = None
+    while True:
+        headers = {"Last-Event-ID": last_id} if last_id else {}
+        try:
+            for event_id, data in parse_sse(api.stream_lines("/events", headers)):
+                last_id = event_id or last_id
+                message = json.loads(data)
+                if types and message.get("type") not in types:
+                    continue
+                print(data if args.json else f"{time.strftime('%H:%M:%S')}  {message.get('type')}  {data}", flush=True)
+        except (CliError, httpx.HTTPError) as e:
+            print(f"Event stream interrupted ({e}), reconnecting", file=sys.stderr)
+        time.sleep(EVENTS_RETRY_SECONDS)
+
+
+# ============ Tags ============
+
+
+def read_tag_dump(path: str) -> dict:
+    """Source of a tag conversion: text dumps (Flipper Zero, Proxmark3 .eml) or binary ones (.bin)."""
+    with open(path, "rb") as f:
+        data = f.read()
+    try:
+        return {"dump": data.decode("utf-8")}
+    except UnicodeDecodeError:
+        return {"dump_base64": base64.b64encode(data).decode("ascii")}
+
+
+def cmd_tags_convert(api: Api, args):
+    request = read_tag_dump(args.file) | {"format": args.format, "tag_uid": args.source_uid, "target_uid": args.uid}
+    result = api.post("/tags/convert", json=request)
+    if args.json:
+        print(json.dumps(result, indent=2))
+        return
+
+    source = result["source"]
+    name = " ".join(p for p in (source.get("brand"), source.get("material"), source.get("color_name")) if p)
+    print(f"Source: {source['tag_type']} tag {source['tag_uid']}  {name}")
+    print(f"Target: {result['encoded']['format']}, {len(result['tag_data_hex']) // 2} bytes from page 4")
+    if result["dropped"]:
+        print(f"Not stored in {result['encoded']['format']}: {', '.join(result['dropped'])}")
+    if args.output:
+        with open(args.output, "wb") as f:
+            f.write(bytes.fromhex(result["tag_data_hex"]))
+        print(f"Written to {args.output}")
+    else:
+        print(result["tag_data_hex"])
+
+
+# ============ Arguments ============
+
+
+def build_parser() -> argparse.ArgumentParser:
+    parser = argparse.ArgumentParser(prog="spoolbuddy-cli", description="Administer a SpoolBuddy server.")
+    parser.add_argument("--url", default=os.environ.get("SPOOLBUDDY_URL", DEFAULT_URL), help="Server address")
+    commands = parser.add_subparsers(dest="command", required=True)
+
+    spools = commands.add_parser("spools", help="List, add and import spools").add_subparsers(
+        dest="spools_command", required=True
+    )
+    list_spools = spools.add_parser("list", help="List spools")
+    list_spools.add_argument("--all", action="store_true", help="Include archived spools")
+    list_spools.add_argument("--json", action="store_true", help="Print JSON")
+    list_spools.set_defaults(func=cmd_spools_list)
+
+    add_spool = spools.add_parser("add", help="Add a spool")
+    add_spool.add_argument("--material", required=True)
+    add_spool.add_argument("--brand")
+    add_spool.add_argument("--subtype")
+    add_spool.add_argument("--color-name")
+    add_spool.add_argument("--rgba", help="Color as RRGGBB or RRGGBBAA")
+    add_spool.add_argument("--label-weight", type=int, help="Filament weight on the label, in grams")
+    add_spool.add_argument("--weight", type=int, help="Current gross weight, in grams")
+    add_spool.add_argument("--location")
+    add_spool.add_argument("--tag-id")
+    add_spool.add_argument("--note")
+    add_spool.set_defaults(func=cmd_spools_add)
+
+    import_spools = spools.add_parser("import", help="Import spools from a CSV file with spool field columns")
+    import_spools.add_argument("file")
+    import_spools.add_argument("--dry-run", action="store_true", help="Only validate the file")
+    import_spools.add_argument(
+        "--skip-invalid", action="store_true", help="Import the valid rows of a file with errors"
+    )
+    import_spools.set_defaults(func=cmd_spools_import)
+
+    backup = commands.add_parser("backup", help="Back up the database")
+    backup.add_argument("--keep", type=int, default=10, help="Backups to keep on the server")
+    backup.add_argument("--output", help="Also download the backup to this file")
+    backup.add_argument("--no-wait", action="store_true", help="Print the job ID instead of waiting")
+    backup.set_defaults(func=cmd_backup)
+
+    printers = commands.add_parser("printers", help="List, discover and add printers").add_subparsers(
+        dest="printers_command", required=True
+    )
+    list_printers = printers.add_parser("list", help="List printers")
+    list_printers.add_argument("--json", action="store_true", help="Print JSON")
+    list_printers.set_defaults(func=cmd_printers_list)
+
+    discover = printers.add_parser("discover", help="Find printers on the network")
+    discover.add_argument("--timeout", type=float, default=DISCOVERY_SECONDS, help="Seconds to listen")
+    discover.set_defaults(func=cmd_printers_discover)
+
+    pair = printers.add_parser("pair", help="Add a printer and connect to it")
+    pair.add_argument("serial")
+    pair.add_argument("--access-code", required=True, help="LAN access code from the printer's screen")
+    pair.add_argument("--ip", help="Printer address, discovered when not given")
+    pair.add_argument("--name")
+    pair.add_argument("--model")
+    pair.add_argument("--no-auto-connect", action="store_true", help="Don't reconnect on server start")
+    pair.add_argument("--timeout", type=float, default=DISCOVERY_SECONDS, help="Seconds to listen for the printer")
+    pair.set_defaults(func=cmd_printers_pair)
+
+    add_manual = printers.add_parser(
+        "add-manual", help="Add a printer that doesn't connect, to log usage against by hand"
+    )
+    add_manual.add_argument("serial", help="Any unique ID for the printer")
+    add_manual.add_argument("--name")
+    add_manual.add_argument("--model")
+    add_manual.set_defaults(func=cmd_printers_add_manual)
+
+    tags = commands.add_parser("tags", help="Convert NFC tags").add_subparsers(dest="tags_command", required=True)
+    convert = tags.add_parser("convert", help="Re-encode a tag dump in another format, for writing to an NTAG sticker")
+    convert.add_argument("file", help="Tag dump (Flipper Zero .nfc, Proxmark3 .eml or .bin)")
+    convert.add_argument(
+        "--format",
+        default="OpenPrintTag",
+        choices=["OpenPrintTag", "SpoolEaseV2", "OpenSpool", "OpenTag3D"],
+        help="Target format",
+    )
+    convert.add_argument("--source-uid", help="UID of the dumped tag, if the dump doesn't contain it")
+    convert.add_argument("--uid", help="UID of the tag to write, for formats that embed it")
+    convert.add_argument("--output", help="Write the tag memory (from page 4) to this file instead of printing hex")
+    convert.add_argument("--json", action="store_true", help="Print JSON")
+    convert.set_defaults(func=cmd_tags_convert)
+
+    events = commands.add_parser("events", help="Follow live events")
+    events.add_argument("--type", action="append", help="Only this event type (repeatable)")
+    events.add_argument("--json", action="store_true", help="Print raw JSON, one event per line")
+    events.set_defaults(func=cmd_events)
+
+    return parser
+
+
+def main(argv: list[str] | None = None) -> int:
+    args = build_parser().parse_args(argv)
+    try:
+        args.func(Api(args.url), args)
+    except CliError as e:
+        print(f"error: {e}", file=sys.stderr)
+        return 1
+    except KeyboardInterrupt:
+        return 130
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
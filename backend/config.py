@@ -1,4 +1,5 @@
 from pathlib import Path
+from typing import Literal
 
 from pydantic_settings import BaseSettings
 
@@ -18,13 +19,76 @@ class Settings(BaseSettings):
 
     # Database
     database_path: Path = Path("spoolbuddy.db")
+    # Write-ahead log, needed by replicators such as Litestream
+    database_wal: bool = True
+    # Checkpoint the WAL into the database file (0 = leave it to SQLite's automatic checkpoints)
+    wal_checkpoint_interval_minutes: float = 5
+    # Pages in the WAL before SQLite checkpoints on its own (0 = off, when a replicator checkpoints)
+    wal_autocheckpoint_pages: int = 1000
+    # Prometheus metrics of a replication sidecar (e.g. Litestream's -addr), shown in the DB status
+    replication_metrics_url: str | None = None
+    # Log statements running longer than this, without their parameters (0 = off)
+    slow_query_ms: float = 250
+
+    # Demo mode (`python main.py --demo`): seeded in-memory database and simulated printers
+    demo: bool = False
 
     # Static files (frontend)
     static_dir: Path = Path("../frontend/dist")
+    # Frontend packed with `python -m services.static_assets`, served from memory instead of static_dir
+    static_bundle: Path | None = None
 
     # Project root (for git operations)
     project_root: Path = Path(__file__).parent.parent
 
+    # Data retention in days (0 = keep forever). AMS sensor history follows
+    # the retention set with the AMS thresholds in the UI.
+    weight_history_retention_days: int = 90  # Older readings are thinned to one per spool per day
+    webhook_delivery_retention_days: int = 30
+    crash_report_retention_days: int = 180
+    job_retention_days: int = 30  # Finished background jobs
+
+    # How long a spool change made from the device (scale sync, tag link) can be undone
+    undo_window_minutes: float = 10
+
+    # Scale readings are checked against the usage tracked since the previous reading; a
+    # difference beyond both tolerances becomes a reconciliation suggestion
+    reconciliation_interval_minutes: float = 15  # 0 = only when asked through the API
+    reconciliation_tolerance_grams: float = 20
+    reconciliation_tolerance_percent: float = 10  # Of the tracked usage
+
+    # Background jobs; database backups are written to backup_dir
+    backup_dir: Path = Path("backups")
+
+    # Minimum time between printer_state broadcasts per printer, per client class
+    # (0 = every report). State changes such as a print starting are sent right away.
+    printer_state_interval_web_ms: int = 500
+    printer_state_interval_device_ms: int = 1000
+
+    # Refuse /ws/ui connections without an API key (X-API-Key, Authorization: Bearer or
+    # ?token=). Without one the web UI falls back to the /api/events stream.
+    ws_require_api_key: bool = False
+
+    # Accept any printer TLS certificate instead of pinning it on first connect
+    printer_tls_insecure: bool = False
+
+    # Database maintenance (retention, ANALYZE and VACUUM)
+    maintenance_interval_hours: float = 24
+    maintenance_vacuum: bool = True
+
+    # Printer, spool and device events over MQTT for local integrations: "broker" runs
+    # an embedded broker, "bridge" publishes to the broker at mqtt_bridge_host
+    mqtt_events: Literal["off", "broker", "bridge"] = "off"
+    mqtt_topic_prefix: str = "spoolbuddy"
+    mqtt_broker_host: str = "0.0.0.0"  # nosec B104
+    mqtt_broker_port: int = 1883
+    mqtt_bridge_host: str | None = None
+    mqtt_bridge_port: int = 1883
+    mqtt_bridge_tls: bool = False
+    # Required from clients of the embedded broker, used to log in to the bridged broker
+    mqtt_username: str | None = None
+    mqtt_password: str | None = None
+
     class Config:
         env_prefix = "SPOOLBUDDY_"
 
@@ -1,3 +1,3 @@
-from .database import Database, get_db
+from .database import Database, SpoolVersionConflict, TagInUse, close_db, get_db
 
-__all__ = ["Database", "get_db"]
+__all__ = ["Database", "SpoolVersionConflict", "TagInUse", "close_db", "get_db"]
@@ -1,3 +1,7 @@
+import base64
+import binascii
+import json
+import re
 import time
 import uuid
 from pathlib import Path
@@ -5,6 +9,10 @@ from pathlib import Path
 import aiosqlite
 from config import settings
 from models import Printer, PrinterCreate, PrinterUpdate, Spool, SpoolCreate, SpoolUpdate
+from services.colors import stored_hue_lightness
+from services.perf import TimedConnection
+from services.report_periods import period_key
+from services.slicer import parse_slicer_filament
 
 SCHEMA = """
 -- Spools table
@@ -12,6 +20,7 @@ CREATE TABLE IF NOT EXISTS spools (
     id TEXT PRIMARY KEY,
     spool_number INTEGER UNIQUE,
     tag_id TEXT UNIQUE,
+    tag_uid TEXT,  -- tag_id as canonical hex (see normalize_tag_id), for lookups by any UID form
     material TEXT NOT NULL,
     subtype TEXT,
     color_name TEXT,
@@ -22,8 +31,11 @@ CREATE TABLE IF NOT EXISTS spools (
     weight_new INTEGER,
     weight_current INTEGER,
     weight_used REAL DEFAULT 0,
+    price REAL,
     slicer_filament TEXT,
     slicer_filament_name TEXT,
+    slicer_setting_id TEXT,
+    slicer_filament_id TEXT,
     location TEXT,
     note TEXT,
     added_time INTEGER,
@@ -34,7 +46,18 @@ CREATE TABLE IF NOT EXISTS spools (
     data_origin TEXT,
     tag_type TEXT,
     ext_has_k INTEGER DEFAULT 0,
+    swap_available INTEGER DEFAULT 0,  -- Listed on the swap/sale list
+    photo_url TEXT,
+    nozzle_temp_min INTEGER,  -- User-tuned slot temperatures, NULL = material defaults
+    nozzle_temp_max INTEGER,
+    color_hue REAL,  -- Derived from rgba (HSL degrees), for sorting and filtering by color
+    color_lightness REAL,  -- Derived from rgba (HSL percent)
+    color_stops TEXT,  -- JSON list of RRGGBBAA colors for multi-color filament, NULL = single color
+    manufactured_at INTEGER,  -- Production date, for shelf life tracking
+    opened_at INTEGER,  -- When the seal was broken, NULL = sealed
     archived_at INTEGER,
+    deleted_at INTEGER,
+    version INTEGER NOT NULL DEFAULT 1,
     created_at INTEGER DEFAULT (strftime('%s', 'now')),
     updated_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
@@ -44,12 +67,23 @@ CREATE TABLE IF NOT EXISTS printers (
     serial TEXT PRIMARY KEY,
     name TEXT,
     model TEXT,
+    printer_type TEXT NOT NULL DEFAULT 'bambu',  -- bambu, or manual (never connects, usage logged by hand)
     ip_address TEXT,
     access_code TEXT,
     last_seen INTEGER,
     config TEXT,
     auto_connect INTEGER DEFAULT 0,
-    nozzle_count INTEGER DEFAULT 1
+    nozzle_count INTEGER DEFAULT 1,
+    deleted_at INTEGER,
+    power_watts REAL,  -- Average power draw while printing, NULL = energy not tracked
+    -- Capability overrides, NULL = use the model's defaults
+    max_nozzle_temp INTEGER,
+    hardened_nozzle INTEGER,
+    enclosed INTEGER,
+    cert_fingerprint TEXT,  -- SHA-256 of the MQTT TLS certificate pinned on first connect
+    -- Reported by the printer on connect (get_version)
+    firmware_version TEXT,
+    hardware_version TEXT
 );
 
 -- K-Profiles table
@@ -74,9 +108,154 @@ CREATE TABLE IF NOT EXISTS usage_history (
     printer_serial TEXT,
     print_name TEXT,
     weight_used REAL,
+    project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL,
+    reason TEXT,  -- Manual adjustment reason, NULL for prints and manual usage
+    member_id INTEGER REFERENCES members(id) ON DELETE SET NULL,  -- Kiosk member who used the filament
     timestamp INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
+-- Estimated energy use per print job (printer's average power draw x print duration)
+CREATE TABLE IF NOT EXISTS print_energy (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    printer_serial TEXT,
+    print_name TEXT,
+    project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL,
+    duration_sec INTEGER NOT NULL,
+    power_watts REAL NOT NULL,
+    energy_kwh REAL NOT NULL,
+    timestamp INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Projects (groups of print jobs and manual usage, e.g. "Voron build")
+CREATE TABLE IF NOT EXISTS projects (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    description TEXT,
+    archived_at INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Spool templates (quick-add presets for spools bought again and again)
+CREATE TABLE IF NOT EXISTS spool_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    material TEXT NOT NULL,
+    subtype TEXT,
+    brand TEXT,
+    color_name TEXT,
+    rgba TEXT,
+    label_weight INTEGER,
+    core_weight INTEGER,
+    price REAL,
+    slicer_filament TEXT,
+    slicer_filament_name TEXT,
+    location TEXT,
+    nozzle_temp_min INTEGER,
+    nozzle_temp_max INTEGER,
+    use_count INTEGER DEFAULT 0,  -- Spools added from the template, for ordering the quick-add list
+    last_used_at INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Spool weight history (scale readings and weights derived from usage)
+CREATE TABLE IF NOT EXISTS weight_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    spool_id TEXT NOT NULL REFERENCES spools(id) ON DELETE CASCADE,
+    weight INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    recorded_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Scale readings that disagree with the usage tracked since the previous reading
+CREATE TABLE IF NOT EXISTS weight_reconciliations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    spool_id TEXT NOT NULL REFERENCES spools(id) ON DELETE CASCADE,
+    measurement_id INTEGER NOT NULL UNIQUE,  -- weight_history row of the disagreeing scale reading
+    previous_weight INTEGER NOT NULL,  -- Previous scale reading (gross)
+    measured_weight INTEGER NOT NULL,
+    tracked_usage REAL NOT NULL,  -- Usage logged between the two readings
+    discrepancy REAL NOT NULL,  -- Measured minus expected, negative = more used than tracked
+    status TEXT NOT NULL DEFAULT 'open',  -- open, accepted, dismissed
+    resolved_grams REAL,  -- Usage logged when accepted
+    measured_at INTEGER NOT NULL,
+    resolved_at INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Filament runout/jam events reported by printers or DIY sensors
+CREATE TABLE IF NOT EXISTS runout_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    printer_serial TEXT NOT NULL,
+    ams_id INTEGER NOT NULL,
+    tray_id INTEGER NOT NULL,
+    spool_id TEXT,  -- Spool assigned to the slot at the time, NULL if none
+    kind TEXT NOT NULL,  -- "runout" or "jam"
+    print_name TEXT,
+    source TEXT,  -- Reporter, e.g. "printer" or "diy-sensor"
+    marked_empty INTEGER DEFAULT 0,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- NFC tags moved from one spool to another; the spool the tag left keeps its history
+CREATE TABLE IF NOT EXISTS tag_moves (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tag_id TEXT NOT NULL,
+    from_spool_id TEXT,  -- NULL if the tag wasn't linked to a spool
+    to_spool_id TEXT NOT NULL,
+    moved_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Every tag write the device reports, with its read-back outcome and quality metrics
+CREATE TABLE IF NOT EXISTS tag_write_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tag_id TEXT NOT NULL,  -- Normalized hex UID
+    spool_id TEXT,  -- Spool the data was written for
+    job_id TEXT,  -- tag_batch job the write belongs to
+    outcome TEXT NOT NULL,  -- verified, write_failed, read_failed, mismatch, too_large
+    pages_written INTEGER NOT NULL DEFAULT 0,
+    retries INTEGER NOT NULL DEFAULT 0,  -- Page writes/reads the device had to retry
+    failed_page INTEGER,
+    rf_signal INTEGER,  -- Raw RF signal reading of the reader, NULL if it can't measure it
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Nozzle installed on each printer extruder (from MQTT)
+CREATE TABLE IF NOT EXISTS printer_nozzles (
+    printer_serial TEXT NOT NULL,
+    extruder_id INTEGER NOT NULL,  -- 0 = right/only, 1 = left
+    diameter TEXT NOT NULL,  -- e.g. "0.4", matches k_profiles.nozzle_diameter
+    nozzle_type TEXT,  -- e.g. "hardened_steel", "stainless_steel"
+    updated_at INTEGER DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (printer_serial, extruder_id)
+);
+
+-- Nozzle swaps detected on printers
+CREATE TABLE IF NOT EXISTS nozzle_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    printer_serial TEXT NOT NULL,
+    extruder_id INTEGER NOT NULL,
+    old_diameter TEXT,
+    old_type TEXT,
+    new_diameter TEXT NOT NULL,
+    new_type TEXT,
+    changed_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- AMS units seen on printers, by serial number (ams_id can change after a power cycle)
+CREATE TABLE IF NOT EXISTS ams_units (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    serial TEXT NOT NULL UNIQUE,
+    printer_serial TEXT NOT NULL,
+    ams_id INTEGER,  -- Current position on the printer, NULL = no longer attached
+    module TEXT,  -- get_version module type: ams, ams_f1 (AMS Lite), n3f (AMS 2 Pro), n3s (AMS HT)
+    product_name TEXT,
+    firmware_version TEXT,
+    hardware_version TEXT,
+    humidity_sensor INTEGER,  -- Reports humidity, NULL = not known yet
+    first_seen INTEGER DEFAULT (strftime('%s', 'now')),
+    last_seen INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
 -- Spool-to-AMS slot assignments (persistent mapping)
 CREATE TABLE IF NOT EXISTS spool_assignments (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -128,6 +307,21 @@ CREATE TABLE IF NOT EXISTS ams_sensor_history (
     recorded_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
+-- AMS slot change ledger (spool inserted/removed/changed, filament loaded/unloaded)
+CREATE TABLE IF NOT EXISTS ams_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    printer_serial TEXT NOT NULL,
+    ams_id INTEGER NOT NULL,
+    tray_id INTEGER NOT NULL,
+    event TEXT NOT NULL,  -- inserted, removed, changed, loaded, unloaded
+    extruder INTEGER,  -- For loaded/unloaded on dual-nozzle printers
+    spool_id TEXT,  -- Spool assigned to the slot at the time
+    tray_type TEXT,
+    tray_color TEXT,
+    tray_info_idx TEXT,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
 -- API keys table
 CREATE TABLE IF NOT EXISTS api_keys (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -142,15 +336,215 @@ CREATE TABLE IF NOT EXISTS api_keys (
     created_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
+-- Webhooks (external automation endpoints)
+CREATE TABLE IF NOT EXISTS webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT,
+    events TEXT NOT NULL,
+    enabled INTEGER DEFAULT 1,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Webhook delivery log (for debugging integrations)
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT,
+    status_code INTEGER,
+    success INTEGER DEFAULT 0,
+    attempts INTEGER DEFAULT 0,
+    error TEXT,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Klipper printers followed through Moonraker (active spool + print history)
+CREATE TABLE IF NOT EXISTS moonraker_printers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,  -- Moonraker base URL, e.g. http://voron.local:7125
+    api_key TEXT,
+    enabled INTEGER DEFAULT 1,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Notification channels (ntfy, Telegram, Discord)
+CREATE TABLE IF NOT EXISTS notification_channels (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    config TEXT NOT NULL,
+    events TEXT NOT NULL,
+    enabled INTEGER DEFAULT 1,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Crash reports uploaded by devices on the boot after a panic or watchdog reset
+CREATE TABLE IF NOT EXISTS crash_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    device_id TEXT NOT NULL,
+    firmware_version TEXT,
+    reset_reason TEXT NOT NULL,
+    panic_message TEXT,
+    task TEXT,
+    backtrace TEXT,
+    crashed_at INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Background jobs (imports, backups, reports); params and result are JSON
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',  -- queued, running, completed, failed, cancelled
+    params TEXT,
+    progress INTEGER DEFAULT 0,
+    total INTEGER,
+    message TEXT,
+    result TEXT,
+    error TEXT,
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    started_at INTEGER,
+    finished_at INTEGER
+);
+
+-- Stocktakes (inventory counts); report is the JSON snapshot taken when one is completed
+CREATE TABLE IF NOT EXISTS stocktakes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT,
+    status TEXT NOT NULL DEFAULT 'open',  -- open, completed, cancelled
+    report TEXT,
+    started_at INTEGER DEFAULT (strftime('%s', 'now')),
+    finished_at INTEGER
+);
+
+-- Spools counted in a stocktake, one row per spool (a rescan replaces it)
+CREATE TABLE IF NOT EXISTS stocktake_scans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stocktake_id INTEGER NOT NULL REFERENCES stocktakes(id) ON DELETE CASCADE,
+    spool_id TEXT NOT NULL,
+    tag_id TEXT,
+    weight REAL,  -- Gross scale reading, null if the spool was only scanned
+    expected_remaining REAL,  -- Filament left according to the inventory at the time of the scan
+    source TEXT,  -- device, web
+    scanned_at INTEGER,
+    UNIQUE(stocktake_id, spool_id)
+);
+
+-- Filament purchases (orders from a supplier), for restocking and spool costs
+CREATE TABLE IF NOT EXISTS purchases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    supplier TEXT,
+    url TEXT,  -- Product or order page, for reordering
+    order_ref TEXT,
+    purchased_at INTEGER,
+    price REAL,  -- Total paid
+    quantity INTEGER NOT NULL DEFAULT 1,  -- Spools bought
+    note TEXT,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Spools that came from a purchase (a spool belongs to at most one)
+CREATE TABLE IF NOT EXISTS purchase_spools (
+    spool_id TEXT PRIMARY KEY,
+    purchase_id INTEGER NOT NULL REFERENCES purchases(id) ON DELETE CASCADE
+);
+
+-- Read-only inventory links for people without access to the server
+CREATE TABLE IF NOT EXISTS share_links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token TEXT NOT NULL UNIQUE,
+    name TEXT,
+    fields TEXT NOT NULL,  -- JSON list of spool fields shown
+    expires_at INTEGER,
+    revoked_at INTEGER,
+    access_count INTEGER NOT NULL DEFAULT 0,
+    last_accessed_at INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Makerspace members, identified at the kiosk by their NFC badge
+CREATE TABLE IF NOT EXISTS members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    badge_id TEXT UNIQUE,  -- Badge tag ID, in the same form as spools.tag_id
+    note TEXT,
+    active INTEGER NOT NULL DEFAULT 1,  -- Inactive members can't check spools out
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Spools checked out at the kiosk, open until checked back in
+CREATE TABLE IF NOT EXISTS spool_checkouts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    spool_id TEXT NOT NULL REFERENCES spools(id) ON DELETE CASCADE,
+    member_id INTEGER REFERENCES members(id) ON DELETE SET NULL,
+    weight_out REAL,  -- Gross scale reading when checked out
+    checked_out_at INTEGER NOT NULL,
+    checked_in_by INTEGER REFERENCES members(id) ON DELETE SET NULL,
+    weight_in REAL,  -- Gross scale reading when checked in
+    weight_used REAL,  -- Filament used while checked out, logged as the member's usage
+    checked_in_at INTEGER
+);
+
 -- Indexes
 CREATE INDEX IF NOT EXISTS idx_spools_tag_id ON spools(tag_id);
 CREATE INDEX IF NOT EXISTS idx_spools_material ON spools(material);
 CREATE INDEX IF NOT EXISTS idx_k_profiles_spool ON k_profiles(spool_id);
 CREATE INDEX IF NOT EXISTS idx_usage_history_spool ON usage_history(spool_id);
+CREATE INDEX IF NOT EXISTS idx_usage_history_timestamp ON usage_history(timestamp);
+CREATE INDEX IF NOT EXISTS idx_print_energy_timestamp ON print_energy(timestamp);
+CREATE INDEX IF NOT EXISTS idx_weight_history_spool ON weight_history(spool_id, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_weight_reconciliations_status ON weight_reconciliations(status, measured_at);
+CREATE INDEX IF NOT EXISTS idx_ams_units_printer ON ams_units(printer_serial, ams_id);
+CREATE INDEX IF NOT EXISTS idx_runout_events_printer ON runout_events(printer_serial, created_at);
+CREATE INDEX IF NOT EXISTS idx_tag_moves_from ON tag_moves(from_spool_id);
+CREATE INDEX IF NOT EXISTS idx_tag_moves_to ON tag_moves(to_spool_id);
+CREATE INDEX IF NOT EXISTS idx_tag_write_log_created ON tag_write_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_tag_write_log_job ON tag_write_log(job_id);
+CREATE INDEX IF NOT EXISTS idx_nozzle_changes_printer ON nozzle_changes(printer_serial, changed_at);
 CREATE INDEX IF NOT EXISTS idx_spool_assignments_slot ON spool_assignments(printer_serial, ams_id, tray_id);
 CREATE INDEX IF NOT EXISTS idx_ams_sensor_history_lookup ON ams_sensor_history(printer_serial, ams_id, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_ams_events_printer ON ams_events(printer_serial, created_at);
+CREATE INDEX IF NOT EXISTS idx_ams_events_spool ON ams_events(spool_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_crash_reports_device ON crash_reports(device_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_jobs_created ON jobs(created_at);
+CREATE INDEX IF NOT EXISTS idx_purchase_spools_purchase ON purchase_spools(purchase_id);
+CREATE INDEX IF NOT EXISTS idx_spool_checkouts_spool ON spool_checkouts(spool_id, checked_in_at);
+CREATE INDEX IF NOT EXISTS idx_spool_checkouts_member ON spool_checkouts(member_id, checked_out_at);
+
+-- Full-text search over spools, printers and projects, kept in step by triggers (see SEARCH_SOURCES)
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    kind UNINDEXED,
+    ref_id UNINDEXED,
+    title,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
 """
 
+# Searchable records: kind -> (table, key column, title expression, body expression).
+# Expressions use {row} for the row alias (NEW in triggers, the table when rebuilding).
+SEARCH_SOURCES = {
+    "spool": (
+        "spools",
+        "id",
+        "'#' || COALESCE({row}.spool_number, '') || ' ' || COALESCE({row}.brand, '') || ' ' || {row}.material"
+        " || ' ' || COALESCE({row}.subtype, '') || ' ' || COALESCE({row}.color_name, '')",
+        "COALESCE({row}.note, '') || ' ' || COALESCE({row}.location, '') || ' '"
+        " || COALESCE({row}.slicer_filament_name, '')",
+    ),
+    "printer": (
+        "printers",
+        "serial",
+        "COALESCE({row}.name, {row}.serial)",
+        "COALESCE({row}.model, '') || ' ' || {row}.serial",
+    ),
+    "project": ("projects", "id", "{row}.name", "COALESCE({row}.description, '')"),
+}
+
 # Default spool catalog data (name, weight in grams)
 DEFAULT_SPOOL_CATALOG = [
     ("3D FilaPrint - Cardboard", 210),
@@ -395,17 +789,80 @@ DEFAULT_COLOR_CATALOG = [
 ]
 
 
+def normalize_tag_id(tag_id: str | None) -> str | None:
+    """Normalize a tag UID to uppercase hex.
+
+    Tags are stored either as base64 (SpoolEase convention) or as hex with
+    optional colon/space separators, so the same physical tag can appear in
+    different forms.
+    """
+    if not tag_id:
+        return None
+    compact = tag_id.replace(":", "").replace(" ", "").replace("-", "")
+    if len(compact) % 2 == 0 and all(c in "0123456789abcdefABCDEF" for c in compact):
+        return compact.upper()
+    try:
+        padded = tag_id + "=" * (-len(tag_id) % 4)
+        return base64.urlsafe_b64decode(padded).hex().upper()
+    except (ValueError, binascii.Error):
+        return tag_id.upper()
+
+
+def tag_uid_candidates(uid: str) -> list[str]:
+    """Canonical hex forms a reader's UID may be stored under: as given, then byte-reversed.
+
+    Some readers report UIDs least significant byte first.
+    """
+    canonical = normalize_tag_id(uid)
+    if not canonical:
+        return []
+    candidates = [canonical]
+    if len(canonical) % 2 == 0 and all(c in "0123456789ABCDEF" for c in canonical):
+        reversed_uid = bytes.fromhex(canonical)[::-1].hex().upper()
+        if reversed_uid != canonical:
+            candidates.append(reversed_uid)
+    return candidates
+
+
+def _color_stops_column(stops: list[str] | None) -> str | None:
+    """A spool's color stops as stored in the color_stops column."""
+    return json.dumps(stops) if stops else None
+
+
+class SpoolVersionConflict(Exception):
+    """Raised when a spool update is based on a stale version."""
+
+    def __init__(self, current: Spool):
+        super().__init__(f"Spool {current.id} is at version {current.version}")
+        self.current = current
+
+
+class TagInUse(Exception):
+    """Raised when a tag is linked to another active spool."""
+
+    def __init__(self, tag_id: str, holder: Spool):
+        super().__init__(f"Tag {tag_id} is linked to spool {holder.id}")
+        self.tag_id = tag_id
+        self.holder = holder
+
+
 class Database:
     """Async SQLite database wrapper."""
 
     def __init__(self, db_path: Path):
         self.db_path = db_path
-        self._connection: aiosqlite.Connection | None = None
+        self._connection: TimedConnection | None = None
+        self.last_checkpoint: dict | None = None
 
     async def connect(self):
         """Connect to database and run migrations."""
-        self._connection = await aiosqlite.connect(self.db_path)
-        self._connection.row_factory = aiosqlite.Row
+        connection = await aiosqlite.connect(self.db_path)
+        connection.row_factory = aiosqlite.Row
+        self._connection = TimedConnection(connection)
+        # Calendar month/week of a timestamp in a timezone, for reports
+        await self._connection.create_function("local_period", 4, period_key, deterministic=True)
+        if settings.database_wal:
+            await self._enable_wal()
         await self._connection.executescript(SCHEMA)
         await self._connection.commit()
 
@@ -418,6 +875,18 @@ class Database:
         # Seed color catalog with defaults if empty
         await self.seed_color_catalog()
 
+    async def _enable_wal(self):
+        """Switch to write-ahead logging, so readers and a replicator don't block writers."""
+        async with self.conn.execute("PRAGMA journal_mode=WAL") as cursor:
+            mode = (await cursor.fetchone())[0]
+        # In-memory databases stay in "memory" mode
+        if mode == "wal":
+            # NORMAL is durable in WAL mode except for the last commits on power loss
+            await self.conn.execute("PRAGMA synchronous=NORMAL")
+            await self.conn.execute(f"PRAGMA wal_autocheckpoint={int(settings.wal_autocheckpoint_pages)}")
+        # Replicators hold short locks on the database while copying the WAL
+        await self.conn.execute("PRAGMA busy_timeout=5000")
+
     async def _run_migrations(self):
         """Run database migrations for new columns."""
         # Check if spool_number column exists
@@ -450,14 +919,100 @@ class Database:
             await self.conn.execute("ALTER TABLE spools ADD COLUMN slicer_filament_name TEXT")
             await self.conn.commit()
 
+        if "swap_available" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN swap_available INTEGER DEFAULT 0")
+            await self.conn.commit()
+
+        if "photo_url" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN photo_url TEXT")
+            await self.conn.commit()
+
+        if "nozzle_temp_min" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN nozzle_temp_min INTEGER")
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN nozzle_temp_max INTEGER")
+            await self.conn.commit()
+
+        if "color_hue" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN color_hue REAL")
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN color_lightness REAL")
+            async with self.conn.execute("SELECT id, rgba FROM spools WHERE rgba IS NOT NULL") as cursor:
+                rows = await cursor.fetchall()
+            for row in rows:
+                await self.conn.execute(
+                    "UPDATE spools SET color_hue = ?, color_lightness = ? WHERE id = ?",
+                    (*stored_hue_lightness(row["rgba"]), row["id"]),
+                )
+            await self.conn.commit()
+
+        if "color_stops" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN color_stops TEXT")
+            await self.conn.commit()
+
+        if "slicer_setting_id" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN slicer_setting_id TEXT")
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN slicer_filament_id TEXT")
+            # Resolve existing free-text slicer_filament values into preset IDs
+            async with self.conn.execute(
+                "SELECT id, slicer_filament FROM spools WHERE slicer_filament IS NOT NULL"
+            ) as cursor:
+                rows = await cursor.fetchall()
+            for row in rows:
+                setting_id, filament_id = parse_slicer_filament(row["slicer_filament"])
+                if setting_id:
+                    await self.conn.execute(
+                        "UPDATE spools SET slicer_setting_id = ?, slicer_filament_id = ? WHERE id = ?",
+                        (setting_id, filament_id, row["id"]),
+                    )
+            await self.conn.commit()
+
         if "weight_used" not in columns:
             await self.conn.execute("ALTER TABLE spools ADD COLUMN weight_used REAL DEFAULT 0")
             await self.conn.commit()
 
+        if "price" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN price REAL")
+            await self.conn.commit()
+
         if "archived_at" not in columns:
             await self.conn.execute("ALTER TABLE spools ADD COLUMN archived_at INTEGER")
             await self.conn.commit()
 
+        if "deleted_at" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN deleted_at INTEGER")
+            await self.conn.commit()
+
+        if "version" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN version INTEGER NOT NULL DEFAULT 1")
+            await self.conn.commit()
+
+        if "opened_at" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN manufactured_at INTEGER")
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN opened_at INTEGER")
+            await self.conn.commit()
+
+        if "tag_uid" not in columns:
+            await self.conn.execute("ALTER TABLE spools ADD COLUMN tag_uid TEXT")
+            async with self.conn.execute("SELECT id, tag_id FROM spools WHERE tag_id IS NOT NULL") as cursor:
+                rows = await cursor.fetchall()
+            for row in rows:
+                await self.conn.execute(
+                    "UPDATE spools SET tag_uid = ? WHERE id = ?", (normalize_tag_id(row["tag_id"]), row["id"])
+                )
+            await self.conn.commit()
+        await self.conn.execute("CREATE INDEX IF NOT EXISTS idx_spools_tag_uid ON spools(tag_uid)")
+        await self.conn.commit()
+
+        # Bump the spool version on every write so clients can detect concurrent edits,
+        # regardless of which code path modified the row
+        await self.conn.execute("""
+            CREATE TRIGGER IF NOT EXISTS spools_bump_version AFTER UPDATE ON spools
+            WHEN NEW.version = OLD.version
+            BEGIN
+                UPDATE spools SET version = OLD.version + 1 WHERE id = NEW.id;
+            END
+        """)
+        await self.conn.commit()
+
         # Check printers table for nozzle_count
         async with self.conn.execute("PRAGMA table_info(printers)") as cursor:
             printer_columns = [row["name"] for row in await cursor.fetchall()]
@@ -466,13 +1021,100 @@ class Database:
             await self.conn.execute("ALTER TABLE printers ADD COLUMN nozzle_count INTEGER DEFAULT 1")
             await self.conn.commit()
 
+        if "deleted_at" not in printer_columns:
+            await self.conn.execute("ALTER TABLE printers ADD COLUMN deleted_at INTEGER")
+            await self.conn.commit()
+
+        if "power_watts" not in printer_columns:
+            await self.conn.execute("ALTER TABLE printers ADD COLUMN power_watts REAL")
+            await self.conn.commit()
+
+        for column in ("max_nozzle_temp", "hardened_nozzle", "enclosed"):
+            if column not in printer_columns:
+                await self.conn.execute(f"ALTER TABLE printers ADD COLUMN {column} INTEGER")
+                await self.conn.commit()
+
+        if "cert_fingerprint" not in printer_columns:
+            await self.conn.execute("ALTER TABLE printers ADD COLUMN cert_fingerprint TEXT")
+            await self.conn.commit()
+
+        for column in ("firmware_version", "hardware_version"):
+            if column not in printer_columns:
+                await self.conn.execute(f"ALTER TABLE printers ADD COLUMN {column} TEXT")
+                await self.conn.commit()
+
+        if "printer_type" not in printer_columns:
+            await self.conn.execute("ALTER TABLE printers ADD COLUMN printer_type TEXT NOT NULL DEFAULT 'bambu'")
+            await self.conn.commit()
+
+        async with self.conn.execute("PRAGMA table_info(usage_history)") as cursor:
+            usage_columns = [row["name"] for row in await cursor.fetchall()]
+
+        if "project_id" not in usage_columns:
+            await self.conn.execute(
+                "ALTER TABLE usage_history ADD COLUMN project_id INTEGER REFERENCES projects(id) ON DELETE SET NULL"
+            )
+            await self.conn.commit()
+        await self.conn.execute("CREATE INDEX IF NOT EXISTS idx_usage_history_project ON usage_history(project_id)")
+        await self.conn.commit()
+
+        if "reason" not in usage_columns:
+            await self.conn.execute("ALTER TABLE usage_history ADD COLUMN reason TEXT")
+            await self.conn.commit()
+
+        if "member_id" not in usage_columns:
+            await self.conn.execute(
+                "ALTER TABLE usage_history ADD COLUMN member_id INTEGER REFERENCES members(id) ON DELETE SET NULL"
+            )
+            await self.conn.commit()
+
+        async with self.conn.execute("PRAGMA table_info(crash_reports)") as cursor:
+            crash_columns = [row["name"] for row in await cursor.fetchall()]
+
+        if "crashed_at" not in crash_columns:
+            await self.conn.execute("ALTER TABLE crash_reports ADD COLUMN crashed_at INTEGER")
+            await self.conn.commit()
+
+        await self._create_search_triggers()
+
+    async def _create_search_triggers(self):
+        """Keep search_index in step with the searchable tables, indexing existing rows once."""
+        async with self.conn.execute(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'trigger' AND name LIKE 'search_%'"
+        ) as cursor:
+            existing = (await cursor.fetchone())[0]
+
+        for kind, (table, key, title, body) in SEARCH_SOURCES.items():
+            select = f"SELECT '{kind}', NEW.{key}, {title.format(row='NEW')}, {body.format(row='NEW')}"
+            remove = f"DELETE FROM search_index WHERE kind = '{kind}' AND ref_id = OLD.{key}"
+            await self.conn.execute(f"""
+                CREATE TRIGGER IF NOT EXISTS search_{table}_insert AFTER INSERT ON {table} BEGIN
+                    INSERT INTO search_index (kind, ref_id, title, body) {select};
+                END
+            """)
+            await self.conn.execute(f"""
+                CREATE TRIGGER IF NOT EXISTS search_{table}_update AFTER UPDATE ON {table} BEGIN
+                    {remove};
+                    INSERT INTO search_index (kind, ref_id, title, body) {select};
+                END
+            """)
+            await self.conn.execute(f"""
+                CREATE TRIGGER IF NOT EXISTS search_{table}_delete AFTER DELETE ON {table} BEGIN
+                    {remove};
+                END
+            """)
+        await self.conn.commit()
+
+        if existing < 3 * len(SEARCH_SOURCES):
+            await self.rebuild_search_index()
+
     async def disconnect(self):
         """Close database connection."""
         if self._connection:
             await self._connection.close()
 
     @property
-    def conn(self) -> aiosqlite.Connection:
+    def conn(self) -> TimedConnection:
         if not self._connection:
             raise RuntimeError("Database not connected")
         return self._connection
@@ -487,22 +1129,41 @@ class Database:
                 SELECT MAX(uh.timestamp) FROM usage_history uh WHERE uh.spool_id = s.id
             ) as last_used_time
             FROM spools s
+            WHERE s.deleted_at IS NULL
             ORDER BY s.created_at DESC
         """
         async with self.conn.execute(query) as cursor:
             rows = await cursor.fetchall()
             return [Spool(**dict(row)) for row in rows]
 
-    async def get_spool(self, spool_id: str) -> Spool | None:
-        """Get a single spool by ID."""
-        async with self.conn.execute("SELECT * FROM spools WHERE id = ?", (spool_id,)) as cursor:
+    async def get_spool(self, spool_id: str, include_deleted: bool = False) -> Spool | None:
+        """Get a single spool by ID.
+
+        Args:
+            spool_id: Spool UUID
+            include_deleted: If True, also return spools that are in the trash
+        """
+        if include_deleted:
+            query = "SELECT * FROM spools WHERE id = ?"
+        else:
+            query = "SELECT * FROM spools WHERE id = ? AND deleted_at IS NULL"
+        async with self.conn.execute(query, (spool_id,)) as cursor:
             row = await cursor.fetchone()
             return Spool(**dict(row)) if row else None
 
     async def create_spool(self, spool: SpoolCreate) -> Spool:
-        """Create a new spool."""
+        """Create a new spool.
+
+        Raises TagInUse if the spool's tag is linked to another active spool.
+        """
         spool_id = str(uuid.uuid4())
         now = int(time.time())
+        if spool.tag_id:
+            await self._claim_tag(spool.tag_id, spool_id)
+
+        setting_id, filament_id = spool.slicer_setting_id, spool.slicer_filament_id
+        if not setting_id:
+            setting_id, filament_id = parse_slicer_filament(spool.slicer_filament)
 
         # Get next spool_number (max + 1)
         async with self.conn.execute("SELECT COALESCE(MAX(spool_number), 0) + 1 FROM spools") as cursor:
@@ -510,14 +1171,18 @@ class Database:
             spool_number = row[0]
 
         await self.conn.execute(
-            """INSERT INTO spools (id, spool_number, tag_id, material, subtype, color_name, rgba, brand,
-               label_weight, core_weight, weight_new, weight_current, slicer_filament, slicer_filament_name,
-               location, note, data_origin, tag_type, ext_has_k, created_at, updated_at)
-               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)""",
+            """INSERT INTO spools (id, spool_number, tag_id, tag_uid, material, subtype, color_name, rgba, brand,
+               label_weight, core_weight, weight_new, weight_current, price, slicer_filament, slicer_filament_name,
+               slicer_setting_id, slicer_filament_id, location, note, data_origin, tag_type, ext_has_k,
+               swap_available, photo_url, nozzle_temp_min, nozzle_temp_max, color_hue, color_lightness,
+               color_stops, manufactured_at, opened_at, created_at, updated_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                       ?, ?)""",
             (
                 spool_id,
                 spool_number,
                 spool.tag_id,
+                normalize_tag_id(spool.tag_id),
                 spool.material,
                 spool.subtype,
                 spool.color_name,
@@ -527,13 +1192,24 @@ class Database:
                 spool.core_weight,
                 spool.weight_new,
                 spool.weight_current,
+                spool.price,
                 spool.slicer_filament,
                 spool.slicer_filament_name,
+                setting_id,
+                filament_id,
                 spool.location,
                 spool.note,
                 spool.data_origin,
                 spool.tag_type,
                 1 if spool.ext_has_k else 0,
+                1 if spool.swap_available else 0,
+                spool.photo_url,
+                spool.nozzle_temp_min,
+                spool.nozzle_temp_max,
+                *stored_hue_lightness(spool.rgba),
+                _color_stops_column(spool.color_stops),
+                spool.manufactured_at,
+                spool.opened_at,
                 now,
                 now,
             ),
@@ -541,22 +1217,47 @@ class Database:
         await self.conn.commit()
         return await self.get_spool(spool_id)
 
-    async def update_spool(self, spool_id: str, spool: SpoolUpdate) -> Spool | None:
-        """Update an existing spool."""
+    @staticmethod
+    def _spool_update_columns(spool: SpoolUpdate) -> dict:
+        """Column values for the fields set on a spool update."""
+        fields = spool.model_dump(exclude_unset=True, exclude={"expected_version"})
+        # Keep the preset mapping in step with slicer_filament unless it was set explicitly
+        if "slicer_filament" in fields and "slicer_setting_id" not in fields:
+            fields["slicer_setting_id"], fields["slicer_filament_id"] = parse_slicer_filament(fields["slicer_filament"])
+        if "rgba" in fields:
+            fields["color_hue"], fields["color_lightness"] = stored_hue_lightness(fields["rgba"])
+        if "color_stops" in fields:
+            fields["color_stops"] = _color_stops_column(fields["color_stops"])
+        if "tag_id" in fields:
+            fields["tag_uid"] = normalize_tag_id(fields["tag_id"])
+        # Convert boolean to int for SQLite
+        for field in ("ext_has_k", "swap_available"):
+            if field in fields:
+                fields[field] = 1 if fields[field] else 0
+        return fields
+
+    async def update_spool(
+        self, spool_id: str, spool: SpoolUpdate, expected_version: int | None = None
+    ) -> Spool | None:
+        """Update an existing spool.
+
+        If expected_version is given, the update only applies when the stored
+        version still matches; otherwise SpoolVersionConflict is raised with
+        the current record. TagInUse is raised if a new tag_id is linked to
+        another active spool.
+        """
         existing = await self.get_spool(spool_id)
         if not existing:
             return None
+        if expected_version is not None and existing.version != expected_version:
+            raise SpoolVersionConflict(existing)
 
-        # Build update query dynamically for non-None fields
-        updates = []
-        values = []
-        for field, value in spool.model_dump(exclude_unset=True).items():
-            updates.append(f"{field} = ?")
-            # Convert boolean to int for SQLite
-            if field == "ext_has_k":
-                values.append(1 if value else 0)
-            else:
-                values.append(value)
+        # Build update query dynamically for the fields that were set
+        columns = self._spool_update_columns(spool)
+        if columns.get("tag_id"):
+            await self._claim_tag(columns["tag_id"], spool_id)
+        updates = [f"{field} = ?" for field in columns]
+        values = list(columns.values())
 
         if updates:
             updates.append("updated_at = ?")
@@ -564,17 +1265,134 @@ class Database:
             values.append(spool_id)
 
             query = f"UPDATE spools SET {', '.join(updates)} WHERE id = ?"  # nosec B608
-            await self.conn.execute(query, values)
+            if expected_version is not None:
+                # Guard against a write that slipped in since the check above
+                query += " AND version = ?"
+                values.append(expected_version)
+            cursor = await self.conn.execute(query, values)
             await self.conn.commit()
+            if cursor.rowcount == 0:
+                current = await self.get_spool(spool_id)
+                if not current:
+                    return None
+                raise SpoolVersionConflict(current)
 
         return await self.get_spool(spool_id)
 
     async def delete_spool(self, spool_id: str) -> bool:
-        """Delete a spool."""
-        cursor = await self.conn.execute("DELETE FROM spools WHERE id = ?", (spool_id,))
+        """Move a spool to the trash (soft delete).
+
+        Usage history and K-profiles are kept so the spool can be restored.
+        """
+        now = int(time.time())
+        cursor = await self.conn.execute(
+            "UPDATE spools SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+            (now, now, spool_id),
+        )
         await self.conn.commit()
         return cursor.rowcount > 0
 
+    async def restore_deleted_spool(self, spool_id: str) -> Spool | None:
+        """Restore a spool from the trash."""
+        now = int(time.time())
+        cursor = await self.conn.execute(
+            "UPDATE spools SET deleted_at = NULL, updated_at = ? WHERE id = ? AND deleted_at IS NOT NULL",
+            (now, spool_id),
+        )
+        await self.conn.commit()
+        if cursor.rowcount == 0:
+            return None
+        return await self.get_spool(spool_id)
+
+    async def get_deleted_spools(self) -> list[Spool]:
+        """Get all spools in the trash, most recently deleted first."""
+        async with self.conn.execute(
+            "SELECT * FROM spools WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [Spool(**dict(row)) for row in rows]
+
+    async def purge_spool(self, spool_id: str) -> bool:
+        """Permanently delete a trashed spool and everything referencing it."""
+        spool = await self.get_spool(spool_id, include_deleted=True)
+        if not spool or not spool.deleted_at:
+            return False
+        await self._purge_spools([spool_id])
+        return True
+
+    async def _purge_spools(self, spool_ids: list[str]) -> None:
+        placeholders = ", ".join("?" for _ in spool_ids)
+        tables = (
+            "usage_history",
+            "weight_history",
+            "k_profiles",
+            "spool_assignments",
+            "runout_events",
+            "purchase_spools",
+            "spool_checkouts",
+        )
+        for table in tables:
+            query = f"DELETE FROM {table} WHERE spool_id IN ({placeholders})"  # nosec B608
+            await self.conn.execute(query, spool_ids)
+        query = f"DELETE FROM spools WHERE id IN ({placeholders})"  # nosec B608
+        await self.conn.execute(query, spool_ids)
+        await self.conn.commit()
+
+    async def bulk_update_spools(
+        self,
+        spool_ids: list[str],
+        spool: SpoolUpdate,
+        archived: bool | None = None,
+        atomic: bool = False,
+    ) -> dict[str, str | None]:
+        """Apply the same partial update to many spools in a single transaction.
+
+        Each spool is updated under its own savepoint, so a failing spool
+        (e.g. a duplicate tag_id) doesn't leave half-applied changes. With
+        atomic=True any failure rolls back the whole batch.
+
+        Returns an error message per spool ID (None = updated).
+        """
+        now = int(time.time())
+        columns = self._spool_update_columns(spool)
+        updates = [f"{field} = ?" for field in columns]
+        values = list(columns.values())
+        if archived is True:
+            # Keep the original archive date of spools that are already archived
+            updates.append("archived_at = COALESCE(archived_at, ?)")
+            values.append(now)
+        elif archived is False:
+            updates.append("archived_at = NULL")
+        updates.append("updated_at = ?")
+        values.append(now)
+        query = f"UPDATE spools SET {', '.join(updates)} WHERE id = ? AND deleted_at IS NULL"  # nosec B608
+
+        results: dict[str, str | None] = {}
+        try:
+            # Explicit transaction, otherwise releasing the first savepoint would commit
+            if not self.conn.in_transaction:
+                await self.conn.execute("BEGIN")
+            for spool_id in dict.fromkeys(spool_ids):
+                await self.conn.execute("SAVEPOINT bulk_item")
+                try:
+                    cursor = await self.conn.execute(query, [*values, spool_id])
+                    results[spool_id] = None if cursor.rowcount else "Spool not found"
+                    await self.conn.execute("RELEASE SAVEPOINT bulk_item")
+                except aiosqlite.Error as e:
+                    await self.conn.execute("ROLLBACK TO SAVEPOINT bulk_item")
+                    await self.conn.execute("RELEASE SAVEPOINT bulk_item")
+                    results[spool_id] = str(e)
+
+            if atomic and any(results.values()):
+                await self.conn.rollback()
+            else:
+                await self.conn.commit()
+        except Exception:
+            await self.conn.rollback()
+            raise
+
+        return results
+
     async def archive_spool(self, spool_id: str) -> Spool | None:
         """Archive a spool by setting archived_at timestamp."""
         now = int(time.time())
@@ -594,29 +1412,71 @@ class Database:
 
         Args:
             tag_id: The tag ID to look up
-            include_archived: If False (default), skip archived spools so recycled tags work
+            include_archived: If False (default), skip archived and trashed spools so recycled tags work
         """
         if include_archived:
             query = "SELECT * FROM spools WHERE tag_id = ?"
         else:
-            query = "SELECT * FROM spools WHERE tag_id = ? AND archived_at IS NULL"
+            query = "SELECT * FROM spools WHERE tag_id = ? AND archived_at IS NULL AND deleted_at IS NULL"
         async with self.conn.execute(query, (tag_id,)) as cursor:
             row = await cursor.fetchone()
             return Spool(**dict(row)) if row else None
 
-    async def get_untagged_spools(self) -> list[Spool]:
-        """Get all spools without a tag_id assigned."""
-        async with self.conn.execute(
-            "SELECT * FROM spools WHERE tag_id IS NULL OR tag_id = '' ORDER BY created_at DESC"
-        ) as cursor:
-            rows = await cursor.fetchall()
-            return [Spool(**dict(row)) for row in rows]
+    async def get_spool_by_tag_uid(self, uid: str, include_archived: bool = False) -> Spool | None:
+        """Get a spool by tag UID in any form a reader reports it.
+
+        Accepts hex with or without colon/space/dash separators, base64, and
+        byte-reversed UIDs; a match in the given byte order wins.
+
+        Args:
+            uid: The tag UID to look up
+            include_archived: If False (default), skip archived and trashed spools
+        """
+        query = "SELECT * FROM spools WHERE tag_uid = ?"
+        if not include_archived:
+            query += " AND archived_at IS NULL AND deleted_at IS NULL"
+        for candidate in tag_uid_candidates(uid):
+            async with self.conn.execute(query, (candidate,)) as cursor:
+                row = await cursor.fetchone()
+                if row:
+                    return Spool(**dict(row))
+        return None
+
+    async def get_spool_by_number(self, spool_number: int) -> Spool | None:
+        """Get an active spool by its spool number."""
+        async with self.conn.execute(
+            "SELECT * FROM spools WHERE spool_number = ? AND archived_at IS NULL AND deleted_at IS NULL",
+            (spool_number,),
+        ) as cursor:
+            row = await cursor.fetchone()
+            return Spool(**dict(row)) if row else None
+
+    async def get_untagged_spools(self) -> list[Spool]:
+        """Get all spools without a tag_id assigned."""
+        async with self.conn.execute(
+            "SELECT * FROM spools WHERE (tag_id IS NULL OR tag_id = '') AND deleted_at IS NULL ORDER BY created_at DESC"
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [Spool(**dict(row)) for row in rows]
+
+    async def _claim_tag(self, tag_id: str, spool_id: str) -> None:
+        """Make a tag free for a spool: recycle it from an archived or trashed spool.
+
+        Raises TagInUse if another active spool has the tag.
+        """
+        holder = await self.get_spool_by_tag(tag_id, include_archived=True)
+        if not holder or holder.id == spool_id:
+            return
+        if holder.archived_at is None and holder.deleted_at is None:
+            raise TagInUse(tag_id, holder)
+        await self.clear_spool_tag(holder.id)
 
     async def clear_spool_tag(self, spool_id: str) -> None:
         """Remove tag_id from a spool (for tag recycling)."""
         now = int(time.time())
         await self.conn.execute(
-            "UPDATE spools SET tag_id = NULL, tag_type = NULL, updated_at = ? WHERE id = ?", (now, spool_id)
+            "UPDATE spools SET tag_id = NULL, tag_uid = NULL, tag_type = NULL, updated_at = ? WHERE id = ?",
+            (now, spool_id),
         )
         await self.conn.commit()
 
@@ -633,14 +1493,18 @@ class Database:
 
         Returns:
             Updated spool on success, None if spool not found
+
+        Raises:
+            TagInUse: The tag is linked to another active spool
         """
         existing = await self.get_spool(spool_id)
         if not existing:
             return None
+        await self._claim_tag(tag_id, spool_id)
 
         now = int(time.time())
-        updates = ["tag_id = ?", "updated_at = ?"]
-        values = [tag_id, now]
+        updates = ["tag_id = ?", "tag_uid = ?", "updated_at = ?"]
+        values = [tag_id, normalize_tag_id(tag_id), now]
 
         if tag_type:
             updates.append("tag_type = ?")
@@ -658,17 +1522,289 @@ class Database:
 
         return await self.get_spool(spool_id)
 
+    async def move_tag(
+        self, spool_id: str, tag_id: str, tag_type: str | None = None, data_origin: str | None = None
+    ) -> tuple[Spool | None, Spool] | None:
+        """Move a tag to a spool, taking it off whichever spool has it.
+
+        The spool the tag leaves keeps its usage and weight history; the move
+        is recorded in tag_moves.
+
+        Returns:
+            (spool the tag was taken from or None, updated spool), None if the spool was not found
+        """
+        if not await self.get_spool(spool_id):
+            return None
+        previous = await self.get_spool_by_tag(tag_id, include_archived=True)
+        if previous and previous.id == spool_id:
+            return None, previous
+        if previous:
+            await self.clear_spool_tag(previous.id)
+        await self.conn.execute(
+            "INSERT INTO tag_moves (tag_id, from_spool_id, to_spool_id) VALUES (?, ?, ?)",
+            (tag_id, previous.id if previous else None, spool_id),
+        )
+        updated = await self.link_tag_to_spool(spool_id, tag_id, tag_type, data_origin)
+        return (await self.get_spool(previous.id, include_deleted=True) if previous else None), updated
+
+    async def get_tag_moves(self, spool_id: str) -> list[dict]:
+        """Tag moves onto or off a spool, newest first."""
+        async with self.conn.execute(
+            """SELECT * FROM tag_moves WHERE from_spool_id = ? OR to_spool_id = ?
+               ORDER BY moved_at DESC, id DESC""",
+            (spool_id, spool_id),
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    # ============ Tag Write Log Operations ============
+
+    async def log_tag_write(
+        self,
+        tag_id: str,
+        outcome: str,
+        spool_id: str | None = None,
+        job_id: str | None = None,
+        pages_written: int = 0,
+        retries: int = 0,
+        failed_page: int | None = None,
+        rf_signal: int | None = None,
+    ) -> dict:
+        """Record a tag write reported by the device."""
+        cursor = await self.conn.execute(
+            """INSERT INTO tag_write_log
+               (tag_id, spool_id, job_id, outcome, pages_written, retries, failed_page, rf_signal, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)""",
+            (tag_id, spool_id, job_id, outcome, pages_written, retries, failed_page, rf_signal, int(time.time())),
+        )
+        await self.conn.commit()
+        async with self.conn.execute("SELECT * FROM tag_write_log WHERE id = ?", (cursor.lastrowid,)) as row_cursor:
+            return dict(await row_cursor.fetchone())
+
+    async def get_tag_writes(
+        self,
+        tag_id: str | None = None,
+        spool_id: str | None = None,
+        job_id: str | None = None,
+        failed_only: bool = False,
+        limit: int = 50,
+    ) -> list[dict]:
+        """Get recent tag writes, newest first."""
+        conditions = []
+        values = []
+        for column, value in (("tag_id", tag_id), ("spool_id", spool_id), ("job_id", job_id)):
+            if value:
+                conditions.append(f"{column} = ?")
+                values.append(value)
+        if failed_only:
+            conditions.append("outcome != 'verified'")
+        where = f"WHERE {' AND '.join(conditions)}" if conditions else ""
+        query = f"SELECT * FROM tag_write_log {where} ORDER BY created_at DESC, id DESC LIMIT ?"  # nosec B608
+        async with self.conn.execute(query, (*values, limit)) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def get_tag_write_stats(self, since: int | None = None) -> dict:
+        """Failure statistics of tag writes, overall and per batch (newest batch first).
+
+        A batch is one tag_batch job; writes made outside a job are grouped
+        under job_id None.
+        """
+        where = "WHERE created_at >= ?" if since is not None else ""
+        values = (since,) if since is not None else ()
+        columns = """COUNT(*) AS writes,
+                     SUM(outcome = 'verified') AS verified,
+                     SUM(outcome != 'verified') AS failed,
+                     COUNT(DISTINCT tag_id) AS tags,
+                     SUM(retries) AS retries,
+                     AVG(retries) AS avg_retries,
+                     AVG(rf_signal) AS avg_rf_signal,
+                     MIN(rf_signal) AS min_rf_signal,
+                     MIN(created_at) AS first_at,
+                     MAX(created_at) AS last_at"""
+
+        def summary(row) -> dict:
+            stats = dict(row)
+            for key in ("verified", "failed", "retries"):
+                stats[key] = stats[key] or 0
+            stats["avg_retries"] = round(stats["avg_retries"] or 0, 2)
+            if stats["avg_rf_signal"] is not None:
+                stats["avg_rf_signal"] = round(stats["avg_rf_signal"], 1)
+            stats["failure_rate"] = round(stats["failed"] / stats["writes"], 3) if stats["writes"] else 0.0
+            return stats
+
+        async with self.conn.execute(f"SELECT {columns} FROM tag_write_log {where}", values) as cursor:  # nosec B608
+            total = summary(await cursor.fetchone())
+        async with self.conn.execute(
+            f"SELECT outcome, COUNT(*) AS count FROM tag_write_log {where} GROUP BY outcome",  # nosec B608
+            values,
+        ) as cursor:
+            outcomes = {row["outcome"]: row["count"] for row in await cursor.fetchall()}
+        async with self.conn.execute(
+            f"""SELECT job_id, {columns} FROM tag_write_log {where}
+                GROUP BY job_id ORDER BY last_at DESC""",  # nosec B608
+            values,
+        ) as cursor:
+            batches = [summary(row) for row in await cursor.fetchall()]
+        return {"total": total, "outcomes": outcomes, "batches": batches}
+
+    async def find_duplicate_spools(self, window_seconds: int = 3600) -> list[dict]:
+        """Find likely duplicate spools among non-archived spools.
+
+        Two spools are considered duplicates if their tag IDs refer to the same
+        physical tag, or if they share brand, material, subtype and color and were
+        created within window_seconds of each other.
+
+        Returns:
+            List of {"reason", "spool_ids"} groups, oldest spool first
+        """
+        async with self.conn.execute(
+            """SELECT * FROM spools WHERE archived_at IS NULL AND deleted_at IS NULL
+               ORDER BY created_at ASC, spool_number ASC"""
+        ) as cursor:
+            spools = [dict(row) for row in await cursor.fetchall()]
+
+        groups = []
+        seen_pairs = set()
+
+        by_tag: dict[str, list[str]] = {}
+        for spool in spools:
+            normalized = normalize_tag_id(spool["tag_id"])
+            if normalized:
+                by_tag.setdefault(normalized, []).append(spool["id"])
+        for spool_ids in by_tag.values():
+            if len(spool_ids) > 1:
+                groups.append({"reason": "same_tag", "spool_ids": spool_ids})
+                seen_pairs.update((a, b) for a in spool_ids for b in spool_ids)
+
+        def attrs(spool: dict) -> tuple:
+            return tuple(
+                (spool[key] or "").strip().lower() for key in ("brand", "material", "subtype", "color_name", "rgba")
+            )
+
+        for i, spool in enumerate(spools):
+            cluster = [spool["id"]]
+            for other in spools[i + 1 :]:
+                if (other["created_at"] or 0) - (spool["created_at"] or 0) > window_seconds:
+                    break
+                if attrs(other) == attrs(spool) and (spool["id"], other["id"]) not in seen_pairs:
+                    cluster.append(other["id"])
+            if len(cluster) > 1:
+                seen_pairs.update((a, b) for a in cluster for b in cluster)
+                groups.append({"reason": "same_attributes", "spool_ids": cluster})
+
+        return groups
+
+    async def merge_spools(self, target_id: str, source_ids: list[str]) -> Spool | None:
+        """Merge source spools into the target spool in a single transaction.
+
+        Usage history, K-profiles and AMS slot assignments are re-pointed to the
+        target, consumption counters are summed, empty target fields are filled
+        from the sources, and the source spools are deleted.
+        """
+        target = await self.get_spool(target_id)
+        if not target:
+            return None
+
+        sources = []
+        for source_id in source_ids:
+            source = await self.get_spool(source_id)
+            if source and source.id != target_id:
+                sources.append(source)
+        if not sources:
+            return target
+
+        placeholders = ", ".join("?" for _ in sources)
+        ids = [s.id for s in sources]
+
+        # Fill empty fields on the target from the sources (oldest data wins)
+        fill_fields = (
+            "tag_id",
+            "tag_type",
+            "subtype",
+            "color_name",
+            "rgba",
+            "brand",
+            "weight_new",
+            "price",
+            "slicer_filament",
+            "slicer_filament_name",
+            "slicer_setting_id",
+            "slicer_filament_id",
+            "location",
+            "note",
+            "data_origin",
+            "photo_url",
+            "nozzle_temp_min",
+            "nozzle_temp_max",
+            "color_stops",
+            "manufactured_at",
+            "opened_at",
+        )
+        updates = {}
+        for field in fill_fields:
+            if getattr(target, field) in (None, ""):
+                for source in sources:
+                    value = getattr(source, field)
+                    if value not in (None, ""):
+                        updates[field] = value
+                        break
+
+        if "rgba" in updates:
+            updates["color_hue"], updates["color_lightness"] = stored_hue_lightness(updates["rgba"])
+        if "color_stops" in updates:
+            updates["color_stops"] = _color_stops_column(updates["color_stops"])
+        if "tag_id" in updates:
+            updates["tag_uid"] = normalize_tag_id(updates["tag_id"])
+
+        # Usage logged against a duplicate still came off the same physical spool
+        updates["consumed_since_add"] = (target.consumed_since_add or 0) + sum(
+            s.consumed_since_add or 0 for s in sources
+        )
+        updates["consumed_since_weight"] = (target.consumed_since_weight or 0) + sum(
+            s.consumed_since_weight or 0 for s in sources
+        )
+        updates["updated_at"] = int(time.time())
+
+        usage_query = f"UPDATE usage_history SET spool_id = ? WHERE spool_id IN ({placeholders})"  # nosec B608
+        weight_query = f"UPDATE weight_history SET spool_id = ? WHERE spool_id IN ({placeholders})"  # nosec B608
+        k_query = f"UPDATE k_profiles SET spool_id = ? WHERE spool_id IN ({placeholders})"  # nosec B608
+        assign_query = f"UPDATE spool_assignments SET spool_id = ? WHERE spool_id IN ({placeholders})"  # nosec B608
+        runout_query = f"UPDATE runout_events SET spool_id = ? WHERE spool_id IN ({placeholders})"  # nosec B608
+        checkout_query = f"UPDATE spool_checkouts SET spool_id = ? WHERE spool_id IN ({placeholders})"  # nosec B608
+        delete_query = f"DELETE FROM spools WHERE id IN ({placeholders})"  # nosec B608
+
+        try:
+            await self.conn.execute(usage_query, [target_id, *ids])
+            await self.conn.execute(weight_query, [target_id, *ids])
+            await self.conn.execute(k_query, [target_id, *ids])
+            await self.conn.execute(assign_query, [target_id, *ids])
+            await self.conn.execute(runout_query, [target_id, *ids])
+            await self.conn.execute(checkout_query, [target_id, *ids])
+            # Delete sources before copying fields so UNIQUE tag_id doesn't conflict
+            await self.conn.execute(delete_query, ids)
+
+            set_clause = ", ".join(f"{k} = ?" for k in updates)
+            query = f"UPDATE spools SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*updates.values(), target_id])
+            await self.conn.commit()
+        except Exception:
+            await self.conn.rollback()
+            raise
+
+        return await self.get_spool(target_id)
+
     # ============ Printer Operations ============
 
     async def get_printers(self) -> list[Printer]:
         """Get all printers."""
-        async with self.conn.execute("SELECT * FROM printers ORDER BY name") as cursor:
+        async with self.conn.execute("SELECT * FROM printers WHERE deleted_at IS NULL ORDER BY name") as cursor:
             rows = await cursor.fetchall()
             return [Printer(**{**dict(row), "auto_connect": bool(row["auto_connect"])}) for row in rows]
 
     async def get_printer(self, serial: str) -> Printer | None:
         """Get a single printer by serial."""
-        async with self.conn.execute("SELECT * FROM printers WHERE serial = ?", (serial,)) as cursor:
+        async with self.conn.execute(
+            "SELECT * FROM printers WHERE serial = ? AND deleted_at IS NULL", (serial,)
+        ) as cursor:
             row = await cursor.fetchone()
             if row:
                 return Printer(**{**dict(row), "auto_connect": bool(row["auto_connect"])})
@@ -679,23 +1815,35 @@ class Database:
         now = int(time.time())
 
         await self.conn.execute(
-            """INSERT INTO printers (serial, name, model, ip_address, access_code, last_seen, auto_connect)
-               VALUES (?, ?, ?, ?, ?, ?, ?)
+            """INSERT INTO printers (serial, name, model, printer_type, ip_address, access_code, last_seen, auto_connect,
+                                     power_watts, max_nozzle_temp, hardened_nozzle, enclosed)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                ON CONFLICT(serial) DO UPDATE SET
                name = excluded.name,
                model = excluded.model,
+               printer_type = excluded.printer_type,
                ip_address = excluded.ip_address,
                access_code = excluded.access_code,
                last_seen = excluded.last_seen,
-               auto_connect = excluded.auto_connect""",
+               auto_connect = excluded.auto_connect,
+               power_watts = COALESCE(excluded.power_watts, printers.power_watts),
+               max_nozzle_temp = COALESCE(excluded.max_nozzle_temp, printers.max_nozzle_temp),
+               hardened_nozzle = COALESCE(excluded.hardened_nozzle, printers.hardened_nozzle),
+               enclosed = COALESCE(excluded.enclosed, printers.enclosed),
+               deleted_at = NULL""",
             (
                 printer.serial,
                 printer.name,
                 printer.model,
+                printer.printer_type.value,
                 printer.ip_address,
                 printer.access_code,
                 now,
                 int(printer.auto_connect),
+                printer.power_watts,
+                printer.max_nozzle_temp,
+                printer.hardened_nozzle,
+                printer.enclosed,
             ),
         )
         await self.conn.commit()
@@ -710,7 +1858,7 @@ class Database:
         updates = []
         values = []
         for field, value in printer.model_dump(exclude_unset=True).items():
-            if field == "auto_connect":
+            if field in ("auto_connect", "hardened_nozzle", "enclosed"):
                 value = int(value) if value is not None else None
             updates.append(f"{field} = ?")
             values.append(value)
@@ -724,11 +1872,62 @@ class Database:
         return await self.get_printer(serial)
 
     async def delete_printer(self, serial: str) -> bool:
-        """Delete a printer."""
-        cursor = await self.conn.execute("DELETE FROM printers WHERE serial = ?", (serial,))
+        """Move a printer to the trash (soft delete)."""
+        cursor = await self.conn.execute(
+            "UPDATE printers SET deleted_at = ? WHERE serial = ? AND deleted_at IS NULL", (int(time.time()), serial)
+        )
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def restore_deleted_printer(self, serial: str) -> Printer | None:
+        """Restore a printer from the trash."""
+        cursor = await self.conn.execute(
+            "UPDATE printers SET deleted_at = NULL WHERE serial = ? AND deleted_at IS NOT NULL", (serial,)
+        )
+        await self.conn.commit()
+        if cursor.rowcount == 0:
+            return None
+        return await self.get_printer(serial)
+
+    async def get_deleted_printers(self) -> list[Printer]:
+        """Get all printers in the trash, most recently deleted first."""
+        async with self.conn.execute(
+            "SELECT * FROM printers WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [Printer(**{**dict(row), "auto_connect": bool(row["auto_connect"])}) for row in rows]
+
+    async def purge_printer(self, serial: str) -> bool:
+        """Permanently delete a trashed printer."""
+        cursor = await self.conn.execute("DELETE FROM printers WHERE serial = ? AND deleted_at IS NOT NULL", (serial,))
         await self.conn.commit()
         return cursor.rowcount > 0
 
+    async def purge_trash(self, retention_days: int | None = None) -> dict:
+        """Permanently delete trashed spools and printers.
+
+        Args:
+            retention_days: Only purge items deleted more than this many days ago
+                (None purges everything in the trash)
+
+        Returns:
+            Counts of purged spools and printers
+        """
+        cutoff = int(time.time()) - retention_days * 86400 if retention_days is not None else None
+        if cutoff is None:
+            condition, params = "deleted_at IS NOT NULL", ()
+        else:
+            condition, params = "deleted_at IS NOT NULL AND deleted_at < ?", (cutoff,)
+
+        async with self.conn.execute(f"SELECT id FROM spools WHERE {condition}", params) as cursor:  # nosec B608
+            spool_ids = [row["id"] for row in await cursor.fetchall()]
+        if spool_ids:
+            await self._purge_spools(spool_ids)
+
+        cursor = await self.conn.execute(f"DELETE FROM printers WHERE {condition}", params)  # nosec B608
+        await self.conn.commit()
+        return {"spools": len(spool_ids), "printers": cursor.rowcount}
+
     async def update_nozzle_count(self, serial: str, nozzle_count: int) -> bool:
         """Update printer nozzle_count (auto-detected from MQTT)."""
         cursor = await self.conn.execute(
@@ -737,12 +1936,105 @@ class Database:
         await self.conn.commit()
         return cursor.rowcount > 0
 
+    async def set_printer_cert_fingerprint(self, serial: str, fingerprint: str | None) -> bool:
+        """Pin a printer's TLS certificate fingerprint (None = pin the next certificate seen)."""
+        cursor = await self.conn.execute(
+            "UPDATE printers SET cert_fingerprint = ? WHERE serial = ?", (fingerprint, serial)
+        )
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def set_printer_versions(
+        self, serial: str, firmware_version: str | None, hardware_version: str | None
+    ) -> Printer | None:
+        """Store the firmware/hardware versions a printer reported. Returns the printer as it was before."""
+        previous = await self.get_printer(serial)
+        if not previous:
+            return None
+        await self.conn.execute(
+            "UPDATE printers SET firmware_version = ?, hardware_version = ? WHERE serial = ?",
+            (firmware_version, hardware_version, serial),
+        )
+        await self.conn.commit()
+        return previous
+
     async def get_auto_connect_printers(self) -> list[Printer]:
-        """Get printers with auto_connect enabled."""
-        async with self.conn.execute("SELECT * FROM printers WHERE auto_connect = 1") as cursor:
+        """Get printers with auto_connect enabled (manual printers never connect)."""
+        async with self.conn.execute(
+            "SELECT * FROM printers WHERE auto_connect = 1 AND printer_type != 'manual' AND deleted_at IS NULL"
+        ) as cursor:
             rows = await cursor.fetchall()
             return [Printer(**{**dict(row), "auto_connect": True}) for row in rows]
 
+    # ============ Nozzle Operations ============
+
+    async def get_printer_nozzles(self, printer_serial: str) -> list[dict]:
+        """Nozzles installed on a printer, by extruder."""
+        async with self.conn.execute(
+            "SELECT * FROM printer_nozzles WHERE printer_serial = ? ORDER BY extruder_id", (printer_serial,)
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def set_printer_nozzle(
+        self, printer_serial: str, extruder_id: int, diameter: str, nozzle_type: str | None
+    ) -> dict | None:
+        """Record the nozzle installed on an extruder.
+
+        Returns the logged change if a different nozzle was recorded before,
+        None for the first report or an unchanged nozzle.
+        """
+        async with self.conn.execute(
+            "SELECT diameter, nozzle_type FROM printer_nozzles WHERE printer_serial = ? AND extruder_id = ?",
+            (printer_serial, extruder_id),
+        ) as cursor:
+            previous = await cursor.fetchone()
+
+        # A report without a type doesn't mean the type changed
+        if nozzle_type is None and previous:
+            nozzle_type = previous["nozzle_type"]
+        if previous and (previous["diameter"], previous["nozzle_type"]) == (diameter, nozzle_type):
+            return None
+
+        now = int(time.time())
+        await self.conn.execute(
+            """INSERT INTO printer_nozzles (printer_serial, extruder_id, diameter, nozzle_type, updated_at)
+               VALUES (?, ?, ?, ?, ?)
+               ON CONFLICT(printer_serial, extruder_id) DO UPDATE SET
+               diameter = excluded.diameter,
+               nozzle_type = excluded.nozzle_type,
+               updated_at = excluded.updated_at""",
+            (printer_serial, extruder_id, diameter, nozzle_type, now),
+        )
+        change = None
+        if previous:
+            change = {
+                "printer_serial": printer_serial,
+                "extruder_id": extruder_id,
+                "old_diameter": previous["diameter"],
+                "old_type": previous["nozzle_type"],
+                "new_diameter": diameter,
+                "new_type": nozzle_type,
+                "changed_at": now,
+            }
+            row_cursor = await self.conn.execute(
+                """INSERT INTO nozzle_changes
+                   (printer_serial, extruder_id, old_diameter, old_type, new_diameter, new_type, changed_at)
+                   VALUES (:printer_serial, :extruder_id, :old_diameter, :old_type, :new_diameter, :new_type,
+                   :changed_at)""",
+                change,
+            )
+            change["id"] = row_cursor.lastrowid
+        await self.conn.commit()
+        return change
+
+    async def get_nozzle_changes(self, printer_serial: str, limit: int = 50) -> list[dict]:
+        """Nozzle change log for a printer, newest first."""
+        async with self.conn.execute(
+            "SELECT * FROM nozzle_changes WHERE printer_serial = ? ORDER BY changed_at DESC, id DESC LIMIT ?",
+            (printer_serial, limit),
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
     # ============ Spool Assignment Operations ============
 
     async def assign_spool_to_slot(self, spool_id: str, printer_serial: str, ams_id: int, tray_id: int) -> bool:
@@ -777,26 +2069,154 @@ class Database:
             row = await cursor.fetchone()
             return row["spool_id"] if row else None
 
+    async def get_all_slot_assignments(self) -> list[dict]:
+        """Get spool assignments for all printers."""
+        async with self.conn.execute("SELECT * FROM spool_assignments") as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
     async def get_slot_assignments(self, printer_serial: str) -> list[dict]:
-        """Get all spool assignments for a printer."""
+        """Get all spool assignments for a printer, with the AMS unit holding the slot when known."""
         async with self.conn.execute(
-            """SELECT sa.*, s.material, s.color_name, s.rgba, s.brand
+            """SELECT sa.*, s.material, s.color_name, s.rgba, s.brand,
+                      au.id AS ams_unit_id, au.serial AS ams_serial
                FROM spool_assignments sa
                LEFT JOIN spools s ON sa.spool_id = s.id
+               LEFT JOIN ams_units au ON au.printer_serial = sa.printer_serial AND au.ams_id = sa.ams_id
                WHERE sa.printer_serial = ?""",
             (printer_serial,),
         ) as cursor:
             rows = await cursor.fetchall()
             return [dict(row) for row in rows]
 
+    # ============ AMS Unit Operations ============
+
+    async def get_ams_units(self, printer_serial: str, include_detached: bool = False) -> list[dict]:
+        """AMS units of a printer, attached ones by position first."""
+        condition = "" if include_detached else " AND ams_id IS NOT NULL"
+        async with self.conn.execute(
+            f"""SELECT * FROM ams_units WHERE printer_serial = ?{condition}
+                ORDER BY ams_id IS NULL, ams_id, last_seen DESC""",  # nosec B608
+            (printer_serial,),
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def sync_ams_units(self, printer_serial: str, units: list[dict]) -> list[dict]:
+        """Record the AMS units a printer reports and follow the ones that moved.
+
+        Each unit is a dict with serial, ams_id, module, product_name,
+        firmware_version, hardware_version and humidity_sensor (None = unknown).
+        Units of the printer that aren't reported are marked detached. Slot
+        assignments move along with a unit whose ams_id changed, so spools stay
+        assigned to the AMS they are in; assignments left on a position now
+        taken by another unit are dropped.
+
+        Returns the moves as dicts with serial, old_ams_id and new_ams_id.
+        """
+        reported = [unit["serial"] for unit in units]
+        placeholders = ", ".join("?" for _ in reported)
+        query = f"SELECT * FROM ams_units WHERE printer_serial = ? OR serial IN ({placeholders})"  # nosec B608
+        async with self.conn.execute(query, (printer_serial, *reported)) as cursor:
+            known = {row["serial"]: dict(row) for row in await cursor.fetchall()}
+
+        now = int(time.time())
+        moves = []
+        try:
+            for unit in units:
+                previous = known.get(unit["serial"])
+                if (
+                    previous
+                    and previous["printer_serial"] == printer_serial
+                    and previous["ams_id"] is not None
+                    and previous["ams_id"] != unit["ams_id"]
+                ):
+                    moves.append(
+                        {"serial": unit["serial"], "old_ams_id": previous["ams_id"], "new_ams_id": unit["ams_id"]}
+                    )
+                humidity_sensor = None if unit.get("humidity_sensor") is None else int(unit["humidity_sensor"])
+                await self.conn.execute(
+                    """INSERT INTO ams_units (serial, printer_serial, ams_id, module, product_name, firmware_version,
+                                              hardware_version, humidity_sensor, first_seen, last_seen)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                       ON CONFLICT(serial) DO UPDATE SET
+                       printer_serial = excluded.printer_serial,
+                       ams_id = excluded.ams_id,
+                       module = excluded.module,
+                       product_name = COALESCE(excluded.product_name, product_name),
+                       firmware_version = excluded.firmware_version,
+                       hardware_version = excluded.hardware_version,
+                       humidity_sensor = COALESCE(excluded.humidity_sensor, humidity_sensor),
+                       last_seen = excluded.last_seen""",
+                    (
+                        unit["serial"],
+                        printer_serial,
+                        unit["ams_id"],
+                        unit.get("module"),
+                        unit.get("product_name"),
+                        unit.get("firmware_version"),
+                        unit.get("hardware_version"),
+                        humidity_sensor,
+                        now,
+                        now,
+                    ),
+                )
+
+            detach = f"UPDATE ams_units SET ams_id = NULL WHERE printer_serial = ? AND serial NOT IN ({placeholders})"  # nosec B608
+            await self.conn.execute(detach, (printer_serial, *reported))
+
+            if moves:
+                # Park the moved assignments on negative ids first so swapped units don't collide
+                for move in moves:
+                    await self.conn.execute(
+                        "UPDATE spool_assignments SET ams_id = ? WHERE printer_serial = ? AND ams_id = ?",
+                        (-1 - move["new_ams_id"], printer_serial, move["old_ams_id"]),
+                    )
+                targets = [move["new_ams_id"] for move in moves]
+                positions = ", ".join("?" for _ in targets)
+                drop = f"DELETE FROM spool_assignments WHERE printer_serial = ? AND ams_id IN ({positions})"  # nosec B608
+                await self.conn.execute(drop, (printer_serial, *targets))
+                await self.conn.execute(
+                    "UPDATE spool_assignments SET ams_id = -1 - ams_id WHERE printer_serial = ? AND ams_id < 0",
+                    (printer_serial,),
+                )
+            await self.conn.commit()
+        except Exception:
+            await self.conn.rollback()
+            raise
+        return moves
+
     # ============ Usage History Operations ============
 
-    async def log_usage(self, spool_id: str, printer_serial: str, print_name: str, weight_used: float) -> int:
-        """Log filament usage for a print job."""
+    async def log_usage(
+        self,
+        spool_id: str,
+        printer_serial: str,
+        print_name: str,
+        weight_used: float,
+        project_id: int | None = None,
+        reason: str | None = None,
+        member_id: int | None = None,
+        timestamp: int | None = None,
+    ) -> int:
+        """Log filament usage for a print job.
+
+        Manual adjustments are logged with a reason; their weight is signed
+        (negative = filament credited back to the spool). Usage between a
+        kiosk check-out and check-in is logged with the member.
+        """
         cursor = await self.conn.execute(
-            """INSERT INTO usage_history (spool_id, printer_serial, print_name, weight_used)
-               VALUES (?, ?, ?, ?)""",
-            (spool_id, printer_serial, print_name, weight_used),
+            """INSERT INTO usage_history
+               (spool_id, printer_serial, print_name, weight_used, project_id, reason, member_id, timestamp)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?)""",
+            (
+                spool_id,
+                printer_serial,
+                print_name,
+                weight_used,
+                project_id,
+                reason,
+                member_id,
+                timestamp or int(time.time()),
+            ),
         )
         await self.conn.commit()
         return cursor.lastrowid
@@ -821,15 +2241,82 @@ class Database:
             rows = await cursor.fetchall()
             return [dict(row) for row in rows]
 
+    async def get_consumption_since(self, since: int, spool_id: str | None = None) -> dict[str, float]:
+        """Total grams consumed per spool since a timestamp."""
+        query = "SELECT spool_id, SUM(weight_used) AS consumed FROM usage_history WHERE timestamp >= ?"
+        params: list = [since]
+        if spool_id:
+            query += " AND spool_id = ?"
+            params.append(spool_id)
+        query += " GROUP BY spool_id"
+        async with self.conn.execute(query, params) as cursor:
+            rows = await cursor.fetchall()
+            return {row["spool_id"]: row["consumed"] or 0.0 for row in rows}
+
+    async def get_usage_report(
+        self, group_by: str, since: int | None = None, timezone: str = "UTC", week_start: str = "monday"
+    ) -> list[dict]:
+        """Aggregate usage history into report groups.
+
+        Args:
+            group_by: One of "material", "printer", "month" or "week"
+            since: Optional epoch timestamp to restrict the report window
+            timezone: IANA timezone whose calendar months and weeks follow
+            week_start: First day of the week ("monday" or "sunday")
+
+        Returns:
+            One row per group with weight, print count, share of total, rank,
+            running total and the previous group's weight (months and weeks
+            are ordered chronologically, other groupings by weight).
+        """
+        group_exprs = {
+            "material": "COALESCE(s.material, 'Unknown')",
+            "printer": "COALESCE(p.name, uh.printer_serial, 'Unknown')",
+            "month": "local_period(uh.timestamp, ?, 'month', ?)",
+            "week": "local_period(uh.timestamp, ?, 'week', ?)",
+        }
+        if group_by not in group_exprs:
+            raise ValueError(f"Unsupported group_by: {group_by}")
+
+        periodic = group_by in ("month", "week")
+        params = [timezone, week_start] if periodic else []
+        order = "grp.key ASC" if periodic else "grp.weight_used DESC, grp.key ASC"
+        query = f"""
+            WITH grp AS (
+                SELECT {group_exprs[group_by]} AS key,
+                       SUM(uh.weight_used) AS weight_used,
+                       COUNT(*) AS prints,
+                       COUNT(DISTINCT uh.spool_id) AS spools,
+                       MAX(uh.timestamp) AS last_used
+                FROM usage_history uh
+                LEFT JOIN spools s ON uh.spool_id = s.id
+                LEFT JOIN printers p ON uh.printer_serial = p.serial
+                WHERE uh.timestamp >= ?
+                GROUP BY 1
+            )
+            SELECT grp.*,
+                   grp.weight_used * 100.0 / SUM(grp.weight_used) OVER () AS share,
+                   RANK() OVER (ORDER BY grp.weight_used DESC) AS rank,
+                   SUM(grp.weight_used) OVER (ORDER BY {order} ROWS UNBOUNDED PRECEDING) AS cumulative_weight,
+                   LAG(grp.weight_used) OVER (ORDER BY {order}) AS previous_weight
+            FROM grp
+            ORDER BY {order}
+        """  # nosec B608
+
+        async with self.conn.execute(query, (*params, since or 0)) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
     async def update_spool_consumption(
-        self, spool_id: str, weight_used: float, new_weight: int | None = None
+        self, spool_id: str, weight_used: float, new_weight: int | None = None, source: str = "usage"
     ) -> Spool | None:
         """Update spool consumption after a print.
 
         Args:
             spool_id: Spool ID
-            weight_used: Grams of filament consumed
+            weight_used: Grams of filament consumed (negative credits filament back)
             new_weight: Optional new current weight (from scale)
+            source: weight_history source for the derived weight
         """
         spool = await self.get_spool(spool_id)
         if not spool:
@@ -840,8 +2327,8 @@ class Database:
         values = [now]
 
         # Increment consumption counters
-        new_consumed_add = (spool.consumed_since_add or 0) + weight_used
-        new_consumed_weight = (spool.consumed_since_weight or 0) + weight_used
+        new_consumed_add = max(0, (spool.consumed_since_add or 0) + weight_used)
+        new_consumed_weight = max(0, (spool.consumed_since_weight or 0) + weight_used)
         updates.extend(["consumed_since_add = ?", "consumed_since_weight = ?"])
         values.extend([new_consumed_add, new_consumed_weight])
 
@@ -858,9 +2345,18 @@ class Database:
             updates.append("weight_current = ?")
             values.append(calculated_weight)
 
+        # Printing from a spool means its seal is broken
+        if weight_used > 0 and spool.opened_at is None:
+            updates.append("opened_at = ?")
+            values.append(now)
+
         values.append(spool_id)
         query = f"UPDATE spools SET {', '.join(updates)} WHERE id = ?"  # nosec B608
         await self.conn.execute(query, values)
+        if new_weight is not None:
+            await self._insert_weight(spool_id, new_weight, "scale", now)
+        elif spool.weight_current is not None:
+            await self._insert_weight(spool_id, calculated_weight, source, now)
         await self.conn.commit()
 
         return await self.get_spool(spool_id)
@@ -908,65 +2404,572 @@ class Database:
                WHERE id = ?""",
             (weight, weight_used_new, now, spool_id),
         )
+        await self._insert_weight(spool_id, weight, "scale", now)
         await self.conn.commit()
         return await self.get_spool(spool_id)
 
-    # ============ Settings Operations ============
+    async def restore_spool_fields(self, spool_id: str, fields: dict, expected_version: int) -> Spool | None:
+        """Put back previous field values (undo), if the spool is still at expected_version.
 
-    async def get_setting(self, key: str) -> str | None:
-        """Get a setting value by key."""
-        async with self.conn.execute("SELECT value FROM settings WHERE key = ?", (key,)) as cursor:
-            row = await cursor.fetchone()
-            return row["value"] if row else None
+        Raises SpoolVersionConflict if the spool changed in between. A restored
+        weight_current is recorded in the weight history.
+        """
+        existing = await self.get_spool(spool_id)
+        if not existing:
+            return None
+        if existing.version != expected_version:
+            raise SpoolVersionConflict(existing)
+
+        columns = {
+            name: (1 if value else 0) if name in ("ext_has_k", "swap_available") else value
+            for name, value in fields.items()
+        }
+        if "rgba" in columns:
+            columns["color_hue"], columns["color_lightness"] = stored_hue_lightness(columns["rgba"])
+        if "color_stops" in columns:
+            columns["color_stops"] = _color_stops_column(columns["color_stops"])
+        if "tag_id" in columns:
+            columns["tag_uid"] = normalize_tag_id(columns["tag_id"])
+        now = int(time.time())
+        updates = [f"{name} = ?" for name in columns]
+        cursor = await self.conn.execute(
+            f"UPDATE spools SET {', '.join(updates)}, updated_at = ? WHERE id = ? AND version = ?",  # nosec B608
+            (*columns.values(), now, spool_id, expected_version),
+        )
+        if cursor.rowcount == 0:
+            raise SpoolVersionConflict(await self.get_spool(spool_id))
+        if fields.get("weight_current") is not None:
+            await self._insert_weight(spool_id, fields["weight_current"], "undo", now)
+        await self.conn.commit()
+        return await self.get_spool(spool_id)
+
+    async def mark_spool_empty(self, spool_id: str, source: str = "runout") -> Spool | None:
+        """Mark a spool as fully consumed (e.g. after a filament runout).
+
+        The net weight drops to zero and the current weight to the bare core.
+        """
+        spool = await self.get_spool(spool_id)
+        if not spool:
+            return None
 
-    async def set_setting(self, key: str, value: str) -> None:
-        """Set a setting value (upsert)."""
         now = int(time.time())
         await self.conn.execute(
-            """INSERT INTO settings (key, value, updated_at)
-               VALUES (?, ?, ?)
-               ON CONFLICT(key) DO UPDATE SET
-               value = excluded.value,
-               updated_at = excluded.updated_at""",
-            (key, value, now),
+            """UPDATE spools SET weight_used = COALESCE(label_weight, 0), consumed_since_weight = 0,
+               weight_current = COALESCE(core_weight, weight_current), updated_at = ?
+               WHERE id = ?""",
+            (now, spool_id),
         )
+        if spool.core_weight:
+            await self._insert_weight(spool_id, spool.core_weight, source, now)
         await self.conn.commit()
+        return await self.get_spool(spool_id)
 
-    async def delete_setting(self, key: str) -> bool:
-        """Delete a setting."""
-        cursor = await self.conn.execute("DELETE FROM settings WHERE key = ?", (key,))
+    # ============ Runout Event Operations ============
+
+    @staticmethod
+    def _runout_row(row) -> dict:
+        event = dict(row)
+        event["marked_empty"] = bool(event["marked_empty"])
+        return event
+
+    async def log_runout_event(
+        self,
+        printer_serial: str,
+        ams_id: int,
+        tray_id: int,
+        kind: str,
+        spool_id: str | None = None,
+        print_name: str | None = None,
+        source: str | None = None,
+        marked_empty: bool = False,
+    ) -> dict:
+        """Record a filament runout or jam."""
+        cursor = await self.conn.execute(
+            """INSERT INTO runout_events
+               (printer_serial, ams_id, tray_id, spool_id, kind, print_name, source, marked_empty, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)""",
+            (
+                printer_serial,
+                ams_id,
+                tray_id,
+                spool_id,
+                kind,
+                print_name,
+                source,
+                1 if marked_empty else 0,
+                int(time.time()),
+            ),
+        )
         await self.conn.commit()
-        return cursor.rowcount > 0
+        async with self.conn.execute("SELECT * FROM runout_events WHERE id = ?", (cursor.lastrowid,)) as row_cursor:
+            return self._runout_row(await row_cursor.fetchone())
+
+    async def get_runout_events(
+        self, printer_serial: str | None = None, spool_id: str | None = None, limit: int = 50
+    ) -> list[dict]:
+        """Get recent runout/jam events, newest first, optionally for one printer or spool."""
+        conditions = []
+        values = []
+        if printer_serial:
+            conditions.append("printer_serial = ?")
+            values.append(printer_serial)
+        if spool_id:
+            conditions.append("spool_id = ?")
+            values.append(spool_id)
+        where = f"WHERE {' AND '.join(conditions)}" if conditions else ""
+        query = f"SELECT * FROM runout_events {where} ORDER BY created_at DESC, id DESC LIMIT ?"  # nosec B608
+        async with self.conn.execute(query, (*values, limit)) as cursor:
+            return [self._runout_row(row) for row in await cursor.fetchall()]
 
-    # ============ K-Profile Operations ============
+    # ============ Print Energy Operations ============
 
-    async def get_spool_k_profiles(self, spool_id: str) -> list[dict]:
-        """Get K-profiles associated with a spool."""
-        async with self.conn.execute("SELECT * FROM k_profiles WHERE spool_id = ?", (spool_id,)) as cursor:
+    async def log_print_energy(
+        self,
+        printer_serial: str,
+        print_name: str,
+        duration_sec: int,
+        power_watts: float,
+        project_id: int | None = None,
+    ) -> int:
+        """Record the estimated energy use of a print job."""
+        energy_kwh = power_watts * duration_sec / 3600 / 1000
+        cursor = await self.conn.execute(
+            """INSERT INTO print_energy (printer_serial, print_name, project_id, duration_sec, power_watts, energy_kwh)
+               VALUES (?, ?, ?, ?, ?, ?)""",
+            (printer_serial, print_name, project_id, duration_sec, power_watts, energy_kwh),
+        )
+        await self.conn.commit()
+        return cursor.lastrowid
+
+    async def get_energy_report(
+        self, group_by: str, since: int | None = None, timezone: str = "UTC", week_start: str = "monday"
+    ) -> list[dict]:
+        """Aggregate print energy by printer, month or week.
+
+        Months and weeks follow the calendar in `timezone` and are ordered
+        chronologically, printers by energy used.
+        """
+        group_exprs = {
+            "printer": "COALESCE(p.name, pe.printer_serial, 'Unknown')",
+            "month": "local_period(pe.timestamp, ?, 'month', ?)",
+            "week": "local_period(pe.timestamp, ?, 'week', ?)",
+        }
+        if group_by not in group_exprs:
+            raise ValueError(f"Unsupported group_by: {group_by}")
+
+        periodic = group_by in ("month", "week")
+        params = [timezone, week_start] if periodic else []
+        order = "key ASC" if periodic else "energy_kwh DESC, key ASC"
+        query = f"""
+            SELECT {group_exprs[group_by]} AS key,
+                   SUM(pe.energy_kwh) AS energy_kwh,
+                   SUM(pe.duration_sec) AS duration_sec,
+                   COUNT(*) AS prints
+            FROM print_energy pe
+            LEFT JOIN printers p ON pe.printer_serial = p.serial
+            WHERE pe.timestamp >= ?
+            GROUP BY 1
+            ORDER BY {order}
+        """  # nosec B608
+        async with self.conn.execute(query, (*params, since or 0)) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    # ============ Project Operations ============
+
+    async def get_projects(self, include_archived: bool = False) -> list[dict]:
+        """Get projects, newest first."""
+        query = "SELECT * FROM projects"
+        if not include_archived:
+            query += " WHERE archived_at IS NULL"
+        async with self.conn.execute(query + " ORDER BY created_at DESC, id DESC") as cursor:
             rows = await cursor.fetchall()
             return [dict(row) for row in rows]
 
-    async def save_spool_k_profiles(self, spool_id: str, profiles: list[dict]) -> None:
-        """Save K-profiles for a spool (replaces existing)."""
-        # Delete existing profiles
-        await self.conn.execute("DELETE FROM k_profiles WHERE spool_id = ?", (spool_id,))
+    async def get_project(self, project_id: int) -> dict | None:
+        """Get a project by ID."""
+        async with self.conn.execute("SELECT * FROM projects WHERE id = ?", (project_id,)) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
 
-        # Insert new profiles
-        for profile in profiles:
-            await self.conn.execute(
-                """INSERT INTO k_profiles
-                   (spool_id, printer_serial, extruder, nozzle_diameter, nozzle_type,
-                    k_value, name, cali_idx, setting_id)
-                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)""",
-                (
-                    spool_id,
-                    profile.get("printer_serial"),
-                    profile.get("extruder"),
-                    profile.get("nozzle_diameter"),
-                    profile.get("nozzle_type"),
-                    profile.get("k_value"),
-                    profile.get("name"),
-                    profile.get("cali_idx"),
+    async def create_project(self, name: str, description: str | None = None) -> dict:
+        """Create a project."""
+        cursor = await self.conn.execute(
+            "INSERT INTO projects (name, description, created_at) VALUES (?, ?, ?)",
+            (name, description, int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_project(cursor.lastrowid)
+
+    async def update_project(self, project_id: int, **fields) -> dict | None:
+        """Update project fields (name, description, archived_at)."""
+        if fields:
+            set_clause = ", ".join(f"{k} = ?" for k in fields)
+            query = f"UPDATE projects SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), project_id])
+            await self.conn.commit()
+        return await self.get_project(project_id)
+
+    async def delete_project(self, project_id: int) -> bool:
+        """Delete a project. Its usage entries are kept, ungrouped."""
+        await self.conn.execute("UPDATE usage_history SET project_id = NULL WHERE project_id = ?", (project_id,))
+        cursor = await self.conn.execute("DELETE FROM projects WHERE id = ?", (project_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def assign_usage_to_project(self, project_id: int | None, usage_ids: list[int]) -> int:
+        """Move usage entries into a project (None removes them from any project).
+
+        Returns the number of entries updated.
+        """
+        if not usage_ids:
+            return 0
+        placeholders = ", ".join("?" for _ in usage_ids)
+        query = f"UPDATE usage_history SET project_id = ? WHERE id IN ({placeholders})"  # nosec B608
+        cursor = await self.conn.execute(query, [project_id, *usage_ids])
+        await self.conn.commit()
+        return cursor.rowcount
+
+    async def get_project_usage(self, project_id: int, limit: int = 100) -> list[dict]:
+        """Get usage entries grouped under a project."""
+        async with self.conn.execute(
+            """SELECT uh.*, s.material, s.color_name, s.brand
+               FROM usage_history uh
+               LEFT JOIN spools s ON uh.spool_id = s.id
+               WHERE uh.project_id = ?
+               ORDER BY uh.timestamp DESC LIMIT ?""",
+            (project_id, limit),
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
+    async def get_project_report(self, since: int | None = None, include_archived: bool = False) -> list[dict]:
+        """Aggregate usage and cost per project.
+
+        Cost is prorated from each spool's price over its label weight; usage
+        from spools without a price counts towards weight but not cost
+        (`priced_weight` tells how much of the weight was costed). Each row
+        carries a per-material breakdown and the estimated print energy (kWh).
+        """
+        archived_filter = "" if include_archived else "WHERE pr.archived_at IS NULL"
+        cost = "uh.weight_used * s.price / NULLIF(s.label_weight, 0)"
+        query = f"""
+            SELECT pr.id AS project_id, pr.name, pr.archived_at,
+                   COALESCE(SUM(uh.weight_used), 0) AS weight_used,
+                   COUNT(uh.id) AS prints,
+                   COUNT(DISTINCT uh.spool_id) AS spools,
+                   SUM({cost}) AS cost,
+                   COALESCE(SUM(CASE WHEN {cost} IS NOT NULL THEN uh.weight_used END), 0) AS priced_weight,
+                   MIN(uh.timestamp) AS first_used,
+                   MAX(uh.timestamp) AS last_used
+            FROM projects pr
+            LEFT JOIN usage_history uh ON uh.project_id = pr.id AND uh.timestamp >= ?
+            LEFT JOIN spools s ON uh.spool_id = s.id
+            {archived_filter}
+            GROUP BY pr.id
+            ORDER BY weight_used DESC, pr.name ASC
+        """  # nosec B608
+        async with self.conn.execute(query, (since or 0,)) as cursor:
+            projects = [dict(row) for row in await cursor.fetchall()]
+
+        async with self.conn.execute(
+            f"""SELECT uh.project_id, COALESCE(s.material, 'Unknown') AS material,
+                       SUM(uh.weight_used) AS weight_used, SUM({cost}) AS cost
+                FROM usage_history uh
+                LEFT JOIN spools s ON uh.spool_id = s.id
+                WHERE uh.project_id IS NOT NULL AND uh.timestamp >= ?
+                GROUP BY 1, 2
+                ORDER BY weight_used DESC""",  # nosec B608
+            (since or 0,),
+        ) as cursor:
+            materials: dict[int, list[dict]] = {}
+            for row in await cursor.fetchall():
+                materials.setdefault(row["project_id"], []).append(
+                    {"material": row["material"], "weight_used": row["weight_used"], "cost": row["cost"]}
+                )
+
+        async with self.conn.execute(
+            """SELECT project_id, SUM(energy_kwh) AS energy_kwh
+               FROM print_energy
+               WHERE project_id IS NOT NULL AND timestamp >= ?
+               GROUP BY 1""",
+            (since or 0,),
+        ) as cursor:
+            energy = {row["project_id"]: row["energy_kwh"] for row in await cursor.fetchall()}
+
+        for project in projects:
+            project["materials"] = materials.get(project["project_id"], [])
+            project["energy_kwh"] = energy.get(project["project_id"], 0)
+        return projects
+
+    # ============ Spool Template Operations ============
+
+    async def get_spool_templates(self) -> list[dict]:
+        """Get spool templates, most used first."""
+        async with self.conn.execute(
+            "SELECT * FROM spool_templates ORDER BY use_count DESC, last_used_at DESC, name COLLATE NOCASE"
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
+    async def get_spool_template(self, template_id: int) -> dict | None:
+        """Get a spool template by ID."""
+        async with self.conn.execute("SELECT * FROM spool_templates WHERE id = ?", (template_id,)) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def create_spool_template(self, **fields) -> dict:
+        """Create a spool template from its fields (name, material, brand, ...)."""
+        fields["created_at"] = int(time.time())
+        columns = ", ".join(fields)
+        placeholders = ", ".join("?" for _ in fields)
+        query = f"INSERT INTO spool_templates ({columns}) VALUES ({placeholders})"  # nosec B608
+        cursor = await self.conn.execute(query, list(fields.values()))
+        await self.conn.commit()
+        return await self.get_spool_template(cursor.lastrowid)
+
+    async def update_spool_template(self, template_id: int, **fields) -> dict | None:
+        """Update spool template fields."""
+        if fields:
+            set_clause = ", ".join(f"{k} = ?" for k in fields)
+            query = f"UPDATE spool_templates SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), template_id])
+            await self.conn.commit()
+        return await self.get_spool_template(template_id)
+
+    async def delete_spool_template(self, template_id: int) -> bool:
+        """Delete a spool template. Spools added from it are kept."""
+        cursor = await self.conn.execute("DELETE FROM spool_templates WHERE id = ?", (template_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def mark_spool_template_used(self, template_id: int):
+        """Count a spool added from a template."""
+        await self.conn.execute(
+            "UPDATE spool_templates SET use_count = use_count + 1, last_used_at = ? WHERE id = ?",
+            (int(time.time()), template_id),
+        )
+        await self.conn.commit()
+
+    # ============ Weight History Operations ============
+
+    async def _insert_weight(self, spool_id: str, weight: int, source: str, recorded_at: int) -> int:
+        cursor = await self.conn.execute(
+            "INSERT INTO weight_history (spool_id, weight, source, recorded_at) VALUES (?, ?, ?, ?)",
+            (spool_id, weight, source, recorded_at),
+        )
+        return cursor.lastrowid
+
+    async def record_weight(self, spool_id: str, weight: int, source: str, recorded_at: int | None = None) -> int:
+        """Record a spool weight measurement.
+
+        Args:
+            spool_id: Spool ID
+            weight: Gross weight in grams (including core)
+            source: "scale" for measurements, "usage" for weights derived from consumption
+            recorded_at: Epoch timestamp (defaults to now)
+        """
+        row_id = await self._insert_weight(spool_id, weight, source, recorded_at or int(time.time()))
+        await self.conn.commit()
+        return row_id
+
+    async def get_weight_history(self, spool_id: str, bucket_seconds: int = 1, since: int | None = None) -> list[dict]:
+        """Get a spool's weight over time, downsampled into fixed-size buckets.
+
+        Each bucket reports the last weight recorded in it plus the min/max and
+        sample count, ordered oldest first.
+        """
+        async with self.conn.execute(
+            """SELECT bucket * ? AS timestamp, weight, min_weight, max_weight, samples
+               FROM (
+                   SELECT recorded_at / ? AS bucket, weight,
+                          MIN(weight) OVER b AS min_weight,
+                          MAX(weight) OVER b AS max_weight,
+                          COUNT(*) OVER b AS samples,
+                          ROW_NUMBER() OVER (PARTITION BY recorded_at / ? ORDER BY recorded_at DESC, id DESC) AS rn
+                   FROM weight_history
+                   WHERE spool_id = ? AND recorded_at >= ?
+                   WINDOW b AS (PARTITION BY recorded_at / ?)
+               )
+               WHERE rn = 1
+               ORDER BY bucket ASC""",
+            (bucket_seconds, bucket_seconds, bucket_seconds, spool_id, since or 0, bucket_seconds),
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
+    async def get_weight_changes(self, spool_id: str, since: int | None = None) -> list[dict]:
+        """Get the change between consecutive weight readings of a spool, oldest first."""
+        async with self.conn.execute(
+            """SELECT recorded_at, source, delta
+               FROM (
+                   SELECT recorded_at, source,
+                          weight - LAG(weight) OVER (ORDER BY recorded_at, id) AS delta
+                   FROM weight_history
+                   WHERE spool_id = ? AND recorded_at >= ?
+               )
+               WHERE delta IS NOT NULL AND delta != 0
+               ORDER BY recorded_at ASC""",
+            (spool_id, since or 0),
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
+    # ============ Weight Reconciliation Operations ============
+
+    async def get_scale_readings_after(self, after_id: int, limit: int = 1000) -> list[dict]:
+        """Scale readings with a weight_history ID above after_id, oldest first."""
+        async with self.conn.execute(
+            "SELECT * FROM weight_history WHERE id > ? AND source = 'scale' ORDER BY id ASC LIMIT ?",
+            (after_id, limit),
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def get_measurement_interval(self, measurement: dict, excluded_reason: str) -> dict | None:
+        """What happened to a spool between a scale reading and the one before it.
+
+        Returns the previous reading, the usage logged in between (except usage
+        with excluded_reason) and how many weight changes in between came from
+        something other than usage, or None for a spool's first reading.
+        """
+        async with self.conn.execute(
+            """SELECT * FROM weight_history
+               WHERE spool_id = ? AND id < ? AND source = 'scale'
+               ORDER BY id DESC LIMIT 1""",
+            (measurement["spool_id"], measurement["id"]),
+        ) as cursor:
+            previous = await cursor.fetchone()
+        if not previous:
+            return None
+
+        async with self.conn.execute(
+            """SELECT COUNT(*) FROM weight_history
+               WHERE spool_id = ? AND id > ? AND id < ? AND source NOT IN ('usage', 'adjustment')""",
+            (measurement["spool_id"], previous["id"], measurement["id"]),
+        ) as cursor:
+            other_changes = (await cursor.fetchone())[0]
+        async with self.conn.execute(
+            """SELECT COALESCE(SUM(weight_used), 0) FROM usage_history
+               WHERE spool_id = ? AND timestamp > ? AND timestamp <= ? AND (reason IS NULL OR reason != ?)""",
+            (measurement["spool_id"], previous["recorded_at"], measurement["recorded_at"], excluded_reason),
+        ) as cursor:
+            tracked_usage = (await cursor.fetchone())[0]
+        return {"previous": dict(previous), "tracked_usage": tracked_usage, "other_changes": other_changes}
+
+    async def create_weight_reconciliation(
+        self,
+        spool_id: str,
+        measurement_id: int,
+        previous_weight: int,
+        measured_weight: int,
+        tracked_usage: float,
+        discrepancy: float,
+        measured_at: int,
+    ) -> dict | None:
+        """Flag a scale reading; None if it was flagged already."""
+        cursor = await self.conn.execute(
+            """INSERT OR IGNORE INTO weight_reconciliations
+               (spool_id, measurement_id, previous_weight, measured_weight, tracked_usage, discrepancy, measured_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?)""",
+            (spool_id, measurement_id, previous_weight, measured_weight, tracked_usage, discrepancy, measured_at),
+        )
+        await self.conn.commit()
+        return await self.get_weight_reconciliation(cursor.lastrowid) if cursor.rowcount else None
+
+    async def get_weight_reconciliation(self, reconciliation_id: int) -> dict | None:
+        """Get a reconciliation suggestion by ID."""
+        query = "SELECT * FROM weight_reconciliations WHERE id = ?"
+        async with self.conn.execute(query, (reconciliation_id,)) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def get_weight_reconciliations(
+        self, status: str | None = None, spool_id: str | None = None, limit: int = 100
+    ) -> list[dict]:
+        """Get reconciliation suggestions, newest reading first."""
+        conditions, params = [], []
+        if status:
+            conditions.append("status = ?")
+            params.append(status)
+        if spool_id:
+            conditions.append("spool_id = ?")
+            params.append(spool_id)
+        where = f"WHERE {' AND '.join(conditions)}" if conditions else ""
+        query = f"SELECT * FROM weight_reconciliations {where} ORDER BY measured_at DESC, id DESC LIMIT ?"  # nosec B608
+        async with self.conn.execute(query, (*params, limit)) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def resolve_weight_reconciliation(
+        self, reconciliation_id: int, status: str, resolved_grams: float | None = None
+    ) -> dict | None:
+        """Mark a suggestion accepted (with the usage logged for it) or dismissed."""
+        await self.conn.execute(
+            "UPDATE weight_reconciliations SET status = ?, resolved_grams = ?, resolved_at = ? WHERE id = ?",
+            (status, resolved_grams, int(time.time()), reconciliation_id),
+        )
+        await self.conn.commit()
+        return await self.get_weight_reconciliation(reconciliation_id)
+
+    # ============ Settings Operations ============
+
+    async def get_setting(self, key: str) -> str | None:
+        """Get a setting value by key."""
+        async with self.conn.execute("SELECT value FROM settings WHERE key = ?", (key,)) as cursor:
+            row = await cursor.fetchone()
+            return row["value"] if row else None
+
+    async def get_settings(self) -> dict[str, str]:
+        """Get all settings as a key -> value dict."""
+        async with self.conn.execute("SELECT key, value FROM settings ORDER BY key") as cursor:
+            return {row["key"]: row["value"] for row in await cursor.fetchall()}
+
+    async def set_setting(self, key: str, value: str) -> None:
+        """Set a setting value (upsert)."""
+        now = int(time.time())
+        await self.conn.execute(
+            """INSERT INTO settings (key, value, updated_at)
+               VALUES (?, ?, ?)
+               ON CONFLICT(key) DO UPDATE SET
+               value = excluded.value,
+               updated_at = excluded.updated_at""",
+            (key, value, now),
+        )
+        await self.conn.commit()
+
+    async def delete_setting(self, key: str) -> bool:
+        """Delete a setting."""
+        cursor = await self.conn.execute("DELETE FROM settings WHERE key = ?", (key,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    # ============ K-Profile Operations ============
+
+    async def get_spool_k_profiles(self, spool_id: str) -> list[dict]:
+        """Get K-profiles associated with a spool."""
+        async with self.conn.execute("SELECT * FROM k_profiles WHERE spool_id = ?", (spool_id,)) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
+    async def save_spool_k_profiles(self, spool_id: str, profiles: list[dict]) -> None:
+        """Save K-profiles for a spool (replaces existing)."""
+        # Delete existing profiles
+        await self.conn.execute("DELETE FROM k_profiles WHERE spool_id = ?", (spool_id,))
+
+        # Insert new profiles
+        for profile in profiles:
+            await self.conn.execute(
+                """INSERT INTO k_profiles
+                   (spool_id, printer_serial, extruder, nozzle_diameter, nozzle_type,
+                    k_value, name, cali_idx, setting_id)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)""",
+                (
+                    spool_id,
+                    profile.get("printer_serial"),
+                    profile.get("extruder"),
+                    profile.get("nozzle_diameter"),
+                    profile.get("nozzle_type"),
+                    profile.get("k_value"),
+                    profile.get("name"),
+                    profile.get("cali_idx"),
                     profile.get("setting_id"),
                 ),
             )
@@ -1126,6 +3129,20 @@ class Database:
                 row = await cursor.fetchone()
                 return dict(row) if row else None
 
+    async def find_color_by_name(
+        self, color_name: str, manufacturer: str | None = None, material: str | None = None
+    ) -> dict | None:
+        """Look up a catalog color by name (case-insensitive), preferring the given manufacturer and material."""
+        async with self.conn.execute(
+            """SELECT id, manufacturer, color_name, hex_color, material, is_default, created_at FROM color_catalog
+               WHERE LOWER(color_name) = LOWER(?)
+               ORDER BY LOWER(manufacturer) = LOWER(?) DESC, LOWER(material) = LOWER(?) DESC, is_default DESC, id
+               LIMIT 1""",
+            (color_name.strip(), manufacturer or "", material or ""),
+        ) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
     # ============ AMS Sensor History Operations ============
 
     async def record_ams_sensor(
@@ -1187,6 +3204,909 @@ class Database:
         await self.conn.commit()
         return cursor.rowcount
 
+    # ============ AMS Event Operations ============
+
+    async def record_ams_event(
+        self,
+        printer_serial: str,
+        ams_id: int,
+        tray_id: int,
+        event: str,
+        extruder: int | None = None,
+        spool_id: str | None = None,
+        tray_type: str | None = None,
+        tray_color: str | None = None,
+        tray_info_idx: str | None = None,
+    ) -> dict:
+        """Append an event to the AMS slot ledger."""
+        row = {
+            "printer_serial": printer_serial,
+            "ams_id": ams_id,
+            "tray_id": tray_id,
+            "event": event,
+            "extruder": extruder,
+            "spool_id": spool_id,
+            "tray_type": tray_type,
+            "tray_color": tray_color,
+            "tray_info_idx": tray_info_idx,
+            "created_at": int(time.time()),
+        }
+        cursor = await self.conn.execute(
+            """INSERT INTO ams_events
+               (printer_serial, ams_id, tray_id, event, extruder, spool_id, tray_type, tray_color, tray_info_idx,
+               created_at)
+               VALUES (:printer_serial, :ams_id, :tray_id, :event, :extruder, :spool_id, :tray_type, :tray_color,
+               :tray_info_idx, :created_at)""",
+            row,
+        )
+        await self.conn.commit()
+        return {"id": cursor.lastrowid, **row}
+
+    async def get_ams_events(
+        self,
+        printer_serial: str | None = None,
+        ams_id: int | None = None,
+        tray_id: int | None = None,
+        spool_id: str | None = None,
+        since: int | None = None,
+        limit: int = 100,
+    ) -> list[dict]:
+        """AMS slot events, newest first."""
+        conditions = []
+        params: list = []
+        for column, value in (
+            ("printer_serial", printer_serial),
+            ("ams_id", ams_id),
+            ("tray_id", tray_id),
+            ("spool_id", spool_id),
+        ):
+            if value is not None:
+                conditions.append(f"{column} = ?")
+                params.append(value)
+        if since is not None:
+            conditions.append("created_at >= ?")
+            params.append(since)
+        where = f"WHERE {' AND '.join(conditions)}" if conditions else ""
+        async with self.conn.execute(
+            f"SELECT * FROM ams_events {where} ORDER BY created_at DESC, id DESC LIMIT ?",  # nosec B608
+            (*params, limit),
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    # ============ Moonraker Printer Operations ============
+
+    @staticmethod
+    def _moonraker_row(row) -> dict:
+        printer = dict(row)
+        printer["enabled"] = bool(printer["enabled"])
+        return printer
+
+    async def get_moonraker_printers(self) -> list[dict]:
+        """Get all Moonraker (Klipper) printers."""
+        async with self.conn.execute("SELECT * FROM moonraker_printers ORDER BY name, id") as cursor:
+            return [self._moonraker_row(row) for row in await cursor.fetchall()]
+
+    async def get_moonraker_printer(self, printer_id: int) -> dict | None:
+        """Get a Moonraker printer by ID."""
+        async with self.conn.execute("SELECT * FROM moonraker_printers WHERE id = ?", (printer_id,)) as cursor:
+            row = await cursor.fetchone()
+            return self._moonraker_row(row) if row else None
+
+    async def create_moonraker_printer(
+        self, name: str, url: str, api_key: str | None = None, enabled: bool = True
+    ) -> dict:
+        """Add a Moonraker printer."""
+        cursor = await self.conn.execute(
+            "INSERT INTO moonraker_printers (name, url, api_key, enabled, created_at) VALUES (?, ?, ?, ?, ?)",
+            (name, url, api_key, 1 if enabled else 0, int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_moonraker_printer(cursor.lastrowid)
+
+    async def update_moonraker_printer(self, printer_id: int, **fields) -> dict | None:
+        """Update Moonraker printer fields (name, url, api_key, enabled)."""
+        if "enabled" in fields:
+            fields["enabled"] = 1 if fields["enabled"] else 0
+
+        if fields:
+            set_clause = ", ".join(f"{k} = ?" for k in fields)
+            query = f"UPDATE moonraker_printers SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), printer_id])
+            await self.conn.commit()
+        return await self.get_moonraker_printer(printer_id)
+
+    async def delete_moonraker_printer(self, printer_id: int, printer_serial: str) -> bool:
+        """Delete a Moonraker printer and its spool assignments (stored under `printer_serial`)."""
+        await self.conn.execute("DELETE FROM spool_assignments WHERE printer_serial = ?", (printer_serial,))
+        cursor = await self.conn.execute("DELETE FROM moonraker_printers WHERE id = ?", (printer_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    # ============ Webhook Operations ============
+
+    @staticmethod
+    def _webhook_row(row) -> dict:
+        webhook = dict(row)
+        webhook["events"] = [e for e in (webhook["events"] or "").split(",") if e]
+        webhook["enabled"] = bool(webhook["enabled"])
+        return webhook
+
+    async def get_webhooks(self) -> list[dict]:
+        """Get all registered webhooks."""
+        async with self.conn.execute("SELECT * FROM webhooks ORDER BY id") as cursor:
+            rows = await cursor.fetchall()
+            return [self._webhook_row(row) for row in rows]
+
+    async def get_webhook(self, webhook_id: int) -> dict | None:
+        """Get a webhook by ID."""
+        async with self.conn.execute("SELECT * FROM webhooks WHERE id = ?", (webhook_id,)) as cursor:
+            row = await cursor.fetchone()
+            return self._webhook_row(row) if row else None
+
+    async def get_webhooks_for_event(self, event: str) -> list[dict]:
+        """Get enabled webhooks subscribed to an event type."""
+        return [w for w in await self.get_webhooks() if w["enabled"] and event in w["events"]]
+
+    async def create_webhook(
+        self, name: str, url: str, events: list[str], secret: str | None = None, enabled: bool = True
+    ) -> dict:
+        """Register a new webhook."""
+        now = int(time.time())
+        cursor = await self.conn.execute(
+            """INSERT INTO webhooks (name, url, secret, events, enabled, created_at)
+               VALUES (?, ?, ?, ?, ?, ?)""",
+            (name, url, secret, ",".join(events), 1 if enabled else 0, now),
+        )
+        await self.conn.commit()
+        return await self.get_webhook(cursor.lastrowid)
+
+    async def update_webhook(self, webhook_id: int, **fields) -> dict | None:
+        """Update webhook fields (name, url, secret, events, enabled)."""
+        if "events" in fields:
+            fields["events"] = ",".join(fields["events"])
+        if "enabled" in fields:
+            fields["enabled"] = 1 if fields["enabled"] else 0
+
+        if fields:
+            set_clause = ", ".join(f"{k} = ?" for k in fields)
+            query = f"UPDATE webhooks SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), webhook_id])
+            await self.conn.commit()
+        return await self.get_webhook(webhook_id)
+
+    async def delete_webhook(self, webhook_id: int) -> bool:
+        """Delete a webhook and its delivery log."""
+        await self.conn.execute("DELETE FROM webhook_deliveries WHERE webhook_id = ?", (webhook_id,))
+        cursor = await self.conn.execute("DELETE FROM webhooks WHERE id = ?", (webhook_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def log_webhook_delivery(
+        self,
+        webhook_id: int,
+        event: str,
+        payload: str,
+        status_code: int | None,
+        success: bool,
+        attempts: int,
+        error: str | None = None,
+    ) -> int:
+        """Record the outcome of a webhook delivery."""
+        now = int(time.time())
+        cursor = await self.conn.execute(
+            """INSERT INTO webhook_deliveries
+               (webhook_id, event, payload, status_code, success, attempts, error, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?)""",
+            (webhook_id, event, payload, status_code, 1 if success else 0, attempts, error, now),
+        )
+        await self.conn.commit()
+        return cursor.lastrowid
+
+    async def get_webhook_deliveries(self, webhook_id: int, limit: int = 50) -> list[dict]:
+        """Get recent deliveries for a webhook, newest first."""
+        async with self.conn.execute(
+            """SELECT * FROM webhook_deliveries WHERE webhook_id = ?
+               ORDER BY created_at DESC, id DESC LIMIT ?""",
+            (webhook_id, limit),
+        ) as cursor:
+            rows = await cursor.fetchall()
+            return [{**dict(row), "success": bool(row["success"])} for row in rows]
+
+    # ============ Notification Channel Operations ============
+
+    @staticmethod
+    def _channel_row(row) -> dict:
+        channel = dict(row)
+        channel["config"] = json.loads(channel["config"] or "{}")
+        channel["events"] = [e for e in (channel["events"] or "").split(",") if e]
+        channel["enabled"] = bool(channel["enabled"])
+        return channel
+
+    async def get_notification_channels(self) -> list[dict]:
+        """Get all notification channels."""
+        async with self.conn.execute("SELECT * FROM notification_channels ORDER BY id") as cursor:
+            rows = await cursor.fetchall()
+            return [self._channel_row(row) for row in rows]
+
+    async def get_notification_channel(self, channel_id: int) -> dict | None:
+        """Get a notification channel by ID."""
+        async with self.conn.execute("SELECT * FROM notification_channels WHERE id = ?", (channel_id,)) as cursor:
+            row = await cursor.fetchone()
+            return self._channel_row(row) if row else None
+
+    async def get_notification_channels_for_event(self, event: str) -> list[dict]:
+        """Get enabled notification channels subscribed to an event type."""
+        return [c for c in await self.get_notification_channels() if c["enabled"] and event in c["events"]]
+
+    async def create_notification_channel(
+        self, name: str, channel_type: str, config: dict, events: list[str], enabled: bool = True
+    ) -> dict:
+        """Create a notification channel."""
+        now = int(time.time())
+        cursor = await self.conn.execute(
+            """INSERT INTO notification_channels (name, type, config, events, enabled, created_at)
+               VALUES (?, ?, ?, ?, ?, ?)""",
+            (name, channel_type, json.dumps(config), ",".join(events), 1 if enabled else 0, now),
+        )
+        await self.conn.commit()
+        return await self.get_notification_channel(cursor.lastrowid)
+
+    async def update_notification_channel(self, channel_id: int, **fields) -> dict | None:
+        """Update notification channel fields (name, config, events, enabled)."""
+        if "config" in fields:
+            fields["config"] = json.dumps(fields["config"])
+        if "events" in fields:
+            fields["events"] = ",".join(fields["events"])
+        if "enabled" in fields:
+            fields["enabled"] = 1 if fields["enabled"] else 0
+
+        if fields:
+            set_clause = ", ".join(f"{k} = ?" for k in fields)
+            query = f"UPDATE notification_channels SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), channel_id])
+            await self.conn.commit()
+        return await self.get_notification_channel(channel_id)
+
+    async def delete_notification_channel(self, channel_id: int) -> bool:
+        """Delete a notification channel."""
+        cursor = await self.conn.execute("DELETE FROM notification_channels WHERE id = ?", (channel_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    # ============ Crash Report Operations ============
+
+    async def create_crash_report(
+        self,
+        device_id: str,
+        reset_reason: str,
+        firmware_version: str | None = None,
+        panic_message: str | None = None,
+        task: str | None = None,
+        backtrace: str | None = None,
+        crashed_at: int | None = None,
+        keep: int = 50,
+    ) -> dict:
+        """Store a crash report, keeping only the newest `keep` reports per device."""
+        now = int(time.time())
+        cursor = await self.conn.execute(
+            """INSERT INTO crash_reports
+               (device_id, firmware_version, reset_reason, panic_message, task, backtrace, crashed_at, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?)""",
+            (device_id, firmware_version, reset_reason, panic_message, task, backtrace, crashed_at, now),
+        )
+        report_id = cursor.lastrowid
+        await self.conn.execute(
+            """DELETE FROM crash_reports WHERE device_id = ? AND id NOT IN (
+                   SELECT id FROM crash_reports WHERE device_id = ? ORDER BY created_at DESC, id DESC LIMIT ?
+               )""",
+            (device_id, device_id, keep),
+        )
+        await self.conn.commit()
+        return await self.get_crash_report(report_id)
+
+    async def get_crash_report(self, report_id: int) -> dict | None:
+        """Get a crash report by ID."""
+        async with self.conn.execute("SELECT * FROM crash_reports WHERE id = ?", (report_id,)) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def get_crash_reports(self, device_id: str | None = None, limit: int = 50) -> list[dict]:
+        """Get recent crash reports, newest first, optionally for one device."""
+        query = "SELECT * FROM crash_reports"
+        params: list = []
+        if device_id:
+            query += " WHERE device_id = ?"
+            params.append(device_id)
+        query += " ORDER BY created_at DESC, id DESC LIMIT ?"
+        params.append(limit)
+        async with self.conn.execute(query, params) as cursor:
+            rows = await cursor.fetchall()
+            return [dict(row) for row in rows]
+
+    async def delete_crash_report(self, report_id: int) -> bool:
+        """Delete a crash report."""
+        cursor = await self.conn.execute("DELETE FROM crash_reports WHERE id = ?", (report_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    # ============ Job Operations ============
+
+    @staticmethod
+    def _job_row(row) -> dict:
+        job = dict(row)
+        job["params"] = json.loads(job["params"]) if job["params"] else {}
+        job["result"] = json.loads(job["result"]) if job["result"] else None
+        return job
+
+    async def create_job(self, job_id: str, kind: str, params: dict) -> dict:
+        """Store a queued job."""
+        await self.conn.execute(
+            "INSERT INTO jobs (id, kind, status, params, created_at) VALUES (?, ?, 'queued', ?, ?)",
+            (job_id, kind, json.dumps(params), int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_job(job_id)
+
+    async def update_job(self, job_id: str, **fields) -> dict | None:
+        """Update a job's status, progress, message, result or error. Returns the job, None if not found."""
+        allowed = {"status", "progress", "total", "message", "result", "error", "started_at", "finished_at"}
+        columns = {k: v for k, v in fields.items() if k in allowed}
+        if "result" in columns:
+            columns["result"] = json.dumps(columns["result"])
+        if columns:
+            assignments = ", ".join(f"{column} = ?" for column in columns)
+            await self.conn.execute(
+                f"UPDATE jobs SET {assignments} WHERE id = ?",  # nosec B608
+                (*columns.values(), job_id),
+            )
+            await self.conn.commit()
+        return await self.get_job(job_id)
+
+    async def get_job(self, job_id: str) -> dict | None:
+        """Get a job by ID."""
+        async with self.conn.execute("SELECT * FROM jobs WHERE id = ?", (job_id,)) as cursor:
+            row = await cursor.fetchone()
+            return self._job_row(row) if row else None
+
+    async def get_jobs(self, kind: str | None = None, status: str | None = None, limit: int = 50) -> list[dict]:
+        """Get recent jobs, newest first."""
+        query = "SELECT * FROM jobs WHERE 1=1"
+        params: list = []
+        if kind:
+            query += " AND kind = ?"
+            params.append(kind)
+        if status:
+            query += " AND status = ?"
+            params.append(status)
+        query += " ORDER BY created_at DESC, rowid DESC LIMIT ?"
+        params.append(limit)
+        async with self.conn.execute(query, params) as cursor:
+            return [self._job_row(row) for row in await cursor.fetchall()]
+
+    async def fail_interrupted_jobs(self) -> int:
+        """Mark jobs left queued or running by a previous server process as failed."""
+        cursor = await self.conn.execute(
+            """UPDATE jobs SET status = 'failed', error = 'Interrupted by a server restart', finished_at = ?
+               WHERE status IN ('queued', 'running')""",
+            (int(time.time()),),
+        )
+        await self.conn.commit()
+        return cursor.rowcount
+
+    async def prune_jobs(self, retention_days: int) -> int:
+        """Delete finished jobs older than the retention window."""
+        cutoff = int(time.time()) - retention_days * 86400
+        cursor = await self.conn.execute(
+            "DELETE FROM jobs WHERE created_at < ? AND status NOT IN ('queued', 'running')", (cutoff,)
+        )
+        await self.conn.commit()
+        return cursor.rowcount
+
+    async def backup_to(self, path: Path):
+        """Write a consistent copy of the database to a new file."""
+        await self.conn.commit()
+        await self.conn.execute("VACUUM INTO ?", (str(path),))
+
+    # ============ Purchase Operations ============
+
+    async def _with_purchase_spools(self, purchases: list[dict]) -> list[dict]:
+        ids = [p["id"] for p in purchases]
+        spool_ids: dict[int, list[str]] = {i: [] for i in ids}
+        if ids:
+            placeholders = ", ".join("?" for _ in ids)
+            query = f"SELECT * FROM purchase_spools WHERE purchase_id IN ({placeholders})"  # nosec B608
+            async with self.conn.execute(query, ids) as cursor:
+                for row in await cursor.fetchall():
+                    spool_ids[row["purchase_id"]].append(row["spool_id"])
+        for purchase in purchases:
+            purchase["spool_ids"] = spool_ids[purchase["id"]]
+        return purchases
+
+    async def get_purchases(self, supplier: str | None = None, limit: int = 100) -> list[dict]:
+        """Get purchases with their spool IDs, most recent first."""
+        query = "SELECT * FROM purchases"
+        params: list = []
+        if supplier:
+            query += " WHERE supplier = ? COLLATE NOCASE"
+            params.append(supplier)
+        query += " ORDER BY purchased_at DESC, id DESC LIMIT ?"
+        params.append(limit)
+        async with self.conn.execute(query, params) as cursor:
+            purchases = [dict(row) for row in await cursor.fetchall()]
+        return await self._with_purchase_spools(purchases)
+
+    async def get_purchase(self, purchase_id: int) -> dict | None:
+        """Get a purchase with its spool IDs."""
+        async with self.conn.execute("SELECT * FROM purchases WHERE id = ?", (purchase_id,)) as cursor:
+            row = await cursor.fetchone()
+        return (await self._with_purchase_spools([dict(row)]))[0] if row else None
+
+    async def create_purchase(self, **fields) -> dict:
+        """Create a purchase from its fields (supplier, url, purchased_at, price, quantity, ...)."""
+        fields["created_at"] = int(time.time())
+        columns = ", ".join(fields)
+        placeholders = ", ".join("?" for _ in fields)
+        query = f"INSERT INTO purchases ({columns}) VALUES ({placeholders})"  # nosec B608
+        cursor = await self.conn.execute(query, list(fields.values()))
+        await self.conn.commit()
+        return await self.get_purchase(cursor.lastrowid)
+
+    async def update_purchase(self, purchase_id: int, **fields) -> dict | None:
+        """Update purchase fields."""
+        if fields:
+            set_clause = ", ".join(f"{k} = ?" for k in fields)
+            query = f"UPDATE purchases SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), purchase_id])
+            await self.conn.commit()
+        return await self.get_purchase(purchase_id)
+
+    async def delete_purchase(self, purchase_id: int) -> bool:
+        """Delete a purchase. Its spools are kept."""
+        await self.conn.execute("DELETE FROM purchase_spools WHERE purchase_id = ?", (purchase_id,))
+        cursor = await self.conn.execute("DELETE FROM purchases WHERE id = ?", (purchase_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def link_purchase_spools(self, purchase_id: int, spool_ids: list[str], unit_price: float | None = None):
+        """Link spools to a purchase, moving them from any other purchase.
+
+        With a unit price, spools that have no price yet get it, so they are
+        costed in reports.
+        """
+        await self.conn.executemany(
+            "INSERT OR REPLACE INTO purchase_spools (spool_id, purchase_id) VALUES (?, ?)",
+            [(spool_id, purchase_id) for spool_id in spool_ids],
+        )
+        if unit_price is not None and spool_ids:
+            placeholders = ", ".join("?" for _ in spool_ids)
+            query = "UPDATE spools SET price = ?, updated_at = ? WHERE price IS NULL"
+            query += f" AND id IN ({placeholders})"  # nosec B608
+            await self.conn.execute(query, [round(unit_price, 2), int(time.time()), *spool_ids])
+        await self.conn.commit()
+
+    async def unlink_purchase_spool(self, purchase_id: int, spool_id: str) -> bool:
+        """Remove a spool from a purchase."""
+        cursor = await self.conn.execute(
+            "DELETE FROM purchase_spools WHERE purchase_id = ? AND spool_id = ?", (purchase_id, spool_id)
+        )
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def get_latest_purchases_by_material(self) -> dict[str, dict]:
+        """Most recent purchase that included a spool of each material (upper-cased), for reordering."""
+        async with self.conn.execute(
+            """SELECT UPPER(s.material) AS material_key, p.*
+               FROM purchases p
+               JOIN purchase_spools ps ON ps.purchase_id = p.id
+               JOIN spools s ON s.id = ps.spool_id
+               ORDER BY p.purchased_at DESC, p.id DESC"""
+        ) as cursor:
+            latest: dict[str, dict] = {}
+            for row in await cursor.fetchall():
+                purchase = dict(row)
+                latest.setdefault(purchase.pop("material_key"), purchase)
+            return latest
+
+    # ============ Stocktake Operations ============
+
+    @staticmethod
+    def _stocktake_row(row) -> dict:
+        stocktake = dict(row)
+        stocktake["report"] = json.loads(stocktake["report"]) if stocktake["report"] else None
+        return stocktake
+
+    async def create_stocktake(self, name: str | None = None) -> dict:
+        """Start a stocktake."""
+        cursor = await self.conn.execute(
+            "INSERT INTO stocktakes (name, status, started_at) VALUES (?, 'open', ?)", (name, int(time.time()))
+        )
+        await self.conn.commit()
+        return await self.get_stocktake(cursor.lastrowid)
+
+    async def get_stocktake(self, stocktake_id: int) -> dict | None:
+        """Get a stocktake by ID, with the number of spools counted so far."""
+        async with self.conn.execute(
+            """SELECT st.*, (SELECT COUNT(*) FROM stocktake_scans sc WHERE sc.stocktake_id = st.id) AS scan_count
+               FROM stocktakes st WHERE st.id = ?""",
+            (stocktake_id,),
+        ) as cursor:
+            row = await cursor.fetchone()
+            return self._stocktake_row(row) if row else None
+
+    async def get_open_stocktake(self) -> dict | None:
+        """Get the stocktake in progress, if any."""
+        query = "SELECT id FROM stocktakes WHERE status = 'open' ORDER BY id DESC LIMIT 1"
+        async with self.conn.execute(query) as cursor:
+            row = await cursor.fetchone()
+        return await self.get_stocktake(row["id"]) if row else None
+
+    async def get_stocktakes(self, limit: int = 50) -> list[dict]:
+        """Get stocktakes, newest first."""
+        async with self.conn.execute(
+            """SELECT st.*, (SELECT COUNT(*) FROM stocktake_scans sc WHERE sc.stocktake_id = st.id) AS scan_count
+               FROM stocktakes st ORDER BY st.started_at DESC, st.id DESC LIMIT ?""",
+            (limit,),
+        ) as cursor:
+            return [self._stocktake_row(row) for row in await cursor.fetchall()]
+
+    async def finish_stocktake(self, stocktake_id: int, status: str, report: dict | None = None) -> dict | None:
+        """Close a stocktake as completed (with its report) or cancelled."""
+        await self.conn.execute(
+            "UPDATE stocktakes SET status = ?, report = ?, finished_at = ? WHERE id = ?",
+            (status, json.dumps(report) if report is not None else None, int(time.time()), stocktake_id),
+        )
+        await self.conn.commit()
+        return await self.get_stocktake(stocktake_id)
+
+    async def save_stocktake_scan(
+        self,
+        stocktake_id: int,
+        spool_id: str,
+        tag_id: str | None,
+        weight: float | None,
+        expected_remaining: float | None,
+        source: str,
+    ) -> dict:
+        """Record a spool as counted. A rescan replaces the spool's earlier scan, keeping its weight if unweighed."""
+        await self.conn.execute(
+            """INSERT INTO stocktake_scans
+               (stocktake_id, spool_id, tag_id, weight, expected_remaining, source, scanned_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?)
+               ON CONFLICT(stocktake_id, spool_id) DO UPDATE SET
+                   tag_id = COALESCE(excluded.tag_id, tag_id),
+                   weight = COALESCE(excluded.weight, weight),
+                   expected_remaining = CASE WHEN excluded.weight IS NULL AND weight IS NOT NULL
+                       THEN expected_remaining ELSE excluded.expected_remaining END,
+                   source = excluded.source,
+                   scanned_at = excluded.scanned_at""",
+            (stocktake_id, spool_id, tag_id, weight, expected_remaining, source, int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_stocktake_scan(stocktake_id, spool_id)
+
+    async def get_stocktake_scan(self, stocktake_id: int, spool_id: str) -> dict | None:
+        """Get a spool's scan in a stocktake."""
+        async with self.conn.execute(
+            "SELECT * FROM stocktake_scans WHERE stocktake_id = ? AND spool_id = ?", (stocktake_id, spool_id)
+        ) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def get_stocktake_scans(self, stocktake_id: int) -> list[dict]:
+        """Get the scans of a stocktake, most recent first."""
+        async with self.conn.execute(
+            "SELECT * FROM stocktake_scans WHERE stocktake_id = ? ORDER BY scanned_at DESC, id DESC", (stocktake_id,)
+        ) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def delete_stocktake_scan(self, stocktake_id: int, spool_id: str) -> bool:
+        """Remove a spool's scan from a stocktake (e.g. a spool scanned by mistake)."""
+        cursor = await self.conn.execute(
+            "DELETE FROM stocktake_scans WHERE stocktake_id = ? AND spool_id = ?", (stocktake_id, spool_id)
+        )
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    # ============ Share Link Operations ============
+
+    @staticmethod
+    def _share_link_row(row) -> dict:
+        link = dict(row)
+        link["fields"] = json.loads(link["fields"])
+        return link
+
+    async def get_share_links(self) -> list[dict]:
+        """Get all share links, newest first."""
+        async with self.conn.execute("SELECT * FROM share_links ORDER BY created_at DESC, id DESC") as cursor:
+            return [self._share_link_row(row) for row in await cursor.fetchall()]
+
+    async def get_share_link(self, link_id: int) -> dict | None:
+        """Get a share link by ID."""
+        async with self.conn.execute("SELECT * FROM share_links WHERE id = ?", (link_id,)) as cursor:
+            row = await cursor.fetchone()
+            return self._share_link_row(row) if row else None
+
+    async def get_share_link_by_token(self, token: str) -> dict | None:
+        """Get a share link by its token, whether or not it is still valid."""
+        async with self.conn.execute("SELECT * FROM share_links WHERE token = ?", (token,)) as cursor:
+            row = await cursor.fetchone()
+            return self._share_link_row(row) if row else None
+
+    async def create_share_link(
+        self, token: str, fields: list[str], name: str | None = None, expires_at: int | None = None
+    ) -> dict:
+        """Create a share link."""
+        cursor = await self.conn.execute(
+            "INSERT INTO share_links (token, name, fields, expires_at, created_at) VALUES (?, ?, ?, ?, ?)",
+            (token, name, json.dumps(fields), expires_at, int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_share_link(cursor.lastrowid)
+
+    async def revoke_share_link(self, link_id: int) -> dict | None:
+        """Revoke a share link, keeping it listed. Revoking twice keeps the first revocation time."""
+        await self.conn.execute(
+            "UPDATE share_links SET revoked_at = COALESCE(revoked_at, ?) WHERE id = ?", (int(time.time()), link_id)
+        )
+        await self.conn.commit()
+        return await self.get_share_link(link_id)
+
+    async def delete_share_link(self, link_id: int) -> bool:
+        """Delete a share link."""
+        cursor = await self.conn.execute("DELETE FROM share_links WHERE id = ?", (link_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    async def record_share_link_access(self, link_id: int) -> None:
+        """Count a view of a share link."""
+        await self.conn.execute(
+            "UPDATE share_links SET access_count = access_count + 1, last_accessed_at = ? WHERE id = ?",
+            (int(time.time()), link_id),
+        )
+        await self.conn.commit()
+
+    # ============ Member Operations ============
+
+    async def get_members(self, include_inactive: bool = True) -> list[dict]:
+        """Get kiosk members by name, with the number of spools each has checked out."""
+        query = """SELECT m.*, (SELECT COUNT(*) FROM spool_checkouts c
+                       WHERE c.member_id = m.id AND c.checked_in_at IS NULL) AS open_checkouts
+                   FROM members m"""
+        if not include_inactive:
+            query += " WHERE m.active = 1"
+        async with self.conn.execute(query + " ORDER BY m.name COLLATE NOCASE, m.id") as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def get_member(self, member_id: int) -> dict | None:
+        """Get a kiosk member by ID."""
+        async with self.conn.execute(
+            """SELECT m.*, (SELECT COUNT(*) FROM spool_checkouts c
+                   WHERE c.member_id = m.id AND c.checked_in_at IS NULL) AS open_checkouts
+               FROM members m WHERE m.id = ?""",
+            (member_id,),
+        ) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def get_member_by_badge(self, badge_id: str) -> dict | None:
+        """Get the member with a badge, active or not."""
+        async with self.conn.execute("SELECT id FROM members WHERE badge_id = ?", (badge_id,)) as cursor:
+            row = await cursor.fetchone()
+        return await self.get_member(row["id"]) if row else None
+
+    async def create_member(self, name: str, badge_id: str | None = None, note: str | None = None) -> dict:
+        """Add a kiosk member."""
+        cursor = await self.conn.execute(
+            "INSERT INTO members (name, badge_id, note, created_at) VALUES (?, ?, ?, ?)",
+            (name, badge_id, note, int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_member(cursor.lastrowid)
+
+    async def update_member(self, member_id: int, **fields) -> dict | None:
+        """Update member fields (name, badge_id, note, active)."""
+        if fields:
+            set_clause = ", ".join(f"{key} = ?" for key in fields)
+            query = f"UPDATE members SET {set_clause} WHERE id = ?"  # nosec B608
+            await self.conn.execute(query, [*fields.values(), member_id])
+            await self.conn.commit()
+        return await self.get_member(member_id)
+
+    async def delete_member(self, member_id: int) -> bool:
+        """Delete a member. Their checkouts and usage are kept without the member."""
+        cursor = await self.conn.execute("DELETE FROM members WHERE id = ?", (member_id,))
+        await self.conn.execute("UPDATE spool_checkouts SET member_id = NULL WHERE member_id = ?", (member_id,))
+        await self.conn.execute("UPDATE spool_checkouts SET checked_in_by = NULL WHERE checked_in_by = ?", (member_id,))
+        await self.conn.execute("UPDATE usage_history SET member_id = NULL WHERE member_id = ?", (member_id,))
+        await self.conn.commit()
+        return cursor.rowcount > 0
+
+    # ============ Checkout Operations ============
+
+    _CHECKOUT_QUERY = """SELECT c.*, m.name AS member_name, s.spool_number, s.brand, s.material, s.color_name
+                         FROM spool_checkouts c
+                         LEFT JOIN members m ON m.id = c.member_id
+                         LEFT JOIN spools s ON s.id = c.spool_id"""
+
+    async def get_checkout(self, checkout_id: int) -> dict | None:
+        """Get a spool checkout by ID."""
+        async with self.conn.execute(f"{self._CHECKOUT_QUERY} WHERE c.id = ?", (checkout_id,)) as cursor:  # nosec B608
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def get_open_checkout(self, spool_id: str) -> dict | None:
+        """Get the checkout a spool is out on, if any."""
+        query = f"{self._CHECKOUT_QUERY} WHERE c.spool_id = ? AND c.checked_in_at IS NULL"  # nosec B608
+        async with self.conn.execute(query + " ORDER BY c.id DESC LIMIT 1", (spool_id,)) as cursor:
+            row = await cursor.fetchone()
+            return dict(row) if row else None
+
+    async def get_checkouts(
+        self, member_id: int | None = None, spool_id: str | None = None, open_only: bool = False, limit: int = 100
+    ) -> list[dict]:
+        """Get spool checkouts, most recent first."""
+        conditions, params = [], []
+        if member_id is not None:
+            conditions.append("c.member_id = ?")
+            params.append(member_id)
+        if spool_id is not None:
+            conditions.append("c.spool_id = ?")
+            params.append(spool_id)
+        if open_only:
+            conditions.append("c.checked_in_at IS NULL")
+        query = self._CHECKOUT_QUERY
+        if conditions:
+            query += " WHERE " + " AND ".join(conditions)
+        query += " ORDER BY c.checked_out_at DESC, c.id DESC LIMIT ?"
+        async with self.conn.execute(query, [*params, limit]) as cursor:
+            return [dict(row) for row in await cursor.fetchall()]
+
+    async def check_out_spool(self, spool_id: str, member_id: int, weight: float | None) -> dict:
+        """Check a spool out to a member."""
+        cursor = await self.conn.execute(
+            "INSERT INTO spool_checkouts (spool_id, member_id, weight_out, checked_out_at) VALUES (?, ?, ?, ?)",
+            (spool_id, member_id, weight, int(time.time())),
+        )
+        await self.conn.commit()
+        return await self.get_checkout(cursor.lastrowid)
+
+    async def check_in_spool(
+        self, checkout_id: int, member_id: int | None, weight: float | None, weight_used: float | None
+    ) -> dict | None:
+        """Close a checkout, recording who brought the spool back and the filament used."""
+        await self.conn.execute(
+            """UPDATE spool_checkouts SET checked_in_by = ?, weight_in = ?, weight_used = ?, checked_in_at = ?
+               WHERE id = ? AND checked_in_at IS NULL""",
+            (member_id, weight, weight_used, int(time.time()), checkout_id),
+        )
+        await self.conn.commit()
+        return await self.get_checkout(checkout_id)
+
+    # ============ Search Operations ============
+
+    async def rebuild_search_index(self):
+        """Re-index every searchable record."""
+        await self.conn.execute("DELETE FROM search_index")
+        for kind, (table, key, title, body) in SEARCH_SOURCES.items():
+            await self.conn.execute(
+                f"INSERT INTO search_index (kind, ref_id, title, body) "  # nosec B608
+                f"SELECT '{kind}', {key}, {title.format(row=table)}, {body.format(row=table)} FROM {table}"
+            )
+        await self.conn.commit()
+
+    async def search(self, text: str, kinds: list[str] | None = None, limit: int = 20) -> list[dict]:
+        """Full-text search over spools, printers and projects, best matches first.
+
+        Every word of the text must match the start of a word in the record
+        ("pla re" finds "PLA Red"). Trashed spools and printers are left out.
+        """
+        words = re.findall(r"\w+", text)
+        if not words:
+            return []
+        match = " ".join(f'"{word}"*' for word in words)
+
+        query = """
+            SELECT search_index.kind, search_index.ref_id, search_index.title,
+                   snippet(search_index, 3, '[', ']', '…', 8) AS snippet, bm25(search_index, 0, 0, 10, 1) AS rank
+            FROM search_index
+            LEFT JOIN spools ON search_index.kind = 'spool' AND spools.id = search_index.ref_id
+            LEFT JOIN printers ON search_index.kind = 'printer' AND printers.serial = search_index.ref_id
+            WHERE search_index MATCH ? AND spools.deleted_at IS NULL AND printers.deleted_at IS NULL
+        """
+        params: list = [match]
+        if kinds:
+            query += f" AND search_index.kind IN ({', '.join('?' for _ in kinds)})"
+            params.extend(kinds)
+        query += " ORDER BY rank LIMIT ?"
+        params.append(limit)
+
+        async with self.conn.execute(query, params) as cursor:  # nosec B608
+            return [dict(row) for row in await cursor.fetchall()]
+
+    # ============ Maintenance Operations ============
+
+    async def _delete_older_than(self, table: str, column: str, retention_days: int) -> int:
+        cutoff = int(time.time()) - retention_days * 86400
+        query = f"DELETE FROM {table} WHERE {column} < ?"  # nosec B608
+        cursor = await self.conn.execute(query, (cutoff,))
+        await self.conn.commit()
+        return cursor.rowcount
+
+    async def compact_weight_history(self, retention_days: int) -> int:
+        """Thin out weight readings older than the retention window.
+
+        Only the last reading per spool and day is kept, so long-term trends
+        survive. Returns the number of deleted readings.
+        """
+        cutoff = int(time.time()) - retention_days * 86400
+        cursor = await self.conn.execute(
+            """DELETE FROM weight_history
+               WHERE recorded_at < ? AND id NOT IN (
+                   SELECT MAX(id) FROM weight_history
+                   WHERE recorded_at < ?
+                   GROUP BY spool_id, recorded_at / 86400
+               )""",
+            (cutoff, cutoff),
+        )
+        await self.conn.commit()
+        return cursor.rowcount
+
+    async def prune_webhook_deliveries(self, retention_days: int) -> int:
+        """Delete webhook delivery log entries older than the retention window."""
+        return await self._delete_older_than("webhook_deliveries", "created_at", retention_days)
+
+    async def prune_crash_reports(self, retention_days: int) -> int:
+        """Delete crash reports older than the retention window."""
+        return await self._delete_older_than("crash_reports", "created_at", retention_days)
+
+    async def get_database_size(self) -> int:
+        """Size of the database file in bytes (pages in use and free)."""
+        async with self.conn.execute("PRAGMA page_count") as cursor:
+            page_count = (await cursor.fetchone())[0]
+        async with self.conn.execute("PRAGMA page_size") as cursor:
+            page_size = (await cursor.fetchone())[0]
+        return page_count * page_size
+
+    async def optimize(self, vacuum: bool = True) -> None:
+        """Refresh query planner statistics and optionally rebuild the file to reclaim space."""
+        await self.conn.commit()
+        await self.conn.execute("ANALYZE")
+        await self.conn.commit()
+        if vacuum:
+            # VACUUM can't run inside a transaction
+            await self.conn.execute("VACUUM")
+
+    async def get_journal_mode(self) -> str:
+        """Current journal mode ("wal", "delete", or "memory" for in-memory databases)."""
+        async with self.conn.execute("PRAGMA journal_mode") as cursor:
+            return (await cursor.fetchone())[0]
+
+    def get_wal_size(self) -> int:
+        """Size of the write-ahead log file in bytes (0 without one)."""
+        wal_path = Path(f"{self.db_path}-wal")
+        return wal_path.stat().st_size if wal_path.exists() else 0
+
+    async def checkpoint(self, mode: str = "PASSIVE") -> dict:
+        """Copy the WAL into the database file.
+
+        PASSIVE never waits for readers or writers, TRUNCATE waits and also
+        empties the WAL file. Returns the frames in the WAL and the frames
+        checkpointed, which differ when a reader held the checkpoint back.
+        """
+        if mode not in ("PASSIVE", "FULL", "RESTART", "TRUNCATE"):
+            raise ValueError(f"Unknown checkpoint mode {mode}")
+        await self.conn.commit()
+        async with self.conn.execute(f"PRAGMA wal_checkpoint({mode})") as cursor:
+            busy, log_frames, checkpointed_frames = await cursor.fetchone()
+        self.last_checkpoint = {
+            "at": int(time.time()),
+            "mode": mode,
+            "busy": bool(busy),
+            "log_frames": log_frames,
+            "checkpointed_frames": checkpointed_frames,
+        }
+        return self.last_checkpoint
+
 
 # Global database instance
 _db: Database | None = None
@@ -1199,3 +4119,11 @@ async def get_db() -> Database:
         _db = Database(settings.database_path)
         await _db.connect()
     return _db
+
+
+async def close_db():
+    """Close the database instance (on shutdown). A later get_db() reconnects."""
+    global _db
+    if _db is not None:
+        await _db.disconnect()
+        _db = None
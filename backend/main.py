@@ -1,44 +1,123 @@
 import asyncio
 import csv
+import functools
 import json
 import logging
+import re
+import signal
 import socket
 import time
 from contextlib import asynccontextmanager
 from pathlib import Path
+from typing import Literal
 
 from api import (
+    admin_router,
     api_keys_router,
     catalog_router,
     colors_router,
+    config_bundle_router,
+    crash_reports_router,
     device_router,
+    diagnostics_router,
     discovery_router,
     firmware_router,
+    integrations_router,
+    jobs_router,
+    kiosk_router,
+    moonraker_router,
+    notifications_router,
     printers_router,
+    projects_router,
+    purchases_router,
+    reconciliation_router,
+    reports_router,
+    search_router,
     serial_router,
+    share_links_router,
+    share_router,
+    slicer_router,
     spools_router,
+    stocktakes_router,
     support_router,
     tags_router,
+    templates_router,
+    trash_router,
     updates_router,
+    webhooks_router,
 )
+from api.api_keys import api_key_from_headers, authenticate_api_key
 from api.cloud import router as cloud_router
-from api.printers import set_printer_manager
+from api.discovery import stop_printer_discovery
+from api.printers import apply_nozzle_k_profiles, set_printer_manager
+from api.projects import get_active_project_id
+from api.settings import get_ams_history_retention_days
 from api.settings import router as settings_router
 from api.support import init_debug_logging
 from config import settings
-from db import get_db
-from fastapi import FastAPI, WebSocket, WebSocketDisconnect
+from db import close_db, get_db
+from fastapi import FastAPI, Header, Query, Request, WebSocket, WebSocketDisconnect
 from fastapi.middleware.cors import CORSMiddleware
+from fastapi.responses import JSONResponse, StreamingResponse
 from fastapi.staticfiles import StaticFiles
-from models import PrinterState
+from models import (
+    DeviceAction,
+    DeviceSpoolInfo,
+    DeviceStateResponse,
+    EncodeTask,
+    FirmwareModule,
+    KioskStatus,
+    NozzleInfo,
+    PrinterState,
+)
 from mqtt import PrinterManager
+from mqtt.client import PrinterConnection, ams_modules, printer_versions
+from services.aging import spools_aging
+from services.ams_events import AmsSlotEvent, detect_ams_events
+from services.demo import DemoPrinterConnection, seed_demo_data
+from services.event_stream import get_event_stream
+from services.forecast import DEPLETION_ALERT_DEFAULT_DAYS, forecast_spool, remaining_grams
+from services.jobs import Job, get_job_runner
+from services.kiosk import get_kiosk, get_kiosk_config
+from services.maintenance import retention_policy, run_maintenance
+from services.moonraker import (
+    ACTIVE_SPOOL_SLOT,
+    ActiveSpoolEvent,
+    JobFinishedEvent,
+    filament_grams,
+    get_moonraker_manager,
+    printer_key,
+)
+from services.mqtt_events import get_mqtt_events
+from services.perf import get_perf_stats
+from services.print_job import fetch_sliced_weight
+from services.reconciliation import scan_measurements
+from services.spool_status import DISPLAY_DEVICE, get_spool_status_tracker
+from services.state_coalescer import CLIENT_DEVICE, CLIENT_WEB, PrinterStateCoalescer
+from services.static_assets import EmbeddedStaticFiles
+from services.stocktake import record_device_scan
+from services.tag_batch import get_encoding_station
+from services.tracing import TraceContextFilter, get_printer_log_handler, request_id_from, span
+from services.webhooks import (
+    EVENT_PRINT_FINISHED,
+    EVENT_PRINTER_AUTH_FAILED,
+    EVENT_PRINTER_ERROR,
+    EVENT_PRINTER_FIRMWARE_CHANGED,
+    EVENT_SPOOL_AGING,
+    EVENT_SPOOL_DEPLETING,
+    EVENT_SPOOL_LOW,
+    EVENT_TAG_SCANNED,
+    emit_event,
+)
+from services.ws_clients import get_ws_clients
 from tags import TagDecoder
 from usage_tracker import UsageTracker, estimate_weight_from_percent
 from zeroconf import ServiceInfo
 from zeroconf.asyncio import AsyncZeroconf
 
 # Configure logging with both console and file output
-LOG_FORMAT = "%(asctime)s %(levelname)s [%(name)s] %(message)s"
+# %(trace)s: " [serial req=id]" inside a tracing span
+LOG_FORMAT = "%(asctime)s %(levelname)s [%(name)s]%(trace)s %(message)s"
 LOG_FILE = "spoolbuddy.log"
 
 # Set up root logger
@@ -49,14 +128,19 @@ root_logger.setLevel(logging.INFO)
 console_handler = logging.StreamHandler()
 console_handler.setLevel(logging.INFO)
 console_handler.setFormatter(logging.Formatter(LOG_FORMAT))
+console_handler.addFilter(TraceContextFilter())
 root_logger.addHandler(console_handler)
 
 # File handler
 file_handler = logging.FileHandler(LOG_FILE, encoding="utf-8")
 file_handler.setLevel(logging.INFO)
 file_handler.setFormatter(logging.Formatter(LOG_FORMAT))
+file_handler.addFilter(TraceContextFilter())
 root_logger.addHandler(file_handler)
 
+# Per-printer log buffers for /api/printers/{serial}/logs/stream
+root_logger.addHandler(get_printer_log_handler())
+
 logger = logging.getLogger(__name__)
 
 # === Bambu Color Name Lookup ===
@@ -128,8 +212,12 @@ def lookup_bambu_color_name(material_id: str, color_rgba: int) -> str | None:
 _load_bambu_color_map()
 
 # Global state
-printer_manager = PrinterManager()
-websocket_clients: set[WebSocket] = set()
+printer_manager = PrinterManager(DemoPrinterConnection if settings.demo else PrinterConnection)
+# Server lifecycle for /api/health: starting, ready or stopping
+_lifecycle: str = "starting"
+# Background loops started at startup, cancelled on shutdown
+_background_tasks: set[asyncio.Task] = set()
+WEBSOCKET_CLOSE_TIMEOUT = 2.0
 usage_tracker = UsageTracker()
 # Track previous printer states for comparison
 _previous_states: dict[str, PrinterState] = {}
@@ -149,11 +237,17 @@ _device_update_available: bool = False
 # Device state (weight, tag) - updated by WebSocket messages from device
 _device_last_weight: float | None = None
 _device_weight_stable: bool = False
+# Per-channel readings when the device has more than one load cell
+# ([{"channel": 0, "weight": ..., "stable": ...}, ...]), None for single-scale devices
+_device_scales: list[dict] | None = None
 # Device WiFi status - reported by ESP32
 _device_wifi_ssid: str | None = None
 _device_wifi_ip: str | None = None
 _device_wifi_rssi: int | None = None
 _device_wifi_state: int = 0  # 0=uninitialized, 1=disconnected, 2=connecting, 3=connected, 4=error
+# Pico NFC bridge health - reported by ESP32 (None on devices without a bridge)
+# {"status": "ok"|"degraded"|"hung"|"recovering", "firmware": "1.3", "failures": ..., "garbage": ..., "resets": ...}
+_device_nfc_bridge: dict | None = None
 
 # === Tag Staging System ===
 # When a tag is detected, it goes to "staging" for 30 seconds.
@@ -252,6 +346,7 @@ def stage_tag(tag_id: str, tag_data: dict) -> bool:
 
     if is_new_tag:
         logger.info(f"Staged new tag: {tag_id} ({tag_data.get('vendor')} {tag_data.get('material')})")
+        emit_event(EVENT_TAG_SCANNED, {"tag_id": tag_id, "tag_data": tag_data})
 
     return is_new_tag
 
@@ -346,18 +441,21 @@ async def udp_log_listener():
     logger.info(f"UDP log listener started on port {UDP_LOG_PORT}")
 
     loop = asyncio.get_event_loop()
-    while True:
-        try:
-            data, addr = await loop.run_in_executor(None, lambda: sock.recvfrom(4096))
-            message = data.decode("utf-8", errors="replace").strip()
-            if message:
-                # Print with ESP32 prefix for clarity
-                print(f"[ESP32] {message}")
-        except BlockingIOError:
-            await asyncio.sleep(0.01)
-        except Exception as e:
-            logger.error(f"UDP listener error: {e}")
-            await asyncio.sleep(1)
+    try:
+        while True:
+            try:
+                data, addr = await loop.run_in_executor(None, lambda: sock.recvfrom(4096))
+                message = data.decode("utf-8", errors="replace").strip()
+                if message:
+                    # Print with ESP32 prefix for clarity
+                    print(f"[ESP32] {message}")
+            except BlockingIOError:
+                await asyncio.sleep(0.01)
+            except Exception as e:
+                logger.error(f"UDP listener error: {e}")
+                await asyncio.sleep(1)
+    finally:
+        sock.close()
 
 
 async def check_display_timeout():
@@ -374,72 +472,323 @@ async def check_display_timeout():
 
 
 async def broadcast_message(message: dict):
-    """Broadcast message to all connected WebSocket clients."""
-    if not websocket_clients:
+    """Broadcast message to all connected WebSocket and SSE clients, and over MQTT if enabled.
+
+    printer_state messages are coalesced per client class first.
+    """
+    if message.get("type") == "printer_state":
+        for coalescer in _state_coalescers.values():
+            await coalescer.submit(message)
         return
+    await _send_to_clients(message)
+
 
-    text = json.dumps(message)
+async def _send_to_clients(message: dict, client_class: str | None = None):
+    """Send a message to the clients of one class, or to all of them.
+
+    SSE and MQTT subscribers get what web clients get.
+    """
+    text = None
+    if client_class in (None, CLIENT_WEB):
+        text = get_event_stream().publish(message).data
+        get_mqtt_events().publish_event(message)
+
+    printer_serial = message.get("serial") if message.get("type") == "printer_state" else None
+    targets = get_ws_clients().targets(client_class, printer_serial)
+    if not targets:
+        return
+
+    text = text or json.dumps(message)
     disconnected = set()
 
-    for ws in websocket_clients:
+    for ws in targets:
         try:
             await ws.send_text(text)
         except Exception:
             disconnected.add(ws)
 
     # Clean up disconnected clients
-    websocket_clients.difference_update(disconnected)
+    for ws in disconnected:
+        get_ws_clients().remove(ws)
+
+
+_state_coalescers = {
+    CLIENT_WEB: PrinterStateCoalescer(
+        settings.printer_state_interval_web_ms, functools.partial(_send_to_clients, client_class=CLIENT_WEB)
+    ),
+    CLIENT_DEVICE: PrinterStateCoalescer(
+        settings.printer_state_interval_device_ms, functools.partial(_send_to_clients, client_class=CLIENT_DEVICE)
+    ),
+}
+
+
+async def on_job_update(job: Job):
+    """Broadcast a background job's status and progress."""
+    await broadcast_message({"type": "job_progress", "job": job.model_dump(mode="json")})
 
 
-async def on_usage_logged(serial: str, print_name: str, tray_usage: dict):
+async def on_usage_logged(serial: str, print_name: str, tray_usage: dict, gcode_file: str | None = None):
     """Handle filament usage detection from print completion.
 
     Args:
         serial: Printer serial number
         print_name: Name of the completed print
-        tray_usage: Dict of (ams_id, tray_id) -> percent_used
+        tray_usage: Dict of (ams_id, tray_id) -> percent_used, None for external
+            holder slots whose usage has to be estimated from the sliced file
+        gcode_file: Plate gcode path of the print, to read the sliced weight
     """
     db = await get_db()
+    project_id = await get_active_project_id(db)
 
+    ams_grams = 0.0  # Estimated grams fed from trays with a remain reading
     for (ams_id, tray_id), percent_used in tray_usage.items():
-        # Look up assigned spool for this slot
-        spool_id = await db.get_spool_for_slot(serial, ams_id, tray_id)
-
-        if not spool_id:
-            logger.debug(f"No spool assigned to slot ({ams_id}, {tray_id}) on {serial}, skipping usage logging")
+        if percent_used is None:
             continue
 
-        # Get spool to calculate weight from percentage
-        spool = await db.get_spool(spool_id)
-        if not spool:
-            continue
+        # Look up assigned spool for this slot
+        spool_id = await db.get_spool_for_slot(serial, ams_id, tray_id)
+        spool = await db.get_spool(spool_id) if spool_id else None
 
         # Estimate grams used
-        label_weight = spool.label_weight or 1000
+        label_weight = (spool.label_weight if spool else None) or 1000
         weight_used = estimate_weight_from_percent(percent_used, label_weight)
+        ams_grams += weight_used
 
-        # Log usage history
-        await db.log_usage(spool_id, serial, print_name, weight_used)
-
-        # Update spool consumption
-        await db.update_spool_consumption(spool_id, weight_used)
+        if not spool:
+            logger.debug(f"No spool assigned to slot ({ams_id}, {tray_id}) on {serial}, skipping usage logging")
+            continue
 
+        await _record_usage(db, spool, serial, print_name, weight_used, project_id)
         logger.info(
             f"Logged usage for spool {spool_id}: {weight_used:.1f}g "
             f"({percent_used}% of {label_weight}g spool) from '{print_name}'"
         )
 
+    external_usage = {}
+    holders = [key for key, percent_used in tray_usage.items() if percent_used is None]
+    if holders:
+        external_usage = await _log_external_usage(db, serial, print_name, gcode_file, holders, ams_grams, project_id)
+
     # Broadcast usage update to UI
     await broadcast_message(
         {
             "type": "usage_logged",
             "serial": serial,
             "print_name": print_name,
-            "tray_usage": {f"{k[0]}_{k[1]}": v for k, v in tray_usage.items()},
+            "tray_usage": {f"{k[0]}_{k[1]}": v for k, v in tray_usage.items() if v is not None},
+            "external_usage": {f"{k[0]}_{k[1]}": v for k, v in external_usage.items()},
         }
     )
 
 
+async def on_print_ended(serial: str, print_name: str, duration_sec: int):
+    """Record the estimated energy use of a finished or failed print.
+
+    Only printers with a configured average power draw are tracked.
+    """
+    db = await get_db()
+    printer = await db.get_printer(serial)
+    if not printer or not printer.power_watts or duration_sec <= 0:
+        return
+
+    project_id = await get_active_project_id(db)
+    await db.log_print_energy(serial, print_name, duration_sec, printer.power_watts, project_id=project_id)
+    logger.info(
+        f"Logged print energy for '{print_name}' on {serial}: "
+        f"{printer.power_watts * duration_sec / 3600 / 1000:.3f}kWh ({duration_sec}s at {printer.power_watts:.0f}W)"
+    )
+
+
+async def on_moonraker_event(printer_id: int, event: ActiveSpoolEvent | JobFinishedEvent):
+    """Sync a Klipper printer's active spool and print usage from Moonraker.
+
+    The active spool (a Moonraker/Spoolman spool ID, matched to SpoolBuddy's
+    spool number) becomes the printer's single slot assignment; finished jobs
+    are charged to the spool assigned at the time.
+    """
+    db = await get_db()
+    serial = printer_key(printer_id)
+    ams_id, tray_id = ACTIVE_SPOOL_SLOT
+
+    if isinstance(event, ActiveSpoolEvent):
+        spool = await db.get_spool_by_number(event.spool_id) if event.spool_id is not None else None
+        if spool:
+            await db.assign_spool_to_slot(spool.id, serial, ams_id, tray_id)
+        else:
+            if event.spool_id is not None:
+                logger.warning(f"Moonraker spool {event.spool_id} on {serial} matches no SpoolBuddy spool number")
+            await db.unassign_slot(serial, ams_id, tray_id)
+        await broadcast_message(
+            {"type": "moonraker_spool", "printer_id": printer_id, "spool_id": spool.id if spool else None}
+        )
+        await publish_spool_status(db)
+        return
+
+    if event.filament_used <= 0:
+        return
+    spool_id = await db.get_spool_for_slot(serial, ams_id, tray_id)
+    spool = await db.get_spool(spool_id) if spool_id else None
+    if not spool:
+        logger.debug(f"No active spool on {serial}, skipping usage logging for '{event.print_name}'")
+        return
+
+    weight_used = filament_grams(event.filament_used, spool.material, event.metadata)
+    project_id = await get_active_project_id(db)
+    await _record_usage(db, spool, serial, event.print_name, weight_used, project_id)
+    logger.info(
+        f"Logged Moonraker usage for spool {spool.id}: {weight_used:.1f}g "
+        f"({event.filament_used:.0f}mm, job {event.status}) from '{event.print_name}'"
+    )
+    await broadcast_message(
+        {
+            "type": "usage_logged",
+            "serial": serial,
+            "print_name": event.print_name,
+            "tray_usage": {},
+            "external_usage": {f"{ams_id}_{tray_id}": round(weight_used, 1)},
+        }
+    )
+
+
+async def _record_usage(db, spool, serial: str, print_name: str, weight_used: float, project_id: int | None):
+    """Log a print's usage of a spool and run the low/depletion checks."""
+    await db.log_usage(spool.id, serial, print_name, weight_used, project_id=project_id)
+
+    # Update spool consumption
+    updated = await db.update_spool_consumption(spool.id, weight_used)
+    if updated:
+        await _check_spool_low(spool, updated)
+        await _check_spool_depletion(spool, updated)
+
+
+async def _log_external_usage(
+    db, serial: str, print_name: str, gcode_file: str | None, holders: list, ams_grams: float, project_id: int | None
+) -> dict:
+    """Charge external holder spools with the sliced weight the AMS trays don't account for.
+
+    Returns {(ams_id, tray_id): grams} for the holders that were charged.
+    """
+    printer = await db.get_printer(serial)
+    if not printer or not printer.ip_address or not printer.access_code:
+        return {}
+
+    sliced_grams = await fetch_sliced_weight(printer.ip_address, printer.access_code, print_name, gcode_file)
+    if sliced_grams is None:
+        logger.info(f"No sliced weight for '{print_name}' on {serial}, external spool usage not logged")
+        return {}
+
+    weight_used = max(0.0, sliced_grams - ams_grams) / len(holders)
+    logged = {}
+    for ams_id, tray_id in holders:
+        spool_id = await db.get_spool_for_slot(serial, ams_id, tray_id)
+        spool = await db.get_spool(spool_id) if spool_id else None
+        if not spool or weight_used <= 0:
+            continue
+        await _record_usage(db, spool, serial, print_name, weight_used, project_id)
+        logged[(ams_id, tray_id)] = round(weight_used, 1)
+        logger.info(
+            f"Logged external spool usage for spool {spool_id}: {weight_used:.1f}g "
+            f"(sliced {sliced_grams:.1f}g, AMS {ams_grams:.1f}g) from '{print_name}'"
+        )
+    return logged
+
+
+SPOOL_LOW_DEFAULT_PERCENT = 20  # Matches the web UI's low stock badge
+
+
+def _remaining_percent(spool) -> float | None:
+    """Remaining filament as a percentage of label weight."""
+    if not spool.label_weight:
+        return None
+    used = (spool.weight_used or 0) + (spool.consumed_since_weight or 0)
+    return max(0.0, 100.0 - used * 100.0 / spool.label_weight)
+
+
+async def _spool_low_threshold(db) -> float:
+    """Low stock threshold in percent of label weight."""
+    threshold_str = await db.get_setting("spool_low_threshold")
+    return float(threshold_str) if threshold_str else SPOOL_LOW_DEFAULT_PERCENT
+
+
+async def _check_spool_low(before, after):
+    """Emit spool.low when consumption pushes a spool below the low stock threshold."""
+    db = await get_db()
+    threshold = await _spool_low_threshold(db)
+
+    prev_remaining = _remaining_percent(before)
+    remaining = _remaining_percent(after)
+    if remaining is None or prev_remaining is None:
+        return
+    if remaining < threshold <= prev_remaining:
+        emit_event(
+            EVENT_SPOOL_LOW,
+            {
+                "spool_id": after.id,
+                "material": after.material,
+                "color_name": after.color_name,
+                "brand": after.brand,
+                "remaining_percent": round(remaining, 1),
+                "threshold": threshold,
+            },
+        )
+
+
+async def _check_spool_depletion(before, after):
+    """Emit spool.depleting when a spool's forecast drops below the alert horizon."""
+    db = await get_db()
+    days_str = await db.get_setting("spool_depletion_alert_days")
+    alert_days = float(days_str) if days_str else DEPLETION_ALERT_DEFAULT_DAYS
+
+    forecast = await forecast_spool(db, after)
+    prev_remaining = remaining_grams(before)
+    if forecast.days_remaining is None or prev_remaining is None:
+        return
+    prev_days = prev_remaining / forecast.daily_usage
+    if forecast.days_remaining < alert_days <= prev_days:
+        emit_event(
+            EVENT_SPOOL_DEPLETING,
+            {
+                "spool_id": after.id,
+                "material": after.material,
+                "color_name": after.color_name,
+                "brand": after.brand,
+                "remaining_grams": forecast.remaining_grams,
+                "daily_usage": forecast.daily_usage,
+                "days_remaining": forecast.days_remaining,
+                "depletion_date": forecast.depletion_date,
+            },
+        )
+
+
+async def _check_spool_aging(now: int | None = None):
+    """Emit spool.aging for opened spools whose shelf life ran out since the last check.
+
+    The first check only records its time, so existing aged spools don't all alert at once.
+    """
+    db = await get_db()
+    now = now or int(time.time())
+    checked_str = await db.get_setting("spool_aging_checked_at")
+    await db.set_setting("spool_aging_checked_at", str(now))
+    if not checked_str:
+        return
+
+    spools = [s for s in await db.get_spools() if s.archived_at is None and s.opened_at is not None]
+    for spool, aging in zip(spools, await spools_aging(db, spools, now), strict=True):
+        if aging.aging and aging.expires_at > int(checked_str):
+            emit_event(
+                EVENT_SPOOL_AGING,
+                {
+                    "spool_id": spool.id,
+                    "material": spool.material,
+                    "color_name": spool.color_name,
+                    "brand": spool.brand,
+                    "opened_at": aging.opened_at,
+                    "open_days": aging.open_days,
+                    "shelf_life_months": aging.shelf_life_months,
+                },
+            )
+
+
 async def _record_ams_sensors(serial: str, state: PrinterState):
     """Record AMS sensor data (humidity/temperature) with rate limiting."""
     global _ams_sensor_last_record
@@ -475,8 +824,51 @@ async def _record_ams_sensors(serial: str, state: PrinterState):
             logger.warning(f"Failed to record AMS sensor data for {serial} AMS {ams_id}: {e}")
 
 
-def on_printer_state_update(serial: str, state: PrinterState):
-    """Handle printer state update from MQTT."""
+async def publish_spool_status(db=None):
+    """Push spools whose live status (on scale, loaded, storage) changed to UI clients."""
+    try:
+        db = db or await get_db()
+        changed = await get_spool_status_tracker().changes(db)
+    except Exception as e:
+        logger.warning(f"Failed to update spool statuses: {e}")
+        return
+    if changed:
+        await broadcast_message(
+            {
+                "type": "spool_status",
+                "spools": {spool_id: status.model_dump() for spool_id, status in changed.items()},
+            }
+        )
+
+
+async def _update_spool_on_scale(tag_id: str | None):
+    """Track the spool whose tag is on the scale."""
+    db = await get_db()
+    spool = None
+    if tag_id:
+        spool = await db.get_spool_by_tag(tag_id)
+    get_spool_status_tracker().set_on_scale(DISPLAY_DEVICE, spool.id if spool else None)
+    await publish_spool_status(db)
+
+
+async def _record_ams_events(serial: str, events: list[AmsSlotEvent]):
+    """Store tray transitions in the AMS slot ledger, with the spool assigned to each slot."""
+    try:
+        db = await get_db()
+        for event in events:
+            spool_id = await db.get_spool_for_slot(serial, event.ams_id, event.tray_id)
+            await db.record_ams_event(serial, spool_id=spool_id, **event.model_dump())
+            logger.info(f"AMS event on {serial}: {event.event} AMS {event.ams_id} tray {event.tray_id}")
+    except Exception as e:
+        logger.warning(f"Failed to record AMS events for {serial}: {e}")
+
+
+# Printer state fields that can move spools in or out of slots
+SPOOL_STATUS_FIELDS = {"ams_units", "vt_tray", "tray_now", "tray_now_left", "tray_now_right"}
+
+
+def on_printer_state_update(serial: str, state: PrinterState, changes: list[str]):
+    """Handle printer state update from MQTT (only called when the state changed)."""
     global _previous_states
 
     # Get previous state for comparison
@@ -485,6 +877,31 @@ def on_printer_state_update(serial: str, state: PrinterState):
     # Update usage tracker (detects print start/end)
     usage_tracker.on_state_update(serial, state, prev_state)
 
+    # Notify webhooks about print completion and failures
+    prev_gcode_state = prev_state.gcode_state if prev_state else None
+    if prev_gcode_state in ("RUNNING", "PAUSE") and state.gcode_state in ("FINISH", "FAILED"):
+        emit_event(
+            EVENT_PRINT_FINISHED,
+            {
+                "serial": serial,
+                "print_name": state.subtask_name,
+                "success": state.gcode_state == "FINISH",
+            },
+        )
+    if state.gcode_state == "FAILED" and prev_gcode_state != "FAILED":
+        emit_event(
+            EVENT_PRINTER_ERROR,
+            {
+                "serial": serial,
+                "gcode_state": state.gcode_state,
+                "print_name": state.subtask_name,
+                "print_error": state.print_error_code,
+            },
+        )
+
+    # Slot ledger: spools inserted/removed, filament loaded/unloaded
+    ams_events = detect_ams_events(prev_state, state)
+
     # Store current state as previous for next update
     _previous_states[serial] = state.model_copy()
 
@@ -493,6 +910,7 @@ def on_printer_state_update(serial: str, state: PrinterState):
         "type": "printer_state",
         "serial": serial,
         "state": state.model_dump(),
+        "changes": changes,  # Top-level state fields that changed since the last message
     }
 
     # Schedule broadcast and AMS sensor recording in event loop
@@ -502,6 +920,10 @@ def on_printer_state_update(serial: str, state: PrinterState):
         # Record AMS sensor data (rate-limited)
         if state.ams_units:
             loop.create_task(_record_ams_sensors(serial, state))
+        if ams_events:
+            loop.create_task(_record_ams_events(serial, ams_events))
+        if SPOOL_STATUS_FIELDS.intersection(changes):
+            loop.create_task(publish_spool_status())
     except RuntimeError:
         pass  # No running loop
 
@@ -528,8 +950,10 @@ def on_printer_disconnect(serial: str):
     """Handle printer disconnection from MQTT."""
     logger.info(f"Printer {serial} disconnected - notifying clients")
 
-    # Clear previous state
+    # Clear previous state, and drop a held state report so it isn't sent after the disconnect
     _previous_states.pop(serial, None)
+    for coalescer in _state_coalescers.values():
+        coalescer.forget(serial)
 
     # Broadcast disconnection
     message = {
@@ -545,6 +969,23 @@ def on_printer_disconnect(serial: str):
         pass  # No running loop
 
 
+def on_printer_auth_failed(serial: str):
+    """Handle a printer refusing its access code (it is not reconnected until the code changes)."""
+    logger.warning(f"Printer {serial} refused its access code - notifying clients")
+
+    async def notify():
+        db = await get_db()
+        printer = await db.get_printer(serial)
+        emit_event(EVENT_PRINTER_AUTH_FAILED, {"serial": serial, "name": printer.name if printer else None})
+        await broadcast_message({"type": "printer_auth_failed", "serial": serial})
+
+    try:
+        loop = asyncio.get_running_loop()
+        loop.create_task(notify())
+    except RuntimeError:
+        pass  # No running loop
+
+
 def on_nozzle_count_update(serial: str, nozzle_count: int):
     """Handle nozzle count detection from MQTT (auto-detect dual-nozzle printers)."""
     logger.info(f"Printer {serial} detected as {nozzle_count}-nozzle printer")
@@ -561,6 +1002,113 @@ def on_nozzle_count_update(serial: str, nozzle_count: int):
         pass  # No running loop
 
 
+async def handle_nozzle_change(serial: str, nozzles: list[NozzleInfo]):
+    """Record the installed nozzles and switch K-profiles for swapped ones."""
+    db = await get_db()
+    changes = []
+    for nozzle in nozzles:
+        change = await db.set_printer_nozzle(serial, nozzle.extruder_id, nozzle.diameter, nozzle.nozzle_type)
+        if change:
+            changes.append(change)
+    if not changes:
+        return
+
+    for change in changes:
+        logger.info(
+            f"Printer {serial} extruder {change['extruder_id']}: nozzle changed from "
+            f"{change['old_diameter']} {change['old_type']} to {change['new_diameter']} {change['new_type']}"
+        )
+    await broadcast_message(
+        {
+            "type": "nozzle_changed",
+            "serial": serial,
+            "nozzles": [n.model_dump() for n in nozzles],
+            "changes": changes,
+        }
+    )
+    await apply_nozzle_k_profiles(db, serial, {c["extruder_id"] for c in changes})
+
+
+def on_nozzle_change(serial: str, nozzles: list[NozzleInfo]):
+    """Handle installed nozzles reported over MQTT."""
+    try:
+        loop = asyncio.get_running_loop()
+        loop.create_task(handle_nozzle_change(serial, nozzles))
+    except RuntimeError:
+        pass  # No running loop
+
+
+async def sync_ams_units(db, serial: str, modules: list[FirmwareModule]):
+    """Record the printer's AMS units by serial number, following units whose ams_id changed."""
+    state = printer_manager.get_state(serial)
+    humidity = {unit.id: unit.humidity is not None for unit in state.ams_units} if state else {}
+    units = [
+        {
+            "serial": module.sn,
+            "ams_id": ams_id,
+            "module": kind,
+            "product_name": module.product_name,
+            "firmware_version": module.sw_ver,
+            "hardware_version": module.hw_ver,
+            "humidity_sensor": humidity.get(ams_id),
+        }
+        for ams_id, (kind, module) in sorted(ams_modules(modules).items())
+    ]
+    moves = await db.sync_ams_units(serial, units)
+    for move in moves:
+        logger.info(f"AMS {move['serial']} on {serial} moved from {move['old_ams_id']} to {move['new_ams_id']}")
+    if moves:
+        await broadcast_message({"type": "ams_units_moved", "serial": serial, "moves": moves})
+
+
+async def handle_printer_version(serial: str, modules: list[FirmwareModule]):
+    """Store a printer's firmware/hardware versions and flag firmware updates."""
+    firmware_version, hardware_version = printer_versions(modules)
+    db = await get_db()
+    await sync_ams_units(db, serial, modules)
+    previous = await db.set_printer_versions(serial, firmware_version, hardware_version)
+    if not previous or (previous.firmware_version, previous.hardware_version) == (firmware_version, hardware_version):
+        return
+
+    await broadcast_message({"type": "printer_updated", "serial": serial})
+    # Nothing to compare against the first time a printer reports its version
+    if previous.firmware_version and firmware_version and previous.firmware_version != firmware_version:
+        logger.warning(f"Printer {serial} firmware changed from {previous.firmware_version} to {firmware_version}")
+        emit_event(
+            EVENT_PRINTER_FIRMWARE_CHANGED,
+            {
+                "serial": serial,
+                "name": previous.name,
+                "old_version": previous.firmware_version,
+                "new_version": firmware_version,
+            },
+        )
+
+
+def on_printer_version(serial: str, modules: list[FirmwareModule]):
+    """Handle a printer reporting its firmware versions."""
+    try:
+        loop = asyncio.get_running_loop()
+        loop.create_task(handle_printer_version(serial, modules))
+    except RuntimeError:
+        pass  # No running loop
+
+
+async def save_cert_fingerprint(serial: str, fingerprint: str):
+    """Store a printer's TLS certificate pinned on first connect."""
+    db = await get_db()
+    await db.set_printer_cert_fingerprint(serial, fingerprint)
+
+
+def on_cert_pinned(serial: str, fingerprint: str):
+    """Handle a printer's TLS certificate being pinned."""
+    try:
+        loop = asyncio.get_running_loop()
+        loop.create_task(save_cert_fingerprint(serial, fingerprint))
+    except RuntimeError:
+        pass  # No running loop
+
+
 # Store recent assignment completions for polling (used by simulator)
 # Format: [(timestamp, serial, ams_id, tray_id, spool_id, success), ...]
 _assignment_completions: list[tuple] = []
@@ -620,6 +1168,18 @@ def on_tray_reading_change(serial: str, old_bits: int | None, new_bits: int):
         pass  # No running loop
 
 
+def on_command_result(serial: str, command: dict):
+    """Handle a printer acknowledging, rejecting or not answering a command."""
+    message = {"type": "command_result", "serial": serial, **command}
+
+    # Schedule broadcast in event loop
+    try:
+        loop = asyncio.get_running_loop()
+        loop.create_task(broadcast_message(message))
+    except RuntimeError:
+        pass  # No running loop
+
+
 async def auto_connect_printers():
     """Periodically connect to printers with auto_connect enabled.
 
@@ -627,6 +1187,7 @@ async def auto_connect_printers():
     - Have auto_connect enabled
     - Have valid ip_address and access_code
     - Are not currently connected
+    - Haven't refused their current access code
     """
     await asyncio.sleep(0.5)  # Wait for startup
 
@@ -640,6 +1201,9 @@ async def auto_connect_printers():
                     # Skip if already connected
                     if printer_manager.is_connected(printer.serial):
                         continue
+                    # Retrying a refused access code can't succeed, wait for a new one
+                    if printer_manager.access_code_refused(printer.serial, printer.access_code):
+                        continue
 
                     logger.info(f"Auto-connecting to printer {printer.serial}")
                     try:
@@ -648,6 +1212,7 @@ async def auto_connect_printers():
                             ip_address=printer.ip_address,
                             access_code=printer.access_code,
                             name=printer.name,
+                            cert_fingerprint=printer.cert_fingerprint,
                         )
                     except Exception as e:
                         logger.error(f"Failed to auto-connect to {printer.serial}: {e}")
@@ -658,33 +1223,190 @@ async def auto_connect_printers():
         await asyncio.sleep(30)
 
 
+async def purge_trash_periodically():
+    """Permanently delete trashed spools/printers older than the retention window.
+
+    Runs once at startup and then every 6 hours.
+    """
+    from api.trash import get_trash_retention_days
+
+    while True:
+        try:
+            db = await get_db()
+            retention_days = await get_trash_retention_days()
+            purged = await db.purge_trash(retention_days=retention_days)
+            if purged["spools"] or purged["printers"]:
+                logger.info(
+                    f"Purged {purged['spools']} spool(s) and {purged['printers']} printer(s) "
+                    f"from trash (older than {retention_days} days)"
+                )
+        except Exception as e:
+            logger.error(f"Error purging trash: {e}")
+
+        await asyncio.sleep(6 * 3600)
+
+
+async def check_spool_aging_periodically():
+    """Alert on opened spools past their shelf life, at startup and then every 6 hours."""
+    while True:
+        try:
+            await _check_spool_aging()
+        except Exception as e:
+            logger.error(f"Error checking spool aging: {e}")
+
+        await asyncio.sleep(6 * 3600)
+
+
+async def maintenance_periodically():
+    """Apply data retention and optimize the database on the configured interval.
+
+    The first run is an hour after startup so it doesn't slow down boot.
+    """
+    await asyncio.sleep(3600)
+    while True:
+        try:
+            db = await get_db()
+            await run_maintenance(db, retention_policy(await get_ams_history_retention_days(db)))
+        except Exception as e:
+            logger.error(f"Error during database maintenance: {e}")
+
+        await asyncio.sleep(settings.maintenance_interval_hours * 3600)
+
+
+async def reconcile_weights_periodically():
+    """Check new scale readings against the tracked usage."""
+    while True:
+        await asyncio.sleep(settings.reconciliation_interval_minutes * 60)
+        try:
+            await scan_measurements(await get_db())
+        except Exception as e:
+            logger.error(f"Error during weight reconciliation: {e}")
+
+
+async def checkpoint_wal_periodically():
+    """Checkpoint the write-ahead log so it doesn't grow while readers keep it busy."""
+    while True:
+        await asyncio.sleep(settings.wal_checkpoint_interval_minutes * 60)
+        try:
+            db = await get_db()
+            result = await db.checkpoint()
+            if result["busy"]:
+                logger.debug(f"WAL checkpoint incomplete: {result}")
+        except Exception as e:
+            logger.error(f"Error checkpointing the database: {e}")
+
+
+def _start_background(coro, name: str) -> asyncio.Task:
+    """Run a background loop that is cancelled on shutdown."""
+    task = asyncio.create_task(coro, name=name)
+    _background_tasks.add(task)
+    task.add_done_callback(_background_tasks.discard)
+    return task
+
+
+async def begin_shutdown():
+    """First shutdown step: stop reporting ready and end streaming connections.
+
+    Runs on the shutdown signal, because uvicorn waits for open connections
+    to close before it runs the lifespan shutdown; an open SSE stream or
+    WebSocket would otherwise hold the server up.
+    """
+    global _lifecycle
+    if _lifecycle == "stopping":
+        return
+    _lifecycle = "stopping"
+    logger.info("Shutting down: closing event streams and WebSocket clients")
+
+    get_event_stream().close()
+    get_printer_log_handler().close()
+    for ws in get_ws_clients().clear():
+        try:
+            # 1001 "going away": the UI reconnects once the server is back
+            await asyncio.wait_for(ws.close(code=1001), WEBSOCKET_CLOSE_TIMEOUT)
+        except Exception:
+            pass
+
+
+def _install_shutdown_signal_handlers():
+    """Call begin_shutdown on SIGINT/SIGTERM, then the previous (uvicorn's) handler."""
+    loop = asyncio.get_running_loop()
+
+    for sig in (signal.SIGINT, signal.SIGTERM):
+        previous = signal.getsignal(sig)
+
+        def handler(signum, frame, previous=previous):
+            loop.call_soon_threadsafe(lambda: asyncio.ensure_future(begin_shutdown()))
+            if callable(previous):
+                previous(signum, frame)
+
+        try:
+            signal.signal(sig, handler)
+        except ValueError:
+            return  # Not the main thread (e.g. embedded in tests)
+
+
 @asynccontextmanager
 async def lifespan(app: FastAPI):
-    """Application lifespan handler."""
-    global _zeroconf, _mdns_service
+    """Application lifespan handler.
+
+    Starts in order: database and migrations, then the managers, then the
+    listeners and background loops that feed them. Shuts down in reverse,
+    closing the database last. /api/health reports ready in between.
+    """
+    global _zeroconf, _mdns_service, _lifecycle
 
     # Startup
     logger.info("Starting SpoolBuddy server...")
+    _lifecycle = "starting"
 
-    # Initialize database
-    await get_db()
+    # 1. Database (runs migrations)
+    db = await get_db()
     logger.info("Database initialized")
 
     # Initialize debug logging from settings
     init_debug_logging()
 
+    if settings.demo:
+        logger.warning("Demo mode: printers are simulated, data is seeded and not kept")
+        await seed_demo_data(db)
+
+    # Jobs still queued or running belonged to the previous process
+    interrupted = await db.fail_interrupted_jobs()
+    if interrupted:
+        logger.warning(f"Marked {interrupted} background job(s) interrupted by the restart as failed")
+
+    # 2. Managers
+    get_job_runner().set_update_callback(on_job_update)
+
     # Set up usage tracker
     usage_tracker.set_usage_callback(on_usage_logged)
+    usage_tracker.set_print_end_callback(on_print_ended)
     usage_tracker.set_event_loop(asyncio.get_running_loop())
 
     # Set up printer manager
     set_printer_manager(printer_manager)
+    get_spool_status_tracker().set_state_source(printer_manager.get_state)
     printer_manager.set_state_callback(on_printer_state_update)
     printer_manager.set_connect_callback(on_printer_connect)
     printer_manager.set_disconnect_callback(on_printer_disconnect)
     printer_manager.set_assignment_complete_callback(on_assignment_complete)
     printer_manager.set_tray_reading_callback(on_tray_reading_change)
     printer_manager.set_nozzle_count_callback(on_nozzle_count_update)
+    printer_manager.set_nozzle_change_callback(on_nozzle_change)
+    printer_manager.set_command_result_callback(on_command_result)
+    printer_manager.set_cert_pinned_callback(on_cert_pinned)
+    printer_manager.set_auth_failed_callback(on_printer_auth_failed)
+    printer_manager.set_version_callback(on_printer_version)
+
+    moonraker_manager = get_moonraker_manager()
+    moonraker_manager.set_event_callback(on_moonraker_event)
+
+    # 3. Listeners and background loops
+    _install_shutdown_signal_handlers()
+
+    # Follow Klipper printers through Moonraker
+    for moonraker_printer in await db.get_moonraker_printers():
+        moonraker_manager.start(moonraker_printer)
 
     # Register mDNS service for device discovery
     # Service type must be <= 15 chars, using "_spbuddy-srv" (12 chars)
@@ -704,25 +1426,49 @@ async def lifespan(app: FastAPI):
         logger.warning(f"Failed to register mDNS service: {e}")
 
     # Auto-connect printers
-    asyncio.create_task(auto_connect_printers())
+    _start_background(auto_connect_printers(), "auto-connect")
 
     # Pre-fetch cloud slicer settings to warm cache
     from api.cloud import prefetch_slicer_settings
 
-    asyncio.create_task(prefetch_slicer_settings())
+    _start_background(prefetch_slicer_settings(), "prefetch-slicer-settings")
 
     # Start display timeout checker
-    asyncio.create_task(check_display_timeout())
+    _start_background(check_display_timeout(), "display-timeout")
+
+    # Purge expired items from the trash
+    _start_background(purge_trash_periodically(), "trash-purge")
+
+    # Alert on spools open past their shelf life
+    _start_background(check_spool_aging_periodically(), "spool-aging")
+
+    # Apply data retention policies and VACUUM
+    if settings.maintenance_interval_hours > 0:
+        _start_background(maintenance_periodically(), "maintenance")
+
+    # Flag scale readings that disagree with the tracked usage
+    if settings.reconciliation_interval_minutes > 0:
+        _start_background(reconcile_weights_periodically(), "weight-reconciliation")
+
+    # Keep the write-ahead log short
+    if settings.database_wal and settings.wal_checkpoint_interval_minutes > 0:
+        _start_background(checkpoint_wal_periodically(), "wal-checkpoint")
 
     # Start UDP log listener for ESP32 logs
-    asyncio.create_task(udp_log_listener())
+    _start_background(udp_log_listener(), "udp-log-listener")
+
+    # Republish events over MQTT, if enabled
+    await get_mqtt_events().start()
+
+    _lifecycle = "ready"
+    logger.info("SpoolBuddy server ready")
 
     yield
 
-    # Shutdown
-    logger.info("Shutting down...")
+    # Shutdown, in reverse order
+    await begin_shutdown()
 
-    # Unregister mDNS service
+    # 3. Listeners: discovery, then the background loops
     if _zeroconf and _mdns_service:
         try:
             await _zeroconf.async_unregister_service(_mdns_service)
@@ -730,8 +1476,23 @@ async def lifespan(app: FastAPI):
             logger.info("mDNS service unregistered")
         except Exception as e:
             logger.warning(f"Failed to unregister mDNS service: {e}")
+    await stop_printer_discovery()
+    await get_mqtt_events().stop()
+
+    tasks = list(_background_tasks)
+    for task in tasks:
+        task.cancel()
+    await asyncio.gather(*tasks, return_exceptions=True)
 
+    # 2. Managers: jobs record their cancellation, printers disconnect cleanly
+    await get_job_runner().shutdown()
+    get_moonraker_manager().stop_all()
     await printer_manager.disconnect_all()
+    logger.info("Printers disconnected")
+
+    # 1. Database
+    await close_db()
+    logger.info("Shutdown complete")
 
 
 # Create FastAPI app
@@ -752,8 +1513,40 @@ app.add_middleware(
     allow_credentials=True,
     allow_methods=["*"],
     allow_headers=["*"],
+    expose_headers=["X-Request-ID"],
 )
 
+# Printer a request is about, for tagging its log records
+_PRINTER_PATH = re.compile(r"^/api/printers/(?!assignment-completions/?$)([^/]+)")
+
+
+@app.middleware("http")
+async def trace_requests(request: Request, call_next):
+    """Run each request in a span with a request id, echoed in the X-Request-ID response header."""
+    request_id = request_id_from(request.headers.get("X-Request-ID"))
+    match = _PRINTER_PATH.match(request.url.path)
+    with span(f"{request.method} {request.url.path}", serial=match and match.group(1), request_id=request_id):
+        response = await call_next(request)
+    response.headers["X-Request-ID"] = request_id
+    return response
+
+
+@app.middleware("http")
+async def record_request_metrics(request: Request, call_next):
+    """Record latency and status code per API route for /api/admin/perf."""
+    if not request.url.path.startswith("/api/"):
+        return await call_next(request)
+    start = time.monotonic()
+    status = 500
+    try:
+        response = await call_next(request)
+        status = response.status_code
+        return response
+    finally:
+        # The route template, so path parameters don't make a route per spool
+        route = getattr(request.scope.get("route"), "path", "(unmatched)")
+        get_perf_stats().record_request(request.method, route, status, (time.monotonic() - start) * 1000)
+
 # API routes
 app.include_router(spools_router, prefix="/api")
 app.include_router(printers_router, prefix="/api")
@@ -769,6 +1562,34 @@ app.include_router(colors_router, prefix="/api")
 app.include_router(settings_router, prefix="/api")
 app.include_router(support_router, prefix="/api")
 app.include_router(api_keys_router, prefix="/api")
+app.include_router(reports_router, prefix="/api")
+app.include_router(webhooks_router, prefix="/api")
+app.include_router(moonraker_router, prefix="/api")
+app.include_router(notifications_router, prefix="/api")
+app.include_router(trash_router, prefix="/api")
+app.include_router(crash_reports_router, prefix="/api")
+app.include_router(diagnostics_router, prefix="/api")
+app.include_router(config_bundle_router, prefix="/api")
+app.include_router(admin_router, prefix="/api")
+app.include_router(slicer_router, prefix="/api")
+app.include_router(projects_router, prefix="/api")
+app.include_router(search_router, prefix="/api")
+app.include_router(jobs_router, prefix="/api")
+app.include_router(templates_router, prefix="/api")
+app.include_router(stocktakes_router, prefix="/api")
+app.include_router(reconciliation_router, prefix="/api")
+app.include_router(purchases_router, prefix="/api")
+app.include_router(integrations_router, prefix="/api")
+app.include_router(kiosk_router, prefix="/api")
+app.include_router(share_links_router, prefix="/api")
+# Share links are served outside /api, so they can be exposed without the API
+app.include_router(share_router)
+
+
+@app.get("/api/health")
+async def health():
+    """Readiness for health checks: 200 once startup has finished, 503 while starting or stopping."""
+    return JSONResponse({"status": _lifecycle}, status_code=200 if _lifecycle == "ready" else 503)
 
 
 @app.get("/api/time")
@@ -789,6 +1610,12 @@ async def display_heartbeat(
     wifi_ssid: str | None = None,
     wifi_ip: str | None = None,
     wifi_rssi: int | None = None,
+    # Pico NFC bridge health from device
+    nfc_bridge: str | None = None,
+    nfc_bridge_fw: str | None = None,
+    nfc_failures: int | None = None,
+    nfc_garbage: int | None = None,
+    nfc_resets: int | None = None,
 ):
     """Heartbeat endpoint for ESP32 display to indicate it's connected."""
     global _display_firmware_version, _device_update_available
@@ -825,6 +1652,7 @@ async def display_heartbeat(
         _device_wifi_ip = wifi_ip
     if wifi_rssi is not None:
         _device_wifi_rssi = wifi_rssi
+    update_nfc_bridge_health(nfc_bridge, nfc_bridge_fw, nfc_failures, nfc_garbage, nfc_resets)
 
     cmd = pop_display_command()
     if cmd:
@@ -833,6 +1661,33 @@ async def display_heartbeat(
     return {"ok": True}
 
 
+def update_nfc_bridge_health(
+    status: str | None,
+    firmware: str | None,
+    failures: int | None,
+    garbage: int | None,
+    resets: int | None,
+):
+    """Store the Pico NFC bridge health reported by the device."""
+    global _device_nfc_bridge
+
+    if status is None:
+        return
+    previous = _device_nfc_bridge["status"] if _device_nfc_bridge else None
+    if status != previous:
+        if status == "hung":
+            logger.warning(f"NFC bridge not responding (resets so far: {resets or 0})")
+        elif previous in ("hung", "recovering") and status == "ok":
+            logger.info("NFC bridge recovered")
+    _device_nfc_bridge = {
+        "status": status,
+        "firmware": firmware,
+        "failures": failures or 0,
+        "garbage": garbage or 0,
+        "resets": resets or 0,
+    }
+
+
 def get_display_firmware_version() -> str | None:
     """Get the last reported firmware version from the display."""
     return _display_firmware_version
@@ -851,6 +1706,7 @@ async def display_status():
         "update_available": _device_update_available,
         "weight": _device_last_weight,
         "weight_stable": _device_weight_stable,
+        "scales": _device_scales,
         # WiFi status from device
         # If device is connected but hasn't reported WiFi, assume connected (it needs WiFi to reach us)
         "wifi": {
@@ -859,6 +1715,7 @@ async def display_status():
             "ip": _device_wifi_ip,
             "rssi": _device_wifi_rssi,
         },
+        "nfc_bridge": _device_nfc_bridge,
         # Staging info (new)
         "staged_tag_id": _staged_tag_id if staged else None,
         "staged_tag_data": staged,
@@ -869,10 +1726,13 @@ async def display_status():
     }
 
 
-@app.post("/api/display/state")
+@app.post("/api/display/state", response_model=DeviceStateResponse)
 async def update_device_state(
     weight: float | None = None,
     stable: bool | None = None,
+    # Second load cell (dual-scale devices only)
+    weight_2: float | None = None,
+    stable_2: bool | None = None,
     tag_id: str | None = None,
     tag_vendor: str | None = None,
     tag_material: str | None = None,
@@ -886,8 +1746,18 @@ async def update_device_state(
     wifi_ssid: str | None = None,
     wifi_ip: str | None = None,
     wifi_rssi: int | None = None,
+    # Pico NFC bridge health from device
+    nfc_bridge: str | None = None,
+    nfc_bridge_fw: str | None = None,
+    nfc_failures: int | None = None,
+    nfc_garbage: int | None = None,
+    nfc_resets: int | None = None,
 ):
-    """HTTP endpoint for device to update state (alternative to WebSocket)."""
+    """HTTP endpoint for device to update state (alternative to WebSocket).
+
+    The reply carries the spool resolved from the tag and a suggested action,
+    so the display can show it without a second request.
+    """
     global _device_wifi_state, _device_wifi_ssid, _device_wifi_ip, _device_wifi_rssi
 
     update_display_heartbeat()
@@ -901,6 +1771,7 @@ async def update_device_state(
         _device_wifi_ip = wifi_ip
     if wifi_rssi is not None:
         _device_wifi_rssi = wifi_rssi
+    update_nfc_bridge_health(nfc_bridge, nfc_bridge_fw, nfc_failures, nfc_garbage, nfc_resets)
 
     # Build tag_data if decoded data provided
     tag_data = None
@@ -923,6 +1794,11 @@ async def update_device_state(
         "weight": weight,
         "stable": stable if stable is not None else False,
     }
+    if weight_2 is not None:
+        message["scales"] = [
+            {"channel": 0, "weight": weight, "stable": message["stable"]},
+            {"channel": 1, "weight": weight_2, "stable": bool(stable_2)},
+        ]
     # Only include tag_id if tag-related params were provided
     # (device sends tag_id when reporting tag status, omits it for weight-only updates)
     if tag_id is not None or tag_vendor is not None:
@@ -930,7 +1806,16 @@ async def update_device_state(
         message["tag_data"] = tag_data
 
     await handle_device_state(message)
-    return {"ok": True}
+    response = await _device_state_response(tag_id, weight, stable)
+    if kiosk := await _kiosk_status(weight, stable):
+        # The kiosk screen takes over, no spool actions while members check spools in and out
+        response.kiosk = kiosk
+        response.action = DeviceAction.NONE
+    if encode := await _encode_task(tag_id):
+        # Batch encoding takes over the screen, the tags placed are about to be (re)written
+        response.encode = encode
+        response.action = DeviceAction.NONE
+    return response
 
 
 @app.post("/api/test/simulate-tag")
@@ -1114,10 +1999,11 @@ async def handle_device_state(message: dict):
     Tag removal is debounced to avoid false triggers from flaky NFC reads.
     """
     global _device_last_weight, _device_weight_stable, _device_current_tag_id, _device_tag_data
-    global _tag_last_seen_time, _confirmed_tag_id
+    global _tag_last_seen_time, _confirmed_tag_id, _device_scales
 
     weight = message.get("weight")
     stable = message.get("stable", False)
+    scales = message.get("scales")
     provided_tag_data = message.get("tag_data")
 
     # Check if tag_id is explicitly present in message (vs just missing)
@@ -1136,9 +2022,14 @@ async def handle_device_state(message: dict):
         _device_weight_stable = stable
         state_changed = True
 
+    if scales != _device_scales:
+        _device_scales = scales
+        state_changed = True
+
     # Don't update tag state if we're in simulation mode
     if _simulating_tag:
         return  # Ignore all tag updates in simulation mode
+    prev_confirmed_tag_id = _confirmed_tag_id
 
     # === Debounced tag detection for frontend display ===
     # Only process tag changes if the message explicitly includes tag_id field
@@ -1330,9 +2221,12 @@ async def handle_device_state(message: dict):
                 "type": "device_state",
                 "weight": _device_last_weight,
                 "stable": _device_weight_stable,
+                "scales": _device_scales,
                 "tag_id": _confirmed_tag_id,  # Use debounced tag for real-time display (avoids flaky NFC)
             }
         )
+    if _confirmed_tag_id != prev_confirmed_tag_id:
+        await _update_spool_on_scale(_confirmed_tag_id)
 
 
 async def _lookup_tag_in_database(tag_id: str) -> dict | None:
@@ -1352,15 +2246,8 @@ async def _lookup_tag_in_database(tag_id: str) -> dict | None:
                 "color_name": spool_dict.get("color_name", ""),
                 "spool_weight": spool_dict.get("label_weight", 0),
             }
-            # Convert RGBA hex to int
-            rgba_str = spool_dict.get("rgba", "")
-            if rgba_str and len(rgba_str) >= 6:
-                try:
-                    if len(rgba_str) == 6:
-                        rgba_str = rgba_str + "FF"
-                    tag_data["color_rgba"] = int(rgba_str, 16)
-                except ValueError:
-                    tag_data["color_rgba"] = 0
+            if spool_dict.get("color"):
+                tag_data["color_rgba"] = spool_dict["color"]["rgba_int"]
             logger.info(f"Tag {tag_id} matched to spool: {spool_dict.get('material')} {spool_dict.get('color_name')}")
             return tag_data
     except Exception as e:
@@ -1369,30 +2256,205 @@ async def _lookup_tag_in_database(tag_id: str) -> dict | None:
     return None
 
 
+# Scale readings further than this from the tracked weight suggest re-weighing
+WEIGHT_MISMATCH_TOLERANCE = 20  # grams
+
+
+async def _device_state_response(tag_id: str | None, weight: float | None, stable: bool | None) -> DeviceStateResponse:
+    """Resolve the tag on the scale to an inventory spool and the next step to offer."""
+    if not tag_id:
+        return DeviceStateResponse()
+
+    try:
+        db = await get_db()
+        spool = await db.get_spool_by_tag(tag_id)
+        if not spool:
+            return DeviceStateResponse(tag_id=tag_id, action=DeviceAction.ADD_SPOOL)
+
+        # Spools put on the scale during a stocktake are counted
+        if scan := await record_device_scan(db, spool, weight if stable else None):
+            await broadcast_message({"type": "stocktake_scan", "scan": scan})
+
+        remaining = remaining_grams(spool)
+        percent = _remaining_percent(spool)
+        low_stock = percent is not None and percent < await _spool_low_threshold(db)
+        info = DeviceSpoolInfo(
+            id=spool.id,
+            spool_number=spool.spool_number,
+            name=" ".join(p for p in (spool.brand, spool.material, spool.subtype) if p),
+            brand=spool.brand,
+            material=spool.material,
+            subtype=spool.subtype,
+            color_name=spool.color_name,
+            color_rgba=spool.color.rgba_int if spool.color else 0,
+            color_stops=[int(stop, 16) for stop in spool.color_stops or []],
+            remaining_g=round(remaining, 1) if remaining is not None else None,
+            label_weight=spool.label_weight,
+            low_stock=low_stock,
+        )
+    except Exception as e:
+        logger.warning(f"Error resolving spool for tag {tag_id}: {e}")
+        return DeviceStateResponse(tag_id=tag_id)
+
+    action = DeviceAction.NONE
+    if stable and weight and remaining is not None and spool.core_weight is not None:
+        if abs(weight - (remaining + spool.core_weight)) > WEIGHT_MISMATCH_TOLERANCE:
+            action = DeviceAction.UPDATE_WEIGHT
+    if action == DeviceAction.NONE and low_stock:
+        action = DeviceAction.REORDER
+    return DeviceStateResponse(tag_id=tag_id, spool=info, action=action)
+
+
+async def _kiosk_status(weight: float | None, stable: bool | None) -> KioskStatus | None:
+    """Advance the check-in/check-out kiosk, None unless kiosk mode is on.
+
+    Uses the debounced tag rather than the request's, the device leaves the
+    tag out of weight-only updates while the spool is still on the scale.
+    """
+    try:
+        db = await get_db()
+        config = await get_kiosk_config(db)
+        if not config.enabled:
+            return None
+        status, checkout = await get_kiosk().handle_tag(db, config, _confirmed_tag_id, weight, stable)
+        if checkout:
+            await broadcast_message({"type": "kiosk_checkout", "checkout": checkout})
+        return status
+    except Exception as e:
+        logger.warning(f"Kiosk error: {e}")
+        return None
+
+
+async def _encode_task(tag_id: str | None) -> EncodeTask | None:
+    """Next tag to write while a tag_batch job runs, None otherwise.
+
+    Prefers the request's tag over the debounced one: bare tags weigh
+    nothing, so the removal-by-weight check keeps clearing it. The device
+    keeps the task it fetched for the tag on its reader over the ones in
+    replies to weight-only updates.
+    """
+    try:
+        return await get_encoding_station().task_for(tag_id or _confirmed_tag_id)
+    except Exception as e:
+        logger.warning(f"Tag batch error: {e}")
+        return None
+
+
+def _initial_state_message() -> dict:
+    """Snapshot of device and printer state sent to newly connected UI clients."""
+    display_connected = is_display_connected()
+    logger.info(f"Sending initial_state: device.connected={display_connected}")
+    return {
+        "type": "initial_state",
+        "device": {
+            "connected": display_connected,
+            "update_available": _device_update_available,
+            "last_weight": _device_last_weight,
+            "weight_stable": _device_weight_stable,
+            "scales": _device_scales,
+            "current_tag_id": _confirmed_tag_id,  # Use debounced tag for real-time display
+        },
+        "printers": {serial: conn.connected for serial, conn in printer_manager._connections.items()},
+        # Full state snapshots - later printer_state messages are only sent on change
+        "printer_states": {
+            serial: conn.state.model_dump() for serial, conn in printer_manager._connections.items() if conn.connected
+        },
+    }
+
+
+SSE_KEEPALIVE_SECONDS = 15
+SSE_RETRY_MS = 3000
+
+
+@app.get("/api/events")
+async def event_stream(
+    request: Request,
+    last_event_id: int | None = Query(None, description="Resume after this event (alternative to the header)"),
+    last_event_id_header: str | None = Header(None, alias="Last-Event-ID"),
+):
+    """Server-sent events alternative to the /ws/ui WebSocket.
+
+    Carries the same messages as the WebSocket, each with an event id. A
+    client reconnecting with Last-Event-ID gets the messages it missed, or a
+    fresh initial_state if they are no longer buffered.
+    """
+    stream = get_event_stream()
+    if last_event_id is None and last_event_id_header and last_event_id_header.strip().isdigit():
+        last_event_id = int(last_event_id_header)
+
+    # Subscribe before building the replay so nothing published in between is lost
+    sub = stream.subscribe()
+    replay = stream.replay_since(last_event_id) if last_event_id is not None else None
+
+    async def generate():
+        try:
+            yield f"retry: {SSE_RETRY_MS}\n\n"
+            sent_id = stream.last_id
+            if replay is None:
+                # Unnumbered, so a reconnect still resumes from the last broadcast
+                yield f"data: {json.dumps(_initial_state_message())}\n\n"
+            else:
+                for event in replay:
+                    yield event.encode()
+                sent_id = replay[-1].id if replay else last_event_id
+
+            while not sub.dropped:
+                try:
+                    event = await asyncio.wait_for(sub.queue.get(), SSE_KEEPALIVE_SECONDS)
+                except TimeoutError:
+                    if await request.is_disconnected():
+                        break
+                    yield ": keepalive\n\n"
+                    continue
+                if sub.dropped:
+                    break
+                if event.id > sent_id:
+                    yield event.encode()
+        finally:
+            stream.unsubscribe(sub)
+
+    return StreamingResponse(
+        generate(),
+        media_type="text/event-stream",
+        # X-Accel-Buffering: stop nginx from buffering the stream
+        headers={"Cache-Control": "no-cache", "X-Accel-Buffering": "no"},
+    )
+
+
 @app.websocket("/ws/ui")
-async def websocket_endpoint(websocket: WebSocket):
-    """WebSocket endpoint for real-time UI updates."""
+async def websocket_endpoint(
+    websocket: WebSocket,
+    client: Literal["web", "device"] = Query(CLIENT_WEB, description="Client class, sets the printer state rate"),
+    token: str | None = Query(None, description="API key, for clients that can't set headers"),
+):
+    """WebSocket endpoint for real-time UI updates.
+
+    An API key given in the handshake (header or token) must be valid and
+    becomes the client's identity; with ws_require_api_key, one is required.
+    """
     global _device_current_tag_id, _device_tag_data
+    headers = websocket.headers
+    key_value = token or api_key_from_headers(headers.get("x-api-key"), headers.get("authorization"))
+    api_key = await authenticate_api_key(key_value) if key_value else None
+    if (key_value or settings.ws_require_api_key) and not (api_key and api_key["can_read"]):
+        # Closing before accepting refuses the upgrade (HTTP 403)
+        logger.warning(f"WebSocket client refused: {'invalid' if key_value else 'no'} API key")
+        await websocket.close(code=1008)
+        return
+
     await websocket.accept()
-    websocket_clients.add(websocket)
-    logger.info("WebSocket client connected")
+    info = get_ws_clients().add(
+        websocket,
+        client,
+        api_key=api_key,
+        address=websocket.client.host if websocket.client else None,
+        user_agent=websocket.headers.get("user-agent"),
+    )
+    logger.info(f"WebSocket client connected ({client}, {info.identity})")
 
     # Send initial state to new client
     try:
-        display_connected = is_display_connected()
-        logger.info(f"Sending initial_state: device.connected={display_connected}")
-        initial_state = {
-            "type": "initial_state",
-            "device": {
-                "connected": display_connected,
-                "update_available": _device_update_available,
-                "last_weight": _device_last_weight,
-                "weight_stable": _device_weight_stable,
-                "current_tag_id": _confirmed_tag_id,  # Use debounced tag for real-time display
-            },
-            "printers": {serial: conn.connected for serial, conn in printer_manager._connections.items()},
-        }
-        await websocket.send_text(json.dumps(initial_state))
+        await websocket.send_text(json.dumps(_initial_state_message()))
     except Exception as e:
         logger.warning(f"Failed to send initial state: {e}")
 
@@ -1400,6 +2462,7 @@ async def websocket_endpoint(websocket: WebSocket):
         while True:
             # Keep connection alive, handle any incoming messages
             data = await websocket.receive_text()
+            get_ws_clients().touch(websocket)
 
             try:
                 message = json.loads(data)
@@ -1413,6 +2476,8 @@ async def websocket_endpoint(websocket: WebSocket):
                     await broadcast_message({"type": "tag_removed"})
                 elif msg_type == "device_state":
                     await handle_device_state(message)
+                elif msg_type == "subscribe":
+                    get_ws_clients().subscribe(websocket, message.get("printers"))
                 else:
                     logger.debug(f"Received from WebSocket: {data}")
 
@@ -1424,20 +2489,38 @@ async def websocket_endpoint(websocket: WebSocket):
     except Exception as e:
         logger.error(f"WebSocket error: {e}")
     finally:
-        websocket_clients.discard(websocket)
+        get_ws_clients().remove(websocket)
 
 
 # Mount static files (frontend) - must be last
-if settings.static_dir.exists():
+if settings.static_bundle:
+    app.mount("/", EmbeddedStaticFiles(settings.static_bundle), name="static")
+elif settings.static_dir.exists():
     app.mount("/", StaticFiles(directory=settings.static_dir, html=True), name="static")
 
 
 if __name__ == "__main__":
+    import argparse
+    import os
+
     import uvicorn
 
+    parser = argparse.ArgumentParser(description="SpoolBuddy server")
+    parser.add_argument(
+        "--demo",
+        action="store_true",
+        help="seed an in-memory database with demo spools and printers and simulate the printers",
+    )
+    if parser.parse_args().demo:
+        # Through the environment, so the reloaded server process sees it too
+        os.environ["SPOOLBUDDY_DEMO"] = "1"
+        os.environ.setdefault("SPOOLBUDDY_DATABASE_PATH", ":memory:")
+
     uvicorn.run(
         "main:app",
         host=settings.host,
         port=settings.port,
         reload=True,
+        # Don't wait forever for clients that ignore the close
+        timeout_graceful_shutdown=10,
     )
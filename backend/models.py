@@ -1,4 +1,9 @@
-from pydantic import BaseModel
+import json
+from enum import IntEnum, StrEnum
+
+from pydantic import BaseModel, Field, computed_field, field_validator, model_validator
+from services.colors import MAX_COLOR_STOPS, ColorSwatch, color_gradient, color_swatch
+from services.slicer import check_filament_preset, normalize_tray_color
 
 # ============ Spool Models ============
 
@@ -14,21 +19,100 @@ class SpoolBase(BaseModel):
     core_weight: int | None = 250
     weight_new: int | None = None
     weight_current: int | None = None
+    price: float | None = None  # Price paid for the spool, used for cost reports
     slicer_filament: str | None = None
     slicer_filament_name: str | None = None
+    slicer_setting_id: str | None = None  # Slicer preset setting_id (e.g. "GFSL05"), derived from slicer_filament
+    slicer_filament_id: str | None = None  # Bambu filament_id / tray_info_idx (e.g. "GFL05")
     location: str | None = None
     note: str | None = None
     data_origin: str | None = None
     tag_type: str | None = None
     ext_has_k: bool | None = False
+    swap_available: bool | None = False  # Offered for swap/sale on the shareable swap list
+    photo_url: str | None = None  # Link to a photo of the spool, shown on the swap list
+    # User-tuned nozzle temperatures sent to AMS slots, None = the material's defaults
+    nozzle_temp_min: int | None = Field(default=None, ge=0, le=350)
+    nozzle_temp_max: int | None = Field(default=None, ge=0, le=350)
+    # Multi-color filament (dual-color silk, gradients): colors in order as RRGGBBAA, rgba is the first
+    color_stops: list[str] | None = None
+    # Shelf life tracking, epoch seconds
+    manufactured_at: int | None = None  # Production date from the label or tag
+    opened_at: int | None = None  # When the seal was broken, None = sealed; set on the first logged usage
+
+    @field_validator("color_stops", mode="before")
+    @classmethod
+    def parse_color_stops(cls, v):
+        return json.loads(v) if isinstance(v, str) else v  # Stored as a JSON list
+
+    @field_validator("color_stops")
+    @classmethod
+    def check_color_stops(cls, v):
+        if not v:
+            return None
+        if not 2 <= len(v) <= MAX_COLOR_STOPS:
+            raise ValueError(f"must have 2 to {MAX_COLOR_STOPS} colors")
+        stops = [normalize_tray_color(color) for color in v]
+        if None in stops:
+            raise ValueError("must be hex colors like FF8800 or FF8800FF")
+        return stops
+
+
+def _check_nozzle_temps(spool):
+    if spool.nozzle_temp_min is not None and spool.nozzle_temp_max is not None:
+        if spool.nozzle_temp_min > spool.nozzle_temp_max:
+            raise ValueError("nozzle_temp_min must not exceed nozzle_temp_max")
+    return spool
+
+
+def _default_rgba(spool):
+    # Single-color consumers (AMS slots, tags, lists) use the first color stop
+    if spool.color_stops and spool.rgba is None:
+        spool.rgba = spool.color_stops[0]
+    return spool
 
 
 class SpoolCreate(SpoolBase):
-    pass
+    @model_validator(mode="after")
+    def check_nozzle_temps(self):
+        return _check_nozzle_temps(self)
+
+    @model_validator(mode="after")
+    def default_rgba(self):
+        return _default_rgba(self)
 
 
 class SpoolUpdate(SpoolBase):
     material: str | None = None
+    expected_version: int | None = None  # Reject the update (409) if the spool has changed since this version
+
+    @model_validator(mode="after")
+    def check_nozzle_temps(self):
+        return _check_nozzle_temps(self)
+
+    @model_validator(mode="after")
+    def default_rgba(self):
+        return _default_rgba(self)
+
+
+class SpoolPresence(StrEnum):
+    """Where a spool physically is right now."""
+
+    STORAGE = "storage"
+    ON_SCALE = "on_scale"  # On the SpoolBuddy scale
+    LOADED = "loaded"  # In a printer slot
+
+
+class SpoolLiveStatus(BaseModel):
+    """Live whereabouts of a spool, from device reports and printer AMS state."""
+
+    status: SpoolPresence = SpoolPresence.STORAGE
+    device: str | None = None  # Scale the spool is on
+    printer_serial: str | None = None  # Slot the spool is loaded in
+    ams_id: int | None = None
+    tray_id: int | None = None
+    active: bool = False  # Filament is fed to the extruder
+    since: int | None = None  # When the spool got there, None if before the server started
 
 
 class Spool(SpoolBase):
@@ -41,9 +125,24 @@ class Spool(SpoolBase):
     consumed_since_weight: float | None = 0
     weight_used: float | None = 0
     archived_at: int | None = None  # Timestamp when archived, null = active
+    deleted_at: int | None = None  # Timestamp when moved to trash, null = not deleted
+    version: int = 1  # Incremented on every change, used for optimistic concurrency
     created_at: int | None = None
     updated_at: int | None = None
     last_used_time: int | None = None  # From usage_history table
+    live_status: SpoolLiveStatus | None = None  # Not stored, filled in by the API
+
+    @computed_field
+    @property
+    def color(self) -> ColorSwatch | None:
+        """The rgba color ready to draw, None if the spool has none."""
+        return color_swatch(self.rgba)
+
+    @computed_field
+    @property
+    def gradient(self) -> str | None:
+        """CSS linear-gradient through the color stops, None for single-color spools."""
+        return color_gradient(self.color_stops)
 
     class Config:
         from_attributes = True
@@ -52,17 +151,34 @@ class Spool(SpoolBase):
 # ============ Printer Models ============
 
 
+class PrinterType(StrEnum):
+    """How a printer is tracked."""
+
+    BAMBU = "bambu"  # Connected over MQTT
+    MANUAL = "manual"  # Never connects, usage is logged by hand (non-networked printers)
+
+
 class PrinterBase(BaseModel):
     serial: str
     name: str | None = None
     model: str | None = None
+    printer_type: PrinterType = PrinterType.BAMBU
     ip_address: str | None = None
     access_code: str | None = None
     auto_connect: bool = False
+    power_watts: float | None = Field(default=None, ge=0, le=5000)  # Average draw while printing, for energy estimates
+    # Capability overrides for material compatibility checks, None = the model's defaults
+    max_nozzle_temp: int | None = Field(default=None, ge=150, le=500)
+    hardened_nozzle: bool | None = None
+    enclosed: bool | None = None
 
 
 class PrinterCreate(PrinterBase):
-    pass
+    @model_validator(mode="after")
+    def check_manual(self):
+        if self.printer_type == PrinterType.MANUAL and self.auto_connect:
+            raise ValueError("manual printers don't connect, auto_connect must be off")
+        return self
 
 
 class PrinterUpdate(BaseModel):
@@ -71,17 +187,42 @@ class PrinterUpdate(BaseModel):
     ip_address: str | None = None
     access_code: str | None = None
     auto_connect: bool | None = None
+    power_watts: float | None = Field(default=None, ge=0, le=5000)
+    max_nozzle_temp: int | None = Field(default=None, ge=150, le=500)
+    hardened_nozzle: bool | None = None
+    enclosed: bool | None = None
 
 
 class Printer(PrinterBase):
     last_seen: int | None = None
     config: str | None = None
     nozzle_count: int = 1  # 1 or 2, auto-detected from MQTT
+    deleted_at: int | None = None  # Timestamp when moved to trash, null = not deleted
+    cert_fingerprint: str | None = None  # Pinned MQTT TLS certificate, null = pinned on next connect
+    firmware_version: str | None = None  # Reported by get_version on connect
+    hardware_version: str | None = None
 
     class Config:
         from_attributes = True
 
 
+class SpeedLevel(IntEnum):
+    """Print speed profile (spd_lvl)."""
+
+    SILENT = 1
+    STANDARD = 2
+    SPORT = 3
+    LUDICROUS = 4
+
+
+class PrintAction(StrEnum):
+    """Control command for the running print."""
+
+    PAUSE = "pause"
+    RESUME = "resume"
+    STOP = "stop"
+
+
 # ============ AMS Models ============
 # NOTE: AMS models defined before PrinterWithStatus to avoid forward references
 
@@ -100,6 +241,12 @@ class AmsTray(BaseModel):
     nozzle_temp_max: int | None = None
     remain: int | None = None  # Remaining filament percentage (0-100)
 
+    @computed_field
+    @property
+    def color(self) -> ColorSwatch | None:
+        """The tray_color ready to draw, None for an empty slot."""
+        return color_swatch(self.tray_color)
+
 
 class AmsUnit(BaseModel):
     """AMS unit with humidity and trays."""
@@ -111,24 +258,65 @@ class AmsUnit(BaseModel):
     trays: list[AmsTray] = []
 
 
+class ActiveSpool(BaseModel):
+    """Slot feeding an extruder during a print, with the spool assigned to it."""
+
+    ams_id: int
+    tray_id: int
+    extruder: int | None = None  # 0 = right nozzle, 1 = left nozzle, None on single-nozzle printers
+    spool_id: str | None = None  # None if no spool is assigned to the slot
+    material: str | None = None
+    color_name: str | None = None
+    rgba: str | None = None
+    remaining_grams: float | None = None  # From the slot's remain reading, else the inventory
+
+
+class AmsEvent(BaseModel):
+    """Entry in the AMS slot change ledger."""
+
+    id: int
+    printer_serial: str
+    ams_id: int
+    tray_id: int
+    event: str  # inserted, removed, changed, loaded, unloaded
+    extruder: int | None = None
+    spool_id: str | None = None
+    tray_type: str | None = None
+    tray_color: str | None = None
+    tray_info_idx: str | None = None
+    created_at: int
+
+
 class PrinterWithStatus(BaseModel):
     """Printer with connection status and live state."""
 
     serial: str
     name: str | None = None
     model: str | None = None
+    printer_type: PrinterType = PrinterType.BAMBU
     ip_address: str | None = None
     access_code: str | None = None
     last_seen: int | None = None
     config: str | None = None
     auto_connect: bool = False
     nozzle_count: int = 1  # 1 or 2, auto-detected from MQTT
+    power_watts: float | None = None  # Average draw while printing, for energy estimates
+    firmware_version: str | None = None  # Reported by get_version on connect
+    hardware_version: str | None = None
     connected: bool = False
+    needs_access_code: bool = False  # Printer refused the stored access code, e.g. after it was regenerated
     # Live state from MQTT
     gcode_state: str | None = None
     print_progress: int | None = None
     subtask_name: str | None = None  # Current print job name
     mc_remaining_time: int | None = None  # Remaining time in minutes
+    print_eta: int | None = None  # Expected finish (Unix time)
+    speed_level: SpeedLevel | None = None
+    print_error_code: str | None = None  # e.g. "0300-400C", null = no error
+    skipped_objects: list[int] = []
+    layer_num: int | None = None
+    total_layer_num: int | None = None
+    active_spools: list[ActiveSpool] = []  # Spools feeding the extruders while printing
     cover_url: str | None = None  # URL to cover image if printing
     # Detailed status tracking
     stg_cur: int = -1  # Current stage number (-1 = idle/unknown)
@@ -139,10 +327,65 @@ class PrinterWithStatus(BaseModel):
     tray_now_left: int | None = None  # Active tray left nozzle (dual)
     tray_now_right: int | None = None  # Active tray right nozzle (dual)
     active_extruder: int | None = None  # Currently active extruder (0=right, 1=left)
+    vt_tray: AmsTray | None = None  # External spool holder
     # Tray reading state (RFID scanning)
     tray_reading_bits: int | None = None  # Bitmask of trays currently being read
 
 
+class NozzleInfo(BaseModel):
+    """Nozzle installed on an extruder."""
+
+    extruder_id: int = 0  # 0 = right/only nozzle, 1 = left nozzle
+    diameter: str  # e.g. "0.4"
+    nozzle_type: str | None = None  # e.g. "hardened_steel"
+
+
+class FirmwareModule(BaseModel):
+    """Module listed in a printer's get_version reply."""
+
+    name: str  # e.g. "ota" (the printer firmware), "mc", "ams/0"
+    sw_ver: str | None = None
+    hw_ver: str | None = None
+    sn: str | None = None
+    product_name: str | None = None
+
+
+class AmsUnitRecord(BaseModel):
+    """AMS unit known by its serial number, whatever position it is attached at."""
+
+    id: int  # Stable across power cycles, unlike ams_id
+    serial: str
+    printer_serial: str
+    ams_id: int | None = None  # Current position, null = no longer attached
+    module: str | None = None  # ams, ams_f1 (AMS Lite), n3f (AMS 2 Pro), n3s (AMS HT)
+    product_name: str | None = None
+    firmware_version: str | None = None
+    hardware_version: str | None = None
+    humidity_sensor: bool | None = None  # null = not reported yet
+    first_seen: int
+    last_seen: int
+
+
+class NozzleChange(BaseModel):
+    """Nozzle swap detected on a printer."""
+
+    id: int
+    printer_serial: str
+    extruder_id: int
+    old_diameter: str | None = None
+    old_type: str | None = None
+    new_diameter: str
+    new_type: str | None = None
+    changed_at: int
+
+
+class PrinterNozzles(BaseModel):
+    """Installed nozzles and change log for a printer."""
+
+    nozzles: list[NozzleInfo]
+    changes: list[NozzleChange]
+
+
 class PrinterState(BaseModel):
     """Real-time printer state from MQTT."""
 
@@ -152,6 +395,7 @@ class PrinterState(BaseModel):
     total_layer_num: int | None = None
     subtask_name: str | None = None
     mc_remaining_time: int | None = None  # Remaining time in minutes
+    print_eta: int | None = None  # Expected finish (Unix time), from mc_remaining_time
     gcode_file: str | None = None  # Current gcode file path
     ams_units: list[AmsUnit] = []
     vt_tray: AmsTray | None = None
@@ -167,30 +411,106 @@ class PrinterState(BaseModel):
     tray_reading_bits: int | None = None  # Bitmask of trays currently being read
     # Nozzle count (auto-detected from MQTT device.extruder.info)
     nozzle_count: int = 1  # 1 = single nozzle, 2 = dual nozzle (H2C/H2D)
+    nozzles: list[NozzleInfo] = []  # Installed nozzles, by extruder
+    # Print options
+    speed_level: SpeedLevel | None = None  # spd_lvl
+    print_error: int = 0  # print_error code, 0 = none
+    skipped_objects: list[int] = []  # s_obj, ids of objects skipped in the current print
+
+    @computed_field
+    @property
+    def print_error_code(self) -> str | None:
+        """print_error as shown by the printer and Bambu's wiki, e.g. "0300-400C"."""
+        if not self.print_error:
+            return None
+        code = f"{self.print_error:08X}"
+        return f"{code[:4]}-{code[4:]}"
 
 
 # ============ AMS Filament Setting ============
 
 
 class AmsFilamentSettingRequest(BaseModel):
-    """Request to set filament in an AMS slot."""
+    """Request to set filament in an AMS slot.
 
+    With spool_id, the preset, material, color and K profile come from the
+    spool; fields set in the request override them. Temperatures default to
+    the material's range. Empty tray_info_idx and tray_type clear the slot.
+    """
+
+    spool_id: str | None = None  # Configure the slot for this inventory spool
     tray_info_idx: str = ""  # Filament preset ID short format (e.g., "GFL05")
     tray_type: str = ""  # Material type (e.g., "PLA")
     tray_sub_brands: str = ""  # Preset name for slicer (e.g., "Bambu PLA Basic")
-    tray_color: str = "FFFFFFFF"  # RGBA hex (e.g., "FF0000FF")
-    nozzle_temp_min: int = 190
-    nozzle_temp_max: int = 230
+    tray_color: str = "FFFFFFFF"  # RGBA hex (e.g., "FF0000FF"); "#FF0000" is normalized
+    nozzle_temp_min: int | None = Field(default=None, ge=0, le=350)
+    nozzle_temp_max: int | None = Field(default=None, ge=0, le=350)
     setting_id: str = ""  # Full setting ID with version (e.g., "GFSL05_07")
+    force: bool = False  # With spool_id: configure even if the spool is incompatible with the printer
+
+    @field_validator("tray_info_idx", "tray_type", "setting_id")
+    @classmethod
+    def strip(cls, v):
+        return v.strip()
+
+    @field_validator("tray_color")
+    @classmethod
+    def check_color(cls, v):
+        color = normalize_tray_color(v)
+        if color is None:
+            raise ValueError("must be a hex color like FF8800 or FF8800FF")
+        return color
+
+    @model_validator(mode="after")
+    def check_preset(self):
+        if (
+            self.nozzle_temp_min is not None
+            and self.nozzle_temp_max is not None
+            and self.nozzle_temp_min > self.nozzle_temp_max
+        ):
+            raise ValueError("nozzle_temp_min must not exceed nozzle_temp_max")
+        error = check_filament_preset(self.tray_info_idx, self.setting_id, self.tray_type)
+        if error:
+            raise ValueError(error)
+        return self
 
 
 class AssignSpoolRequest(BaseModel):
     """Request to assign a spool to an AMS slot."""
 
     spool_id: str
+    force: bool = False  # Assign even if the spool's material is incompatible with the printer
     # Note: ams_id and tray_id come from path parameters, not body
 
 
+EXTERNAL_HOLDER_IDS = (255, 254)  # Virtual tray ams_id: 255 = external/right, 254 = left (dual-nozzle)
+
+
+class ExternalSpoolRequest(BaseModel):
+    """Request to put a spool on a printer's external spool holder."""
+
+    spool_id: str
+    ams_id: int = 255
+    force: bool = False  # Assign even if the spool's material is incompatible with the printer
+
+    @field_validator("ams_id")
+    @classmethod
+    def check_holder(cls, v: int) -> int:
+        if v not in EXTERNAL_HOLDER_IDS:
+            raise ValueError("ams_id must be 255 (external) or 254 (left external on dual-nozzle printers)")
+        return v
+
+
+class ExternalSpool(BaseModel):
+    """Spool on a printer's external holder (virtual tray)."""
+
+    ams_id: int = 255
+    spool: Spool | None = None
+    tray: AmsTray | None = None  # Live vt_tray state from the printer, if connected
+    sequence_id: str | None = None  # ams_filament_setting command sent when assigning
+    warnings: list[str] = []  # Material compatibility warnings when assigning
+
+
 class SetCalibrationRequest(BaseModel):
     """Request to set calibration profile for an AMS slot."""
 
@@ -202,6 +522,151 @@ class SetCalibrationRequest(BaseModel):
     nozzle_temp_max: int = 230  # Max nozzle temp for extrusion_cali_set
 
 
+class SpeedLevelRequest(BaseModel):
+    """Request to change the print speed."""
+
+    level: SpeedLevel
+
+
+class SkipObjectsRequest(BaseModel):
+    """Request to skip objects of the current print."""
+
+    obj_ids: list[int] = Field(min_length=1)  # Object ids from the print's plate metadata
+
+
+class CommandStatus(BaseModel):
+    """Command sent to a printer and its acknowledgement."""
+
+    sequence_id: str
+    command: str
+    status: str  # "pending", "success", "failed" or "timeout"
+    reason: str | None = None  # Printer's reason for a failure
+    sent_at: float
+    completed_at: float | None = None
+
+
+class RunoutKind(StrEnum):
+    """Kind of filament feed problem."""
+
+    RUNOUT = "runout"  # Filament ran out
+    JAM = "jam"  # Filament stuck/tangled, the spool isn't necessarily empty
+
+
+class RunoutEventRequest(BaseModel):
+    """Runout/jam event reported by a printer or DIY sensor."""
+
+    kind: RunoutKind = RunoutKind.RUNOUT
+    ams_id: int = Field(default=255, ge=0, le=255)  # 255/254 = external holder
+    tray_id: int = Field(default=0, ge=0, le=3)
+    spool_id: str | None = None  # Affected spool, defaults to the one assigned to the slot
+    print_name: str | None = None  # Defaults to the printer's current job
+    source: str | None = Field(default=None, max_length=64)  # Reporter, e.g. "printer" or "diy-sensor"
+    mark_empty: bool | None = None  # Mark the spool as used up (default: only for runouts)
+
+
+class RunoutEvent(BaseModel):
+    """Recorded runout/jam event."""
+
+    id: int
+    printer_serial: str
+    ams_id: int
+    tray_id: int
+    spool_id: str | None = None
+    kind: RunoutKind
+    print_name: str | None = None
+    source: str | None = None
+    marked_empty: bool = False
+    created_at: int
+
+
+# ============ Device Models ============
+# Mirrored in firmware/core/src/proto.rs, keep both in step
+
+
+class DeviceAction(StrEnum):
+    """What the device display should offer next."""
+
+    NONE = "none"
+    ADD_SPOOL = "add_spool"  # Tag is not in the inventory yet
+    UPDATE_WEIGHT = "update_weight"  # Scale reading differs from the tracked weight
+    REORDER = "reorder"  # Spool is below the low stock threshold
+
+
+class DeviceSpoolInfo(BaseModel):
+    """Inventory spool resolved from the tag on the scale."""
+
+    id: str
+    spool_number: int | None = None
+    name: str  # e.g. "Bambu Lab PLA Basic"
+    brand: str | None = None
+    material: str
+    subtype: str | None = None
+    color_name: str | None = None
+    color_rgba: int = 0  # Packed 0xRRGGBBAA
+    color_stops: list[int] = []  # Packed 0xRRGGBBAA per color of multi-color filament, in order
+    remaining_g: float | None = None  # None if label weight unknown
+    label_weight: int | None = None
+    low_stock: bool = False
+
+
+class KioskState(StrEnum):
+    """Step of the kiosk check-in/check-out flow shown on the device."""
+
+    SCAN_BADGE = "scan_badge"  # Waiting for a member badge
+    SCAN_SPOOL = "scan_spool"  # Member signed in, waiting for a spool
+    CHECKED_OUT = "checked_out"
+    CHECKED_IN = "checked_in"
+    UNKNOWN_TAG = "unknown_tag"  # Neither a badge nor a spool
+
+
+class KioskStatus(BaseModel):
+    """What the kiosk screen shows."""
+
+    state: KioskState = KioskState.SCAN_BADGE
+    member_name: str | None = None  # Signed-in member
+    spool_name: str | None = None  # Spool just checked out or in
+    weight_used: float | None = None  # Filament used while checked out, on check-in
+    message: str = "Scan your badge"
+    expires_in: int | None = None  # Seconds until the member is signed out
+
+
+class EncodeTask(BaseModel):
+    """Next tag of a batch encoding job, shown by the device."""
+
+    job_id: str
+    index: int  # 1-based position in the batch
+    total: int
+    spool_id: str
+    label: str  # Spool to encode, e.g. "Red PETG"
+    tag_uid: str | None = None  # Tag on the reader the data was built for
+    data: str | None = None  # Hex bytes to write from NTAG page 4 on, None until a usable tag is placed
+    message: str = ""  # Prompt or result of the last attempt
+
+
+class EncodeResult(BaseModel):
+    """Outcome of writing one tag, reported by the device."""
+
+    job_id: str
+    index: int
+    tag_uid: str
+    outcome: str  # verified, write_failed, read_failed, mismatch, too_large
+    pages_written: int = 0
+    retries: int = 0  # Failed page writes/reads the device retried
+    failed_page: int | None = None
+    rf_signal: int | None = None  # Raw RF signal reading of the reader, None if it can't measure it
+
+
+class DeviceStateResponse(BaseModel):
+    """Reply to a device state update."""
+
+    ok: bool = True
+    tag_id: str | None = None
+    spool: DeviceSpoolInfo | None = None
+    action: DeviceAction = DeviceAction.NONE
+    kiosk: KioskStatus | None = None  # Set while kiosk mode is on
+    encode: EncodeTask | None = None  # Set while a tag batch job runs
+
+
 # ============ WebSocket Messages ============
 
 
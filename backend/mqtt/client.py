@@ -1,14 +1,17 @@
 import asyncio
 import json
 import logging
-import ssl
+import threading
 import time
 from collections.abc import Callable
 from dataclasses import dataclass, field
 from typing import Any
 
 import paho.mqtt.client as mqtt
-from models import AmsTray, AmsUnit, PrinterState
+from config import settings
+from models import AmsTray, AmsUnit, FirmwareModule, NozzleInfo, PrintAction, PrinterState, SpeedLevel
+from mqtt.tls import PinningContext, printer_tls_context
+from services.tracing import traced
 
 logger = logging.getLogger(__name__)
 
@@ -97,9 +100,79 @@ class Calibration:
     setting_id: str | None = None  # Full setting ID for slicer compatibility
 
 
+# Keys of a tray report for an empty slot
+EMPTY_TRAY_KEYS = {"id", "state"}
+
 # Grace period before reporting printer as disconnected (handles brief MQTT interruptions)
 DISCONNECT_GRACE_PERIOD_SEC = 5.0
 
+# Seconds to wait for the printer to acknowledge a command before giving up on it
+COMMAND_TIMEOUT_SEC = 10.0
+
+# Commands remembered per printer for status lookups
+MAX_TRACKED_COMMANDS = 50
+
+# CONNACK codes meaning the access code was refused: bad user name or password, not authorized
+# (paho reports the MQTT 3.1.1 codes 4 and 5 as their MQTT 5 equivalents 134 and 135)
+AUTH_FAILURE_REASON_CODES = {4, 5, 134, 135}
+
+
+def is_auth_failure(reason_code) -> bool:
+    """Whether a refused connection means the printer didn't accept the access code."""
+    return getattr(reason_code, "value", reason_code) in AUTH_FAILURE_REASON_CODES
+
+
+def parse_version_modules(info_data: dict) -> list[FirmwareModule]:
+    """Parse the module list of a get_version reply, skipping malformed entries."""
+    modules = []
+    for module in info_data.get("module") or []:
+        if not isinstance(module, dict) or not module.get("name"):
+            continue
+        modules.append(
+            FirmwareModule(
+                name=str(module["name"]),
+                **{
+                    key: str(module[key])
+                    for key in ("sw_ver", "hw_ver", "sn", "product_name")
+                    if module.get(key) not in (None, "")
+                },
+            )
+        )
+    return modules
+
+
+def printer_versions(modules: list[FirmwareModule]) -> tuple[str | None, str | None]:
+    """(firmware, hardware) version of the printer itself.
+
+    The "ota" module carries the firmware version of the whole printer, the
+    main controller ("mc") its hardware revision.
+    """
+    by_name = {module.name: module for module in modules}
+    ota = by_name.get("ota")
+    mc = by_name.get("mc")
+    return (ota.sw_ver if ota else None, (mc.hw_ver if mc else None) or (ota.hw_ver if ota else None))
+
+
+# get_version module types of AMS units, named "<type>/<ams_id>"
+AMS_MODULE_TYPES = ("ams", "ams_f1", "n3f", "n3s")
+
+
+def ams_modules(modules: list[FirmwareModule]) -> dict[int, tuple[str, FirmwareModule]]:
+    """AMS units in a get_version module list, by ams_id: (module type, module)."""
+    units = {}
+    for module in modules:
+        kind, _, position = module.name.partition("/")
+        if kind in AMS_MODULE_TYPES and position.isdigit() and module.sn:
+            units[int(position)] = (kind, module)
+    return units
+
+
+def diff_states(old: PrinterState | None, new: PrinterState) -> list[str]:
+    """Top-level PrinterState fields that differ (all fields when there is no old state)."""
+    if old is None:
+        return list(PrinterState.model_fields)
+    return [name for name in PrinterState.model_fields if getattr(old, name) != getattr(new, name)]
+
 
 @dataclass
 class PendingAssignment:
@@ -117,6 +190,28 @@ class PendingAssignment:
     created_at: float = field(default_factory=time.time)
 
 
+@dataclass
+class CommandRecord:
+    """Command sent to a printer and its acknowledgement."""
+
+    sequence_id: str
+    command: str
+    status: str = "pending"  # pending, success, failed, timeout
+    reason: str | None = None
+    sent_at: float = field(default_factory=time.time)
+    completed_at: float | None = None
+
+    def to_dict(self) -> dict:
+        return {
+            "sequence_id": self.sequence_id,
+            "command": self.command,
+            "status": self.status,
+            "reason": self.reason,
+            "sent_at": self.sent_at,
+            "completed_at": self.completed_at,
+        }
+
+
 @dataclass
 class PrinterConnection:
     """Manages MQTT connection to a single Bambu printer."""
@@ -125,12 +220,19 @@ class PrinterConnection:
     ip_address: str
     access_code: str
     name: str | None = None
+    cert_fingerprint: str | None = None  # Pinned TLS certificate, None = pin the first one seen
+    cert_mismatch: str | None = None  # Fingerprint of a refused certificate that didn't match the pin
+    auth_failed: bool = False  # Printer refused the access code, e.g. after it was regenerated
 
     _client: mqtt.Client | None = field(default=None, repr=False)
+    _tls_context: PinningContext | None = field(default=None, repr=False)
+    _on_cert_pinned: Callable[[str, str], None] | None = field(default=None, repr=False)  # (serial, fingerprint)
+    _on_auth_failed: Callable[[str], None] | None = field(default=None, repr=False)  # (serial)
     _connected: bool = field(default=False, repr=False)
     _disconnect_time: float | None = field(default=None, repr=False)  # Timestamp of disconnect
     _state: PrinterState = field(default_factory=PrinterState, repr=False)
-    _on_state_update: Callable[[str, PrinterState], None] | None = field(default=None, repr=False)
+    _published_state: PrinterState | None = field(default=None, repr=False)  # Last snapshot sent to the listener
+    _on_state_update: Callable[[str, PrinterState, list[str]], None] | None = field(default=None, repr=False)
     _loop: asyncio.AbstractEventLoop | None = field(default=None, repr=False)
     _calibrations: dict = field(default_factory=dict, repr=False)  # cali_idx -> Calibration
     _kprofiles: list = field(default_factory=list, repr=False)  # List of calibration profiles (updated on broadcast)
@@ -149,8 +251,19 @@ class PrinterConnection:
     _on_nozzle_count_update: Callable[[str, int], None] | None = field(
         default=None, repr=False
     )  # (serial, nozzle_count)
+    _on_nozzle_change: Callable[[str, list[NozzleInfo]], None] | None = field(
+        default=None, repr=False
+    )  # (serial, nozzles)
+    _on_version: Callable[[str, list[FirmwareModule]], None] | None = field(
+        default=None, repr=False
+    )  # (serial, modules)
     _nozzle_diameters: dict = field(default_factory=dict, repr=False)  # extruder_id -> nozzle_diameter string
+    _nozzle_types: dict = field(default_factory=dict, repr=False)  # extruder_id -> nozzle type string
     _nozzle_count_detected: bool = field(default=False, repr=False)  # Track if we've already detected nozzle count
+    _sequence_id: int = field(default=0, repr=False)  # Last sequence_id sent
+    _sequence_lock: threading.Lock = field(default_factory=threading.Lock, repr=False)  # Sent from both threads
+    _commands: dict = field(default_factory=dict, repr=False)  # sequence_id -> CommandRecord, oldest first
+    _on_command_result: Callable[[str, dict], None] | None = field(default=None, repr=False)  # (serial, command)
 
     @property
     def connected(self) -> bool:
@@ -174,7 +287,7 @@ class PrinterConnection:
 
     def connect(
         self,
-        on_state_update: Callable[[str, PrinterState], None],
+        on_state_update: Callable[[str, PrinterState, list[str]], None],
         on_disconnect: Callable[[str], None] | None = None,
         on_connect: Callable[[str], None] | None = None,
     ):
@@ -194,11 +307,14 @@ class PrinterConnection:
         # Set credentials
         self._client.username_pw_set("bblp", self.access_code)
 
-        # Configure TLS (Bambu printers use self-signed certs)
-        ssl_context = ssl.create_default_context()
-        ssl_context.check_hostname = False
-        ssl_context.verify_mode = ssl.CERT_NONE
-        self._client.tls_set_context(ssl_context)
+        # Configure TLS (Bambu printers use self-signed certs, pinned on first use)
+        self._tls_context = printer_tls_context(
+            self.cert_fingerprint,
+            insecure=settings.printer_tls_insecure,
+            on_pin=self._handle_cert_pinned,
+            on_mismatch=self._handle_cert_mismatch,
+        )
+        self._client.tls_set_context(self._tls_context)
 
         # Set callbacks
         self._client.on_connect = self._on_connect
@@ -214,6 +330,25 @@ class PrinterConnection:
             logger.error(f"Failed to connect to {self.serial}: {e}")
             raise
 
+    @traced("mqtt.tls")
+    def _handle_cert_pinned(self, fingerprint: str):
+        """TLS certificate seen for the first time, pin it."""
+        logger.info(f"Pinned TLS certificate of {self.serial}: {fingerprint}")
+        self.cert_fingerprint = fingerprint
+        self.cert_mismatch = None
+        if self._on_cert_pinned and self._loop and self._loop.is_running():
+            self._loop.call_soon_threadsafe(self._on_cert_pinned, self.serial, fingerprint)
+
+    @traced("mqtt.tls")
+    def _handle_cert_mismatch(self, fingerprint: str):
+        """Printer presented a certificate other than the pinned one; the connection is refused."""
+        if fingerprint != self.cert_mismatch:
+            logger.error(
+                f"TLS certificate of {self.serial} changed ({fingerprint}, pinned {self.cert_fingerprint}). "
+                "Not connecting; re-pin the printer's certificate if the change is expected."
+            )
+        self.cert_mismatch = fingerprint
+
     def disconnect(self):
         """Disconnect from printer."""
         if self._client:
@@ -254,12 +389,11 @@ class PrinterConnection:
                 "command": "ams_get_rfid",
                 "ams_id": ams_id,
                 "slot_id": slot_id,
-                "sequence_id": "1",
             }
         }
 
         try:
-            result = self._client.publish(topic, json.dumps(rfid_command))
+            result = self._publish_command(topic, rfid_command)
             if result.rc != mqtt.MQTT_ERR_SUCCESS:
                 logger.error(f"Failed to send ams_get_rfid command: {result.rc}")
                 return False
@@ -316,7 +450,6 @@ class PrinterConnection:
                 "ams_id": ams_id,
                 "tray_id": tray_id,  # Local tray index (0-3), not global
                 "slot_id": slot_id,
-                "sequence_id": "1",
             }
         }
         # Include setting_id if provided (helps slicer show correct K profile)
@@ -324,14 +457,12 @@ class PrinterConnection:
             command["print"]["setting_id"] = setting_id
 
         topic = f"device/{self.serial}/request"
-        payload_json = json.dumps(command)
         logger.info(
             f"[{self.serial}] Publishing extrusion_cali_sel: AMS {ams_id}, tray {tray_id}, "
             f"cali_idx={cali_idx}, filament_id={filament_id}, setting_id={setting_id}"
         )
-        logger.info(f"[{self.serial}] MQTT extrusion_cali_sel command: {payload_json}")
         try:
-            result = self._client.publish(topic, payload_json)
+            result = self._publish_command(topic, command)
             if result.rc == mqtt.MQTT_ERR_SUCCESS:
                 logger.info(
                     f"Set calibration on {self.serial}: AMS {ams_id}, tray {tray_id}, "
@@ -398,16 +529,13 @@ class PrinterConnection:
                 "bed_temp": 60,
                 "nozzle_temp": nozzle_temp,
                 "max_volumetric_speed": 20.0,
-                "sequence_id": "1",
             }
         }
 
         topic = f"device/{self.serial}/request"
-        payload_json = json.dumps(command)
         logger.info(f"[{self.serial}] Publishing extrusion_cali_set: tray {tray_id}, k_value={k_value}")
-        logger.info(f"[{self.serial}] MQTT extrusion_cali_set command: {payload_json}")
         try:
-            result = self._client.publish(topic, payload_json)
+            result = self._publish_command(topic, command)
             if result.rc == mqtt.MQTT_ERR_SUCCESS:
                 return True
             else:
@@ -417,6 +545,63 @@ class PrinterConnection:
             logger.error(f"Error setting K value on {self.serial}: {e}")
             return False
 
+    def set_speed_level(self, level: SpeedLevel) -> bool:
+        """Change the speed of the running print."""
+        if not self._client or not self._connected:
+            logger.error(f"Cannot set speed: not connected to {self.serial}")
+            return False
+
+        topic = f"device/{self.serial}/request"
+        command = {"print": {"command": "print_speed", "param": str(int(level))}}
+        try:
+            result = self._publish_command(topic, command)
+            if result.rc != mqtt.MQTT_ERR_SUCCESS:
+                logger.error(f"Failed to publish print_speed: {result.rc}")
+                return False
+            logger.info(f"[{self.serial}] Speed set to {level.name.lower()}")
+            return True
+        except Exception as e:
+            logger.error(f"Error setting speed on {self.serial}: {e}")
+            return False
+
+    def skip_objects(self, obj_ids: list[int]) -> bool:
+        """Skip objects of the running print, by their plate object ids."""
+        if not self._client or not self._connected:
+            logger.error(f"Cannot skip objects: not connected to {self.serial}")
+            return False
+
+        topic = f"device/{self.serial}/request"
+        command = {"print": {"command": "skip_objects", "obj_list": list(obj_ids)}}
+        try:
+            result = self._publish_command(topic, command)
+            if result.rc != mqtt.MQTT_ERR_SUCCESS:
+                logger.error(f"Failed to publish skip_objects: {result.rc}")
+                return False
+            logger.info(f"[{self.serial}] Skipping objects {obj_ids}")
+            return True
+        except Exception as e:
+            logger.error(f"Error skipping objects on {self.serial}: {e}")
+            return False
+
+    def control_print(self, action: PrintAction) -> bool:
+        """Pause, resume or stop the running print."""
+        if not self._client or not self._connected:
+            logger.error(f"Cannot {action} print: not connected to {self.serial}")
+            return False
+
+        topic = f"device/{self.serial}/request"
+        command = {"print": {"command": action.value, "param": ""}}
+        try:
+            result = self._publish_command(topic, command)
+            if result.rc != mqtt.MQTT_ERR_SUCCESS:
+                logger.error(f"Failed to publish {action}: {result.rc}")
+                return False
+            logger.info(f"[{self.serial}] Sent {action} print")
+            return True
+        except Exception as e:
+            logger.error(f"Error sending {action} to {self.serial}: {e}")
+            return False
+
     async def get_kprofiles(
         self, nozzle_diameter: str = "0.4", timeout: float = 5.0, max_retries: int = 3
     ) -> list[dict]:
@@ -544,7 +729,6 @@ class PrinterConnection:
                 "tray_color": tray_color,
                 "nozzle_temp_min": nozzle_temp_min,
                 "nozzle_temp_max": nozzle_temp_max,
-                "sequence_id": "1",
             }
         }
         # Only include setting_id if provided (it's optional)
@@ -552,14 +736,12 @@ class PrinterConnection:
             command["print"]["setting_id"] = setting_id
 
         topic = f"device/{self.serial}/request"
-        payload_json = json.dumps(command)
         logger.info(
             f"[{self.serial}] Publishing ams_filament_setting: AMS {ams_id}, tray {tray_id}, "
             f"tray_info_idx={tray_info_idx}, tray_sub_brands={tray_sub_brands}, setting_id={setting_id}"
         )
-        logger.info(f"[{self.serial}] MQTT ams_filament_setting command: {payload_json}")
         try:
-            result = self._client.publish(topic, payload_json)
+            result = self._publish_command(topic, command)
             if result.rc == mqtt.MQTT_ERR_SUCCESS:
                 logger.info(
                     f"Set filament on {self.serial}: AMS {ams_id}, tray {tray_id}, "
@@ -682,12 +864,15 @@ class PrinterConnection:
                 lambda: self._on_assignment_complete(self.serial, ams_id, tray_id, spool_id, success)
             )
 
+    @traced("mqtt.connect")
     def _on_connect(self, client, userdata, flags, reason_code, properties):
         """MQTT connect callback."""
         logger.info(f"MQTT _on_connect for {self.serial}: reason_code={reason_code}")
         if reason_code == 0:
             self._connected = True
+            self.auth_failed = False
             self._disconnect_time = None  # Clear disconnect timestamp on reconnect
+            self._published_state = None  # Publish the first report after (re)connecting in full
             logger.info(f"Connected to printer {self.serial} - _connected is now True")
 
             # Subscribe to report topic
@@ -699,16 +884,26 @@ class PrinterConnection:
             for nozzle_diameter in ["0.2", "0.4", "0.6", "0.8"]:
                 self._fetch_calibrations(nozzle_diameter)
 
-            # Request full state
+            # Request full state and the firmware versions
             self._send_pushall()
+            self._send_get_version()
 
             # Notify connect callback
             if hasattr(self, "_on_connect_callback") and self._on_connect_callback:
                 if self._loop and self._loop.is_running():
                     self._loop.call_soon_threadsafe(lambda: self._on_connect_callback(self.serial))
+        elif is_auth_failure(reason_code):
+            # Retrying with the same code can't succeed, stop reconnecting until it is changed
+            logger.error(f"Printer {self.serial} refused the access code ({reason_code}), not reconnecting")
+            self.auth_failed = True
+            client.disconnect()
+            client.loop_stop()
+            if self._on_auth_failed and self._loop and self._loop.is_running():
+                self._loop.call_soon_threadsafe(self._on_auth_failed, self.serial)
         else:
             logger.error(f"Connection to {self.serial} failed: {reason_code}")
 
+    @traced("mqtt.disconnect")
     def _on_disconnect(self, client, userdata, flags, reason_code, properties):
         """MQTT disconnect callback."""
         self._connected = False
@@ -722,6 +917,7 @@ class PrinterConnection:
             if self._loop and self._loop.is_running():
                 self._loop.call_soon_threadsafe(lambda: self._on_disconnect_callback(self.serial))
 
+    @traced("mqtt.message")
     def _on_message(self, client, userdata, msg):
         """MQTT message callback."""
         try:
@@ -742,14 +938,104 @@ class PrinterConnection:
         except Exception as e:
             logger.error(f"Error handling message from {self.serial}: {e}")
 
+    def _publish_command(self, topic: str, command: dict) -> mqtt.MQTTMessageInfo:
+        """Publish a command with the next sequence_id and track its acknowledgement.
+
+        The printer echoes the sequence_id in its response, which is matched
+        in _handle_command_response. "pushing" requests are answered with a
+        regular status report and are not tracked.
+        """
+        section, body = next(iter(command.items()))
+        record = None
+        with self._sequence_lock:
+            self._sequence_id += 1
+            body["sequence_id"] = str(self._sequence_id)
+            if section == "print":
+                record = CommandRecord(sequence_id=body["sequence_id"], command=body.get("command", ""))
+                self._commands[record.sequence_id] = record
+                while len(self._commands) > MAX_TRACKED_COMMANDS:
+                    del self._commands[next(iter(self._commands))]
+
+        payload_json = json.dumps(command)
+        logger.info(f"[{self.serial}] MQTT {body.get('command')} command: {payload_json}")
+        try:
+            result = self._client.publish(topic, payload_json)
+        except Exception as e:
+            if record:
+                self._complete_command(record, "failed", str(e))
+            raise
+        if record and result.rc != mqtt.MQTT_ERR_SUCCESS:
+            self._complete_command(record, "failed", f"Publish failed ({result.rc})")
+        return result
+
+    def _complete_command(self, record: CommandRecord, status: str, reason: str | None = None):
+        """Record a command's outcome and notify the listener."""
+        record.status = status
+        record.reason = reason
+        record.completed_at = time.time()
+        if status != "success":
+            logger.warning(f"[{self.serial}] Command {record.command} #{record.sequence_id} {status}: {reason}")
+        if self._on_command_result and self._loop:
+            result = record.to_dict()
+            self._loop.call_soon_threadsafe(lambda: self._on_command_result(self.serial, result))
+
+    def _handle_command_response(self, print_data: dict):
+        """Match a command response to the command it acknowledges."""
+        record = self._commands.get(str(print_data.get("sequence_id")))
+        if not record or record.status != "pending" or record.command != print_data.get("command"):
+            # Status reports carry the printer's own sequence numbers
+            return
+        result = str(print_data.get("result", "success")).lower()
+        if result == "success":
+            self._complete_command(record, "success")
+        else:
+            self._complete_command(record, "failed", print_data.get("reason") or result)
+
+    def _expire_commands(self):
+        """Time out commands the printer never acknowledged."""
+        deadline = time.time() - COMMAND_TIMEOUT_SEC
+        for record in list(self._commands.values()):
+            if record.status == "pending" and record.sent_at < deadline:
+                self._complete_command(record, "timeout", "No response from printer")
+
+    def get_command(self, sequence_id: str) -> dict | None:
+        """Get a sent command and its outcome."""
+        self._expire_commands()
+        record = self._commands.get(sequence_id)
+        return record.to_dict() if record else None
+
+    def get_commands(self) -> list[dict]:
+        """Get recently sent commands, newest first."""
+        self._expire_commands()
+        return [record.to_dict() for record in reversed(list(self._commands.values()))]
+
+    def get_last_command(self, command: str) -> dict | None:
+        """Get the most recently sent command with the given name."""
+        return next((c for c in self.get_commands() if c["command"] == command), None)
+
     def _send_pushall(self):
         """Request full printer state."""
         if self._client and self._connected:
             topic = f"device/{self.serial}/request"
-            payload = json.dumps({"pushing": {"command": "pushall", "sequence_id": "1"}})
-            self._client.publish(topic, payload)
+            self._publish_command(topic, {"pushing": {"command": "pushall"}})
             logger.debug(f"[{self.serial}] Sent pushall request")
 
+    def _send_get_version(self):
+        """Request the firmware and hardware versions of the printer's modules."""
+        if self._client and self._connected:
+            topic = f"device/{self.serial}/request"
+            self._publish_command(topic, {"info": {"command": "get_version"}})
+
+    def _handle_version_response(self, info_data: dict):
+        """Handle a get_version reply."""
+        modules = parse_version_modules(info_data)
+        if not modules:
+            return
+        logger.info(f"[{self.serial}] Firmware {printer_versions(modules)[0]}, {len(modules)} modules")
+        if self._on_version and self._loop:
+            snapshot = [m.model_copy() for m in modules]
+            self._loop.call_soon_threadsafe(lambda: self._on_version(self.serial, snapshot))
+
     def refresh_state(self):
         """Request full printer state refresh (public method)."""
         self._send_pushall()
@@ -758,21 +1044,24 @@ class PrinterConnection:
         """Request calibration profiles for a nozzle diameter."""
         if self._client and self._connected:
             topic = f"device/{self.serial}/request"
-            payload = json.dumps(
+            self._publish_command(
+                topic,
                 {
                     "print": {
                         "command": "extrusion_cali_get",
                         "filament_id": "",
                         "nozzle_diameter": nozzle_diameter,
-                        "sequence_id": "1",
                     }
-                }
+                },
             )
-            self._client.publish(topic, payload)
             logger.info(f"[{self.serial}] Requested calibrations for nozzle {nozzle_diameter}")
 
     def _handle_message(self, payload: dict):
         """Process incoming MQTT message."""
+        info_data = payload.get("info")
+        if isinstance(info_data, dict) and info_data.get("command") == "get_version":
+            self._handle_version_response(info_data)
+
         if "print" not in payload:
             return
 
@@ -782,6 +1071,9 @@ class PrinterConnection:
         command = print_data.get("command")
         if command:
             logger.debug(f"[{self.serial}] Received command: {command}")
+            if "sequence_id" in print_data:
+                self._handle_command_response(print_data)
+        self._expire_commands()
 
         # Debug: check if there are filaments in the response (calibration data)
         if "filaments" in print_data:
@@ -796,9 +1088,11 @@ class PrinterConnection:
             self._handle_calibration_response(print_data)
             return
 
-        # Extract nozzle diameter (single-nozzle printers)
+        # Extract nozzle diameter and type (single-nozzle printers)
         if "nozzle_diameter" in print_data:
-            self._nozzle_diameters[0] = str(print_data["nozzle_diameter"])
+            self._nozzle_diameters[0] = self._normalize_nozzle_diameter(print_data["nozzle_diameter"])
+        if print_data.get("nozzle_type"):
+            self._nozzle_types[0] = str(print_data["nozzle_type"])
 
         # Extract gcode state
         if "gcode_state" in print_data:
@@ -810,9 +1104,10 @@ class PrinterConnection:
             self._state.stg_cur = new_stg
             self._state.stg_cur_name = get_stage_name(new_stg)
 
-        # Extract progress (mc_percent is actual print progress)
+        # Extract progress (mc_percent is print progress; gcode_file_prepare_percent is only the file download)
         if "mc_percent" in print_data:
-            self._state.print_progress = print_data["mc_percent"]
+            progress = self._safe_int(print_data["mc_percent"])
+            self._state.print_progress = max(0, min(progress, 100)) if progress is not None else None
 
         # Extract subtask name
         if "subtask_name" in print_data:
@@ -820,7 +1115,23 @@ class PrinterConnection:
 
         # Extract remaining time (in minutes)
         if "mc_remaining_time" in print_data:
-            self._state.mc_remaining_time = self._safe_int(print_data["mc_remaining_time"])
+            remaining = self._safe_int(print_data["mc_remaining_time"])
+            # The ETA only moves when the printer revises its estimate, not on every report
+            if remaining != self._state.mc_remaining_time or self._state.print_eta is None:
+                self._state.print_eta = int(time.time()) + remaining * 60 if remaining else None
+            self._state.mc_remaining_time = remaining
+
+        # Extract print options: speed level, error code, skipped objects
+        if "spd_lvl" in print_data:
+            try:
+                self._state.speed_level = SpeedLevel(self._safe_int(print_data["spd_lvl"]))
+            except ValueError:
+                self._state.speed_level = None
+        if "print_error" in print_data:
+            self._state.print_error = self._safe_int(print_data["print_error"], 0)
+        if "s_obj" in print_data:
+            objects = print_data["s_obj"] or []
+            self._state.skipped_objects = [obj for obj in map(self._safe_int, objects) if obj is not None]
 
         # Extract gcode file path
         if "gcode_file" in print_data:
@@ -844,7 +1155,7 @@ class PrinterConnection:
 
         # Extract virtual tray (external spool)
         if "vt_tray" in print_data:
-            self._state.vt_tray = self._parse_tray(print_data["vt_tray"], 255, 0)
+            self._state.vt_tray = self._merge_tray(self._state.vt_tray, print_data["vt_tray"], 255, 0)
 
         # Extract tray_now (currently active tray) from AMS data
         ams_data = print_data.get("ams", {})
@@ -881,7 +1192,7 @@ class PrinterConnection:
                 # Parse nozzle diameter (dia field)
                 nozzle_dia = ext_info.get("dia")
                 if nozzle_dia is not None and ext_id is not None:
-                    self._nozzle_diameters[ext_id] = str(nozzle_dia)
+                    self._nozzle_diameters[ext_id] = self._normalize_nozzle_diameter(nozzle_dia)
 
                 snow = ext_info.get("snow")  # encoded tray_now for this extruder
                 if snow is not None:
@@ -916,11 +1227,58 @@ class PrinterConnection:
             if state_val is not None:
                 self._state.active_extruder = (state_val >> 4) & 0xF
 
-        # Notify listener
-        if self._on_state_update:
-            # Schedule callback in event loop if running from MQTT thread
-            if self._loop:
-                self._loop.call_soon_threadsafe(lambda: self._on_state_update(self.serial, self._state))
+        # Installed nozzles (PrintDeviceNozzleInfo): [{"id": 0, "type": "HS01", "diameter": 0.4, ...}]
+        for nozzle_info in device_data.get("nozzle", {}).get("info", []):
+            nozzle_id = self._safe_int(nozzle_info.get("id"))
+            if nozzle_id is None:
+                continue
+            if nozzle_info.get("diameter") is not None:
+                self._nozzle_diameters[nozzle_id] = self._normalize_nozzle_diameter(nozzle_info["diameter"])
+            if nozzle_info.get("type"):
+                self._nozzle_types[nozzle_id] = str(nozzle_info["type"])
+
+        self._update_nozzles()
+        self._publish_state()
+
+    @staticmethod
+    def _normalize_nozzle_diameter(value) -> str:
+        """Nozzle diameter as k-profile key: 0.4, "0.40" and "0.4" all become "0.4"."""
+        try:
+            return f"{round(float(value), 2):g}"
+        except (ValueError, TypeError):
+            return str(value)
+
+    def _update_nozzles(self):
+        """Rebuild the installed nozzle list and notify the listener if it changed."""
+        nozzles = [
+            NozzleInfo(extruder_id=ext_id, diameter=diameter, nozzle_type=self._nozzle_types.get(ext_id))
+            for ext_id, diameter in sorted(self._nozzle_diameters.items())
+        ]
+        if nozzles == self._state.nozzles:
+            return
+        self._state.nozzles = nozzles
+        if self._on_nozzle_change and self._loop:
+            snapshot = [n.model_copy() for n in nozzles]
+            self._loop.call_soon_threadsafe(lambda: self._on_nozzle_change(self.serial, snapshot))
+
+    def _publish_state(self):
+        """Notify the listener with a snapshot of the merged state, if anything changed.
+
+        Printers report several times a second, mostly repeating what they
+        already sent; unchanged reports are dropped here.
+        """
+        if not self._on_state_update:
+            return
+        changes = diff_states(self._published_state, self._state)
+        if not changes:
+            return
+
+        # Listeners get their own copy - self._state keeps changing on the MQTT thread
+        snapshot = self._state.model_copy(deep=True)
+        self._published_state = snapshot
+        # Schedule callback in event loop if running from MQTT thread
+        if self._loop:
+            self._loop.call_soon_threadsafe(lambda: self._on_state_update(self.serial, snapshot, changes))
 
     def _handle_calibration_response(self, print_data: dict):
         """Process calibration profiles from extrusion_cali_get response."""
@@ -988,10 +1346,11 @@ class PrinterConnection:
                 self._pending_kprofile_response.set()
 
     def _parse_ams_data(self, ams_data: dict):
-        """Parse AMS units and trays from MQTT data."""
-        if "ams" not in ams_data:
-            return
+        """Merge AMS units and trays from MQTT data into the retained state.
 
+        Reports can be partial: units, trays and fields missing from a report
+        keep their last known values.
+        """
         # Build/update AMS extruder map from info field
         # This map persists across updates even when info field is missing
         if not hasattr(self, "_ams_extruder_map"):
@@ -1015,8 +1374,8 @@ class PrinterConnection:
             except (ValueError, TypeError):
                 pass
 
-        # Track if we have info field in this update (helps debug)
-        has_info_field = any(unit.get("info") is not None for unit in ams_data["ams"])
+        if "ams" not in ams_data:
+            return
 
         # Only parse extruder assignments for dual-nozzle printers
         # Single-nozzle printers also have info field but don't have dual extruders
@@ -1039,9 +1398,10 @@ class PrinterConnection:
         # Collect trays to check for pending assignments
         trays_to_check = []
 
-        units = []
+        units = {unit.id: unit for unit in self._state.ams_units}
         for ams_unit in ams_data["ams"]:
             unit_id = self._safe_int(ams_unit.get("id"), 0)
+            current = units.get(unit_id)
 
             # Prefer humidity_raw (actual percentage) over humidity (index 1-5)
             humidity_raw = ams_unit.get("humidity_raw")
@@ -1058,13 +1418,13 @@ class PrinterConnection:
             # Get extruder from our persisted map (only for dual-nozzle printers)
             extruder = self._ams_extruder_map.get(unit_id) if self._nozzle_count_detected else None
 
-            # Parse trays
-            trays = []
+            # Merge trays by ID
+            trays = {tray.tray_id: tray for tray in current.trays} if current else {}
             for tray_data in ams_unit.get("tray", []):
                 tray_id = self._safe_int(tray_data.get("id"), 0)
-                tray = self._parse_tray(tray_data, unit_id, tray_id)
+                tray = self._merge_tray(trays.get(tray_id), tray_data, unit_id, tray_id)
                 if tray:
-                    trays.append(tray)
+                    trays[tray_id] = tray
 
                     # Check for spool insertion (tray_type was empty, now has value)
                     key = (unit_id, tray_id)
@@ -1081,22 +1441,40 @@ class PrinterConnection:
                     if was_empty and is_occupied and key in self._pending_assignments:
                         trays_to_check.append((unit_id, tray_id))
 
-            units.append(
-                AmsUnit(
-                    id=unit_id,
-                    humidity=humidity,
-                    temperature=temp,
-                    extruder=extruder,
-                    trays=trays,
-                )
+            units[unit_id] = AmsUnit(
+                id=unit_id,
+                humidity=humidity if humidity is not None else (current.humidity if current else None),
+                temperature=temp if temp is not None else (current.temperature if current else None),
+                extruder=extruder if extruder is not None else (current.extruder if current else None),
+                trays=[trays[tray_id] for tray_id in sorted(trays)],
             )
 
-        self._state.ams_units = units
+        # ams_exist_bits lists the regular AMS units still attached (bit n = unit n)
+        exist_bits = ams_data.get("ams_exist_bits")
+        if exist_bits is not None:
+            try:
+                exist = int(exist_bits, 16) if isinstance(exist_bits, str) else int(exist_bits)
+                units = {uid: unit for uid, unit in units.items() if uid >= 4 or exist & (1 << uid)}
+            except (ValueError, TypeError):
+                pass
+
+        self._state.ams_units = [units[unit_id] for unit_id in sorted(units)]
 
         # Execute any pending assignments (after state is updated)
         for ams_id, tray_id in trays_to_check:
             self._execute_pending_assignment(ams_id, tray_id)
 
+    def _merge_tray(self, current: AmsTray | None, tray_data: dict, ams_id: int, tray_id: int) -> AmsTray | None:
+        """Merge reported tray fields into the last known tray.
+
+        Fields missing from the report (or null) are unchanged. A tray reported
+        with nothing but its ID (and state) is empty.
+        """
+        tray = self._parse_tray(tray_data, ams_id, tray_id)
+        if tray is None or current is None or set(tray_data) <= EMPTY_TRAY_KEYS:
+            return tray or current
+        return current.model_copy(update=tray.model_dump(exclude_none=True))
+
     def _parse_tray(self, tray_data: dict, ams_id: int, tray_id: int) -> AmsTray | None:
         """Parse single tray data."""
         if not tray_data:
@@ -1164,17 +1542,30 @@ class PrinterConnection:
 class PrinterManager:
     """Manages multiple printer connections."""
 
-    def __init__(self):
+    def __init__(self, connection_class: type[PrinterConnection] = PrinterConnection):
+        # Demo mode connects through a simulated printer instead
+        self._connection_class = connection_class
         self._connections: dict[str, PrinterConnection] = {}
-        self._on_state_update: Callable[[str, PrinterState], None] | None = None
+        self._on_state_update: Callable[[str, PrinterState, list[str]], None] | None = None
         self._on_disconnect: Callable[[str], None] | None = None
         self._on_connect: Callable[[str], None] | None = None
         self._on_assignment_complete: Callable[[str, int, int, str, bool], None] | None = None
         self._on_tray_reading_change: Callable[[str, int | None, int], None] | None = None
         self._on_nozzle_count_update: Callable[[str, int], None] | None = None
-
-    def set_state_callback(self, callback: Callable[[str, PrinterState], None]):
-        """Set callback for printer state updates."""
+        self._on_nozzle_change: Callable[[str, list[NozzleInfo]], None] | None = None
+        self._on_version: Callable[[str, list[FirmwareModule]], None] | None = None
+        self._on_command_result: Callable[[str, dict], None] | None = None
+        self._on_cert_pinned: Callable[[str, str], None] | None = None
+        self._cert_mismatches: dict[str, str] = {}  # serial -> refused fingerprint, for printers that never connected
+        self._on_auth_failed: Callable[[str], None] | None = None
+        self._auth_failures: dict[str, str] = {}  # serial -> access code the printer refused
+
+    def set_state_callback(self, callback: Callable[[str, PrinterState, list[str]], None]):
+        """Set callback for printer state updates.
+
+        Callback receives: (serial, state, changed_fields). It is only called
+        when the state actually changed.
+        """
         self._on_state_update = callback
 
     def set_disconnect_callback(self, callback: Callable[[str], None]):
@@ -1217,18 +1608,83 @@ class PrinterManager:
         for conn in self._connections.values():
             conn._on_nozzle_count_update = callback
 
-    async def connect(self, serial: str, ip_address: str, access_code: str, name: str | None = None):
-        """Connect to a printer."""
+    def set_nozzle_change_callback(self, callback: Callable[[str, list[NozzleInfo]], None]):
+        """Set callback for when the installed nozzles change.
+
+        Callback receives: (serial, nozzles). Also called once after connecting,
+        when the printer first reports its nozzles.
+        """
+        self._on_nozzle_change = callback
+        # Also set on existing connections
+        for conn in self._connections.values():
+            conn._on_nozzle_change = callback
+
+    def set_version_callback(self, callback: Callable[[str, list[FirmwareModule]], None]):
+        """Set callback for when a printer reports its firmware versions.
+
+        Callback receives: (serial, modules). Called after every connect.
+        """
+        self._on_version = callback
+        # Also set on existing connections
+        for conn in self._connections.values():
+            conn._on_version = callback
+
+    def set_command_result_callback(self, callback: Callable[[str, dict], None]):
+        """Set callback for when a command is acknowledged, rejected or times out.
+
+        Callback receives: (serial, command) with the fields of CommandRecord.
+        """
+        self._on_command_result = callback
+        # Also set on existing connections
+        for conn in self._connections.values():
+            conn._on_command_result = callback
+
+    def set_cert_pinned_callback(self, callback: Callable[[str, str], None]):
+        """Set callback for when a printer's TLS certificate is pinned on first connect.
+
+        Callback receives: (serial, fingerprint). The fingerprint should be
+        stored and passed to connect() from then on.
+        """
+        self._on_cert_pinned = callback
+        for conn in self._connections.values():
+            conn._on_cert_pinned = callback
+
+    def set_auth_failed_callback(self, callback: Callable[[str], None]):
+        """Set callback for when a printer refuses its access code.
+
+        Callback receives: (serial). The printer is disconnected; see
+        access_code_refused() to hold off reconnecting with the same code.
+        """
+        self._on_auth_failed = callback
+
+    @traced("printer.connect")
+    async def connect(
+        self,
+        serial: str,
+        ip_address: str,
+        access_code: str,
+        name: str | None = None,
+        cert_fingerprint: str | None = None,
+    ):
+        """Connect to a printer.
+
+        cert_fingerprint is the printer's pinned TLS certificate; the
+        connection is refused if the printer presents another one. Without
+        it, the certificate seen now is pinned.
+        """
         if serial in self._connections:
             logger.warning(f"Printer {serial} already connected")
             return
 
-        conn = PrinterConnection(
+        conn = self._connection_class(
             serial=serial,
             ip_address=ip_address,
             access_code=access_code,
             name=name,
+            cert_fingerprint=cert_fingerprint,
         )
+        conn._on_cert_pinned = self._on_cert_pinned
+        conn._on_auth_failed = self._handle_auth_failed
 
         # Set assignment callback if configured
         if self._on_assignment_complete:
@@ -1242,16 +1698,52 @@ class PrinterManager:
         if self._on_nozzle_count_update:
             conn._on_nozzle_count_update = self._on_nozzle_count_update
 
+        # Set nozzle change callback if configured
+        if self._on_nozzle_change:
+            conn._on_nozzle_change = self._on_nozzle_change
+
+        # Set version callback if configured
+        if self._on_version:
+            conn._on_version = self._on_version
+
+        # Set command result callback if configured
+        if self._on_command_result:
+            conn._on_command_result = self._on_command_result
+
         try:
             conn.connect(self._handle_state_update, self._handle_disconnect, self._handle_connect)
             self._connections[serial] = conn
+            self._cert_mismatches.pop(serial, None)
         except Exception as e:
+            if conn.cert_mismatch:
+                self._cert_mismatches[serial] = conn.cert_mismatch
             logger.error(f"Failed to connect to {serial}: {e}")
             raise
 
+    def cert_mismatch(self, serial: str) -> str | None:
+        """Fingerprint of a certificate refused because it didn't match the pin, None if none was."""
+        conn = self._connections.get(serial)
+        if conn:
+            return conn.cert_mismatch
+        return self._cert_mismatches.get(serial)
+
+    def access_code_refused(self, serial: str, access_code: str | None) -> bool:
+        """Whether the printer refused this access code the last time it was tried."""
+        return serial in self._auth_failures and self._auth_failures[serial] == access_code
+
+    def _handle_auth_failed(self, serial: str):
+        """Handle a printer refusing its access code."""
+        # The connection has stopped itself; drop it so connect() can be called again with a new code
+        conn = self._connections.pop(serial, None)
+        if conn:
+            self._auth_failures[serial] = conn.access_code
+        if self._on_auth_failed:
+            self._on_auth_failed(serial)
+
     def _handle_connect(self, serial: str):
         """Handle printer connection."""
         logger.info(f"Printer {serial} connected successfully")
+        self._auth_failures.pop(serial, None)
         # Notify external callback
         if self._on_connect:
             self._on_connect(serial)
@@ -1271,6 +1763,7 @@ class PrinterManager:
         if self._on_disconnect:
             self._on_disconnect(serial)
 
+    @traced("printer.disconnect")
     async def disconnect(self, serial: str):
         """Disconnect from a printer."""
         if serial not in self._connections:
@@ -1318,6 +1811,7 @@ class PrinterManager:
             )
         return statuses
 
+    @traced("printer.set_filament")
     def set_filament(
         self,
         serial: str,
@@ -1349,6 +1843,7 @@ class PrinterManager:
             nozzle_temp_max=nozzle_temp_max,
         )
 
+    @traced("printer.reset_slot")
     def reset_slot(self, serial: str, ams_id: int, tray_id: int) -> bool:
         """Reset/clear an AMS slot on a printer."""
         conn = self._connections.get(serial)
@@ -1358,6 +1853,7 @@ class PrinterManager:
 
         return conn.reset_slot(ams_id=ams_id, tray_id=tray_id)
 
+    @traced("printer.set_calibration")
     def set_calibration(
         self,
         serial: str,
@@ -1383,6 +1879,7 @@ class PrinterManager:
             setting_id=setting_id,
         )
 
+    @traced("printer.set_k_value")
     def set_k_value(
         self,
         serial: str,
@@ -1404,6 +1901,36 @@ class PrinterManager:
             nozzle_temp=nozzle_temp,
         )
 
+    @traced("printer.set_speed_level")
+    def set_speed_level(self, serial: str, level: SpeedLevel) -> bool:
+        """Change the speed of a printer's running print."""
+        conn = self._connections.get(serial)
+        if not conn:
+            logger.error(f"Printer {serial} not connected")
+            return False
+
+        return conn.set_speed_level(level)
+
+    @traced("printer.skip_objects")
+    def skip_objects(self, serial: str, obj_ids: list[int]) -> bool:
+        """Skip objects of a printer's running print."""
+        conn = self._connections.get(serial)
+        if not conn:
+            logger.error(f"Printer {serial} not connected")
+            return False
+
+        return conn.skip_objects(obj_ids)
+
+    @traced("printer.control_print")
+    def control_print(self, serial: str, action: PrintAction) -> bool:
+        """Pause, resume or stop a printer's running print."""
+        conn = self._connections.get(serial)
+        if not conn:
+            logger.error(f"Printer {serial} not connected")
+            return False
+
+        return conn.control_print(action)
+
     def get_calibrations(self, serial: str) -> list[dict]:
         """Get calibration profiles for a printer (sync, returns cached)."""
         conn = self._connections.get(serial)
@@ -1412,6 +1939,7 @@ class PrinterManager:
 
         return conn.get_calibrations()
 
+    @traced("printer.get_kprofiles")
     async def get_kprofiles(self, serial: str, nozzle_diameter: str = "0.4") -> list[dict]:
         """Get K-profiles from printer (async with retry)."""
         conn = self._connections.get(serial)
@@ -1428,6 +1956,7 @@ class PrinterManager:
             return "0.4"  # Fallback
         return conn.get_nozzle_diameter(extruder_id)
 
+    @traced("printer.stage_assignment")
     def stage_assignment(
         self,
         serial: str,
@@ -1466,6 +1995,7 @@ class PrinterManager:
             nozzle_diameter=nozzle_diameter,
         )
 
+    @traced("printer.cancel_assignment")
     def cancel_assignment(self, serial: str, ams_id: int, tray_id: int) -> bool:
         """Cancel a pending assignment for an AMS slot."""
         conn = self._connections.get(serial)
@@ -1490,7 +2020,31 @@ class PrinterManager:
 
         return conn.get_all_pending_assignments()
 
-    def _handle_state_update(self, serial: str, state: PrinterState):
+    def get_command(self, serial: str, sequence_id: str) -> dict | None:
+        """Get a command sent to a printer and its outcome."""
+        conn = self._connections.get(serial)
+        if not conn:
+            return None
+
+        return conn.get_command(sequence_id)
+
+    def get_commands(self, serial: str) -> list[dict]:
+        """Get commands recently sent to a printer, newest first."""
+        conn = self._connections.get(serial)
+        if not conn:
+            return []
+
+        return conn.get_commands()
+
+    def get_last_command(self, serial: str, command: str) -> dict | None:
+        """Get the most recent command with the given name sent to a printer."""
+        conn = self._connections.get(serial)
+        if not conn:
+            return None
+
+        return conn.get_last_command(command)
+
+    def _handle_state_update(self, serial: str, state: PrinterState, changes: list[str]):
         """Handle state update from printer."""
         if self._on_state_update:
-            self._on_state_update(serial, state)
+            self._on_state_update(serial, state, changes)
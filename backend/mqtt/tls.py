@@ -0,0 +1,94 @@
+"""
+TLS for printer connections with trust-on-first-use certificate pinning.
+
+Bambu printers present self-signed certificates, so there is no CA to
+verify them against. Instead the SHA-256 fingerprint of the certificate
+seen on the first connection is pinned, and later connections are refused
+(before the access code is sent) if the printer presents a different one.
+A printer whose certificate legitimately changed is re-pinned through the
+API. Setting SPOOLBUDDY_PRINTER_TLS_INSECURE accepts any certificate.
+"""
+
+import hashlib
+import ssl
+from collections.abc import Callable
+
+
+class CertificateMismatch(ssl.SSLError):
+    """The printer presented a certificate other than the pinned one."""
+
+    def __init__(self, pinned: str, presented: str):
+        super().__init__(f"Printer certificate {presented} does not match the pinned {pinned}")
+        self.pinned = pinned
+        self.presented = presented
+
+
+def cert_fingerprint(der: bytes) -> str:
+    """SHA-256 fingerprint of a DER certificate as colon-separated hex ("AB:CD:...")."""
+    return ":".join(f"{b:02X}" for b in hashlib.sha256(der).digest())
+
+
+class _PinnedSSLSocket(ssl.SSLSocket):
+    def do_handshake(self, block=False):
+        super().do_handshake(block)
+        if isinstance(self.context, PinningContext):
+            self.context.check(self.getpeercert(binary_form=True))
+
+
+class PinningContext(ssl.SSLContext):
+    """Client context that checks the peer certificate against a pinned fingerprint after the handshake.
+
+    on_pin is called with the fingerprint when a certificate is pinned on
+    first use, on_mismatch with the presented fingerprint when a connection
+    is refused.
+    """
+
+    sslsocket_class = _PinnedSSLSocket
+
+    pinned: str | None = None
+    presented: str | None = None  # Fingerprint of the last certificate seen
+    insecure: bool = False
+    on_pin: Callable[[str], None] | None = None
+    on_mismatch: Callable[[str], None] | None = None
+
+    def check(self, der: bytes | None):
+        """Pin or verify a peer certificate, raising CertificateMismatch if it isn't the pinned one."""
+        if der is None:
+            raise ssl.SSLError("Printer presented no certificate")
+        fingerprint = cert_fingerprint(der)
+        self.presented = fingerprint
+        if self.insecure:
+            return
+        if self.pinned is None:
+            self.pinned = fingerprint
+            if self.on_pin:
+                self.on_pin(fingerprint)
+        elif fingerprint != self.pinned:
+            if self.on_mismatch:
+                self.on_mismatch(fingerprint)
+            raise CertificateMismatch(self.pinned, fingerprint)
+
+
+def printer_tls_context(
+    pinned: str | None,
+    insecure: bool = False,
+    on_pin: Callable[[str], None] | None = None,
+    on_mismatch: Callable[[str], None] | None = None,
+) -> PinningContext:
+    """TLS context for a printer's MQTT connection.
+
+    Args:
+        pinned: Fingerprint pinned earlier, None to pin the next certificate seen
+        insecure: Accept any certificate without pinning it
+        on_pin: Called with the fingerprint pinned on first use
+        on_mismatch: Called with the presented fingerprint when a connection is refused
+    """
+    context = PinningContext(ssl.PROTOCOL_TLS_CLIENT)
+    # Self-signed certificates; trust comes from the pinned fingerprint
+    context.check_hostname = False
+    context.verify_mode = ssl.CERT_NONE
+    context.pinned = pinned
+    context.insecure = insecure
+    context.on_pin = on_pin
+    context.on_mismatch = on_mismatch
+    return context
@@ -0,0 +1,100 @@
+"""
+Filament aging.
+
+Opened filament picks up moisture and goes brittle, so each material has a
+recommended shelf life once the seal is broken. Spools opened for longer are
+flagged as aging. The built-in shelf lives can be overridden per material
+with the "shelf_life_months" setting (a JSON object, e.g. {"PETG": 9}).
+"""
+
+import json
+import logging
+import time
+
+from pydantic import BaseModel
+
+logger = logging.getLogger(__name__)
+
+# Recommended months of use after opening, matched on the longest material prefix (PETG-CF -> PETG)
+SHELF_LIFE_MONTHS = {
+    "PLA": 24,
+    "PETG": 12,
+    "PCTG": 12,
+    "PET": 12,
+    "ABS": 24,
+    "ASA": 24,
+    "HIPS": 24,
+    "PC": 12,
+    "TPU": 12,
+    "PA": 6,  # Nylon
+    "PPA": 6,
+    "PVA": 6,
+    "BVOH": 6,
+}
+DEFAULT_SHELF_LIFE_MONTHS = 12
+
+SECONDS_PER_DAY = 86400
+
+
+class SpoolAging(BaseModel):
+    """How long a spool has been open against its material's shelf life."""
+
+    spool_id: str
+    material: str | None
+    manufactured_at: int | None
+    opened_at: int | None  # None = sealed
+    open_days: float | None = None  # Days since opened
+    shelf_life_months: int
+    expires_at: int | None = None  # When the shelf life runs out, epoch seconds
+    aging: bool = False  # Open for longer than the shelf life
+
+
+def _months_to_seconds(months: int) -> int:
+    return months * 365 * SECONDS_PER_DAY // 12
+
+
+def shelf_life_months(material: str | None, overrides: dict[str, int] | None = None) -> int:
+    """Recommended shelf life after opening for a material."""
+    table = {**SHELF_LIFE_MONTHS, **{key.upper(): value for key, value in (overrides or {}).items()}}
+    name = (material or "").upper()
+    matches = [key for key in table if name.startswith(key)]
+    return table[max(matches, key=len)] if matches else DEFAULT_SHELF_LIFE_MONTHS
+
+
+async def shelf_life_overrides(db) -> dict[str, int]:
+    """Per-material shelf lives from the "shelf_life_months" setting."""
+    value = await db.get_setting("shelf_life_months")
+    if not value:
+        return {}
+    try:
+        overrides = json.loads(value)
+        return {str(material): int(months) for material, months in overrides.items()}
+    except (ValueError, TypeError, AttributeError):
+        logger.warning(f"Ignoring invalid shelf_life_months setting: {value!r}")
+        return {}
+
+
+def spool_aging(spool, overrides: dict[str, int] | None = None, now: int | None = None) -> SpoolAging:
+    """Aging of a single spool."""
+    now = now or int(time.time())
+    months = shelf_life_months(spool.material, overrides)
+    result = SpoolAging(
+        spool_id=spool.id,
+        material=spool.material,
+        manufactured_at=spool.manufactured_at,
+        opened_at=spool.opened_at,
+        shelf_life_months=months,
+    )
+    if spool.opened_at is None:
+        return result
+
+    result.open_days = round(max(0, now - spool.opened_at) / SECONDS_PER_DAY, 1)
+    result.expires_at = spool.opened_at + _months_to_seconds(months)
+    result.aging = now > result.expires_at
+    return result
+
+
+async def spools_aging(db, spools: list, now: int | None = None) -> list[SpoolAging]:
+    """Aging of several spools."""
+    overrides = await shelf_life_overrides(db)
+    return [spool_aging(spool, overrides, now) for spool in spools]
@@ -0,0 +1,113 @@
+"""
+AMS slot change ledger.
+
+Compares consecutive printer states and turns tray transitions into events:
+spools physically inserted into or removed from a slot, a slot's filament
+changing, and filament being loaded into or unloaded from an extruder. The
+events are stored in the ams_events table so users can audit when a spool
+was in which slot.
+"""
+
+from pydantic import BaseModel
+
+EVENT_INSERTED = "inserted"  # Empty slot now holds a spool
+EVENT_REMOVED = "removed"  # Spool taken out of the slot
+EVENT_CHANGED = "changed"  # Slot reports different filament without being seen empty
+EVENT_LOADED = "loaded"  # Filament fed from the slot to an extruder
+EVENT_UNLOADED = "unloaded"  # Filament retracted from the extruder back into the slot
+
+NO_TRAY = 255  # tray_now value when nothing is loaded
+
+
+class AmsSlotEvent(BaseModel):
+    """A tray transition detected between two printer states."""
+
+    event: str
+    ams_id: int
+    tray_id: int
+    extruder: int | None = None  # For loaded/unloaded on dual-nozzle printers
+    tray_type: str | None = None
+    tray_color: str | None = None
+    tray_info_idx: str | None = None
+
+
+def tray_slot(tray_index: int | None, nozzle_count: int = 1) -> tuple[int, int] | None:
+    """(ams_id, tray_id) for a tray_now value, None if nothing is loaded.
+
+    0-15 are regular AMS trays, 16+ AMS-HT units. Single-nozzle printers
+    report the external spool as 254; dual-nozzle printers report external
+    holders as (ams_id << 8) | slot.
+    """
+    if tray_index is None or tray_index == NO_TRAY:
+        return None
+    if tray_index < 16:
+        return tray_index // 4, tray_index % 4
+    if tray_index < 254:
+        return 128 + tray_index - 16, 0
+    if tray_index == 254 and nozzle_count == 1:
+        return 255, 0  # External spool holder
+    if tray_index > 255:
+        return (tray_index >> 8) & 0xFF, tray_index & 0xFF
+    return None
+
+
+def _trays(state) -> dict:
+    """(ams_id, tray_id) -> tray for every known slot, including the external holder."""
+    trays = {(tray.ams_id, tray.tray_id): tray for unit in state.ams_units for tray in unit.trays}
+    if state.vt_tray:
+        trays[(255, 0)] = state.vt_tray
+    return trays
+
+
+def _filament(tray) -> tuple:
+    return (tray.tray_type or "", tray.tray_info_idx or "", (tray.tray_color or "").upper())
+
+
+def _slot_event(event: str, slot: tuple[int, int], tray=None, extruder: int | None = None) -> AmsSlotEvent:
+    return AmsSlotEvent(
+        event=event,
+        ams_id=slot[0],
+        tray_id=slot[1],
+        extruder=extruder,
+        tray_type=tray.tray_type if tray else None,
+        tray_color=tray.tray_color if tray else None,
+        tray_info_idx=tray.tray_info_idx if tray else None,
+    )
+
+
+def detect_ams_events(prev, state) -> list[AmsSlotEvent]:
+    """Tray transitions between two states of the same printer.
+
+    Slots missing from either state (e.g. a detached AMS unit) produce no
+    events, since nothing is known about what happened to them.
+    """
+    if prev is None:
+        return []
+
+    events = []
+    prev_trays = _trays(prev)
+    trays = _trays(state)
+    for slot in sorted(prev_trays.keys() & trays.keys()):
+        before, after = prev_trays[slot], trays[slot]
+        if not before.tray_type and after.tray_type:
+            events.append(_slot_event(EVENT_INSERTED, slot, after))
+        elif before.tray_type and not after.tray_type:
+            events.append(_slot_event(EVENT_REMOVED, slot, before))
+        elif after.tray_type and _filament(before) != _filament(after):
+            events.append(_slot_event(EVENT_CHANGED, slot, after))
+
+    # Dual-nozzle printers report the active tray per extruder
+    if state.nozzle_count >= 2:
+        active = [(0, prev.tray_now_right, state.tray_now_right), (1, prev.tray_now_left, state.tray_now_left)]
+    else:
+        active = [(None, prev.tray_now, state.tray_now)]
+    for extruder, before_index, after_index in active:
+        if after_index is None or before_index == after_index:
+            continue  # Not reported, or unchanged
+        before_slot = tray_slot(before_index, state.nozzle_count)
+        after_slot = tray_slot(after_index, state.nozzle_count)
+        if before_slot:
+            events.append(_slot_event(EVENT_UNLOADED, before_slot, trays.get(before_slot), extruder))
+        if after_slot:
+            events.append(_slot_event(EVENT_LOADED, after_slot, trays.get(after_slot), extruder))
+    return events
@@ -0,0 +1,186 @@
+"""
+Bambu Lab Printer Camera Client
+
+Reads JPEG frames from a printer's chamber camera over the LAN.
+A1 and P1 series printers serve frames on TLS port 6000 after an 80-byte
+auth packet. X1, H2 and P2S series only offer RTSPS on port 322, which is
+decoded with ffmpeg when it is installed.
+"""
+
+import asyncio
+import logging
+import shutil
+import ssl
+import struct
+import time
+from collections.abc import AsyncIterator
+
+logger = logging.getLogger(__name__)
+
+CAMERA_PORT = 6000
+RTSP_PORT = 322
+CAMERA_USERNAME = "bblp"
+
+# Models whose camera is only reachable over RTSPS
+RTSP_MODELS = {"X1", "X1-CARBON", "X1C", "X1E", "H2C", "H2D", "H2S", "P2S"}
+
+JPEG_SOI = b"\xff\xd8"
+JPEG_EOI = b"\xff\xd9"
+
+# Frame header of the port 6000 protocol: payload size, then 12 bytes we don't use
+FRAME_HEADER_SIZE = 16
+MAX_FRAME_SIZE = 4 * 1024 * 1024
+
+# Frame rate requested from ffmpeg for RTSPS cameras
+RTSP_FPS = 2
+
+CONNECT_TIMEOUT = 10.0
+
+# Snapshots are shared between requests for this long, so several viewers
+# polling the same printer only open one camera connection
+SNAPSHOT_CACHE_SECONDS = 2.0
+
+_snapshot_cache: dict[str, tuple[float, bytes]] = {}
+_snapshot_locks: dict[str, asyncio.Lock] = {}
+
+
+class CameraError(Exception):
+    """The camera could not be reached or sent no usable frame."""
+
+
+class CameraUnavailable(CameraError):
+    """The camera needs a decoder that is not installed."""
+
+
+def uses_rtsp(model: str | None) -> bool:
+    """Whether the printer model's camera is only reachable over RTSPS."""
+    return (model or "").upper().replace(" ", "-") in RTSP_MODELS
+
+
+def auth_packet(access_code: str, username: str = CAMERA_USERNAME) -> bytes:
+    """Build the 80-byte auth packet of the port 6000 camera protocol."""
+    header = struct.pack("<IIII", 0x40, 0x3000, 0, 0)
+    return header + username.encode().ljust(32, b"\0") + access_code.encode().ljust(32, b"\0")
+
+
+async def read_jpeg_frames(reader: asyncio.StreamReader) -> AsyncIterator[bytes]:
+    """Yield the JPEG frames of a port 6000 camera stream until it ends."""
+    while True:
+        try:
+            header = await reader.readexactly(FRAME_HEADER_SIZE)
+            size = int.from_bytes(header[:4], "little")
+            if not 0 < size <= MAX_FRAME_SIZE:
+                raise CameraError(f"Invalid camera frame size {size}")
+            payload = await reader.readexactly(size)
+        except asyncio.IncompleteReadError:
+            return
+        if not payload.startswith(JPEG_SOI):
+            raise CameraError("Camera frame is not a JPEG image")
+        yield payload
+
+
+def split_jpeg_stream(buffer: bytes) -> tuple[list[bytes], bytes]:
+    """Split concatenated JPEG images, returning complete frames and the unfinished rest."""
+    frames = []
+    while True:
+        start = buffer.find(JPEG_SOI)
+        if start < 0:
+            return frames, b""
+        end = buffer.find(JPEG_EOI, start + 2)
+        if end < 0:
+            return frames, buffer[start:]
+        frames.append(buffer[start : end + 2])
+        buffer = buffer[end + 2 :]
+
+
+def _ssl_context() -> ssl.SSLContext:
+    # Printers use a self-signed certificate
+    context = ssl.create_default_context()
+    context.check_hostname = False
+    context.verify_mode = ssl.CERT_NONE
+    return context
+
+
+async def _tcp_frames(ip_address: str, access_code: str) -> AsyncIterator[bytes]:
+    try:
+        reader, writer = await asyncio.wait_for(
+            asyncio.open_connection(ip_address, CAMERA_PORT, ssl=_ssl_context()),
+            CONNECT_TIMEOUT,
+        )
+    except (OSError, TimeoutError) as e:
+        raise CameraError(f"Camera unreachable: {e}") from e
+    try:
+        writer.write(auth_packet(access_code))
+        await writer.drain()
+        async for frame in read_jpeg_frames(reader):
+            yield frame
+    except OSError as e:
+        raise CameraError(f"Camera connection lost: {e}") from e
+    finally:
+        writer.close()
+
+
+async def _rtsp_frames(ip_address: str, access_code: str) -> AsyncIterator[bytes]:
+    ffmpeg = shutil.which("ffmpeg")
+    if not ffmpeg:
+        raise CameraUnavailable("ffmpeg is required for this printer's camera")
+    url = f"rtsps://{CAMERA_USERNAME}:{access_code}@{ip_address}:{RTSP_PORT}/streaming/live/1"
+    args = ["-hide_banner", "-loglevel", "error", "-rtsp_transport", "tcp", "-i", url]
+    args += ["-r", str(RTSP_FPS), "-f", "image2pipe", "-vcodec", "mjpeg", "-q:v", "5", "-"]
+    proc = await asyncio.create_subprocess_exec(
+        ffmpeg,
+        *args,
+        stdout=asyncio.subprocess.PIPE,
+        stderr=asyncio.subprocess.DEVNULL,
+    )
+    try:
+        buffer = b""
+        while chunk := await proc.stdout.read(65536):
+            frames, buffer = split_jpeg_stream(buffer + chunk)
+            for frame in frames:
+                yield frame
+            if len(buffer) > MAX_FRAME_SIZE:
+                raise CameraError("Camera frame too large")
+    finally:
+        if proc.returncode is None:
+            proc.kill()
+        await proc.wait()
+
+
+def camera_frames(ip_address: str, access_code: str, model: str | None) -> AsyncIterator[bytes]:
+    """Stream JPEG frames from the printer's chamber camera."""
+    if uses_rtsp(model):
+        return _rtsp_frames(ip_address, access_code)
+    return _tcp_frames(ip_address, access_code)
+
+
+async def first_frame(frames: AsyncIterator[bytes], timeout: float = CONNECT_TIMEOUT) -> bytes:
+    """Wait for the first frame of a camera stream.
+
+    Raises:
+        CameraError: If the camera is unreachable or sends no frame in time
+    """
+    try:
+        return await asyncio.wait_for(anext(frames), timeout)
+    except StopAsyncIteration:
+        raise CameraError("Camera closed the connection, check the access code") from None
+    except TimeoutError:
+        await frames.aclose()
+        raise CameraError("Camera sent no frame in time") from None
+
+
+async def snapshot(ip_address: str, access_code: str, model: str | None) -> bytes:
+    """Grab a single JPEG frame from the printer's chamber camera."""
+    lock = _snapshot_locks.setdefault(ip_address, asyncio.Lock())
+    async with lock:
+        cached = _snapshot_cache.get(ip_address)
+        if cached and time.monotonic() - cached[0] < SNAPSHOT_CACHE_SECONDS:
+            return cached[1]
+        frames = camera_frames(ip_address, access_code, model)
+        try:
+            frame = await first_frame(frames)
+        finally:
+            await frames.aclose()
+        _snapshot_cache[ip_address] = (time.monotonic(), frame)
+        logger.debug(f"Camera snapshot from {ip_address}: {len(frame)} bytes")
+        return frame
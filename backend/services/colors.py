@@ -0,0 +1,251 @@
+"""
+Color parsing, swatches and perceptual color matching.
+
+Spool colors arrive in several spellings ("#ff8800", "FF8800FF", "f80").
+They are parsed into numeric RGBA once here, so API clients and the
+firmware get ready-to-draw values instead of converting hex strings
+themselves. Similarity uses CIEDE2000 delta-E in CIELAB space: below ~2 is
+hard to tell apart, below ~10 reads as the same color family.
+"""
+
+import math
+import re
+from functools import lru_cache
+
+from pydantic import BaseModel
+
+_HEX = re.compile(r"[0-9A-F]{3}|[0-9A-F]{6}|[0-9A-F]{8}")
+
+# Basic color names for resolving spoken/typed names and naming a swatch's family
+BASIC_COLORS = {
+    "black": (0, 0, 0),
+    "white": (255, 255, 255),
+    "grey": (128, 128, 128),
+    "silver": (192, 192, 192),
+    "red": (220, 20, 20),
+    "orange": (255, 136, 0),
+    "yellow": (255, 220, 0),
+    "gold": (212, 175, 55),
+    "beige": (235, 220, 185),
+    "brown": (120, 72, 30),
+    "green": (0, 160, 60),
+    "olive": (110, 120, 40),
+    "teal": (0, 128, 128),
+    "cyan": (0, 200, 220),
+    "blue": (20, 60, 220),
+    "navy": (0, 0, 110),
+    "purple": (120, 40, 160),
+    "magenta": (220, 0, 180),
+    "pink": (255, 150, 190),
+}
+# Most color stops a multi-color spool can have
+MAX_COLOR_STOPS = 8
+COLOR_ALIASES = {"gray": "grey", "violet": "purple", "turquoise": "teal", "tan": "beige", "natural": "beige"}
+
+
+class ColorSwatch(BaseModel):
+    """A color in the forms UIs and the firmware draw with."""
+
+    hex: str  # "#RRGGBB" for CSS
+    rgba: str  # "RRGGBBAA", the AMS tray_color format
+    rgba_int: int  # Packed 0xRRGGBBAA, as the firmware stores colors
+    alpha: int  # 0-255, below 255 for translucent filament
+    hue: float  # HSL hue in degrees (0-360), 0 for greys
+    lightness: float  # HSL lightness in percent (0-100)
+    family: str  # Nearest basic color name ("red", "grey", ...)
+    text_color: str  # "#000000" or "#FFFFFF", whichever reads better on the swatch
+
+
+def parse_color(value: str | None) -> tuple[int, int, int, int] | None:
+    """(r, g, b, a) from "#RGB", "RRGGBB" or "RRGGBBAA" (with or without #), None if not a hex color."""
+    color = (value or "").strip().lstrip("#").upper()
+    if not _HEX.fullmatch(color):
+        return None
+    if len(color) == 3:
+        color = "".join(c * 2 for c in color)
+    if len(color) == 6:
+        color += "FF"
+    return tuple(int(color[i : i + 2], 16) for i in range(0, 8, 2))
+
+
+def hue_lightness(r: int, g: int, b: int) -> tuple[float, float]:
+    """HSL hue (degrees) and lightness (percent)."""
+    high, low = max(r, g, b) / 255, min(r, g, b) / 255
+    lightness = (high + low) / 2
+    delta = high - low
+    if delta == 0:
+        return 0.0, round(lightness * 100, 1)
+    rf, gf, bf = r / 255, g / 255, b / 255
+    if high == rf:
+        hue = ((gf - bf) / delta) % 6
+    elif high == gf:
+        hue = (bf - rf) / delta + 2
+    else:
+        hue = (rf - gf) / delta + 4
+    return round(hue * 60, 1), round(lightness * 100, 1)
+
+
+def to_lab(r: int, g: int, b: int) -> tuple[float, float, float]:
+    """CIELAB (D65) coordinates of an sRGB color."""
+
+    def linear(c: int) -> float:
+        c /= 255
+        return c / 12.92 if c <= 0.04045 else ((c + 0.055) / 1.055) ** 2.4
+
+    lr, lg, lb = linear(r), linear(g), linear(b)
+    x = (lr * 0.4124 + lg * 0.3576 + lb * 0.1805) / 0.95047
+    y = lr * 0.2126 + lg * 0.7152 + lb * 0.0722
+    z = (lr * 0.0193 + lg * 0.1192 + lb * 0.9505) / 1.08883
+
+    def f(t: float) -> float:
+        return t ** (1 / 3) if t > 0.008856 else 7.787 * t + 16 / 116
+
+    fx, fy, fz = f(x), f(y), f(z)
+    return 116 * fy - 16, 500 * (fx - fy), 200 * (fy - fz)
+
+
+def delta_e(lab1: tuple[float, float, float], lab2: tuple[float, float, float]) -> float:
+    """CIEDE2000 color difference between two CIELAB colors."""
+    l1, a1, b1 = lab1
+    l2, a2, b2 = lab2
+    c_bar = (math.hypot(a1, b1) + math.hypot(a2, b2)) / 2
+    g = 0.5 * (1 - math.sqrt(c_bar**7 / (c_bar**7 + 25**7)))
+    a1p, a2p = a1 * (1 + g), a2 * (1 + g)
+    c1p, c2p = math.hypot(a1p, b1), math.hypot(a2p, b2)
+    h1p = math.degrees(math.atan2(b1, a1p)) % 360
+    h2p = math.degrees(math.atan2(b2, a2p)) % 360
+
+    dl = l2 - l1
+    dc = c2p - c1p
+    if c1p * c2p == 0:
+        dh = 0.0
+    elif abs(h2p - h1p) <= 180:
+        dh = h2p - h1p
+    else:
+        dh = h2p - h1p - 360 if h2p > h1p else h2p - h1p + 360
+    dh_big = 2 * math.sqrt(c1p * c2p) * math.sin(math.radians(dh / 2))
+
+    l_bar = (l1 + l2) / 2
+    cp_bar = (c1p + c2p) / 2
+    if c1p * c2p == 0:
+        h_bar = h1p + h2p
+    elif abs(h1p - h2p) <= 180:
+        h_bar = (h1p + h2p) / 2
+    else:
+        h_bar = (h1p + h2p + 360) / 2 if h1p + h2p < 360 else (h1p + h2p - 360) / 2
+
+    t = (
+        1
+        - 0.17 * math.cos(math.radians(h_bar - 30))
+        + 0.24 * math.cos(math.radians(2 * h_bar))
+        + 0.32 * math.cos(math.radians(3 * h_bar + 6))
+        - 0.20 * math.cos(math.radians(4 * h_bar - 63))
+    )
+    s_l = 1 + 0.015 * (l_bar - 50) ** 2 / math.sqrt(20 + (l_bar - 50) ** 2)
+    s_c = 1 + 0.045 * cp_bar
+    s_h = 1 + 0.015 * cp_bar * t
+    r_t = (
+        -2
+        * math.sqrt(cp_bar**7 / (cp_bar**7 + 25**7))
+        * math.sin(math.radians(60 * math.exp(-(((h_bar - 275) / 25) ** 2))))
+    )
+    return math.sqrt(
+        (dl / s_l) ** 2 + (dc / s_c) ** 2 + (dh_big / s_h) ** 2 + r_t * (dc / s_c) * (dh_big / s_h)
+    )
+
+
+def color_distance(color1: str | None, color2: str | None) -> float | None:
+    """Delta-E between two hex colors (alpha ignored), None if either isn't a color."""
+    parsed1, parsed2 = parse_color(color1), parse_color(color2)
+    if parsed1 is None or parsed2 is None:
+        return None
+    return delta_e(to_lab(*parsed1[:3]), to_lab(*parsed2[:3]))
+
+
+_BASIC_LABS = {name: to_lab(*rgb) for name, rgb in BASIC_COLORS.items()}
+
+
+@lru_cache(maxsize=1024)
+def color_family(r: int, g: int, b: int) -> str:
+    """Nearest basic color name."""
+    lab = to_lab(r, g, b)
+    return min(_BASIC_LABS, key=lambda name: delta_e(lab, _BASIC_LABS[name]))
+
+
+def resolve_basic_color(name: str | None) -> str | None:
+    """RRGGBBAA hex for a basic color name ("red", "Gray"), None if unknown."""
+    key = (name or "").strip().lower()
+    rgb = BASIC_COLORS.get(COLOR_ALIASES.get(key, key))
+    return f"{rgb[0]:02X}{rgb[1]:02X}{rgb[2]:02X}FF" if rgb else None
+
+
+def stored_hue_lightness(value: str | None) -> tuple[float | None, float | None]:
+    """(hue, lightness) kept in the spools table for sorting and filtering, Nones if not a color."""
+    parsed = parse_color(value)
+    return hue_lightness(*parsed[:3]) if parsed else (None, None)
+
+
+def color_swatch(value: str | None) -> ColorSwatch | None:
+    """Swatch for a hex color, None if the value isn't one."""
+    parsed = parse_color(value)
+    if parsed is None:
+        return None
+    r, g, b, a = parsed
+    hue, lightness = hue_lightness(r, g, b)
+    # Relative luminance decides dark or light text
+    luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b
+    return ColorSwatch(
+        hex=f"#{r:02X}{g:02X}{b:02X}",
+        rgba=f"{r:02X}{g:02X}{b:02X}{a:02X}",
+        rgba_int=(r << 24) | (g << 16) | (b << 8) | a,
+        alpha=a,
+        hue=hue,
+        lightness=lightness,
+        family=color_family(r, g, b),
+        text_color="#000000" if luminance > 140 else "#FFFFFF",
+    )
+
+
+def color_gradient(stops: list[str] | None) -> str | None:
+    """CSS left-to-right linear-gradient through evenly spaced colors, None for fewer than 2 colors."""
+    parsed = [parse_color(stop) for stop in stops or []]
+    if len(parsed) < 2 or None in parsed:
+        return None
+    last = len(parsed) - 1
+    parts = [f"#{r:02X}{g:02X}{b:02X}{a:02X} {round(100 * i / last, 2):g}%" for i, (r, g, b, a) in enumerate(parsed)]
+    return f"linear-gradient(90deg, {', '.join(parts)})"
+
+
+class ResolvedColor(BaseModel):
+    """A color given as hex or by name."""
+
+    source: str  # "hex", "basic" (a basic color name) or "catalog" (a manufacturer's color name)
+    swatch: ColorSwatch
+    manufacturer: str | None = None  # Catalog entry the name matched
+    color_name: str | None = None
+
+
+async def resolve_color(
+    db, value: str, manufacturer: str | None = None, material: str | None = None
+) -> ResolvedColor | None:
+    """Resolve a hex color, a basic color name or a catalog color name ("Jade White").
+
+    With a manufacturer, its catalog colors win over basic names (Bambu Lab
+    "Silver" is not the basic silver).
+    """
+    swatch = color_swatch(value)
+    if swatch:
+        return ResolvedColor(source="hex", swatch=swatch)
+
+    basic = resolve_basic_color(value)
+    if basic and not manufacturer:
+        return ResolvedColor(source="basic", swatch=color_swatch(basic))
+    entry = await db.find_color_by_name(value, manufacturer, material)
+    swatch = color_swatch(entry["hex_color"]) if entry else None
+    if swatch:
+        return ResolvedColor(
+            source="catalog", swatch=swatch, manufacturer=entry["manufacturer"], color_name=entry["color_name"]
+        )
+    if basic:
+        return ResolvedColor(source="basic", swatch=color_swatch(basic))
+    return None
@@ -0,0 +1,403 @@
+"""
+Demo mode: seeded data and simulated printers.
+
+`python main.py --demo` runs the server on an in-memory database seeded with
+spools, printers and a few weeks of usage, and connects the printers through
+DemoPrinterConnection instead of MQTT. Each demo printer plays a scripted
+print loop (prepare, print with progress and layers, finish, idle) and its
+AMS remain goes down while printing, so usage tracking, the web UI and the
+device display can be tried without a Bambu printer.
+
+The simulated reports go through the same parsing as real MQTT reports, and
+commands sent to a demo printer are acknowledged like a printer would.
+"""
+
+import asyncio
+import json
+import logging
+import random
+import time
+from dataclasses import dataclass, field
+
+import paho.mqtt.client as mqtt
+from models import PrintAction, PrinterCreate, SpoolCreate
+from mqtt.client import PrinterConnection
+
+logger = logging.getLogger(__name__)
+
+DEMO_IP_ADDRESS = "127.0.0.1"
+DEMO_ACCESS_CODE = "12345678"
+DEMO_LABEL_WEIGHT = 1000
+DEMO_CORE_WEIGHT = 250
+
+# Seconds between simulated reports; a demo print takes about 100 of them
+DEMO_TICK_SEC = 2.0
+DEMO_IDLE_SEC = (20.0, 60.0)  # Pause between prints
+DEMO_MIN_REMAIN = 10  # Trays below this remain% aren't printed from
+
+# ams_filament_setting fields that change the tray
+FILAMENT_SETTING_KEYS = (
+    "tray_info_idx",
+    "tray_type",
+    "tray_sub_brands",
+    "tray_color",
+    "nozzle_temp_min",
+    "nozzle_temp_max",
+)
+
+
+@dataclass(frozen=True)
+class DemoFilament:
+    brand: str
+    material: str
+    subtype: str | None
+    color_name: str
+    rgba: str  # RRGGBBAA
+    tray_info_idx: str
+    nozzle_temp_min: int
+    nozzle_temp_max: int
+    remain: int  # % left at startup
+    price: float = 19.99
+
+    @property
+    def preset(self) -> str:
+        return " ".join(part for part in (self.brand, self.material, self.subtype) if part)
+
+
+@dataclass(frozen=True)
+class DemoPrinter:
+    serial: str
+    name: str
+    model: str
+    slots: tuple[DemoFilament, ...]  # AMS unit 0, trays in order
+
+
+DEMO_PRINTERS = (
+    DemoPrinter(
+        serial="00M00A000DEMO01",
+        name="X1 Carbon (demo)",
+        model="X1C",
+        slots=(
+            DemoFilament("Bambu", "PLA", "Basic", "Jade White", "FFFFFFFF", "GFA00", 190, 230, 82),
+            DemoFilament("Bambu", "PLA", "Basic", "Black", "000000FF", "GFA00", 190, 230, 45),
+            DemoFilament("Bambu", "PLA", "Matte", "Mandarin Orange", "F99963FF", "GFA01", 190, 230, 64),
+            DemoFilament("Bambu", "PETG", "HF", "Blue", "0A2CA5FF", "GFG02", 230, 260, 91, 22.99),
+        ),
+    ),
+    DemoPrinter(
+        serial="01P00A000DEMO02",
+        name="P1S (demo)",
+        model="P1S",
+        slots=(
+            DemoFilament("Polymaker", "PLA", "PolyTerra", "Cotton White", "E6DDDBFF", "GFL99", 190, 230, 37, 17.99),
+            DemoFilament("Bambu", "ABS", None, "Red", "D02727FF", "GFB00", 240, 270, 73),
+            DemoFilament("eSUN", "PETG", None, "Solid Grey", "8E9089FF", "GFG99", 220, 250, 58, 15.99),
+        ),
+    ),
+    DemoPrinter(
+        serial="03900A000DEMO03",
+        name="A1 (demo)",
+        model="A1",
+        slots=(
+            DemoFilament("Bambu", "PLA", "Silk", "Gold", "F4A925FF", "GFA05", 190, 230, 68, 24.99),
+            DemoFilament("Bambu", "PLA", "Basic", "Cyan", "0086D6FF", "GFA00", 190, 230, 26),
+            DemoFilament("Bambu", "PLA", "Basic", "Magenta", "EC008CFF", "GFA00", 190, 230, 88),
+            DemoFilament("Bambu", "PLA", "Basic", "Yellow", "F4EE2AFF", "GFA00", 190, 230, 15),
+        ),
+    ),
+)
+
+# Spools on the shelf, not loaded in any printer
+DEMO_STORAGE = (
+    DemoFilament("Bambu", "PLA", "Basic", "Jade White", "FFFFFFFF", "GFA00", 190, 230, 100),
+    DemoFilament("Bambu", "PLA", "Basic", "Black", "000000FF", "GFA00", 190, 230, 100),
+    DemoFilament("Bambu", "PETG", "HF", "Black", "000000FF", "GFG02", 230, 260, 100, 22.99),
+    DemoFilament("Bambu", "TPU", "95A HF", "White", "FFFFFFFF", "GFU01", 220, 240, 80, 41.99),
+    DemoFilament("Prusament", "PETG", None, "Galaxy Black", "3D3E3DFF", "GFG99", 230, 260, 54, 29.99),
+    DemoFilament("Sunlu", "PLA", "Matte", "Sakura Pink", "E8AFCFFF", "GFL99", 190, 230, 21, 13.99),
+    DemoFilament("Polymaker", "ASA", None, "Galaxy Grey", "5F6367FF", "GFB98", 240, 270, 100, 27.99),
+)
+
+# name, grams, layers, printer's time estimate in minutes
+DEMO_JOBS = (
+    ("Benchy", 12.5, 240, 42),
+    ("Cable clips x12", 18.0, 60, 35),
+    ("Filament swatch set", 24.0, 20, 48),
+    ("Planter - low poly", 86.0, 410, 215),
+    ("Raspberry Pi 5 case", 54.0, 180, 128),
+    ("Spool holder bracket", 41.5, 150, 96),
+    ("Headphone stand", 112.0, 520, 286),
+)
+
+
+async def seed_demo_data(db) -> bool:
+    """Create the demo printers and spools, with a few weeks of usage and scale readings.
+
+    Leaves a database that already has printers or spools alone; returns
+    whether anything was seeded.
+    """
+    if await db.get_printers() or await db.get_spools():
+        logger.info("Database isn't empty, demo data not seeded")
+        return False
+
+    rng = random.Random(42)  # Same demo data on every start
+    now = int(time.time())
+    for printer in DEMO_PRINTERS:
+        await db.create_printer(
+            PrinterCreate(
+                serial=printer.serial,
+                name=printer.name,
+                model=printer.model,
+                ip_address=DEMO_IP_ADDRESS,
+                access_code=DEMO_ACCESS_CODE,
+                auto_connect=True,
+            )
+        )
+        for tray_id, filament in enumerate(printer.slots):
+            spool = await _seed_spool(db, filament, rng, now, printer.serial, location=printer.name)
+            await db.assign_spool_to_slot(spool.id, printer.serial, 0, tray_id)
+    for filament in DEMO_STORAGE:
+        await _seed_spool(db, filament, rng, now, None, location="Shelf")
+
+    logger.info(f"Seeded demo data: {len(DEMO_PRINTERS)} printers, {len(await db.get_spools())} spools")
+    return True
+
+
+async def _seed_spool(
+    db, filament: DemoFilament, rng: random.Random, now: int, printer_serial: str | None, location: str
+):
+    """Create a spool and the prints that used it down to its remain%."""
+    full = DEMO_LABEL_WEIGHT + DEMO_CORE_WEIGHT
+    spool = await db.create_spool(
+        SpoolCreate(
+            material=filament.material,
+            subtype=filament.subtype,
+            color_name=filament.color_name,
+            rgba=filament.rgba,
+            brand=filament.brand,
+            label_weight=DEMO_LABEL_WEIGHT,
+            core_weight=DEMO_CORE_WEIGHT,
+            weight_new=full,
+            weight_current=full,
+            price=filament.price,
+            slicer_filament=filament.tray_info_idx,
+            slicer_filament_id=filament.tray_info_idx,
+            location=location,
+            nozzle_temp_min=filament.nozzle_temp_min,
+            nozzle_temp_max=filament.nozzle_temp_max,
+        )
+    )
+
+    # Weighed when it arrived, then printed from until it got down to its remain%
+    started = now - rng.randint(20, 45) * 86400
+    await db.record_weight(spool.id, full, "scale", recorded_at=started)
+    left = DEMO_LABEL_WEIGHT * (100 - filament.remain) / 100
+    weight = float(full)
+    timestamp = started
+    while left > 0:
+        name, grams, _layers, _minutes = rng.choice(DEMO_JOBS)
+        grams = min(left, round(grams * rng.uniform(0.8, 1.2), 1))
+        timestamp = min(now - 3600, timestamp + rng.randint(3, 30) * 3600)
+        await db.log_usage(spool.id, printer_serial or "manual", name, grams, timestamp=timestamp)
+        weight -= grams
+        left = round(left - grams, 1)
+        await db.record_weight(spool.id, round(weight), "usage", recorded_at=timestamp)
+
+    await db.set_spool_weight(spool.id, round(weight))
+    return spool
+
+
+class _DemoMessageInfo:
+    """What paho's publish() returns, for a message that was always sent."""
+
+    rc = mqtt.MQTT_ERR_SUCCESS
+
+
+class DemoClient:
+    """Stands in for the paho client: answers requests like a printer would."""
+
+    def __init__(self, connection: "DemoPrinterConnection"):
+        self._connection = connection
+
+    def subscribe(self, topic: str):
+        pass
+
+    def publish(self, topic: str, payload: str) -> _DemoMessageInfo:
+        self._connection._loop.call_soon(self._connection._handle_request, json.loads(payload))
+        return _DemoMessageInfo()
+
+
+@dataclass
+class DemoPrinterConnection(PrinterConnection):
+    """A simulated printer that plays a scripted print loop instead of connecting over MQTT."""
+
+    _trays: list[dict] = field(default_factory=list, repr=False)  # Tray reports of AMS unit 0
+    _paused: asyncio.Event | None = field(default=None, repr=False)  # Cleared while paused
+    _stopped: bool = field(default=False, repr=False)
+    _script: asyncio.Task | None = field(default=None, repr=False)
+
+    def connect(self, on_state_update, on_disconnect=None, on_connect=None):
+        """Start the simulation."""
+        self._on_state_update = on_state_update
+        self._on_disconnect_callback = on_disconnect
+        self._on_connect_callback = on_connect
+        self._loop = asyncio.get_event_loop()
+        self._paused = asyncio.Event()
+        self._paused.set()
+
+        printer = next((p for p in DEMO_PRINTERS if p.serial == self.serial), DEMO_PRINTERS[0])
+        self._trays = [_tray_report(tray_id, filament) for tray_id, filament in enumerate(printer.slots)]
+        self._client = DemoClient(self)
+        self._on_connect(self._client, None, None, 0, None)
+        self._script = self._loop.create_task(self._run_script())
+        logger.info(f"Simulating demo printer {self.serial}")
+
+    def disconnect(self):
+        """Stop the simulation."""
+        if self._script:
+            self._script.cancel()
+            self._script = None
+        self._connected = False
+        logger.info(f"Stopped demo printer {self.serial}")
+
+    def _report(self, print_data: dict):
+        """Feed a status report to the regular report parsing."""
+        self._handle_message({"print": print_data})
+
+    def _ams_report(self, tray_now: int | None = None, trays: list[dict] | None = None) -> dict:
+        report = {"ams": [{"id": "0", "humidity": "4", "humidity_raw": "23", "temp": "26.1", "tray": trays or []}]}
+        if tray_now is not None:
+            report["tray_now"] = str(tray_now)
+        return report
+
+    def _handle_request(self, payload: dict):
+        """Answer a request sent to the printer."""
+        section, body = next(iter(payload.items()))
+        command = body.get("command")
+        if section == "pushing":
+            self._report(
+                {
+                    "gcode_state": "IDLE",
+                    "stg_cur": -1,
+                    "mc_percent": 0,
+                    "nozzle_diameter": "0.4",
+                    "spd_lvl": 2,
+                    "ams": self._ams_report(255, self._trays),
+                }
+            )
+        elif section == "info":
+            self._handle_message(
+                {
+                    "info": {
+                        "command": command,
+                        "sequence_id": body["sequence_id"],
+                        "module": [
+                            {"name": "ota", "sw_ver": "01.08.02.00", "hw_ver": "OTA", "sn": self.serial},
+                            {"name": "mc", "sw_ver": "00.00.30.67", "hw_ver": "MC07", "sn": self.serial},
+                            {"name": "ams/0", "sw_ver": "00.00.06.49", "hw_ver": "AMS08", "sn": f"006{self.serial}"},
+                        ],
+                    }
+                }
+            )
+        elif section == "print":
+            response = {"command": command, "sequence_id": body["sequence_id"], "result": "success"}
+            if command == "extrusion_cali_get":
+                response.update(filaments=[], nozzle_diameter=body.get("nozzle_diameter"))
+            self._report(response)
+            self._apply_command(command, body)
+
+    def _apply_command(self, command: str, body: dict):
+        """Make an acknowledged command take effect."""
+        if command == PrintAction.PAUSE:
+            self._paused.clear()
+            self._report({"gcode_state": "PAUSE", "stg_cur": 16})
+        elif command == PrintAction.RESUME:
+            self._paused.set()
+            self._report({"gcode_state": "RUNNING", "stg_cur": 0})
+        elif command == PrintAction.STOP:
+            self._stopped = True
+            self._paused.set()
+        elif command == "print_speed":
+            self._report({"spd_lvl": body.get("param")})
+        elif command == "ams_filament_setting" and body.get("ams_id") == 0:
+            tray_id = body.get("tray_id", 0)
+            if 0 <= tray_id < len(self._trays):
+                self._trays[tray_id].update({key: body[key] for key in FILAMENT_SETTING_KEYS if key in body})
+                self._report({"ams": self._ams_report(trays=[self._trays[tray_id]])})
+
+    async def _run_script(self):
+        """Print, finish, idle, repeat."""
+        rng = random.Random(self.serial)
+        try:
+            await asyncio.sleep(rng.uniform(*DEMO_IDLE_SEC) / 4)  # Don't start every printer at once
+            while True:
+                trays = [i for i, tray in enumerate(self._trays) if tray["remain"] >= DEMO_MIN_REMAIN]
+                if trays:
+                    await self._print(rng.choice(DEMO_JOBS), rng.choice(trays))
+                await asyncio.sleep(rng.uniform(*DEMO_IDLE_SEC))
+        except asyncio.CancelledError:
+            pass
+        except Exception as e:
+            logger.error(f"Demo printer {self.serial} script failed: {e}")
+
+    async def _print(self, job: tuple, tray_id: int):
+        """One print job, from preparing to finished (or stopped)."""
+        name, grams, layers, minutes = job
+        tray = self._trays[tray_id]
+        self._stopped = False
+        self._report(
+            {
+                "gcode_state": "PREPARE",
+                "stg_cur": 2,
+                "subtask_name": name,
+                "gcode_file": "/data/Metadata/plate_1.gcode",
+                "mc_percent": 0,
+                "mc_remaining_time": minutes,
+                "layer_num": 0,
+                "total_layer_num": layers,
+                "print_error": 0,
+                "ams": self._ams_report(tray_id),
+            }
+        )
+        for stage in (1, 7):  # Bed leveling, heating the nozzle
+            await asyncio.sleep(DEMO_TICK_SEC * 2)
+            self._report({"stg_cur": stage})
+        await asyncio.sleep(DEMO_TICK_SEC)
+        self._report({"gcode_state": "RUNNING", "stg_cur": 0})
+
+        # remain% points used per percent of progress; the tray reports whole points
+        remain = float(tray["remain"])
+        per_percent = grams / DEMO_LABEL_WEIGHT
+        for percent in range(1, 101):
+            await asyncio.sleep(DEMO_TICK_SEC)
+            await self._paused.wait()
+            if self._stopped:
+                self._report({"gcode_state": "IDLE", "stg_cur": -1})
+                return
+            remain = max(0.0, remain - per_percent)
+            tray["remain"] = int(remain)
+            self._report(
+                {
+                    "mc_percent": percent,
+                    "layer_num": layers * percent // 100,
+                    "mc_remaining_time": round(minutes * (100 - percent) / 100),
+                    "ams": self._ams_report(tray_id, [{"id": tray["id"], "remain": tray["remain"]}]),
+                }
+            )
+
+        self._report({"gcode_state": "FINISH", "stg_cur": -1, "ams": self._ams_report(255)})
+        logger.info(f"Demo printer {self.serial} finished '{name}'")
+
+
+def _tray_report(tray_id: int, filament: DemoFilament) -> dict:
+    """AMS tray as a printer reports it."""
+    return {
+        "id": str(tray_id),
+        "tray_type": filament.material,
+        "tray_sub_brands": filament.preset,
+        "tray_color": filament.rgba,
+        "tray_info_idx": filament.tray_info_idx,
+        "nozzle_temp_min": str(filament.nozzle_temp_min),
+        "nozzle_temp_max": str(filament.nozzle_temp_max),
+        "remain": filament.remain,
+    }
@@ -0,0 +1,111 @@
+"""
+Server-sent events stream.
+
+Every UI broadcast is numbered and kept in a short replay buffer, so SSE
+clients that reconnect with Last-Event-ID receive what they missed. If the
+ID is no longer buffered (or from before a server restart) the client has to
+resync from a fresh initial_state.
+"""
+
+import asyncio
+import json
+import logging
+from collections import deque
+from dataclasses import dataclass, field
+
+logger = logging.getLogger(__name__)
+
+REPLAY_BUFFER_SIZE = 500
+SUBSCRIBER_QUEUE_SIZE = 1000
+
+
+@dataclass
+class StreamEvent:
+    """A numbered broadcast message."""
+
+    id: int
+    data: str  # JSON text
+
+    def encode(self) -> str:
+        """SSE wire format."""
+        return f"id: {self.id}\ndata: {self.data}\n\n"
+
+
+@dataclass(eq=False)
+class Subscription:
+    """One connected SSE client."""
+
+    queue: asyncio.Queue = field(default_factory=lambda: asyncio.Queue(maxsize=SUBSCRIBER_QUEUE_SIZE))
+    dropped: bool = False  # Fell too far behind, must reconnect and resync
+
+
+class EventStream:
+    """Fan-out of broadcast messages to SSE subscribers with a replay buffer."""
+
+    def __init__(self, buffer_size: int = REPLAY_BUFFER_SIZE):
+        self._buffer: deque[StreamEvent] = deque(maxlen=buffer_size)
+        self._subscribers: set[Subscription] = set()
+        self._last_id = 0
+
+    @property
+    def last_id(self) -> int:
+        return self._last_id
+
+    def publish(self, message: dict) -> StreamEvent:
+        """Number a message, buffer it and queue it for every subscriber."""
+        self._last_id += 1
+        event = StreamEvent(id=self._last_id, data=json.dumps(message))
+        self._buffer.append(event)
+
+        for sub in list(self._subscribers):
+            try:
+                sub.queue.put_nowait(event)
+            except asyncio.QueueFull:
+                logger.warning("SSE client fell behind, dropping it")
+                sub.dropped = True
+                self._subscribers.discard(sub)
+        return event
+
+    def replay_since(self, last_event_id: int) -> list[StreamEvent] | None:
+        """Buffered events after last_event_id, None if some are no longer available."""
+        if last_event_id > self._last_id:
+            return None  # ID from before a restart
+        missed = [e for e in self._buffer if e.id > last_event_id]
+        first_needed = last_event_id + 1
+        if first_needed <= self._last_id and (not missed or missed[0].id != first_needed):
+            return None
+        return missed
+
+    def subscribe(self) -> Subscription:
+        sub = Subscription()
+        self._subscribers.add(sub)
+        return sub
+
+    def unsubscribe(self, sub: Subscription):
+        self._subscribers.discard(sub)
+
+    def close(self):
+        """Drop every subscriber and wake it, so its stream ends (on shutdown)."""
+        for sub in list(self._subscribers):
+            sub.dropped = True
+            try:
+                sub.queue.put_nowait(None)
+            except asyncio.QueueFull:
+                pass  # Wakes up on the queued events
+        self._subscribers.clear()
+
+    @property
+    def subscriber_count(self) -> int:
+        return len(self._subscribers)
+
+
+# Singleton instance
+_stream: EventStream | None = None
+
+
+def get_event_stream() -> EventStream:
+    """Get the singleton event stream."""
+    global _stream
+    if _stream is None:
+        _stream = EventStream()
+    return _stream
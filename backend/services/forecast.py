@@ -0,0 +1,78 @@
+"""
+Spool depletion forecasts.
+
+Projects when a spool will run out from its consumption rate over a trailing
+window of usage history.
+"""
+
+import time
+
+from pydantic import BaseModel
+
+FORECAST_WINDOW_DAYS = 30
+DEPLETION_ALERT_DEFAULT_DAYS = 7
+
+SECONDS_PER_DAY = 86400
+
+
+class SpoolForecast(BaseModel):
+    """Estimated depletion of a spool at its recent consumption rate."""
+
+    spool_id: str
+    remaining_grams: float | None  # Net filament left, None if label weight unknown
+    daily_usage: float  # Grams per day over the forecast window
+    days_remaining: float | None = None  # None when there is no recent usage
+    depletion_date: int | None = None  # Epoch seconds
+
+
+def remaining_grams(spool) -> float | None:
+    """Net filament left on a spool (label weight minus consumption)."""
+    if not spool.label_weight:
+        return None
+    used = (spool.weight_used or 0) + (spool.consumed_since_weight or 0)
+    return max(0.0, spool.label_weight - used)
+
+
+def forecast(
+    spool,
+    consumed: float,
+    now: int | None = None,
+    window_days: int = FORECAST_WINDOW_DAYS,
+) -> SpoolForecast:
+    """Project depletion from the grams consumed in the last `window_days`.
+
+    The rate is spread over the part of the window the spool has existed for
+    (at least a day), so a spool added last week isn't diluted by three weeks
+    it wasn't in use.
+    """
+    now = now or int(time.time())
+    window_start = now - window_days * SECONDS_PER_DAY
+    added = spool.added_time or spool.created_at or window_start
+    elapsed_days = max(now - max(window_start, added), SECONDS_PER_DAY) / SECONDS_PER_DAY
+
+    remaining = remaining_grams(spool)
+    daily_usage = consumed / elapsed_days
+    result = SpoolForecast(spool_id=spool.id, remaining_grams=remaining, daily_usage=round(daily_usage, 2))
+    if remaining is None or daily_usage <= 0:
+        return result
+
+    days = remaining / daily_usage
+    result.days_remaining = round(days, 1)
+    result.depletion_date = now + int(days * SECONDS_PER_DAY)
+    return result
+
+
+async def forecast_spool(db, spool, now: int | None = None) -> SpoolForecast:
+    """Forecast a single spool from its usage history."""
+    now = now or int(time.time())
+    since = now - FORECAST_WINDOW_DAYS * SECONDS_PER_DAY
+    consumed = await db.get_consumption_since(since, spool_id=spool.id)
+    return forecast(spool, consumed.get(spool.id, 0.0), now)
+
+
+async def forecast_spools(db, spools: list, now: int | None = None) -> list[SpoolForecast]:
+    """Forecast several spools with a single usage query."""
+    now = now or int(time.time())
+    since = now - FORECAST_WINDOW_DAYS * SECONDS_PER_DAY
+    consumed = await db.get_consumption_since(since)
+    return [forecast(spool, consumed.get(spool.id, 0.0), now) for spool in spools]
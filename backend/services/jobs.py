@@ -0,0 +1,205 @@
+"""
+Background jobs for long-running tasks.
+
+Imports, backups and report generation run as asyncio tasks instead of
+inside the HTTP request that started them. Each job has a row in the jobs
+table with its status, progress and result, so clients poll
+/api/jobs/{id} (or follow the job_progress WebSocket messages) and a
+restart leaves a record of what was interrupted.
+
+Job kinds are registered with @job_kind by the modules that own the work.
+A job function receives a JobContext for reporting progress and the job's
+validated parameters, and returns a JSON-serializable result.
+"""
+
+import asyncio
+import logging
+import time
+import uuid
+from collections.abc import Awaitable, Callable
+from dataclasses import dataclass
+from enum import StrEnum
+from typing import Any
+
+from pydantic import BaseModel
+
+logger = logging.getLogger(__name__)
+
+MAX_CONCURRENT_JOBS = 2
+PROGRESS_INTERVAL_SECONDS = 0.5  # Progress is saved and broadcast at most this often
+
+
+class JobStatus(StrEnum):
+    QUEUED = "queued"
+    RUNNING = "running"
+    COMPLETED = "completed"
+    FAILED = "failed"
+    CANCELLED = "cancelled"
+
+
+FINISHED_STATUSES = (JobStatus.COMPLETED, JobStatus.FAILED, JobStatus.CANCELLED)
+
+
+class Job(BaseModel):
+    """A background job."""
+
+    id: str
+    kind: str
+    status: JobStatus
+    params: dict = {}
+    progress: int = 0  # Units done, e.g. colors imported
+    total: int | None = None  # Units to do, None if not known (yet)
+    message: str | None = None  # What the job is doing right now
+    result: Any = None  # Set when completed
+    error: str | None = None  # Set when failed
+    created_at: int | None = None
+    started_at: int | None = None
+    finished_at: int | None = None
+
+
+class UnknownJobKind(Exception):
+    """No job kind with this name is registered."""
+
+
+class JobContext:
+    """Handed to a running job function for reporting progress."""
+
+    def __init__(self, runner: "JobRunner", db, job_id: str):
+        self._runner = runner
+        self._db = db
+        self.job_id = job_id
+        self._last_update = 0.0
+
+    async def update(
+        self, progress: int | None = None, total: int | None = None, message: str | None = None, force: bool = False
+    ):
+        """Report progress. Updates within PROGRESS_INTERVAL_SECONDS of the last one are dropped unless forced."""
+        now = time.monotonic()
+        if not force and now - self._last_update < PROGRESS_INTERVAL_SECONDS:
+            return
+        self._last_update = now
+        fields = {"progress": progress, "total": total, "message": message}
+        await self._runner._update(self._db, self.job_id, **{k: v for k, v in fields.items() if v is not None})
+
+
+JobFunc = Callable[[JobContext, BaseModel | dict], Awaitable[Any]]
+
+
+@dataclass
+class JobKind:
+    func: JobFunc
+    params_model: type[BaseModel] | None = None
+
+
+_kinds: dict[str, JobKind] = {}
+
+
+def job_kind(name: str, params_model: type[BaseModel] | None = None):
+    """Register a job function under a kind name.
+
+    With a params model, a job's parameters are validated when it is
+    started and the function gets the model instance instead of a dict.
+    """
+
+    def decorate(func: JobFunc) -> JobFunc:
+        _kinds[name] = JobKind(func=func, params_model=params_model)
+        return func
+
+    return decorate
+
+
+def job_kinds() -> list[str]:
+    """Names of the registered job kinds."""
+    return sorted(_kinds)
+
+
+class JobRunner:
+    """Runs jobs as asyncio tasks, at most MAX_CONCURRENT_JOBS at a time."""
+
+    def __init__(self, max_concurrent: int = MAX_CONCURRENT_JOBS):
+        self._tasks: dict[str, asyncio.Task] = {}
+        self._slots = asyncio.Semaphore(max_concurrent)
+        self._on_update: Callable[[Job], Awaitable[None]] | None = None
+
+    def set_update_callback(self, callback: Callable[[Job], Awaitable[None]]):
+        """Set the callback for job status and progress changes (e.g. to broadcast them)."""
+        self._on_update = callback
+
+    async def start(self, db, kind: str, params: dict | None = None) -> Job:
+        """Queue a job. Raises UnknownJobKind, or pydantic's ValidationError for bad parameters."""
+        registered = _kinds.get(kind)
+        if registered is None:
+            raise UnknownJobKind(kind)
+        params = params or {}
+        arg = params
+        if registered.params_model:
+            arg = registered.params_model.model_validate(params)
+            params = arg.model_dump(mode="json")
+
+        job_id = uuid.uuid4().hex
+        job = Job(**await db.create_job(job_id, kind, params))
+        self._tasks[job_id] = asyncio.create_task(self._run(db, job_id, registered.func, arg))
+        await self._notify(job)
+        return job
+
+    async def _run(self, db, job_id: str, func: JobFunc, arg):
+        try:
+            async with self._slots:
+                await self._update(db, job_id, status=JobStatus.RUNNING, started_at=int(time.time()))
+                result = await func(JobContext(self, db, job_id), arg)
+            await self._update(db, job_id, status=JobStatus.COMPLETED, result=result, finished_at=int(time.time()))
+        except asyncio.CancelledError:
+            await self._update(db, job_id, status=JobStatus.CANCELLED, finished_at=int(time.time()))
+        except Exception as e:
+            logger.exception(f"Job {job_id} failed")
+            await self._update(db, job_id, status=JobStatus.FAILED, error=str(e), finished_at=int(time.time()))
+        finally:
+            self._tasks.pop(job_id, None)
+
+    async def _update(self, db, job_id: str, **fields):
+        row = await db.update_job(job_id, **fields)
+        if row:
+            await self._notify(Job(**row))
+
+    async def _notify(self, job: Job):
+        if self._on_update:
+            try:
+                await self._on_update(job)
+            except Exception as e:
+                logger.warning(f"Job update callback failed: {e}")
+
+    def is_active(self, job_id: str) -> bool:
+        """Whether a job is queued or running in this process."""
+        return job_id in self._tasks
+
+    async def cancel(self, job_id: str) -> bool:
+        """Cancel a queued or running job and wait for it to stop. False if it isn't active."""
+        task = self._tasks.get(job_id)
+        if task is None:
+            return False
+        task.cancel()
+        await asyncio.gather(task, return_exceptions=True)
+        return True
+
+    async def wait(self, job_id: str):
+        """Wait for an active job to finish."""
+        task = self._tasks.get(job_id)
+        if task:
+            await asyncio.gather(task, return_exceptions=True)
+
+    async def shutdown(self):
+        """Cancel all active jobs."""
+        for job_id in list(self._tasks):
+            await self.cancel(job_id)
+
+
+# Singleton instance
+_runner: JobRunner | None = None
+
+
+def get_job_runner() -> JobRunner:
+    """Get the singleton job runner."""
+    global _runner
+    if _runner is None:
+        _runner = JobRunner()
+    return _runner
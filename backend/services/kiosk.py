@@ -0,0 +1,220 @@
+"""
+Spool check-in/check-out kiosk.
+
+For makerspaces sharing filament: with kiosk mode on, a member scans their
+badge on the device, then puts the spool they take or bring back on the
+scale. A spool that isn't out is checked out to the member with its weight;
+a spool that is out is checked back in, and the filament used in between
+is logged as that member's usage. The member stays signed in for a while
+so several spools can be handled in one go; scanning the badge again signs
+them out.
+
+The device reports its state continuously, so a tag is handled once when it
+arrives and again only after it has left the scale.
+"""
+
+import logging
+import time
+
+from models import KioskState, KioskStatus
+from pydantic import BaseModel, Field
+
+logger = logging.getLogger(__name__)
+
+KIOSK_SETTING = "kiosk_config"
+
+
+class KioskConfig(BaseModel):
+    """Kiosk mode settings."""
+
+    enabled: bool = False
+    session_seconds: int = Field(default=60, ge=10, le=600)  # Member stays signed in this long after a scan
+
+
+class Member(BaseModel):
+    """A makerspace member."""
+
+    id: int
+    name: str
+    badge_id: str | None = None  # Badge tag ID
+    note: str | None = None
+    active: bool = True
+    open_checkouts: int = 0  # Spools the member has out
+    created_at: int | None = None
+
+
+class Checkout(BaseModel):
+    """A spool checked out at the kiosk."""
+
+    id: int
+    spool_id: str
+    spool_number: int | None = None
+    brand: str | None = None
+    material: str | None = None
+    color_name: str | None = None
+    member_id: int | None = None
+    member_name: str | None = None
+    weight_out: float | None = None  # Gross scale reading when checked out
+    checked_out_at: int
+    checked_in_by: int | None = None
+    weight_in: float | None = None
+    weight_used: float | None = None  # Filament used while out, None if not weighed both times
+    checked_in_at: int | None = None  # None while the spool is out
+
+
+async def get_kiosk_config(db) -> KioskConfig:
+    """Configured kiosk mode."""
+    value = await db.get_setting(KIOSK_SETTING)
+    return KioskConfig.model_validate_json(value) if value else KioskConfig()
+
+
+def spool_label(spool) -> str:
+    """Short spool name for the kiosk screen, e.g. "#12 Bambu Lab PLA Black"."""
+    name = " ".join(p for p in (spool.brand, spool.material, spool.color_name) if p)
+    return f"#{spool.spool_number} {name}" if spool.spool_number is not None else name
+
+
+async def check_in(db, spool_id: str, checkout: dict, weight: float | None, member_id: int | None) -> dict:
+    """Close a checkout, logging the filament used against the member who took the spool.
+
+    Without a weight (or one taken at checkout) the usage can't be worked out
+    and the checkout is closed without it.
+    """
+    used = None
+    if weight is not None and checkout["weight_out"] is not None:
+        used = round(max(0.0, checkout["weight_out"] - weight), 1)
+    checkout = await db.check_in_spool(checkout["id"], member_id, weight, used)
+    if used:
+        await db.log_usage(
+            spool_id=spool_id,
+            printer_serial="kiosk",
+            print_name=f"Checked out by {checkout['member_name'] or 'former member'}",
+            weight_used=used,
+            member_id=checkout["member_id"],
+        )
+    if weight is not None:
+        await db.set_spool_weight(spool_id, round(weight))
+    logger.info(f"Kiosk: spool {spool_id} checked in by member {member_id}, {used} g used")
+    return checkout
+
+
+class Kiosk:
+    """Check-in/check-out flow driven by the tags read on the device."""
+
+    def __init__(self):
+        self._member: dict | None = None  # Signed-in member
+        self._signed_in_until = 0.0
+        self._handled_tag: str | None = None  # Tag already handled, until it leaves the scale
+        self._status = KioskStatus()
+
+    def _sign_out(self):
+        self._member = None
+        self._status = KioskStatus()
+
+    def _signed_in(self, now: float, config: KioskConfig, **status) -> KioskStatus:
+        self._signed_in_until = now + config.session_seconds
+        return KioskStatus(member_name=self._member["name"], **status)
+
+    def status(self, now: float | None = None) -> KioskStatus:
+        """What the kiosk screen shows right now."""
+        now = time.monotonic() if now is None else now
+        if self._member and now >= self._signed_in_until:
+            self._sign_out()
+        if not self._member:
+            return self._status
+        return self._status.model_copy(update={"expires_in": max(0, round(self._signed_in_until - now))})
+
+    async def handle_tag(
+        self,
+        db,
+        config: KioskConfig,
+        tag_id: str | None,
+        weight: float | None,
+        stable: bool | None,
+        now: float | None = None,
+    ) -> tuple[KioskStatus, dict | None]:
+        """Advance the flow for the tag on the device.
+
+        Returns the status to show and the checkout opened or closed by this
+        tag, if any.
+        """
+        now = time.monotonic() if now is None else now
+        self.status(now)
+        if not tag_id:
+            # Tag gone: the result of the last spool gives way to the next step
+            self._handled_tag = None
+            if self._member and self._status.state != KioskState.SCAN_SPOOL:
+                self._status = KioskStatus(
+                    state=KioskState.SCAN_SPOOL, member_name=self._member["name"], message="Scan a spool"
+                )
+            return self.status(now), None
+        if tag_id == self._handled_tag:
+            return self.status(now), None
+
+        member = await db.get_member_by_badge(tag_id)
+        if member:
+            self._handled_tag = tag_id
+            if self._member and self._member["id"] == member["id"]:
+                self._sign_out()
+                self._status = KioskStatus(message=f"Bye {member['name']}")
+            elif not member["active"]:
+                self._sign_out()
+                self._status = KioskStatus(message="Badge is not active")
+            else:
+                self._member = member
+                self._status = self._signed_in(
+                    now, config, state=KioskState.SCAN_SPOOL, message=f"Hi {member['name']}, scan a spool"
+                )
+            return self.status(now), None
+
+        spool = await db.get_spool_by_tag(tag_id)
+        if not spool:
+            self._handled_tag = tag_id
+            self._status = KioskStatus(
+                state=KioskState.UNKNOWN_TAG,
+                member_name=self._member["name"] if self._member else None,
+                message="Unknown tag",
+            )
+            return self.status(now), None
+        if not self._member:
+            self._handled_tag = tag_id
+            self._status = KioskStatus(spool_name=spool_label(spool), message="Scan your badge first")
+            return self.status(now), None
+        if weight is not None and not stable:
+            # Wait for a settled reading, the weight is what usage is worked out from
+            return self._status.model_copy(update={"spool_name": spool_label(spool), "message": "Weighing..."}), None
+
+        self._handled_tag = tag_id
+        open_checkout = await db.get_open_checkout(spool.id)
+        if open_checkout:
+            checkout = await check_in(db, spool.id, open_checkout, weight, self._member["id"])
+            used = checkout["weight_used"]
+            message = f"Checked in, {used:.0f} g used" if used is not None else "Checked in"
+            self._status = self._signed_in(
+                now,
+                config,
+                state=KioskState.CHECKED_IN,
+                spool_name=spool_label(spool),
+                weight_used=used,
+                message=message,
+            )
+        else:
+            checkout = await db.check_out_spool(spool.id, self._member["id"], weight)
+            logger.info(f"Kiosk: spool {spool.id} checked out to member {self._member['id']}")
+            self._status = self._signed_in(
+                now, config, state=KioskState.CHECKED_OUT, spool_name=spool_label(spool), message="Checked out"
+            )
+        return self.status(now), checkout
+
+
+
+# Singleton instance
+_kiosk: Kiosk | None = None
+
+
+def get_kiosk() -> Kiosk:
+    """Get the singleton kiosk."""
+    global _kiosk
+    if _kiosk is None:
+        _kiosk = Kiosk()
+    return _kiosk
@@ -0,0 +1,92 @@
+"""
+Database maintenance.
+
+Applies the data retention policies (config file settings plus the AMS
+history retention from the UI) and then runs ANALYZE/VACUUM.
+"""
+
+import logging
+import time
+
+from config import settings
+from pydantic import BaseModel
+
+logger = logging.getLogger(__name__)
+
+
+class RetentionPolicy(BaseModel):
+    """Days of history kept per table (0 = keep forever)."""
+
+    weight_history_days: int  # Older readings are thinned to one per spool per day
+    ams_sensor_history_days: int
+    webhook_delivery_days: int
+    crash_report_days: int
+    job_days: int
+
+
+class MaintenanceResult(BaseModel):
+    """Outcome of a maintenance run."""
+
+    started_at: int
+    duration_ms: int
+    deleted: dict[str, int]  # Rows removed per table
+    vacuumed: bool
+    size_before: int  # Database size in bytes
+    size_after: int
+
+
+_last_result: MaintenanceResult | None = None
+
+
+def retention_policy(ams_history_retention_days: int) -> RetentionPolicy:
+    """Current retention policy."""
+    return RetentionPolicy(
+        weight_history_days=settings.weight_history_retention_days,
+        ams_sensor_history_days=ams_history_retention_days,
+        webhook_delivery_days=settings.webhook_delivery_retention_days,
+        crash_report_days=settings.crash_report_retention_days,
+        job_days=settings.job_retention_days,
+    )
+
+
+def last_result() -> MaintenanceResult | None:
+    """Result of the most recent maintenance run since startup."""
+    return _last_result
+
+
+async def run_maintenance(db, policy: RetentionPolicy, vacuum: bool | None = None) -> MaintenanceResult:
+    """Delete expired history and optimize the database."""
+    global _last_result
+
+    start = time.monotonic()
+    started_at = int(time.time())
+    size_before = await db.get_database_size()
+
+    deleted = {}
+    if policy.weight_history_days > 0:
+        deleted["weight_history"] = await db.compact_weight_history(policy.weight_history_days)
+    if policy.ams_sensor_history_days > 0:
+        deleted["ams_sensor_history"] = await db.cleanup_ams_sensor_history(policy.ams_sensor_history_days)
+    if policy.webhook_delivery_days > 0:
+        deleted["webhook_deliveries"] = await db.prune_webhook_deliveries(policy.webhook_delivery_days)
+    if policy.crash_report_days > 0:
+        deleted["crash_reports"] = await db.prune_crash_reports(policy.crash_report_days)
+    if policy.job_days > 0:
+        deleted["jobs"] = await db.prune_jobs(policy.job_days)
+
+    vacuum = settings.maintenance_vacuum if vacuum is None else vacuum
+    await db.optimize(vacuum=vacuum)
+
+    _last_result = MaintenanceResult(
+        started_at=started_at,
+        duration_ms=int((time.monotonic() - start) * 1000),
+        deleted=deleted,
+        vacuumed=vacuum,
+        size_before=size_before,
+        size_after=await db.get_database_size(),
+    )
+    logger.info(
+        f"Database maintenance: deleted {deleted}, size {size_before} -> {_last_result.size_after} bytes "
+        f"in {_last_result.duration_ms}ms"
+    )
+    return _last_result
@@ -0,0 +1,270 @@
+"""
+Moonraker (Klipper) spool-selection agent.
+
+Keeps a websocket open to each configured Moonraker instance and follows:
+- the active spool (Moonraker's Spoolman integration), mirrored as the
+  printer's spool assignment in SpoolBuddy
+- print history, so finished jobs log their filament usage against the
+  active spool
+
+Moonraker's spool IDs are matched to SpoolBuddy spool numbers.
+"""
+
+import asyncio
+import json
+import logging
+import math
+from collections.abc import Callable
+from dataclasses import dataclass, field
+from urllib.parse import urlparse
+
+import websockets
+from config import APP_VERSION, GITHUB_REPO
+
+logger = logging.getLogger(__name__)
+
+# Klipper printers are keyed as "moonraker-<id>" wherever a printer serial is
+# stored (slot assignments, usage history)
+PRINTER_KEY_PREFIX = "moonraker-"
+
+# The active spool is stored like a Bambu external holder (single toolhead)
+ACTIVE_SPOOL_SLOT = (255, 0)
+
+DEFAULT_FILAMENT_DIAMETER = 1.75  # mm
+DEFAULT_DENSITY = 1.24  # g/cm³ (PLA)
+MATERIAL_DENSITY = {
+    "PLA": 1.24,
+    "PETG": 1.27,
+    "ABS": 1.04,
+    "ASA": 1.07,
+    "TPU": 1.21,
+    "PA": 1.14,
+    "PC": 1.20,
+    "PVA": 1.23,
+    "HIPS": 1.04,
+}
+
+RECONNECT_DELAY_MIN = 5.0
+RECONNECT_DELAY_MAX = 300.0
+
+# JSON-RPC request IDs
+_REQUEST_IDENTIFY = 1
+_REQUEST_ACTIVE_SPOOL = 2
+
+
+def printer_key(printer_id: int) -> str:
+    """Serial under which a Moonraker printer's assignments and usage are stored."""
+    return f"{PRINTER_KEY_PREFIX}{printer_id}"
+
+
+def websocket_url(url: str) -> str:
+    """Moonraker websocket endpoint for a base URL (http://host:7125 or just host)."""
+    parsed = urlparse(url if "://" in url else f"http://{url}")
+    scheme = "wss" if parsed.scheme in ("https", "wss") else "ws"
+    port = f":{parsed.port}" if parsed.port else ":7125"
+    return f"{scheme}://{parsed.hostname}{port}/websocket"
+
+
+def material_density(material: str | None) -> float:
+    """Typical density (g/cm³) for a material, e.g. "PLA Silk" -> PLA."""
+    if material:
+        base = material.upper().replace("-", " ").split()[0]
+        for name, density in MATERIAL_DENSITY.items():
+            if base.startswith(name):
+                return density
+    return DEFAULT_DENSITY
+
+
+def filament_grams(length_mm: float, material: str | None = None, metadata: dict | None = None) -> float:
+    """Convert extruded filament length to grams.
+
+    Prefers the slicer's own length/weight ratio from the gcode metadata and
+    falls back to a 1.75 mm filament of typical density for the material.
+    """
+    metadata = metadata or {}
+    total_mm = metadata.get("filament_total")
+    total_g = metadata.get("filament_weight_total")
+    if total_mm and total_g:
+        return length_mm * total_g / total_mm
+
+    radius_cm = DEFAULT_FILAMENT_DIAMETER / 20
+    volume_cm3 = math.pi * radius_cm**2 * length_mm / 10
+    return volume_cm3 * material_density(material)
+
+
+@dataclass
+class ActiveSpoolEvent:
+    """The active spool changed (None = no spool selected)."""
+
+    spool_id: int | None
+
+
+@dataclass
+class JobFinishedEvent:
+    """A print job ended (completed, cancelled or errored)."""
+
+    filename: str
+    status: str
+    filament_used: float  # mm extruded
+    metadata: dict = field(default_factory=dict)
+
+    @property
+    def print_name(self) -> str:
+        name = self.filename.rsplit("/", 1)[-1]
+        return name.removesuffix(".gcode")
+
+
+def parse_message(message: dict) -> ActiveSpoolEvent | JobFinishedEvent | None:
+    """Turn a Moonraker websocket message into an agent event, if relevant."""
+    if message.get("id") == _REQUEST_ACTIVE_SPOOL and isinstance(message.get("result"), dict):
+        return ActiveSpoolEvent(spool_id=message["result"].get("spool_id"))
+
+    method = message.get("method")
+    params = message.get("params") or [{}]
+    payload = params[0] if isinstance(params[0], dict) else {}
+
+    if method == "notify_active_spool_set":
+        return ActiveSpoolEvent(spool_id=payload.get("spool_id"))
+
+    if method == "notify_history_changed" and payload.get("action") == "finished":
+        job = payload.get("job") or {}
+        return JobFinishedEvent(
+            filename=job.get("filename") or "",
+            status=job.get("status") or "",
+            filament_used=float(job.get("filament_used") or 0),
+            metadata=job.get("metadata") or {},
+        )
+    return None
+
+
+class MoonrakerAgent:
+    """Websocket session with one Moonraker instance, reconnecting as needed."""
+
+    def __init__(self, printer: dict, on_event: Callable):
+        self.printer_id: int = printer["id"]
+        self.url: str = printer["url"]
+        self.api_key: str | None = printer.get("api_key")
+        self._on_event = on_event  # async (printer_id, event)
+        self.connected = False
+        self.active_spool_id: int | None = None
+        self.last_error: str | None = None
+
+    def _rpc(self, method: str, params: dict | None, request_id: int) -> str:
+        return json.dumps({"jsonrpc": "2.0", "method": method, "params": params or {}, "id": request_id})
+
+    async def run(self):
+        """Connect and follow events until cancelled."""
+        delay = RECONNECT_DELAY_MIN
+        while True:
+            try:
+                await self._session()
+                delay = RECONNECT_DELAY_MIN
+            except asyncio.CancelledError:
+                raise
+            except Exception as e:
+                self.last_error = str(e) or type(e).__name__
+                logger.warning(f"Moonraker {self.url}: {self.last_error}, retrying in {delay:.0f}s")
+            finally:
+                self.connected = False
+
+            await asyncio.sleep(delay)
+            delay = min(delay * 2, RECONNECT_DELAY_MAX)
+
+    async def _session(self):
+        headers = {"X-Api-Key": self.api_key} if self.api_key else None
+        async with websockets.connect(websocket_url(self.url), additional_headers=headers) as ws:
+            identify = {
+                "client_name": "SpoolBuddy",
+                "version": APP_VERSION,
+                "type": "agent",
+                "url": f"https://github.com/{GITHUB_REPO}",
+            }
+            if self.api_key:
+                identify["api_key"] = self.api_key
+            await ws.send(self._rpc("server.connection.identify", identify, _REQUEST_IDENTIFY))
+            await ws.send(self._rpc("server.spoolman.get_spool_id", None, _REQUEST_ACTIVE_SPOOL))
+
+            self.connected = True
+            self.last_error = None
+            logger.info(f"Connected to Moonraker at {self.url}")
+
+            async for raw in ws:
+                try:
+                    message = json.loads(raw)
+                except json.JSONDecodeError:
+                    continue
+
+                if message.get("error") and message.get("id") in (_REQUEST_IDENTIFY, _REQUEST_ACTIVE_SPOOL):
+                    # e.g. Spoolman not configured in Moonraker, history still works
+                    logger.info(f"Moonraker {self.url}: {message['error'].get('message')}")
+                    continue
+
+                event = parse_message(message)
+                if event is None:
+                    continue
+                if isinstance(event, ActiveSpoolEvent):
+                    self.active_spool_id = event.spool_id
+                try:
+                    await self._on_event(self.printer_id, event)
+                except Exception as e:
+                    logger.error(f"Error handling Moonraker event from {self.url}: {e}")
+
+        logger.info(f"Disconnected from Moonraker at {self.url}")
+
+
+class MoonrakerManager:
+    """Runs one agent per enabled Moonraker printer."""
+
+    def __init__(self):
+        self._agents: dict[int, tuple[MoonrakerAgent, asyncio.Task]] = {}
+        self._on_event: Callable | None = None
+
+    def set_event_callback(self, callback: Callable):
+        """Set callback for agent events.
+
+        Callback signature: async def on_event(printer_id, event)
+        """
+        self._on_event = callback
+
+    async def _dispatch(self, printer_id: int, event):
+        if self._on_event:
+            await self._on_event(printer_id, event)
+
+    def start(self, printer: dict):
+        """(Re)start the agent for a printer; disabled printers are only stopped."""
+        self.stop(printer["id"])
+        if not printer["enabled"]:
+            return
+        agent = MoonrakerAgent(printer, self._dispatch)
+        self._agents[printer["id"]] = (agent, asyncio.create_task(agent.run()))
+
+    def stop(self, printer_id: int):
+        """Stop the agent for a printer, if running."""
+        entry = self._agents.pop(printer_id, None)
+        if entry:
+            entry[1].cancel()
+
+    def stop_all(self):
+        """Stop all agents."""
+        for printer_id in list(self._agents):
+            self.stop(printer_id)
+
+    def status(self, printer_id: int) -> dict:
+        """Connection state of a printer's agent."""
+        entry = self._agents.get(printer_id)
+        if not entry:
+            return {"connected": False, "active_spool_id": None, "last_error": None}
+        agent = entry[0]
+        return {"connected": agent.connected, "active_spool_id": agent.active_spool_id, "last_error": agent.last_error}
+
+
+# Singleton instance
+_manager: MoonrakerManager | None = None
+
+
+def get_moonraker_manager() -> MoonrakerManager:
+    """Get the singleton Moonraker manager."""
+    global _manager
+    if _manager is None:
+        _manager = MoonrakerManager()
+    return _manager
@@ -0,0 +1,375 @@
+"""
+Embedded MQTT broker.
+
+A small MQTT 3.1.1 broker for local integrations (Node-RED, Home
+Assistant's MQTT integration, scripts) on networks without a broker of
+their own. It supports what event consumers need: subscriptions with
++ and # wildcards, retained messages, keepalive, last will and an
+optional username/password. Messages are delivered at QoS 0; QoS 1 and 2
+publishes from clients are acknowledged and delivered at QoS 0 as well.
+There are no persistent sessions.
+"""
+
+import asyncio
+import hmac
+import logging
+import struct
+import uuid
+from dataclasses import dataclass, field
+
+logger = logging.getLogger(__name__)
+
+# Packet types
+CONNECT = 1
+CONNACK = 2
+PUBLISH = 3
+PUBACK = 4
+PUBREC = 5
+PUBREL = 6
+PUBCOMP = 7
+SUBSCRIBE = 8
+SUBACK = 9
+UNSUBSCRIBE = 10
+UNSUBACK = 11
+PINGREQ = 12
+PINGRESP = 13
+DISCONNECT = 14
+
+# CONNACK return codes
+CONNACK_ACCEPTED = 0
+CONNACK_BAD_PROTOCOL = 1
+CONNACK_BAD_CREDENTIALS = 4
+
+SUBACK_FAILURE = 0x80
+MAX_PACKET_SIZE = 256 * 1024
+MAX_WRITE_BUFFER = 1024 * 1024  # A client this far behind is disconnected
+CONNECT_TIMEOUT = 10.0
+
+
+class ProtocolError(Exception):
+    """Malformed or unexpected packet; the connection is closed."""
+
+
+def encode_remaining_length(length: int) -> bytes:
+    """MQTT variable length encoding."""
+    out = bytearray()
+    while True:
+        byte = length % 128
+        length //= 128
+        out.append(byte | 0x80 if length else byte)
+        if not length:
+            return bytes(out)
+
+
+def encode_string(value: str) -> bytes:
+    data = value.encode()
+    return struct.pack("!H", len(data)) + data
+
+
+def encode_packet(packet_type: int, flags: int, body: bytes) -> bytes:
+    return bytes([packet_type << 4 | flags]) + encode_remaining_length(len(body)) + body
+
+
+def encode_publish(topic: str, payload: bytes, retain: bool = False) -> bytes:
+    """QoS 0 PUBLISH packet."""
+    return encode_packet(PUBLISH, 1 if retain else 0, encode_string(topic) + payload)
+
+
+def valid_topic_filter(topic_filter: str) -> bool:
+    """Whether a subscription filter is well formed (# last and alone in its level, + alone in its level)."""
+    if not topic_filter:
+        return False
+    levels = topic_filter.split("/")
+    for i, level in enumerate(levels):
+        if "#" in level and (level != "#" or i != len(levels) - 1):
+            return False
+        if "+" in level and level != "+":
+            return False
+    return True
+
+
+def topic_matches(topic_filter: str, topic: str) -> bool:
+    """Whether a topic matches a subscription filter with + and # wildcards."""
+    # Wildcards at the first level don't match $-topics ($SYS and the like)
+    if topic.startswith("$") and topic_filter[:1] in ("+", "#"):
+        return False
+    filter_levels = topic_filter.split("/")
+    topic_levels = topic.split("/")
+    for i, level in enumerate(filter_levels):
+        if level == "#":
+            return True
+        if i >= len(topic_levels):
+            return False
+        if level != "+" and level != topic_levels[i]:
+            return False
+    return len(filter_levels) == len(topic_levels)
+
+
+class _Reader:
+    """Reads the fields of a packet body."""
+
+    def __init__(self, data: bytes):
+        self.data = data
+        self.pos = 0
+
+    def remaining(self) -> int:
+        return len(self.data) - self.pos
+
+    def read(self, n: int) -> bytes:
+        if self.remaining() < n:
+            raise ProtocolError("Packet too short")
+        chunk = self.data[self.pos : self.pos + n]
+        self.pos += n
+        return chunk
+
+    def uint16(self) -> int:
+        return struct.unpack("!H", self.read(2))[0]
+
+    def bytes_field(self) -> bytes:
+        return self.read(self.uint16())
+
+    def string(self) -> str:
+        try:
+            return self.bytes_field().decode()
+        except UnicodeDecodeError as e:
+            raise ProtocolError("Invalid UTF-8 string") from e
+
+    def rest(self) -> bytes:
+        return self.read(self.remaining())
+
+
+@dataclass
+class Will:
+    topic: str
+    payload: bytes
+    retain: bool
+
+
+@dataclass(eq=False)
+class BrokerClient:
+    """A connected MQTT client."""
+
+    client_id: str
+    writer: asyncio.StreamWriter
+    subscriptions: set[str] = field(default_factory=set)
+    will: Will | None = None
+
+    def send(self, packet: bytes) -> bool:
+        """Queue a packet, False if the client is too far behind and was dropped."""
+        if self.writer.is_closing():
+            return False
+        if self.writer.transport.get_write_buffer_size() > MAX_WRITE_BUFFER:
+            logger.warning(f"MQTT client {self.client_id} fell behind, disconnecting it")
+            self.writer.close()
+            return False
+        self.writer.write(packet)
+        return True
+
+
+class MqttBroker:
+    """Asyncio MQTT 3.1.1 broker."""
+
+    def __init__(
+        self,
+        host: str = "0.0.0.0",  # nosec B104
+        port: int = 1883,
+        username: str | None = None,
+        password: str | None = None,
+        read_only_prefix: str | None = None,
+    ):
+        """
+        Args:
+            host: Address to listen on
+            port: Port to listen on
+            username: Required username, None to accept anonymous clients
+            password: Required password (with username)
+            read_only_prefix: Topics under this prefix are only published by the
+                server itself; client publishes to them are dropped
+        """
+        self.host = host
+        self.port = port
+        self._username = username
+        self._password = password
+        self._read_only_prefix = read_only_prefix
+        self._server: asyncio.Server | None = None
+        self._clients: dict[str, BrokerClient] = {}
+        self._retained: dict[str, bytes] = {}
+        self._connections: set[asyncio.Task] = set()
+
+    @property
+    def client_count(self) -> int:
+        return len(self._clients)
+
+    @property
+    def bound_port(self) -> int | None:
+        """Port actually listened on (differs from port when that is 0)."""
+        if not self._server or not self._server.sockets:
+            return None
+        return self._server.sockets[0].getsockname()[1]
+
+    async def start(self):
+        self._server = await asyncio.start_server(self._handle_connection, self.host, self.port)
+        logger.info(f"Embedded MQTT broker listening on {self.host}:{self.bound_port}")
+
+    async def stop(self):
+        if self._server:
+            self._server.close()
+        for client in list(self._clients.values()):
+            client.will = None  # Clean shutdown, not a lost connection
+        self._clients.clear()
+        for task in self._connections:
+            task.cancel()
+        await asyncio.gather(*self._connections, return_exceptions=True)
+        if self._server:
+            await self._server.wait_closed()
+            self._server = None
+
+    def publish(self, topic: str, payload: bytes | str, retain: bool = False):
+        """Deliver a message to the matching subscribers, keeping it for later ones if retained."""
+        if isinstance(payload, str):
+            payload = payload.encode()
+        if retain:
+            if payload:
+                self._retained[topic] = payload
+            else:
+                self._retained.pop(topic, None)  # Empty retained message clears it
+        packet = None
+        for client in list(self._clients.values()):
+            if any(topic_matches(f, topic) for f in client.subscriptions):
+                # Retain flag is only set for messages sent on subscribe
+                packet = packet or encode_publish(topic, payload)
+                client.send(packet)
+
+    def _is_read_only(self, topic: str) -> bool:
+        prefix = self._read_only_prefix
+        return bool(prefix) and (topic == prefix or topic.startswith(f"{prefix}/"))
+
+    async def _read_packet(self, reader: asyncio.StreamReader) -> tuple[int, int, bytes]:
+        header = (await reader.readexactly(1))[0]
+        length = 0
+        for shift in range(0, 28, 7):
+            byte = (await reader.readexactly(1))[0]
+            length |= (byte & 0x7F) << shift
+            if not byte & 0x80:
+                break
+        else:
+            raise ProtocolError("Invalid remaining length")
+        if length > MAX_PACKET_SIZE:
+            raise ProtocolError(f"Packet of {length} bytes is too large")
+        return header >> 4, header & 0x0F, await reader.readexactly(length)
+
+    async def _handle_connection(self, reader: asyncio.StreamReader, writer: asyncio.StreamWriter):
+        peer = writer.get_extra_info("peername")
+        task = asyncio.current_task()
+        self._connections.add(task)
+        client = None
+        try:
+            packet_type, _, body = await asyncio.wait_for(self._read_packet(reader), CONNECT_TIMEOUT)
+            if packet_type != CONNECT:
+                raise ProtocolError("Expected CONNECT")
+            client, keepalive = self._connect(body, writer)
+            if not client:
+                return
+            # Clients that stay silent for 1.5 keepalive periods are gone
+            timeout = keepalive * 1.5 if keepalive else None
+            while True:
+                packet_type, flags, body = await asyncio.wait_for(self._read_packet(reader), timeout)
+                if packet_type == DISCONNECT:
+                    client.will = None
+                    break
+                self._handle_packet(client, packet_type, flags, body)
+                await writer.drain()
+        except (asyncio.IncompleteReadError, asyncio.TimeoutError, ConnectionError):
+            pass
+        except asyncio.CancelledError:
+            pass  # Broker stopping; not re-raised, Python 3.11 streams log cancelled handlers as errors
+        except ProtocolError as e:
+            logger.info(f"MQTT client {peer} sent an invalid packet, disconnecting it: {e}")
+        finally:
+            if client and self._clients.get(client.client_id) is client:
+                del self._clients[client.client_id]
+                if client.will:
+                    self.publish(client.will.topic, client.will.payload, client.will.retain)
+            writer.close()
+            self._connections.discard(task)
+
+    def _connect(self, body: bytes, writer: asyncio.StreamWriter) -> tuple[BrokerClient | None, int]:
+        r = _Reader(body)
+        protocol = r.string()
+        level = r.read(1)[0]
+        if (protocol, level) not in (("MQTT", 4), ("MQIsdp", 3)):
+            writer.write(encode_packet(CONNACK, 0, bytes([0, CONNACK_BAD_PROTOCOL])))
+            return None, 0
+        flags = r.read(1)[0]
+        keepalive = r.uint16()
+        client_id = r.string() or f"auto-{uuid.uuid4().hex[:12]}"
+        will = None
+        if flags & 0x04:
+            will = Will(topic=r.string(), payload=r.bytes_field(), retain=bool(flags & 0x20))
+        username = r.string() if flags & 0x80 else None
+        password = r.bytes_field() if flags & 0x40 else None
+
+        if self._username is not None and not (
+            username is not None
+            and hmac.compare_digest(username.encode(), self._username.encode())
+            and hmac.compare_digest(password or b"", (self._password or "").encode())
+        ):
+            writer.write(encode_packet(CONNACK, 0, bytes([0, CONNACK_BAD_CREDENTIALS])))
+            return None, 0
+
+        # A client ID already connected is taken over by the new connection
+        if previous := self._clients.pop(client_id, None):
+            previous.will = None
+            previous.writer.close()
+        client = BrokerClient(client_id=client_id, writer=writer, will=will)
+        self._clients[client_id] = client
+        writer.write(encode_packet(CONNACK, 0, bytes([0, CONNACK_ACCEPTED])))
+        return client, keepalive
+
+    def _handle_packet(self, client: BrokerClient, packet_type: int, flags: int, body: bytes):
+        r = _Reader(body)
+        if packet_type == PUBLISH:
+            qos = (flags >> 1) & 0x03
+            topic = r.string()
+            packet_id = r.uint16() if qos else None
+            payload = r.rest()
+            if qos == 1:
+                client.send(encode_packet(PUBACK, 0, struct.pack("!H", packet_id)))
+            elif qos == 2:
+                client.send(encode_packet(PUBREC, 0, struct.pack("!H", packet_id)))
+            if "+" in topic or "#" in topic:
+                raise ProtocolError("Wildcard in publish topic")
+            if self._is_read_only(topic):
+                logger.debug(f"Dropped publish from {client.client_id} to read-only topic {topic}")
+                return
+            self.publish(topic, payload, retain=bool(flags & 0x01))
+        elif packet_type == PUBREL:
+            client.send(encode_packet(PUBCOMP, 0, r.read(2)))
+        elif packet_type == SUBSCRIBE:
+            packet_id = r.read(2)
+            granted = bytearray()
+            new_filters = []
+            while r.remaining():
+                topic_filter = r.string()
+                r.read(1)  # Requested QoS; everything is delivered at QoS 0
+                if valid_topic_filter(topic_filter):
+                    client.subscriptions.add(topic_filter)
+                    new_filters.append(topic_filter)
+                    granted.append(0)
+                else:
+                    granted.append(SUBACK_FAILURE)
+            client.send(encode_packet(SUBACK, 0, packet_id + bytes(granted)))
+            for topic, payload in list(self._retained.items()):
+                if any(topic_matches(f, topic) for f in new_filters):
+                    client.send(encode_publish(topic, payload, retain=True))
+        elif packet_type == UNSUBSCRIBE:
+            packet_id = r.read(2)
+            while r.remaining():
+                client.subscriptions.discard(r.string())
+            client.send(encode_packet(UNSUBACK, 0, packet_id))
+        elif packet_type == PINGREQ:
+            client.send(encode_packet(PINGRESP, 0, b""))
+        elif packet_type in (PUBACK, PUBREC, PUBCOMP):
+            pass  # Nothing is sent with QoS > 0
+        else:
+            raise ProtocolError(f"Unexpected packet type {packet_type}")
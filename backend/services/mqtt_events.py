@@ -0,0 +1,195 @@
+"""
+Printer, spool and device events over MQTT.
+
+Republishes the server's UI broadcasts on stable MQTT topics for local
+integrations (Node-RED, Home Assistant's generic MQTT integration,
+scripts). In "broker" mode the server runs its own embedded broker that
+consumers connect to; in "bridge" mode it publishes to an existing broker.
+
+Topics, under the configured prefix ("spoolbuddy" by default):
+
+    {prefix}/status                         online/offline (retained)
+    {prefix}/printer/{serial}/state         Printer state JSON (retained)
+    {prefix}/printer/{serial}/availability  online/offline (retained)
+    {prefix}/printer/{serial}/usage         Filament used by a finished print
+    {prefix}/printer/{serial}/runout        Filament ran out
+    {prefix}/spool/{spool_id}/status        Where a spool is right now (retained)
+    {prefix}/device/state                   Scale weight and tag on the scale (retained)
+    {prefix}/device/availability            online/offline (retained)
+    {prefix}/events/{type}                  Every broadcast, as sent to the UI
+"""
+
+import json
+import logging
+import uuid
+from dataclasses import dataclass
+from enum import StrEnum
+
+import paho.mqtt.client as mqtt
+from config import settings
+from services.mqtt_broker import MqttBroker
+
+logger = logging.getLogger(__name__)
+
+
+class MqttEventsMode(StrEnum):
+    OFF = "off"
+    BROKER = "broker"  # Embedded broker
+    BRIDGE = "bridge"  # Publish to an existing broker
+
+
+@dataclass
+class MqttMessage:
+    topic: str
+    payload: str
+    retain: bool = False
+
+
+def _availability(online: bool) -> str:
+    return "online" if online else "offline"
+
+
+def event_messages(message: dict, prefix: str) -> list[MqttMessage]:
+    """MQTT messages for a UI broadcast: its stable topics, then the raw event."""
+    kind = message.get("type")
+    if not kind:
+        return []
+    serial = message.get("serial")
+    messages = []
+    if kind == "printer_state" and serial:
+        messages.append(MqttMessage(f"{prefix}/printer/{serial}/state", json.dumps(message["state"]), retain=True))
+    elif kind in ("printer_connected", "printer_disconnected") and serial:
+        online = kind == "printer_connected"
+        messages.append(MqttMessage(f"{prefix}/printer/{serial}/availability", _availability(online), retain=True))
+    elif kind == "usage_logged" and serial:
+        messages.append(MqttMessage(f"{prefix}/printer/{serial}/usage", json.dumps(message)))
+    elif kind == "filament_runout" and serial:
+        messages.append(MqttMessage(f"{prefix}/printer/{serial}/runout", json.dumps(message)))
+    elif kind == "spool_status":
+        for spool_id, status in message.get("spools", {}).items():
+            messages.append(MqttMessage(f"{prefix}/spool/{spool_id}/status", json.dumps(status), retain=True))
+    elif kind == "device_state":
+        state = {k: v for k, v in message.items() if k != "type"}
+        messages.append(MqttMessage(f"{prefix}/device/state", json.dumps(state), retain=True))
+    elif kind in ("device_connected", "device_disconnected"):
+        online = kind == "device_connected"
+        messages.append(MqttMessage(f"{prefix}/device/availability", _availability(online), retain=True))
+    messages.append(MqttMessage(f"{prefix}/events/{kind}", json.dumps(message)))
+    return messages
+
+
+class _BrokerSink:
+    """Publishes on the embedded broker."""
+
+    def __init__(self, status_topic: str):
+        self._status_topic = status_topic
+        self._broker = MqttBroker(
+            host=settings.mqtt_broker_host,
+            port=settings.mqtt_broker_port,
+            username=settings.mqtt_username,
+            password=settings.mqtt_password,
+            read_only_prefix=settings.mqtt_topic_prefix,
+        )
+
+    async def start(self):
+        await self._broker.start()
+        self._broker.publish(self._status_topic, _availability(True), retain=True)
+
+    async def stop(self):
+        await self._broker.stop()
+
+    def publish(self, message: MqttMessage):
+        self._broker.publish(message.topic, message.payload, retain=message.retain)
+
+
+class _BridgeSink:
+    """Publishes to an existing broker, reconnecting in the background."""
+
+    def __init__(self, status_topic: str):
+        self._status_topic = status_topic
+        self._client = mqtt.Client(
+            client_id=f"spoolbuddy-{uuid.uuid4().hex[:8]}",
+            protocol=mqtt.MQTTv311,
+            callback_api_version=mqtt.CallbackAPIVersion.VERSION2,
+        )
+        if settings.mqtt_username:
+            self._client.username_pw_set(settings.mqtt_username, settings.mqtt_password)
+        if settings.mqtt_bridge_tls:
+            self._client.tls_set()
+        self._client.will_set(status_topic, _availability(False), retain=True)
+        self._client.on_connect = self._on_connect
+
+    def _on_connect(self, client, userdata, flags, reason_code, properties):
+        if reason_code == 0:
+            logger.info(f"MQTT events bridge connected to {settings.mqtt_bridge_host}")
+            client.publish(self._status_topic, _availability(True), retain=True)
+        else:
+            logger.warning(f"MQTT events bridge connection to {settings.mqtt_bridge_host} failed: {reason_code}")
+
+    async def start(self):
+        self._client.connect_async(settings.mqtt_bridge_host, settings.mqtt_bridge_port, keepalive=60)
+        self._client.loop_start()
+
+    async def stop(self):
+        self._client.publish(self._status_topic, _availability(False), retain=True)
+        self._client.disconnect()
+        self._client.loop_stop()
+
+    def publish(self, message: MqttMessage):
+        # Messages while disconnected are dropped; retained state is republished on the next change
+        self._client.publish(message.topic, message.payload, retain=message.retain)
+
+
+class MqttEventPublisher:
+    """Republishes UI broadcasts over MQTT, in the configured mode."""
+
+    def __init__(self):
+        self._sink: _BrokerSink | _BridgeSink | None = None
+
+    @property
+    def running(self) -> bool:
+        return self._sink is not None
+
+    async def start(self):
+        mode = MqttEventsMode(settings.mqtt_events)
+        if mode == MqttEventsMode.OFF or self._sink:
+            return
+        if mode == MqttEventsMode.BRIDGE and not settings.mqtt_bridge_host:
+            logger.error("MQTT events bridge mode needs mqtt_bridge_host, not publishing events")
+            return
+        status_topic = f"{settings.mqtt_topic_prefix}/status"
+        sink = _BrokerSink(status_topic) if mode == MqttEventsMode.BROKER else _BridgeSink(status_topic)
+        try:
+            await sink.start()
+        except Exception as e:
+            logger.error(f"Failed to start MQTT events ({mode}): {e}")
+            return
+        self._sink = sink
+        logger.info(f"Publishing events over MQTT ({mode}) under {settings.mqtt_topic_prefix}/")
+
+    async def stop(self):
+        sink, self._sink = self._sink, None
+        if sink:
+            await sink.stop()
+
+    def publish_event(self, message: dict):
+        """Publish a UI broadcast on its MQTT topics."""
+        if not self._sink:
+            return
+        try:
+            for mqtt_message in event_messages(message, settings.mqtt_topic_prefix):
+                self._sink.publish(mqtt_message)
+        except Exception as e:
+            logger.warning(f"Failed to publish {message.get('type')} over MQTT: {e}")
+
+
+# Singleton instance
+_publisher: MqttEventPublisher | None = None
+
+
+def get_mqtt_events() -> MqttEventPublisher:
+    """Get the singleton MQTT event publisher."""
+    global _publisher
+    if _publisher is None:
+        _publisher = MqttEventPublisher()
+    return _publisher
@@ -0,0 +1,215 @@
+"""
+Built-in notification channels (ntfy, Telegram, Discord).
+
+Each channel type is a Notifier subclass registered in NOTIFIER_TYPES, so
+adding a new service only requires a new subclass with a send() method.
+"""
+
+import asyncio
+import logging
+from abc import ABC, abstractmethod
+from typing import ClassVar
+
+import httpx
+from db import get_db
+
+logger = logging.getLogger(__name__)
+
+
+class NotifierError(Exception):
+    """Raised when a notification could not be delivered."""
+
+    pass
+
+
+class Notifier(ABC):
+    """Base class for notification channels."""
+
+    # Channel type identifier stored in the database
+    type: ClassVar[str]
+    # Config keys that must be present
+    required_config: ClassVar[tuple[str, ...]] = ()
+    # Config keys holding credentials, left out of config exports
+    secret_config: ClassVar[tuple[str, ...]] = ()
+
+    def __init__(self, config: dict):
+        self.config = config
+
+    @classmethod
+    def validate_config(cls, config: dict) -> None:
+        """Raise ValueError if required config keys are missing."""
+        missing = [key for key in cls.required_config if not config.get(key)]
+        if missing:
+            raise ValueError(f"Missing {cls.type} config: {', '.join(missing)}")
+
+    @abstractmethod
+    async def send(self, client: httpx.AsyncClient, title: str, message: str) -> None:
+        """Send a notification. Raises NotifierError on failure."""
+
+    @staticmethod
+    def _check_response(response: httpx.Response) -> None:
+        if not 200 <= response.status_code < 300:
+            raise NotifierError(f"HTTP {response.status_code}: {response.text[:200]}")
+
+
+class NtfyNotifier(Notifier):
+    """ntfy.sh (or self-hosted ntfy) topic."""
+
+    type = "ntfy"
+    required_config = ("topic",)
+    secret_config = ("token",)
+
+    async def send(self, client: httpx.AsyncClient, title: str, message: str) -> None:
+        server = (self.config.get("server") or "https://ntfy.sh").rstrip("/")
+        headers = {"Title": title, "Tags": "spool"}
+        if self.config.get("token"):
+            headers["Authorization"] = f"Bearer {self.config['token']}"
+        if self.config.get("priority"):
+            headers["Priority"] = str(self.config["priority"])
+
+        response = await client.post(f"{server}/{self.config['topic']}", content=message.encode(), headers=headers)
+        self._check_response(response)
+
+
+class TelegramNotifier(Notifier):
+    """Telegram bot message to a chat."""
+
+    type = "telegram"
+    required_config = ("bot_token", "chat_id")
+    secret_config = ("bot_token",)
+
+    async def send(self, client: httpx.AsyncClient, title: str, message: str) -> None:
+        url = f"https://api.telegram.org/bot{self.config['bot_token']}/sendMessage"
+        response = await client.post(url, json={"chat_id": self.config["chat_id"], "text": f"{title}\n{message}"})
+        self._check_response(response)
+
+
+class DiscordNotifier(Notifier):
+    """Discord channel webhook."""
+
+    type = "discord"
+    required_config = ("webhook_url",)
+    secret_config = ("webhook_url",)  # The URL is the credential
+
+    async def send(self, client: httpx.AsyncClient, title: str, message: str) -> None:
+        payload = {
+            "username": self.config.get("username") or "SpoolBuddy",
+            "embeds": [{"title": title, "description": message}],
+        }
+        response = await client.post(self.config["webhook_url"], json=payload)
+        self._check_response(response)
+
+
+NOTIFIER_TYPES: dict[str, type[Notifier]] = {
+    cls.type: cls for cls in (NtfyNotifier, TelegramNotifier, DiscordNotifier)
+}
+
+
+def create_notifier(channel_type: str, config: dict) -> Notifier:
+    """Instantiate a notifier for a channel type."""
+    notifier_cls = NOTIFIER_TYPES.get(channel_type)
+    if not notifier_cls:
+        raise ValueError(f"Unknown notifier type: {channel_type}")
+    notifier_cls.validate_config(config)
+    return notifier_cls(config)
+
+
+def format_event(event: str, data: dict) -> tuple[str, str]:
+    """Build a human-readable (title, message) for an event."""
+    if event == "spool.low":
+        spool = " ".join(str(v) for v in (data.get("brand"), data.get("material"), data.get("color_name")) if v)
+        return "Spool running low", f"{spool or 'Spool'} has {data.get('remaining_percent')}% remaining"
+    if event == "spool.depleting":
+        spool = " ".join(str(v) for v in (data.get("color_name"), data.get("material")) if v)
+        days = round(data.get("days_remaining") or 0)
+        when = "within a day" if days < 1 else f"in ~{days} day{'s' if days != 1 else ''}"
+        return "Spool running out", f"Your {spool or 'spool'} will run out {when} at current usage"
+    if event == "spool.aging":
+        spool = " ".join(str(v) for v in (data.get("color_name"), data.get("material")) if v)
+        months = round((data.get("open_days") or 0) * 12 / 365)
+        return (
+            "Spool past its shelf life",
+            f"Your {spool or 'spool'} has been open {months} months, "
+            f"{data.get('shelf_life_months')} are recommended. Dry it before printing.",
+        )
+    if event == "print.finished":
+        status = "finished" if data.get("success") else "failed"
+        return f"Print {status}", f"'{data.get('print_name') or 'Unknown'}' {status} on {data.get('serial')}"
+    if event == "printer.error":
+        message = f"Printer {data.get('serial')} reported {data.get('gcode_state') or 'an error'}"
+        if data.get("print_error"):
+            message += f" (error {data['print_error']})"
+        return "Printer error", message
+    if event == "tag.scanned":
+        tag_data = data.get("tag_data") or {}
+        spool = " ".join(str(v) for v in (tag_data.get("vendor"), tag_data.get("material")) if v)
+        return "Tag scanned", f"Tag {data.get('tag_id')} {spool}".strip()
+    if event == "filament.runout":
+        spool = " ".join(str(v) for v in (data.get("color_name"), data.get("material")) if v)
+        ams_id = data.get("ams_id")
+        slot = "external spool" if ams_id in (254, 255) else f"AMS {ams_id} tray {data.get('tray_id')}"
+        title = "Filament jam" if data.get("kind") == "jam" else "Filament runout"
+        message = f"{spool or 'Filament'} ({slot}) on {data.get('serial')}"
+        if data.get("print_name"):
+            message += f" during '{data['print_name']}'"
+        return title, message
+    if event == "printer.auth_failed":
+        printer = data.get("name") or data.get("serial")
+        return (
+            "Printer needs a new access code",
+            f"{printer} refused its access code. Enter the current code from the printer's network settings.",
+        )
+    if event == "printer.firmware_changed":
+        printer = data.get("name") or data.get("serial")
+        return (
+            "Printer firmware updated",
+            f"{printer} firmware changed from {data.get('old_version')} to {data.get('new_version')}.",
+        )
+    return event, str(data)
+
+
+class NotificationDispatcher:
+    """Sends events to subscribed notification channels."""
+
+    def __init__(self, timeout: float = 10.0):
+        self.timeout = timeout
+
+    async def dispatch(self, event: str, data: dict) -> None:
+        """Notify every enabled channel subscribed to the event."""
+        try:
+            db = await get_db()
+            channels = await db.get_notification_channels_for_event(event)
+        except Exception as e:
+            logger.warning(f"Failed to load notification channels for {event}: {e}")
+            return
+
+        if not channels:
+            return
+
+        title, message = format_event(event, data)
+        for channel in channels:
+            asyncio.create_task(self.send(channel, title, message))
+
+    async def send(self, channel: dict, title: str, message: str) -> str | None:
+        """Send to a single channel. Returns an error string, or None on success."""
+        try:
+            notifier = create_notifier(channel["type"], channel["config"])
+            async with httpx.AsyncClient(timeout=self.timeout) as client:
+                await notifier.send(client, title, message)
+            return None
+        except (ValueError, NotifierError, httpx.HTTPError) as e:
+            error = str(e) or type(e).__name__
+            logger.warning(f"Notification channel {channel['id']} ({channel['type']}) failed: {error}")
+            return error
+
+
+# Singleton instance
+_dispatcher: NotificationDispatcher | None = None
+
+
+def get_notification_dispatcher() -> NotificationDispatcher:
+    """Get the singleton notification dispatcher."""
+    global _dispatcher
+    if _dispatcher is None:
+        _dispatcher = NotificationDispatcher()
+    return _dispatcher
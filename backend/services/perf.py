@@ -0,0 +1,215 @@
+"""
+Request and query performance metrics.
+
+The HTTP middleware records latency and status codes per route (the route
+template, so /api/spools/{spool_id} is one entry for all spools). The
+database connection is wrapped in TimedConnection, which times every
+statement and logs the ones slower than slow_query_ms. Bound parameters are
+never logged, only how many there were.
+
+Metrics are kept in memory since startup and shown at /api/admin/perf.
+"""
+
+import logging
+import re
+import statistics
+import time
+from collections import deque
+from dataclasses import dataclass, field
+
+from config import settings
+from pydantic import BaseModel
+
+logger = logging.getLogger(__name__)
+
+LATENCY_SAMPLES = 500  # Recent durations per route, for percentiles
+SLOW_QUERY_LOG_SIZE = 50
+
+_WHITESPACE = re.compile(r"\s+")
+
+
+class RouteMetrics(BaseModel):
+    """Latency and status codes of one route."""
+
+    method: str
+    route: str
+    count: int
+    errors: int  # 5xx responses
+    statuses: dict[str, int]
+    total_ms: float
+    mean_ms: float
+    p50_ms: float  # Over the most recent requests
+    p95_ms: float
+    max_ms: float
+
+
+class SlowQuery(BaseModel):
+    """A statement that ran longer than the slow query threshold."""
+
+    at: int
+    duration_ms: float
+    sql: str
+    params: int  # Number of bound parameters, their values are elided
+
+
+class QueryMetrics(BaseModel):
+    """Totals over all database statements."""
+
+    count: int
+    total_ms: float
+    slow_count: int
+    slow_threshold_ms: float
+
+
+class PerfMetrics(BaseModel):
+    """Request and query metrics since startup (or the last reset)."""
+
+    since: int
+    routes: list[RouteMetrics]  # Slowest in total first
+    queries: QueryMetrics
+    slow_queries: list[SlowQuery]  # Most recent first
+
+
+@dataclass
+class _RouteStats:
+    count: int = 0
+    total_ms: float = 0.0
+    max_ms: float = 0.0
+    statuses: dict[int, int] = field(default_factory=dict)
+    samples: deque = field(default_factory=lambda: deque(maxlen=LATENCY_SAMPLES))
+
+
+def _percentile(samples: list[float], pct: int) -> float:
+    if len(samples) < 2:
+        return samples[0] if samples else 0.0
+    return statistics.quantiles(samples, n=100, method="inclusive")[pct - 1]
+
+
+class PerfStats:
+    """In-memory request and query metrics."""
+
+    def __init__(self):
+        self.reset()
+
+    def reset(self):
+        self._since = int(time.time())
+        self._routes: dict[tuple[str, str], _RouteStats] = {}
+        self._query_count = 0
+        self._query_total_ms = 0.0
+        self._slow_count = 0
+        self._slow_queries: deque[SlowQuery] = deque(maxlen=SLOW_QUERY_LOG_SIZE)
+
+    def record_request(self, method: str, route: str, status: int, duration_ms: float):
+        stats = self._routes.get((method, route))
+        if stats is None:
+            stats = self._routes[(method, route)] = _RouteStats()
+        stats.count += 1
+        stats.total_ms += duration_ms
+        stats.max_ms = max(stats.max_ms, duration_ms)
+        stats.statuses[status] = stats.statuses.get(status, 0) + 1
+        stats.samples.append(duration_ms)
+
+    def record_query(self, sql: str, params_count: int, duration_ms: float):
+        self._query_count += 1
+        self._query_total_ms += duration_ms
+        threshold = settings.slow_query_ms
+        if threshold <= 0 or duration_ms < threshold:
+            return
+        self._slow_count += 1
+        sql = _WHITESPACE.sub(" ", sql).strip()
+        self._slow_queries.append(
+            SlowQuery(at=int(time.time()), duration_ms=round(duration_ms, 1), sql=sql, params=params_count)
+        )
+        logger.warning(f"Slow query ({duration_ms:.0f} ms, {params_count} params elided): {sql}")
+
+    def snapshot(self) -> PerfMetrics:
+        routes = []
+        for (method, route), stats in self._routes.items():
+            samples = list(stats.samples)
+            routes.append(
+                RouteMetrics(
+                    method=method,
+                    route=route,
+                    count=stats.count,
+                    errors=sum(n for status, n in stats.statuses.items() if status >= 500),
+                    statuses={str(status): n for status, n in sorted(stats.statuses.items())},
+                    total_ms=round(stats.total_ms, 1),
+                    mean_ms=round(stats.total_ms / stats.count, 1),
+                    p50_ms=round(_percentile(samples, 50), 1),
+                    p95_ms=round(_percentile(samples, 95), 1),
+                    max_ms=round(stats.max_ms, 1),
+                )
+            )
+        routes.sort(key=lambda r: r.total_ms, reverse=True)
+        return PerfMetrics(
+            since=self._since,
+            routes=routes,
+            queries=QueryMetrics(
+                count=self._query_count,
+                total_ms=round(self._query_total_ms, 1),
+                slow_count=self._slow_count,
+                slow_threshold_ms=settings.slow_query_ms,
+            ),
+            slow_queries=list(reversed(self._slow_queries)),
+        )
+
+
+_perf_stats: PerfStats | None = None
+
+
+def get_perf_stats() -> PerfStats:
+    """Global performance metrics."""
+    global _perf_stats
+    if _perf_stats is None:
+        _perf_stats = PerfStats()
+    return _perf_stats
+
+
+class _TimedStatement:
+    """Awaitable and async context manager, like aiosqlite's execute() result."""
+
+    def __init__(self, result, sql: str, params_count: int):
+        self._result = result
+        self._sql = sql
+        self._params_count = params_count
+        self._cursor = None
+
+    async def _run(self):
+        start = time.monotonic()
+        try:
+            return await self._result
+        finally:
+            get_perf_stats().record_query(self._sql, self._params_count, (time.monotonic() - start) * 1000)
+
+    def __await__(self):
+        return self._run().__await__()
+
+    async def __aenter__(self):
+        self._cursor = await self._run()
+        return self._cursor
+
+    async def __aexit__(self, *exc_info):
+        await self._cursor.close()
+
+
+class TimedConnection:
+    """aiosqlite connection timing each statement.
+
+    Times the execution up to the first row; fetching the rest of a large
+    result isn't included.
+    """
+
+    def __init__(self, connection):
+        self._connection = connection
+
+    def execute(self, sql: str, parameters=None):
+        params_count = len(parameters) if parameters is not None else 0
+        return _TimedStatement(self._connection.execute(sql, parameters), sql, params_count)
+
+    def executemany(self, sql: str, parameters):
+        parameters = list(parameters)
+        params_count = sum(len(row) for row in parameters)
+        return _TimedStatement(self._connection.executemany(sql, parameters), sql, params_count)
+
+    def __getattr__(self, name: str):
+        return getattr(self._connection, name)
@@ -0,0 +1,76 @@
+"""Print job files on Bambu Lab printers.
+
+Locates the sliced 3MF of the current job on the printer's storage and reads
+its slicer metadata (plate thumbnails, filament weights).
+"""
+
+import io
+import logging
+import re
+import zipfile
+from xml.etree import ElementTree  # nosec B405
+
+from services.bambu_ftp import download_file_try_paths_async
+
+logger = logging.getLogger(__name__)
+
+
+def print_file_paths(subtask_name: str) -> list[str]:
+    """Possible locations of a job's 3MF file on the printer."""
+    filename = subtask_name
+    if not filename.endswith(".3mf"):
+        filename = filename + ".gcode.3mf"
+    return [
+        f"/{filename}",
+        f"/cache/{filename}",
+        f"/model/{filename}",
+        f"/data/{filename}",
+    ]
+
+
+def plate_number(gcode_file: str | None) -> int:
+    """Plate being printed, from the gcode path (e.g. "Metadata/plate_2.gcode" -> 2)."""
+    if gcode_file:
+        match = re.search(r"plate_(\d+)\.gcode", gcode_file)
+        if match:
+            return int(match.group(1))
+    return 1
+
+
+def parse_sliced_weight(data: bytes, plate: int = 1) -> float | None:
+    """Total filament weight (grams) the slicer estimated for a plate.
+
+    Reads Metadata/slice_info.config from the 3MF, which lists a
+    <filament used_g="..."/> entry per filament used on each plate.
+    Returns None if the file has no slice info for the plate.
+    """
+    try:
+        with zipfile.ZipFile(io.BytesIO(data), "r") as zf:
+            root = ElementTree.fromstring(zf.read("Metadata/slice_info.config"))  # nosec B314
+    except (zipfile.BadZipFile, KeyError, ElementTree.ParseError) as e:
+        logger.debug(f"No slice info in 3MF: {e}")
+        return None
+
+    for plate_el in root.iter("plate"):
+        index = next((m.get("value") for m in plate_el.iter("metadata") if m.get("key") == "index"), None)
+        if index != str(plate):
+            continue
+        total = 0.0
+        for filament in plate_el.iter("filament"):
+            try:
+                total += float(filament.get("used_g") or 0)
+            except ValueError:
+                continue
+        return total
+    return None
+
+
+async def fetch_sliced_weight(
+    ip_address: str, access_code: str, subtask_name: str, gcode_file: str | None = None
+) -> float | None:
+    """Download a job's 3MF from the printer and return its sliced filament weight."""
+    data = await download_file_try_paths_async(ip_address, access_code, print_file_paths(subtask_name), timeout=30.0)
+    if not data:
+        logger.info(f"Could not download 3MF for '{subtask_name}' from {ip_address}")
+        return None
+    return parse_sliced_weight(data, plate_number(gcode_file))
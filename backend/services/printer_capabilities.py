@@ -0,0 +1,153 @@
+"""
+Printer capability profiles and material compatibility checks.
+
+Each printer model has default capabilities (max nozzle temperature,
+hardened nozzle, enclosure). Users can override them per printer, and a
+nozzle type reported over MQTT takes precedence over the model default for
+the hardened nozzle. Assigning a spool checks its material against the
+profile: abrasive filaments on a soft nozzle and materials that need more
+heat than the hotend reaches are errors, materials that warp without an
+enclosure and user-tuned temperatures above the hotend's limit are warnings.
+"""
+
+import re
+
+from pydantic import BaseModel
+
+from models import NozzleInfo, Printer, Spool
+from services.slicer import MATERIAL_TEMP_RANGES, spool_temp_range
+
+SEVERITY_ERROR = "error"
+SEVERITY_WARNING = "warning"
+
+ISSUE_ABRASIVE = "abrasive"
+ISSUE_NOZZLE_TEMP = "nozzle_temp"
+ISSUE_ENCLOSURE = "enclosure"
+
+# Model -> (max nozzle temp, hardened nozzle, enclosed); None = unknown
+MODEL_CAPABILITIES: dict[str, tuple[int | None, bool | None, bool | None]] = {
+    "A1": (300, False, False),
+    "A1-MINI": (300, False, False),
+    "P1P": (300, False, False),
+    "P1S": (300, False, True),
+    "P2S": (300, None, True),
+    "X1": (300, False, True),
+    "X1-CARBON": (300, True, True),
+    "X1C": (300, True, True),
+    "X1E": (320, True, True),
+    "H2C": (350, True, True),
+    "H2D": (350, True, True),
+    "H2S": (350, True, True),
+}
+
+# Fillers that wear brass and stainless nozzles
+ABRASIVE_PATTERN = re.compile(r"(?<![A-Z])(CF|GF|CARBON|GLASS|GLOW)")
+
+# Materials that warp or crack without a heated chamber
+ENCLOSURE_MATERIALS = {"ABS", "ASA", "PC", "PA", "PPS", "PPA"}
+
+
+class PrinterCapabilities(BaseModel):
+    """Effective capabilities of a printer."""
+
+    max_nozzle_temp: int | None = None
+    hardened_nozzle: bool | None = None
+    enclosed: bool | None = None
+
+
+class CompatibilityIssue(BaseModel):
+    """A reason a spool may not print well (or at all) on a printer."""
+
+    code: str  # abrasive, nozzle_temp or enclosure
+    severity: str  # error or warning
+    message: str
+
+
+def model_capabilities(model: str | None) -> PrinterCapabilities:
+    """Default capabilities of a printer model."""
+    defaults = MODEL_CAPABILITIES.get((model or "").upper().replace(" ", "-"), (None, None, None))
+    return PrinterCapabilities(max_nozzle_temp=defaults[0], hardened_nozzle=defaults[1], enclosed=defaults[2])
+
+
+def nozzle_is_hardened(nozzle_type: str | None) -> bool | None:
+    """Whether a reported nozzle type is hardened, None if not reported."""
+    if not nozzle_type:
+        return None
+    nozzle_type = nozzle_type.lower()
+    # "hardened_steel", "tungsten_carbide"; H2 series report HS/HH codes (e.g. "HS01")
+    return "hardened" in nozzle_type or "carbide" in nozzle_type or nozzle_type.startswith(("hs", "hh"))
+
+
+def effective_capabilities(printer: Printer, nozzles: list[NozzleInfo] | None = None) -> PrinterCapabilities:
+    """Capabilities from the printer's overrides, installed nozzles and model defaults, in that order."""
+    caps = model_capabilities(printer.model)
+    reported = [nozzle_is_hardened(n.nozzle_type) for n in nozzles or []]
+    if reported and None not in reported:
+        caps.hardened_nozzle = all(reported)  # Every extruder may get the spool
+    for field in PrinterCapabilities.model_fields:
+        override = getattr(printer, field)
+        if override is not None:
+            setattr(caps, field, override)
+    return caps
+
+
+def base_material(material: str | None) -> str:
+    """Material family without fillers or variants ("PA6-CF" -> "PA")."""
+    material = (material or "").upper().strip()
+    if material in MATERIAL_TEMP_RANGES:
+        return material
+    family = re.split(r"[-\s+]", material, maxsplit=1)[0]
+    if family in MATERIAL_TEMP_RANGES:
+        return family
+    return family.rstrip("0123456789")
+
+
+def is_abrasive(spool: Spool) -> bool:
+    """Whether a spool's filament is filled with abrasive particles."""
+    text = " ".join(filter(None, (spool.material, spool.subtype, spool.slicer_filament_name))).upper()
+    return bool(ABRASIVE_PATTERN.search(text))
+
+
+def check_compatibility(caps: PrinterCapabilities, spool: Spool) -> list[CompatibilityIssue]:
+    """Problems printing a spool on a printer. Unknown capabilities are not checked."""
+    issues = []
+    material = base_material(spool.material)
+
+    if caps.hardened_nozzle is False and is_abrasive(spool):
+        issues.append(
+            CompatibilityIssue(
+                code=ISSUE_ABRASIVE,
+                severity=SEVERITY_ERROR,
+                message=f"{spool.material} is abrasive and will wear out a non-hardened nozzle",
+            )
+        )
+
+    # The base material's range, so filled variants ("PA-CF") are checked too
+    min_temp, max_temp = spool_temp_range(spool.model_copy(update={"material": material}))
+    known_temps = spool.nozzle_temp_min is not None or material in MATERIAL_TEMP_RANGES
+    if caps.max_nozzle_temp is not None and known_temps and min_temp > caps.max_nozzle_temp:
+        issues.append(
+            CompatibilityIssue(
+                code=ISSUE_NOZZLE_TEMP,
+                severity=SEVERITY_ERROR,
+                message=f"{spool.material} needs at least {min_temp}°C, printer reaches {caps.max_nozzle_temp}°C",
+            )
+        )
+    elif caps.max_nozzle_temp is not None and spool.nozzle_temp_max is not None and max_temp > caps.max_nozzle_temp:
+        issues.append(
+            CompatibilityIssue(
+                code=ISSUE_NOZZLE_TEMP,
+                severity=SEVERITY_WARNING,
+                message=f"Spool's max nozzle temperature {max_temp}°C is above the printer's {caps.max_nozzle_temp}°C",
+            )
+        )
+
+    if caps.enclosed is False and material in ENCLOSURE_MATERIALS:
+        issues.append(
+            CompatibilityIssue(
+                code=ISSUE_ENCLOSURE,
+                severity=SEVERITY_WARNING,
+                message=f"{spool.material} tends to warp on printers without an enclosure",
+            )
+        )
+    return issues
@@ -0,0 +1,89 @@
+"""
+Spool weight reconciliation.
+
+Usage is tracked from print estimates and manual entries, and each scale
+reading resets the spool to what the scale says. The difference between
+the two is lost on the way: a scale reading that is 120 g below the
+previous one minus the usage logged in between means 120 g went somewhere
+untracked (a purge, a failed print, a wrong estimate).
+
+Each new scale reading is compared against the previous one. Differences
+beyond the tolerances become suggestions; accepting one logs the
+difference as usage (reason "reconciliation"), so usage reports agree with
+the scale. The spool's weight itself was already synced to the scale.
+"""
+
+import logging
+from enum import StrEnum
+
+from config import settings
+from pydantic import BaseModel
+
+logger = logging.getLogger(__name__)
+
+RECONCILIATION_REASON = "reconciliation"  # usage_history reason of accepted suggestions
+LAST_SCANNED_SETTING = "reconciliation_last_measurement_id"
+
+
+class ReconciliationStatus(StrEnum):
+    OPEN = "open"
+    ACCEPTED = "accepted"
+    DISMISSED = "dismissed"
+
+
+class WeightReconciliation(BaseModel):
+    """A scale reading that disagrees with the tracked usage."""
+
+    id: int
+    spool_id: str
+    measurement_id: int  # weight_history row of the scale reading
+    previous_weight: int  # Gross, grams
+    measured_weight: int
+    tracked_usage: float  # Logged between the two readings
+    discrepancy: float  # Measured minus expected, negative = more used than tracked
+    status: ReconciliationStatus
+    resolved_grams: float | None = None  # Usage logged when accepted
+    measured_at: int
+    resolved_at: int | None = None
+
+
+def is_anomaly(discrepancy: float, tracked_usage: float) -> bool:
+    """Whether a difference is beyond both the absolute and the relative tolerance."""
+    tolerance = max(
+        settings.reconciliation_tolerance_grams,
+        abs(tracked_usage) * settings.reconciliation_tolerance_percent / 100,
+    )
+    return abs(discrepancy) > tolerance
+
+
+async def scan_measurements(db) -> list[WeightReconciliation]:
+    """Check the scale readings recorded since the last scan and flag the ones that disagree."""
+    last_id = int(await db.get_setting(LAST_SCANNED_SETTING) or 0)
+    created = []
+    while readings := await db.get_scale_readings_after(last_id):
+        for reading in readings:
+            last_id = reading["id"]
+            interval = await db.get_measurement_interval(reading, RECONCILIATION_REASON)
+            # First reading, or the spool was reset (emptied, undo) in between
+            if not interval or interval["other_changes"]:
+                continue
+            expected = interval["previous"]["weight"] - interval["tracked_usage"]
+            discrepancy = round(reading["weight"] - expected, 1)
+            if not is_anomaly(discrepancy, interval["tracked_usage"]):
+                continue
+            row = await db.create_weight_reconciliation(
+                spool_id=reading["spool_id"],
+                measurement_id=reading["id"],
+                previous_weight=interval["previous"]["weight"],
+                measured_weight=reading["weight"],
+                tracked_usage=interval["tracked_usage"],
+                discrepancy=discrepancy,
+                measured_at=reading["recorded_at"],
+            )
+            if row:
+                created.append(WeightReconciliation(**row))
+        await db.set_setting(LAST_SCANNED_SETTING, str(last_id))
+
+    if created:
+        logger.info(f"Weight reconciliation flagged {len(created)} scale reading(s)")
+    return created
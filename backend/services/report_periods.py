@@ -0,0 +1,65 @@
+"""
+Calendar periods for reports.
+
+Usage timestamps are epoch seconds; month and week report groups follow the
+calendar in the report timezone instead of UTC, so a print at 00:30 local
+time on the 1st counts towards that month and weeks start at local midnight
+on the configured first day. The conversion goes through zoneinfo, so
+periods spanning a DST change are an hour shorter or longer, as on the
+wall clock.
+
+period_key() is registered as the SQLite function local_period(timestamp,
+timezone, period, week_start), so reports can group by it in SQL.
+"""
+
+import zoneinfo
+from datetime import date, datetime, time, timedelta
+from enum import StrEnum
+from functools import lru_cache
+
+
+class WeekStart(StrEnum):
+    """First day of the week for week reports."""
+
+    MONDAY = "monday"
+    SUNDAY = "sunday"
+
+
+@lru_cache(maxsize=16)
+def _zone(name: str) -> zoneinfo.ZoneInfo:
+    return zoneinfo.ZoneInfo(name)
+
+
+def _week_start_date(day: date, week_start: str) -> date:
+    offset = (day.weekday() + (1 if week_start == WeekStart.SUNDAY else 0)) % 7
+    return day - timedelta(days=offset)
+
+
+def period_key(timestamp: int | None, timezone: str, period: str, week_start: str = WeekStart.MONDAY) -> str | None:
+    """Group key of a timestamp: "YYYY-MM" for months, the local first day "YYYY-MM-DD" for weeks."""
+    if timestamp is None:
+        return None
+    day = datetime.fromtimestamp(timestamp, _zone(timezone)).date()
+    if period == "month":
+        return day.strftime("%Y-%m")
+    if period == "week":
+        return _week_start_date(day, week_start).isoformat()
+    raise ValueError(f"Unsupported period: {period}")
+
+
+def period_bounds(key: str, timezone: str, period: str) -> tuple[int, int]:
+    """Epoch seconds of the local midnights starting and ending a period key."""
+    if period == "month":
+        first = date.fromisoformat(f"{key}-01")
+        last = (first + timedelta(days=32)).replace(day=1)
+    elif period == "week":
+        first = date.fromisoformat(key)
+        last = first + timedelta(days=7)
+    else:
+        raise ValueError(f"Unsupported period: {period}")
+
+    zone = _zone(timezone)
+    return (
+        int(datetime.combine(first, time(), zone).timestamp()),
+        int(datetime.combine(last, time(), zone).timestamp()),
+    )
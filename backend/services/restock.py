@@ -0,0 +1,147 @@
+"""
+Restock suggestions.
+
+Sums the filament left on active spools per material and compares it with
+the stock level configured for the material. Materials below their level
+are suggested for reordering, with the recent consumption rate (how soon
+the stock runs out) and the last purchase of the material (where it came
+from and what it cost).
+"""
+
+import math
+import time
+
+from pydantic import BaseModel, Field
+from services.forecast import FORECAST_WINDOW_DAYS, SECONDS_PER_DAY, remaining_grams
+
+RESTOCK_LEVELS_SETTING = "restock_levels"
+DEFAULT_SPOOL_GRAMS = 1000  # Assumed spool size for a material with no label weights
+
+
+class RestockLevels(BaseModel):
+    """Minimum stock per material, in grams of filament."""
+
+    default: int | None = Field(default=None, ge=0)  # Level for materials not listed, None = only listed ones
+    materials: dict[str, int] = {}  # e.g. {"PLA": 3000, "PETG": 1000}
+
+
+class LastPurchase(BaseModel):
+    """Most recent purchase of a material."""
+
+    id: int
+    supplier: str | None = None
+    url: str | None = None
+    purchased_at: int | None = None
+    unit_price: float | None = None  # Price per spool
+
+
+class RestockSuggestion(BaseModel):
+    """Stock of a material against its restock level."""
+
+    material: str
+    spool_count: int
+    stock_grams: float
+    level_grams: int | None
+    daily_usage: float  # Grams per day over the forecast window
+    days_remaining: float | None = None  # None when there is no recent usage
+    shortfall_grams: float = 0
+    suggested_spools: int = 0
+    last_purchase: LastPurchase | None = None
+
+
+def _levels_key(levels: RestockLevels) -> dict[str, int]:
+    return {material.strip().upper(): level for material, level in levels.materials.items()}
+
+
+def suggest_restock(
+    spools,
+    consumed: dict[str, float],
+    levels: RestockLevels,
+    last_purchases: dict[str, dict] | None = None,
+    include_ok: bool = False,
+    window_days: int = FORECAST_WINDOW_DAYS,
+) -> list[RestockSuggestion]:
+    """Restock suggestions per material.
+
+    Args:
+        spools: All spools; archived ones only count towards consumption
+        consumed: Grams consumed per spool ID over the forecast window
+        levels: Configured stock levels
+        last_purchases: Latest purchase per upper-cased material
+        include_ok: Also list materials at or above their level (and those without one)
+        window_days: Length of the consumption window
+    """
+    level_by_key = _levels_key(levels)
+    groups: dict[str, dict] = {}
+    for spool in spools:
+        if spool.deleted_at is not None:
+            continue
+        key = spool.material.strip().upper()
+        group = groups.setdefault(key, {"material": spool.material, "spools": [], "consumed": 0.0})
+        group["consumed"] += consumed.get(spool.id, 0.0)
+        if spool.archived_at is None:
+            group["spools"].append(spool)
+    for key in level_by_key:
+        groups.setdefault(key, {"material": key, "spools": [], "consumed": 0.0})
+
+    suggestions = []
+    for key, group in groups.items():
+        active = group["spools"]
+        stock = sum(remaining_grams(spool) or 0 for spool in active)
+        level = level_by_key.get(key, levels.default)
+        daily_usage = group["consumed"] / window_days
+        suggestion = RestockSuggestion(
+            material=group["material"],
+            spool_count=len(active),
+            stock_grams=round(stock, 1),
+            level_grams=level,
+            daily_usage=round(daily_usage, 2),
+            days_remaining=round(stock / daily_usage, 1) if daily_usage > 0 else None,
+        )
+        if level is not None and stock < level:
+            spool_size = max((spool.label_weight or 0 for spool in active), default=0) or DEFAULT_SPOOL_GRAMS
+            suggestion.shortfall_grams = round(level - stock, 1)
+            suggestion.suggested_spools = math.ceil((level - stock) / spool_size)
+        elif not include_ok:
+            continue
+        purchase = (last_purchases or {}).get(key)
+        if purchase:
+            quantity = purchase["quantity"] or 1
+            unit_price = purchase["price"] / quantity if purchase["price"] is not None else None
+            suggestion.last_purchase = LastPurchase(
+                id=purchase["id"],
+                supplier=purchase["supplier"],
+                url=purchase["url"],
+                purchased_at=purchase["purchased_at"],
+                unit_price=round(unit_price, 2) if unit_price is not None else None,
+            )
+        suggestions.append(suggestion)
+
+    # Most urgent first: largest shortfall relative to the level, then soonest to run out
+    return sorted(
+        suggestions,
+        key=lambda s: (
+            -(s.shortfall_grams / s.level_grams if s.level_grams else 0),
+            s.days_remaining if s.days_remaining is not None else math.inf,
+            s.material,
+        ),
+    )
+
+
+async def get_restock_levels(db) -> RestockLevels:
+    """Configured stock levels."""
+    value = await db.get_setting(RESTOCK_LEVELS_SETTING)
+    return RestockLevels.model_validate_json(value) if value else RestockLevels()
+
+
+async def restock_suggestions(db, include_ok: bool = False, now: int | None = None) -> list[RestockSuggestion]:
+    """Restock suggestions from the inventory, usage history and purchases."""
+    now = now or int(time.time())
+    consumed = await db.get_consumption_since(now - FORECAST_WINDOW_DAYS * SECONDS_PER_DAY)
+    return suggest_restock(
+        await db.get_spools(),
+        consumed,
+        await get_restock_levels(db),
+        await db.get_latest_purchases_by_material(),
+        include_ok=include_ok,
+    )
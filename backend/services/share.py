@@ -0,0 +1,115 @@
+"""
+Read-only inventory share links.
+
+A share link is an unguessable token that shows a chosen subset of each
+active spool's fields (material, color, filament left, ...) to anyone who
+has it, without access to the rest of the server. IDs, tags, prices,
+notes and printers are never shared. Links can expire and be revoked.
+"""
+
+import html
+import re
+import secrets
+import time
+from enum import StrEnum
+
+from services.forecast import remaining_grams
+
+
+class ShareField(StrEnum):
+    """Spool fields a share link can show."""
+
+    SPOOL_NUMBER = "spool_number"
+    BRAND = "brand"
+    MATERIAL = "material"
+    SUBTYPE = "subtype"
+    COLOR_NAME = "color_name"
+    RGBA = "rgba"
+    REMAINING_GRAMS = "remaining_grams"
+    LABEL_WEIGHT = "label_weight"
+    LOCATION = "location"
+
+
+DEFAULT_SHARE_FIELDS = [
+    ShareField.BRAND,
+    ShareField.MATERIAL,
+    ShareField.SUBTYPE,
+    ShareField.COLOR_NAME,
+    ShareField.RGBA,
+    ShareField.REMAINING_GRAMS,
+]
+
+_COLUMN_TITLES = {
+    ShareField.SPOOL_NUMBER: "#",
+    ShareField.BRAND: "Brand",
+    ShareField.MATERIAL: "Material",
+    ShareField.SUBTYPE: "Type",
+    ShareField.COLOR_NAME: "Color",
+    ShareField.RGBA: "",
+    ShareField.REMAINING_GRAMS: "Remaining",
+    ShareField.LABEL_WEIGHT: "Spool size",
+    ShareField.LOCATION: "Location",
+}
+
+
+def generate_share_token() -> str:
+    """Random URL-safe token for a new share link."""
+    return secrets.token_urlsafe(18)
+
+
+def is_share_link_active(link: dict, now: int | None = None) -> bool:
+    """Whether a share link can still be viewed (not revoked or expired)."""
+    now = now or int(time.time())
+    if link["revoked_at"] is not None:
+        return False
+    return link["expires_at"] is None or link["expires_at"] > now
+
+
+def shared_spools(spools, fields: list[str]) -> list[dict]:
+    """Active spools reduced to the shared fields, sorted by material, brand and color."""
+    items = []
+    for spool in sorted(
+        (s for s in spools if s.archived_at is None),
+        key=lambda s: (s.material, s.brand or "", s.color_name or "", s.spool_number or 0),
+    ):
+        item = {}
+        for field in fields:
+            if field == ShareField.REMAINING_GRAMS:
+                remaining = remaining_grams(spool)
+                item[field] = round(remaining) if remaining is not None else None
+            else:
+                item[field] = getattr(spool, field)
+        items.append(item)
+    return items
+
+
+def _cell(item: dict, field: str) -> str:
+    value = item.get(field)
+    if field == ShareField.RGBA:
+        rgba = (value or "").lstrip("#")
+        color = f"#{rgba[:6]}" if re.fullmatch(r"[0-9A-Fa-f]{6,8}", rgba) else "transparent"
+        return f'<span class="swatch" style="background:{color}"></span>'
+    if value is None:
+        return ""
+    if field in (ShareField.REMAINING_GRAMS, ShareField.LABEL_WEIGHT):
+        return f"{value}g"
+    return html.escape(str(value))
+
+
+def render_html(items: list[dict], fields: list[str], title: str) -> str:
+    """Standalone HTML page with one row per spool."""
+    header = "".join(f"<th>{html.escape(_COLUMN_TITLES[ShareField(f)])}</th>" for f in fields)
+    rows = ["<tr>" + "".join(f"<td>{_cell(item, f)}</td>" for f in fields) + "</tr>" for item in items]
+    body = (
+        f"<table><thead><tr>{header}</tr></thead><tbody>{''.join(rows)}</tbody></table>"
+        if rows
+        else "<p>No filament in stock right now.</p>"
+    )
+    return (
+        "<!DOCTYPE html>\n"
+        f'<html><head><meta charset="utf-8"><meta name="robots" content="noindex">'
+        f'<meta name="viewport" content="width=device-width, initial-scale=1"><title>{html.escape(title)}</title>'
+        "<style>body{font-family:sans-serif}td,th{padding:4px 8px;text-align:left}"
+        ".swatch{display:inline-block;width:12px;height:12px;border:1px solid #888;vertical-align:middle}</style>"
+        f"</head><body><h1>{html.escape(title)}</h1>{body}</body></html>\n"
+    )
@@ -0,0 +1,221 @@
+"""
+Slicer filament preset mapping.
+
+Spools store the slicer preset they print with as `slicer_filament`, which may
+be a Bambu Studio setting_id ("GFSL05_07"), a filament_id read from an RFID
+tag ("GFL05"), a user preset ("PFUS...") or free text. This module resolves
+that into the setting_id/filament_id pair the printer and slicer expect, and
+builds exportable filament presets.
+"""
+
+import logging
+import re
+
+from tags.bambulab import BAMBU_MATERIALS
+
+logger = logging.getLogger(__name__)
+
+# Nozzle temperature range by material (min, max)
+MATERIAL_TEMP_RANGES = {
+    "PLA": (190, 230),
+    "PETG": (220, 260),
+    "ABS": (240, 270),
+    "ASA": (240, 270),
+    "TPU": (200, 230),
+    "PA": (260, 290),
+    "PC": (260, 280),
+    "PVA": (190, 210),
+}
+DEFAULT_TEMP_RANGE = (190, 250)
+
+# Bambu generic filament IDs, used when a preset can't be resolved
+GENERIC_FILAMENT_IDS = {
+    "PLA": "GFL99",
+    "PETG": "GFG99",
+    "ABS": "GFB99",
+    "ASA": "GFB98",
+    "TPU": "GFU99",
+    "PA": "GFN99",
+    "PC": "GFC99",
+    "PVA": "GFS99",
+}
+DEFAULT_FILAMENT_ID = "GFL99"
+
+# Bambu Studio version written into exported presets
+PRESET_VERSION = "1.10.0.0"
+
+_SYSTEM_SETTING_ID = re.compile(r"^GFS[A-Z]\d{2}(_\d+)?$")
+_SYSTEM_FILAMENT_ID = re.compile(r"^GF[A-Z]\d{2}$")
+_USER_PRESET_PREFIXES = ("PFUS", "PFSP")
+# Loose ID formats for validation - the catalog below doesn't list every preset
+_FILAMENT_ID_FORMAT = re.compile(r"^(GF|P)[0-9A-Za-z]{2,15}$")
+_SETTING_ID_FORMAT = re.compile(r"^(GFS|PFUS|PFSP)[0-9A-Za-z_]+$")
+_HEX_COLOR = re.compile(r"^[0-9A-F]{6}([0-9A-F]{2})?$")
+
+
+def temp_range(material: str | None) -> tuple[int, int]:
+    """Nozzle temperature range for a material."""
+    return MATERIAL_TEMP_RANGES.get((material or "").upper(), DEFAULT_TEMP_RANGE)
+
+
+def spool_temp_range(spool) -> tuple[int, int]:
+    """Nozzle temperature range for a spool: its user-tuned values, else the material's."""
+    low, high = temp_range(spool.material)
+    if spool.nozzle_temp_min is not None:
+        low = spool.nozzle_temp_min
+    if spool.nozzle_temp_max is not None:
+        high = spool.nozzle_temp_max
+    return low, max(low, high)
+
+
+def generic_filament_id(material: str | None) -> str:
+    """Bambu generic filament ID for a material."""
+    return GENERIC_FILAMENT_IDS.get((material or "").upper(), DEFAULT_FILAMENT_ID)
+
+
+def is_user_preset(setting_id: str | None) -> bool:
+    """Whether a setting_id refers to a user-created (cloud) preset."""
+    return bool(setting_id) and setting_id.startswith(_USER_PRESET_PREFIXES)
+
+
+def parse_slicer_filament(value: str | None) -> tuple[str | None, str | None]:
+    """Split a `slicer_filament` value into (setting_id, filament_id).
+
+    System presets map both ways ("GFSL05_07" <-> "GFL05"; the version suffix
+    is dropped). User presets only carry a setting_id, their filament_id has
+    to be looked up in the cloud. Free text maps to neither.
+    """
+    value = (value or "").strip()
+    if _SYSTEM_SETTING_ID.match(value):
+        setting_id = value.split("_")[0]
+        return setting_id, "GF" + setting_id[3:]
+    if _SYSTEM_FILAMENT_ID.match(value):
+        return "GFS" + value[2:], value
+    if value.startswith(_USER_PRESET_PREFIXES):
+        return value, None
+    return None, None
+
+
+def normalize_tray_color(value: str | None) -> str | None:
+    """RGBA hex the AMS expects ("FF8800FF") from "#ff8800", "FF8800FF" etc.
+
+    Returns None for anything that isn't a 6 or 8 digit hex color.
+    """
+    color = (value or "").strip().lstrip("#").upper()
+    if not _HEX_COLOR.match(color):
+        return None
+    return color if len(color) == 8 else color + "FF"
+
+
+def check_filament_preset(tray_info_idx: str, setting_id: str, tray_type: str) -> str | None:
+    """Validate AMS slot preset fields against the preset catalog.
+
+    Returns an error message, or None if the combination is valid. IDs missing
+    from the (partial) catalog are accepted as long as they are well-formed.
+    setting_id isn't checked against tray_info_idx: the printer needs
+    tray_info_idx to match the K profile, which may come from another preset.
+    """
+    if tray_info_idx and not _FILAMENT_ID_FORMAT.match(tray_info_idx):
+        return f"Invalid filament preset ID '{tray_info_idx}'"
+
+    if setting_id and not _SETTING_ID_FORMAT.match(setting_id):
+        return f"Invalid setting ID '{setting_id}'"
+
+    # Only Bambu's own filaments are checked; the catalog's generic entries are approximate
+    name, material = BAMBU_MATERIALS.get(tray_info_idx, ("", ""))
+    if tray_type and name.startswith("Bambu ") and material != "Support":
+        # Compare base materials - "PLA-CF" presets are loaded as "PLA-CF" or "PLA"
+        if material.split("-")[0].upper() != tray_type.split("-")[0].upper():
+            return f"Filament preset '{tray_info_idx}' ({name}) is {material}, not {tray_type}"
+
+    return None
+
+
+def filament_preset_name(filament_id: str | None) -> str | None:
+    """Catalog name of a system filament preset, e.g. "Bambu PLA Basic"."""
+    preset = BAMBU_MATERIALS.get(filament_id or "")
+    return preset[0] if preset else None
+
+
+async def resolve_filament_id(db, spool) -> str:
+    """filament_id (AMS tray_info_idx) for a spool's preset.
+
+    User presets are looked up in Bambu Cloud; anything unresolved falls back
+    to the generic ID for the spool's material.
+    """
+    if spool.slicer_filament_id:
+        return spool.slicer_filament_id
+
+    setting_id = spool.slicer_setting_id
+    if is_user_preset(setting_id):
+        from services.bambu_cloud import get_cloud_service
+
+        cloud = get_cloud_service()
+        if not cloud.is_authenticated:
+            token = await db.get_setting("cloud_access_token")
+            if token:
+                cloud.set_token(token)
+
+        preset_detail = await cloud.get_setting_detail(setting_id)
+        if preset_detail:
+            # filament_id is at root level or in setting.filament_id
+            cloud_filament_id = preset_detail.get("filament_id")
+            if not cloud_filament_id and preset_detail.get("setting"):
+                cloud_filament_id = preset_detail["setting"].get("filament_id")
+            if cloud_filament_id:
+                logger.info(f"Custom preset {setting_id} -> filament_id={cloud_filament_id}")
+                return cloud_filament_id
+            logger.warning(f"No filament_id in cloud response for {setting_id}, using generic")
+        else:
+            logger.warning(f"Cloud lookup failed for {setting_id}, using generic")
+
+    return generic_filament_id(spool.material)
+
+
+def _hex_colour(rgba: str | None) -> str:
+    rgb = (rgba or "FFFFFF").lstrip("#")[:6].upper()
+    return f"#{rgb}" if len(rgb) == 6 else "#FFFFFF"
+
+
+def build_filament_preset(spool, filament_id: str, k_value: str | None = None) -> dict:
+    """Bambu Studio / OrcaSlicer user filament preset for a spool.
+
+    List-valued settings use the slicer's per-extruder string arrays. The
+    preset inherits from the spool's system preset when it has one, so the
+    slicer fills in everything not set here.
+    """
+    low, high = spool_temp_range(spool)
+    nozzle_temp = str(round((low + high) / 2 / 5) * 5)
+
+    label = " ".join(p for p in (spool.brand, spool.material, spool.subtype) if p)
+    if spool.color_name:
+        label += f" {spool.color_name}"
+    name = f"{label} #{spool.spool_number}" if spool.spool_number else label
+
+    inherits = ""
+    if spool.slicer_setting_id and not is_user_preset(spool.slicer_setting_id) and spool.slicer_filament_name:
+        inherits = spool.slicer_filament_name
+
+    preset = {
+        "type": "filament",
+        "name": name,
+        "inherits": inherits,
+        "from": "User",
+        "instantiation": "true",
+        "version": PRESET_VERSION,
+        "filament_id": filament_id,
+        "filament_settings_id": [name],
+        "filament_type": [(spool.material or "PLA").upper()],
+        "filament_vendor": [spool.brand or "Generic"],
+        "filament_colour": [_hex_colour(spool.rgba)],
+        "nozzle_temperature": [nozzle_temp],
+        "nozzle_temperature_initial_layer": [nozzle_temp],
+        "nozzle_temperature_range_low": [str(low)],
+        "nozzle_temperature_range_high": [str(high)],
+    }
+    if spool.slicer_setting_id:
+        preset["base_id"] = spool.slicer_setting_id
+    if k_value:
+        preset["enable_pressure_advance"] = ["1"]
+        preset["pressure_advance"] = [k_value]
+    return preset
@@ -0,0 +1,140 @@
+"""
+Live spool whereabouts.
+
+A spool is on the scale while its tag is on the SpoolBuddy device, loaded
+while it is assigned to a printer slot that holds filament, and in storage
+otherwise. Statuses are computed on demand from the device's tag, the slot
+assignments and the printers' live AMS state; changes are published to the
+UI as they happen.
+"""
+
+import time
+from collections.abc import Callable
+
+from models import PrinterState, SpoolLiveStatus, SpoolPresence
+from services.ams_events import tray_slot
+
+DISPLAY_DEVICE = "display"  # The SpoolBuddy display/scale unit
+
+
+def _place(status: SpoolLiveStatus) -> tuple:
+    """Where the spool is, ignoring when it got there and whether it is feeding."""
+    return (status.status, status.device, status.printer_serial, status.ams_id, status.tray_id)
+
+
+def _slot_occupied(state: PrinterState, ams_id: int, tray_id: int) -> bool | None:
+    """Whether the printer reports filament in a slot, None if it doesn't report the slot."""
+    if ams_id in (254, 255):
+        return bool(state.vt_tray.tray_type) if state.vt_tray and ams_id == 255 else None
+    for unit in state.ams_units:
+        if unit.id == ams_id:
+            for tray in unit.trays:
+                if tray.tray_id == tray_id:
+                    return bool(tray.tray_type)
+    return None
+
+
+def active_slots(state: PrinterState) -> dict[int | None, tuple[int, int]]:
+    """Slot (ams_id, tray_id) feeding each extruder, keyed by extruder (None on single-nozzle printers)."""
+    if state.nozzle_count >= 2:
+        active = {0: state.tray_now_right, 1: state.tray_now_left}
+    else:
+        active = {None: state.tray_now}
+    slots = {extruder: tray_slot(index, state.nozzle_count) for extruder, index in active.items()}
+    return {extruder: slot for extruder, slot in slots.items() if slot}
+
+
+def _slot_active(state: PrinterState, ams_id: int, tray_id: int) -> bool:
+    """Whether a slot is feeding one of the printer's extruders."""
+    return (ams_id, tray_id) in active_slots(state).values()
+
+
+class SpoolStatusTracker:
+    """Computes live spool statuses and tracks the ones last published to the UI."""
+
+    def __init__(self):
+        self._on_scale: dict[str, str] = {}  # device -> spool_id
+        self._published: dict[str, SpoolLiveStatus] = {}  # spool_id -> last status sent to the UI
+        self._arrived: dict[str, tuple[tuple, int]] = {}  # spool_id -> (place, when it got there)
+        self._state_source: Callable[[str], PrinterState | None] = lambda serial: None
+
+    def set_state_source(self, source: Callable[[str], PrinterState | None]):
+        """Set the lookup for a printer's live state (None if not connected)."""
+        self._state_source = source
+
+    def set_on_scale(self, device: str, spool_id: str | None):
+        """Record the spool whose tag is on a device's scale (None when it is taken off)."""
+        if spool_id:
+            self._on_scale[device] = spool_id
+        else:
+            self._on_scale.pop(device, None)
+
+    async def current(self, db) -> dict[str, SpoolLiveStatus]:
+        """Statuses of all spools that are not in storage."""
+        now = int(time.time())
+        statuses = {}
+        for assignment in await db.get_all_slot_assignments():
+            serial, ams_id, tray_id = assignment["printer_serial"], assignment["ams_id"], assignment["tray_id"]
+            state = self._state_source(serial)
+            # Printers that don't report the slot (disconnected, Klipper) keep the assignment
+            if state and _slot_occupied(state, ams_id, tray_id) is False:
+                continue  # Assigned, but the spool was taken out
+            statuses[assignment["spool_id"]] = SpoolLiveStatus(
+                status=SpoolPresence.LOADED,
+                printer_serial=serial,
+                ams_id=ams_id,
+                tray_id=tray_id,
+                active=bool(state) and _slot_active(state, ams_id, tray_id),
+            )
+        # A spool on the scale can't be in a printer, whatever the assignment says
+        for device, spool_id in self._on_scale.items():
+            statuses[spool_id] = SpoolLiveStatus(status=SpoolPresence.ON_SCALE, device=device)
+
+        for spool_id, status in statuses.items():
+            place, since = self._arrived.get(spool_id, (None, now))
+            if place != _place(status):
+                since = now
+                self._arrived[spool_id] = (_place(status), since)
+            status.since = since
+        for spool_id in self._arrived.keys() - statuses.keys():
+            del self._arrived[spool_id]
+        return statuses
+
+    def status_of(self, spool_id: str, statuses: dict[str, SpoolLiveStatus]) -> SpoolLiveStatus:
+        """Status of one spool, given the result of current()."""
+        if spool_id in statuses:
+            return statuses[spool_id]
+        published = self._published.get(spool_id)
+        if published and published.status == SpoolPresence.STORAGE:
+            return published
+        return SpoolLiveStatus()
+
+    async def changes(self, db) -> dict[str, SpoolLiveStatus]:
+        """Statuses that changed since the last call, to publish to the UI."""
+        statuses = await self.current(db)
+        now = int(time.time())
+        for spool_id, published in self._published.items():
+            if spool_id not in statuses and published.status != SpoolPresence.STORAGE:
+                statuses[spool_id] = SpoolLiveStatus(since=now)  # Back to storage
+
+        changed = {}
+        for spool_id, status in statuses.items():
+            published = self._published.get(spool_id)
+            if published is None and status.status == SpoolPresence.STORAGE:
+                continue
+            if published != status:
+                changed[spool_id] = status
+                self._published[spool_id] = status
+        return changed
+
+
+# Singleton instance
+_tracker: SpoolStatusTracker | None = None
+
+
+def get_spool_status_tracker() -> SpoolStatusTracker:
+    """Get the singleton spool status tracker."""
+    global _tracker
+    if _tracker is None:
+        _tracker = SpoolStatusTracker()
+    return _tracker
@@ -0,0 +1,86 @@
+"""
+Undo for spool changes made from the device.
+
+Syncing the scale weight or linking a tag is one tap on the device, so the
+wrong spool on the scale silently overwrites a good record. The values a
+device-triggered update replaced are kept for a short window, during which
+the update can be reverted - as long as nothing else changed the spool since.
+"""
+
+import time
+from dataclasses import dataclass, field
+
+from config import settings
+from models import Spool, SpoolBase
+
+ACTION_WEIGHT_SYNC = "weight_sync"
+ACTION_LINK_TAG = "link_tag"
+ACTION_DEVICE_UPDATE = "device_update"
+
+# Stored spool fields an undo can restore
+RESTORABLE_FIELDS = frozenset(SpoolBase.model_fields) | {
+    "weight_used",
+    "consumed_since_weight",
+    "consumed_since_add",
+    "added_full",
+}
+
+
+@dataclass
+class UndoEntry:
+    """Values replaced by a device-triggered spool update."""
+
+    spool_id: str
+    action: str
+    previous: dict  # field -> value before the update
+    version: int  # Spool version right after the update
+    created_at: float = field(default_factory=time.time)
+
+    @property
+    def expires_at(self) -> float:
+        return self.created_at + settings.undo_window_minutes * 60
+
+
+class UndoLog:
+    """Last undoable device-triggered update per spool."""
+
+    def __init__(self):
+        self._entries: dict[str, UndoEntry] = {}
+
+    def record(self, action: str, before: Spool, after: Spool) -> UndoEntry | None:
+        """Remember the fields an update changed. Returns None if it changed nothing restorable."""
+        previous = {
+            name: getattr(before, name)
+            for name in sorted(RESTORABLE_FIELDS)
+            if getattr(before, name) != getattr(after, name)
+        }
+        if not previous:
+            return None
+        now = time.time()
+        self._entries = {spool_id: e for spool_id, e in self._entries.items() if e.expires_at > now}
+        entry = UndoEntry(spool_id=after.id, action=action, previous=previous, version=after.version, created_at=now)
+        self._entries[after.id] = entry
+        return entry
+
+    def get(self, spool_id: str) -> UndoEntry | None:
+        """The spool's undoable update, None if there is none or its window has passed."""
+        entry = self._entries.get(spool_id)
+        if entry and time.time() >= entry.expires_at:
+            del self._entries[spool_id]
+            return None
+        return entry
+
+    def discard(self, spool_id: str):
+        self._entries.pop(spool_id, None)
+
+
+# Singleton instance
+_undo_log: UndoLog | None = None
+
+
+def get_undo_log() -> UndoLog:
+    """Get the singleton undo log."""
+    global _undo_log
+    if _undo_log is None:
+        _undo_log = UndoLog()
+    return _undo_log
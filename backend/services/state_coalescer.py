@@ -0,0 +1,97 @@
+"""
+Printer state broadcast coalescing.
+
+Bambu printers push a report about every second and each one that changes
+anything becomes a printer_state broadcast. UI clients don't need progress
+and temperature ticks that often, so reports are coalesced per printer: at
+most one broadcast per interval, carrying the latest state and every field
+that changed since the last one sent. Reports changing a field that marks a
+state change (print started or failed, tray switched, ...) go out at once.
+
+There is one coalescer per client class, so the web UI and the ESP32 display
+each get their own rate.
+"""
+
+import asyncio
+import logging
+from collections.abc import Awaitable, Callable
+
+logger = logging.getLogger(__name__)
+
+CLIENT_WEB = "web"
+CLIENT_DEVICE = "device"
+CLIENT_CLASSES = (CLIENT_WEB, CLIENT_DEVICE)
+
+# Changes to these fields are sent right away instead of waiting for the interval
+IMMEDIATE_FIELDS = {
+    "gcode_state",
+    "stg_cur",
+    "print_error",
+    "subtask_name",
+    "vt_tray",
+    "tray_now",
+    "tray_now_left",
+    "tray_now_right",
+    "active_extruder",
+    "nozzles",
+    "speed_level",
+    "skipped_objects",
+}
+
+
+def merge_state_messages(older: dict, newer: dict) -> dict:
+    """The newer printer_state message, with the changes of both."""
+    changes = list(dict.fromkeys([*older.get("changes", []), *newer.get("changes", [])]))
+    return {**newer, "changes": changes}
+
+
+class PrinterStateCoalescer:
+    """Rate-limits printer_state broadcasts per printer for one client class."""
+
+    def __init__(self, interval_ms: int, send: Callable[[dict], Awaitable[None]]):
+        self.interval = interval_ms / 1000
+        self._send = send
+        self._last_sent: dict[str, float] = {}
+        self._pending: dict[str, dict] = {}
+        self._timers: dict[str, asyncio.Task] = {}
+
+    async def submit(self, message: dict):
+        """Send a printer_state message now, or hold it until the printer's interval has passed."""
+        serial = message["serial"]
+        pending = self._pending.pop(serial, None)
+        if pending:
+            message = merge_state_messages(pending, message)
+
+        now = asyncio.get_running_loop().time()
+        wait = self._last_sent.get(serial, float("-inf")) + self.interval - now
+        if wait <= 0 or IMMEDIATE_FIELDS.intersection(message.get("changes", [])):
+            timer = self._timers.pop(serial, None)
+            if timer:
+                timer.cancel()
+            self._last_sent[serial] = now
+            await self._send(message)
+            return
+
+        self._pending[serial] = message
+        if serial not in self._timers:
+            self._timers[serial] = asyncio.create_task(self._flush_later(serial, wait))
+
+    async def _flush_later(self, serial: str, delay: float):
+        await asyncio.sleep(delay)
+        self._timers.pop(serial, None)
+        message = self._pending.pop(serial, None)
+        if message is None:
+            return
+        self._last_sent[serial] = asyncio.get_running_loop().time()
+        try:
+            await self._send(message)
+        except Exception as e:
+            logger.error(f"Failed to broadcast coalesced state of {serial}: {e}")
+
+    def forget(self, serial: str):
+        """Drop a printer's held message and timer (e.g. on disconnect)."""
+        self._pending.pop(serial, None)
+        self._last_sent.pop(serial, None)
+        timer = self._timers.pop(serial, None)
+        if timer:
+            timer.cancel()
@@ -0,0 +1,146 @@
+"""
+Web UI served from a single bundle file.
+
+`python -m services.static_assets <dist dir> <bundle.zip>` packs the built
+frontend into one zip. With SPOOLBUDDY_STATIC_BUNDLE pointing at it, the
+server loads the bundle into memory at startup and serves the UI from
+there, so a deployment needs no frontend directory next to the server.
+
+Vite's content-hashed files under /assets/ are cached for a year; other
+files (index.html above all) are revalidated with their ETag on every load,
+so a new release is picked up immediately. Text assets are gzipped once at
+load time. Paths that aren't files fall back to index.html, so the UI's
+client-side routes survive a reload.
+"""
+
+import gzip
+import hashlib
+import mimetypes
+import sys
+import zipfile
+from dataclasses import dataclass
+from pathlib import Path
+
+# Long-lived caching for content-hashed build output
+IMMUTABLE_PREFIX = "assets/"
+IMMUTABLE_CACHE = "public, max-age=31536000, immutable"
+REVALIDATE_CACHE = "no-cache"
+GZIP_MIN_SIZE = 1024
+_COMPRESSIBLE = ("text/", "application/javascript", "application/json", "image/svg+xml", "application/manifest+json")
+
+
+@dataclass
+class Asset:
+    """A file of the web UI, ready to send."""
+
+    body: bytes
+    gzipped: bytes | None  # None if not worth compressing
+    content_type: str
+    etag: str
+    cache_control: str
+
+
+def _content_type(path: str) -> str:
+    content_type = mimetypes.guess_type(path)[0] or "application/octet-stream"
+    if path.endswith((".js", ".mjs")):
+        content_type = "application/javascript"  # Some platforms map .js to text/plain
+    if content_type.startswith("text/") or content_type == "application/javascript":
+        content_type += "; charset=utf-8"
+    return content_type
+
+
+def make_asset(path: str, body: bytes) -> Asset:
+    """Asset for a file in the bundle."""
+    content_type = _content_type(path)
+    gzipped = None
+    if len(body) >= GZIP_MIN_SIZE and content_type.startswith(_COMPRESSIBLE):
+        gzipped = gzip.compress(body, compresslevel=9, mtime=0)
+        if len(gzipped) >= len(body):
+            gzipped = None
+    return Asset(
+        body=body,
+        gzipped=gzipped,
+        content_type=content_type,
+        etag=f'"{hashlib.sha256(body).hexdigest()[:20]}"',
+        cache_control=IMMUTABLE_CACHE if path.startswith(IMMUTABLE_PREFIX) else REVALIDATE_CACHE,
+    )
+
+
+def build_bundle(dist_dir: Path, bundle_path: Path) -> int:
+    """Pack a built frontend into a bundle file. Returns the number of files."""
+    files = sorted(p for p in dist_dir.rglob("*") if p.is_file())
+    if not any(p.relative_to(dist_dir).as_posix() == "index.html" for p in files):
+        raise FileNotFoundError(f"No index.html in {dist_dir}, is the frontend built?")
+    with zipfile.ZipFile(bundle_path, "w", zipfile.ZIP_DEFLATED) as bundle:
+        for path in files:
+            bundle.write(path, path.relative_to(dist_dir).as_posix())
+    return len(files)
+
+
+def load_bundle(bundle_path: Path) -> dict[str, Asset]:
+    """Assets of a bundle file by path ("index.html", "assets/index-1a2b3c.js")."""
+    with zipfile.ZipFile(bundle_path) as bundle:
+        return {name: make_asset(name, bundle.read(name)) for name in bundle.namelist() if not name.endswith("/")}
+
+
+class EmbeddedStaticFiles:
+    """ASGI app serving the web UI from memory."""
+
+    def __init__(self, bundle_path: Path):
+        self.assets = load_bundle(bundle_path)
+        if "index.html" not in self.assets:
+            raise ValueError(f"{bundle_path} has no index.html")
+
+    def lookup(self, url_path: str) -> Asset | None:
+        """Asset for a request path; index.html for client-side routes, None if not found."""
+        path = url_path.lstrip("/")
+        if path == "" or path.endswith("/"):
+            path += "index.html"
+        asset = self.assets.get(path)
+        if asset is None and "." not in path.rsplit("/", 1)[-1] and not path.startswith(("api/", "ws/")):
+            asset = self.assets["index.html"]
+        return asset
+
+    async def __call__(self, scope, receive, send):
+        assert scope["type"] == "http"  # nosec B101
+        method = scope["method"]
+        request_headers = {k.decode("latin-1").lower(): v.decode("latin-1") for k, v in scope["headers"]}
+
+        if method not in ("GET", "HEAD"):
+            await self._send(send, 405, b"Method Not Allowed", [("allow", "GET, HEAD")])
+            return
+        asset = self.lookup(scope["path"])
+        if asset is None:
+            await self._send(send, 404, b"Not Found", [("content-type", "text/plain; charset=utf-8")])
+            return
+
+        headers = [
+            ("etag", asset.etag),
+            ("cache-control", asset.cache_control),
+            ("vary", "Accept-Encoding"),
+        ]
+        if asset.etag in [tag.strip() for tag in request_headers.get("if-none-match", "").split(",")]:
+            await self._send(send, 304, b"", headers)
+            return
+
+        body = asset.body
+        if asset.gzipped and "gzip" in request_headers.get("accept-encoding", ""):
+            body = asset.gzipped
+            headers.append(("content-encoding", "gzip"))
+        headers.append(("content-type", asset.content_type))
+        await self._send(send, 200, body, headers, head_only=method == "HEAD")
+
+    @staticmethod
+    async def _send(send, status: int, body: bytes, headers: list[tuple[str, str]], head_only: bool = False):
+        raw_headers = [(k.encode("latin-1"), v.encode("latin-1")) for k, v in headers]
+        if status != 304:
+            raw_headers.append((b"content-length", str(len(body)).encode()))
+        await send({"type": "http.response.start", "status": status, "headers": raw_headers})
+        await send({"type": "http.response.body", "body": b"" if head_only or status == 304 else body})
+
+
+if __name__ == "__main__":
+    if len(sys.argv) != 3:
+        sys.exit("Usage: python -m services.static_assets <frontend dist dir> <bundle.zip>")
+    count = build_bundle(Path(sys.argv[1]), Path(sys.argv[2]))
+    print(f"Packed {count} files into {sys.argv[2]}")
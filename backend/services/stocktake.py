@@ -0,0 +1,171 @@
+"""
+Stocktakes: counting the spools that are actually on the shelf.
+
+A stocktake is started, then each spool is scanned, either by putting it
+on the device's scale (its tag is read and the weight recorded) or by
+picking it in the web UI. The report lists the active spools nobody
+scanned and the weighed spools whose filament left differs from the
+inventory by more than a tolerance. Completing a stocktake stores its
+report with it, as the record of the count.
+"""
+
+import time
+from enum import StrEnum
+
+from pydantic import BaseModel
+from services.forecast import remaining_grams
+
+DISCREPANCY_TOLERANCE_GRAMS = 50
+DEVICE_RESCAN_GRAMS = 5  # Device readings this close to the recorded weight don't update the scan
+
+
+class StocktakeStatus(StrEnum):
+    OPEN = "open"
+    COMPLETED = "completed"
+    CANCELLED = "cancelled"
+
+
+class ScanSource(StrEnum):
+    DEVICE = "device"
+    WEB = "web"
+
+
+class Stocktake(BaseModel):
+    """An inventory count."""
+
+    id: int
+    name: str | None = None
+    status: StocktakeStatus
+    scan_count: int = 0  # Spools counted so far
+    started_at: int | None = None
+    finished_at: int | None = None
+
+
+class StocktakeScan(BaseModel):
+    """A spool counted in a stocktake."""
+
+    id: int
+    stocktake_id: int
+    spool_id: str
+    tag_id: str | None = None
+    weight: float | None = None  # Gross scale reading, None if only scanned
+    expected_remaining: float | None = None  # Filament left according to the inventory when scanned
+    source: ScanSource
+    scanned_at: int
+
+
+class StocktakeSpool(BaseModel):
+    """A spool listed in a stocktake report."""
+
+    spool_id: str
+    spool_number: int | None = None
+    brand: str | None = None
+    material: str
+    subtype: str | None = None
+    color_name: str | None = None
+    rgba: str | None = None
+    location: str | None = None
+    expected_remaining: float | None = None
+
+
+class StocktakeDiscrepancy(StocktakeSpool):
+    """A weighed spool whose filament left doesn't match the inventory."""
+
+    weight: float
+    measured_remaining: float
+    difference: float  # Measured minus expected; negative = less filament than recorded
+
+
+class StocktakeReport(BaseModel):
+    """What a stocktake found."""
+
+    stocktake_id: int
+    expected_count: int  # Active spools in the inventory
+    seen_count: int
+    unseen: list[StocktakeSpool] = []
+    discrepancies: list[StocktakeDiscrepancy] = []
+    tolerance: float
+    generated_at: int
+
+
+def _report_spool(spool, expected_remaining: float | None) -> dict:
+    return {
+        "spool_id": spool.id,
+        "spool_number": spool.spool_number,
+        "brand": spool.brand,
+        "material": spool.material,
+        "subtype": spool.subtype,
+        "color_name": spool.color_name,
+        "rgba": spool.rgba,
+        "location": spool.location,
+        "expected_remaining": round(expected_remaining, 1) if expected_remaining is not None else None,
+    }
+
+
+def measured_remaining(spool, weight: float | None) -> float | None:
+    """Filament left on a spool according to a gross scale reading, None if the core weight is unknown."""
+    if weight is None or spool.core_weight is None:
+        return None
+    return max(0.0, weight - spool.core_weight)
+
+
+def build_report(
+    stocktake_id: int, spools, scans: list[dict], tolerance: float = DISCREPANCY_TOLERANCE_GRAMS
+) -> StocktakeReport:
+    """Compare a stocktake's scans with the inventory's active spools."""
+    active = [spool for spool in spools if spool.archived_at is None and spool.deleted_at is None]
+    by_id = {spool.id: spool for spool in spools}
+    scanned = {scan["spool_id"]: scan for scan in scans}
+
+    unseen = [
+        StocktakeSpool(**_report_spool(spool, remaining_grams(spool))) for spool in active if spool.id not in scanned
+    ]
+    discrepancies = []
+    for spool_id, scan in scanned.items():
+        spool = by_id.get(spool_id)
+        measured = measured_remaining(spool, scan["weight"]) if spool else None
+        expected = scan["expected_remaining"]
+        if measured is None or expected is None or abs(measured - expected) <= tolerance:
+            continue
+        discrepancies.append(
+            StocktakeDiscrepancy(
+                **_report_spool(spool, expected),
+                weight=scan["weight"],
+                measured_remaining=round(measured, 1),
+                difference=round(measured - expected, 1),
+            )
+        )
+
+    return StocktakeReport(
+        stocktake_id=stocktake_id,
+        expected_count=len(active),
+        seen_count=len(scanned),
+        unseen=sorted(unseen, key=lambda s: (s.location or "", s.spool_number or 0)),
+        discrepancies=sorted(discrepancies, key=lambda d: -abs(d.difference)),
+        tolerance=tolerance,
+        generated_at=int(time.time()),
+    )
+
+
+async def record_scan(db, stocktake_id: int, spool, weight: float | None, source: ScanSource) -> dict:
+    """Count a spool in a stocktake, with the inventory's idea of its filament left at this moment."""
+    return await db.save_stocktake_scan(stocktake_id, spool.id, spool.tag_id, weight, remaining_grams(spool), source)
+
+
+async def record_device_scan(db, spool, weight: float | None) -> dict | None:
+    """Count a spool read by the device while a stocktake is open.
+
+    The device reports its state continuously, so the scan is only saved
+    when the spool is new to the stocktake or its stable weight changed.
+    Returns the saved scan, None if nothing was recorded.
+    """
+    stocktake = await db.get_open_stocktake()
+    if not stocktake:
+        return None
+    previous = await db.get_stocktake_scan(stocktake["id"], spool.id)
+    if previous:
+        if weight is None:
+            return None
+        if previous["weight"] is not None and abs(previous["weight"] - weight) < DEVICE_RESCAN_GRAMS:
+            return None
+    return await record_scan(db, stocktake["id"], spool, weight, ScanSource.DEVICE)
@@ -0,0 +1,122 @@
+"""
+Filament swap list.
+
+Renders the spools marked as available for swap/sale as JSON, Markdown or
+HTML, ready to paste into a maker community swap thread.
+"""
+
+import html
+import re
+
+from pydantic import BaseModel
+from services.forecast import remaining_grams
+
+
+class SwapListItem(BaseModel):
+    """A spool offered for swap/sale."""
+
+    spool_number: int | None = None
+    brand: str | None = None
+    material: str
+    subtype: str | None = None
+    color_name: str | None = None
+    rgba: str | None = None
+    remaining_grams: int | None = None  # None if label weight unknown
+    label_weight: int | None = None
+    photo_url: str | None = None
+
+
+def swap_list_items(spools) -> list[SwapListItem]:
+    """Active spools marked for swap, grouped by material then brand."""
+    items = []
+    for spool in spools:
+        if not spool.swap_available or spool.archived_at is not None:
+            continue
+        remaining = remaining_grams(spool)
+        items.append(
+            SwapListItem(
+                spool_number=spool.spool_number,
+                brand=spool.brand,
+                material=spool.material,
+                subtype=spool.subtype,
+                color_name=spool.color_name,
+                rgba=spool.rgba,
+                remaining_grams=round(remaining) if remaining is not None else None,
+                label_weight=spool.label_weight,
+                photo_url=_web_url(spool.photo_url),
+            )
+        )
+    return sorted(items, key=lambda i: (i.material, i.brand or "", i.spool_number or 0))
+
+
+def _web_url(url: str | None) -> str | None:
+    """Only http(s) links are published, so a stored URL can't inject script."""
+    if url and url.lower().startswith(("http://", "https://")):
+        return url
+    return None
+
+
+def _name(item: SwapListItem) -> str:
+    parts = [item.brand, item.material, item.subtype, item.color_name]
+    return " ".join(p for p in parts if p)
+
+
+def _remaining(item: SwapListItem) -> str:
+    if item.remaining_grams is None:
+        return "unknown"
+    if item.label_weight:
+        return f"~{item.remaining_grams}g of {item.label_weight}g"
+    return f"~{item.remaining_grams}g"
+
+
+def render_markdown(items: list[SwapListItem], title: str) -> str:
+    """Markdown table, one row per spool, with inline photos."""
+    lines = [f"# {title}", ""]
+    if not items:
+        lines.append("Nothing up for swap right now.")
+        return "\n".join(lines) + "\n"
+
+    lines += ["| # | Filament | Remaining | Photo |", "|---|---|---|---|"]
+    for item in items:
+        number = str(item.spool_number) if item.spool_number is not None else ""
+        photo = f"![photo]({item.photo_url})" if item.photo_url else ""
+        name = _name(item).replace("|", "\\|")
+        lines.append(f"| {number} | {name} | {_remaining(item)} | {photo} |")
+    return "\n".join(lines) + "\n"
+
+
+def render_html(items: list[SwapListItem], title: str) -> str:
+    """Standalone HTML page with a color swatch and photo per spool."""
+    rows = []
+    for item in items:
+        rgba = (item.rgba or "").lstrip("#")
+        color = f"#{rgba[:6]}" if re.fullmatch(r"[0-9A-Fa-f]{6,8}", rgba) else "transparent"
+        photo = (
+            f'<a href="{html.escape(item.photo_url)}"><img src="{html.escape(item.photo_url)}" alt="photo"></a>'
+            if item.photo_url
+            else ""
+        )
+        number = item.spool_number if item.spool_number is not None else ""
+        rows.append(
+            "<tr>"
+            f"<td>{number}</td>"
+            f'<td><span class="swatch" style="background:{color}"></span>{html.escape(_name(item))}</td>'
+            f"<td>{html.escape(_remaining(item))}</td>"
+            f"<td>{photo}</td>"
+            "</tr>"
+        )
+
+    body = (
+        "<table><thead><tr><th>#</th><th>Filament</th><th>Remaining</th><th>Photo</th></tr></thead>"
+        f"<tbody>{''.join(rows)}</tbody></table>"
+        if rows
+        else "<p>Nothing up for swap right now.</p>"
+    )
+    return (
+        "<!DOCTYPE html>\n"
+        f'<html><head><meta charset="utf-8"><title>{html.escape(title)}</title>'
+        "<style>body{font-family:sans-serif}td,th{padding:4px 8px;text-align:left}"
+        "img{max-height:96px}.swatch{display:inline-block;width:12px;height:12px;margin-right:6px;"
+        "border:1px solid #888;vertical-align:middle}</style></head>"
+        f"<body><h1>{html.escape(title)}</h1>{body}</body></html>\n"
+    )
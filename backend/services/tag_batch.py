@@ -0,0 +1,174 @@
+"""
+Batch tag encoding on the SpoolBuddy device.
+
+A tag_batch job (api/tags.py) queues the spools whose tags should be
+written. While it runs, every device state reply carries the current
+EncodeTask: the device prompts "Place tag 3 of 12 for Red PETG", and once a
+usable tag is on the reader the task also carries the bytes to write, built
+for that tag's UID. The device writes them, reads them back and posts the
+outcome to /api/tags/batch/result, which waits until the job has handled
+it so the reply to the device's next state update is already up to date.
+
+The device writes a tag once per placement; after a failure the tag has to
+be put back (or another one placed) to try again.
+"""
+
+import asyncio
+import logging
+from collections.abc import Awaitable, Callable
+from dataclasses import dataclass, field
+
+from models import EncodeResult, EncodeTask
+
+logger = logging.getLogger(__name__)
+
+MAX_ATTEMPTS = 3  # Failed writes before a spool is skipped
+
+
+class TagRefused(Exception):
+    """The tag on the reader can't be written for this spool."""
+
+
+class StationBusy(Exception):
+    """Another tag batch is running."""
+
+
+# Builds the tag data for (spool_id, tag_id); raises TagRefused
+PrepareFunc = Callable[[str, str], Awaitable[bytes]]
+
+
+@dataclass
+class BatchSpool:
+    """A spool queued for encoding."""
+
+    spool_id: str
+    label: str  # e.g. "Red PETG"
+
+
+@dataclass
+class _Batch:
+    job_id: str
+    spools: list[BatchSpool]
+    prepare: PrepareFunc
+    index: int = 0  # Spool being encoded
+    message: str = ""
+    written: set[str] = field(default_factory=set)  # Tags encoded in this batch
+    prepared: dict[str, str | None] = field(default_factory=dict)  # Tag -> hex data for the current spool
+    refused: dict[str, str] = field(default_factory=dict)  # Tag -> why it can't be used
+    results: asyncio.Queue = field(default_factory=asyncio.Queue)
+
+
+class EncodingStation:
+    """The device's batch encoding mode, driven by one tag_batch job at a time."""
+
+    def __init__(self):
+        self._batch: _Batch | None = None
+
+    @property
+    def job_id(self) -> str | None:
+        """Job running the station, None while idle."""
+        return self._batch.job_id if self._batch else None
+
+    def start(self, job_id: str, spools: list[BatchSpool], prepare: PrepareFunc):
+        """Put the device into encoding mode. Raises StationBusy."""
+        if self._batch:
+            raise StationBusy(f"Tag batch {self._batch.job_id} is running")
+        self._batch = _Batch(job_id=job_id, spools=spools, prepare=prepare)
+        logger.info(f"Tag batch {job_id}: encoding {len(spools)} tags")
+
+    def stop(self, job_id: str):
+        """Leave encoding mode, if this job is running it."""
+        if self._batch and self._batch.job_id == job_id:
+            # Don't leave device reports waiting for a job that is gone
+            while not self._batch.results.empty():
+                _, handled = self._batch.results.get_nowait()
+                if not handled.done():
+                    handled.set_result(None)
+            self._batch = None
+
+    def select(self, index: int, message: str | None = None):
+        """Move on to the spool at index (0-based)."""
+        batch = self._batch
+        batch.index = index
+        batch.prepared.clear()
+        batch.refused.clear()
+        spool = batch.spools[index]
+        prompt = f"Place tag {index + 1} of {len(batch.spools)} for {spool.label}"
+        batch.message = f"{message}. {prompt}" if message else prompt
+
+    def retry(self, message: str):
+        """Ask for the current spool's tag again after a failed write."""
+        self._batch.message = message
+
+    def mark_written(self, tag_id: str):
+        """Remember an encoded tag so it isn't written again for the next spool."""
+        self._batch.written.add(tag_id)
+
+    @property
+    def message(self) -> str:
+        return self._batch.message if self._batch else ""
+
+    async def task_for(self, tag_id: str | None) -> EncodeTask | None:
+        """What the device should show (and write) with this tag on the reader, None while idle."""
+        batch = self._batch
+        if batch is None or batch.index >= len(batch.spools):
+            return None
+        spool = batch.spools[batch.index]
+        task = EncodeTask(
+            job_id=batch.job_id,
+            index=batch.index + 1,
+            total=len(batch.spools),
+            spool_id=spool.spool_id,
+            label=spool.label,
+            tag_uid=tag_id,
+            message=batch.message,
+        )
+        if not tag_id:
+            return task
+        if tag_id in batch.written:
+            task.message = f"Remove the tag. {batch.message}"
+            return task
+
+        if tag_id not in batch.prepared:
+            try:
+                batch.prepared[tag_id] = (await batch.prepare(spool.spool_id, tag_id)).hex().upper()
+            except TagRefused as e:
+                batch.prepared[tag_id] = None
+                batch.refused[tag_id] = str(e)
+        task.data = batch.prepared[tag_id]
+        if task.data is None:
+            task.message = f"{batch.refused[tag_id]}. Place another tag"
+        return task
+
+    async def report(self, result: EncodeResult) -> bool:
+        """Hand a device's write result to the job and wait until it's handled.
+
+        False if it doesn't belong to the current spool (stale or unknown job).
+        """
+        batch = self._batch
+        if (
+            batch is None
+            or result.job_id != batch.job_id
+            or result.index != batch.index + 1
+            or not batch.prepared.get(result.tag_uid)
+        ):
+            return False
+        handled = asyncio.get_running_loop().create_future()
+        await batch.results.put((result, handled))
+        await handled
+        return True
+
+    async def next_result(self) -> tuple[EncodeResult, asyncio.Future]:
+        """Wait for the device's next write result; set the future once it's handled."""
+        return await self._batch.results.get()
+
+
+_station: EncodingStation | None = None
+
+
+def get_encoding_station() -> EncodingStation:
+    """Get the singleton encoding station."""
+    global _station
+    if _station is None:
+        _station = EncodingStation()
+    return _station
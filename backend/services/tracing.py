@@ -0,0 +1,264 @@
+"""
+Tracing spans and per-printer log streaming.
+
+A span names a unit of work (an MQTT message, a printer command, an API
+request) and carries the printer serial and request id it belongs to. They
+live in context variables, so every log record emitted inside a span is
+tagged without passing them around. Callbacks that paho hands to the event
+loop with call_soon_threadsafe keep the span they were scheduled from.
+
+PrinterLogHandler keeps each printer's recent tagged records and fans them
+out to log stream subscribers for live debugging from the web UI.
+"""
+
+import asyncio
+import functools
+import inspect
+import logging
+import re
+import threading
+import time
+import uuid
+from collections import deque
+from collections.abc import Callable
+from contextlib import contextmanager
+from contextvars import ContextVar
+from dataclasses import dataclass, field
+
+PRINTER_LOG_BUFFER_SIZE = 500  # Recent records kept per printer
+SUBSCRIBER_QUEUE_SIZE = 1000
+
+_span: ContextVar[str | None] = ContextVar("span", default=None)
+_serial: ContextVar[str | None] = ContextVar("printer_serial", default=None)
+_request_id: ContextVar[str | None] = ContextVar("request_id", default=None)
+
+# Client-supplied request ids end up in log lines, keep them short and plain
+_REQUEST_ID = re.compile(r"[A-Za-z0-9._-]{1,64}")
+
+span_logger = logging.getLogger("spoolbuddy.span")
+
+
+def current_serial() -> str | None:
+    """Printer serial of the current span."""
+    return _serial.get()
+
+
+def current_request_id() -> str | None:
+    """Request id of the current span."""
+    return _request_id.get()
+
+
+def request_id_from(header: str | None) -> str:
+    """A client's X-Request-ID if it is usable, else a new id."""
+    if header and _REQUEST_ID.fullmatch(header):
+        return header
+    return uuid.uuid4().hex[:12]
+
+
+@contextmanager
+def span(name: str, serial: str | None = None, request_id: str | None = None):
+    """Run a block in a span. Serial and request id are inherited from the enclosing span if not given."""
+    tokens = [(_span, _span.set(name))]
+    if serial:
+        tokens.append((_serial, _serial.set(serial)))
+    if request_id:
+        tokens.append((_request_id, _request_id.set(request_id)))
+    start = time.monotonic()
+    try:
+        yield
+    finally:
+        if span_logger.isEnabledFor(logging.DEBUG):
+            span_logger.debug(f"{name} took {(time.monotonic() - start) * 1000:.1f} ms")
+        for var, token in reversed(tokens):
+            var.reset(token)
+
+
+def traced(name: str):
+    """Decorator running a function in a span for its printer.
+
+    The serial is taken from a `serial` argument, or from self.serial for
+    methods of a printer connection.
+    """
+
+    def decorate(func: Callable):
+        params = list(inspect.signature(func).parameters)
+        serial_index = params.index("serial") if "serial" in params else None
+
+        def serial_of(args, kwargs) -> str | None:
+            if "serial" in kwargs:
+                return kwargs["serial"]
+            if serial_index is not None and serial_index < len(args):
+                return args[serial_index]
+            return getattr(args[0], "serial", None) if args else None
+
+        if inspect.iscoroutinefunction(func):
+
+            @functools.wraps(func)
+            async def async_wrapper(*args, **kwargs):
+                with span(name, serial=serial_of(args, kwargs)):
+                    return await func(*args, **kwargs)
+
+            return async_wrapper
+
+        @functools.wraps(func)
+        def wrapper(*args, **kwargs):
+            with span(name, serial=serial_of(args, kwargs)):
+                return func(*args, **kwargs)
+
+        return wrapper
+
+    return decorate
+
+
+class TraceContextFilter(logging.Filter):
+    """Adds the current span's printer serial and request id to log records.
+
+    Formatters can use %(trace)s, which reads " [serial req=id]" inside a
+    span and is empty outside one.
+    """
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        record.printer_serial = _serial.get()
+        record.request_id = _request_id.get()
+        record.span = _span.get()
+        parts = [p for p in (record.printer_serial, record.request_id and f"req={record.request_id}") if p]
+        record.trace = f" [{' '.join(parts)}]" if parts else ""
+        return True
+
+
+@dataclass
+class PrinterLogEvent:
+    """A log record tagged with a printer."""
+
+    timestamp: float
+    level: str
+    levelno: int
+    logger_name: str
+    message: str
+    span: str | None = None
+    request_id: str | None = None
+
+    def to_dict(self) -> dict:
+        return {
+            "timestamp": self.timestamp,
+            "level": self.level,
+            "logger_name": self.logger_name,
+            "message": self.message,
+            "span": self.span,
+            "request_id": self.request_id,
+        }
+
+
+@dataclass(eq=False)
+class LogSubscription:
+    """One client tailing a printer's log."""
+
+    serial: str
+    loop: asyncio.AbstractEventLoop
+    queue: asyncio.Queue = field(default_factory=lambda: asyncio.Queue(maxsize=SUBSCRIBER_QUEUE_SIZE))
+    dropped: bool = False  # Fell too far behind, must reconnect
+
+
+class PrinterLogHandler(logging.Handler):
+    """Buffers log records per printer and streams them to subscribers.
+
+    Records come from any thread (paho's network loop included), so the
+    buffers are locked and subscribers are fed through their event loop.
+    """
+
+    def __init__(self, buffer_size: int = PRINTER_LOG_BUFFER_SIZE):
+        super().__init__()
+        self._buffer_size = buffer_size
+        self._buffers: dict[str, deque[PrinterLogEvent]] = {}
+        self._subscribers: dict[str, set[LogSubscription]] = {}
+        self._records_lock = threading.Lock()
+
+    def emit(self, record: logging.LogRecord):
+        serial = getattr(record, "printer_serial", None) or _serial.get()
+        if not serial:
+            return
+        try:
+            event = PrinterLogEvent(
+                timestamp=record.created,
+                level=record.levelname,
+                levelno=record.levelno,
+                logger_name=record.name,
+                message=record.getMessage(),
+                span=getattr(record, "span", None) or _span.get(),
+                request_id=getattr(record, "request_id", None) or _request_id.get(),
+            )
+        except Exception:
+            self.handleError(record)
+            return
+
+        with self._records_lock:
+            buffer = self._buffers.get(serial)
+            if buffer is None:
+                buffer = self._buffers[serial] = deque(maxlen=self._buffer_size)
+            buffer.append(event)
+            subscribers = list(self._subscribers.get(serial, ()))
+        for sub in subscribers:
+            try:
+                sub.loop.call_soon_threadsafe(self._deliver, sub, event)
+            except RuntimeError:
+                self.unsubscribe(sub)  # Loop closed
+
+    def _deliver(self, sub: LogSubscription, event: PrinterLogEvent):
+        try:
+            sub.queue.put_nowait(event)
+        except asyncio.QueueFull:
+            # No logging here, it would feed back into this handler
+            sub.dropped = True
+            self.unsubscribe(sub)
+
+    def recent(self, serial: str, limit: int | None = None) -> list[PrinterLogEvent]:
+        """A printer's buffered records, oldest first."""
+        with self._records_lock:
+            events = list(self._buffers.get(serial, ()))
+        return events[-limit:] if limit else events
+
+    def subscribe(self, serial: str) -> LogSubscription:
+        """Start receiving a printer's records. Must be called from the event loop."""
+        sub = LogSubscription(serial=serial, loop=asyncio.get_running_loop())
+        with self._records_lock:
+            self._subscribers.setdefault(serial, set()).add(sub)
+        return sub
+
+    def unsubscribe(self, sub: LogSubscription):
+        with self._records_lock:
+            subscribers = self._subscribers.get(sub.serial)
+            if subscribers is not None:
+                subscribers.discard(sub)
+                if not subscribers:
+                    del self._subscribers[sub.serial]
+
+    def close(self):
+        """Drop every subscriber and wake it, so its stream ends (on shutdown). Call from the event loop."""
+        with self._records_lock:
+            subscribers = [sub for subs in self._subscribers.values() for sub in subs]
+            self._subscribers.clear()
+        for sub in subscribers:
+            sub.dropped = True
+            try:
+                sub.queue.put_nowait(None)
+            except asyncio.QueueFull:
+                pass
+
+
+def log_event_matches(event: PrinterLogEvent, min_level: int = logging.NOTSET, search: str | None = None) -> bool:
+    """Whether a record passes a log stream's filters."""
+    if event.levelno < min_level:
+        return False
+    return not search or search.lower() in f"{event.logger_name} {event.message}".lower()
+
+
+# Singleton instance
+_handler: PrinterLogHandler | None = None
+
+
+def get_printer_log_handler() -> PrinterLogHandler:
+    """Get the singleton printer log handler."""
+    global _handler
+    if _handler is None:
+        _handler = PrinterLogHandler()
+    return _handler
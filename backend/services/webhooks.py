@@ -0,0 +1,176 @@
+"""
+Webhook dispatcher for external automations.
+
+Delivers signed JSON payloads to user-registered URLs when server events
+occur (spool running low, about to run out or past its shelf life, print
+finished, printer error, tag scanned, filament runout/jam, printer refusing
+its access code, printer firmware updated).
+"""
+
+import asyncio
+import hashlib
+import hmac
+import json
+import logging
+import time
+
+import httpx
+from db import get_db
+from services.notifiers import get_notification_dispatcher
+
+logger = logging.getLogger(__name__)
+
+# Supported event types
+EVENT_SPOOL_LOW = "spool.low"
+EVENT_SPOOL_DEPLETING = "spool.depleting"
+EVENT_SPOOL_AGING = "spool.aging"
+EVENT_PRINT_FINISHED = "print.finished"
+EVENT_PRINTER_ERROR = "printer.error"
+EVENT_TAG_SCANNED = "tag.scanned"
+EVENT_FILAMENT_RUNOUT = "filament.runout"
+EVENT_PRINTER_AUTH_FAILED = "printer.auth_failed"
+EVENT_PRINTER_FIRMWARE_CHANGED = "printer.firmware_changed"
+
+WEBHOOK_EVENTS = (
+    EVENT_SPOOL_LOW,
+    EVENT_SPOOL_DEPLETING,
+    EVENT_SPOOL_AGING,
+    EVENT_PRINT_FINISHED,
+    EVENT_PRINTER_ERROR,
+    EVENT_TAG_SCANNED,
+    EVENT_FILAMENT_RUNOUT,
+    EVENT_PRINTER_AUTH_FAILED,
+    EVENT_PRINTER_FIRMWARE_CHANGED,
+)
+
+SIGNATURE_HEADER = "X-SpoolBuddy-Signature"
+EVENT_HEADER = "X-SpoolBuddy-Event"
+
+
+def validate_event_types(events: list[str] | None) -> list[str] | None:
+    """Reject unknown or empty event lists (for use in pydantic validators)."""
+    if events is None:
+        return None
+    unknown = [e for e in events if e not in WEBHOOK_EVENTS]
+    if unknown:
+        raise ValueError(f"Unknown event type(s): {', '.join(unknown)}")
+    if not events:
+        raise ValueError("At least one event type is required")
+    return events
+
+
+def sign_payload(secret: str, body: bytes) -> str:
+    """Compute the signature header value for a payload (sha256=<hex hmac>)."""
+    digest = hmac.new(secret.encode(), body, hashlib.sha256).hexdigest()
+    return f"sha256={digest}"
+
+
+class WebhookDispatcher:
+    """Fans out events to subscribed webhooks with retries."""
+
+    def __init__(self, max_attempts: int = 3, retry_delay: float = 2.0, timeout: float = 10.0):
+        self.max_attempts = max_attempts
+        self.retry_delay = retry_delay  # Doubles after each failed attempt
+        self.timeout = timeout
+
+    async def dispatch(self, event: str, data: dict) -> None:
+        """Deliver an event to every enabled webhook subscribed to it.
+
+        Deliveries run as background tasks so slow endpoints never block
+        the caller (MQTT callbacks, device state handling).
+        """
+        try:
+            db = await get_db()
+            webhooks = await db.get_webhooks_for_event(event)
+        except Exception as e:
+            logger.warning(f"Failed to load webhooks for {event}: {e}")
+            return
+
+        for webhook in webhooks:
+            asyncio.create_task(self.deliver(webhook, event, data))
+
+    async def deliver(self, webhook: dict, event: str, data: dict) -> dict:
+        """Deliver a single event to a webhook, retrying on failure.
+
+        Returns:
+            Delivery result with success flag, status code, attempts and error
+        """
+        payload = {"event": event, "timestamp": int(time.time()), "data": data}
+        body = json.dumps(payload).encode("utf-8")
+
+        headers = {
+            "Content-Type": "application/json",
+            "User-Agent": "SpoolBuddy-Webhook/1.0",
+            EVENT_HEADER: event,
+        }
+        if webhook.get("secret"):
+            headers[SIGNATURE_HEADER] = sign_payload(webhook["secret"], body)
+
+        status_code = None
+        error = None
+        attempts = 0
+        delay = self.retry_delay
+
+        async with httpx.AsyncClient(timeout=self.timeout) as client:
+            while attempts < self.max_attempts:
+                attempts += 1
+                try:
+                    response = await client.post(webhook["url"], content=body, headers=headers)
+                    status_code = response.status_code
+                    if 200 <= status_code < 300:
+                        error = None
+                        break
+                    error = f"HTTP {status_code}"
+                except httpx.HTTPError as e:
+                    error = str(e) or type(e).__name__
+
+                if attempts < self.max_attempts:
+                    await asyncio.sleep(delay)
+                    delay *= 2
+
+        success = error is None
+        if success:
+            logger.debug(f"Webhook {webhook['id']} delivered {event} in {attempts} attempt(s)")
+        else:
+            logger.warning(f"Webhook {webhook['id']} failed to deliver {event} after {attempts} attempt(s): {error}")
+
+        try:
+            db = await get_db()
+            await db.log_webhook_delivery(
+                webhook_id=webhook["id"],
+                event=event,
+                payload=body.decode("utf-8"),
+                status_code=status_code,
+                success=success,
+                attempts=attempts,
+                error=error,
+            )
+        except Exception as e:
+            logger.warning(f"Failed to log webhook delivery: {e}")
+
+        return {"success": success, "status_code": status_code, "attempts": attempts, "error": error}
+
+
+# Singleton instance
+_dispatcher: WebhookDispatcher | None = None
+
+
+def get_webhook_dispatcher() -> WebhookDispatcher:
+    """Get the singleton webhook dispatcher."""
+    global _dispatcher
+    if _dispatcher is None:
+        _dispatcher = WebhookDispatcher()
+    return _dispatcher
+
+
+def emit_event(event: str, data: dict) -> None:
+    """Schedule an event dispatch to webhooks and notification channels.
+
+    Safe to call from synchronous code running in the event loop.
+    """
+    try:
+        loop = asyncio.get_running_loop()
+        loop.create_task(get_webhook_dispatcher().dispatch(event, data))
+        loop.create_task(get_notification_dispatcher().dispatch(event, data))
+    except RuntimeError:
+        pass  # No running loop
@@ -0,0 +1,214 @@
+"""
+WLED shelf indicator.
+
+Storage locations and AMS slots are mapped to segments of a WLED LED
+controller. Locating a spool lights the segment of where it is (its AMS
+slot while loaded, else its storage location) and turns the other mapped
+segments off; after a while the controller goes back to the state it was
+in before.
+"""
+
+import asyncio
+import logging
+
+import httpx
+from pydantic import BaseModel, Field, field_validator, model_validator
+from services.slicer import normalize_tray_color
+from services.spool_status import get_spool_status_tracker
+
+logger = logging.getLogger(__name__)
+
+WLED_SETTING = "wled_config"
+WLED_TIMEOUT = 5.0
+
+
+class WledError(Exception):
+    """Raised when the WLED controller can't be reached or refuses a request."""
+
+
+class WledMapping(BaseModel):
+    """A WLED segment lighting a storage location or an AMS slot."""
+
+    segment: int = Field(ge=0)  # WLED segment ID
+    location: str | None = None  # Spool location, matched ignoring case
+    printer_serial: str | None = None
+    ams_id: int | None = None
+    tray_id: int | None = None
+
+    @model_validator(mode="after")
+    def check_target(self):
+        slot = (self.printer_serial, self.ams_id, self.tray_id)
+        if self.location is not None:
+            if any(v is not None for v in slot):
+                raise ValueError("Map a segment to a location or an AMS slot, not both")
+        elif any(v is None for v in slot):
+            raise ValueError("Give a location, or printer_serial, ams_id and tray_id")
+        return self
+
+    def matches_location(self, location: str | None) -> bool:
+        return bool(self.location and location) and self.location.strip().casefold() == location.strip().casefold()
+
+    def matches_slot(self, serial: str | None, ams_id: int | None, tray_id: int | None) -> bool:
+        return self.location is None and (self.printer_serial, self.ams_id, self.tray_id) == (serial, ams_id, tray_id)
+
+
+class WledConfig(BaseModel):
+    """WLED integration settings."""
+
+    enabled: bool = False
+    host: str | None = None  # Controller address, e.g. "192.168.1.50" or "wled-shelf.local"
+    color: str = "00FF00"  # Highlight color
+    brightness: int = Field(default=200, ge=1, le=255)
+    duration: int = Field(default=30, ge=1, le=3600)  # Seconds a position stays lit
+    mappings: list[WledMapping] = []
+
+    @field_validator("color")
+    @classmethod
+    def check_color(cls, v):
+        color = normalize_tray_color(v)
+        if not color:
+            raise ValueError("must be a hex color like 00FF00")
+        return color[:6]
+
+    @field_validator("host")
+    @classmethod
+    def check_host(cls, v):
+        v = (v or "").strip().removeprefix("http://").rstrip("/")
+        return v or None
+
+    @model_validator(mode="after")
+    def check_enabled(self):
+        if self.enabled and not self.host:
+            raise ValueError("host is required to enable WLED")
+        return self
+
+
+class LocateResult(BaseModel):
+    """Where a located spool is and what was lit."""
+
+    spool_id: str
+    location: str | None = None  # Storage location, when lit by location
+    printer_serial: str | None = None  # AMS slot, when lit because the spool is loaded
+    ams_id: int | None = None
+    tray_id: int | None = None
+    segments: list[int] = []  # Lit segments, empty if the position isn't mapped
+
+
+def find_segments(config: WledConfig, spool, live_status=None) -> list[int]:
+    """Segments showing where a spool is: its AMS slot while loaded, else its storage location."""
+    if live_status is not None and live_status.printer_serial:
+        return [
+            m.segment
+            for m in config.mappings
+            if m.matches_slot(live_status.printer_serial, live_status.ams_id, live_status.tray_id)
+        ]
+    return [m.segment for m in config.mappings if m.matches_location(spool.location)]
+
+
+def highlight_payload(config: WledConfig, segments: list[int]) -> dict:
+    """WLED JSON state lighting the given segments in the highlight color, other mapped segments off."""
+    rgb = [int(config.color[i : i + 2], 16) for i in (0, 2, 4)]
+    lit = sorted(set(segments))
+    dark = sorted({m.segment for m in config.mappings} - set(lit))
+    return {
+        "on": True,
+        "bri": config.brightness,
+        "seg": [{"id": s, "on": True, "col": [rgb], "fx": 0} for s in lit] + [{"id": s, "on": False} for s in dark],
+    }
+
+
+def restore_payload(state: dict) -> dict:
+    """WLED JSON state putting the controller back as it was (from GET /json/state)."""
+    segments = [
+        {key: seg[key] for key in ("id", "on", "bri", "col", "fx", "sx", "ix", "pal") if key in seg}
+        for seg in state.get("seg", [])
+    ]
+    return {"on": state.get("on", False), "bri": state.get("bri", 128), "seg": segments}
+
+
+class WledController:
+    """Lights segments on the WLED controller, restoring its previous state afterwards."""
+
+    def __init__(self):
+        self._saved_state: dict | None = None  # State before the current highlight
+        self._saved_host: str | None = None
+        self._restore_task: asyncio.Task | None = None
+
+    async def _request(self, host: str, payload: dict | None = None) -> dict:
+        url = f"http://{host}/json/state"
+        try:
+            async with httpx.AsyncClient(timeout=WLED_TIMEOUT) as client:
+                response = await client.post(url, json=payload) if payload is not None else await client.get(url)
+        except httpx.HTTPError as e:
+            raise WledError(f"WLED at {host} is unreachable: {e}") from e
+        if response.status_code != 200:
+            raise WledError(f"WLED at {host} answered HTTP {response.status_code}")
+        return response.json()
+
+    async def highlight(self, config: WledConfig, segments: list[int]):
+        """Light segments for the configured duration. Raises WledError if the controller fails."""
+        if self._restore_task:
+            self._restore_task.cancel()
+            self._restore_task = None
+        # The state from before the first of back-to-back highlights is the one to go back to
+        if self._saved_state is None or self._saved_host != config.host:
+            self._saved_state = await self._request(config.host)
+            self._saved_host = config.host
+        await self._request(config.host, highlight_payload(config, segments))
+        self._restore_task = asyncio.create_task(self._restore_after(config.duration))
+
+    async def _restore_after(self, delay: float):
+        await asyncio.sleep(delay)
+        self._restore_task = None
+        await self.restore()
+
+    async def restore(self):
+        """End the highlight now, putting the controller back as it was."""
+        if self._restore_task:
+            self._restore_task.cancel()
+            self._restore_task = None
+        state, host = self._saved_state, self._saved_host
+        self._saved_state = self._saved_host = None
+        if state is None:
+            return
+        try:
+            await self._request(host, restore_payload(state))
+        except WledError as e:
+            logger.warning(f"Failed to restore WLED state: {e}")
+
+
+async def get_wled_config(db) -> WledConfig:
+    """Configured WLED integration."""
+    value = await db.get_setting(WLED_SETTING)
+    return WledConfig.model_validate_json(value) if value else WledConfig()
+
+
+async def locate_spool(db, spool, config: WledConfig | None = None) -> LocateResult:
+    """Light the position of a spool. Raises WledError if the controller fails."""
+    config = config or await get_wled_config(db)
+    tracker = get_spool_status_tracker()
+    status = tracker.status_of(spool.id, await tracker.current(db))
+    loaded = status.printer_serial is not None
+    result = LocateResult(
+        spool_id=spool.id,
+        location=None if loaded else spool.location,
+        printer_serial=status.printer_serial,
+        ams_id=status.ams_id,
+        tray_id=status.tray_id,
+        segments=find_segments(config, spool, status),
+    )
+    if config.enabled and result.segments:
+        await get_wled_controller().highlight(config, result.segments)
+    return result
+
+
+# Singleton instance
+_controller: WledController | None = None
+
+
+def get_wled_controller() -> WledController:
+    """Get the singleton WLED controller."""
+    global _controller
+    if _controller is None:
+        _controller = WledController()
+    return _controller
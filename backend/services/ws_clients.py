@@ -0,0 +1,109 @@
+"""
+Connected /ws/ui clients and who they are.
+
+Each WebSocket connection is registered with its identity (the API key it
+authenticated with during the handshake, anonymous without one), its client
+class, address and last activity. Clients can narrow the printer_state
+messages they get to some printers by sending
+{"type": "subscribe", "printers": ["<serial>", ...]} (null for all again).
+"""
+
+import time
+import uuid
+
+from pydantic import BaseModel
+
+ANONYMOUS = "anonymous"
+
+
+class WebSocketClientInfo(BaseModel):
+    """A connected WebSocket client."""
+
+    id: str
+    identity: str  # Name of the API key it connected with, "anonymous" without one
+    api_key_id: int | None = None
+    client_class: str  # web or device
+    address: str | None = None
+    user_agent: str | None = None
+    connected_at: int
+    last_activity: int  # Connected or last message received
+    messages_received: int = 0
+    printers: list[str] | None = None  # printer_state subscription, None = all printers
+
+
+class WebSocketClients:
+    """Registry of the connected WebSocket clients."""
+
+    def __init__(self):
+        self._clients: dict[object, WebSocketClientInfo] = {}
+
+    def add(
+        self,
+        websocket,
+        client_class: str,
+        api_key: dict | None = None,
+        address: str | None = None,
+        user_agent: str | None = None,
+    ) -> WebSocketClientInfo:
+        now = int(time.time())
+        info = WebSocketClientInfo(
+            id=uuid.uuid4().hex[:12],
+            identity=api_key["name"] if api_key else ANONYMOUS,
+            api_key_id=api_key["id"] if api_key else None,
+            client_class=client_class,
+            address=address,
+            user_agent=user_agent,
+            connected_at=now,
+            last_activity=now,
+        )
+        self._clients[websocket] = info
+        return info
+
+    def remove(self, websocket):
+        self._clients.pop(websocket, None)
+
+    def clear(self) -> list:
+        """Forget all clients; returns their sockets."""
+        websockets = list(self._clients)
+        self._clients.clear()
+        return websockets
+
+    def touch(self, websocket):
+        """Record a message received from the client."""
+        info = self._clients.get(websocket)
+        if info:
+            info.last_activity = int(time.time())
+            info.messages_received += 1
+
+    def subscribe(self, websocket, printers: list[str] | None):
+        """Limit the client's printer_state messages to these printers (None = all)."""
+        info = self._clients.get(websocket)
+        if info:
+            info.printers = sorted(set(printers)) if printers is not None else None
+
+    def targets(self, client_class: str | None = None, printer_serial: str | None = None) -> list:
+        """Sockets of the clients of a class (None = all) that follow the printer, if given."""
+        return [
+            websocket
+            for websocket, info in self._clients.items()
+            if client_class in (None, info.client_class)
+            and (printer_serial is None or info.printers is None or printer_serial in info.printers)
+        ]
+
+    def snapshot(self) -> list[WebSocketClientInfo]:
+        """Connected clients, longest connected first."""
+        return sorted((info.model_copy() for info in self._clients.values()), key=lambda info: info.connected_at)
+
+    def __len__(self) -> int:
+        return len(self._clients)
+
+
+_ws_clients: WebSocketClients | None = None
+
+
+def get_ws_clients() -> WebSocketClients:
+    """Get the global WebSocket client registry."""
+    global _ws_clients
+    if _ws_clients is None:
+        _ws_clients = WebSocketClients()
+    return _ws_clients
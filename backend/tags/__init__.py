@@ -3,15 +3,21 @@
 Supports:
 - SpoolEase V2 tags (NTAG with NDEF URL)
 - Bambu Lab tags (Mifare Classic 1K)
+- Creality CFS tags (Mifare Classic 1K)
 - OpenPrintTag tags (NTAG with NDEF CBOR)
 - OpenSpool tags (NTAG with NDEF JSON)
 - OpenTag3D tags (NTAG with NDEF binary)
+
+Formats are picked by the decoder registry (tags.registry).
 """
 
 from .bambulab import BambuLabDecoder
+from .creality import CrealityDecoder
 from .decoder import TagDecoder
+from .dump import TagDump
 from .models import (
     BambuLabTagData,
+    CrealityTagData,
     OpenPrintTagData,
     OpenSpoolTagData,
     SpoolEaseTagData,
@@ -21,6 +27,7 @@ from .models import (
 from .openprinttag import OpenPrintTagDecoder
 from .openspool import OpenSpoolDecoder
 from .opentag3d import OpenTag3DDecoder, OpenTag3DTagData
+from .registry import DecoderRegistry, FormatDecoder, RawTag, get_decoder_registry
 from .spoolease_format import SpoolEaseDecoder, SpoolEaseEncoder
 
 __all__ = [
@@ -28,14 +35,21 @@ __all__ = [
     "TagReadResult",
     "SpoolEaseTagData",
     "BambuLabTagData",
+    "CrealityTagData",
     "OpenPrintTagData",
     "OpenSpoolTagData",
     "OpenTag3DTagData",
     "SpoolEaseDecoder",
     "SpoolEaseEncoder",
     "BambuLabDecoder",
+    "CrealityDecoder",
     "OpenPrintTagDecoder",
     "OpenSpoolDecoder",
     "OpenTag3DDecoder",
     "TagDecoder",
+    "TagDump",
+    "DecoderRegistry",
+    "FormatDecoder",
+    "RawTag",
+    "get_decoder_registry",
 ]
@@ -0,0 +1,73 @@
+"""Creality CFS RFID tag decoder.
+
+Creality uses Mifare Classic 1K tags. Sector 1 (blocks 4-6) holds a
+48-character ASCII record:
+
+  - 0-4: Date code (e.g., "AB124")
+  - 5-8: Vendor ID (hex, e.g., "0276")
+  - 9-10: Batch
+  - 11-16: Filament ID (e.g., "101001")
+  - 17-23: Color as "0RRGGBB"
+  - 24-27: Filament length in meters (e.g., "0330")
+  - 28-33: Serial number
+  - 34-47: Reserved
+
+Tags written encrypted don't parse as this record and are left to the
+other decoders.
+"""
+
+import re
+
+from .models import CrealityTagData, SpoolFromTag, TagType
+
+# Fields up to the length, all the Pico bridge reads (blocks 4 and 5)
+_RECORD = re.compile(r"[0-9A-Za-z]{5}[0-9A-Fa-f]{4}[0-9A-Za-z]{8}0([0-9A-Fa-f]{6})([0-9]{4})")
+
+
+class CrealityDecoder:
+    """Decoder for Creality CFS tags."""
+
+    @staticmethod
+    def record(blocks: dict[int, bytes]) -> str | None:
+        """The sector 1 record, None if the blocks don't hold one."""
+        data = b"".join(blocks.get(block, b"") for block in (4, 5, 6))
+        text = data.decode("ascii", errors="replace")
+        return text if _RECORD.match(text) else None
+
+    @staticmethod
+    def decode(uid_hex: str, blocks: dict[int, bytes]) -> CrealityTagData | None:
+        """Decode Creality tag blocks to tag data.
+
+        Args:
+            uid_hex: Hex-encoded tag UID
+            blocks: Dict mapping block number to block data (16 bytes each)
+
+        Returns:
+            Parsed tag data, or None if the blocks don't hold a Creality record
+        """
+        text = CrealityDecoder.record(blocks)
+        if text is None:
+            return None
+        serial = text[28:34]
+        return CrealityTagData(
+            tag_id=uid_hex.upper(),
+            date_code=text[0:5],
+            vendor_id=text[5:9],
+            batch=text[9:11],
+            filament_id=text[11:17],
+            color_rgba=text[18:24].upper() + "FF",
+            length_m=int(text[24:28]),
+            serial=serial if serial.isalnum() and len(serial) == 6 else None,
+        )
+
+    @staticmethod
+    def to_spool(data: CrealityTagData) -> SpoolFromTag:
+        """Convert Creality tag data to normalized spool data."""
+        return SpoolFromTag(
+            tag_id=data.tag_id,
+            tag_type=TagType.CREALITY.value,
+            rgba=data.color_rgba,
+            brand="Creality",
+            note="Missing: Material, Label Weight",
+            data_origin=TagType.CREALITY.value,
+        )
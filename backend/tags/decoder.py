@@ -4,6 +4,8 @@ import base64
 import logging
 
 from .bambulab import BambuLabDecoder
+from .creality import CrealityDecoder
+from .dump import TagDump, ndef_message_from_pages, parse_ndef_message
 from .models import (
     NfcTagType,
     SpoolFromTag,
@@ -13,6 +15,7 @@ from .models import (
 from .openprinttag import OpenPrintTagDecoder
 from .openspool import OpenSpoolDecoder
 from .opentag3d import OpenTag3DDecoder
+from .registry import URL_PREFIXES, RawTag, get_decoder_registry
 from .spoolease_format import SpoolEaseDecoder
 
 logger = logging.getLogger(__name__)
@@ -43,15 +46,7 @@ class TagDecoder:
             tag_type=TagType.UNKNOWN,
         )
 
-        # Try SpoolEase decoder
-        if SpoolEaseDecoder.can_decode(url):
-            spoolease_data = SpoolEaseDecoder.decode(url, uid_hex)
-            if spoolease_data:
-                result.tag_type = TagType.SPOOLEASE_V2 if spoolease_data.version == 2 else TagType.SPOOLEASE_V1
-                result.spoolease_data = spoolease_data
-                return result
-
-        # Unknown NDEF URL
+        get_decoder_registry().decode(RawTag(uid_hex, result.nfc_type, records=[("U", b"\x00" + url.encode())]), result)
         return result
 
     @staticmethod
@@ -75,61 +70,26 @@ class TagDecoder:
             tag_type=TagType.UNKNOWN,
         )
 
+        records = []
         for record in ndef_records:
             record_type = record.get("type", b"")
-            payload = record.get("payload", b"")
-
             if isinstance(record_type, bytes):
                 record_type = record_type.decode("utf-8", errors="ignore")
+            records.append((record_type, record.get("payload", b"")))
 
-            # Check for OpenPrintTag
-            if record_type == OpenPrintTagDecoder.RECORD_TYPE:
-                openprinttag_data = OpenPrintTagDecoder.decode(uid_hex, payload)
-                if openprinttag_data:
-                    result.tag_type = TagType.OPENPRINTTAG
-                    result.openprinttag_data = openprinttag_data
-                    return result
-
-            # Check for OpenSpool (application/json with protocol: openspool)
-            if record_type == OpenSpoolDecoder.RECORD_TYPE:
-                if OpenSpoolDecoder.can_decode_payload(payload):
-                    openspool_data = OpenSpoolDecoder.decode(uid_hex, payload)
-                    if openspool_data:
-                        result.tag_type = TagType.OPENSPOOL
-                        result.openspool_data = openspool_data
-                        return result
-
-            # Check for OpenTag3D (application/opentag3d binary format)
-            if record_type == OpenTag3DDecoder.RECORD_TYPE:
-                opentag3d_data = OpenTag3DDecoder.decode(uid_hex, payload)
-                if opentag3d_data:
-                    result.tag_type = TagType.OPENTAG3D
-                    result.opentag3d_data = opentag3d_data.__dict__
-                    return result
-
-            # Check for URL record (SpoolEase)
-            if record_type == "U" or record_type.startswith("urn:nfc:wkt:U"):
-                # URL record - payload starts with prefix byte
-                if payload:
-                    url = TagDecoder._decode_ndef_url_payload(payload)
-                    if url and SpoolEaseDecoder.can_decode(url):
-                        spoolease_data = SpoolEaseDecoder.decode(url, uid_hex)
-                        if spoolease_data:
-                            result.tag_type = (
-                                TagType.SPOOLEASE_V2 if spoolease_data.version == 2 else TagType.SPOOLEASE_V1
-                            )
-                            result.spoolease_data = spoolease_data
-                            return result
-
+        get_decoder_registry().decode(RawTag(uid_hex, result.nfc_type, records=records), result)
         return result
 
     @staticmethod
-    def decode_mifare_blocks(uid_hex: str, blocks: dict[int, bytes]) -> TagReadResult | None:
-        """Decode Mifare Classic blocks (Bambu Lab tags).
+    def decode_mifare_blocks(
+        uid_hex: str, blocks: dict[int, bytes], nfc_type: NfcTagType = NfcTagType.MIFARE_CLASSIC_1K
+    ) -> TagReadResult | None:
+        """Decode Mifare Classic blocks (Bambu Lab and Creality tags).
 
         Args:
             uid_hex: Hex-encoded tag UID
             blocks: Dict mapping block number to 16-byte block data
+            nfc_type: Mifare Classic variant
 
         Returns:
             TagReadResult with parsed data
@@ -140,20 +100,57 @@ class TagDecoder:
         result = TagReadResult(
             uid=uid_hex.upper(),
             uid_base64=uid_base64,
-            nfc_type=NfcTagType.MIFARE_CLASSIC_1K,
+            nfc_type=nfc_type,
             tag_type=TagType.UNKNOWN,
             mifare_blocks=blocks,
         )
 
-        # Try Bambu Lab decoder
-        bambulab_data = BambuLabDecoder.decode(uid_hex, blocks)
-        if bambulab_data and bambulab_data.material_id:
-            result.tag_type = TagType.BAMBULAB
-            result.bambulab_data = bambulab_data
-            return result
+        get_decoder_registry().decode(RawTag(uid_hex, result.nfc_type, blocks=blocks), result)
+        return result
 
+    @staticmethod
+    def decode_ntag_pages(uid_hex: str, pages: dict[int, bytes]) -> TagReadResult | None:
+        """Decode a raw NTAG page dump by extracting its NDEF message.
+
+        Args:
+            uid_hex: Hex-encoded tag UID
+            pages: Dict mapping page number to 4-byte page data
+
+        Returns:
+            TagReadResult with parsed data
+        """
+        ndef_message = ndef_message_from_pages(pages)
+        records = []
+        if ndef_message:
+            try:
+                records = parse_ndef_message(ndef_message)
+            except IndexError:
+                logger.warning("Truncated NDEF message in tag dump")
+
+        result = TagDecoder.decode_ndef_records(uid_hex, records)
+        result.ndef_message = ndef_message
         return result
 
+    @staticmethod
+    def decode_dump(dump: TagDump, uid_hex: str | None = None) -> TagReadResult | None:
+        """Decode a parsed raw memory dump.
+
+        Args:
+            dump: Parsed dump (see tags.dump)
+            uid_hex: Hex-encoded tag UID, overriding the UID found in the dump
+
+        Returns:
+            TagReadResult with parsed data, or None if no UID is known
+        """
+        uid_hex = uid_hex or dump.uid
+        if not uid_hex:
+            return None
+
+        if dump.nfc_type == NfcTagType.NTAG:
+            return TagDecoder.decode_ntag_pages(uid_hex, dump.blocks)
+
+        return TagDecoder.decode_mifare_blocks(uid_hex, dump.blocks, dump.nfc_type)
+
     @staticmethod
     def to_spool(result: TagReadResult) -> SpoolFromTag | None:
         """Convert TagReadResult to normalized SpoolFromTag.
@@ -172,6 +169,10 @@ class TagDecoder:
             if result.bambulab_data:
                 return BambuLabDecoder.to_spool(result.bambulab_data)
 
+        elif result.tag_type == TagType.CREALITY:
+            if result.creality_data:
+                return CrealityDecoder.to_spool(result.creality_data)
+
         elif result.tag_type == TagType.OPENPRINTTAG:
             if result.openprinttag_data:
                 return OpenPrintTagDecoder.to_spool(result.openprinttag_data)
@@ -194,58 +195,10 @@ class TagDecoder:
     def _decode_ndef_url_payload(payload: bytes) -> str | None:
         """Decode NDEF URL payload to string.
 
-        NDEF URL records have a prefix byte indicating the URL scheme:
-        0x00: No prefix
-        0x01: http://www.
-        0x02: https://www.
-        0x03: http://
-        0x04: https://
-        ... etc
+        NDEF URL records have a prefix byte indicating the URL scheme
+        (see registry.URL_PREFIXES).
         """
-        URL_PREFIXES = {
-            0x00: "",
-            0x01: "http://www.",
-            0x02: "https://www.",
-            0x03: "http://",
-            0x04: "https://",
-            0x05: "tel:",
-            0x06: "mailto:",
-            0x07: "ftp://anonymous:anonymous@",
-            0x08: "ftp://ftp.",
-            0x09: "ftps://",
-            0x0A: "sftp://",
-            0x0B: "smb://",
-            0x0C: "nfs://",
-            0x0D: "ftp://",
-            0x0E: "dav://",
-            0x0F: "news:",
-            0x10: "telnet://",
-            0x11: "imap:",
-            0x12: "rtsp://",
-            0x13: "urn:",
-            0x14: "pop:",
-            0x15: "sip:",
-            0x16: "sips:",
-            0x17: "tftp:",
-            0x18: "btspp://",
-            0x19: "btl2cap://",
-            0x1A: "btgoep://",
-            0x1B: "tcpobex://",
-            0x1C: "irdaobex://",
-            0x1D: "file://",
-            0x1E: "urn:epc:id:",
-            0x1F: "urn:epc:tag:",
-            0x20: "urn:epc:pat:",
-            0x21: "urn:epc:raw:",
-            0x22: "urn:epc:",
-            0x23: "urn:nfc:",
-        }
-
         if not payload:
             return None
 
-        prefix_byte = payload[0]
-        url_part = payload[1:].decode("utf-8", errors="ignore")
-
-        prefix = URL_PREFIXES.get(prefix_byte, "")
-        return prefix + url_part
+        return URL_PREFIXES.get(payload[0], "") + payload[1:].decode("utf-8", errors="ignore")
@@ -0,0 +1,236 @@
+"""Raw tag memory dump parsing.
+
+Turns raw memory dumps into the block maps and NDEF records understood by
+the tag decoders. Supported inputs:
+
+- Block/page maps as sent by the Pico bridge ({block_number: hex})
+- Flipper Zero .nfc files ("UID: ..", "Block N: ..", "Page N: ..")
+- Proxmark3 .eml files (one hex block/page per line)
+- Proxmark3 .bin files (raw memory image)
+
+Mifare Classic dumps are split into 16-byte blocks, NTAG dumps into 4-byte
+pages. Unreadable blocks ("??" in Flipper dumps) are skipped.
+
+The reverse direction, an NDEF message wrapped in its TLV as written to
+NTAG user memory, is built by ndef_record and ndef_tlv.
+"""
+
+import logging
+import re
+
+from pydantic import BaseModel
+
+from .models import NfcTagType
+
+logger = logging.getLogger(__name__)
+
+MIFARE_BLOCK_SIZE = 16
+NTAG_PAGE_SIZE = 4
+NTAG_NDEF_START_PAGE = 4  # Pages 0-3 hold UID, lock bytes and capability container
+
+MIFARE_1K_SIZE = 1024
+MIFARE_4K_SIZE = 4096
+
+NDEF_TLV = 0x03
+TERMINATOR_TLV = 0xFE
+NULL_TLV = 0x00
+
+_FLIPPER_LINE = re.compile(r"^(Block|Page)\s+(\d+)\s*:\s*(.+)$", re.IGNORECASE)
+_FLIPPER_UID = re.compile(r"^UID\s*:\s*(.+)$", re.IGNORECASE)
+
+
+class TagDump(BaseModel):
+    """Parsed raw tag memory."""
+
+    uid: str | None = None  # Hex-encoded UID, if present in the dump
+    nfc_type: NfcTagType = NfcTagType.UNKNOWN
+    blocks: dict[int, bytes] = {}  # Mifare blocks (16 bytes) or NTAG pages (4 bytes)
+
+
+def _hex_bytes(value: str) -> bytes | None:
+    """Parse a hex string with optional separators; None if it has unknown bytes."""
+    cleaned = re.sub(r"[\s:\-]", "", value)
+    if "?" in cleaned:
+        return None
+    return bytes.fromhex(cleaned)
+
+
+def _mifare_type(blocks: dict[int, bytes]) -> NfcTagType:
+    """Pick Mifare 1K or 4K based on the highest block number."""
+    return NfcTagType.MIFARE_CLASSIC_4K if blocks and max(blocks) >= 64 else NfcTagType.MIFARE_CLASSIC_1K
+
+
+def uid_from_blocks(nfc_type: NfcTagType, blocks: dict[int, bytes]) -> str | None:
+    """Extract the UID from manufacturer data.
+
+    Mifare Classic stores a 4-byte UID at the start of block 0. NTAG stores
+    a 7-byte UID across pages 0-1, with a check byte at page 0 offset 3.
+    """
+    if nfc_type == NfcTagType.NTAG:
+        if 0 in blocks and 1 in blocks:
+            return (blocks[0][0:3] + blocks[1][0:4]).hex().upper()
+    elif nfc_type != NfcTagType.UNKNOWN and 0 in blocks:
+        return blocks[0][0:4].hex().upper()
+    return None
+
+
+def parse_block_map(blocks: dict[int, str], nfc_type: NfcTagType) -> TagDump:
+    """Parse a {block_number: hex} map, as produced by the Pico bridge."""
+    parsed = {}
+    for number, data in blocks.items():
+        block = _hex_bytes(data)
+        if block is not None:
+            parsed[int(number)] = block
+    if nfc_type != NfcTagType.NTAG:
+        nfc_type = _mifare_type(parsed)
+    return TagDump(uid=uid_from_blocks(nfc_type, parsed), nfc_type=nfc_type, blocks=parsed)
+
+
+def parse_dump_text(text: str) -> TagDump:
+    """Parse a Flipper Zero .nfc or Proxmark3 .eml text dump."""
+    lines = [line.strip() for line in text.splitlines() if line.strip() and not line.lstrip().startswith("#")]
+    uid = None
+    blocks: dict[int, bytes] = {}
+    is_ntag = False
+
+    if any(_FLIPPER_LINE.match(line) for line in lines):
+        for line in lines:
+            if match := _FLIPPER_UID.match(line):
+                uid = re.sub(r"[\s:]", "", match.group(1)).upper()
+            elif match := _FLIPPER_LINE.match(line):
+                is_ntag = is_ntag or match.group(1).lower() == "page"
+                block = _hex_bytes(match.group(3))
+                if block is not None:
+                    blocks[int(match.group(2))] = block
+    else:
+        # Proxmark .eml: one block (32 hex chars) or page (8 hex chars) per line
+        for number, line in enumerate(lines):
+            block = _hex_bytes(line)
+            if block is None:
+                continue
+            is_ntag = len(block) == NTAG_PAGE_SIZE
+            blocks[number] = block
+
+    nfc_type = NfcTagType.NTAG if is_ntag else _mifare_type(blocks)
+    return TagDump(uid=uid or uid_from_blocks(nfc_type, blocks), nfc_type=nfc_type, blocks=blocks)
+
+
+def parse_dump_bytes(data: bytes) -> TagDump:
+    """Parse a raw binary memory image (Proxmark3 .bin)."""
+    if len(data) in (MIFARE_1K_SIZE, MIFARE_4K_SIZE):
+        size = MIFARE_BLOCK_SIZE
+        nfc_type = NfcTagType.MIFARE_CLASSIC_1K if len(data) == MIFARE_1K_SIZE else NfcTagType.MIFARE_CLASSIC_4K
+    elif len(data) % NTAG_PAGE_SIZE == 0:
+        size = NTAG_PAGE_SIZE
+        nfc_type = NfcTagType.NTAG
+    else:
+        raise ValueError(f"Unrecognized dump size: {len(data)} bytes")
+
+    blocks = {i // size: data[i : i + size] for i in range(0, len(data), size)}
+    return TagDump(uid=uid_from_blocks(nfc_type, blocks), nfc_type=nfc_type, blocks=blocks)
+
+
+def ndef_message_from_pages(pages: dict[int, bytes]) -> bytes | None:
+    """Extract the NDEF message from the NTAG user memory TLV area."""
+    memory = bytearray()
+    page = NTAG_NDEF_START_PAGE
+    while page in pages:
+        memory += pages[page]
+        page += 1
+
+    pos = 0
+    while pos < len(memory):
+        tlv_type = memory[pos]
+        if tlv_type == NULL_TLV:
+            pos += 1
+            continue
+        if tlv_type == TERMINATOR_TLV or pos + 1 >= len(memory):
+            return None
+
+        length = memory[pos + 1]
+        pos += 2
+        if length == 0xFF:  # 3-byte length format
+            if pos + 2 > len(memory):
+                return None
+            length = int.from_bytes(memory[pos : pos + 2], "big")
+            pos += 2
+
+        if tlv_type == NDEF_TLV:
+            if pos + length > len(memory):
+                logger.warning("NDEF TLV extends past end of dump")
+                return None
+            return bytes(memory[pos : pos + length])
+        pos += length
+    return None
+
+
+def parse_ndef_message(message: bytes) -> list[dict]:
+    """Split an NDEF message into records ({"type": str, "payload": bytes})."""
+    records = []
+    pos = 0
+    while pos < len(message):
+        header = message[pos]
+        short_record = bool(header & 0x10)
+        has_id = bool(header & 0x08)
+        pos += 1
+
+        type_length = message[pos]
+        pos += 1
+        if short_record:
+            payload_length = message[pos]
+            pos += 1
+        else:
+            payload_length = int.from_bytes(message[pos : pos + 4], "big")
+            pos += 4
+        id_length = 0
+        if has_id:
+            id_length = message[pos]
+            pos += 1
+
+        record_type = message[pos : pos + type_length].decode("utf-8", errors="ignore")
+        pos += type_length + id_length
+        payload = message[pos : pos + payload_length]
+        pos += payload_length
+
+        records.append({"type": record_type, "payload": payload})
+        if header & 0x40:  # Message end
+            break
+    return records
+
+
+# URI identifier codes for NDEF URL records, longest prefixes first
+_URL_PREFIXES = [(0x02, "https://www."), (0x01, "http://www."), (0x04, "https://"), (0x03, "http://")]
+
+
+def ndef_record(record_type: str, payload: bytes | str) -> bytes:
+    """Encode a single-record NDEF message.
+
+    "U" makes a well-known URL record (payload is the URL), anything else a
+    MIME record of that media type.
+    """
+    if record_type == "U":
+        url = payload.decode() if isinstance(payload, bytes) else payload
+        code, prefix = next(((c, p) for c, p in _URL_PREFIXES if url.startswith(p)), (0x00, ""))
+        payload = bytes([code]) + url[len(prefix) :].encode()
+        tnf = 0x01  # NFC Forum well-known type
+    else:
+        payload = payload.encode() if isinstance(payload, str) else payload
+        tnf = 0x02  # MIME media type
+
+    header = 0x80 | 0x40 | tnf  # Message begin, message end
+    if len(payload) < 256:
+        length = bytes([len(payload)])
+        header |= 0x10  # Short record
+    else:
+        length = len(payload).to_bytes(4, "big")
+    record_type_bytes = record_type.encode()
+    return bytes([header, len(record_type_bytes)]) + length + record_type_bytes + payload
+
+
+def ndef_tlv(message: bytes) -> bytes:
+    """Wrap an NDEF message in its TLV plus terminator, as stored from NTAG page 4 on."""
+    if len(message) < 0xFF:
+        length = bytes([len(message)])
+    else:
+        length = b"\xff" + len(message).to_bytes(2, "big")
+    return bytes([NDEF_TLV]) + length + message + bytes([TERMINATOR_TLV])
@@ -14,6 +14,7 @@ class TagType(StrEnum):
     OPENPRINTTAG = "OpenPrintTag"
     OPENSPOOL = "OpenSpool"
     OPENTAG3D = "OpenTag3D"
+    CREALITY = "Creality"
     UNKNOWN = "Unknown"
 
 
@@ -92,6 +93,19 @@ class OpenSpoolTagData(BaseModel):
     max_temp: int | None = None  # Maximum print temperature
 
 
+class CrealityTagData(BaseModel):
+    """Parsed data from a Creality CFS tag."""
+
+    tag_id: str  # Hex-encoded UID
+    date_code: str | None = None  # e.g., "AB124"
+    vendor_id: str | None = None  # e.g., "0276"
+    batch: str | None = None
+    filament_id: str | None = None  # Creality filament code, e.g., "101001"
+    color_rgba: str | None = None  # e.g., "FF0000FF"
+    length_m: int | None = None  # Filament length in meters
+    serial: str | None = None
+
+
 class TagReadResult(BaseModel):
     """Result of reading an NFC tag."""
 
@@ -106,6 +120,11 @@ class TagReadResult(BaseModel):
     openprinttag_data: OpenPrintTagData | None = None
     openspool_data: OpenSpoolTagData | None = None
     opentag3d_data: dict | None = None  # Uses OpenTag3DTagData from opentag3d module
+    creality_data: CrealityTagData | None = None
+
+    # Decoder that recognized the tag (see tags.registry) and how sure it was, 0-100
+    decoder: str | None = None
+    confidence: int = 0
 
     # Raw data
     ndef_message: bytes | None = None  # Raw NDEF for NTAG
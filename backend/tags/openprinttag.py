@@ -1,4 +1,4 @@
-"""OpenPrintTag decoder.
+"""OpenPrintTag decoder and encoder.
 
 OpenPrintTag is an open standard for NFC-based filament identification.
 It uses NDEF records with CBOR-encoded payload.
@@ -193,6 +193,44 @@ class OpenPrintTagDecoder:
             logger.error(f"Failed to decode OpenPrintTag: {e}")
             return None
 
+    @staticmethod
+    def encode(data: OpenPrintTagData) -> bytes:
+        """Encode OpenPrintTag data to CBOR bytes for writing to tag.
+
+        Writes a meta region pointing at the main region, with the fields
+        the decoder reads. Materials outside the enum (e.g. "PLA-CF") use
+        their base material's index.
+
+        Args:
+            data: Tag data to encode
+
+        Returns:
+            CBOR bytes ready to write as NDEF payload
+        """
+        import cbor2
+
+        main = {}
+        material_type = (data.material_type or "").upper()
+        if material_type not in MATERIAL_TYPES:
+            material_type = material_type.split("-")[0]
+        if material_type in MATERIAL_TYPES:
+            main[9] = MATERIAL_TYPES.index(material_type)
+        if data.material_name:
+            main[10] = data.material_name
+        if data.brand_name:
+            main[11] = data.brand_name
+        for key, weight in ((16, data.nominal_weight), (17, data.actual_weight), (18, data.empty_weight)):
+            if weight:
+                main[key] = weight
+        colors = [data.primary_color, *(data.secondary_colors or [])]
+        for key, color in zip(range(19, 25), colors, strict=False):
+            if color:
+                main[key] = bytes.fromhex(color[:8])
+
+        # Main region right after the meta region
+        meta = cbor2.dumps({0: 0})
+        return cbor2.dumps({0: len(meta)}) + cbor2.dumps(main)
+
     @staticmethod
     def to_spool(data: OpenPrintTagData) -> SpoolFromTag:
         """Convert OpenPrintTag data to normalized spool data."""
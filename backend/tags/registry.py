@@ -0,0 +1,301 @@
+"""Registry of tag format decoders.
+
+Every supported tag format is a FormatDecoder: it scores how sure it is
+(0-100) that a tag holds its format, then decodes it into a TagReadResult.
+The most confident decoder that can decode the tag wins; on equal
+confidence the one registered first does.
+
+The firmware has the same registry (firmware/core/src/decode.rs), with the
+same decoder names and scores, and both decode the vectors in
+firmware/core/testdata/tag_vectors.json in their tests. OpenSpool and
+OpenTag3D are only decoded here.
+"""
+
+from dataclasses import dataclass, field
+
+from .bambulab import BambuLabDecoder
+from .creality import CrealityDecoder
+from .models import NfcTagType, TagReadResult, TagType
+from .openprinttag import OpenPrintTagDecoder
+from .openspool import OpenSpoolDecoder
+from .opentag3d import OpenTag3DDecoder
+from .spoolease_format import SpoolEaseDecoder
+
+URL_PREFIXES = {
+    0x00: "",
+    0x01: "http://www.",
+    0x02: "https://www.",
+    0x03: "http://",
+    0x04: "https://",
+    0x05: "tel:",
+    0x06: "mailto:",
+    0x07: "ftp://anonymous:anonymous@",
+    0x08: "ftp://ftp.",
+    0x09: "ftps://",
+    0x0A: "sftp://",
+    0x0B: "smb://",
+    0x0C: "nfs://",
+    0x0D: "ftp://",
+    0x0E: "dav://",
+    0x0F: "news:",
+    0x10: "telnet://",
+    0x11: "imap:",
+    0x12: "rtsp://",
+    0x13: "urn:",
+    0x14: "pop:",
+    0x15: "sip:",
+    0x16: "sips:",
+    0x17: "tftp:",
+    0x18: "btspp://",
+    0x19: "btl2cap://",
+    0x1A: "btgoep://",
+    0x1B: "tcpobex://",
+    0x1C: "irdaobex://",
+    0x1D: "file://",
+    0x1E: "urn:epc:id:",
+    0x1F: "urn:epc:tag:",
+    0x20: "urn:epc:pat:",
+    0x21: "urn:epc:raw:",
+    0x22: "urn:epc:",
+    0x23: "urn:nfc:",
+}
+
+
+@dataclass
+class RawTag:
+    """Tag contents handed to the decoders."""
+
+    uid_hex: str
+    nfc_type: NfcTagType
+    blocks: dict[int, bytes] = field(default_factory=dict)  # Mifare Classic block number -> data
+    records: list[tuple[str, bytes]] = field(default_factory=list)  # NDEF (record type, payload)
+
+    @property
+    def is_mifare(self) -> bool:
+        return self.nfc_type in (NfcTagType.MIFARE_CLASSIC_1K, NfcTagType.MIFARE_CLASSIC_4K)
+
+    def payloads(self, record_type: str) -> list[bytes]:
+        """Payloads of the NDEF records of this type."""
+        return [payload for type_, payload in self.records if type_ == record_type]
+
+    def urls(self) -> list[str]:
+        """URLs of the NDEF URL records."""
+        return [
+            URL_PREFIXES.get(payload[0], "") + payload[1:].decode("utf-8", errors="ignore")
+            for type_, payload in self.records
+            if (type_ == "U" or type_.startswith("urn:nfc:wkt:U")) and payload
+        ]
+
+
+class FormatDecoder:
+    """A tag format."""
+
+    name = ""  # The same as the firmware's
+
+    def confidence(self, tag: RawTag) -> int:
+        """How sure the decoder is that the tag holds its format, from 0 (not its format) to 100 (certain)."""
+        raise NotImplementedError
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        """Fill in the result's tag type and data; False if the data turns out to be unusable."""
+        raise NotImplementedError
+
+
+class DecoderRegistry:
+    """Tag format decoders, asked in turn for every tag."""
+
+    def __init__(self, decoders: list[FormatDecoder] | None = None):
+        self._decoders = list(decoders or [])
+
+    @property
+    def decoders(self) -> list[FormatDecoder]:
+        return list(self._decoders)
+
+    def register(self, decoder: FormatDecoder):
+        """Add a decoder; it loses ties to the ones already registered."""
+        self._decoders.append(decoder)
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        """Decode the tag with the most confident decoder that can; False if none can."""
+        scored = [(decoder.confidence(tag), decoder) for decoder in self._decoders]
+        scored.sort(key=lambda entry: entry[0], reverse=True)
+        for confidence, decoder in scored:
+            if confidence > 0 and decoder.decode(tag, result):
+                result.decoder = decoder.name
+                result.confidence = confidence
+                return True
+        return False
+
+
+# ============ Decoders ============
+
+
+def _cstr(data: bytes) -> str:
+    return data.split(b"\x00", 1)[0].decode("ascii", errors="replace")
+
+
+class BambuLabFormat(FormatDecoder):
+    """Bambu Lab Mifare Classic tags. The RSA signature isn't checked, so a valid-looking tag scores 95."""
+
+    name = "bambu"
+
+    def confidence(self, tag: RawTag) -> int:
+        if not tag.is_mifare:
+            return 0
+        # Material ID, e.g. "GFA00"
+        if _cstr(tag.blocks.get(1, b"")[8:16]).startswith("GF"):
+            return 95
+        # Filament type, e.g. "PLA"
+        filament_type = _cstr(tag.blocks.get(2, b""))
+        return 50 if filament_type.isprintable() and any(c.isalpha() for c in filament_type) else 0
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        data = BambuLabDecoder.decode(tag.uid_hex, tag.blocks)
+        if not data:
+            return False
+        result.tag_type = TagType.BAMBULAB
+        result.bambulab_data = data
+        return True
+
+
+class CrealityFormat(FormatDecoder):
+    """Creality CFS Mifare Classic tags."""
+
+    name = "creality"
+
+    def confidence(self, tag: RawTag) -> int:
+        return 80 if tag.is_mifare and CrealityDecoder.record(tag.blocks) else 0
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        data = CrealityDecoder.decode(tag.uid_hex, tag.blocks)
+        if not data:
+            return False
+        result.tag_type = TagType.CREALITY
+        result.creality_data = data
+        return True
+
+
+class SpoolEaseFormat(FormatDecoder):
+    """SpoolEase NDEF URL tags."""
+
+    name = "spoolease"
+
+    def _url(self, tag: RawTag) -> str | None:
+        return next((url for url in tag.urls() if SpoolEaseDecoder.can_decode(url)), None)
+
+    def confidence(self, tag: RawTag) -> int:
+        url = self._url(tag)
+        if url is None:
+            return 0
+        return 100 if "/V2/" in url else 90
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        url = self._url(tag)
+        data = SpoolEaseDecoder.decode(url, tag.uid_hex) if url else None
+        if not data:
+            return False
+        result.tag_type = TagType.SPOOLEASE_V2 if data.version == 2 else TagType.SPOOLEASE_V1
+        result.spoolease_data = data
+        return True
+
+
+class OpenPrintTagFormat(FormatDecoder):
+    """OpenPrintTag NDEF MIME records with a CBOR payload."""
+
+    name = "openprinttag"
+
+    def confidence(self, tag: RawTag) -> int:
+        return 90 if tag.payloads(OpenPrintTagDecoder.RECORD_TYPE) else 0
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        for payload in tag.payloads(OpenPrintTagDecoder.RECORD_TYPE):
+            data = OpenPrintTagDecoder.decode(tag.uid_hex, payload)
+            if data:
+                result.tag_type = TagType.OPENPRINTTAG
+                result.openprinttag_data = data
+                return True
+        return False
+
+
+class PrusaFormat(OpenPrintTagFormat):
+    """Prusament spools, whose OpenPrintTags name Prusa as the brand.
+
+    Scores above the generic OpenPrintTag decoder and reports the brand as
+    "Prusament", however it's spelled on the tag.
+    """
+
+    name = "prusa"
+
+    def confidence(self, tag: RawTag) -> int:
+        result = TagReadResult(uid=tag.uid_hex, uid_base64="", nfc_type=tag.nfc_type, tag_type=TagType.UNKNOWN)
+        if not super().decode(tag, result):
+            return 0
+        brand = result.openprinttag_data.brand_name or ""
+        return 95 if brand.lower().startswith("prusa") else 0
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        if not super().decode(tag, result):
+            return False
+        result.openprinttag_data.brand_name = "Prusament"
+        return True
+
+
+class OpenSpoolFormat(FormatDecoder):
+    """OpenSpool NDEF JSON records (application/json with "protocol": "openspool")."""
+
+    name = "openspool"
+
+    def _payloads(self, tag: RawTag) -> list[bytes]:
+        payloads = tag.payloads(OpenSpoolDecoder.RECORD_TYPE)
+        return [payload for payload in payloads if OpenSpoolDecoder.can_decode_payload(payload)]
+
+    def confidence(self, tag: RawTag) -> int:
+        return 100 if self._payloads(tag) else 0
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        for payload in self._payloads(tag):
+            data = OpenSpoolDecoder.decode(tag.uid_hex, payload)
+            if data:
+                result.tag_type = TagType.OPENSPOOL
+                result.openspool_data = data
+                return True
+        return False
+
+
+class OpenTag3DFormat(FormatDecoder):
+    """OpenTag3D NDEF binary records."""
+
+    name = "opentag3d"
+
+    def confidence(self, tag: RawTag) -> int:
+        return 90 if tag.payloads(OpenTag3DDecoder.RECORD_TYPE) else 0
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        for payload in tag.payloads(OpenTag3DDecoder.RECORD_TYPE):
+            data = OpenTag3DDecoder.decode(tag.uid_hex, payload)
+            if data:
+                result.tag_type = TagType.OPENTAG3D
+                result.opentag3d_data = data.__dict__
+                return True
+        return False
+
+
+_registry: DecoderRegistry | None = None
+
+
+def get_decoder_registry() -> DecoderRegistry:
+    """Get the singleton decoder registry, with the built-in formats."""
+    global _registry
+    if _registry is None:
+        _registry = DecoderRegistry(
+            [
+                BambuLabFormat(),
+                CrealityFormat(),
+                SpoolEaseFormat(),
+                PrusaFormat(),
+                OpenPrintTagFormat(),
+                OpenSpoolFormat(),
+                OpenTag3DFormat(),
+            ]
+        )
+    return _registry
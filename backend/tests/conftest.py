@@ -64,11 +64,26 @@ def mock_printer_manager():
     manager.set_calibration = MagicMock(return_value=True)
     manager.set_k_value = MagicMock(return_value=True)
     manager.reset_slot = MagicMock(return_value=True)
+    manager.set_speed_level = MagicMock(return_value=True)
+    manager.skip_objects = MagicMock(return_value=True)
+    manager.control_print = MagicMock(return_value=True)
+    manager.get_last_command = MagicMock(
+        side_effect=lambda serial, command: {
+            "sequence_id": "1",
+            "command": command,
+            "status": "pending",
+            "sent_at": 0.0,
+        }
+    )
+    manager.get_command = MagicMock(return_value=None)
+    manager.get_commands = MagicMock(return_value=[])
     manager.get_kprofiles = AsyncMock(return_value=[])
     manager.get_nozzle_diameter = MagicMock(return_value="0.4")
     manager.stage_assignment = MagicMock(return_value=True)
     manager.cancel_assignment = MagicMock(return_value=True)
     manager.get_all_pending_assignments = MagicMock(return_value={})
+    manager.cert_mismatch = MagicMock(return_value=None)
+    manager.access_code_refused = MagicMock(return_value=False)
 
     # Set the mock as the global printer manager
     original = printers_api._printer_manager
@@ -103,6 +118,26 @@ async def async_client(test_db, mock_printer_manager) -> AsyncGenerator[AsyncCli
         patch("api.settings.get_db", override_get_db),
         patch("api.support.get_db", override_get_db),
         patch("api.tags.get_db", override_get_db),
+        patch("api.reports.get_db", override_get_db),
+        patch("api.webhooks.get_db", override_get_db),
+        patch("services.webhooks.get_db", override_get_db),
+        patch("api.notifications.get_db", override_get_db),
+        patch("services.notifiers.get_db", override_get_db),
+        patch("api.trash.get_db", override_get_db),
+        patch("api.crash_reports.get_db", override_get_db),
+        patch("api.config_bundle.get_db", override_get_db),
+        patch("api.admin.get_db", override_get_db),
+        patch("api.reconciliation.get_db", override_get_db),
+        patch("api.slicer.get_db", override_get_db),
+        patch("api.projects.get_db", override_get_db),
+        patch("api.search.get_db", override_get_db),
+        patch("api.jobs.get_db", override_get_db),
+        patch("api.templates.get_db", override_get_db),
+        patch("api.stocktakes.get_db", override_get_db),
+        patch("api.purchases.get_db", override_get_db),
+        patch("api.share.get_db", override_get_db),
+        patch("api.integrations.get_db", override_get_db),
+        patch("api.kiosk.get_db", override_get_db),
     ):
         async with AsyncClient(transport=ASGITransport(app=app), base_url="http://test") as client:
             yield client
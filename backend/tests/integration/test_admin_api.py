@@ -0,0 +1,118 @@
+"""Integration tests for the administration API."""
+
+from unittest.mock import patch
+
+import httpx
+
+
+class TestDatabaseStatusAPI:
+    """Test the WAL status and checkpoint endpoints."""
+
+    async def test_db_status(self, async_client, test_db, spool_factory):
+        """Test the database runs in WAL mode and reports its WAL."""
+        await spool_factory()
+
+        response = await async_client.get("/api/admin/db-status")
+        assert response.status_code == 200
+        data = response.json()
+        assert data["journal_mode"] == "wal"
+        assert data["size"] > 0
+        assert data["wal_size"] > 0
+        assert data["last_checkpoint"] is None
+        assert data["replication"] is None
+
+    async def test_checkpoint(self, async_client, test_db, spool_factory):
+        """Test a TRUNCATE checkpoint empties the WAL and shows up in the status."""
+        await spool_factory()
+
+        response = await async_client.post("/api/admin/db-checkpoint", params={"mode": "TRUNCATE"})
+        assert response.status_code == 200
+        result = response.json()
+        assert result["mode"] == "TRUNCATE"
+        assert result["busy"] is False
+
+        data = (await async_client.get("/api/admin/db-status")).json()
+        assert data["wal_size"] == 0
+        assert data["last_checkpoint"]["mode"] == "TRUNCATE"
+
+        response = await async_client.post("/api/admin/db-checkpoint", params={"mode": "SOMETIMES"})
+        assert response.status_code == 422
+
+    async def test_replication_metrics(self, async_client, test_db):
+        """Test Litestream samples are picked out of the sidecar's metrics."""
+        metrics = (
+            "# HELP litestream_db_size The current size of the real DB\n"
+            "# TYPE litestream_db_size gauge\n"
+            'litestream_db_size{db="/data/spoolbuddy.db"} 131072\n'
+            "go_goroutines 12\n"
+        )
+        transport = httpx.MockTransport(lambda request: httpx.Response(200, text=metrics))
+        real_client = httpx.AsyncClient
+
+        with (
+            patch("api.admin.settings.replication_metrics_url", "http://litestream:9090/metrics"),
+            patch("api.admin.httpx.AsyncClient", lambda **kwargs: real_client(transport=transport, **kwargs)),
+        ):
+            data = (await async_client.get("/api/admin/db-status")).json()
+
+        assert data["replication"]["reachable"] is True
+        assert data["replication"]["metrics"] == {'litestream_db_size{db="/data/spoolbuddy.db"}': 131072.0}
+
+
+class TestPerfAPI:
+    """Test the request and slow query metrics."""
+
+    async def test_route_metrics(self, async_client, spool_factory):
+        """Test requests are counted per route template and status code."""
+        spool = await spool_factory()
+        assert (await async_client.delete("/api/admin/perf")).status_code == 204
+
+        await async_client.get(f"/api/spools/{spool.id}")
+        await async_client.get("/api/spools/missing")
+
+        data = (await async_client.get("/api/admin/perf")).json()
+        route = next(r for r in data["routes"] if r["route"] == "/api/spools/{spool_id}")
+        assert route["method"] == "GET"
+        assert route["count"] == 2
+        assert route["statuses"] == {"200": 1, "404": 1}
+        assert route["errors"] == 0
+        assert route["max_ms"] >= route["p50_ms"] > 0
+        assert data["queries"]["count"] > 0
+
+    async def test_slow_query_log(self, async_client, test_db):
+        """Test statements over the threshold are logged without their parameters."""
+        await async_client.delete("/api/admin/perf")
+
+        with patch("services.perf.settings.slow_query_ms", 0.000001):
+            await test_db.set_setting("ams_humidity_good", "35")
+
+        data = (await async_client.get("/api/admin/perf")).json()
+        assert data["queries"]["slow_count"] >= 1
+        slow = data["slow_queries"][0]
+        assert slow["params"] == 3  # key, value, updated_at
+        assert "35" not in slow["sql"]
+
+
+class TestWebSocketClientsAPI:
+    """Test listing the connected WebSocket clients."""
+
+    async def test_list_ws_clients(self, async_client):
+        """Test clients are listed with their identity and printer subscription."""
+        from services.ws_clients import get_ws_clients
+
+        clients = get_ws_clients()
+        display, browser = object(), object()
+        clients.add(display, "device", api_key={"id": 3, "name": "Workshop display"}, address="10.0.0.7")
+        clients.add(browser, "web")
+        clients.subscribe(display, ["01P00A000000001"])
+        try:
+            data = (await async_client.get("/api/admin/ws-clients")).json()
+        finally:
+            clients.remove(display)
+            clients.remove(browser)
+
+        by_identity = {c["identity"]: c for c in data}
+        assert by_identity["Workshop display"]["client_class"] == "device"
+        assert by_identity["Workshop display"]["api_key_id"] == 3
+        assert by_identity["Workshop display"]["printers"] == ["01P00A000000001"]
+        assert by_identity["anonymous"]["printers"] is None
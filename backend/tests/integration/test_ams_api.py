@@ -5,7 +5,9 @@ Tests cover:
 - Setting filament on AMS slots
 - Setting calibration profiles
 - Resetting/clearing slots
+- Command outcomes
 - Assigning spools to slots
+- External spool holder
 - Pending assignments
 - Slot history
 """
@@ -13,6 +15,7 @@ Tests cover:
 from unittest.mock import AsyncMock, MagicMock, patch
 
 import pytest
+from models import PrinterCreate
 
 
 class TestAmsFilamentAPI:
@@ -41,7 +44,9 @@ class TestAmsFilamentAPI:
             },
         )
 
-        assert response.status_code == 204
+        assert response.status_code == 202
+        assert response.json()["command"] == "ams_filament_setting"
+        assert response.json()["status"] == "pending"
         mock_printer_manager.set_filament.assert_called_once()
 
     async def test_set_filament_printer_not_connected(self, async_client, sample_printer_data, mock_printer_manager):
@@ -87,6 +92,78 @@ class TestAmsFilamentAPI:
         assert response.status_code == 500
         assert "Failed to set filament" in response.json()["detail"]
 
+    async def test_set_filament_normalizes_color_and_temps(
+        self, async_client, sample_printer_data, mock_printer_manager
+    ):
+        """Test colors are normalized and temperatures default to the material's range."""
+        mock_printer_manager.is_connected.return_value = True
+
+        response = await async_client.post(
+            f"/api/printers/{sample_printer_data['serial']}/ams/0/tray/0/filament",
+            json={"tray_info_idx": "GFG00", "tray_type": "PETG", "tray_color": "#00ff00"},
+        )
+
+        assert response.status_code == 202
+        kwargs = mock_printer_manager.set_filament.call_args.kwargs
+        assert kwargs["tray_color"] == "00FF00FF"
+        assert (kwargs["nozzle_temp_min"], kwargs["nozzle_temp_max"]) == (220, 260)
+
+    async def test_set_filament_validation(self, async_client, sample_printer_data, mock_printer_manager):
+        """Test invalid colors, temperatures and presets are rejected."""
+        mock_printer_manager.is_connected.return_value = True
+        url = f"/api/printers/{sample_printer_data['serial']}/ams/0/tray/0/filament"
+
+        for body in (
+            {"tray_type": "PLA", "tray_color": "red"},
+            {"tray_type": "PLA", "nozzle_temp_min": 230, "nozzle_temp_max": 190},
+            {"tray_info_idx": "GFA00", "tray_type": "PETG"},
+            {"tray_info_idx": "not a preset", "tray_type": "PLA"},
+        ):
+            response = await async_client.post(url, json=body)
+            assert response.status_code == 422, body
+
+        mock_printer_manager.set_filament.assert_not_called()
+
+    async def test_set_filament_from_spool(
+        self, async_client, test_db, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test setting a slot from a spool pulls its preset, color and K profile."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        serial = sample_printer_data["serial"]
+        spool = await spool_factory(material="PLA", rgba="#112233FF", slicer_filament="GFSA00_02")
+        await test_db.save_spool_k_profiles(
+            spool.id, [{"printer_serial": serial, "nozzle_diameter": "0.4", "cali_idx": 7, "k_value": "0.02"}]
+        )
+        mock_printer_manager.is_connected.return_value = True
+
+        response = await async_client.post(
+            f"/api/printers/{serial}/ams/0/tray/2/filament",
+            json={"spool_id": spool.id, "nozzle_temp_max": 225},
+        )
+
+        assert response.status_code == 202
+        kwargs = mock_printer_manager.set_filament.call_args.kwargs
+        assert kwargs["tray_info_idx"] == "GFA00"
+        assert kwargs["setting_id"] == "GFSA00"
+        assert kwargs["tray_type"] == "PLA"
+        assert kwargs["tray_sub_brands"] == "Bambu PLA Basic"
+        assert kwargs["tray_color"] == "112233FF"
+        assert (kwargs["nozzle_temp_min"], kwargs["nozzle_temp_max"]) == (190, 225)
+        assert mock_printer_manager.set_calibration.call_args.kwargs["cali_idx"] == 7
+        assignments = await test_db.get_slot_assignments(serial)
+        assert [(a["spool_id"], a["tray_id"]) for a in assignments] == [(spool.id, 2)]
+
+    async def test_set_filament_unknown_spool(self, async_client, sample_printer_data, mock_printer_manager):
+        """Test setting a slot from an unknown spool returns 404."""
+        mock_printer_manager.is_connected.return_value = True
+
+        response = await async_client.post(
+            f"/api/printers/{sample_printer_data['serial']}/ams/0/tray/0/filament",
+            json={"spool_id": "missing"},
+        )
+
+        assert response.status_code == 404
+
 
 class TestAmsCalibrationAPI:
     """Tests for AMS calibration endpoints."""
@@ -110,7 +187,7 @@ class TestAmsCalibrationAPI:
             },
         )
 
-        assert response.status_code == 204
+        assert response.status_code == 202
         mock_printer_manager.set_calibration.assert_called_once()
 
     async def test_set_calibration_with_k_value(self, async_client, sample_printer_data, mock_printer_manager):
@@ -131,7 +208,7 @@ class TestAmsCalibrationAPI:
             },
         )
 
-        assert response.status_code == 204
+        assert response.status_code == 202
         # Should call both set_calibration and set_k_value
         mock_printer_manager.set_calibration.assert_called_once()
         mock_printer_manager.set_k_value.assert_called_once()
@@ -177,7 +254,7 @@ class TestAmsResetAPI:
 
         response = await async_client.post(f"/api/printers/{sample_printer_data['serial']}/ams/0/tray/1/reset")
 
-        assert response.status_code == 204
+        assert response.status_code == 202
         mock_printer_manager.reset_slot.assert_called_once_with(
             serial=sample_printer_data["serial"], ams_id=0, tray_id=1
         )
@@ -204,6 +281,46 @@ class TestAmsResetAPI:
         assert response.status_code == 500
 
 
+class TestPrinterCommandsAPI:
+    """Tests for printer command outcome endpoints."""
+
+    async def test_get_command(self, async_client, mock_printer_manager):
+        """Test getting a command's outcome."""
+        mock_printer_manager.get_command.return_value = {
+            "sequence_id": "7",
+            "command": "ams_filament_setting",
+            "status": "failed",
+            "reason": "tray not found",
+            "sent_at": 1700000000.0,
+            "completed_at": 1700000001.0,
+        }
+
+        response = await async_client.get("/api/printers/TEST123/commands/7")
+
+        assert response.status_code == 200
+        assert response.json()["status"] == "failed"
+        assert response.json()["reason"] == "tray not found"
+        mock_printer_manager.get_command.assert_called_with("TEST123", "7")
+
+    async def test_get_command_waits_for_outcome(self, async_client, mock_printer_manager):
+        """Test long-polling returns once the command completes."""
+        pending = {"sequence_id": "7", "command": "ams_get_rfid", "status": "pending", "sent_at": 1700000000.0}
+        mock_printer_manager.get_command.side_effect = [pending, pending, {**pending, "status": "success"}]
+
+        response = await async_client.get("/api/printers/TEST123/commands/7?wait=5")
+
+        assert response.json()["status"] == "success"
+        assert mock_printer_manager.get_command.call_count == 3
+
+    async def test_get_command_not_found(self, async_client, mock_printer_manager):
+        """Test unknown sequence IDs return 404."""
+        mock_printer_manager.get_command.return_value = None
+
+        response = await async_client.get("/api/printers/TEST123/commands/99")
+
+        assert response.status_code == 404
+
+
 class TestAssignSpoolAPI:
     """Tests for spool-to-slot assignment endpoints."""
 
@@ -357,6 +474,149 @@ class TestAssignSpoolAPI:
         assert response.status_code == 404
 
 
+class TestExternalSpoolAPI:
+    """Tests for the external spool holder (virtual tray) endpoints."""
+
+    async def test_set_external_spool(
+        self, async_client, test_db, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test putting a spool on the external holder records and configures it."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        serial = sample_printer_data["serial"]
+        spool = await spool_factory(material="PETG", rgba="#00FF00FF")
+        mock_printer_manager.is_connected.return_value = True
+
+        response = await async_client.put(f"/api/printers/{serial}/external-spool", json={"spool_id": spool.id})
+
+        assert response.status_code == 200
+        data = response.json()
+        assert data["ams_id"] == 255
+        assert data["spool"]["id"] == spool.id
+        assert data["sequence_id"] == "1"
+        kwargs = mock_printer_manager.set_filament.call_args.kwargs
+        assert (kwargs["ams_id"], kwargs["tray_id"]) == (255, 0)
+        assert kwargs["tray_type"] == "PETG"
+        assert kwargs["tray_color"] == "00FF00FF"
+        assert await test_db.get_spool_for_slot(serial, 255, 0) == spool.id
+
+    async def test_set_external_spool_uses_tuned_temperatures(
+        self, async_client, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test a spool's own nozzle temperatures replace the material defaults."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        serial = sample_printer_data["serial"]
+        spool = await spool_factory(material="PLA", nozzle_temp_min=205, nozzle_temp_max=215)
+        mock_printer_manager.is_connected.return_value = True
+
+        response = await async_client.put(f"/api/printers/{serial}/external-spool", json={"spool_id": spool.id})
+
+        assert response.status_code == 200
+        kwargs = mock_printer_manager.set_filament.call_args.kwargs
+        assert (kwargs["nozzle_temp_min"], kwargs["nozzle_temp_max"]) == (205, 215)
+
+    async def test_tuned_temperature_above_printer_limit(
+        self, async_client, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test tuned temperatures are validated against the printer's max nozzle temperature."""
+        await async_client.post("/api/printers", json={**sample_printer_data, "max_nozzle_temp": 280})
+        url = f"/api/printers/{sample_printer_data['serial']}/external-spool"
+
+        spool = await spool_factory(material="PLA", nozzle_temp_min=285)
+        response = await async_client.put(url, json={"spool_id": spool.id})
+        assert response.status_code == 422
+
+        spool = await spool_factory(material="PLA", nozzle_temp_min=220, nozzle_temp_max=290)
+        response = await async_client.put(url, json={"spool_id": spool.id})
+        assert response.status_code == 200
+        assert len(response.json()["warnings"]) == 1
+
+    async def test_set_external_spool_offline(
+        self, async_client, test_db, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test the assignment is recorded even if the printer is not connected."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        serial = sample_printer_data["serial"]
+        spool = await spool_factory()
+
+        response = await async_client.put(
+            f"/api/printers/{serial}/external-spool", json={"spool_id": spool.id, "ams_id": 254}
+        )
+
+        assert response.status_code == 200
+        assert response.json()["sequence_id"] is None
+        mock_printer_manager.set_filament.assert_not_called()
+        assert await test_db.get_spool_for_slot(serial, 254, 0) == spool.id
+
+    async def test_set_external_spool_invalid_holder(
+        self, async_client, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test only external holder ids are accepted."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        spool = await spool_factory()
+
+        response = await async_client.put(
+            f"/api/printers/{sample_printer_data['serial']}/external-spool", json={"spool_id": spool.id, "ams_id": 0}
+        )
+
+        assert response.status_code == 422
+
+    async def test_set_external_spool_not_found(self, async_client, sample_printer_data, mock_printer_manager):
+        """Test unknown printers and spools return 404."""
+        response = await async_client.put("/api/printers/UNKNOWN/external-spool", json={"spool_id": "x"})
+        assert response.status_code == 404
+
+        await async_client.post("/api/printers", json=sample_printer_data)
+        response = await async_client.put(
+            f"/api/printers/{sample_printer_data['serial']}/external-spool", json={"spool_id": "missing"}
+        )
+        assert response.status_code == 404
+
+    async def test_get_and_clear_external_spool(
+        self, async_client, test_db, sample_printer_data, mock_printer_manager, spool_factory
+    ):
+        """Test reading and clearing the external holder's spool."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        serial = sample_printer_data["serial"]
+        spool = await spool_factory()
+        await test_db.assign_spool_to_slot(spool.id, serial, 255, 0)
+
+        response = await async_client.get(f"/api/printers/{serial}/external-spool")
+        assert response.status_code == 200
+        assert response.json()["spool"]["id"] == spool.id
+
+        response = await async_client.delete(f"/api/printers/{serial}/external-spool")
+        assert response.status_code == 204
+        response = await async_client.get(f"/api/printers/{serial}/external-spool")
+        assert response.json()["spool"] is None
+
+
+    async def test_external_usage_charged_from_sliced_weight(self, test_db, spool_factory, sample_printer_data):
+        """Test prints fed from the external holder charge its spool with the sliced weight left over."""
+        from main import on_usage_logged
+
+        await test_db.create_printer(PrinterCreate(**sample_printer_data))
+        serial = sample_printer_data["serial"]
+        ams_spool = await spool_factory(label_weight=1000)
+        external_spool = await spool_factory(label_weight=1000)
+        await test_db.assign_spool_to_slot(ams_spool.id, serial, 0, 0)
+        await test_db.assign_spool_to_slot(external_spool.id, serial, 255, 0)
+
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()) as broadcast,
+            patch("main.fetch_sliced_weight", AsyncMock(return_value=35.0)) as fetch,
+        ):
+            await on_usage_logged(serial, "bracket", {(0, 0): 2, (255, 0): None}, "Metadata/plate_2.gcode")
+
+        fetch.assert_awaited_once()
+        assert fetch.call_args.args[2:] == ("bracket", "Metadata/plate_2.gcode")
+        assert (await test_db.get_spool(ams_spool.id)).consumed_since_weight == 20.0
+        assert (await test_db.get_spool(external_spool.id)).consumed_since_weight == 15.0
+        message = broadcast.call_args.args[0]
+        assert message["tray_usage"] == {"0_0": 2}
+        assert message["external_usage"] == {"255_0": 15.0}
+
+
 class TestAmsHistoryAPI:
     """Tests for AMS sensor history endpoint."""
 
@@ -286,3 +286,17 @@ class TestAPIKeysAPI:
         assert response.status_code == 200
         created_at = response.json()["created_at"]
         assert before <= created_at <= after + 1
+
+    async def test_authenticate_api_key(self, async_client, test_db):
+        """Test a key authenticates as itself until it is disabled (used by the WebSocket handshake)."""
+        from api.api_keys import authenticate_api_key
+
+        created = (await async_client.post("/api/api-keys/", json={"name": "Display"})).json()
+
+        api_key = await authenticate_api_key(created["key"])
+        assert api_key["id"] == created["id"]
+        assert api_key["name"] == "Display"
+        assert await authenticate_api_key("sb_not-a-key") is None
+
+        await async_client.patch(f"/api/api-keys/{created['id']}", json={"enabled": False})
+        assert await authenticate_api_key(created["key"]) is None
@@ -377,3 +377,34 @@ class TestColorsSearch:
         data = response.json()
         # Should find entries with partial match
         assert len(data) >= 1
+
+
+class TestColorResolve:
+    """Test resolving hex colors and color names to swatches."""
+
+    async def test_resolve_hex(self, async_client):
+        """Test short and long hex spellings give the same swatch."""
+        response = await async_client.get("/api/colors/resolve", params={"value": "#f80"})
+        assert response.status_code == 200
+        data = response.json()
+        assert data["source"] == "hex"
+        assert data["swatch"]["rgba"] == "FF8800FF"
+        assert data["swatch"]["rgba_int"] == 0xFF8800FF
+        assert data["swatch"]["family"] == "orange"
+
+    async def test_resolve_names(self, async_client):
+        """Test basic names and manufacturer catalog names."""
+        data = (await async_client.get("/api/colors/resolve", params={"value": "Gray"})).json()
+        assert (data["source"], data["swatch"]["hex"]) == ("basic", "#808080")
+
+        data = (await async_client.get("/api/colors/resolve", params={"value": "jade white"})).json()
+        assert data["source"] == "catalog"
+        assert data["color_name"] == "Jade White"
+
+        # A manufacturer's own color wins over the basic name
+        params = {"value": "Silver", "manufacturer": "Bambu Lab"}
+        data = (await async_client.get("/api/colors/resolve", params=params)).json()
+        assert (data["source"], data["swatch"]["hex"]) == ("catalog", "#A6A9AA")
+
+        response = await async_client.get("/api/colors/resolve", params={"value": "Unobtainium Blue"})
+        assert response.status_code == 404
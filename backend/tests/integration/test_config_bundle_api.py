@@ -0,0 +1,97 @@
+"""Integration tests for the configuration export/import API."""
+
+from unittest.mock import MagicMock, patch
+
+import pytest
+
+
+@pytest.fixture
+def mock_moonraker_manager():
+    """Moonraker manager that never opens a websocket."""
+    manager = MagicMock()
+    with patch("api.config_bundle.get_moonraker_manager", return_value=manager):
+        yield manager
+
+
+class TestConfigBundleAPI:
+    """Test exporting and importing the server configuration."""
+
+    async def test_export_leaves_out_credentials(self, async_client, test_db, printer_factory):
+        """Test access codes, secrets and the cloud login are not exported by default."""
+        await printer_factory(serial="00M09A000000001", name="X1C", access_code="12345678")
+        await test_db.set_setting("ams_humidity_good", "35")
+        await test_db.set_setting("cloud_access_token", "token")
+        await test_db.create_webhook(name="Hook", url="http://hook", events=["spool.low"], secret="s3cret")
+        await test_db.create_notification_channel(
+            name="Phone",
+            channel_type="telegram",
+            config={"bot_token": "123:abc", "chat_id": "42"},
+            events=["spool.low"],
+        )
+
+        response = await async_client.get("/api/config/export")
+        assert response.status_code == 200
+        bundle = response.json()
+        assert bundle["version"] == 1
+        assert bundle["settings"] == {"ams_humidity_good": "35"}
+        assert bundle["printers"][0]["serial"] == "00M09A000000001"
+        assert bundle["printers"][0]["access_code"] is None
+        assert bundle["webhooks"][0]["secret"] is None
+        assert bundle["notification_channels"][0]["config"] == {"chat_id": "42"}
+
+        response = await async_client.get("/api/config/export", params={"include_access_codes": True})
+        assert response.json()["printers"][0]["access_code"] == "12345678"
+
+    async def test_import_bundle(self, async_client, test_db, mock_moonraker_manager):
+        """Test a bundle is imported on a fresh install, and importing it again adds nothing."""
+        bundle = {
+            "version": 1,
+            "settings": {"ams_temp_good": "26.0", "cloud_email": "someone@example.com"},
+            "printers": [{"serial": "00M09A000000002", "name": "P1S", "model": "P1S", "auto_connect": True}],
+            "spool_templates": [{"name": "Bambu PLA Black", "material": "PLA", "brand": "Bambu Lab"}],
+            "spool_catalog": [{"name": "Custom Spool", "weight": 180}],
+            "moonraker_printers": [{"name": "Voron", "url": "http://voron.local:7125"}],
+            "webhooks": [{"name": "Hook", "url": "http://hook", "events": ["spool.low"]}],
+            "notification_channels": [
+                {"name": "Phone", "type": "telegram", "config": {"chat_id": "42"}, "events": ["spool.low"]}
+            ],
+        }
+        response = await async_client.post("/api/config/import", json=bundle)
+        assert response.status_code == 200
+        result = response.json()
+        assert result["imported"]["printers"] == 1
+        assert result["imported"]["spool_templates"] == 1
+        assert result["imported"]["settings"] == 1
+        assert result["skipped"]["settings"] == 1  # Cloud login is never imported
+
+        assert await test_db.get_setting("ams_temp_good") == "26.0"
+        assert await test_db.get_setting("cloud_email") is None
+        printer = await test_db.get_printer("00M09A000000002")
+        assert printer.name == "P1S" and printer.auto_connect is True
+        assert mock_moonraker_manager.start.call_count == 1
+        # The channel's bot token wasn't in the bundle, so it stays off until it is set
+        channels = await test_db.get_notification_channels()
+        assert channels[0]["enabled"] is False
+
+        result = (await async_client.post("/api/config/import", json=bundle)).json()
+        assert result["imported"]["spool_templates"] == 0
+        assert result["skipped"]["spool_templates"] == 1
+        assert result["skipped"]["webhooks"] == 1
+        assert len(await test_db.get_spool_templates()) == 1
+
+    async def test_import_keeps_existing_access_code(self, async_client, test_db, printer_factory):
+        """Test importing a printer without an access code keeps the one already set."""
+        await printer_factory(serial="00M09A000000003", name="Old name", access_code="87654321")
+
+        bundle = {"printers": [{"serial": "00M09A000000003", "name": "New name"}]}
+        response = await async_client.post("/api/config/import", json=bundle)
+        assert response.status_code == 200
+
+        printer = await test_db.get_printer("00M09A000000003")
+        assert printer.name == "New name"
+        assert printer.access_code == "87654321"
+
+    async def test_import_rejects_newer_bundle(self, async_client):
+        """Test a bundle from a newer server version is refused."""
+        response = await async_client.post("/api/config/import", json={"version": 99})
+        assert response.status_code == 400
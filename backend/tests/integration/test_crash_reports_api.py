@@ -0,0 +1,75 @@
+"""Integration tests for the device crash report API."""
+
+
+class TestCrashReportsAPI:
+    """Test crash report upload and listing."""
+
+    async def test_upload_crash_report(self, async_client):
+        """Test a device uploading a panic report."""
+        response = await async_client.post(
+            "/api/devices/aabbccddeeff/crash-reports",
+            json={
+                "firmware_version": "0.2.1",
+                "reset_reason": "panic",
+                "panic_message": "index out of bounds at src/main.rs:42",
+                "task": "main",
+                "backtrace": "0x42001234 0x42005678",
+                "crashed_at": 1760000000,
+            },
+        )
+        assert response.status_code == 201
+        report = response.json()
+        assert report["device_id"] == "aabbccddeeff"
+        assert report["reset_reason"] == "panic"
+        assert report["backtrace"].startswith("0x42001234")
+        assert report["crashed_at"] == 1760000000
+        assert report["created_at"] is not None
+
+    async def test_upload_requires_reset_reason(self, async_client):
+        """Test a report without a reset reason is rejected."""
+        response = await async_client.post("/api/devices/aabbccddeeff/crash-reports", json={})
+        assert response.status_code == 422
+
+    async def test_list_device_crash_reports(self, async_client):
+        """Test listing reports for one device, newest first."""
+        await async_client.post("/api/devices/dev1/crash-reports", json={"reset_reason": "panic"})
+        await async_client.post("/api/devices/dev1/crash-reports", json={"reset_reason": "task_wdt"})
+        await async_client.post("/api/devices/dev2/crash-reports", json={"reset_reason": "brownout"})
+
+        response = await async_client.get("/api/devices/dev1/crash-reports")
+        assert response.status_code == 200
+        assert [r["reset_reason"] for r in response.json()] == ["task_wdt", "panic"]
+
+    async def test_list_all_crash_reports(self, async_client):
+        """Test listing reports across all devices."""
+        await async_client.post("/api/devices/dev1/crash-reports", json={"reset_reason": "panic"})
+        await async_client.post("/api/devices/dev2/crash-reports", json={"reset_reason": "brownout"})
+
+        response = await async_client.get("/api/devices/crash-reports")
+        assert response.status_code == 200
+        assert {r["device_id"] for r in response.json()} == {"dev1", "dev2"}
+
+    async def test_get_and_delete_crash_report(self, async_client):
+        """Test fetching and deleting a single report."""
+        created = (await async_client.post("/api/devices/dev1/crash-reports", json={"reset_reason": "panic"})).json()
+
+        response = await async_client.get(f"/api/devices/dev1/crash-reports/{created['id']}")
+        assert response.status_code == 200
+
+        # Reports are scoped to their device
+        response = await async_client.get(f"/api/devices/dev2/crash-reports/{created['id']}")
+        assert response.status_code == 404
+
+        response = await async_client.delete(f"/api/devices/dev1/crash-reports/{created['id']}")
+        assert response.status_code == 204
+        assert (await async_client.get("/api/devices/dev1/crash-reports")).json() == []
+
+    async def test_old_reports_are_pruned(self, test_db):
+        """Test only the newest reports are kept per device."""
+        for i in range(5):
+            await test_db.create_crash_report("dev1", "panic", panic_message=f"crash {i}", keep=3)
+        await test_db.create_crash_report("dev2", "panic")
+
+        reports = await test_db.get_crash_reports(device_id="dev1")
+        assert [r["panic_message"] for r in reports] == ["crash 4", "crash 3", "crash 2"]
+        assert len(await test_db.get_crash_reports(device_id="dev2")) == 1
@@ -8,6 +8,7 @@ Tests cover:
 - Scale operations (tare, calibrate, reset)
 - Device commands (reboot, update, factory reset)
 - Recovery info
+- Display state reply (resolved spool and suggested action)
 """
 
 from unittest.mock import AsyncMock, patch
@@ -264,6 +265,29 @@ class TestScaleAPI:
         assert data["success"] is True
         mock_queue.assert_called_once_with("scale_calibrate:100.5")
 
+    async def test_tare_second_channel(self, async_client):
+        """Test tare command for the second load cell."""
+        with patch("main.is_display_connected", return_value=True), patch("main.queue_display_command") as mock_queue:
+            response = await async_client.post("/api/device/scale/tare?channel=1")
+
+        assert response.status_code == 200
+        mock_queue.assert_called_once_with("scale_tare:1")
+
+    async def test_tare_invalid_channel(self, async_client):
+        """Test tare rejects channels the device doesn't have."""
+        with patch("main.is_display_connected", return_value=True):
+            response = await async_client.post("/api/device/scale/tare?channel=2")
+
+        assert response.status_code == 422
+
+    async def test_calibrate_second_channel(self, async_client):
+        """Test calibrate command for the second load cell."""
+        with patch("main.is_display_connected", return_value=True), patch("main.queue_display_command") as mock_queue:
+            response = await async_client.post("/api/device/scale/calibrate?known_weight=250&channel=1")
+
+        assert response.status_code == 200
+        mock_queue.assert_called_once_with("scale_calibrate:250.0:1")
+
     async def test_calibrate_no_device(self, async_client):
         """Test calibrate fails when no device connected."""
         with patch("main.is_display_connected", return_value=False):
@@ -341,3 +365,104 @@ class TestRecoveryInfoAPI:
         assert "serial_commands" in data
         assert len(data["steps"]) > 0
         assert "flash" in data["serial_commands"]
+
+
+class TestDisplayStateAPI:
+    """Tests for the reply to device state updates."""
+
+    async def _post_state(self, async_client, test_db, **params):
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()),
+        ):
+            return await async_client.post("/api/display/state", params=params)
+
+    async def test_weight_only_update(self, async_client, test_db):
+        """Test a weight-only update gets a plain acknowledgement."""
+        response = await self._post_state(async_client, test_db, weight=0, stable=True)
+
+        assert response.status_code == 200
+        assert response.json() == {"ok": True, "tag_id": None, "spool": None, "action": "none", "kiosk": None}
+
+    async def test_unknown_tag_suggests_adding(self, async_client, test_db):
+        """Test a tag that isn't in the inventory suggests adding the spool."""
+        response = await self._post_state(async_client, test_db, weight=1250, stable=True, tag_id="UNKNOWN==")
+
+        data = response.json()
+        assert data["tag_id"] == "UNKNOWN=="
+        assert data["spool"] is None
+        assert data["action"] == "add_spool"
+
+    async def test_known_tag_resolves_spool(self, async_client, test_db, spool_factory):
+        """Test a known tag returns the spool with its remaining weight."""
+        spool = await spool_factory(tag_id="KNOWN==", subtype="Basic", rgba="#A6A9AA")
+
+        response = await self._post_state(async_client, test_db, weight=1250, stable=True, tag_id="KNOWN==")
+
+        data = response.json()
+        assert data["spool"]["id"] == spool.id
+        assert data["spool"]["name"] == "Bambu Lab PLA Basic"
+        assert data["spool"]["color_rgba"] == 0xA6A9AAFF
+        assert data["spool"]["remaining_g"] == 1000
+        assert data["spool"]["low_stock"] is False
+        assert data["action"] == "none"
+
+    async def test_weight_mismatch_suggests_update(self, async_client, test_db, spool_factory):
+        """Test a stable reading far from the tracked weight suggests re-weighing."""
+        await spool_factory(tag_id="KNOWN==")
+
+        response = await self._post_state(async_client, test_db, weight=700, stable=True, tag_id="KNOWN==")
+        assert response.json()["action"] == "update_weight"
+
+        response = await self._post_state(async_client, test_db, weight=700, stable=False, tag_id="KNOWN==")
+        assert response.json()["action"] == "none"
+
+    async def test_low_stock_suggests_reorder(self, async_client, test_db, spool_factory):
+        """Test a spool below the low stock threshold suggests reordering."""
+        spool = await spool_factory(tag_id="KNOWN==")
+        await test_db.update_spool_consumption(spool.id, 900)
+
+        response = await self._post_state(async_client, test_db, weight=350, stable=True, tag_id="KNOWN==")
+
+        data = response.json()
+        assert data["spool"]["low_stock"] is True
+        assert data["spool"]["remaining_g"] == 100
+        assert data["action"] == "reorder"
+
+    async def test_open_stocktake_counts_spool(self, async_client, test_db, spool_factory):
+        """Test a spool on the scale is counted in the open stocktake, once per stable weight."""
+        spool = await spool_factory(tag_id="KNOWN==")
+        stocktake = await test_db.create_stocktake("Shelf count")
+
+        await self._post_state(async_client, test_db, weight=1100, stable=False, tag_id="KNOWN==")
+        scan = await test_db.get_stocktake_scan(stocktake["id"], spool.id)
+        assert (scan["source"], scan["weight"]) == ("device", None)
+
+        await self._post_state(async_client, test_db, weight=1180, stable=True, tag_id="KNOWN==")
+        await self._post_state(async_client, test_db, weight=1182, stable=True, tag_id="KNOWN==")
+        scans = await test_db.get_stocktake_scans(stocktake["id"])
+        assert [(s["spool_id"], s["weight"], s["expected_remaining"]) for s in scans] == [(spool.id, 1180, 1000)]
+
+    async def test_nfc_bridge_health_in_status(self, async_client, test_db):
+        """Test the NFC bridge health reported with a state update shows in the display status."""
+        with patch("main._device_nfc_bridge", None):
+            await self._post_state(
+                async_client,
+                test_db,
+                weight=0,
+                stable=True,
+                nfc_bridge="hung",
+                nfc_bridge_fw="1.3",
+                nfc_failures=12,
+                nfc_garbage=4,
+                nfc_resets=1,
+            )
+            response = await async_client.get("/api/display/status")
+
+        assert response.json()["nfc_bridge"] == {
+            "status": "hung",
+            "firmware": "1.3",
+            "failures": 12,
+            "garbage": 4,
+            "resets": 1,
+        }
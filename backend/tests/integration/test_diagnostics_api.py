@@ -0,0 +1,50 @@
+"""Integration tests for the device diagnostics API."""
+
+
+class TestDiagnosticsAPI:
+    """Test diagnostics upload and retrieval."""
+
+    async def test_upload_and_get_diagnostics(self, async_client):
+        """Test a device uploading a snapshot and support reading it back."""
+        snapshot = {
+            "firmware_version": "0.2.1",
+            "uptime_s": 3600,
+            "heap": {"free": 120000, "total": 320000, "min_free": 90000},
+            "psram": {"free": 6000000, "total": 8388608, "min_free": 5000000},
+            "wifi_rssi": -61,
+            "i2c": [{"client": "scale", "errors": 2, "timeouts": 0}, {"client": "nfc", "errors": 0, "timeouts": 1}],
+            "nfc_bridge_firmware": "1.3",
+            "nfc_bridge_status": "ok",
+            "scale_raw": [84213, -1200],
+            "fps": 29.5,
+        }
+        response = await async_client.post("/api/devices/diag-dev1/diagnostics", json=snapshot)
+        assert response.status_code == 200
+
+        response = await async_client.get("/api/devices/diag-dev1/diagnostics")
+        assert response.status_code == 200
+        data = response.json()
+        assert data["device_id"] == "diag-dev1"
+        assert data["received_at"] > 0
+        assert data["heap"]["min_free"] == 90000
+        assert data["wifi_rssi"] == -61
+        assert data["i2c"][0] == {"client": "scale", "errors": 2, "timeouts": 0}
+        assert data["nfc_bridge_firmware"] == "1.3"
+        assert data["scale_raw"] == [84213, -1200]
+
+    async def test_latest_snapshot_wins(self, async_client):
+        """Test only the most recent snapshot is kept."""
+        await async_client.post("/api/devices/diag-dev2/diagnostics", json={"uptime_s": 10})
+        await async_client.post("/api/devices/diag-dev2/diagnostics", json={"uptime_s": 70})
+
+        data = (await async_client.get("/api/devices/diag-dev2/diagnostics")).json()
+        assert data["uptime_s"] == 70
+        # Fields missing from older firmware fall back to defaults
+        assert data["psram"] is None
+        assert data["wifi_rssi"] is None
+        assert data["i2c"] == []
+
+    async def test_unknown_device(self, async_client):
+        """Test a device that never reported returns 404."""
+        response = await async_client.get("/api/devices/diag-unknown/diagnostics")
+        assert response.status_code == 404
@@ -360,6 +360,24 @@ class TestFirmwareOtaAPI:
         assert response.status_code == 200
         assert response.headers["x-firmware-version"] == "1.0.0"
 
+    async def test_ota_signature_header(self, async_client):
+        """Test the image signature is passed in a header, and only when well-formed."""
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            tmp_path = Path(tmp_dir)
+            (tmp_path / "spoolbuddy-1.0.0.bin").write_bytes(b"\xe9" + b"\x00" * 255)
+            (tmp_path / "spoolbuddy-1.0.0.bin.sig").write_text("AB" * 64 + "\n")
+            (tmp_path / "spoolbuddy-2.0.0.bin").write_bytes(b"\xe9" + b"\x00" * 255)
+            (tmp_path / "spoolbuddy-2.0.0.bin.sig").write_text("not a signature")
+
+            with patch("api.firmware.FIRMWARE_DIR", tmp_path):
+                signed = await async_client.get("/api/firmware/ota?version=1.0.0")
+                malformed = await async_client.get("/api/firmware/ota")
+                versions = (await async_client.get("/api/firmware/version")).json()
+
+        assert signed.headers["x-firmware-signature"] == "ab" * 64
+        assert "x-firmware-signature" not in malformed.headers
+        assert {v["version"]: v["signed"] for v in versions} == {"1.0.0": True, "2.0.0": False}
+
 
 class TestFirmwareUploadAPI:
     """Tests for firmware upload endpoint."""
@@ -464,6 +482,45 @@ class TestFirmwareUploadAPI:
         assert response.status_code == 400
         assert "Could not determine firmware version" in response.json()["detail"]
 
+    async def test_upload_with_signature(self, async_client):
+        """Test a signature uploaded with the image is stored next to it."""
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            tmp_path = Path(tmp_dir)
+            valid_firmware = b"\xe9" + b"\x00" * 1023
+
+            with patch("api.firmware.FIRMWARE_DIR", tmp_path):
+                response = await async_client.post(
+                    "/api/firmware/upload",
+                    files={
+                        "file": ("spoolbuddy-1.2.3.bin", valid_firmware, "application/octet-stream"),
+                        "signature": ("spoolbuddy-1.2.3.bin.sig", b"0f" * 64 + b"\n", "text/plain"),
+                    },
+                )
+                signature = (tmp_path / "spoolbuddy-1.2.3.bin.sig").read_text()
+
+        assert response.status_code == 200
+        assert response.json()["signed"] is True
+        assert signature.strip() == "0f" * 64
+
+    async def test_upload_invalid_signature(self, async_client):
+        """Test a signature that isn't 128 hex characters is rejected with the image."""
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            tmp_path = Path(tmp_dir)
+            valid_firmware = b"\xe9" + b"\x00" * 1023
+
+            with patch("api.firmware.FIRMWARE_DIR", tmp_path):
+                response = await async_client.post(
+                    "/api/firmware/upload",
+                    files={
+                        "file": ("spoolbuddy-1.2.3.bin", valid_firmware, "application/octet-stream"),
+                        "signature": ("spoolbuddy-1.2.3.bin.sig", b"0f" * 32, "text/plain"),
+                    },
+                )
+
+            assert response.status_code == 400
+            assert "Invalid signature" in response.json()["detail"]
+            assert not (tmp_path / "spoolbuddy-1.2.3.bin").exists()
+
 
 class TestFirmwareDeleteAPI:
     """Tests for firmware deletion endpoint."""
@@ -484,6 +541,8 @@ class TestFirmwareDeleteAPI:
             tmp_path = Path(tmp_dir)
             firmware_file = tmp_path / "spoolbuddy-1.0.0.bin"
             firmware_file.write_bytes(b"\x00" * 100)
+            signature_file = tmp_path / "spoolbuddy-1.0.0.bin.sig"
+            signature_file.write_text("ab" * 64)
 
             with patch("api.firmware.FIRMWARE_DIR", tmp_path):
                 response = await async_client.delete("/api/firmware/1.0.0")
@@ -493,6 +552,7 @@ class TestFirmwareDeleteAPI:
         assert data["success"] is True
         assert "deleted" in data["message"].lower()
         assert not firmware_file.exists()
+        assert not signature_file.exists()
 
     async def test_delete_with_v_prefix(self, async_client):
         """Test deletion with v prefix in version."""
@@ -0,0 +1,33 @@
+"""Integration tests for server readiness and shutdown."""
+
+from unittest.mock import patch
+
+from services.event_stream import get_event_stream
+
+
+class TestHealth:
+    """Test the readiness endpoint and the first shutdown step."""
+
+    async def test_health_ready(self, async_client):
+        """Test health reports ready only between startup and shutdown."""
+        with patch("main._lifecycle", "ready"):
+            response = await async_client.get("/api/health")
+        assert response.status_code == 200
+        assert response.json() == {"status": "ready"}
+
+        with patch("main._lifecycle", "starting"):
+            response = await async_client.get("/api/health")
+        assert response.status_code == 503
+        assert response.json() == {"status": "starting"}
+
+    async def test_begin_shutdown_ends_streams(self):
+        """Test beginning shutdown stops reporting ready and wakes SSE subscribers."""
+        import main
+
+        sub = get_event_stream().subscribe()
+        with patch("main._lifecycle", "ready"):
+            await main.begin_shutdown()
+            assert main._lifecycle == "stopping"
+        assert sub.dropped
+        assert sub.queue.get_nowait() is None
+        assert get_event_stream().subscriber_count == 0
@@ -0,0 +1,104 @@
+"""Integration tests for the integrations API (WLED shelf indicator)."""
+
+from unittest.mock import AsyncMock, patch
+
+import pytest
+from services.wled import WledError
+
+WLED = {
+    "enabled": True,
+    "host": "wled.local",
+    "mappings": [
+        {"segment": 0, "location": "Shelf A"},
+        {"segment": 4, "printer_serial": "00M09A000000001", "ams_id": 0, "tray_id": 2},
+    ],
+}
+
+
+@pytest.fixture
+def highlight():
+    with patch("services.wled.WledController.highlight", AsyncMock()) as mock:
+        yield mock
+
+
+class TestWledSettings:
+    """Test WLED settings."""
+
+    async def test_defaults_and_update(self, async_client):
+        """Test the integration is off by default and its settings are stored."""
+        response = await async_client.get("/api/integrations/wled")
+        assert response.json()["enabled"] is False
+
+        response = await async_client.put("/api/integrations/wled", json=WLED)
+        assert response.status_code == 200
+        config = (await async_client.get("/api/integrations/wled")).json()
+        assert config["host"] == "wled.local"
+        assert [m["segment"] for m in config["mappings"]] == [0, 4]
+
+    async def test_invalid_mapping(self, async_client):
+        """Test a mapping needs a location or a complete AMS slot."""
+        response = await async_client.put(
+            "/api/integrations/wled", json={**WLED, "mappings": [{"segment": 1, "printer_serial": "00M09A000000001"}]}
+        )
+        assert response.status_code == 422
+
+    async def test_test_segments(self, async_client, highlight):
+        """Test lighting every mapped segment, only once enabled."""
+        assert (await async_client.post("/api/integrations/wled/test")).status_code == 409
+
+        await async_client.put("/api/integrations/wled", json=WLED)
+        assert (await async_client.post("/api/integrations/wled/test")).status_code == 204
+        assert highlight.call_args.args[1] == [0, 4]
+
+
+class TestLocateSpool:
+    """Test lighting a spool's position."""
+
+    async def test_by_location(self, async_client, spool_factory, highlight):
+        """Test a stored spool lights its location's segment."""
+        spool = await spool_factory(location="shelf a")
+        await async_client.put("/api/integrations/wled", json=WLED)
+
+        response = await async_client.post(f"/api/spools/{spool.id}/locate")
+        assert response.status_code == 200
+        assert (response.json()["location"], response.json()["segments"]) == ("shelf a", [0])
+        assert highlight.call_args.args[1] == [0]
+
+    async def test_loaded_spool(self, async_client, test_db, spool_factory, highlight):
+        """Test a loaded spool lights its AMS slot."""
+        spool = await spool_factory(location="Shelf A")
+        await test_db.assign_spool_to_slot(spool.id, "00M09A000000001", 0, 2)
+        await async_client.put("/api/integrations/wled", json=WLED)
+
+        result = (await async_client.post(f"/api/spools/{spool.id}/locate")).json()
+        assert (result["printer_serial"], result["tray_id"], result["segments"]) == ("00M09A000000001", 2, [4])
+
+    async def test_unmapped_location(self, async_client, spool_factory, highlight):
+        """Test a spool whose position isn't mapped lights nothing."""
+        spool = await spool_factory(location="Garage")
+        await async_client.put("/api/integrations/wled", json=WLED)
+
+        assert (await async_client.post(f"/api/spools/{spool.id}/locate")).json()["segments"] == []
+        highlight.assert_not_called()
+
+    async def test_errors(self, async_client, spool_factory):
+        """Test locating needs the integration enabled and reports an unreachable controller."""
+        spool = await spool_factory(location="Shelf A")
+        assert (await async_client.post(f"/api/spools/{spool.id}/locate")).status_code == 409
+
+        await async_client.put("/api/integrations/wled", json=WLED)
+        assert (await async_client.post("/api/spools/missing/locate")).status_code == 404
+        with patch("services.wled.WledController.highlight", AsyncMock(side_effect=WledError("unreachable"))):
+            assert (await async_client.post(f"/api/spools/{spool.id}/locate")).status_code == 502
+
+    async def test_search_locate(self, async_client, spool_factory, highlight):
+        """Test a search with locate lights the best spool match, and still answers if WLED fails."""
+        await spool_factory(color_name="Jade White", location="Shelf A")
+        await async_client.put("/api/integrations/wled", json=WLED)
+
+        response = await async_client.get("/api/search", params={"q": "jade", "locate": "true"})
+        assert len(response.json()) == 1
+        assert highlight.call_args.args[1] == [0]
+
+        highlight.side_effect = WledError("unreachable")
+        assert (await async_client.get("/api/search", params={"q": "jade", "locate": "true"})).status_code == 200
@@ -0,0 +1,57 @@
+"""Integration tests for the background jobs API."""
+
+from services.jobs import get_job_runner
+
+
+class TestJobsAPI:
+    """Test starting and following background jobs."""
+
+    async def test_report_job(self, async_client):
+        """Test a report job runs in the background and stores the report as its result."""
+        response = await async_client.post(
+            "/api/jobs", json={"kind": "report", "params": {"report": "usage", "group_by": "month"}}
+        )
+        assert response.status_code == 202
+        job = response.json()
+        assert (job["kind"], job["status"]) == ("report", "queued")
+
+        await get_job_runner().wait(job["id"])
+        response = await async_client.get(f"/api/jobs/{job['id']}")
+        assert response.status_code == 200
+        data = response.json()
+        assert data["status"] == "completed"
+        assert data["result"]["group_by"] == "month"
+
+        response = await async_client.get("/api/jobs", params={"kind": "report"})
+        assert [j["id"] for j in response.json()] == [job["id"]]
+
+    async def test_start_invalid(self, async_client):
+        """Test unknown kinds and invalid parameters are rejected before anything runs."""
+        response = await async_client.post("/api/jobs", json={"kind": "nonexistent"})
+        assert response.status_code == 404
+
+        response = await async_client.post("/api/jobs", json={"kind": "report", "params": {"report": "sales"}})
+        assert response.status_code == 422
+
+        response = await async_client.post(
+            "/api/jobs", json={"kind": "report", "params": {"report": "projects", "group_by": "month"}}
+        )
+        assert response.status_code == 422
+
+    async def test_job_kinds(self, async_client):
+        """Test the registered job kinds are listed."""
+        response = await async_client.get("/api/jobs/kinds")
+        assert {"backup", "color_import", "report"} <= set(response.json())
+
+    async def test_job_not_found(self, async_client):
+        """Test getting and cancelling an unknown job."""
+        assert (await async_client.get("/api/jobs/nonexistent")).status_code == 404
+        assert (await async_client.post("/api/jobs/nonexistent/cancel")).status_code == 404
+
+    async def test_cancel_finished_job(self, async_client):
+        """Test a finished job can't be cancelled."""
+        job = (await async_client.post("/api/jobs", json={"kind": "report", "params": {"report": "energy"}})).json()
+        await get_job_runner().wait(job["id"])
+
+        response = await async_client.post(f"/api/jobs/{job['id']}/cancel")
+        assert response.status_code == 409
@@ -0,0 +1,106 @@
+"""Integration tests for the check-in/check-out kiosk API."""
+
+from unittest.mock import AsyncMock, patch
+
+import pytest
+from services.kiosk import Kiosk
+
+
+@pytest.fixture
+def kiosk():
+    """A fresh kiosk, with no member signed in."""
+    kiosk = Kiosk()
+    with patch("services.kiosk._kiosk", kiosk):
+        yield kiosk
+
+
+class TestKioskMembersAPI:
+    """Test the member registry."""
+
+    async def test_create_list_update(self, async_client):
+        """Test adding, listing and deactivating members."""
+        response = await async_client.post("/api/kiosk/members", json={"name": "Ada", "badge_id": "BADGE=="})
+        assert response.status_code == 201
+        member = response.json()
+        assert (member["name"], member["active"], member["open_checkouts"]) == ("Ada", True, 0)
+        await async_client.post("/api/kiosk/members", json={"name": "Bob"})
+
+        response = await async_client.patch(f"/api/kiosk/members/{member['id']}", json={"active": False})
+        assert response.json()["active"] is False
+        assert [m["name"] for m in (await async_client.get("/api/kiosk/members")).json()] == ["Ada", "Bob"]
+        response = await async_client.get("/api/kiosk/members", params={"include_inactive": False})
+        assert [m["name"] for m in response.json()] == ["Bob"]
+
+    async def test_badge_must_be_free(self, async_client, spool_factory):
+        """Test a badge can't be given to two members or be a spool tag."""
+        await spool_factory(tag_id="SPOOL==")
+        await async_client.post("/api/kiosk/members", json={"name": "Ada", "badge_id": "BADGE=="})
+        bob = (await async_client.post("/api/kiosk/members", json={"name": "Bob"})).json()
+
+        response = await async_client.post("/api/kiosk/members", json={"name": "Eve", "badge_id": "BADGE=="})
+        assert response.status_code == 409
+        response = await async_client.patch(f"/api/kiosk/members/{bob['id']}", json={"badge_id": "SPOOL=="})
+        assert response.status_code == 409
+
+    async def test_delete(self, async_client):
+        """Test deleting a member."""
+        member = (await async_client.post("/api/kiosk/members", json={"name": "Ada"})).json()
+        assert (await async_client.delete(f"/api/kiosk/members/{member['id']}")).status_code == 204
+        assert (await async_client.get(f"/api/kiosk/members/{member['id']}")).status_code == 404
+
+
+class TestKioskDeviceFlow:
+    """Test checking spools out and in from the device."""
+
+    async def _post_state(self, async_client, test_db, **params):
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()),
+        ):
+            response = await async_client.post("/api/display/state", params=params)
+            # Spool off the scale
+            await async_client.post("/api/display/state", params={"weight": 0, "stable": True})
+            return response.json()
+
+    async def test_off_by_default(self, async_client, test_db, kiosk):
+        """Test the device reply has no kiosk screen unless kiosk mode is on."""
+        data = await self._post_state(async_client, test_db, weight=0, stable=True, tag_id="BADGE==")
+        assert data["kiosk"] is None
+
+    async def test_check_out_and_in(self, async_client, test_db, spool_factory, kiosk):
+        """Test a member checks a spool out and back in, with the usage logged against them."""
+        spool = await spool_factory(tag_id="SPOOL==")
+        member = (await async_client.post("/api/kiosk/members", json={"name": "Ada", "badge_id": "BADGE=="})).json()
+        await async_client.put("/api/kiosk/config", json={"enabled": True})
+
+        data = await self._post_state(async_client, test_db, weight=100, stable=True, tag_id="BADGE==")
+        assert (data["kiosk"]["state"], data["kiosk"]["member_name"]) == ("scan_spool", "Ada")
+
+        data = await self._post_state(async_client, test_db, weight=1200, stable=True, tag_id="SPOOL==")
+        assert data["kiosk"]["state"] == "checked_out"
+        assert data["action"] == "none"
+        [checkout] = (await async_client.get("/api/kiosk/checkouts", params={"open": True})).json()
+        assert (checkout["spool_id"], checkout["member_name"]) == (spool.id, "Ada")
+
+        data = await self._post_state(async_client, test_db, weight=1100, stable=True, tag_id="SPOOL==")
+        assert (data["kiosk"]["state"], data["kiosk"]["weight_used"]) == ("checked_in", 100)
+        assert (await async_client.get("/api/kiosk/checkouts", params={"open": True})).json() == []
+        [usage] = await test_db.get_usage_history(spool_id=spool.id)
+        assert usage["member_id"] == member["id"]
+
+
+class TestKioskCheckInAPI:
+    """Test closing checkouts from the web UI."""
+
+    async def test_manual_check_in(self, async_client, test_db, spool_factory):
+        """Test checking a spool in by hand, and that it can only be done once."""
+        spool = await spool_factory()
+        member = await test_db.create_member("Ada")
+        checkout = await test_db.check_out_spool(spool.id, member["id"], 1200)
+        url = f"/api/kiosk/checkouts/{checkout['id']}/check-in"
+
+        response = await async_client.post(url, json={"weight": 1150})
+        assert response.status_code == 200
+        assert response.json()["weight_used"] == 50
+        assert (await async_client.post(url, json={})).status_code == 409
+        assert (await async_client.post("/api/kiosk/checkouts/999/check-in", json={})).status_code == 404
@@ -0,0 +1,104 @@
+"""Integration tests for Moonraker (Klipper) printers."""
+
+from unittest.mock import AsyncMock, MagicMock, patch
+
+import pytest
+from services.moonraker import ActiveSpoolEvent, JobFinishedEvent
+
+
+@pytest.fixture
+def mock_moonraker_manager():
+    """Moonraker manager that never opens a websocket."""
+    manager = MagicMock()
+    manager.status.return_value = {"connected": True, "active_spool_id": 3, "last_error": None}
+    with patch("api.moonraker.get_moonraker_manager", return_value=manager):
+        yield manager
+
+
+class TestMoonrakerAPI:
+    """Test Klipper printer registration."""
+
+    async def test_create_printer(self, async_client, mock_moonraker_manager):
+        """Test a printer is stored and its agent started."""
+        response = await async_client.post(
+            "/api/moonraker/printers",
+            json={"name": "Voron", "url": "http://voron.local:7125/", "api_key": "abc"},
+        )
+        assert response.status_code == 201
+
+        data = response.json()
+        assert data["serial"] == f"moonraker-{data['id']}"
+        assert data["url"] == "http://voron.local:7125"
+        assert data["has_api_key"] is True
+        assert "api_key" not in data
+        assert data["connected"] is True
+        assert mock_moonraker_manager.start.call_count == 1
+
+    async def test_update_restarts_agent(self, async_client, mock_moonraker_manager):
+        """Test changing a printer restarts its agent with the new settings."""
+        created = (await async_client.post("/api/moonraker/printers", json={"name": "A", "url": "a.local"})).json()
+
+        response = await async_client.patch(f"/api/moonraker/printers/{created['id']}", json={"enabled": False})
+        assert response.status_code == 200
+        assert response.json()["enabled"] is False
+        assert mock_moonraker_manager.start.call_args[0][0]["enabled"] is False
+
+    async def test_empty_url_rejected(self, async_client, mock_moonraker_manager):
+        """Test a blank URL is rejected."""
+        response = await async_client.post("/api/moonraker/printers", json={"name": "A", "url": " "})
+        assert response.status_code == 422
+
+    async def test_delete_printer(self, async_client, test_db, spool_factory, mock_moonraker_manager):
+        """Test deleting a printer stops its agent and clears its assignment."""
+        created = (await async_client.post("/api/moonraker/printers", json={"name": "A", "url": "a.local"})).json()
+        spool = await spool_factory()
+        await test_db.assign_spool_to_slot(spool.id, created["serial"], 255, 0)
+
+        response = await async_client.delete(f"/api/moonraker/printers/{created['id']}")
+        assert response.status_code == 204
+        mock_moonraker_manager.stop.assert_called_with(created["id"])
+        assert await test_db.get_spool_for_slot(created["serial"], 255, 0) is None
+
+        response = await async_client.delete(f"/api/moonraker/printers/{created['id']}")
+        assert response.status_code == 404
+
+
+class TestMoonrakerSync:
+    """Test active spool and usage sync from Moonraker events."""
+
+    async def _handle(self, test_db, event):
+        from main import on_moonraker_event
+
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()),
+        ):
+            await on_moonraker_event(1, event)
+
+    async def test_active_spool_assigned_by_number(self, test_db, spool_factory):
+        """Test the active spool ID selects the spool with that number."""
+        await spool_factory()
+        second = await spool_factory()
+
+        await self._handle(test_db, ActiveSpoolEvent(spool_id=second.spool_number))
+        assert await test_db.get_spool_for_slot("moonraker-1", 255, 0) == second.id
+
+        await self._handle(test_db, ActiveSpoolEvent(spool_id=None))
+        assert await test_db.get_spool_for_slot("moonraker-1", 255, 0) is None
+
+    async def test_finished_job_logs_usage(self, test_db, spool_factory):
+        """Test a finished job charges the active spool with the extruded filament."""
+        spool = await spool_factory()
+        await test_db.assign_spool_to_slot(spool.id, "moonraker-1", 255, 0)
+
+        event = JobFinishedEvent(
+            filename="benchy.gcode",
+            status="cancelled",
+            filament_used=1000,
+            metadata={"filament_total": 2000, "filament_weight_total": 10},
+        )
+        await self._handle(test_db, event)
+
+        history = await test_db.get_usage_history(spool_id=spool.id)
+        assert [(h["print_name"], h["weight_used"]) for h in history] == [("benchy", 5)]
+        assert (await test_db.get_spool(spool.id)).consumed_since_add == 5
@@ -0,0 +1,78 @@
+"""Integration tests for the notification channels API."""
+
+
+class TestNotificationsAPI:
+    """Test notification channel configuration."""
+
+    async def test_list_channel_types(self, async_client):
+        """Test listing built-in channel types."""
+        response = await async_client.get("/api/notifications/types")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["ntfy"] == ["topic"]
+        assert data["telegram"] == ["bot_token", "chat_id"]
+        assert data["discord"] == ["webhook_url"]
+
+    async def test_create_channel(self, async_client):
+        """Test creating an ntfy channel."""
+        response = await async_client.post(
+            "/api/notifications/channels",
+            json={"name": "Phone", "type": "ntfy", "config": {"topic": "spools"}, "events": ["spool.low"]},
+        )
+        assert response.status_code == 201
+
+        data = response.json()
+        assert data["type"] == "ntfy"
+        assert data["config"] == {"topic": "spools"}
+        assert data["events"] == ["spool.low"]
+
+    async def test_create_channel_missing_config(self, async_client):
+        """Test required config keys are enforced per type."""
+        response = await async_client.post(
+            "/api/notifications/channels",
+            json={"name": "Bot", "type": "telegram", "config": {"bot_token": "x"}, "events": ["spool.low"]},
+        )
+        assert response.status_code == 400
+        assert "chat_id" in response.json()["detail"]
+
+    async def test_create_channel_unknown_type(self, async_client):
+        """Test unknown channel types are rejected."""
+        response = await async_client.post(
+            "/api/notifications/channels",
+            json={"name": "Pager", "type": "pager", "config": {}, "events": ["spool.low"]},
+        )
+        assert response.status_code == 400
+
+    async def test_update_and_delete_channel(self, async_client):
+        """Test updating events and deleting a channel."""
+        create = await async_client.post(
+            "/api/notifications/channels",
+            json={"name": "Discord", "type": "discord", "config": {"webhook_url": "http://d"}, "events": ["tag.scanned"]},
+        )
+        channel_id = create.json()["id"]
+
+        response = await async_client.patch(
+            f"/api/notifications/channels/{channel_id}", json={"events": ["print.finished"], "enabled": False}
+        )
+        assert response.status_code == 200
+        assert response.json()["events"] == ["print.finished"]
+        assert response.json()["enabled"] is False
+
+        response = await async_client.delete(f"/api/notifications/channels/{channel_id}")
+        assert response.status_code == 204
+        response = await async_client.get(f"/api/notifications/channels/{channel_id}")
+        assert response.status_code == 404
+
+    async def test_test_channel(self, async_client, mock_httpx_client):
+        """Test sending a test notification."""
+        create = await async_client.post(
+            "/api/notifications/channels",
+            json={"name": "Discord", "type": "discord", "config": {"webhook_url": "http://d"}, "events": ["spool.low"]},
+        )
+        channel_id = create.json()["id"]
+
+        response = await async_client.post(f"/api/notifications/channels/{channel_id}/test")
+        assert response.status_code == 200
+        assert response.json() == {"success": True, "error": None}
+        assert mock_httpx_client.post.call_args.args[0] == "http://d"
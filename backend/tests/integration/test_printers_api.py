@@ -1,5 +1,7 @@
 """Integration tests for the printers API."""
 
+from unittest.mock import AsyncMock, patch
+
 import pytest
 
 
@@ -12,6 +14,19 @@ class TestPrintersAPI:
         assert response.status_code == 200
         assert response.json() == []
 
+    async def test_list_printers_not_modified(self, async_client, printer_factory):
+        """Test an unchanged printer list is answered with 304 until a printer changes."""
+        printer = await printer_factory()
+
+        etag = (await async_client.get("/api/printers")).headers["ETag"]
+        response = await async_client.get("/api/printers", headers={"If-None-Match": etag})
+        assert response.status_code == 304
+
+        await async_client.put(f"/api/printers/{printer.serial}", json={"name": "Renamed"})
+        response = await async_client.get("/api/printers", headers={"If-None-Match": etag})
+        assert response.status_code == 200
+        assert response.json()[0]["name"] == "Renamed"
+
     async def test_create_printer(self, async_client, sample_printer_data):
         """Test creating a new printer."""
         response = await async_client.post("/api/printers", json=sample_printer_data)
@@ -61,6 +76,18 @@ class TestPrintersAPI:
         data = response.json()
         assert data["name"] == "Updated Printer Name"
 
+    async def test_update_printer_power_draw(self, async_client, sample_printer_data):
+        """Test the power draw used for energy estimates is stored and validated."""
+        await async_client.post("/api/printers", json=sample_printer_data)
+        url = f"/api/printers/{sample_printer_data['serial']}"
+
+        response = await async_client.put(url, json={"power_watts": 150})
+        assert response.status_code == 200
+        assert response.json()["power_watts"] == 150
+
+        response = await async_client.put(url, json={"power_watts": -1})
+        assert response.status_code == 422
+
     async def test_delete_printer(self, async_client, sample_printer_data):
         """Test deleting a printer."""
         # Create a printer first
@@ -96,6 +123,347 @@ class TestPrintersAPI:
         assert printers[0]["name"] == "New Name"
 
 
+class TestManualPrinters:
+    """Test printers that never connect, for logging usage by hand."""
+
+    async def test_create_manual_printer(self, async_client):
+        """Test a manual printer is created and listed as such."""
+        response = await async_client.post(
+            "/api/printers", json={"serial": "mk3s", "name": "Prusa MK3S", "printer_type": "manual"}
+        )
+        assert response.status_code == 201
+        assert response.json()["printer_type"] == "manual"
+
+        [printer] = (await async_client.get("/api/printers")).json()
+        assert printer["printer_type"] == "manual"
+        assert printer["connected"] is False
+
+    async def test_manual_printer_never_connects(self, async_client, printer_factory, mock_printer_manager):
+        """Test connecting or auto-connecting a manual printer is refused."""
+        printer = await printer_factory(printer_type="manual", ip_address=None, access_code=None)
+
+        response = await async_client.post(f"/api/printers/{printer.serial}/connect")
+        assert response.status_code == 400
+        mock_printer_manager.connect.assert_not_called()
+
+        response = await async_client.post(f"/api/printers/{printer.serial}/auto-connect", json={"auto_connect": True})
+        assert response.status_code == 400
+        response = await async_client.put(f"/api/printers/{printer.serial}", json={"auto_connect": True})
+        assert response.status_code == 400
+
+        response = await async_client.post(
+            "/api/printers", json={"serial": "mk4", "printer_type": "manual", "auto_connect": True}
+        )
+        assert response.status_code == 422
+
+    async def test_manual_printer_usage_statistics(self, async_client, printer_factory, spool_factory):
+        """Test manual usage and external holder spools count towards the printer."""
+        printer = await printer_factory(name="Prusa MK3S", printer_type="manual", ip_address=None, access_code=None)
+        spool = await spool_factory()
+
+        response = await async_client.put(f"/api/printers/{printer.serial}/external-spool", json={"spool_id": spool.id})
+        assert response.status_code == 200
+        assert response.json()["spool"]["id"] == spool.id
+
+        response = await async_client.post(
+            f"/api/spools/{spool.id}/usage", json={"weight_used": 42, "printer_serial": printer.serial}
+        )
+        assert response.status_code == 200
+
+        groups = (await async_client.get("/api/reports/usage?group_by=printer")).json()["groups"]
+        assert [(g["key"], g["weight_used"]) for g in groups] == [("Prusa MK3S", 42)]
+
+    async def test_manual_printer_has_no_ams(self, async_client, printer_factory, spool_factory):
+        """Test AMS assignment points manual printers at the external holder."""
+        printer = await printer_factory(printer_type="manual", ip_address=None, access_code=None)
+        spool = await spool_factory()
+
+        response = await async_client.post(
+            f"/api/printers/{printer.serial}/ams/0/tray/0/assign", json={"spool_id": spool.id}
+        )
+        assert response.status_code == 400
+        assert "external spool holder" in response.json()["detail"]
+
+
+class TestPrinterCertificate:
+    """Test TLS certificate pinning status and re-pinning."""
+
+    FINGERPRINT = ":".join(["AB"] * 32)
+
+    async def test_certificate_status(self, async_client, printer_factory, test_db):
+        """Test the pinned fingerprint and a refused certificate are reported."""
+        printer = await printer_factory()
+        response = await async_client.get(f"/api/printers/{printer.serial}/certificate")
+        assert response.status_code == 200
+        assert response.json() == {"pinned": None, "mismatch": None, "insecure": False}
+
+        await test_db.set_printer_cert_fingerprint(printer.serial, self.FINGERPRINT)
+        response = await async_client.get(f"/api/printers/{printer.serial}/certificate")
+        assert response.json()["pinned"] == self.FINGERPRINT
+
+    async def test_certificate_not_found(self, async_client):
+        """Test the certificate of an unknown printer is a 404."""
+        response = await async_client.get("/api/printers/nonexistent-serial/certificate")
+        assert response.status_code == 404
+
+    async def test_repin_refused_certificate(self, async_client, printer_factory, test_db, mock_printer_manager):
+        """Test re-pinning trusts the certificate that was refused and reconnects."""
+        printer = await printer_factory()
+        await test_db.set_printer_cert_fingerprint(printer.serial, ":".join(["CD"] * 32))
+        mock_printer_manager.cert_mismatch.return_value = self.FINGERPRINT
+
+        response = await async_client.post(f"/api/printers/{printer.serial}/certificate/repin")
+        assert response.status_code == 200
+        assert response.json()["pinned"] == self.FINGERPRINT
+        assert (await test_db.get_printer(printer.serial)).cert_fingerprint == self.FINGERPRINT
+        mock_printer_manager.connect.assert_called_once()
+        assert mock_printer_manager.connect.call_args.kwargs["cert_fingerprint"] == self.FINGERPRINT
+
+    async def test_repin_given_fingerprint(self, async_client, printer_factory):
+        """Test a fingerprint can be given in any spelling, and is validated."""
+        printer = await printer_factory()
+        url = f"/api/printers/{printer.serial}/certificate/repin"
+
+        response = await async_client.post(url, json={"fingerprint": "ab" * 32})
+        assert response.status_code == 200
+        assert response.json()["pinned"] == self.FINGERPRINT
+
+        response = await async_client.post(url, json={"fingerprint": "not-a-fingerprint"})
+        assert response.status_code == 422
+
+    async def test_repin_clears_pin(self, async_client, printer_factory, test_db):
+        """Test re-pinning without a known certificate pins the next one seen."""
+        printer = await printer_factory()
+        await test_db.set_printer_cert_fingerprint(printer.serial, self.FINGERPRINT)
+
+        response = await async_client.post(f"/api/printers/{printer.serial}/certificate/repin")
+        assert response.status_code == 200
+        assert response.json()["pinned"] is None
+
+
+class TestPrinterAccessCode:
+    """Test printers that refused their access code."""
+
+    async def test_refused_code_flagged(self, async_client, printer_factory, mock_printer_manager):
+        """Test a printer refusing its stored access code is flagged."""
+        printer = await printer_factory()
+        mock_printer_manager.access_code_refused.side_effect = lambda serial, code: code == "12345678"
+
+        [listed] = (await async_client.get("/api/printers")).json()
+        assert listed["needs_access_code"] is True
+        response = await async_client.get(f"/api/printers/{printer.serial}")
+        assert response.json()["needs_access_code"] is True
+
+    async def test_new_code_reconnects(self, async_client, printer_factory, mock_printer_manager):
+        """Test setting a new access code reconnects a printer that refused the old one."""
+        printer = await printer_factory()
+        mock_printer_manager.access_code_refused.side_effect = lambda serial, code: code == "12345678"
+
+        await async_client.put(f"/api/printers/{printer.serial}", json={"name": "Renamed"})
+        mock_printer_manager.connect.assert_not_called()
+
+        response = await async_client.put(f"/api/printers/{printer.serial}", json={"access_code": "87654321"})
+        assert response.status_code == 200
+        mock_printer_manager.connect.assert_called_once()
+        assert mock_printer_manager.connect.call_args.kwargs["access_code"] == "87654321"
+
+
+class TestPrinterFirmware:
+    """Test firmware versions reported by get_version."""
+
+    async def test_versions_stored_and_update_flagged(self, async_client, test_db, printer_factory):
+        """Test versions show up in the API and only a changed firmware raises an event."""
+        from main import handle_printer_version
+        from models import FirmwareModule
+
+        printer = await printer_factory()
+
+        def modules(version):
+            return [FirmwareModule(name="ota", sw_ver=version), FirmwareModule(name="mc", hw_ver="MC07")]
+
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()),
+            patch("main.emit_event") as emit,
+        ):
+            await handle_printer_version(printer.serial, modules("01.07.00.00"))
+            await handle_printer_version(printer.serial, modules("01.07.00.00"))
+            emit.assert_not_called()
+
+            await handle_printer_version(printer.serial, modules("01.08.02.00"))
+
+        emit.assert_called_once()
+        event, data = emit.call_args[0]
+        assert event == "printer.firmware_changed"
+        assert (data["old_version"], data["new_version"]) == ("01.07.00.00", "01.08.02.00")
+
+        data = (await async_client.get(f"/api/printers/{printer.serial}")).json()
+        assert (data["firmware_version"], data["hardware_version"]) == ("01.08.02.00", "MC07")
+
+
+class TestAmsUnits:
+    """Test AMS units tracked by serial number."""
+
+    @staticmethod
+    def unit(serial, ams_id, **fields):
+        return {"serial": serial, "ams_id": ams_id, "module": "n3f", "firmware_version": "00.00.06.49", **fields}
+
+    async def test_assignments_follow_swapped_units(self, test_db, spool_factory, printer_factory):
+        """Test a spool stays assigned to its AMS when the printer swaps the ams_ids."""
+        printer = await printer_factory()
+        spool_a, spool_b = await spool_factory(), await spool_factory()
+        await test_db.sync_ams_units(printer.serial, [self.unit("AMS-A", 0), self.unit("AMS-B", 1)])
+        await test_db.assign_spool_to_slot(spool_a.id, printer.serial, 0, 0)
+        await test_db.assign_spool_to_slot(spool_b.id, printer.serial, 1, 0)
+        ids = {u["serial"]: u["id"] for u in await test_db.get_ams_units(printer.serial)}
+
+        moves = await test_db.sync_ams_units(printer.serial, [self.unit("AMS-A", 1), self.unit("AMS-B", 0)])
+
+        assert {(m["serial"], m["old_ams_id"], m["new_ams_id"]) for m in moves} == {("AMS-A", 0, 1), ("AMS-B", 1, 0)}
+        assignments = {a["spool_id"]: (a["ams_id"], a["ams_unit_id"]) for a in await test_db.get_slot_assignments(printer.serial)}
+        assert assignments == {spool_a.id: (1, ids["AMS-A"]), spool_b.id: (0, ids["AMS-B"])}
+
+    async def test_detached_units(self, async_client, test_db, printer_factory):
+        """Test units the printer no longer reports are only listed on request."""
+        printer = await printer_factory()
+        await test_db.sync_ams_units(printer.serial, [self.unit("AMS-A", 0, humidity_sensor=True), self.unit("AMS-B", 1)])
+        await test_db.sync_ams_units(printer.serial, [self.unit("AMS-A", 0)])
+
+        url = f"/api/printers/{printer.serial}/ams-units"
+        [unit] = (await async_client.get(url)).json()
+        assert (unit["serial"], unit["ams_id"], unit["humidity_sensor"]) == ("AMS-A", 0, True)
+        units = (await async_client.get(url, params={"include_detached": True})).json()
+        assert [(u["serial"], u["ams_id"]) for u in units] == [("AMS-A", 0), ("AMS-B", None)]
+        assert (await async_client.get("/api/printers/UNKNOWN/ams-units")).status_code == 404
+
+
+class TestPrintControl:
+    """Test speed and skip object commands."""
+
+    async def test_set_speed_level(self, async_client, mock_printer_manager):
+        """Test the speed level is sent while printing."""
+        from models import PrinterState, SpeedLevel
+
+        mock_printer_manager.is_connected.return_value = True
+        mock_printer_manager.get_state.return_value = PrinterState(gcode_state="RUNNING")
+
+        response = await async_client.post("/api/printers/S1/speed", json={"level": 3})
+
+        assert response.status_code == 202
+        assert response.json()["command"] == "print_speed"
+        mock_printer_manager.set_speed_level.assert_called_once_with("S1", SpeedLevel.SPORT)
+        assert (await async_client.post("/api/printers/S1/speed", json={"level": 5})).status_code == 422
+
+    async def test_skip_objects_needs_a_print(self, async_client, mock_printer_manager):
+        """Test objects can only be skipped during a print."""
+        from models import PrinterState
+
+        mock_printer_manager.is_connected.return_value = True
+        mock_printer_manager.get_state.return_value = PrinterState(gcode_state="IDLE")
+        response = await async_client.post("/api/printers/S1/skip-objects", json={"obj_ids": [3]})
+        assert response.status_code == 409
+
+        mock_printer_manager.get_state.return_value = PrinterState(gcode_state="PAUSE")
+        response = await async_client.post("/api/printers/S1/skip-objects", json={"obj_ids": [3, 7]})
+        assert response.status_code == 202
+        mock_printer_manager.skip_objects.assert_called_once_with("S1", [3, 7])
+
+    async def test_pause_resume_stop(self, async_client, mock_printer_manager):
+        """Test print control follows the print state."""
+        from models import PrintAction, PrinterState
+
+        mock_printer_manager.is_connected.return_value = True
+        mock_printer_manager.get_state.return_value = PrinterState(gcode_state="RUNNING")
+
+        response = await async_client.post("/api/printers/S1/print/pause")
+        assert response.status_code == 202
+        assert response.json()["command"] == "pause"
+        mock_printer_manager.control_print.assert_called_once_with("S1", PrintAction.PAUSE)
+        assert (await async_client.post("/api/printers/S1/print/resume")).status_code == 409
+        assert (await async_client.post("/api/printers/S1/print/restart")).status_code == 422
+
+        mock_printer_manager.get_state.return_value = PrinterState(gcode_state="PAUSE")
+        assert (await async_client.post("/api/printers/S1/print/resume")).status_code == 202
+        assert (await async_client.post("/api/printers/S1/print/stop")).status_code == 202
+
+    async def test_active_spools_listed(
+        self, async_client, test_db, mock_printer_manager, printer_factory, spool_factory
+    ):
+        """Test the spool feeding the nozzle is listed with its remaining weight while printing."""
+        from models import AmsTray, AmsUnit, PrinterState
+
+        printer = await printer_factory()
+        spool = await spool_factory(label_weight=1000)
+        await test_db.assign_spool_to_slot(spool.id, printer.serial, 0, 1)
+        tray = AmsTray(ams_id=0, tray_id=1, tray_type="PLA", tray_color="FF0000FF", remain=40)
+        mock_printer_manager.get_connection_statuses.return_value = {printer.serial: True}
+        mock_printer_manager.get_state.return_value = PrinterState(
+            gcode_state="RUNNING", layer_num=12, total_layer_num=80, tray_now=1, ams_units=[AmsUnit(id=0, trays=[tray])]
+        )
+
+        data = (await async_client.get("/api/printers")).json()[0]
+
+        assert (data["layer_num"], data["total_layer_num"]) == (12, 80)
+        [active] = data["active_spools"]
+        assert (active["ams_id"], active["tray_id"], active["spool_id"]) == (0, 1, spool.id)
+        assert active["remaining_grams"] == 400
+
+
+class TestCamera:
+    """Test the chamber camera proxy."""
+
+    async def test_snapshot_formats(self, async_client, printer_factory):
+        """Test snapshots are proxied as JPEG or downscaled RGB565."""
+        import io
+
+        from PIL import Image
+
+        printer = await printer_factory(model="P1S")
+        buf = io.BytesIO()
+        Image.new("RGB", (64, 36), (255, 255, 255)).save(buf, format="JPEG")
+        jpeg = buf.getvalue()
+
+        with patch("api.printers.snapshot", AsyncMock(return_value=jpeg)) as snapshot:
+            response = await async_client.get(f"/api/printers/{printer.serial}/camera/snapshot")
+            assert response.headers["content-type"] == "image/jpeg"
+            assert response.content == jpeg
+            snapshot.assert_called_once_with(printer.ip_address, "12345678", "P1S")
+
+            response = await async_client.get(
+                f"/api/printers/{printer.serial}/camera/snapshot", params={"format": "rgb565", "width": 32, "height": 18}
+            )
+            assert len(response.content) == 32 * 18 * 2
+
+    async def test_camera_errors(self, async_client, printer_factory):
+        """Test an unreachable camera is a bad gateway and a missing decoder is not implemented."""
+        from services.bambu_camera import CameraError, CameraUnavailable
+
+        printer = await printer_factory()
+        url = f"/api/printers/{printer.serial}/camera/snapshot"
+        with patch("api.printers.snapshot", AsyncMock(side_effect=CameraError("Camera unreachable"))):
+            assert (await async_client.get(url)).status_code == 502
+        with patch("api.printers.snapshot", AsyncMock(side_effect=CameraUnavailable("ffmpeg is required"))):
+            assert (await async_client.get(url)).status_code == 501
+        assert (await async_client.get("/api/printers/UNKNOWN/camera/snapshot")).status_code == 404
+
+
+class TestPrinterLogs:
+    """Test request ids and the printer log stream."""
+
+    async def test_request_id_header(self, async_client):
+        """Test a client's request id is echoed, and one is assigned otherwise."""
+        response = await async_client.get("/api/printers", headers={"X-Request-ID": "ui-42"})
+        assert response.headers["X-Request-ID"] == "ui-42"
+
+        response = await async_client.get("/api/printers")
+        assert response.headers["X-Request-ID"]
+
+    async def test_log_stream_not_found(self, async_client):
+        """Test streaming the log of an unknown printer is a 404."""
+        response = await async_client.get("/api/printers/nonexistent-serial/logs/stream")
+        assert response.status_code == 404
+
+
 class TestPrintersDatabase:
     """Test printer database operations directly."""
 
@@ -119,6 +487,14 @@ class TestPrintersDatabase:
         assert len(auto_printers) == 2
         assert all(p.auto_connect for p in auto_printers)
 
+    async def test_manual_printers_not_auto_connected(self, test_db, printer_factory):
+        """Test manual printers are left out of auto-connect even if the flag is set."""
+        printer = await printer_factory(printer_type="manual")
+        await test_db.conn.execute("UPDATE printers SET auto_connect = 1 WHERE serial = ?", (printer.serial,))
+
+        assert await test_db.get_auto_connect_printers() == []
+        assert (await test_db.get_printer(printer.serial)).printer_type == "manual"
+
     async def test_delete_printer(self, test_db, printer_factory):
         """Test deleting a printer."""
         printer = await printer_factory()
@@ -133,3 +509,219 @@ class TestPrintersDatabase:
         """Test deleting a non-existent printer."""
         deleted = await test_db.delete_printer("nonexistent")
         assert deleted is False
+
+
+class TestRunoutEvents:
+    """Test runout/jam event ingestion."""
+
+    async def test_runout_marks_slot_spool_empty(self, async_client, test_db, spool_factory, printer_factory):
+        """Test a runout records the slot's spool and marks it used up."""
+        printer = await printer_factory()
+        spool = await spool_factory(label_weight=1000, core_weight=250)
+        await test_db.assign_spool_to_slot(spool.id, printer.serial, 0, 2)
+
+        with (
+            patch("main.broadcast_message", AsyncMock()),
+            patch("api.printers.emit_event") as emit,
+        ):
+            response = await async_client.post(
+                f"/api/printers/{printer.serial}/events/runout",
+                json={"ams_id": 0, "tray_id": 2, "print_name": "benchy", "source": "diy-sensor"},
+            )
+        assert response.status_code == 201
+
+        event = response.json()
+        assert event["kind"] == "runout"
+        assert event["spool_id"] == spool.id
+        assert event["marked_empty"] is True
+        assert emit.call_args[0][0] == "filament.runout"
+        assert emit.call_args[0][1]["material"] == "PLA"
+
+        updated = await test_db.get_spool(spool.id)
+        assert updated.weight_used == 1000
+        assert updated.weight_current == 250
+
+        history = (await async_client.get(f"/api/spools/{spool.id}/runout-events")).json()
+        assert [e["print_name"] for e in history] == ["benchy"]
+
+    async def test_jam_keeps_spool(self, async_client, test_db, spool_factory, printer_factory):
+        """Test a jam is recorded without touching the spool's weight."""
+        printer = await printer_factory()
+        spool = await spool_factory()
+        await test_db.assign_spool_to_slot(spool.id, printer.serial, 255, 0)
+
+        with patch("main.broadcast_message", AsyncMock()):
+            response = await async_client.post(f"/api/printers/{printer.serial}/events/runout", json={"kind": "jam"})
+        assert response.json()["marked_empty"] is False
+        assert (await test_db.get_spool(spool.id)).weight_used == 0
+
+        events = (await async_client.get(f"/api/printers/{printer.serial}/events/runout")).json()
+        assert [e["kind"] for e in events] == ["jam"]
+
+    async def test_runout_without_assigned_spool(self, async_client, printer_factory):
+        """Test events for empty slots are still recorded."""
+        printer = await printer_factory()
+
+        with patch("main.broadcast_message", AsyncMock()):
+            response = await async_client.post(f"/api/printers/{printer.serial}/events/runout", json={"ams_id": 1})
+        assert response.status_code == 201
+        assert response.json()["spool_id"] is None
+        assert response.json()["marked_empty"] is False
+
+    async def test_runout_unknown_printer(self, async_client):
+        """Test events for unknown printers are rejected."""
+        response = await async_client.post("/api/printers/UNKNOWN/events/runout", json={})
+        assert response.status_code == 404
+
+
+class TestNozzleTracking:
+    """Test installed nozzle tracking and K-profile switching."""
+
+    async def test_set_printer_nozzle_logs_changes(self, test_db, printer_factory):
+        """Test only a different nozzle is logged as a change."""
+        printer = await printer_factory()
+
+        assert await test_db.set_printer_nozzle(printer.serial, 0, "0.4", "hardened_steel") is None
+        assert await test_db.set_printer_nozzle(printer.serial, 0, "0.4", None) is None
+
+        change = await test_db.set_printer_nozzle(printer.serial, 0, "0.6", None)
+        assert change["old_diameter"] == "0.4"
+        assert change["new_diameter"] == "0.6"
+        assert change["new_type"] == "hardened_steel"
+
+        nozzles = await test_db.get_printer_nozzles(printer.serial)
+        assert [(n["extruder_id"], n["diameter"]) for n in nozzles] == [(0, "0.6")]
+        changes = await test_db.get_nozzle_changes(printer.serial)
+        assert [c["id"] for c in changes] == [change["id"]]
+
+    async def test_get_printer_nozzles(self, async_client, test_db, printer_factory):
+        """Test the nozzle endpoint returns installed nozzles and the change log."""
+        printer = await printer_factory()
+        await test_db.set_printer_nozzle(printer.serial, 0, "0.4", "stainless_steel")
+        await test_db.set_printer_nozzle(printer.serial, 0, "0.2", "stainless_steel")
+
+        response = await async_client.get(f"/api/printers/{printer.serial}/nozzles")
+        assert response.status_code == 200
+        data = response.json()
+        assert data["nozzles"] == [{"extruder_id": 0, "diameter": "0.2", "nozzle_type": "stainless_steel"}]
+        assert data["changes"][0]["old_diameter"] == "0.4"
+
+        response = await async_client.get("/api/printers/UNKNOWN/nozzles")
+        assert response.status_code == 404
+
+    async def test_nozzle_change_reapplies_k_profiles(
+        self, async_client, test_db, mock_printer_manager, spool_factory, printer_factory
+    ):
+        """Test slots get the spool's K profile for the new nozzle."""
+        from main import handle_nozzle_change
+        from models import NozzleInfo
+
+        printer = await printer_factory()
+        spool = await spool_factory()
+        await test_db.save_spool_k_profiles(
+            spool.id,
+            [
+                {"printer_serial": printer.serial, "nozzle_diameter": "0.4", "cali_idx": 4, "name": "0.4"},
+                {"printer_serial": printer.serial, "nozzle_diameter": "0.6", "cali_idx": 6, "name": "0.6"},
+            ],
+        )
+        await test_db.assign_spool_to_slot(spool.id, printer.serial, 0, 1)
+        await test_db.set_printer_nozzle(printer.serial, 0, "0.4", None)
+        mock_printer_manager.is_connected.return_value = True
+        mock_printer_manager.get_nozzle_diameter.return_value = "0.6"
+
+        broadcast = AsyncMock()
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", broadcast),
+        ):
+            await handle_nozzle_change(printer.serial, [NozzleInfo(extruder_id=0, diameter="0.6")])
+
+        assert broadcast.call_args[0][0]["type"] == "nozzle_changed"
+        calibration = mock_printer_manager.set_calibration.call_args.kwargs
+        assert (calibration["ams_id"], calibration["tray_id"]) == (0, 1)
+        assert calibration["cali_idx"] == 6
+        assert calibration["nozzle_diameter"] == "0.6"
+
+
+class TestAmsEvents:
+    """Test the AMS slot change ledger."""
+
+    async def test_records_events_with_assigned_spool(self, async_client, test_db, spool_factory, printer_factory):
+        """Test detected events are stored with the slot's spool and listed newest first."""
+        from main import _record_ams_events
+        from services.ams_events import AmsSlotEvent
+
+        printer = await printer_factory()
+        spool = await spool_factory()
+        await test_db.assign_spool_to_slot(spool.id, printer.serial, 0, 1)
+
+        with patch("main.get_db", AsyncMock(return_value=test_db)):
+            await _record_ams_events(
+                printer.serial,
+                [
+                    AmsSlotEvent(event="inserted", ams_id=0, tray_id=1, tray_type="PLA"),
+                    AmsSlotEvent(event="loaded", ams_id=0, tray_id=2, tray_type="PETG"),
+                ],
+            )
+
+        response = await async_client.get(f"/api/printers/{printer.serial}/ams-events")
+        assert response.status_code == 200
+        events = response.json()
+        assert [(e["event"], e["tray_id"], e["spool_id"]) for e in events] == [
+            ("loaded", 2, None),
+            ("inserted", 1, spool.id),
+        ]
+
+        response = await async_client.get(f"/api/printers/{printer.serial}/ams-events?spool_id={spool.id}")
+        assert [e["event"] for e in response.json()] == ["inserted"]
+
+    async def test_unknown_printer(self, async_client):
+        """Test the ledger of an unknown printer is a 404."""
+        response = await async_client.get("/api/printers/UNKNOWN/ams-events")
+        assert response.status_code == 404
+
+
+class TestMaterialCompatibility:
+    """Test material compatibility checks when assigning spools."""
+
+    async def test_abrasive_spool_needs_force(self, async_client, spool_factory, printer_factory):
+        """Test CF filament on a stock brass nozzle is rejected unless forced."""
+        printer = await printer_factory(model="P1S")
+        spool = await spool_factory(material="PA-CF")
+        url = f"/api/printers/{printer.serial}/external-spool"
+
+        response = await async_client.put(url, json={"spool_id": spool.id})
+        assert response.status_code == 422
+        assert "abrasive" in response.json()["detail"]
+
+        response = await async_client.put(url, json={"spool_id": spool.id, "force": True})
+        assert response.status_code == 200
+        assert len(response.json()["warnings"]) == 1
+
+    async def test_compatibility_endpoint(self, async_client, test_db, spool_factory, printer_factory):
+        """Test the compatibility check uses model defaults and per-printer overrides."""
+        from models import PrinterUpdate
+
+        printer = await printer_factory(model="A1")
+        spool = await spool_factory(material="ABS", subtype="GF")
+        url = f"/api/printers/{printer.serial}/compatibility?spool_id={spool.id}"
+
+        data = (await async_client.get(url)).json()
+        assert data["compatible"] is False
+        assert {(i["code"], i["severity"]) for i in data["issues"]} == {("abrasive", "error"), ("enclosure", "warning")}
+
+        await test_db.update_printer(printer.serial, PrinterUpdate(hardened_nozzle=True, enclosed=True))
+        data = (await async_client.get(url)).json()
+        assert data["compatible"] is True
+        assert data["issues"] == []
+        assert data["capabilities"] == {"max_nozzle_temp": 300, "hardened_nozzle": True, "enclosed": True}
+
+    async def test_compatible_spool_assigns_without_warnings(self, async_client, spool_factory, printer_factory):
+        """Test PLA on any printer passes without issues."""
+        printer = await printer_factory(model="A1-Mini")
+        spool = await spool_factory(material="PLA")
+
+        response = await async_client.put(f"/api/printers/{printer.serial}/external-spool", json={"spool_id": spool.id})
+        assert response.status_code == 200
+        assert response.json()["warnings"] == []
@@ -0,0 +1,125 @@
+"""Integration tests for the projects API."""
+
+from unittest.mock import AsyncMock, patch
+
+
+class TestProjectsAPI:
+    """Test project management and usage grouping."""
+
+    async def test_create_and_list(self, async_client):
+        """Test creating a project and listing it."""
+        response = await async_client.post("/api/projects", json={"name": "Voron build", "description": "2.4 R2"})
+        assert response.status_code == 201
+        project = response.json()
+        assert project["name"] == "Voron build"
+
+        response = await async_client.get("/api/projects")
+        assert [p["id"] for p in response.json()] == [project["id"]]
+
+    async def test_archive_and_restore(self, async_client, test_db):
+        """Test archived projects drop out of the default list."""
+        project = await test_db.create_project("Etsy order #123")
+
+        response = await async_client.put(f"/api/projects/{project['id']}", json={"archived": True})
+        assert response.status_code == 200
+        assert response.json()["archived_at"] is not None
+        assert (await async_client.get("/api/projects")).json() == []
+        assert len((await async_client.get("/api/projects?include_archived=true")).json()) == 1
+
+        response = await async_client.put(f"/api/projects/{project['id']}", json={"archived": False})
+        assert response.json()["archived_at"] is None
+
+    async def test_manual_usage_with_project(self, async_client, test_db, spool_factory):
+        """Test manual usage entries can be logged against a project."""
+        spool = await spool_factory()
+        project = await test_db.create_project("Voron build")
+
+        response = await async_client.post(
+            f"/api/spools/{spool.id}/usage", json={"weight_used": 42, "project_id": project["id"]}
+        )
+        assert response.status_code == 200
+
+        response = await async_client.get(f"/api/projects/{project['id']}/usage")
+        assert [u["weight_used"] for u in response.json()] == [42]
+
+    async def test_manual_usage_unknown_project(self, async_client, spool_factory):
+        """Test logging usage against a missing project is rejected."""
+        spool = await spool_factory()
+        response = await async_client.post(f"/api/spools/{spool.id}/usage", json={"weight_used": 5, "project_id": 999})
+        assert response.status_code == 404
+
+    async def test_assign_existing_usage(self, async_client, test_db, spool_factory):
+        """Test moving print jobs into a project."""
+        spool = await spool_factory()
+        project = await test_db.create_project("Voron build")
+        first = await test_db.log_usage(spool.id, "S1", "part A", 10)
+        second = await test_db.log_usage(spool.id, "S1", "part B", 20)
+
+        response = await async_client.post(f"/api/projects/{project['id']}/usage", json={"usage_ids": [first, second]})
+        assert response.json()["count"] == 2
+
+        usage = await test_db.get_project_usage(project["id"])
+        assert sorted(u["print_name"] for u in usage) == ["part A", "part B"]
+
+    async def test_delete_keeps_usage(self, async_client, test_db, spool_factory):
+        """Test deleting a project ungroups its usage instead of removing it."""
+        spool = await spool_factory()
+        project = await test_db.create_project("Scrap")
+        await test_db.log_usage(spool.id, "S1", "test", 10, project_id=project["id"])
+
+        response = await async_client.delete(f"/api/projects/{project['id']}")
+        assert response.status_code == 204
+        assert (await async_client.get(f"/api/projects/{project['id']}")).status_code == 404
+
+        history = await test_db.get_usage_history(spool_id=spool.id)
+        assert len(history) == 1
+        assert history[0]["project_id"] is None
+
+    async def test_active_project(self, async_client, test_db):
+        """Test setting and clearing the active project."""
+        project = await test_db.create_project("Voron build")
+
+        response = await async_client.put("/api/projects/active", json={"project_id": project["id"]})
+        assert response.status_code == 200
+        assert (await async_client.get("/api/projects/active")).json()["project_id"] == project["id"]
+
+        await async_client.put("/api/projects/active", json={"project_id": None})
+        assert (await async_client.get("/api/projects/active")).json()["project_id"] is None
+
+        response = await async_client.put("/api/projects/active", json={"project_id": 999})
+        assert response.status_code == 404
+
+    async def test_print_jobs_join_active_project(self, test_db, spool_factory):
+        """Test automatically logged prints are grouped under the active project."""
+        from main import on_usage_logged
+
+        spool = await spool_factory(label_weight=1000)
+        await test_db.assign_spool_to_slot(spool.id, "S1", 0, 0)
+        project = await test_db.create_project("Voron build")
+        await test_db.set_setting("active_project_id", str(project["id"]))
+
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()),
+        ):
+            await on_usage_logged("S1", "frame", {(0, 0): 10})
+
+        usage = await test_db.get_project_usage(project["id"])
+        assert [u["print_name"] for u in usage] == ["frame"]
+
+    async def test_print_energy_joins_active_project(self, test_db, printer_factory):
+        """Test print energy is logged for printers with a power draw, under the active project."""
+        from main import on_print_ended
+
+        tracked = await printer_factory(power_watts=300)
+        untracked = await printer_factory()
+        project = await test_db.create_project("Voron build")
+        await test_db.set_setting("active_project_id", str(project["id"]))
+
+        with patch("main.get_db", AsyncMock(return_value=test_db)):
+            await on_print_ended(tracked.serial, "frame", 3600)
+            await on_print_ended(untracked.serial, "panels", 3600)
+
+        report = await test_db.get_project_report()
+        assert report[0]["energy_kwh"] == 0.3
+        assert [g["prints"] for g in await test_db.get_energy_report("printer")] == [1]
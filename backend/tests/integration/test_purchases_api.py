@@ -0,0 +1,101 @@
+"""Integration tests for the purchases and restock API."""
+
+
+class TestPurchasesAPI:
+    """Test purchase records."""
+
+    async def test_create_with_spools(self, async_client, spool_factory):
+        """Test a purchase links its spools and prices those without a price."""
+        unpriced = await spool_factory()
+        priced = await spool_factory(price=30)
+
+        response = await async_client.post(
+            "/api/purchases",
+            json={"supplier": "Bambu Store", "price": 45, "quantity": 2, "spool_ids": [unpriced.id, priced.id]},
+        )
+        assert response.status_code == 201
+        purchase = response.json()
+        assert set(purchase["spool_ids"]) == {unpriced.id, priced.id}
+        assert purchase["unit_price"] == 22.5
+        assert purchase["purchased_at"] is not None
+
+        assert (await async_client.get(f"/api/spools/{unpriced.id}")).json()["price"] == 22.5
+        assert (await async_client.get(f"/api/spools/{priced.id}")).json()["price"] == 30
+
+    async def test_unknown_spool(self, async_client):
+        """Test a purchase with a missing spool is rejected without being recorded."""
+        response = await async_client.post("/api/purchases", json={"supplier": "Shop", "spool_ids": ["missing"]})
+        assert response.status_code == 404
+        assert (await async_client.get("/api/purchases")).json() == []
+
+    async def test_url_must_be_web_link(self, async_client):
+        """Test only http(s) supplier links are accepted."""
+        response = await async_client.post("/api/purchases", json={"url": "javascript:alert(1)"})
+        assert response.status_code == 422
+
+    async def test_list_by_supplier(self, async_client):
+        """Test purchases are listed most recent first and filter by supplier."""
+        await async_client.post("/api/purchases", json={"supplier": "Shop A", "purchased_at": 1000})
+        await async_client.post("/api/purchases", json={"supplier": "Shop B", "purchased_at": 2000})
+
+        purchases = (await async_client.get("/api/purchases")).json()
+        assert [p["supplier"] for p in purchases] == ["Shop B", "Shop A"]
+        purchases = (await async_client.get("/api/purchases?supplier=shop a")).json()
+        assert [p["supplier"] for p in purchases] == ["Shop A"]
+
+    async def test_link_move_and_unlink(self, async_client, spool_factory):
+        """Test linking a spool moves it from its previous purchase."""
+        spool = await spool_factory()
+        first = (await async_client.post("/api/purchases", json={"spool_ids": [spool.id]})).json()
+        second = (await async_client.post("/api/purchases", json={})).json()
+
+        response = await async_client.post(f"/api/purchases/{second['id']}/spools", json={"spool_ids": [spool.id]})
+        assert response.json()["spool_ids"] == [spool.id]
+        assert (await async_client.get(f"/api/purchases/{first['id']}")).json()["spool_ids"] == []
+
+        assert (await async_client.delete(f"/api/purchases/{second['id']}/spools/{spool.id}")).status_code == 204
+        assert (await async_client.delete(f"/api/purchases/{second['id']}/spools/{spool.id}")).status_code == 404
+
+    async def test_update_and_delete(self, async_client, spool_factory):
+        """Test updating and deleting a purchase keeps its spools."""
+        spool = await spool_factory()
+        purchase = (await async_client.post("/api/purchases", json={"spool_ids": [spool.id]})).json()
+
+        response = await async_client.put(f"/api/purchases/{purchase['id']}", json={"order_ref": "#1234"})
+        assert response.json()["order_ref"] == "#1234"
+        assert (await async_client.put(f"/api/purchases/{purchase['id']}", json={"quantity": None})).status_code == 422
+
+        assert (await async_client.delete(f"/api/purchases/{purchase['id']}")).status_code == 204
+        assert (await async_client.get(f"/api/purchases/{purchase['id']}")).status_code == 404
+        assert (await async_client.get(f"/api/spools/{spool.id}")).status_code == 200
+
+
+class TestRestockAPI:
+    """Test restock levels and suggestions."""
+
+    async def test_levels(self, async_client):
+        """Test stock levels are stored and returned."""
+        assert (await async_client.get("/api/purchases/restock/levels")).json() == {"default": None, "materials": {}}
+
+        levels = {"default": 500, "materials": {"PLA": 3000}}
+        assert (await async_client.put("/api/purchases/restock/levels", json=levels)).json() == levels
+        assert (await async_client.get("/api/purchases/restock/levels")).json() == levels
+
+    async def test_suggestions(self, async_client, spool_factory, test_db):
+        """Test materials below their level are suggested with their last purchase."""
+        spool = await spool_factory(material="PLA")
+        await spool_factory(material="PETG")
+        await test_db.update_spool_consumption(spool.id, 600)
+        await async_client.post(
+            "/api/purchases",
+            json={"supplier": "Bambu Store", "url": "https://store.example/pla", "price": 20, "spool_ids": [spool.id]},
+        )
+        await async_client.put("/api/purchases/restock/levels", json={"materials": {"PLA": 2000, "PETG": 500}})
+
+        suggestions = (await async_client.get("/api/purchases/restock")).json()
+        assert [s["material"] for s in suggestions] == ["PLA"]
+        assert (suggestions[0]["stock_grams"], suggestions[0]["suggested_spools"]) == (400, 2)
+        assert suggestions[0]["last_purchase"]["url"] == "https://store.example/pla"
+
+        suggestions = (await async_client.get("/api/purchases/restock?include_ok=true")).json()
+        assert {s["material"] for s in suggestions} == {"PLA", "PETG"}
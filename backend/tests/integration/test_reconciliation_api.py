@@ -0,0 +1,87 @@
+"""Integration tests for the weight reconciliation API."""
+
+import time
+
+
+class TestReconciliationAPI:
+    """Test flagging scale readings and resolving the suggestions."""
+
+    async def _weigh_after_usage(self, test_db, spool_id: str, measured: int, used: float = 50):
+        """A scale reading an hour ago, usage logged since, and a new reading now."""
+        now = int(time.time())
+        await test_db.record_weight(spool_id, 1200, "scale", recorded_at=now - 3600)
+        await test_db.log_usage(spool_id, "00M09A000000001", "Benchy", used)
+        await test_db.record_weight(spool_id, measured, "scale", recorded_at=now)
+
+    async def test_scan_flags_untracked_usage(self, async_client, test_db, spool_factory):
+        """Test a reading 120 g below the tracked usage becomes a suggestion, once."""
+        spool = await spool_factory()
+        await self._weigh_after_usage(test_db, spool.id, measured=1030)
+
+        response = await async_client.post("/api/reconciliation/scan")
+        assert response.status_code == 200
+        created = response.json()
+        assert len(created) == 1
+        assert created[0]["spool_id"] == spool.id
+        assert created[0]["tracked_usage"] == 50
+        assert created[0]["discrepancy"] == -120
+        assert created[0]["status"] == "open"
+
+        # Readings are only checked once
+        assert (await async_client.post("/api/reconciliation/scan")).json() == []
+        listed = (await async_client.get("/api/reconciliation", params={"spool_id": spool.id})).json()
+        assert [r["id"] for r in listed] == [created[0]["id"]]
+
+    async def test_scan_within_tolerance(self, async_client, test_db, spool_factory):
+        """Test readings that roughly match the tracked usage aren't flagged."""
+        spool = await spool_factory()
+        await self._weigh_after_usage(test_db, spool.id, measured=1140)
+
+        assert (await async_client.post("/api/reconciliation/scan")).json() == []
+
+    async def test_scan_skips_reset_spool(self, async_client, test_db, spool_factory):
+        """Test a spool reset between readings isn't compared across the reset."""
+        spool = await spool_factory()
+        now = int(time.time())
+        await test_db.record_weight(spool.id, 1200, "scale", recorded_at=now - 3600)
+        await test_db.record_weight(spool.id, 250, "undo", recorded_at=now - 60)
+        await test_db.record_weight(spool.id, 300, "scale", recorded_at=now)
+
+        assert (await async_client.post("/api/reconciliation/scan")).json() == []
+
+    async def test_accept_logs_untracked_usage(self, async_client, test_db, spool_factory):
+        """Test accepting logs the difference as usage, and an adjusted amount instead if given."""
+        spool = await spool_factory()
+        await self._weigh_after_usage(test_db, spool.id, measured=1030)
+        first = (await async_client.post("/api/reconciliation/scan")).json()[0]
+
+        response = await async_client.post(f"/api/reconciliation/{first['id']}/accept")
+        assert response.status_code == 200
+        assert response.json()["status"] == "accepted"
+        assert response.json()["resolved_grams"] == 120
+        history = await test_db.get_usage_history(spool.id)
+        assert [h["weight_used"] for h in history if h["reason"] == "reconciliation"] == [120]
+
+        response = await async_client.post(f"/api/reconciliation/{first['id']}/accept")
+        assert response.status_code == 409
+
+        other = await spool_factory()
+        await self._weigh_after_usage(test_db, other.id, measured=1030)
+        second = (await async_client.post("/api/reconciliation/scan")).json()[0]
+        response = await async_client.post(f"/api/reconciliation/{second['id']}/accept", json={"grams": 100})
+        assert response.json()["resolved_grams"] == 100
+
+    async def test_dismiss(self, async_client, test_db, spool_factory):
+        """Test a dismissed suggestion leaves the usage alone and drops off the open list."""
+        spool = await spool_factory()
+        await self._weigh_after_usage(test_db, spool.id, measured=1030)
+        suggestion = (await async_client.post("/api/reconciliation/scan")).json()[0]
+
+        response = await async_client.post(f"/api/reconciliation/{suggestion['id']}/dismiss")
+        assert response.status_code == 200
+        assert response.json()["status"] == "dismissed"
+        assert len(await test_db.get_usage_history(spool.id)) == 1
+        assert (await async_client.get("/api/reconciliation")).json() == []
+
+        response = await async_client.post("/api/reconciliation/9999/dismiss")
+        assert response.status_code == 404
@@ -0,0 +1,188 @@
+"""Integration tests for the reports API."""
+
+
+class TestReportsAPI:
+    """Test usage report aggregation."""
+
+    async def _log(self, test_db, spool_id, serial, weight, timestamp):
+        await test_db.conn.execute(
+            """INSERT INTO usage_history (spool_id, printer_serial, print_name, weight_used, timestamp)
+               VALUES (?, ?, 'test', ?, ?)""",
+            (spool_id, serial, weight, timestamp),
+        )
+        await test_db.conn.commit()
+
+    async def test_usage_report_empty(self, async_client):
+        """Test report with no usage history."""
+        response = await async_client.get("/api/reports/usage")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["group_by"] == "material"
+        assert data["total_weight"] == 0
+        assert data["groups"] == []
+        assert data["top"] == []
+
+    async def test_usage_report_by_material(self, async_client, test_db, spool_factory):
+        """Test grouping usage by material."""
+        pla = await spool_factory(material="PLA")
+        petg = await spool_factory(material="PETG")
+        await self._log(test_db, pla.id, "S1", 10, 1700000000)
+        await self._log(test_db, pla.id, "S1", 20, 1700001000)
+        await self._log(test_db, petg.id, "S1", 10, 1700002000)
+
+        response = await async_client.get("/api/reports/usage?group_by=material")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["total_weight"] == 40
+        assert data["total_prints"] == 3
+        assert [g["key"] for g in data["groups"]] == ["PLA", "PETG"]
+        assert data["groups"][0]["share"] == 75
+        assert data["groups"][0]["rank"] == 1
+        assert data["groups"][1]["cumulative_weight"] == 40
+
+    async def test_usage_report_by_printer(self, async_client, test_db, spool_factory, printer_factory):
+        """Test grouping usage by printer uses printer name when known."""
+        spool = await spool_factory()
+        printer = await printer_factory(name="Workshop X1")
+        await self._log(test_db, spool.id, printer.serial, 15, 1700000000)
+        await self._log(test_db, spool.id, "UNKNOWN123", 5, 1700000000)
+
+        response = await async_client.get("/api/reports/usage?group_by=printer")
+        assert response.status_code == 200
+
+        keys = [g["key"] for g in response.json()["groups"]]
+        assert keys == ["Workshop X1", "UNKNOWN123"]
+
+    async def test_usage_report_by_month_trend(self, async_client, test_db, spool_factory):
+        """Test monthly report is chronological with change vs previous month."""
+        spool = await spool_factory()
+        await self._log(test_db, spool.id, "S1", 30, 1701475200)  # 2023-12-02
+        await self._log(test_db, spool.id, "S1", 10, 1699000000)  # 2023-11-03
+
+        response = await async_client.get("/api/reports/usage?group_by=month")
+        assert response.status_code == 200
+
+        groups = response.json()["groups"]
+        assert [g["key"] for g in groups] == ["2023-11", "2023-12"]
+        assert groups[0]["change"] is None
+        assert groups[1]["change"] == 20
+        assert groups[1]["rank"] == 1
+
+    async def test_usage_report_local_month(self, async_client, test_db, spool_factory):
+        """Test months follow the calendar of the requested timezone."""
+        spool = await spool_factory()
+        await self._log(test_db, spool.id, "S1", 10, 1709247600)  # 2024-02-29 23:00 UTC, midnight in Berlin
+
+        response = await async_client.get("/api/reports/usage?group_by=month&timezone=UTC")
+        assert response.json()["groups"][0]["key"] == "2024-02"
+
+        response = await async_client.get("/api/reports/usage?group_by=month&timezone=Europe/Berlin")
+        data = response.json()
+        assert data["timezone"] == "Europe/Berlin"
+        group = data["groups"][0]
+        assert group["key"] == "2024-03"
+        assert group["start"] == 1709247600
+        assert group["end"] == 1711922400  # 2024-04-01 00:00 CEST
+
+    async def test_usage_report_by_week_across_dst(self, async_client, test_db, spool_factory):
+        """Test weeks start on the configured day at local midnight, a week with a DST change is an hour short."""
+        spool = await spool_factory()
+        await test_db.set_setting("report_timezone", "Europe/Berlin")
+        await test_db.set_setting("report_week_start", "sunday")
+        await self._log(test_db, spool.id, "S1", 10, 1711846800)  # Sunday 2024-03-31 03:00 CEST
+        await self._log(test_db, spool.id, "S1", 5, 1711835000)  # Saturday 2024-03-30 22:43 CET
+
+        response = await async_client.get("/api/reports/usage?group_by=week")
+        assert response.status_code == 200
+
+        groups = response.json()["groups"]
+        assert [g["key"] for g in groups] == ["2024-03-24", "2024-03-31"]
+        assert groups[1]["change"] == 5
+        assert groups[1]["end"] - groups[1]["start"] == 7 * 86400 - 3600
+
+    async def test_usage_report_unknown_timezone(self, async_client):
+        """Test an unknown timezone is rejected."""
+        response = await async_client.get("/api/reports/usage?group_by=month&timezone=Mars/Olympus_Mons")
+        assert response.status_code == 400
+
+    async def test_usage_report_invalid_group(self, async_client):
+        """Test invalid group_by is rejected."""
+        response = await async_client.get("/api/reports/usage?group_by=color")
+        assert response.status_code == 422
+
+    async def test_project_report(self, async_client, test_db, spool_factory):
+        """Test per-project rollups with cost prorated from spool prices."""
+        priced = await spool_factory(material="PLA", label_weight=1000, price=20)
+        unpriced = await spool_factory(material="PETG")
+        voron = await test_db.create_project("Voron build")
+        etsy = await test_db.create_project("Etsy order #123")
+        await test_db.log_usage(priced.id, "S1", "frame", 100, project_id=voron["id"])
+        await test_db.log_usage(unpriced.id, "S1", "panels", 50, project_id=voron["id"])
+        await test_db.log_usage(priced.id, "S1", "keychain", 10, project_id=etsy["id"])
+        await test_db.log_usage(priced.id, "S1", "ungrouped", 500)
+
+        response = await async_client.get("/api/reports/projects")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["total_weight"] == 160
+        assert data["total_cost"] == 2.2
+        first = data["projects"][0]
+        assert first["name"] == "Voron build"
+        assert first["weight_used"] == 150
+        assert first["prints"] == 2
+        assert first["cost"] == 2.0
+        assert first["priced_weight"] == 100
+        assert [m["material"] for m in first["materials"]] == ["PLA", "PETG"]
+        assert first["materials"][1]["cost"] is None
+
+    async def test_project_report_hides_archived(self, async_client, test_db):
+        """Test archived projects are only included on request."""
+        project = await test_db.create_project("Old")
+        await test_db.update_project(project["id"], archived_at=1700000000)
+
+        response = await async_client.get("/api/reports/projects")
+        assert response.json()["projects"] == []
+
+        response = await async_client.get("/api/reports/projects?include_archived=true")
+        assert [p["name"] for p in response.json()["projects"]] == ["Old"]
+
+    async def test_project_report_includes_energy(self, async_client, test_db, spool_factory):
+        """Test print energy is costed at the electricity price and added to the total."""
+        spool = await spool_factory(label_weight=1000, price=20)
+        project = await test_db.create_project("Voron build")
+        await test_db.log_usage(spool.id, "S1", "frame", 100, project_id=project["id"])
+        await test_db.log_print_energy("S1", "frame", 7200, 250, project_id=project["id"])
+        await test_db.set_setting("electricity_price", "0.3")
+
+        response = await async_client.get("/api/reports/projects")
+        data = response.json()
+        assert data["electricity_price"] == 0.3
+        assert data["total_energy_kwh"] == 0.5
+        assert data["total_energy_cost"] == 0.15
+        assert data["total_cost"] == 2.15
+        assert data["projects"][0]["energy_kwh"] == 0.5
+        assert data["projects"][0]["energy_cost"] == 0.15
+
+    async def test_energy_report_by_printer(self, async_client, test_db, printer_factory):
+        """Test energy is grouped by printer and left uncosted without a price."""
+        printer = await printer_factory(name="Workshop X1", power_watts=200)
+        await test_db.log_print_energy(printer.serial, "a", 3600, 200)
+        await test_db.log_print_energy(printer.serial, "b", 1800, 200)
+        await test_db.log_print_energy("UNKNOWN123", "c", 3600, 100)
+
+        response = await async_client.get("/api/reports/energy?group_by=printer")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["total_energy_kwh"] == 0.4
+        assert data["total_cost"] is None
+        first = data["groups"][0]
+        assert first["key"] == "Workshop X1"
+        assert first["energy_kwh"] == 0.3
+        assert first["duration_sec"] == 5400
+        assert first["prints"] == 2
+        assert first["cost"] is None
+        assert data["groups"][1]["key"] == "UNKNOWN123"
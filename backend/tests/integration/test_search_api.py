@@ -0,0 +1,51 @@
+"""Integration tests for the global search API."""
+
+
+class TestSearchAPI:
+    """Test full-text search across spools, printers and projects."""
+
+    async def test_search_across_kinds(self, async_client, test_db, spool_factory, printer_factory):
+        """Test one query finds spools, printers and projects by prefix."""
+        spool = await spool_factory(brand="Bambu", material="PLA", color_name="Jade White", note="Voron skirts")
+        await spool_factory(brand="Polymaker", material="PETG", color_name="Red")
+        await printer_factory(serial="01P00A000000001", name="Voron Trident")
+        project = await test_db.create_project("Voron build")
+
+        response = await async_client.get("/api/search", params={"q": "vor"})
+        assert response.status_code == 200
+        results = {(r["kind"], r["id"]) for r in response.json()}
+        assert results == {("spool", spool.id), ("printer", "01P00A000000001"), ("project", str(project["id"]))}
+
+        response = await async_client.get("/api/search", params={"q": "jade wh"})
+        [result] = response.json()
+        assert result["title"] == f"#{spool.spool_number} Bambu PLA Jade White"
+
+    async def test_search_filters_by_kind(self, async_client, test_db, printer_factory):
+        """Test the kind filter."""
+        await printer_factory(name="Workshop")
+        await test_db.create_project("Workshop shelves")
+
+        response = await async_client.get("/api/search", params={"q": "workshop", "kind": "project"})
+        assert [r["kind"] for r in response.json()] == ["project"]
+
+    async def test_search_follows_changes(self, async_client, test_db, spool_factory):
+        """Test edits and trashed spools are reflected in results."""
+        from models import SpoolUpdate
+
+        spool = await spool_factory(color_name="Galaxy Black")
+        assert len((await async_client.get("/api/search", params={"q": "galaxy"})).json()) == 1
+
+        await test_db.update_spool(spool.id, SpoolUpdate(color_name="Sunset Orange"))
+        assert (await async_client.get("/api/search", params={"q": "galaxy"})).json() == []
+        assert len((await async_client.get("/api/search", params={"q": "sunset"})).json()) == 1
+
+        await test_db.delete_spool(spool.id)
+        assert (await async_client.get("/api/search", params={"q": "sunset"})).json() == []
+
+    async def test_search_ignores_query_syntax(self, async_client, spool_factory):
+        """Test FTS operators and quotes in the query are treated as plain text."""
+        await spool_factory(material="PLA")
+
+        response = await async_client.get("/api/search", params={"q": 'PLA" OR NOT *'})
+        assert response.status_code == 200
+        assert response.json() == []
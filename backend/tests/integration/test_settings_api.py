@@ -4,8 +4,11 @@ Integration tests for Settings API.
 Tests cover:
 - Get/Set/Delete individual settings
 - AMS threshold settings
+- Device clock settings (timezone, NTP server)
 """
 
+from unittest.mock import patch
+
 import pytest
 
 
@@ -148,3 +151,165 @@ class TestAMSThresholdsAPI:
         assert data["humidity_good"] == 45
         # Others should be defaults
         assert data["humidity_fair"] == 60
+
+
+class TestDeviceTimeSettingsAPI:
+    """Tests for the device clock settings."""
+
+    async def test_get_default_time_settings(self, async_client, test_db):
+        """Test defaults use the server timezone and the public NTP pool."""
+        with patch("api.settings.server_timezone", return_value="UTC"):
+            response = await async_client.get("/api/settings/device/time")
+
+        assert response.status_code == 200
+        data = response.json()
+        assert data["timezone"] == "UTC"
+        assert data["ntp_server"] == "pool.ntp.org"
+        assert data["posix_tz"] == "UTC0"
+
+    async def test_set_time_settings(self, async_client, test_db):
+        """Test setting a timezone returns its POSIX TZ string with DST rules."""
+        response = await async_client.put(
+            "/api/settings/device/time", json={"timezone": "Europe/Berlin", "ntp_server": "time.example.com"}
+        )
+
+        assert response.status_code == 200
+        assert response.json()["posix_tz"] == "CET-1CEST,M3.5.0,M10.5.0/3"
+
+        response = await async_client.get("/api/settings/device/time")
+        data = response.json()
+        assert data["timezone"] == "Europe/Berlin"
+        assert data["ntp_server"] == "time.example.com"
+
+    async def test_set_unknown_timezone(self, async_client, test_db):
+        """Test an unknown timezone is rejected."""
+        response = await async_client.put("/api/settings/device/time", json={"timezone": "Mars/Olympus_Mons"})
+
+        assert response.status_code == 400
+
+    async def test_fixed_offset_fallback(self):
+        """Test zones without TZif data fall back to their current offset."""
+        from api.settings import posix_tz
+
+        with patch("api.settings._tzif_data", return_value=None):
+            assert posix_tz("Asia/Kolkata") == "UTC-5:30"
+            assert posix_tz("UTC") == "UTC0"
+
+
+class TestReportCalendarAPI:
+    """Tests for the calendar month and week reports follow."""
+
+    async def test_default_follows_device_timezone(self, async_client, test_db):
+        """Test the report timezone defaults to the device's, weeks to starting on Monday."""
+        await test_db.set_setting("device_timezone", "America/New_York")
+
+        response = await async_client.get("/api/settings/reports/calendar")
+        assert response.status_code == 200
+        assert response.json() == {"timezone": "America/New_York", "week_start": "monday"}
+
+    async def test_set_and_clear_calendar(self, async_client, test_db):
+        """Test the report timezone is stored and can be cleared back to the default."""
+        response = await async_client.put(
+            "/api/settings/reports/calendar", json={"timezone": "Europe/Berlin", "week_start": "sunday"}
+        )
+        assert response.status_code == 200
+        assert response.json() == {"timezone": "Europe/Berlin", "week_start": "sunday"}
+
+        with patch("api.settings.server_timezone", return_value="UTC"):
+            await async_client.put("/api/settings/reports/calendar", json={"timezone": None, "week_start": "sunday"})
+            response = await async_client.get("/api/settings/reports/calendar")
+        assert response.json() == {"timezone": "UTC", "week_start": "sunday"}
+
+    async def test_set_unknown_timezone(self, async_client, test_db):
+        """Test an unknown timezone is rejected."""
+        response = await async_client.put("/api/settings/reports/calendar", json={"timezone": "Mars/Olympus_Mons"})
+        assert response.status_code == 400
+
+
+class TestDeviceSoundSettingsAPI:
+    """Tests for the device buzzer settings."""
+
+    async def test_get_default_sound_settings(self, async_client, test_db):
+        """Test the buzzer is unmuted and has no pin by default."""
+        response = await async_client.get("/api/settings/device/sound")
+
+        assert response.status_code == 200
+        assert response.json() == {"muted": False, "buzzer_gpio": None}
+
+    async def test_set_sound_settings(self, async_client, test_db):
+        """Test mute and buzzer pin are stored, and the pin can be cleared again."""
+        response = await async_client.put("/api/settings/device/sound", json={"muted": True, "buzzer_gpio": 6})
+        assert response.status_code == 200
+
+        response = await async_client.get("/api/settings/device/sound")
+        assert response.json() == {"muted": True, "buzzer_gpio": 6}
+
+        await async_client.put("/api/settings/device/sound", json={"muted": False, "buzzer_gpio": None})
+        response = await async_client.get("/api/settings/device/sound")
+        assert response.json() == {"muted": False, "buzzer_gpio": None}
+
+    async def test_set_invalid_buzzer_gpio(self, async_client, test_db):
+        """Test a pin outside the ESP32-S3 GPIO range is rejected."""
+        response = await async_client.put("/api/settings/device/sound", json={"muted": False, "buzzer_gpio": 60})
+        assert response.status_code == 422
+
+
+class TestDeviceThemeSettingsAPI:
+    """Tests for the device display theme."""
+
+    async def test_get_default_theme_settings(self, async_client, test_db):
+        """Test the device uses the dark theme with its own accent by default."""
+        response = await async_client.get("/api/settings/device/theme")
+
+        assert response.status_code == 200
+        assert response.json() == {"mode": "dark", "accent_color": None}
+
+    async def test_set_theme_settings(self, async_client, test_db):
+        """Test mode and accent color are stored, and the accent can be cleared again."""
+        response = await async_client.put(
+            "/api/settings/device/theme", json={"mode": "high_contrast", "accent_color": "#ff8800"}
+        )
+        assert response.status_code == 200
+
+        response = await async_client.get("/api/settings/device/theme")
+        assert response.json() == {"mode": "high_contrast", "accent_color": "#FF8800"}
+
+        await async_client.put("/api/settings/device/theme", json={"mode": "light", "accent_color": None})
+        response = await async_client.get("/api/settings/device/theme")
+        assert response.json() == {"mode": "light", "accent_color": None}
+
+    async def test_set_invalid_theme_settings(self, async_client, test_db):
+        """Test unknown modes and malformed colors are rejected."""
+        response = await async_client.put("/api/settings/device/theme", json={"mode": "sepia"})
+        assert response.status_code == 422
+
+        response = await async_client.put("/api/settings/device/theme", json={"mode": "dark", "accent_color": "red"})
+        assert response.status_code == 422
+
+
+class TestEnergyPricingAPI:
+    """Tests for the electricity price used in energy cost reports."""
+
+    async def test_get_default_pricing(self, async_client, test_db):
+        """Test no electricity price is set by default."""
+        response = await async_client.get("/api/settings/energy/pricing")
+
+        assert response.status_code == 200
+        assert response.json() == {"price_per_kwh": None}
+
+    async def test_set_and_clear_pricing(self, async_client, test_db):
+        """Test the price is stored and can be cleared again."""
+        response = await async_client.put("/api/settings/energy/pricing", json={"price_per_kwh": 0.32})
+        assert response.status_code == 200
+
+        response = await async_client.get("/api/settings/energy/pricing")
+        assert response.json() == {"price_per_kwh": 0.32}
+
+        await async_client.put("/api/settings/energy/pricing", json={"price_per_kwh": None})
+        response = await async_client.get("/api/settings/energy/pricing")
+        assert response.json() == {"price_per_kwh": None}
+
+    async def test_set_negative_pricing(self, async_client, test_db):
+        """Test a negative price is rejected."""
+        response = await async_client.put("/api/settings/energy/pricing", json={"price_per_kwh": -0.1})
+        assert response.status_code == 422
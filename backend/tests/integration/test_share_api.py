@@ -0,0 +1,77 @@
+"""Integration tests for read-only share links."""
+
+
+class TestShareLinksAPI:
+    """Test share link management."""
+
+    async def test_create_and_list(self, async_client):
+        """Test a new link gets a token, the default fields and its path."""
+        response = await async_client.post("/api/share-links", json={"name": "Makerspace"})
+        assert response.status_code == 201
+        link = response.json()
+        assert len(link["token"]) >= 20
+        assert link["path"] == f"/share/{link['token']}"
+        assert "remaining_grams" in link["fields"]
+        assert link["active"] is True
+
+        response = await async_client.get("/api/share-links")
+        assert [item["id"] for item in response.json()] == [link["id"]]
+
+    async def test_invalid_fields(self, async_client):
+        """Test only known fields can be shared, and at least one."""
+        assert (await async_client.post("/api/share-links", json={"fields": ["price"]})).status_code == 422
+        assert (await async_client.post("/api/share-links", json={"fields": []})).status_code == 422
+
+    async def test_revoke_and_delete(self, async_client):
+        """Test a revoked link stays listed as inactive and can be deleted."""
+        link = (await async_client.post("/api/share-links", json={})).json()
+
+        response = await async_client.post(f"/api/share-links/{link['id']}/revoke")
+        assert response.status_code == 200
+        assert response.json()["active"] is False
+        assert response.json()["revoked_at"] is not None
+
+        assert (await async_client.delete(f"/api/share-links/{link['id']}")).status_code == 204
+        assert (await async_client.get(f"/api/share-links/{link['id']}")).status_code == 404
+        assert (await async_client.post(f"/api/share-links/{link['id']}/revoke")).status_code == 404
+
+
+class TestSharedInventory:
+    """Test viewing the inventory through a share link."""
+
+    async def test_json_only_shared_fields(self, async_client, spool_factory):
+        """Test the shared inventory holds only the link's fields, without archived spools."""
+        await spool_factory(material="PETG", tag_id="04AABBCCDD", note="Secret", price=25)
+        archived = await spool_factory(material="ABS")
+        await async_client.post(f"/api/spools/{archived.id}/archive")
+        link = (await async_client.post("/api/share-links", json={"fields": ["material", "color_name"]})).json()
+
+        response = await async_client.get(f"/share/{link['token']}?format=json")
+        assert response.status_code == 200
+        assert response.headers["cache-control"] == "no-store"
+        assert response.json()["spools"] == [{"material": "PETG", "color_name": "Black"}]
+
+        link = (await async_client.get(f"/api/share-links/{link['id']}")).json()
+        assert link["access_count"] == 1
+        assert link["last_accessed_at"] is not None
+
+    async def test_html_page(self, async_client, spool_factory):
+        """Test the page escapes spool fields and leaves out unshared ones."""
+        await spool_factory(brand="<b>Brand</b>", location="Garage")
+        link = (await async_client.post("/api/share-links", json={"name": "My filament"})).json()
+
+        response = await async_client.get(link["path"])
+        assert response.status_code == 200
+        assert response.headers["content-type"].startswith("text/html")
+        assert "My filament" in response.text
+        assert "&lt;b&gt;Brand&lt;/b&gt;" in response.text
+        assert "Garage" not in response.text
+
+    async def test_revoked_expired_and_unknown(self, async_client):
+        """Test revoked, expired and unknown links all answer 404."""
+        revoked = (await async_client.post("/api/share-links", json={})).json()
+        await async_client.post(f"/api/share-links/{revoked['id']}/revoke")
+        expired = (await async_client.post("/api/share-links", json={"expires_at": 1})).json()
+
+        for path in (revoked["path"], expired["path"], "/share/not-a-token"):
+            assert (await async_client.get(path)).status_code == 404
@@ -0,0 +1,91 @@
+"""
+Integration tests for the slicer companion API.
+
+Tests cover:
+- Matching spools to a project's filament requirements
+- AMS slot suggestions
+"""
+
+from models import AmsTray, AmsUnit, PrinterState
+
+
+class TestSlicerMatch:
+    """Tests for POST /api/slicer/match."""
+
+    async def test_match_ranks_by_stock_and_color(self, async_client, test_db, spool_factory):
+        """Test spools are filtered by material and ranked by remaining filament, then color."""
+        red = await spool_factory(material="PLA", rgba="#FF0000FF")
+        await spool_factory(material="PLA", rgba="#EE1010FF", label_weight=1000)
+        low = await spool_factory(material="PLA", rgba="#FF0000FF")
+        await test_db.conn.execute("UPDATE spools SET weight_used = 950 WHERE id = ?", (low.id,))
+        await spool_factory(material="PETG", rgba="#FF0000FF")
+        await test_db.conn.commit()
+
+        response = await async_client.post(
+            "/api/slicer/match",
+            json={"filaments": [{"tool": 0, "material": "pla", "color": "#FF0000", "grams": 120}]},
+        )
+        assert response.status_code == 200
+        data = response.json()
+        assert data["ready"] is True
+        candidates = data["tools"][0]["candidates"]
+        assert [c["spool"]["material"] for c in candidates] == ["PLA", "PLA", "PLA"]
+        assert candidates[0]["spool"]["id"] == red.id
+        assert candidates[0]["color_distance"] == 0
+        assert candidates[-1]["spool"]["id"] == low.id
+        assert candidates[-1]["enough"] is False
+
+    async def test_match_prefers_preset(self, async_client, spool_factory):
+        """Test a spool on the requested slicer preset wins over a closer color."""
+        await spool_factory(material="PLA", rgba="#000000FF")
+        matte = await spool_factory(material="PLA", rgba="#202020FF", slicer_filament="GFSA01_02")
+
+        response = await async_client.post(
+            "/api/slicer/match",
+            json={"filaments": [{"tool": 0, "material": "PLA", "color": "#000000", "setting_id": "GFSA01"}]},
+        )
+        candidates = response.json()["tools"][0]["candidates"]
+        assert candidates[0]["spool"]["id"] == matte.id
+        assert candidates[0]["preset_match"] is True
+
+    async def test_match_out_of_stock(self, async_client, spool_factory):
+        """Test a tool without a matching spool makes the project not ready."""
+        await spool_factory(material="PLA")
+
+        response = await async_client.post(
+            "/api/slicer/match",
+            json={"filaments": [{"tool": 0, "material": "PLA"}, {"tool": 1, "material": "TPU", "grams": 10}]},
+        )
+        data = response.json()
+        assert data["ready"] is False
+        assert data["tools"][1]["candidates"] == []
+
+    async def test_slot_suggestions(self, async_client, test_db, spool_factory, mock_printer_manager):
+        """Test loaded spools keep their slot and others get a free slot."""
+        loaded = await spool_factory(material="PLA", rgba="#FFFFFFFF")
+        other = await spool_factory(material="PETG", rgba="#0000FFFF")
+        await test_db.assign_spool_to_slot(loaded.id, "PRINTER1", 0, 0)
+        mock_printer_manager.get_state.return_value = PrinterState(
+            ams_units=[
+                AmsUnit(
+                    id=0,
+                    trays=[
+                        AmsTray(ams_id=0, tray_id=0, tray_type="PLA"),
+                        AmsTray(ams_id=0, tray_id=1, tray_type="ABS"),
+                        AmsTray(ams_id=0, tray_id=2),
+                    ],
+                )
+            ]
+        )
+
+        response = await async_client.post(
+            "/api/slicer/match",
+            json={
+                "printer_serial": "PRINTER1",
+                "filaments": [{"tool": 0, "material": "PLA"}, {"tool": 1, "material": "PETG"}],
+            },
+        )
+        tools = response.json()["tools"]
+        assert tools[0]["candidates"][0]["slot"] == {"ams_id": 0, "tray_id": 0, "loaded": True}
+        assert tools[1]["candidates"][0]["spool"]["id"] == other.id
+        assert tools[1]["candidates"][0]["slot"] == {"ams_id": 0, "tray_id": 2, "loaded": False}
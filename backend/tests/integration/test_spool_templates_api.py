@@ -0,0 +1,84 @@
+"""Integration tests for the spool templates API."""
+
+TEMPLATE = {"brand": "Bambu", "material": "PLA", "subtype": "Matte", "color_name": "Black", "rgba": "#000000FF"}
+
+
+class TestSpoolTemplatesAPI:
+    """Test template management."""
+
+    async def test_create_and_list(self, async_client):
+        """Test creating a template names it after the filament by default."""
+        response = await async_client.post("/api/spool-templates", json=TEMPLATE)
+        assert response.status_code == 201
+        template = response.json()
+        assert template["name"] == "Bambu PLA Matte Black"
+        assert (template["label_weight"], template["core_weight"], template["use_count"]) == (1000, 250, 0)
+
+        response = await async_client.get("/api/spool-templates")
+        assert [t["id"] for t in response.json()] == [template["id"]]
+
+    async def test_update_and_delete(self, async_client):
+        """Test updating and deleting a template."""
+        template = (await async_client.post("/api/spool-templates", json=TEMPLATE)).json()
+
+        response = await async_client.put(f"/api/spool-templates/{template['id']}", json={"price": 19.99})
+        assert response.status_code == 200
+        assert (response.json()["price"], response.json()["name"]) == (19.99, "Bambu PLA Matte Black")
+
+        response = await async_client.put(f"/api/spool-templates/{template['id']}", json={"material": ""})
+        assert response.status_code == 422
+
+        assert (await async_client.delete(f"/api/spool-templates/{template['id']}")).status_code == 204
+        assert (await async_client.get(f"/api/spool-templates/{template['id']}")).status_code == 404
+        assert (await async_client.delete(f"/api/spool-templates/{template['id']}")).status_code == 404
+
+    async def test_invalid_nozzle_temps(self, async_client):
+        """Test a template with min above max nozzle temperature is rejected."""
+        response = await async_client.post(
+            "/api/spool-templates", json={**TEMPLATE, "nozzle_temp_min": 230, "nozzle_temp_max": 190}
+        )
+        assert response.status_code == 422
+
+
+class TestSpoolFromTemplate:
+    """Test adding spools from a template."""
+
+    async def test_add_spool(self, async_client):
+        """Test a spool added from a template gets its fields, and the template is counted."""
+        template = (await async_client.post("/api/spool-templates", json={**TEMPLATE, "price": 20})).json()
+
+        response = await async_client.post(f"/api/spools/from-template/{template['id']}")
+        assert response.status_code == 201
+        spool = response.json()
+        assert [spool[k] for k in TEMPLATE] == list(TEMPLATE.values())
+        assert (spool["label_weight"], spool["price"], spool["data_origin"]) == (1000, 20, "template")
+
+        template = (await async_client.get(f"/api/spool-templates/{template['id']}")).json()
+        assert template["use_count"] == 1
+        assert template["last_used_at"] is not None
+
+    async def test_per_spool_details(self, async_client):
+        """Test the request body overrides the template for this spool."""
+        template = (await async_client.post("/api/spool-templates", json={**TEMPLATE, "location": "Shelf A"})).json()
+
+        response = await async_client.post(
+            f"/api/spools/from-template/{template['id']}",
+            json={"tag_id": "04AABBCCDD", "weight_current": 1240, "location": "Drybox"},
+        )
+        assert response.status_code == 201
+        spool = response.json()
+        assert (spool["tag_id"], spool["weight_current"], spool["location"]) == ("04AABBCCDD", 1240, "Drybox")
+
+    async def test_tag_in_use(self, async_client, spool_factory):
+        """Test a tag already on another spool is refused like on create."""
+        await spool_factory(tag_id="04AABBCCDD")
+        template = (await async_client.post("/api/spool-templates", json=TEMPLATE)).json()
+
+        response = await async_client.post(f"/api/spools/from-template/{template['id']}", json={"tag_id": "04AABBCCDD"})
+        assert response.status_code == 409
+        assert (await async_client.get(f"/api/spool-templates/{template['id']}")).json()["use_count"] == 0
+
+    async def test_unknown_template(self, async_client):
+        """Test adding from a missing template returns 404."""
+        response = await async_client.post("/api/spools/from-template/999")
+        assert response.status_code == 404
@@ -1,5 +1,7 @@
 """Integration tests for the spools API."""
 
+from unittest.mock import patch
+
 import pytest
 
 
@@ -105,6 +107,72 @@ class TestSpoolsAPI:
         assert len(data) == 3
 
 
+class TestSpoolColors:
+    """Test color swatches and similar-color search."""
+
+    async def test_spool_has_swatch(self, async_client, test_db, spool_factory):
+        """Test responses carry the parsed color and the derived hue/lightness is stored."""
+        spool = await spool_factory(rgba="#00AE42")
+
+        data = (await async_client.get(f"/api/spools/{spool.id}")).json()
+        assert data["color"]["rgba"] == "00AE42FF"
+        assert data["color"]["family"] == "green"
+
+        async with test_db.conn.execute(
+            "SELECT color_hue, color_lightness FROM spools WHERE id = ?", (spool.id,)
+        ) as cursor:
+            row = await cursor.fetchone()
+        assert (round(row[0]), round(row[1])) == (143, 34)
+
+        no_color = await spool_factory(rgba=None)
+        assert (await async_client.get(f"/api/spools/{no_color.id}")).json()["color"] is None
+
+    async def test_similar_color(self, async_client, spool_factory):
+        """Test spools are matched by delta-E, closest first."""
+        red = await spool_factory(material="PLA", rgba="#FF0000FF")
+        near_red = await spool_factory(material="PLA", rgba="#EE1010FF")
+        await spool_factory(material="PLA", rgba="#0000FFFF")
+        petg_red = await spool_factory(material="PETG", rgba="#FF0000FF")
+
+        response = await async_client.get("/api/spools/similar-color", params={"color": "#FF0000"})
+        assert response.status_code == 200
+        matches = response.json()
+        assert {m["spool"]["id"] for m in matches[:2]} == {red.id, petg_red.id}
+        assert matches[2]["spool"]["id"] == near_red.id
+        assert len(matches) == 3
+
+        params = {"color": "red", "material": "petg", "max_delta_e": 50}
+        matches = (await async_client.get("/api/spools/similar-color", params=params)).json()
+        assert [m["spool"]["id"] for m in matches] == [petg_red.id]
+
+        response = await async_client.get("/api/spools/similar-color", params={"color": "not a color"})
+        assert response.status_code == 400
+
+    async def test_multi_color_spool(self, async_client, test_db):
+        """Test color stops are stored in order, default rgba to the first stop and render as a gradient."""
+        payload = {"material": "PLA", "subtype": "Silk Dual", "color_stops": ["#ffd700", "C0C0C0FF"]}
+        response = await async_client.post("/api/spools", json=payload)
+        assert response.status_code == 200
+        data = response.json()
+        assert data["color_stops"] == ["FFD700FF", "C0C0C0FF"]
+        assert data["rgba"] == "FFD700FF"
+        assert data["gradient"] == "linear-gradient(90deg, #FFD700FF 0%, #C0C0C0FF 100%)"
+
+        stops = ["FF0000FF", "00FF00FF", "0000FFFF"]
+        response = await async_client.put(f"/api/spools/{data['id']}", json={"color_stops": stops})
+        assert response.status_code == 200
+        assert response.json()["color_stops"] == stops
+        assert (await test_db.get_spool(data["id"])).color_stops == stops
+
+        response = await async_client.put(f"/api/spools/{data['id']}", json={"color_stops": None})
+        assert response.json()["color_stops"] is None
+        assert response.json()["gradient"] is None
+
+        for stops in (["FF0000"], ["FF0000", "blue"], ["FF0000"] * 9):
+            response = await async_client.post("/api/spools", json={"material": "PLA", "color_stops": stops})
+            assert response.status_code == 422
+
+
 class TestSpoolsDatabase:
     """Test spool database operations directly."""
 
@@ -130,6 +198,35 @@ class TestSpoolsDatabase:
         retrieved = await test_db.get_spool_by_tag("nonexistent")
         assert retrieved is None
 
+    async def test_get_spool_by_tag_uid_any_form(self, test_db, spool_factory):
+        """Test UID lookups match however the reader formats the UID."""
+        spool = await spool_factory(tag_id="hw1RAA")  # base64 of 870D5100
+
+        for uid in ("870D5100", "87:0d:51:00", "87 0D 51 00", "00510D87", "hw1RAA"):
+            retrieved = await test_db.get_spool_by_tag_uid(uid)
+            assert retrieved is not None, uid
+            assert retrieved.id == spool.id
+
+        assert await test_db.get_spool_by_tag_uid("870D5101") is None
+
+    async def test_get_spool_by_tag_uid_follows_tag_changes(self, test_db, spool_factory):
+        """Test the normalized UID is kept in step when a tag is linked or cleared."""
+        spool = await spool_factory(tag_id=None)
+
+        await test_db.link_tag_to_spool(spool.id, "04:A1:B2:C3")
+        assert (await test_db.get_spool_by_tag_uid("04a1b2c3")).id == spool.id
+
+        await test_db.clear_spool_tag(spool.id)
+        assert await test_db.get_spool_by_tag_uid("04A1B2C3") is None
+
+    async def test_get_spool_by_tag_uid_prefers_given_byte_order(self, test_db, spool_factory):
+        """Test an exact match wins over a byte-reversed one."""
+        reversed_spool = await spool_factory(tag_id="44332211")
+        spool = await spool_factory(tag_id="11223344")
+
+        assert (await test_db.get_spool_by_tag_uid("11:22:33:44")).id == spool.id
+        assert (await test_db.get_spool_by_tag_uid("44332211")).id == reversed_spool.id
+
     async def test_update_spool_consumption(self, test_db, spool_factory):
         """Test updating spool consumption tracking."""
         spool = await spool_factory(weight_current=1000)
@@ -152,3 +249,800 @@ class TestSpoolsDatabase:
         assert updated is not None
         assert updated.weight_current == 900
         assert updated.consumed_since_weight == 0  # Reset after scale reading
+
+
+class TestSpoolByTag:
+    """Test fetching spools by tag UID."""
+
+    async def test_get_by_tag(self, async_client, spool_factory):
+        """Test the spool is found from a colon-separated, reversed UID."""
+        spool = await spool_factory(tag_id="04A1B2C3D4E5F6")
+
+        response = await async_client.get("/api/spools/by-tag/F6:E5:D4:C3:B2:A1:04")
+        assert response.status_code == 200
+        assert response.json()["id"] == spool.id
+
+    async def test_get_by_tag_skips_archived(self, async_client, test_db, spool_factory):
+        """Test archived spools don't claim the tag."""
+        spool = await spool_factory(tag_id="04A1B2C3D4E5F6")
+        await test_db.archive_spool(spool.id)
+
+        response = await async_client.get("/api/spools/by-tag/04A1B2C3D4E5F6")
+        assert response.status_code == 404
+
+
+class TestSpoolMerge:
+    """Test duplicate detection and spool merging."""
+
+    async def test_find_duplicates_same_attributes(self, async_client, spool_factory):
+        """Test spools with identical attributes created together are flagged."""
+        first = await spool_factory(material="PLA", color_name="Red", brand="Bambu Lab")
+        second = await spool_factory(material="pla", color_name="red", brand="Bambu Lab")
+        await spool_factory(material="PETG", color_name="Red", brand="Bambu Lab")
+
+        response = await async_client.get("/api/spools/duplicates")
+        assert response.status_code == 200
+
+        groups = response.json()
+        assert len(groups) == 1
+        assert groups[0]["reason"] == "same_attributes"
+        assert {s["id"] for s in groups[0]["spools"]} == {first.id, second.id}
+
+    async def test_find_duplicates_same_tag_different_encoding(self, async_client, spool_factory):
+        """Test hex and base64 forms of the same UID are detected."""
+        await spool_factory(tag_id="hw1RAA", color_name="A")  # base64 of 870D5100
+        await spool_factory(tag_id="87:0D:51:00", color_name="B")
+
+        response = await async_client.get("/api/spools/duplicates")
+        groups = response.json()
+        assert [g["reason"] for g in groups] == ["same_tag"]
+
+    async def test_merge_spools(self, async_client, test_db, spool_factory, printer_factory):
+        """Test merging moves history and assignments to the target."""
+        printer = await printer_factory()
+        target = await spool_factory(tag_id=None, note=None)
+        source = await spool_factory(tag_id="ABC123==", note="from source")
+        await test_db.log_usage(source.id, printer.serial, "benchy", 12.5)
+        await test_db.assign_spool_to_slot(source.id, printer.serial, 0, 1)
+
+        response = await async_client.post(
+            "/api/spools/merge", json={"target_id": target.id, "source_ids": [source.id]}
+        )
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["id"] == target.id
+        assert data["tag_id"] == "ABC123=="
+        assert data["note"] == "from source"
+
+        assert await test_db.get_spool(source.id) is None
+        history = await test_db.get_usage_history(spool_id=target.id)
+        assert len(history) == 1
+        assert await test_db.get_spool_for_slot(printer.serial, 0, 1) == target.id
+
+    async def test_merge_spools_target_in_sources(self, async_client, spool_factory):
+        """Test the target cannot be merged into itself."""
+        spool = await spool_factory()
+        response = await async_client.post("/api/spools/merge", json={"target_id": spool.id, "source_ids": [spool.id]})
+        assert response.status_code == 400
+
+    async def test_merge_spools_source_not_found(self, async_client, spool_factory):
+        """Test merging a missing source spool."""
+        spool = await spool_factory()
+        response = await async_client.post("/api/spools/merge", json={"target_id": spool.id, "source_ids": ["nope"]})
+        assert response.status_code == 404
+
+
+class TestSpoolBulkUpdate:
+    """Test bulk spool updates."""
+
+    async def test_bulk_move_and_rebrand(self, async_client, spool_factory):
+        """Test a partial update is applied to every listed spool."""
+        first = await spool_factory(location="Shelf A")
+        second = await spool_factory(location="Shelf A", note="keep me")
+
+        response = await async_client.patch(
+            "/api/spools/bulk",
+            json={"ids": [first.id, second.id], "changes": {"location": "Dry box", "brand": "Polymaker"}},
+        )
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["updated"] == 2
+        assert data["failed"] == 0
+        for result in data["results"]:
+            assert result["ok"] is True
+            assert result["spool"]["location"] == "Dry box"
+            assert result["spool"]["brand"] == "Polymaker"
+            assert result["spool"]["material"] == "PLA"
+        assert data["results"][1]["spool"]["note"] == "keep me"
+
+    async def test_bulk_archive_reports_missing(self, async_client, test_db, spool_factory):
+        """Test spools are archived and unknown IDs are reported per item."""
+        spool = await spool_factory()
+
+        response = await async_client.patch("/api/spools/bulk", json={"ids": [spool.id, "nope"], "archived": True})
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["updated"] == 1
+        assert data["failed"] == 1
+        assert data["results"][1] == {"id": "nope", "ok": False, "error": "Spool not found", "spool": None}
+        assert (await test_db.get_spool(spool.id)).archived_at is not None
+
+    async def test_bulk_duplicate_tag_fails_single_item(self, async_client, test_db, spool_factory):
+        """Test a failing spool doesn't block the rest of the batch."""
+        await spool_factory(tag_id="TAKEN==")
+        first = await spool_factory()
+        second = await spool_factory()
+
+        response = await async_client.patch(
+            "/api/spools/bulk", json={"ids": [first.id, second.id], "changes": {"tag_id": "TAKEN=="}}
+        )
+        data = response.json()
+        assert data["updated"] == 0
+        assert data["failed"] == 2
+
+        response = await async_client.patch(
+            "/api/spools/bulk", json={"ids": [first.id, second.id], "changes": {"tag_id": "NEW==", "note": "x"}}
+        )
+        data = response.json()
+        assert [r["ok"] for r in data["results"]] == [True, False]
+        assert (await test_db.get_spool(second.id)).note is None
+
+    async def test_bulk_atomic_rolls_back(self, async_client, test_db, spool_factory):
+        """Test an atomic batch applies nothing if any spool fails."""
+        spool = await spool_factory(location="Shelf A")
+
+        response = await async_client.patch(
+            "/api/spools/bulk",
+            json={"ids": [spool.id, "nope"], "changes": {"location": "Dry box"}, "atomic": True},
+        )
+        data = response.json()
+        assert data["updated"] == 0
+        assert data["results"][0]["error"] == "Rolled back"
+        assert (await test_db.get_spool(spool.id)).location == "Shelf A"
+
+    async def test_bulk_requires_changes(self, async_client, spool_factory):
+        """Test a bulk update without changes is rejected."""
+        spool = await spool_factory()
+        response = await async_client.patch("/api/spools/bulk", json={"ids": [spool.id]})
+        assert response.status_code == 400
+
+
+class TestSpoolListCaching:
+    """Test ETag/If-None-Match handling on the spool list."""
+
+    async def test_list_spools_not_modified(self, async_client, spool_factory):
+        """Test an unchanged inventory is answered with 304."""
+        await spool_factory()
+
+        response = await async_client.get("/api/spools")
+        assert response.status_code == 200
+        etag = response.headers["ETag"]
+
+        response = await async_client.get("/api/spools", headers={"If-None-Match": etag})
+        assert response.status_code == 304
+        assert response.headers["ETag"] == etag
+        assert response.content == b""
+
+        response = await async_client.get("/api/spools", headers={"If-None-Match": f'"stale", W/{etag}'})
+        assert response.status_code == 304
+
+    async def test_list_spools_etag_changes(self, async_client, spool_factory):
+        """Test the ETag changes when a spool changes."""
+        spool = await spool_factory()
+        etag = (await async_client.get("/api/spools")).headers["ETag"]
+
+        await async_client.put(f"/api/spools/{spool.id}", json={"note": "changed"})
+
+        response = await async_client.get("/api/spools", headers={"If-None-Match": etag})
+        assert response.status_code == 200
+        assert response.headers["ETag"] != etag
+        assert response.json()[0]["note"] == "changed"
+
+
+class TestSpoolConcurrency:
+    """Test optimistic concurrency on spool updates."""
+
+    async def test_version_increments_on_change(self, async_client, test_db, spool_factory):
+        """Test every write bumps the spool version."""
+        spool = await spool_factory()
+        assert spool.version == 1
+
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"note": "edited"})
+        assert response.json()["version"] == 2
+        assert response.headers["etag"] == '"2"'
+
+        await test_db.set_spool_weight(spool.id, 900)
+        assert (await test_db.get_spool(spool.id)).version == 3
+
+    async def test_update_with_expected_version(self, async_client, spool_factory):
+        """Test an update based on the current version succeeds."""
+        spool = await spool_factory()
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"note": "a", "expected_version": 1})
+        assert response.status_code == 200
+        assert response.json()["note"] == "a"
+
+    async def test_stale_expected_version_conflicts(self, async_client, spool_factory):
+        """Test a stale update is rejected with the current record."""
+        spool = await spool_factory()
+        await async_client.put(f"/api/spools/{spool.id}", json={"note": "from device"})
+
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"note": "from web", "expected_version": 1})
+        assert response.status_code == 409
+        data = response.json()
+        assert data["current"]["note"] == "from device"
+        assert data["current"]["version"] == 2
+
+    async def test_if_match_header(self, async_client, spool_factory):
+        """Test the If-Match header is honoured using the ETag from GET."""
+        spool = await spool_factory()
+        etag = (await async_client.get(f"/api/spools/{spool.id}")).headers["etag"]
+
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"note": "a"}, headers={"If-Match": etag})
+        assert response.status_code == 200
+
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"note": "b"}, headers={"If-Match": etag})
+        assert response.status_code == 409
+
+
+class TestSpoolWeightHistory:
+    """Test weight history recording and the time-series endpoint."""
+
+    async def test_scale_and_usage_weights_recorded(self, test_db, spool_factory):
+        """Test scale syncs and usage updates both land in the history."""
+        spool = await spool_factory(weight_current=1200)
+        await test_db.set_spool_weight(spool.id, 1100)
+        await test_db.update_spool_consumption(spool.id, 30.0)
+
+        async with test_db.conn.execute(
+            "SELECT weight, source FROM weight_history WHERE spool_id = ? ORDER BY id", (spool.id,)
+        ) as cursor:
+            rows = [tuple(row) for row in await cursor.fetchall()]
+        assert rows == [(1100, "scale"), (1070, "usage")]
+
+    async def test_weight_history_downsampled(self, async_client, test_db, spool_factory):
+        """Test readings are bucketed by day, keeping the last weight per bucket."""
+        spool = await spool_factory()
+        day = 1700006400  # Midnight UTC
+        await test_db.record_weight(spool.id, 1200, "scale", day + 100)
+        await test_db.record_weight(spool.id, 1198, "scale", day + 200)
+        await test_db.record_weight(spool.id, 1150, "scale", day + 86400 + 50)
+
+        response = await async_client.get(f"/api/spools/{spool.id}/weight-history?resolution=1d")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["resolution"] == "1d"
+        assert [p["timestamp"] for p in data["points"]] == [day, day + 86400]
+        assert data["points"][0]["weight"] == 1198
+        assert data["points"][0]["max_weight"] == 1200
+        assert data["points"][0]["samples"] == 2
+
+    async def test_weight_history_classifies_changes(self, async_client, test_db, spool_factory):
+        """Test sudden drops count as prints and small changes as drift."""
+        spool = await spool_factory()
+        await test_db.record_weight(spool.id, 1200, "scale", 1700000000)
+        await test_db.record_weight(spool.id, 1198, "scale", 1700000100)  # drift
+        await test_db.record_weight(spool.id, 1150, "scale", 1700000200)  # print
+        await test_db.record_weight(spool.id, 1151, "scale", 1700000300)  # drift
+
+        response = await async_client.get(f"/api/spools/{spool.id}/weight-history?resolution=raw")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert len(data["points"]) == 4
+        assert data["changes"] == [{"timestamp": 1700000200, "delta": -48, "kind": "print"}]
+        assert data["print_consumption"] == 48
+        assert data["drift"] == -1
+
+    async def test_weight_history_not_found(self, async_client):
+        """Test unknown spool returns 404."""
+        response = await async_client.get("/api/spools/nonexistent/weight-history")
+        assert response.status_code == 404
+
+    async def test_weight_history_invalid_resolution(self, async_client, spool_factory):
+        """Test unsupported resolution is rejected."""
+        spool = await spool_factory()
+        response = await async_client.get(f"/api/spools/{spool.id}/weight-history?resolution=5m")
+        assert response.status_code == 422
+
+
+class TestSpoolForecast:
+    """Test depletion forecasts and the spool.depleting alert."""
+
+    async def _log(self, test_db, spool_id, weight, timestamp):
+        await test_db.conn.execute(
+            """INSERT INTO usage_history (spool_id, printer_serial, print_name, weight_used, timestamp)
+               VALUES (?, 'S1', 'test', ?, ?)""",
+            (spool_id, weight, timestamp),
+        )
+        await test_db.conn.commit()
+
+    async def test_forecast_from_recent_usage(self, async_client, test_db, spool_factory):
+        """Test days remaining uses consumption over the last 30 days."""
+        import time
+
+        now = int(time.time())
+        spool = await spool_factory(label_weight=1000)
+        await test_db.conn.execute(
+            "UPDATE spools SET added_time = ?, weight_used = 700 WHERE id = ?", (now - 60 * 86400, spool.id)
+        )
+        await self._log(test_db, spool.id, 150, now - 5 * 86400)
+        await self._log(test_db, spool.id, 150, now - 10 * 86400)
+        await self._log(test_db, spool.id, 500, now - 45 * 86400)  # Outside the window
+
+        response = await async_client.get(f"/api/spools/{spool.id}/forecast")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["remaining_grams"] == 300
+        assert data["daily_usage"] == 10
+        assert data["days_remaining"] == 30
+        assert abs(data["depletion_date"] - (now + 30 * 86400)) < 60
+
+    async def test_forecast_without_usage(self, async_client, spool_factory):
+        """Test spools with no recent usage have no depletion date."""
+        spool = await spool_factory()
+
+        response = await async_client.get(f"/api/spools/{spool.id}/forecast")
+        assert response.status_code == 200
+        assert response.json()["days_remaining"] is None
+        assert response.json()["depletion_date"] is None
+
+    async def test_forecast_not_found(self, async_client):
+        """Test unknown spool returns 404."""
+        response = await async_client.get("/api/spools/nonexistent/forecast")
+        assert response.status_code == 404
+
+    async def test_list_forecasts_soonest_first(self, async_client, test_db, spool_factory):
+        """Test the list only includes spools in use, soonest to run out first."""
+        import time
+
+        now = int(time.time())
+        slow = await spool_factory(material="PETG")
+        fast = await spool_factory(material="PLA")
+        await spool_factory(material="ABS")  # Unused
+        await self._log(test_db, slow.id, 30, now - 86400)
+        await self._log(test_db, fast.id, 300, now - 86400)
+
+        response = await async_client.get("/api/spools/forecast")
+        assert response.status_code == 200
+        assert [f["spool_id"] for f in response.json()] == [fast.id, slow.id]
+
+    async def test_depletion_alert_crossing(self, test_db, spool_factory):
+        """Test spool.depleting fires once when the forecast crosses the alert horizon."""
+        import time
+        from unittest.mock import AsyncMock, patch
+
+        from main import _check_spool_depletion
+
+        now = int(time.time())
+        spool = await spool_factory(label_weight=1000)
+        await test_db.conn.execute("UPDATE spools SET added_time = ? WHERE id = ?", (now - 60 * 86400, spool.id))
+        await self._log(test_db, spool.id, 300, now - 86400)  # 10 g/day over 30 days
+        before = await test_db.get_spool(spool.id)
+        after = before.model_copy(update={"weight_used": 950})  # 50 g left = 5 days
+
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.emit_event") as emit,
+        ):
+            await _check_spool_depletion(before, after)
+            assert emit.call_count == 1
+            assert emit.call_args.args[0] == "spool.depleting"
+            assert emit.call_args.args[1]["days_remaining"] == 5
+
+            # Already below the horizon - no repeat
+            await _check_spool_depletion(after, after.model_copy(update={"weight_used": 960}))
+            assert emit.call_count == 1
+
+
+class TestSpoolAging:
+    """Test shelf life tracking and the spool.aging alert."""
+
+    async def test_aging_opened_petg(self, async_client, spool_factory):
+        """Test a PETG spool open for over 12 months is flagged."""
+        import time
+
+        now = int(time.time())
+        spool = await spool_factory(material="PETG-CF", manufactured_at=now - 500 * 86400, opened_at=now - 400 * 86400)
+
+        response = await async_client.get(f"/api/spools/{spool.id}/aging")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["shelf_life_months"] == 12
+        assert data["open_days"] == 400
+        assert data["expires_at"] == now - 35 * 86400
+        assert data["aging"] is True
+
+    async def test_aging_sealed(self, async_client, spool_factory):
+        """Test sealed spools are never flagged."""
+        spool = await spool_factory(material="PVA")
+
+        response = await async_client.get(f"/api/spools/{spool.id}/aging")
+        assert response.status_code == 200
+        assert response.json()["shelf_life_months"] == 6
+        assert response.json()["open_days"] is None
+        assert response.json()["aging"] is False
+
+    async def test_aging_not_found(self, async_client):
+        """Test unknown spool returns 404."""
+        response = await async_client.get("/api/spools/nonexistent/aging")
+        assert response.status_code == 404
+
+    async def test_shelf_life_override(self, async_client, test_db, spool_factory):
+        """Test the shelf_life_months setting overrides the built-in shelf life."""
+        import time
+
+        spool = await spool_factory(material="PLA", opened_at=int(time.time()) - 200 * 86400)
+        await test_db.set_setting("shelf_life_months", '{"pla": 6}')
+
+        response = await async_client.get(f"/api/spools/{spool.id}/aging")
+        assert response.json()["shelf_life_months"] == 6
+        assert response.json()["aging"] is True
+
+    async def test_list_oldest_open_first(self, async_client, spool_factory):
+        """Test the bulk view lists opened active spools, longest open first."""
+        import time
+
+        now = int(time.time())
+        recent = await spool_factory(material="PETG", opened_at=now - 30 * 86400)
+        old = await spool_factory(material="PETG", opened_at=now - 400 * 86400)
+        await spool_factory(material="PETG")  # Sealed
+
+        response = await async_client.get("/api/spools/aging")
+        assert response.status_code == 200
+        assert [a["spool_id"] for a in response.json()] == [old.id, recent.id]
+
+        response = await async_client.get("/api/spools/aging?aging_only=true")
+        assert [a["spool_id"] for a in response.json()] == [old.id]
+
+    async def test_usage_opens_spool(self, test_db, spool_factory):
+        """Test the first logged usage marks a sealed spool as opened."""
+        spool = await spool_factory()
+
+        updated = await test_db.update_spool_consumption(spool.id, 10)
+        assert updated.opened_at is not None
+
+        await test_db.conn.execute("UPDATE spools SET opened_at = 1 WHERE id = ?", (spool.id,))
+        await test_db.conn.commit()
+        updated = await test_db.update_spool_consumption(spool.id, 10)
+        assert updated.opened_at == 1
+
+    async def test_aging_alert_crossing(self, test_db, spool_factory):
+        """Test spool.aging fires once, for spools whose shelf life ran out since the last check."""
+        import time
+        from unittest.mock import AsyncMock, patch
+
+        from main import _check_spool_aging
+
+        now = int(time.time())
+        year = 365 * 86400
+        crossing = await spool_factory(material="PETG", opened_at=now - year - 3600)
+        await spool_factory(material="PETG", opened_at=now - 2 * year)  # Aged before the last check
+        await spool_factory(material="PLA", opened_at=now - year - 3600)  # 24 months for PLA
+
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.emit_event") as emit,
+        ):
+            # The first check only records its time
+            await _check_spool_aging(now - 86400)
+            assert emit.call_count == 0
+
+            await _check_spool_aging(now)
+            assert emit.call_count == 1
+            assert emit.call_args.args[0] == "spool.aging"
+            assert emit.call_args.args[1]["spool_id"] == crossing.id
+            assert emit.call_args.args[1]["shelf_life_months"] == 12
+
+            await _check_spool_aging(now + 3600)
+            assert emit.call_count == 1
+
+
+class TestSpoolSlicerPreset:
+    """Tests for the slicer preset mapping and export."""
+
+    async def test_preset_ids_derived_from_slicer_filament(self, async_client, spool_factory):
+        """Test setting_id and filament_id are resolved from slicer_filament and kept in step on update."""
+        spool = await spool_factory(slicer_filament="GFSA00_02")
+        assert spool.slicer_setting_id == "GFSA00"
+        assert spool.slicer_filament_id == "GFA00"
+
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"slicer_filament": "Some custom PLA"})
+        assert response.status_code == 200
+        data = response.json()
+        assert data["slicer_setting_id"] is None
+        assert data["slicer_filament_id"] is None
+
+    async def test_export_preset(self, async_client, test_db, spool_factory):
+        """Test the export carries preset IDs, temperatures and the matching K value."""
+        spool = await spool_factory(
+            slicer_filament="GFL05",
+            slicer_filament_name="Bambu PLA Matte @BBL X1C",
+            subtype="Matte",
+            rgba="FF8800FF",
+        )
+        await test_db.save_spool_k_profiles(
+            spool.id,
+            [
+                {"printer_serial": "PRINTER1", "nozzle_diameter": "0.4", "k_value": "0.020"},
+                {"printer_serial": "PRINTER1", "nozzle_diameter": "0.6", "k_value": "0.012"},
+            ],
+        )
+
+        response = await async_client.get(f"/api/spools/{spool.id}/slicer-preset?nozzle_diameter=0.6")
+        assert response.status_code == 200
+        assert "attachment" in response.headers["content-disposition"]
+        preset = response.json()
+        assert preset["type"] == "filament"
+        assert preset["inherits"] == "Bambu PLA Matte @BBL X1C"
+        assert preset["base_id"] == "GFSL05"
+        assert preset["filament_id"] == "GFL05"
+        assert preset["filament_colour"] == ["#FF8800"]
+        assert preset["nozzle_temperature_range_low"] == ["190"]
+        assert preset["nozzle_temperature_range_high"] == ["230"]
+        assert preset["pressure_advance"] == ["0.012"]
+
+    async def test_export_preset_generic_fallback(self, async_client, spool_factory):
+        """Test a spool without a linked preset exports with the generic filament ID and no K value."""
+        spool = await spool_factory(material="PETG", slicer_filament="My PETG")
+
+        response = await async_client.get(f"/api/spools/{spool.id}/slicer-preset")
+        assert response.status_code == 200
+        preset = response.json()
+        assert preset["filament_id"] == "GFG99"
+        assert preset["inherits"] == ""
+        assert "base_id" not in preset
+        assert "pressure_advance" not in preset
+
+    async def test_export_preset_not_found(self, async_client):
+        """Test exporting a nonexistent spool."""
+        response = await async_client.get("/api/spools/nonexistent/slicer-preset")
+        assert response.status_code == 404
+
+
+class TestSpoolAdjustments:
+    """Tests for manual consumption adjustments."""
+
+    async def test_adjustment_consumes(self, async_client, test_db, spool_factory):
+        """Test a positive adjustment is logged with its reason and deducted."""
+        spool = await spool_factory(weight_current=900)
+
+        response = await async_client.post(
+            f"/api/spools/{spool.id}/adjustments",
+            json={"grams": 25, "reason": "failed_print", "note": "spaghetti"},
+        )
+        assert response.status_code == 200
+        data = response.json()
+        assert data["consumed_since_add"] == 25
+        assert data["weight_current"] == 875
+
+        history = await test_db.get_usage_history(spool_id=spool.id)
+        assert history[0]["reason"] == "failed_print"
+        assert history[0]["print_name"] == "Failed print: spaghetti"
+        assert history[0]["weight_used"] == 25
+
+        async with test_db.conn.execute("SELECT source FROM weight_history WHERE spool_id = ?", (spool.id,)) as cur:
+            assert (await cur.fetchone())["source"] == "adjustment"
+
+    async def test_negative_adjustment_credits_back(self, async_client, spool_factory):
+        """Test a measurement correction can give filament back."""
+        spool = await spool_factory(weight_current=900)
+        await async_client.post(f"/api/spools/{spool.id}/usage", json={"weight_used": 100})
+
+        response = await async_client.post(
+            f"/api/spools/{spool.id}/adjustments", json={"grams": -40, "reason": "measurement_error"}
+        )
+        data = response.json()
+        assert data["consumed_since_add"] == 60
+        assert data["weight_current"] == 840
+
+    async def test_adjustment_counts_in_reports(self, async_client, spool_factory):
+        """Test adjustments feed the usage reports."""
+        spool = await spool_factory(material="PETG")
+        await async_client.post(f"/api/spools/{spool.id}/adjustments", json={"grams": 15, "reason": "purge"})
+
+        response = await async_client.get("/api/reports/usage?group_by=material")
+        assert response.json()["total_weight"] == 15
+
+    async def test_adjustment_validation(self, async_client, spool_factory):
+        """Test zero grams, unknown reasons and missing spools are rejected."""
+        spool = await spool_factory()
+
+        url = f"/api/spools/{spool.id}/adjustments"
+        response = await async_client.post(url, json={"grams": 0, "reason": "purge"})
+        assert response.status_code == 422
+        response = await async_client.post(url, json={"grams": 5, "reason": "gremlins"})
+        assert response.status_code == 422
+        response = await async_client.post("/api/spools/nonexistent/adjustments", json={"grams": 5, "reason": "purge"})
+        assert response.status_code == 404
+
+
+class TestSwapList:
+    """Test the shareable swap/sale list."""
+
+    async def test_swap_list_json(self, async_client, test_db, spool_factory):
+        """Test only spools marked for swap are listed, with remaining weight."""
+        spool = await spool_factory(swap_available=True, photo_url="https://example.com/a.jpg")
+        await test_db.update_spool_consumption(spool.id, 400)
+        await spool_factory()
+
+        response = await async_client.get("/api/spools/swap-list")
+        assert response.status_code == 200
+
+        items = response.json()
+        assert len(items) == 1
+        assert items[0]["remaining_grams"] == 600
+        assert items[0]["photo_url"] == "https://example.com/a.jpg"
+
+    async def test_swap_list_markdown_download(self, async_client, spool_factory):
+        """Test the Markdown list is served as a file when requested."""
+        await spool_factory(swap_available=True, material="PETG")
+
+        response = await async_client.get("/api/spools/swap-list?format=markdown&title=Leftovers&download=true")
+        assert response.status_code == 200
+        assert response.headers["content-type"].startswith("text/markdown")
+        assert 'filename="swap-list.md"' in response.headers["content-disposition"]
+        assert response.text.startswith("# Leftovers")
+        assert "PETG" in response.text
+
+    async def test_swap_list_html(self, async_client, spool_factory):
+        """Test the HTML page renders the listed spools."""
+        await spool_factory(swap_available=True, color_name="Galaxy Purple")
+
+        response = await async_client.get("/api/spools/swap-list?format=html")
+        assert response.headers["content-type"].startswith("text/html")
+        assert "Galaxy Purple" in response.text
+
+    async def test_mark_spool_for_swap(self, async_client, spool_factory):
+        """Test the swap flag can be toggled through a spool update."""
+        spool = await spool_factory()
+
+        response = await async_client.put(f"/api/spools/{spool.id}", json={"swap_available": True})
+        assert response.json()["swap_available"] is True
+        assert len((await async_client.get("/api/spools/swap-list")).json()) == 1
+
+
+class TestSpoolLiveStatus:
+    """Test the live status (on scale / loaded / storage) returned with spools."""
+
+    async def test_spool_in_storage(self, async_client, spool_factory):
+        """Test spools that are neither assigned nor on the scale are in storage."""
+        spool = await spool_factory()
+
+        response = await async_client.get(f"/api/spools/{spool.id}")
+        assert response.json()["live_status"]["status"] == "storage"
+
+    async def test_assigned_spool_is_loaded(self, async_client, test_db, spool_factory, printer_factory):
+        """Test a spool assigned to a slot is reported loaded there."""
+        printer = await printer_factory()
+        spool = await spool_factory()
+        await test_db.assign_spool_to_slot(spool.id, printer.serial, 1, 3)
+
+        spools = (await async_client.get("/api/spools")).json()
+        status = spools[0]["live_status"]
+        assert status["status"] == "loaded"
+        assert (status["printer_serial"], status["ams_id"], status["tray_id"]) == (printer.serial, 1, 3)
+
+
+class TestSpoolUndo:
+    """Test undoing device-triggered spool updates."""
+
+    async def test_undo_weight_sync(self, async_client, spool_factory):
+        """Test a scale weight sync can be reverted once."""
+        spool = await spool_factory(weight_current=900)
+
+        await async_client.post(f"/api/spools/{spool.id}/weight", json={"weight": 400})
+        info = (await async_client.get(f"/api/spools/{spool.id}/undo-last")).json()
+        assert info["action"] == "weight_sync"
+        assert info["previous"]["weight_current"] == 900
+
+        response = await async_client.post(f"/api/spools/{spool.id}/undo-last")
+        assert response.status_code == 200
+        assert response.json()["weight_current"] == 900
+        assert response.json()["weight_used"] == spool.weight_used
+
+        response = await async_client.post(f"/api/spools/{spool.id}/undo-last")
+        assert response.status_code == 404
+
+    async def test_undo_device_update(self, async_client, spool_factory):
+        """Test only updates sent by the device are undoable."""
+        spool = await spool_factory(weight_current=900)
+
+        await async_client.put(f"/api/spools/{spool.id}", json={"note": "edited in the UI"})
+        assert (await async_client.post(f"/api/spools/{spool.id}/undo-last")).status_code == 404
+
+        await async_client.put(
+            f"/api/spools/{spool.id}", json={"weight_current": 500}, headers={"X-SpoolBuddy-Source": "device"}
+        )
+        response = await async_client.post(f"/api/spools/{spool.id}/undo-last")
+        assert response.status_code == 200
+        assert response.json()["weight_current"] == 900
+        assert response.json()["note"] == "edited in the UI"
+
+    async def test_undo_link_tag(self, async_client, spool_factory):
+        """Test a tag link can be reverted."""
+        spool = await spool_factory(tag_id=None)
+
+        await async_client.patch(f"/api/spools/{spool.id}/link-tag", json={"tag_id": "BBBB", "tag_type": "generic"})
+        response = await async_client.post(f"/api/spools/{spool.id}/undo-last")
+        assert response.json()["tag_id"] is None
+
+    async def test_undo_after_later_change_conflicts(self, async_client, spool_factory):
+        """Test the undo is refused when the spool changed after the device update."""
+        spool = await spool_factory(weight_current=900)
+
+        await async_client.post(f"/api/spools/{spool.id}/weight", json={"weight": 400})
+        await async_client.put(f"/api/spools/{spool.id}", json={"note": "later edit"})
+
+        response = await async_client.post(f"/api/spools/{spool.id}/undo-last")
+        assert response.status_code == 409
+        assert response.json()["current"]["weight_current"] == 400
+
+    async def test_undo_window_expires(self, async_client, spool_factory):
+        """Test device updates are no longer undoable after the window."""
+        from config import settings
+
+        spool = await spool_factory(weight_current=900)
+        await async_client.post(f"/api/spools/{spool.id}/weight", json={"weight": 400})
+
+        with patch.object(settings, "undo_window_minutes", 0):
+            response = await async_client.post(f"/api/spools/{spool.id}/undo-last")
+        assert response.status_code == 404
+
+
+class TestSpoolTagConflicts:
+    """Test tags already linked to another spool and moving them."""
+
+    async def test_create_with_tag_in_use(self, async_client, spool_factory):
+        """Test creating a spool with another active spool's tag is a 409 naming that spool."""
+        holder = await spool_factory(tag_id="AABBCCDD")
+
+        response = await async_client.post("/api/spools", json={"material": "PETG", "tag_id": "AABBCCDD"})
+        assert response.status_code == 409
+        assert response.json()["tag_id"] == "AABBCCDD"
+        assert response.json()["assigned_to"]["id"] == holder.id
+
+        other = await spool_factory(tag_id=None)
+        response = await async_client.put(f"/api/spools/{other.id}", json={"tag_id": "AABBCCDD"})
+        assert response.status_code == 409
+
+        response = await async_client.patch(f"/api/spools/{other.id}/link-tag", json={"tag_id": "AABBCCDD"})
+        assert response.status_code == 409
+        assert response.json()["assigned_to"]["id"] == holder.id
+
+    async def test_create_recycles_archived_tag(self, async_client, test_db, spool_factory):
+        """Test a tag on an archived spool is taken over instead of failing."""
+        archived = await spool_factory(tag_id="AABBCCDD")
+        await test_db.archive_spool(archived.id)
+
+        response = await async_client.post("/api/spools", json={"material": "PETG", "tag_id": "AABBCCDD"})
+        assert response.status_code == 201
+        assert response.json()["tag_id"] == "AABBCCDD"
+        assert (await test_db.get_spool(archived.id)).tag_id is None
+
+    async def test_move_tag(self, async_client, test_db, spool_factory, printer_factory):
+        """Test moving a tag keeps the old spool's history and records the move on both spools."""
+        printer = await printer_factory()
+        old = await spool_factory(tag_id="AABBCCDD")
+        await test_db.log_usage(old.id, printer.serial, "benchy", 12.5)
+        new = await spool_factory(tag_id=None)
+
+        response = await async_client.post(f"/api/spools/{new.id}/move-tag", json={"tag_id": "AABBCCDD"})
+        assert response.status_code == 200
+        data = response.json()
+        assert data["spool"]["tag_id"] == "AABBCCDD"
+        assert data["previous_spool"]["id"] == old.id
+        assert data["previous_spool"]["tag_id"] is None
+
+        assert len(await test_db.get_usage_history(spool_id=old.id)) == 1
+        for spool in (old, new):
+            moves = (await async_client.get(f"/api/spools/{spool.id}/tag-moves")).json()
+            assert [(m["from_spool_id"], m["to_spool_id"]) for m in moves] == [(old.id, new.id)]
+
+        response = await async_client.post("/api/spools/missing/move-tag", json={"tag_id": "AABBCCDD"})
+        assert response.status_code == 404
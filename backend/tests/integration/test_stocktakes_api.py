@@ -0,0 +1,110 @@
+"""Integration tests for the stocktakes API."""
+
+
+class TestStocktakesAPI:
+    """Test stocktake sessions."""
+
+    async def test_start_and_list(self, async_client):
+        """Test starting a stocktake, and that only one can be open."""
+        response = await async_client.post("/api/stocktakes", json={"name": "Year end"})
+        assert response.status_code == 201
+        stocktake = response.json()
+        assert (stocktake["name"], stocktake["status"], stocktake["scan_count"]) == ("Year end", "open", 0)
+
+        assert (await async_client.post("/api/stocktakes", json={})).status_code == 409
+        assert (await async_client.get("/api/stocktakes/active")).json()["id"] == stocktake["id"]
+        assert [s["id"] for s in (await async_client.get("/api/stocktakes")).json()] == [stocktake["id"]]
+
+    async def test_no_active_stocktake(self, async_client):
+        """Test the active stocktake is null when none is open."""
+        response = await async_client.get("/api/stocktakes/active")
+        assert response.status_code == 200
+        assert response.json() is None
+
+    async def test_scan_by_id_and_tag(self, async_client, spool_factory):
+        """Test spools can be counted by ID or by tag, and a rescan replaces the scan."""
+        first = await spool_factory()
+        second = await spool_factory(tag_id="04AABBCCDD")
+        stocktake = (await async_client.post("/api/stocktakes", json={})).json()
+        scans_url = f"/api/stocktakes/{stocktake['id']}/scans"
+
+        response = await async_client.post(scans_url, json={"spool_id": first.id, "weight": 1250})
+        assert response.status_code == 201
+        assert (response.json()["source"], response.json()["expected_remaining"]) == ("web", 1000)
+        await async_client.post(scans_url, json={"tag_id": "04AABBCCDD", "source": "device"})
+        await async_client.post(scans_url, json={"spool_id": first.id})
+
+        scans = (await async_client.get(scans_url)).json()
+        assert {s["spool_id"]: s["weight"] for s in scans} == {first.id: 1250, second.id: None}
+        assert (await async_client.get(f"/api/stocktakes/{stocktake['id']}")).json()["scan_count"] == 2
+
+    async def test_scan_errors(self, async_client):
+        """Test scans need exactly one of spool ID and tag, of a known spool."""
+        stocktake = (await async_client.post("/api/stocktakes", json={})).json()
+        scans_url = f"/api/stocktakes/{stocktake['id']}/scans"
+
+        assert (await async_client.post(scans_url, json={})).status_code == 422
+        assert (await async_client.post(scans_url, json={"spool_id": "a", "tag_id": "b"})).status_code == 422
+        assert (await async_client.post(scans_url, json={"tag_id": "UNKNOWN"})).status_code == 404
+        assert (await async_client.post("/api/stocktakes/999/scans", json={"spool_id": "a"})).status_code == 404
+
+    async def test_uncount_spool(self, async_client, spool_factory):
+        """Test a spool scanned by mistake can be removed from the count."""
+        spool = await spool_factory()
+        stocktake = (await async_client.post("/api/stocktakes", json={})).json()
+        scans_url = f"/api/stocktakes/{stocktake['id']}/scans"
+        await async_client.post(scans_url, json={"spool_id": spool.id})
+
+        assert (await async_client.delete(f"{scans_url}/{spool.id}")).status_code == 204
+        assert (await async_client.delete(f"{scans_url}/{spool.id}")).status_code == 404
+        assert (await async_client.get(scans_url)).json() == []
+
+    async def test_report(self, async_client, spool_factory):
+        """Test the report lists unseen spools and weights off by more than the tolerance."""
+        seen = await spool_factory(color_name="Black")
+        light = await spool_factory(color_name="White")
+        unseen = await spool_factory(color_name="Red")
+        archived = await spool_factory(color_name="Blue")
+        await async_client.post(f"/api/spools/{archived.id}/archive")
+        stocktake = (await async_client.post("/api/stocktakes", json={})).json()
+        scans_url = f"/api/stocktakes/{stocktake['id']}/scans"
+        await async_client.post(scans_url, json={"spool_id": seen.id, "weight": 1230})  # 20 g off
+        await async_client.post(scans_url, json={"spool_id": light.id, "weight": 650})  # 600 g missing
+
+        report = (await async_client.get(f"/api/stocktakes/{stocktake['id']}/report")).json()
+        assert (report["expected_count"], report["seen_count"]) == (3, 2)
+        assert [s["spool_id"] for s in report["unseen"]] == [unseen.id]
+        assert [(d["spool_id"], d["difference"]) for d in report["discrepancies"]] == [(light.id, -600)]
+
+        report = (await async_client.get(f"/api/stocktakes/{stocktake['id']}/report?tolerance=10")).json()
+        assert {d["spool_id"] for d in report["discrepancies"]} == {seen.id, light.id}
+
+    async def test_complete(self, async_client, spool_factory):
+        """Test completing stores the report and can sync weighed spools to the scale."""
+        spool = await spool_factory()
+        stocktake = (await async_client.post("/api/stocktakes", json={})).json()
+        await async_client.post(f"/api/stocktakes/{stocktake['id']}/scans", json={"spool_id": spool.id, "weight": 650})
+
+        response = await async_client.post(f"/api/stocktakes/{stocktake['id']}/complete", json={"apply_weights": True})
+        assert response.status_code == 200
+        completed = response.json()
+        assert completed["status"] == "completed"
+        assert completed["finished_at"] is not None
+        assert completed["report"]["discrepancies"][0]["difference"] == -600
+
+        assert (await async_client.get(f"/api/spools/{spool.id}")).json()["weight_current"] == 650
+        # The stored report is kept as it was at completion
+        report = (await async_client.get(f"/api/stocktakes/{stocktake['id']}/report")).json()
+        assert report["discrepancies"][0]["difference"] == -600
+
+        scans_url = f"/api/stocktakes/{stocktake['id']}/scans"
+        assert (await async_client.post(scans_url, json={"spool_id": spool.id})).status_code == 409
+        assert (await async_client.post(f"/api/stocktakes/{stocktake['id']}/complete", json={})).status_code == 409
+
+    async def test_cancel(self, async_client):
+        """Test a cancelled stocktake has no report and lets a new one start."""
+        stocktake = (await async_client.post("/api/stocktakes", json={})).json()
+
+        response = await async_client.post(f"/api/stocktakes/{stocktake['id']}/cancel")
+        assert (response.json()["status"], response.json()["report"]) == ("cancelled", None)
+        assert (await async_client.post("/api/stocktakes", json={})).status_code == 201
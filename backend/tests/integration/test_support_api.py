@@ -7,9 +7,11 @@ Tests cover:
 - Log clearing
 - Support bundle generation
 - System information
+- Database maintenance
 """
 
 import tempfile
+import time
 from pathlib import Path
 from unittest.mock import MagicMock, patch
 
@@ -405,3 +407,46 @@ class TestSystemInfoAPI:
             assert data["spool_count"] == 2
             assert data["printer_count"] == 1
             assert data["connected_printers"] == 0
+
+
+class TestMaintenanceAPI:
+    """Tests for data retention and database maintenance."""
+
+    async def test_get_maintenance_status(self, async_client, test_db):
+        """Test the policy combines config defaults with the AMS history setting."""
+        await test_db.set_setting("ams_history_retention_days", "14")
+
+        response = await async_client.get("/api/support/maintenance")
+        assert response.status_code == 200
+
+        data = response.json()
+        assert data["policy"]["weight_history_days"] == 90
+        assert data["policy"]["ams_sensor_history_days"] == 14
+        assert data["last_run"] is None or "deleted" in data["last_run"]
+
+    async def test_run_maintenance(self, async_client, test_db, spool_factory):
+        """Test old weight readings are thinned to one per day and old sensor history is dropped."""
+        spool = await spool_factory()
+        old_day = (int(time.time()) // 86400 - 100) * 86400
+        for offset, weight in ((3600, 900), (7200, 880), (10800, 870)):
+            await test_db.record_weight(spool.id, weight, "scale", recorded_at=old_day + offset)
+        await test_db.record_weight(spool.id, 850, "scale")
+        await test_db.record_ams_sensor("S1", 0, humidity=20, humidity_raw=None, temperature=25)
+        await test_db.conn.execute("UPDATE ams_sensor_history SET recorded_at = ?", (old_day,))
+        await test_db.conn.commit()
+
+        response = await async_client.post("/api/support/maintenance?vacuum=true")
+        assert response.status_code == 200
+
+        result = response.json()
+        assert result["deleted"]["weight_history"] == 2
+        assert result["deleted"]["ams_sensor_history"] == 1
+        assert result["vacuumed"] is True
+
+        async with test_db.conn.execute(
+            "SELECT weight FROM weight_history WHERE spool_id = ? ORDER BY recorded_at", (spool.id,)
+        ) as cursor:
+            assert [row[0] for row in await cursor.fetchall()] == [870, 850]
+
+        status = (await async_client.get("/api/support/maintenance")).json()
+        assert status["last_run"]["deleted"]["weight_history"] == 2
@@ -4,9 +4,20 @@ Integration tests for Tag Encoding/Decoding API.
 Tests cover:
 - List tag formats
 - Decode tag data
+- Convert tags between formats
+- Phone tag scans
+- Batch encoding on the device
+- Tag write log and failure statistics
 """
 
+import asyncio
+import base64
+import json
+from unittest.mock import AsyncMock, patch
+
 import pytest
+from services.jobs import get_job_runner
+from services.tag_batch import EncodingStation
 
 
 class TestTagFormatsAPI:
@@ -19,13 +30,14 @@ class TestTagFormatsAPI:
         assert response.status_code == 200
         data = response.json()
         assert isinstance(data, list)
-        assert len(data) == 3  # SpoolEase, OpenSpool, OpenTag3D
+        assert len(data) == 4  # SpoolEase, OpenSpool, OpenTag3D, OpenPrintTag
 
         # Check format structure
         format_ids = [f["id"] for f in data]
         assert "SpoolEaseV2" in format_ids
         assert "OpenSpool" in format_ids
         assert "OpenTag3D" in format_ids
+        assert "OpenPrintTag" in format_ids
 
     async def test_format_info_structure(self, async_client):
         """Test that format info has all required fields."""
@@ -101,6 +113,97 @@ class TestTagDecodeAPI:
         assert "uid_base64" in data
 
 
+    async def test_decode_mifare_blocks(self, async_client):
+        """Test decoding Bambu Lab blocks as sent by the Pico bridge."""
+        decode_request = {
+            "tag_uid": "75886B1D",
+            "blocks": {
+                "1": "4130302D473100004746413030000000",
+                "2": "504C4100000000000000000000000000",
+            },
+        }
+        response = await async_client.post("/api/tags/decode", json=decode_request)
+
+        assert response.status_code == 200
+        data = response.json()
+        assert data["tag_type"] == "Bambu Lab"
+        assert data["nfc_type"] == "MifareClassic1K"
+        assert data["material"] == "PLA"
+        assert data["raw_data"]["material_id"] == "GFA00"
+
+    async def test_decode_dump_uses_uid_from_dump(self, async_client):
+        """Test text dumps don't require tag_uid."""
+        decode_request = {"dump": "UID: 75 88 6B 1D\nBlock 0: 75 88 6B 1D 8B 08 04 00 62 63 64 65 66 67 68 69"}
+        response = await async_client.post("/api/tags/decode", json=decode_request)
+
+        assert response.status_code == 200
+        assert response.json()["tag_uid"] == "75886B1D"
+        assert response.json()["tag_type"] == "Unknown"
+
+    async def test_decode_invalid_dump(self, async_client):
+        """Test malformed dumps are rejected."""
+        response = await async_client.post("/api/tags/decode", json={"blocks": {"1": "not-hex"}})
+        assert response.status_code == 400
+
+
+BAMBU_BLOCKS = {
+    "1": "4130302D473100004746413030000000",
+    "2": "504C4100000000000000000000000000",
+}
+
+
+class TestTagConvertAPI:
+    """Tests for converting tags between formats."""
+
+    async def test_convert_bambu_to_openprinttag(self, async_client):
+        """Test re-encoding a Bambu Lab tag for an NTAG sticker."""
+        convert_request = {"tag_uid": "75886B1D", "blocks": BAMBU_BLOCKS, "target_uid": "04:11:22:33:44:55:66"}
+        response = await async_client.post("/api/tags/convert", json=convert_request)
+
+        assert response.status_code == 200
+        data = response.json()
+        assert data["source"]["tag_type"] == "Bambu Lab"
+        assert data["encoded"]["format"] == "OpenPrintTag"
+        assert data["encoded"]["ndef_type"] == "application/vnd.openprinttag"
+        assert data["encoded"]["tag_uid"] == "04112233445566"
+        assert "slicer_filament" in data["dropped"]
+        # NDEF TLV, ended by a terminator
+        assert data["tag_data_hex"].startswith("03")
+        assert data["tag_data_hex"].endswith("FE")
+
+        # The converted tag decodes to the same material
+        decoded = await async_client.post(
+            "/api/tags/decode",
+            json={"tag_uid": "04112233445566", "pages": {"4": data["tag_data_hex"]}},
+        )
+        assert decoded.json()["tag_type"] == "OpenPrintTag"
+        assert decoded.json()["material"] == "PLA"
+
+    async def test_convert_to_openspool(self, async_client):
+        """Test converting to a JSON format."""
+        convert_request = {"tag_uid": "75886B1D", "blocks": BAMBU_BLOCKS, "format": "OpenSpool"}
+        response = await async_client.post("/api/tags/convert", json=convert_request)
+
+        assert response.status_code == 200
+        payload = json.loads(response.json()["encoded"]["json_payload"])
+        assert payload["type"] == "PLA"
+
+    async def test_convert_unknown_source(self, async_client):
+        """Test tags that can't be decoded aren't converted."""
+        convert_request = {"dump": "UID: 75 88 6B 1D\nBlock 0: 75 88 6B 1D 8B 08 04 00 62 63 64 65 66 67 68 69"}
+        response = await async_client.post("/api/tags/convert", json=convert_request)
+
+        assert response.status_code == 422
+        assert "Couldn't decode" in response.json()["detail"]
+
+    async def test_convert_to_bambu_rejected(self, async_client):
+        """Test Bambu Lab isn't a target format (its tags are signed)."""
+        convert_request = {"tag_uid": "75886B1D", "blocks": BAMBU_BLOCKS, "format": "Bambu Lab"}
+        response = await async_client.post("/api/tags/convert", json=convert_request)
+
+        assert response.status_code == 422
+
+
 class TestTagEncodeAPI:
     """Tests for tag encoding endpoints."""
 
@@ -130,3 +233,204 @@ class TestTagEncodeAPI:
         response = await async_client.post("/api/tags/encode", json=encode_request)
 
         assert response.status_code == 422  # Validation error
+
+
+@pytest.fixture
+def station():
+    """A fresh encoding station, with no batch running."""
+    station = EncodingStation()
+    with patch("services.tag_batch._station", station):
+        yield station
+
+
+class TestTagBatchAPI:
+    """Tests for batch encoding tags on the device."""
+
+    TAG = "04:A1:B2:C3:D4:E5:F6"
+
+    async def _post_state(self, async_client, test_db, **params):
+        with (
+            patch("main.get_db", AsyncMock(return_value=test_db)),
+            patch("main.broadcast_message", AsyncMock()),
+        ):
+            return (await async_client.post("/api/display/state", params={"weight": 0, **params})).json()
+
+    async def _start(self, async_client, station, spool_ids):
+        response = await async_client.post("/api/jobs", json={"kind": "tag_batch", "params": {"spool_ids": spool_ids}})
+        assert response.status_code == 202
+        job = response.json()
+        for _ in range(100):
+            if station.job_id == job["id"]:
+                break
+            await asyncio.sleep(0.01)
+        return job
+
+    def _report(self, job, index, tag_uid, outcome, **fields):
+        return {"job_id": job["id"], "index": index, "tag_uid": tag_uid, "outcome": outcome, **fields}
+
+    async def test_encodes_and_links_tags(self, async_client, test_db, spool_factory, station):
+        """Test the device is prompted per spool, verified tags are linked and failed ones skipped."""
+        red = await spool_factory(material="PETG", color_name="Red")
+        blue = await spool_factory(material="PLA", color_name="Blue")
+        job = await self._start(async_client, station, [red.id, blue.id])
+
+        data = await self._post_state(async_client, test_db)
+        assert data["encode"]["message"] == "Place tag 1 of 2 for Red PETG"
+        assert data["encode"]["data"] is None
+
+        data = await self._post_state(async_client, test_db, tag_id=self.TAG)
+        task = data["encode"]
+        assert (task["index"], task["tag_uid"], data["action"]) == (1, self.TAG, "none")
+        # The data is a SpoolEase tag built for this tag's UID
+        memory = task["data"] + "00" * (-len(task["data"]) // 2 % 4)
+        pages = {4 + i // 8: memory[i : i + 8] for i in range(0, len(memory), 8)}
+        decoded = await async_client.post("/api/tags/decode", json={"tag_uid": self.TAG, "pages": pages})
+        assert (decoded.json()["tag_type"], decoded.json()["material"]) == ("SpoolEaseV2", "PETG")
+
+        report = self._report(job, 1, self.TAG, "verified", pages_written=30, retries=1, rf_signal=412)
+        response = await async_client.post("/api/tags/batch/result", json=report)
+        assert response.status_code == 200
+        assert response.json()["encode"]["index"] == 2
+        assert response.json()["encode"]["message"] == "Remove the tag. Tag 1 done. Place tag 2 of 2 for Blue PLA"
+        assert (await test_db.get_spool(red.id)).tag_id == self.TAG
+
+        # Reports for a finished spool are refused
+        assert (await async_client.post("/api/tags/batch/result", json=report)).status_code == 409
+
+        other = "04:11:22:33:44:55:66"
+        await self._post_state(async_client, test_db, tag_id=other)
+        for _ in range(3):
+            report = self._report(job, 2, other, "write_failed", failed_page=4)
+            assert (await async_client.post("/api/tags/batch/result", json=report)).status_code == 200
+
+        await get_job_runner().wait(job["id"])
+        data = (await async_client.get(f"/api/jobs/{job['id']}")).json()
+        assert data["status"] == "completed"
+        assert (data["result"]["encoded"], data["result"]["failed"]) == (1, 1)
+        first, second = data["result"]["tags"]
+        assert (first["tag_id"], first["attempts"], first["retries"]) == (self.TAG, 1, 1)
+        assert (second["tag_id"], second["attempts"], second["outcome"]) == (None, 3, "write_failed")
+        assert (await self._post_state(async_client, test_db))["encode"] is None
+
+        # Every write is logged, failed ones included
+        writes = (await async_client.get("/api/tags/writes", params={"job_id": job["id"]})).json()
+        assert [w["outcome"] for w in writes] == ["write_failed"] * 3 + ["verified"]
+        logged = writes[-1]
+        assert (logged["tag_id"], logged["spool_id"], logged["rf_signal"]) == ("04A1B2C3D4E5F6", red.id, 412)
+
+    async def test_refuses_tag_of_other_spool(self, async_client, test_db, spool_factory, station):
+        """Test a tag linked to another spool gets no data to write."""
+        await spool_factory(material="PLA", tag_id=self.TAG)
+        spool = await spool_factory(material="PETG", color_name="Red")
+        job = await self._start(async_client, station, [spool.id])
+
+        task = (await self._post_state(async_client, test_db, tag_id=self.TAG))["encode"]
+        assert task["data"] is None
+        assert task["message"] == "Tag belongs to spool #1. Place another tag"
+
+        await get_job_runner().cancel(job["id"])
+        assert station.job_id is None
+
+    async def test_unknown_spool_fails_job(self, async_client, station):
+        """Test a batch with a missing spool fails without taking over the device."""
+        params = {"spool_ids": ["nope"]}
+        job = (await async_client.post("/api/jobs", json={"kind": "tag_batch", "params": params})).json()
+        await get_job_runner().wait(job["id"])
+
+        data = (await async_client.get(f"/api/jobs/{job['id']}")).json()
+        assert data["status"] == "failed"
+        assert "nope" in data["error"]
+        assert station.job_id is None
+
+
+class TestTagWriteLogAPI:
+    """Tests for the tag write log and its failure statistics."""
+
+    async def test_filters_writes(self, async_client, test_db):
+        """Test writes are listed newest first and filtered by tag or outcome."""
+        await test_db.log_tag_write("04A1B2C3D4E5F6", "verified", job_id="a", pages_written=30)
+        await test_db.log_tag_write("0411223344556F", "mismatch", job_id="a", retries=3, failed_page=6)
+
+        writes = (await async_client.get("/api/tags/writes")).json()
+        assert [w["outcome"] for w in writes] == ["mismatch", "verified"]
+
+        writes = (await async_client.get("/api/tags/writes", params={"tag_id": "04:a1:b2:c3:d4:e5:f6"})).json()
+        assert [w["pages_written"] for w in writes] == [30]
+
+        writes = (await async_client.get("/api/tags/writes", params={"failed": True})).json()
+        assert [w["failed_page"] for w in writes] == [6]
+
+    async def test_stats_per_batch(self, async_client, test_db):
+        """Test failure rates, retries and RF signal are summed up overall and per batch."""
+        await test_db.log_tag_write("04A1", "verified", job_id="good", rf_signal=400)
+        await test_db.log_tag_write("04A2", "verified", job_id="good", retries=1, rf_signal=420)
+        await test_db.log_tag_write("04B1", "write_failed", job_id="bad", retries=3, rf_signal=380)
+        await test_db.log_tag_write("04B1", "verified", job_id="bad", retries=2)
+
+        response = await async_client.get("/api/tags/writes/stats", params={"days": 30})
+        assert response.status_code == 200
+        data = response.json()
+        assert data["since"] is not None
+        assert (data["total"]["writes"], data["total"]["failed"], data["total"]["tags"]) == (4, 1, 3)
+        assert data["outcomes"] == {"verified": 3, "write_failed": 1}
+
+        batches = {b["job_id"]: b for b in data["batches"]}
+        assert batches["good"]["failure_rate"] == 0
+        assert batches["good"]["avg_rf_signal"] == 410
+        assert (batches["bad"]["failure_rate"], batches["bad"]["avg_retries"]) == (0.5, 2.5)
+        assert batches["bad"]["min_rf_signal"] == 380
+
+    async def test_stats_without_writes(self, async_client):
+        """Test the statistics are empty before any tag was written."""
+        data = (await async_client.get("/api/tags/writes/stats")).json()
+        assert (data["total"]["writes"], data["total"]["failure_rate"]) == (0, 0)
+        assert data["batches"] == []
+
+
+def openspool_record(**fields) -> dict:
+    data = {"protocol": "openspool", "version": "1.0", "type": "PETG", "color_hex": "FF0000", "brand": "Acme"}
+    payload = json.dumps({**data, **fields}).encode()
+    return {"type": "application/json", "payload": base64.b64encode(payload).decode()}
+
+
+class TestTagScanAPI:
+    """Tests for tags read with a phone."""
+
+    async def test_scan_creates_spool(self, async_client, test_db):
+        """Test an unknown tag with spool data adds a spool, a second scan matches it."""
+        scan = {"uid": "04:A1:B2:C3:D4:E5:F6", "ndef_records": [openspool_record()]}
+        response = await async_client.post("/api/tags/scan", json=scan)
+
+        assert response.status_code == 200
+        data = response.json()
+        assert data["created"] is True
+        assert data["tag_type"] == "OpenSpool"
+        assert data["spool"]["material"] == "PETG"
+        assert data["spool"]["tag_id"] == data["uid_base64"]
+
+        again = (await async_client.post("/api/tags/scan", json=scan)).json()
+        assert again["created"] is False
+        assert again["spool"]["id"] == data["spool"]["id"]
+
+    async def test_scan_matches_existing_spool(self, async_client, test_db):
+        """Test a tag already on a spool resolves to it even without NDEF data."""
+        spool = (await async_client.post("/api/spools", json={"material": "PLA", "tag_id": "BKGyw9Tl9g"})).json()
+
+        response = await async_client.post("/api/tags/scan", json={"uid": "04A1B2C3D4E5F6"})
+
+        assert response.status_code == 200
+        assert response.json()["spool"]["id"] == spool["id"]
+
+    async def test_scan_without_create(self, async_client, test_db):
+        """Test create=false only resolves."""
+        scan = {"uid": "0A0B0C0D", "ndef_records": [openspool_record()], "create": False}
+        response = await async_client.post("/api/tags/scan", json=scan)
+
+        assert response.status_code == 200
+        assert response.json()["spool"] is None
+        assert response.json()["tag_type"] == "OpenSpool"
+
+    async def test_scan_invalid_uid(self, async_client):
+        """Test a non-hex UID is rejected."""
+        response = await async_client.post("/api/tags/scan", json={"uid": "not-a-uid"})
+        assert response.status_code == 400
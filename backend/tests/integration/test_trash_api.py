@@ -0,0 +1,96 @@
+"""Integration tests for the trash API."""
+
+import time
+
+
+class TestTrashAPI:
+    """Test soft delete, restore and purge of spools and printers."""
+
+    async def test_deleted_spool_moves_to_trash(self, async_client, test_db, spool_factory, printer_factory):
+        """Test deleting a spool keeps it (and its history) in the trash."""
+        printer = await printer_factory()
+        spool = await spool_factory()
+        await test_db.log_usage(spool.id, printer.serial, "benchy", 10)
+
+        response = await async_client.delete(f"/api/spools/{spool.id}")
+        assert response.status_code == 204
+
+        assert (await async_client.get("/api/spools")).json() == []
+
+        trash = (await async_client.get("/api/trash")).json()
+        assert [s["id"] for s in trash["spools"]] == [spool.id]
+        assert trash["spools"][0]["deleted_at"] is not None
+        assert trash["retention_days"] == 30
+        assert len(await test_db.get_usage_history(spool_id=spool.id)) == 1
+
+    async def test_restore_spool(self, async_client, spool_factory):
+        """Test restoring a trashed spool."""
+        spool = await spool_factory()
+        await async_client.delete(f"/api/spools/{spool.id}")
+
+        response = await async_client.post(f"/api/trash/spools/{spool.id}/restore")
+        assert response.status_code == 200
+        assert response.json()["deleted_at"] is None
+
+        response = await async_client.get(f"/api/spools/{spool.id}")
+        assert response.status_code == 200
+
+    async def test_restore_spool_not_in_trash(self, async_client, spool_factory):
+        """Test restoring a spool that isn't trashed."""
+        spool = await spool_factory()
+        response = await async_client.post(f"/api/trash/spools/{spool.id}/restore")
+        assert response.status_code == 404
+
+    async def test_purge_spool(self, async_client, test_db, spool_factory, printer_factory):
+        """Test permanently deleting a trashed spool removes its history."""
+        printer = await printer_factory()
+        spool = await spool_factory()
+        await test_db.log_usage(spool.id, printer.serial, "benchy", 10)
+        await async_client.delete(f"/api/spools/{spool.id}")
+
+        response = await async_client.delete(f"/api/trash/spools/{spool.id}")
+        assert response.status_code == 204
+
+        assert await test_db.get_spool(spool.id, include_deleted=True) is None
+        assert await test_db.get_usage_history(spool_id=spool.id) == []
+
+    async def test_printer_trash_and_restore(self, async_client, printer_factory):
+        """Test deleting and restoring a printer."""
+        printer = await printer_factory()
+
+        response = await async_client.delete(f"/api/printers/{printer.serial}")
+        assert response.status_code == 204
+
+        trash = (await async_client.get("/api/trash")).json()
+        assert [p["serial"] for p in trash["printers"]] == [printer.serial]
+
+        response = await async_client.post(f"/api/trash/printers/{printer.serial}/restore")
+        assert response.status_code == 200
+
+        response = await async_client.get(f"/api/printers/{printer.serial}")
+        assert response.status_code == 200
+
+    async def test_purge_respects_retention(self, test_db, spool_factory):
+        """Test scheduled purge only removes items older than the retention window."""
+        old = await spool_factory(color_name="Old")
+        recent = await spool_factory(color_name="Recent")
+        await test_db.delete_spool(old.id)
+        await test_db.delete_spool(recent.id)
+        await test_db.conn.execute(
+            "UPDATE spools SET deleted_at = ? WHERE id = ?", (int(time.time()) - 40 * 86400, old.id)
+        )
+        await test_db.conn.commit()
+
+        purged = await test_db.purge_trash(retention_days=30)
+        assert purged == {"spools": 1, "printers": 0}
+        assert await test_db.get_spool(old.id, include_deleted=True) is None
+        assert await test_db.get_spool(recent.id, include_deleted=True) is not None
+
+    async def test_empty_trash(self, async_client, spool_factory):
+        """Test emptying the trash."""
+        spool = await spool_factory()
+        await async_client.delete(f"/api/spools/{spool.id}")
+
+        response = await async_client.delete("/api/trash")
+        assert response.status_code == 200
+        assert response.json() == {"spools": 1, "printers": 0}
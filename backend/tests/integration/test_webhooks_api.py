@@ -0,0 +1,85 @@
+"""Integration tests for the webhooks API."""
+
+import json
+
+from services.webhooks import sign_payload
+
+
+class TestWebhooksAPI:
+    """Test webhook registration and delivery log."""
+
+    async def _create(self, async_client, **overrides):
+        data = {
+            "name": "Home Assistant",
+            "url": "http://ha.local/api/webhook/spools",
+            "events": ["spool.low", "print.finished"],
+            "secret": "s3cret",
+        }
+        data.update(overrides)
+        return await async_client.post("/api/webhooks", json=data)
+
+    async def test_list_events(self, async_client):
+        """Test listing supported event types."""
+        response = await async_client.get("/api/webhooks/events")
+        assert response.status_code == 200
+        assert "tag.scanned" in response.json()
+
+    async def test_create_webhook(self, async_client):
+        """Test registering a webhook hides the secret."""
+        response = await self._create(async_client)
+        assert response.status_code == 201
+
+        data = response.json()
+        assert data["events"] == ["spool.low", "print.finished"]
+        assert data["has_secret"] is True
+        assert "secret" not in data
+
+    async def test_create_webhook_unknown_event(self, async_client):
+        """Test unknown event types are rejected."""
+        response = await self._create(async_client, events=["spool.exploded"])
+        assert response.status_code == 422
+
+    async def test_update_webhook(self, async_client):
+        """Test updating webhook subscriptions and enabled flag."""
+        webhook_id = (await self._create(async_client)).json()["id"]
+
+        response = await async_client.patch(
+            f"/api/webhooks/{webhook_id}", json={"events": ["tag.scanned"], "enabled": False}
+        )
+        assert response.status_code == 200
+        assert response.json()["events"] == ["tag.scanned"]
+        assert response.json()["enabled"] is False
+
+    async def test_delete_webhook(self, async_client):
+        """Test deleting a webhook."""
+        webhook_id = (await self._create(async_client)).json()["id"]
+
+        response = await async_client.delete(f"/api/webhooks/{webhook_id}")
+        assert response.status_code == 204
+
+        response = await async_client.get(f"/api/webhooks/{webhook_id}")
+        assert response.status_code == 404
+
+    async def test_test_delivery_is_signed_and_logged(self, async_client, mock_httpx_client):
+        """Test sending a test event signs the payload and records the delivery."""
+        webhook_id = (await self._create(async_client)).json()["id"]
+
+        response = await async_client.post(f"/api/webhooks/{webhook_id}/test")
+        assert response.status_code == 200
+        assert response.json()["success"] is True
+        assert response.json()["attempts"] == 1
+
+        call = mock_httpx_client.post.call_args
+        body = call.kwargs["content"]
+        assert json.loads(body)["event"] == "spool.low"
+        assert call.kwargs["headers"]["X-SpoolBuddy-Signature"] == sign_payload("s3cret", body)
+
+        deliveries = (await async_client.get(f"/api/webhooks/{webhook_id}/deliveries")).json()
+        assert len(deliveries) == 1
+        assert deliveries[0]["status_code"] == 200
+        assert deliveries[0]["success"] is True
+
+    async def test_deliveries_not_found(self, async_client):
+        """Test delivery log for a missing webhook."""
+        response = await async_client.get("/api/webhooks/999/deliveries")
+        assert response.status_code == 404
@@ -0,0 +1,72 @@
+"""Unit tests for AMS slot change detection."""
+
+from models import AmsTray, AmsUnit, PrinterState
+from services.ams_events import detect_ams_events, tray_slot
+
+
+def _state(trays: dict, **fields) -> PrinterState:
+    """State with one AMS unit; trays maps tray_id -> tray_type (None for empty)."""
+    unit = AmsUnit(
+        id=0,
+        trays=[
+            AmsTray(ams_id=0, tray_id=tray_id, tray_type=tray_type, tray_color="FFFFFFFF" if tray_type else None)
+            for tray_id, tray_type in trays.items()
+        ],
+    )
+    return PrinterState(ams_units=[unit], **fields)
+
+
+class TestTraySlot:
+    def test_decodes_tray_indexes(self):
+        assert tray_slot(6) == (1, 2)
+        assert tray_slot(17) == (129, 0)
+        assert tray_slot(254) == (255, 0)
+        assert tray_slot(255) is None
+        assert tray_slot(None) is None
+        # Dual-nozzle external holder: (ams_id << 8) | slot
+        assert tray_slot(0xFE00, nozzle_count=2) == (254, 0)
+
+
+class TestDetectAmsEvents:
+    def test_no_events_without_previous_state(self):
+        assert detect_ams_events(None, _state({0: "PLA"})) == []
+
+    def test_insert_remove_and_change(self):
+        prev = _state({0: None, 1: "PLA", 2: "PETG"})
+        state = _state({0: "ABS", 1: None, 2: "ASA"})
+
+        events = detect_ams_events(prev, state)
+
+        assert [(e.event, e.tray_id, e.tray_type) for e in events] == [
+            ("inserted", 0, "ABS"),
+            ("removed", 1, "PLA"),
+            ("changed", 2, "ASA"),
+        ]
+
+    def test_unchanged_and_missing_slots_are_ignored(self):
+        prev = _state({0: "PLA", 1: "PETG"})
+        state = _state({0: "PLA"})
+
+        assert detect_ams_events(prev, state) == []
+
+    def test_load_and_unload(self):
+        prev = _state({0: "PLA", 1: "PETG"}, tray_now=0)
+        state = _state({0: "PLA", 1: "PETG"}, tray_now=1)
+
+        events = detect_ams_events(prev, state)
+
+        assert [(e.event, e.ams_id, e.tray_id, e.tray_type) for e in events] == [
+            ("unloaded", 0, 0, "PLA"),
+            ("loaded", 0, 1, "PETG"),
+        ]
+        # Unloading to nothing
+        events = detect_ams_events(state, state.model_copy(update={"tray_now": 255}))
+        assert [(e.event, e.tray_id) for e in events] == [("unloaded", 1)]
+
+    def test_dual_nozzle_load_per_extruder(self):
+        prev = _state({0: "PLA", 1: "PETG"}, nozzle_count=2, tray_now_left=255, tray_now_right=0)
+        state = _state({0: "PLA", 1: "PETG"}, nozzle_count=2, tray_now_left=1, tray_now_right=0)
+
+        events = detect_ams_events(prev, state)
+
+        assert [(e.event, e.tray_id, e.extruder) for e in events] == [("loaded", 1, 1)]
@@ -0,0 +1,104 @@
+"""Unit tests for the printer chamber camera client."""
+
+import asyncio
+import struct
+
+import pytest
+from services import bambu_camera
+from services.bambu_camera import (
+    CameraError,
+    auth_packet,
+    first_frame,
+    read_jpeg_frames,
+    snapshot,
+    split_jpeg_stream,
+    uses_rtsp,
+)
+
+
+def make_jpeg(body: bytes = b"frame") -> bytes:
+    return b"\xff\xd8" + body + b"\xff\xd9"
+
+
+def frame_packet(payload: bytes) -> bytes:
+    return struct.pack("<IIII", len(payload), 0, 1, 0) + payload
+
+
+def feed(*chunks: bytes) -> asyncio.StreamReader:
+    reader = asyncio.StreamReader()
+    for chunk in chunks:
+        reader.feed_data(chunk)
+    reader.feed_eof()
+    return reader
+
+
+async def collect(frames) -> list[bytes]:
+    return [frame async for frame in frames]
+
+
+class TestCameraProtocol:
+    """Test the port 6000 camera protocol and MJPEG splitting."""
+
+    def test_auth_packet(self):
+        packet = auth_packet("12345678")
+        assert len(packet) == 80
+        assert struct.unpack("<IIII", packet[:16]) == (0x40, 0x3000, 0, 0)
+        assert packet[16:48].rstrip(b"\0") == b"bblp"
+        assert packet[48:80].rstrip(b"\0") == b"12345678"
+
+    def test_uses_rtsp(self):
+        assert uses_rtsp("X1C")
+        assert uses_rtsp("x1 carbon")
+        assert uses_rtsp("H2D")
+        assert not uses_rtsp("P1S")
+        assert not uses_rtsp("A1-MINI")
+        assert not uses_rtsp(None)
+
+    async def test_read_frames(self):
+        first, second = make_jpeg(), make_jpeg(b"second")
+        reader = feed(frame_packet(first), frame_packet(second))
+        assert await collect(read_jpeg_frames(reader)) == [first, second]
+
+    async def test_truncated_frame_ends_stream(self):
+        jpeg = make_jpeg()
+        reader = feed(frame_packet(jpeg), frame_packet(jpeg)[:-10])
+        assert await collect(read_jpeg_frames(reader)) == [jpeg]
+
+    async def test_invalid_frames_rejected(self):
+        with pytest.raises(CameraError):
+            await collect(read_jpeg_frames(feed(frame_packet(b"not a jpeg"))))
+        with pytest.raises(CameraError):
+            await collect(read_jpeg_frames(feed(struct.pack("<IIII", 0, 0, 0, 0))))
+
+    def test_split_jpeg_stream(self):
+        first, second = make_jpeg(), make_jpeg(b"second")
+        frames, rest = split_jpeg_stream(b"junk" + first + second[:4])
+        assert frames == [first]
+        assert rest == second[:4]
+
+        frames, rest = split_jpeg_stream(rest + second[4:])
+        assert frames == [second]
+        assert rest == b""
+
+
+class TestSnapshot:
+    """Test snapshots and the first-frame wait."""
+
+    async def test_closed_stream_is_an_error(self):
+        with pytest.raises(CameraError, match="access code"):
+            await first_frame(read_jpeg_frames(feed()))
+
+    async def test_snapshot_is_cached(self, monkeypatch):
+        jpeg = make_jpeg()
+        opened = []
+
+        async def frames(ip_address, access_code):
+            opened.append(ip_address)
+            yield jpeg
+
+        monkeypatch.setattr(bambu_camera, "_tcp_frames", frames)
+        monkeypatch.setattr(bambu_camera, "_snapshot_cache", {})
+        assert await snapshot("192.168.1.50", "12345678", "P1S") == jpeg
+        assert await snapshot("192.168.1.50", "12345678", "P1S") == jpeg
+        assert opened == ["192.168.1.50"]
+
@@ -0,0 +1,163 @@
+"""Unit tests for the command line tool."""
+
+import csv
+import io
+from types import SimpleNamespace
+
+import cli
+import pytest
+from cli import (
+    CliError,
+    cmd_printers_add_manual,
+    cmd_printers_pair,
+    cmd_tags_convert,
+    parse_sse,
+    spools_from_csv,
+    wait_for_job,
+)
+
+
+class FakeApi:
+    """Records requests and answers them from a table of canned responses, given in turn."""
+
+    def __init__(self, responses: dict):
+        self.responses = responses
+        self.calls = []
+
+    def _answer(self, method, path, kwargs):
+        self.calls.append((method, path, kwargs.get("json")))
+        answer = self.responses.get((method, path))
+        return answer.pop(0) if answer else None
+
+    def get(self, path, **kwargs):
+        return self._answer("GET", path, kwargs)
+
+    def post(self, path, **kwargs):
+        return self._answer("POST", path, kwargs)
+
+
+def csv_rows(text: str):
+    return csv.DictReader(io.StringIO(text))
+
+
+class TestSpoolsFromCsv:
+    def test_valid_rows(self):
+        rows = csv_rows("material,brand,color_name,rgba,weight_current,extra\nPLA,Bambu,Black,000000,1200,x\n")
+        [(line, spool, error)] = list(spools_from_csv(rows))
+        assert (line, error) == (2, None)
+        assert spool.material == "PLA"
+        assert spool.weight_current == 1200
+        assert "extra" not in spool.model_dump()
+
+    def test_empty_cells_left_unset(self):
+        [(_, spool, _)] = list(spools_from_csv(csv_rows("material,brand,label_weight\nPETG,,\n")))
+        assert "brand" not in spool.model_fields_set
+        assert spool.label_weight == 1000
+
+    def test_invalid_row_reports_line(self):
+        rows = csv_rows("material,weight_current\nPLA,1000\n,500\nPLA,heavy\n")
+        results = list(spools_from_csv(rows))
+        assert [(line, spool is not None) for line, spool, _ in results] == [(2, True), (3, False), (4, False)]
+        assert "material" in results[1][2]
+        assert "weight_current" in results[2][2]
+
+    def test_color_stops_separated(self):
+        [(_, spool, error)] = list(spools_from_csv(csv_rows("material,color_stops\nPLA,FF0000FF|0000FFFF\n")))
+        assert error is None
+        assert len(spool.color_stops) == 2
+
+
+class TestParseSse:
+    def test_events_with_ids(self):
+        lines = ["retry: 3000", "", 'data: {"type": "initial_state"}', "", "id: 7", "data: {}", "", ": keepalive", ""]
+        assert list(parse_sse(lines)) == [(None, '{"type": "initial_state"}'), ("7", "{}")]
+
+    def test_multiline_data(self):
+        assert list(parse_sse(["id: 1", "data: a", "data: b", ""])) == [("1", "a\nb")]
+
+
+class TestWaitForJob:
+    def test_polls_until_completed(self, monkeypatch):
+        monkeypatch.setattr(cli, "JOB_POLL_SECONDS", 0)
+        api = FakeApi(
+            {
+                ("GET", "/jobs/j1"): [
+                    {"id": "j1", "kind": "backup", "status": "running"},
+                    {"id": "j1", "kind": "backup", "status": "completed", "result": {"file": "b.db"}},
+                ]
+            }
+        )
+        job = wait_for_job(api, {"id": "j1", "kind": "backup", "status": "queued"})
+        assert job["result"] == {"file": "b.db"}
+        assert len(api.calls) == 2
+
+    def test_failed_job_raises(self):
+        with pytest.raises(CliError, match="failed: disk full"):
+            wait_for_job(None, {"id": "j1", "kind": "backup", "status": "failed", "error": "disk full"})
+
+
+def pair_args(**kwargs):
+    args = {"serial": "01S00A", "access_code": "12345678", "ip": None, "name": None, "model": None, "timeout": 0}
+    return SimpleNamespace(**{**args, "no_auto_connect": False, **kwargs})
+
+
+class TestPairPrinter:
+    def test_pair_with_discovery(self, monkeypatch):
+        monkeypatch.setattr(cli.time, "sleep", lambda _: None)
+        found = {"serial": "01S00A", "name": "X1C", "ip_address": "192.168.1.20", "model": "X1C"}
+        api = FakeApi({("GET", "/discovery/printers"): [[found]]})
+        cmd_printers_pair(api, pair_args())
+        created = next(body for method, path, body in api.calls if path == "/printers")
+        assert created["ip_address"] == "192.168.1.20"
+        assert created["auto_connect"] is True
+        assert ("POST", "/printers/01S00A/connect", None) in api.calls
+        assert ("POST", "/discovery/stop", None) in api.calls
+
+    def test_pair_not_found(self, monkeypatch):
+        monkeypatch.setattr(cli.time, "sleep", lambda _: None)
+        api = FakeApi({("GET", "/discovery/printers"): [[]]})
+        with pytest.raises(CliError, match="not found"):
+            cmd_printers_pair(api, pair_args())
+
+    def test_add_manual(self):
+        api = FakeApi({})
+        cmd_printers_add_manual(api, SimpleNamespace(serial="mk3s", name="Prusa MK3S", model=None))
+        [(method, path, body)] = api.calls
+        assert (method, path) == ("POST", "/printers")
+        assert body["printer_type"] == "manual"
+        assert body["auto_connect"] is False
+
+
+CONVERTED = {
+    "source": {"tag_type": "Bambu Lab", "tag_uid": "75886B1D", "brand": "Bambu", "material": "PLA", "color_name": None},
+    "encoded": {"format": "OpenPrintTag"},
+    "tag_data_hex": "0303D00000FE",
+    "dropped": ["slicer_filament"],
+}
+
+
+class TestTagsConvert:
+    def test_sends_text_and_binary_dumps(self, tmp_path):
+        text, binary = tmp_path / "tag.nfc", tmp_path / "tag.bin"
+        text.write_text("UID: 75 88 6B 1D\n")
+        binary.write_bytes(b"\x75\x88\xff")
+        api = FakeApi({("POST", "/tags/convert"): [CONVERTED, CONVERTED]})
+        args = SimpleNamespace(format="OpenPrintTag", source_uid=None, uid="04112233445566", output=None, json=False)
+
+        cmd_tags_convert(api, SimpleNamespace(**vars(args), file=str(text)))
+        cmd_tags_convert(api, SimpleNamespace(**vars(args), file=str(binary)))
+        assert api.calls[0][2]["dump"] == "UID: 75 88 6B 1D\n"
+        assert api.calls[0][2]["target_uid"] == "04112233445566"
+        assert api.calls[1][2]["dump_base64"] == "dYj/"
+
+    def test_writes_tag_memory(self, tmp_path, capsys):
+        source, output = tmp_path / "tag.nfc", tmp_path / "sticker.bin"
+        source.write_text("UID: 75 88 6B 1D\n")
+        api = FakeApi({("POST", "/tags/convert"): [CONVERTED]})
+        args = {"format": "OpenPrintTag", "source_uid": None, "uid": None, "json": False}
+        cmd_tags_convert(api, SimpleNamespace(**args, file=str(source), output=str(output)))
+
+        assert output.read_bytes() == bytes.fromhex("0303D00000FE")
+        printed = capsys.readouterr().out
+        assert "Bambu Lab tag 75886B1D  Bambu PLA" in printed
+        assert "Not stored in OpenPrintTag: slicer_filament" in printed
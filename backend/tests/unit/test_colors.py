@@ -0,0 +1,50 @@
+"""Unit tests for color parsing, swatches and delta-E matching."""
+
+from services.colors import (
+    color_distance,
+    color_gradient,
+    color_swatch,
+    delta_e,
+    parse_color,
+    resolve_basic_color,
+)
+
+
+class TestColorParsing:
+    def test_parse_color(self):
+        assert parse_color("#f80") == (255, 136, 0, 255)
+        assert parse_color("FF8800") == (255, 136, 0, 255)
+        assert parse_color(" #ff880080 ") == (255, 136, 0, 128)
+        assert parse_color("red") is None
+        assert parse_color(None) is None
+
+    def test_swatch(self):
+        swatch = color_swatch("FFFFFF")
+        assert (swatch.hex, swatch.rgba_int, swatch.lightness) == ("#FFFFFF", 0xFFFFFFFF, 100.0)
+        assert swatch.text_color == "#000000"
+        assert color_swatch("#000080").text_color == "#FFFFFF"
+        assert color_swatch("A6A9AA").family == "silver"
+
+    def test_basic_names(self):
+        assert resolve_basic_color("Gray") == "808080FF"
+        assert resolve_basic_color("grey") == "808080FF"
+        assert resolve_basic_color("Jade White") is None
+
+    def test_gradient(self):
+        assert color_gradient(["FF0000FF", "0000FF80"]) == "linear-gradient(90deg, #FF0000FF 0%, #0000FF80 100%)"
+        assert color_gradient(["FF0000FF", "00FF00FF", "0000FFFF"]).endswith("#00FF00FF 50%, #0000FFFF 100%)")
+        assert color_gradient(["FF0000FF"]) is None
+        assert color_gradient(None) is None
+
+
+class TestDeltaE:
+    def test_ciede2000_reference_pairs(self):
+        """Reference values from Sharma, Wu & Dalal (2005)."""
+        assert round(delta_e((50, 2.6772, -79.7751), (50, 0, -82.7485)), 4) == 2.0425
+        assert round(delta_e((50, 2.5, 0), (73, 25, -18)), 4) == 27.1492
+
+    def test_color_distance(self):
+        assert color_distance("#FF0000", "FF0000FF") == 0
+        assert color_distance("#FF0000", "#EE1010") < 5
+        assert color_distance("#FF0000", "#0000FF") > 50
+        assert color_distance("#FF0000", None) is None
@@ -1,6 +1,7 @@
 """Unit tests for database operations."""
 
 import pytest
+from db.database import normalize_tag_id, tag_uid_candidates
 
 
 class TestSettingsDatabase:
@@ -221,3 +222,26 @@ class TestSetSpoolWeight:
         assert updated is not None
         assert updated.weight_current == 1500
         assert updated.weight_used == 0  # Clamped to zero
+
+
+class TestNormalizeTagId:
+    """Test tag UID normalization."""
+
+    def test_hex_with_separators(self):
+        """Test colon-separated hex is compacted and uppercased."""
+        assert normalize_tag_id("87:0d:51:00") == "870D5100"
+
+    def test_base64(self):
+        """Test base64 UIDs decode to the same hex form."""
+        assert normalize_tag_id("hw1RAA") == "870D5100"
+
+    def test_empty(self):
+        """Test empty tag IDs normalize to None."""
+        assert normalize_tag_id("") is None
+        assert normalize_tag_id(None) is None
+
+    def test_uid_candidates(self):
+        """Test lookups try the UID as given, then byte-reversed."""
+        assert tag_uid_candidates("87:0d:51:00") == ["870D5100", "00510D87"]
+        assert tag_uid_candidates("A0A0") == ["A0A0"]
+        assert tag_uid_candidates("") == []
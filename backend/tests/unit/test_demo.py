@@ -0,0 +1,74 @@
+"""Unit tests for demo mode seeding and the simulated printers."""
+
+import asyncio
+
+import pytest
+from models import SpeedLevel
+from mqtt.client import PrinterManager
+from services import demo
+from services.demo import DEMO_PRINTERS, DEMO_STORAGE, DemoPrinterConnection, seed_demo_data
+
+
+class TestSeedDemoData:
+    async def test_seeds_printers_spools_and_history(self, test_db):
+        assert await seed_demo_data(test_db)
+
+        printers = await test_db.get_printers()
+        assert {p.serial for p in printers} == {p.serial for p in DEMO_PRINTERS}
+        assert all(p.auto_connect for p in printers)
+
+        spools = await test_db.get_spools()
+        assert len(spools) == sum(len(p.slots) for p in DEMO_PRINTERS) + len(DEMO_STORAGE)
+
+        # Slots hold spools matching the simulated trays, used down to the tray's remain%
+        printer = DEMO_PRINTERS[0]
+        spool = await test_db.get_spool(await test_db.get_spool_for_slot(printer.serial, 0, 0))
+        assert spool.material == printer.slots[0].material
+        assert spool.weight_used == pytest.approx(1000 * (100 - printer.slots[0].remain) / 100, abs=1)
+        history = await test_db.get_usage_history(spool.id)
+        assert sum(h["weight_used"] for h in history) == pytest.approx(spool.weight_used, abs=1)
+
+    async def test_leaves_existing_data_alone(self, test_db, spool_factory):
+        await spool_factory()
+
+        assert not await seed_demo_data(test_db)
+        assert await test_db.get_printers() == []
+
+
+class TestDemoPrinterConnection:
+    @pytest.fixture(autouse=True)
+    def fast_script(self, monkeypatch):
+        monkeypatch.setattr(demo, "DEMO_TICK_SEC", 0.001)
+        monkeypatch.setattr(demo, "DEMO_IDLE_SEC", (0.01, 0.02))
+
+    async def test_plays_print_and_uses_filament(self):
+        printer = DEMO_PRINTERS[0]
+        manager = PrinterManager(DemoPrinterConnection)
+        seen = []
+        manager.set_state_callback(lambda serial, state, changes: seen.append(state.gcode_state))
+
+        await manager.connect(printer.serial, demo.DEMO_IP_ADDRESS, demo.DEMO_ACCESS_CODE)
+        try:
+            await asyncio.sleep(1)
+            assert manager.is_connected(printer.serial)
+            assert {"PREPARE", "RUNNING", "FINISH"} <= set(seen)
+
+            trays = manager.get_state(printer.serial).ams_units[0].trays
+            assert [t.tray_type for t in trays] == [f.material for f in printer.slots]
+            assert sum(t.remain for t in trays) < sum(f.remain for f in printer.slots)
+        finally:
+            await manager.disconnect_all()
+
+    async def test_commands_are_acknowledged(self):
+        printer = DEMO_PRINTERS[1]
+        manager = PrinterManager(DemoPrinterConnection)
+        manager.set_state_callback(lambda *args: None)
+
+        await manager.connect(printer.serial, demo.DEMO_IP_ADDRESS, demo.DEMO_ACCESS_CODE)
+        try:
+            assert manager.set_speed_level(printer.serial, SpeedLevel.SPORT)
+            await asyncio.sleep(0.01)
+            assert manager.get_last_command(printer.serial, "print_speed")["status"] == "success"
+            assert manager.get_state(printer.serial).speed_level == SpeedLevel.SPORT
+        finally:
+            await manager.disconnect_all()
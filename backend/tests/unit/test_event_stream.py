@@ -0,0 +1,62 @@
+"""Unit tests for the server-sent events stream."""
+
+from services.event_stream import EventStream
+
+
+class TestEventStream:
+    def test_publish_numbers_events(self):
+        stream = EventStream()
+        first = stream.publish({"type": "a"})
+        second = stream.publish({"type": "b"})
+
+        assert (first.id, second.id) == (1, 2)
+        assert second.encode() == 'id: 2\ndata: {"type": "b"}\n\n'
+
+    async def test_subscribers_receive_events(self):
+        stream = EventStream()
+        sub = stream.subscribe()
+        stream.publish({"type": "a"})
+
+        event = sub.queue.get_nowait()
+        assert event.data == '{"type": "a"}'
+
+        stream.unsubscribe(sub)
+        stream.publish({"type": "b"})
+        assert sub.queue.empty()
+
+    def test_replay_since(self):
+        stream = EventStream()
+        for i in range(5):
+            stream.publish({"n": i})
+
+        assert [e.id for e in stream.replay_since(3)] == [4, 5]
+        assert stream.replay_since(5) == []
+
+    def test_replay_unavailable(self):
+        stream = EventStream(buffer_size=3)
+        for i in range(5):
+            stream.publish({"n": i})
+
+        # Events 2 and 3 were evicted from the buffer
+        assert stream.replay_since(1) is None
+        assert [e.id for e in stream.replay_since(2)] == [3, 4, 5]
+        # ID from before a server restart
+        assert stream.replay_since(99) is None
+
+    async def test_slow_subscriber_is_dropped(self):
+        stream = EventStream()
+        sub = stream.subscribe()
+        for i in range(sub.queue.maxsize + 1):
+            stream.publish({"n": i})
+
+        assert sub.dropped
+        assert stream.subscriber_count == 0
+
+    async def test_close_wakes_subscribers(self):
+        stream = EventStream()
+        sub = stream.subscribe()
+        stream.close()
+
+        assert sub.dropped
+        assert sub.queue.get_nowait() is None
+        assert stream.subscriber_count == 0
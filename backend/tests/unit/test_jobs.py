@@ -0,0 +1,105 @@
+"""Unit tests for the background job runner."""
+
+import asyncio
+
+import pytest
+from pydantic import BaseModel, ValidationError
+from services.jobs import JobRunner, JobStatus, UnknownJobKind, job_kind
+
+
+class EchoParams(BaseModel):
+    value: int
+
+
+@job_kind("test.echo", EchoParams)
+async def echo_job(ctx, params: EchoParams):
+    await ctx.update(progress=1, total=1, message="echoing", force=True)
+    return {"value": params.value}
+
+
+@job_kind("test.fail")
+async def failing_job(ctx, params):
+    raise RuntimeError("boom")
+
+
+@job_kind("test.slow")
+async def slow_job(ctx, params):
+    await asyncio.sleep(60)
+
+
+class TestJobRunner:
+    async def test_job_completes(self, test_db):
+        runner = JobRunner()
+        updates = []
+
+        async def on_update(job):
+            updates.append((job.status, job.progress))
+
+        runner.set_update_callback(on_update)
+        job = await runner.start(test_db, "test.echo", {"value": 42})
+        assert job.status == JobStatus.QUEUED
+        await runner.wait(job.id)
+
+        stored = await test_db.get_job(job.id)
+        assert (stored["status"], stored["result"], stored["params"]) == ("completed", {"value": 42}, {"value": 42})
+        assert stored["started_at"] and stored["finished_at"]
+        assert updates[0] == (JobStatus.QUEUED, 0)
+        assert (JobStatus.RUNNING, 1) in updates
+        assert updates[-1][0] == JobStatus.COMPLETED
+        assert not runner.is_active(job.id)
+
+    async def test_job_failure(self, test_db):
+        runner = JobRunner()
+        job = await runner.start(test_db, "test.fail")
+        await runner.wait(job.id)
+
+        stored = await test_db.get_job(job.id)
+        assert (stored["status"], stored["error"]) == ("failed", "boom")
+
+    async def test_cancel(self, test_db):
+        runner = JobRunner()
+        job = await runner.start(test_db, "test.slow")
+        await asyncio.sleep(0)
+        assert runner.is_active(job.id)
+
+        assert await runner.cancel(job.id)
+        assert (await test_db.get_job(job.id))["status"] == "cancelled"
+        assert not await runner.cancel(job.id)
+
+    async def test_concurrency_limit(self, test_db):
+        runner = JobRunner(max_concurrent=1)
+        first = await runner.start(test_db, "test.slow")
+        second = await runner.start(test_db, "test.slow")
+        await asyncio.sleep(0.05)
+
+        assert (await test_db.get_job(first.id))["status"] == "running"
+        assert (await test_db.get_job(second.id))["status"] == "queued"
+        await runner.shutdown()
+        assert (await test_db.get_job(second.id))["status"] == "cancelled"
+
+    async def test_start_validation(self, test_db):
+        runner = JobRunner()
+        with pytest.raises(UnknownJobKind):
+            await runner.start(test_db, "test.nonexistent")
+        with pytest.raises(ValidationError):
+            await runner.start(test_db, "test.echo", {"value": "not a number"})
+        assert await test_db.get_jobs() == []
+
+
+class TestJobsDatabase:
+    async def test_fail_interrupted_jobs(self, test_db):
+        await test_db.create_job("a", "test.echo", {})
+        await test_db.create_job("b", "test.echo", {})
+        await test_db.update_job("b", status="completed", result=[1, 2])
+
+        assert await test_db.fail_interrupted_jobs() == 1
+        assert (await test_db.get_job("a"))["status"] == "failed"
+        assert (await test_db.get_job("b"))["result"] == [1, 2]
+
+    async def test_get_jobs_filters(self, test_db):
+        await test_db.create_job("a", "backup", {})
+        await test_db.create_job("b", "report", {"report": "usage"})
+        await test_db.update_job("b", status="running")
+
+        assert [j["id"] for j in await test_db.get_jobs(kind="report")] == ["b"]
+        assert [j["id"] for j in await test_db.get_jobs(status="queued")] == ["a"]
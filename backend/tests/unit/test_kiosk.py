@@ -0,0 +1,94 @@
+"""Unit tests for the check-in/check-out kiosk."""
+
+from models import KioskState
+from services.kiosk import Kiosk, KioskConfig
+
+CONFIG = KioskConfig(enabled=True, session_seconds=60)
+
+
+async def scan(kiosk, db, tag_id, weight=None, stable=True, now=0.0):
+    """Put a tag on the device, then take it off again."""
+    status, checkout = await kiosk.handle_tag(db, CONFIG, tag_id, weight, stable, now=now)
+    await kiosk.handle_tag(db, CONFIG, None, None, True, now=now)
+    return status, checkout
+
+
+class TestKiosk:
+    async def test_spool_needs_badge_first(self, test_db, spool_factory):
+        await spool_factory(tag_id="SPOOL==")
+        status, checkout = await scan(Kiosk(), test_db, "SPOOL==", 1200)
+
+        assert status.state == KioskState.SCAN_BADGE
+        assert status.message == "Scan your badge first"
+        assert checkout is None
+        assert await test_db.get_checkouts() == []
+
+    async def test_check_out_and_in_logs_member_usage(self, test_db, spool_factory):
+        spool = await spool_factory(tag_id="SPOOL==")
+        member = await test_db.create_member("Ada", badge_id="BADGE==")
+        kiosk = Kiosk()
+
+        status, _ = await scan(kiosk, test_db, "BADGE==")
+        assert status.state == KioskState.SCAN_SPOOL
+        assert status.member_name == "Ada"
+
+        status, checkout = await scan(kiosk, test_db, "SPOOL==", 1200)
+        assert status.state == KioskState.CHECKED_OUT
+        assert checkout["member_id"] == member["id"]
+        assert checkout["weight_out"] == 1200
+
+        status, checkout = await scan(kiosk, test_db, "SPOOL==", 1050.5)
+        assert status.state == KioskState.CHECKED_IN
+        assert status.weight_used == 149.5
+        assert checkout["checked_in_at"] is not None
+
+        [usage] = await test_db.get_usage_history(spool_id=spool.id)
+        assert usage["member_id"] == member["id"]
+        assert usage["weight_used"] == 149.5
+        assert (await test_db.get_spool(spool.id)).weight_current == 1050
+
+    async def test_tag_handled_once_while_on_scale(self, test_db, spool_factory):
+        await spool_factory(tag_id="SPOOL==")
+        await test_db.create_member("Ada", badge_id="BADGE==")
+        kiosk = Kiosk()
+        await scan(kiosk, test_db, "BADGE==")
+
+        _, checkout = await kiosk.handle_tag(test_db, CONFIG, "SPOOL==", 1200, True, now=1)
+        assert checkout is not None
+        _, checkout = await kiosk.handle_tag(test_db, CONFIG, "SPOOL==", 1190, True, now=2)
+        assert checkout is None
+        assert len(await test_db.get_checkouts(open_only=True)) == 1
+
+    async def test_waits_for_stable_weight(self, test_db, spool_factory):
+        await spool_factory(tag_id="SPOOL==")
+        await test_db.create_member("Ada", badge_id="BADGE==")
+        kiosk = Kiosk()
+        await scan(kiosk, test_db, "BADGE==")
+
+        status, checkout = await kiosk.handle_tag(test_db, CONFIG, "SPOOL==", 900, False, now=1)
+        assert status.message == "Weighing..."
+        assert checkout is None
+
+        _, checkout = await kiosk.handle_tag(test_db, CONFIG, "SPOOL==", 1200, True, now=2)
+        assert checkout["weight_out"] == 1200
+
+    async def test_session_expires_and_badge_signs_out(self, test_db):
+        await test_db.create_member("Ada", badge_id="BADGE==")
+        kiosk = Kiosk()
+
+        await scan(kiosk, test_db, "BADGE==", now=0)
+        assert kiosk.status(now=30).expires_in == 30
+        assert kiosk.status(now=61).state == KioskState.SCAN_BADGE
+
+        await scan(kiosk, test_db, "BADGE==", now=100)
+        status, _ = await scan(kiosk, test_db, "BADGE==", now=101)
+        assert status.state == KioskState.SCAN_BADGE
+        assert status.message == "Bye Ada"
+
+    async def test_inactive_badge_refused(self, test_db):
+        member = await test_db.create_member("Ada", badge_id="BADGE==")
+        await test_db.update_member(member["id"], active=False)
+
+        status, _ = await scan(Kiosk(), test_db, "BADGE==")
+        assert status.state == KioskState.SCAN_BADGE
+        assert status.message == "Badge is not active"
@@ -0,0 +1,79 @@
+"""Unit tests for the Moonraker agent's message handling."""
+
+import pytest
+from services.moonraker import (
+    ActiveSpoolEvent,
+    JobFinishedEvent,
+    filament_grams,
+    material_density,
+    parse_message,
+    websocket_url,
+)
+
+
+class TestWebsocketUrl:
+    def test_host_only(self):
+        assert websocket_url("voron.local") == "ws://voron.local:7125/websocket"
+
+    def test_base_url_with_port(self):
+        assert websocket_url("http://192.168.1.50:7125") == "ws://192.168.1.50:7125/websocket"
+
+    def test_https(self):
+        assert websocket_url("https://printer.example.com:443/") == "wss://printer.example.com:443/websocket"
+
+
+class TestParseMessage:
+    def test_active_spool_notification(self):
+        message = {"jsonrpc": "2.0", "method": "notify_active_spool_set", "params": [{"spool_id": 7}]}
+        assert parse_message(message) == ActiveSpoolEvent(spool_id=7)
+
+    def test_active_spool_cleared(self):
+        message = {"jsonrpc": "2.0", "method": "notify_active_spool_set", "params": [{"spool_id": None}]}
+        assert parse_message(message) == ActiveSpoolEvent(spool_id=None)
+
+    def test_initial_active_spool_result(self):
+        assert parse_message({"jsonrpc": "2.0", "result": {"spool_id": 3}, "id": 2}) == ActiveSpoolEvent(spool_id=3)
+
+    def test_history_finished(self):
+        message = {
+            "jsonrpc": "2.0",
+            "method": "notify_history_changed",
+            "params": [
+                {
+                    "action": "finished",
+                    "job": {
+                        "filename": "parts/benchy.gcode",
+                        "status": "completed",
+                        "filament_used": 1234.5,
+                        "metadata": {"filament_total": 2000, "filament_weight_total": 6},
+                    },
+                }
+            ],
+        }
+        event = parse_message(message)
+        assert isinstance(event, JobFinishedEvent)
+        assert event.print_name == "benchy"
+        assert event.status == "completed"
+        assert event.filament_used == 1234.5
+
+    def test_history_added_ignored(self):
+        message = {"method": "notify_history_changed", "params": [{"action": "added", "job": {}}]}
+        assert parse_message(message) is None
+
+    def test_other_notifications_ignored(self):
+        assert parse_message({"method": "notify_status_update", "params": [{}, 123.4]}) is None
+
+
+class TestFilamentGrams:
+    def test_uses_slicer_ratio(self):
+        metadata = {"filament_total": 2000, "filament_weight_total": 6}
+        assert filament_grams(1000, "PETG", metadata) == 3
+
+    def test_density_fallback(self):
+        # 1 m of 1.75 mm PLA is about 2.98 g
+        assert filament_grams(1000, "PLA") == pytest.approx(2.98, abs=0.01)
+
+    def test_material_density(self):
+        assert material_density("PETG-CF") == 1.27
+        assert material_density("pla silk") == 1.24
+        assert material_density(None) == 1.24
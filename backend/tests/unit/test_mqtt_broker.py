@@ -0,0 +1,158 @@
+"""Unit tests for the embedded MQTT broker."""
+
+import asyncio
+import struct
+
+from services.mqtt_broker import (
+    CONNACK_ACCEPTED,
+    CONNACK_BAD_CREDENTIALS,
+    MqttBroker,
+    encode_packet,
+    encode_string,
+    topic_matches,
+    valid_topic_filter,
+)
+
+
+class Client:
+    """Minimal MQTT client speaking raw packets."""
+
+    async def connect(self, port: int, client_id: str = "test", username=None, password=None, will=None) -> int:
+        self.reader, self.writer = await asyncio.open_connection("127.0.0.1", port)
+        flags = 0x02
+        payload = encode_string(client_id)
+        if will:
+            flags |= 0x04
+            payload += encode_string(will[0]) + encode_string(will[1])
+        if username is not None:
+            flags |= 0x80 | 0x40
+            payload += encode_string(username) + encode_string(password)
+        body = encode_string("MQTT") + bytes([4, flags]) + struct.pack("!H", 60) + payload
+        self.writer.write(encode_packet(1, 0, body))
+        packet_type, _, body = await self.read()
+        assert packet_type == 2
+        return body[1]
+
+    async def read(self) -> tuple[int, int, bytes]:
+        header = await asyncio.wait_for(self.reader.readexactly(2), 2)
+        return header[0] >> 4, header[0] & 0x0F, await self.reader.readexactly(header[1])
+
+    async def subscribe(self, *filters: str) -> bytes:
+        body = struct.pack("!H", 1) + b"".join(encode_string(f) + b"\x00" for f in filters)
+        self.writer.write(encode_packet(8, 2, body))
+        packet_type, _, body = await self.read()
+        assert packet_type == 9
+        return body[2:]
+
+    def publish(self, topic: str, payload: bytes, retain: bool = False):
+        self.writer.write(encode_packet(3, 1 if retain else 0, encode_string(topic) + payload))
+
+    async def message(self) -> tuple[str, bytes, bool]:
+        packet_type, flags, body = await self.read()
+        assert packet_type == 3
+        length = struct.unpack("!H", body[:2])[0]
+        return body[2 : 2 + length].decode(), body[2 + length :], bool(flags & 0x01)
+
+    def close(self):
+        self.writer.close()
+
+
+async def start_broker(**kwargs) -> MqttBroker:
+    broker = MqttBroker(host="127.0.0.1", port=0, **kwargs)
+    await broker.start()
+    return broker
+
+
+class TestTopics:
+    def test_topic_matches(self):
+        assert topic_matches("spoolbuddy/printer/+/state", "spoolbuddy/printer/01S/state")
+        assert topic_matches("spoolbuddy/#", "spoolbuddy/printer/01S/state")
+        assert topic_matches("spoolbuddy/#", "spoolbuddy")
+        assert not topic_matches("spoolbuddy/printer/+", "spoolbuddy/printer/01S/state")
+        assert not topic_matches("#", "$SYS/uptime")
+
+    def test_valid_topic_filter(self):
+        assert valid_topic_filter("a/+/b/#")
+        assert not valid_topic_filter("a/#/b")
+        assert not valid_topic_filter("a/b+")
+        assert not valid_topic_filter("")
+
+
+class TestMqttBroker:
+    async def test_publish_to_subscriber(self):
+        broker = await start_broker()
+        client = Client()
+        try:
+            assert await client.connect(broker.bound_port) == CONNACK_ACCEPTED
+            assert await client.subscribe("spoolbuddy/printer/+/state", "a/#/b") == bytes([0, 0x80])
+
+            broker.publish("spoolbuddy/printer/01S/state", '{"gcode_state": "RUNNING"}')
+            broker.publish("spoolbuddy/device/state", "{}")
+            broker.publish("spoolbuddy/printer/02S/state", "{}")
+
+            assert await client.message() == ("spoolbuddy/printer/01S/state", b'{"gcode_state": "RUNNING"}', False)
+            assert (await client.message())[0] == "spoolbuddy/printer/02S/state"
+        finally:
+            client.close()
+            await broker.stop()
+
+    async def test_retained_on_subscribe(self):
+        broker = await start_broker()
+        client = Client()
+        try:
+            broker.publish("spoolbuddy/status", "online", retain=True)
+            broker.publish("spoolbuddy/device/state", "{}", retain=True)
+            broker.publish("spoolbuddy/device/state", "", retain=True)  # Clears it
+
+            await client.connect(broker.bound_port)
+            await client.subscribe("spoolbuddy/#")
+            assert await client.message() == ("spoolbuddy/status", b"online", True)
+            broker.publish("spoolbuddy/status", "offline")
+            assert await client.message() == ("spoolbuddy/status", b"offline", False)
+        finally:
+            client.close()
+            await broker.stop()
+
+    async def test_client_publish(self):
+        """Test clients can publish to each other, but not on the server's topics."""
+        broker = await start_broker(read_only_prefix="spoolbuddy")
+        subscriber, publisher = Client(), Client()
+        try:
+            await subscriber.connect(broker.bound_port, "subscriber")
+            await subscriber.subscribe("#")
+            await publisher.connect(broker.bound_port, "publisher")
+            publisher.publish("spoolbuddy/device/state", b"spoofed")
+            publisher.publish("nodered/alert", b"low filament")
+
+            assert await subscriber.message() == ("nodered/alert", b"low filament", False)
+        finally:
+            subscriber.close()
+            publisher.close()
+            await broker.stop()
+
+    async def test_credentials(self):
+        broker = await start_broker(username="user", password="secret")
+        clients = [Client(), Client(), Client()]
+        try:
+            assert await clients[0].connect(broker.bound_port) == CONNACK_BAD_CREDENTIALS
+            port = broker.bound_port
+            assert await clients[1].connect(port, username="user", password="wrong") == CONNACK_BAD_CREDENTIALS
+            assert await clients[2].connect(port, username="user", password="secret") == CONNACK_ACCEPTED
+        finally:
+            for client in clients:
+                client.close()
+            await broker.stop()
+
+    async def test_will_on_lost_connection(self):
+        broker = await start_broker()
+        watcher, client = Client(), Client()
+        try:
+            await watcher.connect(broker.bound_port, "watcher")
+            await watcher.subscribe("clients/+/status")
+            await client.connect(broker.bound_port, "client", will=("clients/client/status", "gone"))
+            client.writer.transport.abort()
+
+            assert await watcher.message() == ("clients/client/status", b"gone", False)
+        finally:
+            watcher.close()
+            await broker.stop()
@@ -8,6 +8,9 @@ Tests cover:
 - Calibration profile handling
 - Command generation
 - Pending assignment lifecycle
+- State change publishing
+- Merging partial reports
+- Command acknowledgements
 """
 
 import json
@@ -15,7 +18,7 @@ import time
 from unittest.mock import AsyncMock, MagicMock, patch
 
 import pytest
-from models import AmsTray, AmsUnit, PrinterState
+from models import AmsTray, AmsUnit, PrintAction, PrinterState, SpeedLevel
 from mqtt.client import (
     DISCONNECT_GRACE_PERIOD_SEC,
     STAGE_NAMES,
@@ -23,7 +26,12 @@ from mqtt.client import (
     PendingAssignment,
     PrinterConnection,
     PrinterManager,
+    ams_modules,
+    diff_states,
     get_stage_name,
+    is_auth_failure,
+    parse_version_modules,
+    printer_versions,
 )
 
 
@@ -386,6 +394,41 @@ class TestDualNozzleSupport:
         assert conn._nozzle_diameters[0] == "0.4"
         assert conn._nozzle_diameters[1] == "0.6"
 
+    def test_parses_installed_nozzles(self):
+        """Test nozzle type and diameter from device.nozzle.info, reported once per change."""
+        conn = PrinterConnection(
+            serial="00M09A123456789",
+            ip_address="192.168.1.100",
+            access_code="12345678",
+        )
+        conn._state = PrinterState()
+        loop = MagicMock()
+        conn._loop = loop
+        conn._on_nozzle_change = MagicMock()
+
+        report = {
+            "print": {
+                "device": {
+                    "nozzle": {
+                        "info": [
+                            {"id": 0, "type": "HS01", "diameter": 0.4},
+                            {"id": 1, "type": "HH01", "diameter": "0.60"},
+                        ]
+                    }
+                }
+            }
+        }
+        conn._handle_message(report)
+        conn._handle_message(report)
+
+        assert [(n.extruder_id, n.diameter, n.nozzle_type) for n in conn._state.nozzles] == [
+            (0, "0.4", "HS01"),
+            (1, "0.6", "HH01"),
+        ]
+        assert conn.get_nozzle_diameter(1) == "0.6"
+        # Unchanged nozzles are not reported again
+        assert loop.call_soon_threadsafe.call_count == 1
+
 
 class TestCalibrationResponse:
     """Tests for handling calibration responses."""
@@ -528,6 +571,64 @@ class TestSetCalibrationCommand:
         assert data["print"]["slot_id"] == 2
 
 
+class TestPrintOptions:
+    """Tests for speed level, print error and skipped objects."""
+
+    def make_conn(self):
+        conn = PrinterConnection(serial="00M09A123456789", ip_address="192.168.1.100", access_code="12345678")
+        conn._connected = True
+        conn._client = MagicMock()
+        conn._client.publish.return_value = MagicMock(rc=0)
+        return conn
+
+    def test_parses_print_options(self):
+        """Test spd_lvl, print_error and s_obj are parsed into the state."""
+        conn = self.make_conn()
+
+        conn._handle_message({"print": {"spd_lvl": 2, "print_error": 50348044, "s_obj": [3, "7"]}})
+
+        assert conn._state.speed_level == SpeedLevel.STANDARD
+        assert conn._state.print_error_code == "0300-400C"
+        assert conn._state.skipped_objects == [3, 7]
+
+        conn._handle_message({"print": {"spd_lvl": 9, "print_error": 0, "s_obj": []}})
+        assert conn._state.speed_level is None
+        assert conn._state.print_error_code is None
+        assert conn._state.skipped_objects == []
+
+    def test_sends_print_speed(self):
+        """Test set_speed_level sends print_speed with the level as a string."""
+        conn = self.make_conn()
+
+        assert conn.set_speed_level(SpeedLevel.LUDICROUS) is True
+        data = json.loads(conn._client.publish.call_args[0][1])
+        assert (data["print"]["command"], data["print"]["param"]) == ("print_speed", "4")
+
+    def test_sends_skip_objects(self):
+        """Test skip_objects sends the object ids."""
+        conn = self.make_conn()
+
+        assert conn.skip_objects([3, 7]) is True
+        data = json.loads(conn._client.publish.call_args[0][1])
+        assert (data["print"]["command"], data["print"]["obj_list"]) == ("skip_objects", [3, 7])
+
+    def test_sends_print_control(self):
+        """Test pause, resume and stop are sent as print commands."""
+        conn = self.make_conn()
+
+        for action in PrintAction:
+            assert conn.control_print(action) is True
+            data = json.loads(conn._client.publish.call_args[0][1])
+            assert data["print"]["command"] == action.value
+
+    def test_not_connected(self):
+        """Test commands fail without a connection."""
+        conn = PrinterConnection(serial="00M09A123456789", ip_address="192.168.1.100", access_code="12345678")
+        assert conn.set_speed_level(SpeedLevel.SILENT) is False
+        assert conn.skip_objects([1]) is False
+        assert conn.control_print(PrintAction.PAUSE) is False
+
+
 class TestSetKValueCommand:
     """Tests for set_k_value command generation."""
 
@@ -724,6 +825,244 @@ class TestResetSlot:
         assert data["print"]["slot_id"] == 2
 
 
+class TestPartialReports:
+    """Tests for merging partial reports into the retained state."""
+
+    def _conn(self, sample_mqtt_report):
+        conn = PrinterConnection(
+            serial="00M09A123456789",
+            ip_address="192.168.1.100",
+            access_code="12345678",
+        )
+        conn._loop = None
+        conn._handle_message(sample_mqtt_report)
+        return conn
+
+    def test_report_without_ams_keeps_trays(self, sample_mqtt_report):
+        """Test a report that omits ams leaves the AMS state alone."""
+        conn = self._conn(sample_mqtt_report)
+
+        conn._handle_message({"print": {"mc_percent": 50}})
+
+        assert conn._state.print_progress == 50
+        assert len(conn._state.ams_units[0].trays) == 4
+        assert conn._state.ams_units[0].trays[0].tray_type == "PLA"
+
+    def test_partial_tray_update(self, sample_mqtt_report):
+        """Test fields missing from a tray report keep their values."""
+        conn = self._conn(sample_mqtt_report)
+
+        conn._handle_message({"print": {"ams": {"ams": [{"id": "0", "tray": [{"id": "0", "remain": 75}]}]}}})
+
+        unit = conn._state.ams_units[0]
+        assert unit.humidity == 42
+        assert unit.temperature == 25.5
+        assert len(unit.trays) == 4
+        assert unit.trays[0].remain == 75
+        assert unit.trays[0].tray_type == "PLA"
+        assert unit.trays[0].k_value == 0.025
+
+    def test_emptied_tray(self, sample_mqtt_report):
+        """Test a tray reported with only its ID is cleared."""
+        conn = self._conn(sample_mqtt_report)
+
+        conn._handle_message({"print": {"ams": {"ams": [{"id": "0", "tray": [{"id": "1"}]}]}}})
+
+        tray = conn._state.ams_units[0].trays[1]
+        assert tray.tray_type is None
+        assert tray.tray_color is None
+
+    def test_detached_unit_removed(self, sample_mqtt_report):
+        """Test units missing from ams_exist_bits are dropped."""
+        conn = self._conn(sample_mqtt_report)
+
+        conn._handle_message({"print": {"ams": {"ams": [], "ams_exist_bits": "0"}}})
+
+        assert conn._state.ams_units == []
+
+    def test_reading_bits_without_units(self):
+        """Test tray_reading_bits is picked up from reports without unit data."""
+        conn = PrinterConnection(
+            serial="00M09A123456789",
+            ip_address="192.168.1.100",
+            access_code="12345678",
+        )
+        conn._loop = None
+
+        conn._handle_message({"print": {"ams": {"tray_reading_bits": "4"}}})
+
+        assert conn._state.tray_reading_bits == 4
+
+
+class TestCommandTracking:
+    """Tests for sequence_id tracking of command acknowledgements."""
+
+    def _conn(self):
+        conn = PrinterConnection(
+            serial="00M09A123456789",
+            ip_address="192.168.1.100",
+            access_code="12345678",
+        )
+        conn._connected = True
+        conn._client = MagicMock()
+        conn._client.publish.return_value = MagicMock(rc=0)
+        return conn
+
+    def test_sequence_ids_increment(self):
+        """Test each command gets its own sequence_id."""
+        conn = self._conn()
+
+        conn.set_filament(ams_id=0, tray_id=0)
+        conn.reset_slot(ams_id=0, tray_id=1)
+
+        sent = [json.loads(call[0][1])["print"]["sequence_id"] for call in conn._client.publish.call_args_list]
+        assert sent == ["1", "2"]
+        assert conn.get_command("1")["command"] == "ams_filament_setting"
+        assert conn.get_command("2")["status"] == "pending"
+
+    def test_success_response(self):
+        """Test a success response completes the matching command."""
+        conn = self._conn()
+        conn.set_filament(ams_id=0, tray_id=0)
+
+        conn._handle_message({"print": {"command": "ams_filament_setting", "sequence_id": "1", "result": "success"}})
+
+        command = conn.get_last_command("ams_filament_setting")
+        assert command["status"] == "success"
+        assert command["completed_at"] is not None
+
+    def test_failure_response(self):
+        """Test a failed response records the printer's reason."""
+        conn = self._conn()
+        conn.set_filament(ams_id=0, tray_id=0)
+        results = []
+        conn._on_command_result = lambda serial, command: results.append(command)
+        conn._loop = MagicMock()
+        conn._loop.call_soon_threadsafe.side_effect = lambda cb: cb()
+
+        conn._handle_message(
+            {"print": {"command": "ams_filament_setting", "sequence_id": "1", "result": "fail", "reason": "busy"}}
+        )
+
+        assert conn.get_command("1")["status"] == "failed"
+        assert conn.get_command("1")["reason"] == "busy"
+        assert results[0]["sequence_id"] == "1"
+
+    def test_status_reports_ignored(self):
+        """Test status reports with the printer's own sequence numbers are not matched."""
+        conn = self._conn()
+        conn.set_filament(ams_id=0, tray_id=0)
+
+        conn._handle_message({"print": {"command": "push_status", "sequence_id": "1", "result": "fail"}})
+
+        assert conn.get_command("1")["status"] == "pending"
+
+    def test_timeout(self):
+        """Test unacknowledged commands time out."""
+        conn = self._conn()
+        conn.set_filament(ams_id=0, tray_id=0)
+        conn._commands["1"].sent_at -= 60
+
+        assert conn.get_command("1")["status"] == "timeout"
+
+    def test_publish_failure(self):
+        """Test a command that could not be published is failed immediately."""
+        conn = self._conn()
+        conn._client.publish.return_value = MagicMock(rc=4)
+
+        assert conn.set_filament(ams_id=0, tray_id=0) is False
+        assert conn.get_command("1")["status"] == "failed"
+
+
+class TestStatePublishing:
+    """Tests for publishing only changed printer state."""
+
+    def _conn(self):
+        conn = PrinterConnection(
+            serial="00M09A123456789",
+            ip_address="192.168.1.100",
+            access_code="12345678",
+        )
+        conn._on_state_update = MagicMock()
+        conn._loop = MagicMock()
+        conn._loop.call_soon_threadsafe.side_effect = lambda cb: cb()
+        return conn
+
+    def test_diff_states(self):
+        """Test diff lists changed top-level fields, or all fields without a previous state."""
+        old = PrinterState(gcode_state="RUNNING", print_progress=10)
+        new = old.model_copy(update={"print_progress": 11})
+
+        assert diff_states(old, new) == ["print_progress"]
+        assert diff_states(old, old.model_copy(deep=True)) == []
+        assert set(diff_states(None, new)) == set(PrinterState.model_fields)
+
+    def test_first_report_published_in_full(self):
+        """Test the first report is published with every field marked changed."""
+        conn = self._conn()
+
+        conn._handle_message({"print": {"gcode_state": "IDLE"}})
+
+        serial, state, changes = conn._on_state_update.call_args.args
+        assert serial == "00M09A123456789"
+        assert state.gcode_state == "IDLE"
+        assert "ams_units" in changes
+
+    def test_unchanged_report_not_published(self):
+        """Test repeated reports don't notify the listener again."""
+        conn = self._conn()
+
+        conn._handle_message({"print": {"gcode_state": "RUNNING", "mc_percent": 10}})
+        conn._handle_message({"print": {"gcode_state": "RUNNING", "mc_percent": 10}})
+        conn._handle_message({"print": {"mc_percent": 10}})
+        assert conn._on_state_update.call_count == 1
+
+        conn._handle_message({"print": {"mc_percent": 11}})
+        assert conn._on_state_update.call_count == 2
+        _, state, changes = conn._on_state_update.call_args.args
+        assert changes == ["print_progress"]
+        assert state.gcode_state == "RUNNING"
+
+    def test_progress_from_mc_percent(self):
+        """Test progress comes from mc_percent only, clamped and tolerant of strings."""
+        conn = self._conn()
+
+        conn._handle_message({"print": {"gcode_file_prepare_percent": "100", "mc_percent": "42"}})
+        assert conn._state.print_progress == 42
+        conn._handle_message({"print": {"mc_percent": 101}})
+        assert conn._state.print_progress == 100
+
+    def test_eta_follows_remaining_time(self):
+        """Test the ETA is set from mc_remaining_time and only moves when the estimate changes."""
+        conn = self._conn()
+
+        with patch("mqtt.client.time.time", return_value=1000):
+            conn._handle_message({"print": {"mc_remaining_time": 30}})
+        assert conn._state.print_eta == 1000 + 30 * 60
+
+        with patch("mqtt.client.time.time", return_value=1030):
+            conn._handle_message({"print": {"mc_remaining_time": 30}})
+        assert conn._state.print_eta == 1000 + 30 * 60
+
+        with patch("mqtt.client.time.time", return_value=1060):
+            conn._handle_message({"print": {"mc_remaining_time": 29}})
+        assert conn._state.print_eta == 1060 + 29 * 60
+
+        conn._handle_message({"print": {"mc_remaining_time": 0}})
+        assert conn._state.print_eta is None
+
+    def test_published_state_is_a_snapshot(self):
+        """Test listeners get a copy that later reports don't mutate."""
+        conn = self._conn()
+
+        conn._handle_message({"print": {"mc_percent": 10}})
+        published = conn._on_state_update.call_args.args[1]
+        conn._handle_message({"print": {"mc_percent": 50}})
+
+        assert published.print_progress == 10
+        assert conn._on_state_update.call_args.args[1].print_progress == 50
+
+
 class TestPrinterManager:
     """Tests for PrinterManager."""
 
@@ -754,6 +1093,119 @@ class TestPrinterManager:
         assert manager._on_connect == connect_cb
 
 
+class TestAuthFailure:
+    """Tests for connections refused because of the access code."""
+
+    def make_conn(self):
+        conn = PrinterConnection(serial="00M09A123456789", ip_address="192.168.1.100", access_code="12345678")
+        conn._loop = MagicMock()
+        conn._loop.is_running.return_value = True
+        conn._on_auth_failed = MagicMock()
+        return conn
+
+    def test_classifies_reason_codes(self):
+        """Test bad credentials and not authorized count, other failures don't."""
+        assert is_auth_failure(4) and is_auth_failure(5)
+        assert is_auth_failure(MagicMock(value=134))
+        assert not is_auth_failure(MagicMock(value=136))  # Server unavailable
+        assert not is_auth_failure(3)
+
+    def test_refused_code_stops_reconnecting(self):
+        """Test an auth failure stops the client and reports it."""
+        conn = self.make_conn()
+        client = MagicMock()
+
+        conn._on_connect(client, None, None, 134, None)
+
+        assert conn.auth_failed is True
+        client.disconnect.assert_called_once()
+        client.loop_stop.assert_called_once()
+        conn._loop.call_soon_threadsafe.assert_called_once_with(conn._on_auth_failed, conn.serial)
+
+    def test_other_failure_keeps_reconnecting(self):
+        """Test a printer that is merely unavailable is retried as before."""
+        conn = self.make_conn()
+        client = MagicMock()
+
+        conn._on_connect(client, None, None, 136, None)
+
+        assert conn.auth_failed is False
+        client.loop_stop.assert_not_called()
+
+    def test_manager_remembers_refused_code(self):
+        """Test the manager drops the connection and remembers the code until a connect succeeds."""
+        manager = PrinterManager()
+        callback = MagicMock()
+        manager.set_auth_failed_callback(callback)
+        manager._connections["S1"] = PrinterConnection(serial="S1", ip_address="1.2.3.4", access_code="12345678")
+
+        manager._handle_auth_failed("S1")
+
+        assert "S1" not in manager._connections
+        assert manager.access_code_refused("S1", "12345678")
+        assert not manager.access_code_refused("S1", "87654321")
+        callback.assert_called_once_with("S1")
+
+        manager._handle_connect("S1")
+        assert not manager.access_code_refused("S1", "12345678")
+
+
+class TestGetVersion:
+    """Tests for the get_version reply."""
+
+    REPLY = {
+        "info": {
+            "command": "get_version",
+            "sequence_id": "2",
+            "module": [
+                {"name": "ota", "sw_ver": "01.08.02.00", "hw_ver": "OTA", "sn": "00M09A123456789"},
+                {"name": "mc", "sw_ver": "00.00.31.21", "hw_ver": "MC07", "sn": ""},
+                {"name": "ams/0", "sw_ver": "00.00.06.49", "hw_ver": "AMS08", "sn": "006A12", "product_name": "AMS"},
+                {"sw_ver": "no name"},
+            ],
+        }
+    }
+
+    def test_parse_modules(self):
+        """Test modules are parsed, skipping empty fields and unnamed entries."""
+        modules = parse_version_modules(self.REPLY["info"])
+
+        assert [m.name for m in modules] == ["ota", "mc", "ams/0"]
+        assert modules[1].sn is None
+        assert modules[2].product_name == "AMS"
+        assert parse_version_modules({"command": "get_version"}) == []
+
+    def test_printer_versions(self):
+        """Test the firmware comes from the ota module and the hardware from the main controller."""
+        assert printer_versions(parse_version_modules(self.REPLY["info"])) == ("01.08.02.00", "MC07")
+        assert printer_versions([]) == (None, None)
+
+    def test_ams_modules(self):
+        """Test AMS units are found by module type and position, only with a serial number."""
+        modules = parse_version_modules(self.REPLY["info"])
+        modules += parse_version_modules({"module": [{"name": "n3s/128", "sn": "19F06A"}, {"name": "ams/1"}]})
+
+        units = ams_modules(modules)
+
+        assert sorted(units) == [0, 128]
+        assert units[0][0] == "ams"
+        assert units[128][1].sn == "19F06A"
+
+    def test_reply_notifies_listener(self):
+        """Test a get_version reply reaches the version callback."""
+        conn = PrinterConnection(serial="S1", ip_address="1.2.3.4", access_code="12345678")
+        conn._loop = MagicMock()
+        conn._on_version = MagicMock()
+
+        conn._handle_message(self.REPLY)
+
+        conn._loop.call_soon_threadsafe.assert_called_once()
+        conn._loop.call_soon_threadsafe.call_args[0][0]()
+        serial, modules = conn._on_version.call_args[0]
+        assert serial == "S1"
+        assert modules[0].sw_ver == "01.08.02.00"
+
+
 class TestSafeConversions:
     """Tests for _safe_int and _safe_float methods."""
 
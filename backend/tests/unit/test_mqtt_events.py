@@ -0,0 +1,41 @@
+"""Unit tests for the MQTT event topics."""
+
+import json
+
+from services.mqtt_events import event_messages
+
+
+def topics(message: dict) -> dict[str, tuple[str, bool]]:
+    return {m.topic: (m.payload, m.retain) for m in event_messages(message, "spoolbuddy")}
+
+
+class TestEventMessages:
+    def test_printer_state(self):
+        result = topics({"type": "printer_state", "serial": "01S", "state": {"gcode_state": "IDLE"}, "changes": []})
+
+        assert result["spoolbuddy/printer/01S/state"] == ('{"gcode_state": "IDLE"}', True)
+        payload, retain = result["spoolbuddy/events/printer_state"]
+        assert json.loads(payload)["serial"] == "01S"
+        assert not retain
+
+    def test_availability(self):
+        assert topics({"type": "printer_disconnected", "serial": "01S"})["spoolbuddy/printer/01S/availability"] == (
+            "offline",
+            True,
+        )
+        assert topics({"type": "device_connected"})["spoolbuddy/device/availability"] == ("online", True)
+
+    def test_spool_status_per_spool(self):
+        result = topics({"type": "spool_status", "spools": {"a": {"state": "loaded"}, "b": {"state": "storage"}}})
+
+        assert result["spoolbuddy/spool/a/status"] == ('{"state": "loaded"}', True)
+        assert result["spoolbuddy/spool/b/status"] == ('{"state": "storage"}', True)
+
+    def test_device_state(self):
+        result = topics({"type": "device_state", "weight": 1234.5, "stable": True, "tag_id": None})
+
+        assert json.loads(result["spoolbuddy/device/state"][0]) == {"weight": 1234.5, "stable": True, "tag_id": None}
+
+    def test_other_events(self):
+        assert list(topics({"type": "tag_removed"})) == ["spoolbuddy/events/tag_removed"]
+        assert event_messages({"no": "type"}, "spoolbuddy") == []
@@ -0,0 +1,115 @@
+"""Unit tests for built-in notifiers."""
+
+from unittest.mock import AsyncMock, MagicMock
+
+import pytest
+from services.notifiers import (
+    NotifierError,
+    NtfyNotifier,
+    TelegramNotifier,
+    create_notifier,
+    format_event,
+)
+
+
+def _client(status_code=200):
+    response = MagicMock()
+    response.status_code = status_code
+    response.text = "error body"
+    client = MagicMock()
+    client.post = AsyncMock(return_value=response)
+    return client
+
+
+class TestNotifiers:
+    """Test notifier payloads and error handling."""
+
+    async def test_ntfy_posts_to_topic(self):
+        """Test ntfy uses the configured server, topic and token."""
+        client = _client()
+        notifier = NtfyNotifier({"server": "https://ntfy.example/", "topic": "spools", "token": "tk"})
+
+        await notifier.send(client, "Title", "Body")
+
+        call = client.post.call_args
+        assert call.args[0] == "https://ntfy.example/spools"
+        assert call.kwargs["content"] == b"Body"
+        assert call.kwargs["headers"]["Title"] == "Title"
+        assert call.kwargs["headers"]["Authorization"] == "Bearer tk"
+
+    async def test_telegram_message(self):
+        """Test Telegram sends title and message to the chat."""
+        client = _client()
+        await TelegramNotifier({"bot_token": "123:abc", "chat_id": "42"}).send(client, "Title", "Body")
+
+        call = client.post.call_args
+        assert call.args[0] == "https://api.telegram.org/bot123:abc/sendMessage"
+        assert call.kwargs["json"] == {"chat_id": "42", "text": "Title\nBody"}
+
+    async def test_http_error_raises(self):
+        """Test non-2xx responses raise NotifierError."""
+        with pytest.raises(NotifierError):
+            await NtfyNotifier({"topic": "x"}).send(_client(500), "Title", "Body")
+
+    def test_create_notifier_validates_config(self):
+        """Test missing config keys are reported."""
+        with pytest.raises(ValueError, match="webhook_url"):
+            create_notifier("discord", {})
+
+    def test_format_spool_low(self):
+        """Test spool.low message formatting."""
+        title, message = format_event(
+            "spool.low", {"brand": "Bambu Lab", "material": "PLA", "color_name": "Red", "remaining_percent": 12.5}
+        )
+        assert title == "Spool running low"
+        assert message == "Bambu Lab PLA Red has 12.5% remaining"
+
+    def test_format_spool_depleting(self):
+        """Test spool.depleting message formatting."""
+        title, message = format_event(
+            "spool.depleting", {"material": "PLA", "color_name": "White", "days_remaining": 4.3}
+        )
+        assert title == "Spool running out"
+        assert message == "Your White PLA will run out in ~4 days at current usage"
+
+        _, message = format_event("spool.depleting", {"material": "PLA", "days_remaining": 0.4})
+        assert message == "Your PLA will run out within a day at current usage"
+
+    def test_format_spool_aging(self):
+        """Test spool.aging gives the months open and the shelf life."""
+        title, message = format_event(
+            "spool.aging", {"material": "PETG", "color_name": "Blue", "open_days": 400, "shelf_life_months": 12}
+        )
+        assert title == "Spool past its shelf life"
+        assert message == "Your Blue PETG has been open 13 months, 12 are recommended. Dry it before printing."
+
+    def test_format_filament_runout(self):
+        """Test filament.runout message formatting for runouts and jams."""
+        title, message = format_event(
+            "filament.runout",
+            {"kind": "runout", "serial": "S1", "ams_id": 0, "tray_id": 2, "material": "PLA", "print_name": "benchy"},
+        )
+        assert title == "Filament runout"
+        assert message == "PLA (AMS 0 tray 2) on S1 during 'benchy'"
+
+        title, message = format_event("filament.runout", {"kind": "jam", "serial": "S1", "ams_id": 255, "tray_id": 0})
+        assert title == "Filament jam"
+        assert message == "Filament (external spool) on S1"
+
+    def test_format_printer_auth_failed(self):
+        """Test printer.auth_failed names the printer, or its serial."""
+        title, message = format_event("printer.auth_failed", {"serial": "S1", "name": "X1C"})
+        assert title == "Printer needs a new access code"
+        assert message.startswith("X1C refused its access code.")
+
+        _, message = format_event("printer.auth_failed", {"serial": "S1", "name": None})
+        assert message.startswith("S1 refused")
+
+    def test_format_printer_firmware_changed(self):
+        """Test printer.firmware_changed gives both versions."""
+        title, message = format_event(
+            "printer.firmware_changed",
+            {"serial": "S1", "name": "X1C", "old_version": "01.07.00.00", "new_version": "01.08.02.00"},
+        )
+        assert title == "Printer firmware updated"
+        assert message == "X1C firmware changed from 01.07.00.00 to 01.08.02.00."
@@ -0,0 +1,108 @@
+"""Unit tests for print job file helpers and external spool usage tracking."""
+
+import io
+import zipfile
+from unittest.mock import MagicMock, patch
+
+from models import AmsTray, PrinterState
+from services.print_job import parse_sliced_weight, plate_number, print_file_paths
+from usage_tracker import UsageTracker
+
+SLICE_INFO = """<?xml version="1.0" encoding="UTF-8"?>
+<config>
+  <plate>
+    <metadata key="index" value="1"/>
+    <filament id="1" type="PLA" used_m="4.10" used_g="12.25"/>
+    <filament id="2" type="PETG" used_m="1.00" used_g="3.5"/>
+  </plate>
+  <plate>
+    <metadata key="index" value="2"/>
+    <filament id="1" type="PLA" used_m="9.00" used_g="27"/>
+  </plate>
+</config>
+"""
+
+
+def make_3mf(slice_info: str | None = SLICE_INFO) -> bytes:
+    buf = io.BytesIO()
+    with zipfile.ZipFile(buf, "w") as zf:
+        zf.writestr("3D/3dmodel.model", "")
+        if slice_info is not None:
+            zf.writestr("Metadata/slice_info.config", slice_info)
+    return buf.getvalue()
+
+
+class TestPrintJobFiles:
+    """Test 3MF path building and slice info parsing."""
+
+    def test_print_file_paths(self):
+        """Test the .gcode.3mf suffix is added once."""
+        assert print_file_paths("bracket")[0] == "/bracket.gcode.3mf"
+        assert print_file_paths("bracket.gcode.3mf")[1] == "/cache/bracket.gcode.3mf"
+
+    def test_plate_number(self):
+        """Test the plate comes from the gcode path, defaulting to 1."""
+        assert plate_number("/data/Metadata/plate_3.gcode") == 3
+        assert plate_number(None) == 1
+        assert plate_number("print.gcode") == 1
+
+    def test_parse_sliced_weight(self):
+        """Test filament weights are summed per plate."""
+        data = make_3mf()
+        assert parse_sliced_weight(data, 1) == 15.75
+        assert parse_sliced_weight(data, 2) == 27.0
+        assert parse_sliced_weight(data, 3) is None
+
+    def test_parse_sliced_weight_invalid(self):
+        """Test files without slice info yield None."""
+        assert parse_sliced_weight(make_3mf(slice_info=None)) is None
+        assert parse_sliced_weight(make_3mf(slice_info="<config")) is None
+        assert parse_sliced_weight(b"not a zip") is None
+
+
+class TestExternalSpoolUsage:
+    """Test usage tracking for prints fed from the external spool holder."""
+
+    def run_print(self, *states: PrinterState) -> list:
+        """Feed states to a tracker and return the usage callback calls."""
+        tracker = UsageTracker()
+        callback = MagicMock()
+        tracker.set_usage_callback(callback)
+        tracker.set_event_loop(MagicMock(call_soon_threadsafe=lambda fn: fn()))
+
+        prev = PrinterState(gcode_state="IDLE")
+        with patch("usage_tracker.asyncio.create_task"):
+            for state in states:
+                tracker.on_state_update("S1", state, prev)
+                prev = state
+        return [c.args for c in callback.call_args_list]
+
+    def test_external_holder_marked_for_estimate(self):
+        """Test tray_now 254 during a print reports the holder without a percentage."""
+        calls = self.run_print(
+            PrinterState(
+                gcode_state="RUNNING", subtask_name="bracket", gcode_file="Metadata/plate_2.gcode", tray_now=255
+            ),
+            PrinterState(gcode_state="RUNNING", subtask_name="bracket", tray_now=254),
+            PrinterState(gcode_state="FINISH", subtask_name="bracket", tray_now=255),
+        )
+
+        assert calls == [("S1", "bracket", {(255, 0): None}, "Metadata/plate_2.gcode")]
+
+    def test_external_remain_used_when_reported(self):
+        """Test a vt_tray remain reading takes precedence over the estimate."""
+        calls = self.run_print(
+            PrinterState(gcode_state="RUNNING", tray_now=254, vt_tray=AmsTray(ams_id=255, tray_id=0, remain=80)),
+            PrinterState(gcode_state="FINISH", tray_now=254, vt_tray=AmsTray(ams_id=255, tray_id=0, remain=75)),
+        )
+
+        assert calls[0][2] == {(255, 0): 5}
+
+    def test_ams_only_print(self):
+        """Test prints that never feed from the holder don't report it."""
+        calls = self.run_print(
+            PrinterState(gcode_state="RUNNING", tray_now=0),
+            PrinterState(gcode_state="FINISH", tray_now=255),
+        )
+
+        assert calls == []
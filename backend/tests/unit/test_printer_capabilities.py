@@ -0,0 +1,70 @@
+"""Unit tests for printer capability profiles and material compatibility."""
+
+from models import NozzleInfo, Printer, Spool
+from services.printer_capabilities import (
+    ISSUE_ABRASIVE,
+    ISSUE_ENCLOSURE,
+    ISSUE_NOZZLE_TEMP,
+    PrinterCapabilities,
+    base_material,
+    check_compatibility,
+    effective_capabilities,
+    is_abrasive,
+)
+
+
+def _spool(material: str, **kwargs) -> Spool:
+    return Spool(id="s1", material=material, **kwargs)
+
+
+class TestCapabilities:
+    def test_model_defaults(self):
+        caps = effective_capabilities(Printer(serial="S", model="X1C"))
+        assert caps == PrinterCapabilities(max_nozzle_temp=300, hardened_nozzle=True, enclosed=True)
+
+        caps = effective_capabilities(Printer(serial="S", model="Unknown"))
+        assert caps == PrinterCapabilities()
+
+    def test_reported_nozzle_overrides_model(self):
+        printer = Printer(serial="S", model="P1S")
+        nozzles = [NozzleInfo(diameter="0.4", nozzle_type="hardened_steel")]
+        assert effective_capabilities(printer, nozzles).hardened_nozzle is True
+
+        # Nozzle type not reported: keep the model default
+        assert effective_capabilities(printer, [NozzleInfo(diameter="0.4")]).hardened_nozzle is False
+
+    def test_printer_overrides_win(self):
+        printer = Printer(serial="S", model="P1S", hardened_nozzle=False, max_nozzle_temp=280)
+        nozzles = [NozzleInfo(diameter="0.4", nozzle_type="hardened_steel")]
+        caps = effective_capabilities(printer, nozzles)
+        assert (caps.hardened_nozzle, caps.max_nozzle_temp, caps.enclosed) == (False, 280, True)
+
+
+class TestCompatibility:
+    def test_base_material(self):
+        assert base_material("PA6-CF") == "PA"
+        assert base_material("petg") == "PETG"
+        assert base_material("PLA Silk") == "PLA"
+
+    def test_abrasive_detection(self):
+        assert is_abrasive(_spool("PLA-CF"))
+        assert is_abrasive(_spool("PLA", subtype="Glow"))
+        assert is_abrasive(_spool("PETG", slicer_filament_name="Bambu PETG-GF"))
+        assert not is_abrasive(_spool("PETG", subtype="Basic"))
+
+    def test_issues(self):
+        caps = PrinterCapabilities(max_nozzle_temp=250, hardened_nozzle=False, enclosed=False)
+        codes = [issue.code for issue in check_compatibility(caps, _spool("PA-CF"))]
+        assert codes == [ISSUE_ABRASIVE, ISSUE_NOZZLE_TEMP, ISSUE_ENCLOSURE]
+        assert check_compatibility(caps, _spool("PLA")) == []
+
+    def test_unknown_capabilities_are_not_checked(self):
+        assert check_compatibility(PrinterCapabilities(), _spool("PC-CF")) == []
+
+    def test_tuned_temperatures(self):
+        caps = PrinterCapabilities(max_nozzle_temp=280)
+        issues = check_compatibility(caps, _spool("PLA", nozzle_temp_min=290, nozzle_temp_max=300))
+        assert [(i.code, i.severity) for i in issues] == [(ISSUE_NOZZLE_TEMP, "error")]
+
+        issues = check_compatibility(caps, _spool("Unknown", nozzle_temp_min=220, nozzle_temp_max=290))
+        assert [(i.code, i.severity) for i in issues] == [(ISSUE_NOZZLE_TEMP, "warning")]
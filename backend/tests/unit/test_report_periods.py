@@ -0,0 +1,45 @@
+"""Unit tests for report calendar periods."""
+
+import pytest
+from services.report_periods import period_bounds, period_key
+
+BERLIN = "Europe/Berlin"
+
+
+class TestReportPeriods:
+    """Test local month/week keys and bounds."""
+
+    def test_month_in_local_time(self):
+        """Should put a timestamp in the month of its local date."""
+        assert period_key(1709247600, "UTC", "month") == "2024-02"  # 2024-02-29 23:00 UTC
+        assert period_key(1709247600, BERLIN, "month") == "2024-03"
+        assert period_key(1709247600, "America/Los_Angeles", "month") == "2024-02"
+
+    def test_week_start(self):
+        """Should key weeks by their first day, Monday or Sunday."""
+        sunday = 1711846800  # 2024-03-31 03:00 CEST
+        assert period_key(sunday, BERLIN, "week") == "2024-03-25"
+        assert period_key(sunday, BERLIN, "week", "sunday") == "2024-03-31"
+
+    def test_bounds_across_dst(self):
+        """Should end periods at local midnight, an hour short or long when DST changes."""
+        start, end = period_bounds("2024-03-25", BERLIN, "week")
+        assert end - start == 7 * 86400 - 3600
+        assert period_key(start, BERLIN, "week") == "2024-03-25"
+        assert period_key(end - 1, BERLIN, "week") == "2024-03-25"
+        assert period_key(end, BERLIN, "week") == "2024-04-01"
+
+        start, end = period_bounds("2024-10", BERLIN, "month")
+        assert end - start == 31 * 86400 + 3600
+
+        start, end = period_bounds("2024-12", "UTC", "month")
+        assert (start, end) == (1733011200, 1735689600)
+
+    def test_no_timestamp(self):
+        """Should leave rows without a timestamp ungrouped."""
+        assert period_key(None, BERLIN, "month") is None
+
+    def test_unsupported_period(self):
+        """Should reject unknown periods."""
+        with pytest.raises(ValueError):
+            period_key(0, "UTC", "day")
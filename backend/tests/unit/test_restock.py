@@ -0,0 +1,57 @@
+"""Unit tests for restock suggestions."""
+
+from models import Spool
+from services.restock import RestockLevels, suggest_restock
+
+
+def make_spool(**kwargs) -> Spool:
+    defaults = {"id": "s1", "material": "PLA", "label_weight": 1000, "weight_used": 0}
+    defaults.update(kwargs)
+    return Spool(**defaults)
+
+
+class TestSuggestRestock:
+    def test_below_level_suggested(self):
+        spools = [
+            make_spool(id="a", weight_used=600),
+            make_spool(id="b", material="pla", weight_used=900),
+            make_spool(id="c", material="PETG"),
+        ]
+        levels = RestockLevels(materials={"PLA": 2000, "PETG": 500})
+        suggestions = suggest_restock(spools, {"a": 150, "b": 150}, levels)
+
+        assert [s.material for s in suggestions] == ["PLA"]
+        pla = suggestions[0]
+        assert (pla.spool_count, pla.stock_grams, pla.shortfall_grams, pla.suggested_spools) == (2, 500, 1500, 2)
+        assert (pla.daily_usage, pla.days_remaining) == (10, 50)
+
+    def test_archived_spools_count_towards_usage_only(self):
+        spools = [make_spool(id="a"), make_spool(id="b", archived_at=1700000000, weight_used=1000)]
+        suggestions = suggest_restock(spools, {"b": 300}, RestockLevels(default=1500))
+
+        assert (suggestions[0].spool_count, suggestions[0].stock_grams, suggestions[0].daily_usage) == (1, 1000, 10)
+
+    def test_material_with_level_but_no_spools(self):
+        suggestions = suggest_restock([], {}, RestockLevels(materials={"ASA": 1000}))
+
+        assert [(s.material, s.stock_grams, s.suggested_spools) for s in suggestions] == [("ASA", 0, 1)]
+
+    def test_include_ok_and_unconfigured(self):
+        spools = [make_spool(id="a"), make_spool(id="b", material="TPU")]
+        levels = RestockLevels(materials={"PLA": 500})
+
+        assert suggest_restock(spools, {}, levels) == []
+        suggestions = suggest_restock(spools, {}, levels, include_ok=True)
+        assert {s.material: s.level_grams for s in suggestions} == {"PLA": 500, "TPU": None}
+
+    def test_last_purchase_unit_price(self):
+        purchase = {"id": 7, "supplier": "Shop", "url": None, "purchased_at": 1, "price": 45.0, "quantity": 2}
+        suggestions = suggest_restock([], {}, RestockLevels(materials={"PLA": 1000}), {"PLA": purchase})
+
+        assert suggestions[0].last_purchase.unit_price == 22.5
+
+    def test_most_urgent_first(self):
+        spools = [make_spool(id="a", weight_used=900), make_spool(id="b", material="PETG", weight_used=500)]
+        suggestions = suggest_restock(spools, {}, RestockLevels(default=1000))
+
+        assert [s.material for s in suggestions] == ["PLA", "PETG"]
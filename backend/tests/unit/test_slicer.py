@@ -0,0 +1,68 @@
+"""Unit tests for slicer preset mapping."""
+
+import pytest
+from services.slicer import (
+    check_filament_preset,
+    normalize_tray_color,
+    parse_slicer_filament,
+    spool_temp_range,
+    temp_range,
+)
+
+
+class TestSlicerPresets:
+    """Test slicer_filament parsing and material defaults."""
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            ("GFSL05_07", ("GFSL05", "GFL05")),
+            ("GFSA00", ("GFSA00", "GFA00")),
+            ("GFL99", ("GFSL99", "GFL99")),
+            ("PFUS9ac902733670a9", ("PFUS9ac902733670a9", None)),
+            ("Bambu PLA Basic", (None, None)),
+            ("", (None, None)),
+            (None, (None, None)),
+        ],
+    )
+    def test_parse_slicer_filament(self, value, expected):
+        """Test setting_id/filament_id resolution for each kind of value."""
+        assert parse_slicer_filament(value) == expected
+
+    def test_temp_range(self):
+        """Test material temperature ranges are case-insensitive with a default."""
+        assert temp_range("petg") == (220, 260)
+        assert temp_range("Unobtainium") == (190, 250)
+        assert temp_range(None) == (190, 250)
+
+    def test_spool_temp_range(self):
+        """Test a spool's tuned temperatures override the material's range."""
+        from models import Spool
+
+        assert spool_temp_range(Spool(id="s", material="PETG")) == (220, 260)
+        assert spool_temp_range(Spool(id="s", material="PETG", nozzle_temp_min=235)) == (235, 260)
+        assert spool_temp_range(Spool(id="s", material="PLA", nozzle_temp_min=240)) == (240, 240)
+
+    @pytest.mark.parametrize(
+        "value,expected",
+        [
+            ("#ff8800", "FF8800FF"),
+            ("FF880080", "FF880080"),
+            (" 00ff00 ", "00FF00FF"),
+            ("red", None),
+            ("FF88", None),
+            (None, None),
+        ],
+    )
+    def test_normalize_tray_color(self, value, expected):
+        """Test colors are normalized to uppercase RGBA hex."""
+        assert normalize_tray_color(value) == expected
+
+    def test_check_filament_preset(self):
+        """Test preset IDs are validated against the catalog."""
+        assert check_filament_preset("GFA00", "GFSA00_02", "PLA") is None
+        assert check_filament_preset("P4d64437", "PFUS9ac902733670a9", "PETG") is None
+        assert check_filament_preset("", "", "") is None
+        assert "PLA, not PETG" in check_filament_preset("GFA00", "", "PETG")
+        assert "Invalid filament preset ID" in check_filament_preset("PLA Basic", "", "PLA")
+        assert "Invalid setting ID" in check_filament_preset("GFA00", "Bambu PLA Basic", "PLA")
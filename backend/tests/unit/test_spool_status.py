@@ -0,0 +1,71 @@
+"""Unit tests for live spool status tracking."""
+
+from unittest.mock import AsyncMock, MagicMock
+
+from models import AmsTray, AmsUnit, PrinterState, SpoolPresence
+from services.spool_status import SpoolStatusTracker, active_slots
+
+
+def _db(assignments: list[tuple]) -> MagicMock:
+    """Database mock with (spool_id, printer_serial, ams_id, tray_id) slot assignments."""
+    db = MagicMock()
+    db.get_all_slot_assignments = AsyncMock(
+        return_value=[
+            {"spool_id": spool_id, "printer_serial": serial, "ams_id": ams_id, "tray_id": tray_id}
+            for spool_id, serial, ams_id, tray_id in assignments
+        ]
+    )
+    return db
+
+
+def _state(tray_types: list[str | None], tray_now: int | None = None) -> PrinterState:
+    trays = [AmsTray(ams_id=0, tray_id=i, tray_type=tray_type) for i, tray_type in enumerate(tray_types)]
+    return PrinterState(ams_units=[AmsUnit(id=0, trays=trays)], tray_now=tray_now)
+
+
+class TestSpoolStatusTracker:
+    async def test_loaded_spools(self):
+        tracker = SpoolStatusTracker()
+        tracker.set_state_source(lambda serial: _state(["PLA", None], tray_now=0) if serial == "P1" else None)
+        db = _db([("a", "P1", 0, 0), ("b", "P1", 0, 1), ("c", "KLIPPER", 0, 0)])
+
+        statuses = await tracker.current(db)
+
+        assert statuses["a"].status == SpoolPresence.LOADED
+        assert (statuses["a"].printer_serial, statuses["a"].tray_id, statuses["a"].active) == ("P1", 0, True)
+        # Slot reported empty: the spool was taken out
+        assert "b" not in statuses
+        # Printer without live state keeps its assignment
+        assert statuses["c"].status == SpoolPresence.LOADED
+        assert tracker.status_of("b", statuses).status == SpoolPresence.STORAGE
+
+    async def test_on_scale_overrides_assignment(self):
+        tracker = SpoolStatusTracker()
+        tracker.set_on_scale("display", "a")
+
+        statuses = await tracker.current(_db([("a", "P1", 0, 0)]))
+
+        assert statuses["a"].status == SpoolPresence.ON_SCALE
+        assert statuses["a"].device == "display"
+
+    async def test_changes_are_published_once(self):
+        tracker = SpoolStatusTracker()
+        db = _db([])
+
+        tracker.set_on_scale("display", "a")
+        changed = await tracker.changes(db)
+        assert changed["a"].status == SpoolPresence.ON_SCALE
+        assert await tracker.changes(db) == {}
+
+        tracker.set_on_scale("display", None)
+        changed = await tracker.changes(db)
+        assert changed["a"].status == SpoolPresence.STORAGE
+        assert changed["a"].since is not None
+        assert await tracker.changes(db) == {}
+
+    def test_active_slots(self):
+        assert active_slots(_state(["PLA"], tray_now=6)) == {None: (1, 2)}
+        assert active_slots(_state(["PLA"], tray_now=255)) == {}
+
+        state = PrinterState(nozzle_count=2, tray_now_right=0, tray_now_left=0xFE00)
+        assert active_slots(state) == {0: (0, 0), 1: (254, 0)}
@@ -0,0 +1,83 @@
+"""Unit tests for printer state broadcast coalescing."""
+
+import asyncio
+
+from services.state_coalescer import PrinterStateCoalescer, merge_state_messages
+
+
+def state(serial: str, changes: list[str], progress: int = 0) -> dict:
+    return {"type": "printer_state", "serial": serial, "state": {"print_progress": progress}, "changes": changes}
+
+
+class TestPrinterStateCoalescer:
+    async def test_first_report_is_sent_at_once(self):
+        sent = []
+        coalescer = PrinterStateCoalescer(500, lambda m: _append(sent, m))
+
+        await coalescer.submit(state("01S", ["print_progress"], 1))
+        assert len(sent) == 1
+
+    async def test_reports_within_interval_are_coalesced(self):
+        sent = []
+        coalescer = PrinterStateCoalescer(50, lambda m: _append(sent, m))
+
+        await coalescer.submit(state("01S", ["print_progress"], 1))
+        await coalescer.submit(state("01S", ["layer_num"], 2))
+        await coalescer.submit(state("01S", ["print_progress"], 3))
+        assert len(sent) == 1
+
+        await asyncio.sleep(0.1)
+        assert len(sent) == 2
+        # Latest state, with everything that changed since the last broadcast
+        assert sent[1]["state"]["print_progress"] == 3
+        assert sent[1]["changes"] == ["layer_num", "print_progress"]
+
+    async def test_state_change_is_sent_immediately(self):
+        sent = []
+        coalescer = PrinterStateCoalescer(1000, lambda m: _append(sent, m))
+
+        await coalescer.submit(state("01S", ["print_progress"], 99))
+        await coalescer.submit(state("01S", ["print_progress"], 100))
+        await coalescer.submit(state("01S", ["gcode_state"], 100))
+
+        assert len(sent) == 2
+        assert sent[1]["changes"] == ["print_progress", "gcode_state"]
+        await asyncio.sleep(0)  # The held report was merged in, nothing else goes out
+        assert len(sent) == 2
+
+    async def test_printers_are_limited_separately(self):
+        sent = []
+        coalescer = PrinterStateCoalescer(1000, lambda m: _append(sent, m))
+
+        await coalescer.submit(state("01S", ["print_progress"]))
+        await coalescer.submit(state("02S", ["print_progress"]))
+        assert [m["serial"] for m in sent] == ["01S", "02S"]
+
+    async def test_zero_interval_sends_every_report(self):
+        sent = []
+        coalescer = PrinterStateCoalescer(0, lambda m: _append(sent, m))
+
+        for i in range(3):
+            await coalescer.submit(state("01S", ["print_progress"], i))
+        assert len(sent) == 3
+
+    async def test_forget_drops_held_report(self):
+        sent = []
+        coalescer = PrinterStateCoalescer(50, lambda m: _append(sent, m))
+
+        await coalescer.submit(state("01S", ["print_progress"], 1))
+        await coalescer.submit(state("01S", ["print_progress"], 2))
+        coalescer.forget("01S")
+
+        await asyncio.sleep(0.1)
+        assert len(sent) == 1
+
+
+def test_merge_state_messages():
+    merged = merge_state_messages(state("01S", ["a", "b"], 1), state("01S", ["b", "c"], 2))
+    assert merged["changes"] == ["a", "b", "c"]
+    assert merged["state"]["print_progress"] == 2
+
+
+async def _append(sent: list, message: dict):
+    sent.append(message)
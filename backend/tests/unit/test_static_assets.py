@@ -0,0 +1,96 @@
+"""Unit tests for serving the web UI from an in-memory bundle."""
+
+import gzip
+
+import pytest
+from services.static_assets import IMMUTABLE_CACHE, REVALIDATE_CACHE, EmbeddedStaticFiles, build_bundle
+
+INDEX = b"<!doctype html><html><body><div id=app></div></body></html>"
+SCRIPT = b"console.log('spoolbuddy');\n" * 100
+
+
+@pytest.fixture
+def static_app(tmp_path):
+    dist = tmp_path / "dist"
+    (dist / "assets").mkdir(parents=True)
+    (dist / "index.html").write_bytes(INDEX)
+    (dist / "assets" / "index-1a2b3c.js").write_bytes(SCRIPT)
+    (dist / "favicon.png").write_bytes(b"\x89PNG")
+    bundle = tmp_path / "bundle.zip"
+    assert build_bundle(dist, bundle) == 3
+    return EmbeddedStaticFiles(bundle)
+
+
+async def _get(app, path: str, method: str = "GET", headers: dict | None = None):
+    scope = {
+        "type": "http",
+        "method": method,
+        "path": path,
+        "headers": [(k.encode(), v.encode()) for k, v in (headers or {}).items()],
+    }
+    sent = []
+
+    async def send(message):
+        sent.append(message)
+
+    await app(scope, None, send)
+    response_headers = {k.decode(): v.decode() for k, v in sent[0]["headers"]}
+    return sent[0]["status"], response_headers, sent[1]["body"]
+
+
+class TestLookup:
+    def test_files_and_client_routes(self, static_app):
+        index = static_app.assets["index.html"]
+        assert static_app.lookup("/") is index
+        assert static_app.lookup("/spools/42") is index
+        assert static_app.lookup("/assets/index-1a2b3c.js") is static_app.assets["assets/index-1a2b3c.js"]
+
+    def test_missing_files_and_api_paths_not_found(self, static_app):
+        assert static_app.lookup("/assets/missing.js") is None
+        assert static_app.lookup("/api/unknown") is None
+        assert static_app.lookup("/ws/unknown") is None
+
+    def test_bundle_without_index_rejected(self, tmp_path):
+        (tmp_path / "dist").mkdir()
+        with pytest.raises(FileNotFoundError):
+            build_bundle(tmp_path / "dist", tmp_path / "bundle.zip")
+
+
+class TestServing:
+    async def test_index_revalidated(self, static_app):
+        status, headers, body = await _get(static_app, "/")
+        assert (status, body) == (200, INDEX)
+        assert headers["cache-control"] == REVALIDATE_CACHE
+        assert headers["content-type"] == "text/html; charset=utf-8"
+        assert "content-encoding" not in headers  # Too small to compress
+
+    async def test_hashed_asset_gzipped_and_immutable(self, static_app):
+        status, headers, body = await _get(
+            static_app, "/assets/index-1a2b3c.js", headers={"Accept-Encoding": "gzip, br"}
+        )
+        assert status == 200
+        assert headers["cache-control"] == IMMUTABLE_CACHE
+        assert headers["content-encoding"] == "gzip"
+        assert headers["vary"] == "Accept-Encoding"
+        assert gzip.decompress(body) == SCRIPT
+        assert headers["content-length"] == str(len(body))
+
+    async def test_uncompressed_without_accept_encoding(self, static_app):
+        _, headers, body = await _get(static_app, "/assets/index-1a2b3c.js")
+        assert body == SCRIPT
+        assert "content-encoding" not in headers
+
+    async def test_not_modified(self, static_app):
+        _, headers, _ = await _get(static_app, "/")
+        status, _, body = await _get(static_app, "/", headers={"If-None-Match": headers["etag"]})
+        assert (status, body) == (304, b"")
+
+    async def test_head(self, static_app):
+        status, headers, body = await _get(static_app, "/", method="HEAD")
+        assert (status, body) == (200, b"")
+        assert headers["content-length"] == str(len(INDEX))
+
+    async def test_not_found_and_method_not_allowed(self, static_app):
+        assert (await _get(static_app, "/assets/missing.js"))[0] == 404
+        status, headers, _ = await _get(static_app, "/", method="POST")
+        assert (status, headers["allow"]) == (405, "GET, HEAD")
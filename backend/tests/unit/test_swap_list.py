@@ -0,0 +1,73 @@
+"""Unit tests for swap list rendering."""
+
+from models import Spool
+from services.swap_list import render_html, render_markdown, swap_list_items
+
+
+def make_spool(**kwargs) -> Spool:
+    defaults = {
+        "id": "s1",
+        "spool_number": 1,
+        "material": "PLA",
+        "brand": "Bambu Lab",
+        "color_name": "Black",
+        "rgba": "#000000FF",
+        "label_weight": 1000,
+        "weight_used": 250,
+        "swap_available": True,
+    }
+    defaults.update(kwargs)
+    return Spool(**defaults)
+
+
+class TestSwapListItems:
+    def test_only_active_swap_spools(self):
+        spools = [
+            make_spool(id="a", spool_number=1),
+            make_spool(id="b", spool_number=2, swap_available=False),
+            make_spool(id="c", spool_number=3, archived_at=1700000000),
+        ]
+        assert [i.spool_number for i in swap_list_items(spools)] == [1]
+
+    def test_remaining_weight_and_order(self):
+        spools = [
+            make_spool(id="a", spool_number=1, material="TPU"),
+            make_spool(id="b", spool_number=2, consumed_since_weight=100.4),
+            make_spool(id="c", spool_number=3, label_weight=None),
+        ]
+        items = swap_list_items(spools)
+        assert [i.spool_number for i in items] == [2, 3, 1]
+        assert items[0].remaining_grams == 650
+        assert items[1].remaining_grams is None
+
+    def test_non_web_photo_urls_dropped(self):
+        items = swap_list_items(
+            [
+                make_spool(id="a", photo_url="https://example.com/a.jpg"),
+                make_spool(id="b", photo_url="javascript:alert(1)"),
+            ]
+        )
+        assert [i.photo_url for i in items] == ["https://example.com/a.jpg", None]
+
+
+class TestSwapListRendering:
+    def test_markdown(self):
+        items = swap_list_items([make_spool(photo_url="https://example.com/a.jpg")])
+        text = render_markdown(items, "Swap")
+        assert text.startswith("# Swap\n")
+        assert "| 1 | Bambu Lab PLA Black | ~750g of 1000g | ![photo](https://example.com/a.jpg) |" in text
+
+    def test_markdown_empty(self):
+        assert "Nothing up for swap" in render_markdown([], "Swap")
+
+    def test_html_escapes_fields(self):
+        items = swap_list_items([make_spool(color_name="<b>Red</b>", rgba="red;}")])
+        page = render_html(items, "Swap & Sell")
+        assert "<title>Swap &amp; Sell</title>" in page
+        assert "&lt;b&gt;Red&lt;/b&gt;" in page
+        assert "background:transparent" in page
+        assert "red;}" not in page
+
+    def test_html_swatch(self):
+        page = render_html(swap_list_items([make_spool(rgba="FF8000FF")]), "Swap")
+        assert "background:#FF8000" in page
@@ -0,0 +1,95 @@
+"""Unit tests for the batch tag encoding station."""
+
+import asyncio
+
+import pytest
+from models import EncodeResult
+from services.tag_batch import BatchSpool, EncodingStation, StationBusy, TagRefused
+
+SPOOLS = [BatchSpool("s1", "Red PETG"), BatchSpool("s2", "Blue PLA")]
+
+
+async def prepare(spool_id, tag_id):
+    if tag_id == "USED":
+        raise TagRefused("Tag belongs to spool #7")
+    return f"{spool_id}:{tag_id}".encode()
+
+
+def result(tag_uid="04:A1", index=1, outcome="verified"):
+    return EncodeResult(job_id="job", index=index, tag_uid=tag_uid, outcome=outcome, pages_written=4)
+
+
+class TestEncodingStation:
+    async def test_idle_without_batch(self):
+        station = EncodingStation()
+        assert await station.task_for("04:A1") is None
+        assert not await station.report(result())
+
+    async def test_prompts_then_builds_data_for_tag(self):
+        station = EncodingStation()
+        station.start("job", SPOOLS, prepare)
+        station.select(0)
+
+        task = await station.task_for(None)
+        assert (task.index, task.total, task.label) == (1, 2, "Red PETG")
+        assert task.message == "Place tag 1 of 2 for Red PETG"
+        assert task.data is None
+
+        task = await station.task_for("04:A1")
+        assert task.tag_uid == "04:A1"
+        assert bytes.fromhex(task.data) == b"s1:04:A1"
+
+        task = await station.task_for("USED")
+        assert task.data is None
+        assert task.message == "Tag belongs to spool #7. Place another tag"
+
+    async def test_written_tag_is_not_reused(self):
+        station = EncodingStation()
+        station.start("job", SPOOLS, prepare)
+        station.select(0)
+        await station.task_for("04:A1")
+        station.mark_written("04:A1")
+        station.select(1, "Tag 1 done")
+
+        task = await station.task_for("04:A1")
+        assert task.index == 2
+        assert task.data is None
+        assert task.message == "Remove the tag. Tag 1 done. Place tag 2 of 2 for Blue PLA"
+
+    async def test_report_waits_for_job(self):
+        station = EncodingStation()
+        station.start("job", SPOOLS, prepare)
+        station.select(0)
+        await station.task_for("04:A1")
+
+        # Not the current spool, or a tag no data was built for
+        assert not await station.report(result(index=2))
+        assert not await station.report(result(tag_uid="04:B2"))
+
+        reported = asyncio.create_task(station.report(result()))
+        received, handled = await station.next_result()
+        assert received.outcome == "verified"
+        assert not reported.done()
+        handled.set_result(None)
+        assert await reported
+
+    async def test_stop_releases_reports(self):
+        station = EncodingStation()
+        station.start("job", SPOOLS, prepare)
+        station.select(0)
+        await station.task_for("04:A1")
+
+        reported = asyncio.create_task(station.report(result()))
+        await asyncio.sleep(0)
+        station.stop("job")
+        assert await reported
+        assert station.job_id is None
+
+    async def test_one_batch_at_a_time(self):
+        station = EncodingStation()
+        station.start("job", SPOOLS, prepare)
+        with pytest.raises(StationBusy):
+            station.start("other", SPOOLS, prepare)
+
+        station.stop("other")
+        assert station.job_id == "job"
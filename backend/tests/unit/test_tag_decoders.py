@@ -5,6 +5,7 @@ import struct
 
 import pytest
 from tags import (
+    OpenPrintTagData,
     OpenPrintTagDecoder,
     OpenSpoolDecoder,
     OpenTag3DDecoder,
@@ -14,6 +15,16 @@ from tags import (
     TagType,
 )
 from tags.bambulab import BambuLabDecoder
+from tags.dump import (
+    NTAG_NDEF_START_PAGE,
+    ndef_message_from_pages,
+    ndef_record,
+    ndef_tlv,
+    parse_dump_bytes,
+    parse_dump_text,
+    parse_ndef_message,
+)
+from tags.models import NfcTagType
 
 
 class TestSpoolEaseDecoder:
@@ -342,6 +353,34 @@ class TestOpenTag3DDecoder:
         assert decoded.bed_temp_c == original.bed_temp_c
 
 
+class TestOpenPrintTagDecoder:
+    """Tests for OpenPrintTag CBOR encoding."""
+
+    def test_encode_decode_roundtrip(self):
+        """Test encoded data decodes back to the same values."""
+        data = OpenPrintTagData(
+            tag_id="BBEiM0RVZg",
+            material_type="PETG",
+            material_name="PETG Red",
+            brand_name="Prusament",
+            primary_color="FF0000FF",
+            nominal_weight=1000,
+            empty_weight=200,
+        )
+        payload = OpenPrintTagDecoder.encode(data)
+        decoded = OpenPrintTagDecoder.decode("04112233445566", payload)
+
+        assert decoded == data
+        spool = OpenPrintTagDecoder.to_spool(decoded)
+        assert spool.color_name == "Red"
+
+    def test_encode_material_variant(self):
+        """Test materials outside the enum are stored as their base material."""
+        payload = OpenPrintTagDecoder.encode(OpenPrintTagData(tag_id="AQID", material_type="PLA-CF"))
+        decoded = OpenPrintTagDecoder.decode("010203", payload)
+        assert decoded.material_type == "PLA"
+
+
 class TestBambuLabDecoder:
     """Tests for Bambu Lab MIFARE decoder."""
 
@@ -521,3 +560,83 @@ class TestTagDecoder:
 
         assert result.tag_type == TagType.UNKNOWN
         assert TagDecoder.to_spool(result) is None
+
+
+class TestTagDump:
+    """Tests for raw tag dump parsing."""
+
+    def _ntag_image(self, record_type: bytes, payload: bytes) -> bytes:
+        """Helper to build an NTAG memory image holding one NDEF record."""
+        record = bytes([0xD2, len(record_type), len(payload)]) + record_type + payload
+        return self._ntag_image_from(bytes([0x03, len(record)]) + record + b"\xfe")
+
+    def _ntag_image_from(self, user_memory: bytes) -> bytes:
+        """Helper to build an NTAG memory image from its user memory (page 4 on)."""
+        memory = bytes.fromhex("04112288" "33445566" "00000000" "E1103E00") + user_memory
+        return memory + b"\x00" * (-len(memory) % 4)
+
+    def test_ntag_binary_dump(self):
+        """Should extract UID and NDEF records from an NTAG image."""
+        payload = json.dumps({"protocol": "openspool", "type": "PETG", "color_hex": "00FF00"}).encode()
+        dump = parse_dump_bytes(self._ntag_image(b"application/json", payload))
+
+        assert dump.nfc_type == NfcTagType.NTAG
+        assert dump.uid == "04112233445566"
+
+        result = TagDecoder.decode_dump(dump)
+        assert result.tag_type == TagType.OPENSPOOL
+        assert result.openspool_data.material_type == "PETG"
+
+    def test_written_ndef_round_trip(self):
+        """Should read back the NTAG memory built by ndef_record and ndef_tlv."""
+        url = "https://info.filament.example/s?id=abc"
+        memory = ndef_tlv(ndef_record("U", url))
+        memory += b"\x00" * (-len(memory) % 4)
+        pages = {NTAG_NDEF_START_PAGE + i // 4: memory[i : i + 4] for i in range(0, len(memory), 4)}
+        [record] = parse_ndef_message(ndef_message_from_pages(pages))
+        assert record["type"] == "U"
+        assert record["payload"][0] == 0x04  # https:// prefix code
+        assert TagDecoder._decode_ndef_url_payload(record["payload"]) == url
+
+        # Long record and 3-byte TLV length
+        payload = json.dumps({"protocol": "openspool", "type": "PETG", "note": "x" * 300}).encode()
+        dump = parse_dump_bytes(self._ntag_image_from(ndef_tlv(ndef_record("application/json", payload))))
+        result = TagDecoder.decode_dump(dump)
+        assert result.tag_type == TagType.OPENSPOOL
+        assert result.openspool_data.material_type == "PETG"
+
+    def test_flipper_mifare_dump(self):
+        """Should parse Flipper Zero dumps, skipping unread blocks."""
+        text = "\n".join(
+            [
+                "Filetype: Flipper NFC device",
+                "# Comment line",
+                "Device type: Mifare Classic",
+                "UID: 75 88 6B 1D",
+                "Block 0: 75 88 6B 1D 8B 08 04 00 62 63 64 65 66 67 68 69",
+                "Block 1: 41 30 30 2D 47 31 00 00 47 46 41 30 30 00 00 00",
+                "Block 3: ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ??",
+            ]
+        )
+        dump = parse_dump_text(text)
+
+        assert dump.nfc_type == NfcTagType.MIFARE_CLASSIC_1K
+        assert dump.uid == "75886B1D"
+        assert sorted(dump.blocks) == [0, 1]
+
+        result = TagDecoder.decode_dump(dump)
+        assert result.tag_type == TagType.BAMBULAB
+        assert result.bambulab_data.material_id == "GFA00"
+
+    def test_proxmark_eml_dump(self):
+        """Should parse Proxmark3 .eml dumps (one block per line)."""
+        dump = parse_dump_text("75886B1D8B0804006263646566676869\n" + "00" * 16)
+
+        assert dump.nfc_type == NfcTagType.MIFARE_CLASSIC_1K
+        assert dump.uid == "75886B1D"
+        assert len(dump.blocks) == 2
+
+    def test_unrecognized_binary_size(self):
+        """Should reject binary dumps that aren't whole blocks or pages."""
+        with pytest.raises(ValueError):
+            parse_dump_bytes(b"\x00" * 7)
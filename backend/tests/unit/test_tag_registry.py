@@ -0,0 +1,141 @@
+"""Unit tests for the tag decoder registry."""
+
+import json
+from pathlib import Path
+
+import pytest
+from tags import TagDecoder, TagType
+from tags.models import NfcTagType, TagReadResult
+from tags.registry import DecoderRegistry, FormatDecoder, RawTag, get_decoder_registry
+
+# Shared with the firmware's decoder tests (firmware/core/src/decode.rs)
+TAG_VECTORS = Path(__file__).parents[3] / "firmware" / "core" / "testdata" / "tag_vectors.json"
+
+NTAG = 1  # Vector tag types, as reported by the Pico bridge
+
+
+def _decode_vector(vector: dict) -> TagReadResult:
+    """Decode a test vector laid out as the Pico bridge sends it."""
+    data = bytes.fromhex(vector["data"])
+    if vector["tag_type"] == NTAG:
+        # Memory from page 4 on
+        pages = {4 + i // 4: data[i : i + 4] for i in range(0, len(data), 4)}
+        return TagDecoder.decode_ntag_pages(vector["uid"], pages)
+    # Blocks 1, 2, 4 and 5
+    blocks = dict(zip((1, 2, 4, 5), (data[i : i + 16] for i in range(0, 64, 16)), strict=True))
+    return TagDecoder.decode_mifare_blocks(vector["uid"], blocks)
+
+
+@pytest.mark.parametrize("vector", json.loads(TAG_VECTORS.read_text()), ids=lambda vector: vector["name"])
+def test_shared_vectors(vector):
+    """Should pick the same decoder, with the same confidence, as the firmware."""
+    result = _decode_vector(vector)
+
+    assert result.decoder == vector["decoder"]
+    if vector["decoder"] is None:
+        assert result.tag_type == TagType.UNKNOWN
+        assert result.confidence == 0
+        return
+
+    assert result.confidence == vector["confidence"]
+    spool = TagDecoder.to_spool(result)
+    assert spool.brand == vector["vendor"]
+    assert (spool.material or "") == vector["material"]
+    assert spool.rgba == vector["color_rgba"]
+
+
+class _Fixed(FormatDecoder):
+    """Decoder with a fixed confidence."""
+
+    def __init__(self, name: str, confidence: int, decodes: bool = True):
+        self.name = name
+        self._confidence = confidence
+        self._decodes = decodes
+
+    def confidence(self, tag: RawTag) -> int:
+        return self._confidence
+
+    def decode(self, tag: RawTag, result: TagReadResult) -> bool:
+        return self._decodes
+
+
+def _result() -> TagReadResult:
+    return TagReadResult(uid="04AA", uid_base64="BKo", nfc_type=NfcTagType.NTAG, tag_type=TagType.UNKNOWN)
+
+
+class TestDecoderRegistry:
+    """Tests for decoder selection."""
+
+    def test_most_confident_wins(self):
+        """Should use the most confident decoder, whatever the registration order."""
+        registry = DecoderRegistry([_Fixed("low", 40), _Fixed("high", 90)])
+        result = _result()
+
+        assert registry.decode(RawTag("04AA", NfcTagType.NTAG), result)
+        assert result.decoder == "high"
+        assert result.confidence == 90
+
+    def test_tie_goes_to_first_registered(self):
+        """Should prefer the earlier decoder on equal confidence."""
+        registry = DecoderRegistry([_Fixed("first", 80)])
+        registry.register(_Fixed("second", 80))
+        result = _result()
+
+        registry.decode(RawTag("04AA", NfcTagType.NTAG), result)
+        assert result.decoder == "first"
+
+    def test_falls_back_when_decode_fails(self):
+        """Should try the next decoder when the most confident can't decode the tag."""
+        registry = DecoderRegistry([_Fixed("broken", 100, decodes=False), _Fixed("fallback", 60), _Fixed("none", 0)])
+        result = _result()
+
+        assert registry.decode(RawTag("04AA", NfcTagType.NTAG), result)
+        assert result.decoder == "fallback"
+        assert result.confidence == 60
+
+    def test_no_match(self):
+        """Should leave the result alone when no decoder is confident."""
+        registry = DecoderRegistry([_Fixed("none", 0)])
+        result = _result()
+
+        assert not registry.decode(RawTag("04AA", NfcTagType.NTAG), result)
+        assert result.decoder is None
+        assert result.tag_type == TagType.UNKNOWN
+
+    def test_builtin_decoder_names(self):
+        """Should register the built-in formats under the firmware's names."""
+        names = [decoder.name for decoder in get_decoder_registry().decoders]
+        assert names == ["bambu", "creality", "spoolease", "prusa", "openprinttag", "openspool", "opentag3d"]
+
+
+class TestCrealityDecoder:
+    """Tests for Creality CFS tags."""
+
+    def _blocks(self, record: str) -> dict[int, bytes]:
+        data = record.encode("ascii").ljust(48, b"0")
+        return {4: data[0:16], 5: data[16:32], 6: data[32:48]}
+
+    def test_decode(self):
+        """Should decode the sector 1 record."""
+        result = TagDecoder.decode_mifare_blocks("A1B2C3D4", self._blocks("AB1240276A21010010FF00000330123456"))
+
+        assert result.tag_type == TagType.CREALITY
+        data = result.creality_data
+        assert data.date_code == "AB124"
+        assert data.vendor_id == "0276"
+        assert data.filament_id == "101001"
+        assert data.color_rgba == "FF0000FF"
+        assert data.length_m == 330
+        assert data.serial == "123456"
+
+        spool = TagDecoder.to_spool(result)
+        assert spool.brand == "Creality"
+        assert spool.material is None
+
+    def test_encrypted_record(self):
+        """Should leave tags whose record doesn't parse to the other decoders."""
+        blocks = {4: bytes(range(16)), 5: bytes(range(16, 32)), 6: bytes(16)}
+        result = TagDecoder.decode_mifare_blocks("A1B2C3D4", blocks)
+
+        assert result.tag_type == TagType.UNKNOWN
+        assert result.creality_data is None
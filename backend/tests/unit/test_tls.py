@@ -0,0 +1,43 @@
+"""Unit tests for printer certificate pinning."""
+
+import pytest
+
+from mqtt.tls import CertificateMismatch, cert_fingerprint, printer_tls_context
+
+CERT_A = b"certificate a"
+CERT_B = b"certificate b"
+
+
+class TestCertificatePinning:
+    def test_fingerprint_format(self):
+        fingerprint = cert_fingerprint(CERT_A)
+        assert len(fingerprint) == 95
+        assert fingerprint == fingerprint.upper()
+        assert fingerprint.count(":") == 31
+
+    def test_pins_first_certificate(self):
+        pinned = []
+        context = printer_tls_context(None, on_pin=pinned.append)
+        context.check(CERT_A)
+        assert context.pinned == cert_fingerprint(CERT_A)
+        assert pinned == [cert_fingerprint(CERT_A)]
+
+        context.check(CERT_A)
+        assert len(pinned) == 1
+
+    def test_refuses_other_certificate(self):
+        refused = []
+        context = printer_tls_context(cert_fingerprint(CERT_A), on_mismatch=refused.append)
+        with pytest.raises(CertificateMismatch) as exc:
+            context.check(CERT_B)
+        assert exc.value.presented == cert_fingerprint(CERT_B)
+        assert refused == [cert_fingerprint(CERT_B)]
+        assert context.pinned == cert_fingerprint(CERT_A)
+
+    def test_insecure_accepts_anything(self):
+        pinned = []
+        context = printer_tls_context(cert_fingerprint(CERT_A), insecure=True, on_pin=pinned.append)
+        context.check(CERT_B)
+        assert context.presented == cert_fingerprint(CERT_B)
+        assert context.pinned == cert_fingerprint(CERT_A)
+        assert pinned == []
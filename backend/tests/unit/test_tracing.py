@@ -0,0 +1,128 @@
+"""Unit tests for tracing spans and per-printer log buffering."""
+
+import asyncio
+import logging
+
+from services.tracing import (
+    PrinterLogHandler,
+    TraceContextFilter,
+    current_request_id,
+    current_serial,
+    log_event_matches,
+    request_id_from,
+    span,
+    traced,
+)
+
+
+def _logger(handler: PrinterLogHandler) -> logging.Logger:
+    log = logging.getLogger("test.tracing")
+    log.handlers = [handler]
+    log.setLevel(logging.DEBUG)
+    log.propagate = False
+    return log
+
+
+class TestSpans:
+    def test_span_context(self):
+        with span("outer", serial="SERIAL1", request_id="req1"):
+            with span("inner"):
+                assert (current_serial(), current_request_id()) == ("SERIAL1", "req1")
+            with span("other", serial="SERIAL2"):
+                assert current_serial() == "SERIAL2"
+            assert current_serial() == "SERIAL1"
+        assert current_serial() is None
+
+    def test_traced_serial(self):
+        class Connection:
+            serial = "SERIAL1"
+
+            @traced("conn.work")
+            def work(self):
+                return current_serial()
+
+        class Manager:
+            @traced("manager.work")
+            def work(self, serial: str):
+                return current_serial()
+
+            @traced("manager.async_work")
+            async def async_work(self, serial: str):
+                return current_serial()
+
+        assert Connection().work() == "SERIAL1"
+        assert Manager().work("SERIAL2") == "SERIAL2"
+        assert Manager().work(serial="SERIAL3") == "SERIAL3"
+        assert asyncio.run(Manager().async_work("SERIAL4")) == "SERIAL4"
+
+    def test_filter_adds_trace(self):
+        record = logging.LogRecord("x", logging.INFO, __file__, 1, "msg", None, None)
+        TraceContextFilter().filter(record)
+        assert record.trace == ""
+        with span("work", serial="SERIAL1", request_id="abc"):
+            TraceContextFilter().filter(record)
+        assert record.trace == " [SERIAL1 req=abc]"
+
+    def test_request_id_from_header(self):
+        assert request_id_from("my-request.1") == "my-request.1"
+        assert len(request_id_from(None)) == 12
+        assert request_id_from("bad id\nwith newline") != "bad id\nwith newline"
+
+
+class TestPrinterLogHandler:
+    def test_buffers_per_printer(self):
+        handler = PrinterLogHandler(buffer_size=2)
+        log = _logger(handler)
+        log.info("untagged")
+        with span("mqtt.message", serial="SERIAL1"):
+            for i in range(3):
+                log.info(f"message {i}")
+        with span("mqtt.message", serial="SERIAL2"):
+            log.warning("other printer")
+
+        assert [e.message for e in handler.recent("SERIAL1")] == ["message 1", "message 2"]
+        assert [e.message for e in handler.recent("SERIAL1", limit=1)] == ["message 2"]
+        event = handler.recent("SERIAL2")[0]
+        assert (event.level, event.span) == ("WARNING", "mqtt.message")
+
+    async def test_subscribers_receive_records(self):
+        handler = PrinterLogHandler()
+        log = _logger(handler)
+        sub = handler.subscribe("SERIAL1")
+        with span("printer.set_filament", serial="SERIAL1", request_id="req1"):
+            log.info("command sent")
+        with span("printer.set_filament", serial="SERIAL2"):
+            log.info("other printer")
+
+        event = await asyncio.wait_for(sub.queue.get(), 1)
+        assert (event.message, event.request_id) == ("command sent", "req1")
+        await asyncio.sleep(0)
+        assert sub.queue.empty()
+
+        handler.unsubscribe(sub)
+        with span("mqtt.message", serial="SERIAL1"):
+            log.info("after unsubscribing")
+        await asyncio.sleep(0)
+        assert sub.queue.empty()
+
+    async def test_close_ends_subscriptions(self):
+        handler = PrinterLogHandler()
+        sub = handler.subscribe("SERIAL1")
+        handler.close()
+
+        assert sub.dropped
+        assert sub.queue.get_nowait() is None
+
+    def test_filters(self):
+        handler = PrinterLogHandler()
+        log = _logger(handler)
+        with span("mqtt.message", serial="SERIAL1"):
+            log.debug("tray update")
+            log.error("Connection failed")
+        debug, error = handler.recent("SERIAL1")
+
+        assert not log_event_matches(debug, logging.INFO)
+        assert log_event_matches(error, logging.INFO)
+        assert log_event_matches(error, search="CONNECTION")
+        assert log_event_matches(error, search="test.tracing")
+        assert not log_event_matches(error, search="tray")
@@ -0,0 +1,84 @@
+"""Unit tests for the WLED shelf indicator."""
+
+import pytest
+from models import SpoolLiveStatus, SpoolPresence
+from pydantic import ValidationError
+from services.wled import WledConfig, WledMapping, find_segments, highlight_payload, restore_payload
+
+
+class FakeSpool:
+    def __init__(self, location=None):
+        self.location = location
+
+
+CONFIG = WledConfig(
+    enabled=True,
+    host="wled.local",
+    color="#ff8800",
+    brightness=150,
+    mappings=[
+        WledMapping(segment=0, location="Shelf A"),
+        WledMapping(segment=1, location="Shelf B"),
+        WledMapping(segment=2, printer_serial="01S", ams_id=0, tray_id=3),
+    ],
+)
+
+
+class TestWledConfig:
+    def test_color_normalized(self):
+        assert CONFIG.color == "FF8800"
+        with pytest.raises(ValidationError):
+            WledConfig(color="orange")
+
+    def test_host_required_when_enabled(self):
+        with pytest.raises(ValidationError):
+            WledConfig(enabled=True)
+        assert WledConfig(enabled=True, host="http://10.0.0.5/").host == "10.0.0.5"
+
+    def test_mapping_target(self):
+        with pytest.raises(ValidationError):
+            WledMapping(segment=0)
+        with pytest.raises(ValidationError):
+            WledMapping(segment=0, location="Shelf A", printer_serial="01S", ams_id=0, tray_id=0)
+        with pytest.raises(ValidationError):
+            WledMapping(segment=0, printer_serial="01S", ams_id=0)
+
+
+class TestFindSegments:
+    def test_by_location(self):
+        assert find_segments(CONFIG, FakeSpool(" shelf b")) == [1]
+        assert find_segments(CONFIG, FakeSpool("Drybox")) == []
+        assert find_segments(CONFIG, FakeSpool(None)) == []
+
+    def test_loaded_spool_by_slot(self):
+        loaded = SpoolLiveStatus(status=SpoolPresence.LOADED, printer_serial="01S", ams_id=0, tray_id=3)
+        assert find_segments(CONFIG, FakeSpool("Shelf A"), loaded) == [2]
+
+        other_slot = SpoolLiveStatus(status=SpoolPresence.LOADED, printer_serial="01S", ams_id=1, tray_id=0)
+        assert find_segments(CONFIG, FakeSpool("Shelf A"), other_slot) == []
+
+    def test_stored_spool_by_location(self):
+        assert find_segments(CONFIG, FakeSpool("Shelf A"), SpoolLiveStatus()) == [0]
+
+
+class TestPayloads:
+    def test_highlight(self):
+        assert highlight_payload(CONFIG, [1]) == {
+            "on": True,
+            "bri": 150,
+            "seg": [
+                {"id": 1, "on": True, "col": [[255, 136, 0]], "fx": 0},
+                {"id": 0, "on": False},
+                {"id": 2, "on": False},
+            ],
+        }
+
+    def test_restore(self):
+        state = {
+            "on": True,
+            "bri": 80,
+            "transition": 7,
+            "seg": [{"id": 0, "on": True, "col": [[1, 2, 3]], "fx": 9, "len": 30, "start": 0}],
+        }
+        restored = restore_payload(state)
+        assert restored == {"on": True, "bri": 80, "seg": [{"id": 0, "on": True, "col": [[1, 2, 3]], "fx": 9}]}
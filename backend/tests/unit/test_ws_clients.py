@@ -0,0 +1,51 @@
+"""Unit tests for the WebSocket client registry."""
+
+from services.ws_clients import ANONYMOUS, WebSocketClients
+
+
+class TestWebSocketClients:
+    def test_identity_from_api_key(self):
+        clients = WebSocketClients()
+        info = clients.add(object(), "device", api_key={"id": 3, "name": "Workshop display"})
+        anonymous = clients.add(object(), "web")
+
+        assert (info.identity, info.api_key_id) == ("Workshop display", 3)
+        assert (anonymous.identity, anonymous.api_key_id) == (ANONYMOUS, None)
+
+    def test_targets_by_class_and_subscription(self):
+        clients = WebSocketClients()
+        web, device, follower = object(), object(), object()
+        clients.add(web, "web")
+        clients.add(device, "device")
+        clients.add(follower, "web")
+        clients.subscribe(follower, ["01S"])
+
+        assert clients.targets() == [web, device, follower]
+        assert clients.targets("web") == [web, follower]
+        assert clients.targets("web", "02S") == [web]
+        assert clients.targets(None, "01S") == [web, device, follower]
+
+        clients.subscribe(follower, None)
+        assert clients.targets("web", "02S") == [web, follower]
+
+    def test_touch_records_activity(self):
+        clients = WebSocketClients()
+        ws = object()
+        info = clients.add(ws, "web")
+        info.last_activity = 0
+
+        clients.touch(ws)
+        snapshot = clients.snapshot()[0]
+        assert snapshot.last_activity > 0
+        assert snapshot.messages_received == 1
+
+    def test_remove_and_clear(self):
+        clients = WebSocketClients()
+        first, second = object(), object()
+        clients.add(first, "web")
+        clients.add(second, "device")
+
+        clients.remove(first)
+        clients.remove(first)  # Already gone
+        assert clients.clear() == [second]
+        assert len(clients) == 0
@@ -5,6 +5,7 @@ Monitors printer state changes via MQTT and logs filament usage when prints comp
 
 import asyncio
 import logging
+import time
 from collections.abc import Callable
 from dataclasses import dataclass, field
 
@@ -12,6 +13,19 @@ from models import PrinterState
 
 logger = logging.getLogger(__name__)
 
+# tray_now value while the external spool holder (virtual tray) feeds the nozzle
+TRAY_NOW_EXTERNAL = 254
+
+
+def _external_holders(state: PrinterState) -> set[tuple[int, int]]:
+    """External holder slots (ams_id, tray_id) currently feeding a nozzle."""
+    holders = set()
+    if TRAY_NOW_EXTERNAL in (state.tray_now, state.tray_now_right):
+        holders.add((255, 0))
+    if state.tray_now_left == TRAY_NOW_EXTERNAL:
+        holders.add((254, 0))  # Left holder on dual-nozzle printers
+    return holders
+
 
 @dataclass
 class PrintSession:
@@ -20,9 +34,13 @@ class PrintSession:
     printer_serial: str
     print_name: str
     start_progress: int = 0
+    started_at: float = field(default_factory=time.time)
     # Track AMS tray remain percentages at start
     tray_remain_start: dict = field(default_factory=dict)  # (ams_id, tray_id) -> remain%
     active_tray: int | None = None  # tray_now at start
+    gcode_file: str | None = None  # Plate being printed, to find the sliced weight
+    # External holder slots that fed the print (their usage is estimated from the sliced file)
+    external_holders: set = field(default_factory=set)
 
 
 @dataclass
@@ -33,17 +51,27 @@ class UsageTracker:
     _sessions: dict[str, PrintSession] = field(default_factory=dict)
     # Callback to log usage (async)
     _on_usage_logged: Callable | None = None
+    # Callback when a print ends (async), e.g. to log its energy use
+    _on_print_ended: Callable | None = None
     # Event loop for async operations
     _loop: asyncio.AbstractEventLoop | None = None
 
     def set_usage_callback(self, callback: Callable):
         """Set callback for when usage is logged.
 
-        Callback signature: async def on_usage(serial, print_name, tray_usage: dict)
-        where tray_usage is {(ams_id, tray_id): remain_used_percent}
+        Callback signature: async def on_usage(serial, print_name, tray_usage: dict, gcode_file)
+        where tray_usage is {(ams_id, tray_id): remain_used_percent}. External holder
+        slots without a remain reading map to None (estimate from the sliced file).
         """
         self._on_usage_logged = callback
 
+    def set_print_end_callback(self, callback: Callable):
+        """Set callback for when a tracked print finishes or fails.
+
+        Callback signature: async def on_print_end(serial, print_name, duration_sec: int)
+        """
+        self._on_print_ended = callback
+
     def set_event_loop(self, loop: asyncio.AbstractEventLoop):
         """Set event loop for async operations."""
         self._loop = loop
@@ -71,6 +99,10 @@ class UsageTracker:
         elif gcode_state == "FINISH" and prev_gcode_state == "PAUSE":
             self._on_print_end(serial, state, success=True)
 
+        # Remember if the external spool fed the print at any point
+        elif serial in self._sessions and gcode_state in ("RUNNING", "PAUSE"):
+            self._sessions[serial].external_holders |= _external_holders(state)
+
     def _on_print_start(self, serial: str, state: PrinterState):
         """Handle print start."""
         print_name = state.subtask_name or "Unknown"
@@ -92,6 +124,8 @@ class UsageTracker:
             start_progress=state.print_progress or 0,
             tray_remain_start=tray_remain,
             active_tray=state.tray_now,
+            gcode_file=state.gcode_file,
+            external_holders=_external_holders(state),
         )
         self._sessions[serial] = session
 
@@ -125,14 +159,26 @@ class UsageTracker:
                 if used_percent > 0:
                     tray_usage[key] = used_percent
 
+        # The external holder rarely reports remain; its usage is estimated by the callback
+        for key in session.external_holders:
+            tray_usage.setdefault(key, None)
+
+        duration_sec = int(time.time() - session.started_at)
         status = "completed" if success else "failed"
-        logger.info(f"Print {status} on {serial}: '{session.print_name}', usage: {tray_usage}")
+        logger.info(f"Print {status} on {serial}: '{session.print_name}' after {duration_sec}s, usage: {tray_usage}")
+
+        if self._on_print_ended and self._loop:
+            self._loop.call_soon_threadsafe(
+                lambda: asyncio.create_task(self._on_print_ended(serial, session.print_name, duration_sec))
+            )
 
         # Notify callback if usage detected
         if tray_usage and self._on_usage_logged:
             if self._loop:
                 self._loop.call_soon_threadsafe(
-                    lambda: asyncio.create_task(self._on_usage_logged(serial, session.print_name, tray_usage))
+                    lambda: asyncio.create_task(
+                        self._on_usage_logged(serial, session.print_name, tray_usage, session.gcode_file)
+                    )
                 )
 
     def get_active_sessions(self) -> dict[str, dict]:
@@ -142,6 +188,7 @@ class UsageTracker:
                 "print_name": session.print_name,
                 "active_tray": session.active_tray,
                 "trays_tracked": len(session.tray_remain_start),
+                "external_spool": bool(session.external_holders),
             }
             for serial, session in self._sessions.items()
         }
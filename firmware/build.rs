@@ -1,3 +1,5 @@
+use std::path::Path;
+
 fn main() {
     // ESP-IDF build configuration is handled by esp-idf-sys
     // Custom fonts are compiled via the ESP-IDF component in components/custom_fonts
@@ -6,4 +8,36 @@ fn main() {
     // Rerun if font files change
     println!("cargo:rerun-if-changed=fonts/");
     println!("cargo:rerun-if-changed=components/custom_fonts/CMakeLists.txt");
+
+    ota_public_key();
+}
+
+/// Bake the OTA signing public key into the firmware (see src/ota_manager.rs).
+/// SPOOLBUDDY_OTA_PUBLIC_KEY is the `.pub` file written by `ota-sign keygen`,
+/// or the key itself as hex. Without it the firmware refuses OTA updates
+/// unless built with the `unsigned-ota` feature.
+fn ota_public_key() {
+    println!("cargo:rerun-if-env-changed=SPOOLBUDDY_OTA_PUBLIC_KEY");
+
+    let key = match std::env::var("SPOOLBUDDY_OTA_PUBLIC_KEY") {
+        Ok(value) if !value.trim().is_empty() => {
+            let value = value.trim().to_string();
+            if Path::new(&value).is_file() {
+                println!("cargo:rerun-if-changed={}", value);
+                std::fs::read_to_string(&value)
+                    .unwrap_or_else(|e| panic!("Can't read OTA public key {}: {}", value, e))
+                    .trim()
+                    .to_string()
+            } else {
+                value
+            }
+        }
+        _ => String::new(),
+    };
+    if !key.is_empty() && (key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit())) {
+        panic!("SPOOLBUDDY_OTA_PUBLIC_KEY is not a 64 character hex ed25519 public key");
+    }
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("ota_public_key.txt");
+    std::fs::write(out, key).expect("Failed to write ota_public_key.txt");
 }
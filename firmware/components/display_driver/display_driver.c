@@ -1,6 +1,7 @@
 /**
- * SpoolBuddy Display Driver for CrowPanel Advance 7.0"
- * 800x480 RGB LCD with GT911 touch controller
+ * SpoolBuddy Display Driver
+ * RGB LCD with GT911 touch controller; resolution, timing and pins come
+ * from the board config passed to display_init()
  * Uses LVGL 9.x and ESP-IDF RGB LCD driver
  */
 
@@ -11,52 +12,27 @@
 #include <string.h>
 #include "esp_lcd_panel_ops.h"
 #include "esp_lcd_panel_rgb.h"
+#include "esp_heap_caps.h"
 #include "esp_log.h"
 #include "esp_timer.h"
 #include "driver/gpio.h"
 #include "driver/i2c.h"
 #include "freertos/FreeRTOS.h"
 #include "freertos/task.h"
+#include "freertos/semphr.h"
 
 static const char *TAG = "display";
 
-// Display dimensions
-#define DISPLAY_WIDTH   800
-#define DISPLAY_HEIGHT  480
-
-// CrowPanel Advance 7.0" RGB pins
-#define PIN_PCLK    39
-#define PIN_HSYNC   40
-#define PIN_VSYNC   41
-#define PIN_DE      42
-
-// RGB565 data pins (from working firmware)
-#define PIN_B0      21
-#define PIN_B1      47
-#define PIN_B2      48
-#define PIN_B3      45
-#define PIN_B4      38
-#define PIN_G0      9
-#define PIN_G1      10
-#define PIN_G2      11
-#define PIN_G3      12
-#define PIN_G4      13
-#define PIN_G5      14
-#define PIN_R0      7
-#define PIN_R1      17
-#define PIN_R2      18
-#define PIN_R3      3
-#define PIN_R4      46
-
-// Touch I2C pins (directly connected on CrowPanel)
+// Board display configuration (set by display_init)
+static display_board_config_t board;
+
+// Touch controller on I2C0
 #define TOUCH_I2C_PORT      I2C_NUM_0
-#define TOUCH_I2C_SDA       15
-#define TOUCH_I2C_SCL       16
-#define GT911_ADDR          0x5D
 
-// Backlight control - CrowPanel uses GPIO1 and GPIO2
-#define PIN_BACKLIGHT1      1
-#define PIN_BACKLIGHT2      2
+// CH422G IO expander commands (Waveshare boards)
+#define CH422G_CMD_SET      0x24
+#define CH422G_CMD_IO       0x38
+#define CH422G_IO_OE        0x01
 
 // Panel handle
 static esp_lcd_panel_handle_t panel_handle = NULL;
@@ -64,11 +40,11 @@ static esp_lcd_panel_handle_t panel_handle = NULL;
 // LVGL display
 static lv_display_t *display = NULL;
 
-// Draw buffers (in internal SRAM for reliability)
+// Draw buffers (in internal SRAM for reliability), sized for the board width
 // For RGB565, each pixel is 2 bytes. LVGL 9.x uses byte buffers.
 #define DRAW_BUF_LINES  40
-static uint8_t draw_buf1[DISPLAY_WIDTH * DRAW_BUF_LINES * 2];  // RGB565 = 2 bytes/pixel
-static uint8_t draw_buf2[DISPLAY_WIDTH * DRAW_BUF_LINES * 2];  // RGB565 = 2 bytes/pixel
+static uint8_t *draw_buf1 = NULL;
+static uint8_t *draw_buf2 = NULL;
 
 // Touch state
 static bool touch_pressed = false;
@@ -77,6 +53,82 @@ static int16_t touch_y = 0;
 
 // Tick timer
 static uint32_t tick_start = 0;
+static uint32_t tick_get_cb(void);
+
+// Partial refresh
+//
+// The panel has no GRAM, so a DPI frame always clocks out every scanline.
+// Instead of letting the driver stream the whole framebuffer out of
+// PSRAM continuously, the panel runs in refresh-on-demand mode: a frame is
+// only sent after LVGL has finished writing its dirty areas, plus a slow
+// keep-alive refresh so the panel doesn't fade. The framebuffer is never
+// written while a frame is being sent, which removes tearing.
+#define PANEL_KEEPALIVE_MS  100
+#define FRAME_WAIT_MS       50
+
+// Time to send one panel frame, from the board timing
+// (CrowPanel at 14 MHz: (800 + 108) x (480 + 44) clocks = ~34 ms)
+static uint32_t frame_period_ms = 34;
+
+static SemaphoreHandle_t frame_done = NULL;
+static volatile bool frame_in_flight = false;
+static uint32_t last_refresh_ms = 0;
+static int64_t refresh_start_us = 0;
+static volatile uint32_t last_panel_us = 0;
+
+// Scanline window touched since the last refresh (dirty_y1 > dirty_y2 = clean)
+static int dirty_y1 = INT16_MAX;
+static int dirty_y2 = -1;
+
+static display_refresh_stats_t refresh_stats = {0};
+
+/**
+ * Frame transfer finished (ISR context)
+ */
+static bool IRAM_ATTR on_frame_done(esp_lcd_panel_handle_t panel, const esp_lcd_rgb_panel_event_data_t *edata, void *user_ctx)
+{
+    BaseType_t woken = pdFALSE;
+    last_panel_us = (uint32_t)(esp_timer_get_time() - refresh_start_us);
+    frame_in_flight = false;
+    xSemaphoreGiveFromISR(frame_done, &woken);
+    return woken == pdTRUE;
+}
+
+/**
+ * Wait for the frame in flight so the framebuffer can be written safely
+ */
+static void wait_frame_done(void)
+{
+    if (frame_in_flight && xSemaphoreTake(frame_done, pdMS_TO_TICKS(FRAME_WAIT_MS)) != pdTRUE) {
+        // Lost the callback - don't stall the UI on it
+        frame_in_flight = false;
+    }
+}
+
+/**
+ * Send the framebuffer to the panel
+ */
+static void start_refresh(bool keepalive)
+{
+    wait_frame_done();
+    xSemaphoreTake(frame_done, 0);  // Drop a stale give
+    frame_in_flight = true;
+    refresh_start_us = esp_timer_get_time();
+    if (esp_lcd_rgb_panel_refresh(panel_handle) != ESP_OK) {
+        frame_in_flight = false;
+        return;
+    }
+    last_refresh_ms = tick_get_cb();
+
+    if (keepalive) {
+        refresh_stats.keepalive_frames++;
+    } else {
+        refresh_stats.frames++;
+        refresh_stats.last_dirty_rows = dirty_y2 - dirty_y1 + 1;
+    }
+    dirty_y1 = board.height;
+    dirty_y2 = -1;
+}
 
 /**
  * LVGL flush callback - copies rendered pixels to display
@@ -117,6 +169,9 @@ static void flush_cb(lv_display_t *disp, const lv_area_t *area, uint8_t *px_map)
         return;
     }
 
+    // Don't touch scanlines the panel is still reading
+    wait_frame_done();
+
     // Copy the rendered area to framebuffer
     uint16_t *fb16 = (uint16_t *)fb;
     uint16_t *src = (uint16_t *)px_map;
@@ -129,7 +184,7 @@ static void flush_cb(lv_display_t *disp, const lv_area_t *area, uint8_t *px_map)
     }
 
     for (int y = area->y1; y <= area->y2; y++) {
-        uint16_t *dst_row = fb16 + y * DISPLAY_WIDTH + area->x1;
+        uint16_t *dst_row = fb16 + y * board.width + area->x1;
         memcpy(dst_row, src, width * sizeof(uint16_t));
         src += width;
 
@@ -143,6 +198,15 @@ static void flush_cb(lv_display_t *disp, const lv_area_t *area, uint8_t *px_map)
         ESP_LOGI(TAG, "flush_cb #%d: memcpy done, calling flush_ready", flush_count);
     }
 
+    if (area->y1 < dirty_y1) dirty_y1 = area->y1;
+    if (area->y2 > dirty_y2) dirty_y2 = area->y2;
+    refresh_stats.bytes_flushed += (uint32_t)width * height * sizeof(uint16_t);
+
+    // Send the frame once LVGL has written all of its dirty areas
+    if (lv_display_flush_is_last(disp)) {
+        start_refresh(false);
+    }
+
     lv_display_flush_ready(disp);
 
     if (flush_count <= 5) {
@@ -159,12 +223,12 @@ static bool read_gt911_touch(int16_t *x, int16_t *y)
     uint8_t reg_addr[2] = {0x81, 0x4E};  // Touch status register
 
     // Write register address
-    if (i2c_master_write_to_device(TOUCH_I2C_PORT, GT911_ADDR, reg_addr, 2, 10) != ESP_OK) {
+    if (i2c_master_write_to_device(TOUCH_I2C_PORT, board.touch_addr, reg_addr, 2, 10) != ESP_OK) {
         return false;
     }
 
     // Read touch data
-    if (i2c_master_read_from_device(TOUCH_I2C_PORT, GT911_ADDR, buf, 7, 10) != ESP_OK) {
+    if (i2c_master_read_from_device(TOUCH_I2C_PORT, board.touch_addr, buf, 7, 10) != ESP_OK) {
         return false;
     }
 
@@ -173,7 +237,7 @@ static bool read_gt911_touch(int16_t *x, int16_t *y)
     if ((status & 0x80) == 0 || (status & 0x0F) == 0) {
         // Clear status flag
         uint8_t clear[3] = {0x81, 0x4E, 0x00};
-        i2c_master_write_to_device(TOUCH_I2C_PORT, GT911_ADDR, clear, 3, 10);
+        i2c_master_write_to_device(TOUCH_I2C_PORT, board.touch_addr, clear, 3, 10);
         return false;
     }
 
@@ -182,12 +246,12 @@ static bool read_gt911_touch(int16_t *x, int16_t *y)
     *y = buf[4] | (buf[5] << 8);
 
     // Bounds check
-    if (*x >= DISPLAY_WIDTH) *x = DISPLAY_WIDTH - 1;
-    if (*y >= DISPLAY_HEIGHT) *y = DISPLAY_HEIGHT - 1;
+    if (*x >= board.width) *x = board.width - 1;
+    if (*y >= board.height) *y = board.height - 1;
 
     // Clear status flag
     uint8_t clear[3] = {0x81, 0x4E, 0x00};
-    i2c_master_write_to_device(TOUCH_I2C_PORT, GT911_ADDR, clear, 3, 10);
+    i2c_master_write_to_device(TOUCH_I2C_PORT, board.touch_addr, clear, 3, 10);
 
     return true;
 }
@@ -217,8 +281,8 @@ static esp_err_t init_touch_i2c(void)
 {
     i2c_config_t conf = {
         .mode = I2C_MODE_MASTER,
-        .sda_io_num = TOUCH_I2C_SDA,
-        .scl_io_num = TOUCH_I2C_SCL,
+        .sda_io_num = board.touch_sda,
+        .scl_io_num = board.touch_scl,
         .sda_pullup_en = GPIO_PULLUP_ENABLE,
         .scl_pullup_en = GPIO_PULLUP_ENABLE,
         .master.clk_speed = 100000,
@@ -235,50 +299,66 @@ static esp_err_t init_touch_i2c(void)
  */
 static esp_err_t init_rgb_panel(void)
 {
+    const display_panel_timing_t *t = &board.timing;
+    const display_rgb_pins_t *p = &board.pins;
+
     ESP_LOGI(TAG, "=== RGB PANEL INIT ===");
-    ESP_LOGI(TAG, "Resolution: %dx%d", DISPLAY_WIDTH, DISPLAY_HEIGHT);
-    ESP_LOGI(TAG, "Pixel clock: 14MHz");
-    ESP_LOGI(TAG, "PCLK=%d HSYNC=%d VSYNC=%d DE=%d", PIN_PCLK, PIN_HSYNC, PIN_VSYNC, PIN_DE);
-    ESP_LOGI(TAG, "B: %d,%d,%d,%d,%d", PIN_B0, PIN_B1, PIN_B2, PIN_B3, PIN_B4);
-    ESP_LOGI(TAG, "G: %d,%d,%d,%d,%d,%d", PIN_G0, PIN_G1, PIN_G2, PIN_G3, PIN_G4, PIN_G5);
-    ESP_LOGI(TAG, "R: %d,%d,%d,%d,%d", PIN_R0, PIN_R1, PIN_R2, PIN_R3, PIN_R4);
+    ESP_LOGI(TAG, "Resolution: %dx%d", board.width, board.height);
+    ESP_LOGI(TAG, "Pixel clock: %lu Hz, frame %lu ms", (unsigned long)t->pclk_hz, (unsigned long)frame_period_ms);
+    ESP_LOGI(TAG, "PCLK=%ld HSYNC=%ld VSYNC=%ld DE=%ld",
+             (long)p->pclk, (long)p->hsync, (long)p->vsync, (long)p->de);
+    ESP_LOGI(TAG, "B: %ld,%ld,%ld,%ld,%ld",
+             (long)p->data[0], (long)p->data[1], (long)p->data[2], (long)p->data[3], (long)p->data[4]);
+    ESP_LOGI(TAG, "G: %ld,%ld,%ld,%ld,%ld,%ld",
+             (long)p->data[5], (long)p->data[6], (long)p->data[7], (long)p->data[8], (long)p->data[9], (long)p->data[10]);
+    ESP_LOGI(TAG, "R: %ld,%ld,%ld,%ld,%ld",
+             (long)p->data[11], (long)p->data[12], (long)p->data[13], (long)p->data[14], (long)p->data[15]);
 
     esp_lcd_rgb_panel_config_t panel_config = {
         .clk_src = LCD_CLK_SRC_DEFAULT,
         .timings = {
-            .pclk_hz = 14000000,  // 14MHz pixel clock (from working firmware)
-            .h_res = DISPLAY_WIDTH,
-            .v_res = DISPLAY_HEIGHT,
-            .hsync_pulse_width = 48,
-            .hsync_back_porch = 20,
-            .hsync_front_porch = 40,
-            .vsync_pulse_width = 4,
-            .vsync_back_porch = 20,
-            .vsync_front_porch = 20,
+            .pclk_hz = t->pclk_hz,
+            .h_res = board.width,
+            .v_res = board.height,
+            .hsync_pulse_width = t->hsync_pulse_width,
+            .hsync_back_porch = t->hsync_back_porch,
+            .hsync_front_porch = t->hsync_front_porch,
+            .vsync_pulse_width = t->vsync_pulse_width,
+            .vsync_back_porch = t->vsync_back_porch,
+            .vsync_front_porch = t->vsync_front_porch,
             .flags = {
-                .pclk_active_neg = true,
+                .pclk_active_neg = t->pclk_active_neg,
             },
         },
         .data_width = 16,
         .num_fbs = 1,
-        .bounce_buffer_size_px = 10 * DISPLAY_WIDTH,  // Bounce buffer for PSRAM
+        .bounce_buffer_size_px = 10 * board.width,  // Bounce buffer for PSRAM
         .psram_trans_align = 64,
-        .hsync_gpio_num = PIN_HSYNC,
-        .vsync_gpio_num = PIN_VSYNC,
-        .de_gpio_num = PIN_DE,
-        .pclk_gpio_num = PIN_PCLK,
+        .hsync_gpio_num = p->hsync,
+        .vsync_gpio_num = p->vsync,
+        .de_gpio_num = p->de,
+        .pclk_gpio_num = p->pclk,
         .disp_gpio_num = -1,
-        .data_gpio_nums = {
-            PIN_B0, PIN_B1, PIN_B2, PIN_B3, PIN_B4,
-            PIN_G0, PIN_G1, PIN_G2, PIN_G3, PIN_G4, PIN_G5,
-            PIN_R0, PIN_R1, PIN_R2, PIN_R3, PIN_R4,
-        },
         .flags = {
             .fb_in_psram = true,
+            .refresh_on_demand = true,  // Frames are sent by start_refresh()
         },
     };
+    for (int i = 0; i < 16; i++) {
+        panel_config.data_gpio_nums[i] = p->data[i];
+    }
+
+    frame_done = xSemaphoreCreateBinary();
+    if (frame_done == NULL) {
+        return ESP_ERR_NO_MEM;
+    }
 
     ESP_ERROR_CHECK(esp_lcd_new_rgb_panel(&panel_config, &panel_handle));
+
+    esp_lcd_rgb_panel_event_callbacks_t callbacks = {
+        .on_vsync = on_frame_done,
+    };
+    ESP_ERROR_CHECK(esp_lcd_rgb_panel_register_event_callbacks(panel_handle, &callbacks, NULL));
     ESP_ERROR_CHECK(esp_lcd_panel_reset(panel_handle));
     ESP_ERROR_CHECK(esp_lcd_panel_init(panel_handle));
 
@@ -293,47 +373,69 @@ static esp_err_t init_rgb_panel(void)
 }
 
 /**
- * Initialize backlight
- * CrowPanel uses both GPIO1 and GPIO2 for backlight control
- * Also tries I2C commands for v1.3+ boards
+ * Initialize backlight GPIO
+ * CrowPanel uses GPIO1 (GPIO2 is left to NFC SPI MOSI); boards with the
+ * backlight on the CH422G expander are switched on once I2C is up
  */
 static void init_backlight(void)
 {
     ESP_LOGI(TAG, "=== BACKLIGHT INIT START ===");
 
-    // GPIO1 backlight
-    ESP_LOGI(TAG, "Configuring GPIO%d as output...", PIN_BACKLIGHT1);
-    gpio_config_t io_conf1 = {
-        .pin_bit_mask = (1ULL << PIN_BACKLIGHT1),
-        .mode = GPIO_MODE_OUTPUT,
-        .pull_up_en = GPIO_PULLUP_DISABLE,
-        .pull_down_en = GPIO_PULLDOWN_DISABLE,
-        .intr_type = GPIO_INTR_DISABLE,
-    };
-    esp_err_t err1 = gpio_config(&io_conf1);
-    ESP_LOGI(TAG, "GPIO%d config result: %d", PIN_BACKLIGHT1, err1);
-    esp_err_t err1b = gpio_set_level(PIN_BACKLIGHT1, 1);
-    ESP_LOGI(TAG, "GPIO%d set HIGH result: %d", PIN_BACKLIGHT1, err1b);
-
-    // GPIO2 backlight - DISABLED (GPIO2 used for NFC SPI MOSI)
-    // GPIO1 alone is sufficient for backlight control
-    ESP_LOGI(TAG, "GPIO2 skipped (reserved for NFC MOSI)");
+    if (board.backlight_gpio >= 0) {
+        ESP_LOGI(TAG, "Configuring GPIO%ld as output...", (long)board.backlight_gpio);
+        gpio_config_t io_conf = {
+            .pin_bit_mask = (1ULL << board.backlight_gpio),
+            .mode = GPIO_MODE_OUTPUT,
+            .pull_up_en = GPIO_PULLUP_DISABLE,
+            .pull_down_en = GPIO_PULLDOWN_DISABLE,
+            .intr_type = GPIO_INTR_DISABLE,
+        };
+        esp_err_t err = gpio_config(&io_conf);
+        ESP_LOGI(TAG, "GPIO%ld config result: %d", (long)board.backlight_gpio, err);
+        err = gpio_set_level(board.backlight_gpio, 1);
+        ESP_LOGI(TAG, "GPIO%ld set HIGH result: %d", (long)board.backlight_gpio, err);
+    } else {
+        ESP_LOGI(TAG, "No backlight GPIO on this board");
+    }
 
     ESP_LOGI(TAG, "=== BACKLIGHT INIT DONE ===");
 }
 
+/**
+ * Switch on the backlight through the CH422G IO expander
+ * All outputs are driven high, which also leaves the SD card deselected
+ * until the SD driver claims its chip select.
+ */
+static void init_expander_backlight(void)
+{
+    uint8_t oe = CH422G_IO_OE;
+    uint8_t outputs = 0xFF;
+    esp_err_t err = i2c_master_write_to_device(TOUCH_I2C_PORT, CH422G_CMD_SET, &oe, 1, 100);
+    if (err == ESP_OK) {
+        err = i2c_master_write_to_device(TOUCH_I2C_PORT, CH422G_CMD_IO, &outputs, 1, 100);
+    }
+    ESP_LOGI(TAG, "Backlight on (CH422G EXIO%ld): %s", (long)board.backlight_exio,
+             err == ESP_OK ? "OK" : "FAIL");
+}
+
 /**
  * Set backlight brightness (0-100%)
- * Uses I2C to STC8H1K28 at address 0x30
+ * Uses the board's I2C PWM controller (STC8H1K28 at 0x30 on the CrowPanel)
  * Called from Rust via FFI
  */
 void display_set_backlight_hw(uint8_t brightness_percent)
 {
+    if (board.backlight_i2c_addr == 0) {
+        // No PWM controller - the backlight stays on
+        ESP_LOGD(TAG, "Backlight dimming not supported on this board");
+        return;
+    }
+
     // Convert 0-100% to 0-255
     uint8_t hw_brightness = (uint8_t)((brightness_percent * 255) / 100);
 
     // Send to I2C backlight controller
-    esp_err_t err = i2c_master_write_to_device(TOUCH_I2C_PORT, 0x30, &hw_brightness, 1, 100);
+    esp_err_t err = i2c_master_write_to_device(TOUCH_I2C_PORT, board.backlight_i2c_addr, &hw_brightness, 1, 100);
     if (err != ESP_OK) {
         ESP_LOGW(TAG, "Backlight I2C write failed: %d", err);
     }
@@ -350,13 +452,27 @@ static uint32_t tick_get_cb(void)
 /**
  * Initialize display, touch, and LVGL
  */
-int display_init(void)
+int display_init(const display_board_config_t *config)
 {
     ESP_LOGI(TAG, "========================================");
     ESP_LOGI(TAG, "SpoolBuddy Display Driver Init");
     ESP_LOGI(TAG, "LVGL 9.x + EEZ Studio UI");
     ESP_LOGI(TAG, "========================================");
 
+    if (config == NULL || config->width == 0 || config->height == 0 || config->timing.pclk_hz == 0) {
+        ESP_LOGE(TAG, "Invalid board config");
+        return -3;
+    }
+    board = *config;
+    dirty_y1 = board.height;
+
+    // One frame: active area plus sync and porches, rounded up to whole ms
+    uint32_t h_total = board.width + board.timing.hsync_pulse_width +
+                       board.timing.hsync_back_porch + board.timing.hsync_front_porch;
+    uint32_t v_total = board.height + board.timing.vsync_pulse_width +
+                       board.timing.vsync_back_porch + board.timing.vsync_front_porch;
+    frame_period_ms = (uint32_t)(((uint64_t)h_total * v_total * 1000 + board.timing.pclk_hz - 1) / board.timing.pclk_hz);
+
     // Record start time for tick
     tick_start = esp_timer_get_time();
 
@@ -377,7 +493,7 @@ int display_init(void)
         esp_err_t i2c_err;
 
         // Scan I2C bus first
-        ESP_LOGI(TAG, "Scanning I2C bus (GPIO15/16)...");
+        ESP_LOGI(TAG, "Scanning I2C bus (GPIO%ld/%ld)...", (long)board.touch_sda, (long)board.touch_scl);
         for (uint8_t addr = 0x08; addr < 0x78; addr++) {
             uint8_t dummy;
             if (i2c_master_read_from_device(TOUCH_I2C_PORT, addr, &dummy, 1, 10) == ESP_OK) {
@@ -418,9 +534,14 @@ int display_init(void)
             ESP_LOGW(TAG, "  Pico NFC Bridge NOT found at 0x55 (err=%d)", nfc_err);
         }
 
-        // Set backlight via 0x30 (STC8H1K28)
-        i2c_err = i2c_master_write_to_device(TOUCH_I2C_PORT, 0x30, &brightness, 1, 100);
-        ESP_LOGI(TAG, "Backlight set (0x30): %s", i2c_err == ESP_OK ? "OK" : "FAIL");
+        // Set backlight via the PWM controller (STC8H1K28 at 0x30 on the CrowPanel)
+        if (board.backlight_i2c_addr != 0) {
+            i2c_err = i2c_master_write_to_device(TOUCH_I2C_PORT, board.backlight_i2c_addr, &brightness, 1, 100);
+            ESP_LOGI(TAG, "Backlight set (0x%02X): %s", board.backlight_i2c_addr, i2c_err == ESP_OK ? "OK" : "FAIL");
+        }
+        if (board.backlight_exio >= 0) {
+            init_expander_backlight();
+        }
     }
 
     // Initialize RGB panel
@@ -436,7 +557,7 @@ int display_init(void)
     lv_tick_set_cb(tick_get_cb);
 
     // Create display
-    display = lv_display_create(DISPLAY_WIDTH, DISPLAY_HEIGHT);
+    display = lv_display_create(board.width, board.height);
     if (display == NULL) {
         ESP_LOGE(TAG, "Failed to create LVGL display");
         return -2;
@@ -446,12 +567,22 @@ int display_init(void)
     lv_display_set_color_format(display, LV_COLOR_FORMAT_RGB565);
 
     // Set draw buffers
+    size_t draw_buf_size = (size_t)board.width * DRAW_BUF_LINES * 2;  // RGB565 = 2 bytes/pixel
+    draw_buf1 = heap_caps_malloc(draw_buf_size, MALLOC_CAP_INTERNAL | MALLOC_CAP_8BIT);
+    draw_buf2 = heap_caps_malloc(draw_buf_size, MALLOC_CAP_INTERNAL | MALLOC_CAP_8BIT);
+    if (draw_buf1 == NULL || draw_buf2 == NULL) {
+        ESP_LOGE(TAG, "Failed to allocate draw buffers");
+        return -2;
+    }
     lv_display_set_buffers(display, draw_buf1, draw_buf2,
-                           sizeof(draw_buf1), LV_DISPLAY_RENDER_MODE_PARTIAL);
+                           draw_buf_size, LV_DISPLAY_RENDER_MODE_PARTIAL);
 
     // Set flush callback
     lv_display_set_flush_cb(display, flush_cb);
 
+    // Rendering is driven by display_tick() in step with the panel
+    lv_timer_pause(lv_display_get_refr_timer(display));
+
     ESP_LOGI(TAG, "LVGL display created");
 
     // Create touch input device
@@ -472,15 +603,85 @@ int display_init(void)
 }
 
 /**
- * Run LVGL timer handler
+ * Render loop
+ *
+ * LVGL's own refresh timer is paused. Each tick runs input, animations and
+ * UI timers, then renders the dirty regions only once the previous frame has
+ * left the panel (vsync) and a frame period has passed: update -> compose
+ * -> send. Frame times and FPS are measured here and can be shown in an
+ * overlay on the system layer.
  */
 static int tick_count = 0;
-static int flush_before_timer = 0;
+static int flush_before_render = 0;
 
+static uint32_t last_frame_ms = 0;
+static display_frame_stats_t frame_stats = {0};
+
+// FPS is counted over one-second windows
+static uint32_t fps_window_start_ms = 0;
+static uint32_t fps_window_frames = 0;
+static uint32_t fps_window_max_us = 0;
+
+static lv_obj_t *stats_label = NULL;
+
+/**
+ * Update the stats overlay text
+ */
+static void update_stats_overlay(void)
+{
+    if (stats_label == NULL) {
+        return;
+    }
+    lv_label_set_text_fmt(stats_label, "%lu.%lu FPS  %lu ms (max %lu)  panel %lu ms  %lu rows",
+                          (unsigned long)(frame_stats.fps_x10 / 10),
+                          (unsigned long)(frame_stats.fps_x10 % 10),
+                          (unsigned long)(frame_stats.render_us / 1000),
+                          (unsigned long)(frame_stats.max_render_us / 1000),
+                          (unsigned long)(frame_stats.panel_us / 1000),
+                          (unsigned long)refresh_stats.last_dirty_rows);
+}
+
+/**
+ * Render dirty regions if the panel is ready for a new frame
+ */
+static void render_frame(void)
+{
+    uint32_t now = tick_get_cb();
+    if (frame_in_flight || now - last_frame_ms < frame_period_ms) {
+        return;
+    }
+    last_frame_ms = now;
+
+    int flushes = flush_count;
+    int64_t start = esp_timer_get_time();
+    lv_refr_now(display);
+    uint32_t render_us = (uint32_t)(esp_timer_get_time() - start);
+
+    if (flush_count != flushes) {
+        frame_stats.frames++;
+        frame_stats.render_us = render_us;
+        frame_stats.panel_us = last_panel_us;
+        fps_window_frames++;
+        if (render_us > fps_window_max_us) fps_window_max_us = render_us;
+    }
+
+    if (now - fps_window_start_ms >= 1000) {
+        uint32_t elapsed = now - fps_window_start_ms;
+        frame_stats.fps_x10 = fps_window_frames * 10000 / elapsed;
+        frame_stats.max_render_us = fps_window_max_us;
+        fps_window_start_ms = now;
+        fps_window_frames = 0;
+        fps_window_max_us = 0;
+        update_stats_overlay();
+    }
+}
+
+/**
+ * Run UI timers and render the next frame
+ */
 void display_tick(void)
 {
     tick_count++;
-    flush_before_timer = flush_count;
 
     if (tick_count <= 10 || tick_count % 200 == 0) {
         ESP_LOGI(TAG, "tick #%d before lv_timer_handler, flush=%d, active=%p",
@@ -488,21 +689,77 @@ void display_tick(void)
     }
 
     lv_timer_handler();
+    ui_tick();
+
+    if (tick_count <= 10 || tick_count % 200 == 0) {
+        ESP_LOGI(TAG, "tick #%d after ui_tick", tick_count);
+    }
 
-    // Log if any flushes happened during timer_handler
-    int flushes_this_tick = flush_count - flush_before_timer;
+    flush_before_render = flush_count;
+    render_frame();
+
+    // Log if any flushes happened during the render
+    int flushes_this_tick = flush_count - flush_before_render;
     if (tick_count <= 10 || tick_count % 200 == 0 || flushes_this_tick > 0) {
-        ESP_LOGI(TAG, "tick #%d after lv_timer, flush=%d (+%d this tick), active=%p",
+        ESP_LOGI(TAG, "tick #%d after render, flush=%d (+%d this tick), active=%p",
                  tick_count, flush_count, flushes_this_tick, lv_screen_active());
     }
 
-    ui_tick();
+    // Nothing changed for a while - resend the framebuffer so the panel
+    // doesn't fade
+    if (panel_handle != NULL && !frame_in_flight &&
+        tick_get_cb() - last_refresh_ms >= PANEL_KEEPALIVE_MS) {
+        start_refresh(true);
+    }
+}
 
-    if (tick_count <= 10 || tick_count % 200 == 0) {
-        ESP_LOGI(TAG, "tick #%d after ui_tick", tick_count);
+/**
+ * Get render loop statistics
+ */
+void display_get_frame_stats(display_frame_stats_t *stats)
+{
+    if (stats != NULL) {
+        *stats = frame_stats;
     }
 }
 
+/**
+ * Show or hide the frame stats overlay
+ */
+void display_set_stats_overlay(bool enabled)
+{
+    if (enabled && stats_label == NULL) {
+        stats_label = lv_label_create(lv_layer_sys());
+        lv_obj_set_style_bg_color(stats_label, lv_color_black(), 0);
+        lv_obj_set_style_bg_opa(stats_label, LV_OPA_70, 0);
+        lv_obj_set_style_text_color(stats_label, lv_color_white(), 0);
+        lv_obj_set_style_pad_all(stats_label, 4, 0);
+        lv_obj_align(stats_label, LV_ALIGN_BOTTOM_RIGHT, 0, 0);
+        update_stats_overlay();
+    } else if (!enabled && stats_label != NULL) {
+        lv_obj_delete(stats_label);
+        stats_label = NULL;
+    }
+}
+
+/**
+ * Get partial refresh statistics
+ */
+void display_get_refresh_stats(display_refresh_stats_t *stats)
+{
+    if (stats != NULL) {
+        *stats = refresh_stats;
+    }
+}
+
+/**
+ * Get the active board display configuration
+ */
+const display_board_config_t *display_get_config(void)
+{
+    return &board;
+}
+
 /**
  * Get elapsed time in milliseconds
  */
@@ -520,10 +777,13 @@ void display_shutdown(void)
     ESP_LOGI(TAG, "Shutting down display...");
 
     // Turn off backlight first
-    gpio_set_level(PIN_BACKLIGHT1, 0);
+    if (board.backlight_gpio >= 0) {
+        gpio_set_level(board.backlight_gpio, 0);
+    }
 
     // Turn off display
     if (panel_handle != NULL) {
+        wait_frame_done();
         esp_lcd_panel_disp_on_off(panel_handle, false);
         vTaskDelay(pdMS_TO_TICKS(50));
 
@@ -1,6 +1,6 @@
 /**
- * SpoolBuddy Display Driver for CrowPanel Advance 7.0"
- * 800x480 RGB LCD with GT911 touch controller
+ * SpoolBuddy Display Driver
+ * RGB LCD with GT911 touch controller, configured per board
  * Uses LVGL 9.x
  */
 
@@ -14,17 +14,87 @@
 extern "C" {
 #endif
 
+/**
+ * RGB panel timing
+ */
+typedef struct {
+    uint32_t pclk_hz;
+    uint16_t hsync_pulse_width;
+    uint16_t hsync_back_porch;
+    uint16_t hsync_front_porch;
+    uint16_t vsync_pulse_width;
+    uint16_t vsync_back_porch;
+    uint16_t vsync_front_porch;
+    bool pclk_active_neg;       // Sample data on the falling PCLK edge
+} display_panel_timing_t;
+
+/**
+ * RGB565 parallel interface pins
+ */
+typedef struct {
+    int32_t pclk;
+    int32_t hsync;
+    int32_t vsync;
+    int32_t de;
+    int32_t data[16];           // B0-B4, G0-G5, R0-R4
+} display_rgb_pins_t;
+
+/**
+ * Board display configuration
+ * Mirrors DisplayConfig in firmware/core/src/board.rs (presets live there)
+ */
+typedef struct {
+    uint16_t width;
+    uint16_t height;
+    display_panel_timing_t timing;
+    display_rgb_pins_t pins;
+    int32_t touch_sda;
+    int32_t touch_scl;
+    uint8_t touch_addr;
+    int32_t backlight_gpio;     // -1 if none
+    uint8_t backlight_i2c_addr; // PWM backlight controller, 0 if none
+    int32_t backlight_exio;     // CH422G output, -1 if none
+} display_board_config_t;
+
+/**
+ * Partial refresh statistics
+ */
+typedef struct {
+    uint32_t frames;            // Frames sent after UI changes
+    uint32_t keepalive_frames;  // Frames resent while the UI was idle
+    uint32_t last_dirty_rows;   // Scanlines changed in the last UI frame
+    uint32_t bytes_flushed;     // Pixel bytes written to the framebuffer
+} display_refresh_stats_t;
+
+/**
+ * Render loop statistics
+ */
+typedef struct {
+    uint32_t frames;            // Frames rendered since boot
+    uint32_t fps_x10;           // Rendered frames per second x10 (last second)
+    uint32_t render_us;         // Time to compose and flush the last frame
+    uint32_t max_render_us;     // Slowest frame in the last second
+    uint32_t panel_us;          // Time to send the last frame to the panel
+} display_frame_stats_t;
+
 /**
  * Initialize the display, touch, and LVGL
  * This must be called before any LVGL operations
  *
+ * @param config Board display configuration (copied)
  * @return 0 on success, negative on error
  */
-int display_init(void);
+int display_init(const display_board_config_t *config);
+
+/**
+ * Get the active board display configuration
+ */
+const display_board_config_t *display_get_config(void);
 
 /**
- * Run LVGL timer handler
- * Call this periodically (every 5-10ms) from the main loop
+ * Run LVGL timers and render the next frame
+ * Call this periodically (every 5-10ms) from the main loop. Frames are only
+ * rendered once the panel has finished the previous one (vsync).
  */
 void display_tick(void);
 
@@ -36,10 +106,28 @@ uint32_t display_get_tick_ms(void);
 
 /**
  * Set backlight brightness (0-100%)
- * Uses I2C to STC8H1K28 at address 0x30
+ * Uses the board's I2C PWM controller (STC8H1K28 on the CrowPanel) if it has
+ * one, otherwise switches the backlight on or off
  */
 void display_set_backlight_hw(uint8_t brightness_percent);
 
+/**
+ * Get partial refresh statistics
+ * Frames are only sent to the panel when the UI changed, plus a slow
+ * keep-alive refresh while idle
+ */
+void display_get_refresh_stats(display_refresh_stats_t *stats);
+
+/**
+ * Get render loop statistics (frame time, FPS)
+ */
+void display_get_frame_stats(display_frame_stats_t *stats);
+
+/**
+ * Show or hide the FPS/frame-time debug overlay
+ */
+void display_set_stats_overlay(bool enabled);
+
 /**
  * Shutdown display before reboot
  * Properly deinitializes the LCD panel to prevent display shift on soft restart
@@ -13,6 +13,8 @@
 #include "ui_internal.h"
 #include "ui_nfc.h"
 #include "ui_nfc_card.h"
+#include "ui_kiosk.h"
+#include "ui_encode.h"
 #include "ui_status_bar.h"
 #include "screens.h"
 #include "images.h"
@@ -119,6 +121,8 @@ void loadScreen(enum ScreensEnum screenId) {
         case SCREEN_ID_NFC_SCREEN: screen = get_nfc_screen(); break;
         case SCREEN_ID_SCALE_CALIBRATION_SCREEN: screen = get_scale_calibration_screen(); break;
         case SCREEN_ID_KEYBOARD_LAYOUT_SCREEN: screen = get_keyboard_layout_screen(); break;
+        case SCREEN_ID_NOW_PRINTING_SCREEN: screen = get_now_printing_screen(); break;
+        case SCREEN_ID_DIAGNOSTICS_SCREEN: screen = get_diagnostics_screen(); break;
         case SCREEN_ID_SPLASH_SCREEN: screen = get_splash_screen(); break;
         default: screen = getLvglObjectFromIndex(currentScreen); break;
     }
@@ -148,6 +152,18 @@ static void encode_tag_click_handler(lv_event_t *e) {
     ui_nfc_card_show_details();
 }
 
+// Printer panel opens the now printing screen while a print is running
+static void printer_panel_click_handler(lv_event_t *e) {
+    (void)e;
+    BackendPrinterInfo printer = {0};
+    int index = get_selected_printer_index();
+    if (index < 0 || backend_get_printer(index, &printer) != 0 || !printer.connected) return;
+    if (strcmp(printer.gcode_state, "RUNNING") == 0 || strcmp(printer.gcode_state, "PAUSE") == 0 ||
+        strcmp(printer.gcode_state, "PAUSED") == 0) {
+        pendingScreen = SCREEN_ID_NOW_PRINTING_SCREEN;
+    }
+}
+
 static void catalog_click_handler(lv_event_t *e) {
     pendingScreen = SCREEN_ID_SPOOL_DETAILS;
 }
@@ -184,6 +200,8 @@ void navigate_to_settings_detail(const char *title) {
         pendingScreen = SCREEN_ID_SCALE_CALIBRATION_SCREEN;
     } else if (strcmp(title, "Keyboard") == 0) {
         pendingScreen = SCREEN_ID_KEYBOARD_LAYOUT_SCREEN;
+    } else if (strcmp(title, "Diagnostics") == 0) {
+        pendingScreen = SCREEN_ID_DIAGNOSTICS_SCREEN;
     } else {
         // Fallback to main settings screen for unsupported detail pages
         pendingScreen = SCREEN_ID_SETTINGS_SCREEN;
@@ -208,6 +226,11 @@ void wire_main_buttons(void) {
         lv_obj_add_event_cb(objects.main_screen_button_settings, settings_click_handler, LV_EVENT_CLICKED, NULL);
     }
 
+    if (objects.main_screen_printer) {
+        lv_obj_add_flag(objects.main_screen_printer, LV_OBJ_FLAG_CLICKABLE);
+        lv_obj_add_event_cb(objects.main_screen_printer, printer_panel_click_handler, LV_EVENT_CLICKED, NULL);
+    }
+
     // Wire printer selection dropdown
     wire_printer_dropdown();
 
@@ -271,6 +294,8 @@ void delete_all_screens(void) {
     reset_notification_state();  // Clear notification dots before deleting screens
     reset_backend_ui_state();    // Clear all dynamic UI state (AMS widgets, labels, etc.)
     cleanup_hardware_screens();  // Delete programmatic NFC/Scale screens
+    cleanup_now_printing_screen();
+    cleanup_diagnostics_screen();
 
     lv_obj_t **screens[] = {
         &objects.main_screen,
@@ -291,6 +316,33 @@ void delete_all_screens(void) {
     }
 }
 
+// =============================================================================
+// Theme
+// =============================================================================
+
+static uint32_t theme_generation = UINT32_MAX;
+
+// Apply the theme synced from the backend; only restyles when the settings changed.
+// LVGL's default theme has no high-contrast variant, so that mode uses the dark base.
+static void apply_theme(void) {
+    ThemeSettingsC settings;
+    backend_get_theme(&settings);
+    if (settings.generation == theme_generation) {
+        return;
+    }
+    theme_generation = settings.generation;
+
+    lv_display_t *dispp = lv_display_get_default();
+    if (!dispp) {
+        return;
+    }
+    lv_color_t primary = settings.has_accent ? lv_color_hex(settings.accent_rgb) : lv_palette_main(LV_PALETTE_BLUE);
+    bool dark = settings.mode != 0;
+    lv_theme_t *theme = lv_theme_default_init(dispp, primary, lv_palette_main(LV_PALETTE_RED), dark, LV_FONT_DEFAULT);
+    lv_display_set_theme(dispp, theme);
+    UI_LOGI("Theme applied: mode=%d accent=%06lX", settings.mode, (unsigned long)settings.accent_rgb);
+}
+
 // =============================================================================
 // Main Entry Points
 // =============================================================================
@@ -300,11 +352,7 @@ void ui_init() {
     load_printers_from_nvs();
 
     // Initialize theme
-    lv_display_t *dispp = lv_display_get_default();
-    if (dispp) {
-        lv_theme_t *theme = lv_theme_default_init(dispp, lv_palette_main(LV_PALETTE_BLUE), lv_palette_main(LV_PALETTE_RED), true, LV_FONT_DEFAULT);
-        lv_display_set_theme(dispp, theme);
-    }
+    apply_theme();
 
     // Show splash screen first
     create_splash_screen();
@@ -339,11 +387,14 @@ void ui_tick() {
 
         // Clean up status bar before any screen transition
         ui_status_bar_cleanup();
+        ui_kiosk_cleanup();
+        ui_encode_cleanup();
 
         // For programmatic screens, create and load BEFORE deleting old screens
         // This prevents LVGL from having an invalid active screen during transition
         if (screen == SCREEN_ID_NFC_SCREEN || screen == SCREEN_ID_SCALE_CALIBRATION_SCREEN ||
-            screen == SCREEN_ID_KEYBOARD_LAYOUT_SCREEN) {
+            screen == SCREEN_ID_KEYBOARD_LAYOUT_SCREEN || screen == SCREEN_ID_NOW_PRINTING_SCREEN ||
+            screen == SCREEN_ID_DIAGNOSTICS_SCREEN) {
             // Create the new programmatic screen
             if (screen == SCREEN_ID_NFC_SCREEN) {
                 create_nfc_screen();
@@ -351,6 +402,10 @@ void ui_tick() {
                 create_scale_calibration_screen();
             } else if (screen == SCREEN_ID_KEYBOARD_LAYOUT_SCREEN) {
                 create_keyboard_layout_screen();
+            } else if (screen == SCREEN_ID_NOW_PRINTING_SCREEN) {
+                create_now_printing_screen();
+            } else if (screen == SCREEN_ID_DIAGNOSTICS_SCREEN) {
+                create_diagnostics_screen();
             }
             // Load it immediately so LVGL has a valid active screen
             loadScreen(screen);
@@ -456,14 +511,23 @@ void ui_tick() {
         // Update firmware update screen if active
         update_firmware_ui();
 
+        // Pick up theme changes from the backend settings
+        apply_theme();
+
         // Update backend status UI (main screen printer info)
         UI_LOGI("Calling update_backend_ui");
         update_backend_ui();
         UI_LOGI("update_backend_ui returned");
 
         // Update NFC card on main screen and AMS overview (tag popup should appear on both)
+        // Kiosk mode takes over the screen, no tag popup while members check spools in and out
+        // A running tag batch takes over the screen too, and wins over kiosk mode
         if (screen_id == SCREEN_ID_MAIN_SCREEN || screen_id == SCREEN_ID_AMS_OVERVIEW) {
-            ui_nfc_card_update();
+            if (ui_encode_update()) {
+                ui_kiosk_cleanup();
+            } else if (!ui_kiosk_update()) {
+                ui_nfc_card_update();
+            }
             ui_status_bar_update();
         }
 
@@ -483,6 +547,12 @@ void ui_tick() {
         if (screen_id == SCREEN_ID_KEYBOARD_LAYOUT_SCREEN) {
             update_keyboard_layout_screen();
         }
+        if (screen_id == SCREEN_ID_NOW_PRINTING_SCREEN) {
+            update_now_printing_screen();
+        }
+        if (screen_id == SCREEN_ID_DIAGNOSTICS_SCREEN) {
+            update_diagnostics_screen();
+        }
 
         // Update WiFi icon for CURRENT screen only (other screen objects are freed)
         WifiStatus status;
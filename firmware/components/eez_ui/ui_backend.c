@@ -1150,6 +1150,8 @@ static void update_ams_display(void) {
             .tray_count = 1,
             .trays = {{.tray_color = 0}}  // Empty
         };
+        // Spool on the external holder (virtual tray), if any
+        backend_get_vt_tray(selected_printer_index, &ext_info.trays[0]);
 
         if (!is_dual_nozzle) {
             // Single-nozzle: create one "Ext" slot on LEFT side, use active_tray_left
@@ -2071,6 +2073,21 @@ static void update_ams_overview_display(void) {
         // EXT-1 always shown (single or dual nozzle)
         lv_obj_clear_flag(objects.ams_screen_ams_panel_ext_1, LV_OBJ_FLAG_HIDDEN);
 
+        // Show the external spool (virtual tray) material and color
+        AmsTrayCInfo vt_tray = {0};
+        backend_get_vt_tray(selected_printer_index, &vt_tray);
+        bool has_spool = vt_tray.tray_color != 0;
+        if (objects.ams_screen_ams_panel_ext_1_label_empty) {
+            lv_label_set_text(objects.ams_screen_ams_panel_ext_1_label_empty,
+                              has_spool && vt_tray.tray_type[0] ? vt_tray.tray_type : "<empty>");
+        }
+        if (objects.ams_screen_ams_panel_ext_1_icon_empty) {
+            uint32_t rgb = has_spool ? (vt_tray.tray_color >> 8) : 0xffffff;
+            lv_obj_set_style_image_recolor(objects.ams_screen_ams_panel_ext_1_icon_empty, lv_color_hex(rgb), 0);
+            lv_obj_set_style_image_recolor_opa(objects.ams_screen_ams_panel_ext_1_icon_empty,
+                                               has_spool ? 255 : 0, 0);
+        }
+
         if (selected_printer_is_dual_nozzle) {
             // Dual-nozzle: show "R" indicator, label as "EXT-1" at normal position
             update_extruder_indicator(objects.ams_screen_ams_panel_ext_1_indicator, 0);
@@ -0,0 +1,235 @@
+// =============================================================================
+// ui_diagnostics.c - Diagnostics Screen
+// =============================================================================
+// Shows what support usually asks for: uptime, heap and PSRAM usage, WiFi
+// signal, render frame rate, I2C error counters, the NFC bridge firmware and
+// the raw scale readings. Values come from diagnostics_get() (Rust) and are
+// refreshed once a second from ui_tick().
+// =============================================================================
+
+#include "ui_internal.h"
+#include "screens.h"
+#include <stdio.h>
+#include <string.h>
+
+#define COLOR_BG_DARK       0x1a1a1a
+#define COLOR_BG_PANEL      0x2d2d2d
+#define COLOR_BORDER        0x3d3d3d
+#define COLOR_TEXT_PRIMARY  0xffffff
+#define COLOR_TEXT_SECONDARY 0x888888
+#define COLOR_ACCENT_GREEN  0x00ff00
+#define COLOR_ACCENT_YELLOW 0xffff00
+#define COLOR_ACCENT_RED    0xff4444
+
+#define REFRESH_INTERVAL_MS 1000
+
+// Screen objects
+static lv_obj_t *diagnostics_screen = NULL;
+static lv_obj_t *diag_top_bar_icon_back = NULL;
+static lv_obj_t *diag_top_bar_clock = NULL;
+static lv_obj_t *diag_uptime_value = NULL;
+static lv_obj_t *diag_heap_value = NULL;
+static lv_obj_t *diag_psram_value = NULL;
+static lv_obj_t *diag_wifi_value = NULL;
+static lv_obj_t *diag_fps_value = NULL;
+static lv_obj_t *diag_i2c_scale_value = NULL;
+static lv_obj_t *diag_i2c_nfc_value = NULL;
+static lv_obj_t *diag_nfc_bridge_value = NULL;
+static lv_obj_t *diag_scale_raw_value = NULL;
+
+static uint32_t diag_last_refresh = 0;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+static lv_obj_t *create_panel(lv_obj_t *parent, int x, int y, int w, int h, const char *title) {
+    lv_obj_t *panel = lv_obj_create(parent);
+    lv_obj_set_pos(panel, x, y);
+    lv_obj_set_size(panel, w, h);
+    lv_obj_set_style_bg_color(panel, lv_color_hex(COLOR_BG_PANEL), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(panel, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_color(panel, lv_color_hex(COLOR_BORDER), LV_PART_MAIN);
+    lv_obj_set_style_border_width(panel, 1, LV_PART_MAIN);
+    lv_obj_set_style_radius(panel, 12, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(panel, 15, LV_PART_MAIN);
+    lv_obj_clear_flag(panel, LV_OBJ_FLAG_SCROLLABLE);
+
+    lv_obj_t *header = lv_label_create(panel);
+    lv_label_set_text(header, title);
+    lv_obj_set_pos(header, 0, 0);
+    lv_obj_set_style_text_font(header, &lv_font_montserrat_14, LV_PART_MAIN);
+    lv_obj_set_style_text_color(header, lv_color_hex(COLOR_TEXT_SECONDARY), LV_PART_MAIN);
+    return panel;
+}
+
+// Name on the left, value label on the right; returns the value label
+static lv_obj_t *create_info_row(lv_obj_t *panel, int y, const char *name) {
+    lv_obj_t *label = lv_label_create(panel);
+    lv_label_set_text(label, name);
+    lv_obj_set_pos(label, 0, y);
+    lv_obj_set_style_text_font(label, &lv_font_montserrat_16, LV_PART_MAIN);
+    lv_obj_set_style_text_color(label, lv_color_hex(COLOR_TEXT_SECONDARY), LV_PART_MAIN);
+
+    lv_obj_t *value = lv_label_create(panel);
+    lv_label_set_text(value, "--");
+    lv_obj_align(value, LV_ALIGN_TOP_RIGHT, 0, y);
+    lv_obj_set_style_text_font(value, &lv_font_montserrat_16, LV_PART_MAIN);
+    lv_obj_set_style_text_color(value, lv_color_hex(COLOR_TEXT_PRIMARY), LV_PART_MAIN);
+    return value;
+}
+
+static void set_value(lv_obj_t *label, const char *text, uint32_t color) {
+    lv_label_set_text(label, text);
+    lv_obj_set_style_text_color(label, lv_color_hex(color), LV_PART_MAIN);
+}
+
+// Green when clean, yellow once anything went wrong
+static void set_i2c_counters(lv_obj_t *label, uint32_t errors, uint32_t timeouts) {
+    char buf[48];
+    snprintf(buf, sizeof(buf), "%lu errors, %lu timeouts", (unsigned long)errors, (unsigned long)timeouts);
+    set_value(label, buf, errors || timeouts ? COLOR_ACCENT_YELLOW : COLOR_ACCENT_GREEN);
+}
+
+// =============================================================================
+// Event Handlers
+// =============================================================================
+
+static void back_handler(lv_event_t *e) {
+    (void)e;
+    pending_settings_tab = 3;  // System tab
+    pendingScreen = SCREEN_ID_SETTINGS_SCREEN;
+}
+
+// =============================================================================
+// Screen Lifecycle
+// =============================================================================
+
+void create_diagnostics_screen(void) {
+    if (diagnostics_screen) return;
+
+    diagnostics_screen = lv_obj_create(NULL);
+    lv_obj_set_size(diagnostics_screen, 800, 480);
+    lv_obj_set_style_bg_color(diagnostics_screen, lv_color_hex(COLOR_BG_DARK), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(diagnostics_screen, 255, LV_PART_MAIN);
+    lv_obj_clear_flag(diagnostics_screen, LV_OBJ_FLAG_SCROLLABLE);
+
+    create_top_bar(diagnostics_screen, "Diagnostics", &diag_top_bar_icon_back, &diag_top_bar_clock);
+    lv_obj_add_event_cb(diag_top_bar_icon_back, back_handler, LV_EVENT_CLICKED, NULL);
+
+    // System panel: uptime, memory, WiFi and render loop
+    lv_obj_t *system = create_panel(diagnostics_screen, 15, 59, 770, 200, "SYSTEM");
+    diag_uptime_value = create_info_row(system, 28, "Uptime");
+    diag_heap_value = create_info_row(system, 58, "Heap");
+    diag_psram_value = create_info_row(system, 88, "PSRAM");
+    diag_wifi_value = create_info_row(system, 118, "WiFi signal");
+    diag_fps_value = create_info_row(system, 148, "Frame rate");
+
+    // Peripherals panel: shared I2C bus, NFC bridge and scale
+    lv_obj_t *peripherals = create_panel(diagnostics_screen, 15, 269, 770, 170, "PERIPHERALS");
+    diag_i2c_scale_value = create_info_row(peripherals, 28, "I2C scale");
+    diag_i2c_nfc_value = create_info_row(peripherals, 58, "I2C NFC");
+    diag_nfc_bridge_value = create_info_row(peripherals, 88, "NFC bridge firmware");
+    diag_scale_raw_value = create_info_row(peripherals, 118, "Scale raw");
+
+    diag_last_refresh = 0;
+    update_diagnostics_screen();
+}
+
+lv_obj_t *get_diagnostics_screen(void) {
+    return diagnostics_screen;
+}
+
+void update_diagnostics_screen(void) {
+    if (!diagnostics_screen || lv_scr_act() != diagnostics_screen) return;
+
+    // Collecting touches every subsystem, so don't do it every tick
+    uint32_t now = lv_tick_get();
+    if (diag_last_refresh != 0 && now - diag_last_refresh < REFRESH_INTERVAL_MS) return;
+    diag_last_refresh = now ? now : 1;
+
+    char buf[64];
+
+    // Clock
+    int time_hhmm = time_get_hhmm();
+    if (diag_top_bar_clock && time_hhmm >= 0) {
+        snprintf(buf, sizeof(buf), "%02d:%02d", (time_hhmm >> 8) & 0xFF, time_hhmm & 0xFF);
+        lv_label_set_text(diag_top_bar_clock, buf);
+    }
+
+    DeviceDiagnosticsC d = {0};
+    diagnostics_get(&d);
+
+    uint32_t days = d.uptime_s / 86400;
+    uint32_t hours = (d.uptime_s / 3600) % 24;
+    uint32_t mins = (d.uptime_s / 60) % 60;
+    if (days > 0) {
+        snprintf(buf, sizeof(buf), "%lud %luh %lum", (unsigned long)days, (unsigned long)hours, (unsigned long)mins);
+    } else {
+        snprintf(buf, sizeof(buf), "%luh %lum %lus", (unsigned long)hours, (unsigned long)mins,
+                 (unsigned long)(d.uptime_s % 60));
+    }
+    lv_label_set_text(diag_uptime_value, buf);
+
+    snprintf(buf, sizeof(buf), "%lu / %lu KB free (min %lu)", (unsigned long)(d.heap_free / 1024),
+             (unsigned long)(d.heap_total / 1024), (unsigned long)(d.heap_min_free / 1024));
+    lv_label_set_text(diag_heap_value, buf);
+
+    if (d.psram_total > 0) {
+        snprintf(buf, sizeof(buf), "%lu / %lu KB free", (unsigned long)(d.psram_free / 1024),
+                 (unsigned long)(d.psram_total / 1024));
+        set_value(diag_psram_value, buf, COLOR_TEXT_PRIMARY);
+    } else {
+        set_value(diag_psram_value, "Not present", COLOR_TEXT_SECONDARY);
+    }
+
+    if (d.wifi_rssi != 0) {
+        snprintf(buf, sizeof(buf), "%d dBm", d.wifi_rssi);
+        set_value(diag_wifi_value, buf, d.wifi_rssi >= -67 ? COLOR_ACCENT_GREEN :
+                                        d.wifi_rssi >= -80 ? COLOR_ACCENT_YELLOW : COLOR_ACCENT_RED);
+    } else {
+        set_value(diag_wifi_value, "Disconnected", COLOR_TEXT_SECONDARY);
+    }
+
+    snprintf(buf, sizeof(buf), "%lu.%lu fps", (unsigned long)(d.fps_x10 / 10), (unsigned long)(d.fps_x10 % 10));
+    lv_label_set_text(diag_fps_value, buf);
+
+    set_i2c_counters(diag_i2c_scale_value, d.i2c_scale_errors, d.i2c_scale_timeouts);
+    set_i2c_counters(diag_i2c_nfc_value, d.i2c_nfc_errors, d.i2c_nfc_timeouts);
+
+    if (d.nfc_bridge_firmware[0]) {
+        snprintf(buf, sizeof(buf), "v%.7s", d.nfc_bridge_firmware);
+        set_value(diag_nfc_bridge_value, buf, COLOR_TEXT_PRIMARY);
+    } else {
+        set_value(diag_nfc_bridge_value, "No bridge", COLOR_TEXT_SECONDARY);
+    }
+
+    if (d.scale_channels == 0) {
+        set_value(diag_scale_raw_value, "No reading", COLOR_TEXT_SECONDARY);
+    } else if (d.scale_channels == 1) {
+        snprintf(buf, sizeof(buf), "%ld", (long)d.scale_raw[0]);
+        set_value(diag_scale_raw_value, buf, COLOR_TEXT_PRIMARY);
+    } else {
+        snprintf(buf, sizeof(buf), "%ld / %ld", (long)d.scale_raw[0], (long)d.scale_raw[1]);
+        set_value(diag_scale_raw_value, buf, COLOR_TEXT_PRIMARY);
+    }
+}
+
+void cleanup_diagnostics_screen(void) {
+    // Delete only when not active (during transition TO this screen it is loaded first)
+    if (!diagnostics_screen || diagnostics_screen == lv_scr_act()) return;
+
+    lv_obj_delete(diagnostics_screen);
+    diagnostics_screen = NULL;
+    diag_top_bar_icon_back = NULL;
+    diag_top_bar_clock = NULL;
+    diag_uptime_value = NULL;
+    diag_heap_value = NULL;
+    diag_psram_value = NULL;
+    diag_wifi_value = NULL;
+    diag_fps_value = NULL;
+    diag_i2c_scale_value = NULL;
+    diag_i2c_nfc_value = NULL;
+    diag_nfc_bridge_value = NULL;
+    diag_scale_raw_value = NULL;
+}
@@ -0,0 +1,127 @@
+/**
+ * Encode UI - Batch tag encoding screen
+ * While a tag batch job runs the device writes one spool tag after another;
+ * this screen says which spool's tag to place and how the last write went
+ */
+
+#include "ui_encode.h"
+#include "ui_internal.h"
+#include "lvgl.h"
+#include <stdio.h>
+#include <string.h>
+#include "esp_log.h"
+
+static const char *TAG = "ui_encode";
+
+// Static state
+static lv_obj_t *encode_overlay = NULL;
+static lv_obj_t *encode_card = NULL;
+static lv_obj_t *encode_progress_label = NULL;
+static lv_obj_t *encode_bar = NULL;
+static lv_obj_t *encode_spool_label = NULL;
+static lv_obj_t *encode_message_label = NULL;
+static EncodeStateC last_state;
+
+static lv_obj_t *create_label(lv_obj_t *parent, const lv_font_t *font, uint32_t color, lv_coord_t y) {
+    lv_obj_t *label = lv_label_create(parent);
+    lv_label_set_text(label, "");
+    lv_obj_set_width(label, 520);
+    lv_label_set_long_mode(label, LV_LABEL_LONG_WRAP);
+    lv_obj_set_style_text_font(label, font, LV_PART_MAIN);
+    lv_obj_set_style_text_color(label, lv_color_hex(color), LV_PART_MAIN);
+    lv_obj_set_style_text_align(label, LV_TEXT_ALIGN_CENTER, LV_PART_MAIN);
+    lv_obj_align(label, LV_ALIGN_TOP_MID, 0, y);
+    return label;
+}
+
+static void create_overlay(void) {
+    ESP_LOGI(TAG, "Tag batch running, showing encoding screen");
+
+    // Full-screen overlay on the top layer, above the main screen and tag popups
+    encode_overlay = lv_obj_create(lv_layer_top());
+    lv_obj_set_size(encode_overlay, 800, 480);
+    lv_obj_set_pos(encode_overlay, 0, 0);
+    lv_obj_set_style_bg_color(encode_overlay, lv_color_hex(0x000000), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(encode_overlay, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_width(encode_overlay, 0, LV_PART_MAIN);
+    lv_obj_set_style_radius(encode_overlay, 0, LV_PART_MAIN);
+    lv_obj_clear_flag(encode_overlay, LV_OBJ_FLAG_SCROLLABLE);
+
+    lv_obj_t *title = lv_label_create(encode_overlay);
+    lv_label_set_text(title, "Encoding Tags");
+    lv_obj_set_style_text_font(title, &lv_font_montserrat_20, LV_PART_MAIN);
+    lv_obj_set_style_text_color(title, lv_color_hex(0x888888), LV_PART_MAIN);
+    lv_obj_align(title, LV_ALIGN_TOP_MID, 0, 20);
+
+    encode_card = lv_obj_create(encode_overlay);
+    lv_obj_set_size(encode_card, 600, 340);
+    lv_obj_align(encode_card, LV_ALIGN_CENTER, 0, 20);
+    lv_obj_set_style_bg_color(encode_card, lv_color_hex(0x1a1a1a), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(encode_card, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_width(encode_card, 2, LV_PART_MAIN);
+    lv_obj_set_style_radius(encode_card, 12, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(encode_card, 20, LV_PART_MAIN);
+    lv_obj_clear_flag(encode_card, LV_OBJ_FLAG_SCROLLABLE);
+
+    encode_progress_label = create_label(encode_card, &lv_font_montserrat_16, 0xAAAAAA, 0);
+    encode_bar = lv_bar_create(encode_card);
+    lv_obj_set_size(encode_bar, 520, 12);
+    lv_obj_align(encode_bar, LV_ALIGN_TOP_MID, 0, 35);
+    lv_obj_set_style_bg_color(encode_bar, lv_color_hex(0x333333), LV_PART_MAIN);
+    lv_obj_set_style_bg_color(encode_bar, lv_color_hex(0x4CAF50), LV_PART_INDICATOR);
+    encode_spool_label = create_label(encode_card, &lv_font_montserrat_28, 0xFFFFFF, 80);
+    encode_message_label = create_label(encode_card, &lv_font_montserrat_20, 0xCCCCCC, 160);
+
+    // Force the first update to fill every label
+    memset(&last_state, 0, sizeof(last_state));
+    last_state.index = -1;
+}
+
+static void update_overlay(const EncodeStateC *state) {
+    if (state->index != last_state.index || state->total != last_state.total) {
+        char text[32];
+        snprintf(text, sizeof(text), "Tag %d of %d", state->index, state->total);
+        lv_label_set_text(encode_progress_label, text);
+        lv_bar_set_range(encode_bar, 0, state->total > 0 ? state->total : 1);
+        lv_bar_set_value(encode_bar, state->index - 1, LV_ANIM_OFF);
+    }
+    if (state->writing != last_state.writing || last_state.index == -1) {
+        // Blue while a tag is being written, white while waiting for one
+        uint32_t color = state->writing ? 0x2196F3 : 0xFFFFFF;
+        lv_obj_set_style_border_color(encode_card, lv_color_hex(color), LV_PART_MAIN);
+    }
+    if (strcmp(state->label, last_state.label) != 0) {
+        lv_label_set_text(encode_spool_label, state->label);
+    }
+    if (strcmp(state->message, last_state.message) != 0) {
+        lv_label_set_text(encode_message_label, state->message);
+    }
+    last_state = *state;
+}
+
+bool ui_encode_update(void) {
+    EncodeStateC state;
+    if (!encode_station_get_state(&state)) {
+        ui_encode_cleanup();
+        return false;
+    }
+
+    if (!encode_overlay) {
+        create_overlay();
+    }
+    update_overlay(&state);
+    return true;
+}
+
+void ui_encode_cleanup(void) {
+    if (encode_overlay) {
+        ESP_LOGI(TAG, "Hiding encoding screen");
+        lv_obj_delete(encode_overlay);
+        encode_overlay = NULL;
+        encode_card = NULL;
+        encode_progress_label = NULL;
+        encode_bar = NULL;
+        encode_spool_label = NULL;
+        encode_message_label = NULL;
+    }
+}
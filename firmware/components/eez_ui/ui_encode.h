@@ -0,0 +1,22 @@
+/**
+ * Encode UI - Batch tag encoding screen
+ */
+
+#ifndef UI_ENCODE_H
+#define UI_ENCODE_H
+
+#include <stdbool.h>
+
+/**
+ * Show, update or hide the encoding screen while a tag batch runs
+ * Call this periodically when main screen is active
+ * Returns true while the encoding screen is shown
+ */
+bool ui_encode_update(void);
+
+/**
+ * Remove the encoding screen (call when leaving main screen)
+ */
+void ui_encode_cleanup(void);
+
+#endif // UI_ENCODE_H
@@ -42,6 +42,7 @@ static int splash_stage = 0;  // Animation stage
 static lv_obj_t *nfc_screen = NULL;
 static lv_obj_t *nfc_screen_top_bar_icon_back = NULL;
 static lv_obj_t *nfc_screen_top_bar_clock = NULL;
+static lv_obj_t *nfc_screen_chip_value = NULL;
 static lv_obj_t *nfc_screen_status_value = NULL;
 static lv_obj_t *nfc_screen_uid_value = NULL;
 static lv_obj_t *nfc_screen_tag_type_value = NULL;
@@ -202,7 +203,7 @@ void cleanup_splash_screen(void) {
 // Helper: Create Standard Top Bar
 // =============================================================================
 
-static lv_obj_t *create_top_bar(lv_obj_t *parent, const char *title, lv_obj_t **back_btn_out, lv_obj_t **clock_out) {
+lv_obj_t *create_top_bar(lv_obj_t *parent, const char *title, lv_obj_t **back_btn_out, lv_obj_t **clock_out) {
     // Top bar container
     lv_obj_t *top_bar = lv_obj_create(parent);
     lv_obj_set_pos(top_bar, 0, 0);
@@ -362,10 +363,9 @@ void create_nfc_screen(void) {
     lv_obj_set_style_text_color(panel_title, lv_color_hex(COLOR_ACCENT_GREEN), LV_PART_MAIN);
     lv_obj_set_pos(panel_title, 0, 0);
 
-    // Chip row
-    lv_obj_t *chip_value;
-    create_info_row(panel, 35, "Chip", &chip_value);
-    lv_label_set_text(chip_value, "PN5180");
+    // Chip row (bridge firmware version filled in by update_nfc_screen)
+    create_info_row(panel, 35, "Chip", &nfc_screen_chip_value);
+    lv_label_set_text(nfc_screen_chip_value, "PN5180");
 
     // Status row
     create_info_row(panel, 90, "Status", &nfc_screen_status_value);
@@ -837,12 +837,31 @@ void update_nfc_screen(void) {
 
     bool initialized = nfc_is_initialized();
     bool tag_present = nfc_tag_present();
+    NfcBridgeHealth bridge;
+    bool has_bridge = nfc_get_bridge_health(&bridge);
+
+    if (nfc_screen_chip_value && has_bridge) {
+        char chip_str[32];
+        snprintf(chip_str, sizeof(chip_str), "PN5180 via Pico v%d.%d", bridge.firmware_major, bridge.firmware_minor);
+        lv_label_set_text(nfc_screen_chip_value, chip_str);
+    }
 
     // Update status
     if (nfc_screen_status_value) {
         if (!initialized) {
             lv_label_set_text(nfc_screen_status_value, "Not Initialized");
             lv_obj_set_style_text_color(nfc_screen_status_value, lv_color_hex(COLOR_ACCENT_RED), LV_PART_MAIN);
+        } else if (has_bridge && bridge.status == NFC_BRIDGE_HUNG) {
+            lv_label_set_text(nfc_screen_status_value, "Bridge Not Responding");
+            lv_obj_set_style_text_color(nfc_screen_status_value, lv_color_hex(COLOR_ACCENT_RED), LV_PART_MAIN);
+        } else if (has_bridge && bridge.status == NFC_BRIDGE_RECOVERING) {
+            lv_label_set_text(nfc_screen_status_value, "Resetting Bridge...");
+            lv_obj_set_style_text_color(nfc_screen_status_value, lv_color_hex(COLOR_ACCENT_YELLOW), LV_PART_MAIN);
+        } else if (has_bridge && bridge.status == NFC_BRIDGE_DEGRADED) {
+            char status_str[48];
+            snprintf(status_str, sizeof(status_str), "Bridge Errors (%lu)", (unsigned long)bridge.consecutive_failures);
+            lv_label_set_text(nfc_screen_status_value, status_str);
+            lv_obj_set_style_text_color(nfc_screen_status_value, lv_color_hex(COLOR_ACCENT_YELLOW), LV_PART_MAIN);
         } else if (tag_present) {
             lv_label_set_text(nfc_screen_status_value, "Tag Detected");
             lv_obj_set_style_text_color(nfc_screen_status_value, lv_color_hex(COLOR_ACCENT_GREEN), LV_PART_MAIN);
@@ -1350,6 +1369,7 @@ void cleanup_hardware_screens(void) {
     if (!nfc_screen) {
         nfc_screen_top_bar_icon_back = NULL;
         nfc_screen_top_bar_clock = NULL;
+        nfc_screen_chip_value = NULL;
         nfc_screen_status_value = NULL;
         nfc_screen_uid_value = NULL;
         nfc_screen_tag_type_value = NULL;
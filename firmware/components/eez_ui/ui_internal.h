@@ -131,16 +131,96 @@ typedef struct {
 extern int backend_get_ams_count(int printer_index);
 extern int backend_get_ams_unit(int printer_index, int ams_index, AmsUnitCInfo *info);
 extern int backend_get_ams_tray(int printer_index, int ams_index, int tray_index, AmsTrayInfo *info);
+extern int backend_get_vt_tray(int printer_index, AmsTrayCInfo *info);  // External spool holder
 extern int backend_get_tray_now(int printer_index);
 extern int backend_get_tray_now_left(int printer_index);
 extern int backend_get_tray_now_right(int printer_index);
 extern int backend_get_active_extruder(int printer_index);  // -1=unknown, 0=right, 1=left
 
+// Spool being consumed by the current print (must match Rust ActiveSpoolCInfo)
+typedef struct {
+    char material[16];      // Material type (e.g., "PLA")
+    char color_name[32];    // Color name, empty if unknown
+    uint32_t color_rgba;    // RGBA packed (0xRRGGBBAA)
+    int remaining_grams;    // -1 if unknown
+    int ams_id;             // AMS unit ID (254/255 = external)
+    int tray_id;            // Tray within the AMS unit
+    int8_t extruder;        // -1 on single-nozzle printers, 0=right, 1=left
+} ActiveSpoolCInfo;
+
+// Layer progress and spools of the current print (must match Rust PrintJobCInfo)
+typedef struct {
+    int layer_num;          // -1 if not reported
+    int total_layer_num;    // -1 if not reported
+    uint8_t spool_count;    // Number of valid entries in spools
+    ActiveSpoolCInfo spools[2];
+} PrintJobCInfo;
+
+extern int backend_get_print_job(int printer_index, PrintJobCInfo *info);
+
 // Time manager functions (implemented in Rust)
 // Returns hour in upper 8 bits, minute in lower 8 bits, or -1 if not synced
 extern int time_get_hhmm(void);
 extern int time_is_synced(void);
 
+// Pico NFC bridge health (implemented in Rust, mirrors BridgeReport)
+typedef enum {
+    NFC_BRIDGE_OK = 0,
+    NFC_BRIDGE_DEGRADED = 1,    // Some recent commands failed
+    NFC_BRIDGE_HUNG = 2,        // Not answering, waiting for a reset
+    NFC_BRIDGE_RECOVERING = 3,  // Reset issued, Pico booting
+} NfcBridgeStatus;
+
+typedef struct NfcBridgeHealth {
+    uint8_t status;             // NfcBridgeStatus
+    uint8_t firmware_major;
+    uint8_t firmware_minor;
+    uint32_t consecutive_failures;
+    uint32_t total_failures;
+    uint32_t garbage_responses;
+    uint32_t resets;
+    uint32_t last_ok_age_s;     // Seconds since the last good response
+} NfcBridgeHealth;
+
+// False if there's no Pico bridge (e.g. PN5180 wired directly on SPI)
+extern bool nfc_get_bridge_health(NfcBridgeHealth *out);
+
+// Device diagnostics (implemented in Rust, mirrors DeviceDiagnosticsC)
+typedef struct DeviceDiagnosticsC {
+    uint32_t uptime_s;
+    uint32_t heap_free;
+    uint32_t heap_total;
+    uint32_t heap_min_free;
+    uint32_t psram_free;        // 0 on boards without PSRAM
+    uint32_t psram_total;
+    int8_t wifi_rssi;           // 0 while WiFi is disconnected
+    uint32_t i2c_scale_errors;
+    uint32_t i2c_scale_timeouts;
+    uint32_t i2c_nfc_errors;
+    uint32_t i2c_nfc_timeouts;
+    char nfc_bridge_firmware[8]; // "major.minor", empty without a Pico bridge
+    uint8_t scale_channels;
+    int32_t scale_raw[2];
+    uint32_t fps_x10;
+} DeviceDiagnosticsC;
+
+extern void diagnostics_get(DeviceDiagnosticsC *out);
+
+// Buzzer feedback (implemented in Rust)
+typedef enum {
+    BUZZER_TAG_DETECTED = 0,
+    BUZZER_WEIGHT_STABLE = 1,
+    BUZZER_ERROR = 2,
+    BUZZER_LOW_FILAMENT = 3,
+} BuzzerPattern;
+
+extern void buzzer_play(uint8_t pattern);
+// Mute state is synced with the backend's device sound settings
+extern void buzzer_set_muted(bool muted);
+extern bool buzzer_is_muted(void);
+// False if no buzzer GPIO is configured
+extern bool buzzer_is_available(void);
+
 // OTA manager functions (implemented in Rust)
 // Returns 1 if update available, 0 otherwise
 extern int ota_is_update_available(void);
@@ -202,6 +282,62 @@ extern bool spool_sync_weight(const char *spool_id, int weight);
 // Check if a spool with given tag_id exists in inventory
 extern bool spool_exists_by_tag(const char *tag_id);
 
+// Reply to the last device state update (must match Rust DeviceStateC struct exactly)
+typedef struct {
+    bool valid;             // True if the tag resolved to an inventory spool
+    char spool_id[64];      // Spool UUID
+    int spool_number;       // -1 if unknown
+    char name[64];          // e.g. "Bambu Lab PLA Basic"
+    char color_name[32];    // Color name
+    uint32_t color_rgba;    // RGBA packed color
+    int remaining_g;        // Net filament left in grams, -1 if unknown
+    int remaining_percent;  // 0-100, -1 if unknown
+    bool low_stock;         // Below the low stock threshold
+    int action;             // 0=none, 1=add spool, 2=update weight, 3=reorder
+    char prompt[32];        // Text for the suggested action, empty if none
+} DeviceStateC;
+
+// Spool and suggested action resolved by the backend for the tag on the scale
+extern bool backend_get_device_state(DeviceStateC *state);
+
+// Kiosk screen from the last device state reply (must match Rust KioskStateC struct exactly)
+typedef struct {
+    bool active;            // True while kiosk mode is on
+    int state;              // 0=scan badge, 1=scan spool, 2=checked out, 3=checked in, 4=unknown tag
+    char member_name[48];   // Signed-in member, empty if none
+    char spool_name[64];    // Spool just checked out or in
+    char message[64];       // Instruction or result to show
+    int weight_used;        // Grams used while checked out, -1 if not a check-in
+    int expires_in;         // Seconds until the member is signed out, -1 if nobody is signed in
+} KioskStateC;
+
+// Kiosk screen to show, false unless kiosk mode is on
+extern bool backend_get_kiosk_state(KioskStateC *state);
+
+// Batch tag encoding screen (must match Rust EncodeStateC struct exactly)
+typedef struct {
+    bool active;            // True while a batch encoding job is running
+    int index;              // 1-based position of the tag in the batch
+    int total;              // Tags in the batch
+    char label[48];         // Spool to encode, e.g. "Red PETG"
+    char message[96];       // Prompt or result of the last attempt
+    bool writing;           // Data for the tag on the reader is ready to write
+} EncodeStateC;
+
+// Encoding screen to show, false unless a tag batch is running
+extern bool encode_station_get_state(EncodeStateC *state);
+
+// Theme synced from the backend settings (must match Rust ThemeSettingsC struct exactly)
+typedef struct {
+    int mode;               // 0=light, 1=dark, 2=high contrast
+    bool has_accent;        // False to use the theme's own accent color
+    uint32_t accent_rgb;    // User accent color, 0xRRGGBB
+    uint32_t generation;    // Changes whenever the settings change
+} ThemeSettingsC;
+
+// Current theme settings
+extern void backend_get_theme(ThemeSettingsC *theme);
+
 // Add a new spool to inventory
 extern bool spool_add_to_inventory(const char *tag_id, const char *vendor, const char *material,
                                     const char *subtype, const char *color_name, uint32_t color_rgba,
@@ -230,6 +366,10 @@ extern int spool_get_untagged_count(void);
 // Returns: 0 = success, -1 = connection error, or HTTP status code (e.g., 409 = already assigned)
 extern int spool_link_tag(const char *spool_id, const char *tag_id, const char *tag_type);
 
+// Move an NFC tag to a spool, taking it off the spool that has it (the other spool keeps its history)
+// Returns: 0 = success, -1 = connection error, or HTTP status code
+extern int spool_move_tag(const char *spool_id, const char *tag_id, const char *tag_type);
+
 // =============================================================================
 // AMS Slot Configuration API (for Configure Slot modal)
 // =============================================================================
@@ -300,6 +440,10 @@ extern bool backend_set_slot_calibration(const char *printer_serial, int ams_id,
 // Returns true on success
 extern bool backend_reset_slot(const char *printer_serial, int ams_id, int tray_id);
 
+// Pause, resume or stop the current print (action: "pause", "resume", "stop")
+// Returns true on success
+extern bool backend_print_control(const char *printer_serial, const char *action);
+
 // Search color catalog by manufacturer and/or material
 // Returns number of colors found (up to max_count), -1 on error
 extern int backend_search_colors(const char *manufacturer, const char *material,
@@ -314,6 +458,8 @@ extern int backend_search_colors(const char *manufacturer, const char *material,
 #define SCREEN_ID_SCALE_CALIBRATION_SCREEN 102
 #define SCREEN_ID_SPLASH_SCREEN 103
 #define SCREEN_ID_KEYBOARD_LAYOUT_SCREEN 104
+#define SCREEN_ID_NOW_PRINTING_SCREEN 105
+#define SCREEN_ID_DIAGNOSTICS_SCREEN 106
 
 // =============================================================================
 // Shared Global Variables (defined in ui_core.c)
@@ -412,6 +558,7 @@ void update_scale_calibration_screen(void);
 void update_keyboard_layout_screen(void);
 void cleanup_hardware_screens(void);
 void cleanup_splash_screen(void);
+lv_obj_t *create_top_bar(lv_obj_t *parent, const char *title, lv_obj_t **back_btn_out, lv_obj_t **clock_out);
 
 // Keyboard layout types
 typedef enum {
@@ -427,6 +574,24 @@ KeyboardLayout get_keyboard_layout(void);
 // Save keyboard layout to NVS
 void save_keyboard_layout(KeyboardLayout layout);
 
+// =============================================================================
+// Module Functions - ui_now_printing.c
+// =============================================================================
+
+void create_now_printing_screen(void);
+lv_obj_t *get_now_printing_screen(void);
+void update_now_printing_screen(void);
+void cleanup_now_printing_screen(void);
+
+// =============================================================================
+// Module Functions - ui_diagnostics.c
+// =============================================================================
+
+void create_diagnostics_screen(void);
+lv_obj_t *get_diagnostics_screen(void);
+void update_diagnostics_screen(void);
+void cleanup_diagnostics_screen(void);
+
 // =============================================================================
 // Module Functions - ui_backend.c
 // =============================================================================
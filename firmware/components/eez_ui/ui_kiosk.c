@@ -0,0 +1,167 @@
+/**
+ * Kiosk UI - Check-in/check-out screen for shared filament
+ * With kiosk mode on, members scan their badge and then the spools they take
+ * or bring back; the backend runs the flow and this screen shows each step
+ */
+
+#include "ui_kiosk.h"
+#include "ui_internal.h"
+#include "lvgl.h"
+#include <stdio.h>
+#include <string.h>
+#include "esp_log.h"
+
+static const char *TAG = "ui_kiosk";
+
+enum {
+    KIOSK_SCAN_BADGE = 0,
+    KIOSK_SCAN_SPOOL = 1,
+    KIOSK_CHECKED_OUT = 2,
+    KIOSK_CHECKED_IN = 3,
+    KIOSK_UNKNOWN_TAG = 4,
+};
+
+// Static state
+static lv_obj_t *kiosk_overlay = NULL;
+static lv_obj_t *kiosk_card = NULL;
+static lv_obj_t *kiosk_icon = NULL;
+static lv_obj_t *kiosk_member_label = NULL;
+static lv_obj_t *kiosk_message_label = NULL;
+static lv_obj_t *kiosk_spool_label = NULL;
+static lv_obj_t *kiosk_expires_label = NULL;
+static KioskStateC last_state;
+
+static uint32_t state_color(int state) {
+    switch (state) {
+        case KIOSK_CHECKED_OUT: return 0x2196F3;  // Blue
+        case KIOSK_CHECKED_IN: return 0x4CAF50;   // Green
+        case KIOSK_UNKNOWN_TAG: return 0xFF9800;  // Orange
+        default: return 0xFFFFFF;
+    }
+}
+
+static const char *state_symbol(int state) {
+    switch (state) {
+        case KIOSK_SCAN_SPOOL: return LV_SYMBOL_DOWNLOAD;
+        case KIOSK_CHECKED_OUT: return LV_SYMBOL_UPLOAD;
+        case KIOSK_CHECKED_IN: return LV_SYMBOL_OK;
+        case KIOSK_UNKNOWN_TAG: return LV_SYMBOL_WARNING;
+        default: return LV_SYMBOL_EYE_OPEN;
+    }
+}
+
+static lv_obj_t *create_label(lv_obj_t *parent, const lv_font_t *font, uint32_t color, lv_coord_t y) {
+    lv_obj_t *label = lv_label_create(parent);
+    lv_label_set_text(label, "");
+    lv_obj_set_width(label, 520);
+    lv_label_set_long_mode(label, LV_LABEL_LONG_WRAP);
+    lv_obj_set_style_text_font(label, font, LV_PART_MAIN);
+    lv_obj_set_style_text_color(label, lv_color_hex(color), LV_PART_MAIN);
+    lv_obj_set_style_text_align(label, LV_TEXT_ALIGN_CENTER, LV_PART_MAIN);
+    lv_obj_align(label, LV_ALIGN_TOP_MID, 0, y);
+    return label;
+}
+
+static void create_overlay(void) {
+    ESP_LOGI(TAG, "Kiosk mode on, showing kiosk screen");
+
+    // Full-screen overlay on the top layer, above the main screen and tag popups
+    kiosk_overlay = lv_obj_create(lv_layer_top());
+    lv_obj_set_size(kiosk_overlay, 800, 480);
+    lv_obj_set_pos(kiosk_overlay, 0, 0);
+    lv_obj_set_style_bg_color(kiosk_overlay, lv_color_hex(0x000000), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(kiosk_overlay, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_width(kiosk_overlay, 0, LV_PART_MAIN);
+    lv_obj_set_style_radius(kiosk_overlay, 0, LV_PART_MAIN);
+    lv_obj_clear_flag(kiosk_overlay, LV_OBJ_FLAG_SCROLLABLE);
+
+    lv_obj_t *title = lv_label_create(kiosk_overlay);
+    lv_label_set_text(title, "Filament Check-In / Check-Out");
+    lv_obj_set_style_text_font(title, &lv_font_montserrat_20, LV_PART_MAIN);
+    lv_obj_set_style_text_color(title, lv_color_hex(0x888888), LV_PART_MAIN);
+    lv_obj_align(title, LV_ALIGN_TOP_MID, 0, 20);
+
+    kiosk_card = lv_obj_create(kiosk_overlay);
+    lv_obj_set_size(kiosk_card, 600, 340);
+    lv_obj_align(kiosk_card, LV_ALIGN_CENTER, 0, 20);
+    lv_obj_set_style_bg_color(kiosk_card, lv_color_hex(0x1a1a1a), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(kiosk_card, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_width(kiosk_card, 2, LV_PART_MAIN);
+    lv_obj_set_style_radius(kiosk_card, 12, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(kiosk_card, 20, LV_PART_MAIN);
+    lv_obj_clear_flag(kiosk_card, LV_OBJ_FLAG_SCROLLABLE);
+
+    kiosk_member_label = create_label(kiosk_card, &lv_font_montserrat_16, 0xAAAAAA, 0);
+    kiosk_icon = lv_label_create(kiosk_card);
+    lv_obj_set_style_text_font(kiosk_icon, &lv_font_montserrat_28, LV_PART_MAIN);
+    lv_obj_align(kiosk_icon, LV_ALIGN_TOP_MID, 0, 50);
+    kiosk_message_label = create_label(kiosk_card, &lv_font_montserrat_28, 0xFFFFFF, 110);
+    kiosk_spool_label = create_label(kiosk_card, &lv_font_montserrat_20, 0xCCCCCC, 170);
+    kiosk_expires_label = create_label(kiosk_card, &lv_font_montserrat_14, 0x888888, 260);
+
+    // Force the first update to fill every label
+    memset(&last_state, 0, sizeof(last_state));
+    last_state.state = -1;
+}
+
+static void update_overlay(const KioskStateC *state) {
+    if (state->state != last_state.state) {
+        uint32_t color = state_color(state->state);
+        lv_obj_set_style_border_color(kiosk_card, lv_color_hex(color), LV_PART_MAIN);
+        lv_label_set_text(kiosk_icon, state_symbol(state->state));
+        lv_obj_set_style_text_color(kiosk_icon, lv_color_hex(color), LV_PART_MAIN);
+    }
+    if (strcmp(state->member_name, last_state.member_name) != 0) {
+        char text[64];
+        if (state->member_name[0] != '\0') {
+            snprintf(text, sizeof(text), "Signed in: %s", state->member_name);
+        } else {
+            text[0] = '\0';
+        }
+        lv_label_set_text(kiosk_member_label, text);
+    }
+    if (strcmp(state->message, last_state.message) != 0) {
+        lv_label_set_text(kiosk_message_label, state->message);
+    }
+    if (strcmp(state->spool_name, last_state.spool_name) != 0) {
+        lv_label_set_text(kiosk_spool_label, state->spool_name);
+    }
+    if (state->expires_in != last_state.expires_in) {
+        char text[64];
+        if (state->expires_in >= 0) {
+            snprintf(text, sizeof(text), "Signing out in %ds - scan badge to sign out now", state->expires_in);
+        } else {
+            text[0] = '\0';
+        }
+        lv_label_set_text(kiosk_expires_label, text);
+    }
+    last_state = *state;
+}
+
+bool ui_kiosk_update(void) {
+    KioskStateC state;
+    if (!backend_get_kiosk_state(&state)) {
+        ui_kiosk_cleanup();
+        return false;
+    }
+
+    if (!kiosk_overlay) {
+        create_overlay();
+    }
+    update_overlay(&state);
+    return true;
+}
+
+void ui_kiosk_cleanup(void) {
+    if (kiosk_overlay) {
+        ESP_LOGI(TAG, "Hiding kiosk screen");
+        lv_obj_delete(kiosk_overlay);
+        kiosk_overlay = NULL;
+        kiosk_card = NULL;
+        kiosk_icon = NULL;
+        kiosk_member_label = NULL;
+        kiosk_message_label = NULL;
+        kiosk_spool_label = NULL;
+        kiosk_expires_label = NULL;
+    }
+}
@@ -0,0 +1,22 @@
+/**
+ * Kiosk UI - Check-in/check-out screen for shared filament
+ */
+
+#ifndef UI_KIOSK_H
+#define UI_KIOSK_H
+
+#include <stdbool.h>
+
+/**
+ * Show, update or hide the kiosk screen from the last backend reply
+ * Call this periodically when main screen is active
+ * Returns true while the kiosk screen is shown
+ */
+bool ui_kiosk_update(void);
+
+/**
+ * Remove the kiosk screen (call when leaving main screen)
+ */
+void ui_kiosk_cleanup(void);
+
+#endif // UI_KIOSK_H
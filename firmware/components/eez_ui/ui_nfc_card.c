@@ -772,6 +772,104 @@ static void link_popup_close_handler(lv_event_t *e) {
     }
 }
 
+// ============================================================================
+// Move tag prompt - the tag is linked to another spool, offer to move it
+// ============================================================================
+
+static int move_tag_spool_index = -1;  // Untagged spool the tag should move to
+
+static void move_tag_confirm_handler(lv_event_t *e) {
+    (void)e;
+    if (move_tag_spool_index < 0 || move_tag_spool_index >= untagged_spools_count) return;
+
+    UntaggedSpoolInfo *spool = &untagged_spools[move_tag_spool_index];
+    move_tag_spool_index = -1;
+    ESP_LOGI(TAG, "Moving tag %s to spool %s", popup_tag_uid, spool->id);
+
+    // Returns: 0 = success, -1 = connection error, other = server error
+    int result = spool_move_tag(spool->id, (const char*)popup_tag_uid, "generic");
+    if (result == 0) {
+        char msg[128];
+        snprintf(msg, sizeof(msg), "Tag Moved!\n%s %s", spool->brand, spool->material);
+        show_success_overlay(msg);
+    } else if (result == -1) {
+        show_success_overlay("Connection error.\nPlease try again.");
+    } else {
+        char msg[64];
+        snprintf(msg, sizeof(msg), "Server error (%d).\nPlease try again.", result);
+        show_success_overlay(msg);
+    }
+}
+
+static void move_tag_cancel_handler(lv_event_t *e) {
+    (void)e;
+    move_tag_spool_index = -1;
+    show_success_overlay("Tag not moved.");
+}
+
+static void show_move_tag_prompt(int spool_index) {
+    if (!tag_popup) return;
+    move_tag_spool_index = spool_index;
+    UntaggedSpoolInfo *spool = &untagged_spools[spool_index];
+
+    // Replace the card (first child) with the prompt
+    lv_obj_t *old_card = lv_obj_get_child(tag_popup, 0);
+    if (old_card) {
+        lv_obj_delete(old_card);
+    }
+
+    lv_obj_t *card = lv_obj_create(tag_popup);
+    lv_obj_set_size(card, 420, 220);
+    lv_obj_center(card);
+    lv_obj_set_style_bg_color(card, lv_color_hex(0x1a1a1a), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(card, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_color(card, lv_color_hex(0xFF9800), LV_PART_MAIN);
+    lv_obj_set_style_border_width(card, 2, LV_PART_MAIN);
+    lv_obj_set_style_radius(card, 12, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(card, 20, LV_PART_MAIN);
+    lv_obj_clear_flag(card, LV_OBJ_FLAG_SCROLLABLE);
+
+    lv_obj_t *msg = lv_label_create(card);
+    char text[160];
+    snprintf(text, sizeof(text),
+             "Tag is linked to another spool.\nMove it to %s %s?\nThe other spool keeps its history.",
+             spool->brand[0] ? spool->brand : "Unknown",
+             spool->material[0] ? spool->material : "Unknown");
+    lv_label_set_text(msg, text);
+    lv_obj_set_style_text_font(msg, &lv_font_montserrat_16, LV_PART_MAIN);
+    lv_obj_set_style_text_color(msg, lv_color_hex(0xFFFFFF), LV_PART_MAIN);
+    lv_obj_set_style_text_align(msg, LV_TEXT_ALIGN_CENTER, LV_PART_MAIN);
+    lv_obj_align(msg, LV_ALIGN_TOP_MID, 0, 0);
+
+    // Move button
+    lv_obj_t *btn_move = lv_btn_create(card);
+    lv_obj_set_size(btn_move, 140, 38);
+    lv_obj_align(btn_move, LV_ALIGN_BOTTOM_LEFT, 10, 0);
+    lv_obj_set_style_bg_color(btn_move, lv_color_hex(0xFF9800), LV_PART_MAIN);
+    lv_obj_set_style_radius(btn_move, 8, LV_PART_MAIN);
+    lv_obj_add_event_cb(btn_move, move_tag_confirm_handler, LV_EVENT_CLICKED, NULL);
+
+    lv_obj_t *move_label = lv_label_create(btn_move);
+    lv_label_set_text(move_label, "Move Tag");
+    lv_obj_set_style_text_font(move_label, &lv_font_montserrat_14, LV_PART_MAIN);
+    lv_obj_set_style_text_color(move_label, lv_color_hex(0xFFFFFF), LV_PART_MAIN);
+    lv_obj_center(move_label);
+
+    // Cancel button
+    lv_obj_t *btn_cancel = lv_btn_create(card);
+    lv_obj_set_size(btn_cancel, 140, 38);
+    lv_obj_align(btn_cancel, LV_ALIGN_BOTTOM_RIGHT, -10, 0);
+    lv_obj_set_style_bg_color(btn_cancel, lv_color_hex(0x666666), LV_PART_MAIN);
+    lv_obj_set_style_radius(btn_cancel, 8, LV_PART_MAIN);
+    lv_obj_add_event_cb(btn_cancel, move_tag_cancel_handler, LV_EVENT_CLICKED, NULL);
+
+    lv_obj_t *cancel_label = lv_label_create(btn_cancel);
+    lv_label_set_text(cancel_label, "Cancel");
+    lv_obj_set_style_text_font(cancel_label, &lv_font_montserrat_14, LV_PART_MAIN);
+    lv_obj_set_style_text_color(cancel_label, lv_color_hex(0xFFFFFF), LV_PART_MAIN);
+    lv_obj_center(cancel_label);
+}
+
 static void spool_item_click_handler(lv_event_t *e) {
     int spool_index = (int)(intptr_t)lv_event_get_user_data(e);
 
@@ -799,7 +897,8 @@ static void spool_item_click_handler(lv_event_t *e) {
         snprintf(msg, sizeof(msg), "Tag Linked!\n%s %s", spool->brand, spool->material);
         show_success_overlay(msg);
     } else if (result == 409) {
-        show_success_overlay("Tag already assigned\nto another spool.");
+        // Tag is on another active spool - let the user move it here
+        show_move_tag_prompt(spool_index);
     } else if (result == -1) {
         show_success_overlay("Connection error.\nPlease try again.");
     } else {
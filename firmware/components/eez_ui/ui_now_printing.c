@@ -0,0 +1,505 @@
+// =============================================================================
+// ui_now_printing.c - Now Printing Screen
+// =============================================================================
+// Shows the selected printer's current job: name, progress, layers, remaining
+// time with ETA, and the spools it is consuming. Pause/resume and stop ask for
+// confirmation before sending the command through the backend.
+// Data comes from the backend printer poll, refreshed from ui_tick().
+// =============================================================================
+
+#include "ui_internal.h"
+#include "screens.h"
+#include "images.h"
+#include <stdio.h>
+#include <string.h>
+
+#ifdef ESP_PLATFORM
+#include "esp_log.h"
+#define NP_LOGI(fmt, ...) ESP_LOGI("ui_now_printing", fmt, ##__VA_ARGS__)
+#else
+#define NP_LOGI(fmt, ...) printf("[ui_now_printing] " fmt "\n", ##__VA_ARGS__)
+#endif
+
+#define COLOR_BG_DARK       0x1a1a1a
+#define COLOR_BG_PANEL      0x2d2d2d
+#define COLOR_BORDER        0x3d3d3d
+#define COLOR_TEXT_PRIMARY  0xffffff
+#define COLOR_TEXT_SECONDARY 0x888888
+#define COLOR_ACCENT_GREEN  0x00ff00
+#define COLOR_ACCENT_RED    0xff4444
+
+#define MAX_SPOOL_ROWS 2
+
+typedef enum {
+    PRINT_ACTION_NONE = 0,
+    PRINT_ACTION_PAUSE,
+    PRINT_ACTION_RESUME,
+    PRINT_ACTION_STOP,
+} PrintAction;
+
+typedef struct {
+    lv_obj_t *row;
+    lv_obj_t *swatch;
+    lv_obj_t *material;
+    lv_obj_t *slot;
+    lv_obj_t *weight;
+} SpoolRow;
+
+// Screen objects
+static lv_obj_t *now_printing_screen = NULL;
+static lv_obj_t *np_top_bar_icon_back = NULL;
+static lv_obj_t *np_top_bar_clock = NULL;
+static lv_obj_t *np_printer_label = NULL;
+static lv_obj_t *np_state_label = NULL;
+static lv_obj_t *np_name_label = NULL;
+static lv_obj_t *np_progress_bar = NULL;
+static lv_obj_t *np_progress_label = NULL;
+static lv_obj_t *np_layer_label = NULL;
+static lv_obj_t *np_remaining_label = NULL;
+static lv_obj_t *np_eta_label = NULL;
+static lv_obj_t *np_spools_empty_label = NULL;
+static SpoolRow np_spool_rows[MAX_SPOOL_ROWS];
+static lv_obj_t *np_pause_btn = NULL;
+static lv_obj_t *np_pause_label = NULL;
+static lv_obj_t *np_stop_btn = NULL;
+static lv_obj_t *np_confirm_modal = NULL;
+
+// Serial of the printer shown, used for commands
+static char np_serial[20] = {0};
+static bool np_paused = false;
+static PrintAction np_pending_action = PRINT_ACTION_NONE;
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+static const char *action_name(PrintAction action) {
+    switch (action) {
+        case PRINT_ACTION_PAUSE: return "pause";
+        case PRINT_ACTION_RESUME: return "resume";
+        case PRINT_ACTION_STOP: return "stop";
+        default: return NULL;
+    }
+}
+
+static bool is_paused_state(const char *gcode_state) {
+    return strcmp(gcode_state, "PAUSE") == 0 || strcmp(gcode_state, "PAUSED") == 0;
+}
+
+static lv_obj_t *create_text(lv_obj_t *parent, const lv_font_t *font, uint32_t color, int x, int y) {
+    lv_obj_t *label = lv_label_create(parent);
+    lv_label_set_text(label, "");
+    lv_obj_set_pos(label, x, y);
+    lv_obj_set_style_text_font(label, font, LV_PART_MAIN);
+    lv_obj_set_style_text_color(label, lv_color_hex(color), LV_PART_MAIN);
+    return label;
+}
+
+static lv_obj_t *create_panel(lv_obj_t *parent, int x, int y, int w, int h) {
+    lv_obj_t *panel = lv_obj_create(parent);
+    lv_obj_set_pos(panel, x, y);
+    lv_obj_set_size(panel, w, h);
+    lv_obj_set_style_bg_color(panel, lv_color_hex(COLOR_BG_PANEL), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(panel, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_color(panel, lv_color_hex(COLOR_BORDER), LV_PART_MAIN);
+    lv_obj_set_style_border_width(panel, 1, LV_PART_MAIN);
+    lv_obj_set_style_radius(panel, 12, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(panel, 15, LV_PART_MAIN);
+    lv_obj_clear_flag(panel, LV_OBJ_FLAG_SCROLLABLE);
+    return panel;
+}
+
+static lv_obj_t *create_action_button(lv_obj_t *parent, int x, uint32_t color, const char *text,
+                                      lv_obj_t **label_out, lv_event_cb_t handler) {
+    lv_obj_t *btn = lv_button_create(parent);
+    lv_obj_set_pos(btn, x, 0);
+    lv_obj_set_size(btn, 180, 50);
+    lv_obj_set_style_bg_color(btn, lv_color_hex(color), LV_PART_MAIN);
+    lv_obj_set_style_radius(btn, 8, LV_PART_MAIN);
+    lv_obj_add_event_cb(btn, handler, LV_EVENT_CLICKED, NULL);
+
+    lv_obj_t *label = lv_label_create(btn);
+    lv_label_set_text(label, text);
+    lv_obj_set_style_text_font(label, &lv_font_montserrat_18, LV_PART_MAIN);
+    lv_obj_set_style_text_color(label, lv_color_hex(COLOR_TEXT_PRIMARY), LV_PART_MAIN);
+    lv_obj_center(label);
+    if (label_out) *label_out = label;
+    return btn;
+}
+
+static void format_slot(char *buf, size_t buf_size, const ActiveSpoolCInfo *spool) {
+    const char *side = spool->extruder == 0 ? "Right, " : spool->extruder == 1 ? "Left, " : "";
+    if (spool->ams_id == 254 || spool->ams_id == 255) {
+        snprintf(buf, buf_size, "%sExternal", side);
+    } else if (spool->ams_id >= 128) {
+        snprintf(buf, buf_size, "%sAMS HT %c", side, 'A' + spool->ams_id - 128);
+    } else {
+        snprintf(buf, buf_size, "%sAMS %c%d", side, 'A' + spool->ams_id, spool->tray_id + 1);
+    }
+}
+
+// =============================================================================
+// Confirmation Modal
+// =============================================================================
+
+static void close_confirm_modal(void) {
+    if (np_confirm_modal) {
+        lv_obj_delete(np_confirm_modal);
+        np_confirm_modal = NULL;
+    }
+    np_pending_action = PRINT_ACTION_NONE;
+}
+
+static void confirm_cancel_handler(lv_event_t *e) {
+    (void)e;
+    close_confirm_modal();
+}
+
+static void confirm_ok_handler(lv_event_t *e) {
+    (void)e;
+    const char *action = action_name(np_pending_action);
+    close_confirm_modal();
+    if (!action || !np_serial[0]) return;
+
+    NP_LOGI("Sending %s to %s", action, np_serial);
+    if (!backend_print_control(np_serial, action)) {
+        NP_LOGI("Failed to %s print on %s", action, np_serial);
+    }
+}
+
+static void show_confirm_modal(PrintAction action) {
+    if (np_confirm_modal) return;  // Already showing
+    np_pending_action = action;
+
+    bool stop = action == PRINT_ACTION_STOP;
+    uint32_t accent = stop ? COLOR_ACCENT_RED : COLOR_ACCENT_GREEN;
+
+    // Semi-transparent overlay, clicking outside the card cancels
+    np_confirm_modal = lv_obj_create(lv_layer_top());
+    lv_obj_set_size(np_confirm_modal, 800, 480);
+    lv_obj_set_pos(np_confirm_modal, 0, 0);
+    lv_obj_set_style_bg_color(np_confirm_modal, lv_color_hex(0x000000), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(np_confirm_modal, 180, LV_PART_MAIN);
+    lv_obj_set_style_border_width(np_confirm_modal, 0, LV_PART_MAIN);
+    lv_obj_clear_flag(np_confirm_modal, LV_OBJ_FLAG_SCROLLABLE);
+    lv_obj_add_event_cb(np_confirm_modal, confirm_cancel_handler, LV_EVENT_CLICKED, NULL);
+
+    lv_obj_t *card = lv_obj_create(np_confirm_modal);
+    lv_obj_set_size(card, 380, 190);
+    lv_obj_center(card);
+    lv_obj_set_style_bg_color(card, lv_color_hex(COLOR_BG_DARK), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(card, 255, LV_PART_MAIN);
+    lv_obj_set_style_border_color(card, lv_color_hex(accent), LV_PART_MAIN);
+    lv_obj_set_style_border_width(card, 2, LV_PART_MAIN);
+    lv_obj_set_style_radius(card, 12, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(card, 20, LV_PART_MAIN);
+    lv_obj_clear_flag(card, LV_OBJ_FLAG_SCROLLABLE);
+    lv_obj_add_flag(card, LV_OBJ_FLAG_CLICKABLE);  // Keep card clicks from closing the modal
+
+    lv_obj_t *title = lv_label_create(card);
+    lv_label_set_text(title, stop ? "Stop Print?" : action == PRINT_ACTION_PAUSE ? "Pause Print?" : "Resume Print?");
+    lv_obj_set_style_text_font(title, &lv_font_montserrat_20, LV_PART_MAIN);
+    lv_obj_set_style_text_color(title, lv_color_hex(accent), LV_PART_MAIN);
+    lv_obj_align(title, LV_ALIGN_TOP_MID, 0, 0);
+
+    lv_obj_t *msg = lv_label_create(card);
+    lv_label_set_text(msg, stop ? "The print will be cancelled.\nThis cannot be undone."
+                                : action == PRINT_ACTION_PAUSE ? "The printer will pause\nafter the current move."
+                                                               : "The printer will continue\nthe paused print.");
+    lv_obj_set_style_text_font(msg, &lv_font_montserrat_16, LV_PART_MAIN);
+    lv_obj_set_style_text_color(msg, lv_color_hex(COLOR_TEXT_PRIMARY), LV_PART_MAIN);
+    lv_obj_set_style_text_align(msg, LV_TEXT_ALIGN_CENTER, LV_PART_MAIN);
+    lv_obj_align(msg, LV_ALIGN_TOP_MID, 0, 40);
+
+    lv_obj_t *cancel_btn = lv_button_create(card);
+    lv_obj_set_size(cancel_btn, 130, 45);
+    lv_obj_align(cancel_btn, LV_ALIGN_BOTTOM_LEFT, 10, 0);
+    lv_obj_set_style_bg_color(cancel_btn, lv_color_hex(0x444444), LV_PART_MAIN);
+    lv_obj_set_style_bg_color(cancel_btn, lv_color_hex(0x555555), LV_PART_MAIN | LV_STATE_PRESSED);
+    lv_obj_add_event_cb(cancel_btn, confirm_cancel_handler, LV_EVENT_CLICKED, NULL);
+
+    lv_obj_t *cancel_label = lv_label_create(cancel_btn);
+    lv_label_set_text(cancel_label, "Cancel");
+    lv_obj_set_style_text_color(cancel_label, lv_color_hex(COLOR_TEXT_PRIMARY), LV_PART_MAIN);
+    lv_obj_center(cancel_label);
+
+    lv_obj_t *ok_btn = lv_button_create(card);
+    lv_obj_set_size(ok_btn, 130, 45);
+    lv_obj_align(ok_btn, LV_ALIGN_BOTTOM_RIGHT, -10, 0);
+    lv_obj_set_style_bg_color(ok_btn, lv_color_hex(stop ? 0xff3333 : 0x00aa00), LV_PART_MAIN);
+    lv_obj_set_style_bg_color(ok_btn, lv_color_hex(stop ? 0xcc0000 : 0x008800), LV_PART_MAIN | LV_STATE_PRESSED);
+    lv_obj_add_event_cb(ok_btn, confirm_ok_handler, LV_EVENT_CLICKED, NULL);
+
+    lv_obj_t *ok_label = lv_label_create(ok_btn);
+    lv_label_set_text(ok_label, stop ? "Stop" : action == PRINT_ACTION_PAUSE ? "Pause" : "Resume");
+    lv_obj_set_style_text_color(ok_label, lv_color_hex(COLOR_TEXT_PRIMARY), LV_PART_MAIN);
+    lv_obj_center(ok_label);
+}
+
+// =============================================================================
+// Event Handlers
+// =============================================================================
+
+static void back_handler(lv_event_t *e) {
+    (void)e;
+    pendingScreen = SCREEN_ID_MAIN_SCREEN;
+}
+
+static void pause_handler(lv_event_t *e) {
+    (void)e;
+    show_confirm_modal(np_paused ? PRINT_ACTION_RESUME : PRINT_ACTION_PAUSE);
+}
+
+static void stop_handler(lv_event_t *e) {
+    (void)e;
+    show_confirm_modal(PRINT_ACTION_STOP);
+}
+
+// =============================================================================
+// Screen Lifecycle
+// =============================================================================
+
+void create_now_printing_screen(void) {
+    if (now_printing_screen) return;
+
+    now_printing_screen = lv_obj_create(NULL);
+    lv_obj_set_size(now_printing_screen, 800, 480);
+    lv_obj_set_style_bg_color(now_printing_screen, lv_color_hex(COLOR_BG_DARK), LV_PART_MAIN);
+    lv_obj_set_style_bg_opa(now_printing_screen, 255, LV_PART_MAIN);
+    lv_obj_clear_flag(now_printing_screen, LV_OBJ_FLAG_SCROLLABLE);
+
+    create_top_bar(now_printing_screen, "Now Printing", &np_top_bar_icon_back, &np_top_bar_clock);
+    lv_obj_add_event_cb(np_top_bar_icon_back, back_handler, LV_EVENT_CLICKED, NULL);
+
+    // Job panel: printer, state, file name, progress bar, layers and time
+    lv_obj_t *job = create_panel(now_printing_screen, 15, 59, 770, 190);
+    np_printer_label = create_text(job, &lv_font_montserrat_14, COLOR_TEXT_SECONDARY, 0, 0);
+    np_state_label = create_text(job, &lv_font_montserrat_14, COLOR_ACCENT_GREEN, 0, 0);
+    lv_obj_align(np_state_label, LV_ALIGN_TOP_RIGHT, 0, 0);
+
+    np_name_label = create_text(job, &lv_font_montserrat_20, COLOR_TEXT_PRIMARY, 0, 24);
+    lv_obj_set_width(np_name_label, 740);
+    lv_label_set_long_mode(np_name_label, LV_LABEL_LONG_DOT);
+
+    np_progress_bar = lv_bar_create(job);
+    lv_obj_set_pos(np_progress_bar, 0, 70);
+    lv_obj_set_size(np_progress_bar, 650, 20);
+    lv_bar_set_range(np_progress_bar, 0, 100);
+    lv_obj_set_style_bg_color(np_progress_bar, lv_color_hex(COLOR_BORDER), LV_PART_MAIN);
+    lv_obj_set_style_bg_color(np_progress_bar, lv_color_hex(COLOR_ACCENT_GREEN), LV_PART_INDICATOR);
+    lv_obj_set_style_radius(np_progress_bar, 6, LV_PART_MAIN);
+    lv_obj_set_style_radius(np_progress_bar, 6, LV_PART_INDICATOR);
+
+    np_progress_label = create_text(job, &lv_font_montserrat_20, COLOR_TEXT_PRIMARY, 0, 67);
+    lv_obj_align(np_progress_label, LV_ALIGN_TOP_RIGHT, 0, 67);
+
+    np_layer_label = create_text(job, &lv_font_montserrat_16, COLOR_TEXT_PRIMARY, 0, 110);
+    np_remaining_label = create_text(job, &lv_font_montserrat_16, COLOR_TEXT_PRIMARY, 260, 110);
+    np_eta_label = create_text(job, &lv_font_montserrat_16, COLOR_TEXT_PRIMARY, 520, 110);
+
+    // Spools panel: one row per spool being consumed
+    lv_obj_t *spools = create_panel(now_printing_screen, 15, 259, 770, 136);
+    lv_obj_t *header = create_text(spools, &lv_font_montserrat_14, COLOR_TEXT_SECONDARY, 0, 0);
+    lv_label_set_text(header, "FILAMENT IN USE");
+    np_spools_empty_label = create_text(spools, &lv_font_montserrat_16, COLOR_TEXT_SECONDARY, 0, 30);
+    lv_label_set_text(np_spools_empty_label, "No active spool reported");
+
+    for (int i = 0; i < MAX_SPOOL_ROWS; i++) {
+        SpoolRow *row = &np_spool_rows[i];
+        row->row = lv_obj_create(spools);
+        lv_obj_set_pos(row->row, 0, 24 + i * 42);
+        lv_obj_set_size(row->row, 740, 38);
+        lv_obj_set_style_bg_opa(row->row, 0, LV_PART_MAIN);
+        lv_obj_set_style_border_width(row->row, 0, LV_PART_MAIN);
+        lv_obj_set_style_pad_all(row->row, 0, LV_PART_MAIN);
+        lv_obj_clear_flag(row->row, LV_OBJ_FLAG_SCROLLABLE);
+
+        row->swatch = lv_obj_create(row->row);
+        lv_obj_set_pos(row->swatch, 0, 5);
+        lv_obj_set_size(row->swatch, 28, 28);
+        lv_obj_set_style_radius(row->swatch, LV_RADIUS_CIRCLE, LV_PART_MAIN);
+        lv_obj_set_style_border_color(row->swatch, lv_color_hex(COLOR_BORDER), LV_PART_MAIN);
+        lv_obj_set_style_border_width(row->swatch, 1, LV_PART_MAIN);
+        lv_obj_clear_flag(row->swatch, LV_OBJ_FLAG_SCROLLABLE);
+
+        row->material = create_text(row->row, &lv_font_montserrat_16, COLOR_TEXT_PRIMARY, 42, 9);
+        lv_obj_set_width(row->material, 360);
+        lv_label_set_long_mode(row->material, LV_LABEL_LONG_DOT);
+        row->slot = create_text(row->row, &lv_font_montserrat_14, COLOR_TEXT_SECONDARY, 420, 10);
+        row->weight = create_text(row->row, &lv_font_montserrat_18, COLOR_TEXT_PRIMARY, 0, 8);
+        lv_obj_align(row->weight, LV_ALIGN_TOP_RIGHT, 0, 8);
+        lv_obj_add_flag(row->row, LV_OBJ_FLAG_HIDDEN);
+    }
+
+    // Print controls
+    lv_obj_t *controls = lv_obj_create(now_printing_screen);
+    lv_obj_set_pos(controls, 15, 410);
+    lv_obj_set_size(controls, 770, 55);
+    lv_obj_set_style_bg_opa(controls, 0, LV_PART_MAIN);
+    lv_obj_set_style_border_width(controls, 0, LV_PART_MAIN);
+    lv_obj_set_style_pad_all(controls, 0, LV_PART_MAIN);
+    lv_obj_clear_flag(controls, LV_OBJ_FLAG_SCROLLABLE);
+
+    np_pause_btn = create_action_button(controls, 390, 0x444444, LV_SYMBOL_PAUSE " Pause",
+                                        &np_pause_label, pause_handler);
+    np_stop_btn = create_action_button(controls, 590, 0xff3333, LV_SYMBOL_STOP " Stop", NULL, stop_handler);
+
+    np_serial[0] = '\0';
+    update_now_printing_screen();
+}
+
+lv_obj_t *get_now_printing_screen(void) {
+    return now_printing_screen;
+}
+
+void update_now_printing_screen(void) {
+    if (!now_printing_screen || lv_scr_act() != now_printing_screen) return;
+
+    char buf[96];
+
+    // Clock
+    int time_hhmm = time_get_hhmm();
+    if (np_top_bar_clock && time_hhmm >= 0) {
+        snprintf(buf, sizeof(buf), "%02d:%02d", (time_hhmm >> 8) & 0xFF, time_hhmm & 0xFF);
+        lv_label_set_text(np_top_bar_clock, buf);
+    }
+
+    int index = get_selected_printer_index();
+    BackendPrinterInfo printer = {0};
+    if (index < 0 || backend_get_printer(index, &printer) != 0) {
+        lv_label_set_text(np_name_label, "No printer selected");
+        return;
+    }
+    strncpy(np_serial, printer.serial, sizeof(np_serial) - 1);
+
+    bool printing = printer.connected && (strcmp(printer.gcode_state, "RUNNING") == 0 ||
+                                          is_paused_state(printer.gcode_state));
+    np_paused = printing && is_paused_state(printer.gcode_state);
+
+    lv_label_set_text(np_printer_label, printer.name[0] ? printer.name : printer.serial);
+    if (!printer.connected) {
+        lv_label_set_text(np_state_label, "Offline");
+    } else if (np_paused) {
+        lv_label_set_text(np_state_label, "Paused");
+    } else if (printing && printer.stg_cur_name[0]) {
+        lv_label_set_text(np_state_label, printer.stg_cur_name);
+    } else {
+        lv_label_set_text(np_state_label, printing ? "Printing" : "Idle");
+    }
+    lv_obj_set_style_text_color(np_state_label,
+        lv_color_hex(np_paused ? 0xffaa00 : printing ? COLOR_ACCENT_GREEN : COLOR_TEXT_SECONDARY), LV_PART_MAIN);
+
+    lv_label_set_text(np_name_label, printing && printer.subtask_name[0] ? printer.subtask_name :
+                                     printing ? "Unnamed print" : "Nothing is printing");
+
+    int progress = printing ? printer.print_progress : 0;
+    lv_bar_set_value(np_progress_bar, progress, LV_ANIM_OFF);
+    snprintf(buf, sizeof(buf), "%d%%", progress);
+    lv_label_set_text(np_progress_label, buf);
+
+    PrintJobCInfo job = {0};
+    bool have_job = printing && backend_get_print_job(index, &job) == 0;
+
+    // Layers
+    if (have_job && job.layer_num >= 0 && job.total_layer_num > 0) {
+        snprintf(buf, sizeof(buf), "Layer %d / %d", job.layer_num, job.total_layer_num);
+    } else {
+        snprintf(buf, sizeof(buf), "Layer --");
+    }
+    lv_label_set_text(np_layer_label, buf);
+
+    // Remaining time and ETA (current time + remaining minutes)
+    if (printing && printer.remaining_time_min > 0) {
+        int hours = printer.remaining_time_min / 60;
+        int mins = printer.remaining_time_min % 60;
+        if (hours > 0) {
+            snprintf(buf, sizeof(buf), "%dh %dm left", hours, mins);
+        } else {
+            snprintf(buf, sizeof(buf), "%dm left", mins);
+        }
+        lv_label_set_text(np_remaining_label, buf);
+
+        if (time_hhmm >= 0) {
+            int total_min = ((time_hhmm >> 8) & 0xFF) * 60 + (time_hhmm & 0xFF) + printer.remaining_time_min;
+            snprintf(buf, sizeof(buf), "ETA %02d:%02d", (total_min / 60) % 24, total_min % 60);
+            lv_label_set_text(np_eta_label, buf);
+        } else {
+            lv_label_set_text(np_eta_label, "ETA --:--");
+        }
+    } else {
+        lv_label_set_text(np_remaining_label, "");
+        lv_label_set_text(np_eta_label, "");
+    }
+
+    // Spools in use, with live remaining weight
+    int spool_count = have_job ? job.spool_count : 0;
+    if (spool_count > MAX_SPOOL_ROWS) spool_count = MAX_SPOOL_ROWS;
+    for (int i = 0; i < MAX_SPOOL_ROWS; i++) {
+        SpoolRow *row = &np_spool_rows[i];
+        if (i >= spool_count) {
+            lv_obj_add_flag(row->row, LV_OBJ_FLAG_HIDDEN);
+            continue;
+        }
+        const ActiveSpoolCInfo *spool = &job.spools[i];
+        lv_obj_remove_flag(row->row, LV_OBJ_FLAG_HIDDEN);
+        lv_obj_set_style_bg_color(row->swatch, lv_color_hex(spool->color_rgba >> 8), LV_PART_MAIN);
+
+        if (spool->color_name[0]) {
+            snprintf(buf, sizeof(buf), "%s - %s", spool->material[0] ? spool->material : "Unknown",
+                     spool->color_name);
+        } else {
+            snprintf(buf, sizeof(buf), "%s", spool->material[0] ? spool->material : "Unknown");
+        }
+        lv_label_set_text(row->material, buf);
+
+        format_slot(buf, sizeof(buf), spool);
+        lv_label_set_text(row->slot, buf);
+
+        if (spool->remaining_grams >= 0) {
+            snprintf(buf, sizeof(buf), "%d g", spool->remaining_grams);
+        } else {
+            snprintf(buf, sizeof(buf), "-- g");
+        }
+        lv_label_set_text(row->weight, buf);
+    }
+    if (spool_count > 0) {
+        lv_obj_add_flag(np_spools_empty_label, LV_OBJ_FLAG_HIDDEN);
+    } else {
+        lv_obj_remove_flag(np_spools_empty_label, LV_OBJ_FLAG_HIDDEN);
+    }
+
+    // Controls follow the print state
+    lv_label_set_text(np_pause_label, np_paused ? LV_SYMBOL_PLAY " Resume" : LV_SYMBOL_PAUSE " Pause");
+    if (printing) {
+        lv_obj_remove_state(np_pause_btn, LV_STATE_DISABLED);
+        lv_obj_remove_state(np_stop_btn, LV_STATE_DISABLED);
+    } else {
+        lv_obj_add_state(np_pause_btn, LV_STATE_DISABLED);
+        lv_obj_add_state(np_stop_btn, LV_STATE_DISABLED);
+        if (np_confirm_modal) close_confirm_modal();
+    }
+}
+
+void cleanup_now_printing_screen(void) {
+    // Delete only when not active (during transition TO this screen it is loaded first)
+    if (!now_printing_screen || now_printing_screen == lv_scr_act()) return;
+
+    close_confirm_modal();
+    lv_obj_delete(now_printing_screen);
+    now_printing_screen = NULL;
+    np_top_bar_icon_back = NULL;
+    np_top_bar_clock = NULL;
+    np_printer_label = NULL;
+    np_state_label = NULL;
+    np_name_label = NULL;
+    np_progress_bar = NULL;
+    np_progress_label = NULL;
+    np_layer_label = NULL;
+    np_remaining_label = NULL;
+    np_eta_label = NULL;
+    np_spools_empty_label = NULL;
+    memset(np_spool_rows, 0, sizeof(np_spool_rows));
+    np_pause_btn = NULL;
+    np_pause_label = NULL;
+    np_stop_btn = NULL;
+}
@@ -22,6 +22,8 @@ extern bool scale_is_stable(void);
 extern int32_t scale_tare(void);
 extern int32_t scale_calibrate(float known_weight_grams);
 extern int32_t scale_get_tare_offset(void);
+extern uint8_t scale_get_channel_count(void);
+extern float scale_get_weight_ch(uint8_t channel);
 #else
 // Simulator: Scale functions that read from backend (which gets from ESP32 device)
 // Forward declare backend functions to avoid header conflicts
@@ -51,6 +53,9 @@ int32_t scale_calibrate(float known_weight_grams) {
     return backend_scale_calibrate(known_weight_grams);
 }
 int32_t scale_get_tare_offset(void) { return 0; }  // Tare offset is managed by ESP32
+// Simulator shows a single scale
+uint8_t scale_get_channel_count(void) { return 1; }
+float scale_get_weight_ch(uint8_t channel) { return channel == 0 ? backend_get_scale_weight() : 0.0f; }
 
 // Simulator control functions (kept for compatibility, but now no-op)
 void sim_set_scale_weight(float weight) { (void)weight; }
@@ -27,6 +27,8 @@ static const char *TAG = "ui_status_bar";
 extern bool nfc_is_initialized(void);
 extern bool nfc_tag_present(void);
 extern float scale_get_weight(void);
+extern float scale_get_weight_ch(uint8_t channel);
+extern uint8_t scale_get_channel_count(void);
 extern bool scale_is_initialized(void);
 extern int get_selected_printer_index(void);
 
@@ -49,10 +51,19 @@ static lv_obj_t *scale_label = NULL;
 // Track which screen we're on
 static bool current_is_main_screen = true;
 
-// Weight display hysteresis
+// Weight display hysteresis (second value is for the second load cell)
 static float last_displayed_weight = 0.0f;
+static float last_displayed_weight_2 = 0.0f;
 static bool weight_initialized = false;
 
+// Round weight for display, hiding +/-20g of noise around zero
+static int display_grams(float weight) {
+    int weight_int = (int)weight;
+    if (weight_int >= -20 && weight_int <= 20) weight_int = 0;
+    if (weight_int < 0) weight_int = 0;
+    return weight_int;
+}
+
 /**
  * Get the active tray info from the selected printer
  * Returns the tray color (RGBA) and material type
@@ -379,21 +390,27 @@ void ui_status_bar_update(void) {
     if (scale_label) {
         if (scale_is_initialized()) {
             float weight = scale_get_weight();
+            bool dual = scale_get_channel_count() > 1;
+            float weight_2 = dual ? scale_get_weight_ch(1) : 0.0f;
 
             // Apply 10g hysteresis
             float diff = weight - last_displayed_weight;
             if (diff < 0) diff = -diff;
+            float diff_2 = weight_2 - last_displayed_weight_2;
+            if (diff_2 < 0) diff_2 = -diff_2;
 
-            if (!weight_initialized || diff >= 10.0f) {
+            if (!weight_initialized || diff >= 10.0f || diff_2 >= 10.0f) {
                 last_displayed_weight = weight;
+                last_displayed_weight_2 = weight_2;
                 weight_initialized = true;
 
-                int weight_int = (int)weight;
-                if (weight_int >= -20 && weight_int <= 20) weight_int = 0;
-                if (weight_int < 0) weight_int = 0;
-
-                char weight_str[24];
-                snprintf(weight_str, sizeof(weight_str), "Scale: %dg", weight_int);
+                char weight_str[32];
+                if (dual) {
+                    snprintf(weight_str, sizeof(weight_str), "Scale: %dg | %dg",
+                             display_grams(weight), display_grams(weight_2));
+                } else {
+                    snprintf(weight_str, sizeof(weight_str), "Scale: %dg", display_grams(weight));
+                }
                 lv_label_set_text(scale_label, weight_str);
             }
             lv_obj_set_style_text_color(scale_label, lv_color_hex(COLOR_WHITE), 0);
@@ -416,6 +433,7 @@ void ui_status_bar_cleanup(void) {
     scale_label = NULL;
     weight_initialized = false;
     last_displayed_weight = 0.0f;
+    last_displayed_weight_2 = 0.0f;
 
     STATUS_LOG("Status bar cleaned up");
 }
@@ -188,8 +188,11 @@
 /* Memory monitor - disabled for production */
 #define LV_USE_MEM_MONITOR 0
 
-/* File system */
-#define LV_USE_FS_STDIO 0
+/* File system - stdio driver serves the SD card (S:assets/... -> /sdcard/assets/...) */
+#define LV_USE_FS_STDIO 1
+#define LV_FS_STDIO_LETTER 'S'
+#define LV_FS_STDIO_PATH "/sdcard/"
+#define LV_FS_STDIO_CACHE_SIZE 0
 #define LV_USE_FS_POSIX 0
 #define LV_USE_FS_WIN32 0
 #define LV_USE_FS_FATFS 0
@@ -0,0 +1,244 @@
+//! Board presets: display resolution, panel timing and pin map
+//!
+//! The firmware picks one preset with a cargo feature and hands its
+//! [`DisplayConfig`] to the C display driver, which is why the display types
+//! are `#[repr(C)]` and mirrored by `display_board_config_t` in
+//! `components/display_driver/display_driver.h`.
+
+/// Unused pin (no GPIO connected)
+pub const NO_PIN: i32 = -1;
+
+/// RGB panel timing
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelTiming {
+    pub pclk_hz: u32,
+    pub hsync_pulse_width: u16,
+    pub hsync_back_porch: u16,
+    pub hsync_front_porch: u16,
+    pub vsync_pulse_width: u16,
+    pub vsync_back_porch: u16,
+    pub vsync_front_porch: u16,
+    /// Sample data on the falling PCLK edge
+    pub pclk_active_neg: bool,
+}
+
+/// RGB565 parallel interface pins
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RgbPins {
+    pub pclk: i32,
+    pub hsync: i32,
+    pub vsync: i32,
+    pub de: i32,
+    /// Data lines in esp_lcd order: B0-B4, G0-G5, R0-R4
+    pub data: [i32; 16],
+}
+
+/// Display panel, touch controller and backlight of a board
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayConfig {
+    pub width: u16,
+    pub height: u16,
+    pub timing: PanelTiming,
+    pub pins: RgbPins,
+    /// GT911 touch controller on I2C0
+    pub touch_sda: i32,
+    pub touch_scl: i32,
+    pub touch_addr: u8,
+    /// Backlight enable GPIO, or [`NO_PIN`]
+    pub backlight_gpio: i32,
+    /// I2C address of a PWM backlight controller, 0 if none
+    pub backlight_i2c_addr: u8,
+    /// CH422G expander output driving the backlight, or [`NO_PIN`]
+    pub backlight_exio: i32,
+}
+
+impl DisplayConfig {
+    /// Pixel clocks per frame, including sync and porches
+    pub fn clocks_per_frame(&self) -> u32 {
+        let t = &self.timing;
+        let h = self.width as u32 + t.hsync_pulse_width as u32 + t.hsync_back_porch as u32 + t.hsync_front_porch as u32;
+        let v = self.height as u32 + t.vsync_pulse_width as u32 + t.vsync_back_porch as u32 + t.vsync_front_porch as u32;
+        h * v
+    }
+
+    /// Time to send one frame to the panel, rounded up
+    pub fn frame_period_ms(&self) -> u32 {
+        let clocks = self.clocks_per_frame() as u64 * 1000;
+        clocks.div_ceil(self.timing.pclk_hz as u64) as u32
+    }
+
+    /// RGB565 framebuffer size in bytes
+    pub fn framebuffer_bytes(&self) -> usize {
+        self.width as usize * self.height as usize * 2
+    }
+}
+
+/// A supported board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Board {
+    pub name: &'static str,
+    pub display: DisplayConfig,
+}
+
+/// ELECROW CrowPanel Advance 7.0" (800x480)
+pub const CROWPANEL_7: Board = Board {
+    name: "CrowPanel Advance 7.0\"",
+    display: DisplayConfig {
+        width: 800,
+        height: 480,
+        timing: PanelTiming {
+            pclk_hz: 14_000_000,
+            hsync_pulse_width: 48,
+            hsync_back_porch: 20,
+            hsync_front_porch: 40,
+            vsync_pulse_width: 4,
+            vsync_back_porch: 20,
+            vsync_front_porch: 20,
+            pclk_active_neg: true,
+        },
+        pins: RgbPins {
+            pclk: 39,
+            hsync: 40,
+            vsync: 41,
+            de: 42,
+            data: [
+                21, 47, 48, 45, 38, // B0-B4
+                9, 10, 11, 12, 13, 14, // G0-G5
+                7, 17, 18, 3, 46, // R0-R4
+            ],
+        },
+        touch_sda: 15,
+        touch_scl: 16,
+        touch_addr: 0x5D,
+        backlight_gpio: 1,
+        backlight_i2c_addr: 0x30,
+        backlight_exio: NO_PIN,
+    },
+};
+
+/// Pin map shared by the Waveshare ESP32-S3-Touch-LCD boards
+const WAVESHARE_PINS: RgbPins = RgbPins {
+    pclk: 7,
+    hsync: 46,
+    vsync: 3,
+    de: 5,
+    data: [
+        14, 38, 18, 17, 10, // B0-B4
+        39, 0, 45, 48, 47, 21, // G0-G5
+        1, 2, 42, 41, 40, // R0-R4
+    ],
+};
+
+/// Waveshare ESP32-S3-Touch-LCD-4.3 (800x480)
+pub const WAVESHARE_4_3: Board = Board {
+    name: "Waveshare ESP32-S3-Touch-LCD-4.3",
+    display: DisplayConfig {
+        width: 800,
+        height: 480,
+        timing: PanelTiming {
+            pclk_hz: 16_000_000,
+            hsync_pulse_width: 4,
+            hsync_back_porch: 8,
+            hsync_front_porch: 8,
+            vsync_pulse_width: 4,
+            vsync_back_porch: 8,
+            vsync_front_porch: 8,
+            pclk_active_neg: true,
+        },
+        pins: WAVESHARE_PINS,
+        touch_sda: 8,
+        touch_scl: 9,
+        touch_addr: 0x5D,
+        backlight_gpio: NO_PIN,
+        backlight_i2c_addr: 0,
+        backlight_exio: 2,
+    },
+};
+
+/// Waveshare ESP32-S3-Touch-LCD-7B (1024x600)
+pub const WAVESHARE_7: Board = Board {
+    name: "Waveshare ESP32-S3-Touch-LCD-7B",
+    display: DisplayConfig {
+        width: 1024,
+        height: 600,
+        timing: PanelTiming {
+            pclk_hz: 30_000_000,
+            hsync_pulse_width: 20,
+            hsync_back_porch: 140,
+            hsync_front_porch: 160,
+            vsync_pulse_width: 3,
+            vsync_back_porch: 20,
+            vsync_front_porch: 12,
+            pclk_active_neg: true,
+        },
+        pins: WAVESHARE_PINS,
+        touch_sda: 8,
+        touch_scl: 9,
+        touch_addr: 0x5D,
+        backlight_gpio: NO_PIN,
+        backlight_i2c_addr: 0,
+        backlight_exio: 2,
+    },
+};
+
+/// All presets, for lookups and tests
+pub const BOARDS: [&Board; 3] = [&CROWPANEL_7, &WAVESHARE_4_3, &WAVESHARE_7];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display_pins(display: &DisplayConfig) -> [i32; 22] {
+        let p = &display.pins;
+        let mut pins = [NO_PIN; 22];
+        pins[..4].copy_from_slice(&[p.pclk, p.hsync, p.vsync, p.de]);
+        pins[4..20].copy_from_slice(&p.data);
+        pins[20] = display.touch_sda;
+        pins[21] = display.touch_scl;
+        pins
+    }
+
+    #[test]
+    fn display_pins_are_distinct() {
+        for board in BOARDS {
+            let mut pins = display_pins(&board.display);
+            pins.sort_unstable();
+            assert!(pins.windows(2).all(|w| w[0] != w[1]), "{} reuses a pin", board.name);
+            assert!(pins.iter().all(|&pin| (0..=48).contains(&pin)), "{} has an invalid pin", board.name);
+        }
+    }
+
+    #[test]
+    fn backlight_pin_is_not_a_display_pin() {
+        for board in BOARDS {
+            let backlight = board.display.backlight_gpio;
+            assert!(!display_pins(&board.display).contains(&backlight), "{}", board.name);
+        }
+    }
+
+    #[test]
+    fn every_board_has_a_backlight_control() {
+        for board in BOARDS {
+            let d = &board.display;
+            assert!(d.backlight_gpio != NO_PIN || d.backlight_exio != NO_PIN, "{}", board.name);
+        }
+    }
+
+    #[test]
+    fn frame_period_from_timing() {
+        // (800 + 108) x (480 + 44) clocks at 14 MHz
+        assert_eq!(CROWPANEL_7.display.clocks_per_frame(), 908 * 524);
+        assert_eq!(CROWPANEL_7.display.frame_period_ms(), 34);
+        // (1024 + 320) x (600 + 35) clocks at 30 MHz
+        assert_eq!(WAVESHARE_7.display.frame_period_ms(), 29);
+    }
+
+    #[test]
+    fn framebuffer_size() {
+        assert_eq!(WAVESHARE_4_3.display.framebuffer_bytes(), 768_000);
+        assert_eq!(WAVESHARE_7.display.framebuffer_bytes(), 1_228_800);
+    }
+}
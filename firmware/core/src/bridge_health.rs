@@ -0,0 +1,283 @@
+//! Pico NFC bridge health tracking
+//!
+//! The bridge driver records the outcome of every command here and sends a
+//! periodic version request as a heartbeat. Enough failures in a row (I2C
+//! errors, timeouts or garbage responses), or no good response for too
+//! long, mark the bridge as hung. [`BridgeHealth::needs_reset`] then says
+//! when to pulse the Pico's reset line, backing off between attempts so a
+//! Pico that is unplugged or dead isn't reset at full rate forever.
+//!
+//! Times are milliseconds from any monotonic clock.
+
+/// Heartbeat period while the bridge is idle
+pub const HEARTBEAT_INTERVAL_MS: u64 = 5_000;
+
+/// Failures in a row before the bridge counts as hung
+pub const HUNG_AFTER_FAILURES: u32 = 5;
+
+/// No good response for this long also counts as hung (three missed heartbeats)
+pub const HUNG_AFTER_SILENCE_MS: u64 = 3 * HEARTBEAT_INTERVAL_MS;
+
+/// Time the Pico needs after reset before it answers on I2C
+pub const PICO_BOOT_MS: u64 = 1_500;
+
+/// Wait before a second reset when the first didn't bring the bridge back;
+/// doubles with every further reset
+const RESET_BACKOFF_MS: u64 = 30_000;
+const MAX_RESET_BACKOFF_MS: u64 = 5 * 60_000;
+
+/// Overall bridge state, as reported to the backend and the UI
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BridgeStatus {
+    /// Last command succeeded
+    #[default]
+    Ok = 0,
+    /// Some recent commands failed
+    Degraded = 1,
+    /// Not answering, waiting for a reset
+    Hung = 2,
+    /// Reset issued, waiting for the Pico to boot
+    Recovering = 3,
+}
+
+impl BridgeStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BridgeStatus::Ok => "ok",
+            BridgeStatus::Degraded => "degraded",
+            BridgeStatus::Hung => "hung",
+            BridgeStatus::Recovering => "recovering",
+        }
+    }
+}
+
+/// Snapshot of the bridge health for status reports and the UI.
+/// Mirrored by `NfcBridgeHealth` in the C UI.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BridgeReport {
+    pub status: BridgeStatus,
+    pub firmware_major: u8,
+    pub firmware_minor: u8,
+    pub consecutive_failures: u32,
+    pub total_failures: u32,
+    pub garbage_responses: u32,
+    pub resets: u32,
+    /// Seconds since the last good response
+    pub last_ok_age_s: u32,
+}
+
+/// Command outcomes and reset history of the bridge
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BridgeHealth {
+    consecutive_failures: u32,
+    /// Failed commands since boot
+    pub total_failures: u32,
+    /// Responses that were neither a known status nor busy
+    pub garbage_responses: u32,
+    /// Reset attempts since boot (counted on boards without a reset line
+    /// too, where the bridge is only given time to come back)
+    pub resets: u32,
+    /// Resets since the last good response (drives the backoff)
+    resets_since_ok: u32,
+    last_ok_ms: u64,
+    last_heartbeat_ms: Option<u64>,
+    last_reset_ms: Option<u64>,
+}
+
+impl BridgeHealth {
+    /// Start tracking a bridge that answered during init at `now_ms`
+    pub fn new(now_ms: u64) -> Self {
+        Self {
+            last_ok_ms: now_ms,
+            ..Default::default()
+        }
+    }
+
+    /// A command got a valid response
+    pub fn record_ok(&mut self, now_ms: u64) {
+        self.consecutive_failures = 0;
+        self.resets_since_ok = 0;
+        self.last_ok_ms = now_ms;
+    }
+
+    /// A command failed (I2C error or timeout)
+    pub fn record_failure(&mut self) {
+        self.consecutive_failures += 1;
+        self.total_failures += 1;
+    }
+
+    /// A response made no sense (e.g. a floating bus reading 0xFF)
+    pub fn record_garbage(&mut self) {
+        self.garbage_responses += 1;
+        self.record_failure();
+    }
+
+    /// Failures since the last good response
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Time of the last good response
+    pub fn last_ok_ms(&self) -> u64 {
+        self.last_ok_ms
+    }
+
+    /// True when a heartbeat should be sent
+    pub fn heartbeat_due(&self, now_ms: u64) -> bool {
+        match self.last_heartbeat_ms {
+            Some(t) => now_ms.saturating_sub(t) >= HEARTBEAT_INTERVAL_MS,
+            None => true,
+        }
+    }
+
+    pub fn heartbeat_sent(&mut self, now_ms: u64) {
+        self.last_heartbeat_ms = Some(now_ms);
+    }
+
+    pub fn status(&self, now_ms: u64) -> BridgeStatus {
+        let since_reset = self.last_reset_ms.map(|t| now_ms.saturating_sub(t));
+        if since_reset.is_some_and(|ms| ms < PICO_BOOT_MS) {
+            return BridgeStatus::Recovering;
+        }
+        // Silence is counted from the later of the last answer and the last reset
+        let quiet_since = self.last_ok_ms.max(self.last_reset_ms.unwrap_or(0));
+        if self.consecutive_failures >= HUNG_AFTER_FAILURES
+            || now_ms.saturating_sub(quiet_since) >= HUNG_AFTER_SILENCE_MS
+        {
+            BridgeStatus::Hung
+        } else if self.consecutive_failures > 0 {
+            BridgeStatus::Degraded
+        } else {
+            BridgeStatus::Ok
+        }
+    }
+
+    /// Wait after the last reset before another one may be issued
+    fn reset_backoff_ms(&self) -> u64 {
+        match self.resets_since_ok {
+            0 => 0,
+            n => (RESET_BACKOFF_MS << (n - 1).min(16)).min(MAX_RESET_BACKOFF_MS),
+        }
+    }
+
+    /// True when the bridge is hung and the backoff since the last reset has passed
+    pub fn needs_reset(&self, now_ms: u64) -> bool {
+        let backoff_done = match self.last_reset_ms {
+            Some(t) => now_ms.saturating_sub(t) >= self.reset_backoff_ms(),
+            None => true,
+        };
+        backoff_done && self.status(now_ms) == BridgeStatus::Hung
+    }
+
+    /// Snapshot for a bridge running `firmware_version` (major, minor)
+    pub fn report(&self, now_ms: u64, firmware_version: (u8, u8)) -> BridgeReport {
+        BridgeReport {
+            status: self.status(now_ms),
+            firmware_major: firmware_version.0,
+            firmware_minor: firmware_version.1,
+            consecutive_failures: self.consecutive_failures,
+            total_failures: self.total_failures,
+            garbage_responses: self.garbage_responses,
+            resets: self.resets,
+            last_ok_age_s: (now_ms.saturating_sub(self.last_ok_ms) / 1000) as u32,
+        }
+    }
+
+    /// A reset was issued at `now_ms`
+    pub fn reset_started(&mut self, now_ms: u64) {
+        self.resets += 1;
+        self.resets_since_ok += 1;
+        self.consecutive_failures = 0;
+        self.last_reset_ms = Some(now_ms);
+        self.last_heartbeat_ms = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_degrade_then_hang() {
+        let mut health = BridgeHealth::new(0);
+        assert_eq!(health.status(10), BridgeStatus::Ok);
+
+        health.record_failure();
+        assert_eq!(health.status(20), BridgeStatus::Degraded);
+
+        for _ in 1..HUNG_AFTER_FAILURES {
+            health.record_garbage();
+        }
+        assert_eq!(health.status(30), BridgeStatus::Hung);
+        assert_eq!(health.total_failures, HUNG_AFTER_FAILURES);
+        assert_eq!(health.garbage_responses, HUNG_AFTER_FAILURES - 1);
+
+        health.record_ok(40);
+        assert_eq!(health.status(50), BridgeStatus::Ok);
+        assert_eq!(health.consecutive_failures(), 0);
+    }
+
+    #[test]
+    fn silence_counts_as_hung() {
+        let health = BridgeHealth::new(1_000);
+        assert_eq!(health.status(1_000 + HUNG_AFTER_SILENCE_MS - 1), BridgeStatus::Ok);
+        assert_eq!(health.status(1_000 + HUNG_AFTER_SILENCE_MS), BridgeStatus::Hung);
+    }
+
+    #[test]
+    fn heartbeat_interval() {
+        let mut health = BridgeHealth::new(0);
+        assert!(health.heartbeat_due(0));
+        health.heartbeat_sent(0);
+        assert!(!health.heartbeat_due(HEARTBEAT_INTERVAL_MS - 1));
+        assert!(health.heartbeat_due(HEARTBEAT_INTERVAL_MS));
+    }
+
+    #[test]
+    fn reset_recovers_or_backs_off() {
+        let mut health = BridgeHealth::new(0);
+        for _ in 0..HUNG_AFTER_FAILURES {
+            health.record_failure();
+        }
+        assert!(health.needs_reset(100));
+
+        health.reset_started(100);
+        assert_eq!(health.resets, 1);
+        assert_eq!(health.status(100 + PICO_BOOT_MS - 1), BridgeStatus::Recovering);
+        assert!(!health.needs_reset(100 + PICO_BOOT_MS - 1));
+
+        // Still dead after boot: hung again, but the next reset waits
+        for _ in 0..HUNG_AFTER_FAILURES {
+            health.record_failure();
+        }
+        assert_eq!(health.status(100 + PICO_BOOT_MS), BridgeStatus::Hung);
+        assert!(!health.needs_reset(100 + RESET_BACKOFF_MS - 1));
+        assert!(health.needs_reset(100 + RESET_BACKOFF_MS));
+
+        // The backoff doubles until a good response clears it
+        health.reset_started(100 + RESET_BACKOFF_MS);
+        for _ in 0..HUNG_AFTER_FAILURES {
+            health.record_failure();
+        }
+        assert!(!health.needs_reset(100 + 2 * RESET_BACKOFF_MS));
+        assert!(health.needs_reset(100 + 3 * RESET_BACKOFF_MS));
+
+        health.record_ok(200_000);
+        assert_eq!(health.status(200_001), BridgeStatus::Ok);
+        assert_eq!(health.resets, 2);
+    }
+
+    #[test]
+    fn report_snapshot() {
+        let mut health = BridgeHealth::new(1_000);
+        health.record_garbage();
+        let report = health.report(4_500, (1, 3));
+        assert_eq!(report.status, BridgeStatus::Degraded);
+        assert_eq!((report.firmware_major, report.firmware_minor), (1, 3));
+        assert_eq!((report.consecutive_failures, report.garbage_responses), (1, 1));
+        assert_eq!(report.last_ok_age_s, 3);
+        assert_eq!(report.status.as_str(), "degraded");
+    }
+}
@@ -0,0 +1,770 @@
+//! Spool tag format decoders
+//!
+//! Every supported tag format is a [`TagDecoder`]: it scores how sure it is
+//! that a tag holds its format, then decodes it. [`decode`] asks all of
+//! [`DECODERS`] and keeps the most confident answer, so the reader drivers
+//! only hand over the raw memory and a new vendor format is one more entry
+//! in the list.
+//!
+//! The backend keeps the same registry in `backend/tags/registry.py`, with
+//! the same decoder names and confidence scale; both sides decode the
+//! vectors in `testdata/tag_vectors.json` in their tests.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::tag::{
+    self, DecodedTagInfo, TAG_TYPE_MIFARE_1K, TAG_TYPE_MIFARE_4K, TAG_TYPE_NTAG,
+};
+
+/// Raw memory of a tag, as the reader drivers return it
+#[derive(Debug, Clone, Copy)]
+pub struct RawTag<'a> {
+    /// `tag::TAG_TYPE_*`
+    pub tag_type: u8,
+    pub uid: &'a [u8],
+    /// MIFARE Classic: blocks 1, 2, 4 and 5. NTAG: user memory from page 4,
+    /// possibly cut short (the Pico bridge reads pages 4-20).
+    pub data: &'a [u8],
+}
+
+impl RawTag<'_> {
+    fn is_mifare(&self) -> bool {
+        self.tag_type == TAG_TYPE_MIFARE_1K || self.tag_type == TAG_TYPE_MIFARE_4K
+    }
+
+    /// MIFARE block 1, 2, 4 or 5
+    fn block(&self, block: usize) -> Option<&[u8]> {
+        let index = match block {
+            1 => 0,
+            2 => 1,
+            4 => 2,
+            5 => 3,
+            _ => return None,
+        };
+        self.data.get(index * 16..(index + 1) * 16)
+    }
+}
+
+/// A tag format
+pub trait TagDecoder: Sync {
+    /// Decoder name, the same as the backend's
+    fn name(&self) -> &'static str;
+
+    /// How sure the decoder is that the tag holds its format, from 0 (not
+    /// its format) to 100 (certain)
+    fn confidence(&self, tag: &RawTag) -> u8;
+
+    /// Decode the tag, None if its data turns out to be unusable
+    fn decode(&self, tag: &RawTag) -> Option<DecodedTagInfo>;
+}
+
+/// Tag decoded by the most confident decoder
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub decoder: &'static str,
+    pub confidence: u8,
+    pub info: DecodedTagInfo,
+}
+
+/// Registered decoders; on equal confidence the earlier one wins
+pub static DECODERS: &[&dyn TagDecoder] = &[&BambuLab, &Creality, &SpoolEase, &Prusa, &OpenPrintTag];
+
+/// Decode a tag with [`DECODERS`]
+pub fn decode(tag: &RawTag) -> Option<Match> {
+    decode_with(DECODERS, tag)
+}
+
+/// Decode a tag with the most confident of `decoders` that can decode it
+pub fn decode_with(decoders: &[&dyn TagDecoder], tag: &RawTag) -> Option<Match> {
+    let mut scored: Vec<(u8, &dyn TagDecoder)> = decoders
+        .iter()
+        .map(|decoder| (decoder.confidence(tag), *decoder))
+        .filter(|(confidence, _)| *confidence > 0)
+        .collect();
+    scored.sort_by_key(|(confidence, _)| core::cmp::Reverse(*confidence));
+    scored.into_iter().find_map(|(confidence, decoder)| {
+        decoder.decode(tag).map(|info| Match {
+            decoder: decoder.name(),
+            confidence,
+            info,
+        })
+    })
+}
+
+/// Tag info for the display: decoded, or just the physical tag type
+pub fn tag_info(tag: &RawTag) -> DecodedTagInfo {
+    decode(tag).map(|m| m.info).unwrap_or_else(|| DecodedTagInfo {
+        tag_type_name: physical_type_name(tag.tag_type).to_string(),
+        ..Default::default()
+    })
+}
+
+fn physical_type_name(tag_type: u8) -> &'static str {
+    match tag_type {
+        TAG_TYPE_NTAG => "NTAG",
+        TAG_TYPE_MIFARE_1K | TAG_TYPE_MIFARE_4K => "MIFARE Classic",
+        _ => "Unknown",
+    }
+}
+
+// ============ Bambu Lab ============
+
+/// Bambu Lab MIFARE Classic tags
+///
+/// The RSA signature isn't checked, so a valid-looking tag scores 95.
+pub struct BambuLab;
+
+impl TagDecoder for BambuLab {
+    fn name(&self) -> &'static str {
+        "bambu"
+    }
+
+    fn confidence(&self, tag: &RawTag) -> u8 {
+        if !tag.is_mifare() {
+            return 0;
+        }
+        // Material ID, e.g. "GFA00"
+        if tag.block(1).is_some_and(|block| tag::extract_cstring(&block[8..]).starts_with("GF")) {
+            return 95;
+        }
+        // Filament type, e.g. "PLA"
+        if tag.block(2).is_some_and(|block| is_label(&tag::extract_cstring(block))) {
+            50
+        } else {
+            0
+        }
+    }
+
+    fn decode(&self, tag: &RawTag) -> Option<DecodedTagInfo> {
+        Some(tag::decode_bambu_tag(tag.data))
+    }
+}
+
+/// Printable ASCII with at least one letter, as in material names
+fn is_label(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes().all(|b| (0x20..0x7f).contains(&b))
+        && s.bytes().any(|b| b.is_ascii_alphabetic())
+}
+
+// ============ Creality ============
+
+/// Creality CFS MIFARE Classic tags
+///
+/// Sector 1 holds a 48-character record; blocks 4 and 5 carry its start:
+/// date (5), vendor (4), batch (2), filament ID (6), color "0RRGGBB" (7),
+/// length in meters (4). Tags written encrypted don't look like this
+/// record and are left to the other decoders.
+pub struct Creality;
+
+impl Creality {
+    fn record<'a>(tag: &RawTag<'a>) -> Option<&'a [u8]> {
+        if !tag.is_mifare() {
+            return None;
+        }
+        let record = tag.data.get(32..60)?;
+        let fields_ok = record.iter().all(u8::is_ascii_alphanumeric)
+            && record[5..9].iter().all(u8::is_ascii_hexdigit)
+            && record[17] == b'0'
+            && record[18..24].iter().all(u8::is_ascii_hexdigit)
+            && record[24..28].iter().all(u8::is_ascii_digit);
+        fields_ok.then_some(record)
+    }
+}
+
+impl TagDecoder for Creality {
+    fn name(&self) -> &'static str {
+        "creality"
+    }
+
+    fn confidence(&self, tag: &RawTag) -> u8 {
+        if Self::record(tag).is_some() {
+            80
+        } else {
+            0
+        }
+    }
+
+    fn decode(&self, tag: &RawTag) -> Option<DecodedTagInfo> {
+        let record = Self::record(tag)?;
+        let rgb = u32::from_str_radix(core::str::from_utf8(&record[18..24]).ok()?, 16).ok()?;
+        let color_rgba = rgb << 8 | 0xFF;
+        Some(DecodedTagInfo {
+            vendor: "Creality".to_string(),
+            color_name: tag::format_color_name(color_rgba),
+            color_rgba,
+            tag_type_name: "Creality".to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+// ============ SpoolEase ============
+
+const SPOOLEASE_HOST: &str = "info.filament3d.org";
+
+/// SpoolEase NDEF URL tags (https://info.filament3d.org/V2/?M=PLA&...)
+pub struct SpoolEase;
+
+impl SpoolEase {
+    fn url(tag: &RawTag) -> Option<(String, bool)> {
+        if tag.tag_type != TAG_TYPE_NTAG {
+            return None;
+        }
+        ndef_records(tag.data).into_iter().find_map(|record| {
+            let url = record.url()?;
+            url.contains(SPOOLEASE_HOST).then_some((url, record.truncated))
+        })
+    }
+}
+
+impl TagDecoder for SpoolEase {
+    fn name(&self) -> &'static str {
+        "spoolease"
+    }
+
+    fn confidence(&self, tag: &RawTag) -> u8 {
+        match Self::url(tag) {
+            Some((url, _)) if url.contains("/V2/") => 100,
+            Some(_) => 90,
+            None => 0,
+        }
+    }
+
+    fn decode(&self, tag: &RawTag) -> Option<DecodedTagInfo> {
+        let (url, truncated) = Self::url(tag)?;
+        let (_, query) = url.split_once('?')?;
+        let mut params: Vec<(&str, String)> = query
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .map(|(key, value)| (key, percent_decode(value)))
+            .collect();
+        if truncated {
+            // The last value may be cut off
+            params.pop();
+        }
+        let param = |key: &str| {
+            params
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default()
+        };
+
+        let color_code = param("CC");
+        let color_rgba = match color_code.len() {
+            6 => u32::from_str_radix(&color_code, 16).map(|rgb| rgb << 8 | 0xFF).ok(),
+            8 => u32::from_str_radix(&color_code, 16).ok(),
+            _ => None,
+        }
+        .unwrap_or(0);
+        let color_name = match param("CN") {
+            name if name.is_empty() && color_rgba != 0 => tag::format_color_name(color_rgba),
+            name => name,
+        };
+        Some(DecodedTagInfo {
+            vendor: param("B"),
+            material: param("M"),
+            material_subtype: param("MS"),
+            color_name,
+            color_rgba,
+            spool_weight: param("WL").parse().unwrap_or(0),
+            tag_type_name: "SpoolEase".to_string(),
+        })
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(core::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// ============ OpenPrintTag ============
+
+const OPENPRINTTAG_TYPE: &[u8] = b"application/vnd.openprinttag";
+
+/// Material type enum of OpenPrintTag key 9, as in the backend
+const OPENPRINTTAG_MATERIALS: [&str; 40] = [
+    "PLA", "PETG", "TPU", "ABS", "ASA", "PC", "PCTG", "PP", "PA6", "PA11", "PA12", "PA66", "CPE",
+    "TPE", "HIPS", "PHA", "PET", "PEI", "PBT", "PVB", "PVA", "PEKK", "PEEK", "BVOH", "TPC", "PPS",
+    "PPSU", "PVC", "PEBA", "PVDF", "PPA", "PCL", "PES", "PMMA", "POM", "PPE", "PS", "PSU", "TPI",
+    "SBS",
+];
+
+/// Fields of an OpenPrintTag main region
+#[derive(Debug, Default)]
+struct OpenPrintTagFields {
+    material: String,
+    name: String,
+    brand: String,
+    weight: i32,
+    color_rgba: u32,
+}
+
+impl OpenPrintTagFields {
+    fn read(tag: &RawTag) -> Option<Self> {
+        let payload = openprinttag_payload(tag)?;
+        // Meta region: key 0 is the main region's offset
+        let meta = cbor::uint_map(payload)?;
+        let main = match cbor::get(&meta, 0) {
+            Some(cbor::Value::Uint(offset)) if offset > 0 => cbor::uint_map(payload.get(offset as usize..)?)?,
+            _ => meta,
+        };
+
+        let mut fields = OpenPrintTagFields::default();
+        if let Some(cbor::Value::Uint(index)) = cbor::get(&main, 9) {
+            fields.material = OPENPRINTTAG_MATERIALS.get(index as usize).copied().unwrap_or("").to_string();
+        }
+        if let Some(cbor::Value::Text(name)) = cbor::get(&main, 10) {
+            fields.name = name.to_string();
+        }
+        if let Some(cbor::Value::Text(brand)) = cbor::get(&main, 11) {
+            fields.brand = brand.to_string();
+        }
+        if let Some(cbor::Value::Uint(weight)) = cbor::get(&main, 16) {
+            fields.weight = weight.min(i32::MAX as u64) as i32;
+        }
+        fields.color_rgba = match cbor::get(&main, 19) {
+            Some(cbor::Value::Bytes(&[r, g, b])) => u32::from_be_bytes([r, g, b, 0xFF]),
+            Some(cbor::Value::Bytes(&[r, g, b, a])) => u32::from_be_bytes([r, g, b, a]),
+            _ => 0,
+        };
+        Some(fields)
+    }
+
+    fn info(self, vendor: String, tag_type_name: &str) -> DecodedTagInfo {
+        // The material name usually repeats the material, e.g. "PETG Galaxy Black"
+        let color_name = self
+            .name
+            .split_whitespace()
+            .filter(|word| !word.eq_ignore_ascii_case(&self.material))
+            .collect::<Vec<_>>()
+            .join(" ");
+        DecodedTagInfo {
+            vendor,
+            material: self.material,
+            material_subtype: String::new(),
+            color_name,
+            color_rgba: self.color_rgba,
+            spool_weight: self.weight,
+            tag_type_name: tag_type_name.to_string(),
+        }
+    }
+}
+
+fn openprinttag_payload<'a>(tag: &RawTag<'a>) -> Option<&'a [u8]> {
+    if tag.tag_type != TAG_TYPE_NTAG {
+        return None;
+    }
+    ndef_records(tag.data)
+        .into_iter()
+        .find(|record| record.tnf == TNF_MIME && record.record_type == OPENPRINTTAG_TYPE)
+        .map(|record| record.payload)
+}
+
+/// OpenPrintTag NDEF MIME records with a CBOR payload
+pub struct OpenPrintTag;
+
+impl TagDecoder for OpenPrintTag {
+    fn name(&self) -> &'static str {
+        "openprinttag"
+    }
+
+    fn confidence(&self, tag: &RawTag) -> u8 {
+        if openprinttag_payload(tag).is_some() {
+            90
+        } else {
+            0
+        }
+    }
+
+    fn decode(&self, tag: &RawTag) -> Option<DecodedTagInfo> {
+        let fields = OpenPrintTagFields::read(tag)?;
+        let vendor = fields.brand.clone();
+        Some(fields.info(vendor, "OpenPrintTag"))
+    }
+}
+
+// ============ Prusa ============
+
+/// Prusament spools, whose OpenPrintTags name Prusa as the brand
+///
+/// Scores above the generic [`OpenPrintTag`] decoder and reports the
+/// vendor as "Prusament", however the brand is spelled on the tag.
+pub struct Prusa;
+
+impl TagDecoder for Prusa {
+    fn name(&self) -> &'static str {
+        "prusa"
+    }
+
+    fn confidence(&self, tag: &RawTag) -> u8 {
+        match OpenPrintTagFields::read(tag) {
+            Some(fields) if fields.brand.to_ascii_lowercase().starts_with("prusa") => 95,
+            _ => 0,
+        }
+    }
+
+    fn decode(&self, tag: &RawTag) -> Option<DecodedTagInfo> {
+        let fields = OpenPrintTagFields::read(tag)?;
+        Some(fields.info("Prusament".to_string(), "Prusament"))
+    }
+}
+
+// ============ NDEF ============
+
+const TNF_WELL_KNOWN: u8 = 0x01;
+const TNF_MIME: u8 = 0x02;
+
+/// NDEF record in NTAG memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdefRecord<'a> {
+    /// Type name format (1 = well-known, 2 = MIME)
+    pub tnf: u8,
+    pub record_type: &'a [u8],
+    pub payload: &'a [u8],
+    /// The payload runs past the end of the memory that was read
+    pub truncated: bool,
+}
+
+impl NdefRecord<'_> {
+    /// URL of a well-known "U" record
+    pub fn url(&self) -> Option<String> {
+        if self.tnf != TNF_WELL_KNOWN || self.record_type != b"U" {
+            return None;
+        }
+        let (&prefix, rest) = self.payload.split_first()?;
+        let prefix = match prefix {
+            0x01 => "http://www.",
+            0x02 => "https://www.",
+            0x03 => "http://",
+            0x04 => "https://",
+            _ => "",
+        };
+        Some(format!("{}{}", prefix, String::from_utf8_lossy(rest)))
+    }
+}
+
+/// Records of the NDEF message TLV in NTAG user memory (from page 4)
+pub fn ndef_records(memory: &[u8]) -> Vec<NdefRecord<'_>> {
+    let mut records = Vec::new();
+    let Some(mut message) = ndef_message(memory) else {
+        return records;
+    };
+
+    while let Some((&header, rest)) = message.split_first() {
+        let short = header & 0x10 != 0;
+        let has_id = header & 0x08 != 0;
+        let length_bytes = if short { 1 } else { 4 };
+        let Some(lengths) = rest.get(..1 + length_bytes) else {
+            break;
+        };
+        let type_len = lengths[0] as usize;
+        let payload_len = lengths[1..]
+            .iter()
+            .fold(0usize, |len, &b| len << 8 | b as usize);
+        let mut pos = 1 + length_bytes;
+        let id_len = if has_id {
+            pos += 1;
+            rest.get(pos - 1).copied().unwrap_or(0) as usize
+        } else {
+            0
+        };
+        let Some(record_type) = rest.get(pos..pos + type_len) else {
+            break;
+        };
+        pos += type_len + id_len;
+        let payload_start = pos.min(rest.len());
+        let payload_end = pos.saturating_add(payload_len);
+        records.push(NdefRecord {
+            tnf: header & 0x07,
+            record_type,
+            payload: &rest[payload_start..payload_end.min(rest.len())],
+            truncated: payload_end > rest.len(),
+        });
+
+        // Message end
+        if header & 0x40 != 0 || payload_end >= rest.len() {
+            break;
+        }
+        message = &rest[payload_end..];
+    }
+    records
+}
+
+/// NDEF message TLV value, cut short if the memory ends first
+fn ndef_message(memory: &[u8]) -> Option<&[u8]> {
+    let mut pos = 0;
+    while let Some(&tlv_type) = memory.get(pos) {
+        match tlv_type {
+            0x00 => {
+                pos += 1;
+                continue;
+            }
+            0xFE => return None,
+            _ => {}
+        }
+        let (length, header) = match *memory.get(pos + 1)? {
+            0xFF => {
+                let bytes = memory.get(pos + 2..pos + 4)?;
+                (u16::from_be_bytes([bytes[0], bytes[1]]) as usize, 4)
+            }
+            length => (length as usize, 2),
+        };
+        let start = pos + header;
+        if tlv_type == 0x03 {
+            return memory.get(start..).map(|rest| &rest[..length.min(rest.len())]);
+        }
+        // Lock or memory control TLV
+        pos = start + length;
+    }
+    None
+}
+
+// ============ CBOR ============
+
+/// Just enough CBOR to read OpenPrintTag's maps with integer keys
+mod cbor {
+    use alloc::vec::Vec;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Value<'a> {
+        Uint(u64),
+        Bytes(&'a [u8]),
+        Text(&'a str),
+        /// Negative integers, floats, arrays, maps and the rest
+        Other,
+    }
+
+    pub fn get<'a>(map: &[(u64, Value<'a>)], key: u64) -> Option<Value<'a>> {
+        map.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+
+    /// Entries of the map at the start of `data` whose keys are unsigned integers
+    pub fn uint_map(data: &[u8]) -> Option<Vec<(u64, Value<'_>)>> {
+        let (major, len, mut pos) = head(data, 0)?;
+        if major != 5 {
+            return None;
+        }
+        let mut entries = Vec::new();
+        for _ in 0..len {
+            let (key, next) = item(data, pos, 0)?;
+            let (value, next) = item(data, next, 0)?;
+            if let Value::Uint(key) = key {
+                entries.push((key, value));
+            }
+            pos = next;
+        }
+        Some(entries)
+    }
+
+    /// Major type, argument and the position after the head
+    fn head(data: &[u8], pos: usize) -> Option<(u8, u64, usize)> {
+        let initial = *data.get(pos)?;
+        let (major, info) = (initial >> 5, initial & 0x1F);
+        let (arg, size) = match info {
+            0..=23 => (info as u64, 0),
+            24..=27 => {
+                let size = 1 << (info - 24);
+                let bytes = data.get(pos + 1..pos + 1 + size)?;
+                (bytes.iter().fold(0u64, |arg, &b| arg << 8 | b as u64), size)
+            }
+            // Indefinite lengths aren't used by OpenPrintTag
+            _ => return None,
+        };
+        Some((major, arg, pos + 1 + size))
+    }
+
+    /// The item at `pos` and the position after it
+    fn item(data: &[u8], pos: usize, depth: u8) -> Option<(Value<'_>, usize)> {
+        if depth > 8 {
+            return None;
+        }
+        let (major, arg, pos) = head(data, pos)?;
+        let end = || pos.checked_add(usize::try_from(arg).ok()?).filter(|&end| end <= data.len());
+        Some(match major {
+            0 => (Value::Uint(arg), pos),
+            2 => {
+                let end = end()?;
+                (Value::Bytes(&data[pos..end]), end)
+            }
+            3 => {
+                let end = end()?;
+                (Value::Text(core::str::from_utf8(&data[pos..end]).ok()?), end)
+            }
+            4 | 5 => {
+                let items = if major == 5 { arg.checked_mul(2)? } else { arg };
+                let mut next = pos;
+                for _ in 0..items {
+                    next = item(data, next, depth + 1)?.1;
+                }
+                (Value::Other, next)
+            }
+            6 => (Value::Other, item(data, pos, depth + 1)?.1),
+            // Negative integers, simple values and floats: the head is all of it
+            _ => (Value::Other, pos),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use serde::Deserialize;
+
+    /// Test vector shared with the backend tests
+    #[derive(Deserialize)]
+    struct Vector {
+        name: String,
+        tag_type: u8,
+        uid: String,
+        data: String,
+        decoder: Option<String>,
+        #[serde(default)]
+        confidence: u8,
+        #[serde(default)]
+        vendor: String,
+        #[serde(default)]
+        material: String,
+        #[serde(default)]
+        color_rgba: Option<String>,
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        crate::encode::from_hex(s).unwrap()
+    }
+
+    #[test]
+    fn decodes_shared_vectors() {
+        let vectors: Vec<Vector> =
+            serde_json::from_str(include_str!("../testdata/tag_vectors.json")).unwrap();
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            let (uid, data) = (hex(&vector.uid), hex(&vector.data));
+            let tag = RawTag { tag_type: vector.tag_type, uid: &uid, data: &data };
+            let found = decode(&tag);
+            assert_eq!(
+                found.as_ref().map(|m| m.decoder),
+                vector.decoder.as_deref(),
+                "{}",
+                vector.name
+            );
+            let Some(found) = found else { continue };
+            assert_eq!(found.confidence, vector.confidence, "{}", vector.name);
+            assert_eq!(found.info.vendor, vector.vendor, "{}", vector.name);
+            assert_eq!(found.info.material, vector.material, "{}", vector.name);
+            if let Some(rgba) = vector.color_rgba {
+                assert_eq!(found.info.color_rgba, u32::from_str_radix(&rgba, 16).unwrap(), "{}", vector.name);
+            }
+        }
+    }
+
+    struct Fixed(&'static str, u8, bool);
+
+    impl TagDecoder for Fixed {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+        fn confidence(&self, _: &RawTag) -> u8 {
+            self.1
+        }
+        fn decode(&self, _: &RawTag) -> Option<DecodedTagInfo> {
+            self.2.then(DecodedTagInfo::default)
+        }
+    }
+
+    #[test]
+    fn most_confident_decoder_wins() {
+        let tag = RawTag { tag_type: TAG_TYPE_NTAG, uid: &[1], data: &[] };
+        let low = Fixed("low", 40, true);
+        let high = Fixed("high", 90, true);
+        let broken = Fixed("broken", 100, false);
+        let none = Fixed("none", 0, true);
+
+        let found = decode_with(&[&low, &broken, &high, &none], &tag).unwrap();
+        assert_eq!((found.decoder, found.confidence), ("high", 90));
+        // Ties go to the earlier decoder
+        assert_eq!(decode_with(&[&low, &Fixed("same", 40, true)], &tag).unwrap().decoder, "low");
+        assert_eq!(decode_with(&[&none], &tag), None);
+    }
+
+    #[test]
+    fn unknown_tags_keep_their_physical_type() {
+        let data = [0u8; 64];
+        let tag = RawTag { tag_type: TAG_TYPE_MIFARE_1K, uid: &[1, 2, 3, 4], data: &data };
+        assert_eq!(tag_info(&tag).tag_type_name, "MIFARE Classic");
+        let tag = RawTag { tag_type: TAG_TYPE_NTAG, uid: &[1], data: &[0x03, 0x00, 0xFE] };
+        assert_eq!(tag_info(&tag).tag_type_name, "NTAG");
+    }
+
+    #[test]
+    fn parses_ndef_records() {
+        // Lock control TLV, then a message with a MIME record and a URL record
+        let mut memory = vec![0x01, 0x03, 0xA0, 0x0C, 0x34, 0x00, 0x03, 0x10];
+        memory.extend_from_slice(&[0x92, 0x03, 0x02, b'a', b'/', b'b', 0x11, 0x22]);
+        memory.extend_from_slice(&[0x51, 0x01, 0x04, b'U', 0x04, b'x', b'.', b'y', 0xFE]);
+        let records = ndef_records(&memory);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].tnf, records[0].record_type), (TNF_MIME, &b"a/b"[..]));
+        assert_eq!(records[0].payload, [0x11, 0x22]);
+        assert_eq!(records[1].url().as_deref(), Some("https://x.y"));
+        assert!(!records[1].truncated);
+
+        // Cut off in the middle of the URL
+        let records = ndef_records(&memory[..22]);
+        assert_eq!(records[1].url().as_deref(), Some("https://x"));
+        assert!(records[1].truncated);
+    }
+
+    #[test]
+    fn truncated_spoolease_url_drops_last_value() {
+        let url = b"\x04info.filament3d.org/V2/?M=PETG&CN=Dark%20Red&B=Acm";
+        let mut memory = vec![0x03, url.len() as u8 + 4, 0xD1, 0x01, url.len() as u8 + 10, b'U'];
+        memory.extend_from_slice(url);
+        let tag = RawTag { tag_type: TAG_TYPE_NTAG, uid: &[1], data: &memory };
+
+        let found = decode(&tag).unwrap();
+        assert_eq!(found.decoder, "spoolease");
+        assert_eq!(found.info.material, "PETG");
+        assert_eq!(found.info.color_name, "Dark Red");
+        assert_eq!(found.info.vendor, "");
+    }
+
+    #[test]
+    fn cbor_skips_nested_items() {
+        // {1: [1, {2: 3}], 10: "PLA", -1: 0, 11: h'01'}
+        let data = [
+            0xA4, 0x01, 0x82, 0x01, 0xA1, 0x02, 0x03, 0x0A, 0x63, b'P', b'L', b'A', 0x20, 0x00, 0x0B,
+            0x41, 0x01,
+        ];
+        let map = cbor::uint_map(&data).unwrap();
+        assert_eq!(cbor::get(&map, 1), Some(cbor::Value::Other));
+        assert_eq!(cbor::get(&map, 10), Some(cbor::Value::Text("PLA")));
+        assert_eq!(cbor::get(&map, 11), Some(cbor::Value::Bytes(&[1])));
+        assert_eq!(map.len(), 3);
+        // Truncated
+        assert_eq!(cbor::uint_map(&data[..10]), None);
+    }
+}
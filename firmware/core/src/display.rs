@@ -0,0 +1,26 @@
+//! Display settings applied through a [`DisplayBackend`]
+
+use crate::hal::DisplayBackend;
+
+pub const MAX_BRIGHTNESS: u8 = 100;
+
+/// Set the backlight, clamped to 0-100%. Returns the level applied.
+pub fn apply_brightness<D: DisplayBackend>(display: &mut D, percent: u8) -> u8 {
+    let percent = percent.min(MAX_BRIGHTNESS);
+    display.set_backlight(percent);
+    percent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockDisplay;
+
+    #[test]
+    fn brightness_is_clamped() {
+        let mut display = MockDisplay::default();
+        assert_eq!(apply_brightness(&mut display, 150), 100);
+        assert_eq!(apply_brightness(&mut display, 40), 40);
+        assert_eq!(display.backlight, [100, 40]);
+    }
+}
@@ -0,0 +1,281 @@
+//! Writing spool tags
+//!
+//! In batch encoding mode the backend hands the device the bytes to put on
+//! an NTAG tag: a TLV-wrapped NDEF message that starts at page 4, right
+//! after the UID, lock bytes and capability container. The device writes
+//! it page by page and reads every page back before the tag counts as
+//! encoded, since a write the tag acknowledged can still have landed
+//! corrupted when the tag was pulled away mid-write.
+//!
+//! Failed writes and reads are retried; the report says how many retries
+//! it took and how strong the RF signal was, so the backend can spot tags
+//! (or a reader, or a placement) that are marginal.
+
+use alloc::vec::Vec;
+
+use crate::hal::TagMemory;
+
+/// First NTAG user memory page
+pub const NDEF_START_PAGE: u8 = 4;
+
+/// NTAG user memory page size
+pub const PAGE_SIZE: usize = 4;
+
+/// Largest NTAG user memory (NTAG216); smaller tags fail the write at their end
+pub const MAX_DATA_LEN: usize = 888;
+
+/// Retries per page write or read before giving up on the tag
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// How writing a tag ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteOutcome {
+    /// Written and read back identical
+    Verified,
+    /// A page write kept failing (tag removed, locked or too small)
+    WriteFailed,
+    /// Reading back kept failing
+    ReadFailed,
+    /// A page read back different from what was written, even after rewriting it
+    Mismatch,
+    /// More data than any NTAG holds
+    TooLarge,
+}
+
+impl WriteOutcome {
+    /// Wire name, as reported to the backend
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WriteOutcome::Verified => "verified",
+            WriteOutcome::WriteFailed => "write_failed",
+            WriteOutcome::ReadFailed => "read_failed",
+            WriteOutcome::Mismatch => "mismatch",
+            WriteOutcome::TooLarge => "too_large",
+        }
+    }
+}
+
+/// Result of [`write_and_verify`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteReport {
+    pub outcome: WriteOutcome,
+    /// Page writes that succeeded, rewrites included
+    pub pages_written: u32,
+    /// Failed writes or reads that were retried, plus rewrites after a mismatch
+    pub retries: u32,
+    /// Page the write or verification stopped at, None once verified
+    pub failed_page: Option<u8>,
+    /// [`TagMemory::rf_signal`] before writing
+    pub rf_signal: Option<u16>,
+}
+
+/// Write `data` from [`NDEF_START_PAGE`] on and verify it by reading it back
+///
+/// The last page is padded with zeros.
+pub fn write_and_verify<M: TagMemory>(tag: &mut M, data: &[u8], max_retries: u32) -> WriteReport {
+    let mut report = WriteReport {
+        outcome: WriteOutcome::Verified,
+        pages_written: 0,
+        retries: 0,
+        failed_page: None,
+        rf_signal: tag.rf_signal(),
+    };
+    if data.len() > MAX_DATA_LEN {
+        report.outcome = WriteOutcome::TooLarge;
+        return report;
+    }
+
+    let pages: Vec<[u8; PAGE_SIZE]> = data
+        .chunks(PAGE_SIZE)
+        .map(|chunk| {
+            let mut page = [0; PAGE_SIZE];
+            page[..chunk.len()].copy_from_slice(chunk);
+            page
+        })
+        .collect();
+
+    for (i, page) in pages.iter().enumerate() {
+        let number = NDEF_START_PAGE + i as u8;
+        if write_page(tag, number, page, max_retries, &mut report).is_err() {
+            return fail(report, WriteOutcome::WriteFailed, number);
+        }
+    }
+
+    // READ returns four pages at a time
+    for (chunk_index, chunk) in pages.chunks(4).enumerate() {
+        let first = NDEF_START_PAGE + (chunk_index * 4) as u8;
+        let mut rewrites = 0;
+        loop {
+            let Ok(read) = read_pages(tag, first, max_retries, &mut report) else {
+                return fail(report, WriteOutcome::ReadFailed, first);
+            };
+            let mismatch = chunk
+                .iter()
+                .enumerate()
+                .find(|(i, page)| read[i * PAGE_SIZE..(i + 1) * PAGE_SIZE] != page[..]);
+            let Some((i, page)) = mismatch else {
+                break;
+            };
+
+            let number = first + i as u8;
+            if rewrites == max_retries {
+                return fail(report, WriteOutcome::Mismatch, number);
+            }
+            rewrites += 1;
+            report.retries += 1;
+            if write_page(tag, number, page, max_retries, &mut report).is_err() {
+                return fail(report, WriteOutcome::WriteFailed, number);
+            }
+        }
+    }
+
+    report
+}
+
+fn fail(mut report: WriteReport, outcome: WriteOutcome, page: u8) -> WriteReport {
+    report.outcome = outcome;
+    report.failed_page = Some(page);
+    report
+}
+
+fn write_page<M: TagMemory>(
+    tag: &mut M,
+    page: u8,
+    data: &[u8; PAGE_SIZE],
+    max_retries: u32,
+    report: &mut WriteReport,
+) -> Result<(), M::Error> {
+    let mut attempt = 0;
+    loop {
+        match tag.write_page(page, data) {
+            Ok(()) => {
+                report.pages_written += 1;
+                return Ok(());
+            }
+            Err(e) if attempt == max_retries => return Err(e),
+            Err(_) => {
+                attempt += 1;
+                report.retries += 1;
+            }
+        }
+    }
+}
+
+fn read_pages<M: TagMemory>(
+    tag: &mut M,
+    page: u8,
+    max_retries: u32,
+    report: &mut WriteReport,
+) -> Result<[u8; 16], M::Error> {
+    let mut attempt = 0;
+    loop {
+        match tag.read_pages(page) {
+            Ok(data) => return Ok(data),
+            Err(e) if attempt == max_retries => return Err(e),
+            Err(_) => {
+                attempt += 1;
+                report.retries += 1;
+            }
+        }
+    }
+}
+
+/// Decode the hex tag data sent by the backend
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let pairs = hex.trim().as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| {
+            let high = (pair[0] as char).to_digit(16)?;
+            let low = (pair[1] as char).to_digit(16)?;
+            Some((high << 4 | low) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockTag;
+
+    // NDEF TLV with a short URL record, 14 bytes -> 4 pages
+    const DATA: &[u8] = b"\x03\x0b\xd1\x01\x07U\x04ab.cd/\xfe";
+
+    #[test]
+    fn writes_and_verifies() {
+        let mut tag = MockTag::new(45);
+        let report = write_and_verify(&mut tag, DATA, DEFAULT_MAX_RETRIES);
+
+        assert_eq!(report.outcome, WriteOutcome::Verified);
+        assert_eq!(report.pages_written, 4);
+        assert_eq!(report.retries, 0);
+        assert_eq!(report.rf_signal, None);
+        assert_eq!(&tag.pages[4][..], &DATA[..4]);
+        // Last page padded
+        assert_eq!(tag.pages[7], [b'/', 0xfe, 0, 0]);
+        // Capability container untouched
+        assert_eq!(tag.pages[3], [0; 4]);
+    }
+
+    #[test]
+    fn retries_failed_writes() {
+        let mut tag = MockTag::new(45);
+        tag.failing_writes = 2;
+        tag.rf_signal = Some(412);
+        let report = write_and_verify(&mut tag, DATA, DEFAULT_MAX_RETRIES);
+        assert_eq!(report.outcome, WriteOutcome::Verified);
+        assert_eq!(report.retries, 2);
+        assert_eq!(report.rf_signal, Some(412));
+
+        let mut tag = MockTag::new(45);
+        tag.failing_writes = 10;
+        let report = write_and_verify(&mut tag, DATA, DEFAULT_MAX_RETRIES);
+        assert_eq!(report.outcome, WriteOutcome::WriteFailed);
+        assert_eq!(report.failed_page, Some(NDEF_START_PAGE));
+        assert_eq!(tag.writes, DEFAULT_MAX_RETRIES + 1);
+    }
+
+    #[test]
+    fn detects_corrupted_pages() {
+        let mut tag = MockTag::new(45);
+        tag.corrupt_pages = alloc::vec![6];
+        let report = write_and_verify(&mut tag, DATA, DEFAULT_MAX_RETRIES);
+
+        assert_eq!(report.outcome, WriteOutcome::Mismatch);
+        assert_eq!(report.failed_page, Some(6));
+        assert_eq!(report.pages_written, 4 + DEFAULT_MAX_RETRIES);
+    }
+
+    #[test]
+    fn tag_too_small_or_data_too_large() {
+        // NTAG213-sized memory ends before the data does
+        let mut tag = MockTag::new(6);
+        let report = write_and_verify(&mut tag, DATA, 0);
+        assert_eq!(report.outcome, WriteOutcome::WriteFailed);
+        assert_eq!(report.failed_page, Some(6));
+
+        let mut tag = MockTag::new(45);
+        let report = write_and_verify(&mut tag, &[0; MAX_DATA_LEN + 1], 0);
+        assert_eq!(report.outcome, WriteOutcome::TooLarge);
+        assert_eq!(tag.writes, 0);
+    }
+
+    #[test]
+    fn unreadable_tag() {
+        let mut tag = MockTag::new(45);
+        tag.fail_reads = true;
+        let report = write_and_verify(&mut tag, DATA, 1);
+        assert_eq!(report.outcome, WriteOutcome::ReadFailed);
+        assert_eq!(report.retries, 1);
+    }
+
+    #[test]
+    fn hex_data() {
+        assert_eq!(from_hex("0310d1FE\n"), Some(alloc::vec![0x03, 0x10, 0xd1, 0xfe]));
+        assert_eq!(from_hex(""), Some(alloc::vec![]));
+        assert_eq!(from_hex("031"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! Hardware abstraction traits
+//!
+//! Implemented by the real drivers in the firmware crate and by mocks in the
+//! tests, so nothing in this crate touches hardware directly.
+
+use crate::tag::{DecodedTagInfo, TagEvent};
+
+/// NFC reader
+pub trait Nfc {
+    type Error;
+
+    /// Advance the reader without blocking; returns a tag change, if any
+    fn poll(&mut self) -> Result<Option<TagEvent>, Self::Error>;
+
+    /// UID of the tag on the reader
+    fn uid(&self) -> Option<&[u8]>;
+
+    /// Decoded contents of the tag on the reader, once read
+    fn tag_info(&self) -> Option<&DecodedTagInfo>;
+}
+
+/// Load cell ADC
+pub trait Scale {
+    type Error;
+
+    /// True when a new conversion is available
+    fn data_ready(&mut self) -> Result<bool, Self::Error>;
+
+    /// Latest conversion as a signed raw value
+    fn read_raw(&mut self) -> Result<i32, Self::Error>;
+
+    /// Wait between data-ready polls
+    fn delay_ms(&mut self, ms: u32);
+}
+
+/// Display panel and UI toolkit
+pub trait DisplayBackend {
+    type Error;
+
+    /// Bring up the panel, touch controller and UI
+    fn init(&mut self) -> Result<(), Self::Error>;
+
+    /// Run UI timers and redraw (call every main loop iteration)
+    fn tick(&mut self);
+
+    /// Backlight brightness in percent
+    fn set_backlight(&mut self, percent: u8);
+
+    /// Blank the panel before a reboot
+    fn shutdown(&mut self);
+}
+
+/// NTAG page access, for writing tags
+pub trait TagMemory {
+    type Error;
+
+    /// Write one 4-byte page
+    fn write_page(&mut self, page: u8, data: &[u8; 4]) -> Result<(), Self::Error>;
+
+    /// Read four pages (16 bytes) starting at `page`
+    fn read_pages(&mut self, page: u8) -> Result<[u8; 16], Self::Error>;
+
+    /// Raw RF signal reading with the tag in the field, None if the reader
+    /// can't measure it. Only comparable between writes on the same reader.
+    fn rf_signal(&mut self) -> Option<u16> {
+        None
+    }
+}
@@ -0,0 +1,35 @@
+//! SpoolBuddy firmware core
+//!
+//! Hardware-independent logic shared by the ESP32 firmware: the NFC tag
+//! format decoders, tag presence tracking and writing, load cell math, the
+//! weight filter chain, the messages exchanged with the backend, Pico
+//! bridge health tracking, OTA image signature checks and the supported
+//! board presets.
+//! Hardware is reached only through the traits in [`hal`], which the
+//! firmware implements with the real drivers (Pico NFC bridge, NAU7802, the
+//! C display driver) and the tests implement with mocks.
+//!
+//! The crate is `no_std` (with `alloc`), so it builds for the ESP32 and runs
+//! under `cargo test` on the host:
+//!
+//! ```text
+//! cd firmware/core && cargo test
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod board;
+pub mod bridge_health;
+pub mod decode;
+pub mod display;
+pub mod encode;
+pub mod hal;
+pub mod ota;
+pub mod proto;
+pub mod tag;
+pub mod weight;
+
+#[cfg(test)]
+mod mock;
@@ -0,0 +1,175 @@
+//! Host-side mocks of the [`hal`](crate::hal) traits
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::hal::{DisplayBackend, Nfc, Scale, TagMemory};
+use crate::tag::{DecodedTagInfo, TagEvent};
+
+#[derive(Debug, PartialEq)]
+pub struct MockError;
+
+/// Reader that replays a list of poll results
+#[derive(Default)]
+pub struct MockNfc {
+    events: VecDeque<Option<TagEvent>>,
+    pub uid: Option<Vec<u8>>,
+    pub info: Option<DecodedTagInfo>,
+    pub fail: bool,
+}
+
+impl MockNfc {
+    pub fn new(events: Vec<Option<TagEvent>>) -> Self {
+        Self {
+            events: events.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Nfc for MockNfc {
+    type Error = MockError;
+
+    fn poll(&mut self) -> Result<Option<TagEvent>, MockError> {
+        if self.fail {
+            return Err(MockError);
+        }
+        Ok(self.events.pop_front().flatten())
+    }
+
+    fn uid(&self) -> Option<&[u8]> {
+        self.uid.as_deref()
+    }
+
+    fn tag_info(&self) -> Option<&DecodedTagInfo> {
+        self.info.as_ref()
+    }
+}
+
+/// ADC that returns queued raw readings, then fails
+#[derive(Default)]
+pub struct MockScale {
+    readings: VecDeque<i32>,
+    /// Data-ready polls answered "not ready" before the first conversion
+    pub not_ready_polls: u32,
+    /// Number of `delay_ms` calls
+    pub delays: u32,
+}
+
+impl MockScale {
+    pub fn new(readings: Vec<i32>) -> Self {
+        Self {
+            readings: readings.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Scale for MockScale {
+    type Error = MockError;
+
+    fn data_ready(&mut self) -> Result<bool, MockError> {
+        if self.not_ready_polls > 0 {
+            self.not_ready_polls -= 1;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn read_raw(&mut self) -> Result<i32, MockError> {
+        self.readings.pop_front().ok_or(MockError)
+    }
+
+    fn delay_ms(&mut self, _ms: u32) {
+        self.delays += 1;
+    }
+}
+
+/// Display that records backlight changes
+#[derive(Default)]
+pub struct MockDisplay {
+    pub initialized: bool,
+    pub ticks: u32,
+    pub backlight: Vec<u8>,
+}
+
+impl DisplayBackend for MockDisplay {
+    type Error = MockError;
+
+    fn init(&mut self) -> Result<(), MockError> {
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    fn set_backlight(&mut self, percent: u8) {
+        self.backlight.push(percent);
+    }
+
+    fn shutdown(&mut self) {
+        self.initialized = false;
+    }
+}
+
+/// NTAG memory with scripted write failures and corrupted pages
+pub struct MockTag {
+    pub pages: Vec<[u8; 4]>,
+    /// Writes that fail before the tag starts accepting them
+    pub failing_writes: u32,
+    /// Pages that read back with a flipped bit once written
+    pub corrupt_pages: Vec<u8>,
+    pub fail_reads: bool,
+    pub writes: u32,
+    pub rf_signal: Option<u16>,
+}
+
+impl MockTag {
+    /// Blank NTAG with this many pages
+    pub fn new(pages: usize) -> Self {
+        Self {
+            pages: alloc::vec![[0; 4]; pages],
+            failing_writes: 0,
+            corrupt_pages: Vec::new(),
+            fail_reads: false,
+            writes: 0,
+            rf_signal: None,
+        }
+    }
+}
+
+impl TagMemory for MockTag {
+    type Error = MockError;
+
+    fn write_page(&mut self, page: u8, data: &[u8; 4]) -> Result<(), MockError> {
+        self.writes += 1;
+        if self.failing_writes > 0 {
+            self.failing_writes -= 1;
+            return Err(MockError);
+        }
+        let slot = self.pages.get_mut(page as usize).ok_or(MockError)?;
+        *slot = *data;
+        if self.corrupt_pages.contains(&page) {
+            slot[0] ^= 0x01;
+        }
+        Ok(())
+    }
+
+    fn read_pages(&mut self, page: u8) -> Result<[u8; 16], MockError> {
+        if self.fail_reads {
+            return Err(MockError);
+        }
+        // Like NTAG READ, pages past the end wrap around
+        let mut data = [0; 16];
+        for (i, chunk) in data.chunks_mut(4).enumerate() {
+            chunk.copy_from_slice(&self.pages[(page as usize + i) % self.pages.len()]);
+        }
+        Ok(data)
+    }
+
+    fn rf_signal(&mut self) -> Option<u16> {
+        self.rf_signal
+    }
+}
@@ -0,0 +1,152 @@
+//! Signed OTA images
+//!
+//! Release images are signed with an ed25519 key kept off the LAN server.
+//! The matching public key is baked into the firmware at build time, and
+//! the device refuses to flash an image whose detached signature doesn't
+//! verify against it, so a compromised server can't push its own firmware.
+//!
+//! The signature covers the whole image as downloaded. It is passed around
+//! as 128 hex characters: in the `<image>.bin.sig` file written by the
+//! `ota-sign` tool (`firmware/tools/ota-sign`) and in the
+//! [`SIGNATURE_HEADER`] of the backend's OTA download. Public keys are 64
+//! hex characters.
+
+use alloc::string::String;
+use ed25519_compact::{PublicKey, Signature};
+
+pub const PUBLIC_KEY_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+
+/// Response header of `GET /api/firmware/ota` carrying the image signature
+pub const SIGNATURE_HEADER: &str = "X-Firmware-Signature";
+
+/// Why an image was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The server sent no signature
+    Missing,
+    /// Not a hex signature (or key) of the right length
+    Malformed,
+    /// The signature doesn't match the image and the baked-in key
+    Invalid,
+}
+
+impl SignatureError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            SignatureError::Missing => "Firmware is not signed",
+            SignatureError::Malformed => "Malformed firmware signature",
+            SignatureError::Invalid => "Firmware signature doesn't match",
+        }
+    }
+}
+
+/// Parse a hex public key
+pub fn parse_public_key(hex: &str) -> Result<[u8; PUBLIC_KEY_LEN], SignatureError> {
+    decode_hex(hex)
+}
+
+/// Parse a hex signature
+pub fn parse_signature(hex: &str) -> Result<[u8; SIGNATURE_LEN], SignatureError> {
+    decode_hex(hex)
+}
+
+/// Check an image against its signature and the firmware's public key
+pub fn verify_image(
+    image: &[u8],
+    signature: &[u8; SIGNATURE_LEN],
+    public_key: &[u8; PUBLIC_KEY_LEN],
+) -> Result<(), SignatureError> {
+    PublicKey::new(*public_key)
+        .verify(image, &Signature::new(*signature))
+        .map_err(|_| SignatureError::Invalid)
+}
+
+/// Lowercase hex, as written to key and signature files
+pub fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0x0F) as usize] as char);
+    }
+    hex
+}
+
+fn decode_hex<const N: usize>(hex: &str) -> Result<[u8; N], SignatureError> {
+    let hex = hex.trim().as_bytes();
+    if hex.len() != N * 2 {
+        return Err(SignatureError::Malformed);
+    }
+    let mut bytes = [0u8; N];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(bytes)
+}
+
+fn nibble(c: u8) -> Result<u8, SignatureError> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(SignatureError::Malformed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_compact::{KeyPair, Seed};
+
+    const IMAGE: &[u8] = b"\xE9\x05firmware image";
+
+    fn key_pair(seed: u8) -> KeyPair {
+        KeyPair::from_seed(Seed::new([seed; 32]))
+    }
+
+    fn sign(key_pair: &KeyPair, image: &[u8]) -> [u8; SIGNATURE_LEN] {
+        *key_pair.sk.sign(image, None)
+    }
+
+    #[test]
+    fn signed_image_verifies() {
+        let keys = key_pair(1);
+        assert_eq!(verify_image(IMAGE, &sign(&keys, IMAGE), &keys.pk), Ok(()));
+    }
+
+    #[test]
+    fn tampered_image_is_refused() {
+        let keys = key_pair(1);
+        let signature = sign(&keys, IMAGE);
+
+        let mut tampered = IMAGE.to_vec();
+        tampered[5] ^= 0x01;
+        assert_eq!(verify_image(&tampered, &signature, &keys.pk), Err(SignatureError::Invalid));
+    }
+
+    #[test]
+    fn other_key_is_refused() {
+        let signature = sign(&key_pair(2), IMAGE);
+        assert_eq!(verify_image(IMAGE, &signature, &key_pair(1).pk), Err(SignatureError::Invalid));
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let keys = key_pair(3);
+        let signature = sign(&keys, IMAGE);
+
+        assert_eq!(parse_public_key(&to_hex(&keys.pk[..])), Ok(*keys.pk));
+        assert_eq!(parse_signature(&to_hex(&signature)), Ok(signature));
+        // Key files end with a newline, headers may be uppercase
+        let upper = alloc::format!("{}\n", to_hex(&signature).to_uppercase());
+        assert_eq!(parse_signature(&upper), Ok(signature));
+    }
+
+    #[test]
+    fn malformed_hex_is_refused() {
+        assert_eq!(parse_signature(""), Err(SignatureError::Malformed));
+        assert_eq!(parse_public_key(&"ab".repeat(31)), Err(SignatureError::Malformed));
+        assert_eq!(parse_public_key(&"zz".repeat(32)), Err(SignatureError::Malformed));
+    }
+}
@@ -0,0 +1,393 @@
+//! Messages exchanged with the SpoolBuddy backend
+//!
+//! The backend mirrors these types in `backend/models.py`; both sides must
+//! stay in step. Unknown fields are ignored and missing ones default, so an
+//! older firmware keeps working against a newer backend and vice versa.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Reply to `POST /api/display/state`
+///
+/// Carries the spool resolved from the reported tag, so the display can show
+/// it straight away instead of fetching `/api/display/status` afterwards.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceStateResponse {
+    #[serde(default)]
+    pub ok: bool,
+    /// Tag UID the reply refers to, None for weight-only updates
+    #[serde(default)]
+    pub tag_id: Option<String>,
+    /// Inventory spool matching the tag, None if the tag is unknown
+    #[serde(default)]
+    pub spool: Option<ResolvedSpool>,
+    #[serde(default)]
+    pub action: SuggestedAction,
+    /// Kiosk screen to show, None unless kiosk mode is on
+    #[serde(default)]
+    pub kiosk: Option<KioskStatus>,
+    /// Tag to write, None unless a batch encoding job is running
+    #[serde(default)]
+    pub encode: Option<EncodeTask>,
+}
+
+/// Inventory spool matched to a tag on the scale
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedSpool {
+    pub id: String,
+    #[serde(default)]
+    pub spool_number: Option<u32>,
+    /// Display name, e.g. "Bambu Lab PLA Basic"
+    pub name: String,
+    #[serde(default)]
+    pub brand: Option<String>,
+    #[serde(default)]
+    pub material: String,
+    #[serde(default)]
+    pub subtype: Option<String>,
+    #[serde(default)]
+    pub color_name: Option<String>,
+    /// Packed 0xRRGGBBAA
+    #[serde(default)]
+    pub color_rgba: u32,
+    /// Net filament left in grams, None if the label weight is unknown
+    #[serde(default)]
+    pub remaining_g: Option<f32>,
+    #[serde(default)]
+    pub label_weight: Option<u32>,
+    #[serde(default)]
+    pub low_stock: bool,
+}
+
+impl ResolvedSpool {
+    /// Remaining filament as 0-100% of the label weight
+    pub fn remaining_percent(&self) -> Option<u8> {
+        let remaining = self.remaining_g?;
+        let label = self.label_weight.filter(|w| *w > 0)? as f32;
+        Some((remaining / label * 100.0).clamp(0.0, 100.0) as u8)
+    }
+}
+
+/// What the display should offer the user next
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestedAction {
+    /// Nothing to do
+    #[default]
+    None,
+    /// Tag is not in the inventory yet
+    AddSpool,
+    /// Scale reading differs from the tracked weight
+    UpdateWeight,
+    /// Spool is below the low stock threshold
+    Reorder,
+    /// Action added by a newer backend
+    #[serde(other)]
+    Unknown,
+}
+
+impl SuggestedAction {
+    /// Short prompt for the display, None if there is nothing to show
+    pub fn prompt(self) -> Option<&'static str> {
+        match self {
+            SuggestedAction::AddSpool => Some("Add to inventory"),
+            SuggestedAction::UpdateWeight => Some("Update weight"),
+            SuggestedAction::Reorder => Some("Running low - reorder"),
+            SuggestedAction::None | SuggestedAction::Unknown => None,
+        }
+    }
+}
+
+/// Step of the check-in/check-out kiosk flow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KioskState {
+    /// Waiting for a member badge
+    #[default]
+    ScanBadge,
+    /// Member signed in, waiting for a spool
+    ScanSpool,
+    /// Spool just checked out to the member
+    CheckedOut,
+    /// Spool just checked back in
+    CheckedIn,
+    /// Tag is neither a badge nor a spool
+    UnknownTag,
+    /// State added by a newer backend
+    #[serde(other)]
+    Unknown,
+}
+
+/// What the kiosk screen shows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KioskStatus {
+    #[serde(default)]
+    pub state: KioskState,
+    /// Signed-in member
+    #[serde(default)]
+    pub member_name: Option<String>,
+    /// Spool just checked out or in
+    #[serde(default)]
+    pub spool_name: Option<String>,
+    /// Filament used while checked out, on check-in
+    #[serde(default)]
+    pub weight_used: Option<f32>,
+    #[serde(default)]
+    pub message: String,
+    /// Seconds until the member is signed out
+    #[serde(default)]
+    pub expires_in: Option<u32>,
+}
+
+/// Next tag of a batch encoding job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EncodeTask {
+    pub job_id: String,
+    /// 1-based position in the batch
+    pub index: u32,
+    pub total: u32,
+    pub spool_id: String,
+    /// Spool to encode, e.g. "Red PETG"
+    pub label: String,
+    /// Tag on the reader the data was built for
+    #[serde(default)]
+    pub tag_uid: Option<String>,
+    /// Hex bytes to write from page 4 on, None until a blank tag is placed
+    #[serde(default)]
+    pub data: Option<String>,
+    /// Prompt or result of the last attempt
+    #[serde(default)]
+    pub message: String,
+}
+
+/// Body of `POST /api/tags/batch/result`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EncodeResult {
+    pub job_id: String,
+    pub index: u32,
+    pub tag_uid: String,
+    /// [`WriteOutcome`](crate::encode::WriteOutcome) wire name
+    pub outcome: String,
+    pub pages_written: u32,
+    pub retries: u32,
+    pub failed_page: Option<u8>,
+    /// Raw RF signal reading of the reader, None if it can't measure it
+    #[serde(default)]
+    pub rf_signal: Option<u16>,
+}
+
+/// Body of `POST /api/devices/{id}/diagnostics`, also shown on the
+/// device's diagnostics screen
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceDiagnostics {
+    #[serde(default)]
+    pub firmware_version: String,
+    #[serde(default)]
+    pub uptime_s: u64,
+    /// Internal RAM
+    #[serde(default)]
+    pub heap: MemoryUsage,
+    /// External PSRAM, None on boards without it
+    #[serde(default)]
+    pub psram: Option<MemoryUsage>,
+    /// None while WiFi is disconnected
+    #[serde(default)]
+    pub wifi_rssi: Option<i8>,
+    /// Shared I2C bus counters per client
+    #[serde(default)]
+    pub i2c: Vec<I2cCounters>,
+    /// Pico bridge firmware as "major.minor", None without a bridge
+    #[serde(default)]
+    pub nfc_bridge_firmware: Option<String>,
+    /// Pico bridge state ("ok", "degraded", "hung", "recovering")
+    #[serde(default)]
+    pub nfc_bridge_status: Option<String>,
+    /// Unfiltered ADC reading per load cell
+    #[serde(default)]
+    pub scale_raw: Vec<i32>,
+    /// Rendered frames per second over the last second
+    #[serde(default)]
+    pub fps: f32,
+}
+
+/// Heap usage in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub free: u32,
+    pub total: u32,
+    /// Lowest free value since boot
+    #[serde(default)]
+    pub min_free: u32,
+}
+
+/// Error counters of one shared I2C bus client
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct I2cCounters {
+    /// "scale", "nfc" or "touch"
+    pub client: String,
+    /// Failed transactions
+    #[serde(default)]
+    pub errors: u32,
+    /// Times the client gave up waiting for the bus
+    #[serde(default)]
+    pub timeouts: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resolved_spool() {
+        let body = r#"{
+            "ok": true,
+            "tag_id": "A7B26500",
+            "spool": {
+                "id": "abc",
+                "spool_number": 12,
+                "name": "Bambu Lab PLA Basic",
+                "brand": "Bambu Lab",
+                "material": "PLA",
+                "subtype": "Basic",
+                "color_name": "Gray",
+                "color_rgba": 2796137215,
+                "remaining_g": 150.5,
+                "label_weight": 1000,
+                "low_stock": true
+            },
+            "action": "reorder"
+        }"#;
+
+        let response: DeviceStateResponse = serde_json::from_str(body).unwrap();
+        let spool = response.spool.unwrap();
+        assert_eq!(spool.spool_number, Some(12));
+        assert_eq!(spool.color_rgba, 0xA6A9AAFF);
+        assert_eq!(spool.remaining_percent(), Some(15));
+        assert!(spool.low_stock);
+        assert_eq!(response.action, SuggestedAction::Reorder);
+    }
+
+    #[test]
+    fn legacy_reply_defaults() {
+        let response: DeviceStateResponse = serde_json::from_str(r#"{"ok": true}"#).unwrap();
+        assert!(response.ok);
+        assert_eq!(response.spool, None);
+        assert_eq!(response.action, SuggestedAction::None);
+        assert_eq!(response.action.prompt(), None);
+    }
+
+    #[test]
+    fn unknown_action_is_tolerated() {
+        let response: DeviceStateResponse =
+            serde_json::from_str(r#"{"ok": true, "action": "dry_spool", "extra": 1}"#).unwrap();
+        assert_eq!(response.action, SuggestedAction::Unknown);
+    }
+
+    #[test]
+    fn parses_kiosk_status() {
+        let body = r##"{
+            "ok": true,
+            "action": "none",
+            "kiosk": {
+                "state": "checked_in",
+                "member_name": "Ada",
+                "spool_name": "#12 Bambu Lab PLA Black",
+                "weight_used": 149.5,
+                "message": "Checked in, 150 g used",
+                "expires_in": 58
+            }
+        }"##;
+
+        let kiosk = serde_json::from_str::<DeviceStateResponse>(body)
+            .unwrap()
+            .kiosk
+            .unwrap();
+        assert_eq!(kiosk.state, KioskState::CheckedIn);
+        assert_eq!(kiosk.member_name.as_deref(), Some("Ada"));
+        assert_eq!(kiosk.weight_used, Some(149.5));
+        assert_eq!(kiosk.expires_in, Some(58));
+    }
+
+    #[test]
+    fn kiosk_off_or_newer_state() {
+        let response: DeviceStateResponse =
+            serde_json::from_str(r#"{"ok": true, "kiosk": null}"#).unwrap();
+        assert_eq!(response.kiosk, None);
+
+        let kiosk: KioskStatus =
+            serde_json::from_str(r#"{"state": "reserved", "message": "Hi"}"#).unwrap();
+        assert_eq!(kiosk.state, KioskState::Unknown);
+    }
+
+    #[test]
+    fn parses_encode_task() {
+        let body = r#"{
+            "ok": true,
+            "encode": {
+                "job_id": "a1b2",
+                "index": 3,
+                "total": 12,
+                "spool_id": "abc",
+                "label": "Red PETG",
+                "tag_uid": "04A1B2C3D4E5F6",
+                "data": "0303d00000fe",
+                "message": "Writing tag 3 of 12"
+            }
+        }"#;
+
+        let task = serde_json::from_str::<DeviceStateResponse>(body)
+            .unwrap()
+            .encode
+            .unwrap();
+        assert_eq!((task.index, task.total), (3, 12));
+        assert_eq!(task.label, "Red PETG");
+        assert_eq!(task.data.as_deref(), Some("0303d00000fe"));
+
+        let waiting: EncodeTask = serde_json::from_str(
+            r#"{"job_id": "a1b2", "index": 1, "total": 2, "spool_id": "abc", "label": "Red PETG"}"#,
+        )
+        .unwrap();
+        assert_eq!(waiting.data, None);
+    }
+
+    #[test]
+    fn diagnostics_wire_format() {
+        let diagnostics = DeviceDiagnostics {
+            firmware_version: "0.1.1".into(),
+            uptime_s: 3600,
+            heap: MemoryUsage { free: 90_000, total: 300_000, min_free: 60_000 },
+            wifi_rssi: Some(-61),
+            i2c: alloc::vec![I2cCounters { client: "scale".into(), errors: 2, timeouts: 0 }],
+            nfc_bridge_firmware: Some("1.3".into()),
+            scale_raw: alloc::vec![-1200, 845_000],
+            fps: 29.5,
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(json["heap"]["min_free"], 60_000);
+        assert_eq!(json["psram"], serde_json::Value::Null);
+        assert_eq!(json["i2c"][0]["client"], "scale");
+        assert_eq!(json["scale_raw"][1], 845_000);
+        assert_eq!(serde_json::from_value::<DeviceDiagnostics>(json).unwrap(), diagnostics);
+    }
+
+    #[test]
+    fn remaining_percent_needs_label_weight() {
+        let spool = ResolvedSpool {
+            remaining_g: Some(1200.0),
+            label_weight: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(spool.remaining_percent(), Some(100));
+        assert_eq!(
+            ResolvedSpool {
+                label_weight: Some(0),
+                ..spool
+            }
+            .remaining_percent(),
+            None
+        );
+    }
+}
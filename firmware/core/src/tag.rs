@@ -0,0 +1,278 @@
+//! NFC tag decoding and presence tracking
+//!
+//! Turns raw reader responses into tag events and decoded spool data. The
+//! reader itself sits behind [`Nfc`], so the firmware's main loop logic can
+//! be exercised against a mock reader.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::hal::Nfc;
+
+pub const TAG_TYPE_UNKNOWN: u8 = 0;
+pub const TAG_TYPE_NTAG: u8 = 1;
+pub const TAG_TYPE_MIFARE_1K: u8 = 2;
+pub const TAG_TYPE_MIFARE_4K: u8 = 3;
+
+/// Longest UID the reader reports (ISO14443A triple size)
+pub const MAX_UID_LEN: usize = 10;
+
+/// Decoded tag data from Bambu/NTAG tags
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecodedTagInfo {
+    pub vendor: String,
+    pub material: String,
+    pub material_subtype: String,
+    pub color_name: String,
+    pub color_rgba: u32,
+    pub spool_weight: i32,
+    pub tag_type_name: String,
+}
+
+/// Tag change reported by an [`Nfc`] reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagEvent {
+    /// A new tag is on the reader
+    Detected,
+    /// Tag data was read and decoded
+    Decoded,
+    /// The tag was removed
+    Removed,
+}
+
+/// Tag change with the data needed to act on it, detached from the reader
+/// (so locks on the reader can be released before network calls)
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagUpdate {
+    Detected { uid_hex: String },
+    Decoded { uid_hex: String, info: Option<DecodedTagInfo> },
+    Removed,
+}
+
+/// Poll a reader and collect the data for any tag change
+pub fn poll_tag<N: Nfc>(nfc: &mut N) -> Result<Option<TagUpdate>, N::Error> {
+    let uid_hex = |nfc: &N| nfc.uid().map(uid_hex).unwrap_or_default();
+    Ok(match nfc.poll()? {
+        None => None,
+        Some(TagEvent::Detected) => Some(TagUpdate::Detected { uid_hex: uid_hex(nfc) }),
+        Some(TagEvent::Decoded) => Some(TagUpdate::Decoded {
+            uid_hex: uid_hex(nfc),
+            info: nfc.tag_info().cloned(),
+        }),
+        Some(TagEvent::Removed) => Some(TagUpdate::Removed),
+    })
+}
+
+/// Compare the tag on the reader with the UID just seen
+///
+/// `current` is the tag being tracked (None if none), `seen` the UID from the
+/// latest scan (None if no tag answered). A different UID without a removal
+/// in between counts as a new tag.
+pub fn presence_change(current: Option<&[u8]>, seen: Option<&[u8]>) -> Option<TagEvent> {
+    match (current, seen) {
+        (Some(_), None) => Some(TagEvent::Removed),
+        (None, None) => None,
+        (Some(current), Some(seen)) if current == seen => None,
+        (_, Some(_)) => Some(TagEvent::Detected),
+    }
+}
+
+/// Format a UID as colon-separated hex ("04:A1:B2:C3")
+pub fn uid_hex(uid: &[u8]) -> String {
+    uid.iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Decode Bambu Lab tag data from raw blocks
+pub fn decode_bambu_tag(block_data: &[u8]) -> DecodedTagInfo {
+    // Block layout (each 16 bytes):
+    // Block 1: Material variant ID (0-7), Material ID (8-15)
+    // Block 2: Filament type (e.g., "PLA")
+    // Block 4: Detailed type (e.g., "PLA Basic")
+    // Block 5: Color RGBA (0-3), Spool weight (4-5 little-endian)
+
+    if block_data.len() < 64 {
+        return DecodedTagInfo {
+            tag_type_name: "Bambu Lab".to_string(),
+            ..Default::default()
+        };
+    }
+
+    let block2 = &block_data[16..32];
+    let block4 = &block_data[32..48];
+    let block5 = &block_data[48..64];
+
+    // Extract filament type (block 2)
+    let filament_type = extract_cstring(block2);
+
+    // Extract detailed type (block 4)
+    let detailed_type = extract_cstring(block4);
+
+    // Extract color RGBA (block 5, bytes 0-3)
+    let color_rgba = u32::from_be_bytes([block5[0], block5[1], block5[2], block5[3]]);
+
+    // Extract spool weight (block 5, bytes 4-5, little-endian)
+    let spool_weight = i16::from_le_bytes([block5[4], block5[5]]) as i32;
+
+    // Derive subtype from detailed_type
+    let material_subtype = if detailed_type.starts_with(&format!("Bambu {} ", filament_type)) {
+        detailed_type.strip_prefix(&format!("Bambu {} ", filament_type))
+            .unwrap_or("")
+            .to_string()
+    } else if detailed_type.starts_with(&filament_type) {
+        detailed_type.strip_prefix(&filament_type)
+            .map(|s| s.trim())
+            .unwrap_or("")
+            .to_string()
+    } else {
+        detailed_type.clone()
+    };
+
+    DecodedTagInfo {
+        vendor: "Bambu".to_string(),
+        material: filament_type,
+        material_subtype,
+        color_name: format_color_name(color_rgba),
+        color_rgba,
+        spool_weight,
+        tag_type_name: "Bambu Lab".to_string(),
+    }
+}
+
+/// Extract null-terminated string from bytes
+pub fn extract_cstring(data: &[u8]) -> String {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).to_string()
+}
+
+/// Format color RGBA as a name (fallback to hex if no name found)
+pub fn format_color_name(rgba: u32) -> String {
+    // Simple color name lookup for common colors
+    // Format is 0xRRGGBBAA
+    let r = (rgba >> 24) & 0xFF;
+    let g = (rgba >> 16) & 0xFF;
+    let b = (rgba >> 8) & 0xFF;
+
+    // Very basic color detection
+    if r > 200 && g < 100 && b < 100 {
+        "Red".to_string()
+    } else if r < 100 && g > 200 && b < 100 {
+        "Green".to_string()
+    } else if r < 100 && g < 100 && b > 200 {
+        "Blue".to_string()
+    } else if r > 200 && g > 200 && b < 100 {
+        "Yellow".to_string()
+    } else if r > 200 && g > 200 && b > 200 {
+        "White".to_string()
+    } else if r < 50 && g < 50 && b < 50 {
+        "Black".to_string()
+    } else if r > 200 && g > 100 && b < 100 {
+        "Orange".to_string()
+    } else {
+        // Return hex color
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockNfc;
+    use alloc::vec;
+
+    fn bambu_blocks(filament: &str, detailed: &str, rgba: u32, weight: i16) -> Vec<u8> {
+        let mut data = vec![0u8; 64];
+        data[16..16 + filament.len()].copy_from_slice(filament.as_bytes());
+        data[32..32 + detailed.len()].copy_from_slice(detailed.as_bytes());
+        data[48..52].copy_from_slice(&rgba.to_be_bytes());
+        data[52..54].copy_from_slice(&weight.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decodes_bambu_tag() {
+        let info = decode_bambu_tag(&bambu_blocks("PLA", "PLA Basic", 0xFF0000FF, 1000));
+        assert_eq!(info.vendor, "Bambu");
+        assert_eq!(info.material, "PLA");
+        assert_eq!(info.material_subtype, "Basic");
+        assert_eq!(info.color_rgba, 0xFF0000FF);
+        assert_eq!(info.color_name, "Red");
+        assert_eq!(info.spool_weight, 1000);
+    }
+
+    #[test]
+    fn strips_bambu_prefix_from_subtype() {
+        let info = decode_bambu_tag(&bambu_blocks("PETG", "Bambu PETG HF", 0x000000FF, 1000));
+        assert_eq!(info.material_subtype, "HF");
+        assert_eq!(info.color_name, "Black");
+    }
+
+    #[test]
+    fn short_block_data_is_only_typed() {
+        let info = decode_bambu_tag(&[0u8; 16]);
+        assert_eq!(info.tag_type_name, "Bambu Lab");
+        assert!(info.material.is_empty());
+    }
+
+    #[test]
+    fn unnamed_colors_fall_back_to_hex() {
+        assert_eq!(format_color_name(0x808080FF), "#808080");
+    }
+
+    #[test]
+    fn cstring_stops_at_nul() {
+        assert_eq!(extract_cstring(b"PLA\0\0junk"), "PLA");
+        assert_eq!(extract_cstring(b"ABS"), "ABS");
+    }
+
+    #[test]
+    fn formats_uid_hex() {
+        assert_eq!(uid_hex(&[0x04, 0xA1, 0x0B]), "04:A1:0B");
+    }
+
+    #[test]
+    fn tracks_presence() {
+        let a: &[u8] = &[1, 2, 3, 4];
+        let b: &[u8] = &[5, 6, 7, 8];
+        assert_eq!(presence_change(None, None), None);
+        assert_eq!(presence_change(None, Some(a)), Some(TagEvent::Detected));
+        assert_eq!(presence_change(Some(a), Some(a)), None);
+        assert_eq!(presence_change(Some(a), Some(b)), Some(TagEvent::Detected));
+        assert_eq!(presence_change(Some(a), None), Some(TagEvent::Removed));
+    }
+
+    #[test]
+    fn poll_collects_tag_data() {
+        let info = decode_bambu_tag(&bambu_blocks("PLA", "PLA Matte", 0xFFFFFFFF, 1000));
+        let mut nfc = MockNfc::new(vec![
+            Some(TagEvent::Detected),
+            None,
+            Some(TagEvent::Decoded),
+            Some(TagEvent::Removed),
+        ]);
+        nfc.uid = Some(vec![0xDE, 0xAD]);
+
+        assert_eq!(
+            poll_tag(&mut nfc).unwrap(),
+            Some(TagUpdate::Detected { uid_hex: "DE:AD".to_string() })
+        );
+        assert_eq!(poll_tag(&mut nfc).unwrap(), None);
+
+        nfc.info = Some(info.clone());
+        assert_eq!(
+            poll_tag(&mut nfc).unwrap(),
+            Some(TagUpdate::Decoded { uid_hex: "DE:AD".to_string(), info: Some(info) })
+        );
+        assert_eq!(poll_tag(&mut nfc).unwrap(), Some(TagUpdate::Removed));
+    }
+
+    #[test]
+    fn poll_passes_reader_errors_through() {
+        let mut nfc = MockNfc::new(vec![]);
+        nfc.fail = true;
+        assert!(poll_tag(&mut nfc).is_err());
+    }
+}
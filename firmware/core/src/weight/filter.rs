@@ -0,0 +1,320 @@
+//! Weight filter chain shared by the load cell drivers.
+//!
+//! Each raw reading (already converted to grams) goes through:
+//!
+//! 1. Temperature compensation - removes the load cell's thermal drift using
+//!    a linear coefficient (grams per °C) around a reference temperature.
+//!    Disabled when the coefficient is 0 or no temperature has been set.
+//! 2. Median filter - rejects single-sample spikes (vibration, bumps).
+//! 3. EMA - smooths the remaining noise. Large steps (a spool being placed
+//!    or removed) use a faster alpha so the display settles quickly.
+//! 4. Zero tracking - while the platform is empty and stable, slowly pulls
+//!    small residual readings back to zero. Limited to a narrow band so a
+//!    light object is never tracked away.
+//!
+//! Stability uses hysteresis: the weight becomes stable after
+//! `stable_samples` consecutive readings that move less than
+//! `stable_enter_grams`, and only becomes unstable again once it drifts more
+//! than `stable_exit_grams` from where it settled.
+
+use super::abs;
+
+/// Largest supported median window
+pub const MAX_MEDIAN_WINDOW: usize = 15;
+
+/// Filter chain settings
+#[derive(Debug, Clone, Copy)]
+pub struct FilterConfig {
+    /// Median window size in samples (1 disables the median stage)
+    pub median_window: usize,
+    /// EMA alpha (0-1, higher = less filtering)
+    pub ema_alpha: f32,
+    /// Steps larger than this use `fast_settle_alpha`
+    pub fast_settle_grams: f32,
+    pub fast_settle_alpha: f32,
+    /// Max change between readings to count towards stability
+    pub stable_enter_grams: f32,
+    /// Drift from the settled weight that ends stability
+    pub stable_exit_grams: f32,
+    /// Consecutive quiet readings needed to become stable
+    pub stable_samples: u8,
+    /// Automatically track the zero point while the platform is empty
+    pub zero_tracking: bool,
+    /// Only readings within this band around zero are tracked
+    pub zero_band_grams: f32,
+    /// Fraction of the residual removed per stable sample
+    pub zero_track_rate: f32,
+    /// Limit on the total correction zero tracking may apply
+    pub zero_track_limit_grams: f32,
+    /// Thermal drift in grams per °C (0 disables compensation)
+    pub temp_coeff_grams_per_c: f32,
+    /// Temperature at which the scale was calibrated
+    pub reference_temp_c: f32,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            median_window: 5,
+            ema_alpha: 0.25, // Moderate filtering - balance between smoothness and response
+            fast_settle_grams: 50.0,
+            fast_settle_alpha: 0.7,
+            // 10g quiet threshold due to noisy hardware
+            stable_enter_grams: 10.0,
+            stable_exit_grams: 20.0,
+            stable_samples: 10,
+            zero_tracking: true,
+            zero_band_grams: 3.0,
+            zero_track_rate: 0.1,
+            zero_track_limit_grams: 20.0,
+            temp_coeff_grams_per_c: 0.0,
+            reference_temp_c: 25.0,
+        }
+    }
+}
+
+/// Filter chain state
+#[derive(Debug, Clone)]
+pub struct WeightFilter {
+    pub config: FilterConfig,
+    samples: [f32; MAX_MEDIAN_WINDOW],
+    sample_count: usize,
+    sample_pos: usize,
+    /// EMA output (before zero tracking), None until the first sample
+    ema: Option<f32>,
+    /// Correction applied by zero tracking
+    zero_drift: f32,
+    /// Latest temperature reading, if a sensor provides one
+    temperature_c: Option<f32>,
+    stable: bool,
+    stable_count: u8,
+    /// Weight at the moment the reading became stable
+    stable_anchor: f32,
+    output: f32,
+}
+
+impl WeightFilter {
+    pub fn new(config: FilterConfig) -> Self {
+        Self {
+            config,
+            samples: [0.0; MAX_MEDIAN_WINDOW],
+            sample_count: 0,
+            sample_pos: 0,
+            ema: None,
+            zero_drift: 0.0,
+            temperature_c: None,
+            stable: false,
+            stable_count: 0,
+            stable_anchor: 0.0,
+            output: 0.0,
+        }
+    }
+
+    /// Feed a new reading in grams and return the filtered weight
+    pub fn update(&mut self, grams: f32) -> f32 {
+        let compensated = grams - self.temperature_offset();
+        let median = self.push_median(compensated);
+
+        let prev = self.ema.unwrap_or(median);
+        let alpha = if abs(median - prev) > self.config.fast_settle_grams {
+            self.config.fast_settle_alpha
+        } else {
+            self.config.ema_alpha
+        };
+        let ema = prev * (1.0 - alpha) + median * alpha;
+        self.ema = Some(ema);
+
+        self.update_stability(ema - prev, ema);
+        self.track_zero(ema);
+
+        self.output = ema - self.zero_drift;
+        self.output
+    }
+
+    /// Last filtered weight in grams
+    pub fn weight(&self) -> f32 {
+        self.output
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.stable
+    }
+
+    /// Current zero tracking correction in grams
+    pub fn zero_drift(&self) -> f32 {
+        self.zero_drift
+    }
+
+    /// Set the latest temperature for drift compensation
+    pub fn set_temperature(&mut self, temp_c: f32) {
+        self.temperature_c = Some(temp_c);
+    }
+
+    /// Change the filter settings, keeping the current weight
+    pub fn set_config(&mut self, config: FilterConfig) {
+        self.config = config;
+        self.config.median_window = config.median_window.clamp(1, MAX_MEDIAN_WINDOW);
+        if !config.zero_tracking {
+            self.zero_drift = 0.0;
+        }
+        let weight = self.output;
+        self.reset(weight);
+    }
+
+    /// Restart filtering at a known weight (after tare or calibration)
+    pub fn reset(&mut self, grams: f32) {
+        self.sample_count = 0;
+        self.sample_pos = 0;
+        self.ema = Some(grams + self.zero_drift);
+        self.stable = false;
+        self.stable_count = 0;
+        self.output = grams;
+    }
+
+    /// Forget the zero tracking correction (the zero point was re-measured)
+    pub fn reset_zero_tracking(&mut self) {
+        self.zero_drift = 0.0;
+    }
+
+    fn temperature_offset(&self) -> f32 {
+        match self.temperature_c {
+            Some(temp) if self.config.temp_coeff_grams_per_c != 0.0 => {
+                (temp - self.config.reference_temp_c) * self.config.temp_coeff_grams_per_c
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn push_median(&mut self, grams: f32) -> f32 {
+        let window = self.config.median_window.clamp(1, MAX_MEDIAN_WINDOW);
+        if window == 1 {
+            return grams;
+        }
+
+        self.samples[self.sample_pos % window] = grams;
+        self.sample_pos = (self.sample_pos + 1) % window;
+        self.sample_count = (self.sample_count + 1).min(window);
+
+        let mut sorted = [0.0f32; MAX_MEDIAN_WINDOW];
+        let sorted = &mut sorted[..self.sample_count];
+        sorted.copy_from_slice(&self.samples[..self.sample_count]);
+        sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+        sorted[sorted.len() / 2]
+    }
+
+    fn update_stability(&mut self, step: f32, ema: f32) {
+        if self.stable {
+            if abs(ema - self.stable_anchor) > self.config.stable_exit_grams {
+                self.stable = false;
+                self.stable_count = 0;
+            }
+            return;
+        }
+
+        if abs(step) < self.config.stable_enter_grams {
+            self.stable_count = self.stable_count.saturating_add(1);
+            if self.stable_count >= self.config.stable_samples {
+                self.stable = true;
+                self.stable_anchor = ema;
+            }
+        } else {
+            self.stable_count = 0;
+        }
+    }
+
+    fn track_zero(&mut self, ema: f32) {
+        if !self.config.zero_tracking || !self.stable {
+            return;
+        }
+        let residual = ema - self.zero_drift;
+        if abs(residual) > self.config.zero_band_grams {
+            return;
+        }
+        let limit = self.config.zero_track_limit_grams;
+        self.zero_drift = (self.zero_drift + residual * self.config.zero_track_rate).clamp(-limit, limit);
+    }
+}
+
+impl Default for WeightFilter {
+    fn default() -> Self {
+        Self::new(FilterConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settle(filter: &mut WeightFilter, grams: f32, samples: usize) -> f32 {
+        (0..samples).map(|_| filter.update(grams)).last().unwrap()
+    }
+
+    #[test]
+    fn median_rejects_single_spikes() {
+        let mut filter = WeightFilter::new(FilterConfig { zero_tracking: false, ..Default::default() });
+        settle(&mut filter, 1000.0, 20);
+        let after_spike = filter.update(5000.0);
+        assert!(abs(after_spike - 1000.0) < 1.0, "spike leaked through: {}", after_spike);
+    }
+
+    #[test]
+    fn large_steps_settle_fast() {
+        let mut filter = WeightFilter::default();
+        settle(&mut filter, 0.0, 20);
+        let weight = settle(&mut filter, 1000.0, 12);
+        assert!(abs(weight - 1000.0) < 5.0, "still settling: {}", weight);
+    }
+
+    #[test]
+    fn stability_has_hysteresis() {
+        let config = FilterConfig { zero_tracking: false, median_window: 1, ema_alpha: 1.0, ..Default::default() };
+        let mut filter = WeightFilter::new(config);
+        settle(&mut filter, 500.0, config.stable_samples as usize + 1);
+        assert!(filter.is_stable());
+
+        // Drift within the exit band keeps it stable
+        filter.update(515.0);
+        assert!(filter.is_stable());
+
+        filter.update(530.0);
+        assert!(!filter.is_stable());
+    }
+
+    #[test]
+    fn zero_tracking_pulls_small_residuals_to_zero() {
+        let mut filter = WeightFilter::default();
+        let weight = settle(&mut filter, 2.0, 200);
+        assert!(abs(weight) < 0.1, "residual not tracked: {}", weight);
+        assert!(abs(filter.zero_drift() - 2.0) < 0.1);
+    }
+
+    #[test]
+    fn zero_tracking_ignores_real_loads() {
+        let mut filter = WeightFilter::default();
+        let weight = settle(&mut filter, 50.0, 200);
+        assert!(abs(weight - 50.0) < 0.1);
+        assert_eq!(filter.zero_drift(), 0.0);
+    }
+
+    #[test]
+    fn compensates_temperature_drift() {
+        let config = FilterConfig {
+            zero_tracking: false,
+            temp_coeff_grams_per_c: 0.5,
+            ..Default::default()
+        };
+        let mut filter = WeightFilter::new(config);
+        filter.set_temperature(35.0); // 10°C above reference -> +5g drift
+        let weight = settle(&mut filter, 505.0, 50);
+        assert!(abs(weight - 500.0) < 0.1, "drift not removed: {}", weight);
+    }
+
+    #[test]
+    fn reset_restarts_at_known_weight() {
+        let mut filter = WeightFilter::default();
+        settle(&mut filter, 300.0, 50);
+        filter.reset(0.0);
+        assert_eq!(filter.weight(), 0.0);
+        assert!(!filter.is_stable());
+    }
+}
@@ -0,0 +1,205 @@
+//! Load cell math
+//!
+//! Raw ADC conversion, the trimmed-mean sampling used for tare and
+//! calibration, calibration factor checks and the [`filter`] chain. The ADC
+//! is reached through [`Scale`].
+
+pub mod filter;
+
+use alloc::vec::Vec;
+
+use crate::hal::Scale;
+
+/// Raw readings averaged for tare and calibration
+pub const CALIBRATION_SAMPLES: usize = 30;
+/// Highest and lowest readings discarded from each end
+pub const CALIBRATION_TRIM: usize = 5;
+
+/// A calibration weight must move the reading at least this much.
+/// A 797g weight produces ~195,000 units with a typical 5kg load cell.
+pub const MIN_CALIBRATION_DELTA: i32 = 10_000;
+/// Plausible calibration factors (raw units per gram)
+pub const MIN_CAL_FACTOR: f32 = 10.0;
+pub const MAX_CAL_FACTOR: f32 = 2000.0;
+
+/// Scale calibration data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    /// Zero offset (tare)
+    pub zero_offset: i32,
+    /// Calibration factor (raw units per gram)
+    pub cal_factor: f32,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            zero_offset: 0,
+            // Default calibration factor - needs actual calibration
+            cal_factor: 1000.0,
+        }
+    }
+}
+
+impl Calibration {
+    /// Convert a raw reading to grams
+    pub fn to_grams(&self, raw: i32) -> f32 {
+        (raw - self.zero_offset) as f32 / self.cal_factor
+    }
+}
+
+/// Why a calibration was rejected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalibrationError {
+    /// The weight lowered the reading (wiring or mounting problem)
+    NegativeDelta(i32),
+    /// No significant weight detected
+    DeltaTooSmall(i32),
+    /// Resulting factor outside `MIN_CAL_FACTOR..=MAX_CAL_FACTOR`
+    FactorOutOfRange(f32),
+}
+
+/// Calibration factor for a known weight that moved the reading by `delta`
+pub fn calibration_factor(delta: i32, known_weight_grams: f32) -> Result<f32, CalibrationError> {
+    if delta < 0 {
+        return Err(CalibrationError::NegativeDelta(delta));
+    }
+    if delta < MIN_CALIBRATION_DELTA {
+        return Err(CalibrationError::DeltaTooSmall(delta));
+    }
+    let factor = delta as f32 / known_weight_grams;
+    if !(MIN_CAL_FACTOR..=MAX_CAL_FACTOR).contains(&factor) {
+        return Err(CalibrationError::FactorOutOfRange(factor));
+    }
+    Ok(factor)
+}
+
+/// Sign-extend a 24-bit two's complement ADC value
+pub fn sign_extend_24(raw: u32) -> i32 {
+    ((raw << 8) as i32) >> 8
+}
+
+/// Result of averaging a batch of raw readings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// Mean of the readings left after trimming
+    pub mean: i32,
+    pub min: i32,
+    pub max: i32,
+    /// Readings that went into the mean
+    pub count: usize,
+}
+
+impl Measurement {
+    /// Spread of all readings (a noise indicator)
+    pub fn range(&self) -> i32 {
+        self.max - self.min
+    }
+}
+
+/// Take `samples` readings and average them, discarding the `trim` highest
+/// and lowest
+pub fn measure<S: Scale>(scale: &mut S, samples: usize, trim: usize) -> Result<Measurement, S::Error> {
+    let mut readings = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        while !scale.data_ready()? {
+            scale.delay_ms(10);
+        }
+        readings.push(scale.read_raw()?);
+    }
+    Ok(trimmed_mean(&mut readings, trim))
+}
+
+/// Trimmed mean of raw readings (sorts `readings` in place). Trimming is
+/// skipped if it would leave nothing.
+pub fn trimmed_mean(readings: &mut [i32], trim: usize) -> Measurement {
+    readings.sort_unstable();
+    let (Some(&min), Some(&max)) = (readings.first(), readings.last()) else {
+        return Measurement { mean: 0, min: 0, max: 0, count: 0 };
+    };
+
+    let kept = if readings.len() > 2 * trim {
+        &readings[trim..readings.len() - trim]
+    } else {
+        &readings[..]
+    };
+    let sum: i64 = kept.iter().map(|&x| x as i64).sum();
+    Measurement {
+        mean: (sum / kept.len() as i64) as i32,
+        min,
+        max,
+        count: kept.len(),
+    }
+}
+
+/// `f32::abs` isn't available in `core` on the firmware's toolchain
+pub(crate) fn abs(x: f32) -> f32 {
+    if x < 0.0 { -x } else { x }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockScale;
+    use alloc::vec;
+
+    #[test]
+    fn converts_raw_to_grams() {
+        let cal = Calibration { zero_offset: 1000, cal_factor: 200.0 };
+        assert_eq!(cal.to_grams(1000), 0.0);
+        assert_eq!(cal.to_grams(201_000), 1000.0);
+        assert_eq!(cal.to_grams(-19_000), -100.0);
+    }
+
+    #[test]
+    fn sign_extends_24_bit_values() {
+        assert_eq!(sign_extend_24(0x000001), 1);
+        assert_eq!(sign_extend_24(0x7FFFFF), 8_388_607);
+        assert_eq!(sign_extend_24(0xFFFFFF), -1);
+        assert_eq!(sign_extend_24(0x800000), -8_388_608);
+    }
+
+    #[test]
+    fn trimmed_mean_drops_outliers() {
+        let mut readings = vec![100, 102, 98, 5000, 101, 99, -4000];
+        let m = trimmed_mean(&mut readings, 1);
+        assert_eq!(m.mean, 100);
+        assert_eq!((m.min, m.max), (-4000, 5000));
+        assert_eq!(m.count, 5);
+        assert_eq!(m.range(), 9000);
+    }
+
+    #[test]
+    fn trimmed_mean_keeps_short_batches() {
+        assert_eq!(trimmed_mean(&mut [10, 20], 5).mean, 15);
+        assert_eq!(trimmed_mean(&mut [], 5).count, 0);
+    }
+
+    #[test]
+    fn measure_waits_for_conversions() {
+        let mut scale = MockScale::new(vec![500; 30]);
+        scale.not_ready_polls = 3;
+        let m = measure(&mut scale, CALIBRATION_SAMPLES, CALIBRATION_TRIM).unwrap();
+        assert_eq!(m.mean, 500);
+        assert_eq!(m.count, 20);
+        assert_eq!(scale.delays, 3);
+    }
+
+    #[test]
+    fn measure_passes_adc_errors_through() {
+        let mut scale = MockScale::new(vec![1, 2]);
+        assert!(measure(&mut scale, 3, 0).is_err());
+    }
+
+    #[test]
+    fn accepts_plausible_calibration() {
+        assert_eq!(calibration_factor(195_000, 797.0).map(|f| f.round()), Ok(245.0));
+    }
+
+    #[test]
+    fn rejects_bad_calibration() {
+        assert_eq!(calibration_factor(-50_000, 500.0), Err(CalibrationError::NegativeDelta(-50_000)));
+        assert_eq!(calibration_factor(5_000, 500.0), Err(CalibrationError::DeltaTooSmall(5_000)));
+        assert!(matches!(calibration_factor(20_000, 5.0), Err(CalibrationError::FactorOutOfRange(_))));
+    }
+}
@@ -0,0 +1,180 @@
+//! SpoolBuddy widget simulator
+//!
+//! Renders the firmware's embedded-graphics UI (`firmware/src/ui`) in a
+//! desktop window, so widgets and screens can be iterated on without
+//! flashing. The UI code is compiled as-is; mouse clicks are fed in as
+//! touch events.
+//!
+//! Run with: cargo run --bin ui-sim --features window
+//! (needs the SDL2 development libraries)
+//!
+//! Keys:
+//!   1-9, 0     jump to a screen
+//!   Up/Down    add/remove 10 g on the scale (Shift: 1 g)
+//!   S          toggle weight stability
+//!   N          put a demo spool on the scale / take it off
+//!   T          cycle the theme (dark, light, high contrast)
+//!   P          save a screenshot to screenshots/ui_<screen>.png
+//!   Esc        quit
+
+#[allow(unused, static_mut_refs, clippy::all)]
+#[path = "../../src/ui/mod.rs"]
+mod ui;
+
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use embedded_graphics_simulator::{
+    sdl2::{Keycode, Mod},
+    OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
+};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use ui::theme::{self, ThemeMode};
+use ui::{Screen, SpoolDisplay, SpoolSource, TouchEvent, UiManager, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+
+/// Screens reachable with the number keys, in key order
+const SCREENS: [Screen; 10] = [
+    Screen::Home,
+    Screen::SpoolInfo,
+    Screen::AmsOverview,
+    Screen::ScanResult,
+    Screen::SpoolDetail,
+    Screen::Catalog,
+    Screen::AmsSelect,
+    Screen::Settings,
+    Screen::Calibration,
+    Screen::About,
+];
+
+fn demo_spool() -> SpoolDisplay {
+    SpoolDisplay {
+        id: "demo-spool".try_into().unwrap(),
+        material: "PLA Basic".try_into().unwrap(),
+        color_name: "Jade White".try_into().unwrap(),
+        brand: "Bambu Lab".try_into().unwrap(),
+        color_rgba: 0xF0F0E6FF,
+        color_stops: heapless::Vec::new(),
+        weight_current: 850.0,
+        weight_label: 1000.0,
+        k_value: Some(0.02),
+        source: SpoolSource::Bambu,
+    }
+}
+
+/// Screen for a number key; 1 is the first screen, 0 the tenth
+fn screen_for_key(key: Keycode) -> Option<Screen> {
+    let index = match key {
+        Keycode::Num1 => 0,
+        Keycode::Num2 => 1,
+        Keycode::Num3 => 2,
+        Keycode::Num4 => 3,
+        Keycode::Num5 => 4,
+        Keycode::Num6 => 5,
+        Keycode::Num7 => 6,
+        Keycode::Num8 => 7,
+        Keycode::Num9 => 8,
+        Keycode::Num0 => 9,
+        _ => return None,
+    };
+    Some(SCREENS[index])
+}
+
+/// Pixel position of a mouse event as touch coordinates
+fn touch_point(point: Point) -> (u16, u16) {
+    (
+        point.x.clamp(0, DISPLAY_WIDTH as i32 - 1) as u16,
+        point.y.clamp(0, DISPLAY_HEIGHT as i32 - 1) as u16,
+    )
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut display = SimulatorDisplay::<Rgb565>::new(Size::new(DISPLAY_WIDTH, DISPLAY_HEIGHT));
+    let output_settings = OutputSettingsBuilder::new().scale(1).build();
+    let mut window = Window::new("SpoolBuddy UI Simulator", &output_settings);
+
+    let mut manager = UiManager::new();
+    manager.set_wifi_status(true, Some("SpoolBuddy-Lab"));
+    manager.set_server_connected(true);
+
+    let mut weight = 0.0f32;
+    let mut stable = true;
+    let started = Instant::now();
+    let frame = Duration::from_millis(1000 / ui::UI_REFRESH_RATE_HZ as u64);
+    let mut touching = false;
+
+    'running: loop {
+        manager.set_weight(weight, stable);
+        manager.tick(started.elapsed().as_millis() as u32);
+
+        if manager.is_dirty() {
+            ui::screens::render_screen(&mut display, manager.current_screen(), manager.state())?;
+            manager.mark_clean();
+        }
+        window.update(&display);
+
+        for event in window.events() {
+            let touch = match event {
+                SimulatorEvent::Quit => break 'running,
+                SimulatorEvent::MouseButtonDown { point, .. } => {
+                    touching = true;
+                    let (x, y) = touch_point(point);
+                    Some(TouchEvent::Press { x, y })
+                }
+                SimulatorEvent::MouseButtonUp { point, .. } => {
+                    touching = false;
+                    let (x, y) = touch_point(point);
+                    Some(TouchEvent::Release { x, y })
+                }
+                SimulatorEvent::MouseMove { point } if touching => {
+                    let (x, y) = touch_point(point);
+                    Some(TouchEvent::Move { x, y })
+                }
+                SimulatorEvent::KeyDown { keycode, keymod, .. } => {
+                    let step = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) { 1.0 } else { 10.0 };
+                    match keycode {
+                        Keycode::Escape => break 'running,
+                        Keycode::Up => weight += step,
+                        Keycode::Down => weight -= step,
+                        Keycode::S => stable = !stable,
+                        Keycode::N => {
+                            let spool = manager.state().spool.is_none().then(demo_spool);
+                            weight = spool.as_ref().map_or(0.0, |spool| spool.weight_current + 250.0);
+                            manager.set_spool(spool);
+                        }
+                        Keycode::T => {
+                            let mode = match theme::theme_mode() {
+                                ThemeMode::Dark => ThemeMode::Light,
+                                ThemeMode::Light => ThemeMode::HighContrast,
+                                ThemeMode::HighContrast => ThemeMode::Dark,
+                            };
+                            theme::set_theme_mode(mode);
+                            println!("Theme: {}", mode.as_setting());
+                            manager.invalidate();
+                        }
+                        Keycode::P => {
+                            let dir = Path::new("screenshots");
+                            std::fs::create_dir_all(dir)?;
+                            let path = dir.join(format!("ui_{:?}.png", manager.current_screen()).to_lowercase());
+                            display.to_rgb_output_image(&output_settings).save_png(&path)?;
+                            println!("Saved {}", path.display());
+                        }
+                        key => {
+                            if let Some(screen) = screen_for_key(key) {
+                                manager.navigate(screen);
+                            }
+                        }
+                    }
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(action) = touch.and_then(|touch| manager.handle_touch(touch)) {
+                println!("Action: {:?}", action);
+            }
+        }
+
+        std::thread::sleep(frame);
+    }
+
+    Ok(())
+}
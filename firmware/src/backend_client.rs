@@ -6,6 +6,7 @@
 use esp_idf_svc::http::client::{Configuration as HttpConfig, EspHttpConnection};
 use log::{info, warn};
 use serde::Deserialize;
+use spoolbuddy_core::proto::{DeviceStateResponse, EncodeResult, EncodeTask, KioskState, SuggestedAction};
 use std::ffi::{c_char, c_int};
 use std::sync::Mutex;
 use embedded_svc::http::client::Client as HttpClient;
@@ -16,6 +17,9 @@ const MAX_PRINTERS: usize = 4;
 /// Maximum number of AMS units per printer
 const MAX_AMS_UNITS: usize = 4;
 
+/// Maximum number of spools feeding a printer at once (one per nozzle)
+const MAX_ACTIVE_SPOOLS: usize = 2;
+
 /// HTTP timeout in milliseconds
 const HTTP_TIMEOUT_MS: u64 = 5000;
 
@@ -51,6 +55,18 @@ struct ApiAmsUnit {
     trays: Vec<ApiAmsTray>,
 }
 
+/// Spool feeding an extruder during a print, from backend API
+#[derive(Debug, Clone, Deserialize, Default)]
+struct ApiActiveSpool {
+    ams_id: i32,
+    tray_id: i32,
+    extruder: Option<i32>,      // 0=right, 1=left, None on single-nozzle printers
+    material: Option<String>,
+    color_name: Option<String>,
+    rgba: Option<String>,       // "#RRGGBBAA"
+    remaining_grams: Option<f32>,
+}
+
 /// Printer status from backend API
 #[derive(Debug, Clone, Deserialize)]
 struct ApiPrinter {
@@ -63,6 +79,10 @@ struct ApiPrinter {
     print_progress: Option<u8>,
     subtask_name: Option<String>,
     mc_remaining_time: Option<u16>,
+    layer_num: Option<i32>,
+    total_layer_num: Option<i32>,
+    #[serde(default)]
+    active_spools: Vec<ApiActiveSpool>,
     cover_url: Option<String>,
     stg_cur: Option<i8>,           // Current stage number (-1 = idle)
     stg_cur_name: Option<String>,  // Human-readable stage name
@@ -72,15 +92,44 @@ struct ApiPrinter {
     tray_now_left: Option<i32>,
     tray_now_right: Option<i32>,
     active_extruder: Option<i32>,  // 0=right, 1=left, None=unknown
+    #[serde(default)]
+    vt_tray: Option<ApiAmsTray>,   // External spool holder
 }
 
 /// Time response from backend API
 #[derive(Debug, Clone, Deserialize)]
 struct ApiTime {
-    hour: u8,
-    minute: u8,
+    timestamp: i64,
+}
+
+/// Device clock settings from backend API
+#[derive(Debug, Clone, Deserialize)]
+struct ApiClockSettings {
+    posix_tz: String,
+    ntp_server: String,
 }
 
+/// Device sound settings from backend API
+#[derive(Debug, Clone, Deserialize)]
+struct ApiSoundSettings {
+    muted: bool,
+    buzzer_gpio: Option<i32>,
+}
+
+/// Device theme settings from backend API
+#[derive(Debug, Clone, Deserialize)]
+struct ApiThemeSettings {
+    mode: String,
+    accent_color: Option<String>,
+}
+
+/// Device settings (clock, sound, theme) are refreshed every N time syncs (~5 minutes at one poll per 2s)
+const DEVICE_SETTINGS_INTERVAL: u32 = 150;
+static TIME_SYNC_COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+/// AMS tray level (%) that triggers the low filament beep
+const LOW_FILAMENT_PERCENT: u8 = 10;
+
 /// Cached AMS tray info
 #[derive(Debug, Clone, Copy, Default)]
 struct CachedAmsTray {
@@ -113,6 +162,18 @@ impl Default for CachedAmsUnit {
     }
 }
 
+/// Cached spool feeding an extruder
+#[derive(Debug, Clone, Copy)]
+struct CachedActiveSpool {
+    material: [u8; 16],
+    color_name: [u8; 32],
+    color_rgba: u32,        // RGBA packed (0xRRGGBBAA)
+    remaining_grams: i32,   // -1 if unknown
+    ams_id: i32,
+    tray_id: i32,
+    extruder: i8,           // -1 on single-nozzle printers, 0=right, 1=left
+}
+
 /// Cached printer info (internal)
 #[derive(Debug, Clone)]
 struct CachedPrinter {
@@ -127,6 +188,10 @@ struct CachedPrinter {
     remaining_time_min: u16,
     stg_cur: i8,            // Current stage number (-1 = idle)
     stg_cur_name: [u8; 48], // Human-readable stage name
+    layer_num: i32,         // -1 if not reported
+    total_layer_num: i32,   // -1 if not reported
+    active_spool_count: u8,
+    active_spools: [CachedActiveSpool; MAX_ACTIVE_SPOOLS],
     // AMS data
     ams_unit_count: u8,
     ams_units: [CachedAmsUnit; MAX_AMS_UNITS],
@@ -134,6 +199,7 @@ struct CachedPrinter {
     tray_now_left: i32,     // -1 if not available
     tray_now_right: i32,    // -1 if not available
     active_extruder: i32,   // -1 if not available, 0=right, 1=left
+    vt_tray: CachedAmsTray, // External spool holder, empty if none loaded
 }
 
 impl Default for CachedPrinter {
@@ -150,12 +216,17 @@ impl Default for CachedPrinter {
             remaining_time_min: 0,
             stg_cur: -1,
             stg_cur_name: [0; 48],
+            layer_num: -1,
+            total_layer_num: -1,
+            active_spool_count: 0,
+            active_spools: [EMPTY_ACTIVE_SPOOL; MAX_ACTIVE_SPOOLS],
             ams_unit_count: 0,
             ams_units: [CachedAmsUnit::default(); MAX_AMS_UNITS],
             tray_now: -1,
             tray_now_left: -1,
             tray_now_right: -1,
             active_extruder: -1,
+            vt_tray: CachedAmsTray::default(),
         }
     }
 }
@@ -174,6 +245,16 @@ const EMPTY_AMS_TRAY: CachedAmsTray = CachedAmsTray {
     remain: 0,
 };
 
+const EMPTY_ACTIVE_SPOOL: CachedActiveSpool = CachedActiveSpool {
+    material: [0; 16],
+    color_name: [0; 32],
+    color_rgba: 0,
+    remaining_grams: -1,
+    ams_id: -1,
+    tray_id: -1,
+    extruder: -1,
+};
+
 const EMPTY_AMS_UNIT: CachedAmsUnit = CachedAmsUnit {
     id: 0,
     humidity: -1,
@@ -195,12 +276,17 @@ const EMPTY_PRINTER: CachedPrinter = CachedPrinter {
     stg_cur_name: [0; 48],
     remaining_time_min: 0,
     stg_cur: -1,
+    layer_num: -1,
+    total_layer_num: -1,
+    active_spool_count: 0,
+    active_spools: [EMPTY_ACTIVE_SPOOL; MAX_ACTIVE_SPOOLS],
     ams_unit_count: 0,
     ams_units: [EMPTY_AMS_UNIT; MAX_AMS_UNITS],
     tray_now: -1,
     tray_now_left: -1,
     tray_now_right: -1,
     active_extruder: -1,
+    vt_tray: EMPTY_AMS_TRAY,
 };
 
 impl BackendManager {
@@ -217,8 +303,26 @@ impl BackendManager {
 // Global backend manager
 static BACKEND_MANAGER: Mutex<BackendManager> = Mutex::new(BackendManager::new());
 
+// Reply to the last device state update (resolved spool, suggested action)
+static DEVICE_STATE_REPLY: Mutex<DeviceStateResponse> = Mutex::new(DeviceStateResponse {
+    ok: false,
+    tag_id: None,
+    spool: None,
+    action: SuggestedAction::None,
+    kiosk: None,
+    encode: None,
+});
+
+// ETag of the cached printer list, sent as If-None-Match when polling
+static PRINTERS_ETAG: Mutex<String> = Mutex::new(String::new());
+
 // Cover image storage (max 64KB for thumbnail)
 const MAX_COVER_SIZE: usize = 65536;
+// Theme from the backend settings: mode (0=light, 1=dark, 2=high contrast) and accent 0xRRGGBB
+static THEME_SETTINGS: Mutex<(c_int, Option<u32>)> = Mutex::new((1, None));
+// Bumped whenever the theme settings change, so the UI only restyles when needed
+static THEME_GENERATION: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
 static COVER_DATA: Mutex<Vec<u8>> = Mutex::new(Vec::new());
 static COVER_VALID: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 static LAST_COVER_URL: Mutex<String> = Mutex::new(String::new());
@@ -232,6 +336,8 @@ pub fn init() {
 pub fn set_server_url(url: &str) {
     let mut manager = BACKEND_MANAGER.lock().unwrap();
     manager.server_url = url.to_string();
+    // A different server has a different printer list
+    PRINTERS_ETAG.lock().unwrap().clear();
 
     // Parse IP from URL for status
     if let Some(ip_str) = url.strip_prefix("http://") {
@@ -270,6 +376,14 @@ pub fn poll_backend() {
     // Send heartbeat to indicate display is connected
     send_heartbeat(&base_url);
 
+    // Send anything queued on the SD card while the backend was offline
+    flush_queued_events(&base_url);
+
+    // Push a mute toggled on the device
+    if crate::buzzer::mute_sync_pending() && push_sound_settings(&base_url) {
+        crate::buzzer::mute_synced();
+    }
+
     // Send current scale weight to backend (so other clients can see it)
     let weight = crate::scale_manager::scale_get_weight();
     let stable = crate::scale_manager::scale_is_stable();
@@ -280,7 +394,9 @@ pub fn poll_backend() {
     let mut cover_url_to_fetch: Option<String> = None;
 
     match fetch_printers(&printers_url) {
-        Ok(printers) => {
+        // Unchanged since the last poll (304), keep the cache
+        Ok(None) => {}
+        Ok(Some(printers)) => {
             // Check if cover URL changed before updating cache
             cover_url_to_fetch = check_cover_url_changed(&printers, &base_url);
 
@@ -303,6 +419,18 @@ pub fn poll_backend() {
 
 /// Get WiFi status parameters for backend state updates
 /// Returns URL query string fragment like "&wifi_state=3&wifi_ssid=MyNetwork&wifi_ip=192.168.1.50&wifi_rssi=-45"
+/// Build query params for the second load cell (empty on single-scale devices)
+fn get_second_scale_params() -> String {
+    if crate::scale_manager::scale_get_channel_count() < 2 {
+        return String::new();
+    }
+    format!(
+        "&weight_2={:.1}&stable_2={}",
+        crate::scale_manager::scale_get_weight_ch(1),
+        crate::scale_manager::scale_is_stable_ch(1)
+    )
+}
+
 fn get_wifi_params() -> String {
     let mut status = crate::wifi_manager::WifiStatus {
         state: 0,
@@ -347,6 +475,23 @@ fn get_wifi_params() -> String {
     params
 }
 
+/// Build query params for the Pico NFC bridge health (empty without a bridge)
+/// like "&nfc_bridge=ok&nfc_bridge_fw=1.3&nfc_failures=0&nfc_garbage=0&nfc_resets=0"
+fn get_nfc_params() -> String {
+    let Some(report) = crate::nfc_bridge_manager::bridge_report() else {
+        return String::new();
+    };
+    format!(
+        "&nfc_bridge={}&nfc_bridge_fw={}.{}&nfc_failures={}&nfc_garbage={}&nfc_resets={}",
+        report.status.as_str(),
+        report.firmware_major,
+        report.firmware_minor,
+        report.total_failures,
+        report.garbage_responses,
+        report.resets
+    )
+}
+
 // External C function to shutdown display before reboot
 extern "C" {
     fn display_shutdown();
@@ -354,16 +499,18 @@ extern "C" {
 
 /// Send heartbeat to backend to indicate display is connected
 /// Also checks for pending commands (e.g., reboot)
-/// Includes WiFi status so backend always has current network info
+/// Includes WiFi status and NFC bridge health so backend always has current
+/// device info
 fn send_heartbeat(base_url: &str) {
     use esp_idf_sys::esp_restart;
 
     let version = env!("CARGO_PKG_VERSION");
     let update_available = crate::ota_manager::is_update_available();
     let wifi_params = get_wifi_params();
+    let nfc_params = get_nfc_params();
     let url = format!(
-        "{}/api/display/heartbeat?version={}&update_available={}{}",
-        base_url, version, update_available, wifi_params
+        "{}/api/display/heartbeat?version={}&update_available={}{}{}",
+        base_url, version, update_available, wifi_params, nfc_params
     );
 
     let config = HttpConfig {
@@ -398,6 +545,7 @@ fn send_heartbeat(base_url: &str) {
                 log::info!("Received update command from backend - starting OTA");
                 if let Err(e) = crate::ota_manager::perform_update(base_url) {
                     log::error!("OTA update failed: {}", e);
+                    crate::buzzer::play(crate::buzzer::Pattern::Error);
                 }
                 // perform_update reboots on success, so we only get here on failure
             }
@@ -415,19 +563,34 @@ fn send_heartbeat(base_url: &str) {
                 let result = crate::scale_manager::scale_tare();
                 log::info!("Scale tare result: {}", result);
             }
-            // Check for scale calibrate command (e.g., "scale_calibrate:100.0")
+            // Check for per-channel scale tare command (e.g., "scale_tare:1")
+            else if body.contains("\"command\":\"scale_tare:") || body.contains("\"command\": \"scale_tare:") {
+                if let Some(start) = body.find("scale_tare:") {
+                    let after_cmd = &body[start + 11..];
+                    let end = after_cmd.find(|c: char| c == '"' || c.is_whitespace()).unwrap_or(after_cmd.len());
+                    match after_cmd[..end].parse::<u8>() {
+                        Ok(channel) => {
+                            log::info!("Received scale_tare command from backend (channel {})", channel);
+                            let result = crate::scale_manager::scale_tare_ch(channel);
+                            log::info!("Scale tare result: {}", result);
+                        }
+                        Err(_) => log::warn!("Failed to parse scale channel: '{}'", &after_cmd[..end]),
+                    }
+                }
+            }
+            // Check for scale calibrate command (e.g., "scale_calibrate:100.0" or "scale_calibrate:100.0:1")
             else if body.contains("\"command\":\"scale_calibrate:") || body.contains("\"command\": \"scale_calibrate:") {
                 log::info!("Detected scale_calibrate command in response body");
-                // Extract the weight value from command
+                // Extract the weight value (and optional channel) from command
                 if let Some(start) = body.find("scale_calibrate:") {
                     let after_cmd = &body[start + 16..];
                     // Find end of weight value (quote or whitespace)
                     let end = after_cmd.find(|c: char| c == '"' || c.is_whitespace()).unwrap_or(after_cmd.len());
-                    let weight_str = &after_cmd[..end];
+                    let (weight_str, channel_str) = after_cmd[..end].split_once(':').unwrap_or((&after_cmd[..end], "0"));
                     log::info!("Parsing weight value: '{}'", weight_str);
-                    if let Ok(known_weight) = weight_str.parse::<f32>() {
-                        log::info!("Received scale_calibrate command from backend: {}g", known_weight);
-                        let result = crate::scale_manager::scale_calibrate(known_weight);
+                    if let (Ok(known_weight), Ok(channel)) = (weight_str.parse::<f32>(), channel_str.parse::<u8>()) {
+                        log::info!("Received scale_calibrate command from backend: {}g (channel {})", known_weight, channel);
+                        let result = crate::scale_manager::scale_calibrate_ch(channel, known_weight);
                         log::info!("Scale calibrate result: {} (0=success, -1=error)", result);
                     } else {
                         log::warn!("Failed to parse weight value: '{}'", weight_str);
@@ -456,8 +619,8 @@ pub fn send_device_state(tag_uid_hex: Option<&str>, weight: f32, stable: bool) -
     let base_url = manager.server_url.clone();
     drop(manager);
 
-    // Get WiFi status to include in state update
-    let wifi_params = get_wifi_params();
+    // Get second scale (if enabled), WiFi status and NFC bridge health to include in state update
+    let extra_params = format!("{}{}{}", get_second_scale_params(), get_wifi_params(), get_nfc_params());
 
     // Build URL with query params, including decoded tag data if available
     let url = if let Some(tag_id) = tag_uid_hex {
@@ -483,19 +646,19 @@ pub fn send_device_state(tag_uid_hex: Option<&str>, weight: f32, stable: bool) -
                 color_rgba,
                 spool_weight,
                 encode(&tag_type),
-                wifi_params
+                extra_params
             )
         } else {
             // Just send tag_id without decoded data
             format!(
                 "{}/api/display/state?weight={:.1}&stable={}&tag_id={}{}",
-                base_url, weight, stable, tag_id, wifi_params
+                base_url, weight, stable, tag_id, extra_params
             )
         }
     } else {
         format!(
             "{}/api/display/state?weight={:.1}&stable={}{}",
-            base_url, weight, stable, wifi_params
+            base_url, weight, stable, extra_params
         )
     };
 
@@ -517,7 +680,7 @@ pub fn send_device_state(tag_uid_hex: Option<&str>, weight: f32, stable: bool) -
         Err(_) => return false,
     };
 
-    let response = match request.submit() {
+    let mut response = match request.submit() {
         Ok(r) => r,
         Err(_) => return false,
     };
@@ -526,13 +689,176 @@ pub fn send_device_state(tag_uid_hex: Option<&str>, weight: f32, stable: bool) -
         return false;
     }
 
-    // If we have a tag, fetch decoded data from display/status
-    if tag_uid_hex.is_some() {
-        fetch_decoded_tag_data(&base_url);
-        return true;
+    // The reply carries the spool resolved from the tag (older backends only send {"ok": true})
+    let mut body = Vec::new();
+    let mut buf = [0u8; 512];
+    loop {
+        match response.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => body.extend_from_slice(&buf[..n]),
+            Err(_) => break,
+        }
     }
+    let reply = serde_json::from_slice::<DeviceStateResponse>(&body).unwrap_or_default();
+    let resolved = reply.spool.clone();
+    crate::encode_station::on_reply(reply.encode.clone());
+    *DEVICE_STATE_REPLY.lock().unwrap() = reply;
 
-    false
+    if tag_uid_hex.is_none() {
+        return false;
+    }
+
+    match resolved {
+        Some(spool) => {
+            // Tags the firmware couldn't decode itself take their details from the inventory
+            if crate::nfc_bridge_manager::get_tag_vendor().is_empty() {
+                crate::nfc_bridge_manager::set_decoded_tag_data(
+                    spool.brand.as_deref().unwrap_or(""),
+                    &spool.material,
+                    spool.subtype.as_deref().unwrap_or(""),
+                    spool.color_name.as_deref().unwrap_or(""),
+                    spool.color_rgba,
+                    spool.label_weight.unwrap_or(0) as i32,
+                    "database",
+                );
+            }
+        }
+        // Unknown tag or older backend, fetch decoded data from display/status
+        None => fetch_decoded_tag_data(&base_url),
+    }
+    true
+}
+
+/// C-compatible reply to the last device state update
+#[repr(C)]
+pub struct DeviceStateC {
+    pub valid: bool,              // True if the tag resolved to an inventory spool
+    pub spool_id: [u8; 64],       // Spool UUID
+    pub spool_number: c_int,      // -1 if unknown
+    pub name: [u8; 64],           // e.g. "Bambu Lab PLA Basic"
+    pub color_name: [u8; 32],
+    pub color_rgba: u32,          // RGBA packed color
+    pub remaining_g: c_int,       // Net filament left in grams, -1 if unknown
+    pub remaining_percent: c_int, // 0-100, -1 if unknown
+    pub low_stock: bool,
+    pub action: c_int,            // 0=none, 1=add spool, 2=update weight, 3=reorder
+    pub prompt: [u8; 32],         // Text for the suggested action, empty if none
+}
+
+/// Get the spool and suggested action from the last device state reply
+#[no_mangle]
+pub extern "C" fn backend_get_device_state(state: *mut DeviceStateC) -> bool {
+    if state.is_null() {
+        return false;
+    }
+
+    let reply = DEVICE_STATE_REPLY.lock().unwrap();
+    let state = unsafe { &mut *state };
+    *state = DeviceStateC {
+        valid: reply.spool.is_some(),
+        spool_id: [0; 64],
+        spool_number: -1,
+        name: [0; 64],
+        color_name: [0; 32],
+        color_rgba: 0,
+        remaining_g: -1,
+        remaining_percent: -1,
+        low_stock: false,
+        action: match reply.action {
+            SuggestedAction::AddSpool => 1,
+            SuggestedAction::UpdateWeight => 2,
+            SuggestedAction::Reorder => 3,
+            SuggestedAction::None | SuggestedAction::Unknown => 0,
+        },
+        prompt: [0; 32],
+    };
+    copy_to_c_buf(reply.action.prompt().unwrap_or(""), &mut state.prompt);
+
+    if let Some(ref spool) = reply.spool {
+        copy_to_c_buf(&spool.id, &mut state.spool_id);
+        copy_to_c_buf(&spool.name, &mut state.name);
+        copy_to_c_buf(spool.color_name.as_deref().unwrap_or(""), &mut state.color_name);
+        state.spool_number = spool.spool_number.map_or(-1, |n| n as c_int);
+        state.color_rgba = spool.color_rgba;
+        state.remaining_g = spool.remaining_g.map_or(-1, |g| g as c_int);
+        state.remaining_percent = spool.remaining_percent().map_or(-1, c_int::from);
+        state.low_stock = spool.low_stock;
+    }
+    state.valid
+}
+
+/// C-compatible theme settings
+#[repr(C)]
+pub struct ThemeSettingsC {
+    pub mode: c_int,      // 0=light, 1=dark, 2=high contrast
+    pub has_accent: bool, // False to use the theme's own accent color
+    pub accent_rgb: u32,  // User accent color, 0xRRGGBB
+    pub generation: u32,  // Changes whenever the settings change
+}
+
+/// Get the theme settings synced from the backend
+#[no_mangle]
+pub extern "C" fn backend_get_theme(theme: *mut ThemeSettingsC) {
+    if theme.is_null() {
+        return;
+    }
+
+    let (mode, accent) = *THEME_SETTINGS.lock().unwrap();
+    let theme = unsafe { &mut *theme };
+    *theme = ThemeSettingsC {
+        mode,
+        has_accent: accent.is_some(),
+        accent_rgb: accent.unwrap_or(0),
+        generation: THEME_GENERATION.load(std::sync::atomic::Ordering::Relaxed),
+    };
+}
+
+/// C-compatible kiosk screen from the last device state reply
+#[repr(C)]
+pub struct KioskStateC {
+    pub active: bool,          // True while kiosk mode is on
+    pub state: c_int,          // 0=scan badge, 1=scan spool, 2=checked out, 3=checked in, 4=unknown tag
+    pub member_name: [u8; 48], // Signed-in member, empty if none
+    pub spool_name: [u8; 64],  // Spool just checked out or in
+    pub message: [u8; 64],     // Instruction or result to show
+    pub weight_used: c_int,    // Grams used while checked out, -1 if not a check-in
+    pub expires_in: c_int,     // Seconds until the member is signed out, -1 if nobody is signed in
+}
+
+/// Get the kiosk screen from the last device state reply, false unless kiosk mode is on
+#[no_mangle]
+pub extern "C" fn backend_get_kiosk_state(state: *mut KioskStateC) -> bool {
+    if state.is_null() {
+        return false;
+    }
+
+    let reply = DEVICE_STATE_REPLY.lock().unwrap();
+    let state = unsafe { &mut *state };
+    *state = KioskStateC {
+        active: reply.kiosk.is_some(),
+        state: 0,
+        member_name: [0; 48],
+        spool_name: [0; 64],
+        message: [0; 64],
+        weight_used: -1,
+        expires_in: -1,
+    };
+
+    if let Some(ref kiosk) = reply.kiosk {
+        state.state = match kiosk.state {
+            KioskState::ScanBadge | KioskState::Unknown => 0,
+            KioskState::ScanSpool => 1,
+            KioskState::CheckedOut => 2,
+            KioskState::CheckedIn => 3,
+            KioskState::UnknownTag => 4,
+        };
+        copy_to_c_buf(kiosk.member_name.as_deref().unwrap_or(""), &mut state.member_name);
+        copy_to_c_buf(kiosk.spool_name.as_deref().unwrap_or(""), &mut state.spool_name);
+        copy_to_c_buf(&kiosk.message, &mut state.message);
+        state.weight_used = kiosk.weight_used.map_or(-1, |g| g.round() as c_int);
+        state.expires_in = kiosk.expires_in.map_or(-1, |s| s as c_int);
+    }
+    state.active
 }
 
 /// Fetch decoded tag data from backend
@@ -612,10 +938,33 @@ fn fetch_decoded_tag_data(base_url: &str) {
 /// Fetch time from backend and update time manager
 /// Can be called independently for quick time sync
 pub fn fetch_and_set_time(base_url: &str) {
+    // Timezone first, so the clock is shown in local time right away
+    if TIME_SYNC_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % DEVICE_SETTINGS_INTERVAL == 0 {
+        let settings_url = format!("{}/api/settings/device/time", base_url);
+        match fetch_small_json::<ApiClockSettings>(&settings_url) {
+            Ok(settings) => {
+                crate::time_manager::apply_clock_settings(&settings.posix_tz, &settings.ntp_server);
+            }
+            Err(e) => warn!("Failed to fetch clock settings: {}", e),
+        }
+
+        let sound_url = format!("{}/api/settings/device/sound", base_url);
+        match fetch_small_json::<ApiSoundSettings>(&sound_url) {
+            Ok(settings) => crate::buzzer::apply_backend_settings(settings.buzzer_gpio, settings.muted),
+            Err(e) => warn!("Failed to fetch sound settings: {}", e),
+        }
+
+        let theme_url = format!("{}/api/settings/device/theme", base_url);
+        match fetch_small_json::<ApiThemeSettings>(&theme_url) {
+            Ok(settings) => apply_theme_settings(&settings),
+            Err(e) => warn!("Failed to fetch theme settings: {}", e),
+        }
+    }
+
     let time_url = format!("{}/api/time", base_url);
-    match fetch_time(&time_url) {
+    match fetch_small_json::<ApiTime>(&time_url) {
         Ok(time) => {
-            crate::time_manager::set_backend_time(time.hour, time.minute);
+            crate::time_manager::set_backend_time(time.timestamp);
         }
         Err(_) => {
             // Silently ignore time fetch errors
@@ -623,6 +972,27 @@ pub fn fetch_and_set_time(base_url: &str) {
     }
 }
 
+/// Store the theme from the backend, bumping the generation if it changed
+fn apply_theme_settings(settings: &ApiThemeSettings) {
+    let mode = match settings.mode.as_str() {
+        "light" => 0,
+        "high_contrast" => 2,
+        _ => 1,
+    };
+    let accent = settings
+        .accent_color
+        .as_deref()
+        .and_then(|hex| u32::from_str_radix(hex.trim_start_matches('#'), 16).ok())
+        .filter(|rgb| *rgb <= 0xFFFFFF);
+
+    let mut theme = THEME_SETTINGS.lock().unwrap();
+    if *theme != (mode, accent) {
+        info!("Theme changed: mode={} accent={:?}", settings.mode, settings.accent_color);
+        *theme = (mode, accent);
+        THEME_GENERATION.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
 /// Quick time sync - call after setting server URL
 pub fn sync_time() {
     let manager = BACKEND_MANAGER.lock().unwrap();
@@ -635,7 +1005,8 @@ pub fn sync_time() {
 }
 
 /// Fetch printers from backend API
-fn fetch_printers(url: &str) -> Result<Vec<ApiPrinter>, String> {
+/// Fetch the printer list, None if it hasn't changed since the last fetch
+fn fetch_printers(url: &str) -> Result<Option<Vec<ApiPrinter>>, String> {
     // Create HTTP client
     let config = HttpConfig {
         timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
@@ -647,8 +1018,14 @@ fn fetch_printers(url: &str) -> Result<Vec<ApiPrinter>, String> {
 
     let mut client = HttpClient::wrap(connection);
 
-    // Make GET request
-    let request = client.get(url)
+    // Make GET request, revalidating the cached list so an unchanged one isn't re-sent
+    let etag = PRINTERS_ETAG.lock().unwrap().clone();
+    let headers: Vec<(&str, &str)> = if etag.is_empty() {
+        Vec::new()
+    } else {
+        vec![("If-None-Match", etag.as_str())]
+    };
+    let request = client.request(embedded_svc::http::Method::Get, url, &headers)
         .map_err(|e| format!("GET request failed: {:?}", e))?;
 
     let mut response = request.submit()
@@ -656,9 +1033,13 @@ fn fetch_printers(url: &str) -> Result<Vec<ApiPrinter>, String> {
 
     // Check status
     let status = response.status();
+    if status == 304 {
+        return Ok(None);
+    }
     if status != 200 {
         return Err(format!("HTTP error: {}", status));
     }
+    let new_etag = response.header("ETag").unwrap_or("").to_string();
 
     // Read response body
     let mut body = Vec::new();
@@ -675,11 +1056,12 @@ fn fetch_printers(url: &str) -> Result<Vec<ApiPrinter>, String> {
     let printers: Vec<ApiPrinter> = serde_json::from_slice(&body)
         .map_err(|e| format!("JSON parse error: {:?}", e))?;
 
-    Ok(printers)
+    *PRINTERS_ETAG.lock().unwrap() = new_etag;
+    Ok(Some(printers))
 }
 
-/// Fetch time from backend API
-fn fetch_time(url: &str) -> Result<ApiTime, String> {
+/// Fetch a small JSON document (time, clock settings) from backend API
+fn fetch_small_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, String> {
     let config = HttpConfig {
         timeout: Some(std::time::Duration::from_millis(2000)), // Short timeout for time
         ..Default::default()
@@ -711,10 +1093,8 @@ fn fetch_time(url: &str) -> Result<ApiTime, String> {
         }
     }
 
-    let time: ApiTime = serde_json::from_slice(&body)
-        .map_err(|e| format!("JSON parse error: {:?}", e))?;
-
-    Ok(time)
+    serde_json::from_slice(&body)
+        .map_err(|e| format!("JSON parse error: {:?}", e))
 }
 
 /// Update the cached printer data
@@ -797,6 +1177,26 @@ fn update_printer_cache(manager: &mut BackendManager, printers: &[ApiPrinter]) {
             cached.stg_cur_name[..len].copy_from_slice(&bytes[..len]);
         }
 
+        // Copy print job progress and the spools it is using
+        cached.layer_num = printer.layer_num.unwrap_or(-1);
+        cached.total_layer_num = printer.total_layer_num.unwrap_or(-1);
+        cached.active_spool_count = printer.active_spools.len().min(MAX_ACTIVE_SPOOLS) as u8;
+        cached.active_spools = [EMPTY_ACTIVE_SPOOL; MAX_ACTIVE_SPOOLS];
+        for (j, spool) in printer.active_spools.iter().take(MAX_ACTIVE_SPOOLS).enumerate() {
+            let cached_spool = &mut cached.active_spools[j];
+            if let Some(ref material) = spool.material {
+                copy_to_c_buf(material, &mut cached_spool.material);
+            }
+            if let Some(ref color_name) = spool.color_name {
+                copy_to_c_buf(color_name, &mut cached_spool.color_name);
+            }
+            cached_spool.color_rgba = spool.rgba.as_ref().map(|c| parse_rgba_hex(c)).unwrap_or(0);
+            cached_spool.remaining_grams = spool.remaining_grams.map(|g| g.round() as i32).unwrap_or(-1);
+            cached_spool.ams_id = spool.ams_id;
+            cached_spool.tray_id = spool.tray_id;
+            cached_spool.extruder = spool.extruder.map(|e| e as i8).unwrap_or(-1);
+        }
+
         // Copy active tray info
         cached.tray_now = printer.tray_now.unwrap_or(-1);
         cached.tray_now_left = printer.tray_now_left.unwrap_or(-1);
@@ -838,9 +1238,34 @@ fn update_printer_cache(manager: &mut BackendManager, printers: &[ApiPrinter]) {
                     .unwrap_or(0);
 
                 // Remaining percentage (clamp negative to 0)
+                let previous_remain = cached_tray.remain;
                 cached_tray.remain = tray.remain.unwrap_or(0).max(0) as u8;
+
+                // Alert once when a known remaining level drops to the threshold
+                if previous_remain > LOW_FILAMENT_PERCENT
+                    && cached_tray.remain <= LOW_FILAMENT_PERCENT
+                    && tray.remain.is_some_and(|r| r >= 0)
+                {
+                    info!("  Tray {} low on filament ({}%)", k, cached_tray.remain);
+                    crate::buzzer::play(crate::buzzer::Pattern::LowFilament);
+                }
             }
         }
+
+        // Copy external spool (virtual tray)
+        cached.vt_tray = EMPTY_AMS_TRAY;
+        if let Some(ref tray) = printer.vt_tray {
+            if let Some(ref tray_type) = tray.tray_type {
+                let bytes = tray_type.as_bytes();
+                let len = bytes.len().min(15);
+                cached.vt_tray.tray_type[..len].copy_from_slice(&bytes[..len]);
+            }
+            cached.vt_tray.tray_color = tray.tray_color
+                .as_ref()
+                .map(|c| parse_rgba_color(c))
+                .unwrap_or(0);
+            cached.vt_tray.remain = tray.remain.unwrap_or(0).max(0) as u8;
+        }
     }
 
 }
@@ -1242,6 +1667,34 @@ pub extern "C" fn backend_get_ams_unit(
     0
 }
 
+/// Get the external spool (virtual tray) of a printer
+/// Returns 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn backend_get_vt_tray(printer_index: c_int, info: *mut AmsTrayCInfo) -> c_int {
+    if info.is_null() {
+        return -1;
+    }
+
+    let manager = BACKEND_MANAGER.lock().unwrap();
+    if printer_index < 0 || printer_index as usize >= manager.printer_count {
+        return -1;
+    }
+
+    let tray = &manager.printers[printer_index as usize].vt_tray;
+
+    unsafe {
+        let out = &mut *info;
+        out.tray_type = [0; 16];
+        for (j, &byte) in tray.tray_type.iter().enumerate() {
+            out.tray_type[j] = byte as c_char;
+        }
+        out.tray_color = tray.tray_color;
+        out.remain = tray.remain;
+    }
+
+    0
+}
+
 /// AMS tray info with string color (for status_bar.c hex parsing)
 #[repr(C)]
 pub struct AmsTrayInfo {
@@ -1345,6 +1798,67 @@ pub extern "C" fn backend_get_active_extruder(printer_index: c_int) -> c_int {
     manager.printers[printer_index as usize].active_extruder
 }
 
+/// Spool feeding an extruder, for C interface
+#[repr(C)]
+pub struct ActiveSpoolCInfo {
+    pub material: [c_char; 16],
+    pub color_name: [c_char; 32],
+    pub color_rgba: u32,          // RGBA packed
+    pub remaining_grams: c_int,   // -1 if unknown
+    pub ams_id: c_int,
+    pub tray_id: c_int,
+    pub extruder: i8,             // -1 on single-nozzle printers, 0=right, 1=left
+}
+
+/// Progress of the current print job, for C interface
+#[repr(C)]
+pub struct PrintJobCInfo {
+    pub layer_num: c_int,         // -1 if not reported
+    pub total_layer_num: c_int,   // -1 if not reported
+    pub spool_count: u8,
+    pub spools: [ActiveSpoolCInfo; MAX_ACTIVE_SPOOLS],
+}
+
+/// Get the layer progress and the spools used by a printer's current print
+/// Returns 0 on success, -1 on error
+#[no_mangle]
+pub extern "C" fn backend_get_print_job(printer_index: c_int, info: *mut PrintJobCInfo) -> c_int {
+    if info.is_null() {
+        return -1;
+    }
+
+    let manager = BACKEND_MANAGER.lock().unwrap();
+    if printer_index < 0 || printer_index as usize >= manager.printer_count {
+        return -1;
+    }
+
+    let printer = &manager.printers[printer_index as usize];
+
+    unsafe {
+        let out = &mut *info;
+        out.layer_num = printer.layer_num;
+        out.total_layer_num = printer.total_layer_num;
+        out.spool_count = printer.active_spool_count;
+
+        for (i, spool) in printer.active_spools.iter().enumerate() {
+            let out_spool = &mut out.spools[i];
+            for (j, &byte) in spool.material.iter().enumerate() {
+                out_spool.material[j] = byte as c_char;
+            }
+            for (j, &byte) in spool.color_name.iter().enumerate() {
+                out_spool.color_name[j] = byte as c_char;
+            }
+            out_spool.color_rgba = spool.color_rgba;
+            out_spool.remaining_grams = spool.remaining_grams;
+            out_spool.ams_id = spool.ams_id;
+            out_spool.tray_id = spool.tray_id;
+            out_spool.extruder = spool.extruder;
+        }
+    }
+
+    0
+}
+
 /// Check if firmware update is available
 /// Returns 1 if update available, 0 otherwise
 #[no_mangle]
@@ -1467,6 +1981,7 @@ pub extern "C" fn ota_start_update() -> c_int {
     std::thread::spawn(move || {
         if let Err(e) = crate::ota_manager::perform_update(&url) {
             log::error!("OTA update failed: {}", e);
+            crate::buzzer::play(crate::buzzer::Pattern::Error);
         }
         // Note: perform_update reboots on success, so we only get here on error
     });
@@ -1512,11 +2027,18 @@ struct ApiSpool {
     subtype: Option<String>,
     color_name: Option<String>,
     rgba: Option<String>,
+    color: Option<ApiColor>,  // Swatch parsed by the backend, None on older backends
     label_weight: Option<i32>,
     weight_current: Option<i32>,
     slicer_filament: Option<String>,
 }
 
+/// API color swatch (only the fields the display uses)
+#[derive(Debug, Deserialize)]
+struct ApiColor {
+    rgba_int: u32,  // Packed 0xRRGGBBAA
+}
+
 /// API response for K-profile
 #[derive(Debug, Deserialize)]
 struct ApiKProfile {
@@ -1535,7 +2057,7 @@ struct ApiAssignResponse {
 }
 
 /// Helper to copy string to fixed-size C buffer
-fn copy_to_c_buf(src: &str, dst: &mut [u8]) {
+pub(crate) fn copy_to_c_buf(src: &str, dst: &mut [u8]) {
     let bytes = src.as_bytes();
     let copy_len = std::cmp::min(bytes.len(), dst.len() - 1);
     dst[..copy_len].copy_from_slice(&bytes[..copy_len]);
@@ -1660,7 +2182,9 @@ pub extern "C" fn spool_get_by_tag(tag_id: *const c_char, info: *mut SpoolInfoC)
                 if let Some(ref c) = spool.color_name {
                     copy_to_c_buf(c, &mut info_ref.color_name);
                 }
-                if let Some(ref rgba) = spool.rgba {
+                if let Some(ref color) = spool.color {
+                    info_ref.color_rgba = color.rgba_int;
+                } else if let Some(ref rgba) = spool.rgba {
                     info_ref.color_rgba = parse_rgba_hex(rgba);
                 }
                 if let Some(w) = spool.label_weight {
@@ -1972,7 +2496,13 @@ pub extern "C" fn spool_add_to_inventory(
     let mut request = match client.request(embedded_svc::http::Method::Post, &url, &headers) {
         Ok(r) => r,
         Err(e) => {
+            // Server unreachable - nothing was sent, so keep it on the SD card
+            // and send it once the backend is back
             warn!("Failed to create POST request: {:?}", e);
+            if crate::sd_card::queue_event("/api/spools", &body) {
+                info!("spool_add_to_inventory: backend offline, queued");
+                return true;
+            }
             return false;
         }
     };
@@ -1998,6 +2528,7 @@ pub extern "C" fn spool_add_to_inventory(
     let status = response.status();
     if status != 200 && status != 201 {
         warn!("spool_add_to_inventory failed with status {}", status);
+        crate::buzzer::play(crate::buzzer::Pattern::Error);
         return false;
     }
 
@@ -2005,6 +2536,159 @@ pub extern "C" fn spool_add_to_inventory(
     true
 }
 
+/// Push the device's sound settings (after a mute toggled on the device).
+/// Returns true if the backend accepted them.
+fn push_sound_settings(base_url: &str) -> bool {
+    let url = format!("{}/api/settings/device/sound", base_url);
+    let gpio = crate::buzzer::gpio().map(|g| g.to_string()).unwrap_or_else(|| "null".to_string());
+    let body = format!(r#"{{"muted":{},"buzzer_gpio":{}}}"#, crate::buzzer::is_muted(), gpio);
+
+    let config = HttpConfig {
+        timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
+        ..Default::default()
+    };
+
+    let connection = match EspHttpConnection::new(&config) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to create HTTP connection: {:?}", e);
+            return false;
+        }
+    };
+    let mut client = HttpClient::wrap(connection);
+
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Content-Length", &body.len().to_string()),
+    ];
+
+    let mut request = match client.request(embedded_svc::http::Method::Put, &url, &headers) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Failed to create PUT request: {:?}", e);
+            return false;
+        }
+    };
+    if request.write(body.as_bytes()).is_err() || request.flush().is_err() {
+        return false;
+    }
+    match request.submit() {
+        Ok(response) if response.status() == 200 => true,
+        Ok(response) => {
+            warn!("Failed to push sound settings: HTTP {}", response.status());
+            false
+        }
+        Err(e) => {
+            warn!("Failed to push sound settings: {:?}", e);
+            false
+        }
+    }
+}
+
+/// Report a batch encoding write to the backend.
+/// Returns the next task (None once the batch is over), or None if the backend couldn't be reached.
+pub fn post_encode_result(result: &EncodeResult) -> Option<Option<EncodeTask>> {
+    let base_url = BACKEND_MANAGER.lock().unwrap().server_url.clone();
+    if base_url.is_empty() {
+        return None;
+    }
+    let url = format!("{}/api/tags/batch/result", base_url);
+    let body = serde_json::to_string(result).ok()?;
+
+    let config = HttpConfig {
+        timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
+        ..Default::default()
+    };
+    let connection = EspHttpConnection::new(&config).ok()?;
+    let mut client = HttpClient::wrap(connection);
+
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Content-Length", &body.len().to_string()),
+    ];
+    let mut request = client.request(embedded_svc::http::Method::Post, &url, &headers).ok()?;
+    if request.write(body.as_bytes()).is_err() || request.flush().is_err() {
+        return None;
+    }
+    let mut response = request.submit().ok()?;
+    match response.status() {
+        200 => {}
+        // The batch moved on or was cancelled while we were writing
+        409 => return Some(None),
+        status => {
+            warn!("Failed to report tag write: HTTP {}", status);
+            return None;
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Reply {
+        encode: Option<EncodeTask>,
+    }
+    let mut body = Vec::new();
+    let mut buf = [0u8; 512];
+    loop {
+        match response.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => body.extend_from_slice(&buf[..n]),
+            Err(_) => break,
+        }
+    }
+    serde_json::from_slice::<Reply>(&body).ok().map(|reply| reply.encode)
+}
+
+/// Send backend requests queued on the SD card while offline, oldest first.
+/// Stops at the first request the backend can't take yet.
+fn flush_queued_events(base_url: &str) {
+    let sent = crate::sd_card::flush_events(|event| post_queued_event(base_url, event));
+    if sent > 0 {
+        info!("Sent {} queued events", sent);
+    }
+}
+
+/// POST a queued event. Returns false if it should stay queued.
+fn post_queued_event(base_url: &str, event: &crate::sd_card::QueuedEvent) -> bool {
+    let url = format!("{}{}", base_url, event.path);
+
+    let config = HttpConfig {
+        timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
+        ..Default::default()
+    };
+
+    let connection = match EspHttpConnection::new(&config) {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+    let mut client = HttpClient::wrap(connection);
+
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Content-Length", &event.body.len().to_string()),
+    ];
+
+    let mut request = match client.request(embedded_svc::http::Method::Post, &url, &headers) {
+        Ok(r) => r,
+        Err(_) => return false,
+    };
+    if request.write(event.body.as_bytes()).is_err() || request.flush().is_err() {
+        return false;
+    }
+    let response = match request.submit() {
+        Ok(r) => r,
+        Err(_) => return false,
+    };
+
+    let status = response.status();
+    if status >= 500 {
+        return false;
+    }
+    if status >= 400 {
+        // Retrying won't help (e.g. the tag was added from another client)
+        warn!("Dropping queued POST {}: HTTP {}", event.path, status);
+    }
+    true
+}
+
 /// Untagged spool info for FFI
 #[repr(C)]
 pub struct UntaggedSpoolInfo {
@@ -2203,6 +2887,30 @@ pub extern "C" fn spool_link_tag(
     spool_id: *const c_char,
     tag_id: *const c_char,
     tag_type: *const c_char,
+) -> c_int {
+    send_tag_request(spool_id, tag_id, tag_type, embedded_svc::http::Method::Patch, "link-tag")
+}
+
+/// Move an NFC tag to a spool, taking it off the spool that has it (after a 409 from spool_link_tag).
+/// The other spool keeps its history.
+/// Returns: 0 = success, -1 = connection error, or HTTP status code on failure
+#[no_mangle]
+pub extern "C" fn spool_move_tag(
+    spool_id: *const c_char,
+    tag_id: *const c_char,
+    tag_type: *const c_char,
+) -> c_int {
+    send_tag_request(spool_id, tag_id, tag_type, embedded_svc::http::Method::Post, "move-tag")
+}
+
+/// Send a tag to /api/spools/{spool_id}/{action}
+/// Returns: 0 = success, -1 = connection error, or HTTP status code on failure
+fn send_tag_request(
+    spool_id: *const c_char,
+    tag_id: *const c_char,
+    tag_type: *const c_char,
+    method: embedded_svc::http::Method,
+    action: &str,
 ) -> c_int {
     if spool_id.is_null() || tag_id.is_null() {
         return -1;
@@ -2233,15 +2941,15 @@ pub extern "C" fn spool_link_tag(
         return -1;
     }
 
-    // PATCH /api/spools/{spool_id}/link-tag
-    let url = format!("{}/api/spools/{}/link-tag", base_url, spool_id_str);
+    // PATCH /api/spools/{spool_id}/link-tag or POST /api/spools/{spool_id}/move-tag
+    let url = format!("{}/api/spools/{}/{}", base_url, spool_id_str, action);
 
     let body = format!(
         r#"{{"tag_id":"{}","tag_type":"{}"}}"#,
         tag_id_str, tag_type_str
     );
 
-    info!("spool_link_tag: PATCH {} with {}", url, body);
+    info!("spool {}: {:?} {} with {}", action, method, url, body);
 
     let config = HttpConfig {
         timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
@@ -2263,10 +2971,10 @@ pub extern "C" fn spool_link_tag(
         ("Content-Length", &body.len().to_string()),
     ];
 
-    let mut request = match client.request(embedded_svc::http::Method::Patch, &url, &headers) {
+    let mut request = match client.request(method, &url, &headers) {
         Ok(r) => r,
         Err(e) => {
-            warn!("Failed to create PATCH request: {:?}", e);
+            warn!("Failed to create {} request: {:?}", action, e);
             return -1;
         }
     };
@@ -2291,11 +2999,11 @@ pub extern "C" fn spool_link_tag(
 
     let status = response.status();
     if status != 200 {
-        warn!("spool_link_tag failed with status {}", status);
+        warn!("spool {} failed with status {}", action, status);
         return status as c_int;
     }
 
-    info!("spool_link_tag: success");
+    info!("spool {}: success", action);
     0
 }
 
@@ -2353,6 +3061,8 @@ pub extern "C" fn spool_sync_weight(
     let headers = [
         ("Content-Type", "application/json"),
         ("Content-Length", &body.len().to_string()),
+        // Lets the backend offer undo in case the wrong spool was on the scale
+        ("X-SpoolBuddy-Source", "device"),
     ];
 
     let mut request = match client.request(embedded_svc::http::Method::Put, &url, &headers) {
@@ -3327,6 +4037,85 @@ pub extern "C" fn backend_reset_slot(
     true
 }
 
+/// Pause, resume or stop the running print ("pause", "resume" or "stop")
+/// Returns true if the printer accepted the command
+#[no_mangle]
+pub extern "C" fn backend_print_control(printer_serial: *const c_char, action: *const c_char) -> bool {
+    if printer_serial.is_null() || action.is_null() {
+        return false;
+    }
+
+    let (serial_str, action_str) = unsafe {
+        match (
+            std::ffi::CStr::from_ptr(printer_serial).to_str(),
+            std::ffi::CStr::from_ptr(action).to_str(),
+        ) {
+            (Ok(s), Ok(a)) => (s, a),
+            _ => return false,
+        }
+    };
+
+    let manager = BACKEND_MANAGER.lock().unwrap();
+    let base_url = manager.server_url.clone();
+    drop(manager);
+
+    if base_url.is_empty() {
+        return false;
+    }
+
+    // POST /api/printers/{serial}/print/{action}
+    let url = format!("{}/api/printers/{}/print/{}", base_url, serial_str, action_str);
+
+    info!("backend_print_control: POST {}", url);
+
+    let config = HttpConfig {
+        timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
+        ..Default::default()
+    };
+
+    let connection = match EspHttpConnection::new(&config) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to create HTTP connection: {:?}", e);
+            return false;
+        }
+    };
+
+    let mut client = HttpClient::wrap(connection);
+
+    // Empty body POST
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Content-Length", "0"),
+    ];
+
+    let request = match client.request(embedded_svc::http::Method::Post, &url, &headers) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Failed to create POST request: {:?}", e);
+            return false;
+        }
+    };
+
+    let response = match request.submit() {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("Failed to submit request: {:?}", e);
+            return false;
+        }
+    };
+
+    // 202: the printer applies the command asynchronously
+    let status = response.status();
+    if status != 202 {
+        warn!("print_control {} failed with status {}", action_str, status);
+        return false;
+    }
+
+    info!("backend_print_control: {} sent", action_str);
+    true
+}
+
 /// Search color catalog by manufacturer and/or material
 /// Returns number of colors found (up to max_count), -1 on error
 #[no_mangle]
@@ -0,0 +1,24 @@
+//! Board selection
+//!
+//! The target board is chosen at build time with a cargo feature; the
+//! presets themselves live in `spoolbuddy_core::board`:
+//!
+//! ```text
+//! cargo build --release                               # CrowPanel Advance 7.0" (800x480)
+//! cargo build --release --features board-waveshare-4-3  # Waveshare 4.3" (800x480)
+//! cargo build --release --features board-waveshare-7    # Waveshare 7" (1024x600)
+//! ```
+
+use spoolbuddy_core::board::{self, Board};
+
+#[cfg(all(feature = "board-waveshare-4-3", feature = "board-waveshare-7"))]
+compile_error!("Select only one board feature");
+
+#[cfg(feature = "board-waveshare-4-3")]
+pub const BOARD: &Board = &board::WAVESHARE_4_3;
+
+#[cfg(feature = "board-waveshare-7")]
+pub const BOARD: &Board = &board::WAVESHARE_7;
+
+#[cfg(not(any(feature = "board-waveshare-4-3", feature = "board-waveshare-7")))]
+pub const BOARD: &Board = &board::CROWPANEL_7;
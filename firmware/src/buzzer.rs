@@ -0,0 +1,293 @@
+//! Buzzer feedback
+//!
+//! Plays short tone patterns on a passive piezo driven by LEDC PWM. Patterns
+//! are queued to a player thread, so callers in the main loop never block.
+//!
+//! The piezo GPIO and the mute flag are device settings: they're synced with
+//! the backend (`/api/settings/device/sound`) and cached in NVS so the buzzer
+//! works (or stays quiet) before the backend is reachable. No GPIO is
+//! configured by default, since the boards don't share a free pin.
+
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs};
+use esp_idf_sys::{
+    gpio_reset_pin, ledc_channel_config, ledc_channel_config_t, ledc_channel_t_LEDC_CHANNEL_0,
+    ledc_clk_cfg_t_LEDC_AUTO_CLK, ledc_intr_type_t_LEDC_INTR_DISABLE, ledc_mode_t_LEDC_LOW_SPEED_MODE,
+    ledc_set_duty, ledc_set_freq, ledc_stop, ledc_timer_bit_t_LEDC_TIMER_10_BIT, ledc_timer_config,
+    ledc_timer_config_t, ledc_timer_t_LEDC_TIMER_0, ledc_update_duty, ESP_OK,
+};
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const NVS_NAMESPACE: &str = "buzzer";
+const NVS_KEY_GPIO: &str = "gpio";
+const NVS_KEY_MUTED: &str = "muted";
+
+/// No buzzer connected
+const NO_GPIO: i32 = -1;
+
+const LEDC_MODE: u32 = ledc_mode_t_LEDC_LOW_SPEED_MODE;
+const LEDC_TIMER: u32 = ledc_timer_t_LEDC_TIMER_0;
+const LEDC_CHANNEL: u32 = ledc_channel_t_LEDC_CHANNEL_0;
+/// 50% duty at 10-bit resolution (loudest for a piezo)
+const DUTY_ON: u32 = 512;
+
+/// Feedback patterns (values shared with the C UI)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+    TagDetected = 0,
+    WeightStable = 1,
+    Error = 2,
+    LowFilament = 3,
+}
+
+impl Pattern {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Pattern::TagDetected),
+            1 => Some(Pattern::WeightStable),
+            2 => Some(Pattern::Error),
+            3 => Some(Pattern::LowFilament),
+            _ => None,
+        }
+    }
+
+    /// (frequency in Hz, duration in ms) steps; 0 Hz is a pause
+    fn tones(self) -> &'static [(u32, u64)] {
+        match self {
+            // Rising chirp
+            Pattern::TagDetected => &[(2000, 40), (0, 30), (2700, 60)],
+            // Single short tick
+            Pattern::WeightStable => &[(3000, 50)],
+            // Low double buzz
+            Pattern::Error => &[(600, 150), (0, 80), (600, 150)],
+            // Three even beeps
+            Pattern::LowFilament => &[(1800, 120), (0, 100), (1800, 120), (0, 100), (1800, 120)],
+        }
+    }
+}
+
+/// Buzzer settings (synced with the backend)
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    gpio: i32,
+    muted: bool,
+}
+
+static SETTINGS: Mutex<Settings> = Mutex::new(Settings { gpio: NO_GPIO, muted: false });
+static NVS_PARTITION: Mutex<Option<EspDefaultNvsPartition>> = Mutex::new(None);
+static PLAYER: Mutex<Option<Sender<Pattern>>> = Mutex::new(None);
+
+/// Set when the mute flag was changed on the device and still has to be
+/// pushed to the backend
+static MUTE_CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Load the cached settings from NVS and start the player thread
+pub fn init(nvs: Option<EspDefaultNvsPartition>) {
+    if let Some(ref partition) = nvs {
+        if let Ok(nvs) = EspNvs::new(partition.clone(), NVS_NAMESPACE, true) {
+            let mut settings = SETTINGS.lock().unwrap();
+            if let Ok(Some(gpio)) = nvs.get_i32(NVS_KEY_GPIO) {
+                settings.gpio = gpio;
+            }
+            if let Ok(Some(muted)) = nvs.get_u8(NVS_KEY_MUTED) {
+                settings.muted = muted != 0;
+            }
+        }
+    }
+    *NVS_PARTITION.lock().unwrap() = nvs;
+
+    let (tx, rx) = mpsc::channel();
+    match std::thread::Builder::new()
+        .name("buzzer".into())
+        .stack_size(3072)
+        .spawn(move || player_thread(rx))
+    {
+        Ok(_) => *PLAYER.lock().unwrap() = Some(tx),
+        Err(e) => warn!("Failed to start buzzer thread: {:?}", e),
+    }
+
+    let settings = *SETTINGS.lock().unwrap();
+    info!("Buzzer initialized (gpio={}, muted={})", settings.gpio, settings.muted);
+}
+
+/// Queue a feedback pattern. Does nothing when muted or no buzzer is configured.
+pub fn play(pattern: Pattern) {
+    let settings = *SETTINGS.lock().unwrap();
+    if settings.muted || settings.gpio == NO_GPIO {
+        return;
+    }
+    if let Some(ref tx) = *PLAYER.lock().unwrap() {
+        let _ = tx.send(pattern);
+    }
+}
+
+/// Current buzzer GPIO, or None if no buzzer is configured
+pub fn gpio() -> Option<i32> {
+    let gpio = SETTINGS.lock().unwrap().gpio;
+    (gpio != NO_GPIO).then_some(gpio)
+}
+
+pub fn is_muted() -> bool {
+    SETTINGS.lock().unwrap().muted
+}
+
+/// Mute or unmute on the device (pushed to the backend on the next poll)
+pub fn set_muted(muted: bool) {
+    if update_settings(None, Some(muted)) {
+        MUTE_CHANGED.store(true, Ordering::Relaxed);
+        info!("Buzzer {}", if muted { "muted" } else { "unmuted" });
+    }
+}
+
+/// Check if a mute change on the device still has to be pushed to the backend
+pub fn mute_sync_pending() -> bool {
+    MUTE_CHANGED.load(Ordering::Relaxed)
+}
+
+/// Mark the mute flag as pushed to the backend
+pub fn mute_synced() {
+    MUTE_CHANGED.store(false, Ordering::Relaxed);
+}
+
+/// Apply settings fetched from the backend. A local mute change that hasn't
+/// been pushed yet wins over the backend's value.
+pub fn apply_backend_settings(gpio: Option<i32>, muted: bool) {
+    let muted = if MUTE_CHANGED.load(Ordering::Relaxed) { None } else { Some(muted) };
+    if update_settings(Some(gpio.unwrap_or(NO_GPIO)), muted) {
+        let settings = *SETTINGS.lock().unwrap();
+        info!("Buzzer settings from backend: gpio={}, muted={}", settings.gpio, settings.muted);
+    }
+}
+
+/// Update settings and persist them. Returns true if anything changed.
+fn update_settings(gpio: Option<i32>, muted: Option<bool>) -> bool {
+    let mut settings = SETTINGS.lock().unwrap();
+    let old = *settings;
+    if let Some(gpio) = gpio {
+        settings.gpio = gpio;
+    }
+    if let Some(muted) = muted {
+        settings.muted = muted;
+    }
+    let new = *settings;
+    drop(settings);
+
+    if new.gpio == old.gpio && new.muted == old.muted {
+        return false;
+    }
+
+    if let Some(ref partition) = *NVS_PARTITION.lock().unwrap() {
+        match EspNvs::new(partition.clone(), NVS_NAMESPACE, true) {
+            Ok(nvs) => {
+                if nvs.set_i32(NVS_KEY_GPIO, new.gpio).is_err() || nvs.set_u8(NVS_KEY_MUTED, new.muted as u8).is_err() {
+                    warn!("Failed to save buzzer settings");
+                }
+            }
+            Err(e) => warn!("Failed to open NVS for buzzer settings: {:?}", e),
+        }
+    }
+    true
+}
+
+// ============================================================================
+// Player
+// ============================================================================
+
+fn player_thread(rx: Receiver<Pattern>) {
+    // GPIO the LEDC channel is currently routed to
+    let mut configured_gpio = NO_GPIO;
+
+    while let Ok(pattern) = rx.recv() {
+        let gpio = SETTINGS.lock().unwrap().gpio;
+        if gpio != configured_gpio {
+            if configured_gpio != NO_GPIO {
+                unsafe {
+                    ledc_stop(LEDC_MODE, LEDC_CHANNEL, 0);
+                    gpio_reset_pin(configured_gpio);
+                }
+            }
+            configured_gpio = NO_GPIO;
+            if gpio == NO_GPIO {
+                continue;
+            }
+            if !configure_ledc(gpio) {
+                warn!("Failed to set up buzzer on GPIO{}", gpio);
+                continue;
+            }
+            configured_gpio = gpio;
+        }
+
+        for &(freq_hz, duration_ms) in pattern.tones() {
+            unsafe {
+                if freq_hz > 0 {
+                    ledc_set_freq(LEDC_MODE, LEDC_TIMER, freq_hz);
+                    ledc_set_duty(LEDC_MODE, LEDC_CHANNEL, DUTY_ON);
+                } else {
+                    ledc_set_duty(LEDC_MODE, LEDC_CHANNEL, 0);
+                }
+                ledc_update_duty(LEDC_MODE, LEDC_CHANNEL);
+            }
+            std::thread::sleep(Duration::from_millis(duration_ms));
+        }
+        unsafe {
+            ledc_set_duty(LEDC_MODE, LEDC_CHANNEL, 0);
+            ledc_update_duty(LEDC_MODE, LEDC_CHANNEL);
+        }
+    }
+}
+
+fn configure_ledc(gpio: i32) -> bool {
+    let timer = ledc_timer_config_t {
+        speed_mode: LEDC_MODE,
+        duty_resolution: ledc_timer_bit_t_LEDC_TIMER_10_BIT,
+        timer_num: LEDC_TIMER,
+        freq_hz: 2000,
+        clk_cfg: ledc_clk_cfg_t_LEDC_AUTO_CLK,
+        ..Default::default()
+    };
+    let channel = ledc_channel_config_t {
+        gpio_num: gpio,
+        speed_mode: LEDC_MODE,
+        channel: LEDC_CHANNEL,
+        intr_type: ledc_intr_type_t_LEDC_INTR_DISABLE,
+        timer_sel: LEDC_TIMER,
+        duty: 0,
+        hpoint: 0,
+        ..Default::default()
+    };
+    unsafe { ledc_timer_config(&timer) == ESP_OK && ledc_channel_config(&channel) == ESP_OK }
+}
+
+// ============================================================================
+// C-callable interface
+// ============================================================================
+
+/// Play a feedback pattern (0=tag detected, 1=weight stable, 2=error, 3=low filament)
+#[no_mangle]
+pub extern "C" fn buzzer_play(pattern: u8) {
+    if let Some(pattern) = Pattern::from_u8(pattern) {
+        play(pattern);
+    }
+}
+
+/// Mute or unmute the buzzer (synced to the backend)
+#[no_mangle]
+pub extern "C" fn buzzer_set_muted(muted: bool) {
+    set_muted(muted);
+}
+
+/// Check if the buzzer is muted
+#[no_mangle]
+pub extern "C" fn buzzer_is_muted() -> bool {
+    is_muted()
+}
+
+/// Check if a buzzer GPIO is configured
+#[no_mangle]
+pub extern "C" fn buzzer_is_available() -> bool {
+    gpio().is_some()
+}
@@ -0,0 +1,311 @@
+//! Task watchdog, panic capture and crash report upload
+//!
+//! - The task watchdog watches the main loop only (not the idle tasks, which
+//!   can be starved during heavy UI rendering). If the loop stops feeding it
+//!   for `WATCHDOG_TIMEOUT_MS` the watchdog panics and the device reboots.
+//! - A panic hook stores the panic message and location in NVS before the
+//!   default handler aborts. ESP-IDF also writes a core dump to the
+//!   `coredump` partition; its summary gives the crashing task and backtrace.
+//! - On the next boot an abnormal reset (panic, watchdog, brownout) is turned
+//!   into a pending report in NVS. It stays there until it has been uploaded
+//!   to `/api/devices/{id}/crash-reports`, so a report survives further
+//!   reboots while the backend is unreachable.
+
+use embedded_svc::http::client::Client as HttpClient;
+use esp_idf_svc::http::client::{Configuration as HttpConfig, EspHttpConnection};
+use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs};
+use esp_idf_sys::{
+    esp_core_dump_get_summary, esp_core_dump_image_erase, esp_core_dump_summary_t,
+    esp_mac_type_t_ESP_MAC_WIFI_STA, esp_read_mac, esp_reset_reason, esp_reset_reason_t,
+    esp_reset_reason_t_ESP_RST_BROWNOUT, esp_reset_reason_t_ESP_RST_INT_WDT,
+    esp_reset_reason_t_ESP_RST_PANIC, esp_reset_reason_t_ESP_RST_TASK_WDT,
+    esp_reset_reason_t_ESP_RST_WDT, esp_task_wdt_add, esp_task_wdt_config_t, esp_task_wdt_init,
+    esp_task_wdt_reconfigure, esp_task_wdt_reset, ESP_ERR_INVALID_STATE, ESP_OK,
+};
+use log::{info, warn};
+use serde::Serialize;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Main loop watchdog timeout. Generous because backend polling and OTA
+/// downloads run on the main task and can block for several seconds.
+const WATCHDOG_TIMEOUT_MS: u32 = 60_000;
+
+const NVS_NAMESPACE: &str = "crash";
+/// Panic message written by the panic hook
+const NVS_KEY_PANIC: &str = "panic";
+/// Unix time of the panic (only written once the clock has been set)
+const NVS_KEY_PANIC_TIME: &str = "panic_time";
+/// Report waiting to be uploaded (JSON)
+const NVS_KEY_REPORT: &str = "report";
+
+const MAX_PANIC_MESSAGE: usize = 512;
+/// Timestamps before this mean the clock was never set (2020-09-13)
+const MIN_VALID_UNIX_TIME: u64 = 1_600_000_000;
+const REPORT_BUF_SIZE: usize = 2048;
+const HTTP_TIMEOUT_MS: u64 = 5000;
+
+static NVS_PARTITION: Mutex<Option<EspDefaultNvsPartition>> = Mutex::new(None);
+static WATCHDOG_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Crash report as sent to the backend
+#[derive(Debug, Serialize)]
+struct CrashReport {
+    firmware_version: String,
+    reset_reason: String,
+    panic_message: Option<String>,
+    task: Option<String>,
+    backtrace: Option<String>,
+    crashed_at: Option<u64>,
+}
+
+/// Store the NVS partition, install the panic hook and capture a report
+/// for the previous run if it ended abnormally. Call early in boot.
+pub fn init(nvs: Option<EspDefaultNvsPartition>) {
+    *NVS_PARTITION.lock().unwrap() = nvs;
+    collect_crash_report();
+    install_panic_hook();
+}
+
+/// Start the task watchdog and subscribe the calling (main) task
+pub fn start_watchdog() {
+    let config = esp_task_wdt_config_t {
+        timeout_ms: WATCHDOG_TIMEOUT_MS,
+        idle_core_mask: 0,
+        trigger_panic: true,
+    };
+
+    let mut err = unsafe { esp_task_wdt_init(&config) };
+    if err == ESP_ERR_INVALID_STATE {
+        // Already initialized by ESP-IDF at startup
+        err = unsafe { esp_task_wdt_reconfigure(&config) };
+    }
+    if err != ESP_OK {
+        warn!("Task watchdog init failed: {}", err);
+        return;
+    }
+
+    let err = unsafe { esp_task_wdt_add(core::ptr::null_mut()) };
+    if err != ESP_OK {
+        warn!("Task watchdog subscribe failed: {}", err);
+        return;
+    }
+
+    WATCHDOG_ACTIVE.store(true, Ordering::Relaxed);
+    info!("Task watchdog started ({}s)", WATCHDOG_TIMEOUT_MS / 1000);
+}
+
+/// Reset the watchdog timer. Call from the main loop and from long-running
+/// work on the main task (OTA download and flashing).
+pub fn feed_watchdog() {
+    if WATCHDOG_ACTIVE.load(Ordering::Relaxed) {
+        unsafe {
+            esp_task_wdt_reset();
+        }
+    }
+}
+
+/// Device identifier used by the backend (WiFi MAC, lowercase hex)
+pub fn device_id() -> String {
+    let mut mac = [0u8; 6];
+    unsafe {
+        esp_read_mac(mac.as_mut_ptr(), esp_mac_type_t_ESP_MAC_WIFI_STA);
+    }
+    mac.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Upload the pending crash report, if any. Called once after WiFi connects;
+/// the report is only cleared once the backend has accepted it.
+pub fn upload_pending_report(base_url: &str) {
+    let Some(body) = read_nvs_str(NVS_KEY_REPORT) else {
+        return;
+    };
+
+    let url = format!("{}/api/devices/{}/crash-reports", base_url, device_id());
+    match post_json(&url, &body) {
+        Ok(status) if status == 200 || status == 201 => {
+            remove_nvs_key(NVS_KEY_REPORT);
+            info!("Crash report uploaded");
+        }
+        Ok(status) => warn!("Crash report upload failed with status {}", status),
+        Err(e) => warn!("Crash report upload failed: {}", e),
+    }
+}
+
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        // "panicked at src/file.rs:12:5:\nmessage"
+        let mut message = panic_info.to_string();
+        if message.len() > MAX_PANIC_MESSAGE {
+            let mut end = MAX_PANIC_MESSAGE;
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            message.truncate(end);
+        }
+
+        // Read the clock directly - the time manager's locks may be held
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        // try_lock: the panic may have happened while the partition was locked
+        if let Ok(guard) = NVS_PARTITION.try_lock() {
+            if let Some(partition) = guard.as_ref() {
+                if let Ok(nvs) = EspNvs::new(partition.clone(), NVS_NAMESPACE, true) {
+                    let _ = nvs.set_str(NVS_KEY_PANIC, &message);
+                    if now >= MIN_VALID_UNIX_TIME {
+                        let _ = nvs.set_u64(NVS_KEY_PANIC_TIME, now);
+                    }
+                }
+            }
+        }
+
+        default_hook(panic_info);
+    }));
+}
+
+fn reset_reason_name(reason: esp_reset_reason_t) -> Option<&'static str> {
+    match reason {
+        esp_reset_reason_t_ESP_RST_PANIC => Some("panic"),
+        esp_reset_reason_t_ESP_RST_TASK_WDT => Some("task_wdt"),
+        esp_reset_reason_t_ESP_RST_INT_WDT => Some("int_wdt"),
+        esp_reset_reason_t_ESP_RST_WDT => Some("wdt"),
+        esp_reset_reason_t_ESP_RST_BROWNOUT => Some("brownout"),
+        _ => None,
+    }
+}
+
+/// Turn an abnormal reset into a pending report
+fn collect_crash_report() {
+    // Always consume the panic message so a stale one isn't attached to a
+    // later, unrelated crash
+    let panic_message = read_nvs_str(NVS_KEY_PANIC);
+    let crashed_at = read_nvs_u64(NVS_KEY_PANIC_TIME);
+    if panic_message.is_some() {
+        remove_nvs_key(NVS_KEY_PANIC);
+        remove_nvs_key(NVS_KEY_PANIC_TIME);
+    }
+
+    let Some(reset_reason) = reset_reason_name(unsafe { esp_reset_reason() }) else {
+        return;
+    };
+
+    let (task, backtrace) = match core_dump_summary() {
+        Some((task, backtrace)) => (Some(task), Some(backtrace)),
+        None => (None, None),
+    };
+
+    warn!(
+        "Previous run ended with a {} reset (task: {}, panic: {})",
+        reset_reason,
+        task.as_deref().unwrap_or("?"),
+        panic_message.as_deref().unwrap_or("-")
+    );
+
+    let report = CrashReport {
+        firmware_version: crate::ota_manager::get_version().to_string(),
+        reset_reason: reset_reason.to_string(),
+        panic_message,
+        task,
+        backtrace,
+        crashed_at,
+    };
+
+    match serde_json::to_string(&report) {
+        Ok(json) => {
+            if !write_nvs_str(NVS_KEY_REPORT, &json) {
+                warn!("Failed to store crash report");
+            }
+        }
+        Err(e) => warn!("Failed to encode crash report: {:?}", e),
+    }
+}
+
+/// Crashing task and backtrace from the core dump partition, if one was
+/// written. The dump is erased afterwards so it isn't reported twice.
+fn core_dump_summary() -> Option<(String, String)> {
+    let mut summary: esp_core_dump_summary_t = unsafe { core::mem::zeroed() };
+    if unsafe { esp_core_dump_get_summary(&mut summary) } != ESP_OK {
+        return None;
+    }
+
+    let task = unsafe { CStr::from_ptr(summary.exc_task.as_ptr()) }
+        .to_string_lossy()
+        .into_owned();
+
+    let bt = &summary.exc_bt_info;
+    let depth = (bt.depth as usize).min(bt.bt.len());
+    let mut backtrace = bt.bt[..depth]
+        .iter()
+        .map(|pc| format!("0x{:08x}", pc))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if bt.corrupted {
+        backtrace.push_str(" |<-CORRUPTED");
+    }
+
+    unsafe {
+        esp_core_dump_image_erase();
+    }
+    Some((task, backtrace))
+}
+
+/// POST a JSON body, returning the HTTP status (also used for diagnostics)
+pub fn post_json(url: &str, body: &str) -> Result<u16, String> {
+    let config = HttpConfig {
+        timeout: Some(std::time::Duration::from_millis(HTTP_TIMEOUT_MS)),
+        ..Default::default()
+    };
+    let connection = EspHttpConnection::new(&config).map_err(|e| format!("{:?}", e))?;
+    let mut client = HttpClient::wrap(connection);
+
+    let content_length = body.len().to_string();
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Content-Length", content_length.as_str()),
+    ];
+    let mut request = client
+        .request(embedded_svc::http::Method::Post, url, &headers)
+        .map_err(|e| format!("{:?}", e))?;
+    request.write(body.as_bytes()).map_err(|e| format!("{:?}", e))?;
+    request.flush().map_err(|e| format!("{:?}", e))?;
+    let response = request.submit().map_err(|e| format!("{:?}", e))?;
+    Ok(response.status())
+}
+
+fn read_nvs_str(key: &str) -> Option<String> {
+    let guard = NVS_PARTITION.lock().unwrap();
+    let nvs = EspNvs::new(guard.as_ref()?.clone(), NVS_NAMESPACE, true).ok()?;
+    let mut buf = vec![0u8; REPORT_BUF_SIZE];
+    nvs.get_str(key, &mut buf).ok()?.map(|s| s.to_string())
+}
+
+fn read_nvs_u64(key: &str) -> Option<u64> {
+    let guard = NVS_PARTITION.lock().unwrap();
+    let nvs = EspNvs::new(guard.as_ref()?.clone(), NVS_NAMESPACE, true).ok()?;
+    nvs.get_u64(key).ok()?
+}
+
+fn write_nvs_str(key: &str, value: &str) -> bool {
+    let guard = NVS_PARTITION.lock().unwrap();
+    let Some(partition) = guard.as_ref() else {
+        return false;
+    };
+    match EspNvs::new(partition.clone(), NVS_NAMESPACE, true) {
+        Ok(nvs) => nvs.set_str(key, value).is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn remove_nvs_key(key: &str) {
+    let guard = NVS_PARTITION.lock().unwrap();
+    if let Some(partition) = guard.as_ref() {
+        if let Ok(nvs) = EspNvs::new(partition.clone(), NVS_NAMESPACE, true) {
+            let _ = nvs.remove(key);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Device diagnostics for remote support
+//!
+//! Collects memory, WiFi, I2C, NFC bridge, scale and render loop figures
+//! into a `spoolbuddy_core::proto::DeviceDiagnostics`. The same snapshot is
+//! shown on the diagnostics screen (through [`diagnostics_get`]) and
+//! uploaded to `/api/devices/{id}/diagnostics` about once a minute, so
+//! support can see what the device saw without a serial console.
+
+use esp_idf_sys::{
+    esp_timer_get_time, heap_caps_get_free_size, heap_caps_get_minimum_free_size,
+    heap_caps_get_total_size, MALLOC_CAP_INTERNAL, MALLOC_CAP_SPIRAM,
+};
+use log::{debug, warn};
+use spoolbuddy_core::proto::{DeviceDiagnostics, I2cCounters, MemoryUsage};
+use std::ffi::c_char;
+
+use crate::shared_i2c::{self, BusClient};
+
+/// Render loop statistics (mirrors `display_frame_stats_t`)
+#[repr(C)]
+#[derive(Default)]
+struct DisplayFrameStats {
+    frames: u32,
+    fps_x10: u32,
+    render_us: u32,
+    max_render_us: u32,
+    panel_us: u32,
+}
+
+extern "C" {
+    fn display_get_frame_stats(stats: *mut DisplayFrameStats);
+}
+
+/// Diagnostics for the C UI (mirrors `DeviceDiagnosticsC` in ui_internal.h)
+#[repr(C)]
+pub struct DeviceDiagnosticsC {
+    pub uptime_s: u32,
+    pub heap_free: u32,
+    pub heap_total: u32,
+    pub heap_min_free: u32,
+    /// 0 on boards without PSRAM
+    pub psram_free: u32,
+    pub psram_total: u32,
+    /// 0 while WiFi is disconnected
+    pub wifi_rssi: i8,
+    pub i2c_scale_errors: u32,
+    pub i2c_scale_timeouts: u32,
+    pub i2c_nfc_errors: u32,
+    pub i2c_nfc_timeouts: u32,
+    /// "major.minor", empty without a Pico bridge
+    pub nfc_bridge_firmware: [c_char; 8],
+    pub scale_channels: u8,
+    pub scale_raw: [i32; 2],
+    pub fps_x10: u32,
+}
+
+fn memory_usage(caps: u32) -> MemoryUsage {
+    unsafe {
+        MemoryUsage {
+            free: heap_caps_get_free_size(caps) as u32,
+            total: heap_caps_get_total_size(caps) as u32,
+            min_free: heap_caps_get_minimum_free_size(caps) as u32,
+        }
+    }
+}
+
+/// Take a diagnostics snapshot
+pub fn collect() -> DeviceDiagnostics {
+    let psram = memory_usage(MALLOC_CAP_SPIRAM);
+    let rssi = crate::wifi_manager::wifi_get_rssi();
+
+    let i2c = shared_i2c::ALL_CLIENTS
+        .iter()
+        .filter(|&&client| client != BusClient::Touch)
+        .map(|&client| {
+            let stats = shared_i2c::bus_stats(client);
+            I2cCounters {
+                client: client.name().to_string(),
+                errors: stats.errors,
+                timeouts: stats.timeouts,
+            }
+        })
+        .collect();
+
+    let bridge = crate::nfc_bridge_manager::bridge_report();
+
+    let mut frame_stats = DisplayFrameStats::default();
+    unsafe { display_get_frame_stats(&mut frame_stats) };
+
+    DeviceDiagnostics {
+        firmware_version: crate::ota_manager::get_version().to_string(),
+        uptime_s: (unsafe { esp_timer_get_time() } / 1_000_000) as u64,
+        heap: memory_usage(MALLOC_CAP_INTERNAL),
+        psram: (psram.total > 0).then_some(psram),
+        wifi_rssi: (rssi != 0).then_some(rssi),
+        i2c,
+        nfc_bridge_firmware: bridge.map(|r| format!("{}.{}", r.firmware_major, r.firmware_minor)),
+        nfc_bridge_status: bridge.map(|r| r.status.as_str().to_string()),
+        scale_raw: crate::scale_manager::raw_readings(),
+        fps: frame_stats.fps_x10 as f32 / 10.0,
+    }
+}
+
+/// Upload a snapshot to the backend
+pub fn upload(base_url: &str) {
+    let body = match serde_json::to_string(&collect()) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to encode diagnostics: {:?}", e);
+            return;
+        }
+    };
+
+    let url = format!("{}/api/devices/{}/diagnostics", base_url, crate::crash_reporter::device_id());
+    match crate::crash_reporter::post_json(&url, &body) {
+        Ok(status) if (200..300).contains(&status) => debug!("Diagnostics uploaded"),
+        Ok(status) => warn!("Diagnostics upload failed with status {}", status),
+        Err(e) => warn!("Diagnostics upload failed: {}", e),
+    }
+}
+
+// =============================================================================
+// C-callable FFI functions
+// =============================================================================
+
+/// Fill `out` with a diagnostics snapshot
+#[no_mangle]
+pub extern "C" fn diagnostics_get(out: *mut DeviceDiagnosticsC) {
+    if out.is_null() {
+        return;
+    }
+
+    let d = collect();
+    let i2c = |client: &str| d.i2c.iter().find(|c| c.client == client).cloned().unwrap_or_default();
+    let (scale, nfc) = (i2c("scale"), i2c("nfc"));
+    let psram = d.psram.unwrap_or_default();
+
+    let mut firmware = [0 as c_char; 8];
+    if let Some(version) = &d.nfc_bridge_firmware {
+        for (dst, &src) in firmware.iter_mut().zip(version.as_bytes().iter().take(7)) {
+            *dst = src as c_char;
+        }
+    }
+
+    let mut scale_raw = [0i32; 2];
+    for (dst, &src) in scale_raw.iter_mut().zip(&d.scale_raw) {
+        *dst = src;
+    }
+
+    let out = unsafe { &mut *out };
+    *out = DeviceDiagnosticsC {
+        uptime_s: d.uptime_s as u32,
+        heap_free: d.heap.free,
+        heap_total: d.heap.total,
+        heap_min_free: d.heap.min_free,
+        psram_free: psram.free,
+        psram_total: psram.total,
+        wifi_rssi: d.wifi_rssi.unwrap_or(0),
+        i2c_scale_errors: scale.errors,
+        i2c_scale_timeouts: scale.timeouts,
+        i2c_nfc_errors: nfc.errors,
+        i2c_nfc_timeouts: nfc.timeouts,
+        nfc_bridge_firmware: firmware,
+        scale_channels: d.scale_raw.len().min(scale_raw.len()) as u8,
+        scale_raw,
+        fps_x10: (d.fps * 10.0) as u32,
+    };
+}
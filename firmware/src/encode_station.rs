@@ -0,0 +1,175 @@
+//! Batch tag encoding on the device
+//!
+//! While a tag_batch job runs on the backend, every device state reply
+//! carries an [`EncodeTask`]: which spool's tag to place next and, once the
+//! backend has seen the tag on the reader, the bytes to write to it. This
+//! module writes them (verified by reading them back), posts the outcome to
+//! the backend and keeps the screen state for the encoding overlay.
+//!
+//! Each placement of a tag is written once; after a failure the backend
+//! asks for the tag again and it has to be lifted and put back.
+
+use log::{info, warn};
+use std::ffi::c_int;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use spoolbuddy_core::encode::{self, WriteOutcome};
+use spoolbuddy_core::proto::{EncodeResult, EncodeTask};
+
+use crate::nfc::transport::READER_BUSY;
+
+/// How often to send the tag on the reader while the task isn't built for it
+const FETCH_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Current task of the running batch, None while idle
+static TASK: Mutex<Option<EncodeTask>> = Mutex::new(None);
+
+/// (tag UID, batch index, job id) already written during this placement
+static ATTEMPTED: Mutex<Option<(String, u32, String)>> = Mutex::new(None);
+
+/// Last time the task was fetched for the tag on the reader
+static LAST_FETCH: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Take the task from a device state reply
+pub fn on_reply(task: Option<EncodeTask>) {
+    let mut current = TASK.lock().unwrap();
+    let Some(task) = task else {
+        if current.take().is_some() {
+            info!("Tag batch finished");
+        }
+        return;
+    };
+
+    // Weight-only updates don't name the tag, so their task carries no data.
+    // Keep the one built for the tag still on the reader.
+    if let Some(ref old) = *current {
+        let same_step = old.job_id == task.job_id && old.index == task.index;
+        if same_step && task.tag_uid.is_none() && old.tag_uid.is_some()
+            && old.tag_uid == crate::nfc_bridge_manager::tag_uid_hex()
+        {
+            return;
+        }
+    }
+    *current = Some(task);
+}
+
+/// Write the current task to the tag on the reader (call from main loop)
+pub fn poll() {
+    let Some(task) = TASK.lock().unwrap().clone() else {
+        return;
+    };
+    let Some(uid) = crate::nfc_bridge_manager::tag_uid_hex() else {
+        // Tag lifted, the next placement may be written again
+        *ATTEMPTED.lock().unwrap() = None;
+        return;
+    };
+
+    if task.tag_uid.as_deref() != Some(uid.as_str()) {
+        // Ask the backend for data built for this tag
+        let mut last = LAST_FETCH.lock().unwrap();
+        if last.is_some_and(|t| t.elapsed() < FETCH_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
+        drop(last);
+        let weight = crate::scale_manager::scale_get_weight();
+        let stable = crate::scale_manager::scale_is_stable();
+        crate::backend_client::send_device_state(Some(&uid), weight, stable);
+        return;
+    }
+
+    let Some(ref hex) = task.data else {
+        return;
+    };
+    let placement = (uid.clone(), task.index, task.job_id.clone());
+    {
+        let mut attempted = ATTEMPTED.lock().unwrap();
+        if attempted.as_ref() == Some(&placement) {
+            return;
+        }
+        *attempted = Some(placement);
+    }
+
+    let mut result = EncodeResult {
+        job_id: task.job_id.clone(),
+        index: task.index,
+        tag_uid: uid,
+        ..Default::default()
+    };
+    let written = match encode::from_hex(hex) {
+        Some(data) => crate::nfc_bridge_manager::write_ntag(&data),
+        None => Err("Malformed tag data"),
+    };
+    match written {
+        Ok(report) => {
+            result.outcome = report.outcome.as_str().to_string();
+            result.pages_written = report.pages_written;
+            result.retries = report.retries;
+            result.failed_page = report.failed_page;
+            result.rf_signal = report.rf_signal;
+        }
+        Err(e) if e == READER_BUSY => {
+            // Try again on the next poll
+            *ATTEMPTED.lock().unwrap() = None;
+            return;
+        }
+        Err(e) => {
+            warn!("Tag write failed: {}", e);
+            result.outcome = WriteOutcome::WriteFailed.as_str().to_string();
+        }
+    }
+
+    info!(
+        "Tag {} of {}: {} ({} retries)",
+        task.index, task.total, result.outcome, result.retries
+    );
+    crate::buzzer::play(if result.outcome == WriteOutcome::Verified.as_str() {
+        crate::buzzer::Pattern::TagDetected
+    } else {
+        crate::buzzer::Pattern::Error
+    });
+
+    if let Some(next) = crate::backend_client::post_encode_result(&result) {
+        on_reply(next);
+    }
+}
+
+/// C-compatible encoding screen state
+#[repr(C)]
+pub struct EncodeStateC {
+    pub active: bool,      // True while a batch encoding job is running
+    pub index: c_int,      // 1-based position of the tag in the batch
+    pub total: c_int,      // Tags in the batch
+    pub label: [u8; 48],   // Spool to encode, e.g. "Red PETG"
+    pub message: [u8; 96], // Prompt or result of the last attempt
+    pub writing: bool,     // Data for the tag on the reader is ready to write
+}
+
+/// Get the encoding screen state, false unless a batch encoding job is running
+#[no_mangle]
+pub extern "C" fn encode_station_get_state(state: *mut EncodeStateC) -> bool {
+    if state.is_null() {
+        return false;
+    }
+
+    let task = TASK.lock().unwrap();
+    let state = unsafe { &mut *state };
+    *state = EncodeStateC {
+        active: task.is_some(),
+        index: 0,
+        total: 0,
+        label: [0; 48],
+        message: [0; 96],
+        writing: false,
+    };
+
+    if let Some(ref task) = *task {
+        state.index = task.index as c_int;
+        state.total = task.total as c_int;
+        crate::backend_client::copy_to_c_buf(&task.label, &mut state.label);
+        crate::backend_client::copy_to_c_buf(&task.message, &mut state.message);
+        state.writing = task.data.is_some();
+    }
+    state.active
+}
@@ -0,0 +1,49 @@
+//! CH422G IO expander (Waveshare boards)
+//!
+//! The expander sits on the touch I2C bus (I2C0, driven by the C display
+//! driver). It has no register map: each I2C address is a command, and the
+//! IO0-7 levels are always written as one byte. The levels are kept here so
+//! each user (SD card chip select, Pico reset) only changes its own pin.
+//!
+//! Boards without the CH422G (e.g. the CrowPanel) don't answer on the
+//! command addresses, so every write returns false there.
+
+use esp_idf_sys::{i2c_master_write_to_device, ESP_OK};
+use std::sync::Mutex;
+
+const TOUCH_I2C_PORT: i32 = 0;
+/// System parameter command (bit 0 = IO0-7 push-pull outputs)
+const CH422G_CMD_SET: u8 = 0x24;
+/// IO0-7 output level command
+const CH422G_CMD_IO: u8 = 0x38;
+const CH422G_IO_OE: u8 = 0x01;
+/// 100ms at the default 100Hz FreeRTOS tick
+const CH422G_TIMEOUT_TICKS: u32 = 10;
+
+/// SD card chip select (active low)
+pub const EXIO_SD_CS: u8 = 4;
+/// Spare output wired to the Pico's RUN pin (active low reset)
+pub const EXIO_PICO_RESET: u8 = 0;
+
+/// Current IO0-7 levels. The display driver drives all of them high at
+/// init, which keeps TP_RST, LCD_BL and LCD_RST (EXIO1-3) released.
+static OUTPUTS: Mutex<u8> = Mutex::new(0xFF);
+
+/// Drive one expander output. Returns false if there's no CH422G.
+pub fn set_output(exio: u8, high: bool) -> bool {
+    let mut outputs = OUTPUTS.lock().unwrap();
+    let levels = if high { *outputs | (1 << exio) } else { *outputs & !(1 << exio) };
+    let ok = write(CH422G_CMD_SET, CH422G_IO_OE) && write(CH422G_CMD_IO, levels);
+    if ok {
+        *outputs = levels;
+    }
+    ok
+}
+
+fn write(command: u8, value: u8) -> bool {
+    let buf = [value];
+    let err = unsafe {
+        i2c_master_write_to_device(TOUCH_I2C_PORT, command, buf.as_ptr(), buf.len(), CH422G_TIMEOUT_TICKS)
+    };
+    err == ESP_OK
+}
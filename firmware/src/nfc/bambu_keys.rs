@@ -0,0 +1,197 @@
+//! Bambu Lab RFID key derivation.
+//!
+//! Bambu Lab spool tags are MIFARE Classic 1K cards where every sector is
+//! protected by its own Crypto-1 key. The keys are derived from the tag UID
+//! with HKDF-SHA256 (RFC 5869):
+//!
+//! ```text
+//! PRK = HMAC-SHA256(salt = BAMBU_MASTER_SALT, ikm = uid)
+//! OKM = HKDF-Expand(PRK, info = "RFID-A\0", length = 16 * 6)
+//! key(sector) = OKM[sector * 6 .. sector * 6 + 6]
+//! ```
+//!
+//! Only key A is needed for reading. The derivation is identical to the one in
+//! the Pico bridge firmware, so the ESP32 can authenticate directly when the
+//! PN5180 is wired to it over SPI.
+//!
+//! SHA-256 is implemented here to avoid pulling a crypto crate into the
+//! firmware for 96 bytes of key material.
+
+/// HKDF salt used by Bambu Lab for tag key derivation
+pub const BAMBU_MASTER_SALT: [u8; 16] = [
+    0x9a, 0x75, 0x9c, 0xf2, 0xc4, 0xf7, 0xca, 0xff,
+    0x22, 0x2c, 0xb9, 0x76, 0x9b, 0x41, 0xbc, 0x96,
+];
+
+/// HKDF info for key A (includes the trailing NUL, as on the tag reader)
+pub const BAMBU_KEY_A_INFO: &[u8] = b"RFID-A\0";
+
+/// Number of sectors on a MIFARE Classic 1K tag
+pub const MIFARE_1K_SECTORS: usize = 16;
+
+/// MIFARE Crypto-1 key length
+pub const MIFARE_KEY_LEN: usize = 6;
+
+/// Per-sector key A for a Bambu Lab tag
+pub type SectorKeys = [[u8; MIFARE_KEY_LEN]; MIFARE_1K_SECTORS];
+
+/// Derive the per-sector key A for a Bambu Lab tag from its UID.
+pub fn derive_sector_keys(uid: &[u8]) -> SectorKeys {
+    let mut okm = [0u8; MIFARE_1K_SECTORS * MIFARE_KEY_LEN];
+    hkdf_sha256(&BAMBU_MASTER_SALT, uid, BAMBU_KEY_A_INFO, &mut okm);
+
+    let mut keys = [[0u8; MIFARE_KEY_LEN]; MIFARE_1K_SECTORS];
+    for (sector, key) in keys.iter_mut().enumerate() {
+        key.copy_from_slice(&okm[sector * MIFARE_KEY_LEN..(sector + 1) * MIFARE_KEY_LEN]);
+    }
+    keys
+}
+
+/// HKDF-SHA256 extract + expand into `okm` (max 255 * 32 bytes)
+fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8], okm: &mut [u8]) {
+    let prk = hmac_sha256(salt, &[ikm]);
+
+    let mut t: [u8; 32] = [0; 32];
+    let mut t_len = 0;
+    for (i, chunk) in okm.chunks_mut(32).enumerate() {
+        let counter = [(i + 1) as u8];
+        t = hmac_sha256(&prk, &[&t[..t_len], info, &counter]);
+        t_len = 32;
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+}
+
+/// HMAC-SHA256 over the concatenation of `parts`
+fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut key_block = [0u8; 64];
+    if key.len() > 64 {
+        key_block[..32].copy_from_slice(&Sha256::digest(&[key]));
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut ipad = [0x36u8; 64];
+    let mut opad = [0x5cu8; 64];
+    for i in 0..64 {
+        ipad[i] ^= key_block[i];
+        opad[i] ^= key_block[i];
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(&ipad);
+    for part in parts {
+        inner.update(part);
+    }
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(&opad);
+    outer.update(&inner_hash);
+    outer.finalize()
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Minimal streaming SHA-256
+struct Sha256 {
+    state: [u32; 8],
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+impl Sha256 {
+    fn new() -> Self {
+        Self {
+            state: [
+                0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+                0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+            ],
+            buf: [0; 64],
+            buf_len: 0,
+            total_len: 0,
+        }
+    }
+
+    fn digest(parts: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hasher.finalize()
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+        while !data.is_empty() {
+            let take = (64 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len == 64 {
+                let block = self.buf;
+                self.compress(&block);
+                self.buf_len = 0;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> [u8; 32] {
+        let bit_len = self.total_len * 8;
+        self.update(&[0x80]);
+        while self.buf_len != 56 {
+            self.update(&[0x00]);
+        }
+        self.update(&bit_len.to_be_bytes());
+
+        let mut out = [0u8; 32];
+        for (i, word) in self.state.iter().enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut w = [0u32; 64];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes([block[i * 4], block[i * 4 + 1], block[i * 4 + 2], block[i * 4 + 3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (s, v) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+}
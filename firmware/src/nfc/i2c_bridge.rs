@@ -3,17 +3,46 @@
 //! Communicates with the Pico NFC bridge over I2C.
 //! The Pico handles PN5180 SPI communication and exposes a simple I2C interface.
 //!
-//! I2C Protocol:
+//! I2C Protocol (v2, request/poll):
 //! - Address: 0x55
+//! - The ESP32 writes a command, then polls with reads. While the Pico is
+//!   still working every read returns `STATUS_BUSY` (0xFE), so nothing has to
+//!   sleep for a guessed processing time.
 //! - Commands:
 //!   - 0x00: Get status (returns 2 bytes: status, tag_present)
 //!   - 0x01: Get version (returns 3 bytes: status, major, minor)
 //!   - 0x10: Scan tag (returns: status, uid_len, uid[0..uid_len])
+//!   - 0x11: Get cached UID from the Pico's background scan (same format as 0x10)
 //!   - 0x20: Read tag data (returns: status, tag_type, uid_len, uid, block_data...)
+//!   - 0x30: Set MIFARE key slot (args: slot, key_type, key[6]; returns: status)
+//!   - 0x31: Authenticate MIFARE sector (args: block, slot; returns: status)
+//!   - 0x32: Read MIFARE block (args: block; returns: status, data[16])
+//!   - 0x33: Write MIFARE block (args: block, data[16]; returns: status)
+//!   - 0x34: Read NTAG pages (args: page; returns: status, data[16] = 4 pages)
+//!   - 0x35: Write NTAG page (args: page, data[4]; returns: status)
+//!   - 0x36: RF status (returns: status, agc[2] little-endian)
+//!
+//! Key slots let third-party MIFARE formats be read and written with their
+//! own keys without new Pico firmware. Slot `KEY_SLOT_BAMBU` uses the key
+//! derived from the tag UID for the block's sector.
+//!
+//! Tag detection is driven by [`poll_bridge`], a non-blocking state machine
+//! that does at most one short I2C transaction per call, so it can run from
+//! the main loop every few milliseconds without stalling the UI.
+//! [`PicoBridge`] exposes it as the core crate's [`Nfc`] reader; tag
+//! formats are decoded by the registry in `spoolbuddy_core::decode`.
 
 use esp_idf_hal::i2c::I2cDriver;
 use log::{debug, info, warn};
+use spoolbuddy_core::bridge_health::{BridgeHealth, BridgeReport, BridgeStatus};
+use spoolbuddy_core::decode::{self, RawTag};
+use spoolbuddy_core::hal::{Nfc, TagMemory};
+use spoolbuddy_core::tag::{self, MAX_UID_LEN};
+
+use super::pn5180::{MifareKeyType, MIFARE_BLOCK_SIZE};
+use crate::io_expander;
 use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::{Duration, Instant};
 
 /// I2C address of the Pico NFC bridge
 pub const PICO_NFC_ADDR: u8 = 0x55;
@@ -29,25 +58,63 @@ fn next_seq() -> u8 {
 #[allow(dead_code)]
 const CMD_GET_STATUS: u8 = 0x00;
 const CMD_GET_VERSION: u8 = 0x01;
+#[allow(dead_code)]
 const CMD_SCAN_TAG: u8 = 0x10;
+const CMD_GET_UID: u8 = 0x11;
 const CMD_READ_TAG_DATA: u8 = 0x20;
+const CMD_SET_KEY: u8 = 0x30;
+const CMD_MFC_AUTH: u8 = 0x31;
+const CMD_MFC_READ: u8 = 0x32;
+const CMD_MFC_WRITE: u8 = 0x33;
+const CMD_NTAG_READ: u8 = 0x34;
+const CMD_NTAG_WRITE: u8 = 0x35;
+const CMD_RF_STATUS: u8 = 0x36;
+
+/// Number of user key slots on the Pico
+pub const MAX_KEY_SLOTS: u8 = 8;
+
+/// Key slot that selects the UID-derived Bambu Lab key
+#[allow(dead_code)]
+pub const KEY_SLOT_BAMBU: u8 = 0xFF;
+
+/// Response status codes for MIFARE commands
+const STATUS_OK: u8 = 0;
+const STATUS_NO_TAG: u8 = 1;
+const STATUS_READ_ERROR: u8 = 2;
+const STATUS_UNSUPPORTED_TAG: u8 = 3;
+const STATUS_AUTH_FAILED: u8 = 4;
+const STATUS_INVALID_ARG: u8 = 5;
+const STATUS_NOT_AUTHENTICATED: u8 = 6;
+const STATUS_WRITE_ERROR: u8 = 7;
+/// Returned by every read until the queued command has been processed
+const STATUS_BUSY: u8 = 0xFE;
+
+/// How often to ask the Pico for the cached UID
+const UID_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Delay between busy polls in blocking helpers
+const BUSY_POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// Give up on a command that is still busy after this long
+const UID_TIMEOUT: Duration = Duration::from_millis(300);
+const TAG_DATA_TIMEOUT: Duration = Duration::from_millis(1500);
+/// How long to hold the Pico's RUN pin low
+const RESET_PULSE: Duration = Duration::from_millis(10);
 
 /// Tag types (matches Pico definitions)
-pub const TAG_TYPE_UNKNOWN: u8 = 0;
-pub const TAG_TYPE_NTAG: u8 = 1;
-pub const TAG_TYPE_MIFARE_1K: u8 = 2;
-pub const TAG_TYPE_MIFARE_4K: u8 = 3;
-
-/// Decoded tag data from Bambu/NTAG tags
-#[derive(Debug, Clone, Default)]
-pub struct DecodedTagInfo {
-    pub vendor: String,
-    pub material: String,
-    pub material_subtype: String,
-    pub color_name: String,
-    pub color_rgba: u32,
-    pub spool_weight: i32,
-    pub tag_type_name: String,
+pub use spoolbuddy_core::tag::{TAG_TYPE_MIFARE_1K, TAG_TYPE_MIFARE_4K, TAG_TYPE_NTAG, TAG_TYPE_UNKNOWN};
+
+pub use spoolbuddy_core::tag::{DecodedTagInfo, TagEvent};
+
+/// Command currently in flight on the bridge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BridgePhase {
+    /// Nothing pending
+    Idle,
+    /// CMD_GET_UID sent, waiting for the response
+    Uid { seq: u8, sent: Instant },
+    /// CMD_READ_TAG_DATA sent, waiting for the response
+    TagData { seq: u8, sent: Instant },
+    /// CMD_GET_VERSION heartbeat sent, waiting for the response
+    Version { seq: u8, sent: Instant },
 }
 
 /// NFC Bridge state
@@ -56,10 +123,17 @@ pub struct NfcBridgeState {
     pub initialized: bool,
     pub firmware_version: (u8, u8),  // major, minor
     pub tag_present: bool,
-    pub tag_uid: [u8; 10],
+    pub tag_uid: [u8; MAX_UID_LEN],
     pub tag_uid_len: u8,
     pub tag_type: u8,
     pub decoded_info: Option<DecodedTagInfo>,
+    pub health: BridgeHealth,
+    /// Tag data has been read (or failed) for the current tag
+    tag_data_done: bool,
+    phase: BridgePhase,
+    last_uid_poll: Option<Instant>,
+    /// Clock origin for `health`
+    started: Instant,
 }
 
 impl NfcBridgeState {
@@ -68,12 +142,66 @@ impl NfcBridgeState {
             initialized: false,
             firmware_version: (0, 0),
             tag_present: false,
-            tag_uid: [0; 10],
+            tag_uid: [0; MAX_UID_LEN],
             tag_uid_len: 0,
             tag_type: TAG_TYPE_UNKNOWN,
             decoded_info: None,
+            health: BridgeHealth::new(0),
+            tag_data_done: false,
+            phase: BridgePhase::Idle,
+            last_uid_poll: None,
+            started: Instant::now(),
         }
     }
+
+    fn now_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    /// No command in flight, so a blocking helper can use the bus
+    pub fn is_idle(&self) -> bool {
+        self.phase == BridgePhase::Idle
+    }
+
+    /// Bridge health snapshot for status reports and the UI
+    pub fn report(&self) -> BridgeReport {
+        self.health.report(self.now_ms(), self.firmware_version)
+    }
+
+    fn clear_tag(&mut self) {
+        self.tag_present = false;
+        self.tag_uid_len = 0;
+        self.tag_type = TAG_TYPE_UNKNOWN;
+        self.decoded_info = None;
+        self.tag_data_done = false;
+    }
+
+    /// UID of the tag on the reader
+    pub fn uid(&self) -> Option<&[u8]> {
+        (self.tag_present && self.tag_uid_len > 0).then(|| &self.tag_uid[..self.tag_uid_len as usize])
+    }
+}
+
+/// The bridge as the core crate's [`Nfc`] reader
+pub struct PicoBridge<'a, 'd> {
+    pub i2c: &'a mut I2cDriver<'d>,
+    pub state: &'a mut NfcBridgeState,
+}
+
+impl Nfc for PicoBridge<'_, '_> {
+    type Error = &'static str;
+
+    fn poll(&mut self) -> Result<Option<TagEvent>, &'static str> {
+        poll_bridge(self.i2c, self.state)
+    }
+
+    fn uid(&self) -> Option<&[u8]> {
+        self.state.uid()
+    }
+
+    fn tag_info(&self) -> Option<&DecodedTagInfo> {
+        self.state.decoded_info.as_ref()
+    }
 }
 
 /// Initialize the NFC I2C bridge
@@ -101,6 +229,7 @@ pub fn init_bridge(i2c: &mut I2cDriver<'_>, state: &mut NfcBridgeState) -> Resul
     }
 
     state.initialized = true;
+    state.health = BridgeHealth::new(state.now_ms());
     info!("=== NFC I2C BRIDGE READY ===");
     Ok(())
 }
@@ -113,14 +242,9 @@ pub fn get_version(i2c: &mut I2cDriver<'_>) -> Result<(u8, u8), &'static str> {
         return Err("I2C write failed");
     }
 
-    // Small delay for Pico to process
-    std::thread::sleep(std::time::Duration::from_millis(10));
-
     // Read response: [status, major, minor]
     let mut resp = [0u8; 3];
-    if i2c.read(PICO_NFC_ADDR, &mut resp, 100).is_err() {
-        return Err("I2C read failed");
-    }
+    wait_response(i2c, &mut resp, UID_TIMEOUT)?;
 
     if resp[0] != 0 {
         return Err("Command failed");
@@ -129,96 +253,220 @@ pub fn get_version(i2c: &mut I2cDriver<'_>) -> Result<(u8, u8), &'static str> {
     Ok((resp[1], resp[2]))
 }
 
-/// Scan for a tag
-pub fn scan_tag(i2c: &mut I2cDriver<'_>, state: &mut NfcBridgeState) -> Result<bool, &'static str> {
-    let seq = next_seq();
+/// Blocking read of a command response, polling while the Pico reports busy
+///
+/// Only used for rare, user-triggered commands; tag detection goes through
+/// [`poll_bridge`].
+fn wait_response(i2c: &mut I2cDriver<'_>, resp: &mut [u8], timeout: Duration) -> Result<(), &'static str> {
+    let start = Instant::now();
+    loop {
+        if i2c.read(PICO_NFC_ADDR, resp, 100).is_err() {
+            return Err("I2C read failed");
+        }
+        if resp[0] != STATUS_BUSY {
+            return Ok(());
+        }
+        if start.elapsed() > timeout {
+            return Err("Bridge timeout");
+        }
+        std::thread::sleep(BUSY_POLL_INTERVAL);
+    }
+}
 
-    // Send scan command with sequence number
-    info!("[#{}] TX: SCAN_TAG", seq);
-    let cmd = [CMD_SCAN_TAG, seq];
-    if i2c.write(PICO_NFC_ADDR, &cmd, 100).is_err() {
-        warn!("[#{}] I2C write failed", seq);
+/// Send a command without waiting for the response
+fn send_command(i2c: &mut I2cDriver<'_>, cmd: &[u8]) -> Result<(), &'static str> {
+    if i2c.write(PICO_NFC_ADDR, cmd, 100).is_err() {
+        warn!("[#{}] I2C write failed", cmd[1]);
         return Err("I2C write failed");
     }
+    Ok(())
+}
 
-    // Wait for scan to complete (Pico needs time to do RF communication)
-    // Hard reset can take 300-500ms, so wait longer
-    std::thread::sleep(std::time::Duration::from_millis(500));
-    info!("[#{}] RX: reading response", seq);
-
-    // Read response: [status, uid_len, uid...]
-    let mut resp = [0u8; 12];  // Max: status + len + 10 UID bytes
-    if i2c.read(PICO_NFC_ADDR, &mut resp, 100).is_err() {
-        warn!("[#{}] I2C read failed", seq);
-        return Err("I2C read failed");
+/// Advance the bridge state machine (call frequently from the main loop)
+///
+/// Each call does at most one I2C transaction: it either queues a command
+/// or polls for the response to the one in flight. Tag presence comes from
+/// the Pico's cached background scan, so a tag is reported within a couple
+/// of Pico scan cycles. Once a tag is seen its data is read and decoded.
+///
+/// A version request goes out every few seconds as a heartbeat. When the
+/// bridge stops answering it is reset, and a tag on the reader is reported
+/// as removed (the Pico rescans it after boot).
+pub fn poll_bridge(i2c: &mut I2cDriver<'_>, state: &mut NfcBridgeState) -> Result<Option<TagEvent>, &'static str> {
+    let now = state.now_ms();
+    if state.health.needs_reset(now) {
+        return Ok(reset_bridge(state, now));
     }
-
-    if resp[0] != 0 {
-        // No tag or error
-        info!("[#{}] No tag (status={})", seq, resp[0]);
-        state.tag_present = false;
-        state.tag_uid_len = 0;
-        state.decoded_info = None;
-        return Ok(false);
+    if state.health.status(now) == BridgeStatus::Recovering {
+        return Ok(None);
     }
 
-    let uid_len = resp[1];
-    if uid_len > 0 && uid_len <= 10 {
-        state.tag_present = true;
-        state.tag_uid_len = uid_len;
-        state.tag_uid[..uid_len as usize].copy_from_slice(&resp[2..2 + uid_len as usize]);
+    match state.phase {
+        BridgePhase::Idle => {
+            if state.tag_present && !state.tag_data_done {
+                let seq = next_seq();
+                info!("[#{}] TX: READ_TAG_DATA", seq);
+                state.tag_data_done = true; // Don't keep retrying on error
+                send_command(i2c, &[CMD_READ_TAG_DATA, seq]).inspect_err(|_| state.health.record_failure())?;
+                state.phase = BridgePhase::TagData { seq, sent: Instant::now() };
+                return Ok(None);
+            }
+
+            if state.health.heartbeat_due(now) {
+                state.health.heartbeat_sent(now);
+                let seq = next_seq();
+                send_command(i2c, &[CMD_GET_VERSION, seq]).inspect_err(|_| state.health.record_failure())?;
+                state.phase = BridgePhase::Version { seq, sent: Instant::now() };
+                return Ok(None);
+            }
+
+            if state.last_uid_poll.is_some_and(|t| t.elapsed() < UID_POLL_INTERVAL) {
+                return Ok(None);
+            }
+            state.last_uid_poll = Some(Instant::now());
+            let seq = next_seq();
+            send_command(i2c, &[CMD_GET_UID, seq]).inspect_err(|_| state.health.record_failure())?;
+            state.phase = BridgePhase::Uid { seq, sent: Instant::now() };
+            Ok(None)
+        }
+        BridgePhase::Uid { seq, sent } => {
+            // Max: status + len + 10 UID bytes
+            let mut resp = [0u8; 12];
+            if !read_ready(i2c, state, seq, sent, UID_TIMEOUT, &mut resp)? {
+                return Ok(None);
+            }
+            Ok(handle_uid_response(state, seq, &resp))
+        }
+        BridgePhase::TagData { seq, sent } => {
+            // Response format:
+            // [0] = status (0 = success, 1 = no tag, 2 = read error, 3 = unknown type)
+            // [1] = tag_type
+            // [2] = uid_len
+            // [3..3+uid_len] = uid
+            // For MIFARE: blocks 1, 2, 4, 5 (64 bytes)
+            // For NTAG: pages 4-20 (68 bytes)
+            let mut resp = [0u8; 100];
+            if !read_ready(i2c, state, seq, sent, TAG_DATA_TIMEOUT, &mut resp)? {
+                return Ok(None);
+            }
+            Ok(handle_tag_data_response(state, seq, &resp))
+        }
+        BridgePhase::Version { seq, sent } => {
+            // [status, major, minor]
+            let mut resp = [0u8; 3];
+            if !read_ready(i2c, state, seq, sent, UID_TIMEOUT, &mut resp)? {
+                return Ok(None);
+            }
+            handle_version_response(state, seq, &resp);
+            Ok(None)
+        }
+    }
+}
 
-        // Tag detected - no sensitive data logged
-        debug!("[#{}] Tag detected", seq);
-        Ok(true)
+/// Pulse the Pico's reset line and drop the command in flight and the tag
+fn reset_bridge(state: &mut NfcBridgeState, now: u64) -> Option<TagEvent> {
+    warn!("NFC bridge not responding (last answer {}s ago), resetting",
+          now.saturating_sub(state.health.last_ok_ms()) / 1000);
+    if io_expander::set_output(io_expander::EXIO_PICO_RESET, false) {
+        std::thread::sleep(RESET_PULSE);
+        io_expander::set_output(io_expander::EXIO_PICO_RESET, true);
     } else {
-        debug!("[#{}] No valid tag", seq);
-        state.tag_present = false;
-        state.tag_uid_len = 0;
-        state.decoded_info = None;
-        Ok(false)
+        warn!("No Pico reset line on this board, waiting for the bridge to recover");
     }
+    state.health.reset_started(now);
+    state.phase = BridgePhase::Idle;
+
+    let had_tag = state.tag_present;
+    state.clear_tag();
+    had_tag.then_some(TagEvent::Removed)
 }
 
-/// Read and decode tag data
-pub fn read_tag_data(i2c: &mut I2cDriver<'_>, state: &mut NfcBridgeState) -> Result<bool, &'static str> {
-    if !state.tag_present {
+/// Poll for the response to the command in flight
+///
+/// Returns `Ok(false)` while the Pico is still busy. On success or error the
+/// state machine goes back to idle, and the outcome is recorded in
+/// `state.health`. A status byte the Pico never sends (e.g. 0xFF from a
+/// floating bus) counts as a garbage response.
+fn read_ready(
+    i2c: &mut I2cDriver<'_>,
+    state: &mut NfcBridgeState,
+    seq: u8,
+    sent: Instant,
+    timeout: Duration,
+    resp: &mut [u8],
+) -> Result<bool, &'static str> {
+    if i2c.read(PICO_NFC_ADDR, resp, 100).is_err() {
+        warn!("[#{}] I2C read failed", seq);
+        state.phase = BridgePhase::Idle;
+        state.health.record_failure();
+        return Err("I2C read failed");
+    }
+    if resp[0] == STATUS_BUSY {
+        if sent.elapsed() > timeout {
+            warn!("[#{}] Bridge timeout", seq);
+            state.phase = BridgePhase::Idle;
+            state.health.record_failure();
+            return Err("Bridge timeout");
+        }
         return Ok(false);
     }
+    state.phase = BridgePhase::Idle;
+    if resp[0] > STATUS_WRITE_ERROR {
+        warn!("[#{}] Garbage response (status=0x{:02X})", seq, resp[0]);
+        state.health.record_garbage();
+        return Err("Garbage response");
+    }
+    state.health.record_ok(state.now_ms());
+    Ok(true)
+}
 
-    let seq = next_seq();
-
-    // Send read tag data command with sequence number
-    info!("[#{}] TX: READ_TAG_DATA", seq);
-    let cmd = [CMD_READ_TAG_DATA, seq];
-    if i2c.write(PICO_NFC_ADDR, &cmd, 100).is_err() {
-        warn!("[#{}] I2C write failed", seq);
-        return Err("I2C write failed");
+/// Update tag presence from a `[status, uid_len, uid...]` response
+fn handle_uid_response(state: &mut NfcBridgeState, seq: u8, resp: &[u8]) -> Option<TagEvent> {
+    let uid_len = resp[1] as usize;
+    let valid_len = uid_len > 0 && uid_len <= MAX_UID_LEN;
+    if resp[0] == STATUS_OK && !valid_len {
+        warn!("[#{}] Garbage UID length {}", seq, uid_len);
+        state.health.record_garbage();
     }
+    let seen = (resp[0] == STATUS_OK && valid_len).then(|| &resp[2..2 + uid_len]);
+
+    // A different tag swapped in between polls counts as a new tag
+    let event = tag::presence_change(state.uid(), seen)?;
+    state.clear_tag();
+    match (event, seen) {
+        (TagEvent::Detected, Some(uid)) => {
+            state.tag_present = true;
+            state.tag_uid_len = uid_len as u8;
+            state.tag_uid[..uid_len].copy_from_slice(uid);
+
+            // Tag detected - no sensitive data logged
+            debug!("[#{}] Tag detected", seq);
+        }
+        _ => debug!("[#{}] Tag gone (status={})", seq, resp[0]),
+    }
+    Some(event)
+}
 
-    // Wait for Pico to read tag data (authentication + block reads take time)
-    info!("[#{}] waiting 1000ms for auth+read", seq);
-    std::thread::sleep(std::time::Duration::from_millis(1000));
-    info!("[#{}] RX: reading response", seq);
-
-    // Read response - up to 200 bytes for tag data
-    // Response format:
-    // [0] = status (0 = success, 1 = no tag, 2 = read error, 3 = unknown type)
-    // [1] = tag_type
-    // [2] = uid_len
-    // [3..3+uid_len] = uid
-    // For MIFARE: blocks 1, 2, 4, 5 (64 bytes)
-    // For NTAG: pages 4-20 (68 bytes)
-    let mut resp = [0u8; 100];
-    if i2c.read(PICO_NFC_ADDR, &mut resp, 100).is_err() {
-        warn!("[#{}] I2C read failed", seq);
-        return Err("I2C read failed");
+/// Track the firmware version reported by a heartbeat (changes after the
+/// Pico is reflashed)
+fn handle_version_response(state: &mut NfcBridgeState, seq: u8, resp: &[u8]) {
+    if resp[0] != STATUS_OK {
+        warn!("[#{}] Version request failed, status: {}", seq, resp[0]);
+        return;
+    }
+    let version = (resp[1], resp[2]);
+    if version != state.firmware_version {
+        info!("Pico firmware: {}.{}", version.0, version.1);
+        state.firmware_version = version;
     }
+}
 
+/// Decode a READ_TAG_DATA response into `state.decoded_info`
+fn handle_tag_data_response(state: &mut NfcBridgeState, seq: u8, resp: &[u8]) -> Option<TagEvent> {
     let status = resp[0];
-    if status != 0 {
+    if status != STATUS_OK {
         warn!("[#{}] Read failed, status: {}", seq, status);
-        return Ok(false);
+        return None;
     }
 
     let tag_type = resp[1];
@@ -227,139 +475,206 @@ pub fn read_tag_data(i2c: &mut I2cDriver<'_>, state: &mut NfcBridgeState) -> Res
 
     debug!("[#{}] Tag read success", seq);
 
-    // Decode based on tag type
-    let data_offset = 3 + uid_len;
-
-    if tag_type == TAG_TYPE_MIFARE_1K || tag_type == TAG_TYPE_MIFARE_4K {
-        // Bambu Lab tag - decode blocks 1, 2, 4, 5
-        let decoded = decode_bambu_tag(&resp[data_offset..]);
-        state.decoded_info = Some(decoded);
-        Ok(true)
-    } else if tag_type == TAG_TYPE_NTAG {
-        // NTAG - could be SpoolEase or OpenPrintTag
-        // For now just mark as NTAG, full NDEF decoding would be more complex
-        state.decoded_info = Some(DecodedTagInfo {
-            tag_type_name: "NTAG".to_string(),
-            ..Default::default()
-        });
-        Ok(true)
-    } else {
+    if tag_type != TAG_TYPE_MIFARE_1K && tag_type != TAG_TYPE_MIFARE_4K && tag_type != TAG_TYPE_NTAG {
         state.decoded_info = None;
-        Ok(false)
+        return None;
     }
+
+    // MIFARE: blocks 1, 2, 4, 5. NTAG: pages 4-20.
+    let Some(uid) = resp.get(3..3 + uid_len) else {
+        warn!("[#{}] Garbage UID length {}", seq, uid_len);
+        return None;
+    };
+    let raw = RawTag { tag_type, uid, data: &resp[3 + uid_len..] };
+    state.decoded_info = Some(match decode::decode(&raw) {
+        Some(found) => {
+            info!("Decoded {} tag ({}% sure): type={}, subtype={}, color=0x{:08X}, weight={}g",
+                  found.info.tag_type_name, found.confidence, found.info.material,
+                  found.info.material_subtype, found.info.color_rgba, found.info.spool_weight);
+            found.info
+        }
+        None => {
+            debug!("[#{}] No decoder for this tag", seq);
+            decode::tag_info(&raw)
+        }
+    });
+    Some(TagEvent::Decoded)
 }
 
-/// Decode Bambu Lab tag data from raw blocks
-fn decode_bambu_tag(block_data: &[u8]) -> DecodedTagInfo {
-    // Block layout (each 16 bytes):
-    // Block 1: Material variant ID (0-7), Material ID (8-15)
-    // Block 2: Filament type (e.g., "PLA")
-    // Block 4: Detailed type (e.g., "PLA Basic")
-    // Block 5: Color RGBA (0-3), Spool weight (4-5 little-endian)
-
-    if block_data.len() < 64 {
-        warn!("Insufficient block data: {} bytes", block_data.len());
-        return DecodedTagInfo {
-            tag_type_name: "Bambu Lab".to_string(),
-            ..Default::default()
-        };
-    }
-
-    let block1 = &block_data[0..16];
-    let block2 = &block_data[16..32];
-    let block4 = &block_data[32..48];
-    let block5 = &block_data[48..64];
-
-    // Extract material ID (block 1, bytes 8-15)
-    let material_id = extract_cstring(&block1[8..16]);
-
-    // Extract filament type (block 2)
-    let filament_type = extract_cstring(block2);
-
-    // Extract detailed type (block 4)
-    let detailed_type = extract_cstring(block4);
-
-    // Extract color RGBA (block 5, bytes 0-3)
-    let color_rgba = u32::from_be_bytes([block5[0], block5[1], block5[2], block5[3]]);
-
-    // Extract spool weight (block 5, bytes 4-5, little-endian)
-    let spool_weight = i16::from_le_bytes([block5[4], block5[5]]) as i32;
-
-    // Derive subtype from detailed_type
-    let material_subtype = if detailed_type.starts_with(&format!("Bambu {} ", filament_type)) {
-        detailed_type.strip_prefix(&format!("Bambu {} ", filament_type))
-            .unwrap_or("")
-            .to_string()
-    } else if detailed_type.starts_with(&filament_type) {
-        detailed_type.strip_prefix(&filament_type)
-            .map(|s| s.trim())
-            .unwrap_or("")
-            .to_string()
-    } else {
-        detailed_type.clone()
+/// Map a MIFARE command status byte to an error
+fn mifare_status(status: u8) -> Result<(), &'static str> {
+    match status {
+        STATUS_OK => Ok(()),
+        STATUS_NO_TAG => Err("No tag"),
+        STATUS_READ_ERROR => Err("Read failed"),
+        STATUS_UNSUPPORTED_TAG => Err("Not a MIFARE Classic tag"),
+        STATUS_AUTH_FAILED => Err("Authentication failed"),
+        STATUS_INVALID_ARG => Err("Invalid argument"),
+        STATUS_NOT_AUTHENTICATED => Err("Sector not authenticated"),
+        STATUS_WRITE_ERROR => Err("Write failed"),
+        _ => Err("Command failed"),
+    }
+}
+
+/// Send a MIFARE command and read back `resp.len()` bytes
+fn mifare_command(
+    i2c: &mut I2cDriver<'_>,
+    cmd: &[u8],
+    timeout: Duration,
+    resp: &mut [u8],
+) -> Result<(), &'static str> {
+    send_command(i2c, cmd)?;
+    if let Err(e) = wait_response(i2c, resp, timeout) {
+        warn!("[#{}] {}", cmd[1], e);
+        return Err(e);
+    }
+    mifare_status(resp[0])
+}
+
+/// Store a MIFARE key in a Pico key slot (slots are lost on Pico reset)
+#[allow(dead_code)]
+pub fn set_key_slot(
+    i2c: &mut I2cDriver<'_>,
+    slot: u8,
+    key_type: MifareKeyType,
+    key: &[u8; 6],
+) -> Result<(), &'static str> {
+    if slot >= MAX_KEY_SLOTS {
+        return Err("Invalid key slot");
+    }
+    let seq = next_seq();
+    info!("[#{}] TX: SET_KEY slot {}", seq, slot);
+
+    let key_type = match key_type {
+        MifareKeyType::KeyA => 0,
+        MifareKeyType::KeyB => 1,
     };
+    let mut cmd = [0u8; 10];
+    cmd[..4].copy_from_slice(&[CMD_SET_KEY, seq, slot, key_type]);
+    cmd[4..].copy_from_slice(key);
+
+    let mut resp = [0u8; 1];
+    mifare_command(i2c, &cmd, UID_TIMEOUT, &mut resp)
+}
+
+/// Authenticate the sector containing `block` using a key slot
+#[allow(dead_code)]
+pub fn mifare_auth(i2c: &mut I2cDriver<'_>, block: u8, slot: u8) -> Result<(), &'static str> {
+    let seq = next_seq();
+    info!("[#{}] TX: MFC_AUTH block {} slot {}", seq, block, slot);
+
+    // Pico reactivates the card before authenticating
+    let mut resp = [0u8; 1];
+    mifare_command(i2c, &[CMD_MFC_AUTH, seq, block, slot], TAG_DATA_TIMEOUT, &mut resp)
+}
+
+/// Read a block from the currently authenticated sector
+#[allow(dead_code)]
+pub fn mifare_read_block(i2c: &mut I2cDriver<'_>, block: u8) -> Result<[u8; MIFARE_BLOCK_SIZE], &'static str> {
+    let seq = next_seq();
+    info!("[#{}] TX: MFC_READ block {}", seq, block);
 
-    info!("Decoded Bambu tag: material_id={}, type={}, detailed={}, color=0x{:08X}, weight={}g",
-          material_id, filament_type, detailed_type, color_rgba, spool_weight);
+    let mut resp = [0u8; 1 + MIFARE_BLOCK_SIZE];
+    mifare_command(i2c, &[CMD_MFC_READ, seq, block], UID_TIMEOUT, &mut resp)?;
 
-    DecodedTagInfo {
-        vendor: "Bambu".to_string(),
-        material: filament_type,
-        material_subtype,
-        color_name: format_color_name(color_rgba),
-        color_rgba,
-        spool_weight,
-        tag_type_name: "Bambu Lab".to_string(),
+    let mut data = [0u8; MIFARE_BLOCK_SIZE];
+    data.copy_from_slice(&resp[1..]);
+    Ok(data)
+}
+
+/// Write a block in the currently authenticated sector
+///
+/// The Pico refuses block 0 and sector trailers so a bad write can't lock
+/// the tag.
+#[allow(dead_code)]
+pub fn mifare_write_block(
+    i2c: &mut I2cDriver<'_>,
+    block: u8,
+    data: &[u8; MIFARE_BLOCK_SIZE],
+) -> Result<(), &'static str> {
+    let seq = next_seq();
+    info!("[#{}] TX: MFC_WRITE block {}", seq, block);
+
+    let mut cmd = [0u8; 3 + MIFARE_BLOCK_SIZE];
+    cmd[..3].copy_from_slice(&[CMD_MFC_WRITE, seq, block]);
+    cmd[3..].copy_from_slice(data);
+
+    let mut resp = [0u8; 1];
+    mifare_command(i2c, &cmd, UID_TIMEOUT, &mut resp)
+}
+
+/// Send an NTAG command and read back `resp.len()` bytes
+fn ntag_command(
+    i2c: &mut I2cDriver<'_>,
+    cmd: &[u8],
+    resp: &mut [u8],
+) -> Result<(), &'static str> {
+    match mifare_command(i2c, cmd, UID_TIMEOUT, resp) {
+        Err(_) if resp[0] == STATUS_UNSUPPORTED_TAG => Err("Not an NTAG tag"),
+        result => result,
     }
 }
 
-/// Extract null-terminated string from bytes
-fn extract_cstring(data: &[u8]) -> String {
-    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
-    String::from_utf8_lossy(&data[..end]).to_string()
+/// Read four NTAG pages starting at `page`
+pub fn ntag_read_pages(i2c: &mut I2cDriver<'_>, page: u8) -> Result<[u8; 16], &'static str> {
+    let seq = next_seq();
+    debug!("[#{}] TX: NTAG_READ page {}", seq, page);
+
+    let mut resp = [0u8; 17];
+    ntag_command(i2c, &[CMD_NTAG_READ, seq, page], &mut resp)?;
+
+    let mut data = [0u8; 16];
+    data.copy_from_slice(&resp[1..]);
+    Ok(data)
 }
 
-/// Format color RGBA as a name (fallback to hex if no name found)
-fn format_color_name(rgba: u32) -> String {
-    // Simple color name lookup for common colors
-    // Format is 0xRRGGBBAA
-    let r = (rgba >> 24) & 0xFF;
-    let g = (rgba >> 16) & 0xFF;
-    let b = (rgba >> 8) & 0xFF;
-
-    // Very basic color detection
-    if r > 200 && g < 100 && b < 100 {
-        "Red".to_string()
-    } else if r < 100 && g > 200 && b < 100 {
-        "Green".to_string()
-    } else if r < 100 && g < 100 && b > 200 {
-        "Blue".to_string()
-    } else if r > 200 && g > 200 && b < 100 {
-        "Yellow".to_string()
-    } else if r > 200 && g > 200 && b > 200 {
-        "White".to_string()
-    } else if r < 50 && g < 50 && b < 50 {
-        "Black".to_string()
-    } else if r > 200 && g > 100 && b < 100 {
-        "Orange".to_string()
-    } else {
-        // Return hex color
-        format!("#{:02X}{:02X}{:02X}", r, g, b)
+/// Write one NTAG page
+///
+/// The Pico refuses pages 0-3 (UID, lock bytes, capability container).
+pub fn ntag_write_page(i2c: &mut I2cDriver<'_>, page: u8, data: &[u8; 4]) -> Result<(), &'static str> {
+    let seq = next_seq();
+    debug!("[#{}] TX: NTAG_WRITE page {}", seq, page);
+
+    let cmd = [CMD_NTAG_WRITE, seq, page, data[0], data[1], data[2], data[3]];
+    let mut resp = [0u8; 1];
+    ntag_command(i2c, &cmd, &mut resp)
+}
+
+/// PN5180 receiver AGC value (0-1023) with the field on
+///
+/// Pico firmware without the command answers with an error.
+pub fn rf_agc(i2c: &mut I2cDriver<'_>) -> Result<u16, &'static str> {
+    let seq = next_seq();
+    debug!("[#{}] TX: RF_STATUS", seq);
+
+    let mut resp = [0u8; 3];
+    mifare_command(i2c, &[CMD_RF_STATUS, seq], UID_TIMEOUT, &mut resp)?;
+    Ok(u16::from_le_bytes([resp[1], resp[2]]))
+}
+
+/// NTAG memory of the tag on the bridge, for `spoolbuddy_core::encode`
+pub struct BridgeTag<'a, 'd> {
+    pub i2c: &'a mut I2cDriver<'d>,
+}
+
+impl TagMemory for BridgeTag<'_, '_> {
+    type Error = &'static str;
+
+    fn write_page(&mut self, page: u8, data: &[u8; 4]) -> Result<(), &'static str> {
+        ntag_write_page(self.i2c, page, data)
+    }
+
+    fn read_pages(&mut self, page: u8) -> Result<[u8; 16], &'static str> {
+        ntag_read_pages(self.i2c, page)
+    }
+
+    fn rf_signal(&mut self) -> Option<u16> {
+        rf_agc(self.i2c).ok()
     }
 }
 
 /// Get UID as hex string
 #[allow(dead_code)]
 pub fn get_uid_hex(state: &NfcBridgeState) -> Option<String> {
-    if !state.tag_present || state.tag_uid_len == 0 {
-        return None;
-    }
-
-    let hex: String = state.tag_uid[..state.tag_uid_len as usize]
-        .iter()
-        .map(|b| format!("{:02X}", b))
-        .collect::<Vec<_>>()
-        .join(":");
-
-    Some(hex)
+    state.uid().map(tag::uid_hex)
 }
@@ -1,31 +1,43 @@
 //! NFC module for PN5180 NFC reader.
 //!
+//! The reader is connected one of two ways, picked at build time (see
+//! [`transport`]):
+//! - Pico NFC bridge on the shared I2C bus (default)
+//! - PN5180 wired directly to the ESP32 on SPI3 (`nfc-spi` feature)
+//!
 //! The PN5180 is a high-performance NFC frontend supporting:
 //! - ISO14443A/B (MIFARE, NFC tags)
 //! - ISO15693 (ICODE, vicinity cards - longer range)
 //!
 //! Interface: SPI (up to 7 MHz) + BUSY + RST pins
 //!
-//! Hardware connection via CrowPanel Advance 7.0" headers:
-//! NOTE: J9 pins (IO4/5/6) are used by the RGB LCD display!
-//! We use UART0-OUT header for SPI instead.
+//! Direct SPI connection on the CrowPanel Advance 7.0" J9/J11 headers.
+//! RST and BUSY are not wired (their header pins clash with touch and
+//! backlight), so the driver relies on power-on reset and fixed delays:
 //!
-//! - IO43 (UART0-OUT) -> SPI SCK
-//! - IO44 (UART0-OUT) -> SPI MISO
-//! - IO16 (J11 Pin 2) -> SPI MOSI
-//! - IO8  (J11 Pin 6) -> NSS chip select
-//! - IO2  (J11 Pin 5) -> BUSY signal
-//! - IO15 (J11 Pin 3) -> RST reset
+//! - IO5 (J9) -> SPI SCK
+//! - IO4 (J9) -> SPI MISO
+//! - IO6 (J9) -> SPI MOSI
+//! - IO8 (J11 Pin 6) -> NSS chip select
 
 #[allow(dead_code)]
 pub mod pn5180;
 
+/// Bambu Lab tag key derivation (HKDF-SHA256 from the tag UID)
+#[allow(dead_code)]
+pub mod bambu_keys;
+
 /// I2C bridge to Pico for NFC (recommended - more reliable than direct SPI)
 pub mod i2c_bridge;
 
+/// Reader transport trait with the I2C bridge and direct SPI implementations
+pub mod transport;
+
 // Re-exports will be used when NFC functionality is integrated
 #[allow(unused_imports)]
-pub use pn5180::{Pn5180State, Pn5180Error, Iso14443aCard, MifareKeyType, BAMBULAB_KEY};
+pub use pn5180::{Pn5180State, Pn5180Error, Iso14443aCard, MifareKeyType, BambuTagBlocks};
+#[allow(unused_imports)]
+pub use bambu_keys::derive_sector_keys;
 #[allow(unused_imports)]
 pub use pn5180::{init_stub, detect_tag_stub, rf_field_on_stub, rf_field_off_stub};
 
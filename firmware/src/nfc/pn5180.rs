@@ -40,7 +40,9 @@
 use esp_idf_hal::gpio::{Input, Output, PinDriver};
 use esp_idf_hal::delay::FreeRtos;
 use embedded_hal::spi::SpiDevice;
-use log::{info, warn};
+use log::{debug, info, warn};
+
+use super::bambu_keys::{derive_sector_keys, MIFARE_1K_SECTORS};
 
 // =============================================================================
 // GPIO Pin Definitions for CrowPanel Advance 7.0"
@@ -102,8 +104,10 @@ pub mod registers {
     pub const TIMER1_CONFIG: u8 = 0x0F;
     pub const TIMER1_RELOAD: u8 = 0x10;
     pub const TIMER1_VALUE: u8 = 0x11;
+    pub const CRC_RX_CONFIG: u8 = 0x12;
+    pub const RX_STATUS: u8 = 0x13;
     pub const TX_DATA_NUM: u8 = 0x14;
-    pub const RX_STATUS: u8 = 0x15;
+    pub const CRC_TX_CONFIG: u8 = 0x19;
     pub const RF_STATUS: u8 = 0x1D;
 }
 
@@ -127,9 +131,21 @@ pub enum MifareKeyType {
     KeyB,
 }
 
-/// Bambu Lab MIFARE key (Crypto-1)
-/// Note: This is the known key for reading Bambu Lab tags
-pub const BAMBULAB_KEY: [u8; 6] = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]; // Placeholder - actual key needed
+impl MifareKeyType {
+    /// Key selector byte for MFC_AUTHENTICATE
+    fn code(self) -> u8 {
+        match self {
+            MifareKeyType::KeyA => 0x60,
+            MifareKeyType::KeyB => 0x61,
+        }
+    }
+}
+
+/// MIFARE Classic block size in bytes
+pub const MIFARE_BLOCK_SIZE: usize = 16;
+
+/// Data blocks of a Bambu Lab tag (1K, excluding sector trailers)
+pub type BambuTagBlocks = [[u8; MIFARE_BLOCK_SIZE]; MIFARE_1K_SECTORS * 4];
 
 /// PN5180 driver state (without hardware - for init tracking)
 pub struct Pn5180State {
@@ -776,6 +792,13 @@ where
         self.send_command(&cmd)
     }
 
+    /// Set bits in a register (32-bit OR mask)
+    pub fn write_register_or_mask(&mut self, reg: u8, mask: u32) -> Result<(), Pn5180Error> {
+        let bytes = mask.to_le_bytes();
+        let cmd = [commands::WRITE_REGISTER_OR_MASK, reg, bytes[0], bytes[1], bytes[2], bytes[3]];
+        self.send_command(&cmd)
+    }
+
     /// Get firmware version
     pub fn get_firmware_version(&mut self) -> Result<(u8, u8, u8), Pn5180Error> {
         // Firmware version is at EEPROM address 0x10, 2 bytes
@@ -857,7 +880,7 @@ where
     pub fn iso14443a_activate(&mut self) -> Result<Option<Iso14443aCard>, Pn5180Error> {
         // Check RF status first
         let rf_status = self.read_register(registers::RF_STATUS)?;
-        info!("  RF_STATUS: 0x{:08X}", rf_status);
+        debug!("  RF_STATUS: 0x{:08X}", rf_status);
 
         // Clear IRQ status
         self.write_register(registers::IRQ_CLEAR, 0xFFFFFFFF)?;
@@ -881,20 +904,20 @@ where
 
         // Check IRQ status
         let irq_status = self.read_register(registers::IRQ_STATUS)?;
-        info!("  IRQ_STATUS: 0x{:08X}", irq_status);
+        debug!("  IRQ_STATUS: 0x{:08X}", irq_status);
 
         // Check RX status
         let rx_status = self.read_register(registers::RX_STATUS)?;
-        info!("  RX_STATUS: 0x{:08X}", rx_status);
+        debug!("  RX_STATUS: 0x{:08X}", rx_status);
 
         let rx_len = (rx_status & 0x1FF) as usize;
         let rx_collision = (rx_status >> 17) & 0x01;  // Bit 17 is collision flag
 
-        info!("  RX bytes: {}, collision: {}", rx_len, rx_collision);
+        debug!("  RX bytes: {}, collision: {}", rx_len, rx_collision);
 
         // Validate RX status - 0xFFFFFFFF or large values indicate SPI error
         if rx_status == 0xFFFFFFFF || rx_len > 64 {
-            info!("  Invalid RX_STATUS (SPI error)");
+            debug!("  Invalid RX_STATUS (SPI error)");
             return Ok(None);
         }
 
@@ -907,11 +930,11 @@ where
         let mut atqa = [0u8; 2];
         let read_cmd = [commands::READ_DATA];  // No extra bytes needed
         self.send_command_read(&read_cmd, &mut atqa)?;
-        info!("  ATQA: {:02X} {:02X}", atqa[0], atqa[1]);
+        debug!("  ATQA: {:02X} {:02X}", atqa[0], atqa[1]);
 
         // Validate ATQA - 0x0000 or 0xFFFF are invalid
         if atqa == [0x00, 0x00] || atqa == [0xFF, 0xFF] {
-            info!("  Invalid ATQA (SPI error or no card)");
+            debug!("  Invalid ATQA (SPI error or no card)");
             return Ok(None);
         }
 
@@ -925,6 +948,86 @@ where
             sak: 0,
         }))
     }
+
+    /// Authenticate a MIFARE Classic sector (MFC_AUTHENTICATE host command)
+    ///
+    /// On success the PN5180 enables Crypto-1 and transparently encrypts
+    /// subsequent exchanges until the card is deselected.
+    pub fn mifare_authenticate(
+        &mut self,
+        block: u8,
+        key_type: MifareKeyType,
+        key: &[u8; 6],
+        card: &Iso14443aCard,
+    ) -> Result<(), Pn5180Error> {
+        if card.uid_len < 4 {
+            return Err(Pn5180Error::NoCard);
+        }
+
+        // Crypto-1 uses the last 4 UID bytes (the whole UID for 4-byte cards)
+        let uid = &card.uid[card.uid_len as usize - 4..card.uid_len as usize];
+        let mut cmd = [0u8; 13];
+        cmd[0] = commands::MIFARE_AUTHENTICATE;
+        cmd[1..7].copy_from_slice(key);
+        cmd[7] = key_type.code();
+        cmd[8] = block;
+        cmd[9..13].copy_from_slice(uid);
+
+        let mut status = [0xFFu8; 1];
+        self.send_command_read(&cmd, &mut status)?;
+        match status[0] {
+            0x00 => Ok(()),
+            0x01 => Err(Pn5180Error::AuthFailed),
+            0x02 => Err(Pn5180Error::Timeout),
+            _ => Err(Pn5180Error::InvalidResponse),
+        }
+    }
+
+    /// Read a 16-byte MIFARE Classic block (sector must be authenticated)
+    pub fn mifare_read_block(&mut self, block: u8) -> Result<[u8; MIFARE_BLOCK_SIZE], Pn5180Error> {
+        self.clear_irq()?;
+
+        // MIFARE READ needs CRC on both directions
+        self.write_register_or_mask(registers::CRC_TX_CONFIG, 0x01)?;
+        self.write_register_or_mask(registers::CRC_RX_CONFIG, 0x01)?;
+
+        // SEND_DATA: [cmd, valid bits in last byte (0 = all), data...]
+        self.send_command(&[commands::SEND_DATA, 0x00, 0x30, block])?;
+        FreeRtos::delay_ms(10);
+
+        let rx_status = self.read_register(registers::RX_STATUS)?;
+        let rx_len = (rx_status & 0x1FF) as usize;
+        if rx_len != MIFARE_BLOCK_SIZE {
+            warn!("  MIFARE read block {} failed, rx_len={}", block, rx_len);
+            return Err(Pn5180Error::ReadFailed);
+        }
+
+        let mut data = [0u8; MIFARE_BLOCK_SIZE];
+        self.send_command_read(&[commands::READ_DATA, 0x00], &mut data)?;
+        Ok(data)
+    }
+
+    /// Read all data blocks of a Bambu Lab tag
+    ///
+    /// Sector keys are derived from the card UID (see `bambu_keys`), so every
+    /// sector can be authenticated rather than only the unprotected ones.
+    /// Sector trailers are left zeroed since they can't be read back.
+    pub fn read_bambu_tag(&mut self, card: &Iso14443aCard) -> Result<BambuTagBlocks, Pn5180Error> {
+        let keys = derive_sector_keys(&card.uid[..card.uid_len as usize]);
+        let mut blocks = [[0u8; MIFARE_BLOCK_SIZE]; MIFARE_1K_SECTORS * 4];
+
+        for (sector, key) in keys.iter().enumerate() {
+            let first_block = (sector * 4) as u8;
+            self.mifare_authenticate(first_block, MifareKeyType::KeyA, key, card)?;
+            for offset in 0..3 {
+                let block = first_block + offset;
+                blocks[block as usize] = self.mifare_read_block(block)?;
+            }
+        }
+
+        info!("  Read {} sectors from Bambu Lab tag", MIFARE_1K_SECTORS);
+        Ok(blocks)
+    }
 }
 
 /// Initialize PN5180 with SPI and GPIO (with optional RST and BUSY pins)
@@ -0,0 +1,289 @@
+//! NFC reader transports
+//!
+//! The PN5180 is either behind the Pico bridge on the shared I2C bus (the
+//! default) or wired straight to the ESP32 on SPI3 (`nfc-spi` feature, for
+//! builds without a Pico). Both sit behind [`NfcTransport`] and report tag
+//! changes through `spoolbuddy_core::tag::poll_tag`, so tag decoding and the
+//! NFC manager don't depend on how the reader is connected.
+
+use spoolbuddy_core::bridge_health::BridgeReport;
+use spoolbuddy_core::encode::WriteReport;
+use spoolbuddy_core::tag::TagUpdate;
+
+/// Error from [`NfcTransport::write_ntag`] when the reader is in the middle
+/// of something else; worth trying again a moment later
+pub const READER_BUSY: &str = "NFC reader busy";
+
+/// A connected NFC reader
+pub trait NfcTransport: Send {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    /// The reader answered during init and can be polled
+    fn is_initialized(&self) -> bool;
+
+    /// UID of the tag on the reader
+    fn uid(&self) -> Option<&[u8]>;
+
+    /// Advance the reader (call from the main loop); returns a tag change, if any
+    fn poll(&mut self) -> Result<Option<TagUpdate>, &'static str>;
+
+    /// Health of the Pico bridge (None for readers without one)
+    fn bridge_report(&self) -> Option<BridgeReport> {
+        None
+    }
+
+    /// Write NTAG user memory of the tag on the reader and read it back
+    /// (see `spoolbuddy_core::encode`)
+    fn write_ntag(&mut self, _data: &[u8]) -> Result<WriteReport, &'static str> {
+        Err("Tag writing needs the Pico bridge")
+    }
+}
+
+#[cfg(not(feature = "nfc-spi"))]
+pub use bridge::I2cBridgeTransport;
+
+#[cfg(feature = "nfc-spi")]
+pub use spi::SpiTransport;
+
+#[cfg(not(feature = "nfc-spi"))]
+mod bridge {
+    use log::warn;
+    use spoolbuddy_core::bridge_health::BridgeReport;
+    use spoolbuddy_core::encode::{self, WriteReport, DEFAULT_MAX_RETRIES};
+    use spoolbuddy_core::tag::{self, TagUpdate};
+
+    use super::{NfcTransport, READER_BUSY};
+    use crate::nfc::i2c_bridge::{self, BridgeTag, NfcBridgeState, PicoBridge};
+    use crate::shared_i2c::{self, BusClient};
+
+    /// Pico NFC bridge on the shared I2C bus
+    pub struct I2cBridgeTransport {
+        state: NfcBridgeState,
+    }
+
+    impl I2cBridgeTransport {
+        /// Look for the Pico and read its firmware version
+        pub fn init() -> Option<Self> {
+            shared_i2c::with_i2c(BusClient::Nfc, |i2c| {
+                let mut state = NfcBridgeState::new();
+                match i2c_bridge::init_bridge(i2c, &mut state) {
+                    Ok(()) => Some(Self { state }),
+                    Err(e) => {
+                        warn!("NFC bridge init failed: {}", e);
+                        None
+                    }
+                }
+            })
+            .flatten()
+        }
+    }
+
+    impl NfcTransport for I2cBridgeTransport {
+        fn name(&self) -> &'static str {
+            "Pico I2C bridge"
+        }
+
+        fn is_initialized(&self) -> bool {
+            self.state.initialized
+        }
+
+        fn uid(&self) -> Option<&[u8]> {
+            self.state.uid()
+        }
+
+        /// Non-blocking: one short I2C transaction per call. If another
+        /// client holds the bus, nothing happens until the next poll.
+        fn poll(&mut self) -> Result<Option<TagUpdate>, &'static str> {
+            let state = &mut self.state;
+            let result = shared_i2c::with_i2c(BusClient::Nfc, |i2c| tag::poll_tag(&mut PicoBridge { i2c, state }))
+                .unwrap_or(Ok(None));
+            if result.is_err() {
+                shared_i2c::record_error(BusClient::Nfc);
+            }
+            result
+        }
+
+        fn bridge_report(&self) -> Option<BridgeReport> {
+            Some(self.state.report())
+        }
+
+        /// Blocks for the whole write and read-back (tens of milliseconds)
+        fn write_ntag(&mut self, data: &[u8]) -> Result<WriteReport, &'static str> {
+            if self.state.uid().is_none() {
+                return Err("No tag");
+            }
+            // A UID or tag data poll in flight would eat our responses
+            if !self.state.is_idle() {
+                return Err(READER_BUSY);
+            }
+            shared_i2c::with_i2c(BusClient::Nfc, |i2c| {
+                encode::write_and_verify(&mut BridgeTag { i2c }, data, DEFAULT_MAX_RETRIES)
+            })
+            .ok_or(READER_BUSY)
+        }
+    }
+}
+
+#[cfg(feature = "nfc-spi")]
+mod spi {
+    use embedded_hal::spi::SpiDevice;
+    use esp_idf_hal::gpio::{Output, PinDriver};
+    use log::{debug, info, warn};
+    use spoolbuddy_core::decode::{self, RawTag};
+    use spoolbuddy_core::hal::Nfc;
+    use spoolbuddy_core::tag::{self, DecodedTagInfo, TagEvent, TagUpdate, MAX_UID_LEN};
+    use std::time::{Duration, Instant};
+
+    use super::NfcTransport;
+    use crate::nfc::pn5180::{self, Iso14443aCard, Pn5180Driver, Pn5180State, MIFARE_BLOCK_SIZE};
+
+    /// How often to look for a tag. Each scan blocks for ~10ms waiting for
+    /// the card to answer REQA.
+    const SCAN_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// MIFARE blocks the tag decoders get, in order (as from the Pico bridge)
+    const DECODE_BLOCKS: [usize; 4] = [1, 2, 4, 5];
+
+    /// PN5180 on the ESP32's own SPI bus
+    pub struct SpiTransport<SPI> {
+        driver: Pn5180Driver<'static, SPI>,
+        state: Pn5180State,
+        tag_uid: [u8; MAX_UID_LEN],
+        tag_uid_len: usize,
+        tag_present: bool,
+        /// Card to read on the next poll (set when a tag is detected)
+        pending_read: Option<Iso14443aCard>,
+        decoded_info: Option<DecodedTagInfo>,
+        last_scan: Option<Instant>,
+    }
+
+    impl<SPI: SpiDevice + Send> SpiTransport<SPI> {
+        /// Reset the PN5180, load the ISO14443A config and switch the field on
+        pub fn init(spi: SPI, nss: PinDriver<'static, Output>) -> Option<Self> {
+            let mut state = Pn5180State::new();
+            let mut driver = match pn5180::init_pn5180(spi, nss, None, None, &mut state) {
+                Ok(driver) => driver,
+                Err(e) => {
+                    warn!("PN5180 init failed: {:?}", e);
+                    return None;
+                }
+            };
+            if let Err(e) = driver.rf_on() {
+                warn!("PN5180 RF field on failed: {:?}", e);
+                return None;
+            }
+            state.rf_on = true;
+
+            Some(Self {
+                driver,
+                state,
+                tag_uid: [0; MAX_UID_LEN],
+                tag_uid_len: 0,
+                tag_present: false,
+                pending_read: None,
+                decoded_info: None,
+                last_scan: None,
+            })
+        }
+
+        fn clear_tag(&mut self) {
+            self.tag_present = false;
+            self.tag_uid_len = 0;
+            self.pending_read = None;
+            self.decoded_info = None;
+        }
+
+        /// Read and decode the tag that was just detected
+        fn read_tag(&mut self, card: &Iso14443aCard) -> Option<DecodedTagInfo> {
+            let uid = &card.uid[..(card.uid_len as usize).min(MAX_UID_LEN)];
+            if card.is_mifare_classic_1k() {
+                let blocks = match self.driver.read_bambu_tag(card) {
+                    Ok(blocks) => blocks,
+                    Err(e) => {
+                        warn!("Tag read failed: {:?}", e);
+                        return None;
+                    }
+                };
+                let mut data = [0u8; DECODE_BLOCKS.len() * MIFARE_BLOCK_SIZE];
+                for (chunk, &block) in data.chunks_exact_mut(MIFARE_BLOCK_SIZE).zip(&DECODE_BLOCKS) {
+                    chunk.copy_from_slice(&blocks[block]);
+                }
+                let info = decode::tag_info(&RawTag { tag_type: tag::TAG_TYPE_MIFARE_1K, uid, data: &data });
+                info!("Decoded {} tag: type={}, subtype={}, color=0x{:08X}, weight={}g",
+                      info.tag_type_name, info.material, info.material_subtype, info.color_rgba, info.spool_weight);
+                Some(info)
+            } else if card.is_ntag() {
+                // NTAG memory isn't read over SPI yet
+                Some(decode::tag_info(&RawTag { tag_type: tag::TAG_TYPE_NTAG, uid, data: &[] }))
+            } else {
+                debug!("Unsupported tag (SAK=0x{:02X})", card.sak);
+                None
+            }
+        }
+    }
+
+    impl<SPI: SpiDevice + Send> Nfc for SpiTransport<SPI> {
+        type Error = &'static str;
+
+        /// Scans every [`SCAN_INTERVAL`]; a new tag is read on the poll
+        /// after it was reported as detected
+        fn poll(&mut self) -> Result<Option<TagEvent>, &'static str> {
+            if let Some(card) = self.pending_read.take() {
+                self.decoded_info = self.read_tag(&card);
+                return Ok(self.decoded_info.is_some().then_some(TagEvent::Decoded));
+            }
+
+            if self.last_scan.is_some_and(|t| t.elapsed() < SCAN_INTERVAL) {
+                return Ok(None);
+            }
+            self.last_scan = Some(Instant::now());
+
+            let card = self.driver.iso14443a_activate().map_err(|e| {
+                warn!("PN5180 scan failed: {:?}", e);
+                "PN5180 scan failed"
+            })?;
+            let seen = card.as_ref().map(|c| &c.uid[..(c.uid_len as usize).min(MAX_UID_LEN)]);
+
+            // A different tag swapped in between scans counts as a new tag
+            let Some(event) = tag::presence_change(Nfc::uid(self), seen) else {
+                return Ok(None);
+            };
+            self.clear_tag();
+            if let (TagEvent::Detected, Some(card)) = (event, card) {
+                let len = (card.uid_len as usize).min(MAX_UID_LEN);
+                self.tag_present = true;
+                self.tag_uid_len = len;
+                self.tag_uid[..len].copy_from_slice(&card.uid[..len]);
+                self.pending_read = Some(card);
+            }
+            Ok(Some(event))
+        }
+
+        fn uid(&self) -> Option<&[u8]> {
+            self.tag_present.then(|| &self.tag_uid[..self.tag_uid_len])
+        }
+
+        fn tag_info(&self) -> Option<&DecodedTagInfo> {
+            self.decoded_info.as_ref()
+        }
+    }
+
+    impl<SPI: SpiDevice + Send> NfcTransport for SpiTransport<SPI> {
+        fn name(&self) -> &'static str {
+            "PN5180 SPI"
+        }
+
+        fn is_initialized(&self) -> bool {
+            self.state.initialized
+        }
+
+        fn uid(&self) -> Option<&[u8]> {
+            Nfc::uid(self)
+        }
+
+        fn poll(&mut self) -> Result<Option<TagUpdate>, &'static str> {
+            tag::poll_tag(self)
+        }
+    }
+}
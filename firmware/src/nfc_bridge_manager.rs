@@ -1,16 +1,20 @@
-//! NFC Bridge Manager with C-callable interface
+//! NFC Manager with C-callable interface
 //!
 //! Provides FFI functions for the C UI code to access NFC tag data.
-//! Uses the Pico NFC bridge over I2C.
+//! The reader is reached through an [`NfcTransport`]: the Pico NFC bridge
+//! over I2C, or a PN5180 on SPI with the `nfc-spi` feature.
 
 use log::{info, warn};
 use std::sync::Mutex;
 
-use crate::nfc::i2c_bridge::{self, NfcBridgeState};
-use crate::shared_i2c;
+use spoolbuddy_core::bridge_health::BridgeReport;
+use spoolbuddy_core::encode::WriteReport;
+use spoolbuddy_core::tag::{self, TagUpdate};
 
-/// Global NFC state protected by mutex
-static NFC_STATE: Mutex<Option<NfcBridgeState>> = Mutex::new(None);
+use crate::nfc::transport::NfcTransport;
+
+/// Active NFC reader protected by mutex
+static NFC_STATE: Mutex<Option<Box<dyn NfcTransport>>> = Mutex::new(None);
 
 /// NFC status for C code
 #[repr(C)]
@@ -21,145 +25,105 @@ pub struct NfcStatus {
     pub uid: [u8; 10],
 }
 
-/// Initialize the NFC bridge manager
-pub fn init_nfc_manager() -> bool {
-    // Use shared I2C to initialize
-    let result = shared_i2c::with_i2c(|i2c| {
-        let mut state = NfcBridgeState::new();
-        match i2c_bridge::init_bridge(i2c, &mut state) {
-            Ok(()) => {
-                info!("NFC bridge manager initialized");
-                Some(state)
-            }
-            Err(e) => {
-                warn!("NFC bridge init failed: {}", e);
-                None
-            }
-        }
-    });
-
-    if let Some(Some(state)) = result {
-        let mut guard = NFC_STATE.lock().unwrap();
-        *guard = Some(state);
-        true
-    } else {
-        false
-    }
+/// Hand the initialized reader to the manager
+pub fn init_nfc_manager(transport: Box<dyn NfcTransport>) {
+    info!("NFC manager initialized ({})", transport.name());
+    let mut guard = NFC_STATE.lock().unwrap();
+    *guard = Some(transport);
 }
 
-/// Poll the NFC bridge (call from main loop)
+/// Poll the NFC reader (call from main loop)
+///
+/// With the Pico bridge this is non-blocking: it advances the bridge state
+/// machine by one I2C transaction, so it's cheap enough to call every loop
+/// iteration.
 pub fn poll_nfc() {
-    static mut LAST_TAG_PRESENT: bool = false;
-    static mut TAG_DATA_READ: bool = false;
-
-    // Collect data from I2C, then release locks before HTTP calls
-    let mut tag_just_appeared = false;
-    let mut tag_just_removed = false;
-    let mut tag_data_decoded = false;
-    let mut uid_hex = String::new();
-    #[allow(unused_variables)]
-    let mut decoded_info: Option<i2c_bridge::DecodedTagInfo> = None;
+    // Collect data from the reader, then release locks before HTTP calls
+    let mut update = None;
 
     {
         let mut guard = NFC_STATE.lock().unwrap();
-        if let Some(ref mut state) = *guard {
-            if state.initialized {
-                let _ = shared_i2c::with_i2c(|i2c| {
-                    match i2c_bridge::scan_tag(i2c, state) {
-                        Ok(found) => {
-                            unsafe {
-                                if found && !LAST_TAG_PRESENT {
-                                    // Tag just appeared
-                                    uid_hex = get_uid_hex_string(state);
-                                    // Log detection without full UID (security: avoid logging sensitive tag identifiers)
-                                    info!("NFC TAG DETECTED");
-                                    TAG_DATA_READ = false;
-                                    tag_just_appeared = true;
-                                }
-
-                                // Read tag data if we haven't yet (for local decoding)
-                                if found && !TAG_DATA_READ {
-                                    match i2c_bridge::read_tag_data(i2c, state) {
-                                        Ok(true) => {
-                                            TAG_DATA_READ = true;
-                                            tag_data_decoded = true;
-                                            decoded_info = state.decoded_info.clone();
-
-                                            // Copy decoded data to FFI storage
-                                            if let Some(ref info) = state.decoded_info {
-                                                set_decoded_tag_data(
-                                                    &info.vendor,
-                                                    &info.material,
-                                                    &info.material_subtype,
-                                                    &info.color_name,
-                                                    info.color_rgba,
-                                                    info.spool_weight,
-                                                    &info.tag_type_name,
-                                                );
-                                                info!("Tag decoded: {} {} {} ({}g)",
-                                                    info.vendor, info.material, info.color_name, info.spool_weight);
-                                            }
-
-                                            if uid_hex.is_empty() {
-                                                uid_hex = get_uid_hex_string(state);
-                                            }
-                                        }
-                                        Ok(false) => {
-                                            // No data yet, will retry
-                                        }
-                                        Err(e) => {
-                                            warn!("Tag data read error: {}", e);
-                                            TAG_DATA_READ = true; // Don't keep retrying on error
-                                        }
-                                    }
-                                }
-
-                                if !found && LAST_TAG_PRESENT {
-                                    // Tag just removed
-                                    info!("NFC TAG REMOVED");
-                                    clear_decoded_tag_data();
-                                    TAG_DATA_READ = false;
-                                    tag_just_removed = true;
-                                }
-                                LAST_TAG_PRESENT = found;
-                            }
-                        }
-                        Err(e) => {
-                            warn!("NFC scan error: {}", e);
-                        }
+        if let Some(ref mut transport) = *guard {
+            if transport.is_initialized() {
+                match transport.poll() {
+                    Ok(u) => update = u,
+                    Err(e) => warn!("NFC poll error: {}", e),
+                }
+
+                match update {
+                    Some(TagUpdate::Detected { .. }) => {
+                        // Log detection without full UID (security: avoid logging sensitive tag identifiers)
+                        info!("NFC TAG DETECTED");
+                        crate::buzzer::play(crate::buzzer::Pattern::TagDetected);
+                        // A different tag may have replaced the previous one without a removal
+                        clear_decoded_tag_data();
                     }
-                });
+                    Some(TagUpdate::Decoded { info: Some(ref info), .. }) => {
+                        // Copy decoded data to FFI storage
+                        set_decoded_tag_data(
+                            &info.vendor,
+                            &info.material,
+                            &info.material_subtype,
+                            &info.color_name,
+                            info.color_rgba,
+                            info.spool_weight,
+                            &info.tag_type_name,
+                        );
+                        info!("Tag decoded: {} {} {} ({}g)",
+                            info.vendor, info.material, info.color_name, info.spool_weight);
+                    }
+                    Some(TagUpdate::Removed) => {
+                        info!("NFC TAG REMOVED");
+                        clear_decoded_tag_data();
+                    }
+                    Some(TagUpdate::Decoded { info: None, .. }) | None => {}
+                }
             }
         }
-    } // Release NFC_STATE lock and I2C lock here
+    } // Release NFC_STATE lock (and the bus) here
 
     // Now make HTTP calls outside the locks
-    if tag_just_appeared || tag_data_decoded {
-        let weight = crate::scale_manager::scale_get_weight();
-        let stable = crate::scale_manager::scale_is_stable();
-        crate::backend_client::send_device_state(Some(&uid_hex), weight, stable);
+    let Some(update) = update else {
+        return;
+    };
+    let weight = crate::scale_manager::scale_get_weight();
+    let stable = crate::scale_manager::scale_is_stable();
+    match update {
+        TagUpdate::Detected { uid_hex } | TagUpdate::Decoded { uid_hex, .. } => {
+            crate::backend_client::send_device_state(Some(&uid_hex), weight, stable);
+        }
+        TagUpdate::Removed => {
+            crate::backend_client::send_device_state(None, weight, stable);
+        }
     }
+}
 
-    if tag_just_removed {
-        let weight = crate::scale_manager::scale_get_weight();
-        let stable = crate::scale_manager::scale_is_stable();
-        crate::backend_client::send_device_state(None, weight, stable);
-    }
+/// Pico bridge health (None without a bridge, e.g. with the SPI reader)
+pub fn bridge_report() -> Option<BridgeReport> {
+    let guard = NFC_STATE.lock().unwrap();
+    guard.as_ref().and_then(|t| t.bridge_report())
+}
+
+/// UID of the tag on the reader as hex (None if no tag or no reader)
+pub fn tag_uid_hex() -> Option<String> {
+    with_uid(|uid| uid.map(tag::uid_hex))
 }
 
-/// Get UID as hex string (internal helper)
-fn get_uid_hex_string(state: &NfcBridgeState) -> String {
-    if state.tag_present && state.tag_uid_len > 0 {
-        state.tag_uid[..state.tag_uid_len as usize]
-            .iter()
-            .map(|b| format!("{:02X}", b))
-            .collect::<Vec<_>>()
-            .join(":")
-    } else {
-        String::new()
+/// Write NTAG user memory of the tag on the reader and verify it
+pub fn write_ntag(data: &[u8]) -> Result<WriteReport, &'static str> {
+    let mut guard = NFC_STATE.lock().unwrap();
+    match *guard {
+        Some(ref mut transport) if transport.is_initialized() => transport.write_ntag(data),
+        _ => Err("NFC not initialized"),
     }
 }
 
+/// Run `f` with the UID of the tag on the reader (None if no tag or no reader)
+fn with_uid<R>(f: impl FnOnce(Option<&[u8]>) -> R) -> R {
+    let guard = NFC_STATE.lock().unwrap();
+    f(guard.as_ref().filter(|t| t.is_initialized()).and_then(|t| t.uid()).filter(|uid| !uid.is_empty()))
+}
+
 // =============================================================================
 // C-callable FFI functions
 // =============================================================================
@@ -171,57 +135,51 @@ pub extern "C" fn nfc_get_status(status: *mut NfcStatus) {
         return;
     }
 
-    let guard = NFC_STATE.lock().unwrap();
     let status = unsafe { &mut *status };
-
-    if let Some(ref state) = *guard {
-        status.initialized = state.initialized;
-        status.tag_present = state.tag_present;
-        status.uid_len = state.tag_uid_len;
-        status.uid = state.tag_uid;
-    } else {
-        status.initialized = false;
-        status.tag_present = false;
-        status.uid_len = 0;
+    status.initialized = nfc_is_initialized();
+    with_uid(|uid| {
+        let uid = uid.unwrap_or_default();
+        let len = uid.len().min(status.uid.len());
+        status.tag_present = !uid.is_empty();
+        status.uid_len = len as u8;
         status.uid = [0; 10];
-    }
+        status.uid[..len].copy_from_slice(&uid[..len]);
+    });
 }
 
 /// Check if NFC is initialized
 #[no_mangle]
 pub extern "C" fn nfc_is_initialized() -> bool {
     let guard = NFC_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.initialized
-    } else {
-        false
+    guard.as_ref().is_some_and(|t| t.is_initialized())
+}
+
+/// Get Pico bridge health. Returns false (and leaves `out` untouched) if
+/// there's no bridge.
+#[no_mangle]
+pub extern "C" fn nfc_get_bridge_health(out: *mut BridgeReport) -> bool {
+    if out.is_null() {
+        return false;
+    }
+    match bridge_report() {
+        Some(report) => {
+            unsafe { *out = report };
+            true
+        }
+        None => false,
     }
 }
 
 /// Check if a tag is present
 #[no_mangle]
 pub extern "C" fn nfc_tag_present() -> bool {
-    let guard = NFC_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.tag_present
-    } else {
-        false
-    }
+    with_uid(|uid| uid.is_some())
 }
 
 /// Get tag UID length (0 if no tag)
 #[no_mangle]
 pub extern "C" fn nfc_get_uid_len() -> u8 {
-    let guard = NFC_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        if state.tag_present {
-            state.tag_uid_len
-        } else {
-            0
-        }
-    } else {
-        0
-    }
+    with_uid(|uid| uid.map_or(0, |uid| uid.len() as u8))
 }
 
 /// Copy tag UID to buffer (returns actual length copied)
@@ -231,17 +189,16 @@ pub extern "C" fn nfc_get_uid(buf: *mut u8, buf_len: u8) -> u8 {
         return 0;
     }
 
-    let guard = NFC_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        if state.tag_present && state.tag_uid_len > 0 {
-            let copy_len = std::cmp::min(state.tag_uid_len, buf_len) as usize;
-            unsafe {
-                std::ptr::copy_nonoverlapping(state.tag_uid.as_ptr(), buf, copy_len);
-            }
-            return copy_len as u8;
+    with_uid(|uid| {
+        let Some(uid) = uid else {
+            return 0;
+        };
+        let copy_len = std::cmp::min(uid.len(), buf_len as usize);
+        unsafe {
+            std::ptr::copy_nonoverlapping(uid.as_ptr(), buf, copy_len);
         }
-    }
-    0
+        copy_len as u8
+    })
 }
 
 /// Get UID as hex string (for display)
@@ -252,39 +209,37 @@ pub extern "C" fn nfc_get_uid_hex(buf: *mut u8, buf_len: u8) -> u8 {
         return 0;
     }
 
-    let guard = NFC_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        if state.tag_present && state.tag_uid_len > 0 {
-            // Format: "XX:XX:XX:XX" - each byte is 2 chars + separator
-            let max_bytes = ((buf_len as usize) + 1) / 3;  // Account for : separators
-            let uid_len = std::cmp::min(state.tag_uid_len as usize, max_bytes);
-
-            let mut pos = 0usize;
-            for i in 0..uid_len {
-                if pos + 2 > buf_len as usize {
-                    break;
-                }
-                let hex_chars: [u8; 16] = *b"0123456789ABCDEF";
-                let byte = state.tag_uid[i];
-                unsafe {
-                    *buf.add(pos) = hex_chars[(byte >> 4) as usize];
-                    *buf.add(pos + 1) = hex_chars[(byte & 0x0F) as usize];
-                }
-                pos += 2;
+    with_uid(|uid| {
+        let Some(uid) = uid else {
+            return 0;
+        };
+        // Format: "XX:XX:XX:XX" - each byte is 2 chars + separator
+        let max_bytes = ((buf_len as usize) + 1) / 3;  // Account for : separators
+        let uid_len = std::cmp::min(uid.len(), max_bytes);
+
+        let mut pos = 0usize;
+        for (i, &byte) in uid[..uid_len].iter().enumerate() {
+            if pos + 2 > buf_len as usize {
+                break;
+            }
+            let hex_chars: [u8; 16] = *b"0123456789ABCDEF";
+            unsafe {
+                *buf.add(pos) = hex_chars[(byte >> 4) as usize];
+                *buf.add(pos + 1) = hex_chars[(byte & 0x0F) as usize];
+            }
+            pos += 2;
 
-                // Add separator if not last byte
-                if i < uid_len - 1 && pos < buf_len as usize {
-                    unsafe {
-                        *buf.add(pos) = b':';
-                    }
-                    pos += 1;
+            // Add separator if not last byte
+            if i < uid_len - 1 && pos < buf_len as usize {
+                unsafe {
+                    *buf.add(pos) = b':';
                 }
+                pos += 1;
             }
-
-            return pos as u8;
         }
-    }
-    0
+
+        pos as u8
+    })
 }
 
 // =============================================================================
@@ -1,8 +1,11 @@
 //! OTA Firmware Update Manager
 //!
 //! Implements PSRAM-buffered OTA for single-partition systems:
-//! 1. Download firmware to PSRAM
-//! 2. Validate checksum
+//! 1. Download firmware to PSRAM (streamed to the SD card first if one is
+//!    mounted, which keeps the image at `/sdcard/ota/firmware.bin`)
+//! 2. Validate the image and its ed25519 signature against the public key
+//!    baked in at build time (see build.rs), so a compromised server can't
+//!    push its own firmware
 //! 3. Erase and write to factory partition
 //! 4. Reboot
 //!
@@ -20,7 +23,11 @@ use esp_idf_sys::{
     esp_restart,
 };
 use embedded_svc::http::client::Client as HttpClient;
-use log::info;
+use log::{info, warn};
+use spoolbuddy_core::ota;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
 use std::ptr;
 use std::sync::Mutex;
 
@@ -56,6 +63,9 @@ static CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 static UPDATE_AVAILABLE: Mutex<bool> = Mutex::new(false);
 static UPDATE_VERSION: Mutex<String> = Mutex::new(String::new());
 
+/// OTA signing public key as hex, empty if the build had none
+static OTA_PUBLIC_KEY: &str = include_str!(concat!(env!("OUT_DIR"), "/ota_public_key.txt"));
+
 /// Get current OTA state
 pub fn get_state() -> OtaState {
     OTA_STATE.lock().unwrap().clone()
@@ -154,17 +164,25 @@ pub fn check_for_update(server_url: &str) -> Result<UpdateInfo, String> {
 }
 
 /// Perform OTA update
-/// Downloads firmware to PSRAM, validates, then flashes
+/// Downloads firmware (to the SD card if one is mounted, else PSRAM),
+/// validates, then flashes
 pub fn perform_update(server_url: &str) -> Result<(), String> {
     info!("Starting OTA update from {}", server_url);
 
-    // Step 1: Download to PSRAM
+    // Step 1: Download
     set_state(OtaState::Downloading { progress: 0 });
-    let firmware_data = download_firmware(server_url)?;
+    let (firmware_data, signature) = match crate::sd_card::ota_image_path() {
+        Some(path) => download_to_sd_card(server_url, &path)?,
+        None => download_to_psram(server_url)?,
+    };
 
     // Step 2: Validate
     set_state(OtaState::Validating);
     validate_firmware(&firmware_data)?;
+    if let Err(e) = verify_signature(&firmware_data, signature.as_deref()) {
+        set_state(OtaState::Error(e.clone()));
+        return Err(e);
+    }
 
     // Step 3: Flash
     flash_firmware(&firmware_data)?;
@@ -184,8 +202,47 @@ pub fn perform_update(server_url: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Download firmware to PSRAM buffer
-fn download_firmware(server_url: &str) -> Result<Vec<u8>, String> {
+/// Download firmware to PSRAM buffer, with its signature if the server sent one
+fn download_to_psram(server_url: &str) -> Result<(Vec<u8>, Option<String>), String> {
+    let mut firmware_data = Vec::new();
+    let signature = download_firmware(server_url, |chunk, content_length| {
+        if firmware_data.capacity() == 0 {
+            // Allocate in PSRAM (Vec uses heap which is configured to use PSRAM for large allocs)
+            firmware_data.reserve(content_length);
+        }
+        firmware_data.extend_from_slice(chunk);
+        Ok(())
+    })?;
+    Ok((firmware_data, signature))
+}
+
+/// Download firmware to the SD card, then load it for flashing.
+/// The image is only held in PSRAM for the flash step: flashing overwrites
+/// the running app, so nothing that executes from flash (like the SD/FAT
+/// driver) may run between partition writes.
+fn download_to_sd_card(server_url: &str, path: &Path) -> Result<(Vec<u8>, Option<String>), String> {
+    let mut file = File::create(path)
+        .map_err(|e| format!("Failed to create {}: {:?}", path.display(), e))?;
+    let signature = download_firmware(server_url, |chunk, _| {
+        file.write_all(chunk)
+            .map_err(|e| format!("SD card write failed: {:?}", e))
+    })?;
+    file.flush()
+        .map_err(|e| format!("SD card write failed: {:?}", e))?;
+    drop(file);
+
+    info!("Firmware stored at {}", path.display());
+    let firmware_data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {:?}", path.display(), e))?;
+    Ok((firmware_data, signature))
+}
+
+/// Download firmware, passing each chunk and the expected total size to `sink`.
+/// Returns the image signature header, if any.
+fn download_firmware(
+    server_url: &str,
+    mut sink: impl FnMut(&[u8], usize) -> Result<(), String>,
+) -> Result<Option<String>, String> {
     let url = format!("{}/api/firmware/ota", server_url);
     info!("Downloading firmware from: {}", url);
 
@@ -216,8 +273,8 @@ fn download_firmware(server_url: &str) -> Result<Vec<u8>, String> {
 
     info!("Firmware size: {} bytes", content_length);
 
-    // Allocate in PSRAM (Vec uses heap which is configured to use PSRAM for large allocs)
-    let mut firmware_data = Vec::with_capacity(content_length);
+    let signature = response.header(ota::SIGNATURE_HEADER).map(|s| s.to_string());
+
     // Use heap-allocated buffer to avoid stack overflow
     let mut buf = vec![0u8; 4096]; // 4KB chunks on heap
     let mut total_read = 0usize;
@@ -226,7 +283,11 @@ fn download_firmware(server_url: &str) -> Result<Vec<u8>, String> {
         match response.read(&mut buf) {
             Ok(0) => break,
             Ok(n) => {
-                firmware_data.extend_from_slice(&buf[..n]);
+                crate::crash_reporter::feed_watchdog();
+                if let Err(e) = sink(&buf[..n], content_length) {
+                    set_state(OtaState::Error(e.clone()));
+                    return Err(e);
+                }
                 total_read += n;
 
                 let progress = ((total_read * 100) / content_length).min(100) as u8;
@@ -243,8 +304,8 @@ fn download_firmware(server_url: &str) -> Result<Vec<u8>, String> {
         }
     }
 
-    info!("Download complete: {} bytes", firmware_data.len());
-    Ok(firmware_data)
+    info!("Download complete: {} bytes", total_read);
+    Ok(signature)
 }
 
 /// Validate firmware binary
@@ -271,6 +332,28 @@ fn validate_firmware(data: &[u8]) -> Result<(), String> {
     Ok(())
 }
 
+/// Check the image signature against the baked-in public key
+fn verify_signature(data: &[u8], signature: Option<&str>) -> Result<(), String> {
+    if OTA_PUBLIC_KEY.is_empty() {
+        if cfg!(feature = "unsigned-ota") {
+            warn!("No OTA public key in this build, skipping signature check");
+            return Ok(());
+        }
+        return Err("No OTA public key in this build, refusing update".to_string());
+    }
+    let public_key = ota::parse_public_key(OTA_PUBLIC_KEY)
+        .map_err(|e| e.message().to_string())?;
+
+    let signature = signature.ok_or(ota::SignatureError::Missing)
+        .and_then(ota::parse_signature)
+        .map_err(|e| e.message().to_string())?;
+    ota::verify_image(data, &signature, &public_key)
+        .map_err(|e| e.message().to_string())?;
+
+    info!("Firmware signature verified");
+    Ok(())
+}
+
 /// Flash firmware to factory partition
 fn flash_firmware(data: &[u8]) -> Result<(), String> {
     info!("Flashing {} bytes to factory partition", data.len());
@@ -307,6 +390,7 @@ fn flash_firmware(data: &[u8]) -> Result<(), String> {
         // Erase partition (in 64KB chunks for progress)
         let erase_size = ((data.len() + 0xFFF) / 0x1000) * 0x1000; // Round up to 4KB
         info!("Erasing {} bytes...", erase_size);
+        crate::crash_reporter::feed_watchdog();
 
         let ret = esp_partition_erase_range(partition, 0, erase_size);
         if ret != 0 {
@@ -327,6 +411,7 @@ fn flash_firmware(data: &[u8]) -> Result<(), String> {
 
             let progress = (((i + 1) * 100) / total_chunks).min(100) as u8;
             set_state(OtaState::Flashing { progress });
+            crate::crash_reporter::feed_watchdog();
         }
 
         info!("Flash complete");
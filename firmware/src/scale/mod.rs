@@ -5,6 +5,10 @@
 //!
 //! The NAU7802 is a 24-bit ADC with I2C interface at address 0x2A.
 //!
+//! Readings are smoothed by the driver-independent filter chain in
+//! [`filter`] (median, EMA, zero tracking, temperature compensation), which
+//! lives in `spoolbuddy-core` together with the calibration math.
+//!
 //! Hardware connection via CrowPanel Advance 7.0" I2C-OUT connector:
 //! - IO19 (I2C-OUT Pin 2) -> SDA
 //! - IO20 (I2C-OUT Pin 3) -> SCL
@@ -14,4 +18,5 @@
 #![allow(dead_code)]
 #![allow(unused)]
 
+pub use spoolbuddy_core::weight::filter;
 pub mod nau7802;
@@ -27,9 +27,19 @@
 //!     - BLK: Excitation- (E-)
 //!     - WHT: Signal- (A-)
 //!     - GRN: Signal+ (A+)
+//!
+//! A second load cell can be wired to the NAU7802's channel 2 inputs
+//! (VIN2P/VIN2N). Both cells share the ADC, so the scale manager alternates
+//! between channels, discarding the first conversion after each switch.
 
 use esp_idf_hal::i2c::I2cDriver;
 use log::{info, warn};
+use spoolbuddy_core::hal::Scale;
+use spoolbuddy_core::weight::{self, CalibrationError, CALIBRATION_SAMPLES, CALIBRATION_TRIM};
+
+use super::filter::WeightFilter;
+
+pub use spoolbuddy_core::weight::Calibration;
 
 /// NAU7802 I2C address
 pub const NAU7802_ADDR: u8 = 0x2A;
@@ -95,6 +105,16 @@ pub enum Gain {
     X128 = 7,
 }
 
+/// Analog input channel (CTRL2 CHS bit)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Ch1 = 0,
+    Ch2 = 1,
+}
+
+/// Conversions to discard after switching channels (input settling)
+const CHANNEL_SWITCH_DISCARD: u8 = 1;
+
 /// LDO Voltage settings
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
@@ -109,25 +129,6 @@ pub enum LdoVoltage {
     V4_5 = 0b000,
 }
 
-/// Scale calibration data
-#[derive(Debug, Clone, Copy)]
-pub struct Calibration {
-    /// Zero offset (tare)
-    pub zero_offset: i32,
-    /// Calibration factor (raw units per gram)
-    pub cal_factor: f32,
-}
-
-impl Default for Calibration {
-    fn default() -> Self {
-        Self {
-            zero_offset: 0,
-            // Default calibration factor - needs actual calibration
-            cal_factor: 1000.0,
-        }
-    }
-}
-
 /// NAU7802 Scale driver state
 pub struct Nau7802State {
     /// Calibration data
@@ -138,12 +139,16 @@ pub struct Nau7802State {
     pub last_raw: i32,
     /// Filtered weight in grams
     pub weight_grams: f32,
-    /// Filter alpha (0-1, higher = less filtering)
-    pub filter_alpha: f32,
+    /// Median/EMA filter chain with zero tracking
+    pub filter: WeightFilter,
     /// Weight stability flag
     pub stable: bool,
-    /// Consecutive stable readings counter
-    pub stable_count: u8,
+    /// Input channel this load cell is wired to
+    pub channel: Channel,
+    /// Conversions left to discard after a channel switch
+    pub discard_samples: u8,
+    /// Number of conversions processed (lets callers see when a new sample arrived)
+    pub sample_count: u32,
 }
 
 impl Nau7802State {
@@ -154,9 +159,20 @@ impl Nau7802State {
             initialized: false,
             last_raw: 0,
             weight_grams: 0.0,
-            filter_alpha: 0.25, // Moderate filtering - balance between smoothness and response
+            filter: WeightFilter::default(),
             stable: false,
-            stable_count: 0,
+            channel: Channel::Ch1,
+            discard_samples: 0,
+            sample_count: 0,
+        }
+    }
+
+    /// Create state for a load cell on the given channel of an initialized chip
+    pub fn for_channel(channel: Channel) -> Self {
+        Self {
+            initialized: true,
+            channel,
+            ..Self::new()
         }
     }
 }
@@ -243,6 +259,20 @@ pub fn set_ldo(i2c: &mut I2cDriver<'_>, voltage: LdoVoltage) -> Result<(), Nau78
     write_reg(i2c, reg::CTRL1, new_ctrl1)
 }
 
+/// Switch the ADC to this load cell's channel and restart conversion
+pub fn select_channel(i2c: &mut I2cDriver<'_>, state: &mut Nau7802State) -> Result<(), Nau7802Error> {
+    let ctrl2 = read_reg(i2c, reg::CTRL2)?;
+    let new_ctrl2 = (ctrl2 & 0x7F) | ((state.channel as u8) << 7);
+    write_reg(i2c, reg::CTRL2, new_ctrl2)?;
+
+    // Restart the conversion cycle so the next result is from the new channel
+    let pu_ctrl_val = read_reg(i2c, reg::PU_CTRL)?;
+    write_reg(i2c, reg::PU_CTRL, pu_ctrl_val | pu_ctrl::CS)?;
+
+    state.discard_samples = CHANNEL_SWITCH_DISCARD;
+    Ok(())
+}
+
 /// Check if data is ready
 pub fn data_ready(i2c: &mut I2cDriver<'_>) -> Result<bool, Nau7802Error> {
     let status = read_reg(i2c, reg::PU_CTRL)?;
@@ -251,21 +281,40 @@ pub fn data_ready(i2c: &mut I2cDriver<'_>) -> Result<bool, Nau7802Error> {
 
 /// Read raw ADC value (24-bit signed)
 pub fn read_raw(i2c: &mut I2cDriver<'_>, state: &mut Nau7802State) -> Result<i32, Nau7802Error> {
+    let raw = read_adc(i2c)?;
+    state.last_raw = raw;
+    Ok(raw)
+}
+
+fn read_adc(i2c: &mut I2cDriver<'_>) -> Result<i32, Nau7802Error> {
     // Read 3 bytes of ADC data
-    let b2 = read_reg(i2c, reg::ADCO_B2)? as i32;
-    let b1 = read_reg(i2c, reg::ADCO_B1)? as i32;
-    let b0 = read_reg(i2c, reg::ADCO_B0)? as i32;
+    let b2 = read_reg(i2c, reg::ADCO_B2)? as u32;
+    let b1 = read_reg(i2c, reg::ADCO_B1)? as u32;
+    let b0 = read_reg(i2c, reg::ADCO_B0)? as u32;
 
-    // Combine into 24-bit value
-    let mut raw = (b2 << 16) | (b1 << 8) | b0;
+    // Combine into 24-bit value and sign extend to 32-bit
+    Ok(weight::sign_extend_24((b2 << 16) | (b1 << 8) | b0))
+}
 
-    // Sign extend from 24-bit to 32-bit
-    if (raw & 0x800000) != 0 {
-        raw |= 0xFF000000u32 as i32;
+/// The NAU7802 as a [`Scale`] for the core's sampling helpers
+pub struct Nau7802Adc<'a, 'd> {
+    pub i2c: &'a mut I2cDriver<'d>,
+}
+
+impl Scale for Nau7802Adc<'_, '_> {
+    type Error = Nau7802Error;
+
+    fn data_ready(&mut self) -> Result<bool, Nau7802Error> {
+        data_ready(self.i2c)
     }
 
-    state.last_raw = raw;
-    Ok(raw)
+    fn read_raw(&mut self) -> Result<i32, Nau7802Error> {
+        read_adc(self.i2c)
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        std::thread::sleep(std::time::Duration::from_millis(ms as u64));
+    }
 }
 
 /// Read weight in grams (with filtering and stability detection)
@@ -280,35 +329,19 @@ pub fn read_weight(i2c: &mut I2cDriver<'_>, state: &mut Nau7802State) -> Result<
     }
 
     let raw = read_raw(i2c, state)?;
+    if state.discard_samples > 0 {
+        // First conversion after a channel switch hasn't settled
+        state.discard_samples -= 1;
+        return Ok(state.weight_grams);
+    }
+    state.sample_count = state.sample_count.wrapping_add(1);
 
     // Convert to grams using calibration
-    let weight = (raw - state.calibration.zero_offset) as f32 / state.calibration.cal_factor;
-
-    // Store previous weight for stability check
-    let prev_weight = state.weight_grams;
-
-    // Quick settle: if weight changed significantly (>50g), jump closer to new value
-    let weight_change = (weight - state.weight_grams).abs();
-    if weight_change > 50.0 {
-        // Large change detected - use stronger alpha for faster response
-        state.weight_grams = state.weight_grams * 0.3 + weight * 0.7;
-    } else {
-        // Normal filtering
-        state.weight_grams = state.weight_grams * (1.0 - state.filter_alpha) + weight * state.filter_alpha;
-    }
+    let weight = state.calibration.to_grams(raw);
 
-    // Check stability (within 10g of previous reading)
-    // Increased threshold due to noisy hardware
-    let diff = (state.weight_grams - prev_weight).abs();
-    if diff < 10.0 {
-        state.stable_count = state.stable_count.saturating_add(1);
-        if state.stable_count >= 10 {
-            state.stable = true;
-        }
-    } else {
-        state.stable_count = 0;
-        state.stable = false;
-    }
+    // Median + EMA filtering, zero tracking and stability detection
+    state.weight_grams = state.filter.update(weight);
+    state.stable = state.filter.is_stable();
 
     Ok(state.weight_grams)
 }
@@ -323,47 +356,26 @@ pub fn tare(i2c: &mut I2cDriver<'_>, state: &mut Nau7802State) -> Result<(), Nau
     info!("  Waiting for scale to settle (1 second)...");
     std::thread::sleep(std::time::Duration::from_millis(1000));
 
-    // Take samples for averaging (reduced to avoid watchdog)
-    let samples = 30;
-    let mut readings = [0i32; 30];
-
-    for i in 0..samples {
-        // Wait for data ready
-        while !data_ready(i2c)? {
-            std::thread::sleep(std::time::Duration::from_millis(10));
-        }
-        readings[i] = read_raw(i2c, state)?;
-    }
-
-    info!("  Raw readings: min={}, max={}, range={}",
-          readings.iter().min().unwrap(),
-          readings.iter().max().unwrap(),
-          readings.iter().max().unwrap() - readings.iter().min().unwrap());
+    // Trimmed mean of 30 samples (reduced to avoid watchdog), dropping the
+    // 5 highest and lowest
+    let m = weight::measure(&mut Nau7802Adc { i2c }, CALIBRATION_SAMPLES, CALIBRATION_TRIM)?;
+    state.last_raw = m.mean;
 
-    // Sort readings for trimmed mean (discard highest and lowest 5 values)
-    readings.sort();
-    let trim = 5;
-    let trimmed = &readings[trim..samples - trim];
-
-    // Calculate average of trimmed values
-    let sum: i64 = trimmed.iter().map(|&x| x as i64).sum();
-    let count = trimmed.len() as i64;
-
-    let new_zero_offset = (sum / count) as i32;
-    info!("  NEW zero_offset: {} (from {} middle samples)", new_zero_offset, count);
+    info!("  Raw readings: min={}, max={}, range={}", m.min, m.max, m.range());
+    info!("  NEW zero_offset: {} (from {} middle samples)", m.mean, m.count);
 
     // Sanity check: range shouldn't be too extreme
-    let range = readings.iter().max().unwrap() - readings.iter().min().unwrap();
-    if range > 100000 {
-        warn!("  Warning: readings are very noisy (range={}), tare may be inaccurate", range);
+    if m.range() > 100000 {
+        warn!("  Warning: readings are very noisy (range={}), tare may be inaccurate", m.range());
     }
 
-    state.calibration.zero_offset = new_zero_offset;
+    state.calibration.zero_offset = m.mean;
 
-    // Reset filtered state
+    // Reset filtered state (new zero point, so drop zero tracking too)
+    state.filter.reset_zero_tracking();
+    state.filter.reset(0.0);
     state.weight_grams = 0.0;
     state.stable = false;
-    state.stable_count = 0;
 
     info!("=== TARE COMPLETE ===");
     info!("  Final zero_offset: {}", state.calibration.zero_offset);
@@ -382,72 +394,49 @@ pub fn calibrate(i2c: &mut I2cDriver<'_>, state: &mut Nau7802State, known_weight
     info!("  Waiting for scale to settle (1 second)...");
     std::thread::sleep(std::time::Duration::from_millis(1000));
 
-    // Take samples for averaging (reduced to avoid watchdog)
-    let samples = 30;
-    let mut readings = [0i32; 30];
-
-    for i in 0..samples {
-        while !data_ready(i2c)? {
-            std::thread::sleep(std::time::Duration::from_millis(10));
-        }
-        readings[i] = read_raw(i2c, state)?;
-    }
-
-    info!("  Raw readings: min={}, max={}, range={}",
-          readings.iter().min().unwrap(),
-          readings.iter().max().unwrap(),
-          readings.iter().max().unwrap() - readings.iter().min().unwrap());
-
-    // Sort readings for trimmed mean (discard highest and lowest 5 values)
-    readings.sort();
-    let trim = 5;
-    let trimmed = &readings[trim..samples - trim];
+    // Trimmed mean of 30 samples (reduced to avoid watchdog)
+    let m = weight::measure(&mut Nau7802Adc { i2c }, CALIBRATION_SAMPLES, CALIBRATION_TRIM)?;
+    state.last_raw = m.mean;
+    let avg_raw = m.mean;
 
-    // Calculate average of trimmed values
-    let sum: i64 = trimmed.iter().map(|&x| x as i64).sum();
-    let count = trimmed.len() as i64;
-    let avg_raw = (sum / count) as i32;
-
-    info!("  Average raw value (trimmed): {} (from {} middle samples)", avg_raw, count);
+    info!("  Raw readings: min={}, max={}, range={}", m.min, m.max, m.range());
+    info!("  Average raw value (trimmed): {} (from {} middle samples)", avg_raw, m.count);
 
     let delta = avg_raw - state.calibration.zero_offset;
     info!("  Delta from zero: {} (avg_raw {} - zero_offset {})",
           delta, avg_raw, state.calibration.zero_offset);
 
-    // Delta must be positive and significant
-    // A 797g weight should produce ~195,000 units of delta with proper calibration
-    // Require at least 10,000 to ensure meaningful signal
-    if delta < 10000 {
-        if delta < 0 {
+    // Delta must be positive and significant, and the resulting factor
+    // plausible (50-500 for a typical 5kg load cell)
+    let new_cal_factor = match weight::calibration_factor(delta, known_weight_grams) {
+        Ok(factor) => factor,
+        Err(CalibrationError::NegativeDelta(delta)) => {
             warn!("  Calibration FAILED: negative delta ({}) - weight decreased readings!", delta);
             warn!("  This usually means: load cell wiring issue, defective load cell, or not mounted correctly");
-        } else {
+            return Err(Nau7802Error::CalibrationFailed);
+        }
+        Err(CalibrationError::DeltaTooSmall(delta)) => {
             warn!("  Calibration FAILED: delta too small ({}) - no significant weight detected", delta);
+            return Err(Nau7802Error::CalibrationFailed);
         }
-        return Err(Nau7802Error::CalibrationFailed);
-    }
-
-    let new_cal_factor = delta as f32 / known_weight_grams;
+        Err(CalibrationError::FactorOutOfRange(factor)) => {
+            warn!("  Calibration FAILED: cal_factor {} is out of reasonable range (10-2000)", factor);
+            return Err(Nau7802Error::CalibrationFailed);
+        }
+    };
     info!("  NEW cal_factor: {} = {} / {}", new_cal_factor, delta, known_weight_grams);
 
-    // Sanity check cal_factor - should be reasonable (50-500 for typical 5kg load cell)
-    if new_cal_factor < 10.0 || new_cal_factor > 2000.0 {
-        warn!("  Calibration FAILED: cal_factor {} is out of reasonable range (10-2000)", new_cal_factor);
-        return Err(Nau7802Error::CalibrationFailed);
-    }
-
     state.calibration.cal_factor = new_cal_factor;
 
     // Reset filtered state
+    state.filter.reset(known_weight_grams);
     state.weight_grams = known_weight_grams;
     state.stable = false;
-    state.stable_count = 0;
 
     info!("=== CALIBRATION COMPLETE ===");
     info!("  Final zero_offset: {}", state.calibration.zero_offset);
     info!("  Final cal_factor: {}", state.calibration.cal_factor);
-    info!("  Expected weight with current raw: {} grams",
-          (avg_raw - state.calibration.zero_offset) as f32 / state.calibration.cal_factor);
+    info!("  Expected weight with current raw: {} grams", state.calibration.to_grams(avg_raw));
     Ok(())
 }
 
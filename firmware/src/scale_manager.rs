@@ -2,21 +2,41 @@
 //!
 //! Provides FFI functions for the C UI code to access scale data.
 //! Uses shared I2C bus.
-//! Calibration data is persisted to NVS flash.
+//! Calibration data and filter settings are persisted to NVS flash.
+//!
+//! Supports up to two load cells on the NAU7802's two input channels. With
+//! two channels enabled the ADC alternates between them, and each channel
+//! has its own tare and calibration. The plain FFI functions (`scale_tare`,
+//! `scale_get_weight`, ...) act on the first channel.
 
 use esp_idf_svc::nvs::{EspDefaultNvsPartition, EspNvs};
 use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 
-use crate::scale::nau7802::{self, Calibration, Nau7802State};
-use crate::shared_i2c;
+use crate::scale::filter::FilterConfig;
+use crate::scale::nau7802::{self, Calibration, Channel, Nau7802State};
+use crate::shared_i2c::{self, BusClient};
 
 /// NVS namespace for scale calibration
 const NVS_NAMESPACE: &str = "scale";
 const NVS_KEY_CALIBRATION: &str = "cal";
+const NVS_KEY_CALIBRATION_2: &str = "cal2";
+const NVS_KEY_FILTER: &str = "filter";
+const NVS_KEY_CHANNELS: &str = "channels";
+
+/// Maximum number of load cells (NAU7802 CH1 + CH2)
+pub const MAX_SCALE_CHANNELS: usize = 2;
+
+/// State for all enabled load cell channels
+struct Scales {
+    channels: Vec<Nau7802State>,
+    /// Channel the ADC is currently converting
+    active: usize,
+}
 
 /// Global scale state protected by mutex
-static SCALE_STATE: Mutex<Option<Nau7802State>> = Mutex::new(None);
+static SCALE_STATE: Mutex<Option<Scales>> = Mutex::new(None);
 
 /// Global NVS partition for calibration persistence
 static NVS_PARTITION: Mutex<Option<EspDefaultNvsPartition>> = Mutex::new(None);
@@ -40,23 +60,86 @@ pub fn init_nvs(nvs: Option<EspDefaultNvsPartition>) {
 }
 
 /// Initialize the scale manager with state (uses shared I2C)
-pub fn init_scale_manager(mut state: Nau7802State) {
-    // Try to load saved calibration from NVS
-    if let Some(calibration) = load_calibration_from_nvs() {
-        info!("Loaded saved calibration: zero_offset={}, cal_factor={}",
-              calibration.zero_offset, calibration.cal_factor);
-        state.calibration = calibration;
-    } else {
-        info!("No saved calibration found, using defaults");
+pub fn init_scale_manager(state: Nau7802State) {
+    let filter_config = load_filter_config_from_nvs();
+    if let Some(config) = filter_config {
+        info!("Loaded filter settings: median={}, alpha={}, zero_tracking={}, temp_coeff={}",
+              config.median_window, config.ema_alpha, config.zero_tracking, config.temp_coeff_grams_per_c);
+    }
+
+    let channel_count = load_channel_count_from_nvs();
+    let mut channels = vec![state];
+    if channel_count > 1 {
+        channels.push(Nau7802State::for_channel(Channel::Ch2));
+    }
+
+    for (index, state) in channels.iter_mut().enumerate() {
+        // Try to load saved calibration from NVS
+        if let Some(calibration) = load_calibration_from_nvs(index) {
+            info!("Loaded saved calibration (channel {}): zero_offset={}, cal_factor={}",
+                  index, calibration.zero_offset, calibration.cal_factor);
+            state.calibration = calibration;
+        } else {
+            info!("No saved calibration found for channel {}, using defaults", index);
+        }
+        if let Some(config) = filter_config {
+            state.filter.set_config(config);
+        }
     }
 
     let mut guard = SCALE_STATE.lock().unwrap();
-    *guard = Some(state);
-    info!("Scale manager initialized");
+    *guard = Some(Scales { channels, active: 0 });
+    info!("Scale manager initialized ({} channel(s))", channel_count);
+}
+
+/// NVS key holding a channel's calibration
+fn calibration_key(channel: usize) -> &'static str {
+    if channel == 0 {
+        NVS_KEY_CALIBRATION
+    } else {
+        NVS_KEY_CALIBRATION_2
+    }
+}
+
+/// Load the number of enabled load cell channels (defaults to 1)
+fn load_channel_count_from_nvs() -> usize {
+    let nvs_guard = NVS_PARTITION.lock().unwrap();
+    let Some(nvs_partition) = nvs_guard.as_ref() else {
+        return 1;
+    };
+    match EspNvs::new(nvs_partition.clone(), NVS_NAMESPACE, true) {
+        Ok(nvs) => match nvs.get_u8(NVS_KEY_CHANNELS) {
+            Ok(Some(count)) => (count as usize).clamp(1, MAX_SCALE_CHANNELS),
+            _ => 1,
+        },
+        Err(_) => 1,
+    }
+}
+
+/// Save the number of enabled load cell channels
+fn save_channel_count_to_nvs(count: usize) -> bool {
+    let nvs_guard = NVS_PARTITION.lock().unwrap();
+    let Some(nvs_partition) = nvs_guard.as_ref() else {
+        warn!("No NVS partition available for saving channel count");
+        return false;
+    };
+    match EspNvs::new(nvs_partition.clone(), NVS_NAMESPACE, true) {
+        Ok(nvs) => {
+            if let Err(e) = nvs.set_u8(NVS_KEY_CHANNELS, count as u8) {
+                warn!("Failed to save channel count to NVS: {:?}", e);
+                return false;
+            }
+            true
+        }
+        Err(e) => {
+            warn!("Failed to open NVS namespace for scale: {:?}", e);
+            false
+        }
+    }
 }
 
 /// Load calibration data from NVS (8 bytes: i32 zero_offset + i32 cal_factor_x1000)
-fn load_calibration_from_nvs() -> Option<Calibration> {
+fn load_calibration_from_nvs(channel: usize) -> Option<Calibration> {
     let nvs_guard = NVS_PARTITION.lock().unwrap();
     let nvs_partition = nvs_guard.as_ref()?;
 
@@ -70,7 +153,7 @@ fn load_calibration_from_nvs() -> Option<Calibration> {
 
     // Read calibration blob
     let mut buf = [0u8; 8];
-    match nvs.get_blob(NVS_KEY_CALIBRATION, &mut buf) {
+    match nvs.get_blob(calibration_key(channel), &mut buf) {
         Ok(Some(_)) => {
             // Parse the calibration data
             let zero_offset = i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
@@ -91,7 +174,7 @@ fn load_calibration_from_nvs() -> Option<Calibration> {
 }
 
 /// Save calibration data to NVS
-fn save_calibration_to_nvs(calibration: &Calibration) -> bool {
+fn save_calibration_to_nvs(channel: usize, calibration: &Calibration) -> bool {
     let nvs_guard = NVS_PARTITION.lock().unwrap();
     let Some(nvs_partition) = nvs_guard.as_ref() else {
         warn!("No NVS partition available for saving calibration");
@@ -113,34 +196,136 @@ fn save_calibration_to_nvs(calibration: &Calibration) -> bool {
     buf[4..8].copy_from_slice(&cal_factor_x1000.to_le_bytes());
 
     // Save as blob
-    if let Err(e) = nvs.set_blob(NVS_KEY_CALIBRATION, &buf) {
+    if let Err(e) = nvs.set_blob(calibration_key(channel), &buf) {
         warn!("Failed to save calibration to NVS: {:?}", e);
         return false;
     }
 
-    info!("Calibration saved to NVS: zero_offset={}, cal_factor={}",
-          calibration.zero_offset, calibration.cal_factor);
+    info!("Calibration saved to NVS (channel {}): zero_offset={}, cal_factor={}",
+          channel, calibration.zero_offset, calibration.cal_factor);
+    true
+}
+
+/// Load filter settings from NVS (14 bytes: u8 median_window, f32 ema_alpha,
+/// u8 zero_tracking, f32 temp_coeff, f32 reference_temp)
+fn load_filter_config_from_nvs() -> Option<FilterConfig> {
+    let nvs_guard = NVS_PARTITION.lock().unwrap();
+    let nvs_partition = nvs_guard.as_ref()?;
+    let nvs = EspNvs::new(nvs_partition.clone(), NVS_NAMESPACE, true).ok()?;
+
+    let mut buf = [0u8; 14];
+    match nvs.get_blob(NVS_KEY_FILTER, &mut buf) {
+        Ok(Some(_)) => {
+            let f32_at = |i: usize| f32::from_le_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+            Some(FilterConfig {
+                median_window: buf[0] as usize,
+                ema_alpha: f32_at(1),
+                zero_tracking: buf[5] != 0,
+                temp_coeff_grams_per_c: f32_at(6),
+                reference_temp_c: f32_at(10),
+                ..FilterConfig::default()
+            })
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to read filter settings from NVS: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Save filter settings to NVS
+fn save_filter_config_to_nvs(config: &FilterConfig) -> bool {
+    let nvs_guard = NVS_PARTITION.lock().unwrap();
+    let Some(nvs_partition) = nvs_guard.as_ref() else {
+        warn!("No NVS partition available for saving filter settings");
+        return false;
+    };
+    let nvs = match EspNvs::new(nvs_partition.clone(), NVS_NAMESPACE, true) {
+        Ok(nvs) => nvs,
+        Err(e) => {
+            warn!("Failed to open NVS namespace for scale: {:?}", e);
+            return false;
+        }
+    };
+
+    let mut buf = [0u8; 14];
+    buf[0] = config.median_window as u8;
+    buf[1..5].copy_from_slice(&config.ema_alpha.to_le_bytes());
+    buf[5] = config.zero_tracking as u8;
+    buf[6..10].copy_from_slice(&config.temp_coeff_grams_per_c.to_le_bytes());
+    buf[10..14].copy_from_slice(&config.reference_temp_c.to_le_bytes());
+
+    if let Err(e) = nvs.set_blob(NVS_KEY_FILTER, &buf) {
+        warn!("Failed to save filter settings to NVS: {:?}", e);
+        return false;
+    }
     true
 }
 
+/// Apply a change to the filter settings (all channels) and persist them
+fn update_filter_config(f: impl FnOnce(&mut FilterConfig)) -> i32 {
+    let mut guard = SCALE_STATE.lock().unwrap();
+    let Some(ref mut scales) = *guard else {
+        return -1;
+    };
+    let mut config = scales.channels[0].filter.config;
+    f(&mut config);
+    for state in scales.channels.iter_mut() {
+        state.filter.set_config(config);
+    }
+    let config = scales.channels[0].filter.config;
+    drop(guard);
+
+    if save_filter_config_to_nvs(&config) {
+        0
+    } else {
+        -1
+    }
+}
+
 /// Counter for rate-limiting error logs
 static ERROR_LOG_COUNTER: Mutex<u32> = Mutex::new(0);
 
+/// Minimum weight on the main channel that counts as a spool for the
+/// "weight stable" beep (ignores drift on an empty platform)
+const SPOOL_PRESENT_GRAMS: f32 = 20.0;
+
+/// Whether the beep for the current spool has been played
+static WEIGHT_SETTLED: AtomicBool = AtomicBool::new(false);
+
 /// Poll the scale (call from main loop)
 pub fn poll_scale() {
     let mut guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref mut state) = *guard {
-        if state.initialized {
-            let result = shared_i2c::with_i2c(|i2c| {
-                nau7802::read_weight(i2c, state)
+    if let Some(ref mut scales) = *guard {
+        if scales.channels[scales.active].initialized {
+            let result = shared_i2c::with_i2c(BusClient::Scale, |i2c| {
+                let before = scales.channels[scales.active].sample_count;
+                let weight = nau7802::read_weight(i2c, &mut scales.channels[scales.active])?;
+
+                // With two load cells, move on to the other channel once this one has a new sample
+                if scales.channels.len() > 1 && scales.channels[scales.active].sample_count != before {
+                    scales.active = (scales.active + 1) % scales.channels.len();
+                    nau7802::select_channel(i2c, &mut scales.channels[scales.active])?;
+                }
+                Ok::<_, nau7802::Nau7802Error>(weight)
             });
             match result {
                 Some(Ok(_)) => {
                     // Reset error counter on success
                     let mut counter = ERROR_LOG_COUNTER.lock().unwrap();
                     *counter = 0;
+
+                    // Beep once when a spool settles on the platform
+                    let main = &scales.channels[0];
+                    let settled = main.stable && main.weight_grams > SPOOL_PRESENT_GRAMS;
+                    let was_settled = WEIGHT_SETTLED.swap(settled, Ordering::Relaxed);
+                    if settled && !was_settled {
+                        crate::buzzer::play(crate::buzzer::Pattern::WeightStable);
+                    }
                 }
                 Some(Err(e)) => {
+                    shared_i2c::record_error(BusClient::Scale);
                     let mut counter = ERROR_LOG_COUNTER.lock().unwrap();
                     *counter += 1;
                     // Log first error and then every 50th error
@@ -164,6 +349,12 @@ pub fn poll_scale() {
 // C-callable FFI functions
 // =============================================================================
 
+/// Run `f` on a channel's state (None if the scale or channel doesn't exist)
+fn with_channel<R>(channel: u8, f: impl FnOnce(&mut Nau7802State) -> R) -> Option<R> {
+    let mut guard = SCALE_STATE.lock().unwrap();
+    guard.as_mut()?.channels.get_mut(channel as usize).map(f)
+}
+
 /// Get current scale status
 #[no_mangle]
 pub extern "C" fn scale_get_status(status: *mut ScaleStatus) {
@@ -171,17 +362,17 @@ pub extern "C" fn scale_get_status(status: *mut ScaleStatus) {
         return;
     }
 
-    let guard = SCALE_STATE.lock().unwrap();
     let status = unsafe { &mut *status };
 
-    if let Some(ref state) = *guard {
+    let found = with_channel(0, |state| {
         status.initialized = state.initialized;
         status.weight_grams = state.weight_grams;
         status.raw_value = state.last_raw;
         status.stable = state.stable;
         status.tare_offset = state.calibration.zero_offset;
         status.cal_factor = state.calibration.cal_factor;
-    } else {
+    });
+    if found.is_none() {
         status.initialized = false;
         status.weight_grams = 0.0;
         status.raw_value = 0;
@@ -191,129 +382,246 @@ pub extern "C" fn scale_get_status(status: *mut ScaleStatus) {
     }
 }
 
-/// Get current weight in grams
+/// Get number of enabled load cell channels (0 if no scale)
 #[no_mangle]
-pub extern "C" fn scale_get_weight() -> f32 {
+pub extern "C" fn scale_get_channel_count() -> u8 {
     let guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.weight_grams
+    guard.as_ref().map_or(0, |scales| scales.channels.len() as u8)
+}
+
+/// Enable one or two load cell channels (persisted)
+#[no_mangle]
+pub extern "C" fn scale_set_channel_count(count: u8) -> i32 {
+    let count = count as usize;
+    if count == 0 || count > MAX_SCALE_CHANNELS {
+        return -1;
+    }
+
+    let mut guard = SCALE_STATE.lock().unwrap();
+    let Some(ref mut scales) = *guard else {
+        return -1;
+    };
+    if count > scales.channels.len() {
+        let mut state = Nau7802State::for_channel(Channel::Ch2);
+        if let Some(calibration) = load_calibration_from_nvs(1) {
+            state.calibration = calibration;
+        }
+        state.filter.set_config(scales.channels[0].filter.config);
+        scales.channels.push(state);
+    } else if count < scales.channels.len() {
+        scales.channels.truncate(count);
+        if scales.active != 0 {
+            // Put the ADC back on the first channel
+            scales.active = 0;
+            let _ = shared_i2c::with_i2c(BusClient::Scale, |i2c| {
+                nau7802::select_channel(i2c, &mut scales.channels[0])
+            });
+        }
+    }
+    drop(guard);
+
+    info!("Scale channels set to {}", count);
+    if save_channel_count_to_nvs(count) {
+        0
     } else {
-        0.0
+        -1
     }
 }
 
+/// Get current weight in grams
+#[no_mangle]
+pub extern "C" fn scale_get_weight() -> f32 {
+    scale_get_weight_ch(0)
+}
+
+/// Get current weight of a channel in grams
+#[no_mangle]
+pub extern "C" fn scale_get_weight_ch(channel: u8) -> f32 {
+    with_channel(channel, |state| state.weight_grams).unwrap_or(0.0)
+}
+
+/// Raw ADC value of every channel, for diagnostics
+pub fn raw_readings() -> Vec<i32> {
+    let guard = SCALE_STATE.lock().unwrap();
+    guard.as_ref().map_or_else(Vec::new, |scales| scales.channels.iter().map(|c| c.last_raw).collect())
+}
+
 /// Get raw ADC value
 #[no_mangle]
 pub extern "C" fn scale_get_raw() -> i32 {
-    let guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.last_raw
-    } else {
-        0
-    }
+    with_channel(0, |state| state.last_raw).unwrap_or(0)
 }
 
 /// Check if scale is initialized
 #[no_mangle]
 pub extern "C" fn scale_is_initialized() -> bool {
-    let guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.initialized
-    } else {
-        false
-    }
+    with_channel(0, |state| state.initialized).unwrap_or(false)
 }
 
 /// Check if weight is stable
 #[no_mangle]
 pub extern "C" fn scale_is_stable() -> bool {
-    let guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.stable
-    } else {
-        false
-    }
+    scale_is_stable_ch(0)
+}
+
+/// Check if a channel's weight is stable
+#[no_mangle]
+pub extern "C" fn scale_is_stable_ch(channel: u8) -> bool {
+    with_channel(channel, |state| state.stable).unwrap_or(false)
 }
 
 /// Tare the scale (set current weight as zero)
 #[no_mangle]
 pub extern "C" fn scale_tare() -> i32 {
+    scale_tare_ch(0)
+}
+
+/// Tare one channel (set its current weight as zero)
+#[no_mangle]
+pub extern "C" fn scale_tare_ch(channel: u8) -> i32 {
+    let index = channel as usize;
     let mut guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref mut state) = *guard {
-        let result = shared_i2c::with_i2c(|i2c| {
-            nau7802::tare(i2c, state)
-        });
-        match result {
-            Some(Ok(())) => {
-                // Save calibration (includes tare offset) to NVS
-                save_calibration_to_nvs(&state.calibration);
-                0
-            }
-            _ => -1,
+    let Some(ref mut scales) = *guard else {
+        return -1;
+    };
+    if index >= scales.channels.len() {
+        return -1;
+    }
+
+    let result = shared_i2c::with_i2c(BusClient::Scale, |i2c| {
+        if scales.active != index {
+            nau7802::select_channel(i2c, &mut scales.channels[index])?;
+            scales.active = index;
         }
-    } else {
-        -1
+        nau7802::tare(i2c, &mut scales.channels[index])
+    });
+    match result {
+        Some(Ok(())) => {
+            // Save calibration (includes tare offset) to NVS
+            save_calibration_to_nvs(index, &scales.channels[index].calibration);
+            0
+        }
+        _ => -1,
     }
 }
 
 /// Calibrate with a known weight (in grams)
 #[no_mangle]
 pub extern "C" fn scale_calibrate(known_weight_grams: f32) -> i32 {
+    scale_calibrate_ch(0, known_weight_grams)
+}
+
+/// Calibrate one channel with a known weight (in grams)
+#[no_mangle]
+pub extern "C" fn scale_calibrate_ch(channel: u8, known_weight_grams: f32) -> i32 {
+    let index = channel as usize;
     let mut guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref mut state) = *guard {
-        let result = shared_i2c::with_i2c(|i2c| {
-            nau7802::calibrate(i2c, state, known_weight_grams)
-        });
-        match result {
-            Some(Ok(())) => {
-                // Save calibration to NVS for persistence across restarts
-                save_calibration_to_nvs(&state.calibration);
-                0
-            }
-            _ => -1,
+    let Some(ref mut scales) = *guard else {
+        return -1;
+    };
+    if index >= scales.channels.len() {
+        return -1;
+    }
+
+    let result = shared_i2c::with_i2c(BusClient::Scale, |i2c| {
+        if scales.active != index {
+            nau7802::select_channel(i2c, &mut scales.channels[index])?;
+            scales.active = index;
         }
-    } else {
-        -1
+        nau7802::calibrate(i2c, &mut scales.channels[index], known_weight_grams)
+    });
+    match result {
+        Some(Ok(())) => {
+            // Save calibration to NVS for persistence across restarts
+            save_calibration_to_nvs(index, &scales.channels[index].calibration);
+            0
+        }
+        _ => -1,
     }
 }
 
 /// Reset calibration to defaults
 #[no_mangle]
 pub extern "C" fn scale_reset_calibration() -> i32 {
-    info!("Resetting scale calibration to defaults...");
-    let mut guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref mut state) = *guard {
+    scale_reset_calibration_ch(0)
+}
+
+/// Reset one channel's calibration to defaults
+#[no_mangle]
+pub extern "C" fn scale_reset_calibration_ch(channel: u8) -> i32 {
+    info!("Resetting scale calibration (channel {}) to defaults...", channel);
+    let result = with_channel(channel, |state| {
         // Reset to default calibration
         state.calibration = Calibration::default();
+        state.filter.reset_zero_tracking();
+        state.filter.reset(0.0);
         state.weight_grams = 0.0;
         state.stable = false;
-        state.stable_count = 0;
-
-        // Clear saved calibration from NVS
-        let nvs_guard = NVS_PARTITION.lock().unwrap();
-        if let Some(ref nvs_partition) = *nvs_guard {
-            if let Ok(nvs) = EspNvs::new(nvs_partition.clone(), NVS_NAMESPACE, true) {
-                let _ = nvs.remove(NVS_KEY_CALIBRATION);
-            }
-        }
-        drop(nvs_guard);
 
         info!("Scale calibration reset: zero_offset={}, cal_factor={}",
               state.calibration.zero_offset, state.calibration.cal_factor);
-        0
-    } else {
+    });
+    if result.is_none() {
         warn!("Scale reset failed: no state");
-        -1
+        return -1;
+    }
+
+    // Clear saved calibration from NVS
+    let nvs_guard = NVS_PARTITION.lock().unwrap();
+    if let Some(ref nvs_partition) = *nvs_guard {
+        if let Ok(nvs) = EspNvs::new(nvs_partition.clone(), NVS_NAMESPACE, true) {
+            let _ = nvs.remove(calibration_key(channel as usize));
+        }
     }
+    0
 }
 
 /// Get tare offset
 #[no_mangle]
 pub extern "C" fn scale_get_tare_offset() -> i32 {
-    let guard = SCALE_STATE.lock().unwrap();
-    if let Some(ref state) = *guard {
-        state.calibration.zero_offset
-    } else {
-        0
+    with_channel(0, |state| state.calibration.zero_offset).unwrap_or(0)
+}
+
+/// Configure the median window (samples, 1 = off) and EMA alpha (0-1)
+#[no_mangle]
+pub extern "C" fn scale_set_filter(median_window: u8, ema_alpha: f32) -> i32 {
+    if !(ema_alpha > 0.0 && ema_alpha <= 1.0) {
+        return -1;
+    }
+    update_filter_config(|config| {
+        config.median_window = median_window as usize;
+        config.ema_alpha = ema_alpha;
+    })
+}
+
+/// Enable or disable automatic zero tracking
+#[no_mangle]
+pub extern "C" fn scale_set_zero_tracking(enabled: bool) -> i32 {
+    update_filter_config(|config| config.zero_tracking = enabled)
+}
+
+/// Configure temperature drift compensation (grams per °C, 0 = off)
+#[no_mangle]
+pub extern "C" fn scale_set_temp_compensation(grams_per_c: f32, reference_temp_c: f32) -> i32 {
+    update_filter_config(|config| {
+        config.temp_coeff_grams_per_c = grams_per_c;
+        config.reference_temp_c = reference_temp_c;
+    })
+}
+
+/// Feed the current temperature for drift compensation
+#[no_mangle]
+pub extern "C" fn scale_set_temperature(temp_c: f32) {
+    let mut guard = SCALE_STATE.lock().unwrap();
+    if let Some(ref mut scales) = *guard {
+        for state in scales.channels.iter_mut() {
+            state.filter.set_temperature(temp_c);
+        }
     }
 }
+
+/// Get the correction currently applied by zero tracking (grams)
+#[no_mangle]
+pub extern "C" fn scale_get_zero_drift() -> f32 {
+    with_channel(0, |state| state.filter.zero_drift()).unwrap_or(0.0)
+}
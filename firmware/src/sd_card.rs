@@ -0,0 +1,337 @@
+//! SD card storage
+//!
+//! The microSD slot has its own SPI bus (SCK=GPIO12, MOSI=GPIO11,
+//! MISO=GPIO13). Its chip select is not a GPIO but EXIO4 of the CH422G IO
+//! expander on the touch I2C bus (I2C0, driven by the C display driver). The
+//! card is the only device on its bus, so CS is pulled low once through the
+//! expander and the card is mounted without a CS pin.
+//!
+//! Boards without the CH422G (e.g. the CrowPanel, which uses GPIO11-13 for
+//! the RGB panel) don't answer on the expander address, and SD init is
+//! skipped before any SPI pin is touched.
+//!
+//! Layout under `/sdcard`:
+//! - `assets/` - UI assets (fonts, icons, spool images) mirrored from the
+//!   server. LVGL reads them through its stdio driver as `S:assets/<name>`.
+//! - `events/pending.jsonl` - backend requests queued while offline
+//! - `ota/firmware.bin` - last downloaded OTA image
+
+#![allow(dead_code)]
+
+use esp_idf_hal::gpio::{AnyIOPin, InputPin, OutputPin};
+use esp_idf_hal::peripheral::Peripheral;
+use esp_idf_hal::spi::{Dma, SpiAnyPins, SpiDriver, SpiDriverConfig};
+use esp_idf_svc::fs::fatfs::Fatfs;
+use esp_idf_svc::io::vfs::MountedFatfs;
+use esp_idf_svc::sd::{spi::SdSpiHostDriver, SdCardConfiguration, SdCardDriver};
+use esp_idf_sys::{esp_vfs_fat_info, EspError, ESP_OK};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_char, CString};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::io_expander;
+
+/// VFS mount point
+pub const MOUNT_POINT: &str = "/sdcard";
+const MAX_OPEN_FILES: usize = 4;
+
+const ASSETS_DIR: &str = "/sdcard/assets";
+const EVENTS_DIR: &str = "/sdcard/events";
+const OTA_DIR: &str = "/sdcard/ota";
+const PENDING_EVENTS: &str = "/sdcard/events/pending.jsonl";
+const OTA_IMAGE: &str = "/sdcard/ota/firmware.bin";
+
+/// Oldest events are dropped beyond this many
+const MAX_QUEUED_EVENTS: usize = 200;
+
+static MOUNTED: AtomicBool = AtomicBool::new(false);
+
+/// Serializes access to the event queue file
+static EVENTS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Backend request queued while the server was unreachable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedEvent {
+    /// API path, e.g. "/api/spools"
+    pub path: String,
+    /// JSON request body (sent as POST)
+    pub body: String,
+}
+
+/// Select the card through the CH422G and mount it at `/sdcard`.
+/// Call after the display driver has set up I2C0.
+pub fn init<S: SpiAnyPins>(
+    spi: impl Peripheral<P = S> + 'static,
+    sclk: impl Peripheral<P = impl OutputPin> + 'static,
+    mosi: impl Peripheral<P = impl OutputPin> + 'static,
+    miso: impl Peripheral<P = impl InputPin> + 'static,
+) -> bool {
+    if !select_card() {
+        info!("No CH422G IO expander, SD card not available on this board");
+        return false;
+    }
+
+    if let Err(e) = mount(spi, sclk, mosi, miso) {
+        warn!("SD card mount failed (no card inserted?): {:?}", e);
+        return false;
+    }
+
+    for dir in [ASSETS_DIR, EVENTS_DIR, OTA_DIR] {
+        if let Err(e) = fs::create_dir_all(dir) {
+            warn!("Failed to create {}: {:?}", dir, e);
+        }
+    }
+
+    MOUNTED.store(true, Ordering::Relaxed);
+    match space() {
+        Some((total, free)) => info!("SD card mounted at {} ({} MB free of {} MB)", MOUNT_POINT, free >> 20, total >> 20),
+        None => info!("SD card mounted at {}", MOUNT_POINT),
+    }
+    true
+}
+
+/// Pull SD_CS low via the IO expander. Returns false if there's no CH422G.
+fn select_card() -> bool {
+    io_expander::set_output(io_expander::EXIO_SD_CS, false)
+}
+
+fn mount<S: SpiAnyPins>(
+    spi: impl Peripheral<P = S> + 'static,
+    sclk: impl Peripheral<P = impl OutputPin> + 'static,
+    mosi: impl Peripheral<P = impl OutputPin> + 'static,
+    miso: impl Peripheral<P = impl InputPin> + 'static,
+) -> Result<(), EspError> {
+    let spi_driver = SpiDriver::new(
+        spi,
+        sclk,
+        mosi,
+        Some(miso),
+        &SpiDriverConfig::new().dma(Dma::Auto(4096)),
+    )?;
+
+    // No CS pin - the card is selected through the IO expander
+    let host = SdSpiHostDriver::new(
+        spi_driver,
+        AnyIOPin::none(),
+        AnyIOPin::none(),
+        AnyIOPin::none(),
+        AnyIOPin::none(),
+        None,
+    )?;
+    let card = SdCardDriver::new_spi(host, &SdCardConfiguration::new())?;
+    let mounted = MountedFatfs::mount(Fatfs::new_sdcard(0, card)?, MOUNT_POINT, MAX_OPEN_FILES)?;
+
+    // Stay mounted for the lifetime of the firmware
+    Box::leak(Box::new(mounted));
+    Ok(())
+}
+
+/// Check if the SD card is mounted
+pub fn is_mounted() -> bool {
+    MOUNTED.load(Ordering::Relaxed)
+}
+
+/// Total and free space in bytes
+pub fn space() -> Option<(u64, u64)> {
+    if !is_mounted() {
+        return None;
+    }
+    let path = CString::new(MOUNT_POINT).ok()?;
+    let mut total = 0u64;
+    let mut free = 0u64;
+    let err = unsafe { esp_vfs_fat_info(path.as_ptr(), &mut total, &mut free) };
+    (err == ESP_OK).then_some((total, free))
+}
+
+// ============================================================================
+// Assets
+// ============================================================================
+
+/// Asset names are relative paths like "icons/pla.png"
+fn asset_file(name: &str) -> Option<PathBuf> {
+    if !is_mounted() || name.is_empty() || name.starts_with('/') || name.split('/').any(|part| part == "..") {
+        return None;
+    }
+    Some(Path::new(ASSETS_DIR).join(name))
+}
+
+/// Path of a stored asset, if present
+pub fn asset_path(name: &str) -> Option<PathBuf> {
+    asset_file(name).filter(|path| path.is_file())
+}
+
+/// Read a stored asset
+pub fn read_asset(name: &str) -> Option<Vec<u8>> {
+    fs::read(asset_path(name)?).ok()
+}
+
+/// Store an asset. Written to a temporary file first so a power loss never
+/// leaves a truncated asset behind.
+pub fn write_asset(name: &str, data: &[u8]) -> bool {
+    let Some(path) = asset_file(name) else {
+        return false;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    let tmp = path.with_extension("tmp");
+    let result = fs::write(&tmp, data).and_then(|_| {
+        // FAT rename doesn't replace existing files
+        let _ = fs::remove_file(&path);
+        fs::rename(&tmp, &path)
+    });
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Failed to store asset {}: {:?}", name, e);
+            let _ = fs::remove_file(&tmp);
+            false
+        }
+    }
+}
+
+// ============================================================================
+// Offline event queue
+// ============================================================================
+
+/// Queue a backend request to be sent once the server is reachable again
+pub fn queue_event(path: &str, body: &str) -> bool {
+    if !is_mounted() {
+        return false;
+    }
+    let _lock = EVENTS_LOCK.lock().unwrap();
+
+    let mut events = read_events();
+    events.push(QueuedEvent {
+        path: path.to_string(),
+        body: body.to_string(),
+    });
+    if events.len() > MAX_QUEUED_EVENTS {
+        let dropped = events.len() - MAX_QUEUED_EVENTS;
+        warn!("Event queue full, dropping {} oldest", dropped);
+        events.drain(..dropped);
+    }
+
+    let queued = write_events(&events);
+    if queued {
+        info!("Queued {} for later ({} pending)", path, events.len());
+    }
+    queued
+}
+
+/// Send queued events in order until `send` fails. Sent events are removed
+/// from the queue; returns how many were sent.
+pub fn flush_events(mut send: impl FnMut(&QueuedEvent) -> bool) -> usize {
+    if !is_mounted() {
+        return 0;
+    }
+    let _lock = EVENTS_LOCK.lock().unwrap();
+
+    let events = read_events();
+    let sent = events.iter().take_while(|event| send(event)).count();
+    if sent > 0 {
+        write_events(&events[sent..]);
+    }
+    sent
+}
+
+fn read_events() -> Vec<QueuedEvent> {
+    let Ok(file) = File::open(PENDING_EVENTS) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+fn write_events(events: &[QueuedEvent]) -> bool {
+    if events.is_empty() {
+        let _ = fs::remove_file(PENDING_EVENTS);
+        return true;
+    }
+
+    let result = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(PENDING_EVENTS)
+        .and_then(|mut file| {
+            for event in events {
+                let line = serde_json::to_string(event).unwrap_or_default();
+                writeln!(file, "{}", line)?;
+            }
+            file.flush()
+        });
+    if let Err(e) = &result {
+        warn!("Failed to write event queue: {:?}", e);
+    }
+    result.is_ok()
+}
+
+// ============================================================================
+// OTA image
+// ============================================================================
+
+/// Where OTA downloads are stored, if an SD card is mounted
+pub fn ota_image_path() -> Option<PathBuf> {
+    is_mounted().then(|| PathBuf::from(OTA_IMAGE))
+}
+
+// ============================================================================
+// C-callable interface
+// ============================================================================
+
+/// Check if an SD card is mounted
+#[no_mangle]
+pub extern "C" fn sd_card_is_mounted() -> bool {
+    is_mounted()
+}
+
+/// Get SD card space in KB. Returns false if no card is mounted.
+#[no_mangle]
+pub extern "C" fn sd_card_get_space(total_kb: *mut u32, free_kb: *mut u32) -> bool {
+    let Some((total, free)) = space() else {
+        return false;
+    };
+    unsafe {
+        if !total_kb.is_null() {
+            *total_kb = (total / 1024) as u32;
+        }
+        if !free_kb.is_null() {
+            *free_kb = (free / 1024) as u32;
+        }
+    }
+    true
+}
+
+/// Get the LVGL path of a stored asset ("S:assets/<name>") into `buf`.
+/// Returns false if the asset isn't on the card.
+#[no_mangle]
+pub extern "C" fn sd_card_asset_path(name: *const c_char, buf: *mut c_char, buf_len: usize) -> bool {
+    if name.is_null() || buf.is_null() || buf_len == 0 {
+        return false;
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(name) }.to_str().unwrap_or("");
+    if asset_path(name).is_none() {
+        return false;
+    }
+
+    let lv_path = format!("S:assets/{}", name);
+    let bytes = lv_path.as_bytes();
+    if bytes.len() >= buf_len {
+        return false;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, bytes.len());
+        *buf.add(bytes.len()) = 0;
+    }
+    true
+}
@@ -1,25 +1,208 @@
 //! Shared I2C bus for multiple devices
 //!
 //! Holds the I2C driver in a static Mutex so multiple managers can share it.
+//!
+//! Access goes through a small arbiter instead of a bare mutex:
+//! - Each client has a priority. When the bus is released, waiting
+//!   higher-priority clients go first (touch > scale > NFC).
+//! - Each client has an acquire timeout. A client that can't get the bus in
+//!   time gets `None` and tries again on its next poll instead of stalling
+//!   the main loop.
+//! - Wait and hold times are tracked per client and logged periodically with
+//!   [`log_bus_stats`], which makes contention easy to spot in the UDP log.
+//!   Clients report failed transactions with [`record_error`], so the
+//!   counters also show a flaky device or bus on the diagnostics screen.
 
 use esp_idf_hal::i2c::I2cDriver;
-use std::sync::Mutex;
+use log::{info, warn};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Devices that use the shared bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusClient {
+    /// Touch controller on boards that wire it to the shared bus
+    /// (the CrowPanel GT911 has its own bus on I2C0)
+    #[allow(dead_code)]
+    Touch,
+    /// NAU7802 scale
+    Scale,
+    /// Pico NFC bridge
+    Nfc,
+}
+
+const CLIENT_COUNT: usize = 3;
+pub const ALL_CLIENTS: [BusClient; CLIENT_COUNT] = [BusClient::Touch, BusClient::Scale, BusClient::Nfc];
+
+impl BusClient {
+    /// Priority index (0 = highest)
+    fn index(self) -> usize {
+        match self {
+            BusClient::Touch => 0,
+            BusClient::Scale => 1,
+            BusClient::Nfc => 2,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BusClient::Touch => "touch",
+            BusClient::Scale => "scale",
+            BusClient::Nfc => "nfc",
+        }
+    }
+
+    /// How long the client waits for the bus before giving up
+    fn acquire_timeout(self) -> Duration {
+        match self {
+            BusClient::Touch => Duration::from_millis(10),
+            BusClient::Scale => Duration::from_millis(50),
+            BusClient::Nfc => Duration::from_millis(100),
+        }
+    }
+
+    /// Expected maximum hold time; longer holds are counted as over budget
+    fn hold_budget(self) -> Duration {
+        match self {
+            BusClient::Touch => Duration::from_millis(5),
+            BusClient::Scale => Duration::from_millis(20),
+            BusClient::Nfc => Duration::from_millis(20),
+        }
+    }
+}
+
+/// Per-client contention counters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BusStats {
+    pub acquisitions: u32,
+    /// Acquisitions that had to wait for another client
+    pub contended: u32,
+    pub timeouts: u32,
+    /// Failed transactions reported by the client
+    pub errors: u32,
+    /// Holds longer than the client's budget
+    pub over_budget: u32,
+    pub total_wait_us: u64,
+    pub max_wait_us: u32,
+    pub max_hold_us: u32,
+}
+
+impl BusStats {
+    const fn new() -> Self {
+        Self {
+            acquisitions: 0,
+            contended: 0,
+            timeouts: 0,
+            errors: 0,
+            over_budget: 0,
+            total_wait_us: 0,
+            max_wait_us: 0,
+            max_hold_us: 0,
+        }
+    }
+}
+
+/// Bus ownership and waiters
+struct Arbiter {
+    busy: bool,
+    waiting: [u16; CLIENT_COUNT],
+    stats: [BusStats; CLIENT_COUNT],
+}
+
+impl Arbiter {
+    /// Whether a client with higher priority than `index` is waiting
+    fn higher_priority_waiting(&self, index: usize) -> bool {
+        self.waiting[..index].iter().any(|&n| n > 0)
+    }
+}
 
 /// Global shared I2C bus
 static SHARED_I2C: Mutex<Option<I2cDriver<'static>>> = Mutex::new(None);
 
+static ARBITER: Mutex<Arbiter> = Mutex::new(Arbiter {
+    busy: false,
+    waiting: [0; CLIENT_COUNT],
+    stats: [BusStats::new(); CLIENT_COUNT],
+});
+
+/// Signalled whenever the bus is released or a waiter gives up
+static BUS_FREE: Condvar = Condvar::new();
+
 /// Initialize the shared I2C bus
 pub fn init_shared_i2c(i2c: I2cDriver<'static>) {
     let mut guard = SHARED_I2C.lock().unwrap();
     *guard = Some(i2c);
 }
 
+/// Releases the bus when dropped (also on panic inside the closure)
+struct BusGuard {
+    client: BusClient,
+    acquired: Instant,
+}
+
+impl Drop for BusGuard {
+    fn drop(&mut self) {
+        let hold = self.acquired.elapsed();
+        let mut arbiter = ARBITER.lock().unwrap();
+        arbiter.busy = false;
+
+        let stats = &mut arbiter.stats[self.client.index()];
+        stats.max_hold_us = stats.max_hold_us.max(hold.as_micros() as u32);
+        if hold > self.client.hold_budget() {
+            stats.over_budget += 1;
+        }
+        drop(arbiter);
+        BUS_FREE.notify_all();
+    }
+}
+
+/// Wait for the bus, respecting priority and the client's timeout
+fn acquire(client: BusClient) -> Option<BusGuard> {
+    let index = client.index();
+    let start = Instant::now();
+    let deadline = start + client.acquire_timeout();
+
+    let mut arbiter = ARBITER.lock().unwrap();
+    arbiter.waiting[index] += 1;
+    let mut contended = false;
+
+    while arbiter.busy || arbiter.higher_priority_waiting(index) {
+        contended = true;
+        let now = Instant::now();
+        if now >= deadline {
+            arbiter.waiting[index] -= 1;
+            arbiter.stats[index].timeouts += 1;
+            drop(arbiter);
+            // Lower-priority waiters may have been yielding to us
+            BUS_FREE.notify_all();
+            return None;
+        }
+        arbiter = BUS_FREE.wait_timeout(arbiter, deadline - now).unwrap().0;
+    }
+
+    arbiter.waiting[index] -= 1;
+    arbiter.busy = true;
+
+    let wait_us = start.elapsed().as_micros() as u32;
+    let stats = &mut arbiter.stats[index];
+    stats.acquisitions = stats.acquisitions.wrapping_add(1);
+    if contended {
+        stats.contended += 1;
+    }
+    stats.total_wait_us += wait_us as u64;
+    stats.max_wait_us = stats.max_wait_us.max(wait_us);
+
+    Some(BusGuard { client, acquired: Instant::now() })
+}
+
 /// Access the shared I2C bus with a closure
-/// Returns None if I2C is not initialized
-pub fn with_i2c<F, R>(f: F) -> Option<R>
+/// Returns None if I2C is not initialized or the bus wasn't available
+/// within the client's timeout
+pub fn with_i2c<F, R>(client: BusClient, f: F) -> Option<R>
 where
     F: FnOnce(&mut I2cDriver<'static>) -> R,
 {
+    let _bus = acquire(client)?;
     let mut guard = SHARED_I2C.lock().unwrap();
     guard.as_mut().map(f)
 }
@@ -30,3 +213,42 @@ pub fn is_initialized() -> bool {
     let guard = SHARED_I2C.lock().unwrap();
     guard.is_some()
 }
+
+/// Count a failed transaction (I2C error or a device that didn't answer)
+pub fn record_error(client: BusClient) {
+    ARBITER.lock().unwrap().stats[client.index()].errors += 1;
+}
+
+/// Get a snapshot of the contention counters for a client
+pub fn bus_stats(client: BusClient) -> BusStats {
+    ARBITER.lock().unwrap().stats[client.index()]
+}
+
+/// Log contention counters for all clients that used the bus
+pub fn log_bus_stats() {
+    let stats = ARBITER.lock().unwrap().stats;
+    for client in ALL_CLIENTS {
+        let s = stats[client.index()];
+        if s.acquisitions == 0 && s.timeouts == 0 {
+            continue;
+        }
+        let avg_wait_us = s.total_wait_us / s.acquisitions.max(1) as u64;
+        let line = format!(
+            "I2C {}: {} acq, {} contended, {} timeouts, {} errors, {} over budget, wait avg {}us max {}us, hold max {}us",
+            client.name(),
+            s.acquisitions,
+            s.contended,
+            s.timeouts,
+            s.errors,
+            s.over_budget,
+            avg_wait_us,
+            s.max_wait_us,
+            s.max_hold_us
+        );
+        if s.timeouts > 0 || s.errors > 0 {
+            warn!("{}", line);
+        } else {
+            info!("{}", line);
+        }
+    }
+}
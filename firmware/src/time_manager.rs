@@ -1,20 +1,41 @@
 //! Time Manager with SNTP synchronization and backend fallback
 //!
 //! Provides NTP time sync and C-callable interface for UI clock display.
-//! Falls back to backend server time if SNTP is unavailable.
+//! Until SNTP completes, the system clock is set from the backend's
+//! `/api/time` timestamp. Local time comes from a POSIX TZ string (with DST
+//! rules) which, like the NTP server, is synced from the backend's device
+//! clock settings (`/api/settings/device/time`).
 
 use esp_idf_svc::sntp::{EspSntp, SyncStatus, SntpConf};
+use esp_idf_sys::{localtime_r, settimeofday, time_t, timeval, tm, tzset};
 use log::{info, warn};
 use std::ffi::c_int;
 use std::sync::Mutex;
 use std::time::SystemTime;
 
+/// Used until the backend's clock settings have been fetched
+/// (Central European Time, which the clock was hardcoded to before)
+const DEFAULT_POSIX_TZ: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+const DEFAULT_NTP_SERVER: &str = "pool.ntp.org";
+
+/// Where the current system time came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TimeSource {
+    None,
+    Backend,
+    Sntp,
+}
+
+/// Clock settings from the backend
+struct ClockSettings {
+    posix_tz: String,
+    ntp_server: String,
+}
+
 /// Time sync state
-static TIME_SYNCED: Mutex<bool> = Mutex::new(false);
+static TIME_SOURCE: Mutex<TimeSource> = Mutex::new(TimeSource::None);
 static SNTP_HANDLE: Mutex<Option<EspSntp<'static>>> = Mutex::new(None);
-
-/// Backend time (hour, minute) - used when SNTP isn't available
-static BACKEND_TIME: Mutex<Option<(u8, u8)>> = Mutex::new(None);
+static CLOCK_SETTINGS: Mutex<Option<ClockSettings>> = Mutex::new(None);
 
 /// Initialize SNTP time synchronization
 /// Call this after WiFi is connected
@@ -24,9 +45,18 @@ pub fn init_sntp() {
         return; // Already initialized
     }
 
-    info!("Initializing SNTP time sync...");
+    apply_timezone(&current_posix_tz());
+    let server = CLOCK_SETTINGS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.ntp_server.clone())
+        .unwrap_or_else(|| DEFAULT_NTP_SERVER.to_string());
+
+    info!("Initializing SNTP time sync ({})...", server);
 
-    let conf = SntpConf::default();
+    let mut conf = SntpConf::default();
+    conf.servers[0] = &server;
     match EspSntp::new(&conf) {
         Ok(sntp) => {
             *handle = Some(sntp);
@@ -38,16 +68,60 @@ pub fn init_sntp() {
     }
 }
 
+/// Apply clock settings fetched from the backend.
+/// Restarts SNTP if the NTP server changed.
+pub fn apply_clock_settings(posix_tz: &str, ntp_server: &str) {
+    let mut settings = CLOCK_SETTINGS.lock().unwrap();
+    let (tz_changed, server_changed) = match settings.as_ref() {
+        Some(s) => (s.posix_tz != posix_tz, s.ntp_server != ntp_server),
+        None => (true, ntp_server != DEFAULT_NTP_SERVER),
+    };
+    *settings = Some(ClockSettings {
+        posix_tz: posix_tz.to_string(),
+        ntp_server: ntp_server.to_string(),
+    });
+    drop(settings);
+
+    if tz_changed {
+        apply_timezone(posix_tz);
+    }
+
+    if server_changed {
+        let restart = SNTP_HANDLE.lock().unwrap().take().is_some();
+        if restart {
+            info!("NTP server changed to {}, restarting SNTP", ntp_server);
+            init_sntp();
+        }
+    }
+}
+
+fn current_posix_tz() -> String {
+    CLOCK_SETTINGS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.posix_tz.clone())
+        .unwrap_or_else(|| DEFAULT_POSIX_TZ.to_string())
+}
+
+fn apply_timezone(posix_tz: &str) {
+    std::env::set_var("TZ", posix_tz);
+    unsafe {
+        tzset();
+    }
+    info!("Timezone set to {}", posix_tz);
+}
+
 /// Check if time is synchronized
 pub fn is_time_synced() -> bool {
     let handle = SNTP_HANDLE.lock().unwrap();
     if let Some(ref sntp) = *handle {
         let synced = sntp.get_sync_status() == SyncStatus::Completed;
         if synced {
-            let mut time_synced = TIME_SYNCED.lock().unwrap();
-            if !*time_synced {
+            let mut source = TIME_SOURCE.lock().unwrap();
+            if *source != TimeSource::Sntp {
                 info!("SNTP time synchronized");
-                *time_synced = true;
+                *source = TimeSource::Sntp;
             }
         }
         synced
@@ -57,37 +131,57 @@ pub fn is_time_synced() -> bool {
 }
 
 /// Set time from backend server
-/// Called when we receive time from the backend API
-pub fn set_backend_time(hour: u8, minute: u8) {
-    let mut backend_time = BACKEND_TIME.lock().unwrap();
-    *backend_time = Some((hour, minute));
+/// Called when we receive time from the backend API. Ignored once SNTP
+/// has synced, since NTP is more accurate.
+pub fn set_backend_time(timestamp: i64) {
+    if is_time_synced() {
+        return;
+    }
+
+    let tv = timeval {
+        tv_sec: timestamp as time_t,
+        tv_usec: 0,
+    };
+    if unsafe { settimeofday(&tv, std::ptr::null()) } != 0 {
+        warn!("Failed to set time from backend");
+        return;
+    }
+
+    let mut source = TIME_SOURCE.lock().unwrap();
+    if *source == TimeSource::None {
+        apply_timezone(&current_posix_tz());
+        info!("Clock set from backend time");
+        *source = TimeSource::Backend;
+    }
 }
 
-// Timezone offset in seconds (CET = UTC+1 = 3600, CEST = UTC+2 = 7200)
-// TODO: Make this configurable via backend
-const TIMEZONE_OFFSET_SECS: u64 = 3600; // CET (UTC+1)
+/// Current Unix time in seconds, or None if the clock hasn't been set
+fn unix_time() -> Option<i64> {
+    is_time_synced(); // Updates TIME_SOURCE once SNTP completes
+    if *TIME_SOURCE.lock().unwrap() == TimeSource::None {
+        return None;
+    }
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
 
-/// Get current time components (for UI display)
-/// Returns (hour, minute) - tries SNTP first, falls back to backend time
-pub fn get_time() -> Option<(u8, u8)> {
-    // Try SNTP first
-    if is_time_synced() {
-        match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-            Ok(duration) => {
-                let secs = duration.as_secs() + TIMEZONE_OFFSET_SECS;
-                // Local time calculation with timezone offset
-                let day_secs = secs % 86400;
-                let hour = (day_secs / 3600) as u8;
-                let minute = ((day_secs % 3600) / 60) as u8;
-                return Some((hour, minute));
-            }
-            Err(_) => {}
-        }
+/// Convert a Unix timestamp to local time (timezone and DST applied)
+fn to_local_time(timestamp: i64) -> tm {
+    let t = timestamp as time_t;
+    let mut local: tm = unsafe { core::mem::zeroed() };
+    unsafe {
+        localtime_r(&t, &mut local);
     }
+    local
+}
 
-    // Fall back to backend time
-    let backend_time = BACKEND_TIME.lock().unwrap();
-    *backend_time
+/// Get current time components (for UI display)
+/// Returns (hour, minute) in local time, or None if the clock hasn't been set
+pub fn get_time() -> Option<(u8, u8)> {
+    let local = to_local_time(unix_time()?);
+    Some((local.tm_hour as u8, local.tm_min as u8))
 }
 
 // ============================================================================
@@ -14,6 +14,7 @@
 #![allow(dead_code)]
 
 pub mod display;
+pub mod overlay;
 pub mod theme;
 pub mod touch;
 
@@ -24,7 +25,7 @@ use core::cell::RefCell;
 use critical_section::Mutex;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use heapless::String;
+use heapless::{String, Vec};
 use log::info;
 
 /// Display dimensions
@@ -42,6 +43,8 @@ pub struct UiManager {
     state: UiState,
     /// Whether the UI needs to be redrawn
     dirty: bool,
+    /// Recent weight samples for the trend arrow
+    weight_history: widgets::WeightTrend,
 }
 
 /// Current active screen
@@ -100,6 +103,10 @@ pub struct UiState {
     pub weight: f32,
     /// Is weight stable?
     pub weight_stable: bool,
+    /// Unit the weight is shown in
+    pub weight_unit: widgets::WeightUnit,
+    /// Direction the weight is moving in
+    pub weight_trend: widgets::Trend,
     /// Current spool info (if any)
     pub spool: Option<SpoolDisplay>,
     /// WiFi connection status
@@ -153,6 +160,8 @@ impl Default for UiState {
         Self {
             weight: 0.0,
             weight_stable: false,
+            weight_unit: widgets::WeightUnit::Grams,
+            weight_trend: widgets::Trend::Steady,
             spool: None,
             wifi_connected: false,
             wifi_ssid: String::new(),
@@ -172,6 +181,9 @@ impl Default for UiState {
     }
 }
 
+/// Most color stops a multi-color spool can have (matches the backend)
+pub const MAX_COLOR_STOPS: usize = 8;
+
 /// Spool information for display
 #[derive(Clone)]
 pub struct SpoolDisplay {
@@ -180,6 +192,8 @@ pub struct SpoolDisplay {
     pub color_name: String<32>,
     pub brand: String<32>,
     pub color_rgba: u32,
+    /// Colors of multi-color (dual-color, gradient) filament in order, empty for single-color
+    pub color_stops: Vec<u32, MAX_COLOR_STOPS>,
     pub weight_current: f32,
     pub weight_label: f32,
     pub k_value: Option<f32>,
@@ -202,6 +216,7 @@ impl UiManager {
             current_screen: Screen::Home,
             state: UiState::default(),
             dirty: true,
+            weight_history: widgets::WeightTrend::new(),
         }
     }
 
@@ -226,6 +241,18 @@ impl UiManager {
             self.state.weight_stable = stable;
             self.dirty = true;
         }
+
+        let trend = self.weight_history.push(grams);
+        if self.state.weight_trend != trend {
+            self.state.weight_trend = trend;
+            self.dirty = true;
+        }
+    }
+
+    /// Switch the weight readout between grams and ounces
+    pub fn toggle_weight_unit(&mut self) {
+        self.state.weight_unit = self.state.weight_unit.toggled();
+        self.dirty = true;
     }
 
     /// Update spool information
@@ -273,13 +300,37 @@ impl UiManager {
         self.dirty = false;
     }
 
+    /// Force a full redraw (e.g., after a theme change)
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
     /// Get current state (for rendering)
     pub fn state(&self) -> &UiState {
         &self.state
     }
 
+    /// Advance timed UI state (toasts); call every frame
+    pub fn tick(&mut self, now_ms: u32) {
+        if overlay::with_overlays(|overlays| overlays.tick(now_ms)) {
+            self.dirty = true;
+        }
+    }
+
     /// Handle touch event
     pub fn handle_touch(&mut self, event: TouchEvent) -> Option<UiAction> {
+        // Toasts and dialogs sit above the screen and get the first look
+        if let Some(touch) = overlay::with_overlays(|overlays| overlays.handle_touch(event)) {
+            self.dirty = true;
+            return match touch {
+                overlay::OverlayTouch::Dialog(result) => Some(UiAction::DialogResult {
+                    id: result.id,
+                    confirmed: result.confirmed,
+                }),
+                overlay::OverlayTouch::Consumed => None,
+            };
+        }
+
         match self.current_screen {
             Screen::Home => self.handle_home_touch(event),
             Screen::SpoolInfo => self.handle_spool_info_touch(event),
@@ -300,6 +351,11 @@ impl UiManager {
     // Touch handlers for each screen
     fn handle_home_touch(&mut self, event: TouchEvent) -> Option<UiAction> {
         if let TouchEvent::Press { x, y } = event {
+            // Weight readout switches units
+            if screens::home::HomeScreen::get_weight_bounds().contains(Point::new(x as i32, y as i32)) {
+                self.toggle_weight_unit();
+                return None;
+            }
             // Tare button (bottom left)
             if x < 200 && y > 400 {
                 return Some(UiAction::TareScale);
@@ -315,6 +371,11 @@ impl UiManager {
 
     fn handle_spool_info_touch(&mut self, event: TouchEvent) -> Option<UiAction> {
         if let TouchEvent::Press { x, y } = event {
+            // Weight readout switches units
+            if screens::spool_info::SpoolInfoScreen::get_weight_bounds().contains(Point::new(x as i32, y as i32)) {
+                self.toggle_weight_unit();
+                return None;
+            }
             // Bottom button row
             if y > 400 {
                 if x < 200 {
@@ -560,6 +621,8 @@ pub enum UiAction {
     SetTimeoutDuration(u16),
     OpenSpoolDetail,
     NavigateBack,
+    /// A confirmation dialog was answered
+    DialogResult { id: u16, confirmed: bool },
 }
 
 /// Display errors
@@ -0,0 +1,472 @@
+//! Overlay layer for toasts and confirmation dialogs.
+//!
+//! Overlays are drawn over the current screen in `theme::layer` order:
+//! dialogs on `OVERLAY`, toasts above them on `TOP`.
+//!
+//! Toast:
+//! ┌──────────────────────────────┐
+//! │ ✓ Weight saved               │
+//! └──────────────────────────────┘
+//!
+//! Dialog:
+//! ┌──────────────────────────────┐
+//! │ Delete spool?                │
+//! │ This cannot be undone.       │
+//! │                              │
+//! │      [CANCEL]    [DELETE]    │
+//! └──────────────────────────────┘
+//!
+//! Any manager can queue overlays through the free functions here; they
+//! take a critical section, so they are safe to call from other tasks.
+
+use crate::ui::theme::{self, layer, radius, spacing};
+use crate::ui::widgets::button::{Button, ButtonStyle};
+use crate::ui::widgets::icon::Icon;
+use crate::ui::{TouchEvent, DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use core::cell::RefCell;
+use critical_section::Mutex;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_10X20, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Text},
+};
+use heapless::{Deque, String};
+use log::info;
+
+/// Most toasts waiting behind the one on screen
+const TOAST_QUEUE: usize = 4;
+/// Most dialogs waiting behind the one on screen
+const DIALOG_QUEUE: usize = 2;
+/// How long a toast stays up by default
+pub const TOAST_DURATION_MS: u32 = 2500;
+
+const TOAST_WIDTH: u32 = 480;
+const TOAST_HEIGHT: u32 = 48;
+/// Distance of toasts from the bottom of the screen
+const TOAST_MARGIN: i32 = 32;
+
+const DIALOG_WIDTH: u32 = 480;
+const DIALOG_HEIGHT: u32 = 220;
+const DIALOG_BUTTON_WIDTH: u32 = 160;
+const DIALOG_BUTTON_HEIGHT: u32 = 48;
+
+/// Toast severity, sets its color and icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// Transient message shown at the bottom of the screen
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub message: String<64>,
+    pub duration_ms: u32,
+}
+
+impl Toast {
+    /// Create a toast with the default duration; long messages are truncated
+    pub fn new(kind: ToastKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: truncated(message),
+            duration_ms: TOAST_DURATION_MS,
+        }
+    }
+
+    /// Set how long the toast stays up
+    pub fn with_duration(mut self, duration_ms: u32) -> Self {
+        self.duration_ms = duration_ms;
+        self
+    }
+}
+
+/// Modal dialog asking the user to confirm an action
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    /// Caller-chosen ID, returned with the answer
+    pub id: u16,
+    pub title: String<32>,
+    pub message: String<96>,
+    pub confirm_label: &'static str,
+    /// `None` for an acknowledge-only dialog
+    pub cancel_label: Option<&'static str>,
+    /// Style the confirm button as destructive
+    pub danger: bool,
+}
+
+impl Dialog {
+    /// Create a confirm/cancel dialog
+    pub fn confirm(id: u16, title: &str, message: &str) -> Self {
+        Self {
+            id,
+            title: truncated(title),
+            message: truncated(message),
+            confirm_label: "OK",
+            cancel_label: Some("Cancel"),
+            danger: false,
+        }
+    }
+
+    /// Set the confirm button label
+    pub fn with_confirm_label(mut self, label: &'static str) -> Self {
+        self.confirm_label = label;
+        self
+    }
+
+    /// Set the cancel button label, or `None` for a single button
+    pub fn with_cancel_label(mut self, label: Option<&'static str>) -> Self {
+        self.cancel_label = label;
+        self
+    }
+
+    /// Style the confirm button as destructive
+    pub fn danger(mut self) -> Self {
+        self.danger = true;
+        self
+    }
+}
+
+/// Answer to a dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialogResult {
+    pub id: u16,
+    pub confirmed: bool,
+}
+
+/// What a touch did to the overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayTouch {
+    /// The touch hit an overlay and must not reach the screen
+    Consumed,
+    /// A dialog was answered
+    Dialog(DialogResult),
+}
+
+fn truncated<const N: usize>(text: &str) -> String<N> {
+    let mut s = String::new();
+    for c in text.chars() {
+        if s.push(c).is_err() {
+            break;
+        }
+    }
+    s
+}
+
+/// Queues and draws toasts and dialogs
+#[derive(Clone)]
+pub struct OverlayManager {
+    /// Toast on screen and the time it appeared
+    toast: Option<(Toast, u32)>,
+    toast_queue: Deque<Toast, TOAST_QUEUE>,
+    dialog: Option<Dialog>,
+    dialog_queue: Deque<Dialog, DIALOG_QUEUE>,
+    /// Dialog button held down (true = confirm)
+    pressed: Option<bool>,
+    /// Time of the last tick
+    now_ms: u32,
+}
+
+impl OverlayManager {
+    /// Create an empty overlay manager
+    pub const fn new() -> Self {
+        Self {
+            toast: None,
+            toast_queue: Deque::new(),
+            dialog: None,
+            dialog_queue: Deque::new(),
+            pressed: None,
+            now_ms: 0,
+        }
+    }
+
+    /// Queue a toast; when the queue is full the oldest waiting toast is dropped
+    pub fn show_toast(&mut self, toast: Toast) {
+        if self.toast.is_none() {
+            self.toast = Some((toast, self.now_ms));
+            return;
+        }
+        if self.toast_queue.is_full() {
+            self.toast_queue.pop_front();
+        }
+        let _ = self.toast_queue.push_back(toast);
+    }
+
+    /// Queue a dialog. Returns false if too many dialogs are waiting.
+    pub fn show_dialog(&mut self, dialog: Dialog) -> bool {
+        info!("Dialog {}: {}", dialog.id, dialog.title.as_str());
+        if self.dialog.is_none() {
+            self.dialog = Some(dialog);
+            return true;
+        }
+        self.dialog_queue.push_back(dialog).is_ok()
+    }
+
+    /// Whether a dialog is blocking the screen
+    pub fn is_modal(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    /// Whether anything is on screen
+    pub fn is_visible(&self) -> bool {
+        self.toast.is_some() || self.dialog.is_some()
+    }
+
+    /// Expire toasts. Returns true if the overlays changed and need a redraw.
+    pub fn tick(&mut self, now_ms: u32) -> bool {
+        self.now_ms = now_ms;
+        let expired = matches!(
+            &self.toast,
+            Some((toast, shown_at)) if now_ms.wrapping_sub(*shown_at) >= toast.duration_ms
+        );
+        if expired {
+            self.toast = self.toast_queue.pop_front().map(|toast| (toast, now_ms));
+        }
+        expired
+    }
+
+    fn dialog_rect() -> Rectangle {
+        Rectangle::new(
+            Point::new(
+                (DISPLAY_WIDTH - DIALOG_WIDTH) as i32 / 2,
+                (DISPLAY_HEIGHT - DIALOG_HEIGHT) as i32 / 2,
+            ),
+            Size::new(DIALOG_WIDTH, DIALOG_HEIGHT),
+        )
+    }
+
+    fn toast_rect() -> Rectangle {
+        Rectangle::new(
+            Point::new(
+                (DISPLAY_WIDTH - TOAST_WIDTH) as i32 / 2,
+                (DISPLAY_HEIGHT - TOAST_HEIGHT) as i32 - TOAST_MARGIN,
+            ),
+            Size::new(TOAST_WIDTH, TOAST_HEIGHT),
+        )
+    }
+
+    /// Confirm and cancel buttons of a dialog
+    fn dialog_buttons(dialog: &Dialog) -> (Button<'static>, Option<Button<'static>>) {
+        let rect = Self::dialog_rect();
+        let size = Size::new(DIALOG_BUTTON_WIDTH, DIALOG_BUTTON_HEIGHT);
+        let y = rect.top_left.y + DIALOG_HEIGHT as i32 - DIALOG_BUTTON_HEIGHT as i32 - spacing::LG;
+        let center = rect.center().x;
+        let confirm_style = if dialog.danger { ButtonStyle::Danger } else { ButtonStyle::Primary };
+
+        match dialog.cancel_label {
+            Some(cancel) => (
+                Button::new(Point::new(center + spacing::SM, y), size, dialog.confirm_label)
+                    .with_style(confirm_style)
+                    .with_large_font(),
+                Some(
+                    Button::new(Point::new(center - spacing::SM - DIALOG_BUTTON_WIDTH as i32, y), size, cancel)
+                        .with_style(ButtonStyle::Secondary)
+                        .with_large_font(),
+                ),
+            ),
+            None => (
+                Button::new(Point::new(center - DIALOG_BUTTON_WIDTH as i32 / 2, y), size, dialog.confirm_label)
+                    .with_style(confirm_style)
+                    .with_large_font(),
+                None,
+            ),
+        }
+    }
+
+    /// Route a touch to the overlays.
+    ///
+    /// Returns `None` if the touch should go to the screen below.
+    pub fn handle_touch(&mut self, event: TouchEvent) -> Option<OverlayTouch> {
+        let (x, y) = match event {
+            TouchEvent::Press { x, y } | TouchEvent::Release { x, y } | TouchEvent::Move { x, y } => (x, y),
+        };
+        let point = Point::new(x as i32, y as i32);
+
+        if let Some(dialog) = &self.dialog {
+            // Modal: every touch is consumed, buttons answer on release
+            let id = dialog.id;
+            let (confirm, cancel) = Self::dialog_buttons(dialog);
+            let hit = if confirm.contains(point) {
+                Some(true)
+            } else if cancel.as_ref().is_some_and(|button| button.contains(point)) {
+                Some(false)
+            } else {
+                None
+            };
+
+            match event {
+                TouchEvent::Press { .. } => self.pressed = hit,
+                TouchEvent::Move { .. } => {
+                    if hit != self.pressed {
+                        self.pressed = None;
+                    }
+                }
+                TouchEvent::Release { .. } => {
+                    if let Some(confirmed) = self.pressed.take().filter(|pressed| hit == Some(*pressed)) {
+                        let result = DialogResult { id, confirmed };
+                        info!("Dialog {} answered: {}", result.id, confirmed);
+                        self.dialog = self.dialog_queue.pop_front();
+                        return Some(OverlayTouch::Dialog(result));
+                    }
+                }
+            }
+            return Some(OverlayTouch::Consumed);
+        }
+
+        // Tapping a toast dismisses it
+        if self.toast.is_some() && Self::toast_rect().contains(point) {
+            if let TouchEvent::Release { .. } = event {
+                self.toast = self.toast_queue.pop_front().map(|toast| (toast, self.now_ms));
+            }
+            return Some(OverlayTouch::Consumed);
+        }
+
+        None
+    }
+
+    /// Draw all overlays over the current screen, lowest layer first
+    pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        for z in [layer::OVERLAY, layer::TOP] {
+            self.draw_layer(display, z)?;
+        }
+        Ok(())
+    }
+
+    /// Draw the overlays on one `theme::layer`
+    pub fn draw_layer<D>(&self, display: &mut D, z: u8) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        match z {
+            layer::OVERLAY => match &self.dialog {
+                Some(dialog) => self.draw_dialog(display, dialog),
+                None => Ok(()),
+            },
+            layer::TOP => match &self.toast {
+                Some((toast, _)) => Self::draw_toast(display, toast),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    fn draw_dialog<D>(&self, display: &mut D, dialog: &Dialog) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let rect = Self::dialog_rect();
+        let card = RoundedRectangle::with_equal_corners(rect, Size::new(radius::LG, radius::LG));
+
+        // Dim the screen below with a sparse checkerboard, cheaper than blending every pixel
+        let screen = Rectangle::new(Point::zero(), Size::new(DISPLAY_WIDTH, DISPLAY_HEIGHT));
+        let dim = screen
+            .points()
+            .filter(|p| (p.x + p.y) % 2 == 0 && !rect.contains(*p))
+            .map(|p| Pixel(p, Rgb565::BLACK));
+        display.draw_iter(dim)?;
+
+        card.into_styled(PrimitiveStyle::with_fill(theme.card_bg)).draw(display)?;
+        let border = if dialog.danger { theme.error } else { theme.border };
+        card.into_styled(PrimitiveStyle::with_stroke(border, 2)).draw(display)?;
+
+        let title_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+        Text::with_alignment(
+            dialog.title.as_str(),
+            Point::new(rect.center().x, rect.top_left.y + spacing::XL + 8),
+            title_style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+
+        let message_style = MonoTextStyle::new(&FONT_10X20, theme.text_secondary);
+        Text::with_alignment(
+            dialog.message.as_str(),
+            Point::new(rect.center().x, rect.top_left.y + spacing::XL * 2 + 16),
+            message_style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+
+        let (mut confirm, cancel) = Self::dialog_buttons(dialog);
+        confirm.set_pressed(self.pressed == Some(true));
+        confirm.draw(display)?;
+        if let Some(mut cancel) = cancel {
+            cancel.set_pressed(self.pressed == Some(false));
+            cancel.draw(display)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_toast<D>(display: &mut D, toast: &Toast) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let rect = Self::toast_rect();
+        let (accent, icon) = match toast.kind {
+            ToastKind::Info => (theme.primary, Icon::Info),
+            ToastKind::Success => (theme.success, Icon::Check),
+            ToastKind::Warning => (theme.warning, Icon::Warning),
+            ToastKind::Error => (theme.error, Icon::Close),
+        };
+
+        let pill = RoundedRectangle::with_equal_corners(rect, Size::new(radius::LG, radius::LG));
+        pill.into_styled(PrimitiveStyle::with_fill(theme.status_bar_bg)).draw(display)?;
+        pill.into_styled(PrimitiveStyle::with_stroke(accent, 2)).draw(display)?;
+
+        let icon_size = 24;
+        icon.draw(
+            display,
+            Point::new(rect.top_left.x + spacing::MD, rect.center().y - icon_size as i32 / 2),
+            icon_size,
+            accent,
+        )?;
+
+        let text_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+        Text::new(
+            toast.message.as_str(),
+            Point::new(rect.top_left.x + spacing::MD * 2 + icon_size as i32, rect.center().y + 6),
+            text_style,
+        )
+        .draw(display)?;
+
+        Ok(())
+    }
+}
+
+impl Default for OverlayManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Global overlay manager, shared by the UI and the other managers
+static OVERLAYS: Mutex<RefCell<OverlayManager>> = Mutex::new(RefCell::new(OverlayManager::new()));
+
+/// Access the overlay manager from any context
+pub fn with_overlays<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut OverlayManager) -> R,
+{
+    critical_section::with(|cs| f(&mut OVERLAYS.borrow_ref_mut(cs)))
+}
+
+/// Queue a toast with the default duration
+pub fn toast(kind: ToastKind, message: &str) {
+    with_overlays(|overlays| overlays.show_toast(Toast::new(kind, message)));
+}
+
+/// Queue a confirmation dialog; the answer comes back as `UiAction::DialogResult`
+pub fn confirm(dialog: Dialog) -> bool {
+    with_overlays(|overlays| overlays.show_dialog(dialog))
+}
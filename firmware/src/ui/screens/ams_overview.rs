@@ -226,9 +226,9 @@ impl AmsOverviewScreen {
         };
 
         draw_action_button(display, btn_x, btn_y, "Scan", Icon::Nfc)?;
-        draw_action_button(display, btn_x + btn_size as i32 + btn_gap, btn_y, "Catalog", Icon::Weight)?;
-        draw_action_button(display, btn_x, btn_y + btn_size as i32 + btn_gap, "Calibrate", Icon::Weight)?;
-        draw_action_button(display, btn_x + btn_size as i32 + btn_gap, btn_y + btn_size as i32 + btn_gap, "Settings", Icon::Setting)?;
+        draw_action_button(display, btn_x + btn_size as i32 + btn_gap, btn_y, "Catalog", Icon::Scale)?;
+        draw_action_button(display, btn_x, btn_y + btn_size as i32 + btn_gap, "Calibrate", Icon::Scale)?;
+        draw_action_button(display, btn_x + btn_size as i32 + btn_gap, btn_y + btn_size as i32 + btn_gap, "Settings", Icon::Settings)?;
 
         // Bottom status bar
         let bar_y = DISPLAY_HEIGHT as i32 - 44;
@@ -13,7 +13,7 @@
 //! │     └─────────────────────────────────────────────┘       │
 //! │                                                            │
 //! │     ┌──────────────────────┐                              │
-//! │     │    1,234.5 g   ✓     │  ← Weight display            │
+//! │     │ ▲   1234.5 g   ✓     │  ← Weight display (tap: g/oz)│
 //! │     └──────────────────────┘                              │
 //! │                                                            │
 //! │     [TARE]              [SETTINGS]                        │
@@ -61,7 +61,7 @@ impl HomeScreen {
 
         // "Place spool" prompt card
         let card_width = 500u32;
-        let card_height = 200u32;
+        let card_height = 160u32;
         let card_x = (DISPLAY_WIDTH - card_width) as i32 / 2;
         let card_y = content_y + ((content_height - card_height - 80) as i32) / 2;
 
@@ -97,16 +97,11 @@ impl HomeScreen {
         )?;
 
         // Weight display widget
-        let weight_width = 300u32;
-        let weight_height = 60u32;
-        let weight_x = (DISPLAY_WIDTH - weight_width) as i32 / 2;
-        let weight_y = card_y + card_height as i32 + spacing::LG;
-
-        let mut weight_display = WeightDisplay::new(
-            Point::new(weight_x, weight_y),
-            Size::new(weight_width, weight_height),
-        );
+        let weight_area = Self::get_weight_bounds();
+        let mut weight_display = WeightDisplay::new(weight_area.top_left, weight_area.size);
         weight_display.set_weight(state.weight, state.weight_stable);
+        weight_display.set_unit(state.weight_unit);
+        weight_display.set_trend(state.weight_trend);
         weight_display.draw(display)?;
 
         // Bottom buttons
@@ -140,6 +135,18 @@ impl HomeScreen {
         Ok(())
     }
 
+    /// Get the weight readout bounds, just above the buttons (tapping it switches units)
+    pub fn get_weight_bounds() -> Rectangle {
+        let size = Size::new(400, 100);
+        Rectangle::new(
+            Point::new(
+                (DISPLAY_WIDTH - size.width) as i32 / 2,
+                DISPLAY_HEIGHT as i32 - 60 - spacing::LG - size.height as i32,
+            ),
+            size,
+        )
+    }
+
     /// Get button bounds for touch handling
     pub fn get_tare_button_bounds() -> Rectangle {
         Rectangle::new(
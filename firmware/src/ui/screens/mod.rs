@@ -28,12 +28,23 @@ pub use settings::SettingsScreen;
 pub use spool_detail::SpoolDetailScreen;
 pub use spool_info::SpoolInfoScreen;
 
+use crate::ui::overlay;
 use crate::ui::{Screen, UiState, DISPLAY_HEIGHT, DISPLAY_WIDTH};
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
 
-/// Render the current screen
+/// Render the current screen with any toasts and dialogs on top
 pub fn render_screen<D>(display: &mut D, screen: Screen, state: &UiState) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    render_content(display, screen, state)?;
+    // Draw from a copy so the critical section isn't held while drawing
+    let overlays = overlay::with_overlays(|overlays| overlays.clone());
+    overlays.draw(display)
+}
+
+fn render_content<D>(display: &mut D, screen: Screen, state: &UiState) -> Result<(), D::Error>
 where
     D: DrawTarget<Color = Rgb565>,
 {
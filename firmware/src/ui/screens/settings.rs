@@ -128,7 +128,7 @@ impl SettingsScreen {
         };
 
         SettingsRow::new(Point::new(card_x, row_y), row_width, "WiFi")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .with_status(wifi_status)
             .with_value(wifi_value)
             .draw(display)?;
@@ -147,7 +147,7 @@ impl SettingsScreen {
         };
 
         SettingsRow::new(Point::new(card_x, row_y), row_width, "Backend Server")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .with_status(server_status)
             .with_value(server_value)
             .draw(display)?;
@@ -155,7 +155,7 @@ impl SettingsScreen {
 
         // Printers row
         SettingsRow::new(Point::new(card_x, row_y), row_width, "Printers")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .with_value("1 connected")
             .without_separator()
             .draw(display)?;
@@ -192,7 +192,7 @@ impl SettingsScreen {
 
         // Scale Calibration row
         SettingsRow::new(Point::new(card_x, row_y), row_width, "Scale Calibration")
-            .with_icon(Icon::Weight)
+            .with_icon(Icon::Scale)
             .with_value("Calibrated")
             .draw(display)?;
         row_y += SettingsRow::HEIGHT as i32;
@@ -206,7 +206,7 @@ impl SettingsScreen {
 
         // Display row
         SettingsRow::new(Point::new(card_x, row_y), row_width, "Display")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .with_value("80%")
             .without_separator()
             .draw(display)?;
@@ -243,19 +243,19 @@ impl SettingsScreen {
 
         // Check for Updates row
         SettingsRow::new(Point::new(card_x, row_y), row_width, "Check for Updates")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .draw(display)?;
         row_y += SettingsRow::HEIGHT as i32;
 
         // Advanced Settings row
         SettingsRow::new(Point::new(card_x, row_y), row_width, "Advanced Settings")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .draw(display)?;
         row_y += SettingsRow::HEIGHT as i32;
 
         // About SpoolBuddy row
         SettingsRow::new(Point::new(card_x, row_y), row_width, "About SpoolBuddy")
-            .with_icon(Icon::Setting)
+            .with_icon(Icon::Settings)
             .with_value(state.firmware_version.as_str())
             .without_separator()
             .draw(display)?;
@@ -12,7 +12,7 @@
 //! │          K: 0.022 (calibrated)                            │
 //! │                                                            │
 //! │  ┌──────────────────────┐                                 │
-//! │  │    1,098.5 g   ✓     │                                 │
+//! │  │ ▲   1098.5 g   ✓     │  ← tap: g/oz                    │
 //! │  └──────────────────────┘                                 │
 //! │                                                            │
 //! │  [ASSIGN TO AMS]  [UPDATE WEIGHT]  [WRITE TAG]  [DETAILS] │
@@ -20,7 +20,7 @@
 //! └────────────────────────────────────────────────────────────┘
 
 use crate::ui::theme::{self, spacing};
-use crate::ui::widgets::{Button, SpoolCard, StatusBar, WeightDisplay};
+use crate::ui::widgets::{Button, SpoolCard, SpoolCardModel, StatusBar, WeightDisplay};
 use crate::ui::widgets::button::{ButtonBar, ButtonStyle};
 use crate::ui::{UiState, DISPLAY_HEIGHT, DISPLAY_WIDTH};
 use embedded_graphics::{
@@ -37,6 +37,9 @@ pub struct SpoolInfoScreen;
 impl SpoolInfoScreen {
     /// Action button labels
     const BUTTONS: [&'static str; 4] = ["ASSIGN AMS", "UPDATE WT", "WRITE TAG", "DETAILS"];
+    /// Spool card position and height
+    const CARD_Y: i32 = 60;
+    const CARD_HEIGHT: u32 = 120;
 
     /// Render the spool info screen
     pub fn render<D>(display: &mut D, state: &UiState) -> Result<(), D::Error>
@@ -57,30 +60,28 @@ impl SpoolInfoScreen {
         status_bar.draw(display)?;
 
         // Spool card
-        let card_y = 60;
-        let card_height = 120u32;
+        let card_y = Self::CARD_Y;
+        let card_height = Self::CARD_HEIGHT;
         let card = SpoolCard::new(
             Point::new(spacing::MD, card_y),
             Size::new(DISPLAY_WIDTH - (spacing::MD as u32 * 2), card_height),
         );
 
         if let Some(ref spool) = state.spool {
-            card.draw(display, spool)?;
+            card.draw(display, &SpoolCardModel::from(spool))?;
         } else {
             card.draw_empty(display, "No spool data")?;
         }
 
         // Weight display widget
-        let weight_y = card_y + card_height as i32 + spacing::LG;
-        let weight_width = 400u32;
-        let weight_height = 70u32;
-        let weight_x = (DISPLAY_WIDTH - weight_width) as i32 / 2;
-
-        let mut weight_display = WeightDisplay::new(
-            Point::new(weight_x, weight_y),
-            Size::new(weight_width, weight_height),
-        );
+        let weight_area = Self::get_weight_bounds();
+        let weight_y = weight_area.top_left.y;
+        let weight_height = weight_area.size.height;
+
+        let mut weight_display = WeightDisplay::new(weight_area.top_left, weight_area.size);
         weight_display.set_weight(state.weight, state.weight_stable);
+        weight_display.set_unit(state.weight_unit);
+        weight_display.set_trend(state.weight_trend);
         weight_display.draw(display)?;
 
         // Additional weight info
@@ -122,6 +123,18 @@ impl SpoolInfoScreen {
         Ok(())
     }
 
+    /// Get the weight readout bounds, below the spool card (tapping it switches units)
+    pub fn get_weight_bounds() -> Rectangle {
+        let size = Size::new(400, 100);
+        Rectangle::new(
+            Point::new(
+                (DISPLAY_WIDTH - size.width) as i32 / 2,
+                Self::CARD_Y + Self::CARD_HEIGHT as i32 + spacing::LG,
+            ),
+            size,
+        )
+    }
+
     /// Get which action button was pressed
     pub fn get_button_at(point: Point) -> Option<usize> {
         let button_y = DISPLAY_HEIGHT as i32 - 60;
@@ -1,8 +1,11 @@
 //! Theme definitions for SpoolBuddy UI.
 //!
-//! Supports both light and dark themes with teal accent colors.
+//! Supports light, dark and high-contrast themes with a teal accent color
+//! that the user can replace. The theme choice is synced from the server's
+//! device settings.
 
-use embedded_graphics::pixelcolor::Rgb565;
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use embedded_graphics::pixelcolor::{raw::RawU16, Rgb565};
 use embedded_graphics::prelude::{IntoStorage, RgbColor};
 
 /// Theme mode
@@ -11,6 +14,44 @@ pub enum ThemeMode {
     Light,
     #[default]
     Dark,
+    HighContrast,
+}
+
+impl ThemeMode {
+    /// Parse the mode name used by the server settings
+    pub fn from_setting(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(ThemeMode::Light),
+            "dark" => Some(ThemeMode::Dark),
+            "high_contrast" => Some(ThemeMode::HighContrast),
+            _ => None,
+        }
+    }
+
+    /// Mode name used by the server settings
+    pub fn as_setting(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => "light",
+            ThemeMode::Dark => "dark",
+            ThemeMode::HighContrast => "high_contrast",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => ThemeMode::Light,
+            2 => ThemeMode::HighContrast,
+            _ => ThemeMode::Dark,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            ThemeMode::Light => 0,
+            ThemeMode::Dark => 1,
+            ThemeMode::HighContrast => 2,
+        }
+    }
 }
 
 /// Color palette for a theme
@@ -46,76 +87,142 @@ pub struct ThemeColors {
     pub border: Rgb565,
 }
 
+/// Convert a 0xRRGGBB color to Rgb565
+pub const fn hex(rgb: u32) -> Rgb565 {
+    Rgb565::new(
+        ((rgb >> 19) & 0x1F) as u8,
+        ((rgb >> 10) & 0x3F) as u8,
+        ((rgb >> 3) & 0x1F) as u8,
+    )
+}
+
 /// Dark theme colors - Bambu Lab inspired
 pub const DARK_THEME: ThemeColors = ThemeColors {
-    bg: Rgb565::new(0x03, 0x03, 0x03),              // #1A1A1A (near black)
-    card_bg: Rgb565::new(0x05, 0x0B, 0x0B),         // #2D2D2D (dark gray)
-    primary: Rgb565::new(0x00, 0x2B, 0x16),         // #00ADB5 (cyan/teal - Bambu style)
-    text_primary: Rgb565::WHITE,                    // #FFFFFF
-    text_secondary: Rgb565::new(0x16, 0x2C, 0x16),  // #B0B0B0 (gray)
-    success: Rgb565::new(0x09, 0x2B, 0x0A),         // #4CAF50 (green)
-    warning: Rgb565::new(0x1F, 0x30, 0x00),         // #FFC107 (amber)
-    error: Rgb565::new(0x1E, 0x08, 0x06),           // #F44336 (red)
-    disabled: Rgb565::new(0x0E, 0x1C, 0x0E),        // #707070 (muted)
-    status_bar_bg: Rgb565::new(0x02, 0x04, 0x02),   // #101010 (darker)
-    button_bg: Rgb565::new(0x05, 0x0B, 0x0B),       // #2D2D2D (card_bg)
-    button_pressed: Rgb565::new(0x00, 0x2B, 0x16),  // primary
-    progress_bg: Rgb565::new(0x07, 0x0F, 0x0F),     // #3D3D3D (elevated)
-    border: Rgb565::new(0x07, 0x0F, 0x0F),          // #3D3D3D
+    bg: hex(0x1A1A1A),             // near black
+    card_bg: hex(0x2D2D2D),        // dark gray
+    primary: hex(0x00ADB5),        // cyan/teal - Bambu style
+    text_primary: Rgb565::WHITE,
+    text_secondary: hex(0xB0B0B0), // gray
+    success: hex(0x4CAF50),        // green
+    warning: hex(0xFFC107),        // amber
+    error: hex(0xF44336),          // red
+    disabled: hex(0x707070),       // muted
+    status_bar_bg: hex(0x101010),  // darker
+    button_bg: hex(0x2D2D2D),      // card_bg
+    button_pressed: hex(0x00ADB5), // primary
+    progress_bg: hex(0x3D3D3D),    // elevated
+    border: hex(0x3D3D3D),
 };
 
 /// Light theme colors
 pub const LIGHT_THEME: ThemeColors = ThemeColors {
-    bg: Rgb565::new(0x1f, 0x3f, 0x1f),              // #f8f9fa (off-white)
-    card_bg: Rgb565::WHITE,                         // #ffffff
-    primary: Rgb565::new(0x00, 0x2a, 0x12),         // #00a884 (darker teal for contrast)
-    text_primary: Rgb565::new(0x02, 0x04, 0x02),    // #111827 (dark gray)
-    text_secondary: Rgb565::new(0x0c, 0x18, 0x0c),  // #6b7280 (medium gray)
-    success: Rgb565::new(0x04, 0x28, 0x08),         // #22c55e
-    warning: Rgb565::new(0x1f, 0x25, 0x00),         // #f59e0b
-    error: Rgb565::new(0x1e, 0x08, 0x08),           // #ef4444
-    disabled: Rgb565::new(0x17, 0x2e, 0x17),        // #d1d5db
-    status_bar_bg: Rgb565::new(0x1d, 0x3b, 0x1d),   // #e5e7eb
-    button_bg: Rgb565::new(0x1d, 0x3b, 0x1d),       // #e5e7eb
-    button_pressed: Rgb565::new(0x00, 0x2a, 0x12),  // primary
-    progress_bg: Rgb565::new(0x1d, 0x3b, 0x1d),     // #e5e7eb
-    border: Rgb565::new(0x17, 0x2e, 0x17),          // #d1d5db
+    bg: hex(0xF8F9FA),             // off-white
+    card_bg: Rgb565::WHITE,
+    primary: hex(0x00A884),        // darker teal for contrast
+    text_primary: hex(0x111827),   // dark gray
+    text_secondary: hex(0x6B7280), // medium gray
+    success: hex(0x22C55E),
+    warning: hex(0xF59E0B),
+    error: hex(0xEF4444),
+    disabled: hex(0xD1D5DB),
+    status_bar_bg: hex(0xE5E7EB),
+    button_bg: hex(0xE5E7EB),
+    button_pressed: hex(0x00A884), // primary
+    progress_bg: hex(0xE5E7EB),
+    border: hex(0xD1D5DB),
+};
+
+/// High-contrast theme colors - pure black and white with a bright accent
+pub const HIGH_CONTRAST_THEME: ThemeColors = ThemeColors {
+    bg: Rgb565::BLACK,
+    card_bg: Rgb565::BLACK,
+    primary: hex(0xFFD600),        // yellow
+    text_primary: Rgb565::WHITE,
+    text_secondary: hex(0xE0E0E0),
+    success: hex(0x00E676),
+    warning: hex(0xFFD600),
+    error: hex(0xFF5252),
+    disabled: hex(0x9E9E9E),
+    status_bar_bg: Rgb565::BLACK,
+    button_bg: hex(0x262626),
+    button_pressed: hex(0xFFD600), // primary
+    progress_bg: hex(0x404040),
+    border: Rgb565::WHITE,
 };
 
-/// Current theme instance (thread-safe via critical section)
-static mut CURRENT_THEME: ThemeMode = ThemeMode::Dark;
+/// Current theme mode
+static THEME_MODE: AtomicU8 = AtomicU8::new(1);
+
+/// User accent color as RGB565 with bit 16 set, 0 for the theme's own accent
+static ACCENT: AtomicU32 = AtomicU32::new(0);
+const ACCENT_SET: u32 = 1 << 16;
 
 /// Get the current theme colors
-pub fn theme() -> &'static ThemeColors {
-    unsafe {
-        match CURRENT_THEME {
-            ThemeMode::Dark => &DARK_THEME,
-            ThemeMode::Light => &LIGHT_THEME,
-        }
+pub fn theme() -> ThemeColors {
+    let mut colors = match theme_mode() {
+        ThemeMode::Dark => DARK_THEME,
+        ThemeMode::Light => LIGHT_THEME,
+        ThemeMode::HighContrast => HIGH_CONTRAST_THEME,
+    };
+    if let Some(accent) = accent_color() {
+        colors.primary = accent;
+        colors.button_pressed = accent;
     }
+    colors
 }
 
 /// Get the current theme mode
 pub fn theme_mode() -> ThemeMode {
-    unsafe { CURRENT_THEME }
+    ThemeMode::from_u8(THEME_MODE.load(Ordering::Relaxed))
 }
 
 /// Set the current theme mode
 pub fn set_theme_mode(mode: ThemeMode) {
-    unsafe {
-        CURRENT_THEME = mode;
-    }
+    THEME_MODE.store(mode.as_u8(), Ordering::Relaxed);
 }
 
 /// Toggle between light and dark themes
 pub fn toggle_theme() -> ThemeMode {
-    unsafe {
-        CURRENT_THEME = match CURRENT_THEME {
-            ThemeMode::Dark => ThemeMode::Light,
-            ThemeMode::Light => ThemeMode::Dark,
-        };
-        CURRENT_THEME
+    let mode = match theme_mode() {
+        ThemeMode::Dark => ThemeMode::Light,
+        ThemeMode::Light | ThemeMode::HighContrast => ThemeMode::Dark,
+    };
+    set_theme_mode(mode);
+    mode
+}
+
+/// User accent color, `None` when the theme's own accent is used
+pub fn accent_color() -> Option<Rgb565> {
+    let raw = ACCENT.load(Ordering::Relaxed);
+    (raw & ACCENT_SET != 0).then(|| Rgb565::from(RawU16::new(raw as u16)))
+}
+
+/// Replace the theme's accent color, or restore it with `None`
+pub fn set_accent_color(color: Option<Rgb565>) {
+    let raw = color.map_or(0, |color| ACCENT_SET | color.into_storage() as u32);
+    ACCENT.store(raw, Ordering::Relaxed);
+}
+
+/// Parse a "#RRGGBB" color
+pub fn parse_hex_color(text: &str) -> Option<Rgb565> {
+    let digits = text.strip_prefix('#').unwrap_or(text);
+    if digits.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok().map(hex)
+}
+
+/// Apply the theme settings from the server.
+///
+/// Unknown modes leave the mode unchanged. Returns true if the theme changed
+/// and the screen needs a redraw.
+pub fn apply_settings(mode: &str, accent: Option<&str>) -> bool {
+    let before = (theme_mode(), accent_color());
+    if let Some(mode) = ThemeMode::from_setting(mode) {
+        set_theme_mode(mode);
     }
+    set_accent_color(accent.and_then(parse_hex_color));
+    before != (theme_mode(), accent_color())
 }
 
 /// Convert RGBA u32 to Rgb565
@@ -129,6 +236,28 @@ pub fn rgba_to_rgb565(rgba: u32) -> Rgb565 {
     Rgb565::new(r >> 3, g >> 2, b >> 3)
 }
 
+/// Color at `pos` (0..`len`) along a gradient through evenly spaced RGBA stops
+pub fn gradient_rgb565(stops: &[u32], pos: u32, len: u32) -> Rgb565 {
+    match stops {
+        [] => Rgb565::BLACK,
+        [only] => rgba_to_rgb565(*only),
+        _ => {
+            // Position in 1/256 steps across the segments between stops
+            let segments = (stops.len() - 1) as u32;
+            let scaled = pos.min(len.saturating_sub(1)) * segments * 256 / len.saturating_sub(1).max(1);
+            let index = ((scaled / 256) as usize).min(stops.len() - 2);
+            let t = scaled - index as u32 * 256;
+
+            let channel = |shift: u32| {
+                let from = (stops[index] >> shift) & 0xFF;
+                let to = (stops[index + 1] >> shift) & 0xFF;
+                ((from * (256 - t) + to * t) / 256) as u8
+            };
+            Rgb565::new(channel(24) >> 3, channel(16) >> 2, channel(8) >> 3)
+        }
+    }
+}
+
 /// Blend two colors (for hover effects, transparency, etc.)
 pub fn blend_colors(fg: Rgb565, bg: Rgb565, alpha: u8) -> Rgb565 {
     let fg_raw = fg.into_storage();
@@ -0,0 +1,293 @@
+//! On-screen text keyboard for touch input.
+//!
+//! Layout (lowercase mode):
+//! ┌────────────────────────────────────────┐
+//! │ 1  2  3  4  5  6  7  8  9  0           │
+//! │ q  w  e  r  t  y  u  i  o  p           │
+//! │  a  s  d  f  g  h  j  k  l             │
+//! │ [^]  z  x  c  v  b  n  m  [<-]         │
+//! │ [?123]     [   space   ]     [OK]      │
+//! └────────────────────────────────────────┘
+
+use crate::ui::theme::{self, radius, spacing};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_10X20, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Text},
+};
+use heapless::Vec;
+
+/// Key produced by the keyboard and keypad widgets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// Printable character
+    Char(char),
+    /// Delete the character before the cursor
+    Backspace,
+    /// Clear the whole input
+    Clear,
+    /// Confirm the input
+    Enter,
+    /// Toggle upper case for the next character
+    Shift,
+    /// Switch between letters and symbols
+    Mode,
+}
+
+/// Character set shown by the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyboardMode {
+    #[default]
+    Lower,
+    Upper,
+    Symbols,
+}
+
+/// Character rows per mode; the last row is framed by Shift and Backspace
+const LOWER_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+const UPPER_ROWS: [&str; 4] = ["1234567890", "QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+const SYMBOL_ROWS: [&str; 4] = ["1234567890", "-/:;()$&@\"", "_#%*+=<>?", ".,!'~^[]"];
+
+/// Number of key rows, including the bottom Mode/Space/Enter row
+const ROWS: i32 = 5;
+/// Number of standard-width keys that fit in a row
+const COLUMNS: i32 = 10;
+/// Gap between keys
+const GAP: i32 = spacing::XS;
+/// Widths in half-key units of the keys around the character rows
+const SHIFT_UNITS: i32 = 3;
+const MODE_UNITS: i32 = 4;
+const SPACE_UNITS: i32 = 10;
+const ENTER_UNITS: i32 = 4;
+
+/// Maximum number of keys on screen at once
+const MAX_KEYS: usize = 44;
+
+/// On-screen keyboard widget
+pub struct Keyboard {
+    /// Position (top-left corner)
+    pub position: Point,
+    /// Size of the keyboard area
+    pub size: Size,
+    /// Current character set
+    pub mode: KeyboardMode,
+    /// Key currently held down, for pressed feedback
+    pub pressed: Option<Key>,
+    /// Label of the Enter key
+    pub enter_label: &'static str,
+}
+
+impl Keyboard {
+    /// Create a keyboard filling the given area
+    pub fn new(position: Point, size: Size) -> Self {
+        Self {
+            position,
+            size,
+            mode: KeyboardMode::Lower,
+            pressed: None,
+            enter_label: "OK",
+        }
+    }
+
+    /// Create a keyboard across the bottom of an 800x480 screen
+    pub fn bottom(screen_width: u32, screen_height: u32) -> Self {
+        let height = screen_height * 2 / 5;
+        Self::new(
+            Point::new(0, (screen_height - height) as i32),
+            Size::new(screen_width, height),
+        )
+    }
+
+    /// Set the Enter key label (e.g., "Connect", "Save")
+    pub fn with_enter_label(mut self, label: &'static str) -> Self {
+        self.enter_label = label;
+        self
+    }
+
+    /// Get bounds for hit testing
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(self.position, self.size)
+    }
+
+    /// Check if point is within the keyboard
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn rows(&self) -> &'static [&'static str; 4] {
+        match self.mode {
+            KeyboardMode::Lower => &LOWER_ROWS,
+            KeyboardMode::Upper => &UPPER_ROWS,
+            KeyboardMode::Symbols => &SYMBOL_ROWS,
+        }
+    }
+
+    /// Width of a standard key
+    fn key_width(&self) -> i32 {
+        (self.size.width as i32 - GAP * (COLUMNS + 1)) / COLUMNS
+    }
+
+    /// Height of a key row
+    fn key_height(&self) -> i32 {
+        (self.size.height as i32 - GAP * (ROWS + 1)) / ROWS
+    }
+
+    /// Pixel width of a key spanning `units` half-keys
+    fn units_width(&self, units: i32) -> i32 {
+        units * (self.key_width() + GAP) / 2 - GAP
+    }
+
+    /// Compute the rectangle of every key in the current mode
+    fn layout(&self) -> Vec<(Rectangle, Key), MAX_KEYS> {
+        let mut keys = Vec::new();
+        let key_h = self.key_height();
+        let row_y = |row: i32| self.position.y + GAP + row * (key_h + GAP);
+
+        // Places a row of keys given as (half-key units, key), centered horizontally
+        let place_row = |keys: &mut Vec<(Rectangle, Key), MAX_KEYS>, row: i32, row_keys: &[(i32, Key)]| {
+            let total: i32 = row_keys.iter().map(|(units, _)| self.units_width(*units) + GAP).sum::<i32>() - GAP;
+            let mut x = self.position.x + (self.size.width as i32 - total) / 2;
+            for (units, key) in row_keys {
+                let width = self.units_width(*units);
+                let rect = Rectangle::new(Point::new(x, row_y(row)), Size::new(width as u32, key_h as u32));
+                let _ = keys.push((rect, *key));
+                x += width + GAP;
+            }
+        };
+
+        let rows = self.rows();
+        for (row, chars) in rows.iter().enumerate() {
+            let mut row_keys: Vec<(i32, Key), 12> = Vec::new();
+            let last = row == rows.len() - 1;
+            if last {
+                let _ = row_keys.push((SHIFT_UNITS, Key::Shift));
+            }
+            for c in chars.chars() {
+                let _ = row_keys.push((2, Key::Char(c)));
+            }
+            if last {
+                let _ = row_keys.push((SHIFT_UNITS, Key::Backspace));
+            }
+            place_row(&mut keys, row as i32, &row_keys);
+        }
+
+        place_row(
+            &mut keys,
+            ROWS - 1,
+            &[(MODE_UNITS, Key::Mode), (SPACE_UNITS, Key::Char(' ')), (ENTER_UNITS, Key::Enter)],
+        );
+
+        keys
+    }
+
+    /// Find the key at a touch point
+    pub fn key_at(&self, point: Point) -> Option<Key> {
+        if !self.contains(point) {
+            return None;
+        }
+        self.layout()
+            .iter()
+            .find(|(rect, _)| rect.contains(point))
+            .map(|(_, key)| *key)
+    }
+
+    /// Handle a touch press.
+    ///
+    /// Shift and Mode only change the keyboard and return `None`; other keys
+    /// are returned for the caller to apply to its input. Shift applies to a
+    /// single character.
+    pub fn press(&mut self, point: Point) -> Option<Key> {
+        let key = self.key_at(point)?;
+        self.pressed = Some(key);
+        match key {
+            Key::Shift => {
+                self.mode = match self.mode {
+                    KeyboardMode::Lower => KeyboardMode::Upper,
+                    KeyboardMode::Upper => KeyboardMode::Lower,
+                    KeyboardMode::Symbols => KeyboardMode::Symbols,
+                };
+                None
+            }
+            Key::Mode => {
+                self.mode = match self.mode {
+                    KeyboardMode::Symbols => KeyboardMode::Lower,
+                    _ => KeyboardMode::Symbols,
+                };
+                None
+            }
+            Key::Char(c) => {
+                if self.mode == KeyboardMode::Upper && c.is_ascii_alphabetic() {
+                    self.mode = KeyboardMode::Lower;
+                }
+                Some(key)
+            }
+            _ => Some(key),
+        }
+    }
+
+    /// Clear pressed feedback when the touch is released
+    pub fn release(&mut self) {
+        self.pressed = None;
+    }
+
+    fn label<'a>(&self, key: Key, buf: &'a mut [u8; 4]) -> &'a str {
+        match key {
+            Key::Char(' ') => "space",
+            Key::Char(c) => c.encode_utf8(buf),
+            Key::Backspace => "<-",
+            Key::Clear => "C",
+            Key::Enter => self.enter_label,
+            Key::Shift => "^",
+            Key::Mode => match self.mode {
+                KeyboardMode::Symbols => "abc",
+                _ => "?123",
+            },
+        }
+    }
+
+    /// Draw the keyboard
+    pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+
+        // Keyboard background
+        self.bounds()
+            .into_styled(PrimitiveStyle::with_fill(theme.status_bar_bg))
+            .draw(display)?;
+
+        for (rect, key) in self.layout() {
+            let pressed = self.pressed == Some(key);
+            let active = key == Key::Shift && self.mode == KeyboardMode::Upper;
+
+            let (bg_color, text_color) = if pressed {
+                (theme.button_pressed, theme.bg)
+            } else if key == Key::Enter || active {
+                (theme.primary, theme.bg)
+            } else if matches!(key, Key::Char(_)) {
+                (theme.button_bg, theme.text_primary)
+            } else {
+                (theme.progress_bg, theme.text_primary)
+            };
+
+            RoundedRectangle::with_equal_corners(rect, Size::new(radius::SM, radius::SM))
+                .into_styled(PrimitiveStyle::with_fill(bg_color))
+                .draw(display)?;
+
+            let mut buf = [0u8; 4];
+            let text_style = MonoTextStyle::new(&FONT_10X20, text_color);
+            Text::with_alignment(
+                self.label(key, &mut buf),
+                rect.center() + Point::new(0, 6),
+                text_style,
+                Alignment::Center,
+            )
+            .draw(display)?;
+        }
+
+        Ok(())
+    }
+}
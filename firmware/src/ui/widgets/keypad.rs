@@ -0,0 +1,183 @@
+//! Numeric keypad for entering weights and other numbers.
+//!
+//! ┌───────────────────────┐
+//! │ [7] [8] [9]  [<-]     │
+//! │ [4] [5] [6]  [C]      │
+//! │ [1] [2] [3]  [  ]     │
+//! │ [.] [0] [-]  [OK]     │
+//! └───────────────────────┘
+
+use crate::ui::theme::{self, radius, spacing};
+use crate::ui::widgets::keyboard::Key;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_10X20, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Text},
+};
+
+/// Key grid; `None` marks the cells covered by the tall Enter key
+const GRID: [[Option<Key>; 4]; 4] = [
+    [Some(Key::Char('7')), Some(Key::Char('8')), Some(Key::Char('9')), Some(Key::Backspace)],
+    [Some(Key::Char('4')), Some(Key::Char('5')), Some(Key::Char('6')), Some(Key::Clear)],
+    [Some(Key::Char('1')), Some(Key::Char('2')), Some(Key::Char('3')), None],
+    [Some(Key::Char('.')), Some(Key::Char('0')), Some(Key::Char('-')), None],
+];
+
+/// Gap between keys
+const GAP: i32 = spacing::SM;
+
+/// Numeric keypad widget
+pub struct NumericKeypad {
+    /// Position (top-left corner)
+    pub position: Point,
+    /// Size of the keypad area
+    pub size: Size,
+    /// Whether the decimal point key is enabled
+    pub allow_decimal: bool,
+    /// Whether the minus key is enabled
+    pub allow_negative: bool,
+    /// Key currently held down, for pressed feedback
+    pub pressed: Option<Key>,
+}
+
+impl NumericKeypad {
+    /// Create a keypad for positive decimal numbers
+    pub fn new(position: Point, size: Size) -> Self {
+        Self {
+            position,
+            size,
+            allow_decimal: true,
+            allow_negative: false,
+            pressed: None,
+        }
+    }
+
+    /// Only allow whole numbers
+    pub fn integer_only(mut self) -> Self {
+        self.allow_decimal = false;
+        self
+    }
+
+    /// Allow negative numbers
+    pub fn with_negative(mut self) -> Self {
+        self.allow_negative = true;
+        self
+    }
+
+    /// Get bounds for hit testing
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(self.position, self.size)
+    }
+
+    /// Check if point is within the keypad
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    fn enabled(&self, key: Key) -> bool {
+        match key {
+            Key::Char('.') => self.allow_decimal,
+            Key::Char('-') => self.allow_negative,
+            _ => true,
+        }
+    }
+
+    fn cell_size(&self) -> Size {
+        Size::new(
+            ((self.size.width as i32 - GAP * 3) / 4) as u32,
+            ((self.size.height as i32 - GAP * 3) / 4) as u32,
+        )
+    }
+
+    fn cell_rect(&self, row: usize, col: usize) -> Rectangle {
+        let cell = self.cell_size();
+        let origin = self.position
+            + Point::new(
+                col as i32 * (cell.width as i32 + GAP),
+                row as i32 * (cell.height as i32 + GAP),
+            );
+        Rectangle::new(origin, cell)
+    }
+
+    /// Enter spans the bottom two cells of the right column
+    fn enter_rect(&self) -> Rectangle {
+        let top = self.cell_rect(2, 3);
+        let cell = self.cell_size();
+        Rectangle::new(top.top_left, Size::new(cell.width, cell.height * 2 + GAP as u32))
+    }
+
+    /// Iterate over every key with its rectangle
+    fn keys(&self) -> impl Iterator<Item = (Rectangle, Key)> + '_ {
+        GRID.iter()
+            .enumerate()
+            .flat_map(move |(row, cols)| {
+                cols.iter()
+                    .enumerate()
+                    .filter_map(move |(col, key)| key.map(|key| (self.cell_rect(row, col), key)))
+            })
+            .chain(core::iter::once((self.enter_rect(), Key::Enter)))
+    }
+
+    /// Find the enabled key at a touch point
+    pub fn key_at(&self, point: Point) -> Option<Key> {
+        if !self.contains(point) {
+            return None;
+        }
+        self.keys()
+            .find(|(rect, key)| rect.contains(point) && self.enabled(*key))
+            .map(|(_, key)| key)
+    }
+
+    /// Handle a touch press, returning the key to apply to the input
+    pub fn press(&mut self, point: Point) -> Option<Key> {
+        let key = self.key_at(point)?;
+        self.pressed = Some(key);
+        Some(key)
+    }
+
+    /// Clear pressed feedback when the touch is released
+    pub fn release(&mut self) {
+        self.pressed = None;
+    }
+
+    /// Draw the keypad
+    pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+
+        for (rect, key) in self.keys() {
+            let (bg_color, text_color) = if !self.enabled(key) {
+                (theme.disabled, theme.text_secondary)
+            } else if self.pressed == Some(key) {
+                (theme.button_pressed, theme.bg)
+            } else if key == Key::Enter {
+                (theme.primary, theme.bg)
+            } else if matches!(key, Key::Char(_)) {
+                (theme.button_bg, theme.text_primary)
+            } else {
+                (theme.progress_bg, theme.text_primary)
+            };
+
+            RoundedRectangle::with_equal_corners(rect, Size::new(radius::MD, radius::MD))
+                .into_styled(PrimitiveStyle::with_fill(bg_color))
+                .draw(display)?;
+
+            let mut buf = [0u8; 4];
+            let label = match key {
+                Key::Char(c) => c.encode_utf8(&mut buf),
+                Key::Backspace => "<-",
+                Key::Clear => "C",
+                _ => "OK",
+            };
+            let text_style = MonoTextStyle::new(&FONT_10X20, text_color);
+            Text::with_alignment(label, rect.center() + Point::new(0, 6), text_style, Alignment::Center)
+                .draw(display)?;
+        }
+
+        Ok(())
+    }
+}
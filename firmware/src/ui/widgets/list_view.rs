@@ -0,0 +1,407 @@
+//! Scrollable list widget with touch drag and kinetic scrolling.
+//!
+//! ┌─────────────────────────────────────┐
+//! │ (o) Bambu PLA Basic         842 g   │
+//! │     Jade White · AMS A1             │
+//! ├─────────────────────────────────────┤
+//! │ (o) PETG HF                 1000 g ▐│
+//! │     Black · Shelf                  ▐│
+//! └─────────────────────────────────────┘
+//!
+//! The list keeps its own scroll state, so screens hold on to the widget
+//! between renders. Feed it touch events, call `tick()` every frame while it
+//! coasts, and pass the items to `draw()`.
+
+use crate::ui::theme::{self, spacing};
+use crate::ui::widgets::icon::Icon;
+use crate::ui::TouchEvent;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, ascii::FONT_10X20, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{Circle, Line, PrimitiveStyle, Rectangle},
+    text::{Alignment, Text},
+};
+
+/// Movement (px) below which a press and release counts as a tap
+const TAP_SLOP: i32 = 10;
+/// Velocity kept per frame while coasting (out of 256)
+const FRICTION: i32 = 240;
+/// Velocity (px/frame, 8.8 fixed point) below which coasting stops
+const MIN_VELOCITY: i32 = 64;
+/// Share (out of 256) of the overscroll pulled back per frame
+const SPRING: i32 = 64;
+/// Furthest the list can be dragged past its ends
+const MAX_OVERSCROLL: i32 = 60;
+/// Width of the scroll indicator
+const SCROLLBAR_WIDTH: u32 = 4;
+
+/// One row of a list
+#[derive(Clone, Copy)]
+pub struct ListItem<'a> {
+    /// Leading icon
+    pub icon: Option<Icon>,
+    /// Leading color swatch, drawn when there is no icon
+    pub swatch: Option<Rgb565>,
+    /// Main text
+    pub title: &'a str,
+    /// Secondary text below the title
+    pub subtitle: Option<&'a str>,
+    /// Trailing value (e.g., weight)
+    pub value: Option<&'a str>,
+}
+
+impl<'a> ListItem<'a> {
+    /// Create a row with only a title
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            icon: None,
+            swatch: None,
+            title,
+            subtitle: None,
+            value: None,
+        }
+    }
+
+    /// Set leading icon
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Set leading color swatch
+    pub fn with_swatch(mut self, color: Rgb565) -> Self {
+        self.swatch = Some(color);
+        self
+    }
+
+    /// Set subtitle
+    pub fn with_subtitle(mut self, subtitle: &'a str) -> Self {
+        self.subtitle = Some(subtitle);
+        self
+    }
+
+    /// Set trailing value
+    pub fn with_value(mut self, value: &'a str) -> Self {
+        self.value = Some(value);
+        self
+    }
+}
+
+/// Touch drag in progress
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    start_y: i32,
+    last_y: i32,
+    moved: bool,
+}
+
+/// Scrollable list widget
+pub struct ListView {
+    /// Position (top-left corner of the viewport)
+    pub position: Point,
+    /// Size of the viewport
+    pub size: Size,
+    /// Height of each row
+    pub row_height: u32,
+    /// Number of rows, for scroll limits
+    item_count: usize,
+    /// Scroll offset in px, 8.8 fixed point
+    offset: i32,
+    /// Scroll velocity in px per frame, 8.8 fixed point
+    velocity: i32,
+    /// Active drag
+    drag: Option<Drag>,
+    /// Row under the finger, for pressed feedback
+    pressed: Option<usize>,
+}
+
+impl ListView {
+    /// Default row height
+    pub const ROW_HEIGHT: u32 = 64;
+
+    /// Create an empty list filling the given viewport
+    pub fn new(position: Point, size: Size) -> Self {
+        Self {
+            position,
+            size,
+            row_height: Self::ROW_HEIGHT,
+            item_count: 0,
+            offset: 0,
+            velocity: 0,
+            drag: None,
+            pressed: None,
+        }
+    }
+
+    /// Set row height
+    pub fn with_row_height(mut self, row_height: u32) -> Self {
+        self.row_height = row_height.max(1);
+        self
+    }
+
+    /// Update the number of rows, keeping the scroll position in range
+    pub fn set_item_count(&mut self, count: usize) {
+        self.item_count = count;
+        self.offset = self.offset.clamp(0, self.max_offset() << 8);
+    }
+
+    /// Get viewport bounds for hit testing
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(self.position, self.size)
+    }
+
+    /// Check if point is within the viewport
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    /// Current scroll offset in px
+    pub fn scroll_offset(&self) -> i32 {
+        self.offset >> 8
+    }
+
+    /// Jump to the top of the list
+    pub fn scroll_to_top(&mut self) {
+        self.offset = 0;
+        self.velocity = 0;
+    }
+
+    /// Scroll just enough to show a row
+    pub fn scroll_to(&mut self, index: usize) {
+        let top = index as i32 * self.row_height as i32;
+        let bottom = top + self.row_height as i32 - self.size.height as i32;
+        let current = self.scroll_offset();
+        let target = current.min(top).max(bottom).clamp(0, self.max_offset());
+        self.offset = target << 8;
+        self.velocity = 0;
+    }
+
+    /// Whether the list is still moving and needs `tick()` and redraws
+    pub fn is_animating(&self) -> bool {
+        self.drag.is_none() && (self.velocity != 0 || self.overscroll() != 0)
+    }
+
+    fn content_height(&self) -> i32 {
+        self.item_count as i32 * self.row_height as i32
+    }
+
+    fn max_offset(&self) -> i32 {
+        (self.content_height() - self.size.height as i32).max(0)
+    }
+
+    /// Distance (px) the list is currently scrolled past either end
+    fn overscroll(&self) -> i32 {
+        let offset = self.scroll_offset();
+        if offset < 0 {
+            offset
+        } else {
+            (offset - self.max_offset()).max(0)
+        }
+    }
+
+    /// Row index at a point in the viewport
+    fn row_at(&self, point: Point) -> Option<usize> {
+        if !self.contains(point) {
+            return None;
+        }
+        let y = point.y - self.position.y + self.scroll_offset();
+        if y < 0 {
+            return None;
+        }
+        let index = (y / self.row_height as i32) as usize;
+        (index < self.item_count).then_some(index)
+    }
+
+    /// Handle a touch event.
+    ///
+    /// Drags scroll the list and keep their speed when released; a press and
+    /// release without movement returns the tapped row index.
+    pub fn handle_touch(&mut self, event: TouchEvent) -> Option<usize> {
+        match event {
+            TouchEvent::Press { x, y } => {
+                let point = Point::new(x as i32, y as i32);
+                if !self.contains(point) {
+                    return None;
+                }
+                // Touching a coasting list stops it without selecting a row
+                let coasting = self.velocity.abs() > MIN_VELOCITY;
+                self.velocity = 0;
+                self.pressed = if coasting { None } else { self.row_at(point) };
+                self.drag = Some(Drag {
+                    start_y: y as i32,
+                    last_y: y as i32,
+                    moved: coasting,
+                });
+                None
+            }
+            TouchEvent::Move { y, .. } => {
+                let max_offset = self.max_offset();
+                let drag = self.drag.as_mut()?;
+                let y = y as i32;
+                let delta = drag.last_y - y;
+                drag.last_y = y;
+                if (y - drag.start_y).abs() > TAP_SLOP {
+                    drag.moved = true;
+                    self.pressed = None;
+                }
+
+                // Drag past the ends with resistance
+                let offset = self.offset >> 8;
+                let delta = if offset < 0 || offset > max_offset { delta / 2 } else { delta };
+                let offset = (offset + delta).clamp(-MAX_OVERSCROLL, max_offset + MAX_OVERSCROLL);
+                self.offset = offset << 8;
+
+                // Smooth the release speed over the last few moves
+                self.velocity = (self.velocity + (delta << 8)) / 2;
+                None
+            }
+            TouchEvent::Release { .. } => {
+                let drag = self.drag.take()?;
+                let tapped = self.pressed.take();
+                if drag.moved {
+                    None
+                } else {
+                    self.velocity = 0;
+                    tapped
+                }
+            }
+        }
+    }
+
+    /// Advance kinetic scrolling by one frame.
+    ///
+    /// Returns true if the list moved and needs a redraw.
+    pub fn tick(&mut self) -> bool {
+        if !self.is_animating() {
+            return false;
+        }
+        let before = self.offset;
+
+        if self.velocity != 0 {
+            self.offset += self.velocity;
+            self.velocity = self.velocity * FRICTION / 256;
+            if self.velocity.abs() < MIN_VELOCITY {
+                self.velocity = 0;
+            }
+        }
+
+        // Spring back from past either end
+        let overscroll = self.overscroll();
+        if overscroll != 0 {
+            self.velocity = 0;
+            let pull = (overscroll * SPRING / 256).abs().max(1) * overscroll.signum();
+            self.offset = (self.scroll_offset() - pull) << 8;
+        }
+
+        self.offset != before
+    }
+
+    /// Draw the visible rows, clipped to the viewport
+    pub fn draw<D>(&self, display: &mut D, items: &[ListItem]) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let viewport = self.bounds();
+        let mut clipped = display.clipped(&viewport);
+
+        viewport
+            .into_styled(PrimitiveStyle::with_fill(theme.bg))
+            .draw(&mut clipped)?;
+
+        let row_height = self.row_height as i32;
+        let offset = self.scroll_offset();
+        let first = (offset.max(0) / row_height) as usize;
+        let visible = (self.size.height as i32 / row_height + 2) as usize;
+
+        for (index, item) in items.iter().enumerate().skip(first).take(visible) {
+            let y = self.position.y + index as i32 * row_height - offset;
+            self.draw_row(&mut clipped, item, y, self.pressed == Some(index))?;
+        }
+
+        // Scroll indicator when the content doesn't fit
+        let content = self.content_height();
+        let height = self.size.height as i32;
+        if content > height {
+            let bar_height = (height * height / content).max(spacing::LG);
+            let travel = height - bar_height;
+            let bar_y = offset.clamp(0, self.max_offset()) * travel / self.max_offset().max(1);
+            Rectangle::new(
+                Point::new(
+                    self.position.x + self.size.width as i32 - SCROLLBAR_WIDTH as i32 - 2,
+                    self.position.y + bar_y,
+                ),
+                Size::new(SCROLLBAR_WIDTH, bar_height as u32),
+            )
+            .into_styled(PrimitiveStyle::with_fill(theme.disabled))
+            .draw(&mut clipped)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_row<D>(&self, display: &mut D, item: &ListItem, y: i32, pressed: bool) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let row_height = self.row_height as i32;
+        let width = self.size.width as i32;
+        let x = self.position.x;
+        let center_y = y + row_height / 2;
+
+        if pressed {
+            Rectangle::new(Point::new(x, y), Size::new(self.size.width, self.row_height))
+                .into_styled(PrimitiveStyle::with_fill(theme.card_bg))
+                .draw(display)?;
+        }
+
+        // Leading icon or color swatch
+        let lead_size = (row_height - spacing::MD * 2).clamp(16, 32) as u32;
+        let lead_pos = Point::new(x + spacing::MD, center_y - lead_size as i32 / 2);
+        let mut text_x = x + spacing::MD;
+        if let Some(icon) = item.icon {
+            icon.draw(display, lead_pos, lead_size, theme.text_primary)?;
+            text_x += lead_size as i32 + spacing::MD;
+        } else if let Some(color) = item.swatch {
+            Circle::new(lead_pos, lead_size)
+                .into_styled(PrimitiveStyle::with_fill(color))
+                .draw(display)?;
+            Circle::new(lead_pos, lead_size)
+                .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+                .draw(display)?;
+            text_x += lead_size as i32 + spacing::MD;
+        }
+
+        // Title and subtitle
+        let title_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+        if let Some(subtitle) = item.subtitle {
+            Text::new(item.title, Point::new(text_x, center_y - 4), title_style).draw(display)?;
+            let subtitle_style = MonoTextStyle::new(&FONT_6X10, theme.text_secondary);
+            Text::new(subtitle, Point::new(text_x, center_y + 14), subtitle_style).draw(display)?;
+        } else {
+            Text::new(item.title, Point::new(text_x, center_y + 6), title_style).draw(display)?;
+        }
+
+        // Trailing value, clear of the scroll indicator
+        if let Some(value) = item.value {
+            let value_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+            Text::with_alignment(
+                value,
+                Point::new(x + width - spacing::MD - SCROLLBAR_WIDTH as i32, center_y + 6),
+                value_style,
+                Alignment::Right,
+            )
+            .draw(display)?;
+        }
+
+        // Separator
+        let sep_y = y + row_height - 1;
+        Line::new(Point::new(text_x, sep_y), Point::new(x + width - spacing::SM, sep_y))
+            .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+            .draw(display)?;
+
+        Ok(())
+    }
+}
@@ -9,12 +9,16 @@ pub mod catalog_card;
 pub mod filter_pill;
 pub mod icon;
 pub mod info_row;
+pub mod keyboard;
+pub mod keypad;
+pub mod list_view;
 pub mod progress_bar;
 pub mod settings_row;
 pub mod slider;
 pub mod spool_card;
 pub mod status_bar;
 pub mod tab_bar;
+pub mod text_input;
 pub mod toggle;
 pub mod weight_display;
 
@@ -23,11 +27,15 @@ pub use button::Button;
 pub use catalog_card::CatalogCard;
 pub use filter_pill::{FilterPill, FilterPillRow};
 pub use info_row::InfoRow;
+pub use keyboard::{Key, Keyboard, KeyboardMode};
+pub use keypad::NumericKeypad;
+pub use list_view::{ListItem, ListView};
 pub use progress_bar::ProgressBar;
 pub use settings_row::{SettingsRow, StatusDot};
 pub use slider::Slider;
-pub use spool_card::SpoolCard;
+pub use spool_card::{SpoolCard, SpoolCardLayout, SpoolCardModel};
 pub use status_bar::StatusBar;
 pub use tab_bar::TabBar;
+pub use text_input::{InputKind, TextInput};
 pub use toggle::Toggle;
-pub use weight_display::WeightDisplay;
+pub use weight_display::{Trend, WeightDisplay, WeightTrend, WeightUnit};
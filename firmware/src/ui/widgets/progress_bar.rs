@@ -36,6 +36,12 @@ impl ProgressBar {
         }
     }
 
+    /// Start with a progress value (0-100)
+    pub fn with_value(mut self, value: u8) -> Self {
+        self.set_value(value);
+        self
+    }
+
     /// Set the progress value (0-100)
     pub fn set_value(&mut self, value: u8) {
         self.value = value.min(100);
@@ -1,4 +1,25 @@
 //! Spool card widget for displaying spool information.
+//!
+//! Cards are drawn from a `SpoolCardModel`, so the scanned-spool screen and
+//! browse grids share one widget.
+//!
+//! Wide layout (scanned spool):
+//! ┌────────────────────────────────────────────┐
+//! │ ┌──────┐  Bambu PLA Basic         [BAMBU]  │
+//! │ │swatch│  Jade White              [LOW]    │
+//! │ └──────┘  [██████████░░░░░]                │
+//! │           850g / 1000g   K: 0.020          │
+//! └────────────────────────────────────────────┘
+//!
+//! Tile layout (browse grid):
+//! ┌──────────────┐
+//! │   ╭──────╮   │
+//! │   │  ()  │   │  progress ring around the swatch
+//! │   ╰──────╯   │
+//! │  PLA Basic   │
+//! │  Bambu       │
+//! │  850 g  85%  │
+//! └──────────────┘
 
 use crate::ui::theme::{self, spacing};
 use crate::ui::{SpoolDisplay, SpoolSource};
@@ -6,53 +27,193 @@ use embedded_graphics::{
     mono_font::{ascii::FONT_6X10, ascii::FONT_10X20, MonoTextStyle},
     pixelcolor::Rgb565,
     prelude::*,
-    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
-    text::Text,
+    primitives::{Arc, Circle, Line, PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Text},
 };
 use super::progress_bar::ProgressBar;
 
+/// Remaining filament (%) at or below which a spool is flagged as low stock
+pub const LOW_STOCK_PERCENT: u8 = 10;
+
+/// Width of the progress ring stroke in the tile layout
+const RING_WIDTH: u32 = 6;
+
+/// Data shown on a spool card
+#[derive(Debug, Clone, Copy)]
+pub struct SpoolCardModel<'a> {
+    pub brand: &'a str,
+    pub material: &'a str,
+    pub color_name: &'a str,
+    pub color_rgba: u32,
+    /// Colors of multi-color filament in order, empty for single-color
+    pub color_stops: &'a [u32],
+    /// Remaining filament in grams
+    pub weight_current: f32,
+    /// Filament weight of a full spool in grams
+    pub weight_label: f32,
+    pub k_value: Option<f32>,
+    /// Where the data came from, `None` hides the badge
+    pub source: Option<SpoolSource>,
+    /// Show the low-stock badge
+    pub low_stock: bool,
+}
+
+impl<'a> SpoolCardModel<'a> {
+    /// Remaining filament as a percentage of the label weight
+    pub fn percentage(&self) -> u8 {
+        theme::weight_percentage(self.weight_current, self.weight_label)
+    }
+
+    /// Override the low-stock flag (e.g., with the server's per-spool threshold)
+    pub fn with_low_stock(mut self, low_stock: bool) -> Self {
+        self.low_stock = low_stock;
+        self
+    }
+
+    /// Hide the source badge
+    pub fn without_source(mut self) -> Self {
+        self.source = None;
+        self
+    }
+}
+
+impl<'a> From<&'a SpoolDisplay> for SpoolCardModel<'a> {
+    fn from(spool: &'a SpoolDisplay) -> Self {
+        let percentage = theme::weight_percentage(spool.weight_current, spool.weight_label);
+        Self {
+            brand: &spool.brand,
+            material: &spool.material,
+            color_name: &spool.color_name,
+            color_rgba: spool.color_rgba,
+            color_stops: &spool.color_stops,
+            weight_current: spool.weight_current,
+            weight_label: spool.weight_label,
+            k_value: spool.k_value,
+            source: Some(spool.source),
+            low_stock: spool.weight_label > 0.0 && percentage <= LOW_STOCK_PERCENT,
+        }
+    }
+}
+
+/// Arrangement of a spool card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpoolCardLayout {
+    /// Swatch on the left, text and a progress bar on the right
+    #[default]
+    Wide,
+    /// Swatch in a progress ring with text below, for grids
+    Tile,
+}
+
 /// Spool card widget showing complete spool information
 pub struct SpoolCard {
     /// Position (top-left corner)
     pub position: Point,
     /// Size of the widget
     pub size: Size,
+    /// Arrangement of the card contents
+    pub layout: SpoolCardLayout,
+    /// Whether the card is highlighted as selected
+    pub selected: bool,
 }
 
 impl SpoolCard {
     /// Create a new spool card
     pub fn new(position: Point, size: Size) -> Self {
-        Self { position, size }
+        Self {
+            position,
+            size,
+            layout: SpoolCardLayout::Wide,
+            selected: false,
+        }
+    }
+
+    /// Set the card layout
+    pub fn with_layout(mut self, layout: SpoolCardLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Set the selected state
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Set the selected state
+    pub fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
     }
 
-    /// Draw the spool card with spool data
-    pub fn draw<D>(&self, display: &mut D, spool: &SpoolDisplay) -> Result<(), D::Error>
+    /// Get bounds for hit testing
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(self.position, self.size)
+    }
+
+    /// Check if point is within the card
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    /// Draw the spool card
+    pub fn draw<D>(&self, display: &mut D, spool: &SpoolCardModel) -> Result<(), D::Error>
     where
         D: DrawTarget<Color = Rgb565>,
     {
-        let theme = theme::theme();
-        let p = self.position;
+        self.draw_background(display)?;
+        match self.layout {
+            SpoolCardLayout::Wide => self.draw_wide(display, spool),
+            SpoolCardLayout::Tile => self.draw_tile(display, spool),
+        }
+    }
 
-        // Card background
+    /// Card background, with a border while selected
+    fn draw_background<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
         let card = RoundedRectangle::with_equal_corners(
-            Rectangle::new(self.position, self.size),
+            self.bounds(),
             Size::new(theme::radius::MD, theme::radius::MD),
         );
-        card.into_styled(PrimitiveStyle::with_fill(theme.card_bg))
+        let bg_color = if self.selected {
+            theme::lighten(theme.card_bg, 10)
+        } else {
+            theme.card_bg
+        };
+        card.into_styled(PrimitiveStyle::with_fill(bg_color))
             .draw(display)?;
+        if self.selected {
+            card.into_styled(PrimitiveStyle::with_stroke(theme.primary, 3))
+                .draw(display)?;
+        }
+        Ok(())
+    }
+
+    fn draw_wide<D>(&self, display: &mut D, spool: &SpoolCardModel) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let p = self.position;
 
         // Color swatch (left side)
         let swatch_size = 60u32;
         let swatch_pos = Point::new(p.x + spacing::MD, p.y + spacing::MD);
-        let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
-
-        let swatch = RoundedRectangle::with_equal_corners(
-            Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size)),
-            Size::new(theme::radius::SM, theme::radius::SM),
-        );
-        swatch
-            .into_styled(PrimitiveStyle::with_fill(swatch_color))
-            .draw(display)?;
+        let swatch_area = Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size));
+        if spool.color_stops.len() >= 2 {
+            draw_gradient(display, swatch_area, spool.color_stops)?;
+        } else {
+            let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
+            let swatch = RoundedRectangle::with_equal_corners(
+                swatch_area,
+                Size::new(theme::radius::SM, theme::radius::SM),
+            );
+            swatch
+                .into_styled(PrimitiveStyle::with_fill(swatch_color))
+                .draw(display)?;
+        }
 
         // Brand and material (top right of swatch)
         let text_x = swatch_pos.x + swatch_size as i32 + spacing::MD;
@@ -61,24 +222,28 @@ impl SpoolCard {
         let title_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
         let subtitle_style = MonoTextStyle::new(&FONT_6X10, theme.text_secondary);
 
-        // Brand + Material
+        // Brand + Material, clear of the badges on the right
         let mut title: heapless::String<64> = heapless::String::new();
         let _ = core::fmt::write(&mut title, format_args!("{} {}", spool.brand, spool.material));
-        Text::new(&title, Point::new(text_x, text_y), title_style).draw(display)?;
+        let title_width = p.x + self.size.width as i32 - spacing::MD * 2 - 50 - text_x;
+        Text::new(fit(&title, title_width, &FONT_10X20), Point::new(text_x, text_y), title_style)
+            .draw(display)?;
 
         // Color name
-        Text::new(&spool.color_name, Point::new(text_x, text_y + 20), subtitle_style).draw(display)?;
+        Text::new(spool.color_name, Point::new(text_x, text_y + 20), subtitle_style).draw(display)?;
 
-        // Weight progress bar
+        // Weight progress bar, red while low on stock
         let progress_y = text_y + 36;
         let progress_width = self.size.width - swatch_size - spacing::MD as u32 * 3 - 60;
-        let percentage = theme::weight_percentage(spool.weight_current, spool.weight_label);
 
         let mut progress = ProgressBar::new(
             Point::new(text_x, progress_y),
             Size::new(progress_width, 16),
         );
-        progress.set_value(percentage);
+        progress.set_value(spool.percentage());
+        if spool.low_stock {
+            progress.set_fill_color(theme.error);
+        }
         progress.draw(display)?;
 
         // Weight text
@@ -106,38 +271,107 @@ impl SpoolCard {
             .draw(display)?;
         }
 
-        // Source badge
-        let badge_text = match spool.source {
-            SpoolSource::Bambu => "BAMBU",
-            SpoolSource::Manual => "MANUAL",
-            SpoolSource::Nfc => "NFC",
-        };
-        let badge_color = match spool.source {
-            SpoolSource::Bambu => theme.primary,
-            SpoolSource::Manual => theme.warning,
-            SpoolSource::Nfc => theme.success,
-        };
-
+        // Source and low-stock badges, stacked in the top right corner
         let badge_x = p.x + self.size.width as i32 - spacing::MD - 50;
-        let badge_y = p.y + spacing::SM;
+        let mut badge_y = p.y + spacing::SM;
+        if let Some(source) = spool.source {
+            draw_source_badge(display, Point::new(badge_x, badge_y), source)?;
+            badge_y += 18 + spacing::XS;
+        }
+        if spool.low_stock {
+            draw_low_stock_badge(display, Point::new(badge_x, badge_y))?;
+        }
 
-        // Badge background
-        let badge = RoundedRectangle::with_equal_corners(
-            Rectangle::new(Point::new(badge_x, badge_y), Size::new(48, 18)),
-            Size::new(theme::radius::SM, theme::radius::SM),
-        );
-        badge
-            .into_styled(PrimitiveStyle::with_fill(badge_color))
+        Ok(())
+    }
+
+    fn draw_tile<D>(&self, display: &mut D, spool: &SpoolCardModel) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let p = self.position;
+        let center_x = p.x + self.size.width as i32 / 2;
+        let text_width = self.size.width as i32 - spacing::SM * 2;
+
+        // Progress ring around a round swatch
+        let diameter = (self.size.width as i32 - spacing::MD * 2)
+            .min(self.size.height as i32 / 2)
+            .max(RING_WIDTH as i32 * 4) as u32;
+        let ring_top_left = Point::new(center_x - diameter as i32 / 2, p.y + spacing::MD);
+        let ring_center = ring_top_left + Point::new(diameter as i32 / 2, diameter as i32 / 2);
+
+        let percentage = spool.percentage();
+        let ring_color = if spool.low_stock {
+            theme.error
+        } else if percentage <= 40 {
+            theme.warning
+        } else {
+            theme.primary
+        };
+        // Stroke is centered on the arc, so shrink it to keep the ring inside `diameter`
+        let ring_diameter = diameter - RING_WIDTH;
+        Circle::with_center(ring_center, ring_diameter)
+            .into_styled(PrimitiveStyle::with_stroke(theme.progress_bg, RING_WIDTH))
             .draw(display)?;
+        if percentage > 0 {
+            Arc::with_center(ring_center, ring_diameter, (-90.0).deg(), (percentage as f32 * 3.6).deg())
+                .into_styled(PrimitiveStyle::with_stroke(ring_color, RING_WIDTH))
+                .draw(display)?;
+        }
 
-        // Badge text
-        let badge_text_style = MonoTextStyle::new(&FONT_6X10, theme.bg);
-        Text::new(
-            badge_text,
-            Point::new(badge_x + 4, badge_y + 12),
-            badge_text_style,
+        let swatch = Circle::with_center(ring_center, diameter - RING_WIDTH * 2 - spacing::XS as u32 * 2);
+        if spool.color_stops.len() >= 2 {
+            let left = swatch.top_left.x;
+            let width = swatch.diameter;
+            display.draw_iter(swatch.points().map(|point| {
+                Pixel(point, theme::gradient_rgb565(spool.color_stops, (point.x - left) as u32, width))
+            }))?;
+        } else {
+            swatch
+                .into_styled(PrimitiveStyle::with_fill(theme::rgba_to_rgb565(spool.color_rgba)))
+                .draw(display)?;
+        }
+
+        // Material, brand and remaining weight below the ring
+        let text_y = ring_top_left.y + diameter as i32 + spacing::MD + 6;
+        let title_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+        let subtitle_style = MonoTextStyle::new(&FONT_6X10, theme.text_secondary);
+
+        Text::with_alignment(
+            fit(spool.material, text_width, &FONT_10X20),
+            Point::new(center_x, text_y),
+            title_style,
+            Alignment::Center,
         )
         .draw(display)?;
+        Text::with_alignment(
+            fit(spool.brand, text_width, &FONT_6X10),
+            Point::new(center_x, text_y + 16),
+            subtitle_style,
+            Alignment::Center,
+        )
+        .draw(display)?;
+
+        let mut weight_text: heapless::String<24> = heapless::String::new();
+        let _ = core::fmt::write(
+            &mut weight_text,
+            format_args!("{:.0} g  {}%", spool.weight_current, percentage),
+        );
+        let weight_style = MonoTextStyle::new(
+            &FONT_6X10,
+            if spool.low_stock { theme.error } else { theme.text_primary },
+        );
+        Text::with_alignment(&weight_text, Point::new(center_x, text_y + 30), weight_style, Alignment::Center)
+            .draw(display)?;
+
+        // Low-stock badge in the top right corner
+        if spool.low_stock {
+            draw_low_stock_badge(
+                display,
+                Point::new(p.x + self.size.width as i32 - spacing::SM - 48, p.y + spacing::SM),
+            )?;
+        }
 
         Ok(())
     }
@@ -150,25 +384,70 @@ impl SpoolCard {
         let theme = theme::theme();
 
         // Card background
-        let card = RoundedRectangle::with_equal_corners(
-            Rectangle::new(self.position, self.size),
-            Size::new(theme::radius::MD, theme::radius::MD),
-        );
-        card.into_styled(PrimitiveStyle::with_fill(theme.card_bg))
-            .draw(display)?;
+        self.draw_background(display)?;
 
         // Centered message
         let text_style = MonoTextStyle::new(&FONT_10X20, theme.text_secondary);
         let text_pos = Point::new(
-            self.position.x + (self.size.width as i32) / 2 - (message.len() as i32 * 5),
+            self.position.x + (self.size.width as i32) / 2,
             self.position.y + (self.size.height as i32) / 2 + 8,
         );
-        Text::new(message, text_pos, text_style).draw(display)?;
+        Text::with_alignment(message, text_pos, text_style, Alignment::Center).draw(display)?;
 
         Ok(())
     }
 }
 
+/// Badge naming where the spool data came from
+fn draw_source_badge<D>(display: &mut D, position: Point, source: SpoolSource) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let theme = theme::theme();
+    let (text, color) = match source {
+        SpoolSource::Bambu => ("BAMBU", theme.primary),
+        SpoolSource::Manual => ("MANUAL", theme.warning),
+        SpoolSource::Nfc => ("NFC", theme.success),
+    };
+    draw_badge(display, position, text, color)
+}
+
+/// Badge flagging a spool that is running out
+fn draw_low_stock_badge<D>(display: &mut D, position: Point) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    draw_badge(display, position, "LOW", theme::theme().error)
+}
+
+fn draw_badge<D>(display: &mut D, position: Point, text: &str, color: Rgb565) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let theme = theme::theme();
+    let badge = RoundedRectangle::with_equal_corners(
+        Rectangle::new(position, Size::new(48, 18)),
+        Size::new(theme::radius::SM, theme::radius::SM),
+    );
+    badge
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)?;
+
+    let badge_text_style = MonoTextStyle::new(&FONT_6X10, theme.bg);
+    Text::with_alignment(text, position + Point::new(24, 12), badge_text_style, Alignment::Center)
+        .draw(display)?;
+    Ok(())
+}
+
+/// Cut `text` to the characters that fit in `width` pixels of a mono font
+fn fit<'a>(text: &'a str, width: i32, font: &embedded_graphics::mono_font::MonoFont) -> &'a str {
+    let max_chars = (width.max(0) as u32 / font.character_size.width) as usize;
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
 /// Compact spool card for lists
 pub struct SpoolCardCompact {
     /// Position (top-left corner)
@@ -187,7 +466,7 @@ impl SpoolCardCompact {
     }
 
     /// Draw the compact card
-    pub fn draw<D>(&self, display: &mut D, spool: &SpoolDisplay, selected: bool) -> Result<(), D::Error>
+    pub fn draw<D>(&self, display: &mut D, spool: &SpoolCardModel, selected: bool) -> Result<(), D::Error>
     where
         D: DrawTarget<Color = Rgb565>,
     {
@@ -211,11 +490,15 @@ impl SpoolCardCompact {
         // Color swatch (small)
         let swatch_size = 32u32;
         let swatch_pos = Point::new(p.x + spacing::SM, p.y + (Self::HEIGHT as i32 - swatch_size as i32) / 2);
-        let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
-
-        Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size))
-            .into_styled(PrimitiveStyle::with_fill(swatch_color))
-            .draw(display)?;
+        let swatch_area = Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size));
+        if spool.color_stops.len() >= 2 {
+            draw_gradient(display, swatch_area, spool.color_stops)?;
+        } else {
+            let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
+            swatch_area
+                .into_styled(PrimitiveStyle::with_fill(swatch_color))
+                .draw(display)?;
+        }
 
         // Material and color name
         let text_x = swatch_pos.x + swatch_size as i32 + spacing::SM;
@@ -226,13 +509,34 @@ impl SpoolCardCompact {
         let _ = core::fmt::write(&mut label, format_args!("{} {}", spool.material, spool.color_name));
         Text::new(&label, Point::new(text_x, p.y + 18), label_style).draw(display)?;
 
-        Text::new(&spool.brand, Point::new(text_x, p.y + 32), sublabel_style).draw(display)?;
+        Text::new(spool.brand, Point::new(text_x, p.y + 32), sublabel_style).draw(display)?;
 
-        // Weight on right side
+        // Weight on right side, red while low on stock
         let weight_text = theme::format_weight(spool.weight_current);
         let weight_x = p.x + self.width as i32 - spacing::SM - (weight_text.len() as i32 * 6);
-        Text::new(&weight_text, Point::new(weight_x, p.y + 24), label_style).draw(display)?;
+        let weight_style = if spool.low_stock {
+            MonoTextStyle::new(&FONT_6X10, theme.error)
+        } else {
+            label_style
+        };
+        Text::new(&weight_text, Point::new(weight_x, p.y + 24), weight_style).draw(display)?;
 
         Ok(())
     }
 }
+
+/// Fill an area with a left-to-right gradient through a multi-color spool's color stops
+fn draw_gradient<D>(display: &mut D, area: Rectangle, stops: &[u32]) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let width = area.size.width;
+    let bottom = area.top_left.y + area.size.height as i32 - 1;
+    for x in 0..width {
+        let column = area.top_left.x + x as i32;
+        Line::new(Point::new(column, area.top_left.y), Point::new(column, bottom))
+            .into_styled(PrimitiveStyle::with_stroke(theme::gradient_rgb565(stops, x, width), 1))
+            .draw(display)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! Text input field edited with the on-screen keyboard or numeric keypad.
+
+use crate::ui::theme::{self, radius, spacing};
+use crate::ui::widgets::keyboard::Key;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, ascii::FONT_10X20, MonoTextStyle},
+    pixelcolor::Rgb565,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    text::{Alignment, Text},
+};
+use heapless::String;
+
+/// Height of the field box below the label
+const FIELD_HEIGHT: u32 = 44;
+/// Space reserved above the field box for the label
+const LABEL_HEIGHT: i32 = 16;
+
+/// What kind of value the field accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputKind {
+    /// Any printable text
+    #[default]
+    Text,
+    /// Text shown as asterisks (e.g., Wi-Fi password)
+    Password,
+    /// A number, at most one decimal point and a leading minus
+    Number,
+}
+
+/// Text input field holding up to `N` bytes
+pub struct TextInput<const N: usize> {
+    /// Position (top-left corner of the label)
+    pub position: Point,
+    /// Width of the field
+    pub width: u32,
+    /// Label shown above the field
+    pub label: &'static str,
+    /// Hint shown while the field is empty
+    pub placeholder: &'static str,
+    /// Unit shown after numbers (e.g., "g")
+    pub suffix: &'static str,
+    /// Accepted input
+    pub kind: InputKind,
+    /// Whether the field has keyboard focus
+    pub focused: bool,
+    /// Current value
+    value: String<N>,
+}
+
+impl<const N: usize> TextInput<N> {
+    /// Create an empty text field
+    pub fn new(position: Point, width: u32, label: &'static str) -> Self {
+        Self {
+            position,
+            width,
+            label,
+            placeholder: "",
+            suffix: "",
+            kind: InputKind::Text,
+            focused: false,
+            value: String::new(),
+        }
+    }
+
+    /// Set the hint shown while empty
+    pub fn with_placeholder(mut self, placeholder: &'static str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Set what kind of value the field accepts
+    pub fn with_kind(mut self, kind: InputKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the unit shown after the value
+    pub fn with_suffix(mut self, suffix: &'static str) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Set the initial value, truncated to the field capacity
+    pub fn with_value(mut self, value: &str) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Current value
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+
+    /// Replace the value, truncated to the field capacity
+    pub fn set_value(&mut self, value: &str) {
+        self.value.clear();
+        for c in value.chars().filter(char::is_ascii) {
+            if self.value.push(c).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Parse the value as a number
+    pub fn as_f32(&self) -> Option<f32> {
+        self.value.parse().ok()
+    }
+
+    /// Whether a character may be appended to the current value
+    fn accepts(&self, c: char) -> bool {
+        match self.kind {
+            InputKind::Text | InputKind::Password => c.is_ascii() && !c.is_ascii_control(),
+            InputKind::Number => match c {
+                '0'..='9' => true,
+                '.' => !self.value.contains('.'),
+                '-' => self.value.is_empty(),
+                _ => false,
+            },
+        }
+    }
+
+    /// Apply a key from the keyboard or keypad.
+    ///
+    /// Returns true when Enter confirms the input.
+    pub fn apply(&mut self, key: Key) -> bool {
+        match key {
+            Key::Char(c) => {
+                if self.accepts(c) {
+                    let _ = self.value.push(c);
+                }
+            }
+            Key::Backspace => {
+                self.value.pop();
+            }
+            Key::Clear => self.value.clear(),
+            Key::Enter => return true,
+            Key::Shift | Key::Mode => {}
+        }
+        false
+    }
+
+    /// Get bounds of the field box for hit testing
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(
+            Point::new(self.position.x, self.position.y + LABEL_HEIGHT),
+            Size::new(self.width, FIELD_HEIGHT),
+        )
+    }
+
+    /// Check if point is within the field box
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    /// Draw the field
+    pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb565>,
+    {
+        let theme = theme::theme();
+        let field = self.bounds();
+
+        // Label
+        let label_style = MonoTextStyle::new(&FONT_6X10, theme.text_secondary);
+        Text::new(self.label, self.position + Point::new(0, 10), label_style).draw(display)?;
+
+        // Field box, highlighted while focused
+        let rounded = RoundedRectangle::with_equal_corners(field, Size::new(radius::SM, radius::SM));
+        rounded
+            .into_styled(PrimitiveStyle::with_fill(theme.card_bg))
+            .draw(display)?;
+        let border = if self.focused { theme.primary } else { theme.border };
+        rounded
+            .into_styled(PrimitiveStyle::with_stroke(border, 2))
+            .draw(display)?;
+
+        // Value, masked for passwords; the field shows its tail when it overflows
+        let char_width = FONT_10X20.character_size.width;
+        let visible = ((self.width as i32 - spacing::MD * 2) / char_width as i32).max(1) as usize;
+        let mut shown: String<N> = String::new();
+        let text = if self.kind == InputKind::Password {
+            for _ in 0..self.value.len() {
+                let _ = shown.push('*');
+            }
+            shown.as_str()
+        } else {
+            self.value.as_str()
+        };
+        let text = &text[text.len().saturating_sub(visible)..];
+
+        let text_pos = Point::new(field.top_left.x + spacing::MD, field.center().y + 6);
+        let end = if text.is_empty() {
+            if !self.placeholder.is_empty() && !self.focused {
+                let hint_style = MonoTextStyle::new(&FONT_10X20, theme.disabled);
+                Text::new(self.placeholder, text_pos, hint_style).draw(display)?;
+            }
+            text_pos
+        } else {
+            let text_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+            Text::new(text, text_pos, text_style).draw(display)?
+        };
+
+        // Cursor after the text
+        if self.focused {
+            Rectangle::new(Point::new(end.x + 1, field.top_left.y + 10), Size::new(2, FIELD_HEIGHT - 20))
+                .into_styled(PrimitiveStyle::with_fill(theme.primary))
+                .draw(display)?;
+        }
+
+        // Unit on the right
+        if !self.suffix.is_empty() {
+            let suffix_style = MonoTextStyle::new(&FONT_10X20, theme.text_secondary);
+            Text::with_alignment(
+                self.suffix,
+                Point::new(field.top_left.x + self.width as i32 - spacing::MD, text_pos.y),
+                suffix_style,
+                Alignment::Right,
+            )
+            .draw(display)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,130 @@
 //! Weight display widget - large, prominent weight readout.
+//!
+//! ┌──────────────────────────────────────────┐
+//! │  ▲   1234.5 g                        ✓   │
+//! │          Tap TARE to zero                │
+//! └──────────────────────────────────────────┘
+//!
+//! Digits are drawn as seven-segment glyphs so they stay crisp at 72 px
+//! without a large bitmap font in flash. Tapping the readout switches
+//! between grams and ounces.
 
-use crate::ui::theme::{self, spacing};
+use crate::ui::theme::{self, font_size, spacing};
 use embedded_graphics::{
-    mono_font::{ascii::FONT_10X20, MonoTextStyle},
+    mono_font::{ascii::FONT_6X10, ascii::FONT_10X20, MonoTextStyle},
     pixelcolor::Rgb565,
     prelude::*,
-    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle, Triangle},
     text::{Alignment, Text},
 };
+use heapless::{Deque, String};
+
+/// Grams per avoirdupois ounce
+const GRAMS_PER_OUNCE: f32 = 28.349_523;
+
+/// Number of weight samples the trend is computed over
+const TREND_WINDOW: usize = 8;
+/// Change between the older and newer half of the window (g) that counts as a trend
+const TREND_THRESHOLD_G: f32 = 1.0;
+
+/// Stable readings off zero by less than this (g) suggest the scale needs a tare
+const TARE_HINT_MAX_G: f32 = 20.0;
+/// Readings within this distance of zero (g) count as zeroed
+const ZERO_TOLERANCE_G: f32 = 0.5;
+
+/// Space below the digits for the tare hint
+const HINT_HEIGHT: i32 = 14;
+
+/// Unit the weight is shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightUnit {
+    #[default]
+    Grams,
+    Ounces,
+}
+
+impl WeightUnit {
+    /// The other unit
+    pub fn toggled(self) -> Self {
+        match self {
+            WeightUnit::Grams => WeightUnit::Ounces,
+            WeightUnit::Ounces => WeightUnit::Grams,
+        }
+    }
+
+    /// Unit label shown after the digits
+    pub fn suffix(self) -> &'static str {
+        match self {
+            WeightUnit::Grams => "g",
+            WeightUnit::Ounces => "oz",
+        }
+    }
+
+    /// Format a weight in grams for this unit
+    pub fn format(self, grams: f32) -> String<12> {
+        let mut s = String::new();
+        let _ = match self {
+            WeightUnit::Grams => core::fmt::write(&mut s, format_args!("{:.1}", grams)),
+            WeightUnit::Ounces => core::fmt::write(&mut s, format_args!("{:.2}", grams / GRAMS_PER_OUNCE)),
+        };
+        s
+    }
+}
+
+/// Direction the weight is moving in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Trend {
+    #[default]
+    Steady,
+    Gaining,
+    Losing,
+}
+
+/// Rolling window of recent weight samples
+#[derive(Debug, Clone, Default)]
+pub struct WeightTrend {
+    samples: Deque<f32, TREND_WINDOW>,
+}
+
+impl WeightTrend {
+    /// Create an empty window
+    pub const fn new() -> Self {
+        Self { samples: Deque::new() }
+    }
+
+    /// Add a sample, dropping the oldest once the window is full
+    pub fn push(&mut self, grams: f32) -> Trend {
+        if self.samples.is_full() {
+            self.samples.pop_front();
+        }
+        let _ = self.samples.push_back(grams);
+        self.trend()
+    }
+
+    /// Forget all samples (e.g., after a tare)
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Compare the mean of the newer half of the window with the older half
+    pub fn trend(&self) -> Trend {
+        if !self.samples.is_full() {
+            return Trend::Steady;
+        }
+        let half = TREND_WINDOW / 2;
+        let older: f32 = self.samples.iter().take(half).sum();
+        let newer: f32 = self.samples.iter().skip(half).sum();
+        let delta = (newer - older) / half as f32;
+
+        if delta > TREND_THRESHOLD_G {
+            Trend::Gaining
+        } else if delta < -TREND_THRESHOLD_G {
+            Trend::Losing
+        } else {
+            Trend::Steady
+        }
+    }
+}
 
 /// Weight display widget showing current weight with stability indicator
 pub struct WeightDisplay {
@@ -19,6 +136,10 @@ pub struct WeightDisplay {
     pub weight: f32,
     /// Whether the weight is stable
     pub stable: bool,
+    /// Unit the weight is shown in
+    pub unit: WeightUnit,
+    /// Direction the weight is moving in
+    pub trend: Trend,
 }
 
 impl WeightDisplay {
@@ -29,6 +150,8 @@ impl WeightDisplay {
             size,
             weight: 0.0,
             stable: false,
+            unit: WeightUnit::Grams,
+            trend: Trend::Steady,
         }
     }
 
@@ -38,6 +161,32 @@ impl WeightDisplay {
         self.stable = stable;
     }
 
+    /// Set the display unit
+    pub fn set_unit(&mut self, unit: WeightUnit) {
+        self.unit = unit;
+    }
+
+    /// Set the trend arrow
+    pub fn set_trend(&mut self, trend: Trend) {
+        self.trend = trend;
+    }
+
+    /// Get bounds for hit testing
+    pub fn bounds(&self) -> Rectangle {
+        Rectangle::new(self.position, self.size)
+    }
+
+    /// Check if point is within the widget
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    /// Whether a settled reading is slightly off zero, or negative, so a tare would help
+    pub fn needs_tare(&self) -> bool {
+        let magnitude = self.weight.abs();
+        self.stable && magnitude > ZERO_TOLERANCE_G && (magnitude < TARE_HINT_MAX_G || self.weight < 0.0)
+    }
+
     /// Draw the widget
     pub fn draw<D>(&self, display: &mut D) -> Result<(), D::Error>
     where
@@ -47,31 +196,66 @@ impl WeightDisplay {
 
         // Background card
         let card = RoundedRectangle::with_equal_corners(
-            Rectangle::new(self.position, self.size),
+            self.bounds(),
             Size::new(theme::radius::MD, theme::radius::MD),
         );
         card.into_styled(PrimitiveStyle::with_fill(theme.card_bg))
             .draw(display)?;
 
-        // Weight text
-        let weight_text = theme::format_weight(self.weight);
-        let text_style = MonoTextStyle::new(&FONT_10X20, theme.text_primary);
+        let tare_hint = self.needs_tare();
+        let hint_space = if tare_hint { HINT_HEIGHT } else { 0 };
 
-        // Center the text in the widget
-        let text_pos = Point::new(
-            self.position.x + (self.size.width as i32) / 2,
-            self.position.y + (self.size.height as i32) / 2 + 8,
-        );
+        // Digits as large as the card allows, up to the XXL font size
+        let text = self.unit.format(self.weight);
+        let suffix = self.unit.suffix();
+        let suffix_width = (suffix.len() as u32 * FONT_10X20.character_size.width) as i32;
+        // Room on each side for the trend arrow and stability indicator
+        let indicators = spacing::MD + 24;
+        let max_width = self.size.width as i32 - indicators * 2 - suffix_width - spacing::SM;
+        let max_height = self.size.height as i32 - spacing::SM * 2 - hint_space;
+        let mut height = (font_size::XXL as i32).min(max_height).max(10);
+        while height > 10 && segment_text_width(&text, height) > max_width {
+            height -= 2;
+        }
+
+        let digits_color = if self.stable { theme.text_primary } else { theme.text_secondary };
+        let total_width = segment_text_width(&text, height) + spacing::SM + suffix_width;
+        let top = self.position.y + (self.size.height as i32 - height - hint_space) / 2;
+        let left = self.position.x + (self.size.width as i32 - total_width) / 2;
+        let end = draw_segment_text(display, &text, Point::new(left, top), height, digits_color)?;
 
-        Text::with_alignment(&weight_text, text_pos, text_style, Alignment::Center)
+        let suffix_style = MonoTextStyle::new(&FONT_10X20, theme.text_secondary);
+        Text::new(suffix, Point::new(end.x + spacing::SM, top + height - 2), suffix_style)
             .draw(display)?;
 
+        let indicator_y = top + height / 2;
+
+        // Trend arrow (left side)
+        let arrow_x = self.position.x + spacing::MD + 10;
+        match self.trend {
+            Trend::Gaining => Triangle::new(
+                Point::new(arrow_x, indicator_y - 10),
+                Point::new(arrow_x - 10, indicator_y + 6),
+                Point::new(arrow_x + 10, indicator_y + 6),
+            )
+            .into_styled(PrimitiveStyle::with_fill(theme.success))
+            .draw(display)?,
+            Trend::Losing => Triangle::new(
+                Point::new(arrow_x, indicator_y + 10),
+                Point::new(arrow_x - 10, indicator_y - 6),
+                Point::new(arrow_x + 10, indicator_y - 6),
+            )
+            .into_styled(PrimitiveStyle::with_fill(theme.warning))
+            .draw(display)?,
+            Trend::Steady => {}
+        }
+
         // Stability indicator (checkmark or dot)
         if self.stable {
             // Draw green checkmark indicator
             let indicator_pos = Point::new(
                 self.position.x + self.size.width as i32 - spacing::MD - 20,
-                self.position.y + (self.size.height as i32) / 2 - 8,
+                indicator_y - 8,
             );
 
             // Simple checkmark using rectangles
@@ -94,7 +278,7 @@ impl WeightDisplay {
             // Draw pulsing dot for unstable
             let indicator_pos = Point::new(
                 self.position.x + self.size.width as i32 - spacing::MD - 12,
-                self.position.y + (self.size.height as i32) / 2 - 6,
+                indicator_y - 6,
             );
 
             embedded_graphics::primitives::Circle::new(indicator_pos, 12)
@@ -102,6 +286,107 @@ impl WeightDisplay {
                 .draw(display)?;
         }
 
+        // Tare hint below the digits
+        if tare_hint {
+            let hint_style = MonoTextStyle::new(&FONT_6X10, theme.warning);
+            Text::with_alignment(
+                "Tap TARE to zero",
+                Point::new(
+                    self.position.x + self.size.width as i32 / 2,
+                    self.position.y + self.size.height as i32 - spacing::SM - 2,
+                ),
+                hint_style,
+                Alignment::Center,
+            )
+            .draw(display)?;
+        }
+
         Ok(())
     }
 }
+
+/// Segments lit for each glyph, bits a-g (top, top right, bottom right,
+/// bottom, bottom left, top left, middle)
+fn segments(c: char) -> Option<u8> {
+    Some(match c {
+        '0' => 0x3F,
+        '1' => 0x06,
+        '2' => 0x5B,
+        '3' => 0x4F,
+        '4' => 0x66,
+        '5' => 0x6D,
+        '6' => 0x7D,
+        '7' => 0x07,
+        '8' => 0x7F,
+        '9' => 0x6F,
+        '-' => 0x40,
+        _ => return None,
+    })
+}
+
+/// Horizontal space a glyph takes up at the given digit height
+fn glyph_advance(c: char, height: i32) -> i32 {
+    let thickness = (height / 10).max(2);
+    match c {
+        '.' => thickness * 2,
+        _ => height / 2 + thickness,
+    }
+}
+
+/// Width of a seven-segment string at the given digit height
+fn segment_text_width(text: &str, height: i32) -> i32 {
+    text.chars().map(|c| glyph_advance(c, height)).sum()
+}
+
+/// Draw a string of digits, '-' and '.' as seven-segment glyphs.
+///
+/// Returns the point just right of the last glyph.
+fn draw_segment_text<D>(
+    display: &mut D,
+    text: &str,
+    top_left: Point,
+    height: i32,
+    color: Rgb565,
+) -> Result<Point, D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let style = PrimitiveStyle::with_fill(color);
+    let t = (height / 10).max(2);
+    let w = height / 2;
+    let half = height / 2;
+    let mut x = top_left.x;
+    let y = top_left.y;
+
+    for c in text.chars() {
+        if c == '.' {
+            Rectangle::new(Point::new(x + t / 2, y + height - t), Size::new(t as u32, t as u32))
+                .into_styled(style)
+                .draw(display)?;
+        } else if let Some(lit) = segments(c) {
+            // (x, y, width, height) of segments a-g, 1 px apart where they meet
+            let horizontal = |top: i32| (x + t, top, w - t * 2, t);
+            let upper = half - t / 2 - t - 1;
+            let lower = height - half - t / 2 - t - 1;
+            let rects = [
+                horizontal(y),
+                (x + w - t, y + t + 1, t, upper),
+                (x + w - t, y + half + t / 2 + 1, t, lower),
+                horizontal(y + height - t),
+                (x, y + half + t / 2 + 1, t, lower),
+                (x, y + t + 1, t, upper),
+                horizontal(y + half - t / 2),
+            ];
+            for (bit, (sx, sy, sw, sh)) in rects.iter().enumerate() {
+                if lit & (1 << bit) != 0 && *sw > 0 && *sh > 0 {
+                    Rectangle::new(Point::new(*sx, *sy), Size::new(*sw as u32, *sh as u32))
+                        .into_styled(style)
+                        .draw(display)?;
+                }
+            }
+        }
+        x += glyph_advance(c, height);
+    }
+
+    Ok(Point::new(x, y + height))
+}
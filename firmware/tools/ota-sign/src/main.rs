@@ -0,0 +1,134 @@
+//! OTA image signing for SpoolBuddy releases
+//!
+//! ```text
+//! ota-sign keygen <key-file>          # writes <key-file> (secret) and <key-file>.pub
+//! ota-sign sign <key-file> <image>    # writes <image>.sig
+//! ota-sign verify <pub-file> <image>  # checks <image>.sig
+//! ```
+//!
+//! Keys and signatures are hex text files (see `spoolbuddy_core::ota`). The
+//! public key goes into the firmware build via `SPOOLBUDDY_OTA_PUBLIC_KEY`;
+//! the secret key stays with whoever cuts releases, never on the server.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use ed25519_compact::{KeyPair, Seed};
+use spoolbuddy_core::ota;
+
+const USAGE: &str = "usage:
+  ota-sign keygen <key-file>
+  ota-sign sign <key-file> <image>
+  ota-sign verify <pub-file> <image>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["keygen", key_file] => keygen(Path::new(key_file)),
+        ["sign", key_file, image] => sign(Path::new(key_file), Path::new(image)),
+        ["verify", pub_file, image] => verify(Path::new(pub_file), Path::new(image)),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ota-sign: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn keygen(key_file: &Path) -> Result<(), String> {
+    let mut seed = [0u8; Seed::BYTES];
+    File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut seed))
+        .map_err(|e| format!("reading /dev/urandom: {e}"))?;
+    let keys = KeyPair::from_seed(Seed::new(seed));
+
+    // Never overwrite an existing key: images signed with it would stop verifying
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut secret = options
+        .open(key_file)
+        .map_err(|e| format!("{}: {e}", key_file.display()))?;
+    writeln!(secret, "{}", ota::to_hex(&seed))
+        .map_err(|e| format!("{}: {e}", key_file.display()))?;
+
+    let pub_file = with_suffix(key_file, ".pub");
+    write_hex(&pub_file, &keys.pk[..])?;
+
+    println!("Secret key: {}", key_file.display());
+    println!(
+        "Public key: {} ({})",
+        pub_file.display(),
+        ota::to_hex(&keys.pk[..])
+    );
+    Ok(())
+}
+
+fn sign(key_file: &Path, image: &Path) -> Result<(), String> {
+    let seed = read_text(key_file)?;
+    let seed: [u8; Seed::BYTES] =
+        parse_hex(&seed).ok_or_else(|| format!("{}: not a secret key", key_file.display()))?;
+    let keys = KeyPair::from_seed(Seed::new(seed));
+
+    let data = fs::read(image).map_err(|e| format!("{}: {e}", image.display()))?;
+    let signature = keys.sk.sign(&data, None);
+
+    let sig_file = with_suffix(image, ".sig");
+    write_hex(&sig_file, &signature[..])?;
+    println!("Signed {} -> {}", image.display(), sig_file.display());
+    Ok(())
+}
+
+fn verify(pub_file: &Path, image: &Path) -> Result<(), String> {
+    let public_key = ota::parse_public_key(&read_text(pub_file)?)
+        .map_err(|e| format!("{}: {}", pub_file.display(), e.message()))?;
+    let sig_file = with_suffix(image, ".sig");
+    let signature = ota::parse_signature(&read_text(&sig_file)?)
+        .map_err(|e| format!("{}: {}", sig_file.display(), e.message()))?;
+
+    let data = fs::read(image).map_err(|e| format!("{}: {e}", image.display()))?;
+    ota::verify_image(&data, &signature, &public_key)
+        .map_err(|e| format!("{}: {}", image.display(), e.message()))?;
+    println!("{}: signature OK", image.display());
+    Ok(())
+}
+
+/// `foo.bin` + `.sig` -> `foo.bin.sig`
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn write_hex(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, format!("{}\n", ota::to_hex(bytes)))
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn parse_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    let hex = hex.trim();
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
@@ -395,6 +395,16 @@ describe('API Client', () => {
         expect(result.status).toBe('configured')
         expect(result.needs_replacement).toBe(false)
       })
+
+      it('should set and clear the external spool', async () => {
+        const result = await api.setExternalSpool('00M09A123456789', 'spool-1')
+        expect(result.ams_id).toBe(255)
+        expect(result.spool?.id).toBe('spool-1')
+
+        const external = await api.getExternalSpool('00M09A123456789')
+        expect(external.ams_id).toBe(255)
+        await expect(api.clearExternalSpool('00M09A123456789')).resolves.not.toThrow()
+      })
     })
   })
 
@@ -18,8 +18,11 @@ export const mockSpools: Spool[] = [
     core_weight: 250,
     weight_new: 1000,
     weight_current: 850,
+    price: null,
     slicer_filament: 'GFSL05',
     slicer_filament_name: 'Bambu PLA Basic',
+    slicer_setting_id: 'GFSL05',
+    slicer_filament_id: 'GFL05',
     location: null,
     note: null,
     added_time: null,
@@ -32,6 +35,7 @@ export const mockSpools: Spool[] = [
     tag_type: null,
     ext_has_k: false,
     archived_at: null,
+    version: 1,
     created_at: 1702000000,
     updated_at: 1702000000,
     last_used_time: null,
@@ -49,8 +53,11 @@ export const mockSpools: Spool[] = [
     core_weight: 250,
     weight_new: 1000,
     weight_current: 1000,
+    price: null,
     slicer_filament: null,
     slicer_filament_name: null,
+    slicer_setting_id: null,
+    slicer_filament_id: null,
     location: 'X1 Carbon',
     note: 'Test spool',
     added_time: null,
@@ -63,6 +70,7 @@ export const mockSpools: Spool[] = [
     tag_type: null,
     ext_has_k: true,
     archived_at: null,
+    version: 1,
     created_at: 1702100000,
     updated_at: 1702100000,
     last_used_time: null,
@@ -206,6 +214,11 @@ export const mockPrinterState: PrinterState = {
   total_layer_num: 200,
   subtask_name: 'test_print.gcode',
   mc_remaining_time: 45,
+  print_eta: null,
+  speed_level: 2,
+  print_error: 0,
+  print_error_code: null,
+  skipped_objects: [],
   gcode_file: '/sdcard/test_print.gcode',
   ams_units: [mockAmsUnit],
   vt_tray: null,
@@ -227,6 +240,11 @@ export const mockDualNozzlePrinterState: PrinterState = {
   total_layer_num: 200,
   subtask_name: 'multicolor_print.gcode',
   mc_remaining_time: 30,
+  print_eta: null,
+  speed_level: 2,
+  print_error: 0,
+  print_error_code: null,
+  skipped_objects: [],
   gcode_file: '/sdcard/multicolor_print.gcode',
   ams_units: mockDualNozzleAmsUnits,
   vt_tray: null,
@@ -81,6 +81,16 @@ export const mockPrinters = [
 // Re-export from data.ts for backward compatibility
 export { mockVersionInfo, mockUpdateCheck, mockCloudStatus, mockCalibrations }
 
+// Command accepted by a printer, as returned by the AMS slot endpoints
+const mockCommand = (command: string) => ({
+  sequence_id: '1',
+  command,
+  status: 'pending',
+  reason: null,
+  sent_at: Date.now() / 1000,
+  completed_at: null,
+})
+
 // API handlers
 export const handlers = [
   // Spools
@@ -229,21 +239,19 @@ export const handlers = [
   }),
 
   // AMS slot operations
-  http.post('/api/printers/:serial/ams/:amsId/tray/:trayId/filament', async ({ request }) => {
-    const body = await request.json() as Record<string, unknown>
+  http.post('/api/printers/:serial/ams/:amsId/tray/:trayId/filament', () => {
     // Simulate setting filament on a slot
-    return HttpResponse.json({ status: 'ok', ...body })
+    return HttpResponse.json(mockCommand('ams_filament_setting'), { status: 202 })
   }),
 
-  http.post('/api/printers/:serial/ams/:amsId/tray/:trayId/calibration', async ({ request }) => {
-    const body = await request.json() as Record<string, unknown>
+  http.post('/api/printers/:serial/ams/:amsId/tray/:trayId/calibration', () => {
     // Simulate setting calibration on a slot
-    return HttpResponse.json({ status: 'ok', ...body })
+    return HttpResponse.json(mockCommand('extrusion_cali_sel'), { status: 202 })
   }),
 
   http.post('/api/printers/:serial/ams/:amsId/tray/:trayId/reset', () => {
     // Simulate RFID re-read request
-    return HttpResponse.json({ status: 'ok' })
+    return HttpResponse.json(mockCommand('ams_get_rfid'), { status: 202 })
   }),
 
   http.post('/api/printers/:serial/ams/:amsId/tray/:trayId/assign', async () => {
@@ -254,6 +262,23 @@ export const handlers = [
     })
   }),
 
+  http.get('/api/printers/:serial/external-spool', () => {
+    return HttpResponse.json({ ams_id: 255, spool: null, tray: null })
+  }),
+
+  http.put('/api/printers/:serial/external-spool', async ({ request }) => {
+    const body = await request.json() as { spool_id: string; ams_id?: number }
+    const spool = mockSpools.find(s => s.id === body.spool_id)
+    if (spool) {
+      return HttpResponse.json({ ams_id: body.ams_id ?? 255, spool, tray: null, sequence_id: '1' })
+    }
+    return new HttpResponse(null, { status: 404 })
+  }),
+
+  http.delete('/api/printers/:serial/external-spool', () => {
+    return new HttpResponse(null, { status: 204 })
+  }),
+
   // Device
   http.get('/api/device/status', () => {
     return HttpResponse.json({
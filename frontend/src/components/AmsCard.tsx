@@ -1,6 +1,6 @@
 import { useState } from "preact/hooks";
 import { AmsUnit, AmsTray } from "../lib/websocket";
-import { CalibrationProfile, AMSThresholds } from "../lib/api";
+import { CalibrationProfile, AMSThresholds, Spool } from "../lib/api";
 import { Droplets, Thermometer } from "lucide-preact";
 import { ConfigureAmsSlotModal } from "./ConfigureAmsSlotModal";
 
@@ -682,9 +682,10 @@ interface ExternalSpoolProps {
   printerSerial?: string;
   calibrations?: CalibrationProfile[];
   trayReadingBits?: number | null;
+  spool?: Spool | null;  // Inventory spool assigned to the holder
 }
 
-export function ExternalSpool({ tray, position = "left", numExtruders = 1, printerSerial, calibrations = [], trayReadingBits }: ExternalSpoolProps) {
+export function ExternalSpool({ tray, position = "left", numExtruders = 1, printerSerial, calibrations = [], trayReadingBits, spool }: ExternalSpoolProps) {
   const isEmpty = !tray || isTrayEmpty(tray);
   const color = tray ? trayColorToCSS(tray.tray_color) : "#808080";
   const material = tray?.tray_type || "";
@@ -755,6 +756,11 @@ export function ExternalSpool({ tray, position = "left", numExtruders = 1, print
               K {kValue.toFixed(3)}
             </span>
           )}
+          {spool && (
+            <span class="text-xs text-[var(--text-muted)] truncate" title="Assigned spool">
+              {[spool.brand, spool.color_name || spool.material].filter(Boolean).join(" ")}
+            </span>
+          )}
           <div class="mt-1.5">
             <FillLevelBar remain={isEmpty ? null : remain ?? null} />
             {!isEmpty && remain !== null && remain !== undefined && remain >= 0 && (
@@ -0,0 +1,254 @@
+import { useState, useEffect, useCallback } from 'preact/hooks'
+import { api, KioskConfig, KioskMember, KioskCheckout } from '../lib/api'
+import { useToast } from '../lib/toast'
+import { useWebSocket } from '../lib/websocket'
+import { IdCard, Plus, Trash2, Loader2, LogIn } from 'lucide-preact'
+
+const DEFAULT_CONFIG: KioskConfig = {
+  enabled: false,
+  session_seconds: 60,
+}
+
+const inputClass =
+  'px-2 py-1 text-sm bg-[var(--bg-secondary)] border border-[var(--border-color)] rounded text-[var(--text-primary)] focus:border-[var(--accent)] focus:outline-none'
+
+function errorText(e: unknown) {
+  return e instanceof Error ? e.message : String(e)
+}
+
+function checkoutSpool(checkout: KioskCheckout) {
+  const name = [checkout.brand, checkout.material, checkout.color_name].filter(Boolean).join(' ')
+  return checkout.spool_number != null ? `#${checkout.spool_number} ${name}` : name
+}
+
+export function KioskSettings() {
+  const { showToast } = useToast()
+  const { subscribe } = useWebSocket()
+  const [config, setConfig] = useState<KioskConfig>(DEFAULT_CONFIG)
+  const [members, setMembers] = useState<KioskMember[]>([])
+  const [checkouts, setCheckouts] = useState<KioskCheckout[]>([])
+  const [loading, setLoading] = useState(true)
+  const [saving, setSaving] = useState(false)
+  const [newName, setNewName] = useState('')
+  const [newBadge, setNewBadge] = useState('')
+
+  const refresh = useCallback(async () => {
+    const [loadedMembers, loadedCheckouts] = await Promise.all([api.getKioskMembers(), api.getKioskCheckouts(true)])
+    setMembers(loadedMembers)
+    setCheckouts(loadedCheckouts)
+  }, [])
+
+  useEffect(() => {
+    Promise.all([api.getKioskConfig().then(setConfig), refresh()])
+      .catch(err => console.error('Failed to load kiosk settings:', err))
+      .finally(() => setLoading(false))
+  }, [refresh])
+
+  // Spools checked out or in on the device
+  useEffect(() => {
+    return subscribe((message) => {
+      if (message.type === 'kiosk_checkout') {
+        refresh().catch(() => {})
+      }
+    })
+  }, [subscribe, refresh])
+
+  const handleSave = async (changes: Partial<KioskConfig>) => {
+    setSaving(true)
+    try {
+      setConfig(await api.setKioskConfig({ ...config, ...changes }))
+    } catch (e) {
+      showToast('error', `Failed to save kiosk settings: ${errorText(e)}`)
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const handleAddMember = async () => {
+    try {
+      await api.createKioskMember({ name: newName.trim(), badge_id: newBadge.trim() || null })
+      setNewName('')
+      setNewBadge('')
+      await refresh()
+    } catch (e) {
+      showToast('error', `Failed to add member: ${errorText(e)}`)
+    }
+  }
+
+  const handleUpdateMember = async (member: KioskMember, changes: Partial<KioskMember>) => {
+    try {
+      const updated = await api.updateKioskMember(member.id, changes)
+      setMembers(prev => prev.map(m => (m.id === updated.id ? updated : m)))
+    } catch (e) {
+      showToast('error', `Failed to update ${member.name}: ${errorText(e)}`)
+    }
+  }
+
+  const handleDeleteMember = async (member: KioskMember) => {
+    if (!confirm(`Delete ${member.name}? Their checkouts and usage are kept.`)) return
+    try {
+      await api.deleteKioskMember(member.id)
+      await refresh()
+    } catch (e) {
+      showToast('error', `Failed to delete ${member.name}: ${errorText(e)}`)
+    }
+  }
+
+  const handleCheckIn = async (checkout: KioskCheckout) => {
+    try {
+      await api.checkInKioskCheckout(checkout.id)
+      showToast('success', `${checkoutSpool(checkout)} checked in`)
+      await refresh()
+    } catch (e) {
+      showToast('error', `Failed to check in: ${errorText(e)}`)
+    }
+  }
+
+  if (loading) {
+    return (
+      <div class="card p-6 flex items-center justify-center">
+        <Loader2 class="w-5 h-5 animate-spin text-[var(--text-muted)]" />
+      </div>
+    )
+  }
+
+  return (
+    <div class="card">
+      <div class="px-6 py-4 border-b border-[var(--border-color)]">
+        <div class="flex items-center justify-between">
+          <div class="flex items-center gap-2">
+            <IdCard class="w-5 h-5 text-[var(--text-muted)]" />
+            <h2 class="text-lg font-medium text-[var(--text-primary)]">Check-In / Check-Out Kiosk</h2>
+          </div>
+          <label class="flex items-center gap-2 text-sm text-[var(--text-secondary)]">
+            {saving && <Loader2 class="w-4 h-4 animate-spin" />}
+            <input
+              type="checkbox"
+              checked={config.enabled}
+              onChange={(e) => handleSave({ enabled: (e.target as HTMLInputElement).checked })}
+            />
+            Enabled
+          </label>
+        </div>
+      </div>
+      <div class="p-6 space-y-4">
+        <p class="text-sm text-[var(--text-secondary)]">
+          Members scan their badge on the device, then put each spool they take or bring back on the scale.
+          Filament used while a spool is out is logged against the member.
+        </p>
+
+        <label class="flex items-center gap-2 text-sm text-[var(--text-secondary)]">
+          Sign members out after
+          <input
+            type="number"
+            min="10"
+            max="600"
+            value={config.session_seconds}
+            onChange={(e) => handleSave({ session_seconds: parseInt((e.target as HTMLInputElement).value) || 60 })}
+            class={`${inputClass} w-20`}
+          />
+          seconds
+        </label>
+
+        <div class="overflow-hidden rounded-lg border border-[var(--border-color)]">
+          <table class="w-full text-sm">
+            <thead>
+              <tr class="bg-[var(--bg-tertiary)]">
+                <th class="px-3 py-2 text-left text-xs font-medium text-[var(--text-muted)]">Member</th>
+                <th class="px-3 py-2 text-left text-xs font-medium text-[var(--text-muted)]">Badge</th>
+                <th class="px-3 py-2 text-left text-xs font-medium text-[var(--text-muted)]">Spools out</th>
+                <th class="px-3 py-2 text-left text-xs font-medium text-[var(--text-muted)]">Active</th>
+                <th class="px-3 py-2" />
+              </tr>
+            </thead>
+            <tbody class="divide-y divide-[var(--border-color)]">
+              {members.length === 0 && (
+                <tr>
+                  <td colSpan={5} class="px-3 py-3 text-center text-[var(--text-muted)]">No members yet</td>
+                </tr>
+              )}
+              {members.map(member => (
+                <tr key={member.id}>
+                  <td class="px-3 py-2 text-[var(--text-primary)]">{member.name}</td>
+                  <td class="px-3 py-2">
+                    <input
+                      type="text"
+                      placeholder="Badge tag ID"
+                      value={member.badge_id ?? ''}
+                      onChange={(e) => handleUpdateMember(member, { badge_id: (e.target as HTMLInputElement).value.trim() || null })}
+                      class={`${inputClass} w-40 font-mono`}
+                    />
+                  </td>
+                  <td class="px-3 py-2 text-[var(--text-secondary)]">{member.open_checkouts}</td>
+                  <td class="px-3 py-2">
+                    <input
+                      type="checkbox"
+                      checked={member.active}
+                      onChange={(e) => handleUpdateMember(member, { active: (e.target as HTMLInputElement).checked })}
+                    />
+                  </td>
+                  <td class="px-3 py-2 text-right">
+                    <button
+                      onClick={() => handleDeleteMember(member)}
+                      class="p-1 text-[var(--text-muted)] hover:text-red-500"
+                      title="Delete member"
+                    >
+                      <Trash2 class="w-4 h-4" />
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+
+        <div class="flex flex-wrap items-center gap-2">
+          <input
+            type="text"
+            placeholder="Name"
+            value={newName}
+            onInput={(e) => setNewName((e.target as HTMLInputElement).value)}
+            class={`${inputClass} w-48`}
+          />
+          <input
+            type="text"
+            placeholder="Badge tag ID (optional)"
+            value={newBadge}
+            onInput={(e) => setNewBadge((e.target as HTMLInputElement).value)}
+            class={`${inputClass} w-48 font-mono`}
+          />
+          <button onClick={handleAddMember} disabled={!newName.trim()} class="btn flex items-center gap-1.5">
+            <Plus class="w-4 h-4" /> Member
+          </button>
+        </div>
+
+        <div class="space-y-2">
+          <h3 class="text-sm font-medium text-[var(--text-primary)]">Spools checked out</h3>
+          {checkouts.length === 0 ? (
+            <p class="text-sm text-[var(--text-muted)]">Every spool is in.</p>
+          ) : (
+            <ul class="divide-y divide-[var(--border-color)] rounded-lg border border-[var(--border-color)]">
+              {checkouts.map(checkout => (
+                <li key={checkout.id} class="flex items-center justify-between gap-3 px-3 py-2 text-sm">
+                  <div>
+                    <div class="text-[var(--text-primary)]">{checkoutSpool(checkout)}</div>
+                    <div class="text-xs text-[var(--text-muted)]">
+                      {checkout.member_name ?? 'Former member'} since {new Date(checkout.checked_out_at * 1000).toLocaleString()}
+                    </div>
+                  </div>
+                  <button
+                    onClick={() => handleCheckIn(checkout)}
+                    class="btn flex items-center gap-1.5"
+                    title="Check in without weighing, no usage is logged"
+                  >
+                    <LogIn class="w-4 h-4" /> Check in
+                  </button>
+                </li>
+              ))}
+            </ul>
+          )}
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,254 @@
+import { useState, useEffect } from 'preact/hooks'
+import { api, WledConfig, WledMapping } from '../lib/api'
+import { useToast } from '../lib/toast'
+import { Lightbulb, Plus, Trash2, Loader2, Play, Square } from 'lucide-preact'
+
+const DEFAULT_CONFIG: WledConfig = {
+  enabled: false,
+  host: null,
+  color: '00FF00',
+  brightness: 200,
+  duration: 30,
+  mappings: [],
+}
+
+const inputClass =
+  'px-2 py-1 text-sm bg-[var(--bg-secondary)] border border-[var(--border-color)] rounded text-[var(--text-primary)] focus:border-[var(--accent)] focus:outline-none'
+
+function isSlotMapping(mapping: WledMapping) {
+  return mapping.location == null
+}
+
+export function WledSettings() {
+  const { showToast } = useToast()
+  const [config, setConfig] = useState<WledConfig>(DEFAULT_CONFIG)
+  const [loading, setLoading] = useState(true)
+  const [saving, setSaving] = useState(false)
+  const [testing, setTesting] = useState(false)
+
+  useEffect(() => {
+    api.getWledConfig()
+      .then(setConfig)
+      .catch(err => console.error('Failed to load WLED settings:', err))
+      .finally(() => setLoading(false))
+  }, [])
+
+  const update = (changes: Partial<WledConfig>) => setConfig(prev => ({ ...prev, ...changes }))
+
+  const updateMapping = (index: number, changes: Partial<WledMapping>) => {
+    setConfig(prev => ({
+      ...prev,
+      mappings: prev.mappings.map((m, i) => (i === index ? { ...m, ...changes } : m)),
+    }))
+  }
+
+  const addMapping = (slot: boolean) => {
+    const segment = config.mappings.reduce((max, m) => Math.max(max, m.segment + 1), 0)
+    const mapping: WledMapping = slot
+      ? { segment, location: null, printer_serial: '', ams_id: 0, tray_id: 0 }
+      : { segment, location: '' }
+    update({ mappings: [...config.mappings, mapping] })
+  }
+
+  const handleSave = async () => {
+    setSaving(true)
+    try {
+      setConfig(await api.setWledConfig(config))
+      showToast('success', 'WLED settings saved')
+    } catch (e) {
+      showToast('error', `Failed to save WLED settings: ${e instanceof Error ? e.message : e}`)
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const handleTest = async (segment?: number) => {
+    setTesting(true)
+    try {
+      await api.testWled(segment)
+    } catch (e) {
+      showToast('error', `WLED test failed: ${e instanceof Error ? e.message : e}`)
+    } finally {
+      setTesting(false)
+    }
+  }
+
+  if (loading) {
+    return (
+      <div class="card p-6 flex items-center justify-center">
+        <Loader2 class="w-5 h-5 animate-spin text-[var(--text-muted)]" />
+      </div>
+    )
+  }
+
+  return (
+    <div class="card">
+      <div class="px-6 py-4 border-b border-[var(--border-color)]">
+        <div class="flex items-center justify-between">
+          <div class="flex items-center gap-2">
+            <Lightbulb class="w-5 h-5 text-[var(--text-muted)]" />
+            <h2 class="text-lg font-medium text-[var(--text-primary)]">WLED Shelf Indicator</h2>
+          </div>
+          <label class="flex items-center gap-2 text-sm text-[var(--text-secondary)]">
+            <input
+              type="checkbox"
+              checked={config.enabled}
+              onChange={(e) => update({ enabled: (e.target as HTMLInputElement).checked })}
+            />
+            Enabled
+          </label>
+        </div>
+      </div>
+      <div class="p-6 space-y-4">
+        <p class="text-sm text-[var(--text-secondary)]">
+          Map storage locations and AMS slots to segments of a WLED controller. Locating a spool lights up where it is.
+        </p>
+
+        <div class="grid grid-cols-2 sm:grid-cols-4 gap-3">
+          <label class="col-span-2 text-xs text-[var(--text-muted)] space-y-1">
+            <span>Controller address</span>
+            <input
+              type="text"
+              placeholder="192.168.1.50"
+              value={config.host ?? ''}
+              onInput={(e) => update({ host: (e.target as HTMLInputElement).value || null })}
+              class={`${inputClass} w-full`}
+            />
+          </label>
+          <label class="text-xs text-[var(--text-muted)] space-y-1">
+            <span>Color</span>
+            <input
+              type="color"
+              value={`#${config.color}`}
+              onInput={(e) => update({ color: (e.target as HTMLInputElement).value.slice(1).toUpperCase() })}
+              class="w-full h-8 rounded border border-[var(--border-color)] bg-transparent"
+            />
+          </label>
+          <label class="text-xs text-[var(--text-muted)] space-y-1">
+            <span>Lit for (s)</span>
+            <input
+              type="number"
+              min="1"
+              max="3600"
+              value={config.duration}
+              onInput={(e) => update({ duration: parseInt((e.target as HTMLInputElement).value) || 30 })}
+              class={`${inputClass} w-full`}
+            />
+          </label>
+        </div>
+
+        <div class="overflow-hidden rounded-lg border border-[var(--border-color)]">
+          <table class="w-full text-sm">
+            <thead>
+              <tr class="bg-[var(--bg-tertiary)]">
+                <th class="px-3 py-2 text-left text-xs font-medium text-[var(--text-muted)]">Segment</th>
+                <th class="px-3 py-2 text-left text-xs font-medium text-[var(--text-muted)]">Lights</th>
+                <th class="px-3 py-2" />
+              </tr>
+            </thead>
+            <tbody class="divide-y divide-[var(--border-color)]">
+              {config.mappings.length === 0 && (
+                <tr>
+                  <td colSpan={3} class="px-3 py-3 text-center text-[var(--text-muted)]">No segments mapped yet</td>
+                </tr>
+              )}
+              {config.mappings.map((mapping, index) => (
+                <tr key={index}>
+                  <td class="px-3 py-2">
+                    <input
+                      type="number"
+                      min="0"
+                      value={mapping.segment}
+                      onInput={(e) => updateMapping(index, { segment: parseInt((e.target as HTMLInputElement).value) || 0 })}
+                      class={`${inputClass} w-16`}
+                    />
+                  </td>
+                  <td class="px-3 py-2">
+                    {isSlotMapping(mapping) ? (
+                      <div class="flex items-center gap-2">
+                        <input
+                          type="text"
+                          placeholder="Printer serial"
+                          value={mapping.printer_serial ?? ''}
+                          onInput={(e) => updateMapping(index, { printer_serial: (e.target as HTMLInputElement).value })}
+                          class={`${inputClass} w-40`}
+                        />
+                        <span class="text-xs text-[var(--text-muted)]">AMS</span>
+                        <input
+                          type="number"
+                          min="0"
+                          value={mapping.ams_id ?? 0}
+                          onInput={(e) => updateMapping(index, { ams_id: parseInt((e.target as HTMLInputElement).value) || 0 })}
+                          class={`${inputClass} w-16`}
+                        />
+                        <span class="text-xs text-[var(--text-muted)]">Slot</span>
+                        <input
+                          type="number"
+                          min="0"
+                          max="3"
+                          value={mapping.tray_id ?? 0}
+                          onInput={(e) => updateMapping(index, { tray_id: parseInt((e.target as HTMLInputElement).value) || 0 })}
+                          class={`${inputClass} w-16`}
+                        />
+                      </div>
+                    ) : (
+                      <input
+                        type="text"
+                        placeholder="Location, e.g. Shelf A"
+                        value={mapping.location ?? ''}
+                        onInput={(e) => updateMapping(index, { location: (e.target as HTMLInputElement).value })}
+                        class={`${inputClass} w-full`}
+                      />
+                    )}
+                  </td>
+                  <td class="px-3 py-2 text-right whitespace-nowrap">
+                    <button
+                      onClick={() => handleTest(mapping.segment)}
+                      disabled={!config.enabled || testing}
+                      class="p-1 text-[var(--text-muted)] hover:text-[var(--accent)] disabled:opacity-40"
+                      title="Light this segment"
+                    >
+                      <Play class="w-4 h-4" />
+                    </button>
+                    <button
+                      onClick={() => update({ mappings: config.mappings.filter((_, i) => i !== index) })}
+                      class="p-1 text-[var(--text-muted)] hover:text-red-500"
+                      title="Remove"
+                    >
+                      <Trash2 class="w-4 h-4" />
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        </div>
+
+        <div class="flex flex-wrap items-center gap-2">
+          <button onClick={() => addMapping(false)} class="btn flex items-center gap-1.5">
+            <Plus class="w-4 h-4" /> Location
+          </button>
+          <button onClick={() => addMapping(true)} class="btn flex items-center gap-1.5">
+            <Plus class="w-4 h-4" /> AMS slot
+          </button>
+          <div class="flex-1" />
+          <button
+            onClick={() => handleTest()}
+            disabled={!config.enabled || testing}
+            class="btn flex items-center gap-1.5"
+            title="Light every mapped segment"
+          >
+            <Play class="w-4 h-4" /> Test all
+          </button>
+          <button onClick={() => api.wledOff().catch(() => {})} disabled={!config.enabled} class="btn flex items-center gap-1.5">
+            <Square class="w-4 h-4" /> Off
+          </button>
+          <button onClick={handleSave} disabled={saving} class="btn btn-primary flex items-center gap-1.5">
+            {saving && <Loader2 class="w-4 h-4 animate-spin" />}
+            Save
+          </button>
+        </div>
+      </div>
+    </div>
+  )
+}
@@ -1,7 +1,16 @@
 import { useState, useEffect, useMemo, useRef } from 'preact/hooks'
 import { Modal } from './Modal'
-import { Spool, SpoolInput, SlicerPreset, SpoolKProfile, CatalogEntry, api } from '../../lib/api'
-import { X, MoreHorizontal, Trash2, Unlink, Archive, ArchiveRestore } from 'lucide-preact'
+import {
+  Spool,
+  SpoolInput,
+  SlicerPreset,
+  SpoolKProfile,
+  CatalogEntry,
+  SpoolTemplate,
+  SpoolFromTemplateInput,
+  api,
+} from '../../lib/api'
+import { X, MoreHorizontal, Trash2, Unlink, Archive, ArchiveRestore, Zap, BookmarkPlus } from 'lucide-preact'
 import { useToast } from '../../lib/toast'
 import {
   // Types
@@ -31,6 +40,7 @@ interface AddSpoolModalProps {
   isOpen: boolean
   onClose: () => void
   onSave: (input: SpoolInput) => Promise<Spool>
+  onAddFromTemplate?: (templateId: number, input: SpoolFromTemplateInput) => Promise<Spool>
   editSpool?: Spool | null
   onDelete?: (spool: Spool) => void
   onArchive?: (spool: Spool) => void
@@ -45,6 +55,7 @@ export function AddSpoolModal({
   isOpen,
   onClose,
   onSave,
+  onAddFromTemplate,
   editSpool,
   onDelete,
   onArchive,
@@ -74,6 +85,9 @@ export function AddSpoolModal({
   const [spoolCatalog, setSpoolCatalog] = useState<CatalogEntry[]>([])
   const [recentColors, setRecentColors] = useState<ColorPreset[]>([])
 
+  // Spool templates for quick add
+  const [templates, setTemplates] = useState<SpoolTemplate[]>([])
+
   // Secondary actions dropdown
   const [showActionsMenu, setShowActionsMenu] = useState(false)
   const actionsMenuRef = useRef<HTMLDivElement>(null)
@@ -140,8 +154,11 @@ export function AddSpoolModal({
       }
       fetchData()
       api.getSpoolCatalog().then(setSpoolCatalog).catch(console.error)
+      if (!editSpool) {
+        api.getSpoolTemplates().then(setTemplates).catch(console.error)
+      }
     }
-  }, [isOpen])
+  }, [isOpen, editSpool])
 
   // Reset form when modal opens/closes or editSpool changes
   useEffect(() => {
@@ -326,6 +343,55 @@ export function AddSpoolModal({
     }
   }
 
+  // Quick add: create the spool straight from a template
+  const handleQuickAdd = async (template: SpoolTemplate) => {
+    if (!onAddFromTemplate) return
+
+    setIsSubmitting(true)
+    const toastId = showToast('loading', `Adding ${template.name}...`)
+
+    try {
+      await onAddFromTemplate(template.id, {
+        tag_id: initialTagId || null,
+        weight_current: initialWeight || null,
+      })
+      updateToast(toastId, 'success', `Added ${template.name}`)
+      onClose()
+    } catch (e) {
+      const errorMsg = e instanceof Error ? e.message : 'Failed to add spool'
+      updateToast(toastId, 'error', errorMsg)
+    } finally {
+      setIsSubmitting(false)
+    }
+  }
+
+  // Save the filament in the form as a template
+  const handleSaveTemplate = async () => {
+    if (!formData.material) {
+      setErrors({ material: 'Material is required' })
+      return
+    }
+
+    try {
+      const template = await api.createSpoolTemplate({
+        material: formData.material,
+        subtype: formData.subtype || null,
+        brand: formData.brand || null,
+        color_name: formData.color_name || null,
+        rgba: formData.rgba.replace('#', '') || null,
+        label_weight: formData.label_weight,
+        core_weight: formData.core_weight,
+        slicer_filament: formData.slicer_filament || null,
+        slicer_filament_name: selectedPresetOption?.displayName || presetInputValue || null,
+        location: formData.location || null,
+      })
+      setTemplates(prev => [...prev, template])
+      showToast('success', `Saved template "${template.name}"`)
+    } catch (e) {
+      showToast('error', e instanceof Error ? e.message : 'Failed to save template')
+    }
+  }
+
   // Handle tag removal
   const handleRemoveTag = async () => {
     if (!editSpool) return
@@ -367,6 +433,18 @@ export function AddSpoolModal({
         <div class="flex items-center justify-between w-full">
           {/* Left side - Secondary actions dropdown */}
           <div class="modal-footer-left">
+            {!isEditing && (
+              <button
+                type="button"
+                class="btn btn-ghost"
+                onClick={handleSaveTemplate}
+                disabled={isSubmitting}
+                title="Save this filament as a quick-add template"
+              >
+                <BookmarkPlus class="w-4 h-4" />
+                Save as Template
+              </button>
+            )}
             {hasSecondaryActions && (
               <div class="actions-dropdown" ref={actionsMenuRef}>
                 <button
@@ -463,6 +541,36 @@ export function AddSpoolModal({
         </div>
       )}
 
+      {/* Quick add from templates */}
+      {!isEditing && onAddFromTemplate && templates.length > 0 && (
+        <div class="mb-3">
+          <div class="flex items-center gap-1 text-xs font-medium text-[var(--text-secondary)] mb-1.5">
+            <Zap class="w-3.5 h-3.5" />
+            Quick add
+          </div>
+          <div class="flex flex-wrap gap-1.5">
+            {templates.map(template => (
+              <button
+                key={template.id}
+                type="button"
+                class="flex items-center gap-1.5 px-2.5 py-1 rounded-full text-xs border border-[var(--border-color)] bg-[var(--bg-secondary)] hover:border-[var(--accent-color)] text-[var(--text-primary)] disabled:opacity-50"
+                onClick={() => handleQuickAdd(template)}
+                disabled={isSubmitting}
+                title={`Add a ${template.name} spool`}
+              >
+                {template.rgba && (
+                  <span
+                    class="w-3 h-3 rounded-full border border-black/20"
+                    style={{ backgroundColor: template.rgba.startsWith('#') ? template.rgba : `#${template.rgba}` }}
+                  />
+                )}
+                {template.name}
+              </button>
+            ))}
+          </div>
+        </div>
+      )}
+
       {/* Tabs */}
       <div class="tabs mb-3">
         <button
@@ -28,7 +28,7 @@ export function SpoolCard({ spool, isInPrinter, printerLocation, onClick }: Spoo
       {/* Color Header */}
       <div
         class="h-16 flex items-center justify-center relative"
-        style={{ backgroundColor: colorStyle }}
+        style={spool.gradient ? { background: spool.gradient } : { backgroundColor: colorStyle }}
       >
         <span class="bg-white/90 text-gray-800 px-3 py-1 rounded-full text-sm font-medium">
           {spool.color_name || 'Unknown'}
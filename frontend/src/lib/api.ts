@@ -1,3 +1,5 @@
+import type { AmsTray, SpeedLevel } from "./websocket";
+
 const API_BASE = "/api";
 
 export interface Spool {
@@ -13,8 +15,11 @@ export interface Spool {
   core_weight: number;
   weight_new: number | null;
   weight_current: number | null;
+  price: number | null;               // Price paid, used for project cost reports
   slicer_filament: string | null;
   slicer_filament_name: string | null;
+  slicer_setting_id: string | null;   // Slicer preset setting_id, resolved from slicer_filament
+  slicer_filament_id: string | null;  // Bambu filament_id (tray_info_idx)
   location: string | null;
   note: string | null;
   added_time: string | null;  // Unix timestamp as string for compat
@@ -26,10 +31,31 @@ export interface Spool {
   data_origin: string | null;
   tag_type: string | null;
   ext_has_k: boolean;         // Whether has pressure advance K calibration
+  swap_available?: boolean;   // Offered on the shareable swap list
+  photo_url?: string | null;
+  nozzle_temp_min?: number | null;  // User-tuned slot temperatures, null = material defaults
+  nozzle_temp_max?: number | null;
+  color_stops?: string[] | null;  // Multi-color filament colors in order (RRGGBBAA), rgba is the first
+  manufactured_at?: number | null;  // Unix timestamp of the production date
+  opened_at?: number | null;  // Unix timestamp when the seal was broken, null = sealed
   archived_at: number | null;  // Unix timestamp when archived, null = active
+  version: number;            // Incremented on every change (optimistic concurrency)
   created_at: number | null;
   updated_at: number | null;
   last_used_time: number | null;  // Unix timestamp of last usage
+  live_status?: SpoolLiveStatus;  // Where the spool is right now; changes arrive as "spool_status" WebSocket messages
+  color?: ColorSwatch | null;  // rgba parsed for drawing, null if the spool has no color
+  gradient?: string | null;  // CSS linear-gradient through color_stops, null for single-color spools
+}
+
+export interface SpoolLiveStatus {
+  status: "storage" | "on_scale" | "loaded";
+  device: string | null;          // Scale the spool is on
+  printer_serial: string | null;  // Slot the spool is loaded in
+  ams_id: number | null;
+  tray_id: number | null;
+  active: boolean;                // Filament is fed to the extruder
+  since: number | null;           // When the spool got there
 }
 
 // Map for tracking which spools are in which printers
@@ -46,45 +72,165 @@ export interface SpoolInput {
   core_weight?: number | null;
   weight_new?: number | null;
   weight_current?: number | null;
+  price?: number | null;
   slicer_filament?: string | null;
   slicer_filament_name?: string | null;
+  slicer_setting_id?: string | null;
+  slicer_filament_id?: string | null;
   location?: string | null;
   note?: string | null;
   data_origin?: string | null;
   tag_type?: string | null;
   ext_has_k?: boolean;  // Whether has pressure advance K calibration
+  swap_available?: boolean;
+  photo_url?: string | null;
+  nozzle_temp_min?: number | null;
+  nozzle_temp_max?: number | null;
+  color_stops?: string[] | null;
+  manufactured_at?: number | null;
+  opened_at?: number | null;
+  expected_version?: number | null;  // Update fails with 409 if the spool changed since this version
+}
+
+// Quick-add preset for spools bought again and again
+export interface SpoolTemplate {
+  id: number;
+  name: string;
+  material: string;
+  subtype: string | null;
+  brand: string | null;
+  color_name: string | null;
+  rgba: string | null;
+  label_weight: number | null;
+  core_weight: number | null;
+  price: number | null;
+  slicer_filament: string | null;
+  slicer_filament_name: string | null;
+  location: string | null;
+  nozzle_temp_min: number | null;
+  nozzle_temp_max: number | null;
+  use_count: number;  // Spools added from the template
+  last_used_at: number | null;
+  created_at: number | null;
 }
 
+export type SpoolTemplateInput = Partial<Omit<SpoolTemplate, "id" | "use_count" | "last_used_at" | "created_at">>;
+
+// Per-spool details for a spool added from a template
+export interface SpoolFromTemplateInput {
+  tag_id?: string | null;
+  weight_current?: number | null;
+  location?: string | null;
+  note?: string | null;
+  price?: number | null;
+}
+
+export type PrinterType = "bambu" | "manual";  // manual = never connects, usage logged by hand
+
 export interface Printer {
   serial: string;
   name: string | null;
   model: string | null;
+  printer_type?: PrinterType;
   ip_address: string | null;
   access_code: string | null;
   last_seen: number | null;
   config: string | null;
   auto_connect: boolean | null;
+  power_watts?: number | null;  // Average draw while printing, for energy estimates
+  max_nozzle_temp?: number | null;  // Capability overrides; null = the model's defaults
+  hardened_nozzle?: boolean | null;
+  enclosed?: boolean | null;
+  cert_fingerprint?: string | null;  // Pinned TLS certificate (SHA-256)
+  firmware_version?: string | null;  // Reported by the printer on connect
+  hardware_version?: string | null;
   connected?: boolean;
+  needs_access_code?: boolean;  // The printer refused its access code
+}
+
+export interface CertificateStatus {
+  pinned: string | null;  // null = the next certificate seen is pinned
+  mismatch: string | null;  // A refused certificate that didn't match the pin
+  insecure: boolean;  // Pinning is off
 }
 
 export interface PrinterInput {
   serial: string;
   name?: string | null;
   model?: string | null;
+  printer_type?: PrinterType;
   ip_address?: string | null;
   access_code?: string | null;
+  power_watts?: number | null;
+  max_nozzle_temp?: number | null;  // Capability overrides; null = the model's defaults
+  hardened_nozzle?: boolean | null;
+  enclosed?: boolean | null;
+}
+
+export interface NozzleInfo {
+  extruder_id: number;  // 0 = right/only nozzle, 1 = left nozzle
+  diameter: string;
+  nozzle_type: string | null;
+}
+
+export interface NozzleChange {
+  id: number;
+  printer_serial: string;
+  extruder_id: number;
+  old_diameter: string | null;
+  old_type: string | null;
+  new_diameter: string;
+  new_type: string | null;
+  changed_at: number;
+}
+
+export interface PrinterNozzles {
+  nozzles: NozzleInfo[];
+  changes: NozzleChange[];  // Newest first
+}
+
+export interface AmsUnitRecord {
+  id: number;  // Stable across power cycles, unlike ams_id
+  serial: string;
+  printer_serial: string;
+  ams_id: number | null;  // Current position, null = no longer attached
+  module: string | null;  // ams, ams_f1 (AMS Lite), n3f (AMS 2 Pro), n3s (AMS HT)
+  product_name: string | null;
+  firmware_version: string | null;
+  hardware_version: string | null;
+  humidity_sensor: boolean | null;
+  first_seen: number;
+  last_seen: number;
+}
+
+export type AmsEventKind = "inserted" | "removed" | "changed" | "loaded" | "unloaded";
+
+export interface AmsEvent {
+  id: number;
+  printer_serial: string;
+  ams_id: number;
+  tray_id: number;
+  event: AmsEventKind;
+  extruder: number | null;
+  spool_id: string | null;  // Spool assigned to the slot at the time
+  tray_type: string | null;
+  tray_color: string | null;
+  tray_info_idx: string | null;
+  created_at: number;
 }
 
+// With spool_id, unset fields are taken from the spool; temps default to the material's range
 export interface SetSlotRequest {
   ams_id: number;
   tray_id: number;
-  tray_info_idx: string;
-  tray_type: string;
-  tray_sub_brands: string;  // Preset name for slicer display (e.g., "Bambu PLA Basic")
-  tray_color: string;
-  nozzle_temp_min: number;
-  nozzle_temp_max: number;
-  setting_id: string;  // Full setting ID with version (e.g., "GFSL05_07")
+  spool_id?: string;
+  tray_info_idx?: string;
+  tray_type?: string;
+  tray_sub_brands?: string;  // Preset name for slicer display (e.g., "Bambu PLA Basic")
+  tray_color?: string;  // RGBA hex; "#RRGGBB" is accepted
+  nozzle_temp_min?: number;
+  nozzle_temp_max?: number;
+  setting_id?: string;  // Full setting ID with version (e.g., "GFSL05_07")
 }
 
 export interface SetCalibrationRequest {
@@ -104,6 +250,40 @@ export interface AssignSpoolResponse {
   status: "configured" | "staged";
   message: string;
   needs_replacement: boolean;
+  sequence_id?: string | null;  // ams_filament_setting command, see getPrinterCommand
+  warnings?: string[];  // Material compatibility issues with the printer
+}
+
+// Spool on a printer's external holder (virtual tray); changes are broadcast as "external_spool" WebSocket messages
+export interface ExternalSpool {
+  ams_id: number;  // 255 = external, 254 = left external on dual-nozzle printers
+  spool: Spool | null;
+  tray: AmsTray | null;  // Live vt_tray state, if the printer is connected
+  sequence_id?: string | null;  // ams_filament_setting command sent when assigning
+  warnings?: string[];  // Material compatibility issues with the printer
+}
+
+export interface CompatibilityIssue {
+  code: "abrasive" | "nozzle_temp" | "enclosure";
+  severity: "error" | "warning";
+  message: string;
+}
+
+// Assigning a spool with error-level issues fails with 422 unless forced
+export interface SpoolCompatibility {
+  capabilities: { max_nozzle_temp: number | null; hardened_nozzle: boolean | null; enclosed: boolean | null };
+  issues: CompatibilityIssue[];
+  compatible: boolean;
+}
+
+// Command sent to a printer; outcomes are also broadcast as "command_result" WebSocket messages
+export interface CommandStatus {
+  sequence_id: string;
+  command: string;
+  status: "pending" | "success" | "failed" | "timeout";
+  reason: string | null;
+  sent_at: number;
+  completed_at: number | null;
 }
 
 export interface CalibrationProfile {
@@ -131,10 +311,17 @@ export interface SpoolKProfile {
   created_at?: number;
 }
 
+export interface ScaleChannel {
+  channel: number;
+  weight: number | null;
+  stable: boolean;
+}
+
 export interface DeviceStatus {
   connected: boolean;
   last_weight: number | null;
   weight_stable: boolean;
+  scales?: ScaleChannel[] | null;  // Per-channel readings on dual-scale devices
   current_tag_id: string | null;
 }
 
@@ -206,6 +393,7 @@ export interface FirmwareVersion {
   size: number | null;
   checksum: string | null;
   url: string | null;
+  signed: boolean;
 }
 
 export interface FirmwareCheck {
@@ -291,6 +479,41 @@ export interface ColorLookupResult {
   material: string | null;
 }
 
+export interface ColorSwatch {
+  hex: string;         // "#RRGGBB"
+  rgba: string;        // "RRGGBBAA"
+  rgba_int: number;    // Packed 0xRRGGBBAA
+  alpha: number;       // 0-255
+  hue: number;         // HSL degrees
+  lightness: number;   // HSL percent
+  family: string;      // Nearest basic color name ("red", "grey", ...)
+  text_color: string;  // "#000000" or "#FFFFFF" for text on the swatch
+}
+
+export interface ResolvedColor {
+  source: "hex" | "basic" | "catalog";
+  swatch: ColorSwatch;
+  manufacturer: string | null;
+  color_name: string | null;
+}
+
+export interface ColorMatch {
+  spool: Spool;
+  delta_e: number;  // CIEDE2000 distance, 0 = identical
+}
+
+export interface TagMoveResult {
+  spool: Spool;  // Spool the tag is now linked to
+  previous_spool: Spool | null;  // Spool the tag was taken from (keeps its history)
+}
+
+export interface TagMove {
+  tag_id: string;
+  from_spool_id: string | null;
+  to_spool_id: string;
+  moved_at: number;
+}
+
 // AMS History types
 export interface AMSHistoryPoint {
   recorded_at: number;
@@ -311,195 +534,1202 @@ export interface AMSHistoryResponse {
   avg_temperature: number | null;
 }
 
-// AMS Thresholds
-export interface AMSThresholds {
-  humidity_good: number;
-  humidity_fair: number;
-  temp_good: number;
-  temp_fair: number;
-  history_retention_days: number;
+// Spool weight history types
+export type WeightResolution = "raw" | "1h" | "6h" | "1d" | "1w";
+
+export interface WeightHistoryPoint {
+  timestamp: number;
+  weight: number;
+  min_weight: number;
+  max_weight: number;
+  samples: number;
 }
 
-// API Key types
-export interface APIKey {
+export interface WeightChange {
+  timestamp: number;
+  delta: number;
+  kind: "print" | "increase";
+}
+
+export interface WeightHistoryResponse {
+  spool_id: string;
+  resolution: WeightResolution;
+  points: WeightHistoryPoint[];
+  changes: WeightChange[];
+  print_consumption: number;
+  drift: number;
+}
+
+export interface SpoolForecast {
+  spool_id: string;
+  remaining_grams: number | null;
+  daily_usage: number;
+  days_remaining: number | null;
+  depletion_date: number | null;
+}
+
+export interface SpoolAging {
+  spool_id: string;
+  material: string | null;
+  manufactured_at: number | null;
+  opened_at: number | null;       // null = sealed
+  open_days: number | null;
+  shelf_life_months: number;      // Recommended use after opening for the material
+  expires_at: number | null;      // When the shelf life runs out
+  aging: boolean;                 // Open past its shelf life
+}
+
+// Filament runout/jam events (from printers or DIY sensors)
+export type RunoutKind = "runout" | "jam";
+
+export interface RunoutEventInput {
+  kind?: RunoutKind;
+  ams_id?: number;
+  tray_id?: number;
+  spool_id?: string | null;
+  print_name?: string | null;
+  source?: string | null;
+  mark_empty?: boolean | null;  // Default: runouts mark the spool empty, jams don't
+}
+
+export interface RunoutEvent {
+  id: number;
+  printer_serial: string;
+  ams_id: number;
+  tray_id: number;
+  spool_id: string | null;
+  kind: RunoutKind;
+  print_name: string | null;
+  source: string | null;
+  marked_empty: boolean;
+  created_at: number;
+}
+
+// Bulk spool updates
+export interface BulkSpoolUpdate {
+  ids: string[];
+  changes?: Partial<SpoolInput>;
+  archived?: boolean | null; // true = archive, false = restore
+  atomic?: boolean; // Roll back everything if any spool fails
+}
+
+export interface BulkSpoolItemResult {
+  id: string;
+  ok: boolean;
+  error: string | null;
+  spool: Spool | null;
+}
+
+export interface BulkSpoolUpdateResult {
+  updated: number;
+  failed: number;
+  results: BulkSpoolItemResult[];
+}
+
+// Spools offered for swap/sale
+export interface SwapListItem {
+  spool_number: number | null;
+  brand: string | null;
+  material: string;
+  subtype: string | null;
+  color_name: string | null;
+  rgba: string | null;
+  remaining_grams: number | null;
+  label_weight: number | null;
+  photo_url: string | null;
+}
+
+export type SwapListFormat = "json" | "markdown" | "html";
+
+// Manual consumption corrections
+export type AdjustmentReason = "purge" | "failed_print" | "measurement_error" | "other";
+
+export interface SpoolAdjustment {
+  grams: number;  // Positive = filament lost, negative = credited back
+  reason: AdjustmentReason;
+  note?: string | null;
+  project_id?: number | null;
+}
+
+// Slicer companion (spool matching for sliced projects)
+export interface FilamentRequirement {
+  tool: number;
+  material: string;
+  color?: string | null;
+  grams?: number;
+  setting_id?: string | null;
+}
+
+export interface SlotSuggestion {
+  ams_id: number;
+  tray_id: number;
+  loaded: boolean;
+}
+
+export interface SpoolCandidate {
+  spool: Spool;
+  remaining_grams: number | null;
+  enough: boolean | null;
+  preset_match: boolean;
+  color_distance: number | null;
+  slot: SlotSuggestion | null;
+}
+
+export interface ToolMatch {
+  tool: number;
+  material: string;
+  color: string | null;
+  grams: number;
+  candidates: SpoolCandidate[];
+}
+
+export interface SpoolMatchResponse {
+  tools: ToolMatch[];
+  ready: boolean;
+}
+
+// Projects (groups of print jobs and manual usage)
+export interface Project {
   id: number;
   name: string;
-  key_prefix: string;
-  can_read: boolean;
-  can_write: boolean;
-  can_control: boolean;
-  enabled: boolean;
-  last_used: number | null;
+  description: string | null;
+  archived_at: number | null;
+  created_at: number | null;
+}
+
+export type SearchKind = "spool" | "printer" | "project";
+
+export interface SearchResult {
+  kind: SearchKind;
+  id: string;  // Spool ID, printer serial or project ID
+  title: string;
+  snippet: string | null;  // Matching notes/description, hits in [brackets]
+}
+
+export type JobStatus = "queued" | "running" | "completed" | "failed" | "cancelled";
+
+// Background job; progress also arrives as "job_progress" WebSocket messages
+export interface Job {
+  id: string;
+  kind: string;  // "color_import", "backup", "report"
+  status: JobStatus;
+  params: Record<string, unknown>;
+  progress: number;
+  total: number | null;
+  message: string | null;
+  result: unknown;  // Set when completed
+  error: string | null;  // Set when failed
+  created_at: number | null;
+  started_at: number | null;
+  finished_at: number | null;
+}
+
+export type TagFormat = "SpoolEaseV2" | "OpenSpool" | "OpenTag3D" | "OpenPrintTag";
+
+// Source of a tag conversion, as for /tags/decode (raw dumps or decoded NDEF payloads)
+export interface TagSource {
+  tag_uid?: string;
+  url?: string;
+  json_payload?: string;
+  payload_base64?: string;
+  blocks?: Record<number, string>;
+  pages?: Record<number, string>;
+  dump?: string;
+  dump_base64?: string;
+}
+
+export interface DecodedTag {
+  tag_type: string;  // e.g. "Bambu Lab", "OpenPrintTag"
+  tag_uid: string;
+  uid_base64: string;
+  nfc_type: string | null;
+  decoder: string | null;  // Decoder that recognized the tag, e.g. "bambu"
+  confidence: number;  // 0-100
+  material: string | null;
+  subtype: string | null;
+  color_name: string | null;
+  rgba: string | null;
+  brand: string | null;
+  label_weight: number | null;
+  core_weight: number | null;
+  slicer_filament: string | null;
+  note: string | null;
+  raw_data: Record<string, unknown> | null;
+}
+
+export interface EncodedTag {
+  format: TagFormat;
+  spool_id: string | null;
+  tag_uid: string | null;
+  url: string | null;
+  payload_base64: string | null;
+  payload_hex: string | null;
+  json_payload: string | null;
+  ndef_type: string | null;
+  payload_size: number;
+}
+
+export interface TagConvertResult {
+  source: DecodedTag;
+  encoded: EncodedTag;
+  tag_data_hex: string;  // NTAG user memory from page 4 on
+  dropped: string[];  // Source fields the target format can't hold
+}
+
+// Per-tag outcome of a tag_batch job
+export interface TagBatchTag {
+  index: number;  // 1-based position in the batch
+  spool_id: string;
+  label: string;  // e.g. "Red PETG"
+  tag_id: string | null;  // Tag linked to the spool, null if it was skipped
+  outcome: string | null;  // verified, write_failed, read_failed, mismatch, too_large, tag_in_use
+  attempts: number;
+  retries: number;  // Page writes/reads the device had to retry
+}
+
+export interface TagBatchResult {
+  encoded: number;
+  failed: number;
+  tags: TagBatchTag[];
+}
+
+// Tag write reported by the device, with its read-back outcome
+export interface TagWrite {
+  id: number;
+  tag_id: string;
+  spool_id: string | null;
+  job_id: string | null;  // tag_batch job the write belongs to
+  outcome: string;  // verified, write_failed, read_failed, mismatch, too_large
+  pages_written: number;
+  retries: number;
+  failed_page: number | null;
+  rf_signal: number | null;  // Raw RF signal reading, only comparable on the same reader
   created_at: number;
 }
 
-export interface APIKeyCreate {
-  name: string;
-  can_read?: boolean;
-  can_write?: boolean;
-  can_control?: boolean;
-}
+export interface TagWriteSummary {
+  writes: number;
+  verified: number;
+  failed: number;
+  failure_rate: number;  // 0-1
+  tags: number;  // Distinct tags written
+  retries: number;
+  avg_retries: number;
+  avg_rf_signal: number | null;
+  min_rf_signal: number | null;
+  first_at: number | null;
+  last_at: number | null;
+}
+
+export interface TagWriteStats {
+  since: number | null;
+  total: TagWriteSummary;
+  outcomes: Record<string, number>;
+  batches: (TagWriteSummary & { job_id: string | null })[];  // Newest first
+}
+
+export type StocktakeStatus = "open" | "completed" | "cancelled";
+
+// Inventory count; spools put on the device's scale are counted while one is open
+export interface Stocktake {
+  id: number;
+  name: string | null;
+  status: StocktakeStatus;
+  scan_count: number;
+  started_at: number | null;
+  finished_at: number | null;
+  report?: StocktakeReport | null;  // Stored when completed
+}
+
+// Also arrives as "stocktake_scan" WebSocket messages for device scans
+export interface StocktakeScan {
+  id: number;
+  stocktake_id: number;
+  spool_id: string;
+  tag_id: string | null;
+  weight: number | null;  // Gross scale reading, null if only scanned
+  expected_remaining: number | null;
+  source: "device" | "web";
+  scanned_at: number;
+}
+
+export interface StocktakeSpool {
+  spool_id: string;
+  spool_number: number | null;
+  brand: string | null;
+  material: string;
+  subtype: string | null;
+  color_name: string | null;
+  rgba: string | null;
+  location: string | null;
+  expected_remaining: number | null;
+}
+
+export interface StocktakeDiscrepancy extends StocktakeSpool {
+  weight: number;
+  measured_remaining: number;
+  difference: number;  // Measured minus expected
+}
+
+export interface StocktakeReport {
+  stocktake_id: number;
+  expected_count: number;
+  seen_count: number;
+  unseen: StocktakeSpool[];
+  discrepancies: StocktakeDiscrepancy[];
+  tolerance: number;
+  generated_at: number;
+}
+
+export interface Purchase {
+  id: number;
+  supplier: string | null;
+  url: string | null;
+  order_ref: string | null;
+  purchased_at: number | null;
+  price: number | null;  // Total paid
+  quantity: number;
+  unit_price: number | null;
+  note: string | null;
+  spool_ids: string[];
+  created_at: number | null;
+}
+
+export type PurchaseInput = Partial<Omit<Purchase, "id" | "unit_price" | "spool_ids" | "created_at">>;
+
+// Minimum stock per material, in grams of filament
+export interface RestockLevels {
+  default: number | null;  // Level for materials not listed
+  materials: Record<string, number>;
+}
+
+export interface RestockSuggestion {
+  material: string;
+  spool_count: number;
+  stock_grams: number;
+  level_grams: number | null;
+  daily_usage: number;
+  days_remaining: number | null;
+  shortfall_grams: number;
+  suggested_spools: number;
+  last_purchase: {
+    id: number;
+    supplier: string | null;
+    url: string | null;
+    purchased_at: number | null;
+    unit_price: number | null;
+  } | null;
+}
+
+export type ShareField =
+  | "spool_number"
+  | "brand"
+  | "material"
+  | "subtype"
+  | "color_name"
+  | "rgba"
+  | "remaining_grams"
+  | "label_weight"
+  | "location";
+
+// WLED segment lighting a storage location or an AMS slot
+export interface WledMapping {
+  segment: number;
+  location?: string | null;
+  printer_serial?: string | null;
+  ams_id?: number | null;
+  tray_id?: number | null;
+}
+
+export interface WledConfig {
+  enabled: boolean;
+  host: string | null;
+  color: string;  // Highlight color, RRGGBB
+  brightness: number;
+  duration: number;  // Seconds a position stays lit
+  mappings: WledMapping[];
+}
+
+// Check-in/check-out kiosk
+export interface KioskConfig {
+  enabled: boolean;
+  session_seconds: number;  // Member stays signed in this long after a scan
+}
+
+export interface KioskMember {
+  id: number;
+  name: string;
+  badge_id: string | null;
+  note: string | null;
+  active: boolean;
+  open_checkouts: number;
+  created_at: number | null;
+}
+
+export interface KioskMemberInput {
+  name?: string;
+  badge_id?: string | null;
+  note?: string | null;
+  active?: boolean;
+}
+
+export interface KioskCheckout {
+  id: number;
+  spool_id: string;
+  spool_number: number | null;
+  brand: string | null;
+  material: string | null;
+  color_name: string | null;
+  member_id: number | null;
+  member_name: string | null;
+  weight_out: number | null;
+  checked_out_at: number;
+  checked_in_by: number | null;
+  weight_in: number | null;
+  weight_used: number | null;
+  checked_in_at: number | null;  // null while the spool is out
+}
+
+export interface LocateResult {
+  spool_id: string;
+  location: string | null;
+  printer_serial: string | null;
+  ams_id: number | null;
+  tray_id: number | null;
+  segments: number[];  // Empty if the spool's position isn't mapped
+}
+
+// Read-only inventory link, viewable by anyone with its path
+export interface ShareLink {
+  id: number;
+  token: string;
+  name: string | null;
+  fields: ShareField[];
+  path: string;  // "/share/<token>", served outside /api
+  active: boolean;  // False once revoked or expired
+  expires_at: number | null;
+  revoked_at: number | null;
+  access_count: number;
+  last_accessed_at: number | null;
+  created_at: number | null;
+}
+
+export interface ProjectInput {
+  name?: string;
+  description?: string | null;
+  archived?: boolean;
+}
+
+export interface ProjectMaterialUsage {
+  material: string;
+  weight_used: number;
+  cost: number | null;
+}
+
+export interface ProjectReportEntry {
+  project_id: number;
+  name: string;
+  archived_at: number | null;
+  weight_used: number;
+  prints: number;
+  spools: number;
+  cost: number | null;
+  priced_weight: number;
+  first_used: number | null;
+  last_used: number | null;
+  materials: ProjectMaterialUsage[];
+  energy_kwh: number;
+  energy_cost: number | null;  // null if no electricity price is set
+}
+
+export interface ProjectReport {
+  since: number | null;
+  total_weight: number;
+  total_energy_kwh: number;
+  total_energy_cost: number;
+  total_cost: number;  // Filament and energy
+  electricity_price: number | null;
+  projects: ProjectReportEntry[];
+}
+
+// Print energy (estimated from each printer's average power draw)
+export interface EnergyPricing {
+  price_per_kwh: number | null;
+}
+
+export interface EnergyReportGroup {
+  key: string;
+  energy_kwh: number;
+  duration_sec: number;
+  prints: number;
+  cost: number | null;
+  start?: number | null;  // Month/week groups: local midnight starting the period
+  end?: number | null;    // Month/week groups: start of the next period
+}
+
+export type ReportPeriod = "month" | "week";
+
+export interface EnergyReport {
+  group_by: "printer" | ReportPeriod;
+  since: number | null;
+  timezone: string | null;  // Calendar of month/week groups
+  electricity_price: number | null;
+  total_energy_kwh: number;
+  total_cost: number | null;
+  groups: EnergyReportGroup[];
+}
+
+// Calendar that month and week reports follow
+export interface ReportCalendar {
+  timezone: string | null;  // IANA name, null = the device's timezone
+  week_start: "monday" | "sunday";
+}
+
+// AMS Thresholds
+export interface AMSThresholds {
+  humidity_good: number;
+  humidity_fair: number;
+  temp_good: number;
+  temp_fair: number;
+  history_retention_days: number;
+}
+
+// API Key types
+export interface APIKey {
+  id: number;
+  name: string;
+  key_prefix: string;
+  can_read: boolean;
+  can_write: boolean;
+  can_control: boolean;
+  enabled: boolean;
+  last_used: number | null;
+  created_at: number;
+}
+
+export interface APIKeyCreate {
+  name: string;
+  can_read?: boolean;
+  can_write?: boolean;
+  can_control?: boolean;
+}
+
+export interface APIKeyCreateResponse extends APIKey {
+  key: string;  // Full key, only shown on creation
+}
+
+export interface APIKeyUpdate {
+  name?: string;
+  can_read?: boolean;
+  can_write?: boolean;
+  can_control?: boolean;
+  enabled?: boolean;
+}
+
+// Support API types
+export interface DebugLoggingState {
+  enabled: boolean;
+  enabled_at: string | null;
+  duration_seconds: number | null;
+}
+
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  logger_name: string;
+  message: string;
+}
+
+export interface PrinterLogEvent {
+  timestamp: number;  // Unix seconds
+  level: string;
+  logger_name: string;
+  message: string;
+  span: string | null;  // e.g. "mqtt.message", "printer.set_filament", "POST /api/printers/..."
+  request_id: string | null;
+}
+
+export interface LogsResponse {
+  entries: LogEntry[];
+  total_count: number;
+  filtered_count: number;
+}
+
+export interface SystemInfo {
+  // Application
+  version: string;
+  uptime: string;
+  uptime_seconds: number;
+  hostname: string;
+  // Database stats
+  spool_count: number;
+  printer_count: number;
+  connected_printers: number;
+  // Storage
+  database_size: string;
+  disk_total: string;
+  disk_used: string;
+  disk_free: string;
+  disk_percent: number;
+  // System
+  platform: string;
+  platform_release: string;
+  python_version: string;
+  boot_time: string;
+  // Memory
+  memory_total: string;
+  memory_used: string;
+  memory_available: string;
+  memory_percent: number;
+  // CPU
+  cpu_count: number;
+  cpu_percent: number;
+}
+
+class ApiClient {
+  private async request<T>(
+    path: string,
+    options: RequestInit = {}
+  ): Promise<T> {
+    const response = await fetch(`${API_BASE}${path}`, {
+      ...options,
+      headers: {
+        "Content-Type": "application/json",
+        ...options.headers,
+      },
+    });
+
+    if (!response.ok) {
+      const error = await response.text();
+      throw new Error(error || `HTTP ${response.status}`);
+    }
+
+    // Handle 204 No Content or empty body
+    if (response.status === 204) {
+      return undefined as T;
+    }
+
+    // Check if response has content before parsing JSON
+    const text = await response.text();
+    if (!text) {
+      return undefined as T;
+    }
+
+    return JSON.parse(text);
+  }
+
+  // Spools
+  async listSpools(params?: {
+    material?: string;
+    brand?: string;
+    search?: string;
+  }): Promise<Spool[]> {
+    const searchParams = new URLSearchParams();
+    if (params?.material) searchParams.set("material", params.material);
+    if (params?.brand) searchParams.set("brand", params.brand);
+    if (params?.search) searchParams.set("search", params.search);
+
+    const query = searchParams.toString();
+    return this.request<Spool[]>(`/spools${query ? `?${query}` : ""}`);
+  }
+
+  async getSpool(id: string): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}`);
+  }
+
+  async getSpoolByTag(uid: string): Promise<Spool> {
+    return this.request<Spool>(`/spools/by-tag/${encodeURIComponent(uid)}`);
+  }
+
+  async createSpool(input: SpoolInput): Promise<Spool> {
+    return this.request<Spool>("/spools", {
+      method: "POST",
+      body: JSON.stringify(input),
+    });
+  }
+
+  async updateSpool(id: string, input: SpoolInput): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}`, {
+      method: "PUT",
+      body: JSON.stringify(input),
+    });
+  }
+
+  async createSpoolFromTemplate(templateId: number, input: SpoolFromTemplateInput = {}): Promise<Spool> {
+    return this.request<Spool>(`/spools/from-template/${templateId}`, {
+      method: "POST",
+      body: JSON.stringify(input),
+    });
+  }
+
+  // Spool templates (most used first)
+  async getSpoolTemplates(): Promise<SpoolTemplate[]> {
+    return this.request<SpoolTemplate[]>("/spool-templates");
+  }
+
+  async createSpoolTemplate(input: SpoolTemplateInput): Promise<SpoolTemplate> {
+    return this.request<SpoolTemplate>("/spool-templates", {
+      method: "POST",
+      body: JSON.stringify(input),
+    });
+  }
+
+  async updateSpoolTemplate(id: number, input: SpoolTemplateInput): Promise<SpoolTemplate> {
+    return this.request<SpoolTemplate>(`/spool-templates/${id}`, {
+      method: "PUT",
+      body: JSON.stringify(input),
+    });
+  }
+
+  async deleteSpoolTemplate(id: number): Promise<void> {
+    return this.request<void>(`/spool-templates/${id}`, {
+      method: "DELETE",
+    });
+  }
+
+  async deleteSpool(id: string): Promise<void> {
+    return this.request<void>(`/spools/${id}`, {
+      method: "DELETE",
+    });
+  }
+
+  async archiveSpool(id: string): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}/archive`, {
+      method: "POST",
+    });
+  }
+
+  async restoreSpool(id: string): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}/restore`, {
+      method: "POST",
+    });
+  }
+
+  async bulkUpdateSpools(update: BulkSpoolUpdate): Promise<BulkSpoolUpdateResult> {
+    return this.request<BulkSpoolUpdateResult>("/spools/bulk", {
+      method: "PATCH",
+      body: JSON.stringify(update),
+    });
+  }
+
+  /**
+   * Set spool weight from scale measurement.
+   * This updates weight_current and resets consumed_since_weight to 0,
+   * effectively syncing the tracking to match the scale.
+   * @param id Spool ID
+   * @param weight Total weight in grams (including spool core)
+   */
+  async setSpoolWeight(id: string, weight: number): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}/weight`, {
+      method: "POST",
+      body: JSON.stringify({ weight }),
+    });
+  }
+
+  /** Revert the last scale weight sync, tag link or device update (within the undo window) */
+  async undoLastSpoolUpdate(id: string): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}/undo-last`, { method: "POST" });
+  }
+
+  /** Move an NFC tag to a spool even if another active spool has it (after a 409 on create/update/link) */
+  async moveTagToSpool(id: string, tagId: string, tagType?: string): Promise<TagMoveResult> {
+    return this.request<TagMoveResult>(`/spools/${id}/move-tag`, {
+      method: "POST",
+      body: JSON.stringify({ tag_id: tagId, tag_type: tagType }),
+    });
+  }
+
+  async getSpoolTagMoves(id: string): Promise<TagMove[]> {
+    return this.request<TagMove[]>(`/spools/${id}/tag-moves`);
+  }
+
+  async getSpoolWeightHistory(id: string, resolution: WeightResolution = "1d", since?: number): Promise<WeightHistoryResponse> {
+    const params = new URLSearchParams({ resolution });
+    if (since !== undefined) params.set("since", String(since));
+    return this.request<WeightHistoryResponse>(`/spools/${id}/weight-history?${params}`);
+  }
+
+  async adjustSpool(id: string, adjustment: SpoolAdjustment): Promise<Spool> {
+    return this.request<Spool>(`/spools/${id}/adjustments`, {
+      method: "POST",
+      body: JSON.stringify(adjustment),
+    });
+  }
+
+  async getSpoolForecast(id: string): Promise<SpoolForecast> {
+    return this.request<SpoolForecast>(`/spools/${id}/forecast`);
+  }
+
+  async listSpoolForecasts(): Promise<SpoolForecast[]> {
+    return this.request<SpoolForecast[]>("/spools/forecast");
+  }
+
+  async getSpoolAging(id: string): Promise<SpoolAging> {
+    return this.request<SpoolAging>(`/spools/${id}/aging`);
+  }
+
+  async listSpoolAging(agingOnly = false): Promise<SpoolAging[]> {
+    return this.request<SpoolAging[]>(`/spools/aging${agingOnly ? "?aging_only=true" : ""}`);
+  }
+
+  async getSpoolRunoutEvents(id: string, limit = 50): Promise<RunoutEvent[]> {
+    return this.request<RunoutEvent[]>(`/spools/${id}/runout-events?limit=${limit}`);
+  }
+
+  async getSwapList(): Promise<SwapListItem[]> {
+    return this.request<SwapListItem[]>("/spools/swap-list");
+  }
+
+  // Shareable swap list as Markdown/HTML text, for posting in swap threads
+  async exportSwapList(format: Exclude<SwapListFormat, "json">, title?: string): Promise<string> {
+    const params = new URLSearchParams({ format });
+    if (title) params.set("title", title);
+    const response = await fetch(`${API_BASE}/spools/swap-list?${params}`);
+    if (!response.ok) {
+      const error = await response.text();
+      throw new Error(error || `HTTP ${response.status}`);
+    }
+    return response.text();
+  }
+
+  // Global search; every word matches as a prefix ("jade wh")
+  async search(q: string, kinds: SearchKind[] = [], limit = 20): Promise<SearchResult[]> {
+    const params = new URLSearchParams({ q, limit: String(limit) });
+    kinds.forEach((kind) => params.append("kind", kind));
+    return this.request<SearchResult[]>(`/search?${params}`);
+  }
+
+  // Background jobs
+  async startJob(kind: string, params: Record<string, unknown> = {}): Promise<Job> {
+    return this.request<Job>("/jobs", {
+      method: "POST",
+      body: JSON.stringify({ kind, params }),
+    });
+  }
+
+  async getJob(id: string): Promise<Job> {
+    return this.request<Job>(`/jobs/${id}`);
+  }
+
+  async getJobs(filters: { kind?: string; status?: JobStatus; limit?: number } = {}): Promise<Job[]> {
+    const params = new URLSearchParams();
+    if (filters.kind) params.set("kind", filters.kind);
+    if (filters.status) params.set("status", filters.status);
+    if (filters.limit) params.set("limit", String(filters.limit));
+    const query = params.toString();
+    return this.request<Job[]>(`/jobs${query ? `?${query}` : ""}`);
+  }
+
+  async cancelJob(id: string): Promise<Job> {
+    return this.request<Job>(`/jobs/${id}/cancel`, { method: "POST" });
+  }
+
+  // Put the device into batch encoding mode; the job result is a TagBatchResult
+  async startTagBatch(spoolIds: string[], format: TagFormat = "SpoolEaseV2"): Promise<Job> {
+    return this.startJob("tag_batch", { spool_ids: spoolIds, format });
+  }
+
+  // Re-encode a decoded tag (e.g. a Bambu Lab dump) for writing to an NTAG sticker
+  async convertTag(source: TagSource, format: TagFormat = "OpenPrintTag", targetUid?: string): Promise<TagConvertResult> {
+    return this.request<TagConvertResult>("/tags/convert", {
+      method: "POST",
+      body: JSON.stringify({ ...source, format, target_uid: targetUid ?? null }),
+    });
+  }
+
+  async getTagWrites(
+    filters: { tagId?: string; spoolId?: string; jobId?: string; failed?: boolean; limit?: number } = {},
+  ): Promise<TagWrite[]> {
+    const params = new URLSearchParams();
+    if (filters.tagId) params.set("tag_id", filters.tagId);
+    if (filters.spoolId) params.set("spool_id", filters.spoolId);
+    if (filters.jobId) params.set("job_id", filters.jobId);
+    if (filters.failed) params.set("failed", "true");
+    if (filters.limit) params.set("limit", String(filters.limit));
+    const query = params.toString();
+    return this.request<TagWrite[]>(`/tags/writes${query ? `?${query}` : ""}`);
+  }
+
+  async getTagWriteStats(days?: number): Promise<TagWriteStats> {
+    return this.request<TagWriteStats>(`/tags/writes/stats${days ? `?days=${days}` : ""}`);
+  }
+
+  // Stocktakes
+  async getStocktakes(): Promise<Stocktake[]> {
+    return this.request<Stocktake[]>("/stocktakes");
+  }
+
+  async getActiveStocktake(): Promise<Stocktake | null> {
+    return this.request<Stocktake | null>("/stocktakes/active");
+  }
+
+  async getStocktake(id: number): Promise<Stocktake> {
+    return this.request<Stocktake>(`/stocktakes/${id}`);
+  }
+
+  async startStocktake(name?: string | null): Promise<Stocktake> {
+    return this.request<Stocktake>("/stocktakes", {
+      method: "POST",
+      body: JSON.stringify({ name: name ?? null }),
+    });
+  }
+
+  async getStocktakeScans(id: number): Promise<StocktakeScan[]> {
+    return this.request<StocktakeScan[]>(`/stocktakes/${id}/scans`);
+  }
+
+  async scanStocktakeSpool(
+    id: number,
+    spool: { spool_id: string } | { tag_id: string },
+    weight?: number | null,
+  ): Promise<StocktakeScan> {
+    return this.request<StocktakeScan>(`/stocktakes/${id}/scans`, {
+      method: "POST",
+      body: JSON.stringify({ ...spool, weight: weight ?? null }),
+    });
+  }
+
+  async deleteStocktakeScan(id: number, spoolId: string): Promise<void> {
+    return this.request<void>(`/stocktakes/${id}/scans/${spoolId}`, {
+      method: "DELETE",
+    });
+  }
+
+  async getStocktakeReport(id: number, tolerance?: number): Promise<StocktakeReport> {
+    const query = tolerance !== undefined ? `?tolerance=${tolerance}` : "";
+    return this.request<StocktakeReport>(`/stocktakes/${id}/report${query}`);
+  }
+
+  async completeStocktake(id: number, options: { tolerance?: number; apply_weights?: boolean } = {}): Promise<Stocktake> {
+    return this.request<Stocktake>(`/stocktakes/${id}/complete`, {
+      method: "POST",
+      body: JSON.stringify(options),
+    });
+  }
+
+  async cancelStocktake(id: number): Promise<Stocktake> {
+    return this.request<Stocktake>(`/stocktakes/${id}/cancel`, { method: "POST" });
+  }
+
+  // Purchases
+  async getPurchases(supplier?: string): Promise<Purchase[]> {
+    const query = supplier ? `?supplier=${encodeURIComponent(supplier)}` : "";
+    return this.request<Purchase[]>(`/purchases${query}`);
+  }
+
+  async createPurchase(input: PurchaseInput & { spool_ids?: string[] }): Promise<Purchase> {
+    return this.request<Purchase>("/purchases", {
+      method: "POST",
+      body: JSON.stringify(input),
+    });
+  }
+
+  async updatePurchase(id: number, input: PurchaseInput): Promise<Purchase> {
+    return this.request<Purchase>(`/purchases/${id}`, {
+      method: "PUT",
+      body: JSON.stringify(input),
+    });
+  }
+
+  async deletePurchase(id: number): Promise<void> {
+    return this.request<void>(`/purchases/${id}`, {
+      method: "DELETE",
+    });
+  }
+
+  async linkPurchaseSpools(id: number, spoolIds: string[]): Promise<Purchase> {
+    return this.request<Purchase>(`/purchases/${id}/spools`, {
+      method: "POST",
+      body: JSON.stringify({ spool_ids: spoolIds }),
+    });
+  }
+
+  async unlinkPurchaseSpool(id: number, spoolId: string): Promise<void> {
+    return this.request<void>(`/purchases/${id}/spools/${spoolId}`, {
+      method: "DELETE",
+    });
+  }
 
-export interface APIKeyCreateResponse extends APIKey {
-  key: string;  // Full key, only shown on creation
-}
+  async getRestockSuggestions(includeOk = false): Promise<RestockSuggestion[]> {
+    return this.request<RestockSuggestion[]>(`/purchases/restock${includeOk ? "?include_ok=true" : ""}`);
+  }
 
-export interface APIKeyUpdate {
-  name?: string;
-  can_read?: boolean;
-  can_write?: boolean;
-  can_control?: boolean;
-  enabled?: boolean;
-}
+  async getRestockLevels(): Promise<RestockLevels> {
+    return this.request<RestockLevels>("/purchases/restock/levels");
+  }
 
-// Support API types
-export interface DebugLoggingState {
-  enabled: boolean;
-  enabled_at: string | null;
-  duration_seconds: number | null;
-}
+  async setRestockLevels(levels: RestockLevels): Promise<RestockLevels> {
+    return this.request<RestockLevels>("/purchases/restock/levels", {
+      method: "PUT",
+      body: JSON.stringify(levels),
+    });
+  }
 
-export interface LogEntry {
-  timestamp: string;
-  level: string;
-  logger_name: string;
-  message: string;
-}
+  // Integrations
+  async getWledConfig(): Promise<WledConfig> {
+    return this.request<WledConfig>("/integrations/wled");
+  }
 
-export interface LogsResponse {
-  entries: LogEntry[];
-  total_count: number;
-  filtered_count: number;
-}
+  async setWledConfig(config: WledConfig): Promise<WledConfig> {
+    return this.request<WledConfig>("/integrations/wled", {
+      method: "PUT",
+      body: JSON.stringify(config),
+    });
+  }
 
-export interface SystemInfo {
-  // Application
-  version: string;
-  uptime: string;
-  uptime_seconds: number;
-  hostname: string;
-  // Database stats
-  spool_count: number;
-  printer_count: number;
-  connected_printers: number;
-  // Storage
-  database_size: string;
-  disk_total: string;
-  disk_used: string;
-  disk_free: string;
-  disk_percent: number;
-  // System
-  platform: string;
-  platform_release: string;
-  python_version: string;
-  boot_time: string;
-  // Memory
-  memory_total: string;
-  memory_used: string;
-  memory_available: string;
-  memory_percent: number;
-  // CPU
-  cpu_count: number;
-  cpu_percent: number;
-}
+  async testWled(segment?: number): Promise<void> {
+    const query = segment !== undefined ? `?segment=${segment}` : "";
+    return this.request<void>(`/integrations/wled/test${query}`, { method: "POST" });
+  }
 
-class ApiClient {
-  private async request<T>(
-    path: string,
-    options: RequestInit = {}
-  ): Promise<T> {
-    const response = await fetch(`${API_BASE}${path}`, {
-      ...options,
-      headers: {
-        "Content-Type": "application/json",
-        ...options.headers,
-      },
+  async wledOff(): Promise<void> {
+    return this.request<void>("/integrations/wled/off", { method: "POST" });
+  }
+
+  // Kiosk
+  async getKioskConfig(): Promise<KioskConfig> {
+    return this.request<KioskConfig>("/kiosk/config");
+  }
+
+  async setKioskConfig(config: KioskConfig): Promise<KioskConfig> {
+    return this.request<KioskConfig>("/kiosk/config", {
+      method: "PUT",
+      body: JSON.stringify(config),
     });
+  }
 
-    if (!response.ok) {
-      const error = await response.text();
-      throw new Error(error || `HTTP ${response.status}`);
-    }
+  async getKioskMembers(): Promise<KioskMember[]> {
+    return this.request<KioskMember[]>("/kiosk/members");
+  }
 
-    // Handle 204 No Content or empty body
-    if (response.status === 204) {
-      return undefined as T;
-    }
+  async createKioskMember(member: KioskMemberInput): Promise<KioskMember> {
+    return this.request<KioskMember>("/kiosk/members", {
+      method: "POST",
+      body: JSON.stringify(member),
+    });
+  }
 
-    // Check if response has content before parsing JSON
-    const text = await response.text();
-    if (!text) {
-      return undefined as T;
-    }
+  async updateKioskMember(id: number, changes: KioskMemberInput): Promise<KioskMember> {
+    return this.request<KioskMember>(`/kiosk/members/${id}`, {
+      method: "PATCH",
+      body: JSON.stringify(changes),
+    });
+  }
 
-    return JSON.parse(text);
+  async deleteKioskMember(id: number): Promise<void> {
+    return this.request<void>(`/kiosk/members/${id}`, { method: "DELETE" });
   }
 
-  // Spools
-  async listSpools(params?: {
-    material?: string;
-    brand?: string;
-    search?: string;
-  }): Promise<Spool[]> {
-    const searchParams = new URLSearchParams();
-    if (params?.material) searchParams.set("material", params.material);
-    if (params?.brand) searchParams.set("brand", params.brand);
-    if (params?.search) searchParams.set("search", params.search);
+  async getKioskCheckouts(openOnly = false): Promise<KioskCheckout[]> {
+    return this.request<KioskCheckout[]>(`/kiosk/checkouts${openOnly ? "?open=true" : ""}`);
+  }
 
-    const query = searchParams.toString();
-    return this.request<Spool[]>(`/spools${query ? `?${query}` : ""}`);
+  async checkInKioskCheckout(id: number, weight?: number): Promise<KioskCheckout> {
+    return this.request<KioskCheckout>(`/kiosk/checkouts/${id}/check-in`, {
+      method: "POST",
+      body: JSON.stringify({ weight: weight ?? null }),
+    });
   }
 
-  async getSpool(id: string): Promise<Spool> {
-    return this.request<Spool>(`/spools/${id}`);
+  async locateSpool(id: string): Promise<LocateResult> {
+    return this.request<LocateResult>(`/spools/${id}/locate`, { method: "POST" });
   }
 
-  async createSpool(input: SpoolInput): Promise<Spool> {
-    return this.request<Spool>("/spools", {
+  // Share links
+  async getShareLinks(): Promise<ShareLink[]> {
+    return this.request<ShareLink[]>("/share-links");
+  }
+
+  async createShareLink(input: { name?: string | null; fields?: ShareField[]; expires_at?: number | null } = {}): Promise<ShareLink> {
+    return this.request<ShareLink>("/share-links", {
       method: "POST",
       body: JSON.stringify(input),
     });
   }
 
-  async updateSpool(id: string, input: SpoolInput): Promise<Spool> {
-    return this.request<Spool>(`/spools/${id}`, {
+  async revokeShareLink(id: number): Promise<ShareLink> {
+    return this.request<ShareLink>(`/share-links/${id}/revoke`, { method: "POST" });
+  }
+
+  async deleteShareLink(id: number): Promise<void> {
+    return this.request<void>(`/share-links/${id}`, {
+      method: "DELETE",
+    });
+  }
+
+  // Projects
+  async getProjects(includeArchived = false): Promise<Project[]> {
+    return this.request<Project[]>(`/projects${includeArchived ? "?include_archived=true" : ""}`);
+  }
+
+  async createProject(name: string, description?: string | null): Promise<Project> {
+    return this.request<Project>("/projects", {
+      method: "POST",
+      body: JSON.stringify({ name, description: description ?? null }),
+    });
+  }
+
+  async updateProject(id: number, input: ProjectInput): Promise<Project> {
+    return this.request<Project>(`/projects/${id}`, {
       method: "PUT",
       body: JSON.stringify(input),
     });
   }
 
-  async deleteSpool(id: string): Promise<void> {
-    return this.request<void>(`/spools/${id}`, {
+  async deleteProject(id: number): Promise<void> {
+    return this.request<void>(`/projects/${id}`, {
       method: "DELETE",
     });
   }
 
-  async archiveSpool(id: string): Promise<Spool> {
-    return this.request<Spool>(`/spools/${id}/archive`, {
-      method: "POST",
-    });
+  async getActiveProject(): Promise<number | null> {
+    const result = await this.request<{ project_id: number | null }>("/projects/active");
+    return result.project_id;
   }
 
-  async restoreSpool(id: string): Promise<Spool> {
-    return this.request<Spool>(`/spools/${id}/restore`, {
-      method: "POST",
+  async setActiveProject(projectId: number | null): Promise<void> {
+    await this.request("/projects/active", {
+      method: "PUT",
+      body: JSON.stringify({ project_id: projectId }),
     });
   }
 
-  /**
-   * Set spool weight from scale measurement.
-   * This updates weight_current and resets consumed_since_weight to 0,
-   * effectively syncing the tracking to match the scale.
-   * @param id Spool ID
-   * @param weight Total weight in grams (including spool core)
-   */
-  async setSpoolWeight(id: string, weight: number): Promise<Spool> {
-    return this.request<Spool>(`/spools/${id}/weight`, {
+  async getProjectReport(days?: number, includeArchived = false): Promise<ProjectReport> {
+    const params = new URLSearchParams();
+    if (days) params.set("days", String(days));
+    if (includeArchived) params.set("include_archived", "true");
+    const query = params.toString();
+    return this.request<ProjectReport>(`/reports/projects${query ? `?${query}` : ""}`);
+  }
+
+  async getEnergyReport(groupBy: "printer" | ReportPeriod = "printer", days?: number, timezone?: string): Promise<EnergyReport> {
+    const params = new URLSearchParams({ group_by: groupBy });
+    if (days) params.set("days", String(days));
+    if (timezone) params.set("timezone", timezone);
+    return this.request<EnergyReport>(`/reports/energy?${params}`);
+  }
+
+  async matchSlicerSpools(filaments: FilamentRequirement[], printerSerial?: string): Promise<SpoolMatchResponse> {
+    return this.request<SpoolMatchResponse>("/slicer/match", {
       method: "POST",
-      body: JSON.stringify({ weight }),
+      body: JSON.stringify({ filaments, printer_serial: printerSerial ?? null }),
     });
   }
 
+  // Bambu Studio / OrcaSlicer filament preset (JSON) for a spool
+  async exportSpoolSlicerPreset(id: string, printerSerial?: string, nozzleDiameter?: string): Promise<Blob> {
+    const params = new URLSearchParams();
+    if (printerSerial) params.set("printer_serial", printerSerial);
+    if (nozzleDiameter) params.set("nozzle_diameter", nozzleDiameter);
+    const query = params.toString();
+    const response = await fetch(`${API_BASE}/spools/${id}/slicer-preset${query ? `?${query}` : ""}`);
+    if (!response.ok) {
+      const error = await response.text();
+      throw new Error(error || `HTTP ${response.status}`);
+    }
+    return response.blob();
+  }
+
   // Spool K-Profiles
   async getSpoolKProfiles(spoolId: string): Promise<SpoolKProfile[]> {
     return this.request<SpoolKProfile[]>(`/spools/${spoolId}/k-profiles`);
@@ -553,9 +1783,9 @@ class ApiClient {
     });
   }
 
-  async setSlotFilament(serial: string, request: SetSlotRequest): Promise<void> {
+  async setSlotFilament(serial: string, request: SetSlotRequest): Promise<CommandStatus> {
     const { ams_id, tray_id, ...filamentData } = request
-    return this.request<void>(`/printers/${serial}/ams/${ams_id}/tray/${tray_id}/filament`, {
+    return this.request<CommandStatus>(`/printers/${serial}/ams/${ams_id}/tray/${tray_id}/filament`, {
       method: "POST",
       body: JSON.stringify(filamentData),
     });
@@ -568,18 +1798,50 @@ class ApiClient {
     });
   }
 
+  async getPrinterCertificate(serial: string): Promise<CertificateStatus> {
+    return this.request<CertificateStatus>(`/printers/${serial}/certificate`);
+  }
+
+  async repinPrinterCertificate(serial: string, fingerprint?: string): Promise<CertificateStatus> {
+    return this.request<CertificateStatus>(`/printers/${serial}/certificate/repin`, {
+      method: "POST",
+      body: JSON.stringify({ fingerprint: fingerprint ?? null }),
+    });
+  }
+
+  async getPrinterNozzles(serial: string): Promise<PrinterNozzles> {
+    return this.request<PrinterNozzles>(`/printers/${serial}/nozzles`);
+  }
+
+  async getAmsUnits(serial: string, includeDetached = false): Promise<AmsUnitRecord[]> {
+    const query = includeDetached ? "?include_detached=true" : "";
+    return this.request<AmsUnitRecord[]>(`/printers/${serial}/ams-units${query}`);
+  }
+
+  async getAmsEvents(
+    serial: string,
+    filters: { ams_id?: number; tray_id?: number; spool_id?: string; since?: number; limit?: number } = {}
+  ): Promise<AmsEvent[]> {
+    const params = new URLSearchParams();
+    for (const [key, value] of Object.entries(filters)) {
+      if (value !== undefined) params.set(key, String(value));
+    }
+    const query = params.toString();
+    return this.request<AmsEvent[]>(`/printers/${serial}/ams-events${query ? `?${query}` : ""}`);
+  }
+
   // AMS slot operations
 
   /** Trigger RFID re-read on an AMS slot (sends ams_get_rfid command) */
-  async rereadSlot(serial: string, amsId: number, trayId: number): Promise<void> {
-    return this.request<void>(`/printers/${serial}/ams/${amsId}/tray/${trayId}/reset`, {
+  async rereadSlot(serial: string, amsId: number, trayId: number): Promise<CommandStatus> {
+    return this.request<CommandStatus>(`/printers/${serial}/ams/${amsId}/tray/${trayId}/reset`, {
       method: "POST",
     });
   }
 
   /** Clear/reset an AMS slot to empty state by setting empty filament info */
-  async clearSlot(serial: string, amsId: number, trayId: number): Promise<void> {
-    return this.request<void>(`/printers/${serial}/ams/${amsId}/tray/${trayId}/filament`, {
+  async clearSlot(serial: string, amsId: number, trayId: number): Promise<CommandStatus> {
+    return this.request<CommandStatus>(`/printers/${serial}/ams/${amsId}/tray/${trayId}/filament`, {
       method: "POST",
       body: JSON.stringify({
         tray_info_idx: "",
@@ -596,13 +1858,36 @@ class ApiClient {
     return this.rereadSlot(serial, amsId, trayId);
   }
 
-  async setCalibration(serial: string, amsId: number, trayId: number, request: SetCalibrationRequest): Promise<void> {
-    return this.request<void>(`/printers/${serial}/ams/${amsId}/tray/${trayId}/calibration`, {
+  async setSpeedLevel(serial: string, level: SpeedLevel): Promise<CommandStatus> {
+    return this.request<CommandStatus>(`/printers/${serial}/speed`, {
+      method: "POST",
+      body: JSON.stringify({ level }),
+    });
+  }
+
+  async skipObjects(serial: string, objIds: number[]): Promise<CommandStatus> {
+    return this.request<CommandStatus>(`/printers/${serial}/skip-objects`, {
+      method: "POST",
+      body: JSON.stringify({ obj_ids: objIds }),
+    });
+  }
+
+  async setCalibration(serial: string, amsId: number, trayId: number, request: SetCalibrationRequest): Promise<CommandStatus> {
+    return this.request<CommandStatus>(`/printers/${serial}/ams/${amsId}/tray/${trayId}/calibration`, {
       method: "POST",
       body: JSON.stringify(request),
     });
   }
 
+  /** Get a command's outcome, waiting up to `wait` seconds while it is pending */
+  async getPrinterCommand(serial: string, sequenceId: string, wait = 0): Promise<CommandStatus> {
+    return this.request<CommandStatus>(`/printers/${serial}/commands/${sequenceId}?wait=${wait}`);
+  }
+
+  async getPrinterCommands(serial: string): Promise<CommandStatus[]> {
+    return this.request<CommandStatus[]>(`/printers/${serial}/commands`);
+  }
+
   async getCalibrations(serial: string): Promise<CalibrationProfile[]> {
     return this.request<CalibrationProfile[]>(`/printers/${serial}/calibrations`);
   }
@@ -611,17 +1896,51 @@ class ApiClient {
     serial: string,
     amsId: number,
     trayId: number,
-    spoolId: string
+    spoolId: string,
+    force = false
   ): Promise<AssignSpoolResponse> {
     return this.request<AssignSpoolResponse>(
       `/printers/${serial}/ams/${amsId}/tray/${trayId}/assign`,
       {
         method: "POST",
-        body: JSON.stringify({ spool_id: spoolId }),
+        body: JSON.stringify({ spool_id: spoolId, force }),
       }
     );
   }
 
+  async getSpoolCompatibility(serial: string, spoolId: string, amsId = 0): Promise<SpoolCompatibility> {
+    return this.request<SpoolCompatibility>(
+      `/printers/${serial}/compatibility?spool_id=${encodeURIComponent(spoolId)}&ams_id=${amsId}`
+    );
+  }
+
+  async getExternalSpool(serial: string, amsId = 255): Promise<ExternalSpool> {
+    return this.request<ExternalSpool>(`/printers/${serial}/external-spool?ams_id=${amsId}`);
+  }
+
+  async setExternalSpool(serial: string, spoolId: string, amsId = 255, force = false): Promise<ExternalSpool> {
+    return this.request<ExternalSpool>(`/printers/${serial}/external-spool`, {
+      method: "PUT",
+      body: JSON.stringify({ spool_id: spoolId, ams_id: amsId, force }),
+    });
+  }
+
+  async clearExternalSpool(serial: string, amsId = 255): Promise<void> {
+    return this.request<void>(`/printers/${serial}/external-spool?ams_id=${amsId}`, { method: "DELETE" });
+  }
+
+  // Filament runout/jam events
+  async reportRunout(serial: string, event: RunoutEventInput = {}): Promise<RunoutEvent> {
+    return this.request<RunoutEvent>(`/printers/${serial}/events/runout`, {
+      method: "POST",
+      body: JSON.stringify(event),
+    });
+  }
+
+  async getRunoutEvents(serial: string, limit = 50): Promise<RunoutEvent[]> {
+    return this.request<RunoutEvent[]>(`/printers/${serial}/events/runout?limit=${limit}`);
+  }
+
   // Device
   async getDeviceStatus(): Promise<DeviceStatus> {
     return this.request<DeviceStatus>("/device/status");
@@ -889,6 +2208,20 @@ class ApiClient {
     return this.request<ColorLookupResult>(`/colors/lookup?${params.toString()}`);
   }
 
+  async resolveColor(value: string, manufacturer?: string, material?: string): Promise<ResolvedColor> {
+    const params = new URLSearchParams({ value });
+    if (manufacturer) params.append("manufacturer", manufacturer);
+    if (material) params.append("material", material);
+    return this.request<ResolvedColor>(`/colors/resolve?${params.toString()}`);
+  }
+
+  // Spools closest to a hex color or color name; maxDeltaE ~10 keeps the same color family
+  async findSpoolsBySimilarColor(color: string, maxDeltaE = 10, material?: string): Promise<ColorMatch[]> {
+    const params = new URLSearchParams({ color, max_delta_e: String(maxDeltaE) });
+    if (material) params.append("material", material);
+    return this.request<ColorMatch[]>(`/spools/similar-color?${params.toString()}`);
+  }
+
   async searchColors(manufacturer?: string, material?: string): Promise<ColorEntry[]> {
     const params = new URLSearchParams();
     if (manufacturer) params.append("manufacturer", manufacturer);
@@ -913,6 +2246,30 @@ class ApiClient {
     });
   }
 
+  // Energy pricing API
+  async getEnergyPricing(): Promise<EnergyPricing> {
+    return this.request<EnergyPricing>("/settings/energy/pricing");
+  }
+
+  async setEnergyPricing(pricing: EnergyPricing): Promise<EnergyPricing> {
+    return this.request<EnergyPricing>("/settings/energy/pricing", {
+      method: "PUT",
+      body: JSON.stringify(pricing),
+    });
+  }
+
+  // Report calendar API
+  async getReportCalendar(): Promise<ReportCalendar> {
+    return this.request<ReportCalendar>("/settings/reports/calendar");
+  }
+
+  async setReportCalendar(calendar: ReportCalendar): Promise<ReportCalendar> {
+    return this.request<ReportCalendar>("/settings/reports/calendar", {
+      method: "PUT",
+      body: JSON.stringify(calendar),
+    });
+  }
+
   // Support API
   async getDebugLogging(): Promise<DebugLoggingState> {
     return this.request<DebugLoggingState>("/support/debug-logging");
@@ -934,6 +2291,16 @@ class ApiClient {
     return this.request<LogsResponse>(`/support/logs${query ? `?${query}` : ""}`);
   }
 
+  // Live log of one printer; each message's data is a PrinterLogEvent
+  streamPrinterLogs(serial: string, params?: { level?: string; search?: string; tail?: number }): EventSource {
+    const searchParams = new URLSearchParams();
+    if (params?.level) searchParams.set("level", params.level);
+    if (params?.search) searchParams.set("search", params.search);
+    if (params?.tail !== undefined) searchParams.set("tail", String(params.tail));
+    const query = searchParams.toString();
+    return new EventSource(`${API_BASE}/printers/${serial}/logs/stream${query ? `?${query}` : ""}`);
+  }
+
   async clearLogs(): Promise<{ message: string }> {
     return this.request<{ message: string }>("/support/logs", {
       method: "DELETE",
@@ -1,5 +1,6 @@
 import { createContext, ComponentChildren } from "preact";
 import { useContext, useEffect, useRef, useCallback, useState } from "preact/hooks";
+import type { ColorSwatch } from "./api";
 
 export interface AmsTray {
   ams_id: number;
@@ -11,6 +12,7 @@ export interface AmsTray {
   nozzle_temp_min: number | null;
   nozzle_temp_max: number | null;
   remain: number | null; // Remaining filament percentage (0-100)
+  color?: ColorSwatch | null; // tray_color parsed for drawing
 }
 
 export interface AmsUnit {
@@ -28,6 +30,7 @@ export interface PrinterState {
   total_layer_num: number | null;
   subtask_name: string | null;
   mc_remaining_time: number | null; // Remaining time in minutes
+  print_eta: number | null; // Expected finish (Unix time)
   gcode_file: string | null; // Current gcode file path
   ams_units: AmsUnit[];
   vt_tray: AmsTray | null;
@@ -40,8 +43,16 @@ export interface PrinterState {
   tray_reading_bits: number | null; // Bitmask of trays currently being read
   // Nozzle count (auto-detected from MQTT)
   nozzle_count: number; // 1 = single nozzle, 2 = dual nozzle (H2C/H2D)
+  // Print options
+  speed_level: SpeedLevel | null;
+  print_error: number; // 0 = none
+  print_error_code: string | null; // e.g. "0300-400C"
+  skipped_objects: number[]; // Object ids skipped in the current print
 }
 
+// 1 silent, 2 standard, 3 sport, 4 ludicrous
+export type SpeedLevel = 1 | 2 | 3 | 4;
+
 interface WebSocketState {
   deviceConnected: boolean;
   deviceUpdateAvailable: boolean;
@@ -61,6 +72,9 @@ interface WebSocketMessage {
   [key: string]: unknown;
 }
 
+// WebSocket connections that fail to open before switching to server-sent events
+const WS_FALLBACK_ATTEMPTS = 3;
+
 const WebSocketContext = createContext<WebSocketContextValue | null>(null);
 
 export function WebSocketProvider({ children }: { children: ComponentChildren }) {
@@ -76,6 +90,8 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
   const wsRef = useRef<WebSocket | null>(null);
   const handlersRef = useRef<Set<(message: WebSocketMessage) => void>>(new Set());
   const reconnectTimeoutRef = useRef<number | null>(null);
+  const eventSourceRef = useRef<EventSource | null>(null);
+  const failedAttemptsRef = useRef(0);
 
   // Handle incoming WebSocket messages
   const handleMessage = useCallback((message: WebSocketMessage) => {
@@ -101,6 +117,11 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
           const printers = message.printers as Record<string, boolean>;
           setPrinterStatuses(new Map(Object.entries(printers)));
         }
+        // Full printer state snapshots (printer_state messages only follow on change)
+        if (message.printer_states && typeof message.printer_states === "object") {
+          const states = message.printer_states as Record<string, PrinterState>;
+          setPrinterStates(new Map(Object.entries(states)));
+        }
         break;
 
       case "device_update_available":
@@ -184,11 +205,43 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
       case "printer_added":
       case "printer_updated":
       case "printer_removed":
+      case "external_spool":
+      case "filament_runout":
+      case "spool_status":
+      case "job_progress":
+      case "stocktake_scan":
+      case "kiosk_checkout":
+      case "printer_auth_failed":
         // These are handled by subscribers (e.g., Printers page)
         break;
     }
   }, []);
 
+  const dispatch = useCallback((data: string) => {
+    try {
+      const message: WebSocketMessage = JSON.parse(data);
+      handleMessage(message);
+
+      // Notify subscribers
+      handlersRef.current.forEach((handler) => handler(message));
+    } catch (e) {
+      console.error("Failed to parse WebSocket message:", e);
+    }
+  }, [handleMessage]);
+
+  // Server-sent events, for proxies that don't pass WebSocket through.
+  // EventSource reconnects by itself and resumes with Last-Event-ID.
+  const connectEvents = useCallback(() => {
+    console.log("Falling back to server-sent events: /api/events");
+    const es = new EventSource("/api/events");
+    eventSourceRef.current = es;
+
+    es.onmessage = (event) => dispatch(event.data);
+    es.onerror = () => {
+      console.log("Event stream interrupted, reconnecting...");
+    };
+  }, [dispatch]);
+
   const connect = useCallback(() => {
     // Determine WebSocket URL
     const protocol = window.location.protocol === "https:" ? "wss:" : "ws:";
@@ -197,9 +250,12 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
     console.log("Connecting to WebSocket:", wsUrl);
     const ws = new WebSocket(wsUrl);
     wsRef.current = ws;
+    let opened = false;
 
     ws.onopen = () => {
       console.log("WebSocket connected");
+      opened = true;
+      failedAttemptsRef.current = 0;
       if (reconnectTimeoutRef.current) {
         clearTimeout(reconnectTimeoutRef.current);
         reconnectTimeoutRef.current = null;
@@ -207,8 +263,13 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
     };
 
     ws.onclose = () => {
-      console.log("WebSocket disconnected, reconnecting in 3s...");
       setDeviceConnected(false);
+      if (!opened && ++failedAttemptsRef.current >= WS_FALLBACK_ATTEMPTS) {
+        wsRef.current = null;
+        connectEvents();
+        return;
+      }
+      console.log("WebSocket disconnected, reconnecting in 3s...");
       reconnectTimeoutRef.current = window.setTimeout(connect, 3000);
     };
 
@@ -216,18 +277,8 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
       console.error("WebSocket error:", error);
     };
 
-    ws.onmessage = (event) => {
-      try {
-        const message: WebSocketMessage = JSON.parse(event.data);
-        handleMessage(message);
-
-        // Notify subscribers
-        handlersRef.current.forEach((handler) => handler(message));
-      } catch (e) {
-        console.error("Failed to parse WebSocket message:", e);
-      }
-    };
-  }, [handleMessage]);
+    ws.onmessage = (event) => dispatch(event.data);
+  }, [dispatch, connectEvents]);
 
   useEffect(() => {
     connect();
@@ -236,6 +287,7 @@ export function WebSocketProvider({ children }: { children: ComponentChildren })
         clearTimeout(reconnectTimeoutRef.current);
       }
       wsRef.current?.close();
+      eventSourceRef.current?.close();
     };
   }, [connect]);
 
@@ -1,5 +1,5 @@
 import { useEffect, useState, useCallback } from "preact/hooks";
-import { api, Spool, SpoolInput, SpoolsInPrinters } from "../lib/api";
+import { api, Spool, SpoolFromTemplateInput, SpoolInput, SpoolsInPrinters } from "../lib/api";
 import {
   SpoolsTable,
   StatsBar,
@@ -159,9 +159,15 @@ export function Inventory() {
     return spool;
   };
 
+  const handleAddFromTemplate = async (templateId: number, input: SpoolFromTemplateInput) => {
+    const spool = await api.createSpoolFromTemplate(templateId, input);
+    await loadSpools();
+    return spool;
+  };
+
   const handleEditSpool = async (input: SpoolInput) => {
     if (!editSpool) throw new Error('No spool to edit');
-    const spool = await api.updateSpool(editSpool.id, input);
+    const spool = await api.updateSpool(editSpool.id, { ...input, expected_version: editSpool.version });
     await loadSpools();
     return spool;
   };
@@ -266,6 +272,7 @@ export function Inventory() {
           setAddWeight(null);
         }}
         onSave={handleAddSpool}
+        onAddFromTemplate={handleAddFromTemplate}
         printersWithCalibrations={printersWithCalibrations}
         initialTagId={addTagId}
         initialWeight={addWeight}
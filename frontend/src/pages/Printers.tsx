@@ -1,6 +1,6 @@
 import { useEffect, useState } from "preact/hooks";
-import { api, Printer, DiscoveredPrinter, CalibrationProfile, AMSThresholds } from "../lib/api";
-import { useWebSocket } from "../lib/websocket";
+import { api, Printer, DiscoveredPrinter, CalibrationProfile, AMSThresholds, ExternalSpool as ExternalSpoolInfo, RunoutEvent, Spool } from "../lib/api";
+import { useWebSocket, SpeedLevel } from "../lib/websocket";
 import { AmsCard, ExternalSpool } from "../components/AmsCard";
 import { AMSHistoryModal } from "../components/AMSHistoryModal";
 import { useToast } from "../lib/toast";
@@ -18,6 +18,8 @@ import {
   RefreshCw,
   Frown,
   Box,
+  KeyRound,
+  Camera,
 } from "lucide-preact";
 
 const EXPANDED_PRINTERS_KEY = "spoolbuddy-expanded-printers";
@@ -34,10 +36,10 @@ function formatRemainingTime(minutes: number | null): string {
   return remainingHours > 0 ? `${days}d ${remainingHours}h` : `${days}d`;
 }
 
-// Calculate ETA from remaining minutes
-function calculateETA(minutes: number | null): string {
+// Format the expected finish, from the printer's ETA or else the remaining minutes
+function calculateETA(minutes: number | null, printEta?: number | null): string {
   if (minutes === null || minutes === undefined || minutes < 0) return "";
-  const eta = new Date(Date.now() + minutes * 60 * 1000);
+  const eta = new Date(printEta ? printEta * 1000 : Date.now() + minutes * 60 * 1000);
   const now = new Date();
   const isToday = eta.toDateString() === now.toDateString();
   const isTomorrow = eta.toDateString() === new Date(Date.now() + 86400000).toDateString();
@@ -48,6 +50,13 @@ function calculateETA(minutes: number | null): string {
   return eta.toLocaleDateString([], { weekday: 'short', hour: '2-digit', minute: '2-digit' });
 }
 
+const SPEED_LEVELS: { value: SpeedLevel; label: string }[] = [
+  { value: 1, label: "Silent" },
+  { value: 2, label: "Standard" },
+  { value: 3, label: "Sport" },
+  { value: 4, label: "Ludicrous" },
+];
+
 // Load expanded state from localStorage
 function loadExpandedPrinters(): Set<string> {
   try {
@@ -119,6 +128,33 @@ function getModelImage(model: string | null): string {
   return '/img/printers/default.png';
 }
 
+// Live chamber camera view, proxied by the backend as MJPEG
+function CameraModal({ printer, onClose }: { printer: Printer; onClose: () => void }) {
+  const [loaded, setLoaded] = useState(false);
+  const [error, setError] = useState(false);
+
+  return (
+    <Modal isOpen={true} onClose={onClose} title={`${printer.name || printer.serial} camera`} size="xl">
+      <div class="relative aspect-video bg-black rounded-lg overflow-hidden flex items-center justify-center">
+        {error ? (
+          <p class="text-sm text-[var(--text-muted)]">Camera unavailable</p>
+        ) : (
+          <>
+            <img
+              src={`/api/printers/${printer.serial}/camera/stream`}
+              alt="Chamber camera"
+              class={`w-full h-full object-contain ${loaded ? "block" : "hidden"}`}
+              onLoad={() => setLoaded(true)}
+              onError={() => setError(true)}
+            />
+            {!loaded && <Loader2 class="w-8 h-8 text-[var(--text-muted)] animate-spin" />}
+          </>
+        )}
+      </div>
+    </Modal>
+  );
+}
+
 // Cover image component for print jobs
 function CoverImage({ url, printName }: { url: string | null; printName?: string | null }) {
   const [loaded, setLoaded] = useState(false);
@@ -182,10 +218,12 @@ export function Printers() {
   const [showDiscoverModal, setShowDiscoverModal] = useState(false);
   const [selectedDiscovered, setSelectedDiscovered] = useState<DiscoveredPrinter | null>(null);
   const [deleteConfirm, setDeleteConfirm] = useState<string | null>(null); // serial to delete
+  const [cameraPrinter, setCameraPrinter] = useState<Printer | null>(null); // printer whose camera is shown
   const [connecting, setConnecting] = useState<string | null>(null); // serial being connected
   const [calibrations, setCalibrations] = useState<Record<string, CalibrationProfile[]>>({}); // serial -> calibrations
   const [expandedPrinters, setExpandedPrinters] = useState<Set<string>>(loadExpandedPrinters); // expanded printers by serial
   const [amsThresholds, setAmsThresholds] = useState<AMSThresholds | undefined>(undefined);
+  const [externalSpools, setExternalSpools] = useState<Record<string, Spool | null>>({}); // "serial:ams_id" -> spool on external holder
   const [historyModal, setHistoryModal] = useState<{
     printerSerial: string;
     amsId: number;
@@ -243,6 +281,16 @@ export function Printers() {
         }
       } else if (message.type === "printer_disconnected") {
         setConnecting(null);
+      } else if (message.type === "printer_auth_failed") {
+        setConnecting(null);
+        loadPrinters();
+      } else if (message.type === "external_spool") {
+        const external = message as unknown as ExternalSpoolInfo & { serial: string };
+        setExternalSpools(prev => ({ ...prev, [`${external.serial}:${external.ams_id}`]: external.spool }));
+      } else if (message.type === "filament_runout") {
+        const event = message as unknown as RunoutEvent;
+        const slot = event.ams_id >= 254 ? "external spool" : `AMS ${event.ams_id} tray ${event.tray_id}`;
+        showToast('error', `Filament ${event.kind} on ${event.printer_serial} (${slot})`);
       }
     });
 
@@ -272,10 +320,25 @@ export function Printers() {
     setHistoryModal({ printerSerial, amsId, amsLabel, mode });
   };
 
+  // Spools assigned to the external holders (255, and 254 = left on dual-nozzle printers)
+  const loadExternalSpools = async (serial: string) => {
+    try {
+      const holders = await Promise.all([255, 254].map(amsId => api.getExternalSpool(serial, amsId)));
+      setExternalSpools(prev => {
+        const next = { ...prev };
+        holders.forEach(h => { next[`${serial}:${h.ams_id}`] = h.spool; });
+        return next;
+      });
+    } catch (e) {
+      console.error(`Failed to fetch external spools for ${serial}:`, e);
+    }
+  };
+
   const loadPrinters = async () => {
     try {
       const data = await api.listPrinters();
       setPrinters(data);
+      data.forEach(p => loadExternalSpools(p.serial));
     } catch (e) {
       console.error("Failed to load printers:", e);
     } finally {
@@ -319,6 +382,28 @@ export function Printers() {
     }
   };
 
+  const handleSpeedLevel = async (serial: string, level: SpeedLevel) => {
+    try {
+      await api.setSpeedLevel(serial, level);
+    } catch (e) {
+      console.error("Failed to set speed:", e);
+      showToast('error', `Failed to set speed: ${e instanceof Error ? e.message : e}`);
+    }
+  };
+
+  // The printer refused its access code, e.g. after it was regenerated on the printer
+  const handleAccessCode = async (printer: Printer) => {
+    const accessCode = prompt(`New access code for "${printer.name || printer.serial}" (printer's network settings):`);
+    if (!accessCode?.trim()) return;
+    try {
+      await api.updatePrinter(printer.serial, { access_code: accessCode.trim() });
+      await loadPrinters();
+    } catch (e) {
+      console.error("Failed to update access code:", e);
+      showToast('error', `Failed to update access code: ${e instanceof Error ? e.message : e}`);
+    }
+  };
+
   const handleDisconnect = async (serial: string) => {
     const printer = printers.find(p => p.serial === serial);
     try {
@@ -442,7 +527,14 @@ export function Printers() {
                         <p class="text-sm text-[var(--text-secondary)]">
                           {effectiveModel || "Unknown Model"} &bull; {printer.ip_address || "No IP"}
                         </p>
-                        <p class="text-xs text-[var(--text-muted)] font-mono">{printer.serial}</p>
+                        <p class="text-xs text-[var(--text-muted)] font-mono">
+                          {printer.serial}
+                          {printer.firmware_version && (
+                            <span title={printer.hardware_version ? `Hardware ${printer.hardware_version}` : undefined}>
+                              {" "}&bull; FW {printer.firmware_version}
+                            </span>
+                          )}
+                        </p>
                       </div>
                     </div>
                     <div class="flex items-center gap-3">
@@ -467,6 +559,15 @@ export function Printers() {
                           <Loader2 class="w-3 h-3 animate-spin" />
                           Connecting...
                         </span>
+                      ) : printer.needs_access_code && !connected ? (
+                        <button
+                          onClick={(e) => { e.stopPropagation(); handleAccessCode(printer); }}
+                          class="inline-flex items-center gap-1.5 px-2.5 py-1 rounded-full text-xs font-medium bg-[var(--error-color)]/10 text-[var(--error-color)] hover:bg-[var(--error-color)]/20"
+                          title="The printer refused its access code. Click to enter the new one."
+                        >
+                          <KeyRound class="w-3 h-3" />
+                          Needs new access code
+                        </button>
                       ) : (
                         <span class={`inline-flex items-center gap-1.5 px-2.5 py-1 rounded-full text-xs font-medium ${
                           connected
@@ -494,6 +595,16 @@ export function Printers() {
                           Disconnect
                         </button>
                       )}
+                      {/* Camera button */}
+                      {connected && (
+                        <button
+                          onClick={(e) => { e.stopPropagation(); setCameraPrinter(printer); }}
+                          class="p-2 text-[var(--text-muted)] hover:text-[var(--text-primary)] hover:bg-[var(--bg-tertiary)] rounded-lg transition-colors"
+                          title="Show camera"
+                        >
+                          <Camera class="w-4 h-4" />
+                        </button>
+                      )}
                       {/* Delete button */}
                       <button
                         onClick={(e) => { e.stopPropagation(); handleDelete(printer.serial); }}
@@ -566,6 +677,7 @@ export function Printers() {
                               printerSerial={printer.serial}
                               calibrations={calibrations[printer.serial] || []}
                               trayReadingBits={state.tray_reading_bits}
+                              spool={externalSpools[`${printer.serial}:${numExtruders >= 2 ? 254 : 255}`]}
                             />
                             {/* Ext-R for dual-nozzle printers only */}
                             {numExtruders >= 2 && (
@@ -576,6 +688,7 @@ export function Printers() {
                                 printerSerial={printer.serial}
                                 calibrations={calibrations[printer.serial] || []}
                                 trayReadingBits={state.tray_reading_bits}
+                                spool={externalSpools[`${printer.serial}:255`]}
                               />
                             )}
                           </div>
@@ -632,9 +745,34 @@ export function Printers() {
                                     <span>•</span>
                                     <span>{formatRemainingTime(state.mc_remaining_time)} left</span>
                                     <span>•</span>
-                                    <span>ETA {calculateETA(state.mc_remaining_time)}</span>
+                                    <span>ETA {calculateETA(state.mc_remaining_time, state.print_eta)}</span>
                                   </>
                                 )}
+                                {state.skipped_objects.length > 0 && (
+                                  <>
+                                    <span>•</span>
+                                    <span>{state.skipped_objects.length} skipped</span>
+                                  </>
+                                )}
+                                {state.print_error_code && (
+                                  <span class="text-[var(--error-color)]" title="Printer error code">
+                                    Error {state.print_error_code}
+                                  </span>
+                                )}
+                                {(state.gcode_state === "RUNNING" || state.gcode_state === "PAUSE") && (
+                                  <select
+                                    value={state.speed_level ?? ""}
+                                    onClick={(e) => e.stopPropagation()}
+                                    onChange={(e) => handleSpeedLevel(printer.serial, Number((e.target as HTMLSelectElement).value) as SpeedLevel)}
+                                    class="ml-auto px-1.5 py-0.5 text-xs bg-[var(--bg-secondary)] border border-[var(--border-color)] rounded text-[var(--text-primary)]"
+                                    title="Print speed"
+                                  >
+                                    {state.speed_level === null && <option value="">Speed</option>}
+                                    {SPEED_LEVELS.map(({ value, label }) => (
+                                      <option key={value} value={value}>{label}</option>
+                                    ))}
+                                  </select>
+                                )}
                               </div>
                             </div>
                           </div>
@@ -727,6 +865,11 @@ export function Printers() {
         );
       })()}
 
+      {/* Camera Modal */}
+      {cameraPrinter && (
+        <CameraModal printer={cameraPrinter} onClose={() => setCameraPrinter(null)} />
+      )}
+
       {/* AMS History Modal */}
       {historyModal && (
         <AMSHistoryModal
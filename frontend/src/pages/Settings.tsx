@@ -2,11 +2,13 @@ import { useState, useEffect, useCallback } from "preact/hooks";
 import * as preact from "preact";
 import { useWebSocket } from "../lib/websocket";
 import { api, CloudAuthStatus, VersionInfo, UpdateCheck, UpdateStatus, FirmwareCheck, AMSThresholds, DebugLoggingState, LogEntry, SystemInfo, APIKey, APIKeyCreate } from "../lib/api";
-import { Cloud, CloudOff, LogOut, Loader2, Mail, Lock, Key, Download, RefreshCw, CheckCircle, AlertCircle, GitBranch, ExternalLink, Wifi, WifiOff, Cpu, Usb, RotateCcw, Upload, HardDrive, Palette, Sun, Moon, LayoutDashboard, Settings2, Package, Monitor, Scale, X, ChevronRight, Droplets, Thermometer, LifeBuoy, Bug, Trash2, FileText, Server, Database, Activity, HelpCircle, Play, Square, Copy, Globe, Plus } from "lucide-preact";
+import { Cloud, CloudOff, LogOut, Loader2, Mail, Lock, Key, Download, RefreshCw, CheckCircle, AlertCircle, GitBranch, ExternalLink, Wifi, WifiOff, Cpu, Usb, RotateCcw, Upload, HardDrive, Palette, Sun, Moon, LayoutDashboard, Settings2, Package, Monitor, Scale, X, ChevronRight, Droplets, Thermometer, LifeBuoy, Bug, Trash2, FileText, Server, Database, Activity, HelpCircle, Play, Square, Copy, Globe, Plus, Lightbulb } from "lucide-preact";
 import { useToast } from "../lib/toast";
 import { SerialTerminal } from "../components/SerialTerminal";
 import { SpoolCatalogSettings } from "../components/SpoolCatalogSettings";
 import { ColorCatalogSettings } from "../components/ColorCatalogSettings";
+import { WledSettings } from "../components/WledSettings";
+import { KioskSettings } from "../components/KioskSettings";
 import { APIBrowser } from "../components/APIBrowser";
 import { useTheme, type ThemeStyle, type DarkBackground, type LightBackground, type ThemeAccent } from "../lib/theme";
 
@@ -295,7 +297,7 @@ function AMSSettings() {
   );
 }
 
-type SettingsTab = 'general' | 'filament' | 'system' | 'api' | 'support';
+type SettingsTab = 'general' | 'filament' | 'integrations' | 'system' | 'api' | 'support';
 
 // Reusable section card component for consistent styling
 function SettingsCard({
@@ -449,6 +451,8 @@ export function Settings() {
       'dashboard': 'filament',
       'catalog': 'filament',
       'colors': 'filament',
+      'wled': 'integrations',
+      'kiosk': 'integrations',
       'system-info': 'support',
       'logs': 'support',
       'debug': 'support',
@@ -558,10 +562,12 @@ export function Settings() {
     }
   }, [showToast]);
 
-  // Upload firmware file
+  // Upload firmware file, with its .sig signature if selected along with it
   const handleFirmwareUpload = useCallback(async (e: Event) => {
     const input = e.target as HTMLInputElement;
-    const file = input.files?.[0];
+    const files = Array.from(input.files ?? []);
+    const file = files.find(f => !f.name.endsWith('.sig'));
+    const signature = files.find(f => f.name.endsWith('.sig'));
     if (!file) return;
 
     if (!file.name.endsWith('.bin')) {
@@ -573,6 +579,9 @@ export function Settings() {
     try {
       const formData = new FormData();
       formData.append('file', file);
+      if (signature) {
+        formData.append('signature', signature);
+      }
 
       const response = await fetch('/api/firmware/upload', {
         method: 'POST',
@@ -585,7 +594,10 @@ export function Settings() {
       }
 
       const result = await response.json();
-      showToast('success', `Firmware v${result.version} uploaded successfully`);
+      showToast(
+        'success',
+        `Firmware v${result.version} uploaded successfully${result.signed ? ' (signed)' : ''}`
+      );
       handleCheckFirmware();
     } catch (e) {
       showToast('error', e instanceof Error ? e.message : 'Failed to upload firmware');
@@ -1066,6 +1078,7 @@ export function Settings() {
   const tabs: { id: SettingsTab; label: string; icon: typeof Settings2 }[] = [
     { id: 'general', label: 'General', icon: Settings2 },
     { id: 'filament', label: 'Filament', icon: Package },
+    { id: 'integrations', label: 'Integrations', icon: Lightbulb },
     { id: 'system', label: 'System', icon: Monitor },
     { id: 'api', label: 'API', icon: Globe },
     { id: 'support', label: 'Support', icon: LifeBuoy },
@@ -1450,6 +1463,18 @@ export function Settings() {
           </div>
         )}
 
+        {/* ============ INTEGRATIONS TAB ============ */}
+        {activeTab === 'integrations' && (
+          <div class="space-y-6">
+            <div id="wled" class="scroll-mt-20">
+              <WledSettings />
+            </div>
+            <div id="kiosk" class="scroll-mt-20">
+              <KioskSettings />
+            </div>
+          </div>
+        )}
+
         {/* ============ API TAB ============ */}
         {activeTab === 'api' && (
           <div class="grid grid-cols-1 xl:grid-cols-2 gap-8">
@@ -2555,7 +2580,7 @@ export function Settings() {
                         <div>
                           <p class="text-sm font-medium text-[var(--text-primary)]">Upload .bin File</p>
                           <p class="text-xs text-[var(--text-muted)]">
-                            Make firmware available for OTA
+                            Make firmware available for OTA (pick its .sig along with it if signed)
                           </p>
                         </div>
                         <label class="btn btn-ghost flex items-center gap-2 cursor-pointer">
@@ -2567,7 +2592,8 @@ export function Settings() {
                           {uploadingFirmware ? 'Uploading...' : 'Choose'}
                           <input
                             type="file"
-                            accept=".bin"
+                            accept=".bin,.sig"
+                            multiple
                             onChange={handleFirmwareUpload}
                             disabled={uploadingFirmware}
                             class="hidden"
@@ -275,6 +275,14 @@ static void parse_printer_state(cJSON *state_json, BackendPrinterState *printer)
             }
         }
     }
+
+    // External spool holder (virtual tray)
+    cJSON *vt_tray = cJSON_GetObjectItem(state_json, "vt_tray");
+    memset(&printer->vt_tray, 0, sizeof(printer->vt_tray));
+    printer->has_vt_tray = vt_tray && cJSON_IsObject(vt_tray);
+    if (printer->has_vt_tray) {
+        parse_ams_tray(vt_tray, &printer->vt_tray);
+    }
 }
 
 // Fetch JSON from URL
@@ -793,6 +801,22 @@ int backend_get_ams_unit(int printer_index, int ams_index, AmsUnitCInfo *info) {
     return 0;
 }
 
+int backend_get_vt_tray(int printer_index, AmsTrayCInfo *info) {
+    if (!info || printer_index < 0 || printer_index >= g_state.printer_count) {
+        return -1;
+    }
+
+    memset(info, 0, sizeof(*info));
+    BackendPrinterState *printer = &g_state.printers[printer_index];
+    if (printer->has_vt_tray) {
+        strncpy(info->tray_type, printer->vt_tray.tray_type, sizeof(info->tray_type) - 1);
+        info->tray_color = parse_hex_color_rgba(printer->vt_tray.tray_color);
+        info->remain = printer->vt_tray.remain;
+    }
+
+    return 0;
+}
+
 int backend_get_tray_now(int printer_index) {
     if (printer_index < 0 || printer_index >= g_state.printer_count) {
         return -1;
@@ -1396,6 +1420,66 @@ bool spool_link_tag(const char *spool_id, const char *tag_id, const char *tag_ty
     return success;
 }
 
+// Move an NFC tag to a spool, taking it off the spool that has it
+int spool_move_tag(const char *spool_id, const char *tag_id, const char *tag_type) {
+    if (!spool_id || !tag_id || !g_curl) {
+        printf("[backend] spool_move_tag: invalid params\n");
+        return -1;
+    }
+
+    char url[512];
+    snprintf(url, sizeof(url), "%s/api/spools/%s/move-tag", g_base_url, spool_id);
+
+    cJSON *json = cJSON_CreateObject();
+    cJSON_AddStringToObject(json, "tag_id", tag_id);
+    if (tag_type && tag_type[0]) {
+        cJSON_AddStringToObject(json, "tag_type", tag_type);
+    }
+    cJSON_AddStringToObject(json, "data_origin", "nfc_link");
+
+    char *body = cJSON_PrintUnformatted(json);
+    cJSON_Delete(json);
+
+    if (!body) {
+        printf("[backend] spool_move_tag: failed to create JSON\n");
+        return -1;
+    }
+
+    printf("[backend] spool_move_tag: POST %s\n", url);
+
+    ResponseBuffer response = {0};
+    struct curl_slist *headers = NULL;
+    headers = curl_slist_append(headers, "Content-Type: application/json");
+
+    curl_easy_reset(g_curl);
+    curl_easy_setopt(g_curl, CURLOPT_URL, url);
+    curl_easy_setopt(g_curl, CURLOPT_POSTFIELDS, body);
+    curl_easy_setopt(g_curl, CURLOPT_HTTPHEADER, headers);
+    curl_easy_setopt(g_curl, CURLOPT_WRITEFUNCTION, write_callback);
+    curl_easy_setopt(g_curl, CURLOPT_WRITEDATA, &response);
+    curl_easy_setopt(g_curl, CURLOPT_TIMEOUT, 5L);
+
+    CURLcode res = curl_easy_perform(g_curl);
+
+    long http_code = 0;
+    curl_easy_getinfo(g_curl, CURLINFO_RESPONSE_CODE, &http_code);
+
+    curl_slist_free_all(headers);
+    free(body);
+    free(response.data);
+
+    if (res != CURLE_OK) {
+        printf("[backend] Failed to move tag: curl %d\n", res);
+        return -1;
+    }
+    if (http_code != 200) {
+        printf("[backend] Failed to move tag: HTTP %ld\n", http_code);
+        return (int)http_code;
+    }
+    printf("[backend] Tag moved to spool: %s\n", spool_id);
+    return 0;
+}
+
 // Sync spool weight from scale to inventory
 bool spool_sync_weight(const char *spool_id, int weight) {
     if (!spool_id || !g_curl) {
@@ -2205,6 +2289,51 @@ bool nfc_tag_present(void) {
     return g_nfc_tag_present;
 }
 
+// The simulator has no Pico bridge
+typedef struct NfcBridgeHealth NfcBridgeHealth;
+bool nfc_get_bridge_health(NfcBridgeHealth *out) {
+    (void)out;
+    return false;
+}
+
+// Mirrors DeviceDiagnosticsC in ui_internal.h
+typedef struct DeviceDiagnosticsC {
+    uint32_t uptime_s;
+    uint32_t heap_free;
+    uint32_t heap_total;
+    uint32_t heap_min_free;
+    uint32_t psram_free;
+    uint32_t psram_total;
+    int8_t wifi_rssi;
+    uint32_t i2c_scale_errors;
+    uint32_t i2c_scale_timeouts;
+    uint32_t i2c_nfc_errors;
+    uint32_t i2c_nfc_timeouts;
+    char nfc_bridge_firmware[8];
+    uint8_t scale_channels;
+    int32_t scale_raw[2];
+    uint32_t fps_x10;
+} DeviceDiagnosticsC;
+
+// Fixed figures resembling a healthy device, with the simulator's real uptime
+void diagnostics_get(DeviceDiagnosticsC *out) {
+    static time_t start_time = 0;
+    if (!out) return;
+    if (start_time == 0) start_time = time(NULL);
+
+    memset(out, 0, sizeof(*out));
+    out->uptime_s = (uint32_t)(time(NULL) - start_time);
+    out->heap_free = 142 * 1024;
+    out->heap_total = 320 * 1024;
+    out->heap_min_free = 98 * 1024;
+    out->psram_free = 5800 * 1024;
+    out->psram_total = 8192 * 1024;
+    out->wifi_rssi = g_wifi_rssi;
+    out->scale_channels = 1;
+    out->scale_raw[0] = 84213;
+    out->fps_x10 = 300;
+}
+
 bool staging_is_active(void) {
     return g_staging_active;
 }
@@ -59,6 +59,8 @@ typedef struct {
     // AMS data
     BackendAmsUnit ams_units[8];
     int ams_unit_count;
+    BackendAmsTray vt_tray;   // External spool holder
+    bool has_vt_tray;
 
     // Active tray indicators
     int tray_now;             // Legacy single nozzle
@@ -179,6 +181,7 @@ void backend_get_status(BackendStatus *status);
 int backend_get_printer(int index, BackendPrinterInfo *info);  // Firmware-compatible
 int backend_get_ams_count(int printer_index);
 int backend_get_ams_unit(int printer_index, int ams_index, AmsUnitCInfo *info);
+int backend_get_vt_tray(int printer_index, AmsTrayCInfo *info);  // External spool holder
 int backend_get_tray_now(int printer_index);
 int backend_get_tray_now_left(int printer_index);
 int backend_get_tray_now_right(int printer_index);
@@ -313,6 +316,10 @@ int spool_get_untagged_count(void);
 // Returns true on success, false on failure (tag already assigned or spool not found)
 bool spool_link_tag(const char *spool_id, const char *tag_id, const char *tag_type);
 
+// Move an NFC tag to a spool, taking it off the spool that has it (the other spool keeps its history)
+// Returns 0 on success, -1 on connection error, or the HTTP status code
+int spool_move_tag(const char *spool_id, const char *tag_id, const char *tag_type);
+
 // Update spool weight in inventory (sync from scale)
 // Returns true on success, false on failure
 bool spool_sync_weight(const char *spool_id, int weight);
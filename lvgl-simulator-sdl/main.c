@@ -1,6 +1,6 @@
 /**
  * SpoolBuddy LVGL 9.x Simulator with SDL2
- * Display: 800x480 (same as CrowPanel 7.0")
+ * Display: 800x480 (same as CrowPanel 7.0") by default, see --resolution
  *
  * This simulator can connect to the real Python backend for testing
  * UI changes without flashing the ESP32 firmware.
@@ -8,10 +8,20 @@
  * Usage:
  *   ./simulator                         # Uses default localhost:3000
  *   ./simulator http://192.168.1.10:3000  # Custom backend URL
+ *   ./simulator --resolution 1024x600   # Simulate another panel size
+ *
+ * Headless screenshots (no window, writes <name>.png per screen):
+ *   ./simulator --screen settings       # Capture one screen
+ *   ./simulator --all --out gallery     # Capture every screen into gallery/
+ *   ./simulator --list-screens          # Show available screen names
  */
 
 #include <stdio.h>
 #include <stdlib.h>
+#include <string.h>
+#include <stdbool.h>
+#include <errno.h>
+#include <sys/stat.h>
 #include <unistd.h>
 #include <pthread.h>
 #include <time.h>
@@ -19,112 +29,47 @@
 #include "lvgl.h"
 #include "ui/ui.h"
 #include "ui/screens.h"
+#include "ui/ui_internal.h"
 #include "sim_control.h"
+#include "sim_display.h"
+#include "sim_png.h"
 
 #ifdef ENABLE_BACKEND_CLIENT
 #include "backend_client.h"
 #endif
 
-#define DISP_HOR_RES 800
-#define DISP_VER_RES 480
-
-static SDL_Window *window;
-static SDL_Renderer *renderer;
-static SDL_Texture *texture;
-static uint32_t *fb_pixels;
-
-static lv_display_t *disp;
-static lv_indev_t *mouse_indev;
+/* Frames rendered after loading a screen before it is captured */
+#define HEADLESS_SETTLE_FRAMES 20
+#define HEADLESS_FRAME_MS 5
+#define HEADLESS_DEFAULT_OUT_DIR "screenshots"
+
+/* Screens that can be captured headless, by command line name */
+typedef struct {
+    const char *name;
+    int id;
+} SimScreen;
+
+static const SimScreen sim_screens[] = {
+    {"main", SCREEN_ID_MAIN_SCREEN},
+    {"ams_overview", SCREEN_ID_AMS_OVERVIEW},
+    {"scan_result", SCREEN_ID_SCAN_RESULT},
+    {"spool_details", SCREEN_ID_SPOOL_DETAILS},
+    {"settings", SCREEN_ID_SETTINGS_SCREEN},
+    {"settings_wifi", SCREEN_ID_SETTINGS_WIFI_SCREEN},
+    {"settings_printer_add", SCREEN_ID_SETTINGS_PRINTER_ADD_SCREEN},
+    {"settings_display", SCREEN_ID_SETTINGS_DISPLAY_SCREEN},
+    {"settings_update", SCREEN_ID_SETTINGS_UPDATE_SCREEN},
+    {"nfc", SCREEN_ID_NFC_SCREEN},
+    {"scale_calibration", SCREEN_ID_SCALE_CALIBRATION_SCREEN},
+    {"keyboard_layout", SCREEN_ID_KEYBOARD_LAYOUT_SCREEN},
+    {"now_printing", SCREEN_ID_NOW_PRINTING_SCREEN},
+    {"splash", SCREEN_ID_SPLASH_SCREEN},
+};
+
+#define SIM_SCREEN_COUNT (sizeof(sim_screens) / sizeof(sim_screens[0]))
 
 static pthread_mutex_t lvgl_mutex = PTHREAD_MUTEX_INITIALIZER;
 
-/* Display flush callback */
-static void sdl_flush_cb(lv_display_t *display, const lv_area_t *area, uint8_t *px_map)
-{
-    int32_t x, y;
-    uint16_t *src = (uint16_t *)px_map;
-
-    for (y = area->y1; y <= area->y2; y++) {
-        for (x = area->x1; x <= area->x2; x++) {
-            uint16_t c = *src++;
-            /* Convert RGB565 to ARGB8888 */
-            uint8_t r = ((c >> 11) & 0x1F) << 3;
-            uint8_t g = ((c >> 5) & 0x3F) << 2;
-            uint8_t b = (c & 0x1F) << 3;
-            fb_pixels[y * DISP_HOR_RES + x] = 0xFF000000 | (r << 16) | (g << 8) | b;
-        }
-    }
-
-    lv_display_flush_ready(display);
-}
-
-/* Mouse read callback */
-static void sdl_mouse_read_cb(lv_indev_t *indev, lv_indev_data_t *data)
-{
-    int x, y;
-    uint32_t buttons = SDL_GetMouseState(&x, &y);
-
-    data->point.x = x;
-    data->point.y = y;
-    data->state = (buttons & SDL_BUTTON(1)) ? LV_INDEV_STATE_PRESSED : LV_INDEV_STATE_RELEASED;
-}
-
-/* Initialize SDL */
-static int sdl_init(void)
-{
-    if (SDL_Init(SDL_INIT_VIDEO) != 0) {
-        fprintf(stderr, "SDL_Init failed: %s\n", SDL_GetError());
-        return -1;
-    }
-
-    window = SDL_CreateWindow(
-        "SpoolBuddy Simulator",
-        SDL_WINDOWPOS_CENTERED, SDL_WINDOWPOS_CENTERED,
-        DISP_HOR_RES, DISP_VER_RES,
-        SDL_WINDOW_SHOWN
-    );
-    if (!window) {
-        fprintf(stderr, "SDL_CreateWindow failed: %s\n", SDL_GetError());
-        return -1;
-    }
-
-    renderer = SDL_CreateRenderer(window, -1, 0);  /* Use any available renderer */
-    if (!renderer) {
-        fprintf(stderr, "SDL_CreateRenderer failed: %s\n", SDL_GetError());
-        return -1;
-    }
-
-    texture = SDL_CreateTexture(
-        renderer,
-        SDL_PIXELFORMAT_ARGB8888,
-        SDL_TEXTUREACCESS_STREAMING,
-        DISP_HOR_RES, DISP_VER_RES
-    );
-    if (!texture) {
-        fprintf(stderr, "SDL_CreateTexture failed: %s\n", SDL_GetError());
-        return -1;
-    }
-
-    fb_pixels = malloc(DISP_HOR_RES * DISP_VER_RES * sizeof(uint32_t));
-    if (!fb_pixels) {
-        fprintf(stderr, "Failed to allocate framebuffer\n");
-        return -1;
-    }
-    memset(fb_pixels, 0, DISP_HOR_RES * DISP_VER_RES * sizeof(uint32_t));
-
-    return 0;
-}
-
-/* Cleanup SDL */
-static void sdl_deinit(void)
-{
-    if (fb_pixels) free(fb_pixels);
-    if (texture) SDL_DestroyTexture(texture);
-    if (renderer) SDL_DestroyRenderer(renderer);
-    if (window) SDL_DestroyWindow(window);
-    SDL_Quit();
-}
-
 /* LVGL tick thread */
 static void *tick_thread(void *arg)
 {
@@ -181,56 +126,152 @@ static void *backend_thread(void *arg)
 }
 #endif
 
-/* Initialize LVGL display */
-static void lvgl_display_init(void)
+static const SimScreen *find_screen(const char *name)
+{
+    for (size_t i = 0; i < SIM_SCREEN_COUNT; i++) {
+        if (strcmp(sim_screens[i].name, name) == 0) {
+            return &sim_screens[i];
+        }
+    }
+    return NULL;
+}
+
+static void print_screens(FILE *out)
+{
+    fprintf(out, "Available screens:\n");
+    for (size_t i = 0; i < SIM_SCREEN_COUNT; i++) {
+        fprintf(out, "  %s\n", sim_screens[i].name);
+    }
+}
+
+/* Load a screen, let it settle and write <out_dir>/<name>.png */
+static int capture_screen(SimDisplay *sd, const SimScreen *screen, const char *out_dir)
 {
-    static uint8_t buf1[DISP_HOR_RES * 100 * 2]; /* 100 lines buffer */
+    char path[512];
+
+    pthread_mutex_lock(&lvgl_mutex);
+    loadScreen((enum ScreensEnum)screen->id);
+    for (int i = 0; i < HEADLESS_SETTLE_FRAMES; i++) {
+        lv_tick_inc(HEADLESS_FRAME_MS);
+        ui_tick();
+        lv_task_handler();
+    }
+    lv_obj_invalidate(lv_screen_active());
+    lv_refr_now(sd->disp);
+    pthread_mutex_unlock(&lvgl_mutex);
 
-    disp = lv_display_create(DISP_HOR_RES, DISP_VER_RES);
-    lv_display_set_flush_cb(disp, sdl_flush_cb);
-    lv_display_set_buffers(disp, buf1, NULL, sizeof(buf1), LV_DISPLAY_RENDER_MODE_PARTIAL);
+    snprintf(path, sizeof(path), "%s/%s.png", out_dir, screen->name);
+    if (sim_png_write(path, sd->fb_pixels, sd->width, sd->height) != 0) {
+        fprintf(stderr, "Failed to write %s\n", path);
+        return -1;
+    }
+    printf("Saved %s\n", path);
+    return 0;
 }
 
-/* Initialize LVGL input (mouse) */
-static void lvgl_input_init(void)
+/*
+ * Render screens without a window and save them as PNG files.
+ * Captures the named screen, or every screen when screen_name is NULL.
+ */
+static int run_headless(SimDisplay *sd, const char *screen_name, const char *out_dir)
 {
-    mouse_indev = lv_indev_create();
-    lv_indev_set_type(mouse_indev, LV_INDEV_TYPE_POINTER);
-    lv_indev_set_read_cb(mouse_indev, sdl_mouse_read_cb);
+    if (mkdir(out_dir, 0755) != 0 && errno != EEXIST) {
+        fprintf(stderr, "Cannot create %s: %s\n", out_dir, strerror(errno));
+        return 1;
+    }
+
+    if (screen_name) {
+        const SimScreen *screen = find_screen(screen_name);
+        if (!screen) {
+            fprintf(stderr, "Unknown screen: %s\n", screen_name);
+            print_screens(stderr);
+            return 1;
+        }
+        return capture_screen(sd, screen, out_dir) == 0 ? 0 : 1;
+    }
+
+    int failed = 0;
+    for (size_t i = 0; i < SIM_SCREEN_COUNT; i++) {
+        if (capture_screen(sd, &sim_screens[i], out_dir) != 0) {
+            failed++;
+        }
+    }
+    printf("Captured %zu of %zu screens to %s/\n",
+           SIM_SCREEN_COUNT - failed, SIM_SCREEN_COUNT, out_dir);
+    return failed ? 1 : 0;
 }
 
-/* Render to SDL */
-static void sdl_render(void)
+static void print_usage(const char *prog)
 {
-    SDL_UpdateTexture(texture, NULL, fb_pixels, DISP_HOR_RES * sizeof(uint32_t));
-    SDL_RenderClear(renderer);
-    SDL_RenderCopy(renderer, texture, NULL, NULL);
-    SDL_RenderPresent(renderer);
+    printf("Usage: %s [[--backend] <url>] [--resolution <WxH>] [--screen <name> | --all] [--out <dir>]\n", prog);
+    printf("  --resolution <WxH>  Panel size:");
+    for (int i = 0; i < sim_resolution_count; i++) {
+        printf(" %s", sim_resolutions[i].name);
+    }
+    printf(" (default: %s)\n", SIM_DEFAULT_RESOLUTION);
+    printf("  --screen <name>     Capture one screen to <dir>/<name>.png and exit\n");
+    printf("  --all               Capture every screen and exit\n");
+    printf("  --out <dir>         Screenshot directory (default: %s)\n", HEADLESS_DEFAULT_OUT_DIR);
+    printf("  --list-screens      Show available screen names\n");
 }
 
 int main(int argc, char **argv)
 {
-    (void)argc;
-    (void)argv;
+    const char *url_arg = NULL;
+    const char *screen_name = NULL;
+    const char *out_dir = HEADLESS_DEFAULT_OUT_DIR;
+    const SimResolution *resolution = sim_resolution_find(SIM_DEFAULT_RESOLUTION);
+    bool capture_all = false;
+
+    for (int i = 1; i < argc; i++) {
+        if (strcmp(argv[i], "--screen") == 0 && i + 1 < argc) {
+            screen_name = argv[++i];
+        } else if (strcmp(argv[i], "--backend") == 0 && i + 1 < argc) {
+            url_arg = argv[++i];
+        } else if (strcmp(argv[i], "--resolution") == 0 && i + 1 < argc) {
+            resolution = sim_resolution_find(argv[++i]);
+            if (!resolution) {
+                fprintf(stderr, "Unsupported resolution: %s\n", argv[i]);
+                print_usage(argv[0]);
+                return 1;
+            }
+        } else if (strcmp(argv[i], "--all") == 0) {
+            capture_all = true;
+        } else if (strcmp(argv[i], "--out") == 0 && i + 1 < argc) {
+            out_dir = argv[++i];
+        } else if (strcmp(argv[i], "--list-screens") == 0) {
+            print_screens(stdout);
+            return 0;
+        } else if (strcmp(argv[i], "--help") == 0 || strcmp(argv[i], "-h") == 0) {
+            print_usage(argv[0]);
+            return 0;
+        } else if (argv[i][0] == '-') {
+            fprintf(stderr, "Unknown option: %s\n", argv[i]);
+            print_usage(argv[0]);
+            return 1;
+        } else {
+            url_arg = argv[i];
+        }
+    }
+    bool headless = capture_all || screen_name != NULL;
 
     printf("===========================================\n");
     printf("  SpoolBuddy LVGL 9 Simulator\n");
     printf("===========================================\n");
-    printf("Display: %dx%d\n", DISP_HOR_RES, DISP_VER_RES);
+    printf("Display: %s, %s%s\n", resolution->name, resolution->panel, headless ? " (headless)" : "");
 
 #ifdef ENABLE_BACKEND_CLIENT
-    const char *backend_url = BACKEND_DEFAULT_URL;
-    if (argc > 1) {
-        backend_url = argv[1];
-    }
+    const char *backend_url = url_arg ? url_arg : BACKEND_DEFAULT_URL;
     printf("Backend: %s\n", backend_url);
 #else
+    (void)url_arg;
     printf("Backend: disabled (offline mode)\n");
 #endif
     printf("\n");
 
-    /* Initialize SDL */
-    if (sdl_init() != 0) {
+    /* Initialize SDL (not needed when capturing headless) */
+    if (!headless && SDL_Init(SDL_INIT_VIDEO) != 0) {
+        fprintf(stderr, "SDL_Init failed: %s\n", SDL_GetError());
         return 1;
     }
 
@@ -243,8 +284,27 @@ int main(int argc, char **argv)
 
     /* Initialize LVGL */
     lv_init();
-    lvgl_display_init();
-    lvgl_input_init();
+    SimDisplay *sd = sim_display_create(resolution, "SpoolBuddy Simulator", headless);
+    if (!sd) {
+        if (!headless) SDL_Quit();
+        return 1;
+    }
+
+    if (headless) {
+#ifdef ENABLE_BACKEND_CLIENT
+        /* One synchronous poll so screens show backend data */
+        backend_poll();
+#endif
+        ui_init();
+        int result = run_headless(sd, capture_all ? NULL : screen_name, out_dir);
+#ifdef ENABLE_BACKEND_CLIENT
+        backend_cleanup();
+#endif
+        sim_display_destroy(sd);
+        return result;
+    }
+
+    sim_display_add_mouse(sd);
 
     /* Start tick thread */
     pthread_t tick_tid;
@@ -280,7 +340,7 @@ int main(int argc, char **argv)
         ui_tick();  /* Process navigation and screen changes */
         pthread_mutex_unlock(&lvgl_mutex);
 
-        sdl_render();
+        sim_display_present(sd);
         usleep(5000); /* ~200 fps max */
     }
 
@@ -291,7 +351,8 @@ int main(int argc, char **argv)
     backend_cleanup();
 #endif
 
-    sdl_deinit();
+    sim_display_destroy(sd);
+    SDL_Quit();
     printf("Simulator exited.\n");
 
     return 0;
@@ -0,0 +1,164 @@
+/**
+ * Simulated display implementation
+ */
+
+#include "sim_display.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+const SimResolution sim_resolutions[] = {
+    {"480x272", "4.3\" 480x272 panel", 480, 272},
+    {"800x480", "CrowPanel 7.0\" / Waveshare 4.3\"", 800, 480},
+    {"1024x600", "Waveshare 7.0\"", 1024, 600},
+};
+
+const int sim_resolution_count = sizeof(sim_resolutions) / sizeof(sim_resolutions[0]);
+
+const SimResolution *sim_resolution_find(const char *name)
+{
+    for (int i = 0; i < sim_resolution_count; i++) {
+        if (strcmp(sim_resolutions[i].name, name) == 0) {
+            return &sim_resolutions[i];
+        }
+    }
+    return NULL;
+}
+
+/* Display flush callback: RGB565 area into the display's ARGB8888 framebuffer */
+static void sim_flush_cb(lv_display_t *display, const lv_area_t *area, uint8_t *px_map)
+{
+    SimDisplay *sd = lv_display_get_user_data(display);
+    uint16_t *src = (uint16_t *)px_map;
+
+    for (int32_t y = area->y1; y <= area->y2; y++) {
+        for (int32_t x = area->x1; x <= area->x2; x++) {
+            uint16_t c = *src++;
+            /* Convert RGB565 to ARGB8888 */
+            uint8_t r = ((c >> 11) & 0x1F) << 3;
+            uint8_t g = ((c >> 5) & 0x3F) << 2;
+            uint8_t b = (c & 0x1F) << 3;
+            sd->fb_pixels[y * sd->width + x] = 0xFF000000 | (r << 16) | (g << 8) | b;
+        }
+    }
+
+    lv_display_flush_ready(display);
+}
+
+/* Mouse read callback: only reports presses while the mouse is over this window */
+static void sim_mouse_read_cb(lv_indev_t *indev, lv_indev_data_t *data)
+{
+    SimDisplay *sd = lv_indev_get_user_data(indev);
+    int x, y;
+    uint32_t buttons = SDL_GetMouseState(&x, &y);
+
+    if (SDL_GetMouseFocus() != sd->window) {
+        data->state = LV_INDEV_STATE_RELEASED;
+        return;
+    }
+
+    data->point.x = x;
+    data->point.y = y;
+    data->state = (buttons & SDL_BUTTON(1)) ? LV_INDEV_STATE_PRESSED : LV_INDEV_STATE_RELEASED;
+}
+
+static int sim_window_init(SimDisplay *sd, const char *title)
+{
+    sd->window = SDL_CreateWindow(
+        title,
+        SDL_WINDOWPOS_CENTERED, SDL_WINDOWPOS_CENTERED,
+        sd->width, sd->height,
+        SDL_WINDOW_SHOWN
+    );
+    if (!sd->window) {
+        fprintf(stderr, "SDL_CreateWindow failed: %s\n", SDL_GetError());
+        return -1;
+    }
+
+    sd->renderer = SDL_CreateRenderer(sd->window, -1, 0);  /* Use any available renderer */
+    if (!sd->renderer) {
+        fprintf(stderr, "SDL_CreateRenderer failed: %s\n", SDL_GetError());
+        return -1;
+    }
+
+    sd->texture = SDL_CreateTexture(
+        sd->renderer,
+        SDL_PIXELFORMAT_ARGB8888,
+        SDL_TEXTUREACCESS_STREAMING,
+        sd->width, sd->height
+    );
+    if (!sd->texture) {
+        fprintf(stderr, "SDL_CreateTexture failed: %s\n", SDL_GetError());
+        return -1;
+    }
+
+    return 0;
+}
+
+SimDisplay *sim_display_create(const SimResolution *res, const char *title, bool headless)
+{
+    SimDisplay *sd = calloc(1, sizeof(SimDisplay));
+    if (!sd) {
+        fprintf(stderr, "Failed to allocate display\n");
+        return NULL;
+    }
+
+    sd->width = res->width;
+    sd->height = res->height;
+    sd->headless = headless;
+
+    sd->fb_pixels = calloc((size_t)sd->width * sd->height, sizeof(uint32_t));
+    size_t draw_buf_size = (size_t)sd->width * SIM_DRAW_BUF_LINES * 2;
+    sd->draw_buf = malloc(draw_buf_size);
+    if (!sd->fb_pixels || !sd->draw_buf) {
+        fprintf(stderr, "Failed to allocate framebuffer\n");
+        sim_display_destroy(sd);
+        return NULL;
+    }
+
+    if (!headless && sim_window_init(sd, title) != 0) {
+        sim_display_destroy(sd);
+        return NULL;
+    }
+
+    sd->disp = lv_display_create(sd->width, sd->height);
+    lv_display_set_user_data(sd->disp, sd);
+    lv_display_set_flush_cb(sd->disp, sim_flush_cb);
+    lv_display_set_buffers(sd->disp, sd->draw_buf, NULL, draw_buf_size, LV_DISPLAY_RENDER_MODE_PARTIAL);
+
+    return sd;
+}
+
+void sim_display_add_mouse(SimDisplay *sd)
+{
+    sd->mouse_indev = lv_indev_create();
+    lv_indev_set_type(sd->mouse_indev, LV_INDEV_TYPE_POINTER);
+    lv_indev_set_display(sd->mouse_indev, sd->disp);
+    lv_indev_set_user_data(sd->mouse_indev, sd);
+    lv_indev_set_read_cb(sd->mouse_indev, sim_mouse_read_cb);
+}
+
+void sim_display_present(SimDisplay *sd)
+{
+    if (sd->headless) return;
+
+    SDL_UpdateTexture(sd->texture, NULL, sd->fb_pixels, sd->width * sizeof(uint32_t));
+    SDL_RenderClear(sd->renderer);
+    SDL_RenderCopy(sd->renderer, sd->texture, NULL, NULL);
+    SDL_RenderPresent(sd->renderer);
+}
+
+void sim_display_destroy(SimDisplay *sd)
+{
+    if (!sd) return;
+
+    if (sd->mouse_indev) lv_indev_delete(sd->mouse_indev);
+    if (sd->disp) lv_display_delete(sd->disp);
+    if (sd->texture) SDL_DestroyTexture(sd->texture);
+    if (sd->renderer) SDL_DestroyRenderer(sd->renderer);
+    if (sd->window) SDL_DestroyWindow(sd->window);
+    free(sd->draw_buf);
+    free(sd->fb_pixels);
+    free(sd);
+}
@@ -0,0 +1,80 @@
+/**
+ * Simulated display: SDL window (optional), framebuffer and LVGL driver state
+ *
+ * All per-display state lives in a SimDisplay instance that LVGL hands back
+ * to the flush and input callbacks through user_data, so several displays
+ * with different resolutions can exist side by side.
+ */
+
+#ifndef SIM_DISPLAY_H
+#define SIM_DISPLAY_H
+
+#include <stdbool.h>
+#include <stdint.h>
+#include <SDL2/SDL.h>
+#include "lvgl.h"
+
+/* Supported panel resolutions */
+typedef struct {
+    const char *name;    /* e.g. "800x480" */
+    const char *panel;   /* Board the resolution corresponds to */
+    int32_t width;
+    int32_t height;
+} SimResolution;
+
+extern const SimResolution sim_resolutions[];
+extern const int sim_resolution_count;
+
+/* Default resolution (CrowPanel 7.0") */
+#define SIM_DEFAULT_RESOLUTION "800x480"
+
+/* Lines in the LVGL partial render buffer */
+#define SIM_DRAW_BUF_LINES 100
+
+typedef struct {
+    int32_t width;
+    int32_t height;
+    bool headless;
+
+    /* SDL output (NULL when headless) */
+    SDL_Window *window;
+    SDL_Renderer *renderer;
+    SDL_Texture *texture;
+
+    /* ARGB8888 framebuffer, width * height pixels */
+    uint32_t *fb_pixels;
+
+    /* LVGL driver state */
+    uint8_t *draw_buf;
+    lv_display_t *disp;
+    lv_indev_t *mouse_indev;
+} SimDisplay;
+
+/**
+ * Find a resolution preset by name ("800x480"). Returns NULL if unknown.
+ */
+const SimResolution *sim_resolution_find(const char *name);
+
+/**
+ * Create a display with its framebuffer and LVGL display driver.
+ * Opens an SDL window unless headless. lv_init() and, for windowed
+ * displays, SDL_Init() must have been called. Returns NULL on failure.
+ */
+SimDisplay *sim_display_create(const SimResolution *res, const char *title, bool headless);
+
+/**
+ * Attach an LVGL pointer input driven by the mouse over this display's window.
+ */
+void sim_display_add_mouse(SimDisplay *sd);
+
+/**
+ * Copy the framebuffer to the window (no-op when headless).
+ */
+void sim_display_present(SimDisplay *sd);
+
+/**
+ * Delete the LVGL display and input, close the window and free buffers.
+ */
+void sim_display_destroy(SimDisplay *sd);
+
+#endif // SIM_DISPLAY_H
@@ -0,0 +1,153 @@
+/**
+ * Minimal PNG writer for simulator screenshots
+ *
+ * The image data is wrapped in stored (uncompressed) deflate blocks, so the
+ * files are larger than a real encoder would produce but need no zlib.
+ */
+
+#include "sim_png.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define STORED_BLOCK_MAX 65535
+
+static uint32_t crc_table[256];
+static int crc_table_ready = 0;
+
+static void crc_init(void)
+{
+    for (uint32_t n = 0; n < 256; n++) {
+        uint32_t c = n;
+        for (int k = 0; k < 8; k++) {
+            c = (c & 1) ? 0xEDB88320u ^ (c >> 1) : c >> 1;
+        }
+        crc_table[n] = c;
+    }
+    crc_table_ready = 1;
+}
+
+static uint32_t crc_update(uint32_t crc, const uint8_t *buf, size_t len)
+{
+    for (size_t i = 0; i < len; i++) {
+        crc = crc_table[(crc ^ buf[i]) & 0xFF] ^ (crc >> 8);
+    }
+    return crc;
+}
+
+static void put_be32(uint8_t *p, uint32_t v)
+{
+    p[0] = (uint8_t)(v >> 24);
+    p[1] = (uint8_t)(v >> 16);
+    p[2] = (uint8_t)(v >> 8);
+    p[3] = (uint8_t)v;
+}
+
+static int write_chunk(FILE *f, const char *type, const uint8_t *data, uint32_t len)
+{
+    uint8_t header[8];
+    uint8_t trailer[4];
+
+    put_be32(header, len);
+    memcpy(header + 4, type, 4);
+
+    uint32_t crc = crc_update(0xFFFFFFFFu, header + 4, 4);
+    crc = crc_update(crc, data, len) ^ 0xFFFFFFFFu;
+    put_be32(trailer, crc);
+
+    if (fwrite(header, 1, 8, f) != 8) return -1;
+    if (len && fwrite(data, 1, len, f) != len) return -1;
+    if (fwrite(trailer, 1, 4, f) != 4) return -1;
+    return 0;
+}
+
+int sim_png_write(const char *path, const uint32_t *argb, int width, int height)
+{
+    static const uint8_t signature[8] = {0x89, 'P', 'N', 'G', '\r', '\n', 0x1A, '\n'};
+
+    if (!crc_table_ready) crc_init();
+
+    /* Raw scanlines: filter byte 0 followed by RGB triplets */
+    size_t stride = 1 + (size_t)width * 3;
+    size_t raw_len = stride * height;
+    uint8_t *raw = malloc(raw_len);
+    if (!raw) return -1;
+
+    for (int y = 0; y < height; y++) {
+        uint8_t *row = raw + y * stride;
+        *row++ = 0;
+        for (int x = 0; x < width; x++) {
+            uint32_t c = argb[y * width + x];
+            *row++ = (uint8_t)(c >> 16);
+            *row++ = (uint8_t)(c >> 8);
+            *row++ = (uint8_t)c;
+        }
+    }
+
+    /* zlib stream: header, stored blocks, adler32 */
+    size_t blocks = raw_len / STORED_BLOCK_MAX + 1;
+    size_t z_len = 2 + raw_len + blocks * 5 + 4;
+    uint8_t *z = malloc(z_len);
+    if (!z) {
+        free(raw);
+        return -1;
+    }
+
+    size_t pos = 0;
+    z[pos++] = 0x78;
+    z[pos++] = 0x01;
+
+    uint32_t a = 1, b = 0;
+    size_t offset = 0;
+    do {
+        size_t n = raw_len - offset;
+        if (n > STORED_BLOCK_MAX) n = STORED_BLOCK_MAX;
+        int last = (offset + n == raw_len);
+
+        z[pos++] = last ? 1 : 0;
+        z[pos++] = (uint8_t)n;
+        z[pos++] = (uint8_t)(n >> 8);
+        z[pos++] = (uint8_t)~n;
+        z[pos++] = (uint8_t)(~n >> 8);
+        memcpy(z + pos, raw + offset, n);
+        pos += n;
+
+        for (size_t i = 0; i < n; i++) {
+            a = (a + raw[offset + i]) % 65521;
+            b = (b + a) % 65521;
+        }
+        offset += n;
+    } while (offset < raw_len);
+
+    put_be32(z + pos, (b << 16) | a);
+    pos += 4;
+    free(raw);
+
+    uint8_t ihdr[13];
+    put_be32(ihdr, (uint32_t)width);
+    put_be32(ihdr + 4, (uint32_t)height);
+    ihdr[8] = 8;   /* bit depth */
+    ihdr[9] = 2;   /* color type: RGB */
+    ihdr[10] = 0;  /* compression */
+    ihdr[11] = 0;  /* filter */
+    ihdr[12] = 0;  /* interlace */
+
+    FILE *f = fopen(path, "wb");
+    if (!f) {
+        free(z);
+        return -1;
+    }
+
+    int result = 0;
+    if (fwrite(signature, 1, sizeof(signature), f) != sizeof(signature) ||
+        write_chunk(f, "IHDR", ihdr, sizeof(ihdr)) != 0 ||
+        write_chunk(f, "IDAT", z, (uint32_t)pos) != 0 ||
+        write_chunk(f, "IEND", NULL, 0) != 0) {
+        result = -1;
+    }
+
+    free(z);
+    if (fclose(f) != 0) result = -1;
+    return result;
+}
@@ -0,0 +1,17 @@
+/**
+ * Minimal PNG writer for simulator screenshots
+ * Writes uncompressed (stored deflate) RGB images, no zlib required.
+ */
+
+#ifndef SIM_PNG_H
+#define SIM_PNG_H
+
+#include <stdint.h>
+
+/**
+ * Write an ARGB8888 framebuffer to a PNG file (alpha is dropped).
+ * Returns 0 on success, -1 on error.
+ */
+int sim_png_write(const char *path, const uint32_t *argb, int width, int height);
+
+#endif // SIM_PNG_H
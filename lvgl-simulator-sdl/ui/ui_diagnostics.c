@@ -0,0 +1 @@
+../../firmware/components/eez_ui/ui_diagnostics.c
\ No newline at end of file
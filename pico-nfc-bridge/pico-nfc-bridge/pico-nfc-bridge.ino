@@ -2,9 +2,17 @@
  * Pico NFC Bridge - Manual PN5180 driver with tag data reading
  * I2C slave (0x55) bridging ESP32 to PN5180
  *
+ * Protocol v2 (request/poll): the ESP32 writes a command, then polls with
+ * reads. Until the response is ready every read returns [STATUS_BUSY], so
+ * the ESP32 never has to sleep for a guessed processing time. Tag presence
+ * and UID are tracked by a fast background scan and served from cache by
+ * CMD_GET_STATUS / CMD_GET_UID without touching the RF field.
+ *
  * Supports:
  * - MIFARE Classic 1K (Bambu Lab tags) with HKDF key derivation
  * - NTAG (SpoolEase/OpenPrintTag with NDEF)
+ * - Generic MIFARE Classic block read/write with user-supplied key slots
+ * - NTAG page read/write for encoding spool tags
  */
 
 #include <SPI.h>
@@ -22,9 +30,43 @@
 // I2C Commands
 #define CMD_GET_STATUS          0x00
 #define CMD_GET_PRODUCT_VERSION 0x01
-#define CMD_SCAN_TAG            0x10
+#define CMD_SCAN_TAG            0x10  // Active RF scan (slow, prefer CMD_GET_UID)
+#define CMD_GET_UID             0x11  // Cached UID from background scan: [status, uid_len, uid...]
 #define CMD_READ_TAG_DATA       0x20  // New: Read tag blocks/pages
 
+// Generic MIFARE Classic commands (request layout after [cmd, seq]):
+//   CMD_SET_KEY:       [slot, keyType (0=A, 1=B), key[6]]  -> [status]
+//   CMD_MFC_AUTH:      [block, slot]                       -> [status]
+//   CMD_MFC_READ:      [block]                             -> [status, data[16]]
+//   CMD_MFC_WRITE:     [block, data[16]]                   -> [status]
+// Slot KEY_SLOT_BAMBU uses the HKDF-derived key for the block's sector.
+#define CMD_SET_KEY             0x30
+#define CMD_MFC_AUTH            0x31
+#define CMD_MFC_READ            0x32
+#define CMD_MFC_WRITE           0x33
+
+// NTAG commands, for writing spool tags (request layout after [cmd, seq]):
+//   CMD_NTAG_READ:     [page]                              -> [status, data[16] = 4 pages]
+//   CMD_NTAG_WRITE:    [page, data[4]]                     -> [status]
+// Pages 0-3 (UID, lock bytes, capability container) are never written.
+#define CMD_NTAG_READ           0x34
+#define CMD_NTAG_WRITE          0x35
+
+// Receiver AGC value with the field on (RF_STATUS bits 0-9), a rough measure of
+// how well the tag couples to the antenna:  [] -> [status, agc_lo, agc_hi]
+#define CMD_RF_STATUS           0x36
+
+// Response status codes
+#define STATUS_OK               0
+#define STATUS_NO_TAG           1
+#define STATUS_READ_ERROR       2
+#define STATUS_UNSUPPORTED_TAG  3
+#define STATUS_AUTH_FAILED      4
+#define STATUS_INVALID_ARG      5
+#define STATUS_NOT_AUTHENTICATED 6
+#define STATUS_WRITE_ERROR      7
+#define STATUS_BUSY             0xFE  // Command queued/processing, poll again
+
 // Tag types (from SAK byte)
 #define TAG_TYPE_UNKNOWN        0
 #define TAG_TYPE_NTAG           1
@@ -51,7 +93,12 @@ uint8_t cachedVersion[2] = {0xFF, 0xFF};
 uint8_t tagDetectCount = 0;       // Consecutive successful detections
 uint8_t tagMissCount = 0;         // Consecutive failed detections
 const uint8_t DETECT_THRESHOLD = 2;   // Reads needed to report "present"
-const uint8_t REMOVE_THRESHOLD = 4;   // Misses needed to report "removed"
+const uint8_t REMOVE_THRESHOLD = 8;   // Misses needed to report "removed"
+
+// Background scan period - fast enough that a tag is reported in <200ms
+const uint32_t SCAN_INTERVAL_MS = 50;
+// Hard reset the PN5180 after this many empty scans (~4s) in case it's stuck
+const uint8_t NO_TAG_SCANS_BEFORE_RESET = 60;
 
 // Reset management
 uint8_t consecutiveFailures = 0;
@@ -70,6 +117,19 @@ const char BAMBU_CONTEXT[] = "RFID-A";
 uint8_t bambuKeys[96];
 bool keysGenerated = false;
 
+// User-supplied MIFARE key slots (volatile, cleared on reset)
+#define MAX_KEY_SLOTS           8
+#define KEY_SLOT_BAMBU          0xFF
+struct KeySlot {
+    bool valid;
+    uint8_t keyType;  // 0x60 = key A, 0x61 = key B
+    uint8_t key[6];
+};
+KeySlot keySlots[MAX_KEY_SLOTS];
+
+// Sector authenticated by the last CMD_MFC_AUTH (-1 = none)
+int authSector = -1;
+
 // Tag data storage
 uint8_t tagBlocks[5][16];  // Blocks 1, 2, 4, 5, 16 for Bambu
 bool tagDataValid = false;
@@ -536,7 +596,7 @@ static uint32_t prng_successor(uint32_t x, uint32_t n) {
 // NOTE: After MFC_AUTHENTICATE, SPI register reads return garbage.
 // We can't verify auth via SYSTEM_CONFIG. Instead, we just proceed
 // and check if the subsequent read works.
-bool mifare_authenticate(uint8_t blockNum, const uint8_t* key) {
+bool mifare_authenticate(uint8_t blockNum, const uint8_t* key, uint8_t keyType = 0x60) {
     logSeqStart("MFC_AUTH block ");
     Serial.print(blockNum);
     Serial.print(" key=");
@@ -565,7 +625,7 @@ bool mifare_authenticate(uint8_t blockNum, const uint8_t* key) {
     for (int i = 0; i < 6; i++) {
         SPI.transfer(key[i]);  // 6-byte key
     }
-    SPI.transfer(0x60);  // Key A (0x60) or Key B (0x61)
+    SPI.transfer(keyType);  // Key A (0x60) or Key B (0x61)
     SPI.transfer(blockNum);
     for (int i = 0; i < 4; i++) {
         SPI.transfer(tagUid[i]);  // 4-byte UID (first 4 bytes)
@@ -654,6 +714,47 @@ bool mifare_readBlock(uint8_t blockNum, uint8_t* buf) {
     return true;
 }
 
+// Write a single MIFARE block (16 bytes)
+// Assumes authentication has already been done (Crypto1 active)
+// WRITE is two-phase: [0xA0, block] -> ACK, then [data 16] -> ACK
+bool mifare_writeBlock(uint8_t blockNum, const uint8_t* data) {
+    logSeqStart("WRITE block ");
+    Serial.println(blockNum);
+
+    // TX CRC on, RX CRC off (the card answers with a 4-bit ACK)
+    pn5180_writeRegisterOrMask(0x19, 0x01);
+    pn5180_writeRegisterAndMask(0x12, 0xFFFFFFFE);
+
+    for (int phase = 0; phase < 2; phase++) {
+        pn5180_writeRegister(0x03, 0xFFFFFFFF);
+        pn5180_setTransceiveMode();
+        delay(1);
+
+        if (phase == 0) {
+            uint8_t writeCmd[2] = {0xA0, blockNum};
+            pn5180_sendData(writeCmd, 2, 0x00);
+        } else {
+            pn5180_sendData(data, 16, 0x00);
+        }
+        delay(phase == 0 ? 5 : 15);  // EEPROM write takes a few ms
+
+        uint32_t rxStatus = pn5180_readRegister(0x13);
+        uint16_t rxLen = rxStatus & 0x1FF;
+        uint8_t ack = 0;
+        if (rxLen > 0) {
+            pn5180_readData(&ack, 1);
+        }
+        if (rxLen == 0 || (ack & 0x0F) != 0x0A) {
+            logSeqStart("Write NAK, phase ");
+            Serial.print(phase);
+            Serial.print(" ack=0x");
+            Serial.println(ack, HEX);
+            return false;
+        }
+    }
+    return true;
+}
+
 // Re-select the card (needed before authentication after RF toggle)
 bool reactivateCard() {
     // Brief RF cycle to reset card state
@@ -762,6 +863,7 @@ bool readBambuTagData() {
 
     tagDataValid = false;
     memset(tagBlocks, 0, sizeof(tagBlocks));
+    authSector = -1;  // Reactivation below drops any generic MIFARE session
 
     // Print all derived keys for debugging
 
@@ -892,6 +994,46 @@ bool ntag_readPages(uint8_t startPage, uint8_t* buf, uint8_t numPages) {
     return true;
 }
 
+// Write a single NTAG page (4 bytes)
+// WRITE is [0xA2, page, data[4]] -> 4-bit ACK
+bool ntag_writePage(uint8_t page, const uint8_t* data) {
+    // TX CRC on, RX CRC off (the tag answers with a 4-bit ACK)
+    pn5180_writeRegisterOrMask(0x19, 0x01);
+    pn5180_writeRegisterAndMask(0x12, 0xFFFFFFFE);
+
+    pn5180_writeRegister(0x03, 0xFFFFFFFF);
+    pn5180_setTransceiveMode();
+    delay(1);
+
+    uint8_t writeCmd[6] = {0xA2, page, data[0], data[1], data[2], data[3]};
+    pn5180_sendData(writeCmd, 6, 0x00);
+    delay(10);  // EEPROM write takes ~4ms
+
+    uint32_t rxStatus = pn5180_readRegister(0x13);
+    uint16_t rxLen = rxStatus & 0x1FF;
+    uint8_t ack = 0;
+    if (rxLen > 0) {
+        pn5180_readData(&ack, 1);
+    }
+    if (rxLen == 0 || (ack & 0x0F) != 0x0A) {
+        logSeqStart("NTAG write NAK, page ");
+        Serial.print(page);
+        Serial.print(" ack=0x");
+        Serial.println(ack, HEX);
+        return false;
+    }
+    return true;
+}
+
+// Make sure the NTAG is selected before a read or write command
+// Within the scan protection window the card is still active from the last command
+bool ntag_ready() {
+    if (millis() < scanProtectionUntil) {
+        return true;
+    }
+    return reactivateCard();
+}
+
 // ============================================================================
 // Tag Activation (with SAK detection)
 // ============================================================================
@@ -1031,8 +1173,8 @@ uint8_t getTagType(uint8_t sak) {
 bool scanTag() {
     static uint8_t noTagCount = 0;
 
-    if (noTagCount > 3) {
-        Serial.println("No tag for 3 scans - HARD RESET");
+    if (noTagCount > NO_TAG_SCANS_BEFORE_RESET) {
+        Serial.println("No tag for a while - HARD RESET");
         pn5180_hardReset();
         noTagCount = 0;
         return false;
@@ -1067,6 +1209,7 @@ bool scanTag() {
             Serial.println("Tag REMOVED (debounced)");
             tagPresent = false;
             tagDataValid = false;
+            authSector = -1;
         }
         return false;
     }
@@ -1101,6 +1244,7 @@ bool scanTag() {
         if (newTag) {
             tagDataValid = false;
             keysGenerated = false;
+            authSector = -1;
             // Reset debounce on new tag (require fresh detection)
             tagDetectCount = 1;
 
@@ -1124,6 +1268,7 @@ bool scanTag() {
         Serial.println("Tag REMOVED (debounced)");
         tagPresent = false;
         tagDataValid = false;
+        authSector = -1;
     }
     return false;
 }
@@ -1174,6 +1319,18 @@ void processCommand() {
             }
             break;
 
+        case CMD_GET_UID:
+            if (tagPresent) {
+                respBuffer[0] = 0;  // Success
+                respBuffer[1] = tagUidLen;
+                memcpy((void*)&respBuffer[2], tagUid, tagUidLen);
+                respLength = 2 + tagUidLen;
+            } else {
+                respBuffer[0] = 1;  // No tag
+                respLength = 1;
+            }
+            break;
+
         case CMD_READ_TAG_DATA:
             Serial.print("READ_TAG_DATA: tagPresent=");
             Serial.print(tagPresent);
@@ -1238,6 +1395,175 @@ void processCommand() {
             }
             break;
 
+        case CMD_SET_KEY: {
+            // [cmd, seq, slot, keyType, key[6]]
+            uint8_t slot = cmdBuffer[2];
+            if (cmdLength < 10 || slot >= MAX_KEY_SLOTS || cmdBuffer[3] > 1) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+            } else {
+                keySlots[slot].valid = true;
+                keySlots[slot].keyType = cmdBuffer[3] == 0 ? 0x60 : 0x61;
+                memcpy(keySlots[slot].key, (const void*)&cmdBuffer[4], 6);
+                respBuffer[0] = STATUS_OK;
+            }
+            respLength = 1;
+            break;
+        }
+
+        case CMD_MFC_AUTH: {
+            // [cmd, seq, block, slot]
+            respLength = 1;
+            if (cmdLength < 4) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+                break;
+            }
+            uint8_t block = cmdBuffer[2];
+            uint8_t slot = cmdBuffer[3];
+            if (!tagPresent) {
+                respBuffer[0] = STATUS_NO_TAG;
+                break;
+            }
+            if (tagType != TAG_TYPE_MIFARE_1K && tagType != TAG_TYPE_MIFARE_4K) {
+                respBuffer[0] = STATUS_UNSUPPORTED_TAG;
+                break;
+            }
+            if (block >= 64 || (slot != KEY_SLOT_BAMBU && (slot >= MAX_KEY_SLOTS || !keySlots[slot].valid))) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+                break;
+            }
+
+            const uint8_t* key;
+            uint8_t keyType = 0x60;
+            if (slot == KEY_SLOT_BAMBU) {
+                if (!keysGenerated) {
+                    hkdf_derive_keys(tagUid, tagUidLen);
+                }
+                key = getSectorKey(block / 4);
+            } else {
+                key = keySlots[slot].key;
+                keyType = keySlots[slot].keyType;
+            }
+
+            // Auth must follow SELECT within a few ms, so reactivate first
+            authSector = -1;
+            if (!reactivateCard() || !mifare_authenticate(block, key, keyType)) {
+                respBuffer[0] = STATUS_AUTH_FAILED;
+                break;
+            }
+            authSector = block / 4;
+            // Keep background scans from dropping the Crypto1 session
+            scanProtectionUntil = millis() + 2000;
+            respBuffer[0] = STATUS_OK;
+            break;
+        }
+
+        case CMD_MFC_READ: {
+            // [cmd, seq, block]
+            uint8_t block = cmdBuffer[2];
+            if (cmdLength < 3) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+                respLength = 1;
+            } else if (authSector < 0 || block / 4 != authSector) {
+                respBuffer[0] = STATUS_NOT_AUTHENTICATED;
+                respLength = 1;
+            } else {
+                uint8_t data[16];
+                if (mifare_readBlock(block, data)) {
+                    respBuffer[0] = STATUS_OK;
+                    memcpy((void*)&respBuffer[1], data, 16);
+                    respLength = 17;
+                    scanProtectionUntil = millis() + 2000;
+                } else {
+                    authSector = -1;
+                    respBuffer[0] = STATUS_READ_ERROR;
+                    respLength = 1;
+                }
+            }
+            break;
+        }
+
+        case CMD_MFC_WRITE: {
+            // [cmd, seq, block, data[16]]
+            uint8_t block = cmdBuffer[2];
+            respLength = 1;
+            // Block 0 (manufacturer) and sector trailers (keys/access bits) are never written
+            if (cmdLength < 19 || block == 0 || block % 4 == 3) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+            } else if (authSector < 0 || block / 4 != authSector) {
+                respBuffer[0] = STATUS_NOT_AUTHENTICATED;
+            } else {
+                uint8_t data[16];
+                memcpy(data, (const void*)&cmdBuffer[3], 16);
+                if (mifare_writeBlock(block, data)) {
+                    respBuffer[0] = STATUS_OK;
+                    tagDataValid = false;  // Cached Bambu blocks may be stale now
+                    scanProtectionUntil = millis() + 2000;
+                } else {
+                    authSector = -1;
+                    respBuffer[0] = STATUS_WRITE_ERROR;
+                }
+            }
+            break;
+        }
+
+        case CMD_NTAG_READ: {
+            // [cmd, seq, page]
+            uint8_t page = cmdBuffer[2];
+            respLength = 1;
+            if (cmdLength < 3) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+            } else if (!tagPresent) {
+                respBuffer[0] = STATUS_NO_TAG;
+            } else if (tagType != TAG_TYPE_NTAG) {
+                respBuffer[0] = STATUS_UNSUPPORTED_TAG;
+            } else {
+                uint8_t data[16];
+                if (ntag_ready() && ntag_readPages(page, data, 4)) {
+                    respBuffer[0] = STATUS_OK;
+                    memcpy((void*)&respBuffer[1], data, 16);
+                    respLength = 17;
+                    scanProtectionUntil = millis() + 2000;
+                } else {
+                    scanProtectionUntil = 0;  // Reactivate before the retry
+                    respBuffer[0] = STATUS_READ_ERROR;
+                }
+            }
+            break;
+        }
+
+        case CMD_NTAG_WRITE: {
+            // [cmd, seq, page, data[4]]
+            uint8_t page = cmdBuffer[2];
+            respLength = 1;
+            if (cmdLength < 7 || page < 4) {
+                respBuffer[0] = STATUS_INVALID_ARG;
+            } else if (!tagPresent) {
+                respBuffer[0] = STATUS_NO_TAG;
+            } else if (tagType != TAG_TYPE_NTAG) {
+                respBuffer[0] = STATUS_UNSUPPORTED_TAG;
+            } else {
+                uint8_t data[4];
+                memcpy(data, (const void*)&cmdBuffer[3], 4);
+                if (ntag_ready() && ntag_writePage(page, data)) {
+                    respBuffer[0] = STATUS_OK;
+                    scanProtectionUntil = millis() + 2000;
+                } else {
+                    scanProtectionUntil = 0;  // Reactivate before the retry
+                    respBuffer[0] = STATUS_WRITE_ERROR;
+                }
+            }
+            break;
+        }
+
+        case CMD_RF_STATUS: {
+            uint16_t agc = pn5180_readRegister(0x1D) & 0x3FF;
+            respBuffer[0] = STATUS_OK;
+            respBuffer[1] = agc & 0xFF;
+            respBuffer[2] = agc >> 8;
+            respLength = 3;
+            break;
+        }
+
         default:
             respBuffer[0] = 0xFF;
             respLength = 1;
@@ -1264,6 +1590,11 @@ void i2cReceive(int n) {
 }
 
 void i2cRequest() {
+    if (cmdReady || processingCommand) {
+        // Response not ready yet - master polls again (not logged, happens often)
+        Wire.write(STATUS_BUSY);
+        return;
+    }
     Serial.print("I2C REQ: ");
     if (respLength > 0) {
         Serial.print(respLength);
@@ -1285,7 +1616,7 @@ void setup() {
     Serial.begin(115200);
     delay(2000);
     Serial.println("Pico NFC Bridge v2.0 starting...");
-    Serial.println("Features: MIFARE Classic + NTAG + Bambu HKDF");
+    Serial.println("Features: MIFARE Classic + NTAG + Bambu HKDF + key slots");
 
     pinMode(PN5180_NSS, OUTPUT);
     digitalWrite(PN5180_NSS, HIGH);
@@ -1351,7 +1682,15 @@ void loop() {
         Serial.println(tagPresent ? "YES" : "no");
     }
 
-    if (millis() - lastScan > 1000) {
+    // Handle queued commands first so the ESP32 isn't kept waiting behind a scan
+    if (cmdReady) {
+        // Mark busy before clearing cmdReady so a poll never sees a stale response
+        processingCommand = true;
+        cmdReady = false;
+        processCommand();
+    }
+
+    if (millis() - lastScan > SCAN_INTERVAL_MS) {
         lastScan = millis();
 
         // Skip scan if processing a command or in protection window
@@ -1377,18 +1716,10 @@ void loop() {
                     case TAG_TYPE_MIFARE_4K: Serial.println("MIFARE 4K"); break;
                     default: Serial.println("Unknown"); break;
                 }
-            } else if (!tagPresent) {
-
-                Serial.println(".");
             }
         } else {
 
             Serial.println("(cooldown)");
         }
     }
-
-    if (cmdReady) {
-        processCommand();
-        cmdReady = false;
-    }
 }
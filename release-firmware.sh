@@ -11,6 +11,10 @@
 #   version   Version to build (e.g., 0.1.0, 0.1.0b2). If not provided, uses Cargo.toml.
 #   --push    Create git tag and push to trigger GitHub Actions release
 #
+# Environment:
+#   SPOOLBUDDY_OTA_PUBLIC_KEY   OTA public key (.pub file) baked into the firmware
+#   SPOOLBUDDY_OTA_SIGNING_KEY  Secret key the OTA binary is signed with (writes <binary>.sig)
+#
 # Examples:
 #   ./release-firmware.sh 0.1.1           # Build locally only
 #   ./release-firmware.sh 0.1.1 --push    # Build and publish to GitHub
@@ -178,9 +182,21 @@ main() {
         exit 1
     fi
 
+    # Sign the OTA binary (see firmware/tools/ota-sign)
+    if [ -n "$SPOOLBUDDY_OTA_SIGNING_KEY" ]; then
+        echo_info "Signing OTA binary..."
+        (cd "$FIRMWARE_DIR/tools/ota-sign" && cargo run --release -q -- sign "$SPOOLBUDDY_OTA_SIGNING_KEY" "$OUTPUT_PATH")
+        if [ -n "$SPOOLBUDDY_OTA_PUBLIC_KEY" ]; then
+            (cd "$FIRMWARE_DIR/tools/ota-sign" && cargo run --release -q -- verify "$SPOOLBUDDY_OTA_PUBLIC_KEY" "$OUTPUT_PATH")
+        fi
+    else
+        rm -f "$OUTPUT_PATH.sig"
+        echo_warn "SPOOLBUDDY_OTA_SIGNING_KEY not set - binary is unsigned, devices with an OTA key will refuse it"
+    fi
+
     # Delete old versions
-    for old_file in "$RELEASES_DIR"/spoolbuddy-*.bin; do
-        if [ -f "$old_file" ] && [ "$old_file" != "$OUTPUT_PATH" ]; then
+    for old_file in "$RELEASES_DIR"/spoolbuddy-*.bin "$RELEASES_DIR"/spoolbuddy-*.bin.sig; do
+        if [ -f "$old_file" ] && [ "$old_file" != "$OUTPUT_PATH" ] && [ "$old_file" != "$OUTPUT_PATH.sig" ]; then
             echo_info "Removing old version: $(basename "$old_file")"
             rm -f "$old_file"
         fi
@@ -17,7 +17,7 @@ use core::cell::RefCell;
 use critical_section::Mutex;
 use embedded_graphics::pixelcolor::Rgb565;
 use embedded_graphics::prelude::*;
-use heapless::String;
+use heapless::{String, Vec};
 use log::info;
 
 /// Display dimensions
@@ -93,6 +93,9 @@ impl Default for UiState {
     }
 }
 
+/// Most color stops a multi-color spool can have (matches the backend)
+pub const MAX_COLOR_STOPS: usize = 8;
+
 /// Spool information for display
 #[derive(Clone)]
 pub struct SpoolDisplay {
@@ -101,6 +104,8 @@ pub struct SpoolDisplay {
     pub color_name: String<32>,
     pub brand: String<32>,
     pub color_rgba: u32,
+    /// Colors of multi-color (dual-color, gradient) filament in order, empty for single-color
+    pub color_stops: Vec<u32, MAX_COLOR_STOPS>,
     pub weight_current: f32,
     pub weight_label: f32,
     pub k_value: Option<f32>,
@@ -129,6 +129,28 @@ pub fn rgba_to_rgb565(rgba: u32) -> Rgb565 {
     Rgb565::new(r >> 3, g >> 2, b >> 3)
 }
 
+/// Color at `pos` (0..`len`) along a gradient through evenly spaced RGBA stops
+pub fn gradient_rgb565(stops: &[u32], pos: u32, len: u32) -> Rgb565 {
+    match stops {
+        [] => Rgb565::BLACK,
+        [only] => rgba_to_rgb565(*only),
+        _ => {
+            // Position in 1/256 steps across the segments between stops
+            let segments = (stops.len() - 1) as u32;
+            let scaled = pos.min(len.saturating_sub(1)) * segments * 256 / len.saturating_sub(1).max(1);
+            let index = ((scaled / 256) as usize).min(stops.len() - 2);
+            let t = scaled - index as u32 * 256;
+
+            let channel = |shift: u32| {
+                let from = (stops[index] >> shift) & 0xFF;
+                let to = (stops[index + 1] >> shift) & 0xFF;
+                ((from * (256 - t) + to * t) / 256) as u8
+            };
+            Rgb565::new(channel(24) >> 3, channel(16) >> 2, channel(8) >> 3)
+        }
+    }
+}
+
 /// Blend two colors (for hover effects, transparency, etc.)
 pub fn blend_colors(fg: Rgb565, bg: Rgb565, alpha: u8) -> Rgb565 {
     let fg_raw = fg.into_storage();
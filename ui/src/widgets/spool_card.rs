@@ -6,7 +6,7 @@ use embedded_graphics::{
     mono_font::{ascii::FONT_6X10, ascii::FONT_10X20, MonoTextStyle},
     pixelcolor::Rgb565,
     prelude::*,
-    primitives::{PrimitiveStyle, Rectangle, RoundedRectangle},
+    primitives::{Line, PrimitiveStyle, Rectangle, RoundedRectangle},
     text::Text,
 };
 use super::progress_bar::ProgressBar;
@@ -44,15 +44,19 @@ impl SpoolCard {
         // Color swatch (left side)
         let swatch_size = 60u32;
         let swatch_pos = Point::new(p.x + spacing::MD, p.y + spacing::MD);
-        let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
-
-        let swatch = RoundedRectangle::with_equal_corners(
-            Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size)),
-            Size::new(theme::radius::SM, theme::radius::SM),
-        );
-        swatch
-            .into_styled(PrimitiveStyle::with_fill(swatch_color))
-            .draw(display)?;
+        let swatch_area = Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size));
+        if spool.color_stops.len() >= 2 {
+            draw_gradient(display, swatch_area, &spool.color_stops)?;
+        } else {
+            let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
+            let swatch = RoundedRectangle::with_equal_corners(
+                swatch_area,
+                Size::new(theme::radius::SM, theme::radius::SM),
+            );
+            swatch
+                .into_styled(PrimitiveStyle::with_fill(swatch_color))
+                .draw(display)?;
+        }
 
         // Brand and material (top right of swatch)
         let text_x = swatch_pos.x + swatch_size as i32 + spacing::MD;
@@ -211,11 +215,15 @@ impl SpoolCardCompact {
         // Color swatch (small)
         let swatch_size = 32u32;
         let swatch_pos = Point::new(p.x + spacing::SM, p.y + (Self::HEIGHT as i32 - swatch_size as i32) / 2);
-        let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
-
-        Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size))
-            .into_styled(PrimitiveStyle::with_fill(swatch_color))
-            .draw(display)?;
+        let swatch_area = Rectangle::new(swatch_pos, Size::new(swatch_size, swatch_size));
+        if spool.color_stops.len() >= 2 {
+            draw_gradient(display, swatch_area, &spool.color_stops)?;
+        } else {
+            let swatch_color = theme::rgba_to_rgb565(spool.color_rgba);
+            swatch_area
+                .into_styled(PrimitiveStyle::with_fill(swatch_color))
+                .draw(display)?;
+        }
 
         // Material and color name
         let text_x = swatch_pos.x + swatch_size as i32 + spacing::SM;
@@ -236,3 +244,19 @@ impl SpoolCardCompact {
         Ok(())
     }
 }
+
+/// Fill an area with a left-to-right gradient through a multi-color spool's color stops
+fn draw_gradient<D>(display: &mut D, area: Rectangle, stops: &[u32]) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb565>,
+{
+    let width = area.size.width;
+    let bottom = area.top_left.y + area.size.height as i32 - 1;
+    for x in 0..width {
+        let column = area.top_left.x + x as i32;
+        Line::new(Point::new(column, area.top_left.y), Point::new(column, bottom))
+            .into_styled(PrimitiveStyle::with_stroke(theme::gradient_rgb565(stops, x, width), 1))
+            .draw(display)?;
+    }
+    Ok(())
+}